@@ -0,0 +1,335 @@
+//! Opt-in usage analytics.
+//!
+//! Off by default. Consent is stored per repository fingerprint in
+//! ~/.config/cosmos/analytics.json, so enabling analytics in one repo never
+//! enables it in another. Events carry only counts, outcomes, and gate names;
+//! never code, paths, or prompts.
+//!
+//! - `local`: events are written to `.cosmos/v2/analytics.jsonl` and nowhere else.
+//! - `share`: events are also sent to `COSMOS_ANALYTICS_ENDPOINT` when it is set.
+
+use crate::cache::{AnalyticsEventRecord, Cache};
+use crate::update::CURRENT_VERSION;
+use crate::util::debug_stderr_enabled;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const ENDPOINT_ENV: &str = "COSMOS_ANALYTICS_ENDPOINT";
+const SHARE_TIMEOUT: Duration = Duration::from_secs(3);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnalyticsMode {
+    #[default]
+    Off,
+    /// Record events under `.cosmos/v2` only.
+    Local,
+    /// Record locally and send events to the configured endpoint.
+    Share,
+}
+
+impl AnalyticsMode {
+    pub fn label(&self) -> &'static str {
+        match self {
+            AnalyticsMode::Off => "off",
+            AnalyticsMode::Local => "local only",
+            AnalyticsMode::Share => "shared",
+        }
+    }
+}
+
+/// Per-repository analytics consent, keyed by repository fingerprint.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AnalyticsConsent {
+    #[serde(default)]
+    pub repos: BTreeMap<String, AnalyticsMode>,
+}
+
+impl AnalyticsConsent {
+    /// Get the consent file path
+    pub fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|p| p.join("cosmos").join("analytics.json"))
+    }
+
+    /// Load consent from disk; a missing or unreadable file means analytics are off.
+    pub fn load() -> Self {
+        Self::path()
+            .map(|path| Self::load_from(&path))
+            .unwrap_or_default()
+    }
+
+    pub fn load_from(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let path =
+            Self::path().ok_or_else(|| "Could not determine config directory".to_string())?;
+        self.save_to(&path)
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<(), String> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create config directory: {}", e))?;
+        }
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize analytics consent: {}", e))?;
+        fs::write(path, content).map_err(|e| format!("Failed to write analytics consent: {}", e))
+    }
+
+    pub fn mode(&self, fingerprint: &str) -> AnalyticsMode {
+        self.repos.get(fingerprint).copied().unwrap_or_default()
+    }
+
+    pub fn set_mode(&mut self, fingerprint: &str, mode: AnalyticsMode) {
+        if mode == AnalyticsMode::Off {
+            self.repos.remove(fingerprint);
+        } else {
+            self.repos.insert(fingerprint.to_string(), mode);
+        }
+    }
+}
+
+fn fnv1a_hex(input: &str) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in input.as_bytes() {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    format!("{:016x}", hash)
+}
+
+/// Anonymous, stable identifier for a repository.
+///
+/// Hashes the root commit so clones of the same project share a fingerprint;
+/// falls back to the canonical path for repos without history.
+pub fn repo_fingerprint(repo_path: &Path) -> String {
+    let root_commit = git2::Repository::discover(repo_path).ok().and_then(|repo| {
+        let mut walk = repo.revwalk().ok()?;
+        walk.push_head().ok()?;
+        walk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::REVERSE)
+            .ok()?;
+        walk.next()?.ok().map(|oid| oid.to_string())
+    });
+    let identity = root_commit.unwrap_or_else(|| {
+        repo_path
+            .canonicalize()
+            .unwrap_or_else(|_| repo_path.to_path_buf())
+            .display()
+            .to_string()
+    });
+    fnv1a_hex(&format!("cosmos-analytics:{}", identity))
+}
+
+/// Analytics mode consented to for `repo_path`.
+pub fn mode_for_repo(repo_path: &Path) -> AnalyticsMode {
+    AnalyticsConsent::load().mode(&repo_fingerprint(repo_path))
+}
+
+/// Record one event if the user opted in for this repo. Best effort: never fails the caller.
+pub fn record(repo_path: &Path, event: &str, passed: Option<bool>, failed_gates: &[String]) {
+    let consent = AnalyticsConsent::load();
+    record_with_consent(&consent, repo_path, event, passed, failed_gates);
+}
+
+pub fn record_with_consent(
+    consent: &AnalyticsConsent,
+    repo_path: &Path,
+    event: &str,
+    passed: Option<bool>,
+    failed_gates: &[String],
+) {
+    if consent.repos.is_empty() {
+        return;
+    }
+    let fingerprint = repo_fingerprint(repo_path);
+    let mode = consent.mode(&fingerprint);
+    if mode == AnalyticsMode::Off {
+        return;
+    }
+    let record = AnalyticsEventRecord {
+        timestamp: Utc::now(),
+        repo: fingerprint,
+        cosmos_version: CURRENT_VERSION.to_string(),
+        event: event.to_string(),
+        passed,
+        failed_gates: failed_gates.to_vec(),
+    };
+    if let Err(err) = Cache::new(repo_path).append_analytics_event(&record) {
+        if debug_stderr_enabled() {
+            eprintln!("  Warning: Failed to record analytics event: {}", err);
+        }
+    }
+    if mode == AnalyticsMode::Share {
+        share(record);
+    }
+}
+
+fn share(record: AnalyticsEventRecord) {
+    let Some(endpoint) = std::env::var(ENDPOINT_ENV)
+        .ok()
+        .filter(|value| !value.trim().is_empty())
+    else {
+        return;
+    };
+    let Ok(handle) = tokio::runtime::Handle::try_current() else {
+        return;
+    };
+    handle.spawn(async move {
+        let Ok(client) = reqwest::Client::builder()
+            .user_agent(format!("cosmos-tui/{}", CURRENT_VERSION))
+            .timeout(SHARE_TIMEOUT)
+            .build()
+        else {
+            return;
+        };
+        let _ = client.post(endpoint).json(&record).send().await;
+    });
+}
+
+/// Per-event counts for `cosmos analytics show`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EventSummary {
+    pub runs: usize,
+    pub passed: usize,
+    pub failed: usize,
+}
+
+impl EventSummary {
+    /// Share of runs with a known outcome that passed.
+    pub fn pass_rate(&self) -> Option<f64> {
+        let decided = self.passed + self.failed;
+        (decided > 0).then(|| self.passed as f64 / decided as f64)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AnalyticsSummary {
+    pub total_events: usize,
+    pub events: BTreeMap<String, EventSummary>,
+    pub failed_gates: BTreeMap<String, usize>,
+}
+
+pub fn summarize(records: &[AnalyticsEventRecord]) -> AnalyticsSummary {
+    let mut summary = AnalyticsSummary {
+        total_events: records.len(),
+        ..AnalyticsSummary::default()
+    };
+    for record in records {
+        let entry = summary.events.entry(record.event.clone()).or_default();
+        entry.runs += 1;
+        match record.passed {
+            Some(true) => entry.passed += 1,
+            Some(false) => entry.failed += 1,
+            None => {}
+        }
+        for gate in &record.failed_gates {
+            *summary.failed_gates.entry(gate.clone()).or_default() += 1;
+        }
+    }
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn create_temp_repo(readme: &str) -> tempfile::TempDir {
+        let dir = tempdir().unwrap();
+        let repo = git2::Repository::init(dir.path()).unwrap();
+        fs::write(dir.path().join("README.md"), readme).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("README.md")).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = git2::Signature::now("Test", "test@example.com").unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "init", &tree, &[])
+            .unwrap();
+        dir
+    }
+
+    #[test]
+    fn fingerprint_is_stable_and_does_not_leak_the_path() {
+        let repo = create_temp_repo("hello\n");
+        let first = repo_fingerprint(repo.path());
+        assert_eq!(first, repo_fingerprint(repo.path()));
+        assert_eq!(first.len(), 16);
+        assert!(!first.contains("tmp"));
+
+        let other = create_temp_repo("another project\n");
+        assert_ne!(first, repo_fingerprint(other.path()));
+    }
+
+    #[test]
+    fn consent_round_trips_and_off_removes_the_repo() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("analytics.json");
+        let mut consent = AnalyticsConsent::default();
+        consent.set_mode("abc", AnalyticsMode::Local);
+        consent.save_to(&path).unwrap();
+
+        let mut loaded = AnalyticsConsent::load_from(&path);
+        assert_eq!(loaded.mode("abc"), AnalyticsMode::Local);
+        assert_eq!(loaded.mode("other"), AnalyticsMode::Off);
+
+        loaded.set_mode("abc", AnalyticsMode::Off);
+        assert!(loaded.repos.is_empty());
+    }
+
+    #[test]
+    fn events_are_recorded_only_after_opt_in() {
+        let repo = create_temp_repo("hello\n");
+        let cache = Cache::new(repo.path());
+        let mut consent = AnalyticsConsent::default();
+
+        record_with_consent(&consent, repo.path(), "apply", Some(true), &[]);
+        assert!(cache.load_recent_analytics_events(10).unwrap().is_empty());
+
+        consent.set_mode(&repo_fingerprint(repo.path()), AnalyticsMode::Local);
+        record_with_consent(
+            &consent,
+            repo.path(),
+            "apply",
+            Some(false),
+            &["quick_check".to_string()],
+        );
+        let events = cache.load_recent_analytics_events(10).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event, "apply");
+        assert_eq!(events[0].failed_gates, vec!["quick_check".to_string()]);
+    }
+
+    #[test]
+    fn summary_counts_runs_pass_rates_and_failed_gates() {
+        let event = |name: &str, passed: Option<bool>, gates: &[&str]| AnalyticsEventRecord {
+            timestamp: Utc::now(),
+            repo: "abc".to_string(),
+            cosmos_version: CURRENT_VERSION.to_string(),
+            event: name.to_string(),
+            passed,
+            failed_gates: gates.iter().map(|g| g.to_string()).collect(),
+        };
+        let summary = summarize(&[
+            event("apply", Some(true), &[]),
+            event("apply", Some(false), &["review"]),
+            event("apply", Some(false), &["review", "tests"]),
+            event("ask", None, &[]),
+        ]);
+        assert_eq!(summary.total_events, 4);
+        let apply = &summary.events["apply"];
+        assert_eq!(apply.runs, 3);
+        assert_eq!(apply.pass_rate(), Some(1.0 / 3.0));
+        assert_eq!(summary.events["ask"].pass_rate(), None);
+        assert_eq!(summary.failed_gates["review"], 2);
+        assert_eq!(summary.failed_gates["tests"], 1);
+    }
+}
@@ -0,0 +1,352 @@
+//! Native Bitbucket Cloud API integration
+//!
+//! Opens pull requests with either an app password (`username:app_password`) or
+//! an access token, from the environment or the system keychain.
+
+use crate::forge::{Forge, ForgeKind, ForgeRepo, PrState, PullRequest};
+use crate::github::sanitize_error_body;
+use crate::keyring;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
+
+const API_BASE_URL: &str = "https://api.bitbucket.org/2.0";
+const API_TIMEOUT_SECS: u64 = 60;
+
+/// Get the stored Bitbucket credentials, or None if not authenticated.
+///
+/// Checks `BITBUCKET_TOKEN`, then `BITBUCKET_USERNAME` + `BITBUCKET_APP_PASSWORD`,
+/// then the keychain.
+pub fn get_stored_token() -> Option<String> {
+    if let Ok(token) = std::env::var("BITBUCKET_TOKEN") {
+        if !token.is_empty() {
+            return Some(token);
+        }
+    }
+    if let (Ok(username), Ok(password)) = (
+        std::env::var("BITBUCKET_USERNAME"),
+        std::env::var("BITBUCKET_APP_PASSWORD"),
+    ) {
+        if !username.is_empty() && !password.is_empty() {
+            return Some(format!("{}:{}", username, password));
+        }
+    }
+
+    match keyring::get_bitbucket_token() {
+        Ok(token) => token,
+        Err(err) => {
+            keyring::warn_keychain_error_once("Bitbucket token", &err);
+            None
+        }
+    }
+}
+
+/// Check if Bitbucket authentication is configured.
+pub fn is_authenticated() -> bool {
+    get_stored_token().is_some()
+}
+
+/// App passwords use basic auth; anything without a `:` is sent as a bearer token.
+fn authorize(builder: reqwest::RequestBuilder, credentials: &str) -> reqwest::RequestBuilder {
+    match credentials.split_once(':') {
+        Some((username, password)) => builder.basic_auth(username, Some(password)),
+        None => builder.bearer_auth(credentials),
+    }
+}
+
+#[derive(Serialize)]
+struct BranchRef {
+    branch: BranchName,
+}
+
+#[derive(Serialize)]
+struct BranchName {
+    name: String,
+}
+
+/// Bitbucket identifies users by `{uuid}` or Atlassian account id.
+#[derive(Serialize)]
+#[serde(untagged)]
+enum Reviewer {
+    Uuid { uuid: String },
+    AccountId { account_id: String },
+}
+
+impl Reviewer {
+    fn new(id: &str) -> Self {
+        if id.starts_with('{') && id.ends_with('}') {
+            Reviewer::Uuid {
+                uuid: id.to_string(),
+            }
+        } else {
+            Reviewer::AccountId {
+                account_id: id.to_string(),
+            }
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct CreatePullRequest {
+    title: String,
+    description: String,
+    source: BranchRef,
+    destination: BranchRef,
+    draft: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    reviewers: Vec<Reviewer>,
+}
+
+impl CreatePullRequest {
+    fn new(request: &PullRequest) -> Self {
+        let branch = |name: &str| BranchRef {
+            branch: BranchName {
+                name: name.to_string(),
+            },
+        };
+        Self {
+            title: request.title.clone(),
+            description: request.body.clone(),
+            source: branch(&request.head),
+            destination: branch(&request.base),
+            draft: request.draft,
+            reviewers: request
+                .reviewers
+                .iter()
+                .map(|id| Reviewer::new(id))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct CreatePullRequestResponse {
+    links: PullRequestLinks,
+}
+
+#[derive(Deserialize)]
+struct PullRequestStateResponse {
+    state: String,
+}
+
+impl PullRequestStateResponse {
+    /// Bitbucket states are `OPEN`, `MERGED`, `DECLINED`, and `SUPERSEDED`.
+    fn pr_state(&self) -> PrState {
+        match self.state.as_str() {
+            "OPEN" => PrState::Open,
+            "MERGED" => PrState::Merged,
+            _ => PrState::Closed,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct PullRequestLinks {
+    html: Link,
+}
+
+#[derive(Deserialize)]
+struct Link {
+    href: String,
+}
+
+#[derive(Deserialize)]
+struct ApiErrorResponse {
+    error: ApiError,
+}
+
+#[derive(Deserialize)]
+struct ApiError {
+    message: String,
+    #[serde(default)]
+    detail: Option<String>,
+}
+
+/// Create a pull request on Bitbucket Cloud.
+///
+/// Returns the URL of the created PR.
+pub async fn create_pull_request(repo: &ForgeRepo, request: &PullRequest) -> Result<String> {
+    let credentials = get_stored_token().ok_or_else(|| {
+        anyhow::anyhow!("Not authenticated with Bitbucket. Please authenticate first.")
+    })?;
+    if !request.labels.is_empty() {
+        return Err(anyhow::anyhow!(
+            "Bitbucket pull requests don't support labels. Clear the labels and ship again."
+        ));
+    }
+
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(API_TIMEOUT_SECS))
+        .build()
+        .context("Failed to create HTTP client")?;
+
+    let url = format!(
+        "{}/repositories/{}/{}/pullrequests",
+        API_BASE_URL, repo.owner, repo.name
+    );
+    let builder = client
+        .post(&url)
+        .header("Accept", "application/json")
+        .header("User-Agent", "cosmos-tui")
+        .json(&CreatePullRequest::new(request));
+    let resp = authorize(builder, &credentials)
+        .send()
+        .await
+        .context("Failed to send PR creation request")?;
+
+    if !resp.status().is_success() {
+        return Err(api_error(resp).await);
+    }
+    let pr: CreatePullRequestResponse = resp.json().await.context("Failed to parse PR response")?;
+    Ok(pr.links.html.href)
+}
+
+/// State of pull request `id`.
+pub async fn pull_request_state(repo: &ForgeRepo, id: u64) -> Result<PrState> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(API_TIMEOUT_SECS))
+        .build()
+        .context("Failed to create HTTP client")?;
+
+    let url = format!(
+        "{}/repositories/{}/{}/pullrequests/{}",
+        API_BASE_URL, repo.owner, repo.name, id
+    );
+    let mut builder = client
+        .get(&url)
+        .header("Accept", "application/json")
+        .header("User-Agent", "cosmos-tui");
+    if let Some(credentials) = get_stored_token() {
+        builder = authorize(builder, &credentials);
+    }
+    let resp = builder
+        .send()
+        .await
+        .context("Failed to fetch pull request")?;
+    if !resp.status().is_success() {
+        return Err(api_error(resp).await);
+    }
+    let pr: PullRequestStateResponse = resp.json().await.context("Failed to parse pull request")?;
+    Ok(pr.pr_state())
+}
+
+async fn api_error(resp: reqwest::Response) -> anyhow::Error {
+    let status = resp.status();
+    let error_body = resp.text().await.unwrap_or_default();
+    if let Ok(api_error) = serde_json::from_str::<ApiErrorResponse>(&error_body) {
+        let msg = match api_error.error.detail.filter(|d| !d.is_empty()) {
+            Some(detail) => format!("{}: {}", api_error.error.message, detail),
+            None => api_error.error.message,
+        };
+        return anyhow::anyhow!("Bitbucket API error: {}", msg);
+    }
+    anyhow::anyhow!(
+        "Bitbucket API error ({}): {}",
+        status,
+        sanitize_error_body(&error_body)
+    )
+}
+
+/// Bitbucket Cloud implementation of the Ship [`Forge`].
+pub struct BitbucketForge;
+
+impl Forge for BitbucketForge {
+    fn kind(&self) -> ForgeKind {
+        ForgeKind::Bitbucket
+    }
+
+    fn is_authenticated(&self) -> bool {
+        is_authenticated()
+    }
+
+    fn create_pull_request<'a>(
+        &'a self,
+        repo: &'a ForgeRepo,
+        request: &'a PullRequest,
+    ) -> Pin<Box<dyn Future<Output = Result<String>> + Send + 'a>> {
+        Box::pin(create_pull_request(repo, request))
+    }
+
+    fn new_pull_request_url(&self, repo: &ForgeRepo, base: &str, head: &str) -> String {
+        let encode = |value: &str| {
+            url::form_urlencoded::byte_serialize(value.as_bytes()).collect::<String>()
+        };
+        format!(
+            "{}/pull-requests/new?source={}&dest={}",
+            repo.web_url(),
+            encode(head),
+            encode(base)
+        )
+    }
+
+    fn pull_request_state<'a>(
+        &'a self,
+        repo: &'a ForgeRepo,
+        number: u64,
+    ) -> Pin<Box<dyn Future<Output = Result<PrState>> + Send + 'a>> {
+        Box::pin(pull_request_state(repo, number))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_pull_request_serialization() {
+        let request = PullRequest {
+            base: "main".to_string(),
+            head: "cosmos/fix".to_string(),
+            title: "Fix bug".to_string(),
+            body: "Details".to_string(),
+            draft: true,
+            reviewers: vec!["{1234-abcd}".to_string(), "557058:abc".to_string()],
+            ..Default::default()
+        };
+        let json = serde_json::to_value(CreatePullRequest::new(&request)).unwrap();
+        assert_eq!(json["source"]["branch"]["name"], "cosmos/fix");
+        assert_eq!(json["destination"]["branch"]["name"], "main");
+        assert_eq!(json["description"], "Details");
+        assert_eq!(json["draft"], true);
+        assert_eq!(
+            json["reviewers"],
+            serde_json::json!([{"uuid": "{1234-abcd}"}, {"account_id": "557058:abc"}])
+        );
+    }
+
+    #[test]
+    fn test_parse_api_error_response() {
+        let json = r#"{"type":"error","error":{"message":"Bad request","detail":"source branch not found"}}"#;
+        let parsed: ApiErrorResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(parsed.error.message, "Bad request");
+        assert_eq!(
+            parsed.error.detail.as_deref(),
+            Some("source branch not found")
+        );
+    }
+
+    #[test]
+    fn test_pull_request_state_maps_declined_to_closed() {
+        let state = |json: &str| {
+            serde_json::from_str::<PullRequestStateResponse>(json)
+                .unwrap()
+                .pr_state()
+        };
+        assert_eq!(state(r#"{"state":"OPEN"}"#), PrState::Open);
+        assert_eq!(state(r#"{"state":"MERGED"}"#), PrState::Merged);
+        assert_eq!(state(r#"{"state":"DECLINED"}"#), PrState::Closed);
+    }
+
+    #[test]
+    fn test_parse_pull_request_response() {
+        let json =
+            r#"{"id":1,"links":{"html":{"href":"https://bitbucket.org/ws/repo/pull-requests/1"}}}"#;
+        let parsed: CreatePullRequestResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            parsed.links.html.href,
+            "https://bitbucket.org/ws/repo/pull-requests/1"
+        );
+    }
+}
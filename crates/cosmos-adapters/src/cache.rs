@@ -14,15 +14,19 @@
 //! For critical data, callers should explicitly handle errors.
 
 use chrono::{DateTime, Duration, Utc};
+use cosmos_core::health::HealthScore;
+use cosmos_core::index::directory::{directory_fingerprint, DirectorySummary};
+use cosmos_core::index::filter::IndexFilter;
 use cosmos_core::index::CodebaseIndex;
-use cosmos_core::suggest::Suggestion;
+use cosmos_core::suggest::{Suggestion, SuggestionCategory, SuggestionFeedback};
 use fs2::FileExt;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs::{self, OpenOptions};
 use std::io::ErrorKind;
 use std::path::{Path, PathBuf};
-use std::time::{Duration as StdDuration, Instant};
+use std::time::{Duration as StdDuration, Instant, SystemTime};
 
 const CACHE_DIR: &str = ".cosmos";
 const CACHE_LAYOUT_V2_DIR: &str = "v2";
@@ -34,17 +38,27 @@ const GLOSSARY_FILE: &str = "glossary.json";
 const GROUPING_AI_CACHE_FILE: &str = "grouping_ai.json";
 const PIPELINE_METRICS_FILE: &str = "pipeline_metrics.jsonl";
 const SUGGESTION_QUALITY_FILE: &str = "suggestion_quality.jsonl";
+const SUGGESTION_FEEDBACK_FILE: &str = "suggestion_feedback.jsonl";
+const REVIEW_FINDING_EDITS_FILE: &str = "review_finding_edits.jsonl";
 const IMPLEMENTATION_HARNESS_FILE: &str = "implementation_harness.jsonl";
 const SUGGESTION_RUN_AUDIT_FILE: &str = "suggestion_runs.jsonl";
 const APPLY_PLAN_AUDIT_FILE: &str = "apply_plan_audit.jsonl";
+const ANALYTICS_FILE: &str = "analytics.jsonl";
+const USAGE_LEDGER_FILE: &str = "usage.jsonl";
+const PROVIDER_PREFLIGHT_FILE: &str = "provider_preflight.json";
 const SUGGESTION_COVERAGE_FILE: &str = "suggestion_coverage.json";
+const DIRECTORY_SUMMARIES_FILE: &str = "directory_summaries.json";
+const SUMMARY_BATCH_FILE: &str = "summary_batch.json";
+const COSMOS_BRANCHES_FILE: &str = "branches.json";
+const SNOOZES_FILE: &str = "snoozes.json";
+const DISMISSALS_FILE: &str = "dismissals.json";
 const CACHE_LOCK_TIMEOUT_SECS: u64 = 5;
 const CACHE_LOCK_RETRY_MS: u64 = 50;
 
 /// Options for selective cache reset
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ResetOption {
-    /// Clear index.json - codebase structure, symbols, patterns
+    /// Clear index.json, embeddings.json, and directory summaries - codebase structure, symbols, patterns, vectors
     Index,
     /// Clear suggestions.json - generated suggestions
     Suggestions,
@@ -54,12 +68,16 @@ pub enum ResetOption {
     Memory,
     /// Clear grouping_ai.json - AI grouping cache
     GroupingAi,
-    /// Clear question_cache.json - persisted question/answer history
+    /// Clear question_cache.json and chat_session.json - persisted question/answer history
     QuestionCache,
-    /// Clear pipeline_metrics.jsonl - latency/cost telemetry rows
+    /// Clear responses/ - AI responses reused for identical requests
+    ResponseCache,
+    /// Clear pipeline_metrics.jsonl and usage.jsonl - latency/cost telemetry rows
     PipelineMetrics,
     /// Clear suggestion_quality.jsonl - per-suggestion validation telemetry
     SuggestionQuality,
+    /// Clear suggestion_feedback.jsonl - good/bad/irrelevant verdicts that tune suggestions
+    SuggestionFeedback,
     /// Clear implementation_harness.jsonl - apply harness telemetry
     ImplementationHarness,
     /// Clear data_notice_seen - provider data use acknowledgement
@@ -76,8 +94,10 @@ impl ResetOption {
             ResetOption::Memory => "Repo Memory",
             ResetOption::GroupingAi => "Grouping AI",
             ResetOption::QuestionCache => "Question Cache",
+            ResetOption::ResponseCache => "Response Cache",
             ResetOption::PipelineMetrics => "Pipeline Metrics",
             ResetOption::SuggestionQuality => "Suggestion Quality",
+            ResetOption::SuggestionFeedback => "Suggestion Feedback",
             ResetOption::ImplementationHarness => "Implementation Harness",
             ResetOption::DataNotice => "Data Notice Ack",
         }
@@ -92,8 +112,10 @@ impl ResetOption {
             ResetOption::Memory => "decisions/conventions",
             ResetOption::GroupingAi => "rebuild AI grouping",
             ResetOption::QuestionCache => "clear saved Q&A",
+            ResetOption::ResponseCache => "clear reused AI responses",
             ResetOption::PipelineMetrics => "clear latency/cost logs",
             ResetOption::SuggestionQuality => "clear validation telemetry",
+            ResetOption::SuggestionFeedback => "forget good/bad ratings",
             ResetOption::ImplementationHarness => "clear apply harness telemetry",
             ResetOption::DataNotice => "show data notice again",
         }
@@ -108,8 +130,10 @@ impl ResetOption {
             ResetOption::Memory,
             ResetOption::GroupingAi,
             ResetOption::QuestionCache,
+            ResetOption::ResponseCache,
             ResetOption::PipelineMetrics,
             ResetOption::SuggestionQuality,
+            ResetOption::SuggestionFeedback,
             ResetOption::ImplementationHarness,
             ResetOption::DataNotice,
         ]
@@ -134,9 +158,21 @@ const DATA_NOTICE_SEEN_FILE: &str = "data_notice_seen";
 /// Question answer cache file
 const QUESTION_CACHE_FILE: &str = "question_cache.json";
 
+/// Code chunk vectors for semantic retrieval
+const EMBEDDINGS_CACHE_FILE: &str = "embeddings.json";
+
+/// Ask Cosmos conversation (prior turns and their context files)
+const CHAT_SESSION_FILE: &str = "chat_session.json";
+
 /// Max age for question cache entries (in hours)
 const QUESTION_CACHE_HOURS: i64 = 24;
 
+/// Directory of reusable AI responses, one `<request hash>.json` file each
+const RESPONSE_CACHE_DIR: &str = "responses";
+
+/// Snapshots of files touched by applied fixes (see `crate::undo`)
+const UNDO_STACK_FILE: &str = "undo_stack.json";
+
 /// Cached index metadata
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IndexCache {
@@ -254,6 +290,65 @@ pub fn normalize_cache_path(path: &Path, root: &Path) -> PathBuf {
     out
 }
 
+// ═══════════════════════════════════════════════════════════════════════════
+//  EMBEDDINGS CACHE - Code chunk vectors for semantic retrieval
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// One embedded chunk of a file (1-based, inclusive lines).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChunkEmbedding {
+    pub start_line: usize,
+    pub end_line: usize,
+    pub vector: Vec<f32>,
+}
+
+/// Chunk vectors for one file, valid while its content hash matches.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileEmbeddings {
+    pub content_hash: String,
+    pub chunks: Vec<ChunkEmbedding>,
+}
+
+/// Cached vectors for one embedding provider/model.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EmbeddingCache {
+    /// Provider and model the vectors came from; a change invalidates every entry
+    pub model: String,
+    pub files: HashMap<PathBuf, FileEmbeddings>,
+}
+
+impl EmbeddingCache {
+    pub fn new(model: &str) -> Self {
+        Self {
+            model: model.to_string(),
+            files: HashMap::new(),
+        }
+    }
+
+    /// Cached chunks for a file, if its content is unchanged.
+    pub fn get(&self, path: &Path, content_hash: &str) -> Option<&[ChunkEmbedding]> {
+        self.files
+            .get(path)
+            .filter(|entry| entry.content_hash == content_hash)
+            .map(|entry| entry.chunks.as_slice())
+    }
+
+    pub fn insert(&mut self, path: PathBuf, content_hash: String, chunks: Vec<ChunkEmbedding>) {
+        self.files.insert(
+            path,
+            FileEmbeddings {
+                content_hash,
+                chunks,
+            },
+        );
+    }
+
+    /// Drop files that are no longer in the index.
+    pub fn retain_files(&mut self, keep: impl Fn(&Path) -> bool) {
+        self.files.retain(|path, _| keep(path));
+    }
+}
+
 // ═══════════════════════════════════════════════════════════════════════════
 //  GROUPING AI CACHE - AI-assisted layer classification hints
 // ═══════════════════════════════════════════════════════════════════════════
@@ -274,6 +369,75 @@ pub struct GroupingAiCache {
     pub cached_at: DateTime<Utc>,
 }
 
+/// Directory rollups keyed by repo-relative directory, each valid while its fingerprint matches.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DirectorySummaryCache {
+    pub entries: HashMap<PathBuf, DirectorySummary>,
+}
+
+impl DirectorySummaryCache {
+    /// Re-synthesize summaries whose files changed and drop directories that no longer hold
+    /// indexed files. Returns true when anything changed.
+    pub fn refresh(&mut self, index: &CodebaseIndex) -> bool {
+        let dirs = index.directories();
+        let before = self.entries.len();
+        self.entries.retain(|path, _| dirs.contains(path));
+        let mut changed = self.entries.len() != before;
+        for dir in dirs {
+            let current = directory_fingerprint(index, &dir);
+            let fresh = self
+                .entries
+                .get(&dir)
+                .is_some_and(|entry| Some(&entry.fingerprint) == current.as_ref());
+            if fresh {
+                continue;
+            }
+            if let Some(summary) = DirectorySummary::synthesize(index, &dir) {
+                self.entries.insert(dir, summary);
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    pub fn get(&self, dir: &Path) -> Option<&DirectorySummary> {
+        self.entries.get(dir)
+    }
+
+    /// Cached directories whose files changed or disappeared since they were summarized.
+    pub fn stale(&self, index: &CodebaseIndex) -> Vec<PathBuf> {
+        let mut stale: Vec<PathBuf> = self
+            .entries
+            .iter()
+            .filter(|(dir, entry)| {
+                directory_fingerprint(index, dir).as_ref() != Some(&entry.fingerprint)
+            })
+            .map(|(dir, _)| dir.clone())
+            .collect();
+        stale.sort();
+        stale
+    }
+}
+
+/// A `cosmos warm --batch` job still running at the provider, so an interrupted run can resume.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PendingSummaryBatch {
+    pub batch_id: String,
+    /// Submitted directories and their fingerprints at submission
+    pub dirs: HashMap<PathBuf, String>,
+    pub submitted_at: DateTime<Utc>,
+}
+
+impl PendingSummaryBatch {
+    pub fn new(batch_id: String, dirs: HashMap<PathBuf, String>) -> Self {
+        Self {
+            batch_id,
+            dirs,
+            submitted_at: Utc::now(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SuggestionCoverageCache {
     pub updated_at: DateTime<Utc>,
@@ -422,6 +586,30 @@ pub struct SuggestionRunAuditRecord {
     pub notes: Vec<String>,
     #[serde(default)]
     pub response_preview: Option<String>,
+    /// Prompt templates overridden via `.cosmos/prompts/` for this run
+    #[serde(default)]
+    pub prompt_overrides: Vec<String>,
+    /// Code chunks picked by semantic retrieval for this run
+    #[serde(default)]
+    pub retrieval_hit_count: Option<usize>,
+    /// Secrets masked before prompts were sent for this run
+    #[serde(default)]
+    pub redacted_secret_count: Option<usize>,
+    /// Findings dropped by `cosmos-ignore:` comments for this run
+    #[serde(default)]
+    pub suppressed_count: Option<usize>,
+    /// Findings hidden by `.cosmos/baseline.json` for this run
+    #[serde(default)]
+    pub baselined_count: Option<usize>,
+    /// Largest estimated prompt (in tokens) any request of this run sent
+    #[serde(default)]
+    pub max_prompt_tokens: Option<u32>,
+    /// Commit checked out when the run finished
+    #[serde(default)]
+    pub git_head: Option<String>,
+    /// Code health score computed from this run's findings
+    #[serde(default)]
+    pub health: Option<HealthScore>,
     pub suggestions: Vec<Suggestion>,
 }
 
@@ -453,6 +641,311 @@ pub struct ApplyPlanAuditRecord {
     pub preview_evidence_snippet: Option<String>,
 }
 
+/// Last successful provider preflight, reused until it goes stale.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProviderPreflightRecord {
+    pub checked_at: DateTime<Utc>,
+    pub backend: String,
+    /// Model ids the provider listed at `checked_at`.
+    pub available_models: Vec<String>,
+}
+
+/// One opt-in usage analytics row written as JSONL to `.cosmos/v2/analytics.jsonl`.
+///
+/// Only counts, outcomes, and gate names; never code, paths, or prompts.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnalyticsEventRecord {
+    pub timestamp: DateTime<Utc>,
+    /// Anonymous repository fingerprint (see `analytics::repo_fingerprint`).
+    pub repo: String,
+    pub cosmos_version: String,
+    /// Pipeline stage or feature, e.g. `suggest`, `apply`, `ship`, `ask`.
+    pub event: String,
+    #[serde(default)]
+    pub passed: Option<bool>,
+    #[serde(default)]
+    pub failed_gates: Vec<String>,
+}
+
+/// Token usage of one AI response, written as JSONL to `.cosmos/v2/usage.jsonl`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UsageLedgerRecord {
+    pub timestamp: DateTime<Utc>,
+    /// One of: suggestions, summaries, fixes, reviews, grouping.
+    pub operation: String,
+    pub model: String,
+    pub prompt_tokens: u32,
+    pub completion_tokens: u32,
+    pub total_tokens: u32,
+    /// Provider-reported cost; None when the provider did not report one.
+    #[serde(default)]
+    pub cost_usd: Option<f64>,
+}
+
+/// User verdict on one suggestion, written as JSONL to `.cosmos/v2/suggestion_feedback.jsonl`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SuggestionFeedbackRecord {
+    pub timestamp: DateTime<Utc>,
+    pub suggestion_id: String,
+    pub feedback: SuggestionFeedback,
+    pub category: SuggestionCategory,
+    pub file: PathBuf,
+    pub summary: String,
+}
+
+/// A user's correction to a review finding, written as JSONL to
+/// `.cosmos/v2/review_finding_edits.jsonl`. Matched by file and the reviewer's original title
+/// so the correction sticks when a later review round raises the same finding.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReviewFindingEditRecord {
+    pub timestamp: DateTime<Utc>,
+    pub file: String,
+    pub original_title: String,
+    pub title: String,
+    pub description: String,
+    pub severity: String,
+}
+
+/// A branch Cosmos created for applied fixes, and the pull request shipped from it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CosmosBranchRecord {
+    pub branch: String,
+    /// Branch the fixes were applied on top of
+    #[serde(default)]
+    pub base: Option<String>,
+    pub created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub pr_url: Option<String>,
+    /// Pull request state from the last poll of the forge
+    #[serde(default)]
+    pub pr_state: Option<crate::forge::PrState>,
+    /// Commit the pull request was opened at
+    #[serde(default)]
+    pub pr_head: Option<String>,
+    #[serde(default)]
+    pub checked_at: Option<DateTime<Utc>>,
+}
+
+/// Branches Cosmos created, stored in `.cosmos/v2/branches.json` so they can be cleaned up
+/// once their pull requests merge.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CosmosBranches {
+    pub branches: Vec<CosmosBranchRecord>,
+}
+
+impl CosmosBranches {
+    pub fn get(&self, branch: &str) -> Option<&CosmosBranchRecord> {
+        self.branches.iter().find(|record| record.branch == branch)
+    }
+
+    /// Start tracking `branch`; a branch already tracked keeps its record.
+    pub fn record_branch(&mut self, branch: &str, base: Option<&str>) -> &mut CosmosBranchRecord {
+        let index = match self
+            .branches
+            .iter()
+            .position(|record| record.branch == branch)
+        {
+            Some(index) => index,
+            None => {
+                self.branches.push(CosmosBranchRecord {
+                    branch: branch.to_string(),
+                    base: base.map(str::to_string),
+                    created_at: Utc::now(),
+                    pr_url: None,
+                    pr_state: None,
+                    pr_head: None,
+                    checked_at: None,
+                });
+                self.branches.len() - 1
+            }
+        };
+        &mut self.branches[index]
+    }
+
+    /// Attach the pull request opened from `branch` at commit `head`, which is open until
+    /// polled otherwise.
+    pub fn record_pull_request(&mut self, branch: &str, url: &str, head: Option<&str>) {
+        let record = self.record_branch(branch, None);
+        if head.is_some() {
+            record.pr_head = head.map(str::to_string);
+        }
+        if record.pr_url.as_deref() != Some(url) {
+            record.pr_url = Some(url.to_string());
+            record.pr_state = Some(crate::forge::PrState::Open);
+            record.checked_at = Some(Utc::now());
+        }
+    }
+
+    pub fn set_pr_state(&mut self, branch: &str, state: crate::forge::PrState) {
+        if let Some(record) = self
+            .branches
+            .iter_mut()
+            .find(|record| record.branch == branch)
+        {
+            record.pr_state = Some(state);
+            record.checked_at = Some(Utc::now());
+        }
+    }
+
+    pub fn remove(&mut self, branch: &str) {
+        self.branches.retain(|record| record.branch != branch);
+    }
+}
+
+/// When a snoozed suggestion comes back
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SnoozeUntil {
+    Date {
+        until: DateTime<Utc>,
+    },
+    /// Once the file's content no longer hashes to `content_hash`
+    FileChanged {
+        content_hash: String,
+    },
+}
+
+/// A suggestion hidden from the active list for a while.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnoozeRecord {
+    /// `Suggestion::finding_id`, so the snooze also covers later runs' re-detections
+    pub finding_id: uuid::Uuid,
+    pub file: PathBuf,
+    pub summary: String,
+    pub snoozed_at: DateTime<Utc>,
+    pub until: SnoozeUntil,
+}
+
+impl SnoozeRecord {
+    pub fn for_days(suggestion: &Suggestion, days: i64) -> Self {
+        Self::new(
+            suggestion,
+            SnoozeUntil::Date {
+                until: Utc::now() + Duration::days(days),
+            },
+        )
+    }
+
+    /// Snooze until the file's content hash changes from `content_hash`.
+    pub fn until_file_changes(suggestion: &Suggestion, content_hash: &str) -> Self {
+        Self::new(
+            suggestion,
+            SnoozeUntil::FileChanged {
+                content_hash: content_hash.to_string(),
+            },
+        )
+    }
+
+    fn new(suggestion: &Suggestion, until: SnoozeUntil) -> Self {
+        Self {
+            finding_id: suggestion.finding_id(),
+            file: suggestion.file.clone(),
+            summary: suggestion.summary.clone(),
+            snoozed_at: Utc::now(),
+            until,
+        }
+    }
+
+    pub fn covers(&self, suggestion: &Suggestion) -> bool {
+        self.finding_id == suggestion.finding_id()
+    }
+
+    /// Whether the snooze still holds at `now`, given the file's current content hash
+    /// (None when the file is gone, which ends a file snooze).
+    pub fn is_active(&self, now: DateTime<Utc>, current_hash: Option<&str>) -> bool {
+        match &self.until {
+            SnoozeUntil::Date { until } => now < *until,
+            SnoozeUntil::FileChanged { content_hash } => current_hash == Some(content_hash),
+        }
+    }
+
+    /// `until Mar 4` or `until file changes`
+    pub fn until_label(&self) -> String {
+        match &self.until {
+            SnoozeUntil::Date { until } => format!("until {}", until.format("%b %-d")),
+            SnoozeUntil::FileChanged { .. } => "until file changes".to_string(),
+        }
+    }
+}
+
+/// Snoozed suggestions, stored in `.cosmos/v2/snoozes.json`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Snoozes {
+    pub snoozes: Vec<SnoozeRecord>,
+}
+
+impl Snoozes {
+    pub fn get(&self, suggestion: &Suggestion) -> Option<&SnoozeRecord> {
+        self.snoozes.iter().find(|record| record.covers(suggestion))
+    }
+
+    /// Add `record`, replacing any earlier snooze of the same finding.
+    pub fn snooze(&mut self, record: SnoozeRecord) {
+        self.unsnooze(record.finding_id);
+        self.snoozes.push(record);
+    }
+
+    pub fn unsnooze(&mut self, finding_id: uuid::Uuid) {
+        self.snoozes
+            .retain(|record| record.finding_id != finding_id);
+    }
+
+    /// Drop snoozes that have ended. `current_hash` gives a file's content hash, if it exists.
+    pub fn retain_active<'a>(
+        &mut self,
+        now: DateTime<Utc>,
+        current_hash: impl Fn(&Path) -> Option<&'a str>,
+    ) {
+        self.snoozes
+            .retain(|record| record.is_active(now, current_hash(&record.file)));
+    }
+}
+
+/// One bulk dismiss; its findings stay hidden in later sessions until it is undone.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DismissalRecord {
+    pub id: uuid::Uuid,
+    /// `low-priority docs suggestions`
+    pub label: String,
+    /// `Suggestion::finding_id` of each dismissed suggestion
+    pub finding_ids: Vec<uuid::Uuid>,
+    pub dismissed_at: DateTime<Utc>,
+}
+
+impl DismissalRecord {
+    pub fn new(label: &str, suggestions: &[&Suggestion]) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4(),
+            label: label.to_string(),
+            finding_ids: suggestions.iter().map(|s| s.finding_id()).collect(),
+            dismissed_at: Utc::now(),
+        }
+    }
+}
+
+/// Bulk dismissals, stored in `.cosmos/v2/dismissals.json`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Dismissals {
+    pub dismissals: Vec<DismissalRecord>,
+}
+
+impl Dismissals {
+    pub fn covers(&self, suggestion: &Suggestion) -> bool {
+        let finding_id = suggestion.finding_id();
+        self.dismissals
+            .iter()
+            .any(|record| record.finding_ids.contains(&finding_id))
+    }
+
+    pub fn record(&mut self, record: DismissalRecord) {
+        self.dismissals.push(record);
+    }
+
+    pub fn remove(&mut self, id: uuid::Uuid) {
+        self.dismissals.retain(|record| record.id != id);
+    }
+}
+
 /// One apply-harness execution summary row written as JSONL.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ImplementationHarnessRecord {
@@ -591,7 +1084,7 @@ impl RepoMemory {
     /// Render a concise memory context for LLM prompts.
     pub fn to_prompt_context(&self, max_entries: usize, max_chars: usize) -> String {
         let mut entries = self.entries.clone();
-        entries.sort_by(|a, b| b.created_at.cmp(&a.created_at)); // newest first
+        entries.sort_by_key(|e| std::cmp::Reverse(e.created_at)); // newest first
 
         let mut out = String::new();
         for e in entries.into_iter().take(max_entries) {
@@ -837,7 +1330,7 @@ impl Cache {
             if is_index_meta_valid(root, &meta) {
                 let content = fs::read_to_string(&index_path).ok()?;
                 let index: CodebaseIndex = serde_json::from_str(&content).ok()?;
-                if index.root == root {
+                if index.root == root && index_filter_unchanged(root, &index) {
                     return Some(index);
                 }
                 return None;
@@ -919,6 +1412,27 @@ impl Cache {
         Ok(())
     }
 
+    /// Load the last successful provider preflight from `.cosmos/provider_preflight.json`
+    pub fn load_provider_preflight(&self) -> Option<ProviderPreflightRecord> {
+        let path = self.cache_dir.join(PROVIDER_PREFLIGHT_FILE);
+        if !path.exists() {
+            return None;
+        }
+        let _lock = self.lock(false).ok()?;
+        fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+    }
+
+    /// Save a successful provider preflight to `.cosmos/provider_preflight.json`
+    pub fn save_provider_preflight(&self, record: &ProviderPreflightRecord) -> anyhow::Result<()> {
+        let _lock = self.lock(true)?;
+        let path = self.cache_dir.join(PROVIDER_PREFLIGHT_FILE);
+        let content = serde_json::to_string(record)?;
+        write_atomic(&path, &content)?;
+        Ok(())
+    }
+
     /// Check if user has seen the welcome overlay
     pub fn has_seen_welcome(&self) -> bool {
         self.cache_dir.join(WELCOME_SEEN_FILE).exists()
@@ -967,6 +1481,204 @@ impl Cache {
         Ok(())
     }
 
+    /// Stored response for the request hashed as `key`, unless it is older than `ttl`
+    pub fn load_cached_response(&self, key: &str, ttl: StdDuration) -> Option<String> {
+        let path = self
+            .cache_dir
+            .join(RESPONSE_CACHE_DIR)
+            .join(format!("{}.json", key));
+        let age = path.metadata().ok()?.modified().ok()?.elapsed().ok()?;
+        if age > ttl {
+            return None;
+        }
+        let _lock = self.lock(false).ok()?;
+        fs::read_to_string(&path).ok()
+    }
+
+    /// Store a response, then drop the oldest ones until the cache fits in `max_bytes`
+    pub fn save_cached_response(
+        &self,
+        key: &str,
+        response: &str,
+        max_bytes: u64,
+    ) -> anyhow::Result<()> {
+        let _lock = self.lock(true)?;
+        let dir = self.cache_dir.join(RESPONSE_CACHE_DIR);
+        fs::create_dir_all(&dir)?;
+        write_atomic(&dir.join(format!("{}.json", key)), response)?;
+
+        let mut entries: Vec<(SystemTime, u64, PathBuf)> = fs::read_dir(&dir)?
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|entry| {
+                let meta = entry.metadata().ok()?;
+                Some((meta.modified().ok()?, meta.len(), entry.path()))
+            })
+            .collect();
+        let mut total: u64 = entries.iter().map(|(_, len, _)| len).sum();
+        entries.sort_by_key(|(modified, _, _)| *modified);
+        for (_, len, path) in entries {
+            if total <= max_bytes {
+                break;
+            }
+            if fs::remove_file(&path).is_ok() {
+                total = total.saturating_sub(len);
+            }
+        }
+        Ok(())
+    }
+
+    /// Load cached chunk vectors, if they came from `model`
+    pub fn load_embedding_cache(&self, model: &str) -> Option<EmbeddingCache> {
+        let path = self.cache_dir.join(EMBEDDINGS_CACHE_FILE);
+        if !path.exists() {
+            return None;
+        }
+        let _lock = self.lock(false).ok()?;
+        let content = fs::read_to_string(&path).ok()?;
+        let cache: EmbeddingCache = serde_json::from_str(&content).ok()?;
+        (cache.model == model).then_some(cache)
+    }
+
+    /// Save chunk vectors
+    pub fn save_embedding_cache(&self, cache: &EmbeddingCache) -> anyhow::Result<()> {
+        let _lock = self.lock(true)?;
+        let path = self.cache_dir.join(EMBEDDINGS_CACHE_FILE);
+        let content = serde_json::to_string(cache)?;
+        write_atomic(&path, &content)?;
+        Ok(())
+    }
+
+    /// Load the Ask Cosmos conversation (the session type lives in the engine)
+    pub fn load_chat_session<T: DeserializeOwned>(&self) -> Option<T> {
+        let path = self.cache_dir.join(CHAT_SESSION_FILE);
+        if !path.exists() {
+            return None;
+        }
+        let _lock = self.lock(false).ok()?;
+        let content = fs::read_to_string(&path).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    /// Save the Ask Cosmos conversation
+    pub fn save_chat_session<T: Serialize>(&self, session: &T) -> anyhow::Result<()> {
+        let _lock = self.lock(true)?;
+        let path = self.cache_dir.join(CHAT_SESSION_FILE);
+        let content = serde_json::to_string(session)?;
+        write_atomic(&path, &content)?;
+        Ok(())
+    }
+
+    /// Load the undo stack of applied-fix snapshots
+    pub fn load_undo_stack(&self) -> Option<crate::undo::UndoStack> {
+        let path = self.cache_dir.join(UNDO_STACK_FILE);
+        if !path.exists() {
+            return None;
+        }
+        let _lock = self.lock(false).ok()?;
+        let content = fs::read_to_string(&path).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    /// Save the undo stack of applied-fix snapshots
+    pub fn save_undo_stack(&self, stack: &crate::undo::UndoStack) -> anyhow::Result<()> {
+        let _lock = self.lock(true)?;
+        let path = self.cache_dir.join(UNDO_STACK_FILE);
+        let content = serde_json::to_string(stack)?;
+        write_atomic(&path, &content)?;
+        Ok(())
+    }
+
+    /// Load the branches Cosmos created
+    pub fn load_cosmos_branches(&self) -> CosmosBranches {
+        let path = self.cache_dir.join(COSMOS_BRANCHES_FILE);
+        if !path.exists() {
+            return CosmosBranches::default();
+        }
+        let Ok(_lock) = self.lock(false) else {
+            return CosmosBranches::default();
+        };
+        fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Change the tracked branches under one lock, so concurrent updates aren't lost.
+    pub fn update_cosmos_branches(
+        &self,
+        update: impl FnOnce(&mut CosmosBranches),
+    ) -> anyhow::Result<CosmosBranches> {
+        let _lock = self.lock(true)?;
+        let path = self.cache_dir.join(COSMOS_BRANCHES_FILE);
+        let mut branches: CosmosBranches = fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        update(&mut branches);
+        write_atomic(&path, &serde_json::to_string(&branches)?)?;
+        Ok(branches)
+    }
+
+    /// Load the snoozed suggestions
+    pub fn load_snoozes(&self) -> Snoozes {
+        let path = self.cache_dir.join(SNOOZES_FILE);
+        if !path.exists() {
+            return Snoozes::default();
+        }
+        let Ok(_lock) = self.lock(false) else {
+            return Snoozes::default();
+        };
+        fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Change the snoozed suggestions under one lock, so concurrent updates aren't lost.
+    pub fn update_snoozes(&self, update: impl FnOnce(&mut Snoozes)) -> anyhow::Result<Snoozes> {
+        let _lock = self.lock(true)?;
+        let path = self.cache_dir.join(SNOOZES_FILE);
+        let mut snoozes: Snoozes = fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        update(&mut snoozes);
+        write_atomic(&path, &serde_json::to_string(&snoozes)?)?;
+        Ok(snoozes)
+    }
+
+    /// Load the recorded bulk dismissals
+    pub fn load_dismissals(&self) -> Dismissals {
+        let path = self.cache_dir.join(DISMISSALS_FILE);
+        if !path.exists() {
+            return Dismissals::default();
+        }
+        let Ok(_lock) = self.lock(false) else {
+            return Dismissals::default();
+        };
+        fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Change the recorded bulk dismissals under one lock, so concurrent updates aren't lost.
+    pub fn update_dismissals(
+        &self,
+        update: impl FnOnce(&mut Dismissals),
+    ) -> anyhow::Result<Dismissals> {
+        let _lock = self.lock(true)?;
+        let path = self.cache_dir.join(DISMISSALS_FILE);
+        let mut dismissals: Dismissals = fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        update(&mut dismissals);
+        write_atomic(&path, &serde_json::to_string(&dismissals)?)?;
+        Ok(dismissals)
+    }
+
     pub fn load_suggestion_coverage_cache(&self) -> Option<SuggestionCoverageCache> {
         let path = self.cache_dir.join(SUGGESTION_COVERAGE_FILE);
         if !path.exists() {
@@ -994,6 +1706,64 @@ impl Cache {
         Ok(())
     }
 
+    /// Load cached directory summaries as stored, stale entries included.
+    pub fn load_directory_summaries(&self) -> DirectorySummaryCache {
+        let path = self.cache_dir.join(DIRECTORY_SUMMARIES_FILE);
+        self.lock(false)
+            .ok()
+            .and_then(|_lock| fs::read_to_string(&path).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Load cached directory summaries, refreshed against `index` (and saved back when stale).
+    pub fn directory_summaries(&self, index: &CodebaseIndex) -> DirectorySummaryCache {
+        let mut summaries = self.load_directory_summaries();
+        if summaries.refresh(index) {
+            let _ = self.save_directory_summaries(&summaries);
+        }
+        summaries
+    }
+
+    pub fn save_directory_summaries(
+        &self,
+        summaries: &DirectorySummaryCache,
+    ) -> anyhow::Result<()> {
+        let _lock = self.lock(true)?;
+        let path = self.cache_dir.join(DIRECTORY_SUMMARIES_FILE);
+        let content = serde_json::to_string(summaries)?;
+        write_atomic(&path, &content)?;
+        Ok(())
+    }
+
+    pub fn load_summary_batch(&self) -> Option<PendingSummaryBatch> {
+        let path = self.cache_dir.join(SUMMARY_BATCH_FILE);
+        if !path.exists() {
+            return None;
+        }
+        let _lock = self.lock(false).ok()?;
+        let content = fs::read_to_string(&path).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    pub fn save_summary_batch(&self, batch: &PendingSummaryBatch) -> anyhow::Result<()> {
+        let _lock = self.lock(true)?;
+        let path = self.cache_dir.join(SUMMARY_BATCH_FILE);
+        let content = serde_json::to_string(batch)?;
+        write_atomic(&path, &content)?;
+        Ok(())
+    }
+
+    /// Forget the pending summary batch once it was collected or failed
+    pub fn clear_summary_batch(&self) -> anyhow::Result<()> {
+        let _lock = self.lock(true)?;
+        let path = self.cache_dir.join(SUMMARY_BATCH_FILE);
+        if path.exists() {
+            fs::remove_file(&path)?;
+        }
+        Ok(())
+    }
+
     /// Append a pipeline metric record (JSONL) for latency/cost tracking.
     pub fn append_pipeline_metric(&self, record: &PipelineMetricRecord) -> anyhow::Result<()> {
         let _lock = self.lock(true)?;
@@ -1019,6 +1789,34 @@ impl Cache {
         Ok(())
     }
 
+    /// Append one suggestion feedback row (JSONL).
+    pub fn append_suggestion_feedback(
+        &self,
+        record: &SuggestionFeedbackRecord,
+    ) -> anyhow::Result<()> {
+        let _lock = self.lock(true)?;
+        let path = self.cache_dir.join(SUGGESTION_FEEDBACK_FILE);
+        let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let row = serde_json::to_string(record)?;
+        use std::io::Write;
+        writeln!(file, "{}", row)?;
+        Ok(())
+    }
+
+    /// Append one review finding edit row (JSONL).
+    pub fn append_review_finding_edit(
+        &self,
+        record: &ReviewFindingEditRecord,
+    ) -> anyhow::Result<()> {
+        let _lock = self.lock(true)?;
+        let path = self.cache_dir.join(REVIEW_FINDING_EDITS_FILE);
+        let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let row = serde_json::to_string(record)?;
+        use std::io::Write;
+        writeln!(file, "{}", row)?;
+        Ok(())
+    }
+
     /// Append one implementation-harness telemetry row (JSONL).
     pub fn append_implementation_harness(
         &self,
@@ -1058,6 +1856,86 @@ impl Cache {
         Ok(())
     }
 
+    /// Append an analytics event (JSONL). Callers check consent first.
+    pub fn append_analytics_event(&self, record: &AnalyticsEventRecord) -> anyhow::Result<()> {
+        let _lock = self.lock(true)?;
+        let path = self.cache_dir.join(ANALYTICS_FILE);
+        let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let row = serde_json::to_string(record)?;
+        use std::io::Write;
+        writeln!(file, "{}", row)?;
+        Ok(())
+    }
+
+    /// Append one AI response's token usage (JSONL).
+    pub fn append_usage_record(&self, record: &UsageLedgerRecord) -> anyhow::Result<()> {
+        let _lock = self.lock(true)?;
+        let path = self.cache_dir.join(USAGE_LEDGER_FILE);
+        let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let row = serde_json::to_string(record)?;
+        use std::io::Write;
+        writeln!(file, "{}", row)?;
+        Ok(())
+    }
+
+    /// Load every recorded usage row (oldest first).
+    pub fn load_usage_records(&self) -> anyhow::Result<Vec<UsageLedgerRecord>> {
+        let path = self.cache_dir.join(USAGE_LEDGER_FILE);
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let _lock = self.lock(false)?;
+        let content = fs::read_to_string(&path)?;
+        Ok(content
+            .lines()
+            .filter_map(|line| serde_json::from_str::<UsageLedgerRecord>(line).ok())
+            .collect())
+    }
+
+    /// Load up to `limit` latest analytics events (newest last).
+    pub fn load_recent_analytics_events(
+        &self,
+        limit: usize,
+    ) -> anyhow::Result<Vec<AnalyticsEventRecord>> {
+        let path = self.cache_dir.join(ANALYTICS_FILE);
+        if !path.exists() || limit == 0 {
+            return Ok(Vec::new());
+        }
+        let _lock = self.lock(false)?;
+        let content = fs::read_to_string(&path)?;
+        let mut records: Vec<AnalyticsEventRecord> = content
+            .lines()
+            .filter_map(|line| serde_json::from_str::<AnalyticsEventRecord>(line).ok())
+            .collect();
+        if records.len() > limit {
+            let split = records.len() - limit;
+            records.drain(0..split);
+        }
+        Ok(records)
+    }
+
+    /// Load up to `limit` latest pipeline metric rows (newest last).
+    pub fn load_recent_pipeline_metrics(
+        &self,
+        limit: usize,
+    ) -> anyhow::Result<Vec<PipelineMetricRecord>> {
+        let path = self.cache_dir.join(PIPELINE_METRICS_FILE);
+        if !path.exists() || limit == 0 {
+            return Ok(Vec::new());
+        }
+        let _lock = self.lock(false)?;
+        let content = fs::read_to_string(&path)?;
+        let mut records: Vec<PipelineMetricRecord> = content
+            .lines()
+            .filter_map(|line| serde_json::from_str::<PipelineMetricRecord>(line).ok())
+            .collect();
+        if records.len() > limit {
+            let split = records.len() - limit;
+            records.drain(0..split);
+        }
+        Ok(records)
+    }
+
     /// Load up to `limit` latest suggestion-quality records (newest last).
     pub fn load_recent_suggestion_quality(
         &self,
@@ -1080,7 +1958,50 @@ impl Cache {
         Ok(records)
     }
 
+    /// Load up to `limit` latest suggestion feedback records (newest last).
+    pub fn load_recent_suggestion_feedback(
+        &self,
+        limit: usize,
+    ) -> anyhow::Result<Vec<SuggestionFeedbackRecord>> {
+        let path = self.cache_dir.join(SUGGESTION_FEEDBACK_FILE);
+        if !path.exists() || limit == 0 {
+            return Ok(Vec::new());
+        }
+        let _lock = self.lock(false)?;
+        let content = fs::read_to_string(&path)?;
+        let mut records: Vec<SuggestionFeedbackRecord> = content
+            .lines()
+            .filter_map(|line| serde_json::from_str::<SuggestionFeedbackRecord>(line).ok())
+            .collect();
+        if records.len() > limit {
+            let split = records.len() - limit;
+            records.drain(0..split);
+        }
+        Ok(records)
+    }
+
     /// Load up to `limit` latest implementation-harness telemetry records (newest last).
+    pub fn load_recent_review_finding_edits(
+        &self,
+        limit: usize,
+    ) -> anyhow::Result<Vec<ReviewFindingEditRecord>> {
+        let path = self.cache_dir.join(REVIEW_FINDING_EDITS_FILE);
+        if !path.exists() || limit == 0 {
+            return Ok(Vec::new());
+        }
+        let _lock = self.lock(false)?;
+        let content = fs::read_to_string(&path)?;
+        let mut records: Vec<ReviewFindingEditRecord> = content
+            .lines()
+            .filter_map(|line| serde_json::from_str::<ReviewFindingEditRecord>(line).ok())
+            .collect();
+        if records.len() > limit {
+            let split = records.len() - limit;
+            records.drain(0..split);
+        }
+        Ok(records)
+    }
+
     pub fn load_recent_implementation_harness(
         &self,
         limit: usize,
@@ -1206,7 +2127,15 @@ impl Cache {
 
         for option in options {
             let files_to_remove: Vec<&str> = match option {
-                ResetOption::Index => vec![INDEX_CACHE_FILE, INDEX_META_FILE],
+                ResetOption::Index => {
+                    vec![
+                        INDEX_CACHE_FILE,
+                        INDEX_META_FILE,
+                        EMBEDDINGS_CACHE_FILE,
+                        DIRECTORY_SUMMARIES_FILE,
+                        SUMMARY_BATCH_FILE,
+                    ]
+                }
                 ResetOption::Suggestions => vec![
                     SUGGESTIONS_CACHE_FILE,
                     SUGGESTION_RUN_AUDIT_FILE,
@@ -1216,9 +2145,18 @@ impl Cache {
                 ResetOption::Glossary => vec![GLOSSARY_FILE],
                 ResetOption::Memory => vec![MEMORY_FILE],
                 ResetOption::GroupingAi => vec![GROUPING_AI_CACHE_FILE],
-                ResetOption::QuestionCache => vec![QUESTION_CACHE_FILE],
-                ResetOption::PipelineMetrics => vec![PIPELINE_METRICS_FILE],
+                ResetOption::QuestionCache => vec![QUESTION_CACHE_FILE, CHAT_SESSION_FILE],
+                ResetOption::ResponseCache => {
+                    let dir = self.cache_dir.join(RESPONSE_CACHE_DIR);
+                    if dir.exists() {
+                        fs::remove_dir_all(&dir)?;
+                        cleared.push(format!("{}/", RESPONSE_CACHE_DIR));
+                    }
+                    Vec::new()
+                }
+                ResetOption::PipelineMetrics => vec![PIPELINE_METRICS_FILE, USAGE_LEDGER_FILE],
                 ResetOption::SuggestionQuality => vec![SUGGESTION_QUALITY_FILE],
+                ResetOption::SuggestionFeedback => vec![SUGGESTION_FEEDBACK_FILE],
                 ResetOption::ImplementationHarness => vec![IMPLEMENTATION_HARNESS_FILE],
                 ResetOption::DataNotice => vec![DATA_NOTICE_SEEN_FILE],
             };
@@ -1266,6 +2204,10 @@ pub async fn reset_cosmos(
 }
 
 fn is_index_cache_valid(root: &Path, index: &CodebaseIndex) -> bool {
+    if !index_filter_unchanged(root, index) {
+        return false;
+    }
+
     // Fast path: check git HEAD and uncommitted changes
     // This avoids a full filesystem walk when the repo hasn't changed
     if let Some(cached_head) = &index.git_head {
@@ -1287,6 +2229,15 @@ fn is_index_cache_valid(root: &Path, index: &CodebaseIndex) -> bool {
     is_index_cache_valid_full(root, index)
 }
 
+/// Whether the index was built with the repo's current `.cosmosignore` / `[index]` globs.
+/// Ignore files are often untracked, so git state alone cannot tell.
+fn index_filter_unchanged(root: &Path, index: &CodebaseIndex) -> bool {
+    match IndexFilter::load(root) {
+        Ok(filter) => filter.fingerprint() == index.filter_fingerprint.as_deref(),
+        Err(_) => false,
+    }
+}
+
 fn is_index_meta_valid(root: &Path, meta: &IndexMeta) -> bool {
     if let Some(cached_head) = &meta.git_head {
         if let Some(current_head) = get_current_git_head(root) {
@@ -1327,6 +2278,7 @@ fn is_index_cache_valid_full(root: &Path, index: &CodebaseIndex) -> bool {
 }
 
 fn compute_current_hashes(root: &Path) -> anyhow::Result<HashMap<PathBuf, String>> {
+    let filter = IndexFilter::load(root)?;
     let mut hashes = HashMap::new();
     for entry in walkdir::WalkDir::new(root)
         .into_iter()
@@ -1343,6 +2295,9 @@ fn compute_current_hashes(root: &Path) -> anyhow::Result<HashMap<PathBuf, String
         if language == cosmos_core::index::Language::Unknown {
             continue;
         }
+        if filter.excludes(path.strip_prefix(root).unwrap_or(path)) {
+            continue;
+        }
 
         let metadata = match fs::metadata(path) {
             Ok(m) => m,
@@ -1484,6 +2439,53 @@ mod tests {
         let _ = fs::remove_dir_all(&root);
     }
 
+    #[test]
+    fn test_directory_summaries_refresh_only_changed_directories() {
+        let mut root = std::env::temp_dir();
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        root.push(format!("cosmos_directory_summaries_test_{}", nanos));
+        fs::create_dir_all(root.join("src/api")).unwrap();
+        fs::create_dir_all(root.join("src/db")).unwrap();
+        fs::write(root.join("src/api/mod.rs"), "pub fn route() {}").unwrap();
+        fs::write(root.join("src/db/mod.rs"), "pub struct Pool;").unwrap();
+
+        let index = CodebaseIndex::new(&root).unwrap();
+        let cache = Cache::new(&root);
+        let summaries = cache.directory_summaries(&index);
+        assert_eq!(summaries.entries.len(), 3);
+        let db = summaries.get(Path::new("src/db")).unwrap().clone();
+        assert_eq!(db.key_exports, vec!["Pool".to_string()]);
+
+        fs::write(
+            root.join("src/api/mod.rs"),
+            "pub fn route() {}\npub fn health() {}",
+        )
+        .unwrap();
+        fs::remove_file(root.join("src/db/mod.rs")).unwrap();
+        let index = CodebaseIndex::new(&root).unwrap();
+        assert_eq!(
+            cache.load_directory_summaries().stale(&index),
+            vec![
+                PathBuf::from("src"),
+                PathBuf::from("src/api"),
+                PathBuf::from("src/db")
+            ]
+        );
+        let mut reloaded = cache.directory_summaries(&index);
+        assert!(!reloaded.refresh(&index));
+        assert!(reloaded.get(Path::new("src/db")).is_none());
+        let api = reloaded.get(Path::new("src/api")).unwrap();
+        assert_eq!(
+            api.key_exports,
+            vec!["route".to_string(), "health".to_string()]
+        );
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
     #[test]
     fn test_index_cache_meta_fast_path() {
         let mut root = std::env::temp_dir();
@@ -1553,6 +2555,86 @@ mod tests {
         assert!(!ResetOption::defaults().contains(&ResetOption::DataNotice));
     }
 
+    #[test]
+    fn response_cache_expires_evicts_oldest_and_resets() {
+        let root = tempfile::tempdir().unwrap();
+        let cache = Cache::new(root.path());
+        let day = StdDuration::from_secs(24 * 3600);
+        assert_eq!(cache.load_cached_response("aaaa", day), None);
+
+        cache
+            .save_cached_response("aaaa", "0123456789", 1024)
+            .unwrap();
+        assert_eq!(
+            cache.load_cached_response("aaaa", day).as_deref(),
+            Some("0123456789")
+        );
+        let dir = root
+            .path()
+            .join(CACHE_DIR)
+            .join(CACHE_LAYOUT_V2_DIR)
+            .join(RESPONSE_CACHE_DIR);
+        let hour_ago = SystemTime::now() - StdDuration::from_secs(3600);
+        fs::File::options()
+            .write(true)
+            .open(dir.join("aaaa.json"))
+            .unwrap()
+            .set_modified(hour_ago)
+            .unwrap();
+        assert_eq!(
+            cache.load_cached_response("aaaa", StdDuration::from_secs(60)),
+            None
+        );
+
+        // Over the size limit the oldest entry goes first
+        cache
+            .save_cached_response("bbbb", "0123456789", 15)
+            .unwrap();
+        assert_eq!(cache.load_cached_response("aaaa", day), None);
+        assert!(cache.load_cached_response("bbbb", day).is_some());
+
+        let cleared = cache
+            .clear_selective(&[ResetOption::ResponseCache])
+            .unwrap();
+        assert_eq!(cleared, vec!["responses/"]);
+        assert!(!dir.exists());
+    }
+
+    #[test]
+    fn embedding_cache_is_keyed_by_model_and_content_hash() {
+        let mut root = std::env::temp_dir();
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        root.push(format!("cosmos_embedding_cache_test_{}", nanos));
+        fs::create_dir_all(&root).unwrap();
+
+        let cache = Cache::new(&root);
+        let mut embeddings = EmbeddingCache::new("local-hash-256");
+        let chunk = ChunkEmbedding {
+            start_line: 1,
+            end_line: 40,
+            vector: vec![0.6, 0.8],
+        };
+        embeddings.insert(
+            PathBuf::from("src/lib.rs"),
+            "abc".to_string(),
+            vec![chunk.clone()],
+        );
+        cache.save_embedding_cache(&embeddings).unwrap();
+
+        let loaded = cache.load_embedding_cache("local-hash-256").unwrap();
+        assert_eq!(
+            loaded.get(Path::new("src/lib.rs"), "abc"),
+            Some(&[chunk][..])
+        );
+        assert!(loaded.get(Path::new("src/lib.rs"), "changed").is_none());
+        assert!(cache.load_embedding_cache("openai:other-model").is_none());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
     #[test]
     fn clear_selective_removes_question_cache_and_pipeline_metrics() {
         let mut root = std::env::temp_dir();
@@ -1593,6 +2675,7 @@ mod tests {
             passed: true,
         };
         cache.append_pipeline_metric(&metric).unwrap();
+        assert_eq!(cache.load_recent_pipeline_metrics(10).unwrap().len(), 1);
         let quality = SuggestionQualityRecord {
             timestamp: Utc::now(),
             run_id: "run-1".to_string(),
@@ -1825,6 +2908,14 @@ mod tests {
                 tool_calls: None,
                 notes: Vec::new(),
                 response_preview: None,
+                prompt_overrides: Vec::new(),
+                retrieval_hit_count: None,
+                redacted_secret_count: None,
+                suppressed_count: None,
+                baselined_count: None,
+                max_prompt_tokens: None,
+                git_head: None,
+                health: None,
                 suggestions: vec![suggestion.clone()],
             };
             cache.append_suggestion_run_audit(&run_row).unwrap();
@@ -1916,4 +3007,132 @@ mod tests {
 
         let _ = fs::remove_dir_all(&root);
     }
+
+    #[test]
+    fn cosmos_branches_track_pull_requests_and_states() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::new(dir.path());
+        assert!(cache.load_cosmos_branches().branches.is_empty());
+
+        cache
+            .update_cosmos_branches(|branches| {
+                branches.record_branch("fix/aaaa-first", Some("main"));
+                branches.record_pull_request(
+                    "fix/aaaa-first",
+                    "https://github.com/o/r/pull/1",
+                    Some("abc123"),
+                );
+                // Ship can open a request from a branch apply didn't create.
+                branches.record_pull_request("cosmos-fix-1", "https://github.com/o/r/pull/2", None);
+            })
+            .unwrap();
+        cache
+            .update_cosmos_branches(|branches| {
+                branches.record_branch("fix/aaaa-first", Some("other"));
+                branches.set_pr_state("fix/aaaa-first", crate::forge::PrState::Merged);
+                branches.remove("cosmos-fix-1");
+            })
+            .unwrap();
+
+        let loaded = cache.load_cosmos_branches();
+        assert_eq!(loaded.branches.len(), 1);
+        let record = loaded.get("fix/aaaa-first").unwrap();
+        assert_eq!(record.base.as_deref(), Some("main"));
+        assert_eq!(
+            record.pr_url.as_deref(),
+            Some("https://github.com/o/r/pull/1")
+        );
+        assert_eq!(record.pr_state, Some(crate::forge::PrState::Merged));
+        assert_eq!(record.pr_head.as_deref(), Some("abc123"));
+    }
+
+    #[test]
+    fn snoozes_end_on_their_date_or_when_the_file_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::new(dir.path());
+        let mut dated = Suggestion::new(
+            cosmos_core::suggest::SuggestionKind::BugFix,
+            cosmos_core::suggest::Priority::High,
+            PathBuf::from("src/a.rs"),
+            "Unchecked index".to_string(),
+            cosmos_core::suggest::SuggestionSource::LlmDeep,
+        );
+        let on_file = Suggestion::new(
+            cosmos_core::suggest::SuggestionKind::Improvement,
+            cosmos_core::suggest::Priority::Low,
+            PathBuf::from("src/b.rs"),
+            "Slow loop".to_string(),
+            cosmos_core::suggest::SuggestionSource::LlmDeep,
+        );
+        cache
+            .update_snoozes(|snoozes| {
+                snoozes.snooze(SnoozeRecord::for_days(&dated, 1));
+                snoozes.snooze(SnoozeRecord::for_days(&dated, 7));
+                snoozes.snooze(SnoozeRecord::until_file_changes(&on_file, "hash-1"));
+            })
+            .unwrap();
+        let mut snoozes = cache.load_snoozes();
+        assert_eq!(snoozes.snoozes.len(), 2);
+        assert!(snoozes
+            .get(&dated)
+            .unwrap()
+            .until_label()
+            .starts_with("until "));
+
+        // A later run's re-detection of the same finding stays snoozed
+        let mut redetected = dated.clone();
+        redetected.id = uuid::Uuid::new_v4();
+        redetected.redetection = Some(cosmos_core::suggest::history::Redetection {
+            first_id: dated.id,
+            first_seen: dated.created_at,
+            runs: 2,
+        });
+        assert!(snoozes.get(&redetected).is_some());
+        dated.id = uuid::Uuid::new_v4();
+        assert!(snoozes.get(&dated).is_none());
+
+        let now = Utc::now();
+        let unchanged = |_: &Path| Some("hash-1");
+        snoozes.retain_active(now, unchanged);
+        assert_eq!(snoozes.snoozes.len(), 2);
+        snoozes.retain_active(now + Duration::days(8), unchanged);
+        assert_eq!(snoozes.snoozes.len(), 1);
+        snoozes.retain_active(now, |_: &Path| Some("hash-2"));
+        assert!(snoozes.snoozes.is_empty());
+    }
+
+    #[test]
+    fn bulk_dismissals_persist_until_removed() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::new(dir.path());
+        let docs = Suggestion::new(
+            cosmos_core::suggest::SuggestionKind::Documentation,
+            cosmos_core::suggest::Priority::Low,
+            PathBuf::from("legacy/a.rs"),
+            "Document the parser".to_string(),
+            cosmos_core::suggest::SuggestionSource::LlmDeep,
+        );
+        let other = Suggestion::new(
+            cosmos_core::suggest::SuggestionKind::BugFix,
+            cosmos_core::suggest::Priority::High,
+            PathBuf::from("src/b.rs"),
+            "Unchecked index".to_string(),
+            cosmos_core::suggest::SuggestionSource::LlmDeep,
+        );
+        let record = DismissalRecord::new("low-priority docs suggestions", &[&docs]);
+        let id = record.id;
+        cache
+            .update_dismissals(|dismissals| dismissals.record(record))
+            .unwrap();
+
+        let dismissals = cache.load_dismissals();
+        assert_eq!(dismissals.dismissals.len(), 1);
+        assert!(dismissals.covers(&docs));
+        assert!(!dismissals.covers(&other));
+
+        cache
+            .update_dismissals(|dismissals| dismissals.remove(id))
+            .unwrap();
+        assert!(cache.load_dismissals().dismissals.is_empty());
+    }
 }
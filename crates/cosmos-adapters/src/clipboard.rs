@@ -0,0 +1,126 @@
+//! Copy text to the system clipboard.
+//!
+//! Uses the platform's clipboard tool (`pbcopy`, `wl-copy`, `xclip`, `xsel`, `clip`) when
+//! running locally. Over SSH, or when no tool is available, falls back to an OSC-52 escape
+//! sequence, which asks the terminal itself to set the clipboard on the machine you are
+//! sitting at. `COSMOS_CLIPBOARD=osc52` always uses the escape sequence.
+
+use anyhow::{Context, Result};
+use base64::Engine;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Largest payload sent over OSC-52; many terminals drop longer sequences silently
+pub const OSC52_MAX_BYTES: usize = 100_000;
+
+/// How text reached the clipboard
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClipboardMethod {
+    /// A local clipboard tool, by command name
+    Native(&'static str),
+    /// An OSC-52 escape sequence written to the terminal
+    Osc52,
+}
+
+/// Copy `text` to the clipboard, natively when possible and via OSC-52 otherwise
+pub fn copy(text: &str) -> Result<ClipboardMethod> {
+    let force_osc52 = std::env::var("COSMOS_CLIPBOARD")
+        .map(|v| v.eq_ignore_ascii_case("osc52"))
+        .unwrap_or(false);
+    // A clipboard tool on the remote host would fill a clipboard nobody can see
+    let remote =
+        std::env::var_os("SSH_CONNECTION").is_some() || std::env::var_os("SSH_TTY").is_some();
+    if !force_osc52 && !remote {
+        for (program, args) in native_commands() {
+            if copy_with(program, args, text).is_ok() {
+                return Ok(ClipboardMethod::Native(program));
+            }
+        }
+    }
+
+    let sequence = osc52_sequence(text, std::env::var_os("TMUX").is_some())?;
+    let mut stdout = std::io::stdout();
+    stdout
+        .write_all(sequence.as_bytes())
+        .and_then(|_| stdout.flush())
+        .context("Failed to write to the terminal")?;
+    Ok(ClipboardMethod::Osc52)
+}
+
+/// Clipboard tools to try, most specific first
+fn native_commands() -> Vec<(&'static str, &'static [&'static str])> {
+    let mut commands: Vec<(&'static str, &'static [&'static str])> = Vec::new();
+    if cfg!(target_os = "macos") {
+        commands.push(("pbcopy", &[]));
+    } else if cfg!(windows) {
+        commands.push(("clip", &[]));
+    } else {
+        if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+            commands.push(("wl-copy", &[]));
+        }
+        if std::env::var_os("DISPLAY").is_some() {
+            commands.push(("xclip", &["-selection", "clipboard"]));
+            commands.push(("xsel", &["--clipboard", "--input"]));
+        }
+    }
+    commands
+}
+
+fn copy_with(program: &str, args: &[&str], text: &str) -> Result<()> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        // Keep the tool from writing over the TUI
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("Failed to start {}", program))?;
+    child
+        .stdin
+        .take()
+        .context("Failed to open clipboard tool input")?
+        .write_all(text.as_bytes())?;
+    let status = child.wait()?;
+    if !status.success() {
+        return Err(anyhow::anyhow!("{} exited with {}", program, status));
+    }
+    Ok(())
+}
+
+/// The OSC-52 "set clipboard" sequence for `text`, wrapped for tmux passthrough when needed
+pub fn osc52_sequence(text: &str, in_tmux: bool) -> Result<String> {
+    let encoded = base64::engine::general_purpose::STANDARD.encode(text);
+    if encoded.len() > OSC52_MAX_BYTES {
+        return Err(anyhow::anyhow!(
+            "Too much text to copy through the terminal ({} KB encoded, limit {} KB)",
+            encoded.len() / 1000,
+            OSC52_MAX_BYTES / 1000
+        ));
+    }
+    let sequence = format!("\x1b]52;c;{}\x07", encoded);
+    if in_tmux {
+        Ok(crate::terminal::tmux_passthrough(&sequence))
+    } else {
+        Ok(sequence)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_osc52_sequence_encodes_and_wraps_for_tmux() {
+        assert_eq!(
+            osc52_sequence("hi there", false).unwrap(),
+            "\x1b]52;c;aGkgdGhlcmU=\x07"
+        );
+        assert_eq!(
+            osc52_sequence("hi", true).unwrap(),
+            "\x1bPtmux;\x1b\x1b]52;c;aGk=\x07\x1b\\"
+        );
+
+        let too_big = "x".repeat(OSC52_MAX_BYTES);
+        assert!(osc52_sequence(&too_big, false).is_err());
+    }
+}
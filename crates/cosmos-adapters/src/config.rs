@@ -5,22 +5,490 @@
 use crate::keyring;
 use crate::util::debug_stderr_enabled;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+/// Logical job an AI request does, routed to a concrete model by `model_routing`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ModelRole {
+    /// Suggestion generation and refinement.
+    Suggest,
+    /// File classification for the grouping view.
+    Summarize,
+    /// Fix previews shown before apply.
+    Preview,
+    /// Applying fixes and repairing review findings.
+    Implement,
+    /// Adversarial review of applied changes.
+    Review,
+    /// Ask Cosmos answers.
+    Ask,
+}
+
+impl ModelRole {
+    pub const ALL: [ModelRole; 6] = [
+        ModelRole::Suggest,
+        ModelRole::Summarize,
+        ModelRole::Preview,
+        ModelRole::Implement,
+        ModelRole::Review,
+        ModelRole::Ask,
+    ];
+
+    /// Key used for this role in `model_routing`.
+    pub fn name(self) -> &'static str {
+        match self {
+            ModelRole::Suggest => "suggest",
+            ModelRole::Summarize => "summarize",
+            ModelRole::Preview => "preview",
+            ModelRole::Implement => "implement",
+            ModelRole::Review => "review",
+            ModelRole::Ask => "ask",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|role| role.name() == name)
+    }
+}
+
+/// Highest completion-token cap a route may set.
+pub const MAX_ROUTE_MAX_TOKENS: u32 = 128_000;
+
+/// Allowed range for a route's `context_window`
+pub const MIN_ROUTE_CONTEXT_WINDOW: u32 = 8_192;
+pub const MAX_ROUTE_CONTEXT_WINDOW: u32 = 2_000_000;
+
+/// Shortest allowed background suggestion scan interval
+pub const MIN_BACKGROUND_SCAN_MINUTES: u64 = 5;
+
+/// Model and sampling settings for one role. Unset fields keep the built-in behavior.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModelRoute {
+    /// Provider model id sent for this role instead of the built-in tier model.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Upper bound on completion tokens for this role's requests.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+    /// Sampling temperature for this role's requests (0.0-2.0).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    /// Context window (prompt plus completion tokens) of the routed model, used to size the
+    /// evidence sent with each request.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_window: Option<u32>,
+    /// Model ids tried in order when the primary model fails with a provider-side error.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fallbacks: Vec<String>,
+}
+
+/// Reuse of earlier responses for identical deterministic requests (temperature 0).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ResponseCacheConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// How long a stored response stays usable (default 24)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ttl_hours: Option<u64>,
+    /// Disk space the cache may use before the oldest responses are dropped (default 64)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_mb: Option<u64>,
+}
+
+/// Default `response_cache.ttl_hours`
+pub const DEFAULT_RESPONSE_CACHE_TTL_HOURS: u64 = 24;
+/// Default `response_cache.max_mb`
+pub const DEFAULT_RESPONSE_CACHE_MAX_MB: u64 = 64;
+/// Longest allowed `response_cache.ttl_hours` (30 days)
+pub const MAX_RESPONSE_CACHE_TTL_HOURS: u64 = 720;
+/// Largest allowed `response_cache.max_mb`
+pub const MAX_RESPONSE_CACHE_MB: u64 = 4096;
+
+impl ResponseCacheConfig {
+    pub fn ttl(&self) -> std::time::Duration {
+        let hours = self.ttl_hours.unwrap_or(DEFAULT_RESPONSE_CACHE_TTL_HOURS);
+        std::time::Duration::from_secs(hours * 3600)
+    }
+
+    pub fn max_bytes(&self) -> u64 {
+        self.max_mb.unwrap_or(DEFAULT_RESPONSE_CACHE_MAX_MB) * 1024 * 1024
+    }
+}
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Config {}
+/// When and how far suggestion runs are split across concurrent workers on very large repos.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SuggestShardingConfig {
+    /// Indexed lines of code from which runs are sharded (default 250000)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_loc: Option<usize>,
+    /// Most shards per run; 1 turns sharding off (default 4)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_shards: Option<usize>,
+}
+
+/// Default `suggest_sharding.min_loc`
+pub const DEFAULT_SHARDING_MIN_LOC: usize = 250_000;
+/// Default `suggest_sharding.max_shards`
+pub const DEFAULT_MAX_SHARDS: usize = 4;
+/// Largest allowed `suggest_sharding.max_shards`
+pub const MAX_SHARDS: usize = 8;
+
+impl SuggestShardingConfig {
+    pub fn min_loc(&self) -> usize {
+        self.min_loc.unwrap_or(DEFAULT_SHARDING_MIN_LOC)
+    }
+
+    pub fn max_shards(&self) -> usize {
+        self.max_shards.unwrap_or(DEFAULT_MAX_SHARDS)
+    }
+}
+
+/// TUI color theme: a built-in palette plus optional per-slot hex overrides.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ThemeConfig {
+    /// Built-in palette: `dark` (default), `light`, or `high-contrast`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Palette slot (`accent`, `green`, `grey_900`, ...) to `#rrggbb`, applied on top of `name`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub colors: BTreeMap<String, String>,
+}
+
+/// Branch and commit naming rules checked before Cosmos creates a branch or commits.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GitPolicy {
+    /// Fix branch template with `<kind>`, `<slug>`, and `<id>` placeholders
+    /// (e.g. `cosmos/<kind>/<slug>`). Unset keeps `fix/<id>-<slug>`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch_pattern: Option<String>,
+    /// Require conventional commit subjects (`type(scope): subject`).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub conventional_commits: bool,
+    /// Longest allowed commit subject line, in characters.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_subject_length: Option<usize>,
+}
 
-impl Default for Config {
-    fn default() -> Self {
-        Self {}
+impl GitPolicy {
+    fn is_unset(&self) -> bool {
+        self == &GitPolicy::default()
     }
 }
 
+/// How Cosmos gets your attention when a long task finishes while the terminal is in the
+/// background.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotifyMode {
+    /// Stay quiet
+    Off,
+    /// Terminal bell only
+    Bell,
+    /// Desktop notification (OSC 777 / OSC 9) only
+    Desktop,
+    /// Bell plus desktop notification
+    #[default]
+    Both,
+}
+
+impl NotifyMode {
+    pub fn bell(self) -> bool {
+        matches!(self, NotifyMode::Bell | NotifyMode::Both)
+    }
+
+    pub fn desktop(self) -> bool {
+        matches!(self, NotifyMode::Desktop | NotifyMode::Both)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Config {
+    /// Workspace trust decisions keyed by canonical repo path (`true` = trusted).
+    /// Untrusted repos never get their quick checks, tests, or hooks executed.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    workspace_trust: BTreeMap<String, bool>,
+    /// Per-role model overrides keyed by role name (`suggest`, `summarize`, `preview`,
+    /// `implement`, `review`, `ask`). Checked by `validate_model_routing` at startup.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    model_routing: BTreeMap<String, ModelRoute>,
+    /// TUI color theme; validated by the UI when it loads.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    theme: Option<ThemeConfig>,
+    /// Key binding overrides: action name to the keys that trigger it (e.g. `"navigate_down":
+    /// ["down", "j"]`). Validated by the UI keymap at startup.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    keybindings: BTreeMap<String, Vec<String>>,
+    /// Re-run suggestions in the background this often (minutes) while the TUI is open;
+    /// unset or 0 turns it off.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    background_scan_minutes: Option<u64>,
+    /// Branch naming and commit message rules enforced when shipping fixes.
+    #[serde(default, skip_serializing_if = "GitPolicy::is_unset")]
+    git_policy: GitPolicy,
+    /// Ship commits each fix onto its own branch, stacked on the previous one, and opens
+    /// pull requests per entry from the stack list.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    stacked_changes: bool,
+    /// Bell and/or desktop notification when a suggestion run or fix finishes unwatched.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    notify_on_finish: Option<NotifyMode>,
+    /// Hard per-operation spend caps in USD keyed by role name (e.g. `"implement": 0.15`
+    /// stops any single fix at 15 cents). Checked by `validate_spend_caps` at startup.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    spend_caps: BTreeMap<String, f64>,
+    /// Opt-in cache of responses to identical temperature-0 requests. Checked by
+    /// `validate_response_cache` at startup.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    response_cache: Option<ResponseCacheConfig>,
+    /// Split suggestion runs on very large repos by top-level module. Checked by
+    /// `validate_suggest_sharding` at startup.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    suggest_sharding: Option<SuggestShardingConfig>,
+}
+
 impl Config {
     fn sanitize(&mut self) {}
 
+    /// The configured route for a role, if any.
+    pub fn model_route(&self, role: ModelRole) -> Option<&ModelRoute> {
+        self.model_routing.get(role.name())
+    }
+
+    /// Set or replace the route for a role; call `save` to persist.
+    pub fn set_model_route(&mut self, role: ModelRole, route: ModelRoute) {
+        self.model_routing.insert(role.name().to_string(), route);
+    }
+
+    /// Add a repository's default routes underneath this config; the user's own entries win.
+    pub fn with_repo_model_routing(mut self, routes: BTreeMap<String, ModelRoute>) -> Self {
+        for (key, route) in routes {
+            self.model_routing.entry(key).or_insert(route);
+        }
+        self
+    }
+
+    /// Check every `model_routing` entry, returning one message per problem.
+    pub fn validate_model_routing(&self) -> Result<(), Vec<String>> {
+        let mut problems = Vec::new();
+        for (key, route) in &self.model_routing {
+            if ModelRole::from_name(key).is_none() {
+                let known: Vec<&str> = ModelRole::ALL.iter().map(|role| role.name()).collect();
+                problems.push(format!(
+                    "model_routing.{}: unknown role (expected one of {})",
+                    key,
+                    known.join(", ")
+                ));
+                continue;
+            }
+            if let Some(model) = &route.model {
+                if model.trim().is_empty() || model.chars().any(char::is_whitespace) {
+                    problems.push(format!(
+                        "model_routing.{}.model: '{}' is not a model id",
+                        key, model
+                    ));
+                }
+            }
+            for (i, fallback) in route.fallbacks.iter().enumerate() {
+                if fallback.trim().is_empty() || fallback.chars().any(char::is_whitespace) {
+                    problems.push(format!(
+                        "model_routing.{}.fallbacks: '{}' is not a model id",
+                        key, fallback
+                    ));
+                } else if route.model.as_ref() == Some(fallback)
+                    || route.fallbacks[..i].contains(fallback)
+                {
+                    problems.push(format!(
+                        "model_routing.{}.fallbacks: '{}' is already earlier in the chain",
+                        key, fallback
+                    ));
+                }
+            }
+            if let Some(max_tokens) = route.max_tokens {
+                if max_tokens == 0 || max_tokens > MAX_ROUTE_MAX_TOKENS {
+                    problems.push(format!(
+                        "model_routing.{}.max_tokens: {} is outside 1-{}",
+                        key, max_tokens, MAX_ROUTE_MAX_TOKENS
+                    ));
+                }
+            }
+            if let Some(temperature) = route.temperature {
+                if !(0.0..=2.0).contains(&temperature) {
+                    problems.push(format!(
+                        "model_routing.{}.temperature: {} is outside 0.0-2.0",
+                        key, temperature
+                    ));
+                }
+            }
+            if let Some(window) = route.context_window {
+                if !(MIN_ROUTE_CONTEXT_WINDOW..=MAX_ROUTE_CONTEXT_WINDOW).contains(&window) {
+                    problems.push(format!(
+                        "model_routing.{}.context_window: {} is outside {}-{}",
+                        key, window, MIN_ROUTE_CONTEXT_WINDOW, MAX_ROUTE_CONTEXT_WINDOW
+                    ));
+                }
+            }
+        }
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems)
+        }
+    }
+
+    /// The spend cap for one operation of `role`, if configured.
+    pub fn spend_cap(&self, role: ModelRole) -> Option<f64> {
+        self.spend_caps.get(role.name()).copied()
+    }
+
+    /// Check every `spend_caps` entry, returning one message per problem.
+    pub fn validate_spend_caps(&self) -> Result<(), Vec<String>> {
+        let mut problems = Vec::new();
+        for (key, cap) in &self.spend_caps {
+            if ModelRole::from_name(key).is_none() {
+                let known: Vec<&str> = ModelRole::ALL.iter().map(|role| role.name()).collect();
+                problems.push(format!(
+                    "spend_caps.{}: unknown role (expected one of {})",
+                    key,
+                    known.join(", ")
+                ));
+            } else if !cap.is_finite() || *cap <= 0.0 {
+                problems.push(format!(
+                    "spend_caps.{}: {} must be a dollar amount greater than 0",
+                    key, cap
+                ));
+            }
+        }
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems)
+        }
+    }
+
+    /// Response cache settings, when the cache is turned on.
+    pub fn response_cache(&self) -> Option<&ResponseCacheConfig> {
+        self.response_cache.as_ref().filter(|cache| cache.enabled)
+    }
+
+    /// Check the `response_cache` limits, returning one message per problem.
+    pub fn validate_response_cache(&self) -> Result<(), Vec<String>> {
+        let mut problems = Vec::new();
+        if let Some(cache) = &self.response_cache {
+            if let Some(hours) = cache.ttl_hours {
+                if !(1..=MAX_RESPONSE_CACHE_TTL_HOURS).contains(&hours) {
+                    problems.push(format!(
+                        "response_cache.ttl_hours: {} is outside 1-{}",
+                        hours, MAX_RESPONSE_CACHE_TTL_HOURS
+                    ));
+                }
+            }
+            if let Some(mb) = cache.max_mb {
+                if !(1..=MAX_RESPONSE_CACHE_MB).contains(&mb) {
+                    problems.push(format!(
+                        "response_cache.max_mb: {} is outside 1-{}",
+                        mb, MAX_RESPONSE_CACHE_MB
+                    ));
+                }
+            }
+        }
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems)
+        }
+    }
+
+    /// Suggestion sharding settings (defaults when unset).
+    pub fn suggest_sharding(&self) -> SuggestShardingConfig {
+        self.suggest_sharding.clone().unwrap_or_default()
+    }
+
+    /// Check `suggest_sharding.max_shards`, returning one message per problem.
+    pub fn validate_suggest_sharding(&self) -> Result<(), Vec<String>> {
+        let mut problems = Vec::new();
+        if let Some(max_shards) = self
+            .suggest_sharding
+            .as_ref()
+            .and_then(|sharding| sharding.max_shards)
+        {
+            if !(1..=MAX_SHARDS).contains(&max_shards) {
+                problems.push(format!(
+                    "suggest_sharding.max_shards: {} is outside 1-{}",
+                    max_shards, MAX_SHARDS
+                ));
+            }
+        }
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems)
+        }
+    }
+
+    pub fn theme(&self) -> Option<&ThemeConfig> {
+        self.theme.as_ref()
+    }
+
+    pub fn keybindings(&self) -> &BTreeMap<String, Vec<String>> {
+        &self.keybindings
+    }
+
+    pub fn git_policy(&self) -> &GitPolicy {
+        &self.git_policy
+    }
+
+    pub fn stacked_changes(&self) -> bool {
+        self.stacked_changes
+    }
+
+    pub fn notify_on_finish(&self) -> NotifyMode {
+        self.notify_on_finish.unwrap_or_default()
+    }
+
+    /// How often the TUI re-runs suggestions in the background, if enabled. Intervals under
+    /// `MIN_BACKGROUND_SCAN_MINUTES` are raised to it to bound AI cost.
+    pub fn background_scan_interval(&self) -> Option<std::time::Duration> {
+        self.background_scan_minutes
+            .filter(|minutes| *minutes > 0)
+            .map(|minutes| {
+                std::time::Duration::from_secs(minutes.max(MIN_BACKGROUND_SCAN_MINUTES) * 60)
+            })
+    }
+
+    /// Switch the built-in palette, keeping custom colors; call `save` to persist.
+    pub fn set_theme_name(&mut self, name: &str) {
+        self.theme.get_or_insert_with(ThemeConfig::default).name = Some(name.to_string());
+    }
+
+    fn workspace_key(repo_root: &Path) -> String {
+        repo_root
+            .canonicalize()
+            .unwrap_or_else(|_| repo_root.to_path_buf())
+            .display()
+            .to_string()
+    }
+
+    /// The recorded trust decision for a repo, or None if the user was never asked.
+    pub fn workspace_trust(&self, repo_root: &Path) -> Option<bool> {
+        self.workspace_trust
+            .get(&Self::workspace_key(repo_root))
+            .copied()
+    }
+
+    /// Whether the repo may run its own commands (quick checks, tests, hooks).
+    pub fn is_workspace_trusted(&self, repo_root: &Path) -> bool {
+        self.workspace_trust(repo_root).unwrap_or(false)
+    }
+
+    /// Record whether the repo is trusted; call `save` to persist.
+    pub fn set_workspace_trust(&mut self, repo_root: &Path, trusted: bool) {
+        self.workspace_trust
+            .insert(Self::workspace_key(repo_root), trusted);
+    }
+
     /// Get the config directory path
     fn config_dir() -> Option<PathBuf> {
         dirs::config_dir().map(|p| p.join("cosmos"))
@@ -283,10 +751,120 @@ mod tests {
         let _parsed: Config = serde_json::from_str(legacy).unwrap();
     }
 
+    #[test]
+    fn test_notify_on_finish_defaults_to_both_and_parses() {
+        assert_eq!(Config::default().notify_on_finish(), NotifyMode::Both);
+        let parsed: Config = serde_json::from_str(r#"{"notify_on_finish":"bell"}"#).unwrap();
+        assert_eq!(parsed.notify_on_finish(), NotifyMode::Bell);
+        assert!(parsed.notify_on_finish().bell() && !parsed.notify_on_finish().desktop());
+    }
+
+    #[test]
+    fn test_spend_caps_parse_and_validate() {
+        let parsed: Config =
+            serde_json::from_str(r#"{"spend_caps":{"implement":0.15,"review":0.05}}"#).unwrap();
+        assert_eq!(parsed.spend_cap(ModelRole::Implement), Some(0.15));
+        assert_eq!(parsed.spend_cap(ModelRole::Ask), None);
+        assert!(parsed.validate_spend_caps().is_ok());
+
+        let bad: Config =
+            serde_json::from_str(r#"{"spend_caps":{"fix":0.15,"review":0}}"#).unwrap();
+        let problems = bad.validate_spend_caps().unwrap_err();
+        assert_eq!(problems.len(), 2);
+        assert!(problems[0].starts_with("spend_caps.fix: unknown role"));
+        assert!(problems[1].contains("greater than 0"));
+    }
+
+    #[test]
+    fn test_response_cache_is_opt_in_with_bounded_limits() {
+        assert!(Config::default().response_cache().is_none());
+        let off: Config = serde_json::from_str(r#"{"response_cache":{"ttl_hours":2}}"#).unwrap();
+        assert!(off.response_cache().is_none());
+
+        let on: Config =
+            serde_json::from_str(r#"{"response_cache":{"enabled":true,"max_mb":8}}"#).unwrap();
+        let cache = on.response_cache().unwrap();
+        assert_eq!(cache.ttl(), std::time::Duration::from_secs(24 * 3600));
+        assert_eq!(cache.max_bytes(), 8 * 1024 * 1024);
+        assert!(on.validate_response_cache().is_ok());
+
+        let bad: Config = serde_json::from_str(
+            r#"{"response_cache":{"enabled":true,"ttl_hours":0,"max_mb":100000}}"#,
+        )
+        .unwrap();
+        assert_eq!(bad.validate_response_cache().unwrap_err().len(), 2);
+    }
+
+    #[test]
+    fn test_suggest_sharding_defaults_and_bounds() {
+        let sharding = Config::default().suggest_sharding();
+        assert_eq!(sharding.min_loc(), DEFAULT_SHARDING_MIN_LOC);
+        assert_eq!(sharding.max_shards(), DEFAULT_MAX_SHARDS);
+
+        let off: Config = serde_json::from_str(r#"{"suggest_sharding":{"max_shards":1}}"#).unwrap();
+        assert_eq!(off.suggest_sharding().max_shards(), 1);
+        assert!(off.validate_suggest_sharding().is_ok());
+
+        let bad: Config = serde_json::from_str(r#"{"suggest_sharding":{"max_shards":0}}"#).unwrap();
+        assert_eq!(bad.validate_suggest_sharding().unwrap_err().len(), 1);
+    }
+
     #[test]
     fn test_config_round_trip() {
-        let config = Config {};
+        let config = Config::default();
         let encoded = serde_json::to_string(&config).unwrap();
         let _decoded: Config = serde_json::from_str(&encoded).unwrap();
     }
+
+    #[test]
+    fn test_model_routing_round_trips_and_validates() {
+        let parsed: Config = serde_json::from_str(
+            r#"{"model_routing":{"review":{"model":"gpt-oss-120b","max_tokens":8000,"temperature":0.2},"ask":{"temperature":0.7}}}"#,
+        )
+        .unwrap();
+        assert!(parsed.validate_model_routing().is_ok());
+        let review = parsed.model_route(ModelRole::Review).unwrap();
+        assert_eq!(review.model.as_deref(), Some("gpt-oss-120b"));
+        assert_eq!(review.max_tokens, Some(8000));
+        assert_eq!(parsed.model_route(ModelRole::Ask).unwrap().model, None);
+        assert!(parsed.model_route(ModelRole::Suggest).is_none());
+
+        let bad: Config = serde_json::from_str(
+            r#"{"model_routing":{"reveiw":{},"suggest":{"model":" ","max_tokens":0,"temperature":3.5,"context_window":1000}}}"#,
+        )
+        .unwrap();
+        let problems = bad.validate_model_routing().unwrap_err();
+        assert_eq!(problems.len(), 5);
+        assert!(problems[0].contains("unknown role"));
+
+        let chained: Config = serde_json::from_str(
+            r#"{"model_routing":{"implement":{"model":"a","fallbacks":["b","c"]},"review":{"model":"a","fallbacks":["a","b","b",""]}}}"#,
+        )
+        .unwrap();
+        let implement = chained.model_route(ModelRole::Implement).unwrap();
+        assert_eq!(implement.fallbacks, vec!["b", "c"]);
+        let problems = chained.validate_model_routing().unwrap_err();
+        assert_eq!(problems.len(), 3);
+        assert!(problems
+            .iter()
+            .all(|p| p.starts_with("model_routing.review.fallbacks")));
+    }
+
+    #[test]
+    fn test_workspace_trust_defaults_to_untrusted_and_round_trips() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = Config::default();
+        assert_eq!(config.workspace_trust(dir.path()), None);
+        assert!(!config.is_workspace_trusted(dir.path()));
+
+        config.set_workspace_trust(dir.path(), true);
+        let decoded: Config =
+            serde_json::from_str(&serde_json::to_string(&config).unwrap()).unwrap();
+        assert!(decoded.is_workspace_trusted(dir.path()));
+
+        let mut declined = Config::default();
+        declined.set_workspace_trust(dir.path(), false);
+        assert_eq!(declined.workspace_trust(dir.path()), Some(false));
+        assert!(!declined.is_workspace_trusted(dir.path()));
+    }
 }
@@ -0,0 +1,423 @@
+//! Code-hosting forge abstraction for Ship
+//!
+//! Ship opens a pull request (GitHub, Bitbucket Cloud) or merge request (GitLab)
+//! on whichever forge the repository's remote points at. Each forge lives in its
+//! own module; this one detects the forge from the remote URL and dispatches.
+
+use crate::{bitbucket, github, gitlab};
+use anyhow::{Context, Result};
+use git2::Repository;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::path::Path;
+use std::pin::Pin;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ForgeKind {
+    GitHub,
+    GitLab,
+    Bitbucket,
+}
+
+impl ForgeKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ForgeKind::GitHub => "GitHub",
+            ForgeKind::GitLab => "GitLab",
+            ForgeKind::Bitbucket => "Bitbucket",
+        }
+    }
+
+    /// What the forge calls a pull request.
+    pub fn request_noun(&self) -> &'static str {
+        match self {
+            ForgeKind::GitLab => "merge request",
+            ForgeKind::GitHub | ForgeKind::Bitbucket => "pull request",
+        }
+    }
+
+    /// CLI flag that stores credentials for this forge.
+    pub fn login_flag(&self) -> &'static str {
+        match self {
+            ForgeKind::GitHub => "--github-login",
+            ForgeKind::GitLab => "--gitlab-login",
+            ForgeKind::Bitbucket => "--bitbucket-login",
+        }
+    }
+
+    /// Environment variable that overrides the stored credentials.
+    pub fn token_env(&self) -> &'static str {
+        match self {
+            ForgeKind::GitHub => "GITHUB_TOKEN",
+            ForgeKind::GitLab => "GITLAB_TOKEN",
+            ForgeKind::Bitbucket => "BITBUCKET_TOKEN",
+        }
+    }
+}
+
+/// A repository on a forge, as identified by a git remote.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForgeRepo {
+    pub kind: ForgeKind,
+    pub host: String,
+    /// Owner, GitLab group path (may contain `/`), or Bitbucket workspace.
+    pub owner: String,
+    pub name: String,
+}
+
+impl ForgeRepo {
+    pub fn web_url(&self) -> String {
+        format!("https://{}/{}/{}", self.host, self.owner, self.name)
+    }
+}
+
+/// A pull/merge request to open from `head` into `base`.
+#[derive(Debug, Clone, Default)]
+pub struct PullRequest {
+    pub base: String,
+    pub head: String,
+    pub title: String,
+    pub body: String,
+    pub draft: bool,
+    /// Usernames to request review from; GitHub teams are written `org/team`.
+    pub reviewers: Vec<String>,
+    pub labels: Vec<String>,
+}
+
+/// Options chosen on the Ship Confirm screen before the request is opened.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PrOptions {
+    pub draft: bool,
+    /// Target branch; the repository's default branch when unset.
+    pub base: Option<String>,
+    pub reviewers: Vec<String>,
+    pub labels: Vec<String>,
+}
+
+/// Where a pull/merge request stands on its forge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PrState {
+    Open,
+    Merged,
+    /// Closed without merging (declined on Bitbucket)
+    Closed,
+}
+
+impl PrState {
+    pub fn label(self) -> &'static str {
+        match self {
+            PrState::Open => "open",
+            PrState::Merged => "merged",
+            PrState::Closed => "closed",
+        }
+    }
+}
+
+/// The request number in a pull/merge request web URL.
+pub fn pull_request_number(url: &str) -> Option<u64> {
+    let mut segments = url.trim().trim_end_matches('/').split('/');
+    segments.find(|segment| matches!(*segment, "pull" | "merge_requests" | "pull-requests"))?;
+    segments.next()?.parse().ok()
+}
+
+/// Split a comma- or whitespace-separated list, dropping blanks, `@` prefixes, and duplicates.
+pub fn split_list(input: &str) -> Vec<String> {
+    let mut items: Vec<String> = Vec::new();
+    for item in input.split(|c: char| c == ',' || c.is_whitespace()) {
+        let item = item.trim().trim_start_matches('@');
+        if !item.is_empty() && !items.iter().any(|existing| existing == item) {
+            items.push(item.to_string());
+        }
+    }
+    items
+}
+
+pub trait Forge: Send + Sync {
+    fn kind(&self) -> ForgeKind;
+
+    /// Whether credentials are available (environment or keychain).
+    fn is_authenticated(&self) -> bool;
+
+    /// Open the request and return its web URL.
+    fn create_pull_request<'a>(
+        &'a self,
+        repo: &'a ForgeRepo,
+        request: &'a PullRequest,
+    ) -> Pin<Box<dyn Future<Output = Result<String>> + Send + 'a>>;
+
+    /// Browser URL that starts a new request, for users without stored credentials.
+    fn new_pull_request_url(&self, repo: &ForgeRepo, base: &str, head: &str) -> String;
+
+    /// Current state of request `number`; public repositories work without credentials.
+    fn pull_request_state<'a>(
+        &'a self,
+        repo: &'a ForgeRepo,
+        number: u64,
+    ) -> Pin<Box<dyn Future<Output = Result<PrState>> + Send + 'a>>;
+}
+
+pub fn forge_for(kind: ForgeKind) -> &'static dyn Forge {
+    match kind {
+        ForgeKind::GitHub => &github::GitHubForge,
+        ForgeKind::GitLab => &gitlab::GitLabForge,
+        ForgeKind::Bitbucket => &bitbucket::BitbucketForge,
+    }
+}
+
+/// Split a remote URL into host and repository path.
+///
+/// Supports scp-like (`git@host:path.git`), `ssh://`, and `https://` remotes.
+fn split_remote_url(url: &str) -> Option<(String, String)> {
+    let url = url.trim();
+    let (host, path) = if url.contains("://") {
+        let parsed = url::Url::parse(url).ok()?;
+        (parsed.host_str()?.to_string(), parsed.path().to_string())
+    } else {
+        let (authority, path) = url.split_once(':')?;
+        let host = authority.rsplit('@').next()?;
+        (host.to_string(), path.to_string())
+    };
+    let path = path
+        .trim_start_matches('/')
+        .trim_end_matches('/')
+        .trim_end_matches(".git")
+        .to_string();
+    if host.is_empty() || path.is_empty() {
+        return None;
+    }
+    Some((host.to_ascii_lowercase(), path))
+}
+
+fn forge_kind_for_host(host: &str) -> Option<ForgeKind> {
+    match host {
+        "github.com" => Some(ForgeKind::GitHub),
+        "bitbucket.org" => Some(ForgeKind::Bitbucket),
+        "gitlab.com" => Some(ForgeKind::GitLab),
+        // Self-managed GitLab instances are conventionally served from gitlab.<domain>.
+        _ if host.starts_with("gitlab.") => Some(ForgeKind::GitLab),
+        _ => None,
+    }
+}
+
+/// Identify the forge and repository a remote URL points at.
+pub fn parse_forge_remote(url: &str) -> Option<ForgeRepo> {
+    let (host, path) = split_remote_url(url)?;
+    let kind = forge_kind_for_host(&host)?;
+    let (owner, name) = match kind {
+        ForgeKind::GitHub => github::parse_remote_url(url)?,
+        ForgeKind::GitLab => {
+            let (group, name) = path.rsplit_once('/')?;
+            (group.to_string(), name.to_string())
+        }
+        ForgeKind::Bitbucket => {
+            let mut parts = path.splitn(3, '/');
+            let workspace = parts.next()?;
+            let slug = parts.next()?;
+            (workspace.to_string(), slug.to_string())
+        }
+    };
+    if owner.is_empty() || name.is_empty() {
+        return None;
+    }
+    Some(ForgeRepo {
+        kind,
+        host,
+        owner,
+        name,
+    })
+}
+
+/// Look up the state of the pull/merge request at `url` on the repository's forge.
+pub async fn pull_request_state(repo_path: &Path, url: &str) -> Result<PrState> {
+    let remote = detect_forge_remote(repo_path)?;
+    let number = pull_request_number(url)
+        .ok_or_else(|| anyhow::anyhow!("'{}' isn't a {} URL", url, remote.kind.request_noun()))?;
+    forge_for(remote.kind)
+        .pull_request_state(&remote, number)
+        .await
+}
+
+/// Find the forge repository behind the repo's remotes (origin and upstream first).
+pub fn detect_forge_remote(repo_path: &Path) -> Result<ForgeRepo> {
+    let repo = Repository::discover(repo_path).with_context(|| {
+        format!(
+            "Failed to open repository from path '{}'",
+            repo_path.display()
+        )
+    })?;
+
+    let mut names = vec!["origin".to_string(), "upstream".to_string()];
+    if let Ok(remotes) = repo.remotes() {
+        for name in remotes.iter().flatten() {
+            if !names.iter().any(|existing| existing == name) {
+                names.push(name.to_string());
+            }
+        }
+    }
+    for name in names {
+        if let Ok(remote) = repo.find_remote(&name) {
+            if let Some(forge_repo) = remote.url().and_then(parse_forge_remote) {
+                return Ok(forge_repo);
+            }
+        }
+    }
+
+    Err(anyhow::anyhow!(
+        "No GitHub, GitLab, or Bitbucket remote found. Make sure you have a remote pointing to github.com, gitlab.com (or a gitlab.* host), or bitbucket.org"
+    ))
+}
+
+type StoreToken = fn(&str) -> Result<(), String>;
+
+/// Prompt for a GitLab or Bitbucket token and store it in the keychain.
+pub fn setup_token_interactive(kind: ForgeKind) -> Result<(), String> {
+    use std::io::{self, Write};
+
+    let (instructions, store): (&[&str], StoreToken) = match kind {
+        ForgeKind::GitLab => (
+            &[
+                "Create a personal access token with the `api` scope:",
+                "  https://gitlab.com/-/user_settings/personal_access_tokens",
+                "(self-managed GitLab: the same page on your instance)",
+            ],
+            crate::keyring::set_gitlab_token,
+        ),
+        ForgeKind::Bitbucket => (
+            &[
+                "Create an app password with Pull requests: Write and enter it as",
+                "`username:app_password`, or paste a repository access token:",
+                "  https://bitbucket.org/account/settings/app-passwords/",
+            ],
+            crate::keyring::set_bitbucket_token,
+        ),
+        ForgeKind::GitHub => {
+            return Err("GitHub uses the browser flow: run `cosmos --github-login`".to_string())
+        }
+    };
+
+    println!();
+    println!("  {} Authentication", kind.label());
+    println!();
+    println!("  Cosmos opens {}s on your behalf.", kind.request_noun());
+    for line in instructions {
+        println!("  {}", line);
+    }
+    println!();
+    println!(
+        "  We'll store it in your {}. Prefer env vars? Set {}.",
+        crate::keyring::credentials_store_label(),
+        kind.token_env()
+    );
+    println!();
+    print!("  Token: ");
+    io::stdout().flush().map_err(|e| e.to_string())?;
+
+    let mut token = String::new();
+    io::stdin()
+        .read_line(&mut token)
+        .map_err(|e| e.to_string())?;
+    let token = token.trim();
+    if token.is_empty() {
+        return Err("No token provided".to_string());
+    }
+    store(token)?;
+
+    println!();
+    println!(
+        "  + Token saved to {}",
+        crate::keyring::credentials_store_label()
+    );
+    println!();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_forge_remote_detects_each_forge() {
+        let github = parse_forge_remote("git@github.com:cameronspears/cosmos.git").unwrap();
+        assert_eq!(github.kind, ForgeKind::GitHub);
+        assert_eq!(
+            (github.owner.as_str(), github.name.as_str()),
+            ("cameronspears", "cosmos")
+        );
+
+        let gitlab = parse_forge_remote("https://gitlab.com/group/sub/project.git").unwrap();
+        assert_eq!(gitlab.kind, ForgeKind::GitLab);
+        assert_eq!(gitlab.owner, "group/sub");
+        assert_eq!(gitlab.name, "project");
+
+        let bitbucket = parse_forge_remote("git@bitbucket.org:workspace/repo.git").unwrap();
+        assert_eq!(bitbucket.kind, ForgeKind::Bitbucket);
+        assert_eq!(bitbucket.web_url(), "https://bitbucket.org/workspace/repo");
+    }
+
+    #[test]
+    fn test_parse_forge_remote_handles_ssh_urls_and_self_managed_gitlab() {
+        let gitlab = parse_forge_remote("ssh://git@gitlab.example.com:2222/team/app.git").unwrap();
+        assert_eq!(gitlab.kind, ForgeKind::GitLab);
+        assert_eq!(gitlab.host, "gitlab.example.com");
+        assert_eq!(gitlab.owner, "team");
+
+        let bitbucket =
+            parse_forge_remote("https://user@bitbucket.org/workspace/repo.git").unwrap();
+        assert_eq!(bitbucket.owner, "workspace");
+        assert_eq!(bitbucket.name, "repo");
+    }
+
+    #[test]
+    fn test_parse_forge_remote_rejects_unknown_hosts_and_bare_owners() {
+        assert!(parse_forge_remote("https://git.example.com/team/app.git").is_none());
+        assert!(parse_forge_remote("https://gitlab.com/project").is_none());
+        assert!(parse_forge_remote("not-a-url").is_none());
+    }
+
+    #[test]
+    fn test_split_list_accepts_commas_spaces_and_mentions() {
+        assert_eq!(
+            split_list("@alice, bob  org/team,alice"),
+            vec!["alice", "bob", "org/team"]
+        );
+        assert!(split_list(" , ").is_empty());
+    }
+
+    #[test]
+    fn test_pull_request_number_reads_each_forge_url() {
+        assert_eq!(
+            pull_request_number("https://github.com/owner/repo/pull/42"),
+            Some(42)
+        );
+        assert_eq!(
+            pull_request_number("https://gitlab.com/group/sub/repo/-/merge_requests/7/"),
+            Some(7)
+        );
+        assert_eq!(
+            pull_request_number("https://bitbucket.org/ws/repo/pull-requests/3"),
+            Some(3)
+        );
+        assert_eq!(pull_request_number("https://github.com/owner/repo"), None);
+    }
+
+    #[test]
+    fn test_new_pull_request_urls_point_at_each_forge() {
+        let url = |remote: &str| {
+            let repo = parse_forge_remote(remote).unwrap();
+            forge_for(repo.kind).new_pull_request_url(&repo, "main", "cosmos/fix")
+        };
+        assert_eq!(
+            url("https://github.com/owner/repo"),
+            "https://github.com/owner/repo/compare/main...cosmos/fix?expand=1"
+        );
+        assert_eq!(
+            url("https://gitlab.com/group/repo"),
+            "https://gitlab.com/group/repo/-/merge_requests/new?merge_request%5Bsource_branch%5D=cosmos%2Ffix&merge_request%5Btarget_branch%5D=main"
+        );
+        assert_eq!(
+            url("https://bitbucket.org/ws/repo"),
+            "https://bitbucket.org/ws/repo/pull-requests/new?source=cosmos%2Ffix&dest=main"
+        );
+    }
+}
@@ -1,11 +1,17 @@
 //! Git operations for the fix-and-ship workflow
 //!
-//! Provides branch, stage, commit, and push operations.
+//! Provides branch, stage, commit, and push operations, and runs the repo's
+//! pre-commit hook before Ship commits. Branch names and commit messages are checked
+//! against the configured `GitPolicy` before git runs.
 
+use crate::config::GitPolicy;
 use crate::util::{resolve_repo_path_allow_new, run_command_with_timeout, CommandRunResult};
 use anyhow::{Context, Result};
 use git2::{Repository, Signature, StatusOptions};
-use std::path::Path;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::time::Duration;
 
@@ -144,14 +150,17 @@ pub fn create_fix_branch_from_main(repo_path: &Path, branch_name: &str) -> Resul
 /// Create a new branch from the current checkout/HEAD and check it out.
 /// Used when the user chooses to continue from their current branch context.
 pub fn create_fix_branch_from_current(repo_path: &Path, branch_name: &str) -> Result<String> {
-    create_fix_branch_from_current_with_outcome(repo_path, branch_name)
+    create_fix_branch_from_current_with_outcome(repo_path, branch_name, &GitPolicy::default())
         .map(|outcome| outcome.branch_name)
 }
 
+/// Like `create_fix_branch_from_current`, refusing names the policy's branch pattern rejects.
 pub fn create_fix_branch_from_current_with_outcome(
     repo_path: &Path,
     branch_name: &str,
+    policy: &GitPolicy,
 ) -> Result<BranchCreateOutcome> {
+    check_branch_name(policy, branch_name).map_err(|problem| anyhow::anyhow!(problem))?;
     let repo = open_repo_discover(repo_path)?;
     let head = repo
         .head()
@@ -275,6 +284,146 @@ pub fn delete_local_branch_safe(repo_path: &Path, branch_name: &str) -> Result<(
     Ok(())
 }
 
+/// Whether a local branch named `branch_name` exists.
+pub fn local_branch_exists(repo_path: &Path, branch_name: &str) -> bool {
+    open_repo_discover(repo_path)
+        .map(|repo| {
+            repo.find_branch(branch_name, git2::BranchType::Local)
+                .is_ok()
+        })
+        .unwrap_or(false)
+}
+
+/// Delete a branch whose pull request merged, locally and on its remote.
+///
+/// Refuses the checked-out branch and a branch with commits its remote-tracking branch
+/// doesn't have, since those never made it into the pull request. A remote branch the
+/// forge already deleted is fine, but with no tracking branch left to compare against the
+/// local tip must already be in `base` (or its upstream) or in `merged_head`, the commit the
+/// pull request was opened at.
+pub fn delete_merged_branch(
+    repo_path: &Path,
+    branch_name: &str,
+    base: Option<&str>,
+    merged_head: Option<&str>,
+) -> Result<()> {
+    let repo = open_repo_discover(repo_path)?;
+    if let Ok(head) = repo.head() {
+        if head.shorthand() == Some(branch_name) {
+            return Err(anyhow::anyhow!(
+                "Refusing to delete currently checked out branch '{}'",
+                branch_name
+            ));
+        }
+    }
+    let mut branch = repo
+        .find_branch(branch_name, git2::BranchType::Local)
+        .context(format!("Local branch '{}' not found", branch_name))?;
+
+    if let Ok(upstream) = branch.upstream() {
+        let local = branch.get().target();
+        let remote = upstream.get().target();
+        if let (Some(local), Some(remote)) = (local, remote) {
+            if local != remote && !repo.graph_descendant_of(remote, local)? {
+                return Err(anyhow::anyhow!(
+                    "Refusing to delete '{}': it has commits that were never pushed",
+                    branch_name
+                ));
+            }
+        }
+        if push_disabled_by_env() {
+            return Err(anyhow::anyhow!(
+                "Remote branch delete blocked: sandbox mode is active (COSMOS_DISABLE_PUSH=1)."
+            ));
+        }
+        let remote_name =
+            resolve_push_remote(&repo, branch_name).unwrap_or_else(|_| "origin".to_string());
+        let mut cmd = Command::new("git");
+        cmd.current_dir(repo_path)
+            .args(["push", &remote_name, "--delete", branch_name])
+            .env("GIT_TERMINAL_PROMPT", "0");
+        let output = run_command_with_timeout(&mut cmd, Duration::from_secs(GIT_PUSH_TIMEOUT_SECS))
+            .map_err(|e| anyhow::anyhow!("Failed to run git push command: {}", e))?;
+        let deleted = output.status.map(|s| s.success()).unwrap_or(false);
+        if !deleted && !output.stderr.contains("remote ref does not exist") {
+            return Err(anyhow::anyhow!(
+                "Failed to delete remote branch (remote: {}, branch: {}): {}",
+                remote_name,
+                branch_name,
+                if output.timed_out {
+                    "timed out"
+                } else {
+                    output.stderr.trim()
+                }
+            ));
+        }
+    } else {
+        ensure_tip_merged(&repo, &branch, branch_name, base, merged_head)?;
+    }
+
+    branch
+        .delete()
+        .context(format!("Failed to delete local branch '{}'", branch_name))?;
+    Ok(())
+}
+
+/// Fail unless the tip of `branch` is reachable from `base`, its upstream, or `merged_head`,
+/// listing the commits deleting it would lose.
+fn ensure_tip_merged(
+    repo: &Repository,
+    branch: &git2::Branch,
+    branch_name: &str,
+    base: Option<&str>,
+    merged_head: Option<&str>,
+) -> Result<()> {
+    let Some(local) = branch.get().target() else {
+        return Ok(());
+    };
+    let mut merged_into = Vec::new();
+    if let Some(base) = base.and_then(|base| repo.find_branch(base, git2::BranchType::Local).ok()) {
+        merged_into.extend(base.get().target());
+        if let Ok(upstream) = base.upstream() {
+            merged_into.extend(upstream.get().target());
+        }
+    }
+    merged_into.extend(merged_head.and_then(|head| git2::Oid::from_str(head).ok()));
+    for target in &merged_into {
+        if *target == local || repo.graph_descendant_of(*target, local).unwrap_or(false) {
+            return Ok(());
+        }
+    }
+
+    const LISTED: usize = 5;
+    let mut walk = repo.revwalk()?;
+    walk.push(local)?;
+    for target in &merged_into {
+        let _ = walk.hide(*target);
+    }
+    let mut lost = Vec::new();
+    let mut more = 0usize;
+    for oid in walk.flatten() {
+        if lost.len() == LISTED {
+            more += 1;
+            continue;
+        }
+        let summary = repo
+            .find_commit(oid)
+            .ok()
+            .and_then(|commit| commit.summary().map(str::to_string))
+            .unwrap_or_default();
+        lost.push(format!("{} {}", &oid.to_string()[..8], summary));
+    }
+    if more > 0 {
+        lost.push(format!("...and {} more", more));
+    }
+    Err(anyhow::anyhow!(
+        "Refusing to delete '{}': its remote branch is gone and these commits aren't in {} or the merged pull request: {}",
+        branch_name,
+        base.unwrap_or("the base branch"),
+        lost.join("; ")
+    ))
+}
+
 /// Generate a branch name from a suggestion summary
 pub fn generate_fix_branch_name(suggestion_id: &str, summary: &str) -> String {
     // Take first 8 chars of UUID
@@ -294,6 +443,149 @@ pub fn generate_fix_branch_name(suggestion_id: &str, summary: &str) -> String {
     }
 }
 
+/// Generate a fix branch name following the policy's branch pattern, if one is set
+pub fn policy_branch_name(
+    policy: &GitPolicy,
+    kind: &str,
+    suggestion_id: &str,
+    summary: &str,
+) -> String {
+    let Some(pattern) = policy.branch_pattern.as_deref() else {
+        return generate_fix_branch_name(suggestion_id, summary);
+    };
+    let short_id = &suggestion_id[..8.min(suggestion_id.len())];
+    let slug = sanitize_branch_slug(summary);
+    let kind = sanitize_branch_slug(kind);
+    pattern
+        .replace("<kind>", if kind.is_empty() { "fix" } else { &kind })
+        .replace("<id>", short_id)
+        .replace("<slug>", if slug.is_empty() { short_id } else { &slug })
+}
+
+/// Whether `name` looks like a branch Cosmos created: one matching the policy's branch
+/// pattern when set, otherwise a default `fix/...` or `cosmos-fix-...` name.
+pub fn is_cosmos_branch(policy: &GitPolicy, name: &str) -> bool {
+    if policy.branch_pattern.is_some() {
+        return check_branch_name(policy, name).is_ok();
+    }
+    is_valid_git_ref(name) && (name.starts_with("fix/") || name.starts_with("cosmos-fix-"))
+}
+
+/// Check a branch name against the policy's branch pattern.
+///
+/// Each placeholder matches one or more characters within a single path segment, so
+/// `cosmos/<kind>/<slug>` accepts `cosmos/bug-fix/guard-empty-input` but not `cosmos/guard`.
+pub fn check_branch_name(policy: &GitPolicy, name: &str) -> std::result::Result<(), String> {
+    if !is_valid_git_ref(name) {
+        return Err(format!("'{}' is not a valid branch name", name));
+    }
+    let Some(pattern) = policy.branch_pattern.as_deref() else {
+        return Ok(());
+    };
+    if branch_matches_pattern(pattern, name) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Branch '{}' doesn't follow the naming policy '{}'",
+            name, pattern
+        ))
+    }
+}
+
+fn branch_matches_pattern(pattern: &str, name: &str) -> bool {
+    let placeholder = ["<kind>", "<slug>", "<id>"]
+        .iter()
+        .find(|placeholder| pattern.starts_with(**placeholder));
+    match placeholder {
+        Some(placeholder) => {
+            let rest = &pattern[placeholder.len()..];
+            let segment_len = name.find('/').unwrap_or(name.len());
+            (1..=segment_len)
+                .filter(|end| name.is_char_boundary(*end))
+                .any(|end| branch_matches_pattern(rest, &name[end..]))
+        }
+        None => match pattern.chars().next() {
+            Some(c) => name
+                .strip_prefix(c)
+                .is_some_and(|rest| branch_matches_pattern(&pattern[c.len_utf8()..], rest)),
+            None => name.is_empty(),
+        },
+    }
+}
+
+/// Conventional commit types accepted when the policy requires conventional commits
+const CONVENTIONAL_COMMIT_TYPES: [&str; 11] = [
+    "feat", "fix", "refactor", "perf", "docs", "test", "build", "ci", "style", "chore", "revert",
+];
+
+/// Check a commit message against the policy, returning one message per problem.
+pub fn check_commit_message(
+    policy: &GitPolicy,
+    message: &str,
+) -> std::result::Result<(), Vec<String>> {
+    let mut lines = message.lines();
+    let subject = lines.next().unwrap_or("").trim();
+    if subject.is_empty() {
+        return Err(vec!["Commit message is empty".to_string()]);
+    }
+
+    let mut problems = Vec::new();
+    if let Some(max) = policy.max_subject_length {
+        let length = subject.chars().count();
+        if length > max {
+            problems.push(format!(
+                "Commit subject is {} characters (limit {})",
+                length, max
+            ));
+        }
+    }
+    if policy.conventional_commits {
+        if let Err(problem) = check_conventional_subject(subject) {
+            problems.push(problem);
+        }
+        if lines.next().is_some_and(|line| !line.trim().is_empty()) {
+            problems.push("Leave a blank line between the commit subject and body".to_string());
+        }
+    }
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(problems)
+    }
+}
+
+fn check_conventional_subject(subject: &str) -> std::result::Result<(), String> {
+    let Some((prefix, description)) = subject.split_once(": ") else {
+        return Err(
+            "Commit subject must look like `type(scope): description` (conventional commits)"
+                .to_string(),
+        );
+    };
+    let prefix = prefix.strip_suffix('!').unwrap_or(prefix);
+    let kind = match prefix.split_once('(') {
+        Some((kind, scope)) => {
+            let scope = scope.strip_suffix(')').unwrap_or("");
+            if scope.trim().is_empty() {
+                return Err(format!("Commit scope in '{}' is empty or unclosed", prefix));
+            }
+            kind
+        }
+        None => prefix,
+    };
+    if !CONVENTIONAL_COMMIT_TYPES.contains(&kind) {
+        return Err(format!(
+            "Commit type '{}' isn't one of {}",
+            kind,
+            CONVENTIONAL_COMMIT_TYPES.join(", ")
+        ));
+    }
+    if description.trim().is_empty() {
+        return Err("Commit subject has no description after the type".to_string());
+    }
+    Ok(())
+}
+
 fn sanitize_branch_slug(summary: &str) -> String {
     // Slugify the summary: lowercase, replace spaces/special chars with dashes
     let slug: String = summary
@@ -394,26 +686,94 @@ pub fn stage_file(repo_path: &Path, file_path: &str) -> Result<()> {
     Ok(())
 }
 
-/// Commit staged changes
-pub fn commit(repo_path: &Path, message: &str) -> Result<String> {
+/// Unified diff of what is staged against HEAD (what the next commit will contain)
+pub fn staged_diff(repo_path: &Path) -> Result<String> {
+    let repo = open_repo_discover(repo_path)?;
+    let head_tree = match repo.head() {
+        Ok(head) => Some(head.peel_to_tree()?),
+        Err(err)
+            if matches!(
+                err.code(),
+                git2::ErrorCode::UnbornBranch | git2::ErrorCode::NotFound
+            ) =>
+        {
+            None
+        }
+        Err(err) => return Err(err.into()),
+    };
+    let diff = repo.diff_tree_to_index(head_tree.as_ref(), None, None)?;
+
+    let mut patch = String::new();
+    diff.print(git2::DiffFormat::Patch, |_, _, line| {
+        if matches!(line.origin(), '+' | '-' | ' ') {
+            patch.push(line.origin());
+        }
+        patch.push_str(&String::from_utf8_lossy(line.content()));
+        true
+    })?;
+    Ok(patch)
+}
+
+/// Commit staged changes, refusing messages the policy rejects
+pub fn commit(repo_path: &Path, message: &str, policy: &GitPolicy) -> Result<String> {
+    check_commit_message(policy, message)
+        .map_err(|problems| anyhow::anyhow!("{}", problems.join("; ")))?;
     let repo = open_repo_discover(repo_path)?;
     let mut index = repo.index()?;
 
     let tree_id = index.write_tree()?;
     let tree = repo.find_tree(tree_id)?;
+    let parent = head_parent(&repo)?;
+    commit_tree(&repo, message, &tree, parent.as_ref())
+}
 
-    let parent = match repo.head() {
+/// Commit only `paths`, as they are staged, on top of HEAD. The rest of the index is left
+/// for later commits, so staged changes can be split across several commits.
+pub fn commit_paths(
+    repo_path: &Path,
+    message: &str,
+    policy: &GitPolicy,
+    paths: &[PathBuf],
+) -> Result<String> {
+    check_commit_message(policy, message)
+        .map_err(|problems| anyhow::anyhow!("{}", problems.join("; ")))?;
+    let repo = open_repo_discover(repo_path)?;
+    let staged = repo.index()?;
+    let parent = head_parent(&repo)?;
+
+    let mut partial = git2::Index::new()?;
+    if let Some(parent) = &parent {
+        partial.read_tree(&parent.tree()?)?;
+    }
+    for path in paths {
+        match staged.get_path(path, 0) {
+            Some(entry) => partial.add(&entry)?,
+            // Not in the index: a staged deletion
+            None => {
+                if partial.get_path(path, 0).is_some() {
+                    partial.remove(path, 0)?;
+                }
+            }
+        }
+    }
+    let tree = repo.find_tree(partial.write_tree_to(&repo)?)?;
+    commit_tree(&repo, message, &tree, parent.as_ref())
+}
+
+/// The commit HEAD points at, or None on an unborn branch
+fn head_parent(repo: &Repository) -> Result<Option<git2::Commit<'_>>> {
+    match repo.head() {
         Ok(head) => match head.peel_to_commit() {
-            Ok(commit) => Some(commit),
+            Ok(commit) => Ok(Some(commit)),
             Err(err)
                 if matches!(
                     err.code(),
                     git2::ErrorCode::UnbornBranch | git2::ErrorCode::NotFound
                 ) =>
             {
-                None
+                Ok(None)
             }
-            Err(err) => return Err(err.into()),
+            Err(err) => Err(err.into()),
         },
         Err(err)
             if matches!(
@@ -421,11 +781,18 @@ pub fn commit(repo_path: &Path, message: &str) -> Result<String> {
                 git2::ErrorCode::UnbornBranch | git2::ErrorCode::NotFound
             ) =>
         {
-            None
+            Ok(None)
         }
-        Err(err) => return Err(err.into()),
-    };
+        Err(err) => Err(err.into()),
+    }
+}
 
+fn commit_tree(
+    repo: &Repository,
+    message: &str,
+    tree: &git2::Tree,
+    parent: Option<&git2::Commit>,
+) -> Result<String> {
     // Get author info from git config
     let config = repo.config()?;
     let name = config
@@ -438,8 +805,8 @@ pub fn commit(repo_path: &Path, message: &str) -> Result<String> {
     let sig = Signature::now(&name, &email)?;
 
     let oid = match parent {
-        Some(ref parent) => repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &[parent])?,
-        None => repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &[])?,
+        Some(parent) => repo.commit(Some("HEAD"), &sig, &sig, message, tree, &[parent])?,
+        None => repo.commit(Some("HEAD"), &sig, &sig, message, tree, &[])?,
     };
 
     Ok(oid.to_string())
@@ -646,24 +1013,256 @@ fn resolve_remote_head_branch(repo: &Repository) -> Option<String> {
 }
 
 // ============================================================================
-// GitHub Integration (via native API)
+// Rebase Onto Base
 // ============================================================================
 
-/// Create a pull request using the GitHub API.
+const GIT_FETCH_TIMEOUT_SECS: u64 = 120;
+const GIT_REBASE_TIMEOUT_SECS: u64 = 120;
+
+/// How far the checked-out branch has drifted from its base.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BranchDrift {
+    /// Ref the branch is compared against, e.g. `origin/main`
+    pub base_ref: String,
+    /// Commits on the base the branch doesn't have
+    pub behind: usize,
+    /// Commits on the branch the base doesn't have
+    pub ahead: usize,
+}
+
+/// Fetch `base` from its remote and count how far `HEAD` has fallen behind it.
+///
+/// A repo without a remote is compared against its local `base` branch.
+pub fn fetch_base_drift(repo_path: &Path, base: &str) -> Result<BranchDrift> {
+    let repo = open_repo_discover(repo_path)?;
+    let remote = resolve_push_remote(&repo, base)?;
+    let base_ref = if repo.find_remote(&remote).is_ok() {
+        let mut cmd = Command::new("git");
+        cmd.current_dir(repo_path)
+            .args(["fetch", &remote, base])
+            .env("GIT_TERMINAL_PROMPT", "0");
+        let output =
+            run_command_with_timeout(&mut cmd, Duration::from_secs(GIT_FETCH_TIMEOUT_SECS))
+                .map_err(|e| anyhow::anyhow!("Failed to run git fetch: {}", e))?;
+        if !output.status.map(|s| s.success()).unwrap_or(false) {
+            return Err(anyhow::anyhow!(
+                "git fetch {} {} failed: {}",
+                remote,
+                base,
+                if output.timed_out {
+                    "timed out"
+                } else {
+                    output.stderr.trim()
+                }
+            ));
+        }
+        format!("{}/{}", remote, base)
+    } else {
+        base.to_string()
+    };
+
+    let head = repo
+        .head()
+        .context("Failed to get HEAD")?
+        .peel_to_commit()
+        .context("Failed to resolve HEAD commit")?
+        .id();
+    let base_commit = repo
+        .revparse_single(&base_ref)
+        .and_then(|object| object.peel_to_commit())
+        .with_context(|| format!("Unknown base '{}'", base_ref))?
+        .id();
+    let (ahead, behind) = repo.graph_ahead_behind(head, base_commit)?;
+    Ok(BranchDrift {
+        base_ref,
+        behind,
+        ahead,
+    })
+}
+
+/// Where a rebase stopped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RebaseStep {
+    /// Every commit was replayed
+    Done,
+    /// Replaying a commit conflicted in these files (relative to the checkout)
+    Conflicted(Vec<PathBuf>),
+}
+
+/// Start rebasing the checkout at `worktree` onto `onto`.
+pub fn rebase_onto(worktree: &Path, onto: &str) -> Result<RebaseStep> {
+    run_rebase(worktree, &["rebase", onto])
+}
+
+/// Stage the resolved files and carry on with the rebase in progress.
 ///
-/// Returns the URL of the created PR.
-pub async fn create_pr(repo_path: &Path, title: &str, body: &str) -> Result<String> {
-    if !crate::github::is_authenticated() {
+/// A commit whose changes are already on the base ends up empty and is skipped; any other
+/// failure to continue is returned, so a commit with changes is never dropped.
+pub fn continue_rebase(worktree: &Path, resolved: &[PathBuf]) -> Result<RebaseStep> {
+    for path in resolved {
+        let mut cmd = Command::new("git");
+        cmd.current_dir(worktree).arg("add").arg("--").arg(path);
+        let output = run_command_with_timeout(&mut cmd, Duration::from_secs(30))
+            .map_err(|e| anyhow::anyhow!("Failed to run git add: {}", e))?;
+        if !output.status.map(|s| s.success()).unwrap_or(false) {
+            return Err(anyhow::anyhow!(
+                "git add {} failed: {}",
+                path.display(),
+                output.stderr.trim()
+            ));
+        }
+    }
+    match run_rebase(worktree, &["rebase", "--continue"]) {
+        Err(error) => {
+            if resolved_commit_is_empty(worktree)? {
+                run_rebase(worktree, &["rebase", "--skip"])
+            } else {
+                Err(error)
+            }
+        }
+        step => step,
+    }
+}
+
+/// Whether the index matches HEAD, i.e. the commit being replayed has nothing left to add.
+fn resolved_commit_is_empty(worktree: &Path) -> Result<bool> {
+    let mut cmd = Command::new("git");
+    cmd.current_dir(worktree)
+        .args(["diff", "--cached", "--quiet"]);
+    let output = run_command_with_timeout(&mut cmd, Duration::from_secs(30))
+        .map_err(|e| anyhow::anyhow!("Failed to run git diff --cached: {}", e))?;
+    Ok(!output.timed_out && output.status.map(|s| s.success()).unwrap_or(false))
+}
+
+/// Give up on the rebase in progress, restoring the checkout to where it started.
+pub fn abort_rebase(worktree: &Path) -> Result<()> {
+    run_rebase(worktree, &["rebase", "--abort"]).map(|_| ())
+}
+
+fn run_rebase(worktree: &Path, args: &[&str]) -> Result<RebaseStep> {
+    let mut cmd = Command::new("git");
+    cmd.current_dir(worktree)
+        .args(args)
+        .env("GIT_EDITOR", "true")
+        .env("GIT_TERMINAL_PROMPT", "0");
+    let output = run_command_with_timeout(&mut cmd, Duration::from_secs(GIT_REBASE_TIMEOUT_SECS))
+        .map_err(|e| anyhow::anyhow!("Failed to run git {}: {}", args.join(" "), e))?;
+    if output.timed_out {
         return Err(anyhow::anyhow!(
-            "Not authenticated with GitHub. Please authenticate first."
+            "git {} timed out after {}s",
+            args.join(" "),
+            GIT_REBASE_TIMEOUT_SECS
         ));
     }
+    if output.status.map(|s| s.success()).unwrap_or(false) {
+        return Ok(RebaseStep::Done);
+    }
+    let conflicted = conflicted_files(worktree)?;
+    if !conflicted.is_empty() {
+        return Ok(RebaseStep::Conflicted(conflicted));
+    }
+    Err(anyhow::anyhow!(
+        "git {} failed: {}",
+        args.join(" "),
+        format!("{}\n{}", output.stdout.trim(), output.stderr.trim()).trim()
+    ))
+}
 
-    let (owner, repo) = crate::github::get_remote_info(repo_path)?;
-    let base = get_main_branch_name(repo_path)?;
+/// Paths git lists as unmerged in the checkout at `worktree`.
+pub fn conflicted_files(worktree: &Path) -> Result<Vec<PathBuf>> {
+    let mut cmd = Command::new("git");
+    cmd.current_dir(worktree)
+        .args(["diff", "--name-only", "--diff-filter=U"]);
+    let output = run_command_with_timeout(&mut cmd, Duration::from_secs(30))
+        .map_err(|e| anyhow::anyhow!("Failed to list conflicted files: {}", e))?;
+    Ok(output
+        .stdout
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(PathBuf::from)
+        .collect())
+}
+
+/// Point the checked-out `branch` at `commit`, such as a rebased copy of it.
+///
+/// Uncommitted changes are kept; git refuses when the move would overwrite them.
+pub fn move_checked_out_branch(repo_path: &Path, branch: &str, commit: &str) -> Result<()> {
+    let current = get_current_branch(repo_path)?;
+    if current != branch {
+        return Err(anyhow::anyhow!(
+            "Expected '{}' to be checked out, found '{}'",
+            branch,
+            current
+        ));
+    }
+    let mut cmd = Command::new("git");
+    cmd.current_dir(repo_path).args(["reset", "--keep", commit]);
+    let output = run_command_with_timeout(&mut cmd, Duration::from_secs(30))
+        .map_err(|e| anyhow::anyhow!("Failed to run git reset: {}", e))?;
+    if !output.status.map(|s| s.success()).unwrap_or(false) {
+        return Err(anyhow::anyhow!(
+            "Couldn't move '{}' to the rebased commit: {}",
+            branch,
+            output.stderr.trim()
+        ));
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Forge Integration (GitHub, GitLab, Bitbucket via native APIs)
+// ============================================================================
+
+/// Create a pull request (a merge request on GitLab) on the forge the remote points at.
+///
+/// Returns the URL of the created request.
+pub async fn create_pr(
+    repo_path: &Path,
+    title: &str,
+    body: &str,
+    options: &crate::forge::PrOptions,
+) -> Result<String> {
     let head = get_current_branch(repo_path)?;
+    create_pr_from_branch(repo_path, &head, title, body, options).await
+}
 
-    crate::github::create_pull_request(&owner, &repo, &base, &head, title, body).await
+/// Open a pull request from `head`, which need not be checked out (stacked branches)
+pub async fn create_pr_from_branch(
+    repo_path: &Path,
+    head: &str,
+    title: &str,
+    body: &str,
+    options: &crate::forge::PrOptions,
+) -> Result<String> {
+    let remote = crate::forge::detect_forge_remote(repo_path)?;
+    let forge = crate::forge::forge_for(remote.kind);
+    let base = match options.base.as_deref().map(str::trim) {
+        Some(base) if !base.is_empty() => base.to_string(),
+        _ => get_main_branch_name(repo_path)?,
+    };
+    let head = head.to_string();
+
+    if !forge.is_authenticated() {
+        return Err(anyhow::anyhow!(
+            "Not authenticated with {}. Run `cosmos {}` or set {}, or open the {} yourself: {}",
+            remote.kind.label(),
+            remote.kind.login_flag(),
+            remote.kind.token_env(),
+            remote.kind.request_noun(),
+            forge.new_pull_request_url(&remote, &base, &head)
+        ));
+    }
+
+    let request = crate::forge::PullRequest {
+        base,
+        head,
+        title: title.to_string(),
+        body: body.to_string(),
+        draft: options.draft,
+        reviewers: options.reviewers.clone(),
+        labels: options.labels.clone(),
+    };
+    forge.create_pull_request(&remote, &request).await
 }
 
 /// Get the current branch name.
@@ -747,6 +1346,43 @@ pub fn restore_file(repo_path: &Path, file_path: &Path) -> Result<()> {
     Ok(())
 }
 
+/// Restore a file to a captured snapshot and sync the index with it.
+/// `None` means the file did not exist when the snapshot was taken, so it is removed.
+pub fn restore_file_content(
+    repo_path: &Path,
+    file_path: &Path,
+    content: Option<&str>,
+) -> Result<()> {
+    let resolved = resolve_repo_path_allow_new(repo_path, file_path)
+        .map_err(|e| anyhow::anyhow!("Invalid path '{}': {}", file_path.display(), e))?;
+
+    let repo = open_repo_discover(repo_path)?;
+    let mut index = repo.index()?;
+
+    match content {
+        Some(content) => {
+            if let Some(parent) = resolved.absolute.parent() {
+                std::fs::create_dir_all(parent)
+                    .with_context(|| format!("Failed to prepare {}", file_path.display()))?;
+            }
+            std::fs::write(&resolved.absolute, content)
+                .with_context(|| format!("Failed to restore {}", file_path.display()))?;
+            index.add_path(&resolved.relative)?;
+        }
+        None => {
+            if resolved.absolute.exists() {
+                std::fs::remove_file(&resolved.absolute).with_context(|| {
+                    format!("Failed to remove new file {}", file_path.display())
+                })?;
+            }
+            let _ = index.remove_path(&resolved.relative);
+        }
+    }
+    index.write()?;
+
+    Ok(())
+}
+
 /// Stash uncommitted changes with a descriptive message
 /// Returns the stash message used (for display purposes)
 pub fn stash_changes(repo_path: &Path) -> Result<String> {
@@ -781,6 +1417,109 @@ pub fn stash_changes(repo_path: &Path) -> Result<String> {
     }
 }
 
+// ============================================================================
+// Branch Context Switching
+// ============================================================================
+
+/// What switching branches did with the uncommitted work.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BranchSwitch {
+    /// Uncommitted work on the old branch was stashed under its name
+    pub stashed: bool,
+    /// Work stashed the last time Cosmos switched away from the new branch was popped
+    pub restored: bool,
+}
+
+/// Stash message tying a stash to the branch it was saved from
+fn branch_stash_message(branch: &str) -> String {
+    format!("cosmos: context for {}", branch)
+}
+
+/// Check out `to`, first stashing uncommitted work (untracked files included) under `from`'s
+/// name, then popping whatever Cosmos stashed the last time it switched away from `to`.
+///
+/// If the checkout fails, the stash is popped again so `from` is left as it was.
+pub fn switch_branch_stashing(repo_path: &Path, from: &str, to: &str) -> Result<BranchSwitch> {
+    let status = current_status(repo_path)?;
+    if status.branch != from {
+        return Err(anyhow::anyhow!(
+            "Expected '{}' to be checked out, found '{}'",
+            from,
+            status.branch
+        ));
+    }
+    // Cosmos's own cache stays put even where `.cosmos/` isn't ignored.
+    let untracked = status
+        .untracked
+        .iter()
+        .filter(|path| !path.starts_with(".cosmos/"))
+        .count();
+    let mut switch = BranchSwitch::default();
+    if !(status.staged.is_empty() && status.modified.is_empty() && untracked == 0) {
+        run_git_stash(
+            repo_path,
+            &[
+                "push",
+                "--include-untracked",
+                "-m",
+                &branch_stash_message(from),
+                "--",
+                ".",
+                ":(exclude).cosmos",
+            ],
+        )?;
+        switch.stashed = true;
+    }
+    if let Err(error) = checkout_branch(repo_path, to) {
+        if switch.stashed {
+            let _ = run_git_stash(repo_path, &["pop"]);
+        }
+        return Err(error);
+    }
+    if let Some(entry) = find_branch_stash(repo_path, to)? {
+        run_git_stash(repo_path, &["pop", &entry]).with_context(|| {
+            format!(
+                "Switched to '{}' but couldn't restore its stashed work ({} was kept)",
+                to, entry
+            )
+        })?;
+        switch.restored = true;
+    }
+    Ok(switch)
+}
+
+/// Newest stash entry (`stash@{n}`) Cosmos saved for `branch`
+fn find_branch_stash(repo_path: &Path, branch: &str) -> Result<Option<String>> {
+    let mut cmd = Command::new("git");
+    cmd.current_dir(repo_path)
+        .args(["stash", "list", "--format=%gd%x09%gs"]);
+    let output = run_command_with_timeout(&mut cmd, Duration::from_secs(30))
+        .map_err(|e| anyhow::anyhow!("Failed to list stashes: {}", e))?;
+    let suffix = format!(": {}", branch_stash_message(branch));
+    Ok(output.stdout.lines().find_map(|line| {
+        let (entry, subject) = line.split_once('\t')?;
+        subject.ends_with(&suffix).then(|| entry.to_string())
+    }))
+}
+
+fn run_git_stash(repo_path: &Path, args: &[&str]) -> Result<()> {
+    let mut cmd = Command::new("git");
+    cmd.current_dir(repo_path).arg("stash").args(args);
+    let output = run_command_with_timeout(&mut cmd, Duration::from_secs(30))
+        .map_err(|e| anyhow::anyhow!("Failed to run git stash: {}", e))?;
+    if output.timed_out {
+        return Err(anyhow::anyhow!("git stash timed out after 30s"));
+    }
+    if !output.status.map(|s| s.success()).unwrap_or(false) {
+        return Err(anyhow::anyhow!(
+            "git stash {} failed: {}",
+            args[0],
+            output.stderr.trim()
+        ));
+    }
+    Ok(())
+}
+
 /// Discard all uncommitted changes (both staged and unstaged)
 /// This resets the working directory to HEAD
 pub fn discard_all_changes(repo_path: &Path) -> Result<()> {
@@ -843,6 +1582,327 @@ pub fn discard_all_changes(repo_path: &Path) -> Result<()> {
     Ok(())
 }
 
+const PRE_COMMIT_HOOK_TIMEOUT_SECS: u64 = 300;
+const PRE_COMMIT_OUTPUT_TAIL_CHARS: usize = 4_000;
+
+/// Which tool installed the repo's pre-commit hook.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreCommitHookKind {
+    Husky,
+    PreCommit,
+    Lefthook,
+    /// A hand-written `pre-commit` script.
+    Script,
+}
+
+impl PreCommitHookKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            PreCommitHookKind::Husky => "husky",
+            PreCommitHookKind::PreCommit => "pre-commit",
+            PreCommitHookKind::Lefthook => "lefthook",
+            PreCommitHookKind::Script => "git hook",
+        }
+    }
+}
+
+/// The pre-commit hook git would run for this repo.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreCommitHook {
+    pub kind: PreCommitHookKind,
+    pub path: PathBuf,
+}
+
+/// Outcome of running the pre-commit hook against the staged changes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreCommitHookRun {
+    pub kind: PreCommitHookKind,
+    pub passed: bool,
+    pub timed_out: bool,
+    /// Tail of the combined stdout/stderr.
+    pub output: String,
+    /// Files the hook rewrote (e.g. formatters), relative to the repo root.
+    pub modified_files: Vec<String>,
+}
+
+/// Find the pre-commit hook git would run (honoring `core.hooksPath`).
+///
+/// Commits are made through libgit2, which never runs hooks, so Ship runs this itself.
+pub fn detect_pre_commit_hook(repo_path: &Path) -> Result<Option<PreCommitHook>> {
+    let repo = open_repo_discover(repo_path)?;
+    if repo.workdir().is_none() {
+        return Ok(None);
+    }
+    let hooks_dir = hooks_dir(repo_path)?;
+    let path = hooks_dir.join("pre-commit");
+    if !is_executable_file(&path) {
+        return Ok(None);
+    }
+
+    let script = std::fs::read_to_string(&path).unwrap_or_default();
+    let kind =
+        if hooks_dir.components().any(|c| c.as_os_str() == ".husky") || script.contains("husky") {
+            PreCommitHookKind::Husky
+        } else if script.contains("lefthook") {
+            PreCommitHookKind::Lefthook
+        } else if script.contains("pre_commit") || script.contains("pre-commit.com") {
+            PreCommitHookKind::PreCommit
+        } else {
+            PreCommitHookKind::Script
+        };
+    Ok(Some(PreCommitHook { kind, path }))
+}
+
+/// Directory git runs hooks from, honoring `core.hooksPath`.
+pub fn hooks_dir(repo_path: &Path) -> Result<PathBuf> {
+    let repo = open_repo_discover(repo_path)?;
+    let base = repo.workdir().unwrap_or_else(|| repo.path()).to_path_buf();
+    Ok(match repo.config()?.get_path("core.hooksPath") {
+        Ok(path) if path.is_absolute() => path,
+        Ok(path) => base.join(path),
+        Err(_) => repo.path().join("hooks"),
+    })
+}
+
+/// Text files added or modified between `base` and `head`, as (path, old content, new content).
+///
+/// Without `base`, the range starts where `head` forked from the main branch. Deleted and
+/// binary files are left out.
+pub fn range_changes(
+    repo_path: &Path,
+    base: Option<&str>,
+    head: &str,
+) -> Result<Vec<(PathBuf, String, String)>> {
+    let repo = open_repo_discover(repo_path)?;
+    let head_commit = repo
+        .revparse_single(head)
+        .and_then(|object| object.peel_to_commit())
+        .with_context(|| format!("Unknown revision '{}'", head))?;
+    let base_oid = range_base(&repo, repo_path, base, &head_commit, head)?;
+    let base_tree = repo.find_commit(base_oid)?.tree()?;
+    let head_tree = head_commit.tree()?;
+    let diff = repo.diff_tree_to_tree(Some(&base_tree), Some(&head_tree), None)?;
+
+    let mut changes = Vec::new();
+    for delta in diff.deltas() {
+        if delta.status() == git2::Delta::Deleted {
+            continue;
+        }
+        let Some(path) = delta.new_file().path() else {
+            continue;
+        };
+        let new_blob = repo.find_blob(delta.new_file().id())?;
+        if new_blob.is_binary() {
+            continue;
+        }
+        let old_content = base_tree
+            .get_path(path)
+            .ok()
+            .and_then(|entry| repo.find_blob(entry.id()).ok())
+            .map(|blob| String::from_utf8_lossy(blob.content()).to_string())
+            .unwrap_or_default();
+        changes.push((
+            path.to_path_buf(),
+            old_content,
+            String::from_utf8_lossy(new_blob.content()).to_string(),
+        ));
+    }
+    Ok(changes)
+}
+
+/// `base`, or where `head` forked from the main branch.
+fn range_base(
+    repo: &Repository,
+    repo_path: &Path,
+    base: Option<&str>,
+    head_commit: &git2::Commit,
+    head: &str,
+) -> Result<git2::Oid> {
+    match base {
+        Some(base) => Ok(repo
+            .revparse_single(base)
+            .and_then(|object| object.peel_to_commit())
+            .with_context(|| format!("Unknown revision '{}'", base))?
+            .id()),
+        None => {
+            let main = get_main_branch_name(repo_path)?;
+            let main_oid = repo
+                .revparse_single(&main)
+                .and_then(|object| object.peel_to_commit())
+                .with_context(|| format!("Unknown revision '{}'", main))?
+                .id();
+            repo.merge_base(head_commit.id(), main_oid)
+                .with_context(|| format!("'{}' shares no history with '{}'", head, main))
+        }
+    }
+}
+
+/// Content of `path` at the start of the range `range_changes` would use, or None if the
+/// file doesn't exist there.
+pub fn file_at_range_base(
+    repo_path: &Path,
+    base: Option<&str>,
+    head: &str,
+    path: &Path,
+) -> Result<Option<String>> {
+    let repo = open_repo_discover(repo_path)?;
+    let head_commit = repo
+        .revparse_single(head)
+        .and_then(|object| object.peel_to_commit())
+        .with_context(|| format!("Unknown revision '{}'", head))?;
+    let base_oid = range_base(&repo, repo_path, base, &head_commit, head)?;
+    let tree = repo.find_commit(base_oid)?.tree()?;
+    let Ok(entry) = tree.get_path(path) else {
+        return Ok(None);
+    };
+    let blob = repo.find_blob(entry.id())?;
+    Ok(Some(String::from_utf8_lossy(blob.content()).to_string()))
+}
+
+/// Full id of the commit `HEAD` points at, if any.
+pub fn head_commit(repo_path: &Path) -> Option<String> {
+    let repo = open_repo_discover(repo_path).ok()?;
+    let commit = repo.head().ok()?.peel_to_commit().ok()?;
+    Some(commit.id().to_string())
+}
+
+/// Full id of the commit local branch `branch` points at, if it exists.
+pub fn branch_tip(repo_path: &Path, branch: &str) -> Option<String> {
+    let repo = open_repo_discover(repo_path).ok()?;
+    let branch = repo.find_branch(branch, git2::BranchType::Local).ok()?;
+    branch.get().target().map(|oid| oid.to_string())
+}
+
+/// One commit in a range, with the files it changed relative to its first parent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitSummary {
+    pub id: String,
+    pub summary: String,
+    pub author: String,
+    pub files: Vec<PathBuf>,
+}
+
+impl CommitSummary {
+    pub fn short_id(&self) -> &str {
+        &self.id[..self.id.len().min(8)]
+    }
+}
+
+/// Commits reachable from `to` but not from `from`, newest first (like `git log from..to`).
+pub fn commits_between(repo_path: &Path, from: &str, to: &str) -> Result<Vec<CommitSummary>> {
+    let repo = open_repo_discover(repo_path)?;
+    let resolve = |rev: &str| {
+        repo.revparse_single(rev)
+            .and_then(|object| object.peel_to_commit())
+            .map(|commit| commit.id())
+            .with_context(|| format!("Unknown revision '{}'", rev))
+    };
+    let mut walk = repo.revwalk()?;
+    walk.push(resolve(to)?)?;
+    walk.hide(resolve(from)?)?;
+    walk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::TIME)?;
+
+    let mut commits = Vec::new();
+    for oid in walk {
+        let commit = repo.find_commit(oid?)?;
+        let parent_tree = commit
+            .parent(0)
+            .ok()
+            .map(|parent| parent.tree())
+            .transpose()?;
+        let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), None)?;
+        let files = diff
+            .deltas()
+            .filter_map(|delta| {
+                delta
+                    .new_file()
+                    .path()
+                    .or_else(|| delta.old_file().path())
+                    .map(Path::to_path_buf)
+            })
+            .collect();
+        commits.push(CommitSummary {
+            id: commit.id().to_string(),
+            summary: commit.summary().unwrap_or("").to_string(),
+            author: commit.author().name().unwrap_or("").to_string(),
+            files,
+        });
+    }
+    Ok(commits)
+}
+
+#[cfg(unix)]
+fn is_executable_file(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::metadata(path)
+        .map(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable_file(path: &Path) -> bool {
+    path.is_file()
+}
+
+/// Content fingerprints of every changed or untracked file, keyed by repo-relative path.
+fn changed_file_fingerprints(repo: &Repository, workdir: &Path) -> Result<HashMap<String, u64>> {
+    let mut opts = StatusOptions::new();
+    opts.include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .include_ignored(false)
+        .exclude_submodules(true);
+    let mut fingerprints = HashMap::new();
+    for entry in repo.statuses(Some(&mut opts))?.iter() {
+        let Some(path) = entry.path() else {
+            continue;
+        };
+        let mut hasher = DefaultHasher::new();
+        std::fs::read(workdir.join(path)).ok().hash(&mut hasher);
+        fingerprints.insert(path.to_string(), hasher.finish());
+    }
+    Ok(fingerprints)
+}
+
+/// Run `hook` from the repo root and report which files it rewrote.
+pub fn run_pre_commit_hook(repo_path: &Path, hook: &PreCommitHook) -> Result<PreCommitHookRun> {
+    let repo = open_repo_discover(repo_path)?;
+    let workdir = repo
+        .workdir()
+        .map(Path::to_path_buf)
+        .ok_or_else(|| anyhow::anyhow!("Cannot run hooks in a bare repository"))?;
+    let before = changed_file_fingerprints(&repo, &workdir)?;
+
+    let mut cmd = Command::new(&hook.path);
+    cmd.current_dir(&workdir).env("GIT_TERMINAL_PROMPT", "0");
+    let result =
+        run_command_with_timeout(&mut cmd, Duration::from_secs(PRE_COMMIT_HOOK_TIMEOUT_SECS))
+            .map_err(|e| {
+                anyhow::anyhow!("Failed to run {} pre-commit hook: {}", hook.kind.label(), e)
+            })?;
+
+    let after = changed_file_fingerprints(&repo, &workdir)?;
+    let mut modified_files = after
+        .iter()
+        .filter(|(path, fingerprint)| before.get(*path) != Some(*fingerprint))
+        .map(|(path, _)| path.clone())
+        .collect::<Vec<_>>();
+    modified_files.sort();
+
+    let output = format!("{}\n{}", result.stdout.trim_end(), result.stderr.trim_end());
+    let output = output.trim();
+    let skip = output
+        .chars()
+        .count()
+        .saturating_sub(PRE_COMMIT_OUTPUT_TAIL_CHARS);
+    Ok(PreCommitHookRun {
+        kind: hook.kind,
+        passed: !result.timed_out && result.status.map(|s| s.success()).unwrap_or(false),
+        timed_out: result.timed_out,
+        output: output.chars().skip(skip).collect(),
+        modified_files,
+    })
+}
+
 /// Allowed URL schemes for security
 const ALLOWED_URL_SCHEMES: &[&str] = &["https://", "http://"];
 
@@ -935,6 +1995,84 @@ mod tests {
     // Branch Name Generation Tests
     // ========================================================================
 
+    #[test]
+    fn test_policy_branch_names_follow_the_pattern() {
+        let policy = GitPolicy {
+            branch_pattern: Some("cosmos/<kind>/<slug>".to_string()),
+            ..Default::default()
+        };
+        let name = policy_branch_name(&policy, "Bug Fix", "12345678-abcd", "Guard empty input");
+        assert_eq!(name, "cosmos/bug-fix/guard-empty-input");
+        assert!(check_branch_name(&policy, &name).is_ok());
+        // Suffixes added for uniqueness still fit the last placeholder.
+        assert!(check_branch_name(&policy, "cosmos/bug-fix/guard-empty-input-2").is_ok());
+        assert!(check_branch_name(&policy, "cosmos/guard-empty-input").is_err());
+        assert!(check_branch_name(&policy, "fix/12345678-guard").is_err());
+        assert!(check_branch_name(&policy, "cosmos/a/b/c").is_err());
+
+        let default = GitPolicy::default();
+        assert_eq!(
+            policy_branch_name(&default, "Bug Fix", "12345678", "Guard"),
+            "fix/12345678-guard"
+        );
+        assert!(check_branch_name(&default, "anything/goes").is_ok());
+        assert!(check_branch_name(&default, "bad..name").is_err());
+
+        assert!(is_cosmos_branch(
+            &policy,
+            "cosmos/bug-fix/guard-empty-input"
+        ));
+        assert!(!is_cosmos_branch(&policy, "fix/12345678-guard"));
+        assert!(is_cosmos_branch(&default, "fix/12345678-guard"));
+        assert!(is_cosmos_branch(&default, "cosmos-fix-20240101-120000"));
+        assert!(!is_cosmos_branch(&default, "main"));
+    }
+
+    #[test]
+    fn test_commit_message_policy_reports_each_problem() {
+        let policy = GitPolicy {
+            conventional_commits: true,
+            max_subject_length: Some(30),
+            ..Default::default()
+        };
+        assert!(check_commit_message(&policy, "fix(parser): guard empty input").is_ok());
+        assert!(check_commit_message(&policy, "feat!: drop v1 api\n\nBody").is_ok());
+
+        let problems =
+            check_commit_message(&policy, "Guard the empty input case in the parser\nBody")
+                .unwrap_err();
+        assert_eq!(problems.len(), 3, "{:?}", problems);
+        assert!(problems[0].contains("limit 30"));
+        assert!(problems[1].contains("conventional commits"));
+        assert!(problems[2].contains("blank line"));
+
+        let problems = check_commit_message(&policy, "bugfix(): x").unwrap_err();
+        assert!(problems[0].contains("scope"), "{:?}", problems);
+        let problems = check_commit_message(&policy, "bugfix: x").unwrap_err();
+        assert!(problems[0].contains("'bugfix'"), "{:?}", problems);
+
+        assert!(check_commit_message(&GitPolicy::default(), "anything").is_ok());
+        assert!(check_commit_message(&GitPolicy::default(), "  \n").is_err());
+    }
+
+    #[test]
+    fn test_commit_refuses_messages_the_policy_rejects() {
+        let (_temp_dir, repo_path) = create_temp_repo();
+        std::fs::write(repo_path.join("a.txt"), "one\n").unwrap();
+        stage_file(&repo_path, "a.txt").unwrap();
+        let policy = GitPolicy {
+            conventional_commits: true,
+            ..Default::default()
+        };
+
+        let err = commit(&repo_path, "Add a", &policy).unwrap_err();
+        assert!(err.to_string().contains("conventional commits"));
+        assert!(!staged_diff(&repo_path).unwrap().is_empty());
+
+        commit(&repo_path, "chore: add a", &policy).unwrap();
+        assert!(staged_diff(&repo_path).unwrap().is_empty());
+    }
+
     #[test]
     fn test_branch_name_sanitization() {
         let name = generate_fix_branch_name("12345678", "Fix: user/login (v2)!!!");
@@ -1099,8 +2237,12 @@ mod tests {
     #[test]
     fn test_create_fix_branch_from_current_with_outcome_marks_created_new() {
         let (_temp_dir, repo_path) = create_temp_repo();
-        let outcome = create_fix_branch_from_current_with_outcome(&repo_path, "fix/new-outcome")
-            .expect("branch should be created");
+        let outcome = create_fix_branch_from_current_with_outcome(
+            &repo_path,
+            "fix/new-outcome",
+            &GitPolicy::default(),
+        )
+        .expect("branch should be created");
         assert_eq!(outcome.branch_name, "fix/new-outcome");
         assert!(outcome.created_new);
     }
@@ -1191,6 +2333,121 @@ mod tests {
             .is_err());
     }
 
+    #[test]
+    fn test_delete_merged_branch_removes_pushed_branch_locally_and_on_remote() {
+        let _guard = PUSH_ENV_LOCK.lock().unwrap();
+        let (_temp_dir, repo_path) = create_temp_repo();
+        let remote_dir = tempfile::tempdir().unwrap();
+        let git = |dir: &Path, args: &[&str]| {
+            let output = Command::new("git")
+                .current_dir(dir)
+                .args(args)
+                .output()
+                .unwrap();
+            assert!(output.status.success(), "git {:?} failed", args);
+        };
+        git(remote_dir.path(), &["init", "--bare", "-q"]);
+        git(
+            &repo_path,
+            &[
+                "remote",
+                "add",
+                "origin",
+                remote_dir.path().to_str().unwrap(),
+            ],
+        );
+
+        let source_branch = get_current_branch(&repo_path).unwrap();
+        create_fix_branch_from_current(&repo_path, "fix/merged").unwrap();
+        git(&repo_path, &["push", "-q", "-u", "origin", "fix/merged"]);
+        assert!(
+            delete_merged_branch(&repo_path, "fix/merged", Some(&source_branch), None)
+                .unwrap_err()
+                .to_string()
+                .contains("checked out")
+        );
+
+        // A commit that never reached the remote blocks the delete.
+        std::fs::write(repo_path.join("late.txt"), "late\n").unwrap();
+        stage_file(&repo_path, "late.txt").unwrap();
+        commit(&repo_path, "Late change", &GitPolicy::default()).unwrap();
+        checkout_branch(&repo_path, &source_branch).unwrap();
+        assert!(
+            delete_merged_branch(&repo_path, "fix/merged", Some(&source_branch), None)
+                .unwrap_err()
+                .to_string()
+                .contains("never pushed")
+        );
+
+        checkout_branch(&repo_path, "fix/merged").unwrap();
+        git(&repo_path, &["push", "-q", "origin", "fix/merged"]);
+        checkout_branch(&repo_path, &source_branch).unwrap();
+        delete_merged_branch(&repo_path, "fix/merged", Some(&source_branch), None).unwrap();
+        assert!(!local_branch_exists(&repo_path, "fix/merged"));
+        let remote = Repository::open_bare(remote_dir.path()).unwrap();
+        assert!(remote
+            .find_branch("fix/merged", git2::BranchType::Local)
+            .is_err());
+    }
+
+    #[test]
+    fn test_delete_merged_branch_keeps_local_commits_when_upstream_is_pruned() {
+        let _guard = PUSH_ENV_LOCK.lock().unwrap();
+        let (_temp_dir, repo_path) = create_temp_repo();
+        let remote_dir = tempfile::tempdir().unwrap();
+        let git = |dir: &Path, args: &[&str]| {
+            let output = Command::new("git")
+                .current_dir(dir)
+                .args(args)
+                .output()
+                .unwrap();
+            assert!(output.status.success(), "git {:?} failed", args);
+        };
+        git(remote_dir.path(), &["init", "--bare", "-q"]);
+        git(
+            &repo_path,
+            &[
+                "remote",
+                "add",
+                "origin",
+                remote_dir.path().to_str().unwrap(),
+            ],
+        );
+
+        let source_branch = get_current_branch(&repo_path).unwrap();
+        create_fix_branch_from_current(&repo_path, "fix/pruned").unwrap();
+        std::fs::write(repo_path.join("fix.txt"), "fix\n").unwrap();
+        stage_file(&repo_path, "fix.txt").unwrap();
+        let pr_head = commit(&repo_path, "Fix", &GitPolicy::default()).unwrap();
+        git(&repo_path, &["push", "-q", "-u", "origin", "fix/pruned"]);
+
+        // The forge deletes the merged branch and a fetch prunes the tracking ref.
+        git(remote_dir.path(), &["branch", "-D", "fix/pruned"]);
+        git(&repo_path, &["fetch", "-q", "--prune", "origin"]);
+        std::fs::write(repo_path.join("late.txt"), "late\n").unwrap();
+        stage_file(&repo_path, "late.txt").unwrap();
+        commit(&repo_path, "Local only change", &GitPolicy::default()).unwrap();
+        checkout_branch(&repo_path, &source_branch).unwrap();
+
+        let err = delete_merged_branch(
+            &repo_path,
+            "fix/pruned",
+            Some(&source_branch),
+            Some(&pr_head),
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("Local only change"), "{}", err);
+        // The pull request head is merged; only the commit after it is listed.
+        assert!(!err.contains(&pr_head[..8]), "{}", err);
+        assert!(local_branch_exists(&repo_path, "fix/pruned"));
+
+        // Once the local commit is in the base branch the delete goes through.
+        git(&repo_path, &["merge", "-q", "--ff-only", "fix/pruned"]);
+        delete_merged_branch(&repo_path, "fix/pruned", Some(&source_branch), None).unwrap();
+        assert!(!local_branch_exists(&repo_path, "fix/pruned"));
+    }
+
     #[test]
     fn test_push_branch_blocked_when_sandbox_flag_is_set() {
         let _guard = PUSH_ENV_LOCK.lock().unwrap();
@@ -1223,7 +2480,7 @@ mod tests {
             "origin",
             "https://github.com/example/cosmos.git",
         );
-        let result = create_pr(&repo_path, "Test PR", "Test body").await;
+        let result = create_pr(&repo_path, "Test PR", "Test body", &Default::default()).await;
 
         // Should fail because not authenticated
         assert!(result.is_err());
@@ -1240,6 +2497,39 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_create_pr_without_gitlab_token_offers_login_and_manual_url() {
+        let orig = std::env::var("GITLAB_TOKEN").ok();
+        std::env::remove_var("GITLAB_TOKEN");
+
+        let (_temp_dir, repo_path) = create_temp_repo();
+        add_test_remote(&repo_path, "origin", "git@gitlab.com:group/app.git");
+        let options = crate::forge::PrOptions {
+            base: Some("release".to_string()),
+            ..Default::default()
+        };
+        let err = create_pr(&repo_path, "Test MR", "Test body", &options)
+            .await
+            .unwrap_err()
+            .to_string();
+
+        assert!(err.contains("--gitlab-login"), "{}", err);
+        assert!(
+            err.contains("https://gitlab.com/group/app/-/merge_requests/new"),
+            "{}",
+            err
+        );
+        assert!(
+            err.contains("merge_request%5Btarget_branch%5D=release"),
+            "{}",
+            err
+        );
+
+        if let Some(val) = orig {
+            std::env::set_var("GITLAB_TOKEN", val);
+        }
+    }
+
     #[tokio::test]
     async fn test_create_pr_is_async() {
         // This test just verifies create_pr is an async function
@@ -1247,7 +2537,8 @@ mod tests {
         let (_temp_dir, repo_path) = create_temp_repo();
 
         // We don't actually want to create a PR, just verify it compiles as async
-        let future = create_pr(&repo_path, "title", "body");
+        let options = crate::forge::PrOptions::default();
+        let future = create_pr(&repo_path, "title", "body", &options);
 
         // Verify it's a future (can be awaited)
         // We'll cancel it immediately by dropping
@@ -1479,4 +2770,343 @@ mod tests {
         let content = std::fs::read_to_string(&test_file).unwrap();
         assert_eq!(content, "original content");
     }
+
+    // ========================================================================
+    // Pre-commit Hook Tests
+    // ========================================================================
+
+    #[cfg(unix)]
+    fn install_hook(path: &Path, script: &str) {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, script).unwrap();
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    #[test]
+    fn test_detect_pre_commit_hook_none_without_hook() {
+        let (_temp_dir, repo_path) = create_temp_repo();
+        assert_eq!(detect_pre_commit_hook(&repo_path).unwrap(), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_detect_pre_commit_hook_honors_hooks_path() {
+        let (_temp_dir, repo_path) = create_temp_repo();
+        install_hook(
+            &repo_path.join(".husky/_/pre-commit"),
+            "#!/bin/sh\nexit 0\n",
+        );
+        let repo = Repository::open(&repo_path).unwrap();
+        repo.config()
+            .unwrap()
+            .set_str("core.hooksPath", ".husky/_")
+            .unwrap();
+
+        let hook = detect_pre_commit_hook(&repo_path).unwrap().unwrap();
+        assert_eq!(hook.kind, PreCommitHookKind::Husky);
+    }
+
+    #[test]
+    fn test_range_changes_skips_deletions_and_defaults_to_the_fork_point() {
+        let (_temp_dir, repo_path) = create_temp_repo();
+        commit_test_file(&repo_path, "keep.txt", "one\n", "Add keep");
+        commit_test_file(&repo_path, "gone.txt", "bye\n", "Add gone");
+        let main = get_main_branch_name(&repo_path).unwrap();
+        create_fix_branch_from_current(&repo_path, "feature/range").unwrap();
+        commit_test_file(&repo_path, "keep.txt", "two\n", "Edit keep");
+        commit_test_file(&repo_path, "new.txt", "fresh\n", "Add new");
+        std::fs::remove_file(repo_path.join("gone.txt")).unwrap();
+        let repo = Repository::open(&repo_path).unwrap();
+        let mut index = repo.index().unwrap();
+        index.remove_path(Path::new("gone.txt")).unwrap();
+        index.write().unwrap();
+        commit(&repo_path, "Remove gone", &GitPolicy::default()).unwrap();
+
+        let mut changes = range_changes(&repo_path, None, "HEAD").unwrap();
+        changes.sort();
+        assert_eq!(
+            changes,
+            vec![
+                (PathBuf::from("keep.txt"), "one\n".into(), "two\n".into()),
+                (PathBuf::from("new.txt"), String::new(), "fresh\n".into()),
+            ]
+        );
+        assert!(range_changes(&repo_path, Some(&main), &main)
+            .unwrap()
+            .is_empty());
+        assert!(range_changes(&repo_path, Some("no-such-ref"), "HEAD").is_err());
+    }
+
+    #[test]
+    fn test_commit_paths_splits_staged_changes_across_commits() {
+        let (_temp_dir, repo_path) = create_temp_repo();
+        commit_test_file(&repo_path, "a.txt", "one\n", "Add a");
+        commit_test_file(&repo_path, "gone.txt", "bye\n", "Add gone");
+        std::fs::write(repo_path.join("a.txt"), "two\n").unwrap();
+        std::fs::write(repo_path.join("b.txt"), "bee\n").unwrap();
+        stage_file(&repo_path, "a.txt").unwrap();
+        stage_file(&repo_path, "b.txt").unwrap();
+        std::fs::remove_file(repo_path.join("gone.txt")).unwrap();
+        let repo = Repository::open(&repo_path).unwrap();
+        let mut index = repo.index().unwrap();
+        index.remove_path(Path::new("gone.txt")).unwrap();
+        index.write().unwrap();
+
+        let policy = GitPolicy::default();
+        commit_paths(&repo_path, "Edit a", &policy, &[PathBuf::from("a.txt")]).unwrap();
+        let head = || repo.head().unwrap().peel_to_commit().unwrap();
+        let first = head().tree().unwrap();
+        assert!(first.get_path(Path::new("b.txt")).is_err());
+        assert!(first.get_path(Path::new("gone.txt")).is_ok());
+        assert_eq!(
+            read_file_from_head(&repo_path, Path::new("a.txt"))
+                .unwrap()
+                .as_deref(),
+            Some("two\n")
+        );
+
+        commit_paths(
+            &repo_path,
+            "Add b, drop gone",
+            &policy,
+            &[PathBuf::from("b.txt"), PathBuf::from("gone.txt")],
+        )
+        .unwrap();
+        let second = head();
+        assert_eq!(second.summary(), Some("Add b, drop gone"));
+        assert_eq!(second.parent(0).unwrap().summary(), Some("Edit a"));
+        assert!(second
+            .tree()
+            .unwrap()
+            .get_path(Path::new("gone.txt"))
+            .is_err());
+        assert!(staged_diff(&repo_path).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_file_at_range_base_reads_the_fork_point_version() {
+        let (_temp_dir, repo_path) = create_temp_repo();
+        commit_test_file(&repo_path, "keep.txt", "one\n", "Add keep");
+        create_fix_branch_from_current(&repo_path, "feature/base").unwrap();
+        commit_test_file(&repo_path, "keep.txt", "two\n", "Edit keep");
+        commit_test_file(&repo_path, "new.txt", "fresh\n", "Add new");
+
+        let read = |name: &str| file_at_range_base(&repo_path, None, "HEAD", Path::new(name));
+        assert_eq!(read("keep.txt").unwrap().as_deref(), Some("one\n"));
+        assert_eq!(read("new.txt").unwrap(), None);
+    }
+
+    #[test]
+    fn test_commits_between_lists_newer_commits_with_their_files() {
+        let (_temp_dir, repo_path) = create_temp_repo();
+        commit_test_file(&repo_path, "a.txt", "one\n", "Add a");
+        let start = head_commit(&repo_path).unwrap();
+        commit_test_file(&repo_path, "a.txt", "two\n", "Edit a");
+        commit_test_file(&repo_path, "b.txt", "bee\n", "Add b");
+
+        let commits = commits_between(&repo_path, &start, "HEAD").unwrap();
+        let summaries: Vec<&str> = commits.iter().map(|c| c.summary.as_str()).collect();
+        assert_eq!(summaries, vec!["Add b", "Edit a"]);
+        assert_eq!(commits[1].files, vec![PathBuf::from("a.txt")]);
+        assert_eq!(commits[0].short_id().len(), 8);
+        assert!(commits_between(&repo_path, "HEAD", "HEAD")
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_rebase_in_a_worktree_resolves_conflicts_and_moves_the_branch() {
+        let (_temp_dir, repo_path) = create_temp_repo();
+        commit_test_file(&repo_path, "a.txt", "one\n", "Add a");
+        let base = get_current_branch(&repo_path).unwrap();
+        create_fix_branch_from_current(&repo_path, "fix/rebase").unwrap();
+        commit_test_file(&repo_path, "a.txt", "branch\n", "Branch edit");
+        checkout_branch(&repo_path, &base).unwrap();
+        commit_test_file(&repo_path, "a.txt", "main\n", "Main edit");
+        checkout_branch(&repo_path, "fix/rebase").unwrap();
+
+        let drift = fetch_base_drift(&repo_path, &base).unwrap();
+        assert_eq!(drift.base_ref, base);
+        assert_eq!((drift.ahead, drift.behind), (1, 1));
+
+        let worktree_dir = tempfile::tempdir().unwrap();
+        let worktree = worktree_dir.path().join("rebase");
+        let output = Command::new("git")
+            .current_dir(&repo_path)
+            .args(["worktree", "add", "--detach", worktree.to_str().unwrap()])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+
+        assert_eq!(
+            rebase_onto(&worktree, &base).unwrap(),
+            RebaseStep::Conflicted(vec![PathBuf::from("a.txt")])
+        );
+        std::fs::write(worktree.join("a.txt"), "main and branch\n").unwrap();
+        assert_eq!(
+            continue_rebase(&worktree, &[PathBuf::from("a.txt")]).unwrap(),
+            RebaseStep::Done
+        );
+
+        let rebased = head_commit(&worktree).unwrap();
+        move_checked_out_branch(&repo_path, "fix/rebase", &rebased).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(repo_path.join("a.txt")).unwrap(),
+            "main and branch\n"
+        );
+        let drift = fetch_base_drift(&repo_path, &base).unwrap();
+        assert_eq!((drift.ahead, drift.behind), (1, 0));
+        assert!(move_checked_out_branch(&repo_path, &base, &rebased).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_continue_rebase_never_skips_a_commit_with_changes() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let (_temp_dir, repo_path) = create_temp_repo();
+        commit_test_file(&repo_path, "a.txt", "one\n", "Add a");
+        let base = get_current_branch(&repo_path).unwrap();
+        create_fix_branch_from_current(&repo_path, "fix/rebase").unwrap();
+        commit_test_file(&repo_path, "a.txt", "branch\n", "Branch edit");
+        commit_test_file(&repo_path, "a.txt", "main\n", "Match main");
+        checkout_branch(&repo_path, &base).unwrap();
+        commit_test_file(&repo_path, "a.txt", "main\n", "Main edit");
+        checkout_branch(&repo_path, "fix/rebase").unwrap();
+
+        let worktree_dir = tempfile::tempdir().unwrap();
+        let worktree = worktree_dir.path().join("rebase");
+        let output = Command::new("git")
+            .current_dir(&repo_path)
+            .args(["worktree", "add", "--detach", worktree.to_str().unwrap()])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+
+        // A failing hook makes `--continue` fail while the resolved commit still has changes.
+        let hook = hooks_dir(&repo_path).unwrap().join("prepare-commit-msg");
+        std::fs::create_dir_all(hook.parent().unwrap()).unwrap();
+        std::fs::write(&hook, "#!/bin/sh\nexit 1\n").unwrap();
+        std::fs::set_permissions(&hook, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        assert_eq!(
+            rebase_onto(&worktree, &base).unwrap(),
+            RebaseStep::Conflicted(vec![PathBuf::from("a.txt")])
+        );
+        std::fs::write(worktree.join("a.txt"), "main and branch\n").unwrap();
+        assert!(continue_rebase(&worktree, &[PathBuf::from("a.txt")]).is_err());
+        assert!(!resolved_commit_is_empty(&worktree).unwrap());
+        assert_eq!(
+            std::fs::read_to_string(worktree.join("a.txt")).unwrap(),
+            "main and branch\n"
+        );
+
+        // Without the hook the commit lands; the next one conflicts, resolves to main's
+        // content, and is skipped as empty.
+        std::fs::remove_file(&hook).unwrap();
+        assert_eq!(
+            continue_rebase(&worktree, &[PathBuf::from("a.txt")]).unwrap(),
+            RebaseStep::Conflicted(vec![PathBuf::from("a.txt")])
+        );
+        std::fs::write(worktree.join("a.txt"), "main and branch\n").unwrap();
+        assert_eq!(
+            continue_rebase(&worktree, &[PathBuf::from("a.txt")]).unwrap(),
+            RebaseStep::Done
+        );
+        let rebased = head_commit(&worktree).unwrap();
+        let summaries: Vec<String> = commits_between(&repo_path, &base, &rebased)
+            .unwrap()
+            .into_iter()
+            .map(|commit| commit.summary)
+            .collect();
+        assert_eq!(summaries, vec!["Branch edit"]);
+    }
+
+    #[test]
+    fn test_switching_branches_stashes_and_restores_each_branchs_work() {
+        let (_temp_dir, repo_path) = create_temp_repo();
+        commit_test_file(&repo_path, "a.txt", "one\n", "Add a");
+        let base = get_current_branch(&repo_path).unwrap();
+        create_fix_branch_from_current(&repo_path, "fix/other").unwrap();
+        checkout_branch(&repo_path, &base).unwrap();
+        std::fs::write(repo_path.join("a.txt"), "base work\n").unwrap();
+        std::fs::write(repo_path.join("new.txt"), "untracked\n").unwrap();
+
+        let switch = switch_branch_stashing(&repo_path, &base, "fix/other").unwrap();
+        assert_eq!(
+            switch,
+            BranchSwitch {
+                stashed: true,
+                restored: false
+            }
+        );
+        assert_eq!(get_current_branch(&repo_path).unwrap(), "fix/other");
+        assert_eq!(
+            std::fs::read_to_string(repo_path.join("a.txt")).unwrap(),
+            "one\n"
+        );
+        assert!(!repo_path.join("new.txt").exists());
+
+        std::fs::write(repo_path.join("a.txt"), "other work\n").unwrap();
+        let switch = switch_branch_stashing(&repo_path, "fix/other", &base).unwrap();
+        assert_eq!(
+            switch,
+            BranchSwitch {
+                stashed: true,
+                restored: true
+            }
+        );
+        assert_eq!(
+            std::fs::read_to_string(repo_path.join("a.txt")).unwrap(),
+            "base work\n"
+        );
+        assert!(repo_path.join("new.txt").exists());
+
+        assert!(switch_branch_stashing(&repo_path, "fix/other", &base).is_err());
+        switch_branch_stashing(&repo_path, &base, "fix/other").unwrap();
+        assert_eq!(
+            std::fs::read_to_string(repo_path.join("a.txt")).unwrap(),
+            "other work\n"
+        );
+    }
+
+    #[test]
+    fn test_staged_diff_covers_only_the_index() {
+        let (_temp_dir, repo_path) = create_temp_repo();
+        commit_test_file(&repo_path, "a.txt", "one\n", "Add a");
+        std::fs::write(repo_path.join("a.txt"), "two\n").unwrap();
+        std::fs::write(repo_path.join("b.txt"), "unstaged\n").unwrap();
+        assert!(staged_diff(&repo_path).unwrap().is_empty());
+
+        stage_file(&repo_path, "a.txt").unwrap();
+        let diff = staged_diff(&repo_path).unwrap();
+        assert!(diff.contains("-one\n+two\n"), "{}", diff);
+        assert!(!diff.contains("b.txt"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_pre_commit_hook_reports_rewritten_files() {
+        let (_temp_dir, repo_path) = create_temp_repo();
+        std::fs::write(repo_path.join("a.txt"), "unformatted").unwrap();
+        stage_file(&repo_path, "a.txt").unwrap();
+        let hooks = Repository::open(&repo_path).unwrap().path().join("hooks");
+        install_hook(
+            &hooks.join("pre-commit"),
+            "#!/bin/sh\n# lefthook\necho 'formatting a.txt'\nprintf formatted > a.txt\nexit 1\n",
+        );
+
+        let hook = detect_pre_commit_hook(&repo_path).unwrap().unwrap();
+        assert_eq!(hook.kind, PreCommitHookKind::Lefthook);
+        let run = run_pre_commit_hook(&repo_path, &hook).unwrap();
+        assert!(!run.passed);
+        assert!(run.output.contains("formatting a.txt"));
+        assert_eq!(run.modified_files, vec!["a.txt".to_string()]);
+
+        // Once the fixes are staged the same hook is a no-op for file contents.
+        stage_file(&repo_path, "a.txt").unwrap();
+        let rerun = run_pre_commit_hook(&repo_path, &hook).unwrap();
+        assert!(rerun.modified_files.is_empty());
+    }
 }
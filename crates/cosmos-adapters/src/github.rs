@@ -3,11 +3,15 @@
 //! Provides OAuth device flow authentication and PR creation without requiring
 //! the `gh` CLI. Tokens are stored securely in the system keychain via keyring.
 
+use crate::forge::{Forge, ForgeKind, ForgeRepo, PrState, PullRequest};
 use crate::keyring;
 use anyhow::{Context, Result};
 use git2::Repository;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::future::Future;
 use std::path::Path;
+use std::pin::Pin;
 use std::time::Duration;
 
 // ============================================================================
@@ -72,7 +76,7 @@ const MAX_ERROR_BODY_LEN: usize = 200;
 
 /// Sanitize an API error body to prevent credential leakage.
 /// Truncates long responses and redacts potential secrets.
-fn sanitize_error_body(body: &str) -> String {
+pub(crate) fn sanitize_error_body(body: &str) -> String {
     // Patterns that might indicate secrets in error responses
     const SECRET_PATTERNS: &[&str] = &[
         "token",
@@ -398,11 +402,41 @@ struct CreatePrRequest {
     body: String,
     head: String,
     base: String,
+    draft: bool,
 }
 
 #[derive(Deserialize)]
 struct CreatePrResponse {
     html_url: String,
+    number: u64,
+}
+
+#[derive(Serialize)]
+struct RequestReviewers {
+    reviewers: Vec<String>,
+    team_reviewers: Vec<String>,
+}
+
+impl RequestReviewers {
+    /// `org/team` entries are team reviewers (by slug); anything else is a username.
+    fn new(reviewers: &[String]) -> Self {
+        let mut request = Self {
+            reviewers: Vec::new(),
+            team_reviewers: Vec::new(),
+        };
+        for reviewer in reviewers {
+            match reviewer.rsplit_once('/') {
+                Some((_, team)) => request.team_reviewers.push(team.to_string()),
+                None => request.reviewers.push(reviewer.clone()),
+            }
+        }
+        request
+    }
+}
+
+#[derive(Serialize)]
+struct AddLabels<'a> {
+    labels: &'a [String],
 }
 
 #[derive(Deserialize)]
@@ -417,77 +451,813 @@ struct ApiErrorDetail {
     message: Option<String>,
 }
 
-/// Create a pull request on GitHub.
+async fn api_error(resp: reqwest::Response) -> anyhow::Error {
+    let status = resp.status();
+    let error_body = resp.text().await.unwrap_or_default();
+
+    // Try to parse structured error
+    if let Ok(api_error) = serde_json::from_str::<ApiErrorResponse>(&error_body) {
+        let detail = api_error
+            .errors
+            .first()
+            .and_then(|e| e.message.clone())
+            .unwrap_or_default();
+
+        let msg = if detail.is_empty() {
+            api_error.message
+        } else {
+            format!("{}: {}", api_error.message, detail)
+        };
+
+        return anyhow::anyhow!("GitHub API error: {}", msg);
+    }
+
+    // Sanitize raw error body to prevent credential leakage
+    let sanitized = sanitize_error_body(&error_body);
+    anyhow::anyhow!("GitHub API error ({}): {}", status, sanitized)
+}
+
+fn api_post(client: &reqwest::Client, url: &str, token: &str) -> reqwest::RequestBuilder {
+    client
+        .post(url)
+        .header("Accept", "application/vnd.github+json")
+        .header("Authorization", format!("Bearer {}", token))
+        .header("User-Agent", "cosmos-tui")
+        .header("X-GitHub-Api-Version", "2022-11-28")
+}
+
+/// Create a pull request on GitHub, then request reviewers and add labels.
 ///
 /// Returns the URL of the created PR.
-pub async fn create_pull_request(
+pub async fn create_pull_request(owner: &str, repo: &str, request: &PullRequest) -> Result<String> {
+    let token = get_stored_token().ok_or_else(|| {
+        anyhow::anyhow!("Not authenticated with GitHub. Please authenticate first.")
+    })?;
+
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(API_TIMEOUT_SECS))
+        .build()
+        .context("Failed to create HTTP client")?;
+
+    let api_base = format!("https://api.github.com/repos/{}/{}", owner, repo);
+    let body = CreatePrRequest {
+        title: request.title.clone(),
+        body: request.body.clone(),
+        head: request.head.clone(),
+        base: request.base.clone(),
+        draft: request.draft,
+    };
+
+    let resp = api_post(&client, &format!("{}/pulls", api_base), &token)
+        .json(&body)
+        .send()
+        .await
+        .context("Failed to send PR creation request")?;
+    if !resp.status().is_success() {
+        return Err(api_error(resp).await);
+    }
+    let pr: CreatePrResponse = resp.json().await.context("Failed to parse PR response")?;
+
+    // Reviewers and labels are separate calls; the PR already exists if they fail.
+    if !request.reviewers.is_empty() {
+        let resp = api_post(
+            &client,
+            &format!("{}/pulls/{}/requested_reviewers", api_base, pr.number),
+            &token,
+        )
+        .json(&RequestReviewers::new(&request.reviewers))
+        .send()
+        .await
+        .context("Failed to send reviewer request")?;
+        if !resp.status().is_success() {
+            let err = api_error(resp).await;
+            return Err(anyhow::anyhow!(
+                "Opened {} but couldn't request reviewers: {}",
+                pr.html_url,
+                err
+            ));
+        }
+    }
+    if !request.labels.is_empty() {
+        let resp = api_post(
+            &client,
+            &format!("{}/issues/{}/labels", api_base, pr.number),
+            &token,
+        )
+        .json(&AddLabels {
+            labels: &request.labels,
+        })
+        .send()
+        .await
+        .context("Failed to send label request")?;
+        if !resp.status().is_success() {
+            let err = api_error(resp).await;
+            return Err(anyhow::anyhow!(
+                "Opened {} but couldn't add labels: {}",
+                pr.html_url,
+                err
+            ));
+        }
+    }
+
+    Ok(pr.html_url)
+}
+
+// ============================================================================
+// Pull Request Review
+// ============================================================================
+
+/// Largest pull request (in changed files) that Cosmos will review.
+pub const MAX_REVIEW_FILES: usize = 30;
+
+/// An open pull request to review: `42`, `#42`, or `https://github.com/owner/repo/pull/42`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrReference {
+    /// `(owner, repo)` from a URL; None means the repository's own GitHub remote
+    pub repo: Option<(String, String)>,
+    pub number: u64,
+}
+
+impl std::str::FromStr for PrReference {
+    type Err = String;
+
+    fn from_str(input: &str) -> std::result::Result<Self, Self::Err> {
+        let input = input.trim();
+        let invalid = || format!("expected a PR number or GitHub PR URL, got '{}'", input);
+        if let Ok(number) = input.trim_start_matches('#').parse::<u64>() {
+            return Ok(Self { repo: None, number });
+        }
+
+        let path = input
+            .strip_prefix("https://")
+            .or_else(|| input.strip_prefix("http://"))
+            .unwrap_or(input);
+        let path = path.strip_prefix("www.").unwrap_or(path);
+        let path = path.strip_prefix("github.com/").ok_or_else(invalid)?;
+        match path.split('/').collect::<Vec<_>>().as_slice() {
+            [owner, repo, "pull", number, ..] if !owner.is_empty() && !repo.is_empty() => {
+                Ok(Self {
+                    repo: Some((owner.to_string(), repo.to_string())),
+                    number: number.parse().map_err(|_| invalid())?,
+                })
+            }
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// One changed file of a pull request, at its base and head revisions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PullRequestFile {
+    pub path: String,
+    /// Content at the base commit (empty for added files)
+    pub original: String,
+    /// Content at the head commit
+    pub content: String,
+    /// Head-side lines shown in the diff; GitHub only accepts inline comments on these
+    pub diff_lines: BTreeSet<u32>,
+}
+
+/// An open pull request fetched for review.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpenPullRequest {
+    pub owner: String,
+    pub repo: String,
+    pub number: u64,
+    pub title: String,
+    pub body: String,
+    pub html_url: String,
+    pub head_sha: String,
+    /// Reviewable files; removed and binary files are left out
+    pub files: Vec<PullRequestFile>,
+}
+
+/// A finding to post on a pull request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrReviewComment {
+    pub path: String,
+    pub line: Option<u32>,
+    pub body: String,
+}
+
+#[derive(Deserialize)]
+struct PullResponse {
+    title: String,
+    #[serde(default)]
+    body: Option<String>,
+    html_url: String,
+    state: String,
+    head: CommitRef,
+    base: CommitRef,
+}
+
+#[derive(Deserialize)]
+struct CommitRef {
+    sha: String,
+}
+
+#[derive(Deserialize)]
+struct PullFileResponse {
+    filename: String,
+    status: String,
+    #[serde(default)]
+    previous_filename: Option<String>,
+    /// Missing for binary files and very large diffs
+    #[serde(default)]
+    patch: Option<String>,
+}
+
+#[derive(Serialize)]
+struct CreateReview<'a> {
+    commit_id: &'a str,
+    event: &'static str,
+    body: String,
+    comments: Vec<ReviewCommentRequest<'a>>,
+}
+
+#[derive(Serialize)]
+struct ReviewCommentRequest<'a> {
+    path: &'a str,
+    line: u32,
+    side: &'static str,
+    body: &'a str,
+}
+
+#[derive(Deserialize)]
+struct CreateReviewResponse {
+    html_url: String,
+}
+
+impl<'a> CreateReview<'a> {
+    /// Findings on diff lines become inline comments; the rest are listed in the review body.
+    fn new(pr: &'a OpenPullRequest, summary: &str, comments: &'a [PrReviewComment]) -> Self {
+        let mut body = summary.trim().to_string();
+        let mut inline = Vec::new();
+        let mut outside_diff = Vec::new();
+        for comment in comments {
+            let on_diff = comment.line.filter(|line| {
+                pr.files
+                    .iter()
+                    .any(|file| file.path == comment.path && file.diff_lines.contains(line))
+            });
+            match on_diff {
+                Some(line) => inline.push(ReviewCommentRequest {
+                    path: &comment.path,
+                    line,
+                    side: "RIGHT",
+                    body: &comment.body,
+                }),
+                None => outside_diff.push(comment),
+            }
+        }
+        if !outside_diff.is_empty() {
+            body.push_str("\n\n**Outside the diff**\n");
+            for comment in outside_diff {
+                let location = match comment.line {
+                    Some(line) => format!("{}:{}", comment.path, line),
+                    None => comment.path.clone(),
+                };
+                body.push_str(&format!("\n- `{}`: {}", location, comment.body));
+            }
+        }
+        body.push_str("\n\n---\n*Reviewed with Cosmos*");
+        Self {
+            commit_id: &pr.head_sha,
+            event: "COMMENT",
+            body,
+            comments: inline,
+        }
+    }
+}
+
+/// Head-side line numbers covered by a unified diff patch (added and context lines).
+fn patch_diff_lines(patch: &str) -> BTreeSet<u32> {
+    let mut lines = BTreeSet::new();
+    let mut next_line: Option<u32> = None;
+    for line in patch.lines() {
+        if let Some(header) = line.strip_prefix("@@ ") {
+            // @@ -a,b +c,d @@
+            next_line = header
+                .split_whitespace()
+                .find_map(|part| part.strip_prefix('+'))
+                .and_then(|range| range.split(',').next())
+                .and_then(|start| start.parse().ok());
+            continue;
+        }
+        let Some(current) = next_line else {
+            continue;
+        };
+        if line.starts_with('-') || line.starts_with('\\') {
+            continue;
+        }
+        lines.insert(current);
+        next_line = Some(current + 1);
+    }
+    lines
+}
+
+fn api_get(client: &reqwest::Client, url: &str, token: Option<&str>) -> reqwest::RequestBuilder {
+    let builder = client
+        .get(url)
+        .header("Accept", "application/vnd.github+json")
+        .header("User-Agent", "cosmos-tui")
+        .header("X-GitHub-Api-Version", "2022-11-28");
+    match token {
+        Some(token) => builder.header("Authorization", format!("Bearer {}", token)),
+        None => builder,
+    }
+}
+
+async fn fetch_file_content(
+    client: &reqwest::Client,
     owner: &str,
     repo: &str,
-    base: &str,
-    head: &str,
-    title: &str,
-    body: &str,
+    path: &str,
+    sha: &str,
+    token: Option<&str>,
+) -> Result<String> {
+    let mut url = url::Url::parse("https://api.github.com/repos")?;
+    url.path_segments_mut()
+        .map_err(|_| anyhow::anyhow!("Invalid GitHub API URL"))?
+        .extend([owner, repo, "contents"])
+        .extend(path.split('/'));
+    url.query_pairs_mut().append_pair("ref", sha);
+
+    let resp = api_get(client, url.as_str(), token)
+        .header("Accept", "application/vnd.github.raw+json")
+        .send()
+        .await
+        .with_context(|| format!("Failed to fetch {}", path))?;
+    if !resp.status().is_success() {
+        return Err(api_error(resp).await);
+    }
+    resp.text()
+        .await
+        .with_context(|| format!("Failed to read {}", path))
+}
+
+/// Fetch an open pull request with the base and head contents of each changed file.
+///
+/// Public repositories work without signing in; private ones need `--github-login`.
+pub async fn fetch_pull_request(owner: &str, repo: &str, number: u64) -> Result<OpenPullRequest> {
+    let token = get_stored_token();
+    let token = token.as_deref();
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(API_TIMEOUT_SECS))
+        .build()
+        .context("Failed to create HTTP client")?;
+    let api_base = format!("https://api.github.com/repos/{}/{}", owner, repo);
+
+    let resp = api_get(&client, &format!("{}/pulls/{}", api_base, number), token)
+        .send()
+        .await
+        .context("Failed to fetch pull request")?;
+    if !resp.status().is_success() {
+        return Err(api_error(resp).await);
+    }
+    let pull: PullResponse = resp.json().await.context("Failed to parse pull request")?;
+    if pull.state != "open" {
+        return Err(anyhow::anyhow!(
+            "PR #{} is {}; only open pull requests can be reviewed",
+            number,
+            pull.state
+        ));
+    }
+
+    let resp = api_get(
+        &client,
+        &format!(
+            "{}/pulls/{}/files?per_page={}",
+            api_base,
+            number,
+            MAX_REVIEW_FILES + 1
+        ),
+        token,
+    )
+    .send()
+    .await
+    .context("Failed to list pull request files")?;
+    if !resp.status().is_success() {
+        return Err(api_error(resp).await);
+    }
+    let changed: Vec<PullFileResponse> = resp
+        .json()
+        .await
+        .context("Failed to parse pull request files")?;
+    if changed.len() > MAX_REVIEW_FILES {
+        return Err(anyhow::anyhow!(
+            "PR #{} changes more than {} files, too many to review at once",
+            number,
+            MAX_REVIEW_FILES
+        ));
+    }
+
+    let mut files = Vec::new();
+    for file in changed {
+        let Some(patch) = file.patch.as_deref() else {
+            continue;
+        };
+        if file.status == "removed" {
+            continue;
+        }
+        let original = if file.status == "added" {
+            String::new()
+        } else {
+            let base_path = file.previous_filename.as_deref().unwrap_or(&file.filename);
+            fetch_file_content(&client, owner, repo, base_path, &pull.base.sha, token).await?
+        };
+        let content =
+            fetch_file_content(&client, owner, repo, &file.filename, &pull.head.sha, token).await?;
+        files.push(PullRequestFile {
+            diff_lines: patch_diff_lines(patch),
+            path: file.filename,
+            original,
+            content,
+        });
+    }
+    if files.is_empty() {
+        return Err(anyhow::anyhow!(
+            "PR #{} has no text changes to review",
+            number
+        ));
+    }
+
+    Ok(OpenPullRequest {
+        owner: owner.to_string(),
+        repo: repo.to_string(),
+        number,
+        title: pull.title,
+        body: pull.body.unwrap_or_default(),
+        html_url: pull.html_url,
+        head_sha: pull.head.sha,
+        files,
+    })
+}
+
+/// Post findings as a single "comment" review on the pull request's head commit.
+///
+/// Returns the URL of the posted review.
+pub async fn post_pull_request_review(
+    pr: &OpenPullRequest,
+    summary: &str,
+    comments: &[PrReviewComment],
 ) -> Result<String> {
     let token = get_stored_token().ok_or_else(|| {
-        anyhow::anyhow!("Not authenticated with GitHub. Please authenticate first.")
+        anyhow::anyhow!("Not authenticated with GitHub. Run `cosmos --github-login` first.")
     })?;
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(API_TIMEOUT_SECS))
+        .build()
+        .context("Failed to create HTTP client")?;
 
+    let url = format!(
+        "https://api.github.com/repos/{}/{}/pulls/{}/reviews",
+        pr.owner, pr.repo, pr.number
+    );
+    let resp = api_post(&client, &url, &token)
+        .json(&CreateReview::new(pr, summary, comments))
+        .send()
+        .await
+        .context("Failed to send review")?;
+    if !resp.status().is_success() {
+        return Err(api_error(resp).await);
+    }
+    let review: CreateReviewResponse = resp
+        .json()
+        .await
+        .context("Failed to parse review response")?;
+    Ok(review.html_url)
+}
+
+// ============================================================================
+// Follow-up Pushes
+// ============================================================================
+
+/// An open pull request whose head is a local branch; later fixes are pushed onto it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BranchPullRequest {
+    pub owner: String,
+    pub repo: String,
+    pub number: u64,
+    pub title: String,
+    pub html_url: String,
+    pub branch: String,
+}
+
+#[derive(Deserialize)]
+struct BranchPullResponse {
+    number: u64,
+    title: String,
+    html_url: String,
+}
+
+#[derive(Serialize)]
+struct CreateComment<'a> {
+    body: &'a str,
+}
+
+#[derive(Deserialize)]
+struct CreateCommentResponse {
+    html_url: String,
+}
+
+/// Find the open pull request from `branch` in the repository itself (not a fork).
+pub async fn find_open_pull_request(
+    owner: &str,
+    repo: &str,
+    branch: &str,
+) -> Result<Option<BranchPullRequest>> {
+    let token = get_stored_token();
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(API_TIMEOUT_SECS))
+        .build()
+        .context("Failed to create HTTP client")?;
+
+    let mut url = url::Url::parse("https://api.github.com/repos")?;
+    url.path_segments_mut()
+        .map_err(|_| anyhow::anyhow!("Invalid GitHub API URL"))?
+        .extend([owner, repo, "pulls"]);
+    url.query_pairs_mut()
+        .append_pair("head", &format!("{}:{}", owner, branch))
+        .append_pair("state", "open");
+
+    let resp = api_get(&client, url.as_str(), token.as_deref())
+        .send()
+        .await
+        .context("Failed to list pull requests")?;
+    if !resp.status().is_success() {
+        return Err(api_error(resp).await);
+    }
+    let pulls: Vec<BranchPullResponse> =
+        resp.json().await.context("Failed to parse pull requests")?;
+    Ok(pulls.into_iter().next().map(|pull| BranchPullRequest {
+        owner: owner.to_string(),
+        repo: repo.to_string(),
+        number: pull.number,
+        title: pull.title,
+        html_url: pull.html_url,
+        branch: branch.to_string(),
+    }))
+}
+
+/// Post a conversation comment on the pull request.
+///
+/// Returns the URL of the posted comment.
+pub async fn post_pull_request_comment(pr: &BranchPullRequest, body: &str) -> Result<String> {
+    let token = get_stored_token().ok_or_else(|| {
+        anyhow::anyhow!("Not authenticated with GitHub. Run `cosmos --github-login` first.")
+    })?;
     let client = reqwest::Client::builder()
         .timeout(Duration::from_secs(API_TIMEOUT_SECS))
         .build()
         .context("Failed to create HTTP client")?;
 
-    let url = format!("https://api.github.com/repos/{}/{}/pulls", owner, repo);
+    // Pull request conversation comments live on the issue with the same number.
+    let url = format!(
+        "https://api.github.com/repos/{}/{}/issues/{}/comments",
+        pr.owner, pr.repo, pr.number
+    );
+    let resp = api_post(&client, &url, &token)
+        .json(&CreateComment { body })
+        .send()
+        .await
+        .context("Failed to send comment")?;
+    if !resp.status().is_success() {
+        return Err(api_error(resp).await);
+    }
+    let comment: CreateCommentResponse = resp
+        .json()
+        .await
+        .context("Failed to parse comment response")?;
+    Ok(comment.html_url)
+}
+
+// ============================================================================
+// Pull Request Status
+// ============================================================================
+
+#[derive(Deserialize)]
+struct PullStateResponse {
+    state: String,
+    #[serde(default)]
+    merged: bool,
+}
+
+impl PullStateResponse {
+    fn pr_state(&self) -> PrState {
+        if self.merged {
+            PrState::Merged
+        } else if self.state == "open" {
+            PrState::Open
+        } else {
+            PrState::Closed
+        }
+    }
+}
 
-    let request = CreatePrRequest {
-        title: title.to_string(),
-        body: body.to_string(),
-        head: head.to_string(),
-        base: base.to_string(),
+/// State of pull request `number`.
+pub async fn pull_request_state(owner: &str, repo: &str, number: u64) -> Result<PrState> {
+    let token = get_stored_token();
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(API_TIMEOUT_SECS))
+        .build()
+        .context("Failed to create HTTP client")?;
+    let url = format!(
+        "https://api.github.com/repos/{}/{}/pulls/{}",
+        owner, repo, number
+    );
+    let resp = api_get(&client, &url, token.as_deref())
+        .send()
+        .await
+        .context("Failed to fetch pull request")?;
+    if !resp.status().is_success() {
+        return Err(api_error(resp).await);
+    }
+    let pull: PullStateResponse = resp.json().await.context("Failed to parse pull request")?;
+    Ok(pull.pr_state())
+}
+
+// ============================================================================
+// Check Runs
+// ============================================================================
+
+/// Name of the check run Cosmos reports under
+pub const CHECK_RUN_NAME: &str = "Cosmos";
+
+/// GitHub accepts at most this many annotations per check-run request.
+const ANNOTATIONS_PER_REQUEST: usize = 50;
+
+/// Severity shown next to an annotation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnnotationLevel {
+    Notice,
+    Warning,
+    Failure,
+}
+
+/// A finding to attach to a check run as an inline annotation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CheckAnnotation {
+    pub path: String,
+    pub start_line: u32,
+    pub end_line: u32,
+    pub annotation_level: AnnotationLevel,
+    pub title: String,
+    pub message: String,
+}
+
+#[derive(Serialize)]
+struct CheckRunRequest<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    head_sha: Option<&'a str>,
+    status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    conclusion: Option<&'static str>,
+    output: CheckRunOutput<'a>,
+}
+
+#[derive(Serialize)]
+struct CheckRunOutput<'a> {
+    title: &'a str,
+    summary: &'a str,
+    annotations: &'a [CheckAnnotation],
+}
+
+#[derive(Deserialize)]
+struct CheckRunResponse {
+    id: u64,
+    html_url: String,
+}
+
+/// Check-run requests for a report: create the run with the first batch of annotations, add
+/// the rest in updates, and complete it with the last one.
+fn check_run_requests<'a>(
+    head_sha: &'a str,
+    title: &'a str,
+    summary: &'a str,
+    annotations: &'a [CheckAnnotation],
+) -> Vec<CheckRunRequest<'a>> {
+    let batches: Vec<&[CheckAnnotation]> = if annotations.is_empty() {
+        vec![&[]]
+    } else {
+        annotations.chunks(ANNOTATIONS_PER_REQUEST).collect()
     };
+    let last = batches.len() - 1;
+    batches
+        .into_iter()
+        .enumerate()
+        .map(|(idx, batch)| {
+            let done = idx == last;
+            CheckRunRequest {
+                name: (idx == 0).then_some(CHECK_RUN_NAME),
+                head_sha: (idx == 0).then_some(head_sha),
+                status: if done { "completed" } else { "in_progress" },
+                // Findings are advice, so they never fail the build on their own.
+                conclusion: done.then_some(if annotations.is_empty() {
+                    "success"
+                } else {
+                    "neutral"
+                }),
+                output: CheckRunOutput {
+                    title,
+                    summary,
+                    annotations: batch,
+                },
+            }
+        })
+        .collect()
+}
 
-    let resp = client
-        .post(&url)
+fn api_patch(client: &reqwest::Client, url: &str, token: &str) -> reqwest::RequestBuilder {
+    client
+        .patch(url)
         .header("Accept", "application/vnd.github+json")
         .header("Authorization", format!("Bearer {}", token))
         .header("User-Agent", "cosmos-tui")
         .header("X-GitHub-Api-Version", "2022-11-28")
-        .json(&request)
-        .send()
-        .await
-        .context("Failed to send PR creation request")?;
+}
 
-    let status = resp.status();
-    if status.is_success() {
-        let pr: CreatePrResponse = resp.json().await.context("Failed to parse PR response")?;
-        Ok(pr.html_url)
-    } else {
-        let error_body = resp.text().await.unwrap_or_default();
-
-        // Try to parse structured error
-        if let Ok(api_error) = serde_json::from_str::<ApiErrorResponse>(&error_body) {
-            let detail = api_error
-                .errors
-                .first()
-                .and_then(|e| e.message.clone())
-                .unwrap_or_default();
-
-            let msg = if detail.is_empty() {
-                api_error.message
-            } else {
-                format!("{}: {}", api_error.message, detail)
-            };
-
-            return Err(anyhow::anyhow!("GitHub API error: {}", msg));
+/// Create the Cosmos check run on a commit and fill it with annotations.
+///
+/// GitHub appends annotations on every update, so each report is a fresh run; the newest
+/// run with the same name is the one shown on the pull request. Needs a token with
+/// `checks: write` (the Actions `GITHUB_TOKEN` works). Returns the check run's URL.
+pub async fn publish_check_run(
+    owner: &str,
+    repo: &str,
+    head_sha: &str,
+    title: &str,
+    summary: &str,
+    annotations: &[CheckAnnotation],
+) -> Result<String> {
+    let token = get_stored_token().ok_or_else(|| {
+        anyhow::anyhow!(
+            "Not authenticated with GitHub. Set GITHUB_TOKEN or run `cosmos --github-login`."
+        )
+    })?;
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(API_TIMEOUT_SECS))
+        .build()
+        .context("Failed to create HTTP client")?;
+
+    let url = format!("https://api.github.com/repos/{}/{}/check-runs", owner, repo);
+    let mut run: Option<CheckRunResponse> = None;
+    for request in check_run_requests(head_sha, title, summary, annotations) {
+        let builder = match &run {
+            None => api_post(&client, &url, &token),
+            Some(run) => api_patch(&client, &format!("{}/{}", url, run.id), &token),
+        };
+        let resp = builder
+            .json(&request)
+            .send()
+            .await
+            .context("Failed to send check run")?;
+        if !resp.status().is_success() {
+            return Err(api_error(resp).await);
         }
+        run = Some(
+            resp.json()
+                .await
+                .context("Failed to parse check run response")?,
+        );
+    }
+    run.map(|run| run.html_url)
+        .ok_or_else(|| anyhow::anyhow!("No check run was created"))
+}
 
-        // Sanitize raw error body to prevent credential leakage
-        let sanitized = sanitize_error_body(&error_body);
-        Err(anyhow::anyhow!(
-            "GitHub API error ({}): {}",
-            status,
-            sanitized
-        ))
+/// GitHub implementation of the Ship [`Forge`].
+pub struct GitHubForge;
+
+impl Forge for GitHubForge {
+    fn kind(&self) -> ForgeKind {
+        ForgeKind::GitHub
+    }
+
+    fn is_authenticated(&self) -> bool {
+        is_authenticated()
+    }
+
+    fn create_pull_request<'a>(
+        &'a self,
+        repo: &'a ForgeRepo,
+        request: &'a PullRequest,
+    ) -> Pin<Box<dyn Future<Output = Result<String>> + Send + 'a>> {
+        Box::pin(create_pull_request(&repo.owner, &repo.name, request))
+    }
+
+    fn new_pull_request_url(&self, repo: &ForgeRepo, base: &str, head: &str) -> String {
+        format!("{}/compare/{}...{}?expand=1", repo.web_url(), base, head)
+    }
+
+    fn pull_request_state<'a>(
+        &'a self,
+        repo: &'a ForgeRepo,
+        number: u64,
+    ) -> Pin<Box<dyn Future<Output = Result<PrState>> + Send + 'a>> {
+        Box::pin(pull_request_state(&repo.owner, &repo.name, number))
     }
 }
 
@@ -751,14 +1521,148 @@ mod tests {
             body: "This fixes the bug".to_string(),
             head: "fix/my-branch".to_string(),
             base: "main".to_string(),
+            draft: true,
         };
         let json = serde_json::to_string(&request).unwrap();
+        assert!(json.contains("\"draft\":true"));
         assert!(json.contains("\"title\":\"Fix bug\""));
         assert!(json.contains("\"body\":\"This fixes the bug\""));
         assert!(json.contains("\"head\":\"fix/my-branch\""));
         assert!(json.contains("\"base\":\"main\""));
     }
 
+    #[test]
+    fn test_request_reviewers_splits_users_and_teams() {
+        let request =
+            RequestReviewers::new(&["octocat".to_string(), "my-org/platform".to_string()]);
+        assert_eq!(request.reviewers, vec!["octocat"]);
+        assert_eq!(request.team_reviewers, vec!["platform"]);
+    }
+
+    // ========================================================================
+    // PR Review Tests
+    // ========================================================================
+
+    #[test]
+    fn test_parse_pr_reference() {
+        let local: PrReference = "#42".parse().unwrap();
+        assert_eq!(local.repo, None);
+        assert_eq!(local.number, 42);
+
+        let url: PrReference = "https://github.com/octo/cosmos/pull/7/files"
+            .parse()
+            .unwrap();
+        assert_eq!(url.repo, Some(("octo".to_string(), "cosmos".to_string())));
+        assert_eq!(url.number, 7);
+
+        assert!("https://gitlab.com/octo/cosmos/pull/7"
+            .parse::<PrReference>()
+            .is_err());
+        assert!("https://github.com/octo/cosmos/issues/7"
+            .parse::<PrReference>()
+            .is_err());
+    }
+
+    #[test]
+    fn test_patch_diff_lines_tracks_head_side() {
+        let patch = "@@ -1,3 +1,4 @@\n fn a() {}\n-fn b() {}\n+fn b2() {}\n+fn c() {}\n \n@@ -20,2 +21,2 @@\n-x\n+y\n\\ No newline at end of file";
+        let lines: Vec<u32> = patch_diff_lines(patch).into_iter().collect();
+        assert_eq!(lines, vec![1, 2, 3, 4, 21]);
+    }
+
+    #[test]
+    fn test_pull_state_response_distinguishes_merged_from_closed() {
+        let state = |json: &str| {
+            serde_json::from_str::<PullStateResponse>(json)
+                .unwrap()
+                .pr_state()
+        };
+        assert_eq!(state(r#"{"state":"open","merged":false}"#), PrState::Open);
+        assert_eq!(
+            state(r#"{"state":"closed","merged":true}"#),
+            PrState::Merged
+        );
+        assert_eq!(state(r#"{"state":"closed"}"#), PrState::Closed);
+    }
+
+    #[test]
+    fn test_create_review_moves_comments_outside_diff_to_body() {
+        let pr = OpenPullRequest {
+            owner: "octo".to_string(),
+            repo: "cosmos".to_string(),
+            number: 7,
+            title: "Retry".to_string(),
+            body: String::new(),
+            html_url: String::new(),
+            head_sha: "abc123".to_string(),
+            files: vec![PullRequestFile {
+                path: "src/retry.rs".to_string(),
+                original: String::new(),
+                content: String::new(),
+                diff_lines: BTreeSet::from([10, 11]),
+            }],
+        };
+        let comments = vec![
+            PrReviewComment {
+                path: "src/retry.rs".to_string(),
+                line: Some(11),
+                body: "Off by one".to_string(),
+            },
+            PrReviewComment {
+                path: "src/retry.rs".to_string(),
+                line: Some(40),
+                body: "Unbounded loop".to_string(),
+            },
+        ];
+        let json = serde_json::to_value(CreateReview::new(&pr, "Two issues.", &comments)).unwrap();
+        assert_eq!(json["commit_id"], "abc123");
+        assert_eq!(json["event"], "COMMENT");
+        assert_eq!(
+            json["comments"],
+            serde_json::json!([{"path": "src/retry.rs", "line": 11, "side": "RIGHT", "body": "Off by one"}])
+        );
+        let body = json["body"].as_str().unwrap();
+        assert!(body.starts_with("Two issues."));
+        assert!(body.contains("- `src/retry.rs:40`: Unbounded loop"));
+    }
+
+    #[test]
+    fn test_check_run_requests_batch_annotations_and_complete_last() {
+        let annotations: Vec<CheckAnnotation> = (1..=120)
+            .map(|line| CheckAnnotation {
+                path: "src/lib.rs".to_string(),
+                start_line: line,
+                end_line: line,
+                annotation_level: AnnotationLevel::Warning,
+                title: "Finding".to_string(),
+                message: "Details".to_string(),
+            })
+            .collect();
+        let requests = check_run_requests("abc123", "3 findings", "summary", &annotations);
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[0].output.annotations.len(), 50);
+        assert_eq!(requests[2].output.annotations.len(), 20);
+
+        let first = serde_json::to_value(&requests[0]).unwrap();
+        assert_eq!(first["name"], CHECK_RUN_NAME);
+        assert_eq!(first["head_sha"], "abc123");
+        assert_eq!(first["status"], "in_progress");
+        assert!(first.get("conclusion").is_none());
+        assert_eq!(
+            first["output"]["annotations"][0]["annotation_level"],
+            "warning"
+        );
+
+        let last = serde_json::to_value(&requests[2]).unwrap();
+        assert!(last.get("head_sha").is_none());
+        assert_eq!(last["status"], "completed");
+        assert_eq!(last["conclusion"], "neutral");
+
+        let clean = check_run_requests("abc123", "No findings", "summary", &[]);
+        assert_eq!(clean.len(), 1);
+        assert_eq!(clean[0].conclusion, Some("success"));
+    }
+
     #[test]
     fn test_create_pr_request_handles_special_chars() {
         let request = CreatePrRequest {
@@ -766,6 +1670,7 @@ mod tests {
             body: "Line1\nLine2\n\n## Header".to_string(),
             head: "fix/branch-name".to_string(),
             base: "main".to_string(),
+            draft: false,
         };
         let json = serde_json::to_string(&request).unwrap();
         // Should properly escape quotes
@@ -0,0 +1,346 @@
+//! Native GitLab API integration
+//!
+//! Opens merge requests on gitlab.com or self-managed instances with a personal
+//! access token from `GITLAB_TOKEN` or the system keychain.
+
+use crate::forge::{Forge, ForgeKind, ForgeRepo, PrState, PullRequest};
+use crate::github::sanitize_error_body;
+use crate::keyring;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
+
+const API_TIMEOUT_SECS: u64 = 60;
+
+/// Get the stored GitLab token, or None if not authenticated.
+pub fn get_stored_token() -> Option<String> {
+    if let Ok(token) = std::env::var("GITLAB_TOKEN") {
+        if !token.is_empty() {
+            return Some(token);
+        }
+    }
+
+    match keyring::get_gitlab_token() {
+        Ok(token) => token,
+        Err(err) => {
+            keyring::warn_keychain_error_once("GitLab token", &err);
+            None
+        }
+    }
+}
+
+/// Check if GitLab authentication is configured.
+pub fn is_authenticated() -> bool {
+    get_stored_token().is_some()
+}
+
+fn encode(value: &str) -> String {
+    url::form_urlencoded::byte_serialize(value.as_bytes()).collect()
+}
+
+#[derive(Serialize)]
+struct CreateMergeRequest {
+    source_branch: String,
+    target_branch: String,
+    title: String,
+    description: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    labels: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    reviewer_ids: Vec<u64>,
+}
+
+impl CreateMergeRequest {
+    fn new(request: &PullRequest, reviewer_ids: Vec<u64>) -> Self {
+        // GitLab marks merge requests as drafts by title prefix.
+        let title = if request.draft {
+            format!("Draft: {}", request.title)
+        } else {
+            request.title.clone()
+        };
+        Self {
+            source_branch: request.head.clone(),
+            target_branch: request.base.clone(),
+            title,
+            description: request.body.clone(),
+            labels: request.labels.join(","),
+            reviewer_ids,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct User {
+    id: u64,
+}
+
+#[derive(Deserialize)]
+struct CreateMergeRequestResponse {
+    web_url: String,
+}
+
+#[derive(Deserialize)]
+struct MergeRequestStateResponse {
+    state: String,
+}
+
+impl MergeRequestStateResponse {
+    /// GitLab states are `opened`, `closed`, `locked` (briefly, while merging), and `merged`.
+    fn pr_state(&self) -> PrState {
+        match self.state.as_str() {
+            "merged" => PrState::Merged,
+            "opened" | "locked" => PrState::Open,
+            _ => PrState::Closed,
+        }
+    }
+}
+
+/// GitLab reports errors as `{"message": ...}` (a string, list, or field map) or `{"error": ...}`.
+#[derive(Deserialize)]
+struct ApiErrorResponse {
+    #[serde(default)]
+    message: Option<serde_json::Value>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+fn flatten_error_message(value: &serde_json::Value) -> Vec<String> {
+    match value {
+        serde_json::Value::String(text) => vec![text.clone()],
+        serde_json::Value::Array(items) => items.iter().flat_map(flatten_error_message).collect(),
+        serde_json::Value::Object(fields) => fields
+            .iter()
+            .flat_map(|(field, value)| {
+                flatten_error_message(value)
+                    .into_iter()
+                    .map(move |message| format!("{} {}", field, message))
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn api_error_message(body: &str) -> Option<String> {
+    let parsed = serde_json::from_str::<ApiErrorResponse>(body).ok()?;
+    let mut messages = parsed
+        .message
+        .as_ref()
+        .map(flatten_error_message)
+        .unwrap_or_default();
+    messages.extend(parsed.error);
+    (!messages.is_empty()).then(|| messages.join("; "))
+}
+
+/// Resolve reviewer usernames to the user ids the merge request API expects.
+async fn lookup_reviewer_ids(
+    client: &reqwest::Client,
+    host: &str,
+    token: &str,
+    usernames: &[String],
+) -> Result<Vec<u64>> {
+    let mut ids = Vec::with_capacity(usernames.len());
+    for username in usernames {
+        let resp = client
+            .get(format!("https://{}/api/v4/users", host))
+            .query(&[("username", username.as_str())])
+            .header("PRIVATE-TOKEN", token)
+            .header("User-Agent", "cosmos-tui")
+            .send()
+            .await
+            .context("Failed to look up GitLab reviewer")?;
+        if !resp.status().is_success() {
+            return Err(anyhow::anyhow!(
+                "GitLab API error ({}) looking up reviewer '{}'",
+                resp.status(),
+                username
+            ));
+        }
+        let users: Vec<User> = resp
+            .json()
+            .await
+            .context("Failed to parse GitLab user lookup")?;
+        let user = users
+            .first()
+            .ok_or_else(|| anyhow::anyhow!("GitLab user '{}' not found", username))?;
+        ids.push(user.id);
+    }
+    Ok(ids)
+}
+
+/// Create a merge request on GitLab.
+///
+/// Returns the URL of the created merge request.
+pub async fn create_merge_request(repo: &ForgeRepo, request: &PullRequest) -> Result<String> {
+    let token = get_stored_token().ok_or_else(|| {
+        anyhow::anyhow!("Not authenticated with GitLab. Please authenticate first.")
+    })?;
+
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(API_TIMEOUT_SECS))
+        .build()
+        .context("Failed to create HTTP client")?;
+
+    let url = format!(
+        "https://{}/api/v4/projects/{}/merge_requests",
+        repo.host,
+        encode(&format!("{}/{}", repo.owner, repo.name))
+    );
+    let reviewer_ids = lookup_reviewer_ids(&client, &repo.host, &token, &request.reviewers).await?;
+    let body = CreateMergeRequest::new(request, reviewer_ids);
+
+    let resp = client
+        .post(&url)
+        .header("PRIVATE-TOKEN", token)
+        .header("User-Agent", "cosmos-tui")
+        .json(&body)
+        .send()
+        .await
+        .context("Failed to send merge request creation request")?;
+
+    if !resp.status().is_success() {
+        return Err(api_error(resp).await);
+    }
+    let mr: CreateMergeRequestResponse = resp
+        .json()
+        .await
+        .context("Failed to parse merge request response")?;
+    Ok(mr.web_url)
+}
+
+/// State of merge request `iid`.
+pub async fn merge_request_state(repo: &ForgeRepo, iid: u64) -> Result<PrState> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(API_TIMEOUT_SECS))
+        .build()
+        .context("Failed to create HTTP client")?;
+
+    let url = format!(
+        "https://{}/api/v4/projects/{}/merge_requests/{}",
+        repo.host,
+        encode(&format!("{}/{}", repo.owner, repo.name)),
+        iid
+    );
+    let mut builder = client.get(&url).header("User-Agent", "cosmos-tui");
+    if let Some(token) = get_stored_token() {
+        builder = builder.header("PRIVATE-TOKEN", token);
+    }
+    let resp = builder
+        .send()
+        .await
+        .context("Failed to fetch merge request")?;
+    if !resp.status().is_success() {
+        return Err(api_error(resp).await);
+    }
+    let mr: MergeRequestStateResponse =
+        resp.json().await.context("Failed to parse merge request")?;
+    Ok(mr.pr_state())
+}
+
+async fn api_error(resp: reqwest::Response) -> anyhow::Error {
+    let status = resp.status();
+    let error_body = resp.text().await.unwrap_or_default();
+    if let Some(message) = api_error_message(&error_body) {
+        return anyhow::anyhow!("GitLab API error: {}", message);
+    }
+    anyhow::anyhow!(
+        "GitLab API error ({}): {}",
+        status,
+        sanitize_error_body(&error_body)
+    )
+}
+
+/// GitLab implementation of the Ship [`Forge`].
+pub struct GitLabForge;
+
+impl Forge for GitLabForge {
+    fn kind(&self) -> ForgeKind {
+        ForgeKind::GitLab
+    }
+
+    fn is_authenticated(&self) -> bool {
+        is_authenticated()
+    }
+
+    fn create_pull_request<'a>(
+        &'a self,
+        repo: &'a ForgeRepo,
+        request: &'a PullRequest,
+    ) -> Pin<Box<dyn Future<Output = Result<String>> + Send + 'a>> {
+        Box::pin(create_merge_request(repo, request))
+    }
+
+    fn new_pull_request_url(&self, repo: &ForgeRepo, base: &str, head: &str) -> String {
+        format!(
+            "{}/-/merge_requests/new?{}={}&{}={}",
+            repo.web_url(),
+            encode("merge_request[source_branch]"),
+            encode(head),
+            encode("merge_request[target_branch]"),
+            encode(base)
+        )
+    }
+
+    fn pull_request_state<'a>(
+        &'a self,
+        repo: &'a ForgeRepo,
+        number: u64,
+    ) -> Pin<Box<dyn Future<Output = Result<PrState>> + Send + 'a>> {
+        Box::pin(merge_request_state(repo, number))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_merge_request_serialization() {
+        let request = PullRequest {
+            base: "main".to_string(),
+            head: "cosmos/fix".to_string(),
+            title: "Fix bug".to_string(),
+            body: "Details".to_string(),
+            ..Default::default()
+        };
+        let json = serde_json::to_value(CreateMergeRequest::new(&request, Vec::new())).unwrap();
+        assert_eq!(json["source_branch"], "cosmos/fix");
+        assert_eq!(json["target_branch"], "main");
+        assert_eq!(json["description"], "Details");
+        assert!(json.get("labels").is_none());
+        assert!(json.get("reviewer_ids").is_none());
+    }
+
+    #[test]
+    fn test_create_merge_request_draft_labels_and_reviewers() {
+        let request = PullRequest {
+            title: "Fix bug".to_string(),
+            draft: true,
+            labels: vec!["bug".to_string(), "cosmos".to_string()],
+            ..Default::default()
+        };
+        let json = serde_json::to_value(CreateMergeRequest::new(&request, vec![42])).unwrap();
+        assert_eq!(json["title"], "Draft: Fix bug");
+        assert_eq!(json["labels"], "bug,cosmos");
+        assert_eq!(json["reviewer_ids"], serde_json::json!([42]));
+    }
+
+    #[test]
+    fn test_api_error_message_flattens_gitlab_error_shapes() {
+        assert_eq!(
+            api_error_message(r#"{"message":["Another open merge request already exists"]}"#)
+                .as_deref(),
+            Some("Another open merge request already exists")
+        );
+        assert_eq!(
+            api_error_message(r#"{"message":{"source_branch":["is invalid"]}}"#).as_deref(),
+            Some("source_branch is invalid")
+        );
+        assert_eq!(
+            api_error_message(r#"{"error":"insufficient_scope"}"#).as_deref(),
+            Some("insufficient_scope")
+        );
+        assert_eq!(api_error_message("<html>"), None);
+    }
+}
@@ -29,6 +29,10 @@ struct StoredCredentials {
     openrouter_api_key: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     github_token: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    gitlab_token: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bitbucket_token: Option<String>,
 }
 
 type KeyringResult<T> = Result<T, String>;
@@ -68,6 +72,8 @@ fn keyring_disabled() -> bool {
         if creds.cerebras_api_key.is_some()
             || creds.openrouter_api_key.is_some()
             || creds.github_token.is_some()
+            || creds.gitlab_token.is_some()
+            || creds.bitbucket_token.is_some()
         {
             return true;
         }
@@ -307,6 +313,36 @@ pub fn set_github_token(token: &str) -> Result<(), String> {
     Ok(())
 }
 
+/// Get the GitLab token from the keychain
+pub fn get_gitlab_token() -> KeyringResult<Option<String>> {
+    let creds = read_credentials_cached()?;
+    Ok(creds.gitlab_token)
+}
+
+/// Set the GitLab token in the keychain
+pub fn set_gitlab_token(token: &str) -> Result<(), String> {
+    let mut creds = read_credentials_cached().unwrap_or_default();
+    creds.gitlab_token = Some(token.to_string());
+    write_credentials(&creds).map_err(|e| e.to_string())?;
+    update_cache(creds);
+    Ok(())
+}
+
+/// Get the Bitbucket token from the keychain
+pub fn get_bitbucket_token() -> KeyringResult<Option<String>> {
+    let creds = read_credentials_cached()?;
+    Ok(creds.bitbucket_token)
+}
+
+/// Set the Bitbucket token in the keychain
+pub fn set_bitbucket_token(token: &str) -> Result<(), String> {
+    let mut creds = read_credentials_cached().unwrap_or_default();
+    creds.bitbucket_token = Some(token.to_string());
+    write_credentials(&creds).map_err(|e| e.to_string())?;
+    update_cache(creds);
+    Ok(())
+}
+
 // ============================================================================
 // Tests
 // ============================================================================
@@ -330,6 +366,8 @@ mod tests {
             cerebras_api_key: Some("csk-test".to_string()),
             openrouter_api_key: Some("sk-test".to_string()),
             github_token: Some("ghp_test".to_string()),
+            gitlab_token: None,
+            bitbucket_token: None,
         };
         let json = serde_json::to_string(&creds).unwrap();
         assert!(json.contains("csk-test"));
@@ -349,6 +387,8 @@ mod tests {
             cerebras_api_key: Some("csk-test".to_string()),
             openrouter_api_key: None,
             github_token: None,
+            gitlab_token: None,
+            bitbucket_token: None,
         };
         let json = serde_json::to_string(&creds).unwrap();
         assert!(json.contains("csk-test"));
@@ -405,6 +445,20 @@ mod tests {
             get_github_token().unwrap(),
             Some("ghp-test-token".to_string())
         );
+        set_gitlab_token("glpat-test-token").unwrap();
+        set_bitbucket_token("user:app-password").unwrap();
+        assert_eq!(
+            get_gitlab_token().unwrap(),
+            Some("glpat-test-token".to_string())
+        );
+        assert_eq!(
+            get_bitbucket_token().unwrap(),
+            Some("user:app-password".to_string())
+        );
+        assert_eq!(
+            get_github_token().unwrap(),
+            Some("ghp-test-token".to_string())
+        );
 
         let _ = std::fs::remove_file(&path);
         std::env::remove_var("COSMOS_CREDENTIALS_FILE");
@@ -1,10 +1,20 @@
-//! Runtime adapters for Cosmos (git, config/auth, persistence, updates).
+//! Runtime adapters for Cosmos (git, forges, config/auth, persistence, analytics, usage, undo,
+//! updates, org presets, clipboard, terminal capabilities).
 
+pub mod analytics;
+pub mod bitbucket;
 pub mod cache;
+pub mod clipboard;
 pub mod config;
+pub mod forge;
 pub mod git_ops;
 pub mod github;
+pub mod gitlab;
 pub mod keyring;
 pub mod onboarding;
+pub mod preset;
+pub mod terminal;
+pub mod undo;
 pub mod update;
+pub mod usage;
 pub mod util;
@@ -0,0 +1,469 @@
+//! Organization presets: one bundle of `.cosmos/` files (suggestion policy, prompt templates,
+//! and the repo config carrying model routing and budgets) that an org publishes once and each
+//! repository installs with `cosmos preset apply <source>`.
+//!
+//! A bundle is a TOML file:
+//!
+//! ```toml
+//! name = "acme"
+//! description = "Acme engineering defaults"
+//!
+//! [files]
+//! "policy.yml" = "..."
+//! "config.toml" = "..."
+//! "prompts/review.md" = "..."
+//! ```
+//!
+//! Remote bundles must match a SHA-256 digest given up front, so a compromised host or a
+//! moved tag can't change what gets installed.
+
+use crate::util::run_command_with_timeout;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+
+/// File read from the root of a git source.
+pub const BUNDLE_FILE: &str = "cosmos-preset.toml";
+/// Where bundle files are installed, relative to the repo root.
+pub const PRESET_DIR: &str = ".cosmos";
+const GIT_CLONE_TIMEOUT_SECS: u64 = 120;
+const MAX_BUNDLE_BYTES: usize = 1024 * 1024;
+
+/// Where a bundle comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PresetSource {
+    /// `https://` URL of the bundle file
+    Url(String),
+    /// Git repository with `cosmos-preset.toml` at its root, optionally at a branch or tag
+    /// (`git+https://host/org/presets.git#v2`)
+    Git { url: String, rev: Option<String> },
+    /// Bundle file on disk
+    File(PathBuf),
+}
+
+impl PresetSource {
+    pub fn parse(source: &str) -> Self {
+        let source = source.trim();
+        if let Some(rest) = source.strip_prefix("git+") {
+            return Self::git(rest);
+        }
+        let is_git = source.starts_with("git@")
+            || source.starts_with("ssh://")
+            || source.trim_end_matches('/').ends_with(".git")
+            || source.contains(".git#");
+        if is_git {
+            return Self::git(source);
+        }
+        if source.starts_with("https://") || source.starts_with("http://") {
+            return PresetSource::Url(source.to_string());
+        }
+        PresetSource::File(PathBuf::from(source))
+    }
+
+    fn git(spec: &str) -> Self {
+        match spec.split_once('#') {
+            Some((url, rev)) if !rev.trim().is_empty() => PresetSource::Git {
+                url: url.to_string(),
+                rev: Some(rev.trim().to_string()),
+            },
+            Some((url, _)) => PresetSource::Git {
+                url: url.to_string(),
+                rev: None,
+            },
+            None => PresetSource::Git {
+                url: spec.to_string(),
+                rev: None,
+            },
+        }
+    }
+
+    /// Remote sources must be pinned with a checksum.
+    pub fn is_remote(&self) -> bool {
+        !matches!(self, PresetSource::File(_))
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PresetBundle {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// File contents keyed by path relative to `.cosmos/`
+    pub files: BTreeMap<String, String>,
+}
+
+impl PresetBundle {
+    pub fn parse(content: &str) -> Result<Self> {
+        let bundle: PresetBundle =
+            toml::from_str(content).map_err(|e| anyhow::anyhow!("Invalid preset bundle: {}", e))?;
+        if bundle.name.trim().is_empty() {
+            anyhow::bail!("Invalid preset bundle: name cannot be empty");
+        }
+        if bundle.files.is_empty() {
+            anyhow::bail!("Invalid preset bundle: [files] is empty");
+        }
+        let problems: Vec<String> = bundle
+            .files
+            .keys()
+            .filter(|path| !is_allowed_path(path))
+            .map(|path| format!("'{}'", path))
+            .collect();
+        if !problems.is_empty() {
+            anyhow::bail!(
+                "Invalid preset bundle: {} not allowed (expected policy.yml, config.toml, or prompts/<name>.md)",
+                problems.join(", ")
+            );
+        }
+        Ok(bundle)
+    }
+}
+
+/// Bundles may only write the files Cosmos reads from `.cosmos/`; anything else (including
+/// `..` and absolute paths) is refused.
+fn is_allowed_path(path: &str) -> bool {
+    match path {
+        "policy.yml" | "config.toml" => true,
+        _ => path
+            .strip_prefix("prompts/")
+            .and_then(|name| name.strip_suffix(".md"))
+            .is_some_and(|stem| {
+                !stem.is_empty()
+                    && stem
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+            }),
+    }
+}
+
+/// Lowercase hex SHA-256 of `bytes`.
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// Check `bytes` against `expected`; remote sources can't skip the check.
+pub fn verify_checksum(bytes: &[u8], expected: Option<&str>, remote: bool) -> Result<()> {
+    let actual = sha256_hex(bytes);
+    match expected {
+        Some(expected) => {
+            let expected = expected.trim().to_ascii_lowercase();
+            let expected = expected.strip_prefix("sha256:").unwrap_or(&expected);
+            if expected != actual {
+                anyhow::bail!(
+                    "Preset checksum mismatch: expected {}, got {}. Nothing was installed.",
+                    expected,
+                    actual
+                );
+            }
+            Ok(())
+        }
+        None if remote => anyhow::bail!(
+            "Remote presets need a checksum: rerun with --sha256 {} after checking the bundle is the one you expect",
+            actual
+        ),
+        None => Ok(()),
+    }
+}
+
+/// Download or read the bundle bytes.
+pub async fn fetch(source: &PresetSource) -> Result<Vec<u8>> {
+    let bytes = match source {
+        PresetSource::File(path) => {
+            std::fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?
+        }
+        PresetSource::Url(url) => {
+            let client = reqwest::Client::builder()
+                .user_agent(format!("cosmos-tui/{}", env!("CARGO_PKG_VERSION")))
+                .build()
+                .context("Failed to create HTTP client")?;
+            client
+                .get(url)
+                .send()
+                .await
+                .and_then(|response| response.error_for_status())
+                .with_context(|| format!("Failed to fetch {}", url))?
+                .bytes()
+                .await
+                .with_context(|| format!("Failed to read {}", url))?
+                .to_vec()
+        }
+        PresetSource::Git { url, rev } => fetch_from_git(url, rev.as_deref())?,
+    };
+    if bytes.len() > MAX_BUNDLE_BYTES {
+        anyhow::bail!(
+            "Preset bundle is {} bytes; the limit is {}",
+            bytes.len(),
+            MAX_BUNDLE_BYTES
+        );
+    }
+    Ok(bytes)
+}
+
+fn fetch_from_git(url: &str, rev: Option<&str>) -> Result<Vec<u8>> {
+    let checkout = std::env::temp_dir().join(format!("cosmos-preset-{}", uuid::Uuid::new_v4()));
+    let mut cmd = Command::new("git");
+    cmd.args(["clone", "--quiet", "--depth", "1"]);
+    if let Some(rev) = rev {
+        cmd.args(["--branch", rev]);
+    }
+    cmd.arg(url).arg(&checkout).env("GIT_TERMINAL_PROMPT", "0");
+    let result = run_command_with_timeout(&mut cmd, Duration::from_secs(GIT_CLONE_TIMEOUT_SECS))
+        .map_err(|e| anyhow::anyhow!("Failed to run git clone: {}", e));
+    let bytes = result.and_then(|run| {
+        if run.timed_out {
+            anyhow::bail!("git clone of {} timed out", url);
+        }
+        if !run.status.is_some_and(|status| status.success()) {
+            anyhow::bail!("git clone of {} failed: {}", url, run.stderr.trim());
+        }
+        std::fs::read(checkout.join(BUNDLE_FILE))
+            .with_context(|| format!("{} has no {} at its root", url, BUNDLE_FILE))
+    });
+    let _ = std::fs::remove_dir_all(&checkout);
+    bytes
+}
+
+/// One bundle file and what it replaces.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlannedFile {
+    /// Path relative to the repo root
+    pub path: PathBuf,
+    pub current: Option<String>,
+    pub content: String,
+}
+
+impl PlannedFile {
+    pub fn is_unchanged(&self) -> bool {
+        self.current.as_deref() == Some(self.content.as_str())
+    }
+}
+
+/// Compare the bundle against what's in the repo now.
+pub fn plan(repo_root: &Path, bundle: &PresetBundle) -> Result<Vec<PlannedFile>> {
+    bundle
+        .files
+        .iter()
+        .map(|(relative, content)| {
+            let path = Path::new(PRESET_DIR).join(relative);
+            let current = match std::fs::read_to_string(repo_root.join(&path)) {
+                Ok(current) => Some(current),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+                Err(e) => return Err(anyhow::anyhow!("Failed to read {}: {}", path.display(), e)),
+            };
+            Ok(PlannedFile {
+                path,
+                current,
+                content: content.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Unified diff of every file the bundle changes.
+pub fn render_diff(planned: &[PlannedFile]) -> String {
+    let mut out = String::new();
+    for file in planned.iter().filter(|file| !file.is_unchanged()) {
+        let path = file.path.display().to_string();
+        let old_header = match file.current {
+            Some(_) => format!("a/{}", path),
+            None => "/dev/null".to_string(),
+        };
+        let diff = similar::TextDiff::from_lines(
+            file.current.as_deref().unwrap_or(""),
+            file.content.as_str(),
+        );
+        out.push_str(
+            &diff
+                .unified_diff()
+                .context_radius(3)
+                .header(&old_header, &format!("b/{}", path))
+                .to_string(),
+        );
+        if !out.ends_with('\n') {
+            out.push('\n');
+        }
+    }
+    out
+}
+
+/// Write the planned files into `repo_root`.
+pub fn install(repo_root: &Path, planned: &[PlannedFile]) -> Result<()> {
+    for file in planned.iter().filter(|file| !file.is_unchanged()) {
+        let path = repo_root.join(&file.path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        std::fs::write(&path, &file.content)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+    }
+    Ok(())
+}
+
+/// Run `check` against a scratch copy of the repo's `.cosmos/` with the bundle applied, so a
+/// bundle the engine would reject never reaches the repo.
+pub fn validate_staged(
+    repo_root: &Path,
+    planned: &[PlannedFile],
+    check: impl FnOnce(&Path) -> Result<()>,
+) -> Result<()> {
+    let staging = std::env::temp_dir().join(format!("cosmos-preset-{}", uuid::Uuid::new_v4()));
+    let result = copy_dir(&repo_root.join(PRESET_DIR), &staging.join(PRESET_DIR))
+        .and_then(|_| install(&staging, planned))
+        .and_then(|_| check(&staging));
+    let _ = std::fs::remove_dir_all(&staging);
+    result
+}
+
+fn copy_dir(from: &Path, to: &Path) -> Result<()> {
+    let entries = match std::fs::read_dir(from) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(anyhow::anyhow!("Failed to read {}: {}", from.display(), e)),
+    };
+    std::fs::create_dir_all(to).with_context(|| format!("Failed to create {}", to.display()))?;
+    for entry in entries.flatten() {
+        let target = to.join(entry.file_name());
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else if file_type.is_file() {
+            std::fs::copy(entry.path(), &target)
+                .with_context(|| format!("Failed to copy {}", entry.path().display()))?;
+        }
+    }
+    Ok(())
+}
+
+/// Fetch, verify, and show the bundle's diff, then install it once the user confirms
+/// (or straight away with `assume_yes`). `check` validates the result before anything is
+/// written; see `validate_staged`.
+pub async fn apply(
+    repo_root: &Path,
+    source: &str,
+    sha256: Option<&str>,
+    assume_yes: bool,
+    check: impl FnOnce(&Path) -> Result<()>,
+) -> Result<()> {
+    let source = PresetSource::parse(source);
+    let bytes = fetch(&source).await?;
+    verify_checksum(&bytes, sha256, source.is_remote())?;
+    let content = String::from_utf8(bytes).context("Preset bundle is not UTF-8")?;
+    let bundle = PresetBundle::parse(&content)?;
+    let planned = plan(repo_root, &bundle)?;
+    validate_staged(repo_root, &planned, check)?;
+
+    let label = match &bundle.description {
+        Some(description) => format!("{} ({})", bundle.name, description.trim()),
+        None => bundle.name.clone(),
+    };
+    if planned.iter().all(PlannedFile::is_unchanged) {
+        println!("Preset {} is already installed; nothing to change.", label);
+        return Ok(());
+    }
+    println!("Preset {}:", label);
+    print!("{}", render_diff(&planned));
+
+    if !assume_yes && !confirm("Install these changes?")? {
+        println!("Nothing was installed.");
+        return Ok(());
+    }
+    install(repo_root, &planned)?;
+    let written = planned.iter().filter(|file| !file.is_unchanged()).count();
+    println!(
+        "Installed preset {}: {} file(s) under {}/. Review and commit them to share with your team.",
+        bundle.name, written, PRESET_DIR
+    );
+    Ok(())
+}
+
+fn confirm(question: &str) -> Result<bool> {
+    print!("{} [y/N] ", question);
+    io::stdout().flush()?;
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    Ok(matches!(
+        answer.trim().to_ascii_lowercase().as_str(),
+        "y" | "yes"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BUNDLE: &str = r#"
+name = "acme"
+
+[files]
+"policy.yml" = "min_priority: medium\n"
+"prompts/review.md" = "Review carefully.\n"
+"#;
+
+    #[test]
+    fn test_preset_plan_diff_install_and_checksum() {
+        assert_eq!(
+            PresetSource::parse("git+https://example.com/org/presets#v2"),
+            PresetSource::Git {
+                url: "https://example.com/org/presets".to_string(),
+                rev: Some("v2".to_string()),
+            }
+        );
+        assert!(matches!(
+            PresetSource::parse("https://example.com/preset.toml"),
+            PresetSource::Url(_)
+        ));
+        assert!(!PresetSource::parse("presets/acme.toml").is_remote());
+
+        let bad = BUNDLE.replace("prompts/review.md", "../hooks/pre-push");
+        assert!(PresetBundle::parse(&bad).is_err());
+        assert!(PresetBundle::parse(&BUNDLE.replace("review.md", "review.txt")).is_err());
+
+        let digest = sha256_hex(BUNDLE.as_bytes());
+        assert!(verify_checksum(BUNDLE.as_bytes(), Some(&digest), true).is_ok());
+        assert!(verify_checksum(BUNDLE.as_bytes(), Some(&"0".repeat(64)), true).is_err());
+        assert!(verify_checksum(BUNDLE.as_bytes(), None, true).is_err());
+        assert!(verify_checksum(BUNDLE.as_bytes(), None, false).is_ok());
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join(".cosmos")).unwrap();
+        std::fs::write(dir.path().join(".cosmos/policy.yml"), "min_priority: low\n").unwrap();
+        let bundle = PresetBundle::parse(BUNDLE).unwrap();
+        let planned = plan(dir.path(), &bundle).unwrap();
+        let diff = render_diff(&planned);
+        assert!(diff.contains("-min_priority: low\n+min_priority: medium"));
+        assert!(diff.contains("--- /dev/null\n+++ b/.cosmos/prompts/review.md"));
+
+        assert!(validate_staged(dir.path(), &planned, |_| anyhow::bail!("rejected")).is_err());
+        validate_staged(dir.path(), &planned, |staged| {
+            assert_eq!(
+                std::fs::read_to_string(staged.join(".cosmos/policy.yml")).unwrap(),
+                "min_priority: medium\n"
+            );
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join(".cosmos/policy.yml")).unwrap(),
+            "min_priority: low\n"
+        );
+
+        install(dir.path(), &planned).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join(".cosmos/prompts/review.md")).unwrap(),
+            "Review carefully.\n"
+        );
+        assert!(plan(dir.path(), &bundle)
+            .unwrap()
+            .iter()
+            .all(PlannedFile::is_unchanged));
+    }
+}
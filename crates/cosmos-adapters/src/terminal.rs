@@ -0,0 +1,310 @@
+//! What the terminal can do beyond text (images, desktop notifications), and the escape
+//! sequences for it.
+//!
+//! Detection reads the environment only: querying the terminal means writing a request and
+//! waiting for a reply on stdin, which races the event loop and hangs on terminals that
+//! never answer. Multiplexers (tmux, screen) are treated as text-only because they drop or
+//! misplace image sequences unless passthrough is configured. `COSMOS_GRAPHICS=kitty`,
+//! `sixel`, or `text` overrides the guess.
+
+use crate::config::NotifyMode;
+use base64::Engine;
+
+/// How images can be shown in the current terminal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GraphicsProtocol {
+    /// Kitty graphics protocol (kitty, WezTerm, Ghostty)
+    Kitty,
+    /// DEC sixel graphics (foot, mlterm, iTerm2, xterm built with sixel)
+    Sixel,
+    /// Text only; images fall back to ASCII art
+    #[default]
+    Text,
+}
+
+/// Detect the graphics protocol from the process environment
+pub fn graphics_protocol() -> GraphicsProtocol {
+    detect_graphics(|name| std::env::var(name).ok())
+}
+
+/// Detect the graphics protocol from environment lookups made through `var`
+pub fn detect_graphics(var: impl Fn(&str) -> Option<String>) -> GraphicsProtocol {
+    if let Some(forced) = var("COSMOS_GRAPHICS") {
+        match forced.trim().to_lowercase().as_str() {
+            "kitty" => return GraphicsProtocol::Kitty,
+            "sixel" => return GraphicsProtocol::Sixel,
+            "text" | "ascii" | "none" | "off" | "0" => return GraphicsProtocol::Text,
+            _ => {}
+        }
+    }
+
+    let term = var("TERM").unwrap_or_default();
+    let term_program = var("TERM_PROGRAM").unwrap_or_default();
+    if var("TMUX").is_some()
+        || term.starts_with("screen")
+        || term.starts_with("tmux")
+        || term == "dumb"
+    {
+        return GraphicsProtocol::Text;
+    }
+
+    if var("KITTY_WINDOW_ID").is_some()
+        || term == "xterm-kitty"
+        || term == "xterm-ghostty"
+        || matches!(term_program.as_str(), "WezTerm" | "ghostty")
+    {
+        return GraphicsProtocol::Kitty;
+    }
+    if term.contains("sixel")
+        || term.starts_with("foot")
+        || term == "mlterm"
+        || matches!(term_program.as_str(), "iTerm.app" | "mlterm")
+    {
+        return GraphicsProtocol::Sixel;
+    }
+    GraphicsProtocol::Text
+}
+
+/// One-colour image: `pixels` is row-major, true where the foreground is drawn
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bitmap {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<bool>,
+}
+
+impl Bitmap {
+    fn lit(&self, x: usize, y: usize) -> bool {
+        x < self.width && y < self.height && self.pixels[y * self.width + x]
+    }
+}
+
+/// Id for images Cosmos places, so they can be deleted without touching anyone else's
+const KITTY_IMAGE_ID: u32 = 7_270_301;
+
+/// Kitty payloads are sent in chunks of at most this many base64 bytes
+const KITTY_CHUNK: usize = 4096;
+
+/// Kitty sequence that draws `bitmap` in `color` at the cursor, scaled to `cols` x `rows` cells
+///
+/// Unlit pixels are transparent, so the cell background shows through.
+pub fn kitty_image(bitmap: &Bitmap, color: (u8, u8, u8), cols: u16, rows: u16) -> String {
+    let mut rgba = Vec::with_capacity(bitmap.pixels.len() * 4);
+    for &lit in &bitmap.pixels {
+        let alpha = if lit { 255 } else { 0 };
+        rgba.extend_from_slice(&[color.0, color.1, color.2, alpha]);
+    }
+    let payload = base64::engine::general_purpose::STANDARD.encode(rgba);
+    let chunks: Vec<&[u8]> = payload.as_bytes().chunks(KITTY_CHUNK).collect();
+
+    let mut out = String::with_capacity(payload.len() + chunks.len() * 16 + 64);
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = u8::from(i + 1 < chunks.len());
+        let chunk = std::str::from_utf8(chunk).unwrap_or_default();
+        if i == 0 {
+            out.push_str(&format!(
+                "\x1b_Ga=T,f=32,i={},s={},v={},c={},r={},C=1,q=2,m={};{}\x1b\\",
+                KITTY_IMAGE_ID, bitmap.width, bitmap.height, cols, rows, more, chunk
+            ));
+        } else {
+            out.push_str(&format!("\x1b_Gm={};{}\x1b\\", more, chunk));
+        }
+    }
+    out
+}
+
+/// Kitty sequence that removes the image placed by [`kitty_image`]
+pub fn kitty_delete() -> String {
+    format!("\x1b_Ga=d,d=I,i={},q=2\x1b\\", KITTY_IMAGE_ID)
+}
+
+/// Sixel sequence that draws `bitmap` in `color` at the cursor, one image pixel per dot
+///
+/// Unlit pixels are left untouched (transparent background).
+pub fn sixel_image(bitmap: &Bitmap, color: (u8, u8, u8)) -> String {
+    let percent = |c: u8| u32::from(c) * 100 / 255;
+    let mut out = format!(
+        "\x1bP0;1;0q\"1;1;{};{}#1;2;{};{};{}",
+        bitmap.width,
+        bitmap.height,
+        percent(color.0),
+        percent(color.1),
+        percent(color.2)
+    );
+    for band in (0..bitmap.height).step_by(6) {
+        out.push_str("#1");
+        let mut run: Option<(char, usize)> = None;
+        for x in 0..bitmap.width {
+            let bits = (0..6)
+                .filter(|dy| bitmap.lit(x, band + dy))
+                .fold(0u8, |bits, dy| bits | (1 << dy));
+            let sixel = char::from(63 + bits);
+            run = match run {
+                Some((c, n)) if c == sixel => Some((c, n + 1)),
+                Some((c, n)) => {
+                    push_sixel_run(&mut out, c, n);
+                    Some((sixel, 1))
+                }
+                None => Some((sixel, 1)),
+            };
+        }
+        if let Some((c, n)) = run {
+            push_sixel_run(&mut out, c, n);
+        }
+        out.push('-');
+    }
+    out.push_str("\x1b\\");
+    out
+}
+
+fn push_sixel_run(out: &mut String, sixel: char, count: usize) {
+    if count > 3 {
+        out.push_str(&format!("!{}{}", count, sixel));
+    } else {
+        out.extend(std::iter::repeat_n(sixel, count));
+    }
+}
+
+/// Bell and/or desktop notification for `title` and `body`, per `mode`
+///
+/// Desktop notifications use OSC 9 where that is the supported form (iTerm2, Windows
+/// Terminal, ConEmu) and OSC 777 elsewhere (foot, WezTerm, Ghostty, rxvt, VTE); sending
+/// both would show two notifications in terminals that understand both. Inside tmux the
+/// notification is passed through to the outer terminal.
+pub fn notification_sequence(mode: NotifyMode, title: &str, body: &str) -> String {
+    build_notification(mode, title, body, |name| std::env::var(name).ok())
+}
+
+fn build_notification(
+    mode: NotifyMode,
+    title: &str,
+    body: &str,
+    var: impl Fn(&str) -> Option<String>,
+) -> String {
+    let mut out = String::new();
+    if mode.bell() {
+        out.push('\x07');
+    }
+    if mode.desktop() {
+        // Control characters would end the sequence early; `;` separates OSC 777 fields
+        let clean = |text: &str| -> String {
+            text.chars()
+                .map(|c| if c.is_control() || c == ';' { ' ' } else { c })
+                .collect()
+        };
+        let (title, body) = (clean(title), clean(body));
+        let osc9 = var("TERM_PROGRAM").as_deref() == Some("iTerm.app")
+            || var("WT_SESSION").is_some()
+            || var("ConEmuPID").is_some();
+        let sequence = if osc9 {
+            format!("\x1b]9;{}: {}\x1b\\", title, body)
+        } else {
+            format!("\x1b]777;notify;{};{}\x1b\\", title, body)
+        };
+        if var("TMUX").is_some() {
+            out.push_str(&tmux_passthrough(&sequence));
+        } else {
+            out.push_str(&sequence);
+        }
+    }
+    out
+}
+
+/// Wrap an escape sequence so tmux forwards it to the outer terminal.
+pub fn tmux_passthrough(sequence: &str) -> String {
+    // tmux forwards DCS passthrough with every ESC doubled
+    format!("\x1bPtmux;{}\x1b\\", sequence.replace('\x1b', "\x1b\x1b"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn detect(vars: &[(&str, &str)]) -> GraphicsProtocol {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        detect_graphics(|name| vars.get(name).cloned())
+    }
+
+    #[test]
+    fn detection_prefers_override_then_multiplexer_then_terminal() {
+        assert_eq!(detect(&[("TERM", "xterm-kitty")]), GraphicsProtocol::Kitty);
+        assert_eq!(
+            detect(&[("TERM", "xterm-256color"), ("TERM_PROGRAM", "WezTerm")]),
+            GraphicsProtocol::Kitty
+        );
+        assert_eq!(detect(&[("TERM", "foot")]), GraphicsProtocol::Sixel);
+        assert_eq!(
+            detect(&[
+                ("TERM", "xterm-kitty"),
+                ("TMUX", "/tmp/tmux-1000/default,1,0")
+            ]),
+            GraphicsProtocol::Text
+        );
+        assert_eq!(
+            detect(&[("TERM", "xterm-kitty"), ("COSMOS_GRAPHICS", "text")]),
+            GraphicsProtocol::Text
+        );
+        assert_eq!(
+            detect(&[("TERM", "xterm-256color"), ("COSMOS_GRAPHICS", "Sixel")]),
+            GraphicsProtocol::Sixel
+        );
+        assert_eq!(
+            detect(&[("TERM", "xterm-256color")]),
+            GraphicsProtocol::Text
+        );
+    }
+
+    #[test]
+    fn image_sequences_encode_the_bitmap() {
+        // 2x7: left column lit, right column dark
+        let bitmap = Bitmap {
+            width: 2,
+            height: 7,
+            pixels: (0..14).map(|i| i % 2 == 0).collect(),
+        };
+
+        let sixel = sixel_image(&bitmap, (255, 255, 255));
+        assert!(sixel.starts_with("\x1bP0;1;0q\"1;1;2;7#1;2;100;100;100"));
+        // First band: six lit pixels (0b111111 + 63 = '~') then an empty column ('?')
+        assert!(sixel.contains("#1~?-#1@?-"));
+        assert!(sixel.ends_with("\x1b\\"));
+
+        let kitty = kitty_image(&bitmap, (255, 255, 255), 4, 2);
+        assert!(kitty.starts_with("\x1b_Ga=T,f=32,i=7270301,s=2,v=7,c=4,r=2,C=1,q=2,m=0;"));
+        let payload = kitty
+            .split_once(';')
+            .and_then(|(_, rest)| rest.strip_suffix("\x1b\\"))
+            .unwrap();
+        let rgba = base64::engine::general_purpose::STANDARD
+            .decode(payload)
+            .unwrap();
+        assert_eq!(rgba.len(), 14 * 4);
+        assert_eq!(&rgba[..8], &[255, 255, 255, 255, 255, 255, 255, 0]);
+    }
+
+    #[test]
+    fn notifications_pick_the_terminal_flavour_and_respect_the_mode() {
+        let none = |_: &str| None;
+        assert_eq!(build_notification(NotifyMode::Off, "a", "b", none), "");
+        assert_eq!(build_notification(NotifyMode::Bell, "a", "b", none), "\x07");
+        assert_eq!(
+            build_notification(NotifyMode::Both, "Cosmos", "Fix; applied\n", none),
+            "\x07\x1b]777;notify;Cosmos;Fix  applied \x1b\\"
+        );
+
+        let iterm = |name: &str| (name == "TERM_PROGRAM").then(|| "iTerm.app".to_string());
+        assert_eq!(
+            build_notification(NotifyMode::Desktop, "Cosmos", "done", iterm),
+            "\x1b]9;Cosmos: done\x1b\\"
+        );
+        let tmux = |name: &str| (name == "TMUX").then(|| "/tmp/tmux".to_string());
+        assert_eq!(
+            build_notification(NotifyMode::Desktop, "Cosmos", "done", tmux),
+            "\x1bPtmux;\x1b\x1b]777;notify;Cosmos;done\x1b\x1b\\\x1b\\"
+        );
+    }
+}
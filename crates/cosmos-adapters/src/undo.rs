@@ -0,0 +1,310 @@
+//! Undo stack for applied fixes
+//!
+//! Every applied fix records a snapshot of each file it touches *before* the
+//! write happens. Undoing restores those snapshots (multi-file aware) instead of
+//! resetting to HEAD, so stacked fixes that touch the same file can be peeled
+//! back one at a time without losing earlier work.
+
+use crate::cache::Cache;
+use crate::git_ops;
+use crate::util::resolve_repo_path_allow_new;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+/// Max number of applied fixes kept on the stack.
+const UNDO_STACK_LIMIT: usize = 20;
+
+/// Pre-apply content of a single file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileSnapshot {
+    /// Repo-relative path
+    pub path: PathBuf,
+    /// Content before the fix; `None` when the fix created the file
+    pub original: Option<String>,
+}
+
+/// One applied fix that can be rolled back.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UndoEntry {
+    /// Identifier for this apply run
+    pub run_id: String,
+    pub suggestion_id: Uuid,
+    pub description: String,
+    pub created_at: DateTime<Utc>,
+    pub files: Vec<FileSnapshot>,
+}
+
+impl UndoEntry {
+    pub fn new(suggestion_id: Uuid, description: String, files: Vec<FileSnapshot>) -> Self {
+        Self {
+            run_id: Uuid::new_v4().to_string(),
+            suggestion_id,
+            description,
+            created_at: Utc::now(),
+            files,
+        }
+    }
+
+    fn touches_any(&self, paths: &HashSet<&PathBuf>) -> bool {
+        self.files.iter().any(|f| paths.contains(&f.path))
+    }
+}
+
+/// Persistent stack of applied fixes, newest last.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UndoStack {
+    pub entries: Vec<UndoEntry>,
+}
+
+impl UndoStack {
+    /// Load the stack for a repository (empty when nothing was recorded).
+    pub fn load(repo_root: &Path) -> Self {
+        Cache::new(repo_root).load_undo_stack().unwrap_or_default()
+    }
+
+    pub fn save(&self, repo_root: &Path) -> Result<()> {
+        Cache::new(repo_root).save_undo_stack(self)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Push a new entry, dropping the oldest ones beyond the stack limit.
+    pub fn push(&mut self, entry: UndoEntry) {
+        self.entries.push(entry);
+        if self.entries.len() > UNDO_STACK_LIMIT {
+            let overflow = self.entries.len() - UNDO_STACK_LIMIT;
+            self.entries.drain(..overflow);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Undo the most recent applied fix.
+    pub fn undo_last(&mut self, repo_root: &Path) -> Result<UndoEntry> {
+        let run_id = self
+            .entries
+            .last()
+            .map(|e| e.run_id.clone())
+            .ok_or_else(|| anyhow::anyhow!("Nothing to undo"))?;
+        self.undo(repo_root, &run_id)
+    }
+
+    /// Undo a specific applied fix.
+    ///
+    /// Refuses when a newer fix touched any of the same files, since restoring the
+    /// older snapshot would silently discard the newer change.
+    pub fn undo(&mut self, repo_root: &Path, run_id: &str) -> Result<UndoEntry> {
+        let position = self
+            .entries
+            .iter()
+            .position(|e| e.run_id == run_id)
+            .ok_or_else(|| anyhow::anyhow!("No applied fix with run id {}", run_id))?;
+
+        let paths: HashSet<&PathBuf> = self.entries[position]
+            .files
+            .iter()
+            .map(|f| &f.path)
+            .collect();
+        if let Some(newer) = self.entries[position + 1..]
+            .iter()
+            .find(|e| e.touches_any(&paths))
+        {
+            return Err(anyhow::anyhow!(
+                "A newer fix ({}) changed the same files. Undo that one first.",
+                newer.description
+            ));
+        }
+
+        restore_snapshots(repo_root, &self.entries[position].files)?;
+        Ok(self.entries.remove(position))
+    }
+}
+
+/// Capture the current content of files about to be written.
+pub fn snapshot_files(repo_root: &Path, paths: &[PathBuf]) -> Result<Vec<FileSnapshot>> {
+    let mut snapshots = Vec::with_capacity(paths.len());
+    for path in paths {
+        let resolved = resolve_repo_path_allow_new(repo_root, path)
+            .map_err(|e| anyhow::anyhow!("Invalid path '{}': {}", path.display(), e))?;
+        let original = match std::fs::read_to_string(&resolved.absolute) {
+            Ok(content) => Some(content),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => {
+                return Err(e).with_context(|| format!("Failed to snapshot {}", path.display()))
+            }
+        };
+        snapshots.push(FileSnapshot {
+            path: resolved.relative,
+            original,
+        });
+    }
+    Ok(snapshots)
+}
+
+/// Restore every snapshot, collecting failures so one bad file doesn't hide others.
+fn restore_snapshots(repo_root: &Path, snapshots: &[FileSnapshot]) -> Result<()> {
+    let mut errors = Vec::new();
+    for snapshot in snapshots {
+        if let Err(e) =
+            git_ops::restore_file_content(repo_root, &snapshot.path, snapshot.original.as_deref())
+        {
+            errors.push(format!("{}: {}", snapshot.path.display(), e));
+        }
+    }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(anyhow::anyhow!("Failed to restore {}", errors.join("; ")))
+    }
+}
+
+/// Record an applied fix on the persistent stack and return its run id.
+pub fn record_applied_fix(
+    repo_root: &Path,
+    suggestion_id: Uuid,
+    description: String,
+    files: Vec<FileSnapshot>,
+) -> Result<String> {
+    let entry = UndoEntry::new(suggestion_id, description, files);
+    let run_id = entry.run_id.clone();
+    let mut stack = UndoStack::load(repo_root);
+    stack.push(entry);
+    stack.save(repo_root)?;
+    Ok(run_id)
+}
+
+/// Undo the most recent applied fix and persist the updated stack.
+pub fn undo_last(repo_root: &Path) -> Result<UndoEntry> {
+    let mut stack = UndoStack::load(repo_root);
+    let entry = stack.undo_last(repo_root)?;
+    stack.save(repo_root)?;
+    Ok(entry)
+}
+
+/// Undo a specific applied fix by run id and persist the updated stack.
+pub fn undo(repo_root: &Path, run_id: &str) -> Result<UndoEntry> {
+    let mut stack = UndoStack::load(repo_root);
+    let entry = stack.undo(repo_root, run_id)?;
+    stack.save(repo_root)?;
+    Ok(entry)
+}
+
+/// Drop all recorded snapshots (after the changes are committed).
+pub fn clear(repo_root: &Path) -> Result<()> {
+    let mut stack = UndoStack::load(repo_root);
+    if stack.is_empty() {
+        return Ok(());
+    }
+    stack.clear();
+    stack.save(repo_root)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git2::{Repository, Signature};
+
+    fn create_temp_repo() -> (tempfile::TempDir, PathBuf) {
+        let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let repo_path = temp_dir.path().to_path_buf();
+        let repo = Repository::init(&repo_path).expect("Failed to init repo");
+        std::fs::write(repo_path.join("a.rs"), "fn a() {}\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("a.rs")).unwrap();
+        index.write().unwrap();
+        let tree_id = index.write_tree().unwrap();
+        let tree = repo.find_tree(tree_id).unwrap();
+        let sig = Signature::now("Test User", "test@example.com").unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "Initial commit", &tree, &[])
+            .unwrap();
+        (temp_dir, repo_path)
+    }
+
+    fn apply(repo: &Path, path: &str, content: &str) -> String {
+        let snapshots = snapshot_files(repo, &[PathBuf::from(path)]).unwrap();
+        let target = repo.join(path);
+        std::fs::create_dir_all(target.parent().unwrap()).unwrap();
+        std::fs::write(&target, content).unwrap();
+        git_ops::stage_file(repo, path).unwrap();
+        record_applied_fix(repo, Uuid::new_v4(), format!("edit {}", path), snapshots).unwrap()
+    }
+
+    #[test]
+    fn undo_last_restores_stacked_changes_one_at_a_time() {
+        let (_dir, repo) = create_temp_repo();
+        apply(&repo, "a.rs", "fn a() { 1 }\n");
+        apply(&repo, "a.rs", "fn a() { 2 }\n");
+
+        undo_last(&repo).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(repo.join("a.rs")).unwrap(),
+            "fn a() { 1 }\n"
+        );
+        undo_last(&repo).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(repo.join("a.rs")).unwrap(),
+            "fn a() {}\n"
+        );
+        assert!(undo_last(&repo).is_err());
+    }
+
+    #[test]
+    fn undo_removes_files_created_by_the_fix() {
+        let (_dir, repo) = create_temp_repo();
+        let run_id = apply(&repo, "src/new.rs", "fn new() {}\n");
+        assert!(repo.join("src/new.rs").exists());
+
+        undo(&repo, &run_id).unwrap();
+        assert!(!repo.join("src/new.rs").exists());
+        let status = git_ops::current_status(&repo).unwrap();
+        assert!(status.staged.is_empty());
+    }
+
+    #[test]
+    fn undo_by_run_id_refuses_when_newer_fix_touches_same_file() {
+        let (_dir, repo) = create_temp_repo();
+        let first = apply(&repo, "a.rs", "fn a() { 1 }\n");
+        apply(&repo, "a.rs", "fn a() { 2 }\n");
+
+        let err = undo(&repo, &first).unwrap_err();
+        assert!(err.to_string().contains("Undo that one first"));
+        assert_eq!(UndoStack::load(&repo).len(), 2);
+    }
+
+    #[test]
+    fn undo_by_run_id_allows_independent_older_fix() {
+        let (_dir, repo) = create_temp_repo();
+        let first = apply(&repo, "a.rs", "fn a() { 1 }\n");
+        apply(&repo, "b.rs", "fn b() {}\n");
+
+        let entry = undo(&repo, &first).unwrap();
+        assert_eq!(entry.run_id, first);
+        assert_eq!(
+            std::fs::read_to_string(repo.join("a.rs")).unwrap(),
+            "fn a() {}\n"
+        );
+        assert!(repo.join("b.rs").exists());
+        assert_eq!(UndoStack::load(&repo).len(), 1);
+    }
+
+    #[test]
+    fn push_caps_stack_size() {
+        let mut stack = UndoStack::default();
+        for _ in 0..(UNDO_STACK_LIMIT + 5) {
+            stack.push(UndoEntry::new(Uuid::new_v4(), String::new(), Vec::new()));
+        }
+        assert_eq!(stack.len(), UNDO_STACK_LIMIT);
+    }
+}
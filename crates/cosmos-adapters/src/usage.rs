@@ -0,0 +1,251 @@
+//! Token and cost usage ledger.
+//!
+//! Every AI response's usage is appended to `.cosmos/v2/usage.jsonl`, tagged with the
+//! operation that made it and the model that served it. `cosmos usage` rolls the ledger up
+//! by day, model, and operation so costs can be charged back to the teams that ran them.
+
+use crate::cache::{Cache, UsageLedgerRecord};
+use crate::util::debug_stderr_enabled;
+use chrono::Utc;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+pub use chrono::NaiveDate;
+
+/// Suggestion generation and refinement.
+pub const OPERATION_SUGGESTIONS: &str = "suggestions";
+/// Ask Cosmos answers, file summaries, and commit messages.
+pub const OPERATION_SUMMARIES: &str = "summaries";
+/// Fix previews and applied fixes.
+pub const OPERATION_FIXES: &str = "fixes";
+/// Adversarial review of applied changes and pull requests.
+pub const OPERATION_REVIEWS: &str = "reviews";
+/// AI-assisted file grouping.
+pub const OPERATION_GROUPING: &str = "grouping";
+
+/// Append one response's usage to the repo's ledger. Failures are ignored; usage
+/// accounting must never break the operation it measures.
+pub fn record(
+    repo_path: &Path,
+    operation: &str,
+    model: &str,
+    prompt_tokens: u32,
+    completion_tokens: u32,
+    total_tokens: u32,
+    cost_usd: Option<f64>,
+) {
+    let row = UsageLedgerRecord {
+        timestamp: Utc::now(),
+        operation: operation.to_string(),
+        model: model.to_string(),
+        prompt_tokens,
+        completion_tokens,
+        total_tokens,
+        cost_usd,
+    };
+    if let Err(e) = Cache::new(repo_path).append_usage_record(&row) {
+        if debug_stderr_enabled() {
+            eprintln!("cosmos: failed to record usage: {}", e);
+        }
+    }
+}
+
+/// Usage totals for one day, model, and operation.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct UsageReportRow {
+    pub day: NaiveDate,
+    pub model: String,
+    pub operation: String,
+    pub calls: usize,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub total_tokens: u64,
+    pub cost_usd: f64,
+    /// Calls whose provider did not report a cost (counted as zero in `cost_usd`).
+    pub calls_without_cost: usize,
+}
+
+/// Roll ledger rows up by day (UTC), model, and operation, optionally from `since` on.
+pub fn build_report(
+    records: &[UsageLedgerRecord],
+    since: Option<NaiveDate>,
+) -> Vec<UsageReportRow> {
+    let mut rows: BTreeMap<(NaiveDate, String, String), UsageReportRow> = BTreeMap::new();
+    for record in records {
+        let day = record.timestamp.date_naive();
+        if since.is_some_and(|since| day < since) {
+            continue;
+        }
+        let row = rows
+            .entry((day, record.model.clone(), record.operation.clone()))
+            .or_insert_with(|| UsageReportRow {
+                day,
+                model: record.model.clone(),
+                operation: record.operation.clone(),
+                ..UsageReportRow::default()
+            });
+        row.calls += 1;
+        row.prompt_tokens += record.prompt_tokens as u64;
+        row.completion_tokens += record.completion_tokens as u64;
+        row.total_tokens += record.total_tokens as u64;
+        match record.cost_usd {
+            Some(cost) => row.cost_usd += cost,
+            None => row.calls_without_cost += 1,
+        }
+    }
+    rows.into_values().collect()
+}
+
+/// Sum of every row, labelled `total`.
+pub fn report_total(rows: &[UsageReportRow]) -> UsageReportRow {
+    let mut total = UsageReportRow {
+        day: rows.first().map(|row| row.day).unwrap_or_default(),
+        model: "total".to_string(),
+        operation: "total".to_string(),
+        ..UsageReportRow::default()
+    };
+    for row in rows {
+        total.calls += row.calls;
+        total.prompt_tokens += row.prompt_tokens;
+        total.completion_tokens += row.completion_tokens;
+        total.total_tokens += row.total_tokens;
+        total.cost_usd += row.cost_usd;
+        total.calls_without_cost += row.calls_without_cost;
+    }
+    total
+}
+
+/// Parse a `--since` day given as `YYYY-MM-DD`.
+pub fn parse_day(value: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d")
+        .map_err(|_| format!("expected a date like 2026-03-01, got '{}'", value))
+}
+
+/// Pretty-printed JSON array of report rows.
+pub fn report_json(rows: &[UsageReportRow]) -> String {
+    serde_json::to_string_pretty(rows).unwrap_or_else(|_| "[]".to_string())
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// CSV with a header row, one line per report row.
+pub fn report_csv(rows: &[UsageReportRow]) -> String {
+    let mut out = String::from(
+        "day,model,operation,calls,prompt_tokens,completion_tokens,total_tokens,cost_usd,calls_without_cost\n",
+    );
+    for row in rows {
+        out.push_str(&format!(
+            "{},{},{},{},{},{},{},{:.6},{}\n",
+            row.day,
+            csv_field(&row.model),
+            csv_field(&row.operation),
+            row.calls,
+            row.prompt_tokens,
+            row.completion_tokens,
+            row.total_tokens,
+            row.cost_usd,
+            row.calls_without_cost
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn row(
+        day: u32,
+        operation: &str,
+        model: &str,
+        tokens: u32,
+        cost: Option<f64>,
+    ) -> UsageLedgerRecord {
+        UsageLedgerRecord {
+            timestamp: Utc.with_ymd_and_hms(2026, 3, day, 12, 0, 0).unwrap(),
+            operation: operation.to_string(),
+            model: model.to_string(),
+            prompt_tokens: tokens - 10,
+            completion_tokens: 10,
+            total_tokens: tokens,
+            cost_usd: cost,
+        }
+    }
+
+    #[test]
+    fn report_groups_by_day_model_and_operation() {
+        let records = vec![
+            row(1, OPERATION_FIXES, "glm", 100, Some(0.01)),
+            row(1, OPERATION_FIXES, "glm", 300, None),
+            row(1, OPERATION_SUGGESTIONS, "glm", 50, Some(0.002)),
+            row(2, OPERATION_FIXES, "glm", 40, Some(0.001)),
+        ];
+
+        let rows = build_report(&records, None);
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0].operation, OPERATION_FIXES);
+        assert_eq!(rows[0].calls, 2);
+        assert_eq!(rows[0].total_tokens, 400);
+        assert_eq!(rows[0].calls_without_cost, 1);
+        assert_eq!(rows[1].operation, OPERATION_SUGGESTIONS);
+        assert_eq!(rows[2].day, NaiveDate::from_ymd_opt(2026, 3, 2).unwrap());
+
+        let since = build_report(&records, NaiveDate::from_ymd_opt(2026, 3, 2));
+        assert_eq!(since.len(), 1);
+
+        let total = report_total(&rows);
+        assert_eq!(total.calls, 4);
+        assert_eq!(total.total_tokens, 490);
+        assert!((total.cost_usd - 0.013).abs() < 1e-9);
+    }
+
+    #[test]
+    fn csv_has_a_header_and_quotes_awkward_fields() {
+        let rows = build_report(
+            &[row(1, OPERATION_REVIEWS, "vendor,model", 20, Some(0.5))],
+            None,
+        );
+        let csv = report_csv(&rows);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert!(lines[0].starts_with("day,model,operation,calls"));
+        assert_eq!(
+            lines[1],
+            "2026-03-01,\"vendor,model\",reviews,1,10,10,20,0.500000,0"
+        );
+    }
+
+    #[test]
+    fn since_day_must_be_iso_formatted() {
+        assert_eq!(
+            parse_day("2026-03-01"),
+            Ok(NaiveDate::from_ymd_opt(2026, 3, 1).unwrap())
+        );
+        assert!(parse_day("03/01/2026").is_err());
+    }
+
+    #[test]
+    fn recorded_usage_round_trips_through_the_ledger() {
+        let dir = tempfile::tempdir().unwrap();
+        record(
+            dir.path(),
+            OPERATION_SUMMARIES,
+            "glm",
+            90,
+            10,
+            100,
+            Some(0.003),
+        );
+        let records = Cache::new(dir.path()).load_usage_records().unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].operation, OPERATION_SUMMARIES);
+        assert_eq!(records[0].total_tokens, 100);
+    }
+}
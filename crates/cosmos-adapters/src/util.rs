@@ -1,26 +1,10 @@
-use std::io::{BufReader, Read};
+use std::io::{BufRead, BufReader, Read};
 use std::path::{Component, Path, PathBuf};
 use std::process::{Command, ExitStatus, Stdio};
 use std::thread;
 use std::time::{Duration, Instant};
 
-pub fn truncate(s: &str, max: usize) -> String {
-    if max == 0 {
-        return String::new();
-    }
-
-    let char_count = s.chars().count();
-    if char_count <= max {
-        return s.to_string();
-    }
-
-    if max <= 3 {
-        return s.chars().take(max).collect();
-    }
-
-    let truncated: String = s.chars().take(max - 3).collect();
-    format!("{}...", truncated)
-}
+pub use cosmos_core::util::{display_width, pad_to_width, truncate, truncate_to_width};
 
 #[derive(Debug)]
 pub struct CommandRunResult {
@@ -30,9 +14,22 @@ pub struct CommandRunResult {
     pub timed_out: bool,
 }
 
+/// Receives each line of a running command's output as it arrives (without the newline)
+pub type OutputLineSink = std::sync::Arc<dyn Fn(&str) + Send + Sync>;
+
 pub fn run_command_with_timeout(
     command: &mut Command,
     timeout: Duration,
+) -> Result<CommandRunResult, String> {
+    run_command_with_timeout_streaming(command, timeout, None)
+}
+
+/// Like `run_command_with_timeout`, also handing stdout and stderr lines to `on_line` as the
+/// command writes them so long runs can show progress.
+pub fn run_command_with_timeout_streaming(
+    command: &mut Command,
+    timeout: Duration,
+    on_line: Option<OutputLineSink>,
 ) -> Result<CommandRunResult, String> {
     let mut child = command
         .stdout(Stdio::piped())
@@ -49,18 +46,9 @@ pub fn run_command_with_timeout(
         .take()
         .ok_or_else(|| "Failed to capture stderr".to_string())?;
 
-    let stdout_handle = thread::spawn(move || {
-        let mut buf = Vec::new();
-        let mut reader = BufReader::new(stdout);
-        let _ = reader.read_to_end(&mut buf);
-        buf
-    });
-    let stderr_handle = thread::spawn(move || {
-        let mut buf = Vec::new();
-        let mut reader = BufReader::new(stderr);
-        let _ = reader.read_to_end(&mut buf);
-        buf
-    });
+    let stdout_sink = on_line.clone();
+    let stdout_handle = thread::spawn(move || read_output(stdout, stdout_sink));
+    let stderr_handle = thread::spawn(move || read_output(stderr, on_line));
 
     let start = Instant::now();
     let mut timed_out = false;
@@ -93,6 +81,27 @@ pub fn run_command_with_timeout(
     })
 }
 
+/// Read a pipe to the end, forwarding complete lines to `on_line` when there is one.
+fn read_output(pipe: impl Read, on_line: Option<OutputLineSink>) -> Vec<u8> {
+    let mut buf = Vec::new();
+    let mut reader = BufReader::new(pipe);
+    let Some(on_line) = on_line else {
+        let _ = reader.read_to_end(&mut buf);
+        return buf;
+    };
+    loop {
+        let start = buf.len();
+        match reader.read_until(b'\n', &mut buf) {
+            Ok(0) | Err(_) => break,
+            Ok(_) => {
+                let line = String::from_utf8_lossy(&buf[start..]);
+                on_line(line.trim_end_matches(['\n', '\r']));
+            }
+        }
+    }
+    buf
+}
+
 pub struct RepoPath {
     pub absolute: PathBuf,
     pub relative: PathBuf,
@@ -208,22 +217,35 @@ pub fn debug_stderr_enabled() -> bool {
 
 #[cfg(test)]
 mod tests {
-    use super::{hash_str, resolve_repo_path_allow_new, truncate};
+    use super::{
+        hash_str, resolve_repo_path_allow_new, run_command_with_timeout_streaming, OutputLineSink,
+    };
     use std::fs;
     use std::path::PathBuf;
-    use std::time::{SystemTime, UNIX_EPOCH};
-
-    #[test]
-    fn test_truncate_unicode_safe() {
-        let input = "ééééé";
-        assert_eq!(truncate(input, 4), "é...");
-    }
+    use std::process::Command;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+    #[cfg(unix)]
     #[test]
-    fn test_truncate_small_max() {
-        let input = "こんにちは";
-        assert_eq!(truncate(input, 3), "こんに");
-        assert_eq!(truncate(input, 0), "");
+    fn test_streaming_run_forwards_lines_and_keeps_output() {
+        let lines = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let seen = std::sync::Arc::clone(&lines);
+        let sink: OutputLineSink = std::sync::Arc::new(move |line: &str| {
+            seen.lock().unwrap().push(line.to_string());
+        });
+        let mut command = Command::new("sh");
+        command
+            .arg("-c")
+            .arg("echo one; echo two >&2; printf three");
+        let result =
+            run_command_with_timeout_streaming(&mut command, Duration::from_secs(10), Some(sink))
+                .unwrap();
+
+        let mut lines = lines.lock().unwrap().clone();
+        lines.sort();
+        assert_eq!(lines, vec!["one", "three", "two"]);
+        assert_eq!(result.stdout, "one\nthree");
+        assert_eq!(result.stderr, "two\n");
     }
 
     #[test]
@@ -0,0 +1,233 @@
+//! `cosmos compare`: how code health moved between two recorded suggestion runs.
+//!
+//! Every suggestion run is logged with its findings and the commit it ran against. Comparing
+//! two runs splits their findings into new, resolved, and persisting, and credits each
+//! resolved finding to the commits in between that touched its files.
+
+use anyhow::Result;
+use cosmos_adapters::cache::{Cache, SuggestionRunAuditRecord};
+use cosmos_adapters::git_ops::{self, CommitSummary};
+use cosmos_core::suggest::{compare_runs, Suggestion};
+use std::path::Path;
+
+/// Commits listed per resolved finding before the rest are summarized as "+N more".
+const MAX_ATTRIBUTED_COMMITS: usize = 3;
+
+pub fn run(path: &Path, earlier: Option<&str>, later: Option<&str>) -> Result<()> {
+    let runs = Cache::new(path).load_recent_suggestion_run_audit(usize::MAX)?;
+    if runs.is_empty() {
+        anyhow::bail!("No recorded suggestion runs yet. Run Cosmos or `cosmos suggest` first.");
+    }
+    let Some(earlier) = earlier else {
+        list_runs(&runs);
+        return Ok(());
+    };
+    let earlier = resolve_run(&runs, earlier)?;
+    let later = resolve_run(&runs, later.unwrap_or("latest"))?;
+
+    let comparison = compare_runs(&earlier.suggestions, &later.suggestions);
+    println!("{} -> {}", describe(earlier), describe(later));
+    println!(
+        "{} new, {} resolved, {} persisting",
+        comparison.new.len(),
+        comparison.resolved.len(),
+        comparison.persisting.len()
+    );
+
+    let commits = match (&earlier.git_head, &later.git_head) {
+        (Some(from), Some(to)) if from != to => match git_ops::commits_between(path, from, to) {
+            Ok(commits) => Some(commits),
+            Err(e) => {
+                eprintln!("Can't attribute resolved findings to commits: {}", e);
+                None
+            }
+        },
+        (Some(_), Some(_)) => Some(Vec::new()),
+        _ => None,
+    };
+
+    if !comparison.resolved.is_empty() {
+        println!("\nResolved:");
+        for suggestion in &comparison.resolved {
+            println!("  - {}", line(suggestion));
+            let Some(commits) = &commits else {
+                continue;
+            };
+            let touching = attribute(suggestion, commits);
+            if touching.is_empty() {
+                println!("      no commit in between touched its files");
+                continue;
+            }
+            for commit in touching.iter().take(MAX_ATTRIBUTED_COMMITS) {
+                println!(
+                    "      {} {} ({})",
+                    commit.short_id(),
+                    commit.summary,
+                    commit.author
+                );
+            }
+            if touching.len() > MAX_ATTRIBUTED_COMMITS {
+                println!("      +{} more", touching.len() - MAX_ATTRIBUTED_COMMITS);
+            }
+        }
+        if commits.is_none() {
+            println!("  (one of the runs has no recorded commit, so fixes can't be attributed)");
+        }
+    }
+    if !comparison.new.is_empty() {
+        println!("\nNew:");
+        for suggestion in &comparison.new {
+            println!("  + {}", line(suggestion));
+        }
+    }
+    if !comparison.persisting.is_empty() {
+        println!("\nPersisting:");
+        for (_, suggestion) in &comparison.persisting {
+            println!("    {}", line(suggestion));
+        }
+    }
+    Ok(())
+}
+
+fn list_runs(runs: &[SuggestionRunAuditRecord]) {
+    println!("Recorded suggestion runs (oldest first):");
+    for run in runs {
+        println!("  {}", describe(run));
+    }
+    println!("\nCompare two with `cosmos compare <run> [<run>]` (a run id prefix, `latest`, or `latest~N`).");
+}
+
+/// `latest`, `latest~N` (N runs before the latest), or a unique run id prefix.
+fn resolve_run<'a>(
+    runs: &'a [SuggestionRunAuditRecord],
+    reference: &str,
+) -> Result<&'a SuggestionRunAuditRecord> {
+    let reference = reference.trim();
+    if let Some(back) = reference.strip_prefix("latest") {
+        let back = match back.strip_prefix('~') {
+            Some(n) => n
+                .parse::<usize>()
+                .map_err(|_| anyhow::anyhow!("Invalid run reference '{}'", reference))?,
+            None if back.is_empty() => 0,
+            None => anyhow::bail!("Invalid run reference '{}'", reference),
+        };
+        return runs
+            .len()
+            .checked_sub(back + 1)
+            .map(|idx| &runs[idx])
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Only {} run(s) are recorded; '{}' is too far back",
+                    runs.len(),
+                    reference
+                )
+            });
+    }
+    let matches: Vec<&SuggestionRunAuditRecord> = runs
+        .iter()
+        .filter(|run| !reference.is_empty() && run.run_id.starts_with(reference))
+        .collect();
+    match matches.as_slice() {
+        [run] => Ok(run),
+        [] => anyhow::bail!(
+            "No recorded run matches '{}'; run `cosmos compare` to list them",
+            reference
+        ),
+        _ => anyhow::bail!(
+            "'{}' matches {} runs; use a longer prefix",
+            reference,
+            matches.len()
+        ),
+    }
+}
+
+fn describe(run: &SuggestionRunAuditRecord) -> String {
+    let commit = run
+        .git_head
+        .as_deref()
+        .map(|head| &head[..head.len().min(8)])
+        .unwrap_or("unknown commit");
+    format!(
+        "{} ({}, {}, {} suggestion(s))",
+        &run.run_id[..run.run_id.len().min(8)],
+        run.timestamp.format("%Y-%m-%d %H:%M"),
+        commit,
+        run.suggestions.len()
+    )
+}
+
+fn line(suggestion: &Suggestion) -> String {
+    let location = match suggestion.line {
+        Some(line) => format!("{}:{}", suggestion.file.display(), line),
+        None => suggestion.file.display().to_string(),
+    };
+    format!(
+        "[{}] {} ({})",
+        suggestion.kind.label(),
+        suggestion.summary,
+        location
+    )
+}
+
+/// Commits (newest first) that changed any file the finding was about.
+fn attribute<'a>(suggestion: &Suggestion, commits: &'a [CommitSummary]) -> Vec<&'a CommitSummary> {
+    let files = suggestion.affected_files();
+    commits
+        .iter()
+        .filter(|commit| commit.files.iter().any(|file| files.contains(&file)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmos_core::suggest::{Priority, SuggestionKind, SuggestionSource};
+    use std::path::PathBuf;
+
+    fn record(run_id: &str) -> SuggestionRunAuditRecord {
+        serde_json::from_value(serde_json::json!({
+            "timestamp": "2026-10-01T12:00:00Z",
+            "run_id": run_id,
+            "suggestion_count": 0,
+            "validated_count": 0,
+            "rejected_count": 0,
+            "suggestions": []
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn resolves_run_references_and_attributes_fixes_to_commits() {
+        let runs = vec![record("aaaa1111"), record("aaaa2222"), record("bbbb3333")];
+        assert_eq!(resolve_run(&runs, "latest").unwrap().run_id, "bbbb3333");
+        assert_eq!(resolve_run(&runs, "latest~2").unwrap().run_id, "aaaa1111");
+        assert_eq!(resolve_run(&runs, "aaaa2").unwrap().run_id, "aaaa2222");
+        assert!(resolve_run(&runs, "aaaa").is_err());
+        assert!(resolve_run(&runs, "latest~3").is_err());
+        assert!(resolve_run(&runs, "latest2").is_err());
+
+        let suggestion = Suggestion::new(
+            SuggestionKind::BugFix,
+            Priority::High,
+            PathBuf::from("src/retry.rs"),
+            "Retry loop never backs off".to_string(),
+            SuggestionSource::LlmDeep,
+        );
+        let commit = |id: &str, file: &str| CommitSummary {
+            id: id.to_string(),
+            summary: format!("Touch {}", file),
+            author: "dev".to_string(),
+            files: vec![PathBuf::from(file)],
+        };
+        let commits = vec![
+            commit("c3", "src/retry.rs"),
+            commit("c2", "README.md"),
+            commit("c1", "src/retry.rs"),
+        ];
+        let ids: Vec<&str> = attribute(&suggestion, &commits)
+            .iter()
+            .map(|c| c.id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["c3", "c1"]);
+    }
+}
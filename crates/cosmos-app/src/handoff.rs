@@ -0,0 +1,364 @@
+//! `cosmos export`: a self-contained report of selected suggestions for a reviewer who doesn't
+//! run Cosmos.
+//!
+//! Each entry carries the rationale, a code excerpt read from the working tree (falling back to
+//! the evidence snippet Cosmos recorded), and where the finding came from. Reports are
+//! anonymous: paths are repo-relative, excerpts have secrets masked, and nothing about the
+//! machine, user, model, or cost is included.
+
+use anyhow::Result;
+use clap::ValueEnum;
+use cosmos_core::suggest::{
+    Confidence, Suggestion, SuggestionEngine, SuggestionSource, SuggestionValidationState,
+    VerificationState,
+};
+use cosmos_engine::llm::redaction::redact_secrets;
+use std::fmt::Write as _;
+use std::path::Path;
+use uuid::Uuid;
+
+/// Lines of context shown on each side of a suggestion's line.
+const EXCERPT_CONTEXT_LINES: usize = 4;
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportFormat {
+    Markdown,
+    /// Single HTML file with inline styles
+    Html,
+}
+
+/// One suggestion as it appears in the report.
+pub(crate) struct Entry<'a> {
+    pub suggestion: &'a Suggestion,
+    /// Repo-relative path
+    pub file: String,
+    pub location: String,
+    pub excerpt: Option<Excerpt>,
+    pub provenance: Vec<String>,
+}
+
+pub(crate) struct Excerpt {
+    pub language: String,
+    /// First line number of `code`, when it was read from the file
+    pub first_line: Option<usize>,
+    pub code: String,
+}
+
+/// Render the engine's suggestions with `ids`, in that order; empty `ids` means every active
+/// suggestion.
+pub fn render(engine: &SuggestionEngine, ids: &[Uuid], format: ExportFormat) -> Result<String> {
+    let selected: Vec<&Suggestion> = if ids.is_empty() {
+        engine.active_suggestions()
+    } else {
+        ids.iter()
+            .map(|id| {
+                engine
+                    .suggestions
+                    .iter()
+                    .find(|s| s.id == *id)
+                    .ok_or_else(|| anyhow::anyhow!("Suggestion {} is no longer available", id))
+            })
+            .collect::<Result<_>>()?
+    };
+    if selected.is_empty() {
+        anyhow::bail!("No suggestions to export. Run Cosmos once to generate suggestions.");
+    }
+    let root = engine.index.root.as_path();
+    let entries: Vec<Entry> = selected
+        .into_iter()
+        .map(|suggestion| entry(root, suggestion))
+        .collect();
+    Ok(match format {
+        ExportFormat::Markdown => render_markdown(&entries),
+        ExportFormat::Html => render_html(&entries),
+    })
+}
+
+pub(crate) fn entry<'a>(root: &Path, suggestion: &'a Suggestion) -> Entry<'a> {
+    let file = relative(root, &suggestion.file);
+    let location = match suggestion.line {
+        Some(line) => format!("{}:{}", file, line),
+        None => file.clone(),
+    };
+    let language = Path::new(&file)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or("")
+        .to_string();
+    let excerpt = suggestion
+        .line
+        .and_then(|line| file_excerpt(&root.join(&file), line))
+        .map(|(first_line, code)| Excerpt {
+            language: language.clone(),
+            first_line: Some(first_line),
+            code,
+        })
+        .or_else(|| {
+            suggestion
+                .evidence
+                .as_deref()
+                .filter(|evidence| !evidence.trim().is_empty())
+                .map(|evidence| Excerpt {
+                    language,
+                    first_line: None,
+                    code: evidence.trim_end().to_string(),
+                })
+        })
+        .map(|excerpt| Excerpt {
+            code: redact_secrets(&excerpt.code).0,
+            ..excerpt
+        });
+    Entry {
+        suggestion,
+        file,
+        location,
+        excerpt,
+        provenance: provenance(root, suggestion),
+    }
+}
+
+fn relative(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .display()
+        .to_string()
+}
+
+/// Lines around `line` (1-based), with the number of the first one.
+fn file_excerpt(path: &Path, line: usize) -> Option<(usize, String)> {
+    let content = std::fs::read_to_string(path).ok()?;
+    let lines: Vec<&str> = content.lines().collect();
+    if line == 0 || line > lines.len() {
+        return None;
+    }
+    let start = line.saturating_sub(EXCERPT_CONTEXT_LINES).max(1);
+    let end = (line + EXCERPT_CONTEXT_LINES).min(lines.len());
+    Some((start, lines[start - 1..end].join("\n")))
+}
+
+fn provenance(root: &Path, suggestion: &Suggestion) -> Vec<String> {
+    let source = match suggestion.source {
+        SuggestionSource::Static => "static analysis",
+        SuggestionSource::Cached => "earlier AI run",
+        SuggestionSource::LlmFast => "AI (fast pass)",
+        SuggestionSource::LlmDeep => "AI (deep analysis)",
+        SuggestionSource::Plugin => "analyzer plugin",
+    };
+    let confidence = match suggestion.confidence {
+        Confidence::High => "high",
+        Confidence::Medium => "medium",
+        Confidence::Low => "low",
+    };
+    let verification = match suggestion.verification_state {
+        VerificationState::Verified => "verified against the code",
+        VerificationState::Contradicted => "contradicted by the code",
+        VerificationState::InsufficientEvidence => "insufficient evidence",
+        VerificationState::Unverified => "not verified",
+    };
+    let mut lines = vec![
+        format!(
+            "Found by {} on {}",
+            source,
+            suggestion.created_at.format("%Y-%m-%d")
+        ),
+        format!("Confidence: {}; {}", confidence, verification),
+    ];
+    if suggestion.validation_state == SuggestionValidationState::Validated {
+        lines.push("Passed Cosmos's second-pass validation".to_string());
+    }
+    if !suggestion.evidence_refs.is_empty() {
+        let refs: Vec<String> = suggestion
+            .evidence_refs
+            .iter()
+            .map(|r| format!("{}:{}", relative(root, &r.file), r.line))
+            .collect();
+        lines.push(format!("Evidence: {}", refs.join(", ")));
+    }
+    let others: Vec<String> = suggestion
+        .additional_files
+        .iter()
+        .map(|file| relative(root, file))
+        .collect();
+    if !others.is_empty() {
+        lines.push(format!("Also touches: {}", others.join(", ")));
+    }
+    lines
+}
+
+pub(crate) fn heading(index: usize, suggestion: &Suggestion) -> String {
+    format!(
+        "{}. [{}] {}",
+        index + 1,
+        suggestion.kind.label(),
+        suggestion.summary.trim()
+    )
+}
+
+pub(crate) fn detail(suggestion: &Suggestion) -> Option<&str> {
+    suggestion
+        .detail
+        .as_deref()
+        .map(str::trim)
+        .filter(|detail| !detail.is_empty())
+}
+
+fn render_markdown(entries: &[Entry]) -> String {
+    let mut out = String::from("# Cosmos suggestions\n\n");
+    let _ = writeln!(
+        out,
+        "{} suggestion(s) for review. Line numbers refer to the working tree when this report was generated.",
+        entries.len()
+    );
+    for (index, entry) in entries.iter().enumerate() {
+        let _ = write!(
+            out,
+            "\n## {}\n\n`{}` · {}\n\n",
+            heading(index, entry.suggestion),
+            entry.location,
+            entry.suggestion.category.label()
+        );
+        if let Some(detail) = detail(entry.suggestion) {
+            let _ = write!(out, "{}\n\n", detail);
+        }
+        if let Some(excerpt) = &entry.excerpt {
+            // A fence longer than any backtick run in the code keeps the block intact.
+            let longest_run = excerpt
+                .code
+                .split(|c| c != '`')
+                .map(str::len)
+                .max()
+                .unwrap_or(0);
+            let fence = "`".repeat(longest_run.max(2) + 1);
+            if let Some(first_line) = excerpt.first_line {
+                let _ = writeln!(out, "From line {}:\n", first_line);
+            }
+            let _ = write!(
+                out,
+                "{}{}\n{}\n{}\n\n",
+                fence, excerpt.language, excerpt.code, fence
+            );
+        }
+        for line in &entry.provenance {
+            let _ = writeln!(out, "- {}", line);
+        }
+    }
+    out
+}
+
+pub(crate) fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+fn render_html(entries: &[Entry]) -> String {
+    let mut out = String::from(concat!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n",
+        "<title>Cosmos suggestions</title>\n<style>\n",
+        "body{font-family:system-ui,sans-serif;max-width:56rem;margin:2rem auto;padding:0 1rem;line-height:1.5;color:#1f2328}\n",
+        "section{border-top:1px solid #d0d7de;padding-top:1rem;margin-top:1.5rem}\n",
+        ".meta{color:#59636e}\n",
+        "pre{background:#f6f8fa;padding:.75rem;overflow-x:auto;border-radius:6px}\n",
+        "p.detail{white-space:pre-wrap}\n",
+        "</style>\n</head>\n<body>\n<h1>Cosmos suggestions</h1>\n"
+    ));
+    let _ = writeln!(
+        out,
+        "<p class=\"meta\">{} suggestion(s) for review. Line numbers refer to the working tree when this report was generated.</p>",
+        entries.len()
+    );
+    for (index, entry) in entries.iter().enumerate() {
+        let _ = write!(
+            out,
+            "<section>\n<h2>{}</h2>\n<p class=\"meta\"><code>{}</code> · {}</p>\n",
+            escape_html(&heading(index, entry.suggestion)),
+            escape_html(&entry.location),
+            entry.suggestion.category.label()
+        );
+        if let Some(detail) = detail(entry.suggestion) {
+            let _ = writeln!(out, "<p class=\"detail\">{}</p>", escape_html(detail));
+        }
+        if let Some(excerpt) = &entry.excerpt {
+            if let Some(first_line) = excerpt.first_line {
+                let _ = writeln!(out, "<p class=\"meta\">From line {}:</p>", first_line);
+            }
+            let _ = writeln!(
+                out,
+                "<pre><code>{}</code></pre>",
+                escape_html(&excerpt.code)
+            );
+        }
+        out.push_str("<ul class=\"meta\">\n");
+        for line in &entry.provenance {
+            let _ = writeln!(out, "<li>{}</li>", escape_html(line));
+        }
+        out.push_str("</ul>\n</section>\n");
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmos_core::index::CodebaseIndex;
+    use cosmos_core::suggest::{Priority, SuggestionKind};
+    use std::path::PathBuf;
+
+    #[test]
+    fn exports_selected_suggestions_with_excerpts_and_masked_secrets() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("src")).unwrap();
+        std::fs::write(
+            dir.path().join("src/lib.rs"),
+            "fn a() {}\nconst API_KEY: &str = \"sk-abcdefghijklmnopqrstuvwxyz123456\";\nfn b() {}\n",
+        )
+        .unwrap();
+        let index = CodebaseIndex::new(dir.path()).unwrap();
+        let mut engine = SuggestionEngine::new(index);
+        let leak = Suggestion::new(
+            SuggestionKind::BugFix,
+            Priority::High,
+            PathBuf::from("src/lib.rs"),
+            "Hardcoded <API> key".to_string(),
+            SuggestionSource::LlmDeep,
+        )
+        .with_line(2)
+        .with_detail("The key ships in the binary.".to_string());
+        let other = Suggestion::new(
+            SuggestionKind::Documentation,
+            Priority::Low,
+            PathBuf::from("README.md"),
+            "Document setup".to_string(),
+            SuggestionSource::Static,
+        );
+        let leak_id = leak.id;
+        engine.add_llm_suggestion(leak);
+        engine.add_llm_suggestion(other);
+
+        let markdown = render(&engine, &[leak_id], ExportFormat::Markdown).unwrap();
+        assert!(markdown.contains("## 1. [Fix] Hardcoded <API> key"));
+        assert!(markdown.contains("`src/lib.rs:2`"));
+        assert!(markdown.contains("From line 1:\n\n```rs\nfn a() {}"));
+        assert!(!markdown.contains("sk-abcdefghijklmnopqrstuvwxyz123456"));
+        assert!(markdown.contains("- Found by AI (deep analysis) on"));
+        assert!(!markdown.contains("Document setup"));
+        assert!(!markdown.contains(&dir.path().display().to_string()));
+
+        let html = render(&engine, &[], ExportFormat::Html).unwrap();
+        assert!(html.contains("Hardcoded &lt;API&gt; key"));
+        assert!(html.contains("Document setup"));
+        assert!(html.ends_with("</html>\n"));
+
+        assert!(render(&engine, &[Uuid::new_v4()], ExportFormat::Markdown).is_err());
+    }
+}
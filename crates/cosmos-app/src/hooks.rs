@@ -0,0 +1,272 @@
+//! `cosmos hooks` and `cosmos review`: review the commits being pushed before they leave.
+//!
+//! `hooks install` writes a pre-push hook that runs `cosmos review` over each pushed range and
+//! blocks the push on findings at or above a severity. The hook steps aside when `COSMOS_SKIP`
+//! is set, when `cosmos` isn't on PATH, and (inside `cosmos review`) when AI is unavailable.
+
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use cosmos_adapters::{git_ops, usage};
+use cosmos_engine::llm::{self, ReviewFinding};
+use std::path::{Path, PathBuf};
+
+/// First line after the shebang; only hooks carrying it are replaced or removed.
+const HOOK_MARKER: &str = "# Installed by `cosmos hooks install`";
+/// Large pushes are reviewed on their first files only, to keep a push from stalling.
+const MAX_REVIEW_FILES: usize = 25;
+
+/// Lowest review severity that blocks a push
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BlockOn {
+    Critical,
+    Warning,
+    Suggestion,
+    /// Report findings but never block
+    Never,
+}
+
+impl BlockOn {
+    fn name(self) -> &'static str {
+        match self {
+            BlockOn::Critical => "critical",
+            BlockOn::Warning => "warning",
+            BlockOn::Suggestion => "suggestion",
+            BlockOn::Never => "never",
+        }
+    }
+
+    fn blocks(self, severity: &str) -> bool {
+        let rank = |severity: &str| match severity.trim().to_ascii_lowercase().as_str() {
+            "critical" => 3,
+            "warning" => 2,
+            "suggestion" => 1,
+            _ => 0,
+        };
+        match self {
+            BlockOn::Never => false,
+            threshold => rank(severity) >= rank(threshold.name()),
+        }
+    }
+}
+
+pub fn install(repo_path: &Path, block_on: BlockOn, force: bool) -> Result<()> {
+    let path = install_into(&git_ops::hooks_dir(repo_path)?, block_on, force)?;
+    println!(
+        "Installed {}: pushes are reviewed and blocked on {} findings. Set COSMOS_SKIP=1 to skip once.",
+        path.display(),
+        block_on.name()
+    );
+    Ok(())
+}
+
+pub fn uninstall(repo_path: &Path) -> Result<()> {
+    match uninstall_from(&git_ops::hooks_dir(repo_path)?)? {
+        Some(path) => println!("Removed {}", path.display()),
+        None => println!("No Cosmos pre-push hook is installed."),
+    }
+    Ok(())
+}
+
+fn install_into(hooks_dir: &Path, block_on: BlockOn, force: bool) -> Result<PathBuf> {
+    let path = hooks_dir.join("pre-push");
+    if let Ok(existing) = std::fs::read_to_string(&path) {
+        if !existing.contains(HOOK_MARKER) && !force {
+            anyhow::bail!(
+                "{} already exists and wasn't installed by Cosmos; rerun with --force to replace it",
+                path.display()
+            );
+        }
+    }
+    std::fs::create_dir_all(hooks_dir)
+        .with_context(|| format!("Failed to create {}", hooks_dir.display()))?;
+    std::fs::write(&path, hook_script(block_on))
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    make_executable(&path)?;
+    Ok(path)
+}
+
+fn uninstall_from(hooks_dir: &Path) -> Result<Option<PathBuf>> {
+    let path = hooks_dir.join("pre-push");
+    match std::fs::read_to_string(&path) {
+        Ok(existing) if existing.contains(HOOK_MARKER) => {
+            std::fs::remove_file(&path)
+                .with_context(|| format!("Failed to remove {}", path.display()))?;
+            Ok(Some(path))
+        }
+        Ok(_) => anyhow::bail!(
+            "{} wasn't installed by Cosmos; leaving it in place",
+            path.display()
+        ),
+        Err(_) => Ok(None),
+    }
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755))
+        .with_context(|| format!("Failed to make {} executable", path.display()))
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> Result<()> {
+    Ok(())
+}
+
+/// Git feeds pre-push one `<local ref> <local sha> <remote ref> <remote sha>` line per ref.
+/// New branches (all-zero remote sha) and remote commits missing locally are reviewed from
+/// where they forked off the main branch; deletions are skipped.
+fn hook_script(block_on: BlockOn) -> String {
+    format!(
+        r#"#!/bin/sh
+{marker}; remove with `cosmos hooks uninstall`.
+# Reviews the commits being pushed and blocks on {block_on} findings. Set COSMOS_SKIP=1 to skip.
+[ -n "$COSMOS_SKIP" ] && exit 0
+if ! command -v cosmos >/dev/null 2>&1; then
+    echo "cosmos: not on PATH; pushing without review" >&2
+    exit 0
+fi
+zero=$(git hash-object --stdin </dev/null | tr '0-9a-f' '0')
+while read -r local_ref local_sha remote_ref remote_sha; do
+    [ "$local_sha" = "$zero" ] && continue
+    if [ "$remote_sha" != "$zero" ] && git cat-file -e "$remote_sha^{{commit}}" 2>/dev/null; then
+        cosmos review --head "$local_sha" --base "$remote_sha" --block-on {block_on} </dev/null || exit 1
+    else
+        cosmos review --head "$local_sha" --block-on {block_on} </dev/null || exit 1
+    fi
+done
+exit 0
+"#,
+        marker = HOOK_MARKER,
+        block_on = block_on.name()
+    )
+}
+
+/// Review the changes between `base` (default: the fork point from the main branch) and
+/// `head`, failing when a finding reaches `block_on`.
+///
+/// Anything that keeps the review from running (no API key, a provider error) is reported
+/// and lets the push through; only findings block.
+pub async fn review(
+    repo_path: &Path,
+    base: Option<&str>,
+    head: &str,
+    block_on: BlockOn,
+) -> Result<()> {
+    if std::env::var_os("COSMOS_SKIP").is_some_and(|value| !value.is_empty()) {
+        eprintln!("cosmos: COSMOS_SKIP is set; skipping review");
+        return Ok(());
+    }
+    if !llm::is_available() {
+        eprintln!("cosmos: AI is unavailable (no API key configured); skipping review");
+        return Ok(());
+    }
+
+    let mut changes = git_ops::range_changes(repo_path, base, head)?;
+    if changes.is_empty() {
+        println!("cosmos: nothing to review");
+        return Ok(());
+    }
+    changes.sort_by(|a, b| a.0.cmp(&b.0));
+    if changes.len() > MAX_REVIEW_FILES {
+        eprintln!(
+            "cosmos: reviewing the first {} of {} changed files",
+            MAX_REVIEW_FILES,
+            changes.len()
+        );
+        changes.truncate(MAX_REVIEW_FILES);
+    }
+    println!("cosmos: reviewing {} changed file(s)...", changes.len());
+
+    let files: Vec<(PathBuf, String, String)> = changes
+        .into_iter()
+        .map(|(path, old, new)| (repo_path.join(path), old, new))
+        .collect();
+    let review = match llm::verify_changes(&files, 1, &[], None).await {
+        Ok(review) => review,
+        Err(e) => {
+            eprintln!("cosmos: review failed ({}); skipping review", e);
+            return Ok(());
+        }
+    };
+    if let Some(usage) = &review.usage {
+        usage::record(
+            repo_path,
+            usage::OPERATION_REVIEWS,
+            usage.model.as_deref().unwrap_or("unknown"),
+            usage.prompt_tokens,
+            usage.completion_tokens,
+            usage.total_tokens,
+            usage.cost,
+        );
+    }
+
+    println!("{}", review.summary.trim());
+    for finding in &review.findings {
+        print_finding(repo_path, finding);
+    }
+    let blocking = review
+        .findings
+        .iter()
+        .filter(|finding| block_on.blocks(&finding.severity))
+        .count();
+    if blocking > 0 {
+        anyhow::bail!(
+            "Push blocked: {} finding(s) at or above {}. Fix them, or push with COSMOS_SKIP=1.",
+            blocking,
+            block_on.name()
+        );
+    }
+    Ok(())
+}
+
+fn print_finding(repo_path: &Path, finding: &ReviewFinding) {
+    let file = Path::new(&finding.file);
+    let file = file.strip_prefix(repo_path).unwrap_or(file).display();
+    let location = match finding.line {
+        Some(line) => format!("{}:{}", file, line),
+        None => file.to_string(),
+    };
+    println!(
+        "  [{}] {} ({})",
+        finding.severity.to_ascii_lowercase(),
+        finding.title,
+        location
+    );
+    println!("      {}", finding.description.trim());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn installs_only_over_its_own_hook_and_blocks_by_threshold() {
+        let dir = tempfile::tempdir().unwrap();
+        let hooks = dir.path().join("hooks");
+
+        let path = install_into(&hooks, BlockOn::Warning, false).unwrap();
+        let script = std::fs::read_to_string(&path).unwrap();
+        assert!(script.starts_with("#!/bin/sh\n# Installed by `cosmos hooks install`"));
+        assert!(script.contains("--block-on warning"));
+        assert!(script.contains("[ -n \"$COSMOS_SKIP\" ] && exit 0"));
+        install_into(&hooks, BlockOn::Critical, false).unwrap();
+        assert_eq!(uninstall_from(&hooks).unwrap(), Some(path.clone()));
+        assert_eq!(uninstall_from(&hooks).unwrap(), None);
+
+        std::fs::write(&path, "#!/bin/sh\nrun-lint\n").unwrap();
+        assert!(install_into(&hooks, BlockOn::Critical, false).is_err());
+        assert!(uninstall_from(&hooks).is_err());
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "#!/bin/sh\nrun-lint\n"
+        );
+        install_into(&hooks, BlockOn::Critical, true).unwrap();
+
+        assert!(BlockOn::Critical.blocks("Critical"));
+        assert!(!BlockOn::Critical.blocks("warning"));
+        assert!(BlockOn::Warning.blocks("critical"));
+        assert!(!BlockOn::Suggestion.blocks("nitpick"));
+        assert!(!BlockOn::Never.blocks("critical"));
+    }
+}
@@ -0,0 +1,381 @@
+//! `cosmos suggest --report html`: a static HTML bundle for publishing as a CI artifact.
+//!
+//! `index.html` holds a filterable table of every suggestion and a summary of recorded harness
+//! runs; each file with suggestions gets a page under `files/` with the rationale, a code
+//! excerpt, and, when the harness has produced a fix for the suggestion, its diff and gate
+//! results. Pages are self-contained (inline styles and script) so the directory can be
+//! uploaded as-is.
+
+use crate::handoff::{self, escape_html, Entry};
+use anyhow::{Context, Result};
+use cosmos_core::suggest::{Criticality, Suggestion};
+use cosmos_engine::llm::ImplementationRunDiagnostics;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+const STYLE: &str = concat!(
+    "body{font-family:system-ui,sans-serif;margin:2rem auto;max-width:72rem;padding:0 1rem;line-height:1.5;color:#1f2328}\n",
+    "table{border-collapse:collapse;width:100%}th,td{text-align:left;padding:.35rem .5rem;border-bottom:1px solid #d0d7de;vertical-align:top}\n",
+    ".meta{color:#59636e}.pass{color:#1a7f37}.fail{color:#cf222e}\n",
+    ".filters{display:flex;gap:.5rem;margin:1rem 0}.filters input{flex:1}\n",
+    "section{border-top:1px solid #d0d7de;padding-top:1rem;margin-top:1.5rem}\n",
+    "pre{background:#f6f8fa;padding:.75rem;overflow-x:auto;border-radius:6px}\n",
+    "pre.diff .add{color:#1a7f37}pre.diff .del{color:#cf222e}pre.diff .hunk{color:#8250df}\n",
+    "p.detail{white-space:pre-wrap}\n",
+);
+
+/// Hides table rows that don't match the search box and the selected kind/severity.
+const FILTER_SCRIPT: &str = r#"<script>
+function applyFilters() {
+  const text = document.getElementById('search').value.toLowerCase();
+  const kind = document.getElementById('kind').value;
+  const severity = document.getElementById('severity').value;
+  for (const row of document.querySelectorAll('#suggestions tbody tr')) {
+    const show = row.textContent.toLowerCase().includes(text)
+      && (!kind || row.dataset.kind === kind)
+      && (!severity || row.dataset.severity === severity);
+    row.hidden = !show;
+  }
+}
+for (const id of ['search', 'kind', 'severity']) {
+  document.getElementById(id).addEventListener('input', applyFilters);
+}
+</script>
+"#;
+
+/// Write the bundle into `out_dir`, returning the path of `index.html`.
+pub fn write_bundle(
+    out_dir: &Path,
+    root: &Path,
+    suggestions: &[&Suggestion],
+    runs: &[ImplementationRunDiagnostics],
+) -> Result<PathBuf> {
+    let entries: Vec<Entry> = suggestions
+        .iter()
+        .map(|suggestion| handoff::entry(root, suggestion))
+        .collect();
+    let mut by_file: BTreeMap<&str, Vec<(usize, &Entry)>> = BTreeMap::new();
+    for (index, entry) in entries.iter().enumerate() {
+        by_file
+            .entry(entry.file.as_str())
+            .or_default()
+            .push((index, entry));
+    }
+    let pages: BTreeMap<&str, String> = by_file
+        .keys()
+        .enumerate()
+        .map(|(n, file)| (*file, format!("files/{}-{}.html", n + 1, slug(file))))
+        .collect();
+    let latest_runs = latest_run_by_suggestion(runs);
+
+    let files_dir = out_dir.join("files");
+    std::fs::create_dir_all(&files_dir)
+        .with_context(|| format!("Failed to create {}", files_dir.display()))?;
+    for (file, file_entries) in &by_file {
+        let page = file_page(file, file_entries, &latest_runs);
+        let path = out_dir.join(&pages[file]);
+        std::fs::write(&path, page)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+    }
+    let index_path = out_dir.join("index.html");
+    std::fs::write(&index_path, index_page(&entries, &pages, runs))
+        .with_context(|| format!("Failed to write {}", index_path.display()))?;
+    Ok(index_path)
+}
+
+/// Latest harness run recorded for each suggestion id.
+fn latest_run_by_suggestion(
+    runs: &[ImplementationRunDiagnostics],
+) -> BTreeMap<&str, &ImplementationRunDiagnostics> {
+    runs.iter()
+        .map(|run| (run.suggestion_id.as_str(), run))
+        .collect()
+}
+
+fn slug(file: &str) -> String {
+    let slug: String = file
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    slug.trim_matches('-').chars().take(80).collect()
+}
+
+fn severity(criticality: Criticality) -> &'static str {
+    match criticality {
+        Criticality::Critical => "critical",
+        Criticality::High => "high",
+        Criticality::Medium => "medium",
+        Criticality::Low => "low",
+    }
+}
+
+fn page_start(title: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>\n{}</style>\n</head>\n<body>\n",
+        escape_html(title),
+        STYLE
+    )
+}
+
+fn index_page(
+    entries: &[Entry],
+    pages: &BTreeMap<&str, String>,
+    runs: &[ImplementationRunDiagnostics],
+) -> String {
+    let mut out = page_start("Cosmos report");
+    out.push_str("<h1>Cosmos report</h1>\n");
+    let _ = writeln!(
+        out,
+        "<p class=\"meta\">{} suggestion(s) across {} file(s); {} harness run(s) recorded.</p>",
+        entries.len(),
+        pages.len(),
+        runs.len()
+    );
+
+    let mut kinds: Vec<&str> = entries.iter().map(|e| e.suggestion.kind.label()).collect();
+    kinds.sort_unstable();
+    kinds.dedup();
+    out.push_str("<div class=\"filters\">\n<input id=\"search\" type=\"search\" placeholder=\"Filter suggestions\">\n<select id=\"kind\"><option value=\"\">All kinds</option>");
+    for kind in kinds {
+        let _ = write!(out, "<option>{}</option>", escape_html(kind));
+    }
+    out.push_str("</select>\n<select id=\"severity\"><option value=\"\">All severities</option>");
+    for level in ["critical", "high", "medium", "low"] {
+        let _ = write!(out, "<option>{}</option>", level);
+    }
+    out.push_str("</select>\n</div>\n");
+
+    out.push_str("<table id=\"suggestions\">\n<thead><tr><th>#</th><th>Severity</th><th>Kind</th><th>Suggestion</th><th>Location</th></tr></thead>\n<tbody>\n");
+    for (index, entry) in entries.iter().enumerate() {
+        let suggestion = entry.suggestion;
+        let _ = writeln!(
+            out,
+            "<tr data-kind=\"{kind}\" data-severity=\"{severity}\"><td>{n}</td><td>{severity}</td><td>{kind}</td><td>{summary}</td><td><a href=\"{page}#s{n}\"><code>{location}</code></a></td></tr>",
+            kind = escape_html(suggestion.kind.label()),
+            severity = severity(suggestion.criticality),
+            n = index + 1,
+            summary = escape_html(suggestion.summary.trim()),
+            page = escape_html(&pages[entry.file.as_str()]),
+            location = escape_html(&entry.location),
+        );
+    }
+    out.push_str("</tbody>\n</table>\n");
+
+    if !runs.is_empty() {
+        out.push_str("<h2>Harness runs</h2>\n<table>\n<thead><tr><th>Suggestion</th><th>Result</th><th>Attempts</th><th>Time</th><th>Gates</th></tr></thead>\n<tbody>\n");
+        for run in runs.iter().rev() {
+            let _ = writeln!(
+                out,
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{:.1}s</td><td>{}</td></tr>",
+                escape_html(&run.suggestion_summary),
+                run_result(run),
+                run.attempt_count,
+                run.total_ms as f64 / 1000.0,
+                gate_summary(run)
+            );
+        }
+        out.push_str("</tbody>\n</table>\n");
+    }
+    out.push_str(FILTER_SCRIPT);
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+fn run_result(run: &ImplementationRunDiagnostics) -> &'static str {
+    if run.passed {
+        "<span class=\"pass\">passed</span>"
+    } else {
+        "<span class=\"fail\">failed</span>"
+    }
+}
+
+/// Gates of the run's last attempt, e.g. `✓ scope ✗ quick_checks`.
+fn gate_summary(run: &ImplementationRunDiagnostics) -> String {
+    let Some(attempt) = run.attempts.last() else {
+        return String::new();
+    };
+    attempt
+        .gates
+        .iter()
+        .map(|gate| {
+            let (class, mark) = if gate.passed {
+                ("pass", "✓")
+            } else {
+                ("fail", "✗")
+            };
+            format!(
+                "<span class=\"{}\" title=\"{}\">{} {}</span>",
+                class,
+                escape_html(&gate.detail),
+                mark,
+                escape_html(&gate.gate)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn file_page(
+    file: &str,
+    entries: &[(usize, &Entry)],
+    runs: &BTreeMap<&str, &ImplementationRunDiagnostics>,
+) -> String {
+    let mut out = page_start(file);
+    let _ = writeln!(
+        out,
+        "<p><a href=\"../index.html\">← All suggestions</a></p>\n<h1><code>{}</code></h1>",
+        escape_html(file)
+    );
+    for (index, entry) in entries {
+        let suggestion = entry.suggestion;
+        let _ = write!(
+            out,
+            "<section id=\"s{}\">\n<h2>{}</h2>\n<p class=\"meta\"><code>{}</code> · {} · {}</p>\n",
+            index + 1,
+            escape_html(&handoff::heading(*index, suggestion)),
+            escape_html(&entry.location),
+            suggestion.category.label(),
+            severity(suggestion.criticality)
+        );
+        if let Some(detail) = handoff::detail(suggestion) {
+            let _ = writeln!(out, "<p class=\"detail\">{}</p>", escape_html(detail));
+        }
+        if let Some(excerpt) = &entry.excerpt {
+            if let Some(first_line) = excerpt.first_line {
+                let _ = writeln!(out, "<p class=\"meta\">From line {}:</p>", first_line);
+            }
+            let _ = writeln!(
+                out,
+                "<pre><code>{}</code></pre>",
+                escape_html(&excerpt.code)
+            );
+        }
+        out.push_str("<ul class=\"meta\">\n");
+        for line in &entry.provenance {
+            let _ = writeln!(out, "<li>{}</li>", escape_html(line));
+        }
+        out.push_str("</ul>\n");
+        if let Some(run) = runs.get(suggestion.id.to_string().as_str()) {
+            let _ = writeln!(
+                out,
+                "<h3>Proposed fix: {}</h3>\n<p>{}</p>",
+                run_result(run),
+                gate_summary(run)
+            );
+            if !run.passed {
+                out.push_str("<ul class=\"fail\">\n");
+                for reason in &run.fail_reasons {
+                    let _ = writeln!(out, "<li>{}</li>", escape_html(reason));
+                }
+                out.push_str("</ul>\n");
+            }
+            if !run.proposed_diff.is_empty() {
+                let _ = writeln!(
+                    out,
+                    "<pre class=\"diff\">{}</pre>",
+                    diff_html(&run.proposed_diff)
+                );
+            }
+        }
+        out.push_str("</section>\n");
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+fn diff_html(diff: &str) -> String {
+    diff.lines()
+        .map(|line| {
+            let class = if line.starts_with("+++") || line.starts_with("---") {
+                None
+            } else if line.starts_with('+') {
+                Some("add")
+            } else if line.starts_with('-') {
+                Some("del")
+            } else if line.starts_with("@@") {
+                Some("hunk")
+            } else {
+                None
+            };
+            match class {
+                Some(class) => format!("<span class=\"{}\">{}</span>", class, escape_html(line)),
+                None => escape_html(line),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmos_core::suggest::{Priority, SuggestionKind, SuggestionSource};
+
+    fn run_for(suggestion: &Suggestion) -> ImplementationRunDiagnostics {
+        serde_json::from_value(serde_json::json!({
+            "run_id": "run-1",
+            "suggestion_id": suggestion.id.to_string(),
+            "suggestion_summary": suggestion.summary,
+            "model": "m",
+            "strict_mode": true,
+            "passed": true,
+            "attempt_count": 1,
+            "total_ms": 1200,
+            "total_cost_usd": 0.01,
+            "attempts": [{
+                "attempt_index": 1,
+                "passed": true,
+                "fail_reasons": [],
+                "gates": [{"gate": "quick_checks", "passed": true, "detail": "cargo check"}],
+                "changed_files": ["src/retry.rs"],
+                "changed_lines_total": 2,
+                "quick_check_status": "passed",
+                "review_iterations": 1,
+                "review_blocking_remaining": 0,
+                "attempt_ms": 1200,
+                "attempt_cost_usd": 0.01
+            }],
+            "proposed_diff": "--- a/src/retry.rs\n+++ b/src/retry.rs\n@@ -1 +1 @@\n-loop {}\n+backoff();\n"
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn bundle_links_table_rows_to_file_pages_with_fix_diffs() {
+        let dir = tempfile::tempdir().unwrap();
+        let retry = Suggestion::new(
+            SuggestionKind::BugFix,
+            Priority::High,
+            PathBuf::from("src/retry.rs"),
+            "Retry loop <never> backs off".to_string(),
+            SuggestionSource::LlmDeep,
+        )
+        .with_criticality(Criticality::Critical);
+        let docs = Suggestion::new(
+            SuggestionKind::Documentation,
+            Priority::Low,
+            PathBuf::from("README.md"),
+            "Document setup".to_string(),
+            SuggestionSource::Static,
+        );
+        let runs = vec![run_for(&retry)];
+        let out = dir.path().join("report");
+
+        let index_path = write_bundle(&out, dir.path(), &[&retry, &docs], &runs).unwrap();
+        let index = std::fs::read_to_string(index_path).unwrap();
+        assert!(index.contains("<tr data-kind=\"Fix\" data-severity=\"critical\"><td>1</td>"));
+        assert!(index.contains("Retry loop &lt;never&gt; backs off"));
+        assert!(index.contains("href=\"files/2-src-retry-rs.html#s1\""));
+        assert!(index.contains("✓ quick_checks"));
+        assert!(index.contains("function applyFilters()"));
+
+        let page = std::fs::read_to_string(out.join("files/2-src-retry-rs.html")).unwrap();
+        assert!(page.contains("<section id=\"s1\">"));
+        assert!(page.contains("<span class=\"add\">+backoff();</span>"));
+        assert!(page.contains("<span class=\"del\">-loop {}</span>"));
+        let readme = std::fs::read_to_string(out.join("files/1-README-md.html")).unwrap();
+        assert!(readme.contains("Document setup"));
+        assert!(!readme.contains("Proposed fix"));
+    }
+}
@@ -0,0 +1,471 @@
+//! `cosmos lsp` - a Language Server Protocol facade over the `cosmos serve` session.
+//!
+//! Suggestions are published as `textDocument/publishDiagnostics` (source `cosmos`), and each
+//! one offers a quick-fix code action. Running it applies the fix through the strict harness
+//! and hands the result back as a `workspace/applyEdit`, so the editor owns the undo stack.
+//! `cosmos.refresh` re-runs the AI scan; saving a file re-indexes and republishes.
+
+use crate::serve::Session;
+use anyhow::Result;
+use cosmos_adapters::util::resolve_repo_path_allow_new;
+use cosmos_core::suggest::{Criticality, Suggestion};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+const APPLY_FIX_COMMAND: &str = "cosmos.applyFix";
+const REFRESH_COMMAND: &str = "cosmos.refresh";
+
+/// LSP `MessageType`
+const MESSAGE_ERROR: u8 = 1;
+const MESSAGE_INFO: u8 = 3;
+
+/// Read one `Content-Length` framed message; None at end of input.
+pub async fn read_message<R: AsyncBufRead + Unpin>(reader: &mut R) -> Result<Option<Value>> {
+    let mut length = None;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header).await? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                length = Some(value.trim().parse::<usize>()?);
+            }
+        }
+    }
+    let Some(length) = length else {
+        anyhow::bail!("message without Content-Length header");
+    };
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).await?;
+    Ok(Some(serde_json::from_slice(&body)?))
+}
+
+pub async fn write_message<W: AsyncWrite + Unpin>(writer: &mut W, message: &Value) -> Result<()> {
+    let body = message.to_string();
+    writer
+        .write_all(format!("Content-Length: {}\r\n\r\n", body.len()).as_bytes())
+        .await?;
+    writer.write_all(body.as_bytes()).await?;
+    writer.flush().await?;
+    Ok(())
+}
+
+fn file_uri(path: &Path) -> String {
+    url::Url::from_file_path(path)
+        .map(|url| url.to_string())
+        .unwrap_or_else(|_| format!("file://{}", path.display()))
+}
+
+fn uri_path(uri: &str) -> Option<PathBuf> {
+    url::Url::parse(uri).ok()?.to_file_path().ok()
+}
+
+fn severity(criticality: Criticality) -> u8 {
+    match criticality {
+        Criticality::Critical | Criticality::High => 2,
+        Criticality::Medium => 3,
+        Criticality::Low => 4,
+    }
+}
+
+/// Diagnostics for every active suggestion, grouped by absolute file path.
+pub fn diagnostics_by_file(
+    root: &Path,
+    suggestions: &[&Suggestion],
+) -> BTreeMap<PathBuf, Vec<Value>> {
+    let mut by_file: BTreeMap<PathBuf, Vec<Value>> = BTreeMap::new();
+    let mut contents: BTreeMap<PathBuf, String> = BTreeMap::new();
+    for suggestion in suggestions {
+        let path = root.join(&suggestion.file);
+        let content = contents
+            .entry(path.clone())
+            .or_insert_with(|| std::fs::read_to_string(&path).unwrap_or_default());
+        let line = suggestion.line.unwrap_or(1).saturating_sub(1);
+        let end = content
+            .lines()
+            .nth(line)
+            .map(|text| text.encode_utf16().count())
+            .unwrap_or(0);
+        let message = match suggestion
+            .detail
+            .as_deref()
+            .filter(|d| !d.trim().is_empty())
+        {
+            Some(detail) => format!("{}\n\n{}", suggestion.summary, detail.trim()),
+            None => suggestion.summary.clone(),
+        };
+        by_file.entry(path).or_default().push(json!({
+            "range": {
+                "start": { "line": line, "character": 0 },
+                "end": { "line": line, "character": end },
+            },
+            "severity": severity(suggestion.criticality),
+            "source": "cosmos",
+            "code": suggestion.kind.label(),
+            "message": message,
+            "data": { "id": suggestion.id },
+        }));
+    }
+    by_file
+}
+
+/// A workspace edit replacing each file's whole content (creating files that don't exist).
+///
+/// Fails if any path would land outside `root`, so a fix is never applied in part.
+fn whole_file_edit(root: &Path, files: &[Value]) -> Result<Value, String> {
+    let mut changes = Vec::new();
+    for file in files {
+        let (Some(path), Some(content)) = (
+            file.get("path").and_then(Value::as_str),
+            file.get("content").and_then(Value::as_str),
+        ) else {
+            continue;
+        };
+        let absolute = resolve_repo_path_allow_new(root, Path::new(path))
+            .map_err(|e| format!("Cosmos won't edit {}: {}", path, e))?
+            .absolute;
+        let uri = file_uri(&absolute);
+        let original = std::fs::read_to_string(&absolute);
+        if original.is_err() {
+            changes.push(
+                json!({ "kind": "create", "uri": uri, "options": { "ignoreIfExists": true } }),
+            );
+        }
+        let original = original.unwrap_or_default();
+        let last_line = original.rsplit('\n').next().unwrap_or_default();
+        changes.push(json!({
+            "textDocument": { "uri": uri, "version": null },
+            "edits": [{
+                "range": {
+                    "start": { "line": 0, "character": 0 },
+                    "end": {
+                        "line": original.matches('\n').count(),
+                        "character": last_line.encode_utf16().count(),
+                    },
+                },
+                "newText": content,
+            }],
+        }));
+    }
+    Ok(json!({ "documentChanges": changes }))
+}
+
+struct Server {
+    session: Session,
+    /// Files that currently have diagnostics, so cleared files get an empty publish
+    published: BTreeSet<PathBuf>,
+    next_request_id: u64,
+    shutdown_requested: bool,
+}
+
+impl Server {
+    fn publish_diagnostics(&mut self) -> Vec<Value> {
+        let by_file = diagnostics_by_file(self.session.root(), &self.session.active_suggestions());
+        let mut messages = Vec::new();
+        for path in &self.published {
+            if !by_file.contains_key(path) {
+                messages.push(publish(path, Vec::new()));
+            }
+        }
+        for (path, diagnostics) in &by_file {
+            messages.push(publish(path, diagnostics.clone()));
+        }
+        self.published = by_file.into_keys().collect();
+        messages
+    }
+
+    fn code_actions(&self, params: &Value) -> Value {
+        let Some(path) = params
+            .pointer("/textDocument/uri")
+            .and_then(Value::as_str)
+            .and_then(uri_path)
+        else {
+            return json!([]);
+        };
+        let start = params
+            .pointer("/range/start/line")
+            .and_then(Value::as_u64)
+            .unwrap_or(0);
+        let end = params
+            .pointer("/range/end/line")
+            .and_then(Value::as_u64)
+            .unwrap_or(start);
+        let actions: Vec<Value> = self
+            .session
+            .active_suggestions()
+            .into_iter()
+            .filter(|s| self.session.root().join(&s.file) == path)
+            .filter(|s| {
+                let line = s.line.unwrap_or(1).saturating_sub(1) as u64;
+                (start..=end).contains(&line)
+            })
+            .map(|s| {
+                json!({
+                    "title": format!("Cosmos: fix - {}", s.summary),
+                    "kind": "quickfix",
+                    "command": {
+                        "title": "Apply Cosmos fix",
+                        "command": APPLY_FIX_COMMAND,
+                        "arguments": [s.id],
+                    },
+                })
+            })
+            .collect();
+        json!(actions)
+    }
+
+    /// Run a fix through the harness and turn the result into a `workspace/applyEdit` request.
+    async fn apply_fix(&mut self, id: &Value) -> Vec<Value> {
+        let preview = match self
+            .session
+            .dispatch("suggestions.preview", json!({ "id": id }))
+            .await
+        {
+            Ok(preview) => preview,
+            Err(e) => return vec![show_message(MESSAGE_ERROR, &e.message)],
+        };
+        let applied = self
+            .session
+            .dispatch(
+                "suggestions.apply",
+                json!({ "id": id, "preview_hash": preview["preview_hash"], "write": false }),
+            )
+            .await;
+        let result = match applied {
+            Ok(result) => result,
+            Err(e) => return vec![show_message(MESSAGE_ERROR, &e.message)],
+        };
+        if result["passed"] != Value::Bool(true) {
+            let reasons: Vec<&str> = result["fail_reasons"]
+                .as_array()
+                .map(|reasons| reasons.iter().filter_map(Value::as_str).collect())
+                .unwrap_or_default();
+            return vec![show_message(
+                MESSAGE_ERROR,
+                &format!("Cosmos couldn't produce a safe fix: {}", reasons.join("; ")),
+            )];
+        }
+        let files = result["files"].as_array().cloned().unwrap_or_default();
+        let edit = match whole_file_edit(self.session.root(), &files) {
+            Ok(edit) => edit,
+            Err(e) => return vec![show_message(MESSAGE_ERROR, &e)],
+        };
+        self.next_request_id += 1;
+        vec![
+            json!({
+                "jsonrpc": "2.0",
+                "id": format!("cosmos-{}", self.next_request_id),
+                "method": "workspace/applyEdit",
+                "params": {
+                    "label": preview["friendly_title"],
+                    "edit": edit,
+                },
+            }),
+            show_message(
+                MESSAGE_INFO,
+                result["description"].as_str().unwrap_or("Cosmos fix ready"),
+            ),
+        ]
+    }
+
+    async fn execute_command(&mut self, params: &Value) -> Result<Vec<Value>, String> {
+        match params.get("command").and_then(Value::as_str) {
+            Some(APPLY_FIX_COMMAND) => {
+                let Some(id) = params.pointer("/arguments/0") else {
+                    return Err("cosmos.applyFix needs a suggestion id".to_string());
+                };
+                Ok(self.apply_fix(&id.clone()).await)
+            }
+            Some(REFRESH_COMMAND) => match self
+                .session
+                .dispatch("suggestions.refresh", Value::Null)
+                .await
+            {
+                Ok(_) => Ok(self.publish_diagnostics()),
+                Err(e) => Ok(vec![show_message(MESSAGE_ERROR, &e.message)]),
+            },
+            other => Err(format!("unknown command {:?}", other)),
+        }
+    }
+
+    /// Handle one incoming message, returning the messages to send back.
+    async fn handle(&mut self, message: Value) -> Vec<Value> {
+        let Some(method) = message.get("method").and_then(Value::as_str) else {
+            // Responses to our own requests (e.g. applyEdit) need no action.
+            return Vec::new();
+        };
+        let id = message.get("id").cloned();
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+        let mut out = Vec::new();
+        let result: Result<Value, (i64, String)> = match method {
+            "initialize" => Ok(json!({
+                "capabilities": {
+                    "textDocumentSync": { "openClose": true, "save": true },
+                    "codeActionProvider": { "codeActionKinds": ["quickfix"] },
+                    "executeCommandProvider": { "commands": [APPLY_FIX_COMMAND, REFRESH_COMMAND] },
+                },
+                "serverInfo": { "name": "cosmos", "version": env!("CARGO_PKG_VERSION") },
+            })),
+            "initialized" => {
+                out.extend(self.publish_diagnostics());
+                Ok(Value::Null)
+            }
+            "textDocument/didSave" => {
+                if self
+                    .session
+                    .dispatch("index.refresh", Value::Null)
+                    .await
+                    .is_ok()
+                {
+                    out.extend(self.publish_diagnostics());
+                }
+                Ok(Value::Null)
+            }
+            "textDocument/codeAction" => Ok(self.code_actions(&params)),
+            "workspace/executeCommand" => match self.execute_command(&params).await {
+                Ok(messages) => {
+                    out.extend(messages);
+                    Ok(Value::Null)
+                }
+                Err(e) => Err((-32602, e)),
+            },
+            "shutdown" => {
+                self.shutdown_requested = true;
+                Ok(Value::Null)
+            }
+            _ if id.is_none() => Ok(Value::Null),
+            other => Err((-32601, format!("unknown method '{}'", other))),
+        };
+        if let Some(id) = id {
+            let response = match result {
+                Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+                Err((code, message)) => json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "error": { "code": code, "message": message },
+                }),
+            };
+            out.insert(0, response);
+        }
+        out
+    }
+}
+
+fn publish(path: &Path, diagnostics: Vec<Value>) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/publishDiagnostics",
+        "params": { "uri": file_uri(path), "diagnostics": diagnostics },
+    })
+}
+
+fn show_message(kind: u8, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "window/showMessage",
+        "params": { "type": kind, "message": message },
+    })
+}
+
+/// Speak LSP on `reader`/`writer` until the client sends `exit`.
+pub async fn serve_stream<R, W>(session: Session, mut reader: R, mut writer: W) -> Result<()>
+where
+    R: AsyncBufRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let mut server = Server {
+        session,
+        published: BTreeSet::new(),
+        next_request_id: 0,
+        shutdown_requested: false,
+    };
+    while let Some(message) = read_message(&mut reader).await? {
+        if message.get("method").and_then(Value::as_str) == Some("exit") {
+            break;
+        }
+        for reply in server.handle(message).await {
+            write_message(&mut writer, &reply).await?;
+        }
+    }
+    if !server.shutdown_requested {
+        eprintln!("  Client exited without shutdown");
+    }
+    Ok(())
+}
+
+pub async fn run(session: Session) -> Result<()> {
+    let stdin = tokio::io::BufReader::new(tokio::io::stdin());
+    serve_stream(session, stdin, tokio::io::stdout()).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmos_core::suggest::{Priority, SuggestionKind, SuggestionSource};
+
+    #[tokio::test]
+    async fn frames_round_trip() {
+        let mut buffer = Vec::new();
+        write_message(&mut buffer, &json!({ "jsonrpc": "2.0", "method": "exit" }))
+            .await
+            .unwrap();
+        let mut reader = buffer.as_slice();
+        let message = read_message(&mut reader).await.unwrap().unwrap();
+        assert_eq!(message["method"], "exit");
+        assert!(read_message(&mut reader).await.unwrap().is_none());
+    }
+
+    #[test]
+    fn suggestions_become_line_diagnostics_and_whole_file_edits() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("src/lib.rs"), "fn a() {}\nfn unused() {}\n").unwrap();
+        let suggestion = Suggestion::new(
+            SuggestionKind::Quality,
+            Priority::Medium,
+            PathBuf::from("src/lib.rs"),
+            "Remove unused function".to_string(),
+            SuggestionSource::Static,
+        )
+        .with_line(2)
+        .with_criticality(Criticality::High);
+
+        let by_file = diagnostics_by_file(dir.path(), &[&suggestion]);
+        let diagnostics = &by_file[&dir.path().join("src/lib.rs")];
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0]["range"]["start"]["line"], 1);
+        assert_eq!(diagnostics[0]["range"]["end"]["character"], 14);
+        assert_eq!(diagnostics[0]["severity"], 2);
+        assert_eq!(diagnostics[0]["data"]["id"], json!(suggestion.id));
+
+        let edit = whole_file_edit(
+            dir.path(),
+            &[
+                json!({ "path": "src/lib.rs", "content": "fn a() {}\n" }),
+                json!({ "path": "src/new.rs", "content": "" }),
+            ],
+        )
+        .unwrap();
+        let changes = edit["documentChanges"].as_array().unwrap();
+        assert_eq!(changes[0]["edits"][0]["range"]["end"]["line"], 2);
+        assert_eq!(changes[1]["kind"], "create");
+
+        for escaping in ["../outside.rs", "/etc/passwd"] {
+            assert!(whole_file_edit(
+                dir.path(),
+                &[
+                    json!({ "path": "src/lib.rs", "content": "" }),
+                    json!({ "path": escaping, "content": "" }),
+                ],
+            )
+            .is_err());
+        }
+    }
+}
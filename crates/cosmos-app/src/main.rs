@@ -5,17 +5,29 @@
 //! An AI-powered IDE in the terminal that uses codebase indexing
 //! to suggest improvements, bug fixes, and optimizations.
 
-use anyhow::Result;
-use clap::Parser;
-use cosmos_adapters::{cache, config, git_ops, github, keyring};
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand, ValueEnum};
+use cosmos_adapters::{analytics, cache, config, forge, git_ops, github, keyring, preset, usage};
 use cosmos_core::context::WorkContext;
 use cosmos_core::index::CodebaseIndex;
-use cosmos_core::suggest::SuggestionEngine;
+use cosmos_core::suggest::{baseline, SuggestionEngine};
 use cosmos_engine::llm;
 use cosmos_ui::app;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
+mod compare;
+mod handoff;
+mod hooks;
+mod html_report;
+mod lsp;
+mod plain;
+mod ratchet;
+mod report;
+mod resolve;
+mod serve;
+mod summaries;
+
 #[derive(Parser, Debug)]
 #[command(
     name = "cosmos",
@@ -38,6 +50,14 @@ struct Args {
     #[arg(long)]
     github_login: bool,
 
+    /// Store a GitLab personal access token for merge request creation
+    #[arg(long)]
+    gitlab_login: bool,
+
+    /// Store Bitbucket Cloud credentials for PR creation
+    #[arg(long)]
+    bitbucket_login: bool,
+
     /// Run suggestions in non-interactive mode and print quality/gate results
     #[arg(long)]
     suggest_audit: bool,
@@ -57,6 +77,227 @@ struct Args {
     /// Stream reasoning/thinking deltas during suggestion audit (debug-only; output may be truncated)
     #[arg(long, requires = "suggest_audit")]
     suggest_stream_reasoning: bool,
+
+    /// Print the AI calls, token estimates, and projected cost of a run without calling the AI
+    #[arg(long, conflicts_with = "suggest_audit")]
+    dry_run: bool,
+
+    /// In dry-run mode, also plan applying suggestion N from the last recorded suggestion run
+    #[arg(long, value_name = "N", requires = "dry_run")]
+    dry_run_apply: Option<usize>,
+
+    /// Also show findings listed in .cosmos/baseline.json (hidden by default)
+    #[arg(long)]
+    show_baselined: bool,
+
+    /// Review an open GitHub pull request (number or URL) and post findings as review comments
+    #[arg(long, value_name = "N|URL", conflicts_with_all = ["suggest_audit", "dry_run"])]
+    review_pr: Option<github::PrReference>,
+
+    #[command(subcommand)]
+    command: Option<CliCommand>,
+}
+
+#[derive(Subcommand, Debug)]
+enum CliCommand {
+    /// Opt-in usage analytics for this repository (off by default)
+    Analytics {
+        #[command(subcommand)]
+        action: AnalyticsAction,
+    },
+    /// Report AI token usage and cost by day, model, and operation
+    Usage {
+        /// Output format
+        #[arg(long, value_enum, default_value_t = UsageFormat::Table)]
+        format: UsageFormat,
+        /// Only include usage from this day on (YYYY-MM-DD, UTC)
+        #[arg(long, value_name = "YYYY-MM-DD", value_parser = usage::parse_day)]
+        since: Option<usage::NaiveDate>,
+    },
+    /// Show the code health score of recent suggestion runs and how it is trending
+    Stats {
+        /// Output format
+        #[arg(long, value_enum, default_value_t = StatsFormat::Table)]
+        format: StatsFormat,
+        /// Number of most recent scored runs to include
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+    },
+    /// Trust this repository to run its own quick checks, tests, and pre-commit hooks
+    Trust,
+    /// Return this repository to restricted mode (none of its commands run)
+    Untrust,
+    /// Serve the engine over JSON-RPC for editor integrations (stdio by default)
+    Serve {
+        /// Listen on this unix socket instead of stdio
+        #[arg(long, value_name = "PATH")]
+        socket: Option<PathBuf>,
+    },
+    /// Generate suggestions once and print them, or publish them for CI
+    Suggest {
+        /// Where to report: stdout, or a GitHub check run with inline annotations
+        #[arg(long, value_enum, default_value_t = report::SuggestReport::Text)]
+        report: report::SuggestReport,
+        /// Directory for the HTML bundle (with --report html)
+        #[arg(long, value_name = "DIR", default_value = "cosmos-report")]
+        output: PathBuf,
+        /// Exit nonzero only on new high-priority findings in changed files, judged against
+        /// the base branch's .cosmos/baseline.json
+        #[arg(long)]
+        ratchet: bool,
+        /// Base revision for --ratchet (default: where HEAD forked from the main branch)
+        #[arg(long, value_name = "REV", requires = "ratchet")]
+        base: Option<String>,
+    },
+    /// Language server on stdio: suggestions as diagnostics, fixes as code actions
+    Lsp,
+    /// Model Context Protocol server on stdio exposing the index to other AI agents
+    Mcp,
+    /// Review the changes in a commit range and fail on serious findings (run by the pre-push hook)
+    Review {
+        /// Start of the range (default: where --head forked from the main branch)
+        #[arg(long, value_name = "REV")]
+        base: Option<String>,
+        /// End of the range
+        #[arg(long, value_name = "REV", default_value = "HEAD")]
+        head: String,
+        /// Lowest finding severity that fails the review
+        #[arg(long, value_enum, default_value_t = hooks::BlockOn::Critical)]
+        block_on: hooks::BlockOn,
+    },
+    /// Propose resolutions for the merge conflicts in the working tree, checked for syntax and
+    /// against the quick checks, and accept or reject each one
+    Resolve {
+        /// Accept every proposal that passed the gates without asking
+        #[arg(long)]
+        yes: bool,
+    },
+    /// Install or remove the pre-push hook that runs `cosmos review`
+    Hooks {
+        #[command(subcommand)]
+        action: HooksAction,
+    },
+    /// Compare two recorded suggestion runs: new, resolved (with the commits that fixed them),
+    /// and persisting findings. Without arguments, list the recorded runs.
+    Compare {
+        /// Earlier run: a run id prefix, `latest`, or `latest~N`
+        #[arg(value_name = "RUN_A")]
+        earlier: Option<String>,
+        /// Later run (default: latest)
+        #[arg(value_name = "RUN_B")]
+        later: Option<String>,
+    },
+    /// Write a markdown or HTML report of suggestions for a reviewer who doesn't run Cosmos
+    Export {
+        /// Suggestions to include, numbered as in the last recorded run (default: all)
+        #[arg(value_name = "N")]
+        numbers: Vec<usize>,
+        /// Report format
+        #[arg(long, value_enum, default_value_t = handoff::ExportFormat::Markdown)]
+        format: handoff::ExportFormat,
+        /// Write the report here instead of stdout
+        #[arg(long, short, value_name = "PATH")]
+        output: Option<PathBuf>,
+    },
+    /// Check cached directory summaries for staleness and accuracy, and regenerate weak ones
+    Summaries {
+        #[command(subcommand)]
+        action: SummariesAction,
+    },
+    /// Have the AI write every directory summary ahead of the first session
+    Warm {
+        /// Submit the requests as one provider batch job: cheaper, but it can take hours
+        #[arg(long)]
+        batch: bool,
+        /// Seconds between batch status checks
+        #[arg(long, value_name = "SECS", default_value_t = 30, requires = "batch")]
+        poll_secs: u64,
+    },
+    /// Install an organization's preset bundle (policy, prompts, model routing, budgets)
+    Preset {
+        #[command(subcommand)]
+        action: PresetAction,
+    },
+    /// Record existing findings so later runs only show regressions
+    Baseline {
+        #[command(subcommand)]
+        action: BaselineAction,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum BaselineAction {
+    /// Add the last suggestion run's findings, plus current dead-code, offline, and plugin
+    /// findings, to .cosmos/baseline.json
+    Create,
+}
+
+#[derive(Subcommand, Debug)]
+enum SummariesAction {
+    /// Flag stale summaries, score a sample against the code, and offer to regenerate
+    Audit {
+        /// Number of current summaries to score with AI
+        #[arg(long, default_value_t = 10)]
+        sample: usize,
+        /// Regenerate flagged summaries without asking
+        #[arg(long)]
+        yes: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum PresetAction {
+    /// Fetch a bundle, show the changes to .cosmos/, and install it once confirmed
+    Apply {
+        /// Bundle URL, git repository (git+URL[#branch-or-tag]), or local file
+        source: String,
+        /// Expected SHA-256 of the bundle file (required for URLs and git repositories)
+        #[arg(long, value_name = "HEX")]
+        sha256: Option<String>,
+        /// Install without asking for confirmation
+        #[arg(long)]
+        yes: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum HooksAction {
+    /// Write a pre-push hook that reviews pushed commits (COSMOS_SKIP=1 skips it)
+    Install {
+        /// Lowest finding severity that blocks the push
+        #[arg(long, value_enum, default_value_t = hooks::BlockOn::Critical)]
+        block_on: hooks::BlockOn,
+        /// Replace an existing pre-push hook that Cosmos didn't install
+        #[arg(long)]
+        force: bool,
+    },
+    /// Remove the pre-push hook installed by Cosmos
+    Uninstall,
+}
+
+#[derive(Subcommand, Debug)]
+enum AnalyticsAction {
+    /// Show the analytics mode and a summary of recorded events
+    Show,
+    /// Record counts and pass rates locally under .cosmos/v2 only
+    Local,
+    /// Record locally and also send events to COSMOS_ANALYTICS_ENDPOINT
+    Share,
+    /// Stop recording analytics for this repository
+    Off,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum UsageFormat {
+    Table,
+    Json,
+    Csv,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum StatsFormat {
+    Table,
+    Json,
 }
 
 #[tokio::main]
@@ -73,7 +314,91 @@ async fn main() -> Result<()> {
         return github_login().await;
     }
 
+    // Handle --gitlab-login / --bitbucket-login flags
+    if args.gitlab_login || args.bitbucket_login {
+        let kind = if args.gitlab_login {
+            forge::ForgeKind::GitLab
+        } else {
+            forge::ForgeKind::Bitbucket
+        };
+        return forge::setup_token_interactive(kind).map_err(|e| anyhow::anyhow!(e));
+    }
+
     let path = args.path.canonicalize()?;
+    // A baseline is built from everything found, so creating one sees the full set too. The
+    // ratchet judges findings against the base branch's baseline instead of the local one.
+    if args.show_baselined
+        || matches!(
+            args.command,
+            Some(CliCommand::Baseline { .. } | CliCommand::Suggest { ratchet: true, .. })
+        )
+    {
+        std::env::set_var(baseline::SHOW_BASELINED_ENV, "1");
+    }
+
+    match args.command {
+        Some(CliCommand::Analytics { action }) => return run_analytics_command(&path, action),
+        Some(CliCommand::Usage { format, since }) => {
+            return run_usage_command(&path, format, since)
+        }
+        Some(CliCommand::Stats { format, limit }) => {
+            return run_stats_command(&path, format, limit)
+        }
+        Some(CliCommand::Compare { earlier, later }) => {
+            return compare::run(&path, earlier.as_deref(), later.as_deref())
+        }
+        Some(CliCommand::Trust) => return set_workspace_trust(&path, true),
+        Some(CliCommand::Untrust) => return set_workspace_trust(&path, false),
+        Some(CliCommand::Hooks { action }) => {
+            return match action {
+                HooksAction::Install { block_on, force } => hooks::install(&path, block_on, force),
+                HooksAction::Uninstall => hooks::uninstall(&path),
+            }
+        }
+        Some(CliCommand::Preset {
+            action:
+                PresetAction::Apply {
+                    source,
+                    sha256,
+                    yes,
+                },
+        }) => {
+            return preset::apply(
+                &path,
+                &source,
+                sha256.as_deref(),
+                yes,
+                validate_repo_settings,
+            )
+            .await
+        }
+        Some(CliCommand::Review {
+            base,
+            head,
+            block_on,
+        }) => {
+            init_prompt_templates(&path)?;
+            init_model_routing(&path)?;
+            init_custom_tools(&path)?;
+            return hooks::review(&path, base.as_deref(), &head, block_on).await;
+        }
+        Some(CliCommand::Resolve { yes }) => {
+            init_prompt_templates(&path)?;
+            init_model_routing(&path)?;
+            return resolve::run(&path, yes).await;
+        }
+        Some(
+            CliCommand::Serve { .. }
+            | CliCommand::Suggest { .. }
+            | CliCommand::Export { .. }
+            | CliCommand::Summaries { .. }
+            | CliCommand::Warm { .. }
+            | CliCommand::Baseline { .. }
+            | CliCommand::Lsp
+            | CliCommand::Mcp,
+        )
+        | None => {}
+    }
 
     // Initialize cache
     let cache_manager = cache::Cache::new(&path);
@@ -81,6 +406,26 @@ async fn main() -> Result<()> {
     // Initialize index (fast, synchronous)
     let index = init_index(&path, &cache_manager)?;
     let context = init_context(&path)?;
+    init_prompt_templates(&path)?;
+    init_model_routing(&path)?;
+    init_custom_tools(&path)?;
+    baseline::Baseline::load(&path)?;
+    init_keybindings()?;
+
+    if let Some(CliCommand::Summaries {
+        action: SummariesAction::Audit { sample, yes },
+    }) = args.command
+    {
+        return summaries::audit(&path, &index, sample, yes).await;
+    }
+    if let Some(CliCommand::Warm { batch, poll_secs }) = args.command {
+        let poll = std::time::Duration::from_secs(poll_secs.max(1));
+        return summaries::warm(&path, &index, batch, poll).await;
+    }
+
+    if args.dry_run {
+        return run_dry_run(&path, &cache_manager, args.dry_run_apply);
+    }
 
     if args.suggest_audit {
         if args.suggest_stream_reasoning {
@@ -99,11 +444,115 @@ async fn main() -> Result<()> {
         .await;
     }
 
-    // Create suggestion engine (LLM suggestions generated on demand)
-    let suggestions = SuggestionEngine::new(index.clone());
+    // Create suggestion engine (LLM suggestions generated on demand; dead code, and offline
+    // heuristics without an API key, found up front)
+    let mut suggestions = SuggestionEngine::new(index.clone());
+    suggestions.refresh_static_suggestions(!llm::is_available());
+    run_analyzer_plugins(&path, &mut suggestions)?;
+    if suggestions.suppressed_count() > 0 {
+        eprintln!(
+            "  Suppressed {} findings via cosmos-ignore comments",
+            suggestions.suppressed_count()
+        );
+    }
+    if suggestions.baselined_count() > 0 {
+        eprintln!(
+            "  Hiding {} baselined findings (--show-baselined to include them)",
+            suggestions.baselined_count()
+        );
+    }
+
+    if let Some(CliCommand::Baseline {
+        action: BaselineAction::Create,
+    }) = args.command
+    {
+        return create_baseline(&path, &cache_manager, &suggestions);
+    }
+
+    if let Some(CliCommand::Serve { socket }) = args.command {
+        let session = serve::Session::new(path, index, context, suggestions);
+        return serve::run(session, socket).await;
+    }
+    if let Some(CliCommand::Export {
+        numbers,
+        format,
+        output,
+    }) = args.command
+    {
+        return run_export(
+            &cache_manager,
+            suggestions,
+            &numbers,
+            format,
+            output.as_deref(),
+        );
+    }
+    if let Some(CliCommand::Suggest {
+        report,
+        output,
+        ratchet,
+        base,
+    }) = args.command
+    {
+        let ratchet = ratchet.then_some(ratchet::Ratchet { base });
+        return report::run(
+            &path,
+            &index,
+            &context,
+            suggestions,
+            report,
+            &output,
+            ratchet.as_ref(),
+        )
+        .await;
+    }
+    if let Some(CliCommand::Lsp) = args.command {
+        let session = serve::Session::new(path, index, context, suggestions);
+        return lsp::run(session).await;
+    }
+    if let Some(CliCommand::Mcp) = args.command {
+        let server = cosmos_engine::mcp::McpServer::new(path, index, context, suggestions);
+        return run_mcp_server(server).await;
+    }
+
+    // Piped output or a dumb terminal can't host the TUI; print line-based output instead.
+    if !plain::tui_supported() {
+        if args.review_pr.is_some() {
+            anyhow::bail!("--review-pr needs an interactive terminal");
+        }
+        return plain::run(&path, &index, &context, suggestions).await;
+    }
 
     // Run TUI with background LLM tasks
-    app::run_tui(index, suggestions, context, cache_manager, path).await
+    app::run_tui(
+        index,
+        suggestions,
+        context,
+        cache_manager,
+        path,
+        args.review_pr,
+    )
+    .await
+}
+
+/// Speak MCP over stdio, one JSON-RPC message per line, until stdin closes.
+async fn run_mcp_server(mut server: cosmos_engine::mcp::McpServer) -> Result<()> {
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt};
+
+    let mut lines = tokio::io::BufReader::new(tokio::io::stdin()).lines();
+    let mut stdout = tokio::io::stdout();
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(response) = server.handle_message(&line).await {
+            stdout
+                .write_all(format!("{}\n", response).as_bytes())
+                .await?;
+            stdout.flush().await?;
+        }
+    }
+    Ok(())
 }
 
 async fn run_suggestion_audit(
@@ -345,6 +794,54 @@ async fn run_suggestion_audit(
                 best.gate.fail_reasons.join("; ")
             }
         );
+        println!(
+            "  prompt_overrides={}",
+            if best.diagnostics.prompt_overrides.is_empty() {
+                "none".to_string()
+            } else {
+                best.diagnostics.prompt_overrides.join(",")
+            }
+        );
+        println!(
+            "  retrieval_hits={} sent_snippets={} sent_bytes={}/{} ({:.0}% of budget) retrieval_ms={} redacted_secrets={} suppressed={} baselined={} redetected={}",
+            best.diagnostics.retrieval_hit_count,
+            best.diagnostics.sent_snippet_count,
+            best.diagnostics.sent_bytes,
+            best.diagnostics.sent_budget_bytes,
+            best.diagnostics.sent_utilization * 100.0,
+            best.diagnostics.evidence_pack_ms,
+            best.diagnostics.redacted_secret_count,
+            best.diagnostics.suppressed_count,
+            best.diagnostics.baselined_count,
+            best.diagnostics.redetected_count
+        );
+        if let Some(peak) = best
+            .diagnostics
+            .context_budgets
+            .iter()
+            .max_by_key(|budget| budget.prompt_tokens)
+        {
+            println!(
+                "  llm_requests={} peak_prompt_tokens~{} peak_context_used={:.0}% of {}",
+                best.diagnostics.context_budgets.len(),
+                peak.prompt_tokens,
+                peak.fraction() * 100.0,
+                peak.context_window
+            );
+        }
+        let cache = &best.diagnostics.response_cache;
+        if !cache.is_empty() {
+            println!(
+                "  response_cache hits={} misses={} saved=${:.4}",
+                cache.hits, cache.misses, cache.saved_usd
+            );
+        }
+        for downgrade in &best.diagnostics.model_downgrades {
+            println!("  model_downgrade: {}", downgrade.message());
+        }
+        for shard in &best.diagnostics.shards {
+            println!("  shard: {}", shard.summary());
+        }
         println!(
             "  reasoning_output_requested={} (set COSMOS_INCLUDE_REASONING=1 to request provider rationale in traces)",
             include_reasoning_output_from_env()
@@ -394,6 +891,419 @@ async fn run_suggestion_audit(
     Ok(())
 }
 
+fn set_workspace_trust(path: &Path, trusted: bool) -> Result<()> {
+    let mut config = config::Config::load();
+    config.set_workspace_trust(path, trusted);
+    config.save().map_err(|e| anyhow::anyhow!(e))?;
+    if trusted {
+        println!(
+            "Trusted {}: quick checks, tests, and pre-commit hooks will run.",
+            path.display()
+        );
+    } else {
+        println!(
+            "{} is in restricted mode: none of its commands will run.",
+            path.display()
+        );
+    }
+    Ok(())
+}
+
+/// Recent analytics events summarized by `cosmos analytics show`.
+const ANALYTICS_SHOW_WINDOW: usize = 5000;
+
+fn run_analytics_command(path: &Path, action: AnalyticsAction) -> Result<()> {
+    let fingerprint = analytics::repo_fingerprint(path);
+    let mut consent = analytics::AnalyticsConsent::load();
+    let mode = match action {
+        AnalyticsAction::Show => {
+            let mode = consent.mode(&fingerprint);
+            println!("Analytics for this repo: {}", mode.label());
+            println!("  repo fingerprint: {}", fingerprint);
+            let events = cache::Cache::new(path)
+                .load_recent_analytics_events(ANALYTICS_SHOW_WINDOW)
+                .unwrap_or_default();
+            if events.is_empty() {
+                if mode == analytics::AnalyticsMode::Off {
+                    println!("\nNothing recorded. Run `cosmos analytics local` to count runs and pass rates on this machine only.");
+                } else {
+                    println!("\nNo events recorded yet.");
+                }
+                return Ok(());
+            }
+            let summary = analytics::summarize(&events);
+            println!("\n{} event(s) recorded:", summary.total_events);
+            for (event, counts) in &summary.events {
+                match counts.pass_rate() {
+                    Some(rate) => println!(
+                        "  {:<14} {:>5} run(s)  {:>5.1}% passed",
+                        event,
+                        counts.runs,
+                        rate * 100.0
+                    ),
+                    None => println!("  {:<14} {:>5} run(s)", event, counts.runs),
+                }
+            }
+            if !summary.failed_gates.is_empty() {
+                println!("\nFailed gates:");
+                for (gate, count) in &summary.failed_gates {
+                    println!("  {:<20} {:>5}", gate, count);
+                }
+            }
+            return Ok(());
+        }
+        AnalyticsAction::Local => analytics::AnalyticsMode::Local,
+        AnalyticsAction::Share => analytics::AnalyticsMode::Share,
+        AnalyticsAction::Off => analytics::AnalyticsMode::Off,
+    };
+    consent.set_mode(&fingerprint, mode);
+    consent.save().map_err(|e| anyhow::anyhow!(e))?;
+    match mode {
+        analytics::AnalyticsMode::Off => {
+            println!("Analytics off for this repo. Recorded events stay in .cosmos/v2/analytics.jsonl until you delete them.")
+        }
+        analytics::AnalyticsMode::Local => {
+            println!("Analytics on for this repo, local only: events are written to .cosmos/v2/analytics.jsonl and never sent.")
+        }
+        analytics::AnalyticsMode::Share => {
+            println!("Analytics on for this repo and shared: events are also sent to $COSMOS_ANALYTICS_ENDPOINT when set.");
+            println!("Events contain counts, pass/fail, and gate names only; never code, paths, or prompts.");
+        }
+    }
+    Ok(())
+}
+
+fn run_usage_command(
+    path: &Path,
+    format: UsageFormat,
+    since: Option<usage::NaiveDate>,
+) -> Result<()> {
+    let records = cache::Cache::new(path).load_usage_records()?;
+    let rows = usage::build_report(&records, since);
+    match format {
+        UsageFormat::Json => println!("{}", usage::report_json(&rows)),
+        UsageFormat::Csv => print!("{}", usage::report_csv(&rows)),
+        UsageFormat::Table => {
+            if rows.is_empty() {
+                println!("No AI usage recorded for this repo yet.");
+                return Ok(());
+            }
+            println!(
+                "{:<10}  {:<24}  {:<11}  {:>5}  {:>10}  {:>10}",
+                "day", "model", "operation", "calls", "tokens", "cost"
+            );
+            let total = usage::report_total(&rows);
+            for row in rows.iter().chain(std::iter::once(&total)) {
+                let day = if row.model == "total" {
+                    String::new()
+                } else {
+                    row.day.to_string()
+                };
+                println!(
+                    "{:<10}  {}  {:<11}  {:>5}  {:>10}  {:>10}",
+                    day,
+                    cosmos_adapters::util::pad_to_width(&row.model, 24),
+                    row.operation,
+                    row.calls,
+                    row.total_tokens,
+                    format!("${:.4}", row.cost_usd)
+                );
+            }
+            if total.calls_without_cost > 0 {
+                println!(
+                    "\n{} call(s) had no provider-reported cost and are counted as $0.",
+                    total.calls_without_cost
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+fn run_stats_command(path: &Path, format: StatsFormat, limit: usize) -> Result<()> {
+    let mut runs: Vec<_> = cache::Cache::new(path)
+        .load_recent_suggestion_run_audit(usize::MAX)?
+        .into_iter()
+        .filter_map(|run| run.health.map(|health| (run, health)))
+        .collect();
+    runs.drain(..runs.len().saturating_sub(limit));
+    match format {
+        StatsFormat::Json => {
+            let rows: Vec<_> = runs
+                .iter()
+                .map(|(run, health)| {
+                    serde_json::json!({
+                        "timestamp": run.timestamp,
+                        "run_id": run.run_id,
+                        "git_head": run.git_head,
+                        "suggestion_count": run.suggestion_count,
+                        "health": health,
+                    })
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&rows)?);
+        }
+        StatsFormat::Table => {
+            if runs.is_empty() {
+                println!("No scored suggestion runs yet. Run Cosmos or `cosmos suggest` first.");
+                return Ok(());
+            }
+            println!(
+                "{:<16}  {:<8}  {:>5}  {:>8}  {:>8}  {:>8}",
+                "run", "commit", "score", "findings", "hotspots", "coverage"
+            );
+            for (run, health) in &runs {
+                let commit = run.git_head.as_deref().unwrap_or("-");
+                println!(
+                    "{:<16}  {:<8}  {:>5}  {:>8.2}  {:>8}  {:>8}",
+                    run.timestamp.format("%Y-%m-%d %H:%M"),
+                    &commit[..commit.len().min(8)],
+                    health.score,
+                    health.finding_density,
+                    health.hotspots,
+                    health
+                        .coverage
+                        .map(|pct| format!("{:.1}%", pct))
+                        .unwrap_or_else(|| "-".to_string())
+                );
+            }
+            let scores: Vec<u8> = runs.iter().map(|(_, health)| health.score).collect();
+            println!(
+                "\nTrend: {}  (findings are priority-weighted per 1k lines)",
+                cosmos_core::health::sparkline(&scores)
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Export suggestions from the last recorded run (plus static findings) as a handoff report.
+fn run_export(
+    cache_manager: &cache::Cache,
+    mut suggestions: SuggestionEngine,
+    numbers: &[usize],
+    format: handoff::ExportFormat,
+    output: Option<&Path>,
+) -> Result<()> {
+    let last_run = cache_manager
+        .load_recent_suggestion_run_audit(1)
+        .unwrap_or_default()
+        .pop();
+    let recorded = last_run.map(|run| run.suggestions).unwrap_or_default();
+    let ids = numbers
+        .iter()
+        .map(|&number| {
+            number
+                .checked_sub(1)
+                .and_then(|idx| recorded.get(idx))
+                .map(|suggestion| suggestion.id)
+                .ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "Suggestion {} not found; the last run has {} suggestion(s).",
+                        number,
+                        recorded.len()
+                    )
+                })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    suggestions.replace_llm_suggestions(recorded);
+
+    let report = handoff::render(&suggestions, &ids, format)?;
+    match output {
+        Some(output) => {
+            std::fs::write(output, report)
+                .with_context(|| format!("Failed to write {}", output.display()))?;
+            eprintln!("Wrote {}", output.display());
+        }
+        None => print!("{}", report),
+    }
+    Ok(())
+}
+
+/// Merge the last recorded run and the current deterministic findings into the baseline.
+/// Existing entries are kept: the recorded run already had them hidden.
+fn create_baseline(
+    path: &Path,
+    cache_manager: &cache::Cache,
+    suggestions: &SuggestionEngine,
+) -> Result<()> {
+    let recorded = cache_manager
+        .load_recent_suggestion_run_audit(1)
+        .unwrap_or_default()
+        .pop()
+        .map(|run| run.suggestions)
+        .unwrap_or_default();
+    if recorded.is_empty() {
+        eprintln!(
+            "  No recorded suggestion run; run `cosmos suggest` first to include AI findings."
+        );
+    }
+    let current = baseline::Baseline::from_suggestions(
+        recorded
+            .iter()
+            .chain(suggestions.suggestions.iter().filter(|s| !s.applied)),
+    );
+    let previous = baseline::Baseline::load(path)?
+        .map(|previous| previous.entries)
+        .unwrap_or_default();
+    let added = current
+        .entries
+        .iter()
+        .filter(|entry| !previous.contains(entry))
+        .count();
+    let mut merged = current;
+    for entry in previous {
+        if !merged.entries.contains(&entry) {
+            merged.entries.push(entry);
+        }
+    }
+    merged
+        .entries
+        .sort_by(|a, b| (&a.file, a.line).cmp(&(&b.file, b.line)));
+    let written = merged.save(path)?;
+    println!(
+        "Baselined {} findings ({} new) in {}",
+        merged.entries.len(),
+        added,
+        written.display()
+    );
+    Ok(())
+}
+
+/// Recent pipeline metrics used to price dry-run estimates.
+const DRY_RUN_METRIC_WINDOW: usize = 200;
+
+fn run_dry_run(
+    path: &Path,
+    cache_manager: &cache::Cache,
+    apply_choice: Option<usize>,
+) -> Result<()> {
+    let rates = llm::dry_run::ObservedCostRates::from_pipeline_metrics(
+        &cache_manager
+            .load_recent_pipeline_metrics(DRY_RUN_METRIC_WINDOW)
+            .unwrap_or_default(),
+    );
+    let repo_memory = cache_manager.load_repo_memory().to_prompt_context(12, 900);
+    // Mirror the interactive run: one bounded retry when a pass finds nothing.
+    let gate_config = llm::SuggestionQualityGateConfig {
+        min_final_count: 1,
+        max_attempts: 2,
+        ..Default::default()
+    };
+    let index = cache_manager.load_index_cache(path);
+    let suggest_calls = llm::plan_gated_suggestion_run(
+        path,
+        index.as_ref(),
+        Some(repo_memory.as_str()).filter(|memory| !memory.trim().is_empty()),
+        &gate_config,
+    );
+
+    println!("Dry run: no AI calls will be made.");
+    println!("\nSuggestions:");
+    print_planned_calls(&suggest_calls);
+    print_projection(&suggest_calls, &rates, "suggest", None);
+
+    let Some(choice) = apply_choice else {
+        println!("\nPass --dry-run-apply N to also plan applying a suggestion from the last run.");
+        return Ok(());
+    };
+    let last_run = cache_manager
+        .load_recent_suggestion_run_audit(1)
+        .unwrap_or_default()
+        .pop()
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "No recorded suggestion run found. Run Cosmos once to generate suggestions."
+            )
+        })?;
+    let suggestion = choice
+        .checked_sub(1)
+        .and_then(|idx| last_run.suggestions.get(idx))
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "Suggestion {} not found; the last run has {} suggestion(s).",
+                choice,
+                last_run.suggestions.len()
+            )
+        })?;
+    let mut config =
+        llm::ImplementationHarnessConfig::interactive_strict().with_repo_config(path)?;
+    if !config::Config::load().is_workspace_trusted(path) {
+        config = config.for_untrusted_workspace();
+    }
+    let plan = llm::plan_harness_apply(path, suggestion, &config);
+
+    println!("\nApply #{}: {}", choice, plan.summary);
+    println!("  would write:");
+    for file in &plan.files_written {
+        println!("    {}", file.display());
+    }
+    println!("  gates:");
+    for gate in &plan.gates {
+        println!(
+            "    {:<13} {:<12} {}",
+            gate.gate,
+            gate.outcome.label(),
+            gate.detail
+        );
+    }
+    print_planned_calls(&plan.calls);
+    print_projection(&plan.calls, &rates, "apply", Some(plan.max_total_cost_usd));
+    println!(
+        "  harness stops after {}s or ${:.3}, whichever comes first",
+        plan.max_total_ms / 1000,
+        plan.max_total_cost_usd
+    );
+    Ok(())
+}
+
+fn print_planned_calls(calls: &[llm::dry_run::PlannedLlmCall]) {
+    for call in calls {
+        println!(
+            "  {:<7} {:<28} model={} requests<={} prompt~{} tok/request{}",
+            call.stage,
+            call.label,
+            call.model,
+            call.max_requests,
+            call.prompt_tokens_per_request,
+            if call.conditional {
+                " (only if needed)"
+            } else {
+                ""
+            }
+        );
+    }
+}
+
+fn print_projection(
+    calls: &[llm::dry_run::PlannedLlmCall],
+    rates: &llm::dry_run::ObservedCostRates,
+    stage: &str,
+    cost_cap: Option<f64>,
+) {
+    let (expected, worst) = llm::dry_run::planned_token_range(calls);
+    println!(
+        "  tokens: ~{} expected, ~{} worst case (packed prompts only; tool output adds more)",
+        expected, worst
+    );
+    let cap = |cost: f64| cost_cap.map(|cap| cost.min(cap)).unwrap_or(cost);
+    match (rates.project(expected), rates.project(worst)) {
+        (Some(expected_cost), Some(worst_cost)) => println!(
+            "  projected cost: ${:.4} expected, ${:.4} worst case (rate from {} recorded run(s))",
+            cap(expected_cost),
+            cap(worst_cost),
+            rates.samples
+        ),
+        _ => println!("  projected cost: unknown until a priced run has been recorded"),
+    }
+    if let Some((count, avg)) = rates.avg_cost_by_stage.get(stage) {
+        println!(
+            "  recorded average: ${:.4} per {} run ({} run(s))",
+            avg, stage, count
+        );
+    }
+}
+
 fn include_reasoning_output_from_env() -> bool {
     std::env::var("COSMOS_INCLUDE_REASONING")
         .ok()
@@ -426,6 +1336,12 @@ fn init_index(path: &Path, cache_manager: &cache::Cache) -> Result<CodebaseIndex
                 eprintln!("    ({} more)", stats.skipped_files - 3);
             }
         }
+        if stats.excluded_files > 0 {
+            eprintln!(
+                "  Excluded {} files by .cosmosignore / [index] globs",
+                stats.excluded_files
+            );
+        }
         return Ok(index);
     }
 
@@ -450,10 +1366,104 @@ fn init_index(path: &Path, cache_manager: &cache::Cache) -> Result<CodebaseIndex
             eprintln!("    ({} more)", stats.skipped_files - 3);
         }
     }
+    if stats.excluded_files > 0 {
+        eprintln!(
+            "  Excluded {} files by .cosmosignore / [index] globs",
+            stats.excluded_files
+        );
+    }
 
     Ok(index)
 }
 
+/// Load `.cosmos/prompts/` overrides, failing fast on invalid templates
+fn init_prompt_templates(path: &Path) -> Result<()> {
+    let overridden = llm::prompt_templates::install(path)?;
+    if !overridden.is_empty() {
+        eprintln!("  Using custom prompt templates: {}", overridden.join(", "));
+    }
+    Ok(())
+}
+
+/// Everything Cosmos reads from `.cosmos/` must load cleanly under `repo_root`.
+fn validate_repo_settings(repo_root: &Path) -> Result<()> {
+    llm::SuggestionPolicy::load(repo_root)?;
+    llm::prompt_templates::PromptTemplateSet::load(repo_root)?;
+    llm::routing::load_repo_model_routing(repo_root)?;
+    llm::ImplementationHarnessConfig::interactive_strict().with_repo_config(repo_root)?;
+    cosmos_engine::plugins::load_plugins(repo_root)?;
+    llm::custom_tools::load_repo_tools(repo_root)?;
+    baseline::Baseline::load(repo_root)?;
+    Ok(())
+}
+
+/// Replace plugin suggestions with a fresh run of the repo's `[[plugins]]`
+pub(crate) fn run_analyzer_plugins(
+    repo_root: &Path,
+    suggestions: &mut SuggestionEngine,
+) -> Result<()> {
+    let run = cosmos_engine::plugins::run_configured_plugins(repo_root, &suggestions.index)?;
+    for report in &run.reports {
+        match &report.error {
+            Some(error) => eprintln!("  Plugin {}: {}", report.name, error),
+            None if report.dropped > 0 => eprintln!(
+                "  Plugin {}: {} suggestions ({} outside the index dropped)",
+                report.name, report.suggestions, report.dropped
+            ),
+            None => eprintln!(
+                "  Plugin {}: {} suggestions",
+                report.name, report.suggestions
+            ),
+        }
+    }
+    if !run.reports.is_empty() {
+        suggestions.replace_plugin_suggestions(run.suggestions);
+    }
+    Ok(())
+}
+
+/// Offer the repo's `[[tools]]` to the agent; untrusted workspaces only get a note
+fn init_custom_tools(repo_root: &Path) -> Result<()> {
+    let trusted = config::Config::load().is_workspace_trusted(repo_root);
+    let installed = llm::custom_tools::install(repo_root, trusted)?;
+    if !installed.active.is_empty() {
+        eprintln!("  Using custom tools: {}", installed.active.join(", "));
+    }
+    if !installed.untrusted.is_empty() {
+        eprintln!(
+            "  Custom tools not loaded (workspace not trusted): {}",
+            installed.untrusted.join(", ")
+        );
+    }
+    Ok(())
+}
+
+fn init_model_routing(repo_root: &Path) -> Result<()> {
+    let config = config::Config::load();
+    let routed = llm::routing::install_for_repo(&config, repo_root)?;
+    llm::spend::install(&config)?;
+    llm::response_cache::install(&config, repo_root)?;
+    llm::analysis::sharding::install(&config)?;
+    if !routed.is_empty() {
+        eprintln!("  Using model routing for: {}", routed.join(", "));
+    }
+    Ok(())
+}
+
+/// Reject a config file with bad `keybindings` before the TUI starts
+fn init_keybindings() -> Result<()> {
+    let config = config::Config::load();
+    let (_, problems) = cosmos_ui::ui::keymap::Keymap::from_config(config.keybindings());
+    if !problems.is_empty() {
+        anyhow::bail!(
+            "Invalid keybindings in {}:\n  {}",
+            config::Config::config_location(),
+            problems.join("\n  ")
+        );
+    }
+    Ok(())
+}
+
 /// Initialize the work context
 fn init_context(path: &Path) -> Result<WorkContext> {
     eprint!("  Loading context...");
@@ -0,0 +1,237 @@
+//! Plain-text mode for pipes and dumb terminals.
+//!
+//! Used instead of the TUI when stdout is not a terminal (or `TERM=dumb`). Suggestions and
+//! previews are printed as lines; when stdin and stdout are both terminals, a line prompt on
+//! stderr lets the user open previews one at a time.
+
+use anyhow::Result;
+use cosmos_core::context::WorkContext;
+use cosmos_core::index::CodebaseIndex;
+use cosmos_core::suggest::{Suggestion, SuggestionEngine};
+use cosmos_engine::llm;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::Path;
+
+/// Whether the full-screen TUI can run: stdout must be a terminal that understands escapes.
+pub fn tui_supported() -> bool {
+    io::stdout().is_terminal() && std::env::var("TERM").map_or(true, |term| term != "dumb")
+}
+
+pub async fn run(
+    path: &Path,
+    index: &CodebaseIndex,
+    context: &WorkContext,
+    mut suggestions: SuggestionEngine,
+) -> Result<()> {
+    println!(
+        "Cosmos - {} on {} ({} files indexed)",
+        path.display(),
+        context.branch,
+        index.files.len()
+    );
+
+    if let Err(e) = generate(path, index, context, &mut suggestions).await {
+        println!("AI suggestions failed: {}", e);
+    }
+
+    let active = suggestions.active_suggestions();
+    if active.is_empty() {
+        println!("\nNo suggestions.");
+        return Ok(());
+    }
+    println!();
+    for (idx, suggestion) in active.iter().enumerate() {
+        print_suggestion(idx + 1, suggestion);
+    }
+
+    if !(io::stdin().is_terminal() && io::stdout().is_terminal()) {
+        return Ok(());
+    }
+    prompt_previews(&active)
+}
+
+/// Add grounded AI suggestions when a key is configured, then rank everything for `context`.
+///
+/// On error the offline findings are still ranked and kept.
+pub async fn generate(
+    path: &Path,
+    index: &CodebaseIndex,
+    context: &WorkContext,
+    suggestions: &mut SuggestionEngine,
+) -> Result<()> {
+    if llm::is_available() {
+        println!("Generating suggestions...");
+        let gate_config = llm::SuggestionQualityGateConfig {
+            min_final_count: 1,
+            max_attempts: 2,
+            ..Default::default()
+        };
+        match llm::run_fast_grounded_with_gate_with_progress_and_stream(
+            path,
+            index,
+            context,
+            None,
+            gate_config,
+            None,
+            |_, _, _, _| {},
+        )
+        .await
+        {
+            Ok(result) => {
+                llm::record_suggestion_run(
+                    path,
+                    index,
+                    &result.suggestions,
+                    &result.diagnostics.model,
+                    &result.diagnostics,
+                );
+                suggestions.replace_llm_suggestions(result.suggestions);
+            }
+            Err(e) => {
+                suggestions.sort_with_context(context, None);
+                return Err(e);
+            }
+        }
+    } else {
+        println!("AI is unavailable (run `cosmos --setup`); showing offline findings only.");
+    }
+    suggestions.sort_with_context(context, None);
+    Ok(())
+}
+
+pub fn print_suggestion(number: usize, suggestion: &Suggestion) {
+    print!("{}", format_suggestion(number, suggestion));
+}
+
+fn format_suggestion(number: usize, suggestion: &Suggestion) -> String {
+    let location = match suggestion.line {
+        Some(line) => format!("{}:{}", suggestion.file.display(), line),
+        None => suggestion.file.display().to_string(),
+    };
+    let mut out = format!(
+        "{}. [{}] {} ({})\n",
+        number,
+        suggestion.kind.label(),
+        suggestion.summary,
+        location
+    );
+    if let Some(detail) = suggestion
+        .detail
+        .as_deref()
+        .filter(|d| !d.trim().is_empty())
+    {
+        out.push_str(&format!("   {}\n", detail.trim()));
+    }
+    out
+}
+
+/// What the user typed at the preview prompt
+#[derive(Debug, PartialEq, Eq)]
+enum Selection {
+    Quit,
+    /// Index into the listed suggestions
+    Preview(usize),
+    Invalid,
+}
+
+fn parse_selection(answer: &str, count: usize) -> Selection {
+    let answer = answer.trim();
+    if answer.is_empty() || answer.eq_ignore_ascii_case("q") {
+        return Selection::Quit;
+    }
+    match answer.parse::<usize>() {
+        Ok(n) if (1..=count).contains(&n) => Selection::Preview(n - 1),
+        _ => Selection::Invalid,
+    }
+}
+
+/// Ask for suggestion numbers and print each preview until the user quits or input ends.
+///
+/// The prompt goes to stderr so it never mixes into the listing on stdout.
+fn prompt_previews(active: &[&Suggestion]) -> Result<()> {
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    loop {
+        eprint!("\nPreview suggestion (1-{}, Enter to quit): ", active.len());
+        io::stderr().flush()?;
+        let Some(line) = lines.next().transpose()? else {
+            return Ok(());
+        };
+        match parse_selection(&line, active.len()) {
+            Selection::Quit => return Ok(()),
+            Selection::Preview(index) => print!("{}", format_preview(active[index])),
+            Selection::Invalid => eprintln!("Enter a number from 1 to {}.", active.len()),
+        }
+    }
+}
+
+fn format_preview(suggestion: &Suggestion) -> String {
+    let preview = llm::build_fix_preview_from_validated_suggestion(suggestion);
+    let mut out = format!("\n{}\n", preview.friendly_title);
+    out.push_str(&format!("  Problem: {}\n", preview.problem_summary));
+    out.push_str(&format!("  After the fix: {}\n", preview.outcome));
+    if !preview.description.trim().is_empty() {
+        out.push_str(&format!("  Change: {}\n", preview.description));
+    }
+    out.push_str(&format!(
+        "  Scope: {} ({})\n",
+        preview.scope.label(),
+        preview.affected_areas.join(", ")
+    ));
+    if let Some(snippet) = preview.evidence_snippet.as_deref() {
+        let start = preview.evidence_line.unwrap_or(1) as usize;
+        out.push_str("  Evidence:\n");
+        for (offset, line) in snippet.lines().enumerate() {
+            out.push_str(&format!("    {:>5} | {}\n", start + offset, line));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmos_core::suggest::{Priority, SuggestionKind, SuggestionSource};
+    use std::path::PathBuf;
+
+    fn suggestion() -> Suggestion {
+        Suggestion::new(
+            SuggestionKind::BugFix,
+            Priority::High,
+            PathBuf::from("src/lib.rs"),
+            "Retry loop never backs off".to_string(),
+            SuggestionSource::LlmDeep,
+        )
+        .with_line(12)
+        .with_detail("Each retry fires immediately.".to_string())
+    }
+
+    #[test]
+    fn selection_parsing_quits_on_empty_and_rejects_bad_numbers() {
+        assert_eq!(parse_selection("", 3), Selection::Quit);
+        assert_eq!(parse_selection("  \n", 3), Selection::Quit);
+        assert_eq!(parse_selection("Q", 3), Selection::Quit);
+        assert_eq!(parse_selection(" 2 ", 3), Selection::Preview(1));
+        assert_eq!(parse_selection("0", 3), Selection::Invalid);
+        assert_eq!(parse_selection("4", 3), Selection::Invalid);
+        assert_eq!(parse_selection("-1", 3), Selection::Invalid);
+        assert_eq!(parse_selection("two", 3), Selection::Invalid);
+        assert_eq!(parse_selection("1", 0), Selection::Invalid);
+    }
+
+    #[test]
+    fn suggestions_and_previews_format_as_plain_lines() {
+        let suggestion = suggestion();
+        assert_eq!(
+            format_suggestion(1, &suggestion),
+            "1. [Fix] Retry loop never backs off (src/lib.rs:12)\n   Each retry fires immediately.\n"
+        );
+
+        let preview = format_preview(&suggestion);
+        assert!(preview.starts_with('\n'));
+        assert!(preview.contains("  Problem: "));
+        assert!(preview.contains("  After the fix: "));
+        assert!(preview.contains("  Scope: "));
+        assert!(preview.lines().all(|line| !line.contains('\u{1b}')));
+    }
+}
@@ -0,0 +1,126 @@
+//! `cosmos suggest --ratchet`: fail CI only on newly introduced issues.
+//!
+//! Findings are judged against the baseline committed on the base branch (where the PR forked
+//! from the main branch, or `--base`), not the working tree's copy, so a change can't hide its
+//! own findings by editing `.cosmos/baseline.json`. Only high-priority findings in files the
+//! change touches count; everything else is reported but never fails the run.
+
+use anyhow::Result;
+use cosmos_adapters::git_ops;
+use cosmos_core::context::WorkContext;
+use cosmos_core::suggest::baseline::{Baseline, BASELINE_PATH};
+use cosmos_core::suggest::{Priority, Suggestion};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// `--ratchet` settings for a `cosmos suggest` run.
+#[derive(Debug, Clone, Default)]
+pub struct Ratchet {
+    /// Revision to compare against; defaults to where HEAD forked from the main branch
+    pub base: Option<String>,
+}
+
+/// High-priority findings in `changed` files that `baseline` doesn't cover.
+pub fn new_findings<'a>(
+    active: &[&'a Suggestion],
+    baseline: Option<&Baseline>,
+    changed: &HashSet<PathBuf>,
+) -> Vec<&'a Suggestion> {
+    active
+        .iter()
+        .copied()
+        .filter(|suggestion| suggestion.priority == Priority::High)
+        .filter(|suggestion| changed.contains(&suggestion.file))
+        .filter(|suggestion| !baseline.is_some_and(|baseline| baseline.covers(suggestion)))
+        .collect()
+}
+
+/// Files changed since the base, committed or not.
+fn changed_files(
+    path: &Path,
+    base: Option<&str>,
+    context: &WorkContext,
+) -> Result<HashSet<PathBuf>> {
+    let mut changed: HashSet<PathBuf> = git_ops::range_changes(path, base, "HEAD")?
+        .into_iter()
+        .map(|(file, _, _)| file)
+        .collect();
+    changed.extend(context.all_changed_files().into_iter().cloned());
+    Ok(changed)
+}
+
+/// Print the ratchet verdict and fail when the change introduces new high-priority findings.
+pub fn check(
+    path: &Path,
+    ratchet: &Ratchet,
+    context: &WorkContext,
+    active: &[&Suggestion],
+) -> Result<()> {
+    let base = ratchet.base.as_deref();
+    let changed = changed_files(path, base, context)?;
+    let baseline = match git_ops::file_at_range_base(path, base, "HEAD", Path::new(BASELINE_PATH))?
+    {
+        Some(content) => Some(Baseline::parse(&content)?),
+        None => {
+            println!(
+                "Ratchet: no {} on the base branch; every high-priority finding in changed files is new",
+                BASELINE_PATH
+            );
+            None
+        }
+    };
+
+    let new = new_findings(active, baseline.as_ref(), &changed);
+    if new.is_empty() {
+        println!(
+            "Ratchet: no new high-priority findings in {} changed file(s)",
+            changed.len()
+        );
+        return Ok(());
+    }
+    println!("Ratchet: {} new high-priority finding(s):", new.len());
+    for suggestion in &new {
+        let location = match suggestion.line {
+            Some(line) => format!("{}:{}", suggestion.file.display(), line),
+            None => suggestion.file.display().to_string(),
+        };
+        println!("  {} - {}", location, suggestion.summary);
+    }
+    anyhow::bail!(
+        "{} new high-priority finding(s) in changed files",
+        new.len()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmos_core::suggest::{test_finding, SuggestionKind};
+
+    fn finding(file: &str, priority: Priority, summary: &str) -> Suggestion {
+        test_finding(SuggestionKind::BugFix, priority, file, 10, summary)
+    }
+
+    #[test]
+    fn test_new_findings_only_counts_unbaselined_high_priority_in_changed_files() {
+        let known = finding("src/db.rs", Priority::High, "Query built from user input");
+        let baseline = Baseline::from_suggestions([&known]);
+        let changed: HashSet<PathBuf> = [PathBuf::from("src/db.rs"), PathBuf::from("src/api.rs")]
+            .into_iter()
+            .collect();
+
+        let suggestions = [
+            known.clone(),
+            finding("src/api.rs", Priority::High, "Token logged on error"),
+            finding("src/api.rs", Priority::Medium, "Retry loop has no backoff"),
+            finding("src/untouched.rs", Priority::High, "Panics on empty input"),
+        ];
+        let active: Vec<&Suggestion> = suggestions.iter().collect();
+
+        let new = new_findings(&active, Some(&baseline), &changed);
+        assert_eq!(new.len(), 1);
+        assert_eq!(new[0].summary, "Token logged on error");
+
+        assert_eq!(new_findings(&active, None, &changed).len(), 2);
+    }
+}
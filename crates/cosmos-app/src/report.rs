@@ -0,0 +1,219 @@
+//! `cosmos suggest`: one-shot suggestion run for scripts and CI.
+//!
+//! Prints the ranked suggestions, with `--report github` publishes them as a GitHub check
+//! run whose annotations show inline on pull requests, or with `--report html` writes a static
+//! HTML bundle (see `html_report`). With `--ratchet` the run then fails only on new
+//! high-priority findings in changed files (see `ratchet`).
+
+use crate::ratchet::{self, Ratchet};
+use crate::{html_report, plain};
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use cosmos_adapters::github::{self, AnnotationLevel, CheckAnnotation};
+use cosmos_core::context::WorkContext;
+use cosmos_core::index::CodebaseIndex;
+use cosmos_core::suggest::{Criticality, Suggestion, SuggestionEngine};
+use cosmos_engine::llm;
+use std::path::Path;
+
+/// GitHub rejects annotation titles longer than this.
+const MAX_ANNOTATION_TITLE: usize = 255;
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SuggestReport {
+    /// Numbered list on stdout
+    Text,
+    /// Check run with one annotation per suggestion (for GitHub Actions)
+    Github,
+    /// Static HTML bundle with fix diffs and harness gate results (for CI artifacts)
+    Html,
+}
+
+pub async fn run(
+    path: &Path,
+    index: &CodebaseIndex,
+    context: &WorkContext,
+    mut suggestions: SuggestionEngine,
+    report: SuggestReport,
+    output: &Path,
+    ratchet: Option<&Ratchet>,
+) -> Result<()> {
+    // A CI report built from offline findings alone would silently hide the AI's results.
+    let generated = plain::generate(path, index, context, &mut suggestions).await;
+    if report == SuggestReport::Github {
+        generated.context("AI suggestions failed")?;
+    } else if let Err(e) = generated {
+        println!("AI suggestions failed: {}", e);
+    }
+
+    let active = suggestions.active_suggestions();
+    match report {
+        SuggestReport::Text => {
+            if active.is_empty() {
+                println!("No suggestions.");
+            }
+            for (idx, suggestion) in active.iter().enumerate() {
+                plain::print_suggestion(idx + 1, suggestion);
+            }
+        }
+        SuggestReport::Github => publish_github(path, index, &active).await?,
+        SuggestReport::Html => {
+            let runs = llm::load_harness_reports(path);
+            let index_path = html_report::write_bundle(output, path, &active, &runs)?;
+            println!(
+                "Wrote {} suggestion(s) to {}",
+                active.len(),
+                index_path.display()
+            );
+        }
+    }
+    match ratchet {
+        Some(ratchet) => ratchet::check(path, ratchet, context, &active),
+        None => Ok(()),
+    }
+}
+
+async fn publish_github(path: &Path, index: &CodebaseIndex, active: &[&Suggestion]) -> Result<()> {
+    let (owner, repo) = github_repository(path)?;
+    let head_sha = head_sha(index)?;
+    let annotations: Vec<CheckAnnotation> = active.iter().map(|s| annotation(s)).collect();
+    let title = match annotations.len() {
+        0 => "No suggestions".to_string(),
+        1 => "1 suggestion".to_string(),
+        n => format!("{} suggestions", n),
+    };
+    let url = github::publish_check_run(
+        &owner,
+        &repo,
+        &head_sha,
+        &title,
+        &check_summary(active),
+        &annotations,
+    )
+    .await?;
+    println!("Published {} to {}", title.to_lowercase(), url);
+    Ok(())
+}
+
+/// `GITHUB_REPOSITORY` inside Actions, otherwise the repository's GitHub remote.
+fn github_repository(path: &Path) -> Result<(String, String)> {
+    if let Some((owner, repo)) = std::env::var("GITHUB_REPOSITORY")
+        .ok()
+        .as_deref()
+        .and_then(|full| full.split_once('/'))
+    {
+        return Ok((owner.to_string(), repo.to_string()));
+    }
+    github::get_remote_info(path)
+}
+
+/// The commit to annotate.
+///
+/// For `pull_request` events Actions checks out a merge commit, so prefer the PR's head from
+/// the event payload; annotations on the merge commit never show on the PR.
+fn head_sha(index: &CodebaseIndex) -> Result<String> {
+    let from_event = std::env::var("GITHUB_EVENT_PATH")
+        .ok()
+        .and_then(|event_path| std::fs::read_to_string(event_path).ok())
+        .and_then(|event| pull_request_head_sha(&event));
+    from_event
+        .or_else(|| {
+            std::env::var("GITHUB_SHA")
+                .ok()
+                .filter(|sha| !sha.is_empty())
+        })
+        .or_else(|| index.git_head.clone())
+        .context("Can't tell which commit to report on; set GITHUB_SHA")
+}
+
+fn pull_request_head_sha(event: &str) -> Option<String> {
+    let event: serde_json::Value = serde_json::from_str(event).ok()?;
+    event["pull_request"]["head"]["sha"]
+        .as_str()
+        .map(str::to_string)
+}
+
+fn annotation_level(criticality: Criticality) -> AnnotationLevel {
+    match criticality {
+        Criticality::Critical => AnnotationLevel::Failure,
+        Criticality::High | Criticality::Medium => AnnotationLevel::Warning,
+        Criticality::Low => AnnotationLevel::Notice,
+    }
+}
+
+fn annotation(suggestion: &Suggestion) -> CheckAnnotation {
+    let line = suggestion.line.unwrap_or(1).max(1) as u32;
+    let mut title = format!("{}: {}", suggestion.kind.label(), suggestion.summary);
+    if title.chars().count() > MAX_ANNOTATION_TITLE {
+        title = title.chars().take(MAX_ANNOTATION_TITLE - 1).collect();
+        title.push('…');
+    }
+    let message = suggestion
+        .detail
+        .as_deref()
+        .map(str::trim)
+        .filter(|detail| !detail.is_empty())
+        .unwrap_or(&suggestion.summary)
+        .to_string();
+    CheckAnnotation {
+        // Check runs want forward slashes regardless of the runner's OS.
+        path: suggestion.file.to_string_lossy().replace('\\', "/"),
+        start_line: line,
+        end_line: line,
+        annotation_level: annotation_level(suggestion.criticality),
+        title,
+        message,
+    }
+}
+
+fn check_summary(active: &[&Suggestion]) -> String {
+    if active.is_empty() {
+        return "Cosmos found nothing to suggest for this commit.".to_string();
+    }
+    let mut summary = String::from("| Severity | Suggestion | Location |\n|---|---|---|\n");
+    for suggestion in active {
+        let location = match suggestion.line {
+            Some(line) => format!("{}:{}", suggestion.file.display(), line),
+            None => suggestion.file.display().to_string(),
+        };
+        summary.push_str(&format!(
+            "| {:?} | {} | `{}` |\n",
+            suggestion.criticality,
+            suggestion.summary.replace('|', "\\|"),
+            location
+        ));
+    }
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmos_core::suggest::{Priority, SuggestionKind, SuggestionSource};
+    use std::path::PathBuf;
+
+    #[test]
+    fn annotations_carry_location_severity_and_detail() {
+        let suggestion = Suggestion::new(
+            SuggestionKind::BugFix,
+            Priority::High,
+            PathBuf::from("src/retry.rs"),
+            "Retry loop never backs off".to_string(),
+            SuggestionSource::LlmDeep,
+        )
+        .with_line(42)
+        .with_detail("  Each attempt fires immediately.  ".to_string())
+        .with_criticality(Criticality::Critical);
+
+        let annotation = annotation(&suggestion);
+        assert_eq!(annotation.path, "src/retry.rs");
+        assert_eq!((annotation.start_line, annotation.end_line), (42, 42));
+        assert_eq!(annotation.annotation_level, AnnotationLevel::Failure);
+        assert!(annotation.title.ends_with("Retry loop never backs off"));
+        assert_eq!(annotation.message, "Each attempt fires immediately.");
+
+        let event = r#"{"pull_request":{"head":{"sha":"abc123"}}}"#;
+        assert_eq!(pull_request_head_sha(event).as_deref(), Some("abc123"));
+        assert_eq!(pull_request_head_sha(r#"{"ref":"main"}"#), None);
+    }
+}
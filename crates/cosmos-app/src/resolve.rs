@@ -0,0 +1,213 @@
+//! `cosmos resolve`: propose resolutions for the merge conflicts in the working tree.
+//!
+//! Works on whatever left the conflict blocks behind (a merge, rebase, cherry-pick, or stash
+//! pop). Each block is shown with both sides and the lines around it, next to a proposed
+//! resolution that already went through the syntax and quick-check gates. Accepted blocks are
+//! written back, and a file with no blocks left is staged as resolved.
+
+use anyhow::Result;
+use cosmos_adapters::{config, usage};
+use cosmos_engine::llm::{self, FileResolution, ImplementationQuickCheckStatus};
+use std::io::{self, Write};
+use std::path::Path;
+
+/// Unchanged lines shown above and below each conflict.
+const CONTEXT_LINES: usize = 3;
+
+enum Answer {
+    Accept,
+    Reject,
+    Quit,
+}
+
+pub async fn run(path: &Path, yes: bool) -> Result<()> {
+    let conflicted = llm::find_conflicted_files(path)?;
+    if conflicted.is_empty() {
+        println!("No conflict markers in the working tree.");
+        return Ok(());
+    }
+    if !llm::is_available() {
+        anyhow::bail!("Resolving conflicts needs AI; run `cosmos --setup` first");
+    }
+
+    let mut harness =
+        llm::ImplementationHarnessConfig::interactive_strict().with_repo_config(path)?;
+    if !config::Config::load().is_workspace_trusted(path) {
+        harness = harness.for_untrusted_workspace();
+        println!("Restricted workspace: quick checks won't run (`cosmos trust` enables them).");
+    }
+    println!("{} conflicted file(s)", conflicted.len());
+    let plan = llm::propose_conflict_resolutions(path, &harness, |stage| {
+        println!("  {}", stage);
+    })
+    .await?;
+    record_usage(path, plan.usage.as_ref());
+
+    for (file, error) in &plan.failures {
+        println!("  couldn't resolve {}: {}", file.display(), error);
+    }
+    let checks = plan
+        .quick_check_command
+        .as_deref()
+        .unwrap_or("quick checks");
+    let checks_failed = plan.quick_check_status == ImplementationQuickCheckStatus::Failed;
+    match plan.quick_check_status {
+        ImplementationQuickCheckStatus::Passed => {
+            println!("\n{} passed with every proposal applied.", checks)
+        }
+        ImplementationQuickCheckStatus::Failed => {
+            println!("\n{} failed with every proposal applied:", checks);
+            if let Some(failure) = &plan.quick_check_failure {
+                println!("  {}", failure);
+            }
+        }
+        ImplementationQuickCheckStatus::Unavailable => {
+            println!("\nNo quick checks ran; review each proposal carefully.")
+        }
+    }
+
+    let mut resolved = 0;
+    for file in &plan.files {
+        println!(
+            "\n{} ({} conflict(s))",
+            file.path.display(),
+            file.hunks.len()
+        );
+        if let Some(error) = &file.syntax_error {
+            println!("  doesn't parse with the proposals applied: {}", error);
+        }
+        let mut accepted = vec![false; file.hunks.len()];
+        let mut quit = false;
+        for (i, slot) in accepted.iter_mut().enumerate() {
+            for line in conflict_lines(file, i) {
+                println!("{}", line);
+            }
+            if yes {
+                *slot = file.syntax_error.is_none() && !checks_failed;
+                continue;
+            }
+            match ask("  Accept this resolution?")? {
+                Answer::Accept => *slot = true,
+                Answer::Reject => {}
+                Answer::Quit => {
+                    quit = true;
+                    break;
+                }
+            }
+        }
+        if accepted.iter().any(|&a| a) {
+            if llm::apply_file_resolution(path, file, &accepted)? {
+                resolved += 1;
+                println!("  resolved and staged {}", file.path.display());
+            } else {
+                println!("  kept the rejected conflicts in {}", file.path.display());
+            }
+        }
+        if quit {
+            break;
+        }
+    }
+    println!(
+        "\nResolved {} of {} conflicted file(s).",
+        resolved,
+        conflicted.len()
+    );
+    Ok(())
+}
+
+/// One conflict for review: the lines around it, each side, and the proposed resolution.
+fn conflict_lines(file: &FileResolution, index: usize) -> Vec<String> {
+    let hunk = &file.hunks[index];
+    let lines: Vec<&str> = file.content.lines().collect();
+    let mut out = vec![format!(
+        "  -- conflict {} of {} (lines {}-{})",
+        index + 1,
+        file.hunks.len(),
+        hunk.start_line,
+        hunk.end_line
+    )];
+    let before = hunk.start_line.saturating_sub(1 + CONTEXT_LINES)..hunk.start_line - 1;
+    let after = hunk.end_line..(hunk.end_line + CONTEXT_LINES).min(lines.len());
+    let context = |range: std::ops::Range<usize>| {
+        lines[range]
+            .iter()
+            .map(|line| format!("     | {}", line))
+            .collect::<Vec<_>>()
+    };
+    let side = |label: &str, marker: char, text: &str| {
+        let mut out = vec![format!("    {}:", label)];
+        if text.is_empty() {
+            out.push(format!("   {} | (nothing)", marker));
+        }
+        out.extend(text.lines().map(|line| format!("   {} | {}", marker, line)));
+        out
+    };
+    out.extend(context(before));
+    out.extend(side("ours", '<', &hunk.ours));
+    out.extend(side("theirs", '>', &hunk.theirs));
+    out.extend(side("proposed", '=', &file.proposals[index]));
+    out.extend(context(after));
+    out
+}
+
+fn ask(question: &str) -> Result<Answer> {
+    print!("{} [y/N/q] ", question);
+    io::stdout().flush()?;
+    let mut answer = String::new();
+    if io::stdin().read_line(&mut answer)? == 0 {
+        return Ok(Answer::Quit);
+    }
+    Ok(match answer.trim().to_ascii_lowercase().as_str() {
+        "y" | "yes" => Answer::Accept,
+        "q" | "quit" => Answer::Quit,
+        _ => Answer::Reject,
+    })
+}
+
+fn record_usage(path: &Path, usage: Option<&llm::Usage>) {
+    if let Some(usage) = usage {
+        usage::record(
+            path,
+            usage::OPERATION_FIXES,
+            usage.model.as_deref().unwrap_or("unknown"),
+            usage.prompt_tokens,
+            usage.completion_tokens,
+            usage.total_tokens,
+            usage.cost,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn conflict_lines_show_context_both_sides_and_the_proposal() {
+        let content = "a\nb\nc\nd\n<<<<<<< HEAD\nours\n=======\ntheirs\n>>>>>>> topic\ne\n";
+        let file = FileResolution {
+            path: PathBuf::from("notes.txt"),
+            hunks: cosmos_engine::llm::merge_conflicts::conflict_hunks(content),
+            content: content.to_string(),
+            proposals: vec![String::new()],
+            syntax_error: None,
+        };
+        assert_eq!(
+            conflict_lines(&file, 0),
+            vec![
+                "  -- conflict 1 of 1 (lines 5-9)",
+                "     | b",
+                "     | c",
+                "     | d",
+                "    ours:",
+                "   < | ours",
+                "    theirs:",
+                "   > | theirs",
+                "    proposed:",
+                "   = | (nothing)",
+                "     | e",
+            ]
+        );
+    }
+}
@@ -0,0 +1,472 @@
+//! `cosmos serve` - drive the engine from an editor over JSON-RPC 2.0.
+//!
+//! Requests and responses are newline-delimited JSON on stdio, or on a unix socket with
+//! `--socket` (one client at a time). Methods follow the `Engine` contract:
+//!
+//! - `index.status` / `index.refresh` - repo, branch, and index counts
+//! - `suggestions.list` / `suggestions.refresh` - current suggestions / run a new scan
+//! - `suggestions.preview` `{id}` - what a fix will do, plus a `preview_hash`
+//! - `suggestions.apply` `{id, preview_hash, write?}` - run the strict harness and, when it
+//!   passes, write the files (unless `write` is false)
+//! - `ask` `{question, paths?}` / `ask.reset` - Ask Cosmos with a running conversation
+//! - `shutdown` - end the session
+
+use anyhow::Result;
+use cosmos_adapters::config;
+use cosmos_core::context::WorkContext;
+use cosmos_core::index::CodebaseIndex;
+use cosmos_core::protocol::{Engine, RepoSnapshot};
+use cosmos_core::suggest::{Suggestion, SuggestionEngine};
+use cosmos_engine::llm;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt};
+use uuid::Uuid;
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+/// Engine failures (AI errors, harness failures, I/O)
+const ENGINE_ERROR: i64 = -32000;
+/// The files behind a preview changed before apply
+const CONFLICT_ERROR: i64 = -32001;
+
+pub(crate) struct RpcError {
+    pub(crate) code: i64,
+    pub(crate) message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+
+    fn params(message: impl Into<String>) -> Self {
+        Self::new(INVALID_PARAMS, message)
+    }
+
+    fn engine(error: impl std::fmt::Display) -> Self {
+        Self::new(ENGINE_ERROR, error.to_string())
+    }
+}
+
+/// Repo state shared by every request in a session
+pub struct Session {
+    root: PathBuf,
+    index: CodebaseIndex,
+    context: WorkContext,
+    suggestions: SuggestionEngine,
+    chat: llm::ChatSession,
+    shutdown: bool,
+}
+
+impl Session {
+    pub fn new(
+        root: PathBuf,
+        index: CodebaseIndex,
+        context: WorkContext,
+        suggestions: SuggestionEngine,
+    ) -> Self {
+        Self {
+            root,
+            index,
+            context,
+            suggestions,
+            chat: llm::ChatSession::default(),
+            shutdown: false,
+        }
+    }
+
+    /// Handle one line of input, returning the response line (None for notifications).
+    pub async fn handle_line(&mut self, line: &str) -> Option<String> {
+        let request: Value = match serde_json::from_str(line) {
+            Ok(request) => request,
+            Err(e) => {
+                return Some(error_response(
+                    Value::Null,
+                    RpcError::new(PARSE_ERROR, e.to_string()),
+                ))
+            }
+        };
+        let id = request.get("id").cloned();
+        let Some(method) = request.get("method").and_then(Value::as_str) else {
+            return Some(error_response(
+                id.unwrap_or(Value::Null),
+                RpcError::new(INVALID_REQUEST, "missing method"),
+            ));
+        };
+        let params = request.get("params").cloned().unwrap_or(Value::Null);
+        let result = self.dispatch(method, params).await;
+        let id = id?;
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }).to_string(),
+            Err(error) => error_response(id, error),
+        })
+    }
+
+    pub(crate) async fn dispatch(
+        &mut self,
+        method: &str,
+        params: Value,
+    ) -> Result<Value, RpcError> {
+        match method {
+            "index.status" => Ok(self.status()),
+            "index.refresh" => {
+                self.index = CodebaseIndex::new(&self.root).map_err(RpcError::engine)?;
+                self.context = WorkContext::load(&self.root).map_err(RpcError::engine)?;
+                self.suggestions.index = self.index.clone();
+                self.suggestions
+                    .refresh_static_suggestions(!llm::is_available());
+                crate::run_analyzer_plugins(&self.root, &mut self.suggestions)
+                    .map_err(RpcError::engine)?;
+                Ok(self.status())
+            }
+            "suggestions.list" => Ok(self.suggestion_list()),
+            "suggestions.refresh" => self.refresh_suggestions().await,
+            "suggestions.preview" => {
+                let params: IdParams = parse_params(params)?;
+                let suggestion = self.suggestion(params.id)?;
+                Ok(self.preview_json(suggestion))
+            }
+            "suggestions.apply" => self.apply(parse_params(params)?).await,
+            "ask" => self.ask(parse_params(params)?).await,
+            "ask.reset" => {
+                self.chat.clear();
+                Ok(Value::Null)
+            }
+            "shutdown" => {
+                self.shutdown = true;
+                Ok(Value::Null)
+            }
+            other => Err(RpcError::new(
+                METHOD_NOT_FOUND,
+                format!("unknown method '{}'", other),
+            )),
+        }
+    }
+
+    fn status(&self) -> Value {
+        json!({
+            "root": self.root,
+            "branch": self.context.branch,
+            "files": self.index.files.len(),
+            "changed_files": self.context.modified_count,
+            "git_head": self.index.git_head,
+            "ai_available": llm::is_available(),
+            "suggestions": self.suggestions.active_suggestions().len(),
+        })
+    }
+
+    pub(crate) fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub(crate) fn active_suggestions(&self) -> Vec<&Suggestion> {
+        self.suggestions.active_suggestions()
+    }
+
+    fn suggestion_list(&self) -> Value {
+        json!(self.suggestions.active_suggestions())
+    }
+
+    fn suggestion(&self, id: Uuid) -> Result<&Suggestion, RpcError> {
+        self.suggestions
+            .active_suggestions()
+            .into_iter()
+            .find(|s| s.id == id)
+            .ok_or_else(|| RpcError::params(format!("no active suggestion {}", id)))
+    }
+
+    async fn refresh_suggestions(&mut self) -> Result<Value, RpcError> {
+        if !llm::is_available() {
+            return Err(RpcError::engine(
+                "AI is unavailable. Configure an API key first (`cosmos --setup`).",
+            ));
+        }
+        let repo = RepoSnapshot {
+            root: self.root.clone(),
+            index: self.index.clone(),
+            context: self.context.clone(),
+        };
+        let fresh = cosmos_engine::CosmosEngine
+            .scan_and_suggest(&repo)
+            .await
+            .map_err(RpcError::engine)?;
+        self.suggestions.replace_llm_suggestions(fresh);
+        self.suggestions.sort_with_context(&self.context, None);
+        Ok(self.suggestion_list())
+    }
+
+    fn preview_json(&self, suggestion: &Suggestion) -> Value {
+        let preview = llm::build_fix_preview_from_validated_suggestion(suggestion);
+        json!({
+            "id": suggestion.id,
+            "summary": preview.problem_summary,
+            "outcome": preview.outcome,
+            "files": suggestion.affected_files(),
+            "preview_hash": cosmos_engine::preview_hash(&self.root, suggestion),
+            "friendly_title": preview.friendly_title,
+            "description": preview.description,
+            "affected_areas": preview.affected_areas,
+            "scope": preview.scope.label(),
+            "verified": preview.verified,
+            "evidence_snippet": preview.evidence_snippet,
+            "evidence_line": preview.evidence_line,
+        })
+    }
+
+    async fn apply(&mut self, params: ApplyParams) -> Result<Value, RpcError> {
+        if !llm::is_available() {
+            return Err(RpcError::engine(
+                "AI is unavailable. Configure an API key first (`cosmos --setup`).",
+            ));
+        }
+        let suggestion = self.suggestion(params.id)?.clone();
+        if cosmos_engine::preview_hash(&self.root, &suggestion) != params.preview_hash {
+            return Err(RpcError::new(
+                CONFLICT_ERROR,
+                "files changed since the preview; request a new preview",
+            ));
+        }
+        let trusted = config::Config::load().is_workspace_trusted(&self.root);
+        let result = cosmos_engine::run_apply_harness(
+            &self.root,
+            &suggestion,
+            trusted,
+            params.write,
+            "cosmos serve",
+        )
+        .await
+        .map_err(RpcError::engine)?;
+
+        let passed = result.diagnostics.passed;
+        let written = passed && params.write;
+        if written {
+            self.suggestions.mark_applied(suggestion.id);
+        }
+
+        Ok(json!({
+            "passed": passed,
+            "written": written,
+            "description": result.description,
+            "files": result.file_changes,
+            "fail_reasons": result.diagnostics.fail_reasons,
+            "report_path": result.diagnostics.report_path,
+        }))
+    }
+
+    async fn ask(&mut self, params: AskParams) -> Result<Value, RpcError> {
+        if !llm::is_available() {
+            return Err(RpcError::engine(
+                "AI is unavailable. Configure an API key first (`cosmos --setup`).",
+            ));
+        }
+        let (answer, _usage, report) = llm::ask_question(
+            &self.index,
+            &self.context,
+            &params.question,
+            None,
+            &params.paths,
+            &self.chat,
+        )
+        .await
+        .map_err(RpcError::engine)?;
+        self.chat
+            .record(params.question, answer.clone(), Some(&report));
+        Ok(json!({ "answer": answer }))
+    }
+}
+
+#[derive(Deserialize)]
+struct IdParams {
+    id: Uuid,
+}
+
+#[derive(Deserialize)]
+struct ApplyParams {
+    id: Uuid,
+    preview_hash: String,
+    #[serde(default = "default_write")]
+    write: bool,
+}
+
+fn default_write() -> bool {
+    true
+}
+
+#[derive(Deserialize)]
+struct AskParams {
+    question: String,
+    #[serde(default)]
+    paths: Vec<PathBuf>,
+}
+
+fn parse_params<T: serde::de::DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    serde_json::from_value(params).map_err(|e| RpcError::params(e.to_string()))
+}
+
+fn error_response(id: Value, error: RpcError) -> String {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": error.code, "message": error.message },
+    })
+    .to_string()
+}
+
+/// Serve requests from `reader` until it closes or the client sends `shutdown`.
+pub async fn serve_stream<R, W>(session: &mut Session, reader: R, mut writer: W) -> Result<()>
+where
+    R: AsyncBufRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let mut lines = reader.lines();
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(response) = session.handle_line(&line).await {
+            writer.write_all(response.as_bytes()).await?;
+            writer.write_all(b"\n").await?;
+            writer.flush().await?;
+        }
+        if session.shutdown {
+            break;
+        }
+    }
+    Ok(())
+}
+
+pub async fn run(mut session: Session, socket: Option<PathBuf>) -> Result<()> {
+    match socket {
+        None => {
+            let stdin = tokio::io::BufReader::new(tokio::io::stdin());
+            serve_stream(&mut session, stdin, tokio::io::stdout()).await
+        }
+        Some(socket) => serve_socket(session, &socket).await,
+    }
+}
+
+/// Remove a socket left behind by an earlier `serve`; anything else at the path is an error.
+#[cfg(unix)]
+fn remove_stale_socket(socket: &Path) -> Result<()> {
+    use std::os::unix::fs::FileTypeExt;
+
+    match std::fs::symlink_metadata(socket) {
+        Ok(metadata) if metadata.file_type().is_socket() => {
+            std::fs::remove_file(socket)?;
+            Ok(())
+        }
+        Ok(_) => anyhow::bail!(
+            "{} already exists and is not a socket; pick another --socket path",
+            socket.display()
+        ),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[cfg(unix)]
+async fn serve_socket(mut session: Session, socket: &Path) -> Result<()> {
+    remove_stale_socket(socket)?;
+    let listener = tokio::net::UnixListener::bind(socket)?;
+    eprintln!("  Listening on {}", socket.display());
+    while !session.shutdown {
+        let (stream, _) = listener.accept().await?;
+        let (reader, writer) = stream.into_split();
+        if let Err(e) = serve_stream(&mut session, tokio::io::BufReader::new(reader), writer).await
+        {
+            eprintln!("  Client disconnected: {}", e);
+        }
+    }
+    let _ = std::fs::remove_file(socket);
+    Ok(())
+}
+
+#[cfg(not(unix))]
+async fn serve_socket(_session: Session, _socket: &Path) -> Result<()> {
+    anyhow::bail!("--socket needs unix domain sockets; use stdio instead")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn session() -> Session {
+        let root = std::env::temp_dir();
+        let index = CodebaseIndex {
+            root: root.clone(),
+            files: HashMap::new(),
+            index_errors: Vec::new(),
+            git_head: Some("deadbeef".to_string()),
+            packages: Vec::new(),
+            excluded_files: 0,
+            filter_fingerprint: None,
+        };
+        let context = WorkContext {
+            branch: "main".to_string(),
+            uncommitted_files: Vec::new(),
+            staged_files: Vec::new(),
+            untracked_files: Vec::new(),
+            inferred_focus: None,
+            modified_count: 0,
+            repo_root: root.clone(),
+        };
+        let suggestions = SuggestionEngine::new(index.clone());
+        Session::new(root, index, context, suggestions)
+    }
+
+    #[tokio::test]
+    async fn answers_requests_and_reports_json_rpc_errors() {
+        let mut session = session();
+        let input = concat!(
+            "{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"index.status\"}\n",
+            "not json\n",
+            "{\"jsonrpc\":\"2.0\",\"id\":2,\"method\":\"suggestions.preview\",\"params\":{}}\n",
+            "{\"jsonrpc\":\"2.0\",\"method\":\"ask.reset\"}\n",
+            "{\"jsonrpc\":\"2.0\",\"id\":3,\"method\":\"launch\"}\n",
+            "{\"jsonrpc\":\"2.0\",\"id\":4,\"method\":\"shutdown\"}\n",
+            "{\"jsonrpc\":\"2.0\",\"id\":5,\"method\":\"index.status\"}\n",
+        );
+        let mut output = Vec::new();
+        serve_stream(&mut session, input.as_bytes(), &mut output)
+            .await
+            .unwrap();
+
+        let responses: Vec<Value> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(responses.len(), 5);
+        assert_eq!(responses[0]["result"]["branch"], "main");
+        assert_eq!(responses[1]["error"]["code"], PARSE_ERROR);
+        assert_eq!(responses[2]["error"]["code"], INVALID_PARAMS);
+        assert_eq!(responses[3]["error"]["code"], METHOD_NOT_FOUND);
+        assert_eq!(responses[4]["id"], 4);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn socket_path_holding_a_regular_file_is_left_alone() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.txt");
+        std::fs::write(&path, "keep me").unwrap();
+
+        let err = serve_socket(session(), &path).await.unwrap_err();
+        assert!(err.to_string().contains("is not a socket"));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "keep me");
+
+        // A stale socket from an earlier run is replaced
+        let stale = dir.path().join("cosmos.sock");
+        drop(std::os::unix::net::UnixListener::bind(&stale).unwrap());
+        remove_stale_socket(&stale).unwrap();
+        assert!(!stale.exists());
+    }
+}
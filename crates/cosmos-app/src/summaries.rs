@@ -0,0 +1,320 @@
+//! `cosmos summaries audit`: find stale or weak cached directory summaries and regenerate them.
+//!
+//! Stale summaries (their files changed since they were cached) are found without AI. A sample
+//! of the current ones is scored against the directory's entry files with the Speed model, and
+//! anything flagged can be regenerated after confirmation.
+//!
+//! `cosmos warm` has the AI write every summary that isn't AI-written yet, ahead of the first
+//! session. With `--batch` the requests go out as one provider batch job instead: cheaper,
+//! but finished whenever the provider gets to it. The job id is kept in the cache while it
+//! runs, so an interrupted `cosmos warm --batch` picks the same job back up.
+
+use anyhow::Result;
+use cosmos_adapters::cache::{Cache, PendingSummaryBatch};
+use cosmos_adapters::usage;
+use cosmos_core::index::directory::DirectorySummary;
+use cosmos_core::index::CodebaseIndex;
+use cosmos_engine::llm;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+pub async fn audit(path: &Path, index: &CodebaseIndex, sample: usize, yes: bool) -> Result<()> {
+    let cache = Cache::new(path);
+    let mut summaries = cache.load_directory_summaries();
+    if summaries.entries.is_empty() {
+        println!("No cached directory summaries yet; Cosmos builds them on its next start.");
+        return Ok(());
+    }
+
+    let stale = summaries.stale(index);
+    println!(
+        "{} cached directory summaries, {} stale",
+        summaries.entries.len(),
+        stale.len()
+    );
+    for dir in &stale {
+        println!("  stale     {}", dir.display());
+    }
+
+    let mut weak: Vec<PathBuf> = Vec::new();
+    if llm::is_available() {
+        let mut current: Vec<PathBuf> = summaries
+            .entries
+            .keys()
+            .filter(|dir| !stale.contains(dir))
+            .cloned()
+            .collect();
+        current.sort();
+        let sampled = sample_paths(&current, sample);
+        println!(
+            "\nScoring {} of {} current summaries...",
+            sampled.len(),
+            current.len()
+        );
+        for dir in sampled {
+            let Some(summary) = summaries.get(&dir) else {
+                continue;
+            };
+            match llm::score_directory_summary(index, summary).await {
+                Ok((score, usage)) => {
+                    record_usage(path, usage.as_ref());
+                    let label = if score.passed() { "ok" } else { "mismatch" };
+                    println!("  {:<9} {:>2}/10  {}", label, score.score, dir.display());
+                    if !score.issue.is_empty() {
+                        println!("                   {}", score.issue);
+                    }
+                    if !score.passed() {
+                        weak.push(dir);
+                    }
+                }
+                Err(e) => println!("  error            {} ({})", dir.display(), e),
+            }
+        }
+    } else {
+        println!("\nAI is unavailable (run `cosmos --setup`); only checked for stale summaries.");
+    }
+
+    let flagged: Vec<PathBuf> = stale.iter().chain(&weak).cloned().collect();
+    if flagged.is_empty() {
+        println!("\nNothing to regenerate.");
+        return Ok(());
+    }
+    if !yes && !confirm(&format!("\nRegenerate {} summaries?", flagged.len()))? {
+        return Ok(());
+    }
+
+    let mut regenerated = 0;
+    for dir in flagged {
+        match regenerate(path, index, &dir).await {
+            Ok(Some(summary)) => {
+                summaries.entries.insert(dir, summary);
+                regenerated += 1;
+            }
+            Ok(None) => {
+                // The directory no longer holds indexed files.
+                summaries.entries.remove(&dir);
+            }
+            Err(e) => eprintln!("  {}: {}", dir.display(), e),
+        }
+    }
+    cache.save_directory_summaries(&summaries)?;
+    println!("Regenerated {} summaries.", regenerated);
+    Ok(())
+}
+
+pub async fn warm(path: &Path, index: &CodebaseIndex, batch: bool, poll: Duration) -> Result<()> {
+    if !llm::is_available() {
+        anyhow::bail!("AI is unavailable (run `cosmos --setup`); nothing to warm.");
+    }
+    let cache = Cache::new(path);
+    if batch {
+        if let Some(pending) = cache.load_summary_batch() {
+            println!(
+                "Resuming batch {} submitted {}",
+                pending.batch_id,
+                pending.submitted_at.format("%Y-%m-%d %H:%M UTC")
+            );
+            return finish_batch(path, index, &cache, pending, poll).await;
+        }
+    }
+
+    let summaries = cache.directory_summaries(index);
+    let mut pending: Vec<PathBuf> = summaries
+        .entries
+        .iter()
+        .filter(|(_, summary)| !summary.ai_written)
+        .map(|(dir, _)| dir.clone())
+        .collect();
+    pending.sort();
+    if pending.is_empty() {
+        println!(
+            "All {} directory summaries are AI-written; nothing to warm.",
+            summaries.entries.len()
+        );
+        return Ok(());
+    }
+
+    if batch {
+        match llm::submit_summary_batch(index, &pending).await {
+            Ok(batch_id) => {
+                let dirs = pending
+                    .iter()
+                    .filter_map(|dir| {
+                        let summary = summaries.get(dir)?;
+                        Some((dir.clone(), summary.fingerprint.clone()))
+                    })
+                    .collect();
+                let submitted = PendingSummaryBatch::new(batch_id, dirs);
+                cache.save_summary_batch(&submitted)?;
+                println!(
+                    "Submitted {} directory summaries as batch {}.\n\
+                     The provider has up to {} to finish; interrupt any time and run \
+                     `cosmos warm --batch` again to pick it back up.",
+                    pending.len(),
+                    submitted.batch_id,
+                    llm::batch::COMPLETION_WINDOW
+                );
+                return finish_batch(path, index, &cache, submitted, poll).await;
+            }
+            Err(e) if e.downcast_ref::<llm::BatchUnsupported>().is_some() => {
+                println!("{}; sending the requests one by one instead.", e);
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    println!("Writing {} directory summaries...", pending.len());
+    let mut summaries = summaries;
+    let mut written = 0;
+    for dir in pending {
+        match regenerate(path, index, &dir).await {
+            Ok(Some(summary)) => {
+                summaries.entries.insert(dir, summary);
+                written += 1;
+            }
+            Ok(None) => {
+                summaries.entries.remove(&dir);
+            }
+            Err(e) => eprintln!("  {}: {}", dir.display(), e),
+        }
+    }
+    cache.save_directory_summaries(&summaries)?;
+    println!("Wrote {} summaries.", written);
+    Ok(())
+}
+
+/// Poll `batch` until it's done, then merge its summaries into the cache.
+async fn finish_batch(
+    path: &Path,
+    index: &CodebaseIndex,
+    cache: &Cache,
+    batch: PendingSummaryBatch,
+    poll: Duration,
+) -> Result<()> {
+    let mut last_progress = None;
+    let (output_file_id, failed) = loop {
+        match llm::summary_batch_status(&batch.batch_id).await? {
+            llm::BatchStatus::Pending { completed, total } => {
+                if last_progress != Some(completed) {
+                    println!("  {}/{} done", completed, total);
+                    last_progress = Some(completed);
+                }
+                tokio::time::sleep(poll).await;
+            }
+            llm::BatchStatus::Completed {
+                output_file_id,
+                failed,
+            } => break (output_file_id, failed),
+            llm::BatchStatus::Failed(reason) => {
+                cache.clear_summary_batch()?;
+                anyhow::bail!("Batch {} failed: {}", batch.batch_id, reason);
+            }
+        }
+    };
+
+    let mut summaries = cache.directory_summaries(index);
+    let mut written = 0;
+    let mut changed = 0;
+    if let Some(output_file_id) = output_file_id {
+        let (results, usage) = llm::collect_summary_batch(index, &output_file_id).await?;
+        record_usage(path, usage.as_ref());
+        for (dir, result) in results {
+            match result {
+                Ok(summary) if batch.dirs.get(&dir) == Some(&summary.fingerprint) => {
+                    summaries.entries.insert(dir, summary);
+                    written += 1;
+                }
+                // The files changed while the job ran; the next warm covers it
+                Ok(_) => changed += 1,
+                Err(e) => eprintln!("  {}: {}", dir.display(), e),
+            }
+        }
+        cache.save_directory_summaries(&summaries)?;
+    }
+    cache.clear_summary_batch()?;
+    println!("Wrote {} summaries.", written);
+    if changed > 0 {
+        println!(
+            "{} directories changed while the batch ran; run `cosmos warm` again for them.",
+            changed
+        );
+    }
+    if failed > 0 {
+        println!("{} requests in the batch failed.", failed);
+    }
+    Ok(())
+}
+
+/// AI-written when available, otherwise re-synthesized from the index.
+async fn regenerate(
+    path: &Path,
+    index: &CodebaseIndex,
+    dir: &Path,
+) -> Result<Option<DirectorySummary>> {
+    let Some(synthesized) = DirectorySummary::synthesize(index, dir) else {
+        return Ok(None);
+    };
+    if !llm::is_available() {
+        return Ok(Some(synthesized));
+    }
+    let (summary, usage) = llm::regenerate_directory_summary(index, dir).await?;
+    record_usage(path, usage.as_ref());
+    Ok(Some(summary))
+}
+
+fn record_usage(path: &Path, usage: Option<&llm::Usage>) {
+    if let Some(usage) = usage {
+        usage::record(
+            path,
+            usage::OPERATION_SUMMARIES,
+            usage.model.as_deref().unwrap_or("unknown"),
+            usage.prompt_tokens,
+            usage.completion_tokens,
+            usage.total_tokens,
+            usage.cost,
+        );
+    }
+}
+
+/// Up to `n` paths spread evenly across `paths`, so the sample covers the whole repo.
+fn sample_paths(paths: &[PathBuf], n: usize) -> Vec<PathBuf> {
+    if n == 0 || paths.is_empty() {
+        return Vec::new();
+    }
+    if paths.len() <= n {
+        return paths.to_vec();
+    }
+    (0..n).map(|i| paths[i * paths.len() / n].clone()).collect()
+}
+
+fn confirm(question: &str) -> Result<bool> {
+    print!("{} [y/N] ", question);
+    io::stdout().flush()?;
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    Ok(matches!(
+        answer.trim().to_ascii_lowercase().as_str(),
+        "y" | "yes"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn samples_spread_across_the_sorted_paths() {
+        let paths: Vec<PathBuf> = (0..10).map(|i| PathBuf::from(format!("d{}", i))).collect();
+        assert_eq!(
+            sample_paths(&paths, 3),
+            vec![
+                PathBuf::from("d0"),
+                PathBuf::from("d3"),
+                PathBuf::from("d6")
+            ]
+        );
+        assert_eq!(sample_paths(&paths, 20).len(), 10);
+        assert!(sample_paths(&paths, 0).is_empty());
+    }
+}
@@ -5,8 +5,14 @@
 //! - Current branch
 //! - Work-in-progress detection
 
-use git2::{Repository, StatusOptions};
-use std::path::{Path, PathBuf};
+use crate::index::workspace::WorkspacePackage;
+#[cfg(feature = "native")]
+use git2::{Oid, Repository, StatusOptions};
+use std::collections::{BTreeMap, BTreeSet};
+#[cfg(feature = "native")]
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::SystemTime;
 
 /// Current work context inferred from git state
 #[derive(Debug, Clone)]
@@ -29,6 +35,7 @@ pub struct WorkContext {
 
 impl WorkContext {
     /// Load work context from a git repository
+    #[cfg(feature = "native")]
     pub fn load(repo_path: &Path) -> anyhow::Result<Self> {
         let repo = Repository::discover(repo_path)?;
         let repo_root = repo
@@ -54,6 +61,7 @@ impl WorkContext {
     }
 
     /// Refresh the context (e.g., after a change)
+    #[cfg(feature = "native")]
     pub fn refresh(&mut self) -> anyhow::Result<()> {
         // We already know the repo root; avoid Repository::discover on every refresh.
         let repo = Repository::open(&self.repo_root)?;
@@ -79,9 +87,154 @@ impl WorkContext {
             .chain(self.untracked_files.iter())
             .collect()
     }
+
+    /// Narrow the context to changes inside a single workspace package.
+    ///
+    /// The inferred focus is computed relative to the package root, so a
+    /// monorepo doesn't always report its `packages/` directory as the focus.
+    pub fn scoped_to_package(&self, package: &WorkspacePackage) -> Self {
+        let keep = |files: &[PathBuf]| -> Vec<PathBuf> {
+            files
+                .iter()
+                .filter(|p| package.contains(p))
+                .cloned()
+                .collect()
+        };
+        let uncommitted = keep(&self.uncommitted_files);
+        let staged = keep(&self.staged_files);
+        let untracked = keep(&self.untracked_files);
+
+        let relative = |files: &[PathBuf]| -> Vec<PathBuf> {
+            files
+                .iter()
+                .filter_map(|p| p.strip_prefix(&package.root).ok().map(PathBuf::from))
+                .collect()
+        };
+        let inferred_focus = infer_focus(
+            &relative(&uncommitted),
+            &relative(&staged),
+            &relative(&untracked),
+        );
+
+        Self {
+            branch: self.branch.clone(),
+            modified_count: uncommitted.len() + staged.len() + untracked.len(),
+            uncommitted_files: uncommitted,
+            staged_files: staged,
+            untracked_files: untracked,
+            inferred_focus,
+            repo_root: self.repo_root.clone(),
+        }
+    }
+}
+
+/// Git state compared between refreshes to notice commits, checkouts, and edits.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GitStateSnapshot {
+    /// HEAD commit, if the branch has one
+    pub head: Option<String>,
+    pub branch: String,
+    /// Size and modification time of each changed file (None once deleted)
+    pub changed_files: BTreeMap<PathBuf, Option<(u64, SystemTime)>>,
+}
+
+/// What moved between two snapshots.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GitStateChange {
+    pub head_changed: bool,
+    pub branch_changed: bool,
+    /// Repo-relative files whose content may differ: edited, staged, reverted, or changed
+    /// by the commits between the two HEADs
+    pub files: BTreeSet<PathBuf>,
+}
+
+impl GitStateChange {
+    pub fn is_empty(&self) -> bool {
+        !self.head_changed && !self.branch_changed && self.files.is_empty()
+    }
+}
+
+#[cfg(feature = "native")]
+impl WorkContext {
+    /// Snapshot HEAD, the branch, and the changed files' metadata (no file contents are read).
+    pub fn git_snapshot(&self) -> GitStateSnapshot {
+        let head = Repository::open(&self.repo_root).ok().and_then(|repo| {
+            repo.head()
+                .ok()
+                .and_then(|head| head.target())
+                .map(|oid| oid.to_string())
+        });
+        let changed_files = self
+            .all_changed_files()
+            .into_iter()
+            .map(|path| {
+                let stamp = std::fs::metadata(self.repo_root.join(path))
+                    .ok()
+                    .and_then(|meta| Some((meta.len(), meta.modified().ok()?)));
+                (path.clone(), stamp)
+            })
+            .collect();
+        GitStateSnapshot {
+            head,
+            branch: self.branch.clone(),
+            changed_files,
+        }
+    }
+
+    /// Compare two snapshots of this repository.
+    pub fn changes_between(
+        &self,
+        previous: &GitStateSnapshot,
+        current: &GitStateSnapshot,
+    ) -> GitStateChange {
+        let mut files: BTreeSet<PathBuf> = previous
+            .changed_files
+            .iter()
+            .filter(|(path, stamp)| current.changed_files.get(*path) != Some(*stamp))
+            .map(|(path, _)| path.clone())
+            .collect();
+        files.extend(
+            current
+                .changed_files
+                .keys()
+                .filter(|path| !previous.changed_files.contains_key(*path))
+                .cloned(),
+        );
+
+        let head_changed = previous.head != current.head;
+        if head_changed {
+            if let (Some(old), Some(new)) = (&previous.head, &current.head) {
+                files.extend(files_between_commits(&self.repo_root, old, new).unwrap_or_default());
+            }
+        }
+
+        GitStateChange {
+            head_changed,
+            branch_changed: previous.branch != current.branch,
+            files,
+        }
+    }
+}
+
+/// Paths that differ between two commits' trees.
+#[cfg(feature = "native")]
+fn files_between_commits(repo_root: &Path, old: &str, new: &str) -> anyhow::Result<Vec<PathBuf>> {
+    let repo = Repository::open(repo_root)?;
+    let old_tree = repo.find_commit(Oid::from_str(old)?)?.tree()?;
+    let new_tree = repo.find_commit(Oid::from_str(new)?)?.tree()?;
+    let diff = repo.diff_tree_to_tree(Some(&old_tree), Some(&new_tree), None)?;
+    Ok(diff
+        .deltas()
+        .flat_map(|delta| [delta.old_file().path(), delta.new_file().path()])
+        .flatten()
+        .map(Path::to_path_buf)
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect())
 }
 
 /// Get the current branch name
+#[cfg(feature = "native")]
 fn get_current_branch(repo: &Repository) -> anyhow::Result<String> {
     let head = repo.head()?;
     let shorthand = head.shorthand().unwrap_or("HEAD");
@@ -89,6 +242,7 @@ fn get_current_branch(repo: &Repository) -> anyhow::Result<String> {
 }
 
 /// Get file statuses (uncommitted, staged)
+#[cfg(feature = "native")]
 fn get_file_statuses(
     repo: &Repository,
 ) -> anyhow::Result<(Vec<PathBuf>, Vec<PathBuf>, Vec<PathBuf>)> {
@@ -227,4 +381,85 @@ mod tests {
         assert!(focus.is_some());
         assert!(focus.unwrap().contains("auth"));
     }
+
+    #[test]
+    #[cfg(feature = "native")]
+    fn test_changes_between_reports_edits_and_committed_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let commit_all = |message: &str| {
+            let mut index = repo.index().unwrap();
+            index
+                .add_all(["*"], git2::IndexAddOption::DEFAULT, None)
+                .unwrap();
+            index.write().unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+            let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+            let parents: Vec<&git2::Commit> = parent.iter().collect();
+            repo.commit(
+                Some("HEAD"),
+                &signature,
+                &signature,
+                message,
+                &tree,
+                &parents,
+            )
+            .unwrap();
+        };
+        std::fs::write(dir.path().join("a.rs"), "fn a() {}\n").unwrap();
+        std::fs::write(dir.path().join("b.rs"), "fn b() {}\n").unwrap();
+        commit_all("init");
+
+        let mut context = WorkContext::load(dir.path()).unwrap();
+        let clean = context.git_snapshot();
+        assert!(context.changes_between(&clean, &clean).is_empty());
+
+        std::fs::write(dir.path().join("a.rs"), "fn a() { todo!() }\n").unwrap();
+        context.refresh().unwrap();
+        let edited = context.git_snapshot();
+        let change = context.changes_between(&clean, &edited);
+        assert!(!change.head_changed);
+        assert_eq!(change.files, BTreeSet::from([PathBuf::from("a.rs")]));
+
+        std::fs::write(dir.path().join("b.rs"), "fn b() { todo!() }\n").unwrap();
+        commit_all("edit both");
+        context.refresh().unwrap();
+        let committed = context.git_snapshot();
+        let change = context.changes_between(&edited, &committed);
+        assert!(change.head_changed);
+        assert!(!change.branch_changed);
+        assert_eq!(
+            change.files,
+            BTreeSet::from([PathBuf::from("a.rs"), PathBuf::from("b.rs")])
+        );
+    }
+
+    #[test]
+    fn test_scoped_to_package_keeps_only_package_changes() {
+        use crate::index::workspace::WorkspaceKind;
+
+        let context = WorkContext {
+            branch: "main".to_string(),
+            uncommitted_files: vec![
+                PathBuf::from("packages/api/src/auth/login.ts"),
+                PathBuf::from("packages/web/src/app.tsx"),
+            ],
+            staged_files: vec![PathBuf::from("packages/api/src/auth/session.ts")],
+            untracked_files: vec![PathBuf::from("README.md")],
+            inferred_focus: None,
+            modified_count: 4,
+            repo_root: PathBuf::from("/repo"),
+        };
+        let package = WorkspacePackage {
+            name: "api".to_string(),
+            root: PathBuf::from("packages/api"),
+            kind: WorkspaceKind::Pnpm,
+        };
+
+        let scoped = context.scoped_to_package(&package);
+        assert_eq!(scoped.modified_count, 2);
+        assert!(scoped.untracked_files.is_empty());
+        assert_eq!(scoped.inferred_focus.as_deref(), Some("auth (src)"));
+    }
 }
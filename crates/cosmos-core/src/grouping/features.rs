@@ -270,6 +270,8 @@ fn detect_layer_features(files: &[PathBuf], index: &CodebaseIndex, layer: Layer)
         }
     }
 
+    keep_headers_with_sources(&mut features, files);
+
     // Sort features by file count (larger features first), but misc always last
     features.sort_by(|a, b| {
         let a_is_misc = a.name.starts_with("other");
@@ -284,6 +286,23 @@ fn detect_layer_features(files: &[PathBuf], index: &CodebaseIndex, layer: Layer)
     features
 }
 
+/// Move C/C++ headers into the feature that holds their implementation file.
+fn keep_headers_with_sources(features: &mut Vec<Feature>, files: &[PathBuf]) {
+    for (header, source) in super::pair_headers_with_sources(files) {
+        let Some(target) = features.iter().position(|f| f.files.contains(&source)) else {
+            continue;
+        };
+        if features[target].files.contains(&header) {
+            continue;
+        }
+        for feature in features.iter_mut() {
+            feature.files.retain(|f| f != &header);
+        }
+        features[target].files.push(header);
+    }
+    features.retain(|f| !f.files.is_empty());
+}
+
 /// Group files by their semantic purpose
 fn group_by_purpose(files: &[PathBuf], index: &CodebaseIndex) -> Vec<Feature> {
     let mut purpose_groups: HashMap<String, Vec<PathBuf>> = HashMap::new();
@@ -847,8 +866,36 @@ mod tests {
             files: HashMap::new(),
             index_errors: Vec::new(),
             git_head: None,
+            packages: Vec::new(),
+            excluded_files: 0,
+            filter_fingerprint: None,
         };
 
         assert_eq!(generate_cluster_name(&files, &index, 0), "user");
     }
+
+    #[test]
+    fn test_headers_join_their_source_feature() {
+        let files = vec![
+            PathBuf::from("include/widget.h"),
+            PathBuf::from("src/widget.cpp"),
+            PathBuf::from("src/util.h"),
+            PathBuf::from("src/util.c"),
+            PathBuf::from("lib/a/log.h"),
+            PathBuf::from("lib/a/log.c"),
+            PathBuf::from("lib/b/log.c"),
+        ];
+        let pairs = crate::grouping::pair_headers_with_sources(&files);
+        assert_eq!(pairs.get(Path::new("include/widget.h")), Some(&files[1]));
+        assert_eq!(pairs.get(Path::new("src/util.h")), Some(&files[3]));
+        assert_eq!(pairs.get(Path::new("lib/a/log.h")), Some(&files[5]));
+
+        let mut features = vec![
+            Feature::new("widgets").with_files(vec![files[1].clone()]),
+            Feature::new("headers").with_files(vec![files[0].clone()]),
+        ];
+        keep_headers_with_sources(&mut features, &files);
+        assert_eq!(features.len(), 1);
+        assert_eq!(features[0].files, vec![files[1].clone(), files[0].clone()]);
+    }
 }
@@ -74,6 +74,25 @@ pub fn categorize_codebase(index: &CodebaseIndex) -> CodebaseGrouping {
         grouping.assign_file_with_confidence(path.clone(), detection.layer, detection.confidence);
     }
 
+    // Headers follow their implementation file unless they were detected explicitly.
+    for (header, source) in super::pair_headers_with_sources(index.files.keys()) {
+        let Some(source_assignment) = grouping.file_assignments.get(&source).cloned() else {
+            continue;
+        };
+        let Some(header_assignment) = grouping.file_assignments.get(&header) else {
+            continue;
+        };
+        if header_assignment.confidence != Confidence::High
+            && header_assignment.layer != source_assignment.layer
+        {
+            grouping.reassign_file_with_confidence(
+                &header,
+                source_assignment.layer,
+                source_assignment.confidence,
+            );
+        }
+    }
+
     grouping
 }
 
@@ -823,6 +842,30 @@ fn detect_by_language_conventions(path: &Path, file_index: &FileIndex) -> Layer
                 Layer::Backend
             }
         }
+        Language::Java | Language::Kotlin => {
+            if filename.ends_with("Test.java")
+                || filename.ends_with("Test.kt")
+                || filename.ends_with("Tests.java")
+                || filename.ends_with("Tests.kt")
+            {
+                Layer::Tests
+            } else {
+                Layer::Backend
+            }
+        }
+        Language::C | Language::Cpp => {
+            // gtest/catch conventions: foo_unittest.cc, foo_tests.cpp, FooTest.cpp
+            let stem = path.file_stem().and_then(|n| n.to_str()).unwrap_or("");
+            if stem.ends_with("_unittest")
+                || stem.ends_with("_tests")
+                || stem.ends_with("Test")
+                || stem.ends_with("Tests")
+            {
+                Layer::Tests
+            } else {
+                Layer::Backend
+            }
+        }
         Language::Unknown => Layer::Unknown,
     }
 }
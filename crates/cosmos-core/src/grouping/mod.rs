@@ -82,6 +82,63 @@ pub fn display_name_with_context(path: &Path) -> String {
     filename.to_string()
 }
 
+/// C/C++ header extensions that pair with an implementation file
+const HEADER_EXTENSIONS: &[&str] = &["h", "hh", "hpp", "hxx"];
+
+/// C/C++ implementation extensions
+const SOURCE_EXTENSIONS: &[&str] = &["c", "cc", "cpp", "cxx"];
+
+fn has_extension(path: &Path, extensions: &[&str]) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|ext| extensions.iter().any(|e| ext.eq_ignore_ascii_case(e)))
+        .unwrap_or(false)
+}
+
+/// Pair C/C++ headers with their implementation files, keyed by header.
+///
+/// A header pairs with the same-stem source in its own directory, or failing that
+/// with the only same-stem source in the repo (e.g. `include/widget.h` and
+/// `src/widget.cpp`). Ambiguous stems are left unpaired.
+pub fn pair_headers_with_sources<'a>(
+    files: impl IntoIterator<Item = &'a PathBuf>,
+) -> HashMap<PathBuf, PathBuf> {
+    let mut headers = Vec::new();
+    let mut sources_by_stem: HashMap<String, Vec<&PathBuf>> = HashMap::new();
+    for file in files {
+        let Some(stem) = file.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        if has_extension(file, HEADER_EXTENSIONS) {
+            headers.push((file, stem.to_string()));
+        } else if has_extension(file, SOURCE_EXTENSIONS) {
+            sources_by_stem
+                .entry(stem.to_string())
+                .or_default()
+                .push(file);
+        }
+    }
+
+    let mut pairs = HashMap::new();
+    for (header, stem) in headers {
+        let Some(candidates) = sources_by_stem.get(&stem) else {
+            continue;
+        };
+        let sibling = candidates
+            .iter()
+            .find(|source| source.parent() == header.parent());
+        let paired = match sibling {
+            Some(source) => Some(*source),
+            None if candidates.len() == 1 => Some(candidates[0]),
+            None => None,
+        };
+        if let Some(source) = paired {
+            pairs.insert(header.clone(), source.clone());
+        }
+    }
+    pairs
+}
+
 /// Architectural layer classification
 #[derive(
     Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, PartialOrd, Ord, Default,
@@ -0,0 +1,142 @@
+//! Composite code health score.
+//!
+//! Computed once per suggestion run and stored with it, so the TUI header and `cosmos stats`
+//! can show how the repo is trending. The score starts at 100 and loses points for open
+//! findings (weighted by priority, per thousand lines), for complexity hotspots, and for
+//! missing line coverage when an lcov report is present.
+
+use crate::index::{CodebaseIndex, SymbolKind};
+use crate::suggest::{Priority, Suggestion};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Function complexity at or above which a function counts as a hotspot.
+pub const HOTSPOT_COMPLEXITY: f64 = 15.0;
+
+/// Most points each component can take off the score.
+const MAX_FINDINGS_PENALTY: f64 = 50.0;
+const MAX_HOTSPOT_PENALTY: f64 = 30.0;
+const MAX_COVERAGE_PENALTY: f64 = 20.0;
+
+/// Where coverage tools usually leave an lcov report, relative to the repo root.
+const LCOV_PATHS: &[&str] = &[
+    "lcov.info",
+    "coverage/lcov.info",
+    "target/lcov.info",
+    "target/coverage/lcov.info",
+];
+
+const SPARK_LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct HealthScore {
+    /// 0 (worst) to 100 (no findings, no hotspots, full coverage)
+    pub score: u8,
+    /// Priority-weighted findings per thousand lines (high 3, medium 2, low 1)
+    pub finding_density: f64,
+    /// Functions with complexity at or above `HOTSPOT_COMPLEXITY`
+    pub hotspots: usize,
+    /// Line coverage percentage, when a coverage report was found
+    #[serde(default)]
+    pub coverage: Option<f64>,
+}
+
+impl HealthScore {
+    pub fn compute(
+        index: &CodebaseIndex,
+        suggestions: &[Suggestion],
+        coverage: Option<f64>,
+    ) -> Self {
+        let kloc = (index.stats().total_loc as f64 / 1000.0).max(1.0);
+        let weighted: f64 = suggestions
+            .iter()
+            .filter(|s| !s.applied)
+            .map(|s| match s.priority {
+                Priority::High => 3.0,
+                Priority::Medium => 2.0,
+                Priority::Low => 1.0,
+            })
+            .sum();
+        let finding_density = weighted / kloc;
+        let hotspots = index
+            .files
+            .values()
+            .flat_map(|file| &file.symbols)
+            .filter(|s| matches!(s.kind, SymbolKind::Function | SymbolKind::Method))
+            .filter(|s| s.complexity >= HOTSPOT_COMPLEXITY)
+            .count();
+
+        let findings_penalty = (finding_density * 5.0).min(MAX_FINDINGS_PENALTY);
+        let hotspot_penalty = (hotspots as f64 / kloc * 10.0).min(MAX_HOTSPOT_PENALTY);
+        let coverage_penalty = coverage
+            .map(|pct| (100.0 - pct.clamp(0.0, 100.0)) / 100.0 * MAX_COVERAGE_PENALTY)
+            .unwrap_or(0.0);
+        let score = (100.0 - findings_penalty - hotspot_penalty - coverage_penalty)
+            .round()
+            .clamp(0.0, 100.0) as u8;
+
+        Self {
+            score,
+            finding_density,
+            hotspots,
+            coverage,
+        }
+    }
+}
+
+/// Line coverage from the first lcov report found in the repo, as a percentage.
+pub fn load_line_coverage(repo_root: &Path) -> Option<f64> {
+    LCOV_PATHS
+        .iter()
+        .find_map(|rel| std::fs::read_to_string(repo_root.join(rel)).ok())
+        .and_then(|content| parse_lcov(&content))
+}
+
+/// Sum of `LH` (lines hit) over `LF` (lines found) across every record.
+fn parse_lcov(content: &str) -> Option<f64> {
+    let (mut found, mut hit) = (0u64, 0u64);
+    for line in content.lines() {
+        if let Some(n) = line.strip_prefix("LF:") {
+            found += n.trim().parse::<u64>().unwrap_or(0);
+        } else if let Some(n) = line.strip_prefix("LH:") {
+            hit += n.trim().parse::<u64>().unwrap_or(0);
+        }
+    }
+    (found > 0).then(|| hit as f64 / found as f64 * 100.0)
+}
+
+/// One block character per score, scaled between the lowest and highest score shown.
+pub fn sparkline(scores: &[u8]) -> String {
+    let (Some(&min), Some(&max)) = (scores.iter().min(), scores.iter().max()) else {
+        return String::new();
+    };
+    let span = (max - min).max(1) as usize;
+    scores
+        .iter()
+        .map(|&score| {
+            let level = if max == min {
+                SPARK_LEVELS.len() / 2
+            } else {
+                (score - min) as usize * (SPARK_LEVELS.len() - 1) / span
+            };
+            SPARK_LEVELS[level]
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_lcov_and_draws_sparklines() {
+        let lcov =
+            "SF:src/a.rs\nLF:80\nLH:60\nend_of_record\nSF:src/b.rs\nLF:20\nLH:20\nend_of_record\n";
+        assert_eq!(parse_lcov(lcov), Some(80.0));
+        assert_eq!(parse_lcov("SF:src/a.rs\nend_of_record\n"), None);
+
+        assert_eq!(sparkline(&[]), "");
+        assert_eq!(sparkline(&[70, 70]), "▅▅");
+        assert_eq!(sparkline(&[60, 80, 74]), "▁█▅");
+    }
+}
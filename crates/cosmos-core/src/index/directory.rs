@@ -0,0 +1,283 @@
+//! Directory-level summaries rolled up from the files beneath each directory.
+//!
+//! A summary says what a directory holds, which files other code enters it through, and its
+//! most prominent public symbols. It costs no AI calls, so prompts can carry one line per
+//! relevant directory instead of whole files.
+
+use super::{CodebaseIndex, FileIndex, SymbolKind, Visibility};
+use crate::util::hash_str;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+/// File stems that conventionally mark a module's entry point.
+const ENTRY_STEMS: &[&str] = &["mod", "lib", "main", "index", "__init__", "app"];
+const MAX_ENTRY_POINTS: usize = 3;
+const MAX_KEY_EXPORTS: usize = 6;
+const MAX_PURPOSES: usize = 2;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DirectorySummary {
+    /// Repo-relative directory
+    pub path: PathBuf,
+    /// What the directory holds, in a sentence or two
+    pub purpose: String,
+    pub file_count: usize,
+    pub loc: usize,
+    /// Conventional entry files first, then the files most used from outside the directory
+    pub entry_points: Vec<PathBuf>,
+    /// Public types and functions defined directly or in nested files
+    pub key_exports: Vec<String>,
+    /// Hash over the content hashes of every file beneath, for cache invalidation
+    pub fingerprint: String,
+    /// True once the AI rewrote `purpose` from the entry files (see `cosmos summaries audit`)
+    #[serde(default)]
+    pub ai_written: bool,
+}
+
+impl DirectorySummary {
+    /// Roll up the indexed files under `dir`, or `None` when no indexed file lives there.
+    pub fn synthesize(index: &CodebaseIndex, dir: &Path) -> Option<Self> {
+        let files = files_under(index, dir);
+        if files.is_empty() {
+            return None;
+        }
+
+        let mut languages: BTreeMap<String, usize> = BTreeMap::new();
+        for (_, file) in &files {
+            *languages.entry(format!("{:?}", file.language)).or_default() += 1;
+        }
+        let language = languages
+            .iter()
+            .max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0)))
+            .map(|(name, _)| name.as_str())
+            .unwrap_or("mixed");
+
+        let entry_points = entry_points(&files, dir);
+        let key_exports = key_exports(&files, &entry_points);
+        // Entry files speak for the directory; other files fill in when they have nothing.
+        let mut purposes: Vec<&str> = Vec::new();
+        for file in entry_points
+            .iter()
+            .filter_map(|path| index.files.get(path))
+            .chain(files.iter().map(|(_, file)| *file))
+        {
+            let purpose = file.summary.purpose.trim();
+            if !purpose.is_empty() && !purposes.contains(&purpose) {
+                purposes.push(purpose);
+            }
+        }
+        purposes.truncate(MAX_PURPOSES);
+        let purpose = if purposes.is_empty() {
+            let names: Vec<String> = files
+                .iter()
+                .filter(|(path, _)| path.parent() == Some(dir))
+                .filter_map(|(path, _)| path.file_stem())
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .take(5)
+                .collect();
+            let mut purpose = format!("{} {} file(s)", files.len(), language);
+            if !names.is_empty() {
+                purpose.push_str(&format!(": {}", names.join(", ")));
+            }
+            purpose
+        } else {
+            purposes.join(" ")
+        };
+
+        Some(Self {
+            path: dir.to_path_buf(),
+            purpose,
+            file_count: files.len(),
+            loc: files.iter().map(|(_, file)| file.loc).sum(),
+            entry_points,
+            key_exports,
+            fingerprint: fingerprint_files(&files),
+            ai_written: false,
+        })
+    }
+
+    /// One line for prompts: "src/index/ (6 files, 2400 lines): ... entry: mod.rs; exports: A, B".
+    pub fn to_prompt_line(&self) -> String {
+        let mut line = format!(
+            "{}/ ({} files, {} lines): {}",
+            self.path.display(),
+            self.file_count,
+            self.loc,
+            self.purpose
+        );
+        if !self.entry_points.is_empty() {
+            let entries: Vec<String> = self
+                .entry_points
+                .iter()
+                .map(|path| path.display().to_string())
+                .collect();
+            line.push_str(&format!("; entry: {}", entries.join(", ")));
+        }
+        if !self.key_exports.is_empty() {
+            line.push_str(&format!("; exports: {}", self.key_exports.join(", ")));
+        }
+        line
+    }
+}
+
+/// Fingerprint of the files under `dir`, matching `DirectorySummary::fingerprint` when the
+/// summary is still current.
+pub fn directory_fingerprint(index: &CodebaseIndex, dir: &Path) -> Option<String> {
+    let files = files_under(index, dir);
+    (!files.is_empty()).then(|| fingerprint_files(&files))
+}
+
+impl CodebaseIndex {
+    /// Every directory that (transitively) contains an indexed file, excluding the repo root.
+    pub fn directories(&self) -> Vec<PathBuf> {
+        let mut dirs = BTreeSet::new();
+        for path in self.files.keys() {
+            let mut parent = path.parent();
+            while let Some(dir) = parent.filter(|dir| !dir.as_os_str().is_empty()) {
+                if !dirs.insert(dir.to_path_buf()) {
+                    break;
+                }
+                parent = dir.parent();
+            }
+        }
+        dirs.into_iter().collect()
+    }
+}
+
+/// Indexed files under `dir` keyed by their repo-relative path, sorted by path.
+fn files_under<'a>(index: &'a CodebaseIndex, dir: &Path) -> Vec<(&'a Path, &'a FileIndex)> {
+    let mut files: Vec<(&Path, &FileIndex)> = index
+        .files
+        .iter()
+        .filter(|(path, _)| path.starts_with(dir) && path.as_path() != dir)
+        .map(|(path, file)| (path.as_path(), file))
+        .collect();
+    files.sort_by(|a, b| a.0.cmp(b.0));
+    files
+}
+
+fn fingerprint_files(files: &[(&Path, &FileIndex)]) -> String {
+    let joined: String = files
+        .iter()
+        .map(|(path, file)| format!("{}:{}\n", path.display(), file.content_hash))
+        .collect();
+    hash_str(&joined)
+}
+
+fn entry_points(files: &[(&Path, &FileIndex)], dir: &Path) -> Vec<PathBuf> {
+    let mut entries: Vec<PathBuf> = files
+        .iter()
+        .filter(|(path, _)| path.parent() == Some(dir))
+        .filter(|(path, _)| {
+            path.file_stem()
+                .and_then(|stem| stem.to_str())
+                .is_some_and(|stem| ENTRY_STEMS.contains(&stem))
+        })
+        .map(|(path, _)| path.to_path_buf())
+        .collect();
+
+    let used_from_outside = |file: &FileIndex| {
+        file.summary
+            .used_by
+            .iter()
+            .filter(|user| !user.starts_with(dir))
+            .count()
+    };
+    let mut depended_on: Vec<(&Path, usize)> = files
+        .iter()
+        .map(|(path, file)| (*path, used_from_outside(file)))
+        .filter(|(path, count)| *count > 0 && !entries.iter().any(|entry| entry == path))
+        .collect();
+    depended_on.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+    entries.extend(depended_on.into_iter().map(|(path, _)| path.to_path_buf()));
+    entries.truncate(MAX_ENTRY_POINTS);
+    entries
+}
+
+fn key_exports(files: &[(&Path, &FileIndex)], entry_points: &[PathBuf]) -> Vec<String> {
+    // Entry files first: what they export is the directory's public face.
+    let is_entry = |path: &Path| entry_points.iter().any(|entry| entry == path);
+    let mut ordered: Vec<&FileIndex> = files
+        .iter()
+        .filter(|(path, _)| is_entry(path))
+        .map(|(_, file)| *file)
+        .collect();
+    ordered.extend(
+        files
+            .iter()
+            .filter(|(path, _)| !is_entry(path))
+            .map(|(_, file)| *file),
+    );
+
+    let mut seen = BTreeSet::new();
+    ordered
+        .iter()
+        .flat_map(|file| &file.symbols)
+        .filter(|symbol| symbol.visibility == Visibility::Public)
+        .filter(|symbol| {
+            matches!(
+                symbol.kind,
+                SymbolKind::Struct
+                    | SymbolKind::Enum
+                    | SymbolKind::Trait
+                    | SymbolKind::Class
+                    | SymbolKind::Interface
+                    | SymbolKind::Function
+            )
+        })
+        .filter(|symbol| seen.insert(symbol.name.clone()))
+        .map(|symbol| symbol.name.clone())
+        .take(MAX_KEY_EXPORTS)
+        .collect()
+}
+
+#[cfg(all(test, feature = "native"))]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn rolls_up_files_beneath_a_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let files = [
+            ("src/main.rs", "mod store;\nfn main() { store::open(); }\n"),
+            (
+                "src/store/mod.rs",
+                "pub mod cache;\npub struct Store;\npub fn open() -> Store { Store }\n",
+            ),
+            ("src/store/cache.rs", "pub struct Cache;\nfn evict() {}\n"),
+        ];
+        for (rel, content) in files {
+            let path = dir.path().join(rel);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+        let index = CodebaseIndex::new(dir.path()).unwrap();
+
+        assert_eq!(
+            index.directories(),
+            vec![PathBuf::from("src"), PathBuf::from("src/store")]
+        );
+
+        let store = DirectorySummary::synthesize(&index, Path::new("src/store")).unwrap();
+        assert_eq!(store.file_count, 2);
+        assert_eq!(store.entry_points, vec![PathBuf::from("src/store/mod.rs")]);
+        assert_eq!(
+            store.key_exports[..2],
+            ["Store".to_string(), "open".to_string()]
+        );
+        assert!(store.key_exports.contains(&"Cache".to_string()));
+        assert!(!store.key_exports.contains(&"evict".to_string()));
+        assert!(store.to_prompt_line().starts_with("src/store/ (2 files"));
+        assert_eq!(
+            directory_fingerprint(&index, Path::new("src/store")).as_deref(),
+            Some(store.fingerprint.as_str())
+        );
+
+        let src = DirectorySummary::synthesize(&index, Path::new("src")).unwrap();
+        assert_eq!(src.file_count, 3);
+        assert_ne!(src.fingerprint, store.fingerprint);
+        assert!(DirectorySummary::synthesize(&index, Path::new("docs")).is_none());
+    }
+}
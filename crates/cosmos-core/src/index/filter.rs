@@ -0,0 +1,231 @@
+//! Include/exclude globs for indexing
+//!
+//! Two sources, both optional and both relative to the repo root:
+//!
+//! - `.cosmosignore`: one gitignore-style pattern per line (`#` comments, `!` to re-include,
+//!   trailing `/` for directories, leading `/` to anchor at the root).
+//! - `.cosmos/config.toml`:
+//!
+//! ```toml
+//! [index]
+//! include = ["src/", "packages/*/src/"]  # when set, only matching files are indexed
+//! exclude = ["**/*.generated.ts", "fixtures/"]
+//! ```
+//!
+//! Built-in ignores (`target`, `node_modules`, dotfiles, ...) still apply on top.
+
+use crate::util::hash_str;
+use glob::{MatchOptions, Pattern};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// Repo ignore file, relative to the repo root.
+pub const COSMOSIGNORE_FILE: &str = ".cosmosignore";
+
+/// Repo config file holding the `[index]` table, relative to the repo root.
+const REPO_CONFIG_PATH: &str = ".cosmos/config.toml";
+
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+#[derive(Debug, Default, Deserialize)]
+struct RepoIndexConfig {
+    #[serde(default)]
+    index: IndexSection,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct IndexSection {
+    include: Vec<String>,
+    exclude: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+struct Rule {
+    pattern: Pattern,
+    dir_only: bool,
+}
+
+impl Rule {
+    fn parse(raw: &str) -> Result<Self, String> {
+        let mut body = raw.trim();
+        let dir_only = body.ends_with('/');
+        body = body.trim_end_matches('/');
+        let anchored = body.starts_with('/');
+        body = body.trim_start_matches('/');
+        if body.is_empty() {
+            return Err(format!("'{}' is empty", raw));
+        }
+        // Like gitignore, a pattern without a slash matches at any depth.
+        let glob = if anchored || body.contains('/') {
+            body.to_string()
+        } else {
+            format!("**/{}", body)
+        };
+        let pattern = Pattern::new(&glob).map_err(|e| format!("'{}': {}", raw, e))?;
+        Ok(Self { pattern, dir_only })
+    }
+
+    /// Whether the rule matches `path` itself or one of its parent directories.
+    fn matches(&self, path: &Path) -> bool {
+        if !self.dir_only && self.pattern.matches_path_with(path, MATCH_OPTIONS) {
+            return true;
+        }
+        path.ancestors()
+            .skip(1)
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .any(|dir| self.pattern.matches_path_with(dir, MATCH_OPTIONS))
+    }
+}
+
+/// Repo-configured include/exclude rules for the indexer.
+#[derive(Debug, Clone, Default)]
+pub struct IndexFilter {
+    include: Vec<Rule>,
+    exclude: Vec<Rule>,
+    reinclude: Vec<Rule>,
+    fingerprint: Option<String>,
+}
+
+impl IndexFilter {
+    /// Load `.cosmosignore` and the `[index]` table of `.cosmos/config.toml`.
+    /// Missing files mean no extra rules; invalid patterns are reported together.
+    pub fn load(root: &Path) -> anyhow::Result<Self> {
+        let ignore = read_optional(&root.join(COSMOSIGNORE_FILE))?;
+        let config = match read_optional(&root.join(REPO_CONFIG_PATH))? {
+            Some(content) => {
+                toml::from_str::<RepoIndexConfig>(&content)
+                    .map_err(|e| anyhow::anyhow!("Invalid {}: {}", REPO_CONFIG_PATH, e))?
+                    .index
+            }
+            None => IndexSection::default(),
+        };
+        Self::parse(
+            ignore.as_deref().unwrap_or(""),
+            &config.include,
+            &config.exclude,
+        )
+        .map_err(|problems| anyhow::anyhow!("Invalid index globs: {}", problems.join("; ")))
+    }
+
+    fn parse(ignore: &str, include: &[String], exclude: &[String]) -> Result<Self, Vec<String>> {
+        let mut filter = Self::default();
+        let mut problems = Vec::new();
+        let mut push = |target: &mut Vec<Rule>, source: &str, raw: &str| match Rule::parse(raw) {
+            Ok(rule) => target.push(rule),
+            Err(e) => problems.push(format!("{} {}", source, e)),
+        };
+        for line in ignore.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            match line.strip_prefix('!') {
+                Some(negated) => push(&mut filter.reinclude, COSMOSIGNORE_FILE, negated),
+                None => push(&mut filter.exclude, COSMOSIGNORE_FILE, line),
+            }
+        }
+        for raw in include {
+            push(&mut filter.include, "index.include", raw);
+        }
+        for raw in exclude {
+            push(&mut filter.exclude, "index.exclude", raw);
+        }
+        if !problems.is_empty() {
+            return Err(problems);
+        }
+
+        if !filter.is_empty() {
+            let rules = |rules: &[Rule]| -> String {
+                rules
+                    .iter()
+                    .map(|rule| format!("{}{}", rule.pattern.as_str(), rule.dir_only))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            };
+            filter.fingerprint = Some(hash_str(&format!(
+                "include={};exclude={};reinclude={}",
+                rules(&filter.include),
+                rules(&filter.exclude),
+                rules(&filter.reinclude)
+            )));
+        }
+        Ok(filter)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty() && self.reinclude.is_empty()
+    }
+
+    /// Stable hash of the rules (None when there are none), so cached indexes built
+    /// under different rules can be told apart.
+    pub fn fingerprint(&self) -> Option<&str> {
+        self.fingerprint.as_deref()
+    }
+
+    /// Whether a repo-relative file path is left out of the index.
+    pub fn excludes(&self, rel_path: &Path) -> bool {
+        let path = normalize(rel_path);
+        if !self.include.is_empty() && !self.include.iter().any(|rule| rule.matches(&path)) {
+            return true;
+        }
+        self.exclude.iter().any(|rule| rule.matches(&path))
+            && !self.reinclude.iter().any(|rule| rule.matches(&path))
+    }
+}
+
+fn read_optional(path: &Path) -> anyhow::Result<Option<String>> {
+    match std::fs::read_to_string(path) {
+        Ok(content) => Ok(Some(content)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(anyhow::anyhow!("Failed to read {}: {}", path.display(), e)),
+    }
+}
+
+fn normalize(path: &Path) -> PathBuf {
+    PathBuf::from(
+        path.to_string_lossy()
+            .replace('\\', "/")
+            .trim_start_matches("./"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cosmosignore_and_config_globs_combine() {
+        let filter = IndexFilter::parse(
+            "# generated code\n*.generated.ts\nfixtures/\n/scripts\n!**/fixtures/keep.rs\n",
+            &["src/".to_string(), "scripts/**".to_string()],
+            &["src/legacy/**".to_string()],
+        )
+        .unwrap();
+
+        assert!(!filter.excludes(Path::new("src/main.rs")));
+        assert!(filter.excludes(Path::new("docs/build.rs")));
+        assert!(filter.excludes(Path::new("src/api/client.generated.ts")));
+        assert!(filter.excludes(Path::new("src/tests/fixtures/big.rs")));
+        assert!(filter.excludes(Path::new("src/legacy/old.rs")));
+        assert!(filter.excludes(Path::new("scripts/gen.py")));
+        assert!(!filter.excludes(Path::new("src/fixtures/keep.rs")));
+        assert!(filter.fingerprint().is_some());
+    }
+
+    #[test]
+    fn invalid_globs_are_reported_together() {
+        let problems = IndexFilter::parse("[unclosed\n", &[], &["/".to_string()]).unwrap_err();
+        assert_eq!(problems.len(), 2);
+        assert!(problems[0].starts_with(COSMOSIGNORE_FILE));
+        assert!(problems[1].starts_with("index.exclude"));
+
+        let empty = IndexFilter::parse("# nothing\n", &[], &[]).unwrap();
+        assert!(empty.is_empty());
+        assert!(empty.fingerprint().is_none());
+        assert!(!empty.excludes(Path::new("anything.rs")));
+    }
+}
@@ -3,13 +3,19 @@
 //! Uses tree-sitter for multi-language AST parsing to build
 //! semantic understanding of the codebase.
 
+pub mod directory;
+pub mod filter;
+#[cfg(feature = "native")]
 pub mod parser;
+pub mod workspace;
 
+#[cfg(feature = "native")]
 use crate::util::hash_str;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+#[cfg(feature = "native")]
 use walkdir::WalkDir;
 
 // ═══════════════════════════════════════════════════════════════════════════
@@ -33,6 +39,10 @@ pub enum Language {
     TypeScript,
     Python,
     Go,
+    Java,
+    Kotlin,
+    C,
+    Cpp,
     Unknown,
 }
 
@@ -44,10 +54,27 @@ impl Language {
             "ts" | "tsx" => Language::TypeScript,
             "py" | "pyi" => Language::Python,
             "go" => Language::Go,
+            "java" => Language::Java,
+            "kt" | "kts" => Language::Kotlin,
+            "c" | "h" => Language::C,
+            "cc" | "cpp" | "cxx" | "c++" | "hh" | "hpp" | "hxx" | "h++" | "ipp" => Language::Cpp,
             _ => Language::Unknown,
         }
     }
 
+    /// Language named by a markdown code fence (`rust`, `py`, `c++`, ...)
+    pub fn from_fence_tag(tag: &str) -> Self {
+        match tag.trim().to_lowercase().as_str() {
+            "rust" => Language::Rust,
+            "javascript" | "node" => Language::JavaScript,
+            "typescript" => Language::TypeScript,
+            "python" | "python3" => Language::Python,
+            "golang" => Language::Go,
+            "kotlin" => Language::Kotlin,
+            other => Self::from_extension(other),
+        }
+    }
+
     pub fn icon(&self) -> &'static str {
         match self {
             Language::Rust => "rs",
@@ -55,6 +82,10 @@ impl Language {
             Language::TypeScript => "ts",
             Language::Python => "py",
             Language::Go => "go",
+            Language::Java => "java",
+            Language::Kotlin => "kt",
+            Language::C => "c",
+            Language::Cpp => "cpp",
             Language::Unknown => "??",
         }
     }
@@ -574,7 +605,7 @@ fn resolve_import_path(import: &str, from_file: &Path, root: &Path) -> Option<Pa
             }
         } else {
             // Try common extensions
-            for ext in &["rs", "ts", "tsx", "js", "jsx", "py", "go"] {
+            for ext in &["rs", "ts", "tsx", "js", "jsx", "py", "go", "java", "kt"] {
                 let candidate = base.with_extension(ext);
                 if root.join(&candidate).exists() {
                     return Some(candidate);
@@ -688,22 +719,36 @@ pub struct CodebaseIndex {
     /// Git HEAD commit hash at time of indexing (for fast cache validation)
     #[serde(default)]
     pub git_head: Option<String>,
+    /// Monorepo packages (empty for single-project repositories)
+    #[serde(default)]
+    pub packages: Vec<workspace::WorkspacePackage>,
+    /// Supported files left out by `.cosmosignore` or `[index]` globs
+    #[serde(default)]
+    pub excluded_files: usize,
+    /// Fingerprint of the include/exclude globs the index was built with
+    #[serde(default)]
+    pub filter_fingerprint: Option<String>,
 }
 
 impl CodebaseIndex {
     /// Create a new index for a codebase
+    #[cfg(feature = "native")]
     pub fn new(root: &Path) -> anyhow::Result<Self> {
         // Capture git HEAD for fast cache validation
         let git_head = get_git_head(root);
+        let filter = filter::IndexFilter::load(root)?;
 
         let mut index = Self {
             root: root.to_path_buf(),
             files: HashMap::new(),
             index_errors: Vec::new(),
             git_head,
+            packages: workspace::detect_workspace_packages(root),
+            excluded_files: 0,
+            filter_fingerprint: filter.fingerprint().map(str::to_string),
         };
 
-        index.scan(root)?;
+        index.scan(root, &filter)?;
 
         // Build the dependency graph after all files are indexed
         index.build_dependency_graph();
@@ -712,10 +757,12 @@ impl CodebaseIndex {
     }
 
     /// Scan directory and index all supported files
-    fn scan(&mut self, root: &Path) -> anyhow::Result<()> {
+    #[cfg(feature = "native")]
+    fn scan(&mut self, root: &Path, filter: &filter::IndexFilter) -> anyhow::Result<()> {
         use rayon::prelude::*;
 
         // Phase 1: Collect all file paths (single-threaded, fast)
+        let mut excluded_files = 0usize;
         let file_entries: Vec<_> = WalkDir::new(root)
             .into_iter()
             // Never prune traversal at depth 0 (the scan root itself), even if its
@@ -728,12 +775,16 @@ impl CodebaseIndex {
                 let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
                 let language = Language::from_extension(ext);
                 if language == Language::Unknown {
-                    None
-                } else {
-                    Some((path.to_path_buf(), language))
+                    return None;
                 }
+                if filter.excludes(path.strip_prefix(root).unwrap_or(path)) {
+                    excluded_files += 1;
+                    return None;
+                }
+                Some((path.to_path_buf(), language))
             })
             .collect();
+        self.excluded_files = excluded_files;
 
         // Phase 2: Index files in parallel
         let results: Vec<_> = file_entries
@@ -766,6 +817,7 @@ impl CodebaseIndex {
     }
 
     /// Index a single file (static version for parallel processing)
+    #[cfg(feature = "native")]
     fn index_file_static(
         path: &Path,
         language: Language,
@@ -982,6 +1034,44 @@ impl CodebaseIndex {
         }
     }
 
+    /// Find the workspace package a repo-relative path belongs to
+    pub fn package_for_path(&self, path: &Path) -> Option<&workspace::WorkspacePackage> {
+        workspace::package_for_path(&self.packages, path)
+    }
+
+    /// Find a workspace package by name
+    pub fn package_named(&self, name: &str) -> Option<&workspace::WorkspacePackage> {
+        self.packages.iter().find(|p| p.name == name)
+    }
+
+    /// Copy of this index restricted to files inside one workspace package.
+    ///
+    /// Paths stay repo-relative so downstream consumers can keep reading files
+    /// from the repository root.
+    pub fn scoped_to_package(&self, package: &workspace::WorkspacePackage) -> Self {
+        let files = self
+            .files
+            .iter()
+            .filter(|(path, _)| package.contains(path))
+            .map(|(path, file)| (path.clone(), file.clone()))
+            .collect();
+        let index_errors = self
+            .index_errors
+            .iter()
+            .filter(|e| package.contains(&e.path))
+            .cloned()
+            .collect();
+        Self {
+            root: self.root.clone(),
+            files,
+            index_errors,
+            git_head: self.git_head.clone(),
+            packages: vec![package.clone()],
+            excluded_files: self.excluded_files,
+            filter_fingerprint: self.filter_fingerprint.clone(),
+        }
+    }
+
     /// Get total statistics
     pub fn stats(&self) -> IndexStats {
         IndexStats {
@@ -989,6 +1079,7 @@ impl CodebaseIndex {
             total_loc: self.files.values().map(|f| f.loc).sum(),
             symbol_count: self.files.values().map(|f| f.symbols.len()).sum(),
             skipped_files: self.index_errors.len(),
+            excluded_files: self.excluded_files,
         }
     }
 
@@ -1016,6 +1107,7 @@ pub struct IndexStats {
     pub total_loc: usize,
     pub symbol_count: usize,
     pub skipped_files: usize,
+    pub excluded_files: usize,
 }
 
 /// Flattened file tree entry for UI display
@@ -1029,6 +1121,7 @@ pub struct FlatTreeEntry {
 }
 
 /// Result of single-pass content analysis
+#[cfg(feature = "native")]
 struct ContentAnalysis {
     loc: usize,
     complexity: f64,
@@ -1038,6 +1131,7 @@ struct ContentAnalysis {
 }
 
 /// Analyze content in a single pass: count lines, calculate complexity, find TODOs
+#[cfg(feature = "native")]
 fn analyze_content_single_pass(content: &str) -> ContentAnalysis {
     let mut loc = 0;
     let mut complexity = 1.0; // Base complexity
@@ -1099,6 +1193,7 @@ fn analyze_content_single_pass(content: &str) -> ContentAnalysis {
 }
 
 /// Check if a path should be ignored
+#[cfg(feature = "native")]
 fn is_ignored(path: &Path) -> bool {
     let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
 
@@ -1140,6 +1235,7 @@ fn normalize_path(path: &Path) -> PathBuf {
 }
 
 /// Get the current git HEAD commit hash for the repository
+#[cfg(feature = "native")]
 fn get_git_head(root: &Path) -> Option<String> {
     use std::process::Command;
 
@@ -1192,10 +1288,18 @@ mod tests {
         assert_eq!(Language::from_extension("ts"), Language::TypeScript);
         assert_eq!(Language::from_extension("py"), Language::Python);
         assert_eq!(Language::from_extension("go"), Language::Go);
+        assert_eq!(Language::from_extension("java"), Language::Java);
+        assert_eq!(Language::from_extension("kt"), Language::Kotlin);
+        assert_eq!(Language::from_extension("kts"), Language::Kotlin);
+        assert_eq!(Language::from_extension("c"), Language::C);
+        assert_eq!(Language::from_extension("h"), Language::C);
+        assert_eq!(Language::from_extension("cpp"), Language::Cpp);
+        assert_eq!(Language::from_extension("hpp"), Language::Cpp);
         assert_eq!(Language::from_extension("txt"), Language::Unknown);
     }
 
     #[test]
+    #[cfg(feature = "native")]
     fn test_complexity_calculation() {
         let code = "if x { } else { } for i in items { if y { } }";
         let analysis = analyze_content_single_pass(code);
@@ -1227,6 +1331,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "native")]
     fn test_scan_does_not_ignore_root_named_target() {
         let mut parent = std::env::temp_dir();
         let nanos = SystemTime::now()
@@ -1245,4 +1350,30 @@ mod tests {
 
         let _ = fs::remove_dir_all(&parent);
     }
+
+    #[test]
+    #[cfg(feature = "native")]
+    fn test_scan_honors_cosmosignore_and_counts_exclusions() {
+        let mut root = std::env::temp_dir();
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        root.push(format!("cosmos_index_ignore_{}", nanos));
+
+        fs::create_dir_all(root.join("src/generated")).unwrap();
+        fs::write(root.join("src/main.rs"), "fn main() {}\n").unwrap();
+        fs::write(root.join("src/generated/api.rs"), "fn api() {}\n").unwrap();
+        fs::write(root.join("src/schema.gen.rs"), "fn schema() {}\n").unwrap();
+        fs::write(root.join(".cosmosignore"), "generated/\n*.gen.rs\n").unwrap();
+
+        let index = CodebaseIndex::new(&root).unwrap();
+        let stats = index.stats();
+        assert_eq!(stats.file_count, 1);
+        assert_eq!(stats.excluded_files, 2);
+        assert!(index.files.contains_key(Path::new("src/main.rs")));
+        assert!(index.filter_fingerprint.is_some());
+
+        let _ = fs::remove_dir_all(&root);
+    }
 }
@@ -5,6 +5,11 @@ use std::cell::RefCell;
 use std::path::Path;
 use tree_sitter::Parser;
 
+mod highlight;
+mod kotlin;
+
+pub use highlight::{highlight, Highlight, HighlightKind};
+
 // ═══════════════════════════════════════════════════════════════════════════
 //  THREAD-LOCAL PARSER POOL
 // ═══════════════════════════════════════════════════════════════════════════
@@ -50,6 +55,24 @@ thread_local! {
         let _ = p.set_language(&tree_sitter_go::LANGUAGE.into());
         p
     });
+
+    static JAVA_PARSER: RefCell<Parser> = RefCell::new({
+        let mut p = Parser::new();
+        let _ = p.set_language(&tree_sitter_java::LANGUAGE.into());
+        p
+    });
+
+    static C_PARSER: RefCell<Parser> = RefCell::new({
+        let mut p = Parser::new();
+        let _ = p.set_language(&tree_sitter_c::LANGUAGE.into());
+        p
+    });
+
+    static CPP_PARSER: RefCell<Parser> = RefCell::new({
+        let mut p = Parser::new();
+        let _ = p.set_language(&tree_sitter_cpp::LANGUAGE.into());
+        p
+    });
 }
 
 /// Parse content using a thread-local parser for the given language
@@ -75,6 +98,25 @@ fn parse_with_pooled_parser(
         }
         Language::Python => PYTHON_PARSER.with(|p| p.borrow_mut().parse(content, None)),
         Language::Go => GO_PARSER.with(|p| p.borrow_mut().parse(content, None)),
+        Language::Java => JAVA_PARSER.with(|p| p.borrow_mut().parse(content, None)),
+        Language::C => {
+            let tree = C_PARSER.with(|p| p.borrow_mut().parse(content, None));
+            // `.h` is shared by C and C++; retry C++ headers with the C++ grammar.
+            let is_header = path
+                .and_then(|p| p.extension())
+                .and_then(|ext| ext.to_str())
+                .map(|ext| ext.eq_ignore_ascii_case("h"))
+                .unwrap_or(false);
+            match tree {
+                Some(tree) if is_header && tree.root_node().has_error() => CPP_PARSER
+                    .with(|p| p.borrow_mut().parse(content, None))
+                    .filter(|cpp| !cpp.root_node().has_error())
+                    .or(Some(tree)),
+                other => other,
+            }
+        }
+        Language::Cpp => CPP_PARSER.with(|p| p.borrow_mut().parse(content, None)),
+        Language::Kotlin => return Err(anyhow::anyhow!("No tree-sitter grammar for Kotlin")),
         Language::Unknown => return Err(anyhow::anyhow!("Unknown language")),
     };
 
@@ -90,6 +132,9 @@ pub fn parse_file(
     if language == Language::Unknown {
         return Ok((Vec::new(), Vec::new()));
     }
+    if language == Language::Kotlin {
+        return Ok(kotlin::parse(content, path));
+    }
 
     let tree = parse_with_pooled_parser(content, language, Some(path))?;
     let root = tree.root_node();
@@ -100,7 +145,9 @@ pub fn parse_file(
         Language::JavaScript | Language::TypeScript => extract_js_symbols(&root, content, path),
         Language::Python => extract_python_symbols(&root, content, path),
         Language::Go => extract_go_symbols(&root, content, path),
-        Language::Unknown => Vec::new(),
+        Language::Java => extract_java_symbols(&root, content, path),
+        Language::C | Language::Cpp => extract_c_symbols(&root, content, path),
+        Language::Kotlin | Language::Unknown => Vec::new(),
     };
 
     let dependencies = match language {
@@ -108,7 +155,9 @@ pub fn parse_file(
         Language::JavaScript | Language::TypeScript => extract_js_deps(&root, content, path),
         Language::Python => extract_python_deps(&root, content, path),
         Language::Go => extract_go_deps(&root, content, path),
-        Language::Unknown => Vec::new(),
+        Language::Java => extract_java_deps(&root, content, path),
+        Language::C | Language::Cpp => extract_c_deps(&root, content, path),
+        Language::Kotlin | Language::Unknown => Vec::new(),
     };
 
     Ok((symbols, dependencies))
@@ -123,6 +172,9 @@ pub fn parse_file_has_errors(
     if language == Language::Unknown {
         return Ok(false);
     }
+    if language == Language::Kotlin {
+        return Ok(kotlin::syntax_error(content).is_some());
+    }
     let tree = parse_with_pooled_parser(content, language, Some(path))?;
     Ok(tree.root_node().has_error())
 }
@@ -167,12 +219,10 @@ fn extract_rust_symbols_recursive(
                     });
                 }
             }
-            "impl_item" => {
-                // Extract methods from impl blocks
-                if cursor.goto_first_child() {
-                    extract_rust_symbols_recursive(cursor, content, path, symbols);
-                    cursor.goto_parent();
-                }
+            // Extract methods from impl blocks
+            "impl_item" if cursor.goto_first_child() => {
+                extract_rust_symbols_recursive(cursor, content, path, symbols);
+                cursor.goto_parent();
             }
             "struct_item" => {
                 if let Some(name_node) = node.child_by_field_name("name") {
@@ -690,6 +740,335 @@ fn extract_go_deps(root: &tree_sitter::Node, content: &str, path: &Path) -> Vec<
     }
 }
 
+/// Extract symbols from Java code
+fn extract_java_symbols(root: &tree_sitter::Node, content: &str, path: &Path) -> Vec<Symbol> {
+    let mut symbols = Vec::new();
+    let mut cursor = root.walk();
+
+    loop {
+        let node = cursor.node();
+        let kind = match node.kind() {
+            "class_declaration" | "record_declaration" => Some(SymbolKind::Class),
+            "interface_declaration" | "annotation_type_declaration" => Some(SymbolKind::Interface),
+            "enum_declaration" => Some(SymbolKind::Enum),
+            "method_declaration" | "constructor_declaration" => Some(SymbolKind::Method),
+            _ => None,
+        };
+
+        if let Some(kind) = kind {
+            if let Some(name_node) = node.child_by_field_name("name") {
+                let complexity = if kind == SymbolKind::Method {
+                    estimate_complexity(&node, content)
+                } else {
+                    1.0
+                };
+                symbols.push(Symbol {
+                    name: get_node_text(&name_node, content),
+                    kind,
+                    file: path.to_path_buf(),
+                    line: node.start_position().row + 1,
+                    end_line: node.end_position().row + 1,
+                    complexity,
+                    visibility: java_visibility(&node, content),
+                });
+            }
+        }
+
+        if cursor.goto_first_child() {
+            continue;
+        }
+
+        while !cursor.goto_next_sibling() {
+            if !cursor.goto_parent() {
+                return symbols;
+            }
+        }
+    }
+}
+
+fn java_visibility(node: &tree_sitter::Node, content: &str) -> Visibility {
+    for i in 0..node.child_count() {
+        if let Some(child) = node.child(i) {
+            if child.kind() == "modifiers" {
+                let text = get_node_text(&child, content);
+                if text.split_whitespace().any(|m| m == "public") {
+                    return Visibility::Public;
+                }
+                if text.split_whitespace().any(|m| m == "private") {
+                    return Visibility::Private;
+                }
+            }
+        }
+    }
+    // Package-private and protected members aren't part of the public surface.
+    Visibility::Internal
+}
+
+/// Extract dependencies from Java code
+fn extract_java_deps(root: &tree_sitter::Node, content: &str, path: &Path) -> Vec<Dependency> {
+    let mut package = None;
+    let mut deps = Vec::new();
+
+    for i in 0..root.named_child_count() {
+        let Some(node) = root.named_child(i) else {
+            continue;
+        };
+        match node.kind() {
+            "package_declaration" => {
+                package = Some(
+                    get_node_text(&node, content)
+                        .trim_start_matches("package")
+                        .trim()
+                        .trim_end_matches(';')
+                        .trim()
+                        .to_string(),
+                );
+            }
+            "import_declaration" => {
+                let import_path = get_node_text(&node, content)
+                    .trim_start_matches("import")
+                    .trim()
+                    .trim_start_matches("static ")
+                    .trim()
+                    .trim_end_matches(';')
+                    .trim()
+                    .to_string();
+                deps.push(Dependency {
+                    from_file: path.to_path_buf(),
+                    is_external: jvm_import_is_external(&import_path, package.as_deref()),
+                    import_path,
+                    line: node.start_position().row + 1,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    deps
+}
+
+/// Extract symbols from C and C++ code (functions, types, namespaces, macros)
+fn extract_c_symbols(root: &tree_sitter::Node, content: &str, path: &Path) -> Vec<Symbol> {
+    let mut symbols = Vec::new();
+    extract_c_symbols_recursive(root, content, path, &mut symbols, None);
+    symbols
+}
+
+/// `member_visibility` is `Some` inside a class/struct body and tracks the
+/// current access level as access specifiers are encountered.
+fn extract_c_symbols_recursive(
+    node: &tree_sitter::Node,
+    content: &str,
+    path: &Path,
+    symbols: &mut Vec<Symbol>,
+    mut member_visibility: Option<Visibility>,
+) {
+    for i in 0..node.named_child_count() {
+        let Some(child) = node.named_child(i) else {
+            continue;
+        };
+        let line = child.start_position().row + 1;
+        let end_line = child.end_position().row + 1;
+
+        match child.kind() {
+            "access_specifier" if member_visibility.is_some() => {
+                member_visibility = Some(match get_node_text(&child, content).trim() {
+                    "public" => Visibility::Public,
+                    "private" => Visibility::Private,
+                    _ => Visibility::Internal,
+                });
+            }
+            "function_definition" | "declaration" | "field_declaration" => {
+                if let Some(name) = c_function_name(&child, content) {
+                    let qualified = name.contains("::");
+                    let is_definition = child.kind() == "function_definition";
+                    symbols.push(Symbol {
+                        name: name.rsplit("::").next().unwrap_or(&name).to_string(),
+                        kind: if member_visibility.is_some() || qualified {
+                            SymbolKind::Method
+                        } else {
+                            SymbolKind::Function
+                        },
+                        file: path.to_path_buf(),
+                        line,
+                        end_line,
+                        complexity: if is_definition {
+                            estimate_complexity(&child, content)
+                        } else {
+                            1.0
+                        },
+                        visibility: member_visibility
+                            .unwrap_or_else(|| c_storage_visibility(&child, content)),
+                    });
+                } else {
+                    // `struct Foo { ... };` and friends are declarations wrapping a specifier.
+                    extract_c_symbols_recursive(&child, content, path, symbols, member_visibility);
+                }
+            }
+            "class_specifier" | "struct_specifier" | "union_specifier" | "enum_specifier" => {
+                let Some(body) = child.child_by_field_name("body") else {
+                    continue;
+                };
+                let name = child
+                    .child_by_field_name("name")
+                    .map(|n| get_node_text(&n, content))
+                    .or_else(|| c_typedef_name(node, content));
+                if let Some(name) = name {
+                    symbols.push(Symbol {
+                        name,
+                        kind: match child.kind() {
+                            "class_specifier" => SymbolKind::Class,
+                            "enum_specifier" => SymbolKind::Enum,
+                            _ => SymbolKind::Struct,
+                        },
+                        file: path.to_path_buf(),
+                        line,
+                        end_line,
+                        complexity: 1.0,
+                        visibility: member_visibility.unwrap_or(Visibility::Public),
+                    });
+                }
+                if child.kind() != "enum_specifier" {
+                    let default = if child.kind() == "class_specifier" {
+                        Visibility::Private
+                    } else {
+                        Visibility::Public
+                    };
+                    extract_c_symbols_recursive(&body, content, path, symbols, Some(default));
+                }
+            }
+            "namespace_definition" => {
+                if let Some(name_node) = child.child_by_field_name("name") {
+                    symbols.push(Symbol {
+                        name: get_node_text(&name_node, content),
+                        kind: SymbolKind::Module,
+                        file: path.to_path_buf(),
+                        line,
+                        end_line,
+                        complexity: 1.0,
+                        visibility: Visibility::Public,
+                    });
+                }
+                if let Some(body) = child.child_by_field_name("body") {
+                    extract_c_symbols_recursive(&body, content, path, symbols, None);
+                }
+            }
+            "preproc_def" | "preproc_function_def" => {
+                if let Some(name_node) = child.child_by_field_name("name") {
+                    symbols.push(Symbol {
+                        name: get_node_text(&name_node, content),
+                        kind: if child.kind() == "preproc_def" {
+                            SymbolKind::Constant
+                        } else {
+                            SymbolKind::Function
+                        },
+                        file: path.to_path_buf(),
+                        line,
+                        end_line,
+                        complexity: 1.0,
+                        visibility: Visibility::Public,
+                    });
+                }
+            }
+            "type_definition"
+            | "template_declaration"
+            | "linkage_specification"
+            | "declaration_list"
+            | "preproc_if"
+            | "preproc_ifdef"
+            | "preproc_else"
+            | "preproc_elif" => {
+                extract_c_symbols_recursive(&child, content, path, symbols, member_visibility);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Name of the function declared or defined by `node`, if its declarator is a function.
+fn c_function_name(node: &tree_sitter::Node, content: &str) -> Option<String> {
+    let mut declarator = node.child_by_field_name("declarator")?;
+    // Unwrap pointer/reference declarators (`int *foo()`, `Foo& bar()`).
+    while declarator.kind() != "function_declarator" {
+        declarator = declarator
+            .child_by_field_name("declarator")
+            .or_else(|| declarator.named_child(declarator.named_child_count().checked_sub(1)?))?;
+    }
+    let name = declarator.child_by_field_name("declarator")?;
+    Some(get_node_text(&name, content))
+}
+
+/// Name given by `typedef struct { ... } Name;` to an anonymous specifier.
+fn c_typedef_name(parent: &tree_sitter::Node, content: &str) -> Option<String> {
+    if parent.kind() != "type_definition" {
+        return None;
+    }
+    parent
+        .child_by_field_name("declarator")
+        .map(|n| get_node_text(&n, content))
+}
+
+fn c_storage_visibility(node: &tree_sitter::Node, content: &str) -> Visibility {
+    for i in 0..node.named_child_count() {
+        if let Some(child) = node.named_child(i) {
+            if child.kind() == "storage_class_specifier"
+                && get_node_text(&child, content).trim() == "static"
+            {
+                return Visibility::Private;
+            }
+        }
+    }
+    Visibility::Public
+}
+
+/// Extract `#include` dependencies from C and C++ code
+fn extract_c_deps(root: &tree_sitter::Node, content: &str, path: &Path) -> Vec<Dependency> {
+    let mut deps = Vec::new();
+    collect_c_includes(root, content, path, &mut deps);
+    deps
+}
+
+fn collect_c_includes(
+    node: &tree_sitter::Node,
+    content: &str,
+    path: &Path,
+    deps: &mut Vec<Dependency>,
+) {
+    for i in 0..node.named_child_count() {
+        let Some(child) = node.named_child(i) else {
+            continue;
+        };
+        if child.kind() == "preproc_include" {
+            if let Some(target) = child.child_by_field_name("path") {
+                let raw = get_node_text(&target, content);
+                deps.push(Dependency {
+                    from_file: path.to_path_buf(),
+                    import_path: raw
+                        .trim_matches(|c| c == '"' || c == '<' || c == '>')
+                        .to_string(),
+                    line: child.start_position().row + 1,
+                    // `<...>` includes come from the system or third-party include path.
+                    is_external: target.kind() == "system_lib_string",
+                });
+            }
+        } else if child.kind().starts_with("preproc_") || child.kind() == "linkage_specification" {
+            collect_c_includes(&child, content, path, deps);
+        }
+    }
+}
+
+/// JVM imports are internal when they share the file's top-level package (e.g. `com.acme`).
+fn jvm_import_is_external(import_path: &str, package: Option<&str>) -> bool {
+    let Some(package) = package else {
+        return true;
+    };
+    let root = package.split('.').take(2).collect::<Vec<_>>().join(".");
+    if root.is_empty() {
+        return true;
+    }
+    !(import_path == root || import_path.starts_with(&format!("{}.", root)))
+}
+
 // Helper functions
 
 fn get_node_text(node: &tree_sitter::Node, content: &str) -> String {
@@ -711,7 +1090,10 @@ fn has_pub_modifier(node: &tree_sitter::Node, content: &str) -> bool {
 }
 
 fn estimate_complexity(node: &tree_sitter::Node, content: &str) -> f64 {
-    let text = get_node_text(node, content);
+    estimate_text_complexity(&get_node_text(node, content))
+}
+
+fn estimate_text_complexity(text: &str) -> f64 {
     let mut complexity = 1.0;
 
     // Count decision points
@@ -794,4 +1176,221 @@ mod tests {
         assert!(!symbols.is_empty());
         assert!(!parse_file_has_errors(path, content, Language::TypeScript).unwrap());
     }
+
+    #[test]
+    fn test_java_parsing() {
+        let content = r#"
+package com.acme.billing;
+
+import java.util.List;
+import com.acme.core.Money;
+
+public class InvoiceService {
+    private final List<String> ids;
+
+    public InvoiceService(List<String> ids) {
+        this.ids = ids;
+    }
+
+    public Money total() {
+        if (ids.isEmpty()) {
+            return Money.ZERO;
+        }
+        return Money.of(ids.size());
+    }
+}
+"#;
+
+        let path = Path::new("InvoiceService.java");
+        let (symbols, deps) = parse_file(path, content, Language::Java).unwrap();
+        let class = symbols
+            .iter()
+            .find(|s| s.name == "InvoiceService" && s.kind == SymbolKind::Class)
+            .unwrap();
+        assert_eq!(class.visibility, Visibility::Public);
+        assert!(class.end_line > class.line);
+        assert!(symbols
+            .iter()
+            .any(|s| s.name == "total" && s.kind == SymbolKind::Method));
+        assert_eq!(deps.len(), 2);
+        assert!(deps
+            .iter()
+            .any(|d| d.import_path == "java.util.List" && d.is_external));
+        assert!(deps
+            .iter()
+            .any(|d| d.import_path == "com.acme.core.Money" && !d.is_external));
+        assert!(!parse_file_has_errors(path, content, Language::Java).unwrap());
+        assert!(
+            parse_file_has_errors(path, "public class Broken { void f( }", Language::Java).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_kotlin_parsing() {
+        let content = r#"
+package com.acme.app
+
+import kotlinx.coroutines.flow.Flow
+import com.acme.data.UserRepository
+
+const val MAX_USERS = 10
+
+/* class NotReal { } */
+data class User(
+    val id: String,
+    val name: String,
+)
+
+class UserViewModel(private val repo: UserRepository) {
+    private val greeting = "Hello { ${repo.name} }"
+
+    fun load(): Flow<User> {
+        val local = 1
+        fun helper() = local
+        return repo.users()
+    }
+
+    companion object {
+        fun create() = UserViewModel(UserRepository())
+    }
+}
+
+internal fun String.shout(): String = uppercase()
+"#;
+
+        let path = Path::new("UserViewModel.kt");
+        let (symbols, deps) = parse_file(path, content, Language::Kotlin).unwrap();
+        let names: Vec<&str> = symbols.iter().map(|s| s.name.as_str()).collect();
+        assert!(!names.contains(&"NotReal"));
+        assert!(!names.contains(&"helper"));
+        assert!(!names.contains(&"greeting"));
+
+        let find = |name: &str| symbols.iter().find(|s| s.name == name).unwrap();
+        assert_eq!(find("MAX_USERS").kind, SymbolKind::Constant);
+        assert_eq!(find("User").end_line, find("User").line + 3);
+        let view_model = find("UserViewModel");
+        assert_eq!(view_model.kind, SymbolKind::Class);
+        assert!(view_model.end_line > find("create").line);
+        assert_eq!(find("load").kind, SymbolKind::Method);
+        assert_eq!(find("create").kind, SymbolKind::Method);
+        let shout = find("shout");
+        assert_eq!(shout.kind, SymbolKind::Function);
+        assert_eq!(shout.visibility, Visibility::Internal);
+
+        assert_eq!(deps.len(), 2);
+        assert!(deps
+            .iter()
+            .any(|d| d.import_path.starts_with("kotlinx") && d.is_external));
+        assert!(deps
+            .iter()
+            .any(|d| d.import_path.starts_with("com.acme") && !d.is_external));
+        assert!(!parse_file_has_errors(path, content, Language::Kotlin).unwrap());
+    }
+
+    #[test]
+    fn test_kotlin_syntax_errors() {
+        let path = Path::new("Broken.kt");
+        assert!(parse_file_has_errors(
+            path,
+            "fun main() {\n    println(\"hi\")\n",
+            Language::Kotlin
+        )
+        .unwrap());
+        assert!(
+            parse_file_has_errors(path, "fun main() = listOf(1, 2]\n", Language::Kotlin).unwrap()
+        );
+        assert!(parse_file_has_errors(path, "val s = \"open\n", Language::Kotlin).unwrap());
+        assert!(
+            !parse_file_has_errors(path, "val s = \"\"\"{ raw\n}}\"\"\"\n", Language::Kotlin)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_cpp_parsing() {
+        let content = r#"
+#include <vector>
+#include "widget.h"
+
+#define MAX_WIDGETS 16
+
+namespace ui {
+
+class Widget {
+public:
+    Widget();
+    int area() const { return w * h; }
+private:
+    int w;
+    int h;
+};
+
+int Widget::area_twice() const {
+    if (w > 0) { return area() * 2; }
+    return 0;
+}
+
+static int helper(int x) { return x; }
+
+}  // namespace ui
+"#;
+
+        let path = Path::new("src/widget.cpp");
+        let (symbols, deps) = parse_file(path, content, Language::Cpp).unwrap();
+        let find = |name: &str| symbols.iter().find(|s| s.name == name).unwrap();
+        assert_eq!(find("ui").kind, SymbolKind::Module);
+        assert_eq!(find("Widget").kind, SymbolKind::Class);
+        assert_eq!(find("area").kind, SymbolKind::Method);
+        assert_eq!(find("area").visibility, Visibility::Public);
+        assert_eq!(find("area_twice").kind, SymbolKind::Method);
+        assert_eq!(find("helper").kind, SymbolKind::Function);
+        assert_eq!(find("helper").visibility, Visibility::Private);
+        assert_eq!(find("MAX_WIDGETS").kind, SymbolKind::Constant);
+
+        assert_eq!(deps.len(), 2);
+        assert!(deps
+            .iter()
+            .any(|d| d.import_path == "vector" && d.is_external));
+        assert!(deps
+            .iter()
+            .any(|d| d.import_path == "widget.h" && !d.is_external));
+        assert!(!parse_file_has_errors(path, content, Language::Cpp).unwrap());
+        assert!(parse_file_has_errors(path, "int main() { return 0;", Language::Cpp).unwrap());
+    }
+
+    #[test]
+    fn test_c_header_parsing() {
+        let c_header = r#"
+#ifndef POINT_H
+#define POINT_H
+#include <stdint.h>
+
+typedef struct {
+    int32_t x;
+    int32_t y;
+} Point;
+
+Point point_add(Point a, Point b);
+#define POINT_ZERO(p) ((p).x == 0 && (p).y == 0)
+
+#endif
+"#;
+        let path = Path::new("include/point.h");
+        let (symbols, deps) = parse_file(path, c_header, Language::C).unwrap();
+        let find = |name: &str| symbols.iter().find(|s| s.name == name).unwrap();
+        assert_eq!(find("Point").kind, SymbolKind::Struct);
+        assert_eq!(find("point_add").kind, SymbolKind::Function);
+        assert_eq!(find("POINT_ZERO").kind, SymbolKind::Function);
+        assert_eq!(deps.len(), 1);
+        assert!(!parse_file_has_errors(path, c_header, Language::C).unwrap());
+
+        // C++ headers commonly use `.h`; they must not trip the syntax gate.
+        let cpp_header =
+            "namespace geo {\nclass Shape {\npublic:\n    virtual ~Shape() = default;\n};\n}\n";
+        assert!(!parse_file_has_errors(path, cpp_header, Language::C).unwrap());
+        let (symbols, _) = parse_file(path, cpp_header, Language::C).unwrap();
+        assert!(symbols
+            .iter()
+            .any(|s| s.name == "Shape" && s.kind == SymbolKind::Class));
+    }
 }
@@ -0,0 +1,154 @@
+//! Syntax highlighting from the tree-sitter grammars used for indexing
+//!
+//! Classifies the leaves of a parse tree into a handful of token kinds, which is enough to
+//! color code snippets in the UI without shipping a separate highlighting engine.
+
+use super::parse_with_pooled_parser;
+use crate::index::Language;
+use std::ops::Range;
+use tree_sitter::Node;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HighlightKind {
+    Keyword,
+    String,
+    Comment,
+    Number,
+    Function,
+    Type,
+}
+
+/// A highlighted byte range of the source
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Highlight {
+    pub range: Range<usize>,
+    pub kind: HighlightKind,
+}
+
+/// Highlighted ranges of `code`, in order and never overlapping.
+///
+/// Languages without a grammar (Kotlin, unknown fences) come back empty, as does code that
+/// fails to parse.
+pub fn highlight(code: &str, language: Language) -> Vec<Highlight> {
+    let Ok(tree) = parse_with_pooled_parser(code, language, None) else {
+        return Vec::new();
+    };
+    let mut highlights = Vec::new();
+    let mut cursor = tree.walk();
+    loop {
+        let node = cursor.node();
+        let kind = classify(node, code);
+        if let Some(kind) = kind {
+            if node.end_byte() > node.start_byte() {
+                highlights.push(Highlight {
+                    range: node.start_byte()..node.end_byte(),
+                    kind,
+                });
+            }
+        }
+        // Strings and comments are colored whole, so their children are skipped
+        let descend = !matches!(kind, Some(HighlightKind::String | HighlightKind::Comment));
+        if descend && cursor.goto_first_child() {
+            continue;
+        }
+        while !cursor.goto_next_sibling() {
+            if !cursor.goto_parent() {
+                return highlights;
+            }
+        }
+    }
+}
+
+fn classify(node: Node<'_>, code: &str) -> Option<HighlightKind> {
+    let kind = node.kind();
+    if kind.contains("comment") {
+        return Some(HighlightKind::Comment);
+    }
+    if kind.contains("string") || kind == "char_literal" || kind == "rune_literal" {
+        return Some(HighlightKind::String);
+    }
+    if node.child_count() > 0 {
+        return None;
+    }
+    if kind.contains("integer") || kind.contains("float") || kind.contains("number") {
+        return Some(HighlightKind::Number);
+    }
+    if matches!(
+        kind,
+        "true" | "false" | "none" | "null" | "nil" | "boolean_literal" | "self"
+    ) {
+        return Some(HighlightKind::Keyword);
+    }
+    if matches!(
+        kind,
+        "type_identifier" | "primitive_type" | "predefined_type" | "builtin_type"
+    ) {
+        return Some(HighlightKind::Type);
+    }
+    if !node.is_named() {
+        // Anonymous word tokens are the grammar's keywords (`fn`, `return`, `def`, ...)
+        let text = code.get(node.byte_range()).unwrap_or_default();
+        if !text.is_empty() && text.chars().all(|c| c.is_ascii_alphabetic() || c == '_') {
+            return Some(HighlightKind::Keyword);
+        }
+        return None;
+    }
+    if kind.ends_with("identifier") && is_function_name(node) {
+        return Some(HighlightKind::Function);
+    }
+    None
+}
+
+/// Whether an identifier names the function being defined or called
+fn is_function_name(node: Node<'_>) -> bool {
+    let Some(parent) = node.parent() else {
+        return false;
+    };
+    let parent_kind = parent.kind();
+    if !(parent_kind.contains("function")
+        || parent_kind.contains("method")
+        || parent_kind.contains("call"))
+    {
+        return false;
+    }
+    ["name", "function", "declarator"]
+        .iter()
+        .any(|field| parent.child_by_field_name(field) == Some(node))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(code: &str, language: Language) -> Vec<(&str, HighlightKind)> {
+        highlight(code, language)
+            .into_iter()
+            .map(|h| (&code[h.range], h.kind))
+            .collect()
+    }
+
+    #[test]
+    fn highlights_keywords_strings_comments_and_calls() {
+        let code = "// greet\nfn main() { let n: u32 = 42; println(\"hi\"); }";
+        let found = kinds(code, Language::Rust);
+        assert!(found
+            .iter()
+            .any(|(text, kind)| text.trim_end() == "// greet" && *kind == HighlightKind::Comment));
+        assert!(found.contains(&("fn", HighlightKind::Keyword)));
+        assert!(found.contains(&("let", HighlightKind::Keyword)));
+        assert!(found.contains(&("main", HighlightKind::Function)));
+        assert!(found.contains(&("println", HighlightKind::Function)));
+        assert!(found.contains(&("u32", HighlightKind::Type)));
+        assert!(found.contains(&("42", HighlightKind::Number)));
+        assert!(found.contains(&("\"hi\"", HighlightKind::String)));
+
+        let found = kinds("def f(x):\n    return None  # done\n", Language::Python);
+        assert!(found.contains(&("def", HighlightKind::Keyword)));
+        assert!(found.contains(&("f", HighlightKind::Function)));
+        assert!(found.contains(&("# done", HighlightKind::Comment)));
+
+        assert!(highlight("fun main() {}", Language::Kotlin).is_empty());
+        assert_eq!(Language::from_fence_tag("py"), Language::Python);
+        assert_eq!(Language::from_fence_tag("C++"), Language::Cpp);
+    }
+}
@@ -0,0 +1,408 @@
+//! Kotlin support without a tree-sitter grammar.
+//!
+//! There is no maintained tree-sitter Kotlin crate compatible with our
+//! tree-sitter version, so Kotlin is handled by a small scanner: strings and
+//! comments are masked out, delimiters are balance-checked for the syntax gate,
+//! and declarations are picked up line by line with brace tracking.
+
+use super::super::{Dependency, Symbol, SymbolKind, Visibility};
+use super::{estimate_text_complexity, jvm_import_is_external};
+use regex::Regex;
+use std::path::Path;
+use std::sync::OnceLock;
+
+fn declaration_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(
+            r"^\s*(?:@[\w.]+(?:\([^)]*\))?\s+)*(?P<mods>(?:(?:public|private|internal|protected|open|abstract|sealed|data|enum|annotation|inner|value|inline|override|suspend|operator|infix|tailrec|external|const|lateinit|companion|final|actual|expect)\s+)*)(?P<kw>fun\s+interface|class|interface|object|fun|val|var)\b\s*(?:<[^>]*>\s*)?(?:[\w.<>?]+\.)?(?P<name>\w+)?",
+        )
+        .expect("valid kotlin declaration regex")
+    })
+}
+
+fn import_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"^\s*import\s+(?P<path>[\w.]+(?:\.\*)?)").expect("valid kotlin import regex")
+    })
+}
+
+fn package_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"^\s*package\s+(?P<path>[\w.]+)").expect("valid kotlin package regex")
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Literal {
+    Str,
+    RawStr,
+    /// Code inside a `${...}` template, with the count of open braces
+    Template(usize),
+}
+
+struct Masked {
+    text: String,
+    /// Line (1-based) and description of an unterminated string or comment
+    unterminated: Option<(usize, &'static str)>,
+}
+
+/// Replace string and comment contents with spaces, keeping line structure intact.
+fn mask(content: &str) -> Masked {
+    let chars: Vec<char> = content.chars().collect();
+    let mut out = String::with_capacity(content.len());
+    let mut stack: Vec<Literal> = Vec::new();
+    let mut block_depth = 0usize;
+    let mut block_start = 0usize;
+    let mut line_comment = false;
+    let mut literal_start = 0usize;
+    let mut line = 1usize;
+    let mut i = 0usize;
+
+    let blank = |c: char| if c == '\n' { '\n' } else { ' ' };
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+
+        if line_comment {
+            if c == '\n' {
+                line_comment = false;
+            }
+            out.push(blank(c));
+        } else if block_depth > 0 {
+            if c == '/' && next == Some('*') {
+                block_depth += 1;
+                out.push_str("  ");
+                i += 1;
+            } else if c == '*' && next == Some('/') {
+                block_depth -= 1;
+                out.push_str("  ");
+                i += 1;
+            } else {
+                out.push(blank(c));
+            }
+        } else {
+            match stack.last().copied() {
+                Some(Literal::Str) | Some(Literal::RawStr) => {
+                    let raw = stack.last() == Some(&Literal::RawStr);
+                    if !raw && c == '\\' {
+                        out.push(' ');
+                        if let Some(escaped) = next {
+                            out.push(blank(escaped));
+                            if escaped == '\n' {
+                                line += 1;
+                            }
+                        }
+                        i += 2;
+                        continue;
+                    } else if c == '$' && next == Some('{') {
+                        stack.push(Literal::Template(0));
+                        out.push_str("  ");
+                        i += 1;
+                    } else if raw && c == '"' && chars[i..].starts_with(&['"', '"', '"']) {
+                        stack.pop();
+                        out.push_str("   ");
+                        i += 2;
+                    } else if !raw && c == '"' {
+                        stack.pop();
+                        out.push(' ');
+                    } else {
+                        out.push(blank(c));
+                    }
+                }
+                top => {
+                    let in_template = matches!(top, Some(Literal::Template(_)));
+                    if c == '/' && next == Some('/') {
+                        line_comment = true;
+                        out.push_str("  ");
+                        i += 1;
+                    } else if c == '/' && next == Some('*') {
+                        block_depth = 1;
+                        block_start = line;
+                        out.push_str("  ");
+                        i += 1;
+                    } else if c == '"' {
+                        if stack.is_empty() {
+                            literal_start = line;
+                        }
+                        if chars[i..].starts_with(&['"', '"', '"']) {
+                            stack.push(Literal::RawStr);
+                            out.push_str("   ");
+                            i += 2;
+                        } else {
+                            stack.push(Literal::Str);
+                            out.push(' ');
+                        }
+                    } else if c == '\'' {
+                        // Char literal: skip to the closing quote on this line.
+                        out.push(' ');
+                        let mut j = i + 1;
+                        while j < chars.len() && chars[j] != '\'' && chars[j] != '\n' {
+                            if chars[j] == '\\' {
+                                out.push(' ');
+                                j += 1;
+                            }
+                            if j < chars.len() && chars[j] != '\n' {
+                                out.push(' ');
+                                j += 1;
+                            }
+                        }
+                        if j < chars.len() && chars[j] == '\'' {
+                            out.push(' ');
+                            i = j + 1;
+                        } else {
+                            i = j;
+                        }
+                        continue;
+                    } else if in_template && c == '{' {
+                        if let Some(Literal::Template(depth)) = stack.last_mut() {
+                            *depth += 1;
+                        }
+                        out.push(' ');
+                    } else if in_template && c == '}' {
+                        match stack.last_mut() {
+                            Some(Literal::Template(0)) => {
+                                stack.pop();
+                            }
+                            Some(Literal::Template(depth)) => *depth -= 1,
+                            _ => {}
+                        }
+                        out.push(' ');
+                    } else if in_template {
+                        out.push(blank(c));
+                    } else {
+                        out.push(c);
+                    }
+                }
+            }
+        }
+
+        if c == '\n' {
+            line += 1;
+        }
+        i += 1;
+    }
+
+    let unterminated = if block_depth > 0 {
+        Some((block_start, "unterminated block comment"))
+    } else if !stack.is_empty() {
+        Some((literal_start, "unterminated string literal"))
+    } else {
+        None
+    };
+
+    Masked {
+        text: out,
+        unterminated,
+    }
+}
+
+/// First syntax problem found: unbalanced delimiters or an unterminated literal.
+pub(super) fn syntax_error(content: &str) -> Option<(usize, String)> {
+    let masked = mask(content);
+    if let Some((line, message)) = masked.unterminated {
+        return Some((line, message.to_string()));
+    }
+
+    let mut open: Vec<(char, usize)> = Vec::new();
+    for (idx, text) in masked.text.lines().enumerate() {
+        for c in text.chars() {
+            match c {
+                '(' | '[' | '{' => open.push((c, idx + 1)),
+                ')' | ']' | '}' => {
+                    let expected = match c {
+                        ')' => '(',
+                        ']' => '[',
+                        _ => '{',
+                    };
+                    match open.pop() {
+                        Some((found, _)) if found == expected => {}
+                        Some((found, opened)) => {
+                            return Some((
+                                idx + 1,
+                                format!("'{}' does not close '{}' from line {}", c, found, opened),
+                            ))
+                        }
+                        None => return Some((idx + 1, format!("unmatched '{}'", c))),
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    open.pop()
+        .map(|(c, line)| (line, format!("unclosed '{}'", c)))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Scope {
+    Type,
+    Body,
+}
+
+struct Pending {
+    symbol: usize,
+    paren_depth: usize,
+    scope: Scope,
+}
+
+/// Extract symbols and imports from Kotlin source.
+pub(super) fn parse(content: &str, path: &Path) -> (Vec<Symbol>, Vec<Dependency>) {
+    let masked = mask(content);
+    let original_lines: Vec<&str> = content.lines().collect();
+    let mut symbols: Vec<Symbol> = Vec::new();
+    let mut deps = Vec::new();
+    let mut package: Option<String> = None;
+
+    // Open braces, with the symbol whose body each one starts (if any).
+    let mut scopes: Vec<(Scope, Option<usize>)> = Vec::new();
+    let mut paren_depth = 0usize;
+    let mut pending: Option<Pending> = None;
+
+    for (idx, text) in masked.text.lines().enumerate() {
+        let line = idx + 1;
+
+        if scopes.is_empty() {
+            if let Some(caps) = package_re().captures(text) {
+                package = Some(caps["path"].to_string());
+                continue;
+            }
+            if let Some(caps) = import_re().captures(text) {
+                let import_path = caps["path"].to_string();
+                deps.push(Dependency {
+                    from_file: path.to_path_buf(),
+                    is_external: jvm_import_is_external(&import_path, package.as_deref()),
+                    import_path,
+                    line,
+                });
+                continue;
+            }
+        }
+
+        // Only declarations in files or type bodies are indexed; locals are skipped.
+        let in_type_or_file = scopes.iter().all(|(scope, _)| *scope == Scope::Type);
+        if pending.is_none() && in_type_or_file {
+            if let Some(declared) = declaration(text, !scopes.is_empty(), path, line) {
+                let scope = declared.1;
+                symbols.push(declared.0);
+                pending = Some(Pending {
+                    symbol: symbols.len() - 1,
+                    paren_depth,
+                    scope,
+                });
+            }
+        }
+
+        for c in text.chars() {
+            match c {
+                '(' | '[' => paren_depth += 1,
+                ')' | ']' => paren_depth = paren_depth.saturating_sub(1),
+                '{' => {
+                    let owner = match &pending {
+                        Some(p) if p.paren_depth == paren_depth => Some((p.scope, p.symbol)),
+                        _ => None,
+                    };
+                    match owner {
+                        Some((scope, symbol)) => {
+                            scopes.push((scope, Some(symbol)));
+                            pending = None;
+                        }
+                        None => scopes.push((Scope::Body, None)),
+                    }
+                }
+                '}' => {
+                    if let Some((_, Some(symbol))) = scopes.pop() {
+                        symbols[symbol].end_line = line;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // A declaration without a body ends once its signature is complete.
+        if let Some(p) = &pending {
+            symbols[p.symbol].end_line = line;
+            let trimmed = text.trim_end();
+            let continues = paren_depth > p.paren_depth
+                || trimmed.ends_with(',')
+                || trimmed.ends_with('=')
+                || trimmed.ends_with(':')
+                || trimmed.ends_with("->");
+            if !continues {
+                pending = None;
+            }
+        }
+    }
+
+    for symbol in &mut symbols {
+        if symbol.kind == SymbolKind::Function || symbol.kind == SymbolKind::Method {
+            let start = symbol.line.saturating_sub(1);
+            let end = symbol.end_line.min(original_lines.len());
+            if start < end {
+                symbol.complexity =
+                    estimate_text_complexity(&original_lines[start..end].join("\n"));
+            }
+        }
+    }
+
+    (symbols, deps)
+}
+
+fn declaration(text: &str, nested: bool, path: &Path, line: usize) -> Option<(Symbol, Scope)> {
+    let caps = declaration_re().captures(text)?;
+    let mods: Vec<&str> = caps["mods"].split_whitespace().collect();
+    let keyword = caps["kw"].split_whitespace().last().unwrap_or_default();
+    let name = match caps.name("name").map(|m| m.as_str()) {
+        Some(name) => name.to_string(),
+        // `companion object { ... }` is unnamed
+        None if keyword == "object" && mods.contains(&"companion") => "Companion".to_string(),
+        None => return None,
+    };
+
+    let (kind, scope) = match keyword {
+        "class" if mods.contains(&"enum") => (SymbolKind::Enum, Scope::Type),
+        "class" | "object" => (SymbolKind::Class, Scope::Type),
+        "interface" => (SymbolKind::Interface, Scope::Type),
+        "fun" if nested => (SymbolKind::Method, Scope::Body),
+        "fun" => (SymbolKind::Function, Scope::Body),
+        // Properties inside classes are fields, not index symbols.
+        "val" | "var" if nested => return None,
+        "val" if mods.contains(&"const") || is_screaming_case(&name) => {
+            (SymbolKind::Constant, Scope::Body)
+        }
+        "val" | "var" => (SymbolKind::Variable, Scope::Body),
+        _ => return None,
+    };
+
+    let visibility = if mods.contains(&"private") {
+        Visibility::Private
+    } else if mods.contains(&"internal") || mods.contains(&"protected") {
+        Visibility::Internal
+    } else {
+        Visibility::Public
+    };
+
+    Some((
+        Symbol {
+            name,
+            kind,
+            file: path.to_path_buf(),
+            line,
+            end_line: line,
+            complexity: 1.0,
+            visibility,
+        },
+        scope,
+    ))
+}
+
+fn is_screaming_case(name: &str) -> bool {
+    name.chars().any(|c| c.is_ascii_uppercase())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+}
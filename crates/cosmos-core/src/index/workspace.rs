@@ -0,0 +1,435 @@
+//! Monorepo workspace detection
+//!
+//! Finds the packages declared by pnpm (`pnpm-workspace.yaml`), Cargo
+//! (`[workspace] members`) and Go (`go.work`) so the index, work context and UI
+//! can scope themselves to a single package instead of the whole tree.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Component, Path, PathBuf};
+
+/// Directories never treated as workspace packages, even when a glob matches them.
+const SKIPPED_PACKAGE_DIRS: &[&str] = &["node_modules", "target", ".git", "dist", "build"];
+
+/// Which workspace manifest declared a package
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkspaceKind {
+    Pnpm,
+    Cargo,
+    GoWork,
+}
+
+impl WorkspaceKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            WorkspaceKind::Pnpm => "pnpm",
+            WorkspaceKind::Cargo => "cargo",
+            WorkspaceKind::GoWork => "go",
+        }
+    }
+}
+
+/// A single package inside a monorepo workspace
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkspacePackage {
+    /// Package name from its manifest (falls back to the directory name)
+    pub name: String,
+    /// Package directory relative to the repository root
+    pub root: PathBuf,
+    pub kind: WorkspaceKind,
+}
+
+impl WorkspacePackage {
+    /// Whether a repo-relative path lives inside this package
+    pub fn contains(&self, path: &Path) -> bool {
+        path.starts_with(&self.root)
+    }
+}
+
+/// Detect workspace packages declared at the repository root.
+///
+/// Returns an empty list for single-project repositories. Packages are sorted by
+/// root path and deduplicated when several manifests declare the same directory.
+pub fn detect_workspace_packages(root: &Path) -> Vec<WorkspacePackage> {
+    let mut packages = Vec::new();
+
+    if let Ok(content) = std::fs::read_to_string(root.join("pnpm-workspace.yaml")) {
+        let patterns = parse_pnpm_workspace_patterns(&content);
+        for dir in expand_member_patterns(root, &patterns) {
+            let name = package_json_name(&root.join(&dir)).unwrap_or_else(|| dir_name(&dir));
+            packages.push(WorkspacePackage {
+                name,
+                root: dir,
+                kind: WorkspaceKind::Pnpm,
+            });
+        }
+    }
+
+    if let Ok(content) = std::fs::read_to_string(root.join("Cargo.toml")) {
+        let (members, excludes) = parse_cargo_workspace_members(&content);
+        let mut patterns = members;
+        patterns.extend(excludes.into_iter().map(|e| format!("!{}", e)));
+        for dir in expand_member_patterns(root, &patterns) {
+            let manifest = root.join(&dir).join("Cargo.toml");
+            if !manifest.is_file() {
+                continue;
+            }
+            let name = cargo_package_name(&manifest).unwrap_or_else(|| dir_name(&dir));
+            packages.push(WorkspacePackage {
+                name,
+                root: dir,
+                kind: WorkspaceKind::Cargo,
+            });
+        }
+    }
+
+    if let Ok(content) = std::fs::read_to_string(root.join("go.work")) {
+        for dir in parse_go_work_uses(&content) {
+            if !root.join(&dir).is_dir() {
+                continue;
+            }
+            let name = go_module_name(&root.join(&dir)).unwrap_or_else(|| dir_name(&dir));
+            packages.push(WorkspacePackage {
+                name,
+                root: dir,
+                kind: WorkspaceKind::GoWork,
+            });
+        }
+    }
+
+    // The repo root itself (e.g. `use .` or a root crate) isn't a useful scope.
+    packages.retain(|p| !p.root.as_os_str().is_empty());
+    packages.sort_by(|a, b| a.root.cmp(&b.root));
+    packages.dedup_by(|a, b| a.root == b.root);
+    packages
+}
+
+/// Find the innermost package containing a repo-relative path
+pub fn package_for_path<'a>(
+    packages: &'a [WorkspacePackage],
+    path: &Path,
+) -> Option<&'a WorkspacePackage> {
+    packages
+        .iter()
+        .filter(|p| p.contains(path))
+        .max_by_key(|p| p.root.components().count())
+}
+
+/// Parse the `packages:` list of a pnpm-workspace.yaml (block or flow style).
+fn parse_pnpm_workspace_patterns(content: &str) -> Vec<String> {
+    let mut patterns = Vec::new();
+    let mut in_packages = false;
+
+    for line in content.lines() {
+        let without_comment = line.split(" #").next().unwrap_or("");
+        let trimmed = without_comment.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        let indented = line.starts_with(' ') || line.starts_with('\t');
+        if !indented {
+            in_packages = false;
+            if let Some(rest) = trimmed.strip_prefix("packages:") {
+                let rest = rest.trim();
+                if let Some(flow) = rest.strip_prefix('[').and_then(|r| r.strip_suffix(']')) {
+                    patterns.extend(flow.split(',').map(unquote).filter(|p| !p.is_empty()));
+                } else {
+                    in_packages = true;
+                }
+            }
+            continue;
+        }
+
+        if in_packages {
+            if let Some(item) = trimmed.strip_prefix('-') {
+                let item = unquote(item);
+                if !item.is_empty() {
+                    patterns.push(item);
+                }
+            }
+        }
+    }
+
+    patterns
+}
+
+/// Parse `[workspace] members` and `exclude` from a Cargo manifest.
+fn parse_cargo_workspace_members(content: &str) -> (Vec<String>, Vec<String>) {
+    let Ok(manifest) = content.parse::<toml::Table>() else {
+        return (Vec::new(), Vec::new());
+    };
+    let Some(workspace) = manifest.get("workspace").and_then(|w| w.as_table()) else {
+        return (Vec::new(), Vec::new());
+    };
+    let strings = |key: &str| -> Vec<String> {
+        workspace
+            .get(key)
+            .and_then(|v| v.as_array())
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(|v| v.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    (strings("members"), strings("exclude"))
+}
+
+/// Parse `use` directives from a go.work file (single-line and block form).
+fn parse_go_work_uses(content: &str) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    let mut in_block = false;
+
+    for line in content.lines() {
+        let trimmed = line.split("//").next().unwrap_or("").trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        if in_block {
+            if trimmed == ")" {
+                in_block = false;
+            } else if let Some(dir) = normalize_member(&unquote(trimmed)) {
+                dirs.push(dir);
+            }
+            continue;
+        }
+        if let Some(rest) = trimmed.strip_prefix("use") {
+            let rest = rest.trim();
+            if rest == "(" {
+                in_block = true;
+            } else if let Some(dir) = normalize_member(&unquote(rest)) {
+                dirs.push(dir);
+            }
+        }
+    }
+
+    dirs
+}
+
+/// Expand member globs (with `!` negations) into repo-relative package directories.
+fn expand_member_patterns(root: &Path, patterns: &[String]) -> Vec<PathBuf> {
+    let mut included = Vec::new();
+    let mut excluded = HashSet::new();
+
+    for pattern in patterns {
+        let (negated, pattern) = match pattern.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, pattern.as_str()),
+        };
+        for dir in glob_dirs(root, pattern) {
+            if negated {
+                excluded.insert(dir);
+            } else if !included.contains(&dir) {
+                included.push(dir);
+            }
+        }
+    }
+
+    included.retain(|dir| !excluded.contains(dir));
+    included
+}
+
+fn glob_dirs(root: &Path, pattern: &str) -> Vec<PathBuf> {
+    let Some(relative) = normalize_member(pattern) else {
+        return Vec::new();
+    };
+    let full = root.join(&relative);
+    let Some(full) = full.to_str() else {
+        return Vec::new();
+    };
+    let Ok(paths) = glob::glob(full) else {
+        return Vec::new();
+    };
+
+    paths
+        .filter_map(Result::ok)
+        .filter(|p| p.is_dir())
+        .filter_map(|p| p.strip_prefix(root).ok().map(Path::to_path_buf))
+        .filter(|p| {
+            !p.components().any(|c| {
+                c.as_os_str()
+                    .to_str()
+                    .is_some_and(|s| SKIPPED_PACKAGE_DIRS.contains(&s))
+            })
+        })
+        .collect()
+}
+
+/// Normalize a manifest member path; rejects absolute and parent-escaping entries.
+fn normalize_member(raw: &str) -> Option<PathBuf> {
+    let raw = raw.trim().trim_end_matches('/');
+    let mut normalized = PathBuf::new();
+    for component in Path::new(raw).components() {
+        match component {
+            Component::CurDir => {}
+            Component::Normal(part) => normalized.push(part),
+            _ => return None,
+        }
+    }
+    Some(normalized)
+}
+
+fn unquote(raw: &str) -> String {
+    raw.trim()
+        .trim_matches(|c| c == '\'' || c == '"')
+        .to_string()
+}
+
+fn dir_name(dir: &Path) -> String {
+    dir.file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn package_json_name(dir: &Path) -> Option<String> {
+    let content = std::fs::read_to_string(dir.join("package.json")).ok()?;
+    let value: serde_json::Value = serde_json::from_str(&content).ok()?;
+    value
+        .get("name")
+        .and_then(|n| n.as_str())
+        .filter(|n| !n.is_empty())
+        .map(str::to_string)
+}
+
+fn cargo_package_name(manifest: &Path) -> Option<String> {
+    let content = std::fs::read_to_string(manifest).ok()?;
+    let table = content.parse::<toml::Table>().ok()?;
+    table
+        .get("package")?
+        .get("name")?
+        .as_str()
+        .map(str::to_string)
+}
+
+fn go_module_name(dir: &Path) -> Option<String> {
+    let content = std::fs::read_to_string(dir.join("go.mod")).ok()?;
+    content
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("module "))
+        .map(unquote)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn write(root: &Path, rel: &str, content: &str) {
+        let path = root.join(rel);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn detects_pnpm_packages_with_globs_and_negations() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(
+            root,
+            "pnpm-workspace.yaml",
+            "packages:\n  - 'packages/*'\n  - \"apps/web\" # main app\n  - '!packages/legacy'\n",
+        );
+        write(root, "packages/ui/package.json", r#"{"name":"@acme/ui"}"#);
+        write(root, "packages/utils/index.ts", "export {}");
+        write(root, "packages/legacy/package.json", r#"{"name":"legacy"}"#);
+        write(root, "apps/web/package.json", r#"{"name":"web"}"#);
+
+        let packages = detect_workspace_packages(root);
+        let names: Vec<_> = packages.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["web", "@acme/ui", "utils"]);
+        assert!(packages.iter().all(|p| p.kind == WorkspaceKind::Pnpm));
+    }
+
+    #[test]
+    fn parses_pnpm_flow_style_list() {
+        let patterns = parse_pnpm_workspace_patterns("packages: ['a/*', \"b\"]\n");
+        assert_eq!(patterns, vec!["a/*", "b"]);
+    }
+
+    #[test]
+    fn detects_cargo_workspace_members() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(
+            root,
+            "Cargo.toml",
+            "[workspace]\nmembers = [\"crates/*\"]\nexclude = [\"crates/scratch\"]\n",
+        );
+        write(
+            root,
+            "crates/core/Cargo.toml",
+            "[package]\nname = \"acme-core\"\n",
+        );
+        write(
+            root,
+            "crates/cli/Cargo.toml",
+            "[package]\nname = \"acme-cli\"\n",
+        );
+        write(
+            root,
+            "crates/scratch/Cargo.toml",
+            "[package]\nname = \"scratch\"\n",
+        );
+        write(root, "crates/notes/README.md", "not a crate");
+
+        let packages = detect_workspace_packages(root);
+        let names: Vec<_> = packages.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["acme-cli", "acme-core"]);
+    }
+
+    #[test]
+    fn detects_go_work_modules() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(
+            root,
+            "go.work",
+            "go 1.22\n\nuse (\n\t./svc/api\n\t./svc/worker // jobs\n)\nuse ./tools\n",
+        );
+        write(root, "svc/api/go.mod", "module example.com/svc/api\n");
+        write(root, "svc/worker/go.mod", "module example.com/svc/worker\n");
+        write(root, "tools/go.mod", "module example.com/tools\n");
+
+        let packages = detect_workspace_packages(root);
+        let roots: Vec<_> = packages.iter().map(|p| p.root.clone()).collect();
+        assert_eq!(
+            roots,
+            vec![
+                PathBuf::from("svc/api"),
+                PathBuf::from("svc/worker"),
+                PathBuf::from("tools")
+            ]
+        );
+        assert_eq!(packages[0].name, "example.com/svc/api");
+    }
+
+    #[test]
+    fn single_project_repo_has_no_packages() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "Cargo.toml", "[package]\nname = \"solo\"\n");
+        assert!(detect_workspace_packages(dir.path()).is_empty());
+    }
+
+    #[test]
+    fn package_for_path_prefers_innermost_root() {
+        let packages = vec![
+            WorkspacePackage {
+                name: "apps".into(),
+                root: PathBuf::from("apps"),
+                kind: WorkspaceKind::Pnpm,
+            },
+            WorkspacePackage {
+                name: "web".into(),
+                root: PathBuf::from("apps/web"),
+                kind: WorkspaceKind::Pnpm,
+            },
+        ];
+        let found = package_for_path(&packages, Path::new("apps/web/src/main.ts")).unwrap();
+        assert_eq!(found.name, "web");
+        assert!(package_for_path(&packages, Path::new("apps-old/x.ts")).is_none());
+    }
+}
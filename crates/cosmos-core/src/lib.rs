@@ -1,7 +1,11 @@
 //! Core domain model and contracts for Cosmos.
+//!
+//! Building an index and reading git state need the default `native` feature; the models,
+//! index queries, grouping, and protocol types also build without it (e.g. for wasm32).
 
 pub mod context;
 pub mod grouping;
+pub mod health;
 pub mod index;
 pub mod protocol;
 pub mod suggest;
@@ -1,3 +1,5 @@
+pub mod schemas;
+
 use crate::context::WorkContext;
 use crate::index::CodebaseIndex;
 use crate::suggest::Suggestion;
@@ -140,6 +142,8 @@ pub struct ApplyResult {
 #[derive(Debug, Clone)]
 pub struct ChangeSet {
     pub files: Vec<AppliedFile>,
+    /// Content before the change, by path; files missing here are treated as new
+    pub originals: Vec<AppliedFile>,
 }
 
 #[derive(Debug, Clone)]
@@ -0,0 +1,408 @@
+//! Versioned JSON schemas for structured model responses.
+//!
+//! Each structured response has one registered schema. It is sent as the request's
+//! `response_format` (named `<kind>_v<version>`), and replies are decoded through
+//! [`decode_response`], which upgrades payloads written against an earlier version (older prompt
+//! templates, or payloads saved by earlier builds) before deserializing them. Bump a kind's
+//! version whenever its schema changes shape, and add the matching step to its upgrade.
+
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+
+/// A structured response Cosmos asks a model for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ResponseKind {
+    /// Bug and security findings from a suggestion run
+    Suggestions,
+    /// Verification and plan for a suggestion before it is applied
+    Preview,
+    /// Search/replace edits that implement a fix
+    Fix,
+    /// Adversarial review of applied changes
+    Review,
+}
+
+impl ResponseKind {
+    pub const ALL: [ResponseKind; 4] = [
+        ResponseKind::Suggestions,
+        ResponseKind::Preview,
+        ResponseKind::Fix,
+        ResponseKind::Review,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ResponseKind::Suggestions => "agentic_suggestions",
+            ResponseKind::Preview => "fix_preview",
+            ResponseKind::Fix => "fix_response",
+            ResponseKind::Review => "review_response",
+        }
+    }
+
+    /// Current schema version.
+    pub fn version(self) -> u32 {
+        match self {
+            // v2 added `observed_behavior` and `impact_class`.
+            ResponseKind::Suggestions => 2,
+            // v2 replaced the `verified` flag with `verification_state`.
+            ResponseKind::Preview => 2,
+            ResponseKind::Fix => 1,
+            ResponseKind::Review => 1,
+        }
+    }
+
+    /// Name sent with the `response_format`, e.g. `fix_preview_v2`.
+    pub fn schema_name(self) -> String {
+        format!("{}_v{}", self.name(), self.version())
+    }
+
+    /// The kind a `response_format` name refers to, versioned or not.
+    pub fn from_schema_name(name: &str) -> Option<Self> {
+        let base = match name.rsplit_once("_v") {
+            Some((base, version)) if version.parse::<u32>().is_ok() => base,
+            _ => name,
+        };
+        Self::ALL.into_iter().find(|kind| kind.name() == base)
+    }
+
+    /// JSON Schema for the current version.
+    pub fn schema(self) -> Value {
+        match self {
+            ResponseKind::Suggestions => suggestions_schema(),
+            ResponseKind::Preview => preview_schema(),
+            ResponseKind::Fix => fix_schema(),
+            ResponseKind::Review => review_schema(),
+        }
+    }
+
+    /// Bring a payload written against any earlier version up to the current shape. Payloads
+    /// already current pass through unchanged.
+    pub fn upgrade(self, payload: Value) -> Value {
+        match self {
+            ResponseKind::Suggestions => upgrade_suggestions(payload),
+            ResponseKind::Preview => upgrade_preview(payload),
+            ResponseKind::Fix => upgrade_fix(payload),
+            ResponseKind::Review => upgrade_review(payload),
+        }
+    }
+}
+
+/// Deserialize a `kind` payload of any known version.
+pub fn decode_response<T: DeserializeOwned>(
+    kind: ResponseKind,
+    payload: Value,
+) -> Result<T, serde_json::Error> {
+    serde_json::from_value(kind.upgrade(payload))
+}
+
+fn suggestions_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "suggestions": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "file": { "type": "string" },
+                        "line": { "type": "integer", "minimum": 1 },
+                        "kind": {
+                            "type": "string",
+                            "enum": ["bugfix", "security", "reliability"]
+                        },
+                        "priority": { "type": "string", "enum": ["high", "medium", "low"] },
+                        "confidence": { "type": "string", "enum": ["high", "medium"] },
+                        "observed_behavior": { "type": "string" },
+                        "impact_class": {
+                            "type": "string",
+                            "enum": [
+                                "correctness",
+                                "reliability",
+                                "security",
+                                "data_integrity"
+                            ]
+                        },
+                        "summary": { "type": "string" },
+                        "detail": { "type": "string" },
+                        "evidence_quote": { "type": "string" }
+                    },
+                    "required": [
+                        "file",
+                        "kind",
+                        "priority",
+                        "confidence",
+                        "observed_behavior",
+                        "impact_class",
+                        "summary",
+                        "detail",
+                        "evidence_quote"
+                    ],
+                    "additionalProperties": false
+                }
+            }
+        },
+        "required": ["suggestions"],
+        "additionalProperties": false
+    })
+}
+
+fn preview_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "verified": {
+                "type": "boolean",
+                "description": "Whether the issue was verified to exist in the code"
+            },
+            "verification_state": {
+                "type": "string",
+                "enum": ["verified", "contradicted", "insufficient_evidence"],
+                "description": "Explicit verification contract result"
+            },
+            "friendly_title": {
+                "type": "string",
+                "description": "Friendly topic name for non-technical users"
+            },
+            "problem_summary": {
+                "type": "string",
+                "description": "Behavior-focused problem description"
+            },
+            "outcome": {
+                "type": "string",
+                "description": "What happens after the fix"
+            },
+            "verification_note": {
+                "type": "string",
+                "description": "Explanation of verification result"
+            },
+            "evidence_snippet": {
+                "type": ["string", "null"],
+                "description": "Code snippet that proves the claim"
+            },
+            "evidence_line": {
+                "type": ["integer", "null"],
+                "description": "Starting line number of the evidence snippet"
+            },
+            "description": {
+                "type": "string",
+                "description": "Human-readable description of what will change"
+            },
+            "affected_areas": {
+                "type": "array",
+                "items": { "type": "string" },
+                "description": "Which functions/areas are affected"
+            },
+            "scope": {
+                "type": "string",
+                "enum": ["small", "medium", "large"],
+                "description": "Estimated scope of the fix"
+            }
+        },
+        "required": ["verification_state", "friendly_title", "problem_summary", "outcome", "verification_note", "description", "affected_areas", "scope"],
+        "additionalProperties": false
+    })
+}
+
+fn fix_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "description": {
+                "type": "string",
+                "description": "Brief description of what was changed"
+            },
+            "modified_areas": {
+                "type": "array",
+                "items": { "type": "string" },
+                "description": "List of functions/areas that were modified"
+            },
+            "edits": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "old_string": {
+                            "type": "string",
+                            "description": "Exact text to find (must match exactly once)"
+                        },
+                        "new_string": {
+                            "type": "string",
+                            "description": "Replacement text"
+                        }
+                    },
+                    "required": ["old_string", "new_string"],
+                    "additionalProperties": false
+                },
+                "description": "Search/replace edit operations"
+            }
+        },
+        "required": ["edits"],
+        "additionalProperties": false
+    })
+}
+
+fn review_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "summary": {
+                "type": "string",
+                "description": "Brief overall assessment of the code changes"
+            },
+            "findings": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "file": {
+                            "type": "string",
+                            "description": "Path to the file containing the issue"
+                        },
+                        "line": {
+                            "type": ["integer", "null"],
+                            "description": "Line number where the issue occurs"
+                        },
+                        "severity": {
+                            "type": "string",
+                            "enum": ["critical", "warning", "suggestion", "nitpick"],
+                            "description": "Severity level of the finding"
+                        },
+                        "category": {
+                            "type": "string",
+                            "description": "Category like bug, security, performance, logic, error-handling, style"
+                        },
+                        "title": {
+                            "type": "string",
+                            "description": "Short title for the finding"
+                        },
+                        "description": {
+                            "type": "string",
+                            "description": "Detailed explanation in plain language"
+                        },
+                        "recommended": {
+                            "type": "boolean",
+                            "description": "Whether the reviewer recommends fixing this"
+                        }
+                    },
+                    "required": ["severity", "title", "description", "recommended"],
+                    "additionalProperties": false
+                },
+                "description": "List of issues found in the code"
+            }
+        },
+        "required": ["summary", "findings"],
+        "additionalProperties": false
+    })
+}
+
+/// Wrap a bare top-level array, or a single item (an object with `item_field` but no `key`),
+/// in `{ "<key>": [...] }`, the envelope every version uses.
+fn wrap_bare_items(payload: Value, key: &str, item_field: &str) -> Value {
+    match payload {
+        Value::Array(items) => json!({ key: items }),
+        Value::Object(item) if !item.contains_key(key) && item.contains_key(item_field) => {
+            json!({ key: [item] })
+        }
+        other => other,
+    }
+}
+
+/// v1 suggestions had no `observed_behavior` or `impact_class`; derive them from the summary
+/// and kind.
+fn upgrade_suggestions(payload: Value) -> Value {
+    let mut payload = wrap_bare_items(payload, "suggestions", "file");
+    if let Some(items) = payload.get_mut("suggestions").and_then(Value::as_array_mut) {
+        for item in items.iter_mut().filter_map(Value::as_object_mut) {
+            if !item.contains_key("observed_behavior") {
+                let summary = item.get("summary").cloned().unwrap_or(json!(""));
+                item.insert("observed_behavior".to_string(), summary);
+            }
+            if !item.contains_key("impact_class") {
+                let impact = match item.get("kind").and_then(Value::as_str) {
+                    Some("security") => "security",
+                    Some("reliability") => "reliability",
+                    _ => "correctness",
+                };
+                item.insert("impact_class".to_string(), json!(impact));
+            }
+        }
+    }
+    payload
+}
+
+/// v1 previews only carried `verified`; map it onto `verification_state`.
+fn upgrade_preview(mut payload: Value) -> Value {
+    if let Some(object) = payload.as_object_mut() {
+        if !object.contains_key("verification_state") {
+            let verified = object
+                .get("verified")
+                .and_then(Value::as_bool)
+                .unwrap_or(false);
+            let state = if verified { "verified" } else { "contradicted" };
+            object.insert("verification_state".to_string(), json!(state));
+        }
+    }
+    payload
+}
+
+fn upgrade_fix(payload: Value) -> Value {
+    wrap_bare_items(payload, "edits", "old_string")
+}
+
+fn upgrade_review(payload: Value) -> Value {
+    wrap_bare_items(payload, "findings", "title")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn schema_names_are_versioned_and_round_trip() {
+        assert_eq!(ResponseKind::Preview.schema_name(), "fix_preview_v2");
+        for kind in ResponseKind::ALL {
+            assert_eq!(
+                ResponseKind::from_schema_name(&kind.schema_name()),
+                Some(kind)
+            );
+            assert_eq!(ResponseKind::from_schema_name(kind.name()), Some(kind));
+            let schema = kind.schema();
+            assert_eq!(schema["type"], "object");
+            assert_eq!(schema["additionalProperties"], false);
+        }
+        assert_eq!(ResponseKind::from_schema_name("commit_message"), None);
+    }
+
+    #[test]
+    fn older_payloads_upgrade_to_the_current_shape() {
+        let v1 = json!([{
+            "file": "src/lib.rs",
+            "kind": "security",
+            "summary": "Tokens are logged",
+            "evidence_quote": "log(token)"
+        }]);
+        let upgraded = ResponseKind::Suggestions.upgrade(v1);
+        let item = &upgraded["suggestions"][0];
+        assert_eq!(item["observed_behavior"], "Tokens are logged");
+        assert_eq!(item["impact_class"], "security");
+
+        let preview = ResponseKind::Preview.upgrade(json!({ "verified": true }));
+        assert_eq!(preview["verification_state"], "verified");
+        let current = json!({ "verified": false, "verification_state": "insufficient_evidence" });
+        assert_eq!(ResponseKind::Preview.upgrade(current.clone()), current);
+
+        #[derive(serde::Deserialize)]
+        struct Edits {
+            edits: Vec<Value>,
+        }
+        let edits: Edits = decode_response(
+            ResponseKind::Fix,
+            json!([{ "old_string": "a", "new_string": "b" }]),
+        )
+        .unwrap();
+        assert_eq!(edits.edits.len(), 1);
+        let single =
+            ResponseKind::Review.upgrade(json!({ "title": "Leak", "severity": "warning" }));
+        assert_eq!(single["findings"][0]["title"], "Leak");
+    }
+}
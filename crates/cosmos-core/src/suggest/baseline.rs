@@ -0,0 +1,209 @@
+//! Baseline of acknowledged findings.
+//!
+//! `cosmos baseline create` records the findings a repo already has in `.cosmos/baseline.json`.
+//! Later runs hide findings the baseline covers, so teams adopting Cosmos on legacy code only
+//! see regressions. A finding is covered when its fingerprint (file, kind, and normalized
+//! summary) matches, or when it has the same file and kind within a few lines of a baselined
+//! one, so rewording and small code moves don't resurface it. Setting
+//! `COSMOS_SHOW_BASELINED=1` (`cosmos --show-baselined`) shows the full set.
+
+use super::{Suggestion, SuggestionKind};
+use crate::util::hash_str;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Baseline file, relative to the repo root.
+pub const BASELINE_PATH: &str = ".cosmos/baseline.json";
+
+/// Set to show baselined findings instead of hiding them.
+pub const SHOW_BASELINED_ENV: &str = "COSMOS_SHOW_BASELINED";
+
+const BASELINE_VERSION: u32 = 1;
+const LINE_SLACK: usize = 3;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BaselineEntry {
+    pub fingerprint: String,
+    pub file: PathBuf,
+    pub kind: SuggestionKind,
+    #[serde(default)]
+    pub line: Option<usize>,
+    /// For people reading the file; matching uses the fingerprint.
+    pub summary: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Baseline {
+    pub version: u32,
+    pub created_at: DateTime<Utc>,
+    pub entries: Vec<BaselineEntry>,
+}
+
+/// Stable identity of a finding across runs: file, kind, and whitespace/case-normalized summary.
+pub fn fingerprint(suggestion: &Suggestion) -> String {
+    let summary = suggestion
+        .summary
+        .split_whitespace()
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ");
+    hash_str(&format!(
+        "{}\0{:?}\0{}",
+        suggestion.file.to_string_lossy().replace('\\', "/"),
+        suggestion.kind,
+        summary
+    ))
+}
+
+impl Baseline {
+    /// Snapshot `suggestions`, sorted by file and line so the file diffs cleanly.
+    pub fn from_suggestions<'a>(suggestions: impl IntoIterator<Item = &'a Suggestion>) -> Self {
+        let mut entries: Vec<BaselineEntry> = Vec::new();
+        for suggestion in suggestions {
+            let entry = BaselineEntry {
+                fingerprint: fingerprint(suggestion),
+                file: suggestion.file.clone(),
+                kind: suggestion.kind,
+                line: suggestion.line,
+                summary: suggestion.summary.clone(),
+            };
+            if !entries.iter().any(|existing| existing == &entry) {
+                entries.push(entry);
+            }
+        }
+        entries.sort_by(|a, b| (&a.file, a.line).cmp(&(&b.file, b.line)));
+        Self {
+            version: BASELINE_VERSION,
+            created_at: Utc::now(),
+            entries,
+        }
+    }
+
+    /// Load `.cosmos/baseline.json`; a missing file means no baseline.
+    pub fn load(repo_root: &Path) -> anyhow::Result<Option<Self>> {
+        let content = match std::fs::read_to_string(repo_root.join(BASELINE_PATH)) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(anyhow::anyhow!("Failed to read {}: {}", BASELINE_PATH, e)),
+        };
+        Self::parse(&content).map(Some)
+    }
+
+    /// Parse baseline JSON, e.g. a copy read from another revision.
+    pub fn parse(content: &str) -> anyhow::Result<Self> {
+        let baseline: Self = serde_json::from_str(content)
+            .map_err(|e| anyhow::anyhow!("Invalid {}: {}", BASELINE_PATH, e))?;
+        if baseline.version > BASELINE_VERSION {
+            return Err(anyhow::anyhow!(
+                "{} is version {}; this Cosmos reads up to version {}",
+                BASELINE_PATH,
+                baseline.version,
+                BASELINE_VERSION
+            ));
+        }
+        Ok(baseline)
+    }
+
+    /// The baseline to hide findings with: none when `COSMOS_SHOW_BASELINED` is set.
+    pub fn active(repo_root: &Path) -> anyhow::Result<Option<Self>> {
+        if std::env::var_os(SHOW_BASELINED_ENV).is_some_and(|value| value != "0") {
+            return Ok(None);
+        }
+        Self::load(repo_root)
+    }
+
+    pub fn save(&self, repo_root: &Path) -> anyhow::Result<PathBuf> {
+        let path = repo_root.join(BASELINE_PATH);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, format!("{}\n", serde_json::to_string_pretty(self)?))
+            .map_err(|e| anyhow::anyhow!("Failed to write {}: {}", BASELINE_PATH, e))?;
+        Ok(path)
+    }
+
+    pub fn covers(&self, suggestion: &Suggestion) -> bool {
+        let print = fingerprint(suggestion);
+        self.entries.iter().any(|entry| {
+            entry.fingerprint == print
+                || (entry.file == suggestion.file
+                    && entry.kind == suggestion.kind
+                    && matches!(
+                        (entry.line, suggestion.line),
+                        (Some(a), Some(b)) if a.abs_diff(b) <= LINE_SLACK
+                    ))
+        })
+    }
+
+    /// Drop baselined suggestions. Returns the kept suggestions and how many were hidden.
+    pub fn retain_new(&self, suggestions: Vec<Suggestion>) -> (Vec<Suggestion>, usize) {
+        let before = suggestions.len();
+        let kept: Vec<Suggestion> = suggestions
+            .into_iter()
+            .filter(|suggestion| !self.covers(suggestion))
+            .collect();
+        let hidden = before - kept.len();
+        (kept, hidden)
+    }
+}
+
+/// Hide findings covered by the repo's active baseline, if it has one. Returns the kept
+/// suggestions and how many were hidden.
+pub fn retain_unbaselined(
+    repo_root: &Path,
+    suggestions: Vec<Suggestion>,
+) -> anyhow::Result<(Vec<Suggestion>, usize)> {
+    Ok(match Baseline::active(repo_root)? {
+        Some(baseline) => baseline.retain_new(suggestions),
+        None => (suggestions, 0),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::suggest::{test_finding, Priority};
+
+    fn finding(file: &str, line: usize, summary: &str) -> Suggestion {
+        test_finding(SuggestionKind::BugFix, Priority::High, file, line, summary)
+    }
+
+    #[test]
+    fn test_baseline_hides_known_findings_and_keeps_regressions() {
+        let known = finding("src/db.rs", 40, "Query built from  user input");
+        let baseline = Baseline::from_suggestions([&known, &known]);
+        assert_eq!(baseline.entries.len(), 1);
+
+        let (kept, hidden) = baseline.retain_new(vec![
+            finding("src/db.rs", 90, "query built from user input"),
+            finding("src/db.rs", 42, "Reworded by a later run"),
+            finding("src/db.rs", 60, "Connection is never closed"),
+            finding("src/api.rs", 40, "Query built from user input"),
+        ]);
+        assert_eq!(hidden, 2);
+        let kept: Vec<_> = kept.iter().map(|s| (s.file.clone(), s.line)).collect();
+        assert_eq!(
+            kept,
+            vec![
+                (PathBuf::from("src/db.rs"), Some(60)),
+                (PathBuf::from("src/api.rs"), Some(40)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_baseline_round_trips_and_rejects_newer_versions() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(Baseline::load(dir.path()).unwrap().is_none());
+
+        let mut baseline = Baseline::from_suggestions([&finding("src/a.rs", 1, "x")]);
+        baseline.save(dir.path()).unwrap();
+        let loaded = Baseline::load(dir.path()).unwrap().unwrap();
+        assert_eq!(loaded.entries, baseline.entries);
+
+        baseline.version = BASELINE_VERSION + 1;
+        baseline.save(dir.path()).unwrap();
+        assert!(Baseline::load(dir.path()).is_err());
+    }
+}
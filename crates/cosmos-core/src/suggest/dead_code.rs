@@ -0,0 +1,418 @@
+//! Deterministic dead code detection.
+//!
+//! Builds a cross-reference of identifiers across every indexed file and flags exported
+//! symbols nothing else uses, private functions nothing calls, and files no other file
+//! mentions. These need no LLM, so they show up even before an API key is configured.
+//! Anything that might be reached by a framework, attribute, or entry point is skipped.
+
+use super::{
+    Confidence, Criticality, Priority, Suggestion, SuggestionKind, SuggestionSource,
+    SuggestionValidationState, VerificationState,
+};
+use crate::index::{CodebaseIndex, FileIndex, Language, Symbol, SymbolKind, Visibility};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Cap on dead code findings per run so they never crowd out AI suggestions.
+pub const MAX_DEAD_CODE_SUGGESTIONS: usize = 30;
+
+/// Names shorter than this are too generic to trust a zero reference count.
+const MIN_NAME_CHARS: usize = 3;
+
+/// Attributes that do not register a symbol with anything outside the code.
+const INERT_ATTRIBUTES: &[&str] = &[
+    "#[derive(",
+    "#[allow(",
+    "#[must_use",
+    "#[inline",
+    "#[doc",
+    "#[serde(",
+    "#[non_exhaustive",
+    "#[deprecated",
+];
+
+/// Symbol names that are called by a runtime or convention rather than by code.
+const ENTRY_POINT_NAMES: &[&str] = &["main", "new", "default", "setup", "teardown"];
+
+/// File stems that are entry points or loaded by tooling.
+const ENTRY_POINT_STEMS: &[&str] = &[
+    "main", "lib", "build", "index", "mod", "__init__", "__main__", "setup", "conftest", "manage",
+    "app", "server", "cli",
+];
+
+/// Directories whose files are run or routed by tooling, not imported.
+const ENTRY_POINT_DIRS: &[&str] = &[
+    "bin",
+    "tests",
+    "test",
+    "__tests__",
+    "examples",
+    "benches",
+    "scripts",
+    "pages",
+    "app",
+    "routes",
+    "migrations",
+    "fixtures",
+];
+
+/// Which identifiers each file mentions, and how often.
+pub(super) struct CrossReferences {
+    pub(super) contents: HashMap<PathBuf, String>,
+    /// Identifier counts per file
+    pub(super) counts: HashMap<PathBuf, HashMap<String, usize>>,
+    /// Number of files mentioning each identifier
+    file_frequency: HashMap<String, usize>,
+}
+
+impl CrossReferences {
+    pub(super) fn build(index: &CodebaseIndex) -> Self {
+        let mut contents = HashMap::new();
+        let mut counts = HashMap::new();
+        let mut file_frequency: HashMap<String, usize> = HashMap::new();
+        for path in index.files.keys() {
+            let Ok(content) = std::fs::read_to_string(index.root.join(path)) else {
+                continue;
+            };
+            let mut file_counts: HashMap<String, usize> = HashMap::new();
+            for token in identifiers(&content) {
+                *file_counts.entry(token.to_string()).or_default() += 1;
+            }
+            for token in file_counts.keys() {
+                *file_frequency.entry(token.clone()).or_default() += 1;
+            }
+            counts.insert(path.clone(), file_counts);
+            contents.insert(path.clone(), content);
+        }
+        Self {
+            contents,
+            counts,
+            file_frequency,
+        }
+    }
+
+    fn count_in(&self, path: &Path, name: &str) -> usize {
+        self.counts
+            .get(path)
+            .and_then(|counts| counts.get(name))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Whether any file other than `path` mentions `name`.
+    fn referenced_elsewhere(&self, path: &Path, name: &str) -> bool {
+        if name.chars().all(is_identifier_char) {
+            let total = self.file_frequency.get(name).copied().unwrap_or(0);
+            let own = usize::from(self.count_in(path, name) > 0);
+            return total > own;
+        }
+        self.contents
+            .iter()
+            .any(|(other, content)| other != path && content.contains(name))
+    }
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+fn identifiers(content: &str) -> impl Iterator<Item = &str> {
+    content
+        .split(|c: char| !is_identifier_char(c))
+        .filter(|token| token.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_'))
+}
+
+fn has_component(path: &Path, names: &[&str]) -> bool {
+    path.parent().is_some_and(|parent| {
+        parent
+            .components()
+            .any(|c| c.as_os_str().to_str().is_some_and(|c| names.contains(&c)))
+    })
+}
+
+pub(super) fn is_test_path(path: &Path) -> bool {
+    let name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+    has_component(path, &["tests", "test", "__tests__", "spec"])
+        || name.starts_with("test_")
+        || name.starts_with("tests.")
+        || name.contains("_test.")
+        || name.contains(".test.")
+        || name.contains(".spec.")
+        || name.contains(".stories.")
+}
+
+/// Module name other files would use to refer to this file.
+pub(super) fn module_name(path: &Path) -> Option<&str> {
+    let stem = path.file_stem()?.to_str()?;
+    if matches!(stem, "mod" | "index" | "__init__") {
+        return path.parent()?.file_name()?.to_str();
+    }
+    Some(stem)
+}
+
+fn is_entry_point_file(path: &Path, file: &FileIndex, content: &str) -> bool {
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("");
+    let is_root_module = path.parent().is_none_or(|p| p.as_os_str().is_empty());
+    ENTRY_POINT_STEMS.contains(&stem)
+        || stem.starts_with('.')
+        || stem.contains("config")
+        || stem.ends_with(".d")
+        || has_component(path, ENTRY_POINT_DIRS)
+        || is_test_path(path)
+        || (is_root_module && file.language != Language::Rust)
+        || match file.language {
+            // Packages and translation units are wired up by the build, not by imports.
+            Language::Go | Language::Unknown => true,
+            Language::C | Language::Cpp => !path
+                .extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| e.starts_with('h')),
+            Language::Rust => content.contains("fn main("),
+            Language::Python => content.contains("__main__"),
+            Language::Java => content.contains("static void main"),
+            Language::Kotlin => content.contains("fun main("),
+            Language::JavaScript | Language::TypeScript => false,
+        }
+}
+
+/// Whether the symbol is defined at the top level of its file (not inside an impl, class,
+/// or inline module, where trait and framework dispatch hide call sites).
+fn is_top_level(lines: &[&str], symbol: &Symbol) -> bool {
+    lines
+        .get(symbol.line.saturating_sub(1))
+        .is_some_and(|line| line.starts_with(|c: char| !c.is_whitespace()))
+}
+
+/// Whether attributes or decorators above the symbol may register it somewhere.
+fn has_registering_attribute(lines: &[&str], symbol: &Symbol) -> bool {
+    lines[..symbol.line.saturating_sub(1).min(lines.len())]
+        .iter()
+        .rev()
+        .map(|line| line.trim())
+        .take_while(|line| line.starts_with("#[") || line.starts_with('@'))
+        .any(|line| {
+            line.starts_with('@') || !INERT_ATTRIBUTES.iter().any(|inert| line.starts_with(inert))
+        })
+}
+
+fn is_exported(symbol: &Symbol, language: Language, definition: &str) -> bool {
+    match language {
+        Language::JavaScript | Language::TypeScript => definition.starts_with("export"),
+        Language::Python => symbol.visibility == Visibility::Public,
+        _ => symbol.visibility != Visibility::Private,
+    }
+}
+
+fn kind_label(kind: SymbolKind) -> &'static str {
+    match kind {
+        SymbolKind::Function | SymbolKind::Method => "function",
+        SymbolKind::Struct => "struct",
+        SymbolKind::Class => "class",
+        SymbolKind::Enum => "enum",
+        SymbolKind::Interface => "interface",
+        SymbolKind::Trait => "trait",
+        SymbolKind::Module => "module",
+        SymbolKind::Constant => "constant",
+        SymbolKind::Variable => "variable",
+    }
+}
+
+fn dead_code_suggestion(file: &Path, summary: String, detail: String) -> Suggestion {
+    Suggestion::new(
+        SuggestionKind::Refactoring,
+        Priority::Low,
+        file.to_path_buf(),
+        summary,
+        SuggestionSource::Static,
+    )
+    .with_criticality(Criticality::Low)
+    .with_confidence(Confidence::High)
+    .with_detail(detail)
+    .with_verification_state(VerificationState::Verified)
+    .with_validation_state(SuggestionValidationState::Validated)
+}
+
+fn symbol_findings(
+    path: &Path,
+    file: &FileIndex,
+    content: &str,
+    xrefs: &CrossReferences,
+) -> Vec<Suggestion> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut findings = Vec::new();
+    for symbol in &file.symbols {
+        let considered = matches!(
+            symbol.kind,
+            SymbolKind::Function
+                | SymbolKind::Struct
+                | SymbolKind::Class
+                | SymbolKind::Enum
+                | SymbolKind::Interface
+                | SymbolKind::Trait
+                | SymbolKind::Constant
+        );
+        let name = symbol.name.as_str();
+        if !considered
+            || name.chars().count() < MIN_NAME_CHARS
+            || ENTRY_POINT_NAMES.contains(&name)
+            || name.starts_with("test")
+            || !is_top_level(&lines, symbol)
+            || has_registering_attribute(&lines, symbol)
+            || xrefs.count_in(path, name) > 1
+            || xrefs.referenced_elsewhere(path, name)
+        {
+            continue;
+        }
+
+        let definition = lines[symbol.line - 1];
+        let label = kind_label(symbol.kind);
+        let suggestion = if is_exported(symbol, file.language, definition) {
+            dead_code_suggestion(
+                path,
+                format!("Exported {} `{}` is never used", label, name),
+                format!(
+                    "No other file in the repository references `{}`, and {} does not use it \
+                     itself. Remove it, or stop exporting it if it is kept on purpose.",
+                    name,
+                    path.display()
+                ),
+            )
+        } else if symbol.kind == SymbolKind::Function {
+            dead_code_suggestion(
+                path,
+                format!("Function `{}` is never called", name),
+                format!(
+                    "`{}` is private to {} and nothing calls it, so it cannot run. Remove it, \
+                     or wire it up if a call was lost.",
+                    name,
+                    path.display()
+                ),
+            )
+        } else {
+            continue;
+        };
+        findings.push(
+            suggestion
+                .with_line(symbol.line)
+                .with_evidence(definition.trim_end().to_string()),
+        );
+    }
+    findings
+}
+
+/// Flag unused exports, unreachable functions, and orphan files in the index.
+pub fn detect_dead_code(index: &CodebaseIndex) -> Vec<Suggestion> {
+    find_dead_code(index, &CrossReferences::build(index))
+}
+
+pub(super) fn find_dead_code(index: &CodebaseIndex, xrefs: &CrossReferences) -> Vec<Suggestion> {
+    let mut paths: Vec<&PathBuf> = index.files.keys().collect();
+    paths.sort();
+
+    let mut findings = Vec::new();
+    for path in paths {
+        let (Some(file), Some(content)) = (index.files.get(path), xrefs.contents.get(path)) else {
+            continue;
+        };
+        if is_entry_point_file(path, file, content) {
+            continue;
+        }
+        if let Some(module) = module_name(path) {
+            if xrefs.contents.len() > 1 && !xrefs.referenced_elsewhere(path, module) {
+                findings.push(dead_code_suggestion(
+                    path,
+                    format!("{} is not referenced by any other file", path.display()),
+                    format!(
+                        "Nothing in the repository imports or mentions `{}`, so this file is \
+                         never compiled in or loaded. Delete it, or add the missing import.",
+                        module
+                    ),
+                ));
+                // Everything inside an orphan is dead too; one finding covers it.
+                continue;
+            }
+        }
+        findings.extend(symbol_findings(path, file, content, xrefs));
+    }
+    findings.truncate(MAX_DEAD_CODE_SUGGESTIONS);
+    findings
+}
+
+#[cfg(all(test, feature = "native"))]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn index_of(files: &[(&str, &str)]) -> (tempfile::TempDir, CodebaseIndex) {
+        let dir = tempfile::tempdir().unwrap();
+        for (rel, content) in files {
+            let path = dir.path().join(rel);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+        let index = CodebaseIndex::new(dir.path()).unwrap();
+        (dir, index)
+    }
+
+    fn summaries(findings: &[Suggestion]) -> Vec<String> {
+        findings.iter().map(|s| s.summary.clone()).collect()
+    }
+
+    #[test]
+    fn flags_unused_exports_and_uncalled_private_functions() {
+        let (_dir, index) = index_of(&[
+            (
+                "src/main.rs",
+                "mod util;\nfn main() {\n    util::used();\n}\n",
+            ),
+            (
+                "src/util.rs",
+                "pub fn used() {\n    helper();\n}\n\nfn helper() {}\n\npub fn stale() {}\n\n\
+                 fn forgotten() {}\n\n#[derive(Debug)]\npub struct Orphaned;\n\n#[test]\nfn checks() {}\n\n\
+                 impl Orphan {\n    fn fmt(&self) {}\n}\n",
+            ),
+        ]);
+
+        let findings = detect_dead_code(&index);
+        assert_eq!(
+            summaries(&findings),
+            vec![
+                "Exported function `stale` is never used",
+                "Function `forgotten` is never called",
+                "Exported struct `Orphaned` is never used",
+            ]
+        );
+        assert!(findings.iter().all(|s| s.confidence == Confidence::High
+            && s.source == SuggestionSource::Static
+            && s.file == Path::new("src/util.rs")));
+        assert_eq!(findings[0].line, Some(7));
+        assert_eq!(findings[0].evidence.as_deref(), Some("pub fn stale() {}"));
+    }
+
+    #[test]
+    fn flags_orphan_files_but_not_entry_points() {
+        let (_dir, index) = index_of(&[
+            (
+                "src/main.rs",
+                "mod live;\nfn main() {\n    live::run();\n}\n",
+            ),
+            ("src/live.rs", "pub fn run() {}\n"),
+            ("src/leftover.rs", "pub fn run_old() {}\n"),
+            ("tests/smoke.rs", "fn smoke_helper() {}\n"),
+            ("web/src/widget.ts", "export function renderWidget() {}\n"),
+        ]);
+
+        let findings = detect_dead_code(&index);
+        assert_eq!(
+            summaries(&findings),
+            vec![
+                "src/leftover.rs is not referenced by any other file",
+                "web/src/widget.ts is not referenced by any other file",
+            ]
+        );
+    }
+}
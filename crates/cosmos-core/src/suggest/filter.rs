@@ -0,0 +1,241 @@
+//! Narrowing the suggestion list from a search query.
+//!
+//! A query is free text plus `key:value` terms, and a suggestion has to match all of them:
+//!
+//! - `kind:fix`: the kind, by its label (`fix`, `cleanup`), commit type (`perf`, `docs`), or
+//!   name (`bug_fix`, `refactoring`). Repeat the term to allow several kinds.
+//! - `priority:high` (or `p:`): at least this priority
+//! - `confidence:medium` (or `c:`): at least this confidence
+//! - `file:src/**/*.rs` (or `f:`): one of the affected files matches the glob. A glob without
+//!   `/` matches file names at any depth.
+//!
+//! Free text is matched case-insensitively, word by word, against the summary, detail, and
+//! affected file paths.
+
+use super::suppress::KIND_NAMES;
+use super::{Confidence, Priority, Suggestion, SuggestionKind};
+use glob::{MatchOptions, Pattern};
+
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// Parsed suggestion filter; the default matches everything.
+#[derive(Debug, Clone, Default)]
+pub struct SuggestionFilter {
+    query: String,
+    kinds: Vec<SuggestionKind>,
+    min_priority: Option<Priority>,
+    min_confidence: Option<Confidence>,
+    files: Vec<(String, Pattern)>,
+    words: Vec<String>,
+    /// Terms that didn't parse, such as `kind:bogus`; they are left out of the filter
+    pub problems: Vec<String>,
+}
+
+impl SuggestionFilter {
+    pub fn parse(query: &str) -> Self {
+        let mut filter = Self {
+            query: query.to_string(),
+            ..Self::default()
+        };
+        for term in query.split_whitespace() {
+            let Some((key, value)) = term.split_once(':') else {
+                filter.words.push(term.to_lowercase());
+                continue;
+            };
+            // A term still being typed (`kind:`) doesn't filter yet.
+            if value.is_empty() {
+                continue;
+            }
+            let parsed = match key.to_ascii_lowercase().as_str() {
+                "kind" | "k" => parse_kind(value).map(|kind| filter.kinds.push(kind)),
+                "priority" | "p" => parse_priority(value).map(|p| filter.min_priority = Some(p)),
+                "confidence" | "c" => {
+                    parse_confidence(value).map(|c| filter.min_confidence = Some(c))
+                }
+                "file" | "f" => {
+                    let glob = if value.contains('/') {
+                        value.to_string()
+                    } else {
+                        format!("**/{}", value)
+                    };
+                    Pattern::new(&glob)
+                        .ok()
+                        .map(|pattern| filter.files.push((value.to_string(), pattern)))
+                }
+                _ => {
+                    filter.words.push(term.to_lowercase());
+                    Some(())
+                }
+            };
+            if parsed.is_none() {
+                filter.problems.push(term.to_string());
+            }
+        }
+        filter
+    }
+
+    /// The query as typed
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.query.trim().is_empty()
+    }
+
+    pub fn matches(&self, suggestion: &Suggestion) -> bool {
+        if !self.kinds.is_empty() && !self.kinds.contains(&suggestion.kind) {
+            return false;
+        }
+        if self
+            .min_priority
+            .is_some_and(|min| suggestion.priority < min)
+        {
+            return false;
+        }
+        if self
+            .min_confidence
+            .is_some_and(|min| suggestion.confidence < min)
+        {
+            return false;
+        }
+        // Walked in place rather than through `affected_files()`: the list is filtered every frame.
+        let files = || std::iter::once(&suggestion.file).chain(&suggestion.additional_files);
+        if !self
+            .files
+            .iter()
+            .all(|(_, pattern)| files().any(|file| pattern.matches_path_with(file, MATCH_OPTIONS)))
+        {
+            return false;
+        }
+        if self.words.is_empty() {
+            return true;
+        }
+        let mut haystack = suggestion.summary.to_lowercase();
+        if let Some(detail) = &suggestion.detail {
+            haystack.push('\n');
+            haystack.push_str(&detail.to_lowercase());
+        }
+        for file in files() {
+            haystack.push('\n');
+            haystack.push_str(&file.to_string_lossy().to_lowercase());
+        }
+        self.words.iter().all(|word| haystack.contains(word))
+    }
+
+    /// Compact summary for a panel title, e.g. `kind:fix priority≥high "retry"`
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if !self.kinds.is_empty() {
+            let kinds: Vec<String> = self
+                .kinds
+                .iter()
+                .map(|kind| kind.label().to_lowercase())
+                .collect();
+            parts.push(format!("kind:{}", kinds.join(",")));
+        }
+        if let Some(priority) = self.min_priority {
+            parts.push(format!("priority≥{}", level_label(priority as u8)));
+        }
+        if let Some(confidence) = self.min_confidence {
+            parts.push(format!("confidence≥{}", level_label(confidence as u8)));
+        }
+        for (glob, _) in &self.files {
+            parts.push(format!("file:{}", glob));
+        }
+        if !self.words.is_empty() {
+            parts.push(format!("\"{}\"", self.words.join(" ")));
+        }
+        for problem in &self.problems {
+            parts.push(format!("{}?", problem));
+        }
+        parts.join(" ")
+    }
+}
+
+fn parse_kind(value: &str) -> Option<SuggestionKind> {
+    let value = value.to_ascii_lowercase();
+    KIND_NAMES.iter().find_map(|(name, kind)| {
+        (*name == value
+            || kind.label().eq_ignore_ascii_case(&value)
+            || kind.conventional_type() == value)
+            .then_some(*kind)
+    })
+}
+
+fn parse_level(value: &str) -> Option<u8> {
+    match value.to_ascii_lowercase().as_str() {
+        "low" | "l" => Some(0),
+        "medium" | "med" | "m" => Some(1),
+        "high" | "h" => Some(2),
+        _ => None,
+    }
+}
+
+fn parse_priority(value: &str) -> Option<Priority> {
+    parse_level(value).map(|level| match level {
+        0 => Priority::Low,
+        1 => Priority::Medium,
+        _ => Priority::High,
+    })
+}
+
+fn parse_confidence(value: &str) -> Option<Confidence> {
+    parse_level(value).map(|level| match level {
+        0 => Confidence::Low,
+        1 => Confidence::Medium,
+        _ => Confidence::High,
+    })
+}
+
+fn level_label(level: u8) -> &'static str {
+    match level {
+        0 => "low",
+        1 => "medium",
+        _ => "high",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::suggest::test_finding;
+
+    fn suggestion(kind: SuggestionKind, priority: Priority, file: &str) -> Suggestion {
+        test_finding(kind, priority, file, 1, "Retry loop never backs off")
+    }
+
+    #[test]
+    fn terms_and_free_text_must_all_match() {
+        let bug = suggestion(SuggestionKind::BugFix, Priority::High, "src/net/client.rs");
+        let cleanup = suggestion(SuggestionKind::Refactoring, Priority::Low, "docs/guide.md");
+
+        let filter = SuggestionFilter::parse("kind:fix p:medium file:src/**/*.rs retry");
+        assert!(filter.matches(&bug));
+        assert!(!filter.matches(&cleanup));
+        assert_eq!(
+            filter.describe(),
+            "kind:fix priority≥medium file:src/**/*.rs \"retry\""
+        );
+
+        assert!(SuggestionFilter::parse("kind:refactor kind:bug_fix").matches(&cleanup));
+        assert!(SuggestionFilter::parse("f:client.rs").matches(&bug));
+        assert!(!SuggestionFilter::parse("f:*.rs").matches(&cleanup));
+        assert!(!SuggestionFilter::parse("timeout").matches(&bug));
+        assert!(!SuggestionFilter::parse("confidence:high").matches(&bug));
+    }
+
+    #[test]
+    fn unfinished_and_unknown_terms_do_not_filter() {
+        let bug = suggestion(SuggestionKind::BugFix, Priority::Low, "src/lib.rs");
+        let filter = SuggestionFilter::parse("kind: priority:urgent");
+        assert!(filter.matches(&bug));
+        assert_eq!(filter.problems, vec!["priority:urgent".to_string()]);
+        assert_eq!(filter.describe(), "priority:urgent?");
+        assert!(SuggestionFilter::parse("  ").is_empty());
+    }
+}
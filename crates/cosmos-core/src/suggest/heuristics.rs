@@ -0,0 +1,428 @@
+//! Offline heuristic suggestions.
+//!
+//! Without an API key the Suggestions panel would stay empty. These come straight from the
+//! index and file contents: long functions, TODO/FIXME markers, duplicated blocks, and
+//! sizeable files no test touches. They are hints, not validated findings, so they cannot be
+//! applied until AI is configured.
+
+use super::dead_code::{is_test_path, module_name, CrossReferences};
+use super::{Confidence, Priority, Suggestion, SuggestionKind, SuggestionSource};
+use crate::index::{CodebaseIndex, PatternKind, SymbolKind, LONG_FUNCTION_THRESHOLD};
+use crate::util::truncate;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+const MAX_LONG_FUNCTION_SUGGESTIONS: usize = 10;
+const MAX_TODO_SUGGESTIONS: usize = 10;
+const MAX_DUPLICATE_SUGGESTIONS: usize = 5;
+const MAX_MISSING_TEST_SUGGESTIONS: usize = 5;
+
+/// Consecutive significant lines that must match to count as a duplicated block.
+const DUPLICATE_WINDOW_LINES: usize = 8;
+/// Files smaller than this are not worth a missing-test hint.
+const MISSING_TEST_MIN_LOC: usize = 100;
+/// Functions a file needs before missing tests are worth a hint.
+const MISSING_TEST_MIN_FUNCTIONS: usize = 3;
+/// Line prefixes that repeat across files by convention (imports, includes).
+const BOILERPLATE_PREFIXES: &[&str] = &[
+    "use ", "pub use ", "import ", "from ", "package ", "require(",
+];
+
+fn heuristic_suggestion(
+    kind: SuggestionKind,
+    priority: Priority,
+    file: &Path,
+    summary: String,
+    detail: String,
+) -> Suggestion {
+    Suggestion::new(
+        kind,
+        priority,
+        file.to_path_buf(),
+        summary,
+        SuggestionSource::Static,
+    )
+    .with_confidence(Confidence::Medium)
+    .with_detail(detail)
+}
+
+fn sorted_paths(index: &CodebaseIndex) -> Vec<&PathBuf> {
+    let mut paths: Vec<&PathBuf> = index.files.keys().collect();
+    paths.sort();
+    paths
+}
+
+fn long_functions(index: &CodebaseIndex) -> Vec<Suggestion> {
+    let mut found = Vec::new();
+    for path in sorted_paths(index) {
+        for symbol in &index.files[path].symbols {
+            if matches!(symbol.kind, SymbolKind::Function | SymbolKind::Method)
+                && symbol.line_count() > LONG_FUNCTION_THRESHOLD
+            {
+                found.push((path, symbol));
+            }
+        }
+    }
+    found.sort_by_key(|(_, symbol)| std::cmp::Reverse(symbol.line_count()));
+    found
+        .into_iter()
+        .take(MAX_LONG_FUNCTION_SUGGESTIONS)
+        .map(|(path, symbol)| {
+            let lines = symbol.line_count();
+            let priority = if lines > LONG_FUNCTION_THRESHOLD * 3 {
+                Priority::Medium
+            } else {
+                Priority::Low
+            };
+            heuristic_suggestion(
+                SuggestionKind::Refactoring,
+                priority,
+                path,
+                format!("`{}` is {} lines long", symbol.name, lines),
+                format!(
+                    "Functions past {} lines are hard to review and test. Split `{}` into \
+                     smaller steps whose names say what each one does.",
+                    LONG_FUNCTION_THRESHOLD, symbol.name
+                ),
+            )
+            .with_line(symbol.line)
+        })
+        .collect()
+}
+
+fn todo_markers(index: &CodebaseIndex) -> Vec<Suggestion> {
+    let mut found = Vec::new();
+    for path in sorted_paths(index) {
+        for pattern in &index.files[path].patterns {
+            if pattern.kind != PatternKind::TodoMarker {
+                continue;
+            }
+            if let Some((marker, note)) = comment_marker(&pattern.description) {
+                found.push((path, pattern, marker, note));
+            }
+        }
+    }
+    // FIXME and HACK flag known problems; plain TODOs come after them.
+    found.sort_by_key(|(_, _, marker, _)| *marker == "TODO");
+    found
+        .into_iter()
+        .take(MAX_TODO_SUGGESTIONS)
+        .map(|(path, pattern, marker, note)| {
+            let (kind, priority) = if marker == "TODO" {
+                (SuggestionKind::Improvement, Priority::Low)
+            } else {
+                (SuggestionKind::Quality, Priority::Medium)
+            };
+            let summary = if note.is_empty() {
+                format!("Unresolved {}", marker)
+            } else {
+                format!("Unresolved {}: {}", marker, truncate(note, 80))
+            };
+            heuristic_suggestion(
+                kind,
+                priority,
+                path,
+                summary,
+                format!(
+                    "{} leaves a {} at line {}. Finish it, or turn it into a tracked issue \
+                     so it is not forgotten.",
+                    path.display(),
+                    marker,
+                    pattern.line
+                ),
+            )
+            .with_line(pattern.line)
+            .with_evidence(pattern.description.clone())
+        })
+        .collect()
+}
+
+/// The marker and note of a comment that opens with TODO, FIXME, or HACK.
+///
+/// The index flags any line mentioning a marker; only comments led by one count here, so
+/// string literals and prose that merely mention "TODO" are skipped.
+fn comment_marker(line: &str) -> Option<(&'static str, &str)> {
+    ["//", "/*", "#", "--"]
+        .iter()
+        .filter_map(|leader| line.find(leader).map(|at| &line[at..]))
+        .find_map(|comment| {
+            let body = comment.trim_start_matches(['/', '*', '!', '#', '-', ' ']);
+            let marker = ["TODO", "FIXME", "HACK"]
+                .into_iter()
+                .find(|marker| body.starts_with(marker))?;
+            let rest = &body[marker.len()..];
+            if rest.starts_with(|c: char| c.is_alphanumeric() || c == '_') {
+                return None;
+            }
+            let rest = match rest.strip_prefix('(') {
+                Some(tagged) => tagged.split_once(')').map_or(tagged, |(_, rest)| rest),
+                None => rest,
+            };
+            let note = rest
+                .trim_start_matches([':', '-', ' '])
+                .trim_end_matches("*/")
+                .trim();
+            Some((marker, note))
+        })
+}
+
+/// Non-blank lines worth comparing, with their 1-based line numbers.
+fn significant_lines(content: &str) -> Vec<(usize, &str)> {
+    content
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| {
+            line.chars().filter(|c| c.is_alphanumeric()).count() >= 3
+                && !line.starts_with("//")
+                && !line.starts_with('#')
+                && !BOILERPLATE_PREFIXES
+                    .iter()
+                    .any(|prefix| line.starts_with(prefix))
+        })
+        .collect()
+}
+
+fn duplicate_blocks(xrefs: &CrossReferences) -> Vec<Suggestion> {
+    let mut paths: Vec<&PathBuf> = xrefs
+        .contents
+        .keys()
+        .filter(|path| !is_test_path(path))
+        .collect();
+    paths.sort();
+
+    let mut first_seen: HashMap<String, (&PathBuf, usize)> = HashMap::new();
+    let mut reported: Vec<(&PathBuf, &PathBuf)> = Vec::new();
+    let mut found = Vec::new();
+    for path in paths {
+        let lines = significant_lines(&xrefs.contents[path]);
+        let mut i = 0;
+        while i + DUPLICATE_WINDOW_LINES <= lines.len() {
+            let window = &lines[i..i + DUPLICATE_WINDOW_LINES];
+            let key = window
+                .iter()
+                .map(|(_, line)| *line)
+                .collect::<Vec<_>>()
+                .join("\n");
+            let start = window[0].0;
+            match first_seen.get(&key) {
+                Some(&(other, other_start))
+                    if other != path || other_start + DUPLICATE_WINDOW_LINES <= window[0].0 =>
+                {
+                    if !reported.contains(&(other, path)) {
+                        reported.push((other, path));
+                        found.push((other, other_start, path, start, window[0].1));
+                    }
+                    // Skip the rest of this block so one copy is one finding.
+                    i += DUPLICATE_WINDOW_LINES;
+                    continue;
+                }
+                Some(_) => {}
+                None => {
+                    first_seen.insert(key, (path, start));
+                }
+            }
+            i += 1;
+        }
+    }
+
+    found
+        .into_iter()
+        .take(MAX_DUPLICATE_SUGGESTIONS)
+        .map(|(original, original_line, copy, copy_line, first_line)| {
+            let mut suggestion = heuristic_suggestion(
+                SuggestionKind::Refactoring,
+                Priority::Low,
+                copy,
+                format!(
+                    "Block duplicated from {}:{}",
+                    original.display(),
+                    original_line
+                ),
+                format!(
+                    "At least {} lines starting at {}:{} repeat {}:{}. Fixes made to one copy \
+                     will be missed in the other; extract a shared helper.",
+                    DUPLICATE_WINDOW_LINES,
+                    copy.display(),
+                    copy_line,
+                    original.display(),
+                    original_line
+                ),
+            )
+            .with_line(copy_line)
+            .with_evidence(first_line.to_string());
+            if original != copy {
+                suggestion.additional_files.push(original.clone());
+            }
+            suggestion
+        })
+        .collect()
+}
+
+fn missing_tests(index: &CodebaseIndex, xrefs: &CrossReferences) -> Vec<Suggestion> {
+    let test_files: Vec<&HashMap<String, usize>> = xrefs
+        .counts
+        .iter()
+        .filter(|(path, _)| is_test_path(path))
+        .map(|(_, counts)| counts)
+        .collect();
+
+    let mut found = Vec::new();
+    for path in sorted_paths(index) {
+        let file = &index.files[path];
+        let Some(content) = xrefs.contents.get(path) else {
+            continue;
+        };
+        let functions = file
+            .symbols
+            .iter()
+            .filter(|s| matches!(s.kind, SymbolKind::Function | SymbolKind::Method))
+            .count();
+        if is_test_path(path)
+            || file.loc < MISSING_TEST_MIN_LOC
+            || functions < MISSING_TEST_MIN_FUNCTIONS
+            || content.contains("#[cfg(test)]")
+            || content.contains("#[test]")
+        {
+            continue;
+        }
+        let Some(module) = module_name(path) else {
+            continue;
+        };
+        if test_files.iter().any(|counts| {
+            counts.contains_key(module) || counts.contains_key(&module.replace('-', "_"))
+        }) {
+            continue;
+        }
+        found.push((path, module, functions, file.complexity));
+    }
+    found.sort_by(|a, b| b.3.total_cmp(&a.3));
+    found
+        .into_iter()
+        .take(MAX_MISSING_TEST_SUGGESTIONS)
+        .map(|(path, module, functions, _)| {
+            heuristic_suggestion(
+                SuggestionKind::Testing,
+                Priority::Low,
+                path,
+                format!("{} has no tests", path.display()),
+                format!(
+                    "It defines {} functions, has no inline tests, and no test file mentions \
+                     `{}`. Start with the branchiest function.",
+                    functions, module
+                ),
+            )
+        })
+        .collect()
+}
+
+/// Heuristic suggestions for when no AI provider is configured.
+pub fn detect_offline_heuristics(index: &CodebaseIndex) -> Vec<Suggestion> {
+    find_offline_heuristics(index, &CrossReferences::build(index))
+}
+
+pub(super) fn find_offline_heuristics(
+    index: &CodebaseIndex,
+    xrefs: &CrossReferences,
+) -> Vec<Suggestion> {
+    let mut suggestions = todo_markers(index);
+    suggestions.extend(long_functions(index));
+    suggestions.extend(duplicate_blocks(xrefs));
+    suggestions.extend(missing_tests(index, xrefs));
+    suggestions
+}
+
+#[cfg(all(test, feature = "native"))]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn index_of(files: &[(&str, String)]) -> (tempfile::TempDir, CodebaseIndex) {
+        let dir = tempfile::tempdir().unwrap();
+        for (rel, content) in files {
+            let path = dir.path().join(rel);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+        let index = CodebaseIndex::new(dir.path()).unwrap();
+        (dir, index)
+    }
+
+    fn block(tag: &str) -> String {
+        (1..=10)
+            .map(|i| format!("    let value_{i} = compute_{tag}({i}) + offset;\n"))
+            .collect()
+    }
+
+    #[test]
+    fn harvests_markers_long_functions_and_duplicates() {
+        let long_body: String = (0..60).map(|i| format!("    step({});\n", i)).collect();
+        let (_dir, index) = index_of(&[
+            (
+                "src/a.rs",
+                format!(
+                    "// TODO: cache this lookup\nfn shared() {{\n{}}}\n\n// FIXME: races on retry\nfn long() {{\n{}}}\n",
+                    block("x"),
+                    long_body
+                ),
+            ),
+            ("src/b.rs", format!("fn copy() {{\n{}}}\n", block("x"))),
+        ]);
+
+        let suggestions = detect_offline_heuristics(&index);
+        let summaries: Vec<&str> = suggestions.iter().map(|s| s.summary.as_str()).collect();
+        assert_eq!(summaries[0], "Unresolved FIXME: races on retry");
+        assert_eq!(summaries[1], "Unresolved TODO: cache this lookup");
+        assert!(summaries.contains(&"`long` is 62 lines long"));
+        let duplicate = suggestions
+            .iter()
+            .find(|s| s.summary == "Block duplicated from src/a.rs:3")
+            .expect("duplicate block");
+        assert_eq!(duplicate.file, PathBuf::from("src/b.rs"));
+        assert_eq!(duplicate.additional_files, vec![PathBuf::from("src/a.rs")]);
+        assert!(suggestions
+            .iter()
+            .all(|s| s.source == SuggestionSource::Static
+                && s.validation_state == super::super::SuggestionValidationState::Pending));
+    }
+
+    #[test]
+    fn comment_marker_only_counts_comments_led_by_a_marker() {
+        assert_eq!(
+            comment_marker("// TODO(sam): drop v1"),
+            Some(("TODO", "drop v1"))
+        );
+        assert_eq!(comment_marker("# FIXME - flaky"), Some(("FIXME", "flaky")));
+        assert_eq!(comment_marker("/* HACK */"), Some(("HACK", "")));
+        assert_eq!(
+            comment_marker("retry(); // TODO backoff"),
+            Some(("TODO", "backoff"))
+        );
+        assert_eq!(comment_marker("if line.contains(\"TODO\") {"), None);
+        assert_eq!(comment_marker("//! Harvests TODO/FIXME markers"), None);
+        assert_eq!(comment_marker("// TODOS are tracked elsewhere"), None);
+    }
+
+    #[test]
+    fn flags_large_untested_files_only() {
+        let functions: String = (0..40)
+            .map(|i| format!("fn handler_{i}(x: u32) -> u32 {{\n    x + {i}\n}}\n"))
+            .collect();
+        let (_dir, index) = index_of(&[
+            ("src/untested.rs", functions.clone()),
+            ("src/covered.rs", functions.clone()),
+            (
+                "src/inline.rs",
+                format!("{}#[cfg(test)]\nmod tests {{}}\n", functions),
+            ),
+            ("tests/covered_test.rs", "use crate::covered;\n".to_string()),
+        ]);
+
+        let summaries: Vec<String> = detect_offline_heuristics(&index)
+            .into_iter()
+            .filter(|s| s.kind == SuggestionKind::Testing)
+            .map(|s| s.summary)
+            .collect();
+        assert_eq!(summaries, vec!["src/untested.rs has no tests"]);
+    }
+}
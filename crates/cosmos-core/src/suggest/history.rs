@@ -0,0 +1,230 @@
+//! Findings reported by earlier suggestion runs.
+//!
+//! Each run asks the model afresh, so an issue nobody fixed comes back every run, usually
+//! reworded and often a few lines off. Within a run, duplicates are dropped; across runs,
+//! [`FindingHistory`] matches a run's findings against the suggestion-run log and marks the
+//! ones seen before with a [`Redetection`] that points at the first report, so the history
+//! holds one issue seen N times rather than N issues.
+//!
+//! Two findings match when `Suggestion::same_finding` says so, or when they are in the same
+//! file with the same kind and their summaries share most of their significant words.
+
+use super::Suggestion;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+/// Share of significant summary words two findings must have in common to match
+const SUMMARY_SIMILARITY_MIN: f64 = 0.5;
+/// Summary words shorter than this are ignored when comparing
+const MIN_WORD_LEN: usize = 3;
+/// Words too common in findings to tell two of them apart
+const STOP_WORDS: &[&str] = &[
+    "the", "and", "for", "when", "with", "that", "this", "from", "into", "can", "may", "not",
+    "are", "its", "but", "which", "could", "would", "should", "because", "without", "instead",
+];
+
+/// Marks a finding an earlier run already reported.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Redetection {
+    /// Id the finding had when it was first reported
+    pub first_id: Uuid,
+    pub first_seen: DateTime<Utc>,
+    /// Runs that reported it, this one included
+    pub runs: usize,
+}
+
+#[derive(Debug, Clone)]
+struct Entry {
+    /// Most recent report, whose wording and line the next run is compared with
+    latest: Suggestion,
+    first_id: Uuid,
+    first_seen: DateTime<Utc>,
+    runs: usize,
+}
+
+/// Distinct findings across earlier runs.
+#[derive(Debug, Clone, Default)]
+pub struct FindingHistory {
+    entries: Vec<Entry>,
+}
+
+impl FindingHistory {
+    /// Build from each run's suggestions, oldest run first.
+    pub fn from_runs<'a>(runs: impl IntoIterator<Item = &'a [Suggestion]>) -> Self {
+        let mut history = Self::default();
+        for run in runs {
+            let mut claimed = HashSet::new();
+            for suggestion in run {
+                match history.find(suggestion, &claimed) {
+                    Some(i) => {
+                        let entry = &mut history.entries[i];
+                        entry.latest = suggestion.clone();
+                        entry.runs += 1;
+                        claimed.insert(i);
+                    }
+                    None => {
+                        let (first_id, first_seen) = match &suggestion.redetection {
+                            Some(seen) => (seen.first_id, seen.first_seen),
+                            None => (suggestion.id, suggestion.created_at),
+                        };
+                        claimed.insert(history.entries.len());
+                        history.entries.push(Entry {
+                            latest: suggestion.clone(),
+                            first_id,
+                            first_seen,
+                            runs: seen_runs(suggestion),
+                        });
+                    }
+                }
+            }
+        }
+        history
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Mark the findings of a new run that earlier runs reported. Returns how many were.
+    pub fn mark_redetections(&self, suggestions: &mut [Suggestion]) -> usize {
+        let mut claimed = HashSet::new();
+        let mut marked = 0;
+        for suggestion in suggestions.iter_mut() {
+            let Some(i) = self.find(suggestion, &claimed) else {
+                continue;
+            };
+            claimed.insert(i);
+            let entry = &self.entries[i];
+            suggestion.redetection = Some(Redetection {
+                first_id: entry.first_id,
+                first_seen: entry.first_seen,
+                runs: entry.runs + 1,
+            });
+            marked += 1;
+        }
+        marked
+    }
+
+    fn find(&self, suggestion: &Suggestion, claimed: &HashSet<usize>) -> Option<usize> {
+        self.entries
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !claimed.contains(i))
+            .find(|(_, entry)| similar_findings(&entry.latest, suggestion))
+            .map(|(i, _)| i)
+    }
+}
+
+/// Runs that reported `suggestion`, counting any earlier ones it was marked with
+fn seen_runs(suggestion: &Suggestion) -> usize {
+    suggestion
+        .redetection
+        .as_ref()
+        .map(|seen| seen.runs)
+        .unwrap_or(1)
+}
+
+fn similar_findings(a: &Suggestion, b: &Suggestion) -> bool {
+    if a.same_finding(b) {
+        return true;
+    }
+    a.file == b.file
+        && a.kind == b.kind
+        && summary_similarity(&a.summary, &b.summary) >= SUMMARY_SIMILARITY_MIN
+}
+
+/// Jaccard similarity of the summaries' significant words
+fn summary_similarity(a: &str, b: &str) -> f64 {
+    let a = significant_words(a);
+    let b = significant_words(b);
+    let union = a.union(&b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(&b).count() as f64 / union as f64
+}
+
+fn significant_words(summary: &str) -> HashSet<String> {
+    summary
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .map(str::to_lowercase)
+        .filter(|word| word.len() >= MIN_WORD_LEN && !STOP_WORDS.contains(&word.as_str()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::suggest::{test_finding, Priority, SuggestionKind};
+
+    fn finding(file: &str, line: usize, summary: &str) -> Suggestion {
+        test_finding(SuggestionKind::BugFix, Priority::High, file, line, summary)
+    }
+
+    #[test]
+    fn reworded_findings_from_earlier_runs_are_marked_as_redetections() {
+        let first = vec![
+            finding(
+                "src/cache.rs",
+                40,
+                "Cache writes can leave a partially written file when the disk is full",
+            ),
+            finding("src/api.rs", 10, "Unchecked index panics on empty input"),
+        ];
+        let mut second = vec![finding(
+            "src/cache.rs",
+            88,
+            "A full disk leaves the cache file partially written",
+        )];
+        let history = FindingHistory::from_runs([first.as_slice()]);
+        assert_eq!(history.len(), 2);
+        assert_eq!(history.mark_redetections(&mut second), 1);
+        let seen = second[0].redetection.clone().unwrap();
+        assert_eq!(seen.first_id, first[0].id);
+        assert_eq!(seen.runs, 2);
+
+        // A third run still points at the first report and counts every run
+        let mut third = vec![
+            finding(
+                "src/cache.rs",
+                90,
+                "Partially written cache file left behind on a full disk",
+            ),
+            finding(
+                "src/cache.rs",
+                200,
+                "Eviction never runs for expired entries",
+            ),
+        ];
+        let history = FindingHistory::from_runs([first.as_slice(), second.as_slice()]);
+        assert_eq!(history.len(), 2);
+        assert_eq!(history.mark_redetections(&mut third), 1);
+        let seen = third[0].redetection.clone().unwrap();
+        assert_eq!(seen.first_id, first[0].id);
+        assert_eq!(seen.runs, 3);
+        assert!(third[1].redetection.is_none());
+    }
+
+    #[test]
+    fn one_earlier_finding_matches_at_most_one_new_one() {
+        let earlier = vec![finding(
+            "src/api.rs",
+            10,
+            "Unchecked index panics on empty input",
+        )];
+        let mut later = vec![
+            finding("src/api.rs", 11, "Unchecked index panics on empty input"),
+            finding("src/api.rs", 12, "Unchecked index panics on empty input"),
+        ];
+        let history = FindingHistory::from_runs([earlier.as_slice()]);
+        assert_eq!(history.mark_redetections(&mut later), 1);
+        assert!(later[1].redetection.is_none());
+        assert!(summary_similarity("the and for", "the and for") == 0.0);
+    }
+}
@@ -2,6 +2,17 @@
 //!
 //! LLM-driven suggestions.
 //! Suggestions are generated on-demand via `analyze_codebase()`.
+//! Deterministic findings (see `dead_code`, and `heuristics` when AI is not configured)
+//! are added whenever the index changes. Findings covered by a `cosmos-ignore:` comment
+//! (see `suppress`) are dropped, and so are findings in the repo's `baseline`. `filter`
+//! narrows the list shown from a search query.
+
+pub mod baseline;
+pub mod dead_code;
+pub mod filter;
+pub mod heuristics;
+pub mod history;
+pub mod suppress;
 
 use crate::index::CodebaseIndex;
 use chrono::{DateTime, Utc};
@@ -20,6 +31,8 @@ pub enum SuggestionSource {
     LlmFast,
     /// LLM for detailed analysis
     LlmDeep,
+    /// A repo-configured analyzer plugin
+    Plugin,
 }
 
 /// Kind of suggestion
@@ -56,6 +69,19 @@ impl SuggestionKind {
             SuggestionKind::Refactoring => "Cleanup",
         }
     }
+
+    /// Conventional commit type for changes of this kind (also `<kind>` in branch patterns)
+    pub fn conventional_type(&self) -> &'static str {
+        match self {
+            SuggestionKind::BugFix => "fix",
+            SuggestionKind::Feature => "feat",
+            SuggestionKind::Optimization => "perf",
+            SuggestionKind::Documentation => "docs",
+            SuggestionKind::Testing => "test",
+            SuggestionKind::Refactoring => "refactor",
+            SuggestionKind::Improvement | SuggestionKind::Quality => "chore",
+        }
+    }
 }
 
 /// Priority level
@@ -84,6 +110,30 @@ impl SuggestionCategory {
     }
 }
 
+/// User verdict on a suggestion, fed back into later suggestion runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SuggestionFeedback {
+    Good,
+    Bad,
+    Irrelevant,
+}
+
+impl SuggestionFeedback {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SuggestionFeedback::Good => "good",
+            SuggestionFeedback::Bad => "bad",
+            SuggestionFeedback::Irrelevant => "irrelevant",
+        }
+    }
+
+    /// Bad and irrelevant findings both count against their category.
+    pub fn is_rejection(&self) -> bool {
+        !matches!(self, SuggestionFeedback::Good)
+    }
+}
+
 /// Criticality level used for primary ordering.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Default)]
 #[serde(rename_all = "snake_case")]
@@ -228,6 +278,9 @@ pub struct Suggestion {
     pub created_at: DateTime<Utc>,
     /// Whether the suggestion has been applied
     pub applied: bool,
+    /// Set when an earlier run already reported this finding
+    #[serde(default)]
+    pub redetection: Option<history::Redetection>,
 }
 
 impl Suggestion {
@@ -261,6 +314,7 @@ impl Suggestion {
             source,
             created_at: Utc::now(),
             applied: false,
+            redetection: None,
         }
     }
 
@@ -346,12 +400,185 @@ impl Suggestion {
     pub fn file_count(&self) -> usize {
         1 + self.additional_files.len()
     }
+
+    /// Id of the finding across runs: the id of its first report when an earlier run
+    /// already had it, else its own.
+    pub fn finding_id(&self) -> Uuid {
+        self.redetection
+            .as_ref()
+            .map(|seen| seen.first_id)
+            .unwrap_or(self.id)
+    }
+
+    /// Whether `other` reports the same finding, e.g. from an earlier scan. Runs reword
+    /// summaries and shift lines a little, so either a matching summary or a nearby line in
+    /// the same file and kind counts.
+    pub fn same_finding(&self, other: &Suggestion) -> bool {
+        const LINE_SLACK: usize = 3;
+        if self.file != other.file || self.kind != other.kind {
+            return false;
+        }
+        let normalize = |summary: &str| {
+            summary
+                .split_whitespace()
+                .map(str::to_lowercase)
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        if normalize(&self.summary) == normalize(&other.summary) {
+            return true;
+        }
+        matches!((self.line, other.line), (Some(a), Some(b)) if a.abs_diff(b) <= LINE_SLACK)
+    }
+}
+
+/// How the findings of two suggestion runs line up.
+#[derive(Debug, Default)]
+pub struct RunComparison<'a> {
+    /// Only in the later run
+    pub new: Vec<&'a Suggestion>,
+    /// Only in the earlier run
+    pub resolved: Vec<&'a Suggestion>,
+    /// In both runs, as (earlier, later)
+    pub persisting: Vec<(&'a Suggestion, &'a Suggestion)>,
+}
+
+/// A line-anchored finding for tests that only care about kind, priority, place and summary.
+#[cfg(any(test, feature = "test-support"))]
+#[doc(hidden)]
+pub fn test_finding(
+    kind: SuggestionKind,
+    priority: Priority,
+    file: &str,
+    line: usize,
+    summary: &str,
+) -> Suggestion {
+    Suggestion::new(
+        kind,
+        priority,
+        PathBuf::from(file),
+        summary.to_string(),
+        SuggestionSource::LlmDeep,
+    )
+    .with_line(line)
+}
+
+/// Match the findings of an earlier and a later run with `Suggestion::same_finding`; each
+/// finding pairs with at most one from the other run.
+pub fn compare_runs<'a>(earlier: &'a [Suggestion], later: &'a [Suggestion]) -> RunComparison<'a> {
+    let mut claimed = vec![false; later.len()];
+    let mut comparison = RunComparison::default();
+    for old in earlier {
+        let matched = later
+            .iter()
+            .enumerate()
+            .position(|(idx, new)| !claimed[idx] && (new.id == old.id || old.same_finding(new)));
+        match matched {
+            Some(idx) => {
+                claimed[idx] = true;
+                comparison.persisting.push((old, &later[idx]));
+            }
+            None => comparison.resolved.push(old),
+        }
+    }
+    comparison.new = later
+        .iter()
+        .zip(&claimed)
+        .filter(|(_, claimed)| !**claimed)
+        .map(|(suggestion, _)| suggestion)
+        .collect();
+    comparison
+}
+
+/// Rough leverage of fixing a suggestion: priority × file hotspot × affected lines.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImpactEstimate {
+    /// 1 (low) to 3 (high)
+    pub priority: f64,
+    /// 1, plus how many files use the touched files and how complex their functions are
+    pub hotspot: f64,
+    /// Lines in the innermost symbol at the suggestion's line, or in the whole touched files
+    pub affected_loc: usize,
+    /// `priority × hotspot × log2(2 + affected_loc)`; size counts without swamping the rest
+    pub score: f64,
+}
+
+/// Which suggestions a bulk dismiss covers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BulkDismissScope {
+    /// Of this kind, and of exactly this priority when one is given
+    Kind {
+        kind: SuggestionKind,
+        priority: Option<Priority>,
+    },
+    /// Touching any file under this directory
+    Directory(PathBuf),
+}
+
+impl BulkDismissScope {
+    /// Scopes around `suggestion`, narrowest first: its kind at its priority, its kind, then
+    /// each directory above its file, deepest first.
+    pub fn around(suggestion: &Suggestion) -> Vec<Self> {
+        let mut scopes = vec![
+            BulkDismissScope::Kind {
+                kind: suggestion.kind,
+                priority: Some(suggestion.priority),
+            },
+            BulkDismissScope::Kind {
+                kind: suggestion.kind,
+                priority: None,
+            },
+        ];
+        scopes.extend(
+            suggestion
+                .file
+                .ancestors()
+                .skip(1)
+                .filter(|dir| !dir.as_os_str().is_empty())
+                .map(|dir| BulkDismissScope::Directory(dir.to_path_buf())),
+        );
+        scopes
+    }
+
+    pub fn matches(&self, suggestion: &Suggestion) -> bool {
+        match self {
+            BulkDismissScope::Kind { kind, priority } => {
+                suggestion.kind == *kind && priority.is_none_or(|p| suggestion.priority == p)
+            }
+            BulkDismissScope::Directory(dir) => suggestion
+                .affected_files()
+                .iter()
+                .any(|file| file.starts_with(dir)),
+        }
+    }
+
+    /// `low-priority docs suggestions` or `suggestions in legacy/`
+    pub fn label(&self) -> String {
+        match self {
+            BulkDismissScope::Kind { kind, priority } => {
+                let priority = match priority {
+                    Some(Priority::Low) => "low-priority ",
+                    Some(Priority::Medium) => "medium-priority ",
+                    Some(Priority::High) => "high-priority ",
+                    None => "",
+                };
+                format!("{}{} suggestions", priority, kind.conventional_type())
+            }
+            BulkDismissScope::Directory(dir) => {
+                format!("suggestions in {}/", dir.display())
+            }
+        }
+    }
 }
 
 /// The suggestion engine
 pub struct SuggestionEngine {
     pub suggestions: Vec<Suggestion>,
     pub index: CodebaseIndex,
+    suppressed_static: usize,
+    suppressed_plugin: usize,
+    baselined_static: usize,
+    baselined_plugin: usize,
 }
 
 impl SuggestionEngine {
@@ -395,6 +622,58 @@ impl SuggestionEngine {
             .unwrap_or(0)
     }
 
+    /// Estimate how much fixing `suggestion` is worth from the index.
+    ///
+    /// Files missing from the index count as cold and empty, so the estimate is
+    /// still defined (just small) for stale suggestions.
+    pub fn impact_estimate(&self, suggestion: &Suggestion) -> ImpactEstimate {
+        let priority = match suggestion.priority {
+            Priority::Low => 1.0,
+            Priority::Medium => 2.0,
+            Priority::High => 3.0,
+        };
+        let mut hotspot: f64 = 1.0;
+        let mut affected_loc = 0;
+        for (idx, path) in suggestion.affected_files().into_iter().enumerate() {
+            let Some(file) = self.index.files.get(path) else {
+                continue;
+            };
+            let max_complexity = file
+                .symbols
+                .iter()
+                .map(|symbol| symbol.complexity)
+                .fold(0.0, f64::max);
+            hotspot = hotspot.max(
+                1.0 + (file.summary.used_by.len() as f64).ln_1p()
+                    + (max_complexity / crate::health::HOTSPOT_COMPLEXITY).min(1.0),
+            );
+            let enclosing = suggestion.line.filter(|_| idx == 0).and_then(|line| {
+                file.symbols
+                    .iter()
+                    .filter(|symbol| symbol.line <= line && line <= symbol.end_line)
+                    .map(|symbol| symbol.line_count())
+                    .min()
+            });
+            affected_loc += enclosing.unwrap_or(file.loc);
+        }
+        ImpactEstimate {
+            priority,
+            hotspot,
+            affected_loc,
+            score: priority * hotspot * (2.0 + affected_loc as f64).log2(),
+        }
+    }
+
+    /// Order `suggestions` by estimated impact, highest first; ties keep their order.
+    pub fn sort_by_impact(&self, suggestions: &mut Vec<&Suggestion>) {
+        let mut scored: Vec<(f64, &Suggestion)> = suggestions
+            .iter()
+            .map(|s| (self.impact_estimate(s).score, *s))
+            .collect();
+        scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+        *suggestions = scored.into_iter().map(|(_, s)| s).collect();
+    }
+
     /// Create a new suggestion engine from a codebase index
     ///
     /// Starts empty - LLM suggestions are generated separately.
@@ -402,6 +681,35 @@ impl SuggestionEngine {
         Self {
             suggestions: Vec::new(),
             index,
+            suppressed_static: 0,
+            suppressed_plugin: 0,
+            baselined_static: 0,
+            baselined_plugin: 0,
+        }
+    }
+
+    /// Deterministic and plugin findings dropped by `cosmos-ignore:` comments in their
+    /// latest refresh.
+    pub fn suppressed_count(&self) -> usize {
+        self.suppressed_static + self.suppressed_plugin
+    }
+
+    /// Deterministic and plugin findings hidden by `.cosmos/baseline.json` in their latest
+    /// refresh.
+    pub fn baselined_count(&self) -> usize {
+        self.baselined_static + self.baselined_plugin
+    }
+
+    /// Suppression comments first, then the active baseline. An unreadable baseline hides
+    /// nothing; startup validation reports it.
+    fn retain_reportable(&self, suggestions: Vec<Suggestion>) -> (Vec<Suggestion>, usize, usize) {
+        let (kept, suppressed) = suppress::retain_unsuppressed(&self.index.root, suggestions);
+        match baseline::Baseline::active(&self.index.root) {
+            Ok(Some(baseline)) => {
+                let (kept, baselined) = baseline.retain_new(kept);
+                (kept, suppressed, baselined)
+            }
+            _ => (kept, suppressed, 0),
         }
     }
 
@@ -432,6 +740,27 @@ impl SuggestionEngine {
         self.update_suggestion(id, |s| s.applied = false);
     }
 
+    /// Remove the unapplied suggestions in `ids`, returning them so they can be restored.
+    pub fn dismiss(&mut self, ids: &std::collections::HashSet<Uuid>) -> Vec<Suggestion> {
+        let (dismissed, kept) = std::mem::take(&mut self.suggestions)
+            .into_iter()
+            .partition(|s| !s.applied && ids.contains(&s.id));
+        self.suggestions = kept;
+        dismissed
+    }
+
+    /// Put back dismissed suggestions, skipping any listed again since (e.g. by a rescan).
+    /// Returns how many came back.
+    pub fn restore(&mut self, suggestions: Vec<Suggestion>) -> usize {
+        let listed: std::collections::HashSet<Uuid> =
+            self.suggestions.iter().map(|s| s.id).collect();
+        let before = self.suggestions.len();
+        self.suggestions
+            .extend(suggestions.into_iter().filter(|s| !listed.contains(&s.id)));
+        self.sort_by_criticality_desc();
+        self.suggestions.len() - before
+    }
+
     /// Add a suggestion from LLM
     pub fn add_llm_suggestion(&mut self, suggestion: Suggestion) {
         self.suggestions.push(suggestion);
@@ -448,6 +777,81 @@ impl SuggestionEngine {
         self.sort_by_criticality_desc();
     }
 
+    /// Replace LLM suggestions with a rescan's results, returning the ids of findings that
+    /// are new since the previous scan.
+    ///
+    /// Findings that were already listed keep their old id, so selection, ratings, and
+    /// expanded state carry over.
+    pub fn merge_rescan(&mut self, mut suggestions: Vec<Suggestion>) -> Vec<Uuid> {
+        let previous: Vec<&Suggestion> = self
+            .suggestions
+            .iter()
+            .filter(|s| s.source == SuggestionSource::LlmDeep && !s.applied)
+            .collect();
+        let mut claimed = std::collections::HashSet::new();
+        let mut new_ids = Vec::new();
+        for suggestion in &mut suggestions {
+            match previous
+                .iter()
+                .find(|old| !claimed.contains(&old.id) && old.same_finding(suggestion))
+            {
+                Some(old) => {
+                    claimed.insert(old.id);
+                    suggestion.id = old.id;
+                }
+                None => new_ids.push(suggestion.id),
+            }
+        }
+        self.replace_llm_suggestions(suggestions);
+        new_ids
+    }
+
+    /// Drop unapplied suggestions that touch any of `files`, whose evidence may now be stale,
+    /// except `keep`. Returns how many were removed.
+    pub fn invalidate_files(
+        &mut self,
+        files: &std::collections::BTreeSet<PathBuf>,
+        keep: Option<Uuid>,
+    ) -> usize {
+        let before = self.suggestions.len();
+        self.suggestions.retain(|s| {
+            s.applied
+                || Some(s.id) == keep
+                || !s.affected_files().iter().any(|file| files.contains(*file))
+        });
+        before - self.suggestions.len()
+    }
+
+    /// Recompute deterministic suggestions from the current index: dead code always, plus
+    /// offline heuristics when no AI provider is configured.
+    ///
+    /// Replaces earlier static findings; LLM and applied suggestions are kept.
+    pub fn refresh_static_suggestions(&mut self, offline: bool) {
+        self.suggestions
+            .retain(|s| s.source != SuggestionSource::Static || s.applied);
+        let xrefs = dead_code::CrossReferences::build(&self.index);
+        let mut found = dead_code::find_dead_code(&self.index, &xrefs);
+        if offline {
+            found.extend(heuristics::find_offline_heuristics(&self.index, &xrefs));
+        }
+        let (kept, suppressed, baselined) = self.retain_reportable(found);
+        self.suppressed_static = suppressed;
+        self.baselined_static = baselined;
+        self.suggestions.extend(kept);
+        self.sort_by_criticality_desc();
+    }
+
+    /// Replace earlier plugin findings with a new plugin run's; applied ones are kept.
+    pub fn replace_plugin_suggestions(&mut self, suggestions: Vec<Suggestion>) {
+        self.suggestions
+            .retain(|s| s.source != SuggestionSource::Plugin || s.applied);
+        let (kept, suppressed, baselined) = self.retain_reportable(suggestions);
+        self.suppressed_plugin = suppressed;
+        self.baselined_plugin = baselined;
+        self.suggestions.extend(kept);
+        self.sort_by_criticality_desc();
+    }
+
     /// Sort suggestions by priority first, then confidence and contradiction history,
     /// then git context (changed files, blast radius).
     pub fn sort_with_context(
@@ -614,6 +1018,9 @@ mod tests {
             files: std::collections::HashMap::new(),
             index_errors: Vec::new(),
             git_head: None,
+            packages: Vec::new(),
+            excluded_files: 0,
+            filter_fingerprint: None,
         };
         let mut engine = SuggestionEngine::new(index);
         for i in 0..40 {
@@ -643,6 +1050,9 @@ mod tests {
             files: std::collections::HashMap::new(),
             index_errors: Vec::new(),
             git_head: None,
+            packages: Vec::new(),
+            excluded_files: 0,
+            filter_fingerprint: None,
         };
         let mut engine = SuggestionEngine::new(index);
         engine.add_llm_suggestion(Suggestion::new(
@@ -655,6 +1065,130 @@ mod tests {
         assert!(engine.active_suggestions_with_limit(0).is_empty());
     }
 
+    #[test]
+    fn test_replace_plugin_suggestions_keeps_other_sources_and_applied() {
+        let index = CodebaseIndex {
+            root: PathBuf::from("."),
+            files: std::collections::HashMap::new(),
+            index_errors: Vec::new(),
+            git_head: None,
+            packages: Vec::new(),
+            excluded_files: 0,
+            filter_fingerprint: None,
+        };
+        let plugin = |summary: &str| {
+            Suggestion::new(
+                SuggestionKind::Quality,
+                Priority::Medium,
+                PathBuf::from("src/lib.rs"),
+                summary.to_string(),
+                SuggestionSource::Plugin,
+            )
+        };
+        let mut engine = SuggestionEngine::new(index);
+        engine.add_llm_suggestion(Suggestion::new(
+            SuggestionKind::BugFix,
+            Priority::High,
+            PathBuf::from("src/lib.rs"),
+            "From the model".to_string(),
+            SuggestionSource::LlmDeep,
+        ));
+        engine.replace_plugin_suggestions(vec![plugin("stale"), plugin("applied")]);
+        let applied = engine
+            .suggestions
+            .iter()
+            .find(|s| s.summary == "applied")
+            .unwrap()
+            .id;
+        engine.mark_applied(applied);
+
+        engine.replace_plugin_suggestions(vec![plugin("fresh")]);
+        let mut summaries: Vec<_> = engine
+            .suggestions
+            .iter()
+            .map(|s| s.summary.as_str())
+            .collect();
+        summaries.sort();
+        assert_eq!(summaries, vec!["From the model", "applied", "fresh"]);
+    }
+
+    #[test]
+    fn test_impact_estimate_weighs_priority_hotspot_and_enclosing_symbol() {
+        use crate::index::{FileIndex, FileSummary, Language, Symbol, SymbolKind, Visibility};
+
+        let file = |path: &str, loc: usize, used_by: usize, symbols: Vec<Symbol>| FileIndex {
+            path: PathBuf::from(path),
+            language: Language::Rust,
+            loc,
+            content_hash: String::new(),
+            symbols,
+            dependencies: Vec::new(),
+            patterns: Vec::new(),
+            complexity: 1.0,
+            last_modified: Utc::now(),
+            summary: FileSummary {
+                used_by: (0..used_by)
+                    .map(|i| PathBuf::from(format!("src/user{}.rs", i)))
+                    .collect(),
+                ..FileSummary::default()
+            },
+            layer: None,
+            feature: None,
+        };
+        let handler = Symbol {
+            name: "handle".to_string(),
+            kind: SymbolKind::Function,
+            file: PathBuf::from("src/core.rs"),
+            line: 10,
+            end_line: 39,
+            complexity: 30.0,
+            visibility: Visibility::Public,
+        };
+        let mut files = std::collections::HashMap::new();
+        files.insert(
+            PathBuf::from("src/core.rs"),
+            file("src/core.rs", 400, 7, vec![handler]),
+        );
+        files.insert(
+            PathBuf::from("src/leaf.rs"),
+            file("src/leaf.rs", 30, 0, Vec::new()),
+        );
+        let engine = SuggestionEngine::new(CodebaseIndex {
+            root: PathBuf::from("."),
+            files,
+            index_errors: Vec::new(),
+            git_head: None,
+            packages: Vec::new(),
+            excluded_files: 0,
+            filter_fingerprint: None,
+        });
+
+        let core = Suggestion::new(
+            SuggestionKind::BugFix,
+            Priority::Medium,
+            PathBuf::from("src/core.rs"),
+            "Core".to_string(),
+            SuggestionSource::LlmDeep,
+        )
+        .with_line(12);
+        let leaf = Suggestion::new(
+            SuggestionKind::BugFix,
+            Priority::High,
+            PathBuf::from("src/leaf.rs"),
+            "Leaf".to_string(),
+            SuggestionSource::LlmDeep,
+        );
+        let estimate = engine.impact_estimate(&core);
+        assert_eq!(estimate.affected_loc, 30);
+        assert!((estimate.hotspot - (2.0 + 8f64.ln())).abs() < 1e-9);
+        assert!((estimate.score - 2.0 * estimate.hotspot * 32f64.log2()).abs() < 1e-9);
+        assert_eq!(engine.impact_estimate(&leaf).hotspot, 1.0);
+
+        let mut ordered = vec![&leaf, &core];
+        engine.sort_by_impact(&mut ordered);
+        assert_eq!(ordered[0].summary, "Core");
+    }
+
     #[test]
     fn test_sort_with_context_prefers_kind_over_changed() {
         let index = CodebaseIndex {
@@ -662,6 +1196,9 @@ mod tests {
             files: std::collections::HashMap::new(),
             index_errors: Vec::new(),
             git_head: None,
+            packages: Vec::new(),
+            excluded_files: 0,
+            filter_fingerprint: None,
         };
 
         let mut engine = SuggestionEngine::new(index);
@@ -705,6 +1242,9 @@ mod tests {
             files: std::collections::HashMap::new(),
             index_errors: Vec::new(),
             git_head: None,
+            packages: Vec::new(),
+            excluded_files: 0,
+            filter_fingerprint: None,
         };
         let mut engine = SuggestionEngine::new(index);
         let high = Suggestion::new(
@@ -744,6 +1284,9 @@ mod tests {
             files: std::collections::HashMap::new(),
             index_errors: Vec::new(),
             git_head: None,
+            packages: Vec::new(),
+            excluded_files: 0,
+            filter_fingerprint: None,
         };
         let mut engine = SuggestionEngine::new(index);
         let contradicted = Suggestion::new(
@@ -788,6 +1331,81 @@ mod tests {
         assert_eq!(engine.suggestions[0].summary, "Clean evidence");
     }
 
+    #[test]
+    fn test_merge_rescan_keeps_known_ids_and_reports_new_findings() {
+        let finding = |summary: &str, line: usize| {
+            Suggestion::new(
+                SuggestionKind::BugFix,
+                Priority::High,
+                PathBuf::from("src/retry.rs"),
+                summary.to_string(),
+                SuggestionSource::LlmDeep,
+            )
+            .with_line(line)
+        };
+        let mut engine = SuggestionEngine::new(CodebaseIndex {
+            root: PathBuf::from("."),
+            files: std::collections::HashMap::new(),
+            index_errors: Vec::new(),
+            git_head: None,
+            packages: Vec::new(),
+            excluded_files: 0,
+            filter_fingerprint: None,
+        });
+        let reworded = finding("Retry loop never backs off", 40);
+        let same_summary = finding("Timeout is ignored", 90);
+        let (reworded_id, same_summary_id) = (reworded.id, same_summary.id);
+        engine.replace_llm_suggestions(vec![reworded, same_summary]);
+
+        let fresh = finding("Off-by-one in retry count", 12);
+        let fresh_id = fresh.id;
+        let new_ids = engine.merge_rescan(vec![
+            finding("Retries fire without any backoff", 42),
+            finding("timeout  is ignored", 120),
+            fresh,
+        ]);
+
+        assert_eq!(new_ids, vec![fresh_id]);
+        let ids: std::collections::HashSet<Uuid> =
+            engine.suggestions.iter().map(|s| s.id).collect();
+        assert_eq!(
+            ids,
+            std::collections::HashSet::from([reworded_id, same_summary_id, fresh_id])
+        );
+    }
+
+    #[test]
+    fn test_compare_runs_splits_new_resolved_and_persisting() {
+        let finding = |file: &str, summary: &str, line: usize| {
+            Suggestion::new(
+                SuggestionKind::BugFix,
+                Priority::High,
+                PathBuf::from(file),
+                summary.to_string(),
+                SuggestionSource::LlmDeep,
+            )
+            .with_line(line)
+        };
+        let earlier = vec![
+            finding("src/retry.rs", "Retry loop never backs off", 40),
+            finding("src/io.rs", "File handle leaks on error", 10),
+        ];
+        let later = vec![
+            finding("src/retry.rs", "Retries fire without backoff", 41),
+            finding("src/api.rs", "Missing timeout", 7),
+        ];
+
+        let comparison = compare_runs(&earlier, &later);
+        assert_eq!(comparison.persisting.len(), 1);
+        assert_eq!(
+            comparison.persisting[0].1.summary,
+            "Retries fire without backoff"
+        );
+        assert_eq!(comparison.resolved[0].summary, "File handle leaks on error");
+        assert_eq!(comparison.new[0].summary, "Missing timeout");
+        assert_eq!((comparison.resolved.len(), comparison.new.len()), (1, 1));
+    }
+
     #[test]
     fn test_kind_labels_are_plain_language() {
         assert_eq!(SuggestionKind::Refactoring.label(), "Cleanup");
@@ -796,4 +1414,68 @@ mod tests {
         assert_eq!(SuggestionKind::Testing.label(), "Safety");
         assert_eq!(SuggestionKind::Documentation.label(), "Guidance");
     }
+
+    #[test]
+    fn test_bulk_dismiss_removes_a_scope_and_restores_it() {
+        let mut engine = SuggestionEngine::new(CodebaseIndex {
+            root: PathBuf::from("."),
+            files: std::collections::HashMap::new(),
+            index_errors: Vec::new(),
+            git_head: None,
+            packages: Vec::new(),
+            excluded_files: 0,
+            filter_fingerprint: None,
+        });
+        let suggestion = |kind, priority, file: &str| {
+            Suggestion::new(
+                kind,
+                priority,
+                PathBuf::from(file),
+                format!("{:?} in {}", kind, file),
+                SuggestionSource::LlmDeep,
+            )
+        };
+        engine.replace_llm_suggestions(vec![
+            suggestion(SuggestionKind::Documentation, Priority::Low, "src/a.rs"),
+            suggestion(SuggestionKind::Documentation, Priority::High, "src/b.rs"),
+            suggestion(SuggestionKind::BugFix, Priority::Low, "legacy/old/c.rs"),
+            suggestion(SuggestionKind::BugFix, Priority::High, "src/d.rs"),
+        ]);
+
+        let legacy_fix = engine
+            .suggestions
+            .iter()
+            .find(|s| s.file.as_path() == std::path::Path::new("legacy/old/c.rs"))
+            .unwrap();
+        let scopes = BulkDismissScope::around(legacy_fix);
+        let low_docs = BulkDismissScope::Kind {
+            kind: SuggestionKind::Documentation,
+            priority: Some(Priority::Low),
+        };
+        assert_eq!(low_docs.label(), "low-priority docs suggestions");
+        let legacy = BulkDismissScope::Directory(PathBuf::from("legacy"));
+        assert_eq!(legacy.label(), "suggestions in legacy/");
+        assert_eq!(
+            scopes[2..],
+            [
+                BulkDismissScope::Directory(PathBuf::from("legacy/old")),
+                legacy.clone(),
+            ]
+        );
+
+        let ids: std::collections::HashSet<Uuid> = engine
+            .suggestions
+            .iter()
+            .filter(|s| low_docs.matches(s) || legacy.matches(s))
+            .map(|s| s.id)
+            .collect();
+        let dismissed = engine.dismiss(&ids);
+        assert_eq!(dismissed.len(), 2);
+        assert_eq!(engine.suggestions.len(), 2);
+
+        // Anything already listed again is not duplicated on restore
+        engine.suggestions.push(dismissed[0].clone());
+        assert_eq!(engine.restore(dismissed), 1);
+        assert_eq!(engine.suggestions.len(), 4);
+    }
 }
@@ -0,0 +1,158 @@
+//! Inline suppression comments.
+//!
+//! A comment like `// cosmos-ignore: security token is test-only` acknowledges a known
+//! exception. It covers findings on its own line and on the line below it, and only those
+//! whose category matches: `all`, `bug`, `security`, or a suggestion kind (`bug_fix`,
+//! `refactoring`, ...). A suppression without a reason is ignored so every exception carries
+//! its justification into code review.
+
+use super::{Suggestion, SuggestionCategory, SuggestionKind};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+pub const SUPPRESSION_MARKER: &str = "cosmos-ignore:";
+
+pub(super) const KIND_NAMES: [(&str, SuggestionKind); 8] = [
+    ("improvement", SuggestionKind::Improvement),
+    ("bug_fix", SuggestionKind::BugFix),
+    ("feature", SuggestionKind::Feature),
+    ("optimization", SuggestionKind::Optimization),
+    ("quality", SuggestionKind::Quality),
+    ("documentation", SuggestionKind::Documentation),
+    ("testing", SuggestionKind::Testing),
+    ("refactoring", SuggestionKind::Refactoring),
+];
+
+/// One `cosmos-ignore:` comment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Suppression {
+    /// 1-based line of the comment
+    pub line: usize,
+    pub category: String,
+    pub reason: String,
+}
+
+impl Suppression {
+    /// Whether this suppression covers a finding of `suggestion`'s category at `line`.
+    pub fn covers(&self, suggestion: &Suggestion, line: usize) -> bool {
+        (line == self.line || line == self.line + 1) && self.matches_category(suggestion)
+    }
+
+    fn matches_category(&self, suggestion: &Suggestion) -> bool {
+        match self.category.as_str() {
+            "all" => true,
+            "bug" => suggestion.category == SuggestionCategory::Bug,
+            "security" => suggestion.category == SuggestionCategory::Security,
+            name => KIND_NAMES
+                .iter()
+                .any(|(kind_name, kind)| *kind_name == name && *kind == suggestion.kind),
+        }
+    }
+}
+
+/// Every well-formed suppression in `content`. The marker may follow any comment syntax.
+pub fn parse_suppressions(content: &str) -> Vec<Suppression> {
+    content
+        .lines()
+        .enumerate()
+        .filter_map(|(idx, line)| {
+            let (_, rest) = line.split_once(SUPPRESSION_MARKER)?;
+            let rest = rest.trim().trim_end_matches("*/").trim_end_matches("-->");
+            let (category, reason) = rest.trim().split_once(char::is_whitespace)?;
+            let reason = reason.trim();
+            if reason.is_empty() {
+                return None;
+            }
+            Some(Suppression {
+                line: idx + 1,
+                category: category.trim().to_ascii_lowercase().replace('-', "_"),
+                reason: reason.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Drop suggestions covered by a suppression in their primary file. Files are read from
+/// `root` once each; findings without a line are never suppressed. Returns the kept
+/// suggestions and how many were suppressed.
+pub fn retain_unsuppressed(root: &Path, suggestions: Vec<Suggestion>) -> (Vec<Suggestion>, usize) {
+    let mut by_file: HashMap<PathBuf, Vec<Suppression>> = HashMap::new();
+    let before = suggestions.len();
+    let kept: Vec<Suggestion> = suggestions
+        .into_iter()
+        .filter(|suggestion| {
+            let Some(line) = suggestion.line else {
+                return true;
+            };
+            let suppressions = by_file.entry(suggestion.file.clone()).or_insert_with(|| {
+                std::fs::read_to_string(root.join(&suggestion.file))
+                    .map(|content| parse_suppressions(&content))
+                    .unwrap_or_default()
+            });
+            !suppressions
+                .iter()
+                .any(|suppression| suppression.covers(suggestion, line))
+        })
+        .collect();
+    let suppressed = before - kept.len();
+    (kept, suppressed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::suggest::{test_finding, Priority};
+
+    fn finding(kind: SuggestionKind, line: usize) -> Suggestion {
+        test_finding(kind, Priority::Medium, "src/lib.rs", line, "finding")
+    }
+
+    #[test]
+    fn test_parse_suppressions_requires_category_and_reason() {
+        let content = "fn a() {}\n// cosmos-ignore: security token is a test fixture\n# cosmos-ignore: all\n/* cosmos-ignore: Bug-Fix known race, see #12 */\n";
+        let parsed = parse_suppressions(content);
+        assert_eq!(
+            parsed,
+            vec![
+                Suppression {
+                    line: 2,
+                    category: "security".to_string(),
+                    reason: "token is a test fixture".to_string(),
+                },
+                Suppression {
+                    line: 4,
+                    category: "bug_fix".to_string(),
+                    reason: "known race, see #12".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_retain_unsuppressed_matches_line_and_category() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("src")).unwrap();
+        std::fs::write(
+            dir.path().join("src/lib.rs"),
+            "// cosmos-ignore: refactoring kept for the plugin ABI\npub fn unused() {}\n\nfn other() {}\n",
+        )
+        .unwrap();
+
+        let mut no_line = finding(SuggestionKind::Refactoring, 1);
+        no_line.line = None;
+        let (kept, suppressed) = retain_unsuppressed(
+            dir.path(),
+            vec![
+                finding(SuggestionKind::Refactoring, 2),
+                finding(SuggestionKind::BugFix, 2),
+                finding(SuggestionKind::Refactoring, 4),
+                no_line,
+            ],
+        );
+        assert_eq!(suppressed, 1);
+        assert_eq!(kept.len(), 3);
+        assert!(kept
+            .iter()
+            .all(|s| s.kind != SuggestionKind::Refactoring || s.line != Some(2)));
+    }
+}
@@ -3,23 +3,66 @@ use std::path::{Component, Path, PathBuf};
 use std::process::{Command, ExitStatus, Stdio};
 use std::thread;
 use std::time::{Duration, Instant};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 
+/// Truncate to at most `max` user-perceived characters, appending "..." when shortened.
+///
+/// Counts grapheme clusters so combining marks and emoji sequences are never split.
 pub fn truncate(s: &str, max: usize) -> String {
     if max == 0 {
         return String::new();
     }
 
-    let char_count = s.chars().count();
-    if char_count <= max {
+    let graphemes: Vec<&str> = s.graphemes(true).collect();
+    if graphemes.len() <= max {
         return s.to_string();
     }
 
     if max <= 3 {
-        return s.chars().take(max).collect();
+        return graphemes[..max].concat();
     }
 
-    let truncated: String = s.chars().take(max - 3).collect();
-    format!("{}...", truncated)
+    format!("{}...", graphemes[..max - 3].concat())
+}
+
+/// Display width of text in terminal columns (CJK and emoji count as two)
+pub fn display_width(text: &str) -> usize {
+    UnicodeWidthStr::width(text)
+}
+
+/// Split text after the longest grapheme prefix that fits within `max_width` columns
+pub fn split_at_width(text: &str, max_width: usize) -> (&str, &str) {
+    let mut used = 0;
+    for (idx, grapheme) in text.grapheme_indices(true) {
+        let width = UnicodeWidthStr::width(grapheme);
+        if used + width > max_width {
+            return text.split_at(idx);
+        }
+        used += width;
+    }
+    (text, "")
+}
+
+/// Truncate text to fit within `max_width` columns, ending with "…" when shortened
+pub fn truncate_to_width(text: &str, max_width: usize) -> String {
+    if max_width == 0 {
+        return String::new();
+    }
+    if display_width(text) <= max_width {
+        return text.to_string();
+    }
+    let (head, _) = split_at_width(text, max_width - 1);
+    format!("{head}…")
+}
+
+/// Fit text to exactly `width` columns for table output: truncated when wider, padded with
+/// spaces when narrower. `format!("{:<24}")` pads by `char`, which misaligns wide characters.
+pub fn pad_to_width(text: &str, width: usize) -> String {
+    let mut out = truncate_to_width(text, width);
+    let used = display_width(&out);
+    out.extend(std::iter::repeat_n(' ', width.saturating_sub(used)));
+    out
 }
 
 #[derive(Debug)]
@@ -198,7 +241,10 @@ pub fn hash_str(content: &str) -> String {
 
 #[cfg(test)]
 mod tests {
-    use super::{hash_str, resolve_repo_path_allow_new, truncate};
+    use super::{
+        display_width, hash_str, pad_to_width, resolve_repo_path_allow_new, truncate,
+        truncate_to_width,
+    };
     use std::fs;
     use std::path::PathBuf;
     use std::time::{SystemTime, UNIX_EPOCH};
@@ -209,6 +255,22 @@ mod tests {
         assert_eq!(truncate(input, 4), "é...");
     }
 
+    #[test]
+    fn test_truncate_keeps_combining_marks_together() {
+        // "e" + combining acute accent must stay a single unit
+        let input = "e\u{301}e\u{301}e\u{301}e\u{301}e\u{301}";
+        assert_eq!(truncate(input, 4), "e\u{301}...");
+        assert_eq!(truncate(input, 5), input);
+    }
+
+    #[test]
+    fn test_truncate_keeps_emoji_sequences_together() {
+        let family = "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}";
+        let input = format!("{family}{family}{family}{family}{family}");
+        assert_eq!(truncate(&input, 4), format!("{family}..."));
+        assert_eq!(truncate("👍🏽👍🏽", 1), "👍🏽");
+    }
+
     #[test]
     fn test_truncate_small_max() {
         let input = "こんにちは";
@@ -216,6 +278,16 @@ mod tests {
         assert_eq!(truncate(input, 0), "");
     }
 
+    #[test]
+    fn test_width_helpers_count_wide_characters_as_two_columns() {
+        assert_eq!(display_width("こんにちは"), 10);
+        assert_eq!(truncate_to_width("こんにちは", 5), "こん…");
+
+        let cells = [pad_to_width("モデル名", 6), pad_to_width("gpt", 6)];
+        assert_eq!(cells, ["モデ… ".to_string(), "gpt   ".to_string()]);
+        assert!(cells.iter().all(|cell| display_width(cell) == 6));
+    }
+
     #[test]
     fn test_hash_str_is_stable() {
         let a = hash_str("hello");
@@ -0,0 +1,399 @@
+//! Stable entry point for embedding the suggestion pipeline in another Rust program.
+//!
+//! ```no_run
+//! # async fn run() -> anyhow::Result<()> {
+//! use cosmos_engine::{Cosmos, Provider};
+//!
+//! let cosmos = Cosmos::builder()
+//!     .repo("/path/to/repo")
+//!     .provider(Provider::cerebras(std::env::var("CEREBRAS_API_KEY")?))
+//!     .build()?;
+//! for suggestion in cosmos.suggest().await? {
+//!     let preview = cosmos.preview(&suggestion).await?;
+//!     let outcome = cosmos.apply(&suggestion, &preview).await?;
+//!     println!("{}: passed={}", preview.summary, outcome.passed);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Everything here speaks the `cosmos_core::protocol` types and follows [`API_VERSION`];
+//! the `llm`, `lab`, and `mcp` modules serve the Cosmos binaries and may change in any release.
+
+use crate::llm::custom_tools::CustomToolManifest;
+use crate::{llm, CosmosEngine};
+use anyhow::Result;
+use cosmos_adapters::config::Config;
+use cosmos_adapters::util::{hash_str, resolve_repo_path_allow_new};
+use cosmos_core::context::WorkContext;
+use cosmos_core::index::CodebaseIndex;
+use cosmos_core::protocol::{
+    AppliedFile, ChangeSet, Engine as _, FixContext, FixPreview, RepoSnapshot, ReviewReport,
+};
+use cosmos_core::suggest::Suggestion;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+
+/// Semver of the embedding API (`Cosmos`, `CosmosBuilder`, `Provider`, `ApplyOutcome`,
+/// `CustomToolManifest`). It moves independently of the `cosmos` TUI release version.
+pub const API_VERSION: &str = "1.1.0";
+
+/// Credentials for the model provider.
+#[derive(Clone, Default)]
+pub struct Provider {
+    api_key: Option<String>,
+}
+
+impl Provider {
+    /// Cerebras with an explicit API key, scoped to this `Cosmos` instance.
+    pub fn cerebras(api_key: impl Into<String>) -> Self {
+        Self {
+            api_key: Some(api_key.into()),
+        }
+    }
+
+    /// Whatever the `cosmos` CLI would use: the stored key, then `CEREBRAS_API_KEY`.
+    pub fn from_environment() -> Self {
+        Self::default()
+    }
+
+    async fn scope<F: Future>(&self, fut: F) -> F::Output {
+        match &self.api_key {
+            Some(key) => llm::client::with_api_key(key.clone(), fut).await,
+            None => fut.await,
+        }
+    }
+}
+
+impl std::fmt::Debug for Provider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Provider")
+            .field("api_key", &self.api_key.as_ref().map(|_| "<redacted>"))
+            .finish()
+    }
+}
+
+/// Builder for [`Cosmos`]; only `repo` is required.
+#[derive(Debug, Default)]
+pub struct CosmosBuilder {
+    repo: Option<PathBuf>,
+    provider: Provider,
+    trusted: Option<bool>,
+    tools: Vec<CustomToolManifest>,
+}
+
+impl CosmosBuilder {
+    pub fn repo(mut self, path: impl Into<PathBuf>) -> Self {
+        self.repo = Some(path.into());
+        self
+    }
+
+    pub fn provider(mut self, provider: Provider) -> Self {
+        self.provider = provider;
+        self
+    }
+
+    /// Whether `apply` may run the repo's quick checks and tests. Defaults to the workspace
+    /// trust recorded by the `cosmos` CLI.
+    pub fn trust_workspace(mut self, trusted: bool) -> Self {
+        self.trusted = Some(trusted);
+        self
+    }
+
+    /// Offer an extra tool to the agent, e.g. a lookup in an internal service catalog.
+    /// Registered tools are process-wide and run in any workspace, unlike the repo's `[[tools]]`.
+    pub fn tool(mut self, manifest: CustomToolManifest) -> Self {
+        self.tools.push(manifest);
+        self
+    }
+
+    /// Index the repo and load its `.cosmos/config.toml` model routing, prompt overrides, and
+    /// tools, plus the config's spend caps, response cache, and suggestion sharding. These are
+    /// process-wide, so the last built instance wins.
+    pub fn build(self) -> Result<Cosmos> {
+        let repo = self
+            .repo
+            .ok_or_else(|| anyhow::anyhow!("Cosmos::builder() needs a repo path"))?;
+        for manifest in &self.tools {
+            manifest.validate()?;
+        }
+        let cosmos = Cosmos::open(&repo, self.provider, self.trusted)?;
+        let config = Config::load();
+        llm::routing::install_for_repo(&config, cosmos.root())?;
+        llm::spend::install(&config)?;
+        llm::response_cache::install(&config, cosmos.root())?;
+        llm::analysis::sharding::install(&config)?;
+        llm::prompt_templates::install(cosmos.root())?;
+        llm::custom_tools::install(cosmos.root(), cosmos.trusted)?;
+        for manifest in self.tools {
+            llm::custom_tools::register(manifest)?;
+        }
+        Ok(cosmos)
+    }
+}
+
+/// Result of running the apply harness for one suggestion.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct ApplyOutcome {
+    /// The harness passed and `files` were written to the repo
+    pub passed: bool,
+    pub description: String,
+    pub files: Vec<AppliedFile>,
+    /// Why the harness rejected the change; empty when it passed
+    pub fail_reasons: Vec<String>,
+    /// Harness report on disk, when one was written
+    pub report_path: Option<PathBuf>,
+}
+
+/// An indexed repository plus the provider used to analyze it.
+#[derive(Debug)]
+pub struct Cosmos {
+    repo: RepoSnapshot,
+    provider: Provider,
+    trusted: bool,
+}
+
+impl Cosmos {
+    pub fn builder() -> CosmosBuilder {
+        CosmosBuilder::default()
+    }
+
+    fn open(repo: &Path, provider: Provider, trusted: Option<bool>) -> Result<Self> {
+        let root = repo
+            .canonicalize()
+            .map_err(|e| anyhow::anyhow!("Cannot open repo {}: {}", repo.display(), e))?;
+        let trusted = trusted.unwrap_or_else(|| Config::load().is_workspace_trusted(&root));
+        Ok(Self {
+            repo: RepoSnapshot {
+                index: CodebaseIndex::new(&root)?,
+                context: WorkContext::load(&root)?,
+                root,
+            },
+            provider,
+            trusted,
+        })
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.repo.root
+    }
+
+    /// The index built at `build` (or the last `refresh`).
+    pub fn index(&self) -> &CodebaseIndex {
+        &self.repo.index
+    }
+
+    /// Re-index after the working tree changed (including after `apply`).
+    pub fn refresh(&mut self) -> Result<()> {
+        self.repo.index = CodebaseIndex::new(&self.repo.root)?;
+        self.repo.context = WorkContext::load(&self.repo.root)?;
+        Ok(())
+    }
+
+    /// Run a grounded suggestion scan and return the suggestions that passed the quality gate.
+    pub async fn suggest(&self) -> Result<Vec<Suggestion>> {
+        self.provider
+            .scope(CosmosEngine.scan_and_suggest(&self.repo))
+            .await
+    }
+
+    /// Describe the fix for `suggestion`. The `preview_hash` pins the affected files; `apply`
+    /// refuses the preview once any of them change.
+    pub async fn preview(&self, suggestion: &Suggestion) -> Result<FixPreview> {
+        let preview = llm::build_fix_preview_from_validated_suggestion(suggestion);
+        Ok(FixPreview {
+            summary: preview.problem_summary,
+            outcome: preview.outcome,
+            files: suggestion.affected_files().into_iter().cloned().collect(),
+            preview_hash: self.preview_hash(suggestion),
+        })
+    }
+
+    /// Implement `suggestion` under the strict harness and write the files when it passes.
+    pub async fn apply(
+        &self,
+        suggestion: &Suggestion,
+        preview: &FixPreview,
+    ) -> Result<ApplyOutcome> {
+        if self.preview_hash(suggestion) != preview.preview_hash {
+            return Err(anyhow::anyhow!(
+                "Files changed since the preview; request a new preview"
+            ));
+        }
+        let result = self
+            .provider
+            .scope(run_apply_harness(
+                self.root(),
+                suggestion,
+                self.trusted,
+                true,
+                "the embedding API",
+            ))
+            .await?;
+
+        Ok(ApplyOutcome {
+            passed: result.diagnostics.passed,
+            description: result.description,
+            files: result
+                .file_changes
+                .into_iter()
+                .map(|file| AppliedFile {
+                    path: file.path,
+                    content: file.content,
+                })
+                .collect(),
+            fail_reasons: result.diagnostics.fail_reasons,
+            report_path: result.diagnostics.report_path,
+        })
+    }
+
+    /// Adversarially review a change set against what the fix was meant to do.
+    pub async fn review(&self, change_set: &ChangeSet, ctx: &FixContext) -> Result<ReviewReport> {
+        self.provider
+            .scope(CosmosEngine.adversarial_review(change_set, ctx))
+            .await
+    }
+
+    fn preview_hash(&self, suggestion: &Suggestion) -> String {
+        preview_hash(self.root(), suggestion)
+    }
+}
+
+/// Hash of the files `suggestion` touches, so apply can refuse stale previews.
+///
+/// Shared with `cosmos serve`, whose previews use the same hash.
+#[doc(hidden)]
+pub fn preview_hash(root: &Path, suggestion: &Suggestion) -> String {
+    let mut combined = suggestion.id.to_string();
+    for path in suggestion.affected_files() {
+        combined.push('\0');
+        combined.push_str(&path.to_string_lossy());
+        combined.push('\0');
+        combined.push_str(&std::fs::read_to_string(root.join(path)).unwrap_or_default());
+    }
+    hash_str(&combined)
+}
+
+/// Implement `suggestion` under the strict harness and, when it passes and `write` is set,
+/// write the files and record the finalization outcome as applied through `origin`.
+///
+/// The single apply path behind [`Cosmos::apply`] and `cosmos serve`.
+#[doc(hidden)]
+pub async fn run_apply_harness(
+    root: &Path,
+    suggestion: &Suggestion,
+    trusted: bool,
+    write: bool,
+    origin: &str,
+) -> Result<llm::ImplementationRunResult> {
+    let harness = llm::ImplementationHarnessConfig::interactive_strict().with_repo_config(root)?;
+    let harness = if trusted {
+        harness
+    } else {
+        harness.for_untrusted_workspace()
+    };
+    let fix_preview = llm::build_fix_preview_from_validated_suggestion(suggestion);
+    let mut result = llm::implement_validated_suggestion_with_harness(
+        root,
+        suggestion,
+        &fix_preview,
+        None,
+        harness,
+    )
+    .await?;
+
+    if result.diagnostics.passed && write {
+        let written = write_files(root, &result.file_changes);
+        let (status, detail) = match &written {
+            Ok(()) => (
+                llm::ImplementationFinalizationStatus::Applied,
+                format!("Applied passing harness result through {}", origin),
+            ),
+            Err(e) => (
+                llm::ImplementationFinalizationStatus::FailedBeforeFinalize,
+                e.clone(),
+            ),
+        };
+        let _ = llm::record_harness_finalization_outcome(
+            root,
+            &mut result.diagnostics,
+            status,
+            Some(detail),
+            Some(written.is_err()),
+            llm::ImplementationHarnessRunContext::Interactive,
+            None,
+        );
+        written.map_err(|e| anyhow::anyhow!(e))?;
+    }
+    Ok(result)
+}
+
+fn write_files(root: &Path, files: &[llm::ImplementationAppliedFile]) -> Result<(), String> {
+    for file in files {
+        let resolved = resolve_repo_path_allow_new(root, &file.path)?;
+        if let Some(parent) = resolved.absolute.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        std::fs::write(&resolved.absolute, &file.content)
+            .map_err(|e| format!("Failed to write {}: {}", file.path.display(), e))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::repo_with_file;
+    use cosmos_core::suggest::{Priority, SuggestionKind, SuggestionSource};
+
+    #[test]
+    fn test_builder_requires_repo() {
+        let err = Cosmos::builder().build().unwrap_err();
+        assert!(err.to_string().contains("repo path"));
+    }
+
+    #[test]
+    fn test_builder_rejects_tool_shadowing_a_builtin() {
+        let dir = repo_with_file();
+        let err = Cosmos::builder()
+            .repo(dir.path())
+            .tool(CustomToolManifest {
+                name: "shell".to_string(),
+                description: "Not allowed".to_string(),
+                parameters: serde_json::json!({ "type": "object" }),
+                command: "true".to_string(),
+                timeout_ms: None,
+            })
+            .build()
+            .unwrap_err();
+        assert!(err.to_string().contains("built-in tool"));
+    }
+
+    #[tokio::test]
+    async fn test_provider_key_is_scoped_to_its_calls() {
+        let provider = Provider::cerebras("embedded-key");
+        let key = provider.scope(async { llm::client::api_key() }).await;
+        assert_eq!(key.as_deref(), Some("embedded-key"));
+        assert!(!format!("{:?}", provider).contains("embedded-key"));
+    }
+
+    #[tokio::test]
+    async fn test_apply_rejects_stale_preview() {
+        let dir = repo_with_file();
+        let cosmos = Cosmos::open(dir.path(), Provider::cerebras("unused"), Some(false)).unwrap();
+        let suggestion = Suggestion::new(
+            SuggestionKind::BugFix,
+            Priority::High,
+            PathBuf::from("src/lib.rs"),
+            "one() returns the wrong value".to_string(),
+            SuggestionSource::LlmDeep,
+        );
+        let preview = cosmos.preview(&suggestion).await.unwrap();
+        assert_eq!(preview.files, vec![PathBuf::from("src/lib.rs")]);
+
+        std::fs::write(dir.path().join("src/lib.rs"), "pub fn one() -> u32 { 2 }\n").unwrap();
+        let err = cosmos.apply(&suggestion, &preview).await.unwrap_err();
+        assert!(err.to_string().contains("changed since the preview"));
+    }
+}
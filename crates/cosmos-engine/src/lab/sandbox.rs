@@ -10,7 +10,9 @@ const GIT_SWITCH_TIMEOUT: Duration = Duration::from_secs(30);
 const SANDBOX_ROOT_DIR: &str = "cosmos-sandbox";
 
 /// Isolated worktree session used for safe validation loops.
-#[derive(Debug, Clone)]
+///
+/// The worktree is removed on drop, so a cancelled apply never leaks it.
+#[derive(Debug)]
 pub struct SandboxSession {
     source_repo: PathBuf,
     run_root: PathBuf,
@@ -170,6 +172,12 @@ impl SandboxSession {
     }
 }
 
+impl Drop for SandboxSession {
+    fn drop(&mut self) {
+        let _ = self.cleanup();
+    }
+}
+
 fn run_git(repo_dir: &Path, args: &[&str], timeout: Duration) -> Result<()> {
     let mut cmd = Command::new("git");
     cmd.current_dir(repo_dir).args(args);
@@ -1,4 +1,8 @@
 //! Engine implementation and LLM orchestration for Cosmos.
+//!
+//! To embed the suggestion pipeline, use [`Cosmos`]: it and the types next to it are the
+//! supported API, versioned by [`API_VERSION`]. The remaining public modules back the Cosmos
+//! binaries and carry no compatibility promise.
 
 use anyhow::Result;
 use cosmos_core::protocol::{
@@ -11,8 +15,23 @@ use std::future::Future;
 use std::pin::Pin;
 use uuid::Uuid;
 
+mod facade;
+#[doc(hidden)]
 pub mod lab;
+#[doc(hidden)]
 pub mod llm;
+#[doc(hidden)]
+pub mod mcp;
+#[doc(hidden)]
+pub mod plugins;
+#[cfg(any(test, feature = "test-support"))]
+#[doc(hidden)]
+pub mod test_support;
+
+#[doc(hidden)]
+pub use facade::{preview_hash, run_apply_harness};
+pub use facade::{ApplyOutcome, Cosmos, CosmosBuilder, Provider, API_VERSION};
+pub use llm::custom_tools::CustomToolManifest;
 
 #[derive(Debug, Default, Clone)]
 pub struct CosmosEngine;
@@ -79,7 +98,15 @@ impl EngineContract for CosmosEngine {
             let files: Vec<_> = change_set
                 .files
                 .iter()
-                .map(|f| (f.path.clone(), String::new(), f.content.clone()))
+                .map(|f| {
+                    let original = change_set
+                        .originals
+                        .iter()
+                        .find(|o| o.path == f.path)
+                        .map(|o| o.content.clone())
+                        .unwrap_or_default();
+                    (f.path.clone(), original, f.content.clone())
+                })
                 .collect();
             let review = llm::verify_changes(
                 &files,
@@ -8,12 +8,19 @@ use super::client::{
     is_retryable_network_error, missing_api_key_message, model_id_for_backend, parse_retry_after,
     send_with_retry, supports_parallel_tool_calls_for_backend, MAX_RETRIES, REQUEST_TIMEOUT_SECS,
 };
-use super::models::{merge_usage, Model, Usage};
+use super::custom_tools;
+use super::failover;
+use super::models::{merge_usage, tag_usage, Model, Usage};
+use super::redaction;
+use super::routing;
+use super::spend;
+use super::tokens;
 #[cfg(test)]
 use super::tools::get_relace_search_tool_definitions;
 use super::tools::{
-    execute_tool, get_relace_search_tool_definitions_cerebras, get_tool_definitions,
-    parse_report_back_payload, ReportBackExplanation, ReportBackPayload, ToolCall, ToolDefinition,
+    execute_tool_with_budget, get_relace_search_tool_definitions_cerebras, get_tool_definitions,
+    parse_report_back_content, ReportBackExplanation, ReportBackPayload, ToolBudget, ToolCall,
+    ToolDefinition,
 };
 use futures::StreamExt;
 use serde::{Deserialize, Serialize};
@@ -114,6 +121,12 @@ pub struct AgenticTrace {
     pub repeated_tool_error_count: u32,
     #[serde(default)]
     pub invalid_report_back_count: u32,
+    /// File lines the read tools returned during the run
+    #[serde(default)]
+    pub lines_read: usize,
+    /// Search tool calls the run made
+    #[serde(default)]
+    pub searches: usize,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -244,6 +257,7 @@ async fn execute_tool_calls_and_append_messages(
     messages: &mut Vec<Message>,
     repo_root: &Path,
     tool_calls: &[ToolCallMessage],
+    tool_budget: &Arc<ToolBudget>,
 ) {
     if tool_calls.is_empty() {
         return;
@@ -265,13 +279,12 @@ async fn execute_tool_calls_and_append_messages(
         })
         .collect();
 
-    let results = run_parallel_ordered_blocking(
-        inputs,
-        MAX_PARALLEL_TOOL_EXECUTIONS,
-        Arc::new(|(repo_root, tool_call): (PathBuf, ToolCall)| {
-            execute_tool(&repo_root, &tool_call)
-        }),
-    )
+    let results = run_parallel_ordered_blocking(inputs, MAX_PARALLEL_TOOL_EXECUTIONS, {
+        let tool_budget = Arc::clone(tool_budget);
+        Arc::new(move |(repo_root, tool_call): (PathBuf, ToolCall)| {
+            execute_tool_with_budget(&repo_root, &tool_call, &tool_budget)
+        })
+    })
     .await;
 
     for (idx, tc) in tool_calls.iter().enumerate() {
@@ -287,7 +300,7 @@ async fn execute_tool_calls_and_append_messages(
             });
         messages.push(Message {
             role: "tool".to_string(),
-            content: Some(result.content),
+            content: Some(redaction::redact(&result.content)),
             tool_calls: None,
             tool_call_id: Some(tc_id),
         });
@@ -494,7 +507,7 @@ fn env_u64(name: &str) -> Option<u64> {
 }
 
 fn bounded_max_completion_tokens(model: Model, configured: u32) -> u32 {
-    let cap = model.max_tokens();
+    let cap = routing::routed_max_tokens(model.max_tokens());
     configured.max(256).min(cap)
 }
 
@@ -835,6 +848,7 @@ fn apply_tool_call_deltas(
     names
 }
 
+#[allow(clippy::too_many_arguments)]
 fn process_sse_payload(
     payload: &str,
     content: &mut String,
@@ -919,10 +933,23 @@ async fn send_streaming_chat_request(
     request: &ChatRequest,
     stream_sink: Option<&AgenticStreamSink>,
 ) -> anyhow::Result<ChatResponse> {
-    let mut retry_count = 0;
+    spend::check()?;
+    tokens::note_request(request);
+    failover::send(request, |body| {
+        send_streaming_to_model(client, api_key, body, stream_sink)
+    })
+    .await
+}
 
+async fn send_streaming_to_model(
+    client: &reqwest::Client,
+    api_key: &str,
+    request: serde_json::Value,
+    stream_sink: Option<&AgenticStreamSink>,
+) -> anyhow::Result<ChatResponse> {
+    let mut retry_count = 0;
     loop {
-        let request_builder = client.post(chat_completions_url()).json(request);
+        let request_builder = client.post(chat_completions_url()).json(&request);
         let response = match apply_backend_headers(request_builder, api_key).send().await {
             Ok(response) => response,
             Err(err) => {
@@ -1166,7 +1193,8 @@ async fn maybe_format_agentic_content(
                 role: "user".to_string(),
                 content: Some(format!(
                     "Original task:\n{}\n\nDraft answer:\n{}\n\nReturn only valid JSON.",
-                    user_prompt, draft_content
+                    redaction::redact(user_prompt),
+                    draft_content
                 )),
                 tool_calls: None,
                 tool_call_id: None,
@@ -1211,7 +1239,7 @@ async fn maybe_format_agentic_content(
 
     Ok(AgenticResponse {
         content: formatted,
-        usage: merge_usage(usage, parsed.usage),
+        usage: merge_usage(usage, tag_usage(parsed.usage, model)),
     })
 }
 
@@ -1241,7 +1269,9 @@ pub async fn call_llm_agentic(
 
     let client = create_http_client(REQUEST_TIMEOUT_SECS)?;
 
-    let tools = get_tool_definitions();
+    let mut tools = get_tool_definitions();
+    tools.extend(custom_tools::definitions());
+    let tool_budget = Arc::new(ToolBudget::from_env());
     let mut messages = vec![
         Message {
             role: "system".to_string(),
@@ -1251,7 +1281,7 @@ pub async fn call_llm_agentic(
         },
         Message {
             role: "user".to_string(),
-            content: Some(user.to_string()),
+            content: Some(redaction::redact(user)),
             tool_calls: None,
             tool_call_id: None,
         },
@@ -1282,7 +1312,7 @@ pub async fn call_llm_agentic(
             user: None,
             max_completion_tokens: tool_turn_max_completion_tokens(model),
             stream: false,
-            temperature: Some(TOOL_CALL_TEMPERATURE),
+            temperature: routing::routed_temperature(Some(TOOL_CALL_TEMPERATURE)),
             response_format: None,
             disable_reasoning: reasoning.disable_reasoning,
             clear_thinking: reasoning.clear_thinking,
@@ -1301,7 +1331,7 @@ pub async fn call_llm_agentic(
 
         let parsed: ChatResponse = serde_json::from_str(&text)
             .map_err(|e| anyhow::anyhow!("Failed to parse response: {}\n{}", e, text))?;
-        total_usage = merge_usage(total_usage, parsed.usage.clone());
+        total_usage = merge_usage(total_usage, tag_usage(parsed.usage.clone(), model));
 
         let choice = parsed
             .choices
@@ -1344,14 +1374,14 @@ pub async fn call_llm_agentic(
                     })
                     .collect();
 
-                let results = run_parallel_ordered_blocking(
-                    inputs,
-                    MAX_PARALLEL_TOOL_EXECUTIONS,
-                    Arc::new(|(repo_root, tool_call): (PathBuf, ToolCall)| {
-                        execute_tool(&repo_root, &tool_call)
-                    }),
-                )
-                .await;
+                let results =
+                    run_parallel_ordered_blocking(inputs, MAX_PARALLEL_TOOL_EXECUTIONS, {
+                        let tool_budget = Arc::clone(&tool_budget);
+                        Arc::new(move |(repo_root, tool_call): (PathBuf, ToolCall)| {
+                            execute_tool_with_budget(&repo_root, &tool_call, &tool_budget)
+                        })
+                    })
+                    .await;
 
                 for (idx, tc) in tool_calls.iter().enumerate() {
                     let tc_id = tc.id.clone();
@@ -1367,7 +1397,7 @@ pub async fn call_llm_agentic(
                         });
                     messages.push(Message {
                         role: "tool".to_string(),
-                        content: Some(result.content),
+                        content: Some(redaction::redact(&result.content)),
                         tool_calls: None,
                         tool_call_id: Some(tc_id),
                     });
@@ -1429,7 +1459,7 @@ pub async fn call_llm_agentic(
         user: None,
         max_completion_tokens: final_response_max_completion_tokens(model),
         stream: false,
-        temperature: None,
+        temperature: routing::routed_temperature(None),
         response_format: None,
         disable_reasoning: reasoning.disable_reasoning,
         clear_thinking: reasoning.clear_thinking,
@@ -1449,7 +1479,7 @@ pub async fn call_llm_agentic(
                 let p: ChatResponse = serde_json::from_str(&text).map_err(|e| {
                     anyhow::anyhow!("Failed to parse final response: {}\n{}", e, text)
                 })?;
-                total_usage = merge_usage(total_usage, p.usage.clone());
+                total_usage = merge_usage(total_usage, tag_usage(p.usage.clone(), model));
                 let choice = p
                     .choices
                     .first()
@@ -1532,10 +1562,36 @@ pub async fn call_llm_agentic_report_back_only(
     repo_root: &Path,
     max_iterations: usize,
     stream_sink: Option<AgenticStreamSink>,
+) -> anyhow::Result<AgenticReportBackResponse> {
+    let tool_budget = Arc::new(ToolBudget::from_env());
+    let mut response = Box::pin(report_back_loop(
+        system,
+        user,
+        model,
+        repo_root,
+        max_iterations,
+        stream_sink,
+        &tool_budget,
+    ))
+    .await?;
+    response.trace.lines_read = tool_budget.lines_read();
+    response.trace.searches = tool_budget.searches();
+    Ok(response)
+}
+
+async fn report_back_loop(
+    system: &str,
+    user: &str,
+    model: Model,
+    repo_root: &Path,
+    max_iterations: usize,
+    stream_sink: Option<AgenticStreamSink>,
+    tool_budget: &Arc<ToolBudget>,
 ) -> anyhow::Result<AgenticReportBackResponse> {
     let api_key = api_key().ok_or_else(|| anyhow::anyhow!(missing_api_key_message()))?;
     let client = create_http_client(REQUEST_TIMEOUT_SECS)?;
-    let tools = get_relace_search_tool_definitions_cerebras();
+    let mut tools = get_relace_search_tool_definitions_cerebras();
+    tools.extend(custom_tools::definitions());
 
     let mut messages = vec![
         Message {
@@ -1546,7 +1602,7 @@ pub async fn call_llm_agentic_report_back_only(
         },
         Message {
             role: "user".to_string(),
-            content: Some(user.to_string()),
+            content: Some(redaction::redact(user)),
             tool_calls: None,
             tool_call_id: None,
         },
@@ -1645,7 +1701,7 @@ pub async fn call_llm_agentic_report_back_only(
                 tool_turn_max_completion_tokens(model)
             },
             stream: stream_reasoning,
-            temperature: Some(TOOL_CALL_TEMPERATURE),
+            temperature: routing::routed_temperature(Some(TOOL_CALL_TEMPERATURE)),
             response_format: None,
             disable_reasoning: reasoning.disable_reasoning,
             clear_thinking: reasoning.clear_thinking,
@@ -1693,7 +1749,7 @@ pub async fn call_llm_agentic_report_back_only(
             serde_json::from_str(&text)
                 .map_err(|e| anyhow::anyhow!("Failed to parse response: {}\n{}", e, text))?
         };
-        total_usage = merge_usage(total_usage, parsed.usage.clone());
+        total_usage = merge_usage(total_usage, tag_usage(parsed.usage.clone(), model));
 
         let choice = parsed
             .choices
@@ -1745,7 +1801,7 @@ pub async fn call_llm_agentic_report_back_only(
                     .iter()
                     .find(|tool_call| is_report_back_tool_name(&tool_call.function.name))
                 {
-                    match parse_report_back_payload(&report_call.function.arguments) {
+                    match parse_report_back_content(&report_call.function.arguments) {
                         Ok(payload) => {
                             let elapsed_ms = start.elapsed().as_millis() as u64;
                             let needs_more_rounds_for_empty_report =
@@ -1764,6 +1820,7 @@ pub async fn call_llm_agentic_report_back_only(
                                     &mut messages,
                                     repo_root,
                                     tool_calls,
+                                    tool_budget,
                                 )
                                 .await;
                                 let mut requirements = Vec::new();
@@ -1825,6 +1882,7 @@ pub async fn call_llm_agentic_report_back_only(
                                         &mut messages,
                                         repo_root,
                                         tool_calls,
+                                        tool_budget,
                                     )
                                     .await;
                                     let first_error = first_invalid_report_back_error
@@ -1875,6 +1933,7 @@ pub async fn call_llm_agentic_report_back_only(
                                 &mut messages,
                                 repo_root,
                                 tool_calls,
+                                tool_budget,
                             )
                             .await;
                             messages.push(Message {
@@ -1920,14 +1979,14 @@ pub async fn call_llm_agentic_report_back_only(
                     })
                     .collect();
 
-                let results = run_parallel_ordered_blocking(
-                    inputs,
-                    MAX_PARALLEL_TOOL_EXECUTIONS,
-                    Arc::new(|(repo_root, tool_call): (PathBuf, ToolCall)| {
-                        execute_tool(&repo_root, &tool_call)
-                    }),
-                )
-                .await;
+                let results =
+                    run_parallel_ordered_blocking(inputs, MAX_PARALLEL_TOOL_EXECUTIONS, {
+                        let tool_budget = Arc::clone(tool_budget);
+                        Arc::new(move |(repo_root, tool_call): (PathBuf, ToolCall)| {
+                            execute_tool_with_budget(&repo_root, &tool_call, &tool_budget)
+                        })
+                    })
+                    .await;
 
                 let mut round_error_signatures = Vec::new();
                 for (idx, tc) in tool_calls.iter().enumerate() {
@@ -1949,7 +2008,7 @@ pub async fn call_llm_agentic_report_back_only(
                     }
                     messages.push(Message {
                         role: "tool".to_string(),
-                        content: Some(result.content),
+                        content: Some(redaction::redact(&result.content)),
                         tool_calls: None,
                         tool_call_id: Some(tc_id),
                     });
@@ -2063,7 +2122,7 @@ pub async fn call_llm_agentic_report_back_only(
         }
 
         if finalization_round {
-            if let Ok(payload) = parse_report_back_payload(content.trim()) {
+            if let Ok(payload) = parse_report_back_content(content.trim()) {
                 trace.finalized_with_report_back = true;
                 trace.termination_reason = Some("text_report_back_ok".to_string());
                 trace.repeated_tool_error_count = trace
@@ -2152,7 +2211,7 @@ mod tests {
     #[test]
     fn test_tool_definition_serialization() {
         let tools = get_tool_definitions();
-        assert_eq!(tools.len(), 5); // tree, head, search, read_range, shell
+        assert_eq!(tools.len(), 5); // tree, head, search, read_lines, shell
 
         // First tool should be tree (for top-down exploration)
         assert_eq!(tools[0].function.name, "tree");
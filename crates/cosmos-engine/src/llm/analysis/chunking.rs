@@ -0,0 +1,453 @@
+//! Chunked analysis of very large files.
+//!
+//! The review tools return at most a few kilobytes per call, so a worker that opens a
+//! multi-thousand-line file sees its head and then guesses. Large files are instead split into
+//! chunks along top-level symbol boundaries (from the index), each overlapping the previous one
+//! so a defect that straddles a cut is seen whole. Workers are pointed at the chunks, and the
+//! lines they report are mapped back onto the file: a worker that numbered lines from the start
+//! of its chunk, or drifted, is corrected by locating its evidence quote.
+
+use cosmos_core::index::{CodebaseIndex, FileIndex};
+use cosmos_core::suggest::Suggestion;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Files with at least this many lines of code are analyzed in chunks.
+pub(super) const LARGE_FILE_LINES: usize = 2_000;
+/// Longest chunk; cuts fall on the last symbol boundary before it.
+const CHUNK_MAX_LINES: usize = 800;
+/// Lines each chunk repeats from the end of the previous one.
+const CHUNK_OVERLAP_LINES: usize = 40;
+/// Chunks never end earlier than this fraction of `CHUNK_MAX_LINES` to reach a boundary.
+const MIN_CHUNK_FILL: usize = 2;
+/// Symbol names listed per chunk in prompts.
+const MAX_CHUNK_SYMBOLS: usize = 4;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct FileChunk {
+    /// First line, 1-based and inclusive (includes the overlap with the previous chunk)
+    pub(super) start_line: usize,
+    /// Last line, inclusive
+    pub(super) end_line: usize,
+    /// Top-level symbols that start inside the chunk
+    pub(super) symbols: Vec<String>,
+}
+
+impl FileChunk {
+    pub(super) fn len(&self) -> usize {
+        self.end_line + 1 - self.start_line
+    }
+
+    fn contains(&self, line: usize) -> bool {
+        (self.start_line..=self.end_line).contains(&line)
+    }
+
+    /// `1-820 (parse, Lexer)`
+    pub(super) fn label(&self) -> String {
+        if self.symbols.is_empty() {
+            format!("{}-{}", self.start_line, self.end_line)
+        } else {
+            format!(
+                "{}-{} ({})",
+                self.start_line,
+                self.end_line,
+                self.symbols.join(", ")
+            )
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct ChunkedFile {
+    pub(super) path: PathBuf,
+    pub(super) total_lines: usize,
+    pub(super) chunks: Vec<FileChunk>,
+}
+
+impl ChunkedFile {
+    /// Map a line a worker reported for this file onto the file itself.
+    ///
+    /// The worker may have used file line numbers or counted from the top of a chunk. Each
+    /// reading is a candidate; when the evidence quote's first line occurs in the file, the
+    /// occurrence closest to any candidate wins, otherwise the reported line is kept if it
+    /// exists and the first chunk-relative reading otherwise.
+    pub(super) fn remap_line(&self, reported: usize, evidence_quote: &str, content: &str) -> usize {
+        let reported = reported.max(1);
+        let mut candidates = vec![reported];
+        candidates.extend(
+            self.chunks
+                .iter()
+                .filter(|chunk| reported <= chunk.len())
+                .map(|chunk| chunk.start_line + reported - 1),
+        );
+
+        let quote = evidence_quote
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty());
+        if let Some(quote) = quote {
+            let nearest = content
+                .lines()
+                .enumerate()
+                .filter(|(_, line)| line.contains(quote))
+                .map(|(idx, _)| idx + 1)
+                .min_by_key(|line| {
+                    candidates
+                        .iter()
+                        .map(|candidate| candidate.abs_diff(*line))
+                        .min()
+                        .unwrap_or(usize::MAX)
+                });
+            if let Some(line) = nearest {
+                return line;
+            }
+        }
+        if reported <= self.total_lines {
+            return reported;
+        }
+        candidates
+            .into_iter()
+            .skip(1)
+            .find(|line| self.chunks.iter().any(|chunk| chunk.contains(*line)))
+            .unwrap_or(reported)
+    }
+}
+
+/// Split a file of `total_lines` lines into overlapping chunks cut at top-level symbol starts.
+pub(super) fn chunk_file(file: &FileIndex, total_lines: usize) -> Vec<FileChunk> {
+    if total_lines == 0 {
+        return Vec::new();
+    }
+    // Symbols nested in another (methods in an impl or class) are not cut points.
+    let mut spans: Vec<(usize, usize, &str)> = file
+        .symbols
+        .iter()
+        .map(|symbol| (symbol.line, symbol.end_line, symbol.name.as_str()))
+        .collect();
+    spans.sort();
+    let top_level: Vec<(usize, &str)> = spans
+        .iter()
+        .filter(|(line, end, _)| {
+            !spans.iter().any(|(outer_line, outer_end, _)| {
+                (outer_line, outer_end) != (line, end) && outer_line <= line && end <= outer_end
+            })
+        })
+        .map(|(line, _, name)| (*line, *name))
+        .collect();
+
+    let mut chunks = Vec::new();
+    let mut boundary = 1usize;
+    while boundary <= total_lines {
+        let limit = boundary + CHUNK_MAX_LINES - 1;
+        let end = if limit >= total_lines {
+            total_lines
+        } else {
+            // Cut just before the last symbol that starts in the back part of the window.
+            top_level
+                .iter()
+                .map(|(line, _)| *line)
+                .filter(|line| {
+                    *line > boundary + CHUNK_MAX_LINES / MIN_CHUNK_FILL && *line <= limit + 1
+                })
+                .max()
+                .map(|line| line - 1)
+                .unwrap_or(limit)
+        };
+        let start_line = if chunks.is_empty() {
+            boundary
+        } else {
+            boundary.saturating_sub(CHUNK_OVERLAP_LINES).max(1)
+        };
+        let symbols = top_level
+            .iter()
+            .filter(|(line, _)| (boundary..=end).contains(line))
+            .map(|(_, name)| name.to_string())
+            .take(MAX_CHUNK_SYMBOLS)
+            .collect();
+        chunks.push(FileChunk {
+            start_line,
+            end_line: end,
+            symbols,
+        });
+        boundary = end + 1;
+    }
+    chunks
+}
+
+/// A unit of work for a worker: a whole file, or one chunk of a large one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct FocusUnit {
+    pub(super) path: PathBuf,
+    pub(super) chunk: Option<FileChunk>,
+}
+
+impl FocusUnit {
+    /// `src/big.rs` or `src/big.rs (lines 561-1200: f2, f3)`
+    pub(super) fn label(&self) -> String {
+        match &self.chunk {
+            None => self.path.display().to_string(),
+            Some(chunk) if chunk.symbols.is_empty() => format!(
+                "{} (lines {}-{})",
+                self.path.display(),
+                chunk.start_line,
+                chunk.end_line
+            ),
+            Some(chunk) => format!(
+                "{} (lines {}-{}: {})",
+                self.path.display(),
+                chunk.start_line,
+                chunk.end_line,
+                chunk.symbols.join(", ")
+            ),
+        }
+    }
+}
+
+/// One unit per file, except planned large files, which get one unit per chunk.
+pub(super) fn expand_focus_files(paths: &[PathBuf], plans: &[ChunkedFile]) -> Vec<FocusUnit> {
+    paths
+        .iter()
+        .flat_map(|path| match plans.iter().find(|plan| &plan.path == path) {
+            Some(plan) => plan
+                .chunks
+                .iter()
+                .map(|chunk| FocusUnit {
+                    path: path.clone(),
+                    chunk: Some(chunk.clone()),
+                })
+                .collect::<Vec<_>>(),
+            None => vec![FocusUnit {
+                path: path.clone(),
+                chunk: None,
+            }],
+        })
+        .collect()
+}
+
+/// Chunk plans for the indexed files among `paths` that are large enough to need one.
+pub(super) fn plan_large_files(
+    repo_root: &Path,
+    index: &CodebaseIndex,
+    paths: &[PathBuf],
+) -> Vec<ChunkedFile> {
+    paths
+        .iter()
+        .filter_map(|path| {
+            let file = index.files.get(path)?;
+            if file.loc < LARGE_FILE_LINES {
+                return None;
+            }
+            let content = std::fs::read_to_string(repo_root.join(path)).ok()?;
+            let total_lines = content.lines().count();
+            let chunks = chunk_file(file, total_lines);
+            (chunks.len() > 1).then(|| ChunkedFile {
+                path: path.clone(),
+                total_lines,
+                chunks,
+            })
+        })
+        .collect()
+}
+
+/// The largest indexed files, biggest first, for workers that pick their own files.
+pub(super) fn largest_files(index: &CodebaseIndex, limit: usize) -> Vec<PathBuf> {
+    largest_files_where(index, limit, |_| true)
+}
+
+/// `largest_files` among the files `keep` accepts.
+pub(super) fn largest_files_where(
+    index: &CodebaseIndex,
+    limit: usize,
+    keep: impl Fn(&Path) -> bool,
+) -> Vec<PathBuf> {
+    let mut large: Vec<(&PathBuf, usize)> = index
+        .files
+        .iter()
+        .filter(|(path, file)| file.loc >= LARGE_FILE_LINES && keep(path))
+        .map(|(path, file)| (path, file.loc))
+        .collect();
+    large.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+    large
+        .into_iter()
+        .take(limit)
+        .map(|(path, _)| path.clone())
+        .collect()
+}
+
+/// Prompt section telling a worker how to read the planned files.
+pub(super) fn large_files_prompt_section(plans: &[ChunkedFile]) -> String {
+    if plans.is_empty() {
+        return String::new();
+    }
+    let mut section = String::from(
+        "\nLARGE FILES (too big to read at once; page through them chunk by chunk with \
+`read_lines`, and report file line numbers, not chunk-relative ones):\n",
+    );
+    for plan in plans {
+        let chunks: Vec<String> = plan.chunks.iter().map(FileChunk::label).collect();
+        section.push_str(&format!(
+            "- {} ({} lines): {}\n",
+            plan.path.display(),
+            plan.total_lines,
+            chunks.join("; ")
+        ));
+    }
+    section
+}
+
+/// Correct the lines of suggestions in large files (see `ChunkedFile::remap_line`), returning
+/// how many changed.
+pub(super) fn remap_chunked_lines(
+    repo_root: &Path,
+    index: &CodebaseIndex,
+    suggestions: &mut [Suggestion],
+) -> usize {
+    let mut files: HashMap<PathBuf, Option<(ChunkedFile, String)>> = HashMap::new();
+    let mut remapped = 0;
+    for suggestion in suggestions.iter_mut() {
+        let Some(reported) = suggestion.line else {
+            continue;
+        };
+        let entry = files.entry(suggestion.file.clone()).or_insert_with(|| {
+            let plan =
+                plan_large_files(repo_root, index, std::slice::from_ref(&suggestion.file)).pop()?;
+            let content = std::fs::read_to_string(repo_root.join(&plan.path)).ok()?;
+            Some((plan, content))
+        });
+        let Some((plan, content)) = entry else {
+            continue;
+        };
+        let quote = suggestion.evidence.as_deref().unwrap_or_default();
+        let line = plan.remap_line(reported, quote, content);
+        if line == reported {
+            continue;
+        }
+        suggestion.line = Some(line);
+        for evidence_ref in suggestion
+            .evidence_refs
+            .iter_mut()
+            .filter(|evidence_ref| evidence_ref.file == suggestion.file)
+            .filter(|evidence_ref| evidence_ref.line == reported)
+        {
+            evidence_ref.line = line;
+        }
+        remapped += 1;
+    }
+    remapped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use cosmos_core::index::{FileSummary, Language, Symbol, SymbolKind, Visibility};
+
+    fn function(name: &str, line: usize, end_line: usize) -> Symbol {
+        Symbol {
+            name: name.to_string(),
+            kind: SymbolKind::Function,
+            file: PathBuf::from("src/big.rs"),
+            line,
+            end_line,
+            complexity: 1.0,
+            visibility: Visibility::Private,
+        }
+    }
+
+    fn big_file(symbols: Vec<Symbol>) -> FileIndex {
+        FileIndex {
+            path: PathBuf::from("src/big.rs"),
+            language: Language::Rust,
+            loc: 2_400,
+            content_hash: String::new(),
+            symbols,
+            dependencies: Vec::new(),
+            patterns: Vec::new(),
+            complexity: 1.0,
+            last_modified: Utc::now(),
+            summary: FileSummary::default(),
+            layer: None,
+            feature: None,
+        }
+    }
+
+    #[test]
+    fn chunks_cut_at_top_level_symbols_with_overlap() {
+        let mut symbols: Vec<Symbol> = (0..8)
+            .map(|i| function(&format!("f{}", i), i * 300 + 1, i * 300 + 290))
+            .collect();
+        // A method inside f1 is not a cut point.
+        symbols.push(function("inner", 700, 720));
+        let chunks = chunk_file(&big_file(symbols), 2_400);
+
+        assert_eq!(
+            chunks
+                .iter()
+                .map(|c| (c.start_line, c.end_line))
+                .collect::<Vec<_>>(),
+            vec![(1, 600), (561, 1_200), (1_161, 1_800), (1_761, 2_400)]
+        );
+        assert_eq!(chunks[0].symbols, vec!["f0".to_string(), "f1".to_string()]);
+        assert_eq!(chunks[1].label(), "561-1200 (f2, f3)");
+
+        // No symbols at all: fixed-size windows.
+        let plain = chunk_file(&big_file(Vec::new()), 2_000);
+        assert_eq!(plain.len(), 3);
+        assert_eq!((plain[2].start_line, plain[2].end_line), (1_561, 2_000));
+    }
+
+    #[test]
+    fn large_files_expand_into_one_focus_unit_per_chunk() {
+        let plan = ChunkedFile {
+            path: PathBuf::from("src/big.rs"),
+            total_lines: 2_400,
+            chunks: chunk_file(&big_file(vec![function("parse", 1, 2_400)]), 2_400),
+        };
+        let units = expand_focus_files(
+            &[PathBuf::from("src/big.rs"), PathBuf::from("src/small.rs")],
+            &[plan],
+        );
+        assert_eq!(units.len(), 4);
+        assert_eq!(units[0].label(), "src/big.rs (lines 1-800: parse)");
+        assert_eq!(units[1].label(), "src/big.rs (lines 761-1600)");
+        assert_eq!(units[3].label(), "src/small.rs");
+    }
+
+    #[test]
+    fn reported_lines_map_back_onto_the_file() {
+        let content: String = (1..=1_200)
+            .map(|i| {
+                if i == 650 {
+                    "    let total = items.len() - 1;\n".to_string()
+                } else {
+                    format!("    step_{}();\n", i)
+                }
+            })
+            .collect();
+        let plan = ChunkedFile {
+            path: PathBuf::from("src/big.rs"),
+            total_lines: 1_200,
+            chunks: vec![
+                FileChunk {
+                    start_line: 1,
+                    end_line: 600,
+                    symbols: Vec::new(),
+                },
+                FileChunk {
+                    start_line: 561,
+                    end_line: 1_200,
+                    symbols: Vec::new(),
+                },
+            ],
+        };
+        let quote = "let total = items.len() - 1;";
+        // Chunk-relative (650 - 561 + 1 = 90) and slightly-off absolute lines both resolve.
+        assert_eq!(plan.remap_line(90, quote, &content), 650);
+        assert_eq!(plan.remap_line(648, quote, &content), 650);
+        // Without a matching quote, a valid line is kept as reported.
+        assert_eq!(plan.remap_line(90, "missing()", &content), 90);
+        // A line past the end is read as chunk-relative.
+        assert_eq!(plan.remap_line(1_500, "missing()", &content), 1_500);
+        assert_eq!(plan.remap_line(1_250, "", &content), 1_250);
+    }
+}
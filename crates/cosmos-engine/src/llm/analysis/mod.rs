@@ -2,16 +2,36 @@ use super::agentic::{
     call_llm_agentic, call_llm_agentic_report_back_only, schema_to_response_format,
     AgenticStreamEvent, AgenticStreamSink, AgenticTrace,
 };
-use super::client::{call_llm_with_usage, truncate_str};
+use super::chat::ChatSession;
+use super::client::{call_llm_with_usage, parse_response, truncate_str};
+use super::dry_run::{estimate_tokens, PlannedLlmCall, COMPLETION_TOKENS_PER_REQUEST_ESTIMATE};
 use super::models::merge_usage;
 use super::models::{Model, Usage};
+use super::preflight::preflight_provider;
+use super::prompt_templates::{active_overrides, render as render_prompt, PromptTemplate};
 use super::prompt_utils::format_repo_memory_section;
 use super::prompts::ask_question_system;
+use super::redaction;
+use super::response_cache::{self, ResponseCacheStats};
+use super::retrieval::{retrieve, EmbeddingConfig, EvidenceBudget, Retrieval};
+use super::routing;
+use super::spend::{self, ModelDowngrade};
+use super::structured::ParseStrategy;
+use super::tokens::{self, count_tokens, ContextBudget};
+use super::tools::get_relace_search_tool_definitions_cerebras;
+use cosmos_adapters::cache::{Cache, SuggestionFeedbackRecord, SuggestionRunAuditRecord};
+use cosmos_adapters::config::ModelRole;
+use cosmos_adapters::git_ops;
+use cosmos_adapters::util::resolve_repo_path_allow_new;
 use cosmos_core::context::WorkContext;
+use cosmos_core::health::{self, HealthScore};
+use cosmos_core::index::filter::IndexFilter;
 use cosmos_core::index::{CodebaseIndex, SymbolKind};
+use cosmos_core::protocol::schemas::ResponseKind;
 use cosmos_core::suggest::{
-    Criticality, Suggestion, SuggestionCategory, SuggestionEvidenceRef, SuggestionKind,
-    SuggestionValidationMetadata, SuggestionValidationState, VerificationState,
+    baseline::Baseline, history::FindingHistory, suppress, Criticality, Suggestion,
+    SuggestionCategory, SuggestionEvidenceRef, SuggestionKind, SuggestionValidationMetadata,
+    SuggestionValidationState, VerificationState,
 };
 use futures::future::join_all;
 use std::collections::{HashMap, HashSet};
@@ -20,10 +40,16 @@ use std::process::Command;
 use std::sync::Arc;
 use uuid::Uuid;
 
+mod chunking;
 mod context_limits;
+mod policy;
+pub mod sharding;
 mod summary_normalization;
 
+use chunking::FocusUnit;
 use context_limits::AdaptiveLimits;
+pub use policy::{SuggestionPolicy, POLICY_PATH};
+pub use sharding::ShardDiagnostics;
 use summary_normalization::{
     normalize_ethos_summary, normalize_grounded_detail, normalize_grounded_summary,
 };
@@ -49,15 +75,28 @@ const SUMMARY_MIN_CHARS: usize = 24;
 const DEFAULT_MIN_IMPLEMENTATION_READINESS_SCORE: f32 = 0.30;
 const DEFAULT_MAX_SMART_REWRITES_PER_RUN: usize = 8;
 const ASK_ETHOS_MAX_CHARS: usize = 2_500;
+const ASK_SELECTED_FILE_MAX_CHARS: usize = 12_000;
+const ASK_SELECTED_TOTAL_MAX_CHARS: usize = 48_000;
 const REVIEW_AGENT_ETHOS_MAX_CHARS: usize = 800;
 const REVIEW_AGENT_MEMORY_MAX_CHARS: usize = 600;
 const REVIEW_AGENT_RETRY_FEEDBACK_MAX_CHARS: usize = 500;
+const REVIEW_AGENT_USER_FEEDBACK_MAX_CHARS: usize = 900;
+const REVIEW_AGENT_POLICY_MAX_CHARS: usize = 1_200;
+const REVIEW_AGENT_USER_FEEDBACK_MAX_EXAMPLES: usize = 5;
+const REVIEW_AGENT_CHUNKED_FILES_MAX: usize = 4;
 const DEFAULT_REVIEW_AGENT_TIMEOUT_MS: u64 = 120_000;
 const DEFAULT_REVIEW_AGENT_MAX_ITERATIONS: usize = 8;
 const MAX_SUGGESTION_ATTEMPTS_HARD_CAP: usize = 3;
 const DETERMINISTIC_SUGGESTION_SOFT_TARGET_MIN: usize = 4;
 const DETERMINISTIC_SUGGESTION_SOFT_TARGET_MAX: usize = 6;
 const DETERMINISTIC_SUGGESTION_PER_FILE_MAX: usize = 2;
+const SUGGESTION_RETRIEVAL_LIMIT: usize = 8;
+const SUGGESTION_RETRIEVAL_HEADING: &str =
+    "SEMANTIC SEARCH LEADS (related code to start from; verify with tools before reporting)";
+const ASK_RETRIEVAL_LIMIT: usize = 6;
+/// Earlier suggestion runs a new run's findings are checked against for re-detections
+const REDETECTION_HISTORY_RUNS: usize = 50;
+const ASK_MODULE_SUMMARY_LIMIT: usize = 8;
 
 const RELACE_BUG_HUNTER_SYSTEM: &str = r#"You are bug_hunter.
 
@@ -234,7 +273,7 @@ fn rank_top_churn_files_for_subagents(
         .collect::<Vec<_>>()
 }
 
-fn shard_subagent_focus_files(files: &[PathBuf], subagent_count: usize) -> Vec<Vec<PathBuf>> {
+fn shard_subagent_focus_files<T: Clone>(files: &[T], subagent_count: usize) -> Vec<Vec<T>> {
     if subagent_count == 0 {
         return Vec::new();
     }
@@ -258,7 +297,7 @@ fn build_subagent_user_prompt(
     subagent_index: usize,
     subagent_count: usize,
     target_for_subagent: usize,
-    focus_files: &[PathBuf],
+    focus_files: &[FocusUnit],
     project_ethos: Option<&str>,
     retry_feedback: Option<&str>,
 ) -> String {
@@ -274,9 +313,15 @@ Each finding must be bug/security only and include exact `evidence_quote` text f
 
     if !focus_files.is_empty() {
         prompt.push_str("\n\nASSIGNED FILES (focus here first):");
-        for path in focus_files {
+        for unit in focus_files {
             prompt.push_str("\n- ");
-            prompt.push_str(&path.display().to_string());
+            prompt.push_str(&unit.label());
+        }
+        if focus_files.iter().any(|unit| unit.chunk.is_some()) {
+            prompt.push_str(
+                "\nFor files with a line range, read only that range with `read_lines`, and \
+report file line numbers.",
+            );
         }
     }
 
@@ -308,14 +353,66 @@ Each finding must be bug/security only and include exact `evidence_quote` text f
     prompt
 }
 
+/// What an Ask Cosmos prompt was grounded on, reported alongside the answer.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AskContextReport {
+    /// Files included in the prompt (contents when picked, paths otherwise)
+    pub files: Vec<PathBuf>,
+    /// Picked files left out because they were unreadable or over budget
+    pub skipped: Vec<PathBuf>,
+    /// Bytes of file content sent
+    pub content_bytes: usize,
+    /// Total prompt size in bytes (system + user message)
+    pub prompt_bytes: usize,
+    /// Estimated prompt tokens (system + user message)
+    pub prompt_tokens: u32,
+    /// Context window of the model that answered
+    pub context_window: u32,
+    /// True when the user picked the grounding files
+    pub user_selected: bool,
+    /// Code chunks found by semantic retrieval and sent with the question
+    pub retrieval_hits: usize,
+}
+
 /// Ask cosmos a general question about the codebase
 /// Uses the Smart model for thoughtful, well-reasoned responses in plain English
+///
+/// When `selected_paths` is non-empty (files or directories, repo-relative), their
+/// contents ground the answer instead of the automatic file ranking. Prior turns in
+/// `conversation` are replayed into the prompt, and a follow-up with no picked files
+/// reuses the files the conversation was grounded on.
 pub async fn ask_question(
     index: &CodebaseIndex,
     context: &WorkContext,
     question: &str,
     repo_memory: Option<String>,
-) -> anyhow::Result<(String, Option<Usage>)> {
+    selected_paths: &[PathBuf],
+    conversation: &ChatSession,
+) -> anyhow::Result<(String, Option<Usage>, AskContextReport)> {
+    routing::with_role(
+        ModelRole::Ask,
+        ask_question_impl(
+            index,
+            context,
+            question,
+            repo_memory,
+            selected_paths,
+            conversation,
+        ),
+    )
+    .await
+}
+
+async fn ask_question_impl(
+    index: &CodebaseIndex,
+    context: &WorkContext,
+    question: &str,
+    repo_memory: Option<String>,
+    selected_paths: &[PathBuf],
+    conversation: &ChatSession,
+) -> anyhow::Result<(String, Option<Usage>, AskContextReport)> {
+    preflight_provider(&context.repo_root).await?;
+
     // Build context about the codebase
     let stats = index.stats();
     let limits =
@@ -330,14 +427,71 @@ pub async fn ask_question(
         .map(tokenize_question_terms)
         .unwrap_or_default();
 
-    let file_list = rank_files_for_question(
-        index,
-        &query_terms,
-        &focus_terms,
-        &changed_paths,
-        &changed_roots,
-        limits.file_list_limit,
+    let carried_paths = if selected_paths.is_empty() {
+        conversation.grounded_files()
+    } else {
+        Vec::new()
+    };
+    let selected_paths = if carried_paths.is_empty() {
+        selected_paths
+    } else {
+        &carried_paths
+    };
+    let selected = collect_selected_files(
+        &context.repo_root,
+        &expand_selected_paths(index, selected_paths),
     );
+    let user_selected = !selected.files.is_empty();
+    let context_window = routing::routed_context_window(Model::Smart.context_window());
+    let evidence_budget = EvidenceBudget::for_context_window(context_window, ASK_RETRIEVAL_LIMIT);
+    // Without picked files, semantic search supplies the most related code.
+    let retrieval = if user_selected {
+        Retrieval::default()
+    } else {
+        retrieve(
+            &context.repo_root,
+            index,
+            question,
+            evidence_budget.snippets,
+        )
+        .await
+        .unwrap_or_default()
+    };
+    let file_list = if user_selected {
+        selected
+            .files
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect()
+    } else {
+        // Files from earlier turns stay on the list so follow-ups can refer back to them,
+        // followed by files with semantic hits.
+        let mut file_list: Vec<String> = Vec::new();
+        for path in conversation
+            .recent_files()
+            .into_iter()
+            .chain(retrieval.files())
+        {
+            let path = path.display().to_string();
+            if index.files.contains_key(Path::new(&path)) && !file_list.contains(&path) {
+                file_list.push(path);
+            }
+        }
+        for path in rank_files_for_question(
+            index,
+            &query_terms,
+            &focus_terms,
+            &changed_paths,
+            &changed_roots,
+            limits.file_list_limit,
+        ) {
+            if !file_list.contains(&path) {
+                file_list.push(path);
+            }
+        }
+        file_list.truncate(limits.file_list_limit);
+        file_list
+    };
 
     // Get symbols for context (used internally, not exposed to user).
     let symbols = rank_symbols_for_question(
@@ -349,6 +503,12 @@ pub async fn ask_question(
         limits.symbol_limit,
     );
 
+    let grounding_files: Vec<PathBuf> = if user_selected {
+        selected.files.clone()
+    } else {
+        file_list.iter().map(PathBuf::from).collect()
+    };
+    let modules_section = module_summaries_section(&context.repo_root, index, &grounding_files);
     let memory_section = format_repo_memory_section(repo_memory.as_deref(), "PROJECT NOTES");
     let project_ethos = load_project_ethos(&context.repo_root);
     let system = ask_question_system(project_ethos.as_deref());
@@ -360,10 +520,10 @@ pub async fn ask_question(
 - symbols: {}
 - branch: {}
 - likely areas: {}
-
-REFERENCE MAP (internal names):
 {}
+REFERENCE MAP (internal names):
 {}
+{}{}{}{}
 
 QUESTION:
 {}"#,
@@ -372,13 +532,138 @@ QUESTION:
         stats.symbol_count,
         context.branch,
         file_list.join(", "),
+        modules_section,
         symbols.join("\n"),
         memory_section,
+        selected.section,
+        retrieval.to_prompt_section("RELEVANT CODE (semantic search)", &evidence_budget),
+        conversation.to_prompt_section(),
         question
     );
 
+    let report = AskContextReport {
+        files: grounding_files,
+        skipped: selected.skipped,
+        content_bytes: selected.content_bytes,
+        prompt_bytes: system.len() + user.len(),
+        prompt_tokens: count_tokens(&system) + count_tokens(&user),
+        context_window,
+        user_selected,
+        retrieval_hits: retrieval.stats.hits,
+    };
+
     let response = call_llm_with_usage(&system, &user, Model::Smart, false).await?;
-    Ok((response.content, response.usage))
+    Ok((response.content, response.usage, report))
+}
+
+/// Cached rollups of the directories holding `files` (each file's own directory before its
+/// parents), so the model knows what each area does without reading it.
+fn module_summaries_section(repo_root: &Path, index: &CodebaseIndex, files: &[PathBuf]) -> String {
+    let mut dirs: Vec<&Path> = Vec::new();
+    for file in files {
+        let mut parent = file.parent();
+        while let Some(dir) = parent.filter(|dir| !dir.as_os_str().is_empty()) {
+            if !dirs.contains(&dir) {
+                dirs.push(dir);
+            }
+            parent = dir.parent();
+        }
+    }
+    if dirs.is_empty() {
+        return String::new();
+    }
+    let summaries = Cache::new(repo_root).directory_summaries(index);
+    let lines: Vec<String> = dirs
+        .into_iter()
+        .filter_map(|dir| summaries.get(dir))
+        .take(ASK_MODULE_SUMMARY_LIMIT)
+        .map(|summary| format!("- {}", summary.to_prompt_line()))
+        .collect();
+    if lines.is_empty() {
+        return String::new();
+    }
+    format!("\nMODULES:\n{}\n", lines.join("\n"))
+}
+
+/// Estimated tokens the picked files and directories add to an Ask prompt, after the same
+/// per-file and total budgets `ask_question` applies.
+pub fn estimate_ask_context_tokens(
+    repo_root: &Path,
+    index: &CodebaseIndex,
+    selected_paths: &[PathBuf],
+) -> u32 {
+    if selected_paths.is_empty() {
+        return 0;
+    }
+    let selected = collect_selected_files(repo_root, &expand_selected_paths(index, selected_paths));
+    count_tokens(&selected.section)
+}
+
+/// Expand picked files/directories into indexed files, keeping pick order.
+fn expand_selected_paths(index: &CodebaseIndex, selected_paths: &[PathBuf]) -> Vec<PathBuf> {
+    let mut seen = HashSet::new();
+    let mut expanded = Vec::new();
+    for selected in selected_paths {
+        if index.files.contains_key(selected) {
+            if seen.insert(selected.clone()) {
+                expanded.push(selected.clone());
+            }
+            continue;
+        }
+        let mut under_dir: Vec<&PathBuf> = index
+            .files
+            .keys()
+            .filter(|path| path.starts_with(selected))
+            .collect();
+        under_dir.sort();
+        for path in under_dir {
+            if seen.insert(path.clone()) {
+                expanded.push(path.clone());
+            }
+        }
+    }
+    expanded
+}
+
+#[derive(Debug, Default)]
+struct SelectedFileContext {
+    section: String,
+    files: Vec<PathBuf>,
+    skipped: Vec<PathBuf>,
+    content_bytes: usize,
+}
+
+/// Read picked files into a prompt section, within a per-file and total budget.
+fn collect_selected_files(repo_root: &Path, paths: &[PathBuf]) -> SelectedFileContext {
+    let mut out = SelectedFileContext::default();
+    let mut remaining = ASK_SELECTED_TOTAL_MAX_CHARS;
+    for path in paths {
+        let content = resolve_repo_path_allow_new(repo_root, path)
+            .ok()
+            .and_then(|resolved| std::fs::read_to_string(resolved.absolute).ok());
+        let Some(content) = content else {
+            out.skipped.push(path.clone());
+            continue;
+        };
+        if remaining == 0 {
+            out.skipped.push(path.clone());
+            continue;
+        }
+        let snippet = truncate_str(&content, ASK_SELECTED_FILE_MAX_CHARS.min(remaining));
+        remaining = remaining.saturating_sub(snippet.chars().count());
+        out.content_bytes += snippet.len();
+        if out.section.is_empty() {
+            out.section
+                .push_str("\n\nSELECTED FILES (chosen by the user; ground the answer in these):");
+        }
+        out.section
+            .push_str(&format!("\n\n--- {} ---\n{}", path.display(), snippet));
+        if snippet.len() < content.len() {
+            out.section.push_str("\n[... truncated ...]");
+        }
+        out.files.push(path.clone());
+    }
+    out
 }
 
 fn load_project_ethos(repo_root: &Path) -> Option<String> {
@@ -682,10 +967,16 @@ pub struct SuggestionDiagnostics {
     pub sent_snippet_count: usize,
     /// Rough outbound evidence size in bytes after redaction.
     pub sent_bytes: usize,
+    /// Evidence budget for the active model's context window, in bytes.
+    pub sent_budget_bytes: usize,
+    /// `sent_bytes` over `sent_budget_bytes`.
+    pub sent_utilization: f64,
     pub pack_pattern_count: usize,
     pub pack_hotspot_count: usize,
     pub pack_core_count: usize,
     pub pack_line1_ratio: f64,
+    /// Code chunks picked by semantic retrieval and sent with the prompt.
+    pub retrieval_hit_count: usize,
     pub provisional_count: usize,
     pub generation_waves: usize,
     pub generation_topup_calls: usize,
@@ -726,9 +1017,83 @@ pub struct SuggestionDiagnostics {
     pub readiness_score_mean: f64,
     pub regeneration_attempts: usize,
     pub refinement_complete: bool,
+    /// Prompt templates replaced by `.cosmos/prompts/` overrides during this run.
+    pub prompt_overrides: Vec<String>,
+    /// Secrets masked in prompts, tool results, and embedding inputs before they were sent.
+    pub redacted_secret_count: usize,
+    /// Findings dropped by `cosmos-ignore:` comments in the source.
+    pub suppressed_count: usize,
+    /// Findings hidden because `.cosmos/baseline.json` already lists them.
+    pub baselined_count: usize,
+    /// Findings an earlier run already reported, marked with a `Redetection`.
+    pub redetected_count: usize,
+    /// Estimated context use of every model request the run sent, in order.
+    pub context_budgets: Vec<ContextBudget>,
+    /// Switches to a cheaper model made because the run neared its spend cap.
+    pub model_downgrades: Vec<ModelDowngrade>,
+    /// How each worker did when the run was split by module across workers (see `sharding`).
+    pub shards: Vec<ShardDiagnostics>,
+    /// Requests answered from, or missed in, the response cache.
+    pub response_cache: ResponseCacheStats,
     pub notes: Vec<String>,
 }
 
+/// Append a finished run, with every suggestion it produced and the commit it ran against,
+/// to the repo's suggestion-run log (read back by `cosmos compare`, `cosmos stats`, and dry
+/// runs). Runs that passed the quality gate also get a health score, which is returned.
+pub fn record_suggestion_run(
+    repo_root: &Path,
+    index: &CodebaseIndex,
+    suggestions: &[Suggestion],
+    model: &str,
+    diagnostics: &SuggestionDiagnostics,
+) -> Option<HealthScore> {
+    // A withheld run's findings are incomplete, so scoring it would overstate health.
+    let health = diagnostics
+        .gate_passed
+        .then(|| HealthScore::compute(index, suggestions, health::load_line_coverage(repo_root)));
+    let validated_count = suggestions
+        .iter()
+        .filter(|s| s.validation_state == SuggestionValidationState::Validated)
+        .count();
+    let run_audit = SuggestionRunAuditRecord {
+        timestamp: chrono::Utc::now(),
+        run_id: diagnostics.run_id.clone(),
+        suggestion_count: suggestions.len(),
+        validated_count,
+        rejected_count: diagnostics.rejected_count,
+        model: Some(model.to_string()),
+        parse_strategy: Some(diagnostics.parse_strategy.clone()),
+        attempt_index: Some(diagnostics.attempt_index),
+        attempt_count: Some(diagnostics.attempt_count),
+        gate_passed: Some(diagnostics.gate_passed),
+        gate_fail_reasons: diagnostics.gate_fail_reasons.clone(),
+        llm_ms: Some(diagnostics.llm_ms),
+        tool_calls: Some(diagnostics.tool_calls),
+        notes: diagnostics.notes.clone(),
+        response_preview: if diagnostics.response_preview.trim().is_empty() {
+            None
+        } else {
+            Some(diagnostics.response_preview.clone())
+        },
+        prompt_overrides: diagnostics.prompt_overrides.clone(),
+        retrieval_hit_count: Some(diagnostics.retrieval_hit_count),
+        redacted_secret_count: Some(diagnostics.redacted_secret_count),
+        suppressed_count: Some(diagnostics.suppressed_count),
+        baselined_count: Some(diagnostics.baselined_count),
+        max_prompt_tokens: diagnostics
+            .context_budgets
+            .iter()
+            .map(|budget| budget.prompt_tokens)
+            .max(),
+        git_head: git_ops::head_commit(repo_root),
+        health,
+        suggestions: suggestions.to_vec(),
+    };
+    let _ = Cache::new(repo_root).append_suggestion_run_audit(&run_audit);
+    health
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum SuggestionReviewFocus {
     #[default]
@@ -751,6 +1116,14 @@ impl SuggestionReviewFocus {
         }
     }
 
+    /// Category of the findings this focus reports.
+    pub fn category(self) -> SuggestionCategory {
+        match self {
+            Self::BugHunt => SuggestionCategory::Bug,
+            Self::SecurityReview => SuggestionCategory::Security,
+        }
+    }
+
     pub fn toggle(self) -> Self {
         match self {
             Self::BugHunt => Self::SecurityReview,
@@ -770,6 +1143,9 @@ pub struct SuggestionQualityGateConfig {
     pub max_suggest_ms: u64,
     pub max_attempts: usize,
     pub review_focus: SuggestionReviewFocus,
+    /// The user's good/bad/irrelevant verdicts on earlier findings (newest last). Rejected
+    /// findings become negative examples in the worker prompt for their category.
+    pub feedback: Vec<SuggestionFeedbackRecord>,
 }
 
 impl Default for SuggestionQualityGateConfig {
@@ -786,6 +1162,7 @@ impl Default for SuggestionQualityGateConfig {
             max_suggest_ms: 0,
             max_attempts: 1,
             review_focus: SuggestionReviewFocus::default(),
+            feedback: Vec::new(),
         }
     }
 }
@@ -918,7 +1295,7 @@ fn is_test_like_path(path: &Path) -> bool {
         || normalized.ends_with(".spec.js")
 }
 
-#[derive(Debug, Clone, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
 struct AgenticSuggestionJson {
     #[serde(default)]
     file: String,
@@ -942,11 +1319,18 @@ struct AgenticSuggestionJson {
     evidence_quote: String,
 }
 
-#[derive(Debug, Clone, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
 struct AgenticSuggestionResponseJson {
     suggestions: Vec<AgenticSuggestionJson>,
 }
 
+/// Parse a fast-path subagent reply (`agentic_churn_subagents`), normalized to the current
+/// suggestions schema.
+pub(crate) fn parse_agentic_suggestions(content: &str) -> anyhow::Result<serde_json::Value> {
+    let parsed: AgenticSuggestionResponseJson = parse_response(ResponseKind::Suggestions, content)?;
+    Ok(serde_json::to_value(parsed)?)
+}
+
 type ReportFindingJson = super::tools::ReportBackFinding;
 type AgentReportEnvelopeJson = super::tools::ReportBackExplanation;
 
@@ -1122,8 +1506,7 @@ fn deterministic_soft_target_count(config: &SuggestionQualityGateConfig) -> usiz
 fn bounded_suggestion_attempt_count(config: &SuggestionQualityGateConfig) -> usize {
     config
         .max_attempts
-        .max(1)
-        .min(MAX_SUGGESTION_ATTEMPTS_HARD_CAP)
+        .clamp(1, MAX_SUGGESTION_ATTEMPTS_HARD_CAP)
 }
 
 fn review_focus_for_attempt(
@@ -1686,57 +2069,6 @@ fn build_claim_summary(observed_behavior: &str, impact_class: Option<&str>) -> S
     format!("{observed_core}, {impact}.")
 }
 
-fn agentic_suggestion_schema() -> serde_json::Value {
-    serde_json::json!({
-        "type": "object",
-        "properties": {
-            "suggestions": {
-                "type": "array",
-                "items": {
-                    "type": "object",
-                    "properties": {
-                        "file": { "type": "string" },
-                        "line": { "type": "integer", "minimum": 1 },
-                        "kind": {
-                            "type": "string",
-                            "enum": ["bugfix", "security", "reliability"]
-                        },
-                        "priority": { "type": "string", "enum": ["high", "medium", "low"] },
-                        "confidence": { "type": "string", "enum": ["high", "medium"] },
-                        "observed_behavior": { "type": "string" },
-                        "impact_class": {
-                            "type": "string",
-                            "enum": [
-                                "correctness",
-                                "reliability",
-                                "security",
-                                "data_integrity"
-                            ]
-                        },
-                        "summary": { "type": "string" },
-                        "detail": { "type": "string" },
-                        "evidence_quote": { "type": "string" }
-                    },
-                    "required": [
-                        "file",
-                        "kind",
-                        "priority",
-                        "confidence",
-                        "observed_behavior",
-                        "impact_class",
-                        "summary",
-                        "detail",
-                        "evidence_quote"
-                    ],
-                    "additionalProperties": false
-                }
-            }
-        },
-        "required": ["suggestions"],
-        "additionalProperties": false
-    })
-}
-
 fn resolve_agentic_file(repo_root: &Path, raw_file: &str) -> Option<PathBuf> {
     let trimmed = raw_file.trim().trim_start_matches("./").replace('\\', "/");
     if trimmed.is_empty() {
@@ -2309,6 +2641,31 @@ pub async fn analyze_codebase_fast_grounded(
     generation_model: Model,
     generation_target: usize,
     retry_feedback: Option<&str>,
+) -> anyhow::Result<(Vec<Suggestion>, Option<Usage>, SuggestionDiagnostics)> {
+    routing::with_role(
+        ModelRole::Suggest,
+        analyze_codebase_fast_grounded_impl(
+            repo_root,
+            index,
+            context,
+            _repo_memory,
+            generation_model,
+            generation_target,
+            retry_feedback,
+        ),
+    )
+    .await
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn analyze_codebase_fast_grounded_impl(
+    repo_root: &Path,
+    index: &CodebaseIndex,
+    context: &WorkContext,
+    _repo_memory: Option<String>,
+    generation_model: Model,
+    generation_target: usize,
+    retry_feedback: Option<&str>,
 ) -> anyhow::Result<(Vec<Suggestion>, Option<Usage>, SuggestionDiagnostics)> {
     ensure_non_summary_model(generation_model, "Suggestion generation")?;
     let run_id = Uuid::new_v4().to_string();
@@ -2318,7 +2675,10 @@ pub async fn analyze_codebase_fast_grounded(
     let focus_file_limit = subagent_count * AGENTIC_SUBAGENT_FILES_PER_AGENT;
     let focus_files =
         rank_top_churn_files_for_subagents(repo_root, index, context, target, focus_file_limit);
-    let focus_shards = shard_subagent_focus_files(&focus_files, subagent_count);
+    // Large focus files are split so several subagents each read one chunk of them.
+    let chunk_plans = chunking::plan_large_files(repo_root, index, &focus_files);
+    let focus_units = chunking::expand_focus_files(&focus_files, &chunk_plans);
+    let focus_shards = shard_subagent_focus_files(&focus_units, subagent_count);
     let project_ethos = load_project_ethos(repo_root);
     let mut subagent_targets = vec![(target / subagent_count).clamp(2, 4); subagent_count];
     let mut distributed = subagent_targets.iter().sum::<usize>();
@@ -2335,9 +2695,12 @@ pub async fn analyze_codebase_fast_grounded(
     }
 
     let call_start = std::time::Instant::now();
-    let response_format =
-        schema_to_response_format("agentic_suggestions", agentic_suggestion_schema());
+    let response_format = schema_to_response_format(
+        &ResponseKind::Suggestions.schema_name(),
+        ResponseKind::Suggestions.schema(),
+    );
 
+    let system = render_prompt(PromptTemplate::Suggestion, AGENTIC_SUGGESTIONS_SYSTEM, &[]);
     let agent_tasks = focus_shards
         .into_iter()
         .enumerate()
@@ -2358,10 +2721,11 @@ pub async fn analyze_codebase_fast_grounded(
                 retry_feedback,
             );
             let response_format = response_format.clone();
+            let system = system.clone();
             async move {
                 let started = std::time::Instant::now();
                 let response = call_llm_agentic(
-                    AGENTIC_SUGGESTIONS_SYSTEM,
+                    &system,
                     &user_prompt,
                     generation_model,
                     &repo_root,
@@ -2399,7 +2763,7 @@ pub async fn analyze_codebase_fast_grounded(
             let scope_preview = shard
                 .iter()
                 .take(2)
-                .map(|path| path.display().to_string())
+                .map(FocusUnit::label)
                 .collect::<Vec<_>>()
                 .join(",");
             tool_names.push(format!(
@@ -2430,7 +2794,10 @@ pub async fn analyze_codebase_fast_grounded(
             truncate_str(&response.content, 80)
         ));
 
-        match serde_json::from_str::<AgenticSuggestionResponseJson>(&response.content) {
+        match parse_response::<AgenticSuggestionResponseJson>(
+            ResponseKind::Suggestions,
+            &response.content,
+        ) {
             Ok(parsed) => {
                 let raw_this = parsed.suggestions.len();
                 raw_count = raw_count.saturating_add(raw_this);
@@ -2450,6 +2817,7 @@ pub async fn analyze_codebase_fast_grounded(
     }
 
     let response_preview = truncate_str(&response_preview_parts.join(" | "), 240).to_string();
+    let chunk_lines_remapped = chunking::remap_chunked_lines(repo_root, index, &mut suggestions);
 
     let mut run_notes: Vec<String> = Vec::new();
     let evidence_pack_ms = 0u64;
@@ -2479,6 +2847,10 @@ pub async fn analyze_codebase_fast_grounded(
         successful_subagents, subagent_count
     ));
     run_notes.push(format!("churn_focus_file_count:{}", focus_files.len()));
+    if !chunk_plans.is_empty() {
+        run_notes.push(format!("chunked_files:{}", chunk_plans.len()));
+        run_notes.push(format!("chunk_lines_remapped:{}", chunk_lines_remapped));
+    }
     if !focus_files.is_empty() {
         let focus_preview = focus_files
             .iter()
@@ -2508,7 +2880,7 @@ pub async fn analyze_codebase_fast_grounded(
         formatting_pass: false,
         response_format: true,
         response_healing: true,
-        parse_strategy: "agentic_churn_subagents".to_string(),
+        parse_strategy: ParseStrategy::AgenticChurnSubagents.as_str().to_string(),
         parse_stripped_markdown: false,
         parse_used_sanitized_fix: false,
         parse_used_json_fix: false,
@@ -2528,10 +2900,13 @@ pub async fn analyze_codebase_fast_grounded(
         evidence_pack_ms,
         sent_snippet_count,
         sent_bytes,
+        sent_budget_bytes: 0,
+        sent_utilization: 0.0,
         pack_pattern_count: 0,
         pack_hotspot_count: 0,
         pack_core_count: 0,
         pack_line1_ratio: 0.0,
+        retrieval_hit_count: 0,
         provisional_count: suggestions.len(),
         generation_waves: subagent_count,
         generation_topup_calls: 0,
@@ -2572,6 +2947,15 @@ pub async fn analyze_codebase_fast_grounded(
         readiness_score_mean: 0.0,
         regeneration_attempts: 0,
         refinement_complete: false,
+        prompt_overrides: active_overrides(),
+        redacted_secret_count: 0,
+        suppressed_count: 0,
+        baselined_count: 0,
+        redetected_count: 0,
+        context_budgets: Vec::new(),
+        model_downgrades: Vec::new(),
+        shards: Vec::new(),
+        response_cache: ResponseCacheStats::default(),
         notes: run_notes,
     };
 
@@ -2659,15 +3043,72 @@ fn build_gate_snapshot(
     }
 }
 
+/// Few-shot section from the user's verdicts on earlier findings in `category`.
+///
+/// Only the latest verdict per suggestion counts. Returns None until the user has rejected
+/// at least one finding in the category.
+fn build_user_feedback_section(
+    feedback: &[SuggestionFeedbackRecord],
+    category: SuggestionCategory,
+) -> Option<String> {
+    let mut seen = HashSet::new();
+    let latest: Vec<&SuggestionFeedbackRecord> = feedback
+        .iter()
+        .rev()
+        .filter(|record| record.category == category)
+        .filter(|record| seen.insert(record.suggestion_id.as_str()))
+        .collect();
+    let rejected: Vec<&SuggestionFeedbackRecord> = latest
+        .iter()
+        .copied()
+        .filter(|record| record.feedback.is_rejection())
+        .collect();
+    if rejected.is_empty() {
+        return None;
+    }
+
+    let good = latest.len() - rejected.len();
+    let mut section = format!(
+        "The user rated {} earlier {} findings in this repo: {} good, {} bad or irrelevant.\n",
+        latest.len(),
+        category.label().to_ascii_lowercase(),
+        good,
+        rejected.len()
+    );
+    if rejected.len() > good {
+        section.push_str(
+            "Most were rejected: only report findings in this category with strong, concrete evidence.\n",
+        );
+    }
+    section.push_str("Do not report findings like these rejected ones:\n");
+    let mut summaries = HashSet::new();
+    for record in rejected
+        .into_iter()
+        .filter(|record| summaries.insert(record.summary.trim().to_ascii_lowercase()))
+        .take(REVIEW_AGENT_USER_FEEDBACK_MAX_EXAMPLES)
+    {
+        section.push_str(&format!(
+            "- [{}] {}: {}\n",
+            record.feedback.label(),
+            record.file.display(),
+            truncate_str(record.summary.trim(), 160)
+        ));
+    }
+    Some(section)
+}
+
 fn build_review_agent_user_prompt(
     role: &str,
     project_ethos: Option<&str>,
     repo_memory: Option<&str>,
+    policy: Option<&str>,
+    user_feedback: Option<&str>,
     retry_feedback: Option<&str>,
 ) -> String {
     let mut prompt = String::from(
         "Repository is mounted at /repo.\n\
-Explore freely with tools (`view_directory`, `grep_search`, `view_file`) and choose the most promising areas yourself.\n\
+Explore freely with tools (`tree`, `search`, `read_lines`) and choose the most promising areas yourself.\n\
+Search first, then read only the line ranges you need; reads and searches are capped per run.\n\
 Do not wait for assigned files; investigate independently and follow evidence across related code.\n",
     );
 
@@ -2695,6 +3136,16 @@ Do not wait for assigned files; investigate independently and follow evidence ac
         prompt.push_str(truncate_str(memory, REVIEW_AGENT_MEMORY_MAX_CHARS));
         prompt.push('\n');
     }
+    if let Some(policy) = policy.map(str::trim).filter(|v| !v.is_empty()) {
+        prompt.push_str("\nREPO SUGGESTION POLICY (must follow):\n");
+        prompt.push_str(truncate_str(policy, REVIEW_AGENT_POLICY_MAX_CHARS));
+        prompt.push('\n');
+    }
+    if let Some(feedback) = user_feedback.map(str::trim).filter(|v| !v.is_empty()) {
+        prompt.push_str("\nUSER FEEDBACK ON EARLIER FINDINGS:\n");
+        prompt.push_str(truncate_str(feedback, REVIEW_AGENT_USER_FEEDBACK_MAX_CHARS));
+        prompt.push('\n');
+    }
     if let Some(feedback) = retry_feedback.map(str::trim).filter(|v| !v.is_empty()) {
         prompt.push_str("\nRETRY FEEDBACK:\n");
         prompt.push_str(truncate_str(
@@ -2766,23 +3217,27 @@ fn summarize_agentic_trace(trace: &AgenticTrace) -> String {
 
     if rationale_preview.is_empty() {
         format!(
-            "steps={} tool_calls={} report_back_iter={} termination_reason={} repeated_tool_errors={} invalid_report_back={}",
+            "steps={} tool_calls={} report_back_iter={} termination_reason={} repeated_tool_errors={} invalid_report_back={} lines_read={} searches={}",
             trace.steps.len(),
             tool_call_count,
             report_back_iteration,
             termination_reason,
             trace.repeated_tool_error_count,
-            trace.invalid_report_back_count
+            trace.invalid_report_back_count,
+            trace.lines_read,
+            trace.searches
         )
     } else {
         format!(
-            "steps={} tool_calls={} report_back_iter={} termination_reason={} repeated_tool_errors={} invalid_report_back={} rationale={}",
+            "steps={} tool_calls={} report_back_iter={} termination_reason={} repeated_tool_errors={} invalid_report_back={} lines_read={} searches={} rationale={}",
             trace.steps.len(),
             tool_call_count,
             report_back_iteration,
             termination_reason,
             trace.repeated_tool_error_count,
             trace.invalid_report_back_count,
+            trace.lines_read,
+            trace.searches,
             rationale_preview
         )
     }
@@ -2837,6 +3292,20 @@ fn review_agent_iteration_budget() -> usize {
         .unwrap_or(DEFAULT_REVIEW_AGENT_MAX_ITERATIONS)
 }
 
+/// What semantic retrieval looks for in each review mode.
+fn retrieval_query_for_focus(review_focus: SuggestionReviewFocus) -> &'static str {
+    match review_focus {
+        SuggestionReviewFocus::BugHunt => {
+            "unwrap expect panic error handling unchecked index bounds overflow empty null \
+race lock concurrent retry timeout parse fallible"
+        }
+        SuggestionReviewFocus::SecurityReview => {
+            "auth token password secret credential permission sql query shell command exec \
+path traversal deserialize untrusted input sanitize escape"
+        }
+    }
+}
+
 fn role_config_for_focus(review_focus: SuggestionReviewFocus) -> (&'static str, &'static str) {
     match review_focus {
         SuggestionReviewFocus::BugHunt => ("bug_hunter", RELACE_BUG_HUNTER_SYSTEM),
@@ -2846,6 +3315,7 @@ fn role_config_for_focus(review_focus: SuggestionReviewFocus) -> (&'static str,
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn analyze_codebase_single_agent_reviewed(
     repo_root: &Path,
     index: &CodebaseIndex,
@@ -2853,6 +3323,56 @@ pub async fn analyze_codebase_single_agent_reviewed(
     repo_memory: Option<String>,
     review_focus: SuggestionReviewFocus,
     attempt_index: usize,
+    policy: &SuggestionPolicy,
+    user_feedback: Option<&str>,
+    retry_feedback: Option<&str>,
+    stream_sink: Option<SuggestionStreamSink>,
+) -> anyhow::Result<(Vec<Suggestion>, Option<Usage>, SuggestionDiagnostics)> {
+    // Boxed for the same reason as the implementation harness: the scoped future is large.
+    let run = Box::pin(analyze_codebase_single_agent_reviewed_impl(
+        repo_root,
+        index,
+        _context,
+        repo_memory,
+        review_focus,
+        attempt_index,
+        policy,
+        user_feedback,
+        retry_feedback,
+        stream_sink,
+    ));
+    let ((((result, redacted), budgets), downgrades), cache_stats) =
+        response_cache::record_stats(spend::record_downgrades(tokens::record_budgets(
+            redaction::count_redactions(routing::with_role(ModelRole::Suggest, run)),
+        )))
+        .await;
+    result.map(|(suggestions, usage, mut diagnostics)| {
+        diagnostics.redacted_secret_count = redacted;
+        diagnostics.context_budgets = budgets;
+        diagnostics.model_downgrades = downgrades;
+        diagnostics.response_cache = cache_stats;
+        (suggestions, usage, diagnostics)
+    })
+}
+
+/// One review worker's prompt, and the shard it covers when the run is sharded
+struct ReviewWorkerJob<'a> {
+    prompt: String,
+    shard: Option<&'a sharding::Shard>,
+    snippet_count: usize,
+    evidence_section: String,
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn analyze_codebase_single_agent_reviewed_impl(
+    repo_root: &Path,
+    index: &CodebaseIndex,
+    _context: &WorkContext,
+    repo_memory: Option<String>,
+    review_focus: SuggestionReviewFocus,
+    attempt_index: usize,
+    policy: &SuggestionPolicy,
+    user_feedback: Option<&str>,
     retry_feedback: Option<&str>,
     stream_sink: Option<SuggestionStreamSink>,
 ) -> anyhow::Result<(Vec<Suggestion>, Option<Usage>, SuggestionDiagnostics)> {
@@ -2863,48 +3383,160 @@ pub async fn analyze_codebase_single_agent_reviewed(
     let review_timeout_ms = review_agent_timeout_ms();
 
     let (review_role, review_system_prompt) = role_config_for_focus(review_focus);
-    let prompt = build_review_agent_user_prompt(
+    let base_prompt = build_review_agent_user_prompt(
         review_role,
         project_ethos.as_deref(),
         repo_memory.as_deref(),
+        policy.prompt_section().as_deref(),
+        user_feedback,
         retry_feedback,
     );
-    let planned_worker_jobs = 1usize;
+    // Very large repos get one worker per shard of top-level modules (see `sharding`).
+    let shards = sharding::plan(index);
+    let planned_worker_jobs = shards.len().max(1);
+    // Semantic search picks starting points for the worker; it still explores freely. How
+    // much of it is sent scales with the routed model's context window.
+    let evidence_budget = EvidenceBudget::for_context_window(
+        routing::routed_context_window(Model::Speed.context_window()),
+        SUGGESTION_RETRIEVAL_LIMIT,
+    );
+    let retrieval_started = std::time::Instant::now();
+    let mut retrieval_notes = Vec::new();
+    let retrieval = match retrieve(
+        repo_root,
+        index,
+        retrieval_query_for_focus(review_focus),
+        evidence_budget.snippets * planned_worker_jobs,
+    )
+    .await
+    {
+        Ok(retrieval) => retrieval,
+        Err(err) => {
+            retrieval_notes.push(format!(
+                "retrieval_error:{}",
+                truncate_str(&err.to_string(), 160)
+            ));
+            Retrieval::default()
+        }
+    };
+    let evidence_pack_ms = retrieval_started.elapsed().as_millis() as u64;
+    // Each worker picks its own files, so it gets a chunk map of the largest ones up front.
+    let mut jobs = Vec::new();
+    let mut chunked_file_count = 0usize;
+    if shards.is_empty() {
+        let section = retrieval.to_prompt_section(SUGGESTION_RETRIEVAL_HEADING, &evidence_budget);
+        let chunk_plans = chunking::plan_large_files(
+            repo_root,
+            index,
+            &chunking::largest_files(index, REVIEW_AGENT_CHUNKED_FILES_MAX),
+        );
+        chunked_file_count += chunk_plans.len();
+        jobs.push(ReviewWorkerJob {
+            prompt: format!(
+                "{}{}{}",
+                base_prompt,
+                section,
+                chunking::large_files_prompt_section(&chunk_plans)
+            ),
+            shard: None,
+            snippet_count: retrieval.snippets.len(),
+            evidence_section: section,
+        });
+    } else {
+        for (shard_index, shard) in shards.iter().enumerate() {
+            let shard_retrieval = Retrieval {
+                snippets: retrieval
+                    .snippets
+                    .iter()
+                    .filter(|snippet| shard.contains(index, &snippet.file))
+                    .take(evidence_budget.snippets)
+                    .cloned()
+                    .collect(),
+                stats: retrieval.stats.clone(),
+            };
+            let section =
+                shard_retrieval.to_prompt_section(SUGGESTION_RETRIEVAL_HEADING, &evidence_budget);
+            let chunk_plans = chunking::plan_large_files(
+                repo_root,
+                index,
+                &chunking::largest_files_where(index, REVIEW_AGENT_CHUNKED_FILES_MAX, |path| {
+                    shard.contains(index, path)
+                }),
+            );
+            chunked_file_count += chunk_plans.len();
+            jobs.push(ReviewWorkerJob {
+                prompt: format!(
+                    "{}{}{}{}",
+                    base_prompt,
+                    shard.prompt_section(shard_index, shards.len()),
+                    section,
+                    chunking::large_files_prompt_section(&chunk_plans)
+                ),
+                shard: Some(shard),
+                snippet_count: shard_retrieval.snippets.len(),
+                evidence_section: section,
+            });
+        }
+    }
+    if !retrieval.stats.model.is_empty() {
+        retrieval_notes.push(format!("retrieval_model:{}", retrieval.stats.model));
+        retrieval_notes.push(format!("retrieval_hits:{}", retrieval.stats.hits));
+        retrieval_notes.push(format!(
+            "retrieval_cached_files:{}",
+            retrieval.stats.cached_files
+        ));
+        retrieval_notes.push(format!(
+            "retrieval_embedded_files:{}",
+            retrieval.stats.embedded_files
+        ));
+    }
 
     let started = std::time::Instant::now();
-    let worker_stream_sink = stream_sink.as_ref().map(|sink| {
-        let sink = Arc::clone(sink);
-        let worker = format!("{}#1", review_role);
-        Arc::new(move |event: AgenticStreamEvent| {
-            sink(worker.clone(), event.kind, event.line);
-        }) as AgenticStreamSink
+    let worker_runs = jobs.iter().enumerate().map(|(job_index, job)| {
+        let worker_label = format!("{}#{}", review_role, job_index + 1);
+        let worker_stream_sink = stream_sink.as_ref().map(|sink| {
+            let sink = Arc::clone(sink);
+            let worker = worker_label.clone();
+            Arc::new(move |event: AgenticStreamEvent| {
+                sink(worker.clone(), event.kind, event.line);
+            }) as AgenticStreamSink
+        });
+        async move {
+            let worker_started = std::time::Instant::now();
+            let result = if let Some(timeout_ms) = review_timeout_ms {
+                tokio::time::timeout(
+                    std::time::Duration::from_millis(timeout_ms),
+                    call_llm_agentic_report_back_only(
+                        review_system_prompt,
+                        &job.prompt,
+                        Model::Speed,
+                        repo_root,
+                        iteration_budget,
+                        worker_stream_sink,
+                    ),
+                )
+                .await
+                .map_err(|_| anyhow::anyhow!("worker timed out after {}ms", timeout_ms))
+                .and_then(|inner| inner)
+            } else {
+                call_llm_agentic_report_back_only(
+                    review_system_prompt,
+                    &job.prompt,
+                    Model::Speed,
+                    repo_root,
+                    iteration_budget,
+                    worker_stream_sink,
+                )
+                .await
+            };
+            (
+                worker_label,
+                worker_started.elapsed().as_millis() as u64,
+                result,
+            )
+        }
     });
-    let worker_result = if let Some(timeout_ms) = review_timeout_ms {
-        tokio::time::timeout(
-            std::time::Duration::from_millis(timeout_ms),
-            call_llm_agentic_report_back_only(
-                review_system_prompt,
-                &prompt,
-                Model::Speed,
-                repo_root,
-                iteration_budget,
-                worker_stream_sink,
-            ),
-        )
-        .await
-        .map_err(|_| anyhow::anyhow!("worker timed out after {}ms", timeout_ms))
-        .and_then(|inner| inner)
-    } else {
-        call_llm_agentic_report_back_only(
-            review_system_prompt,
-            &prompt,
-            Model::Speed,
-            repo_root,
-            iteration_budget,
-            worker_stream_sink,
-        )
-        .await
-    };
+    let worker_results = join_all(worker_runs).await;
     let elapsed_ms = started.elapsed().as_millis() as u64;
 
     let mut usage: Option<Usage> = None;
@@ -2919,91 +3551,110 @@ pub async fn analyze_codebase_single_agent_reviewed(
     let mut worker_failure_tool_error_loop_count = 0usize;
     let mut worker_failure_invalid_report_back_count = 0usize;
     let mut worker_failure_other_count = 0usize;
-    let worker_label = format!("{}#1", review_role);
-    match worker_result {
-        Ok(agent_result) => {
-            usage = merge_usage(usage, agent_result.usage);
-            let trace_summary = summarize_agentic_trace(&agent_result.trace);
-            worker_trace_notes.push(format!("{} trace: {}", worker_label, trace_summary));
-            let tool_call_count: usize = agent_result
-                .trace
-                .steps
-                .iter()
-                .map(|step| step.tool_call_names.len())
-                .sum();
-            let report_back_iteration = agent_result
-                .trace
-                .steps
-                .iter()
-                .find(|step| step.report_back_called)
-                .map(|step| step.iteration)
-                .unwrap_or(0);
-            let termination_reason = agent_result
-                .trace
-                .termination_reason
-                .as_deref()
-                .unwrap_or("unknown");
-            worker_trace_notes.push(format!(
-                "worker_summary:role/batch={}#1 termination_reason={} tool_calls={} report_back_iter={} repeated_tool_errors={} invalid_report_back={}",
-                review_role,
-                termination_reason,
-                tool_call_count,
-                report_back_iteration,
-                agent_result.trace.repeated_tool_error_count,
-                agent_result.trace.invalid_report_back_count
-            ));
-            if let Some(preview) = trace_response_preview(&agent_result.trace) {
-                response_preview_parts.push(format!("{}:{}", worker_label, preview));
-            }
-            match parse_agent_report(&agent_result.report_back) {
-                Ok(parsed) => {
-                    worker_success_count += 1;
-                    let finding_count = parsed.findings.len();
-                    if review_role == "bug_hunter" {
-                        bug_findings_count = bug_findings_count.saturating_add(finding_count);
-                    } else {
-                        security_findings_count =
-                            security_findings_count.saturating_add(finding_count);
-                    }
-                    merged_findings.extend(parsed.findings);
+    let mut shard_diagnostics = Vec::new();
+    for (job, (worker_label, worker_ms, worker_result)) in jobs.iter().zip(worker_results) {
+        let mut shard_outcome = ShardDiagnostics {
+            elapsed_ms: worker_ms,
+            snippet_count: job.snippet_count,
+            ..Default::default()
+        };
+        match worker_result {
+            Ok(agent_result) => {
+                shard_outcome.cost_usd =
+                    agent_result.usage.as_ref().map(|u| u.cost()).unwrap_or(0.0);
+                usage = merge_usage(usage, agent_result.usage);
+                let trace_summary = summarize_agentic_trace(&agent_result.trace);
+                worker_trace_notes.push(format!("{} trace: {}", worker_label, trace_summary));
+                let tool_call_count: usize = agent_result
+                    .trace
+                    .steps
+                    .iter()
+                    .map(|step| step.tool_call_names.len())
+                    .sum();
+                let report_back_iteration = agent_result
+                    .trace
+                    .steps
+                    .iter()
+                    .find(|step| step.report_back_called)
+                    .map(|step| step.iteration)
+                    .unwrap_or(0);
+                let termination_reason = agent_result
+                    .trace
+                    .termination_reason
+                    .as_deref()
+                    .unwrap_or("unknown");
+                worker_trace_notes.push(format!(
+                    "worker_summary:role/batch={} termination_reason={} tool_calls={} report_back_iter={} repeated_tool_errors={} invalid_report_back={}",
+                    worker_label,
+                    termination_reason,
+                    tool_call_count,
+                    report_back_iteration,
+                    agent_result.trace.repeated_tool_error_count,
+                    agent_result.trace.invalid_report_back_count
+                ));
+                if let Some(preview) = trace_response_preview(&agent_result.trace) {
+                    response_preview_parts.push(format!("{}:{}", worker_label, preview));
                 }
-                Err(err) => {
-                    worker_failures.push(format!(
-                        "{} parse_failed: {}",
-                        worker_label,
-                        truncate_str(&err.to_string(), 160)
-                    ));
+                match parse_agent_report(&agent_result.report_back) {
+                    Ok(parsed) => {
+                        worker_success_count += 1;
+                        let finding_count = parsed.findings.len();
+                        if review_role == "bug_hunter" {
+                            bug_findings_count = bug_findings_count.saturating_add(finding_count);
+                        } else {
+                            security_findings_count =
+                                security_findings_count.saturating_add(finding_count);
+                        }
+                        shard_outcome.finding_count = finding_count;
+                        shard_outcome.outcome = "success".to_string();
+                        merged_findings.extend(parsed.findings);
+                    }
+                    Err(err) => {
+                        let error = truncate_str(&err.to_string(), 160).to_string();
+                        shard_outcome.outcome = "parse_failed".to_string();
+                        shard_outcome.error = Some(error.clone());
+                        worker_failures.push(format!("{} parse_failed: {}", worker_label, error));
+                    }
                 }
             }
-        }
-        Err(err) => {
-            let err_text = err.to_string();
-            let failure_kind = classify_worker_failure(&err_text);
-            worker_trace_notes.push(format!(
-                "worker_summary:role/batch={}#1 termination_reason={} tool_calls=0 report_back_iter=0 repeated_tool_errors=0 invalid_report_back=0",
-                review_role,
-                failure_kind
-            ));
-            match classify_worker_failure(&err_text) {
-                "tool_error_loop" => {
-                    worker_failure_tool_error_loop_count =
-                        worker_failure_tool_error_loop_count.saturating_add(1)
-                }
-                "timeout" => {
-                    worker_failure_timeout_count = worker_failure_timeout_count.saturating_add(1)
-                }
-                "invalid_report_back" => {
-                    worker_failure_invalid_report_back_count =
-                        worker_failure_invalid_report_back_count.saturating_add(1)
+            Err(err) => {
+                let err_text = err.to_string();
+                let failure_kind = classify_worker_failure(&err_text);
+                worker_trace_notes.push(format!(
+                    "worker_summary:role/batch={} termination_reason={} tool_calls=0 report_back_iter=0 repeated_tool_errors=0 invalid_report_back=0",
+                    worker_label,
+                    failure_kind
+                ));
+                match failure_kind {
+                    "tool_error_loop" => {
+                        worker_failure_tool_error_loop_count =
+                            worker_failure_tool_error_loop_count.saturating_add(1)
+                    }
+                    "timeout" => {
+                        worker_failure_timeout_count =
+                            worker_failure_timeout_count.saturating_add(1)
+                    }
+                    "invalid_report_back" => {
+                        worker_failure_invalid_report_back_count =
+                            worker_failure_invalid_report_back_count.saturating_add(1)
+                    }
+                    _ => worker_failure_other_count = worker_failure_other_count.saturating_add(1),
                 }
-                _ => worker_failure_other_count = worker_failure_other_count.saturating_add(1),
+                shard_outcome.outcome = failure_kind.to_string();
+                shard_outcome.error = Some(truncate_str(&err_text, 160).to_string());
+                worker_failures.push(format!(
+                    "{} call_failed({}): {}",
+                    worker_label,
+                    failure_kind,
+                    truncate_str(&err_text, 160)
+                ));
             }
-            worker_failures.push(format!(
-                "{} call_failed({}): {}",
-                worker_label,
-                failure_kind,
-                truncate_str(&err_text, 160)
-            ));
+        }
+        if let Some(shard) = job.shard {
+            shard_outcome.modules = shard.module_names();
+            shard_outcome.files = shard.files;
+            shard_outcome.loc = shard.loc;
+            shard_diagnostics.push(shard_outcome);
         }
     }
 
@@ -3015,12 +3666,16 @@ pub async fn analyze_codebase_single_agent_reviewed(
         return Err(anyhow::anyhow!("Suggestion worker failed: {}", reason));
     }
 
-    let suggestions = map_report_findings_to_suggestions(repo_root, index, merged_findings);
+    let mut suggestions = map_report_findings_to_suggestions(repo_root, index, merged_findings);
+    let chunk_lines_remapped = chunking::remap_chunked_lines(repo_root, index, &mut suggestions);
     let response_preview = truncate_str(&response_preview_parts.join(" | "), 240).to_string();
     let response_chars = response_preview_parts
         .iter()
         .map(|part| part.chars().count())
         .sum();
+    let sent_snippet_count: usize = jobs.iter().map(|job| job.snippet_count).sum();
+    let sent_bytes: usize = jobs.iter().map(|job| job.evidence_section.len()).sum();
+    let sent_budget_bytes = evidence_budget.section_max_chars * jobs.len();
 
     let mut notes = vec![
         format!("attempt_index:{}", attempt_index),
@@ -3058,14 +3713,23 @@ pub async fn analyze_codebase_single_agent_reviewed(
         ),
         format!("bug_findings_reported:{}", bug_findings_count),
         format!("security_findings_reported:{}", security_findings_count),
+        format!("chunked_files:{}", chunked_file_count),
+        format!("chunk_lines_remapped:{}", chunk_lines_remapped),
     ];
+    if !shard_diagnostics.is_empty() {
+        notes.push(format!("shards:{}", shard_diagnostics.len()));
+        for (shard_index, shard) in shard_diagnostics.iter().enumerate() {
+            notes.push(format!("shard_{}:{}", shard_index + 1, shard.summary()));
+        }
+    }
+    notes.extend(retrieval_notes);
     notes.extend(worker_trace_notes);
     notes.extend(worker_failures);
 
     let diagnostics = SuggestionDiagnostics {
         run_id,
         model: Model::Speed.id().to_string(),
-        iterations: 1,
+        iterations: jobs.len(),
         tool_calls: 0,
         tool_names: vec![review_role.to_string()],
         tool_exec_ms: elapsed_ms,
@@ -3075,7 +3739,7 @@ pub async fn analyze_codebase_single_agent_reviewed(
         formatting_pass: false,
         response_format: false,
         response_healing: true,
-        parse_strategy: "single_agent_direct_report".to_string(),
+        parse_strategy: ParseStrategy::SingleAgentDirectReport.as_str().to_string(),
         parse_stripped_markdown: false,
         parse_used_sanitized_fix: false,
         parse_used_json_fix: false,
@@ -3092,13 +3756,20 @@ pub async fn analyze_codebase_single_agent_reviewed(
         final_count: suggestions.len(),
         response_chars,
         response_preview,
-        evidence_pack_ms: 0,
-        sent_snippet_count: 0,
-        sent_bytes: 0,
+        evidence_pack_ms,
+        sent_snippet_count,
+        sent_bytes,
+        sent_budget_bytes,
+        sent_utilization: if sent_budget_bytes == 0 {
+            0.0
+        } else {
+            sent_bytes as f64 / sent_budget_bytes as f64
+        },
         pack_pattern_count: 0,
         pack_hotspot_count: 0,
         pack_core_count: 0,
         pack_line1_ratio: 0.0,
+        retrieval_hit_count: retrieval.stats.hits,
         provisional_count: suggestions.len(),
         generation_waves: 1,
         generation_topup_calls: 0,
@@ -3139,6 +3810,15 @@ pub async fn analyze_codebase_single_agent_reviewed(
         readiness_score_mean: 0.0,
         regeneration_attempts: 0,
         refinement_complete: true,
+        prompt_overrides: active_overrides(),
+        redacted_secret_count: 0,
+        suppressed_count: 0,
+        baselined_count: 0,
+        redetected_count: 0,
+        context_budgets: Vec::new(),
+        model_downgrades: Vec::new(),
+        shards: shard_diagnostics,
+        response_cache: ResponseCacheStats::default(),
         notes,
     };
 
@@ -3198,6 +3878,33 @@ where
 }
 
 pub async fn run_fast_grounded_with_gate_with_progress_and_stream<F>(
+    repo_root: &Path,
+    index: &CodebaseIndex,
+    context: &WorkContext,
+    repo_memory: Option<String>,
+    gate_config: SuggestionQualityGateConfig,
+    stream_sink: Option<SuggestionStreamSink>,
+    on_progress: F,
+) -> anyhow::Result<GatedSuggestionRunResult>
+where
+    F: FnMut(usize, usize, &SuggestionGateSnapshot, &SuggestionDiagnostics),
+{
+    routing::with_role(
+        ModelRole::Suggest,
+        run_fast_grounded_with_gate_with_progress_and_stream_impl(
+            repo_root,
+            index,
+            context,
+            repo_memory,
+            gate_config,
+            stream_sink,
+            on_progress,
+        ),
+    )
+    .await
+}
+
+async fn run_fast_grounded_with_gate_with_progress_and_stream_impl<F>(
     repo_root: &Path,
     index: &CodebaseIndex,
     context: &WorkContext,
@@ -3209,6 +3916,19 @@ pub async fn run_fast_grounded_with_gate_with_progress_and_stream<F>(
 where
     F: FnMut(usize, usize, &SuggestionGateSnapshot, &SuggestionDiagnostics),
 {
+    // Fail before the evidence pack if the provider or model is unavailable.
+    preflight_provider(repo_root).await?;
+
+    let policy = SuggestionPolicy::load(repo_root)?;
+    let index_filter = IndexFilter::load(repo_root)?;
+    let baseline = Baseline::active(repo_root)?;
+    let history = FindingHistory::from_runs(
+        Cache::new(repo_root)
+            .load_recent_suggestion_run_audit(REDETECTION_HISTORY_RUNS)
+            .unwrap_or_default()
+            .iter()
+            .map(|run| run.suggestions.as_slice()),
+    );
     let total_start = std::time::Instant::now();
     let attempt_count = bounded_suggestion_attempt_count(&gate_config);
     let deterministic_target_count = deterministic_soft_target_count(&gate_config);
@@ -3218,6 +3938,8 @@ where
 
     for attempt_index in 1..=attempt_count {
         let attempt_focus = review_focus_for_attempt(gate_config.review_focus, attempt_index);
+        let user_feedback =
+            build_user_feedback_section(&gate_config.feedback, attempt_focus.category());
         let elapsed_before_attempt_ms = total_start.elapsed().as_millis() as u64;
         if gate_config.max_suggest_ms > 0 && elapsed_before_attempt_ms >= gate_config.max_suggest_ms
         {
@@ -3248,6 +3970,8 @@ where
                     repo_memory.clone(),
                     attempt_focus,
                     attempt_index,
+                    &policy,
+                    user_feedback.as_deref(),
                     retry_feedback.as_deref(),
                     stream_sink.clone(),
                 ),
@@ -3268,6 +3992,8 @@ where
                 repo_memory.clone(),
                 attempt_focus,
                 attempt_index,
+                &policy,
+                user_feedback.as_deref(),
                 retry_feedback.as_deref(),
                 stream_sink.clone(),
             )
@@ -3295,12 +4021,25 @@ where
         };
 
         aggregate_usage = merge_usage(aggregate_usage, attempt_usage.clone());
+        let (provisional, policy_dropped) = policy.retain_allowed(provisional);
+        let (provisional, suppressed) = suppress::retain_unsuppressed(repo_root, provisional);
+        let (mut provisional, baselined) = match &baseline {
+            Some(baseline) => baseline.retain_new(provisional),
+            None => (provisional, 0),
+        };
+        diagnostics.suppressed_count = suppressed;
+        diagnostics.baselined_count = baselined;
+        // The worker can still open files the indexer skipped; keep their findings out too.
+        let before_index_filter = provisional.len();
+        provisional.retain(|suggestion| !index_filter.excludes(&suggestion.file));
+        let index_excluded_dropped = before_index_filter - provisional.len();
         let selection = deterministic_select_suggestions(
             &provisional,
             deterministic_target_count,
             gate_config.max_final_count,
         );
-        let suggestions = selection.suggestions;
+        let (mut suggestions, policy_capped) = policy.cap_per_file(selection.suggestions);
+        diagnostics.redetected_count = history.mark_redetections(&mut suggestions);
 
         diagnostics.refinement_complete = true;
         diagnostics.final_count = suggestions.len();
@@ -3308,7 +4047,9 @@ where
             .iter()
             .filter(|suggestion| suggestion_is_verified_bug_or_security(suggestion))
             .count();
-        diagnostics.rejected_count = provisional.len().saturating_sub(suggestions.len());
+        diagnostics.rejected_count =
+            (provisional.len() + policy_dropped + suppressed + baselined + index_excluded_dropped)
+                .saturating_sub(suggestions.len());
         diagnostics.semantic_dedup_dropped_count = selection.dedup_dropped_count;
         diagnostics.file_balance_dropped_count = selection.file_balance_dropped_count;
         diagnostics.speculative_impact_dropped_count = selection.speculative_dropped_count;
@@ -3321,6 +4062,44 @@ where
         diagnostics
             .notes
             .push(format!("attempt_review_focus:{}", attempt_focus.as_str()));
+        if !policy.is_empty() {
+            diagnostics
+                .notes
+                .push(format!("policy_dropped:{}", policy_dropped + policy_capped));
+        }
+        if suppressed > 0 {
+            diagnostics.notes.push(format!("suppressed:{}", suppressed));
+        }
+        if baselined > 0 {
+            diagnostics.notes.push(format!("baselined:{}", baselined));
+        }
+        if diagnostics.redetected_count > 0 {
+            diagnostics
+                .notes
+                .push(format!("redetected:{}", diagnostics.redetected_count));
+        }
+        if index.excluded_files > 0 {
+            diagnostics
+                .notes
+                .push(format!("index_excluded_files:{}", index.excluded_files));
+        }
+        if index_excluded_dropped > 0 {
+            diagnostics
+                .notes
+                .push(format!("index_excluded_dropped:{}", index_excluded_dropped));
+        }
+        if diagnostics.redacted_secret_count > 0 {
+            diagnostics.notes.push(format!(
+                "redacted_secrets:{}",
+                diagnostics.redacted_secret_count
+            ));
+        }
+        if user_feedback.is_some() {
+            diagnostics.notes.push(format!(
+                "user_feedback_applied:{}",
+                attempt_focus.category().label().to_ascii_lowercase()
+            ));
+        }
 
         let total_cost_usd = aggregate_usage.as_ref().map(|u| u.cost()).unwrap_or(0.0);
         let total_ms = total_start.elapsed().as_millis() as u64;
@@ -3363,5 +4142,97 @@ where
     ))
 }
 
+/// Plan the model calls `run_fast_grounded_with_gate` would make, without calling the model.
+///
+/// Each gate attempt runs one review worker, or one per shard when `index` is large enough
+/// to be sharded; attempts after the first only happen when the previous one produced
+/// nothing usable, so they are marked conditional.
+pub fn plan_gated_suggestion_run(
+    repo_root: &Path,
+    index: Option<&CodebaseIndex>,
+    repo_memory: Option<&str>,
+    gate_config: &SuggestionQualityGateConfig,
+) -> Vec<PlannedLlmCall> {
+    let project_ethos = load_project_ethos(repo_root);
+    let policy_section = SuggestionPolicy::load(repo_root)
+        .ok()
+        .and_then(|policy| policy.prompt_section());
+    let mut tools = get_relace_search_tool_definitions_cerebras();
+    tools.extend(super::custom_tools::definitions());
+    let tool_chars = serde_json::to_string(&tools)
+        .map(|json| json.len())
+        .unwrap_or(0);
+    let iteration_budget = match review_agent_iteration_budget() {
+        0 => DEFAULT_REVIEW_AGENT_MAX_ITERATIONS,
+        budget => budget,
+    };
+    let retrieval_chars = match EmbeddingConfig::load(repo_root) {
+        Ok(EmbeddingConfig::Off) => 0,
+        _ => {
+            EvidenceBudget::for_context_window(
+                routing::context_window_for_role(ModelRole::Suggest, Model::Speed),
+                SUGGESTION_RETRIEVAL_LIMIT,
+            )
+            .section_max_chars
+        }
+    };
+
+    let shards = index.map(sharding::plan).unwrap_or_default();
+
+    (1..=bounded_suggestion_attempt_count(gate_config))
+        .flat_map(|attempt_index| {
+            let focus = review_focus_for_attempt(gate_config.review_focus, attempt_index);
+            let (role, system) = role_config_for_focus(focus);
+            // Retry feedback is at most a short paragraph; reserve its cap for later attempts.
+            let retry_feedback =
+                (attempt_index > 1).then(|| "x".repeat(REVIEW_AGENT_RETRY_FEEDBACK_MAX_CHARS));
+            let user_feedback =
+                build_user_feedback_section(&gate_config.feedback, focus.category());
+            let user = build_review_agent_user_prompt(
+                role,
+                project_ethos.as_deref(),
+                repo_memory,
+                policy_section.as_deref(),
+                user_feedback.as_deref(),
+                retry_feedback.as_deref(),
+            );
+            let planned = |label: String, user_chars: usize| PlannedLlmCall {
+                stage: "suggest".to_string(),
+                label,
+                model: routing::model_id_for_role(ModelRole::Suggest, Model::Speed),
+                max_requests: iteration_budget,
+                prompt_tokens_per_request: estimate_tokens(
+                    system.len() + user_chars + retrieval_chars + tool_chars,
+                ),
+                completion_tokens_per_request: COMPLETION_TOKENS_PER_REQUEST_ESTIMATE,
+                conditional: attempt_index > 1,
+            };
+            if shards.is_empty() {
+                return vec![planned(
+                    format!("attempt {} {}", attempt_index, role),
+                    user.len(),
+                )];
+            }
+            shards
+                .iter()
+                .enumerate()
+                .map(|(shard_index, shard)| {
+                    planned(
+                        format!(
+                            "attempt {} {} shard {}/{} ({})",
+                            attempt_index,
+                            role,
+                            shard_index + 1,
+                            shards.len(),
+                            shard.label()
+                        ),
+                        user.len() + shard.prompt_section(shard_index, shards.len()).len(),
+                    )
+                })
+                .collect()
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests;
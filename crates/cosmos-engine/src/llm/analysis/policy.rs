@@ -0,0 +1,309 @@
+//! Repo-level suggestion policy
+//!
+//! Maintainers can check in `.cosmos/policy.yml` to constrain suggestions:
+//!
+//! ```yaml
+//! exclude_paths: ["vendor/**", "**/*.generated.ts"]
+//! banned_kinds: [documentation, refactoring]
+//! min_priority: medium
+//! max_per_file: 2
+//! focus_areas: ["payment flows in src/billing"]
+//! ```
+//!
+//! The policy is stated in the review worker's prompt and enforced again on the findings it
+//! returns, so a worker that ignores the prompt still cannot surface excluded findings.
+
+use cosmos_core::suggest::{Priority, Suggestion, SuggestionKind};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Policy file, relative to the repo root.
+pub const POLICY_PATH: &str = ".cosmos/policy.yml";
+
+const KIND_NAMES: [(&str, SuggestionKind); 8] = [
+    ("improvement", SuggestionKind::Improvement),
+    ("bug_fix", SuggestionKind::BugFix),
+    ("feature", SuggestionKind::Feature),
+    ("optimization", SuggestionKind::Optimization),
+    ("quality", SuggestionKind::Quality),
+    ("documentation", SuggestionKind::Documentation),
+    ("testing", SuggestionKind::Testing),
+    ("refactoring", SuggestionKind::Refactoring),
+];
+
+const PRIORITY_NAMES: [(&str, Priority); 3] = [
+    ("low", Priority::Low),
+    ("medium", Priority::Medium),
+    ("high", Priority::High),
+];
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct PolicyFile {
+    exclude_paths: Vec<String>,
+    banned_kinds: Vec<String>,
+    min_priority: Option<String>,
+    max_per_file: Option<usize>,
+    focus_areas: Vec<String>,
+}
+
+/// Validated suggestion policy for one repository.
+#[derive(Debug, Clone, Default)]
+pub struct SuggestionPolicy {
+    exclude_paths: Vec<glob::Pattern>,
+    banned_kinds: Vec<SuggestionKind>,
+    min_priority: Option<Priority>,
+    max_per_file: Option<usize>,
+    focus_areas: Vec<String>,
+}
+
+impl SuggestionPolicy {
+    /// Load and validate `.cosmos/policy.yml`. A missing file means no constraints.
+    pub fn load(repo_root: &Path) -> anyhow::Result<Self> {
+        let content = match std::fs::read_to_string(repo_root.join(POLICY_PATH)) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(anyhow::anyhow!("Failed to read {}: {}", POLICY_PATH, e)),
+        };
+        Self::parse(&content)
+            .map_err(|problems| anyhow::anyhow!("Invalid {}: {}", POLICY_PATH, problems.join("; ")))
+    }
+
+    fn parse(content: &str) -> Result<Self, Vec<String>> {
+        if content.trim().is_empty() {
+            return Ok(Self::default());
+        }
+        let file: PolicyFile = serde_yaml::from_str(content).map_err(|e| vec![e.to_string()])?;
+
+        let mut problems = Vec::new();
+        let mut policy = Self {
+            max_per_file: file.max_per_file,
+            focus_areas: file
+                .focus_areas
+                .into_iter()
+                .map(|area| area.trim().to_string())
+                .filter(|area| !area.is_empty())
+                .collect(),
+            ..Self::default()
+        };
+        for pattern in file.exclude_paths {
+            match glob::Pattern::new(pattern.trim()) {
+                Ok(compiled) => policy.exclude_paths.push(compiled),
+                Err(e) => problems.push(format!("exclude_paths '{}': {}", pattern, e)),
+            }
+        }
+        for name in file.banned_kinds {
+            match lookup(&KIND_NAMES, &name) {
+                Some(kind) => policy.banned_kinds.push(kind),
+                None => problems.push(format!(
+                    "banned_kinds '{}' is not a suggestion kind (expected one of: {})",
+                    name,
+                    names(&KIND_NAMES)
+                )),
+            }
+        }
+        if let Some(name) = file.min_priority {
+            match lookup(&PRIORITY_NAMES, &name) {
+                Some(priority) => policy.min_priority = Some(priority),
+                None => problems.push(format!(
+                    "min_priority '{}' must be one of: {}",
+                    name,
+                    names(&PRIORITY_NAMES)
+                )),
+            }
+        }
+        if policy.max_per_file == Some(0) {
+            problems.push("max_per_file must be at least 1".to_string());
+        }
+
+        if problems.is_empty() {
+            Ok(policy)
+        } else {
+            Err(problems)
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.exclude_paths.is_empty()
+            && self.banned_kinds.is_empty()
+            && self.min_priority.is_none()
+            && self.max_per_file.is_none()
+            && self.focus_areas.is_empty()
+    }
+
+    /// Constraints for the review worker's prompt, or None when the policy is empty.
+    pub fn prompt_section(&self) -> Option<String> {
+        if self.is_empty() {
+            return None;
+        }
+        let mut section = String::new();
+        if !self.focus_areas.is_empty() {
+            section.push_str("Focus areas (look here first):\n");
+            for area in &self.focus_areas {
+                section.push_str(&format!("- {}\n", area));
+            }
+        }
+        if !self.exclude_paths.is_empty() {
+            let patterns: Vec<&str> = self.exclude_paths.iter().map(|p| p.as_str()).collect();
+            section.push_str(&format!(
+                "Never report findings in paths matching: {}\n",
+                patterns.join(", ")
+            ));
+        }
+        if !self.banned_kinds.is_empty() {
+            let kinds: Vec<&str> = self
+                .banned_kinds
+                .iter()
+                .filter_map(|kind| KIND_NAMES.iter().find(|(_, k)| k == kind))
+                .map(|(name, _)| *name)
+                .collect();
+            section.push_str(&format!(
+                "Do not report these kinds of findings: {}\n",
+                kinds.join(", ")
+            ));
+        }
+        if let Some(min) = self.min_priority {
+            if let Some((name, _)) = PRIORITY_NAMES.iter().find(|(_, p)| *p == min) {
+                section.push_str(&format!(
+                    "Only report findings of {} priority or higher.\n",
+                    name
+                ));
+            }
+        }
+        if let Some(max) = self.max_per_file {
+            section.push_str(&format!("Report at most {} finding(s) per file.\n", max));
+        }
+        Some(section)
+    }
+
+    /// Drop findings in excluded paths, of banned kinds, or below the minimum priority.
+    /// Returns the kept findings and how many were dropped.
+    pub fn retain_allowed(&self, suggestions: Vec<Suggestion>) -> (Vec<Suggestion>, usize) {
+        let before = suggestions.len();
+        let kept: Vec<Suggestion> = suggestions.into_iter().filter(|s| self.allows(s)).collect();
+        let dropped = before - kept.len();
+        (kept, dropped)
+    }
+
+    /// Keep at most `max_per_file` findings per primary file, in the given (ranked) order.
+    /// Returns the kept findings and how many were dropped.
+    pub fn cap_per_file(&self, suggestions: Vec<Suggestion>) -> (Vec<Suggestion>, usize) {
+        let Some(max) = self.max_per_file else {
+            return (suggestions, 0);
+        };
+        let before = suggestions.len();
+        let mut per_file: HashMap<std::path::PathBuf, usize> = HashMap::new();
+        let kept: Vec<Suggestion> = suggestions
+            .into_iter()
+            .filter(|s| {
+                let count = per_file.entry(s.file.clone()).or_insert(0);
+                *count += 1;
+                *count <= max
+            })
+            .collect();
+        let dropped = before - kept.len();
+        (kept, dropped)
+    }
+
+    fn allows(&self, suggestion: &Suggestion) -> bool {
+        if self.banned_kinds.contains(&suggestion.kind) {
+            return false;
+        }
+        if self
+            .min_priority
+            .is_some_and(|min| suggestion.priority < min)
+        {
+            return false;
+        }
+        !std::iter::once(&suggestion.file)
+            .chain(suggestion.additional_files.iter())
+            .any(|path| self.excludes(path))
+    }
+
+    fn excludes(&self, path: &Path) -> bool {
+        let path = path.to_string_lossy().replace('\\', "/");
+        let path = path.trim_start_matches("./");
+        self.exclude_paths
+            .iter()
+            .any(|pattern| pattern.matches(path))
+    }
+}
+
+fn lookup<T: Copy>(table: &[(&str, T)], name: &str) -> Option<T> {
+    let name = name.trim().to_ascii_lowercase().replace(['-', ' '], "_");
+    table
+        .iter()
+        .find(|(candidate, _)| *candidate == name)
+        .map(|(_, value)| *value)
+}
+
+fn names<T>(table: &[(&str, T)]) -> String {
+    table
+        .iter()
+        .map(|(name, _)| *name)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmos_core::suggest::SuggestionSource;
+    use std::path::PathBuf;
+
+    fn suggestion(file: &str, kind: SuggestionKind, priority: Priority) -> Suggestion {
+        Suggestion::new(
+            kind,
+            priority,
+            PathBuf::from(file),
+            format!("finding in {}", file),
+            SuggestionSource::LlmDeep,
+        )
+    }
+
+    #[test]
+    fn policy_filters_paths_kinds_priority_and_caps_per_file() {
+        let policy = SuggestionPolicy::parse(
+            "exclude_paths: [\"vendor/**\"]\n\
+             banned_kinds: [documentation]\n\
+             min_priority: medium\n\
+             max_per_file: 1\n\
+             focus_areas: [\"billing\"]\n",
+        )
+        .unwrap();
+
+        let (kept, dropped) = policy.retain_allowed(vec![
+            suggestion("src/a.rs", SuggestionKind::BugFix, Priority::High),
+            suggestion("src/a.rs", SuggestionKind::BugFix, Priority::Medium),
+            suggestion("vendor/lib/x.rs", SuggestionKind::BugFix, Priority::High),
+            suggestion("src/b.rs", SuggestionKind::Documentation, Priority::High),
+            suggestion("src/c.rs", SuggestionKind::BugFix, Priority::Low),
+        ]);
+        assert_eq!(dropped, 3);
+        let (kept, capped) = policy.cap_per_file(kept);
+        assert_eq!(capped, 1);
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].priority, Priority::High);
+
+        let section = policy.prompt_section().unwrap();
+        assert!(section.contains("- billing"));
+        assert!(section.contains("vendor/**"));
+        assert!(section.contains("documentation"));
+        assert!(section.contains("medium priority or higher"));
+        assert!(section.contains("at most 1 finding"));
+    }
+
+    #[test]
+    fn invalid_policy_lists_every_problem() {
+        let problems = SuggestionPolicy::parse(
+            "banned_kinds: [style_nits]\nmin_priority: urgent\nmax_per_file: 0\n",
+        )
+        .unwrap_err();
+        assert_eq!(problems.len(), 3);
+        assert!(problems[0].contains("style_nits"));
+
+        assert!(SuggestionPolicy::parse("max_suggestions: 3\n").is_err());
+        assert!(SuggestionPolicy::parse("").unwrap().is_empty());
+    }
+}
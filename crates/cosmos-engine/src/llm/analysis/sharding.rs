@@ -0,0 +1,278 @@
+//! Sharded suggestion runs for very large repositories.
+//!
+//! One review worker reads only so much before its iteration budget runs out, so on a repo of
+//! a million lines it reviews one corner and stops. Past `suggest_sharding.min_loc` indexed
+//! lines, a run instead splits the index by top-level module (a workspace package, or the
+//! first directory of paths outside packages), packs the modules into at most `max_shards`
+//! shards of similar size, and runs one worker per shard concurrently, each told to report on
+//! its own modules only. The semantic-search evidence is fetched once and split the same way.
+//! The workers' findings are merged before the run's usual dedupe, ranking, and quality gate,
+//! which see them as one run; each shard's outcome is kept in the run's diagnostics.
+
+use cosmos_adapters::config::{Config, SuggestShardingConfig, MAX_SHARDS};
+use cosmos_core::index::CodebaseIndex;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+/// Module names listed in a shard's label before the rest are counted
+const LABEL_MODULES: usize = 3;
+
+static ACTIVE: RwLock<Option<SuggestShardingConfig>> = RwLock::new(None);
+
+/// Validate the config's `suggest_sharding` and make it active for this process.
+pub fn install(config: &Config) -> anyhow::Result<()> {
+    if let Err(problems) = config.validate_suggest_sharding() {
+        return Err(anyhow::anyhow!(
+            "Invalid suggest_sharding in {}:\n  {}",
+            Config::config_location(),
+            problems.join("\n  ")
+        ));
+    }
+    if let Ok(mut active) = ACTIVE.write() {
+        *active = Some(config.suggest_sharding());
+    }
+    Ok(())
+}
+
+fn settings() -> SuggestShardingConfig {
+    ACTIVE
+        .read()
+        .ok()
+        .and_then(|active| active.clone())
+        .unwrap_or_default()
+}
+
+/// The part of the repo one worker reviews
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Shard {
+    /// Module roots, sorted; empty for files at the repository root
+    pub(super) modules: Vec<PathBuf>,
+    pub(super) files: usize,
+    pub(super) loc: usize,
+}
+
+impl Shard {
+    pub(super) fn contains(&self, index: &CodebaseIndex, path: &Path) -> bool {
+        self.modules.contains(&module_of(index, path))
+    }
+
+    /// `crates/core, crates/engine, docs +2 more`
+    pub(super) fn label(&self) -> String {
+        let mut names: Vec<String> = self
+            .modules
+            .iter()
+            .take(LABEL_MODULES)
+            .map(|module| module_name(module))
+            .collect();
+        if self.modules.len() > LABEL_MODULES {
+            names.push(format!("+{} more", self.modules.len() - LABEL_MODULES));
+        }
+        names.join(", ")
+    }
+
+    /// Module names as shown to workers and in diagnostics
+    pub(super) fn module_names(&self) -> Vec<String> {
+        self.modules.iter().map(|m| module_name(m)).collect()
+    }
+
+    /// Prompt section scoping worker `shard_index` (0-based) of `shard_count` to this shard.
+    pub(super) fn prompt_section(&self, shard_index: usize, shard_count: usize) -> String {
+        let modules = self.module_names();
+        format!(
+            "\n\nSHARD {} OF {} (the repository is split across parallel reviewers):\n\
+Report findings only in these modules: {}.\n\
+Read code elsewhere when you need context, but leave findings there to the other reviewers.\n",
+            shard_index + 1,
+            shard_count,
+            modules.join(", ")
+        )
+    }
+}
+
+/// How one shard's worker did
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ShardDiagnostics {
+    pub modules: Vec<String>,
+    pub files: usize,
+    pub loc: usize,
+    /// Semantic-search leads sent with the shard
+    pub snippet_count: usize,
+    /// Findings the worker reported, before dedupe and the gate
+    pub finding_count: usize,
+    pub elapsed_ms: u64,
+    pub cost_usd: f64,
+    /// `success`, or the failure kind (`timeout`, `tool_error_loop`, ...)
+    pub outcome: String,
+    pub error: Option<String>,
+}
+
+impl ShardDiagnostics {
+    /// One line for traces: modules, size, and outcome
+    pub fn summary(&self) -> String {
+        format!(
+            "[{}] files={} loc={} leads={} findings={} ms={} cost=${:.4} outcome={}",
+            self.modules.join(", "),
+            self.files,
+            self.loc,
+            self.snippet_count,
+            self.finding_count,
+            self.elapsed_ms,
+            self.cost_usd,
+            self.outcome
+        )
+    }
+}
+
+/// Shards for a run over `index`, or none when the repo is small enough for one worker.
+pub(super) fn plan(index: &CodebaseIndex) -> Vec<Shard> {
+    plan_with(index, &settings())
+}
+
+fn plan_with(index: &CodebaseIndex, config: &SuggestShardingConfig) -> Vec<Shard> {
+    let max_shards = config.max_shards().min(MAX_SHARDS);
+    let total_loc: usize = index.files.values().map(|file| file.loc).sum();
+    if max_shards <= 1 || total_loc < config.min_loc() {
+        return Vec::new();
+    }
+
+    let mut modules: BTreeMap<PathBuf, (usize, usize)> = BTreeMap::new();
+    for (path, file) in &index.files {
+        let entry = modules.entry(module_of(index, path)).or_default();
+        entry.0 += 1;
+        entry.1 += file.loc;
+    }
+    if modules.len() < 2 {
+        return Vec::new();
+    }
+
+    // Largest module first onto the lightest shard keeps shard sizes close
+    let mut by_size: Vec<(PathBuf, (usize, usize))> = modules.into_iter().collect();
+    by_size.sort_by(|a, b| b.1 .1.cmp(&a.1 .1).then_with(|| a.0.cmp(&b.0)));
+    let mut shards = vec![
+        Shard {
+            modules: Vec::new(),
+            files: 0,
+            loc: 0,
+        };
+        max_shards.min(by_size.len())
+    ];
+    for (module, (files, loc)) in by_size {
+        if let Some(lightest) = shards.iter_mut().min_by_key(|shard| shard.loc) {
+            lightest.modules.push(module);
+            lightest.files += files;
+            lightest.loc += loc;
+        }
+    }
+    for shard in &mut shards {
+        shard.modules.sort();
+    }
+    shards.sort_by(|a, b| a.modules.cmp(&b.modules));
+    shards
+}
+
+/// The workspace package holding `path`, or else its first directory (empty at the root).
+fn module_of(index: &CodebaseIndex, path: &Path) -> PathBuf {
+    if let Some(package) = index.package_for_path(path) {
+        return package.root.clone();
+    }
+    let mut components = path.components();
+    match (components.next(), components.next()) {
+        (Some(first), Some(_)) => PathBuf::from(first.as_os_str()),
+        _ => PathBuf::new(),
+    }
+}
+
+fn module_name(module: &Path) -> String {
+    if module.as_os_str().is_empty() {
+        "(repository root)".to_string()
+    } else {
+        format!("{}/", module.display())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use cosmos_core::index::{FileIndex, FileSummary, Language};
+
+    fn index_with(files: &[(&str, usize)]) -> CodebaseIndex {
+        let mut index = CodebaseIndex {
+            root: PathBuf::from("/repo"),
+            files: Default::default(),
+            index_errors: Vec::new(),
+            git_head: None,
+            packages: Vec::new(),
+            excluded_files: 0,
+            filter_fingerprint: None,
+        };
+        for (path, loc) in files {
+            let file = FileIndex {
+                path: PathBuf::from(path),
+                language: Language::Rust,
+                loc: *loc,
+                content_hash: String::new(),
+                symbols: Vec::new(),
+                dependencies: Vec::new(),
+                patterns: Vec::new(),
+                complexity: 1.0,
+                last_modified: Utc::now(),
+                summary: FileSummary::default(),
+                layer: None,
+                feature: None,
+            };
+            index.files.insert(PathBuf::from(path), file);
+        }
+        index
+    }
+
+    #[test]
+    fn large_repos_split_by_module_into_balanced_shards() {
+        let index = index_with(&[
+            ("api/handlers.rs", 400),
+            ("api/routes.rs", 200),
+            ("core/engine.rs", 500),
+            ("ui/view.rs", 300),
+            ("tools/gen.rs", 100),
+            ("build.rs", 50),
+        ]);
+        let config = SuggestShardingConfig {
+            min_loc: Some(1_000),
+            max_shards: Some(3),
+        };
+        let shards = plan_with(&index, &config);
+        assert_eq!(shards.len(), 3);
+        let mut locs: Vec<usize> = shards.iter().map(|shard| shard.loc).collect();
+        locs.sort();
+        // api (600) and core (500) alone; ui, tools, and the root files share the third
+        assert_eq!(locs, vec![450, 500, 600]);
+
+        let api = shards
+            .iter()
+            .find(|shard| shard.contains(&index, Path::new("api/routes.rs")))
+            .unwrap();
+        assert_eq!(api.modules, vec![PathBuf::from("api")]);
+        assert_eq!(api.files, 2);
+        let root = shards
+            .iter()
+            .find(|shard| shard.contains(&index, Path::new("build.rs")))
+            .unwrap();
+        assert!(root.label().contains("(repository root)"));
+        assert!(root.prompt_section(0, 3).contains("SHARD 1 OF 3"));
+
+        // Small repos, one-shard configs, and single-module repos run unsharded
+        let small = SuggestShardingConfig {
+            min_loc: Some(10_000),
+            max_shards: Some(3),
+        };
+        assert!(plan_with(&index, &small).is_empty());
+        let off = SuggestShardingConfig {
+            min_loc: Some(0),
+            max_shards: Some(1),
+        };
+        assert!(plan_with(&index, &off).is_empty());
+        let one_module = index_with(&[("src/a.rs", 900), ("src/b.rs", 900)]);
+        assert!(plan_with(&one_module, &config).is_empty());
+    }
+}
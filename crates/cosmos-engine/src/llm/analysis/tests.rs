@@ -3,7 +3,8 @@ use chrono::Utc;
 use cosmos_core::context::WorkContext;
 use cosmos_core::index::{CodebaseIndex, FileIndex, FileSummary, Language, Pattern, Symbol};
 use cosmos_core::suggest::{
-    Priority, SuggestionKind, SuggestionSource, SuggestionValidationMetadata, VerificationState,
+    Priority, SuggestionFeedback, SuggestionKind, SuggestionSource, SuggestionValidationMetadata,
+    VerificationState,
 };
 use std::collections::{HashMap, HashSet};
 use std::fs;
@@ -165,6 +166,9 @@ fn rank_top_churn_files_falls_back_to_risk_scoring_when_history_unavailable() {
         files,
         index_errors: Vec::new(),
         git_head: None,
+        packages: Vec::new(),
+        excluded_files: 0,
+        filter_fingerprint: None,
     };
     let context = empty_context(&root);
 
@@ -272,7 +276,7 @@ fn role_config_tracks_review_focus() {
 
 #[test]
 fn dual_agent_prompt_uses_autonomous_exploration_without_assigned_files() {
-    let prompt = build_review_agent_user_prompt("bug_hunter", None, None, None);
+    let prompt = build_review_agent_user_prompt("bug_hunter", None, None, None, None, None);
     assert!(!prompt.contains("Assigned files"));
     assert!(prompt.contains("Do not wait for assigned files"));
     assert!(prompt.contains("Role: bug_hunter"));
@@ -280,15 +284,82 @@ fn dual_agent_prompt_uses_autonomous_exploration_without_assigned_files() {
 
 #[test]
 fn dual_agent_prompt_keeps_role_specific_checklists() {
-    let bug_prompt = build_review_agent_user_prompt("bug_hunter", None, None, None);
+    let bug_prompt = build_review_agent_user_prompt("bug_hunter", None, None, None, None, None);
     assert!(bug_prompt.contains("Bug checklist"));
     assert!(!bug_prompt.contains("Security checklist"));
 
-    let security_prompt = build_review_agent_user_prompt("security_reviewer", None, None, None);
+    let security_prompt =
+        build_review_agent_user_prompt("security_reviewer", None, None, None, None, None);
     assert!(security_prompt.contains("Security checklist"));
     assert!(!security_prompt.contains("Bug checklist"));
 }
 
+fn feedback_record(
+    id: &str,
+    feedback: SuggestionFeedback,
+    category: SuggestionCategory,
+    summary: &str,
+) -> SuggestionFeedbackRecord {
+    SuggestionFeedbackRecord {
+        timestamp: Utc::now(),
+        suggestion_id: id.to_string(),
+        feedback,
+        category,
+        file: PathBuf::from("src/lib.rs"),
+        summary: summary.to_string(),
+    }
+}
+
+#[test]
+fn user_feedback_turns_rejected_findings_into_negative_examples() {
+    let feedback = vec![
+        feedback_record(
+            "a",
+            SuggestionFeedback::Bad,
+            SuggestionCategory::Bug,
+            "Unwrap on config parse may panic",
+        ),
+        feedback_record(
+            "b",
+            SuggestionFeedback::Irrelevant,
+            SuggestionCategory::Bug,
+            "Test helper ignores errors",
+        ),
+        feedback_record(
+            "c",
+            SuggestionFeedback::Bad,
+            SuggestionCategory::Security,
+            "Token logged at debug level",
+        ),
+        // A later verdict on the same suggestion replaces the earlier one.
+        feedback_record(
+            "a",
+            SuggestionFeedback::Good,
+            SuggestionCategory::Bug,
+            "Unwrap on config parse may panic",
+        ),
+    ];
+
+    let section = build_user_feedback_section(&feedback, SuggestionCategory::Bug).unwrap();
+    assert!(section.contains("2 earlier bug findings"));
+    assert!(section.contains("1 good, 1 bad or irrelevant"));
+    assert!(section.contains("[irrelevant] src/lib.rs: Test helper ignores errors"));
+    assert!(!section.contains("Unwrap on config parse"));
+    assert!(!section.contains("Token logged"));
+
+    let prompt =
+        build_review_agent_user_prompt("bug_hunter", None, None, None, Some(&section), None);
+    assert!(prompt.contains("USER FEEDBACK ON EARLIER FINDINGS"));
+
+    let only_good = vec![feedback_record(
+        "d",
+        SuggestionFeedback::Good,
+        SuggestionCategory::Bug,
+        "Off-by-one in pager",
+    )];
+    assert!(build_user_feedback_section(&only_good, SuggestionCategory::Bug).is_none());
+}
+
 #[test]
 fn gate_default_mapping_matches_expected_ranges() {
     let gate = SuggestionQualityGateConfig::default();
@@ -299,8 +370,10 @@ fn gate_default_mapping_matches_expected_ranges() {
 
 #[test]
 fn bounded_attempt_count_respects_floor_and_hard_cap() {
-    let mut config = SuggestionQualityGateConfig::default();
-    config.max_attempts = 0;
+    let mut config = SuggestionQualityGateConfig {
+        max_attempts: 0,
+        ..Default::default()
+    };
     assert_eq!(bounded_suggestion_attempt_count(&config), 1);
 
     config.max_attempts = 2;
@@ -663,3 +736,94 @@ fn gate_snapshot_keeps_diversity_metrics_without_enforcing_file_gate() {
     assert!(gate.dominant_file_ratio > 0.9);
     assert_eq!(gate.unique_file_count, 1);
 }
+
+#[test]
+fn expand_selected_paths_expands_directories_in_pick_order() {
+    let root = temp_root("ask_expand");
+    let files = ["src/b.rs", "src/a.rs", "src/ui/view.rs", "docs/guide.rs"]
+        .into_iter()
+        .map(|rel| mk_file_index(rel, 10, 1.0, Vec::new(), Vec::new(), 0))
+        .collect::<HashMap<_, _>>();
+    let index = CodebaseIndex {
+        root: root.clone(),
+        files,
+        index_errors: Vec::new(),
+        git_head: None,
+        packages: Vec::new(),
+        excluded_files: 0,
+        filter_fingerprint: None,
+    };
+
+    let expanded = expand_selected_paths(
+        &index,
+        &[
+            PathBuf::from("docs/guide.rs"),
+            PathBuf::from("src"),
+            PathBuf::from("src/a.rs"),
+            PathBuf::from("missing"),
+        ],
+    );
+
+    assert_eq!(
+        expanded,
+        vec![
+            PathBuf::from("docs/guide.rs"),
+            PathBuf::from("src/a.rs"),
+            PathBuf::from("src/b.rs"),
+            PathBuf::from("src/ui/view.rs"),
+        ]
+    );
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn collect_selected_files_reports_sent_and_skipped_files() {
+    let root = temp_root("ask_selected");
+    write_fixture_file(&root, "src/lib.rs", 8);
+    let big = "x".repeat(ASK_SELECTED_TOTAL_MAX_CHARS);
+    fs::write(root.join("src/big.rs"), &big).unwrap();
+
+    let selected = collect_selected_files(
+        &root,
+        &[
+            PathBuf::from("src/lib.rs"),
+            PathBuf::from("src/missing.rs"),
+            PathBuf::from("src/big.rs"),
+        ],
+    );
+
+    assert_eq!(
+        selected.files,
+        vec![PathBuf::from("src/lib.rs"), PathBuf::from("src/big.rs")]
+    );
+    assert!(selected.skipped.contains(&PathBuf::from("src/missing.rs")));
+    assert!(selected.section.contains("--- src/lib.rs ---"));
+    assert!(selected.section.contains("[... truncated ...]"));
+    assert!(selected.content_bytes <= ASK_SELECTED_FILE_MAX_CHARS + 200);
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn plan_gated_suggestion_run_alternates_roles_and_marks_retries_conditional() {
+    let root = temp_root("dry_run_plan");
+    let gate_config = SuggestionQualityGateConfig {
+        max_attempts: 10,
+        ..SuggestionQualityGateConfig::default()
+    };
+
+    let calls = plan_gated_suggestion_run(&root, None, None, &gate_config);
+    assert_eq!(calls.len(), MAX_SUGGESTION_ATTEMPTS_HARD_CAP);
+    assert_eq!(calls[0].label, "attempt 1 bug_hunter");
+    assert_eq!(calls[1].label, "attempt 2 security_reviewer");
+    assert!(!calls[0].conditional);
+    assert!(calls[1..].iter().all(|call| call.conditional));
+    assert!(calls.iter().all(|call| call.max_requests > 0));
+
+    fs::write(root.join("ETHOS.md"), "x".repeat(600)).unwrap();
+    let with_ethos = plan_gated_suggestion_run(&root, None, None, &gate_config);
+    assert!(
+        with_ethos[0].prompt_tokens_per_request > calls[0].prompt_tokens_per_request,
+        "project ethos should be packed into the prompt"
+    );
+    let _ = fs::remove_dir_all(&root);
+}
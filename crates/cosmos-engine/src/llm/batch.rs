@@ -0,0 +1,364 @@
+//! Batch jobs through the backend's OpenAI-compatible Batch API.
+//!
+//! A batch trades latency for price: requests are uploaded together as one JSONL file, the
+//! backend works through them within [`COMPLETION_WINDOW`], and the results come back as
+//! another file, billed at the provider's batch rate. Nothing here retries or fails over;
+//! callers that need an answer now use the regular client. A backend without the batch
+//! endpoints answers the upload with 404 (or 405), reported as [`BatchUnsupported`] so
+//! callers can fall back to sending the requests one by one.
+
+use super::client::{
+    api_base_url, api_key, backend_label, create_http_client, missing_api_key_message,
+    sanitize_api_response, REQUEST_TIMEOUT_SECS,
+};
+use super::spend;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// How long the backend has to finish a job
+pub const COMPLETION_WINDOW: &str = "24h";
+/// Endpoint every request in a job is sent to
+const BATCH_ENDPOINT: &str = "/v1/chat/completions";
+const MULTIPART_BOUNDARY: &str = "cosmos-batch-7f3a9c1e";
+
+/// Where a submitted job stands
+#[derive(Debug, Clone, PartialEq)]
+pub enum BatchStatus {
+    /// Validating, queued, or running
+    Pending { completed: usize, total: usize },
+    /// Done; `output_file_id` holds the results (None when every request failed)
+    Completed {
+        output_file_id: Option<String>,
+        failed: usize,
+    },
+    /// Failed, expired, or cancelled
+    Failed(String),
+}
+
+/// The backend has no batch endpoints; send the requests individually instead.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchUnsupported {
+    pub backend: &'static str,
+}
+
+impl std::fmt::Display for BatchUnsupported {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} doesn't offer a batch API", self.backend)
+    }
+}
+
+impl std::error::Error for BatchUnsupported {}
+
+#[derive(Deserialize)]
+struct FileObject {
+    id: String,
+}
+
+#[derive(Deserialize)]
+struct BatchObject {
+    id: String,
+    status: String,
+    #[serde(default)]
+    output_file_id: Option<String>,
+    #[serde(default)]
+    request_counts: Option<RequestCounts>,
+    #[serde(default)]
+    errors: Option<BatchErrors>,
+}
+
+#[derive(Deserialize, Default)]
+struct RequestCounts {
+    #[serde(default)]
+    total: usize,
+    #[serde(default)]
+    completed: usize,
+    #[serde(default)]
+    failed: usize,
+}
+
+#[derive(Deserialize)]
+struct BatchErrors {
+    #[serde(default)]
+    data: Vec<BatchError>,
+}
+
+#[derive(Deserialize)]
+struct BatchError {
+    #[serde(default)]
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct ResultLine {
+    custom_id: String,
+    #[serde(default)]
+    response: Option<ResultResponse>,
+    #[serde(default)]
+    error: Option<BatchError>,
+}
+
+#[derive(Deserialize)]
+struct ResultResponse {
+    status_code: u16,
+    body: serde_json::Value,
+}
+
+/// Upload `requests` (custom id, chat completion request body) and start a job over them.
+///
+/// Returns the job id to pass to [`status`].
+pub(crate) async fn submit(requests: &[(String, serde_json::Value)]) -> anyhow::Result<String> {
+    if requests.is_empty() {
+        anyhow::bail!("Nothing to submit");
+    }
+    spend::check()?;
+    let api_key = api_key().ok_or_else(|| anyhow::anyhow!(missing_api_key_message()))?;
+    let client = create_http_client(REQUEST_TIMEOUT_SECS)?;
+
+    let upload = client
+        .post(format!("{}/files", api_base_url()))
+        .bearer_auth(&api_key)
+        .header(
+            "Content-Type",
+            format!("multipart/form-data; boundary={}", MULTIPART_BOUNDARY),
+        )
+        .body(multipart_body(&jsonl(requests)))
+        .send()
+        .await?;
+    if matches!(upload.status().as_u16(), 404 | 405) {
+        return Err(BatchUnsupported {
+            backend: backend_label(),
+        }
+        .into());
+    }
+    let file: FileObject = read_json(upload, "batch upload").await?;
+
+    let created = client
+        .post(format!("{}/batches", api_base_url()))
+        .bearer_auth(&api_key)
+        .json(&serde_json::json!({
+            "input_file_id": file.id,
+            "endpoint": BATCH_ENDPOINT,
+            "completion_window": COMPLETION_WINDOW,
+        }))
+        .send()
+        .await?;
+    if matches!(created.status().as_u16(), 404 | 405) {
+        return Err(BatchUnsupported {
+            backend: backend_label(),
+        }
+        .into());
+    }
+    let batch: BatchObject = read_json(created, "batch creation").await?;
+    Ok(batch.id)
+}
+
+/// Current state of the job `batch_id`.
+pub(crate) async fn status(batch_id: &str) -> anyhow::Result<BatchStatus> {
+    let api_key = api_key().ok_or_else(|| anyhow::anyhow!(missing_api_key_message()))?;
+    let client = create_http_client(REQUEST_TIMEOUT_SECS)?;
+    let response = client
+        .get(format!("{}/batches/{}", api_base_url(), batch_id))
+        .bearer_auth(&api_key)
+        .send()
+        .await?;
+    let batch: BatchObject = read_json(response, "batch status").await?;
+    Ok(batch_status(batch))
+}
+
+/// Download a finished job's results: each request's response body (as JSON text) or error,
+/// keyed by custom id.
+pub(crate) async fn results(
+    output_file_id: &str,
+) -> anyhow::Result<BTreeMap<String, Result<String, String>>> {
+    let api_key = api_key().ok_or_else(|| anyhow::anyhow!(missing_api_key_message()))?;
+    let client = create_http_client(REQUEST_TIMEOUT_SECS)?;
+    let response = client
+        .get(format!(
+            "{}/files/{}/content",
+            api_base_url(),
+            output_file_id
+        ))
+        .bearer_auth(&api_key)
+        .send()
+        .await?;
+    let status = response.status();
+    let text = response.text().await?;
+    if !status.is_success() {
+        anyhow::bail!(
+            "{} batch results download failed ({}): {}",
+            backend_label(),
+            status,
+            sanitize_api_response(&text)
+        );
+    }
+    Ok(parse_results(&text))
+}
+
+async fn read_json<T: serde::de::DeserializeOwned>(
+    response: reqwest::Response,
+    what: &str,
+) -> anyhow::Result<T> {
+    let status = response.status();
+    let text = response.text().await?;
+    if !status.is_success() {
+        anyhow::bail!(
+            "{} {} failed ({}): {}",
+            backend_label(),
+            what,
+            status,
+            sanitize_api_response(&text)
+        );
+    }
+    serde_json::from_str(&text).map_err(|e| {
+        anyhow::anyhow!(
+            "Failed to parse {} {} response: {}\n{}",
+            backend_label(),
+            what,
+            e,
+            sanitize_api_response(&text)
+        )
+    })
+}
+
+fn batch_status(batch: BatchObject) -> BatchStatus {
+    let counts = batch.request_counts.unwrap_or_default();
+    match batch.status.as_str() {
+        "completed" => BatchStatus::Completed {
+            output_file_id: batch.output_file_id,
+            failed: counts.failed,
+        },
+        "failed" | "expired" | "cancelling" | "cancelled" => {
+            let reason = batch
+                .errors
+                .and_then(|errors| errors.data.into_iter().next())
+                .map(|error| error.message)
+                .filter(|message| !message.is_empty())
+                .unwrap_or_else(|| format!("batch {} {}", batch.id, batch.status));
+            BatchStatus::Failed(reason)
+        }
+        _ => BatchStatus::Pending {
+            completed: counts.completed + counts.failed,
+            total: counts.total,
+        },
+    }
+}
+
+/// One request per line in the Batch API's input format
+fn jsonl(requests: &[(String, serde_json::Value)]) -> String {
+    let mut out = String::new();
+    for (custom_id, body) in requests {
+        let line = serde_json::json!({
+            "custom_id": custom_id,
+            "method": "POST",
+            "url": BATCH_ENDPOINT,
+            "body": body,
+        });
+        out.push_str(&line.to_string());
+        out.push('\n');
+    }
+    out
+}
+
+/// `multipart/form-data` for a `/files` upload of `content` with purpose `batch`
+fn multipart_body(content: &str) -> String {
+    format!(
+        "--{b}\r\nContent-Disposition: form-data; name=\"purpose\"\r\n\r\nbatch\r\n\
+         --{b}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"cosmos-batch.jsonl\"\r\n\
+         Content-Type: application/jsonl\r\n\r\n{content}\r\n--{b}--\r\n",
+        b = MULTIPART_BOUNDARY,
+        content = content
+    )
+}
+
+fn parse_results(text: &str) -> BTreeMap<String, Result<String, String>> {
+    let mut results = BTreeMap::new();
+    for line in text.lines().filter(|line| !line.trim().is_empty()) {
+        let Ok(line) = serde_json::from_str::<ResultLine>(line) else {
+            continue;
+        };
+        let result = match (line.response, line.error) {
+            (Some(response), _) if (200..300).contains(&response.status_code) => {
+                Ok(response.body.to_string())
+            }
+            (Some(response), _) => Err(format!(
+                "API error {}: {}",
+                response.status_code,
+                sanitize_api_response(&response.body.to_string())
+            )),
+            (None, Some(error)) => Err(error.message),
+            (None, None) => Err("no response".to_string()),
+        };
+        results.insert(line.custom_id, result);
+    }
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requests_go_out_as_jsonl_in_a_multipart_upload() {
+        let requests = vec![
+            ("src".to_string(), serde_json::json!({ "model": "m" })),
+            ("src/llm".to_string(), serde_json::json!({ "model": "m" })),
+        ];
+        let lines: Vec<serde_json::Value> = jsonl(&requests)
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[1]["custom_id"], "src/llm");
+        assert_eq!(lines[1]["url"], "/v1/chat/completions");
+        assert_eq!(lines[1]["body"]["model"], "m");
+
+        let body = multipart_body("{}\n");
+        assert!(body.contains("name=\"purpose\"\r\n\r\nbatch\r\n"));
+        assert!(body.contains("Content-Type: application/jsonl\r\n\r\n{}\n\r\n"));
+        assert!(body.ends_with(&format!("--{}--\r\n", MULTIPART_BOUNDARY)));
+    }
+
+    #[test]
+    fn results_and_statuses_are_read_from_the_batch_api_shapes() {
+        let output = concat!(
+            r#"{"custom_id":"a","response":{"status_code":200,"body":{"choices":[]}}}"#,
+            "\n",
+            r#"{"custom_id":"b","response":{"status_code":400,"body":{"error":"bad"}}}"#,
+            "\n",
+            r#"{"custom_id":"c","response":null,"error":{"message":"expired"}}"#,
+            "\nnot json\n"
+        );
+        let results = parse_results(output);
+        assert_eq!(results.len(), 3);
+        assert_eq!(results["a"], Ok(r#"{"choices":[]}"#.to_string()));
+        assert!(results["b"]
+            .as_ref()
+            .unwrap_err()
+            .starts_with("API error 400"));
+        assert_eq!(results["c"], Err("expired".to_string()));
+
+        let batch = |json: &str| batch_status(serde_json::from_str(json).unwrap());
+        assert_eq!(
+            batch(
+                r#"{"id":"b1","status":"in_progress","request_counts":{"total":4,"completed":1,"failed":1}}"#
+            ),
+            BatchStatus::Pending {
+                completed: 2,
+                total: 4
+            }
+        );
+        assert_eq!(
+            batch(
+                r#"{"id":"b1","status":"completed","output_file_id":"f2","request_counts":{"total":4,"completed":3,"failed":1}}"#
+            ),
+            BatchStatus::Completed {
+                output_file_id: Some("f2".to_string()),
+                failed: 1
+            }
+        );
+        assert_eq!(
+            batch(r#"{"id":"b1","status":"expired"}"#),
+            BatchStatus::Failed("batch b1 expired".to_string())
+        );
+    }
+}
@@ -0,0 +1,220 @@
+//! Conversation memory for Ask Cosmos.
+//!
+//! Each question used to be answered in isolation, so follow-ups like "what about the other
+//! call site?" had nothing to refer to. A chat session keeps the prior turns and the files
+//! each answer was grounded on; recent turns go back into the prompt and their files carry
+//! forward as context for the next question. Sessions are persisted per repo in the cache.
+
+use super::analysis::AskContextReport;
+use super::client::truncate_str;
+use chrono::{DateTime, Utc};
+use cosmos_adapters::cache::Cache;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Turns kept in a session; older ones are dropped.
+pub const MAX_CHAT_TURNS: usize = 20;
+/// Recent turns replayed into the prompt.
+const PROMPT_TURNS: usize = 6;
+/// Per-answer budget when replaying a turn into the prompt.
+const PROMPT_ANSWER_MAX_CHARS: usize = 1_500;
+/// Files carried forward from earlier turns when the question picks none.
+const CARRIED_FILES_MAX: usize = 12;
+
+/// One question and its answer.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChatTurn {
+    pub question: String,
+    pub answer: String,
+    /// Files the answer was grounded on (empty for cached answers)
+    #[serde(default)]
+    pub context_files: Vec<PathBuf>,
+    /// True when `context_files` were read into the prompt rather than listed by path
+    #[serde(default)]
+    pub grounded: bool,
+    pub asked_at: DateTime<Utc>,
+}
+
+/// Prior Ask Cosmos turns for one repo.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChatSession {
+    pub turns: Vec<ChatTurn>,
+}
+
+impl ChatSession {
+    /// Load the repo's saved session, or start a new one.
+    pub fn load(cache: &Cache) -> Self {
+        cache.load_chat_session().unwrap_or_default()
+    }
+
+    pub fn save(&self, cache: &Cache) -> anyhow::Result<()> {
+        cache.save_chat_session(self)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.turns.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.turns.len()
+    }
+
+    pub fn clear(&mut self) {
+        self.turns.clear();
+    }
+
+    /// Record an answered question, with the context report when the answer came from the model.
+    pub fn record(&mut self, question: String, answer: String, report: Option<&AskContextReport>) {
+        self.turns.push(ChatTurn {
+            question,
+            answer,
+            context_files: report.map(|r| r.files.clone()).unwrap_or_default(),
+            grounded: report.is_some_and(|r| r.user_selected),
+            asked_at: Utc::now(),
+        });
+        if self.turns.len() > MAX_CHAT_TURNS {
+            let excess = self.turns.len() - MAX_CHAT_TURNS;
+            self.turns.drain(..excess);
+        }
+    }
+
+    /// Files whose contents grounded the latest grounded turn, to re-read for a follow-up.
+    pub fn grounded_files(&self) -> Vec<PathBuf> {
+        self.turns
+            .iter()
+            .rev()
+            .find(|turn| turn.grounded && !turn.context_files.is_empty())
+            .map(|turn| turn.context_files.clone())
+            .unwrap_or_default()
+    }
+
+    /// Files referenced by recent turns, newest first and deduplicated.
+    pub fn recent_files(&self) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = Vec::new();
+        for turn in self.turns.iter().rev().take(PROMPT_TURNS) {
+            for file in &turn.context_files {
+                if files.len() == CARRIED_FILES_MAX {
+                    return files;
+                }
+                if !files.contains(file) {
+                    files.push(file.clone());
+                }
+            }
+        }
+        files
+    }
+
+    /// Prompt section replaying recent turns, or empty when there are none.
+    pub fn to_prompt_section(&self) -> String {
+        if self.turns.is_empty() {
+            return String::new();
+        }
+        let skip = self.turns.len().saturating_sub(PROMPT_TURNS);
+        let mut section =
+            String::from("\n\nCONVERSATION SO FAR (oldest first; the question may refer back):\n");
+        for turn in &self.turns[skip..] {
+            section.push_str(&format!(
+                "\nQ: {}\nA: {}\n",
+                turn.question.trim(),
+                truncate_str(turn.answer.trim(), PROMPT_ANSWER_MAX_CHARS)
+            ));
+        }
+        section
+    }
+
+    /// Markdown transcript of every turn before the latest one.
+    pub fn earlier_transcript(&self) -> String {
+        let earlier = &self.turns[..self.turns.len().saturating_sub(1)];
+        let mut transcript = String::new();
+        for turn in earlier {
+            transcript.push_str(&format!(
+                "**You:** {}\n\n{}\n\n---\n\n",
+                turn.question.trim(),
+                turn.answer.trim()
+            ));
+        }
+        transcript
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(files: &[&str], user_selected: bool) -> AskContextReport {
+        AskContextReport {
+            files: files.iter().map(PathBuf::from).collect(),
+            user_selected,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn record_caps_turns_and_prompt_replays_recent_ones() {
+        let mut session = ChatSession::default();
+        for i in 0..MAX_CHAT_TURNS + 3 {
+            session.record(format!("question {}", i), format!("answer {}", i), None);
+        }
+        assert_eq!(session.len(), MAX_CHAT_TURNS);
+        assert_eq!(session.turns[0].question, "question 3");
+
+        let section = session.to_prompt_section();
+        assert!(section.contains("CONVERSATION SO FAR"));
+        assert!(section.contains("Q: question 22\nA: answer 22"));
+        assert!(!section.contains("question 16\n"));
+        assert!(ChatSession::default().to_prompt_section().is_empty());
+    }
+
+    #[test]
+    fn follow_ups_carry_forward_earlier_context() {
+        let mut session = ChatSession::default();
+        session.record(
+            "Where is retry handled?".to_string(),
+            "In `src/retry.rs`.".to_string(),
+            Some(&report(&["src/retry.rs", "src/client.rs"], true)),
+        );
+        session.record(
+            "And the backoff?".to_string(),
+            "Also there.".to_string(),
+            Some(&report(&["src/backoff.rs", "src/retry.rs"], false)),
+        );
+        session.record("Cached".to_string(), "cached".to_string(), None);
+
+        assert_eq!(
+            session.grounded_files(),
+            vec![
+                PathBuf::from("src/retry.rs"),
+                PathBuf::from("src/client.rs")
+            ]
+        );
+        assert_eq!(
+            session.recent_files(),
+            vec![
+                PathBuf::from("src/backoff.rs"),
+                PathBuf::from("src/retry.rs"),
+                PathBuf::from("src/client.rs"),
+            ]
+        );
+
+        let transcript = session.earlier_transcript();
+        assert!(transcript.starts_with("**You:** Where is retry handled?"));
+        assert!(transcript.contains("**You:** And the backoff?\n\nAlso there."));
+        assert!(!transcript.contains("Cached"));
+    }
+
+    #[test]
+    fn session_round_trips_through_the_repo_cache() {
+        let root = tempfile::tempdir().unwrap();
+        let cache = Cache::new(root.path());
+        assert!(ChatSession::load(&cache).is_empty());
+
+        let mut session = ChatSession::default();
+        session.record(
+            "What does main do?".to_string(),
+            "It starts the TUI.".to_string(),
+            Some(&report(&["src/main.rs"], false)),
+        );
+        session.save(&cache).unwrap();
+        assert_eq!(ChatSession::load(&cache), session);
+    }
+}
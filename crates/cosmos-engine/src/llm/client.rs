@@ -1,5 +1,12 @@
-use super::models::{Model, Usage};
+use super::failover;
+use super::models::{tag_usage, Model, Usage};
+use super::redaction;
+use super::response_cache;
+use super::routing;
+use super::spend;
+use super::tokens;
 use cosmos_adapters::config::Config;
+use cosmos_core::protocol::schemas::{decode_response, ResponseKind};
 use serde::{Deserialize, Serialize};
 use std::time::{Duration, Instant};
 use tokio::time::timeout;
@@ -7,7 +14,7 @@ use tokio::time::timeout;
 /// Cerebras OpenAI-compatible API URL.
 pub(crate) const CEREBRAS_URL: &str = "https://api.cerebras.ai/v1/chat/completions";
 
-fn backend_label() -> &'static str {
+pub(crate) fn backend_label() -> &'static str {
     "Cerebras"
 }
 
@@ -15,8 +22,26 @@ pub(crate) fn chat_completions_url() -> &'static str {
     CEREBRAS_URL
 }
 
+/// Cerebras OpenAI-compatible API root, for the endpoints beside chat completions (batches).
+pub(crate) const CEREBRAS_API_BASE: &str = "https://api.cerebras.ai/v1";
+
+pub(crate) fn api_base_url() -> &'static str {
+    CEREBRAS_API_BASE
+}
+
+/// Cerebras OpenAI-compatible model listing, used by the startup preflight.
+pub(crate) const CEREBRAS_MODELS_URL: &str = "https://api.cerebras.ai/v1/models";
+
+pub(crate) fn models_url() -> &'static str {
+    CEREBRAS_MODELS_URL
+}
+
+pub(crate) fn backend_slug() -> &'static str {
+    "cerebras"
+}
+
 fn model_id_for_backend_impl(model: Model) -> String {
-    model.id().to_string()
+    routing::routed_model_id(model)
 }
 
 pub(crate) fn model_id_for_backend(model: Model) -> String {
@@ -45,7 +70,7 @@ pub(crate) fn apply_backend_headers(
 const MAX_ERROR_CONTENT_LEN: usize = 12_000;
 
 /// Sanitize API response content for error messages to prevent credential leakage.
-fn sanitize_api_response(content: &str) -> String {
+pub(crate) fn sanitize_api_response(content: &str) -> String {
     const SECRET_PATTERNS: &[&str] = &[
         "api_key",
         "apikey",
@@ -185,6 +210,54 @@ pub(crate) fn parse_structured_content<T>(content: &str) -> anyhow::Result<T>
 where
     T: serde::de::DeserializeOwned,
 {
+    parse_candidates(content, |candidate| serde_json::from_str::<T>(candidate))
+}
+
+/// `parse_structured_content` for a registered response, upgrading payloads written against
+/// an earlier schema version first.
+pub(crate) fn parse_response<T>(kind: ResponseKind, content: &str) -> anyhow::Result<T>
+where
+    T: serde::de::DeserializeOwned,
+{
+    parse_candidates(content, |candidate| {
+        serde_json::from_str::<serde_json::Value>(candidate)
+            .and_then(|payload| decode_response::<T>(kind, payload))
+    })
+}
+
+/// Parse the structured response of a `response_format` named `schema_name`.
+fn parse_named_response<T>(schema_name: &str, content: &str) -> anyhow::Result<T>
+where
+    T: serde::de::DeserializeOwned,
+{
+    match ResponseKind::from_schema_name(schema_name) {
+        Some(kind) => parse_response(kind, content),
+        None => parse_structured_content(content),
+    }
+}
+
+fn parse_candidates<T, E: std::fmt::Display>(
+    content: &str,
+    decode: impl Fn(&str) -> Result<T, E>,
+) -> anyhow::Result<T> {
+    let mut last_err: Option<String> = None;
+    for candidate in structured_candidates(content) {
+        match decode(&candidate) {
+            Ok(data) => return Ok(data),
+            Err(err) => last_err = Some(err.to_string()),
+        }
+    }
+
+    Err(anyhow::anyhow!(
+        "Failed to parse structured response: {}\nContent: {}",
+        last_err.unwrap_or_else(|| "unknown parse error".to_string()),
+        sanitize_api_response(content)
+    ))
+}
+
+/// The reply itself, then deterministic salvage candidates for mildly malformed output, in the
+/// order they should be tried.
+pub(crate) fn structured_candidates(content: &str) -> Vec<String> {
     let mut candidates = Vec::new();
     push_unique_candidate(&mut candidates, content);
     if let Some(stripped) = strip_markdown_fences(content) {
@@ -201,26 +274,74 @@ where
         if let Some(unwrapped) = unwrap_outer_wrapper(&current) {
             push_unique_candidate(&mut candidates, unwrapped);
         }
+        if let Some(without_commas) = strip_trailing_commas(&current) {
+            push_unique_candidate(&mut candidates, without_commas);
+        }
         idx += 1;
     }
+    candidates
+}
 
-    let mut last_err: Option<String> = None;
-    for candidate in candidates {
-        match serde_json::from_str::<T>(&candidate) {
-            Ok(data) => return Ok(data),
-            Err(err) => last_err = Some(err.to_string()),
+/// Drop commas directly before a closing `}` or `]` (outside strings), or `None` when there
+/// are none.
+fn strip_trailing_commas(content: &str) -> Option<String> {
+    let mut out = String::with_capacity(content.len());
+    let mut pending_comma: Option<usize> = None;
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut changed = false;
+    for ch in content.chars() {
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if ch == '\\' {
+                escaped = true;
+            } else if ch == '"' {
+                in_string = false;
+            }
+            out.push(ch);
+            continue;
         }
+        match ch {
+            '}' | ']' => {
+                if let Some(at) = pending_comma.take() {
+                    out.remove(at);
+                    changed = true;
+                }
+            }
+            ',' => {
+                pending_comma = Some(out.len());
+                out.push(ch);
+                continue;
+            }
+            ch if ch.is_whitespace() => {
+                out.push(ch);
+                continue;
+            }
+            '"' => in_string = true,
+            _ => {}
+        }
+        pending_comma = None;
+        out.push(ch);
     }
+    changed.then_some(out)
+}
 
-    Err(anyhow::anyhow!(
-        "Failed to parse structured response: {}\nContent: {}",
-        last_err.unwrap_or_else(|| "unknown parse error".to_string()),
-        sanitize_api_response(content)
-    ))
+tokio::task_local! {
+    static SCOPED_API_KEY: String;
+}
+
+/// Run `fut` with `key` as the API key for every request it makes, instead of the configured
+/// one. Used by embedders that bring their own credentials.
+pub(crate) async fn with_api_key<F: std::future::Future>(key: String, fut: F) -> F::Output {
+    SCOPED_API_KEY.scope(key, fut).await
 }
 
 /// Get the configured API key for the active backend.
 pub(crate) fn api_key() -> Option<String> {
+    if let Ok(key) = SCOPED_API_KEY.try_with(Clone::clone) {
+        return Some(key);
+    }
     let mut config = Config::load();
     config
         .get_api_key()
@@ -246,6 +367,8 @@ struct ChatRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     user: Option<String>,
     max_completion_tokens: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
     stream: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     response_format: Option<ResponseFormat>,
@@ -355,7 +478,7 @@ pub(crate) fn is_retryable_network_error(err: &reqwest::Error) -> bool {
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
-enum ProviderFailureKind {
+pub(crate) enum ProviderFailureKind {
     Timeout,
     RateLimited,
     ServerError,
@@ -363,25 +486,25 @@ enum ProviderFailureKind {
     Other,
 }
 
-fn classify_provider_error(message: &str) -> ProviderFailureKind {
+pub(crate) fn classify_provider_error(message: &str) -> ProviderFailureKind {
     let lower = message.to_ascii_lowercase();
     if lower.contains("timed out after") || lower.contains("request timed out") {
         ProviderFailureKind::Timeout
     } else if lower.contains("no endpoints found") || lower.contains("404 not found") {
         // Some backends use 404 for routing/capability mismatches.
         ProviderFailureKind::ServerError
-    } else if lower.contains("rate limited") {
+    } else if lower.contains("rate limited") || lower.contains("api error 429") {
         ProviderFailureKind::RateLimited
-    } else if lower.contains("server error") {
+    } else if lower.contains("server error") || lower.contains("api error 5") {
         ProviderFailureKind::ServerError
-    } else if lower.contains("could not connect") {
+    } else if lower.contains("could not connect") || lower.contains("error sending request") {
         ProviderFailureKind::NetworkError
     } else {
         ProviderFailureKind::Other
     }
 }
 
-fn provider_outcome_kind(kind: ProviderFailureKind) -> &'static str {
+pub(crate) fn provider_outcome_kind(kind: ProviderFailureKind) -> &'static str {
     match kind {
         ProviderFailureKind::Timeout => "timeout",
         ProviderFailureKind::RateLimited => "rate_limited",
@@ -392,6 +515,9 @@ fn provider_outcome_kind(kind: ProviderFailureKind) -> &'static str {
 }
 
 /// Structured LLM call for the Speed tier with normalized diagnostics.
+///
+/// The diagnostics list every model the request went to, so a failover down the role's
+/// chain (see `failover`) shows up as several attempts.
 pub(crate) async fn call_llm_structured_limited_speed_with_failover<T>(
     system: &str,
     user: &str,
@@ -404,48 +530,68 @@ where
     T: serde::de::DeserializeOwned,
 {
     let call_start = Instant::now();
+    let (result, attempts) =
+        failover::record_attempts(call_llm_structured_limited_with_reasoning::<T>(
+            system,
+            user,
+            Model::Speed,
+            schema_name,
+            schema,
+            max_tokens,
+            timeout_ms,
+            false,
+        ))
+        .await;
+
     let mut diagnostics = SpeedFailoverDiagnostics {
         total_timeout_ms: timeout_ms,
-        attempts: Vec::new(),
+        attempts: attempts
+            .into_iter()
+            .map(|attempt| ProviderAttemptDiagnostics {
+                provider_slug: backend_slug().to_string(),
+                model: Some(attempt.model),
+                mode: "json_schema".to_string(),
+                slice_timeout_ms: timeout_ms,
+                elapsed_ms: attempt.elapsed_ms,
+                outcome_kind: attempt.outcome_kind,
+                error_tail: attempt.error.map(|error| sanitize_api_response(&error)),
+            })
+            .collect(),
         selected_provider: None,
     };
 
-    match call_llm_structured_limited_with_reasoning::<T>(
-        system,
-        user,
-        Model::Speed,
-        schema_name,
-        schema,
-        max_tokens,
-        timeout_ms,
-        false,
-    )
-    .await
-    {
+    match result {
         Ok(mut response) => {
-            diagnostics.selected_provider = Some("cerebras".to_string());
-            diagnostics.attempts.push(ProviderAttemptDiagnostics {
-                provider_slug: "cerebras".to_string(),
-                mode: "json_schema".to_string(),
-                slice_timeout_ms: timeout_ms,
-                elapsed_ms: call_start.elapsed().as_millis() as u64,
-                outcome_kind: "success".to_string(),
-                error_tail: None,
-            });
+            diagnostics.selected_provider = Some(backend_slug().to_string());
+            if diagnostics.attempts.is_empty() {
+                diagnostics.attempts.push(ProviderAttemptDiagnostics {
+                    provider_slug: backend_slug().to_string(),
+                    model: None,
+                    mode: "json_schema".to_string(),
+                    slice_timeout_ms: timeout_ms,
+                    elapsed_ms: call_start.elapsed().as_millis() as u64,
+                    outcome_kind: "success".to_string(),
+                    error_tail: None,
+                });
+            }
             response.speed_failover = Some(diagnostics);
             Ok(response)
         }
         Err(err) => {
-            let err_text = err.to_string();
-            let kind = classify_provider_error(&err_text);
-            diagnostics.attempts.push(ProviderAttemptDiagnostics {
-                provider_slug: "cerebras".to_string(),
-                mode: "json_schema".to_string(),
-                slice_timeout_ms: timeout_ms,
-                elapsed_ms: call_start.elapsed().as_millis() as u64,
-                outcome_kind: provider_outcome_kind(kind).to_string(),
-                error_tail: Some(sanitize_api_response(&err_text)),
-            });
+            let err_text = format!("{:#}", err);
+            if diagnostics.attempts.is_empty() {
+                // Failed before any request went out (spend cap, missing key, timeout)
+                let kind = classify_provider_error(&err_text);
+                diagnostics.attempts.push(ProviderAttemptDiagnostics {
+                    provider_slug: backend_slug().to_string(),
+                    model: None,
+                    mode: "json_schema".to_string(),
+                    slice_timeout_ms: timeout_ms,
+                    elapsed_ms: call_start.elapsed().as_millis() as u64,
+                    outcome_kind: provider_outcome_kind(kind).to_string(),
+                    error_tail: Some(sanitize_api_response(&err_text)),
+                });
+            }
 
             Err(anyhow::Error::new(SpeedFailoverError {
                 diagnostics,
@@ -491,17 +637,41 @@ pub(crate) struct ProviderApiError {
 /// - Server errors (5xx)
 /// - 200-with-error payloads from upstream proxy layers
 ///
-/// Returns the response text on success, or an error after all retries exhausted.
+/// When retries don't help, the request moves down the role's failover chain (see
+/// `failover`). Identical temperature-0 requests may be answered from `response_cache`.
+/// Returns the response text on success, or an error once every model failed.
 pub(crate) async fn send_with_retry<T: Serialize>(
     client: &reqwest::Client,
     api_key: &str,
     request_body: &T,
+) -> anyhow::Result<String> {
+    let cache_key = response_cache::key(request_body);
+    if let Some(cached) = cache_key.as_deref().and_then(response_cache::lookup) {
+        return Ok(cached);
+    }
+    spend::check()?;
+    tokens::note_request(request_body);
+    let text = failover::send(request_body, |body| {
+        send_to_model_with_retry(client, api_key, body)
+    })
+    .await?;
+    if let Some(key) = &cache_key {
+        response_cache::store(key, &text);
+    }
+    Ok(text)
+}
+
+/// One model's share of `send_with_retry`: the request as-is, retried on transient failures.
+async fn send_to_model_with_retry(
+    client: &reqwest::Client,
+    api_key: &str,
+    request_body: serde_json::Value,
 ) -> anyhow::Result<String> {
     let mut last_error = String::new();
     let mut retry_count = 0;
 
     while retry_count <= MAX_RETRIES {
-        let request_builder = client.post(chat_completions_url()).json(request_body);
+        let request_builder = client.post(chat_completions_url()).json(&request_body);
         let response = match apply_backend_headers(request_builder, api_key).send().await {
             Ok(response) => response,
             Err(err) => {
@@ -646,11 +816,12 @@ pub(crate) async fn call_llm_with_usage(
             },
             Message {
                 role: "user".to_string(),
-                content: user.to_string(),
+                content: redaction::redact(user),
             },
         ],
         user: None,
-        max_completion_tokens: model.max_tokens(),
+        max_completion_tokens: routing::routed_max_tokens(model.max_tokens()),
+        temperature: routing::routed_temperature(None),
         stream,
         response_format,
         disable_reasoning: reasoning.disable_reasoning,
@@ -693,7 +864,7 @@ pub(crate) async fn call_llm_with_usage(
 
     Ok(LlmResponse {
         content,
-        usage: parsed.usage,
+        usage: tag_usage(parsed.usage, model),
     })
 }
 
@@ -721,6 +892,9 @@ pub struct SpeedFailoverDiagnostics {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProviderAttemptDiagnostics {
     pub provider_slug: String,
+    /// Model the attempt went to, when the request reached the provider
+    #[serde(default)]
+    pub model: Option<String>,
     pub mode: String,
     pub slice_timeout_ms: u64,
     pub elapsed_ms: u64,
@@ -758,7 +932,37 @@ where
     T: serde::de::DeserializeOwned,
 {
     let api_key = api_key().ok_or_else(|| anyhow::anyhow!(missing_api_key_message()))?;
+    let request = structured_request(system, user, model, schema_name, schema)?;
+    let client = create_http_client(REQUEST_TIMEOUT_SECS)?;
+    let text = send_with_retry(&client, &api_key, &request).await?;
+    parse_structured_completion(&text, model, schema_name)
+}
+
+/// The request body `call_llm_structured` sends, for callers that deliver it another way
+/// (batch jobs).
+pub(crate) fn structured_request_body(
+    system: &str,
+    user: &str,
+    model: Model,
+    schema_name: &str,
+    schema: serde_json::Value,
+) -> anyhow::Result<serde_json::Value> {
+    Ok(serde_json::to_value(structured_request(
+        system,
+        user,
+        model,
+        schema_name,
+        schema,
+    )?)?)
+}
 
+fn structured_request(
+    system: &str,
+    user: &str,
+    model: Model,
+    schema_name: &str,
+    schema: serde_json::Value,
+) -> anyhow::Result<ChatRequest> {
     if !model.supports_structured_outputs() {
         return Err(anyhow::anyhow!(
             "Structured outputs aren't supported for {}. Try a different model.",
@@ -766,8 +970,6 @@ where
         ));
     }
 
-    let client = create_http_client(REQUEST_TIMEOUT_SECS)?;
-
     let response_format = Some(ResponseFormat {
         format_type: "json_schema".to_string(),
         json_schema: Some(JsonSchemaWrapper {
@@ -780,7 +982,7 @@ where
     let stream = false;
     let reasoning = reasoning_fields_for_model(model, true);
 
-    let request = ChatRequest {
+    Ok(ChatRequest {
         model: model_id_for_backend(model),
         messages: vec![
             Message {
@@ -789,25 +991,34 @@ where
             },
             Message {
                 role: "user".to_string(),
-                content: user.to_string(),
+                content: redaction::redact(user),
             },
         ],
         user: None,
-        max_completion_tokens: model.max_tokens(),
+        max_completion_tokens: routing::routed_max_tokens(model.max_tokens()),
+        temperature: routing::routed_temperature(None),
         stream,
         response_format,
         disable_reasoning: reasoning.disable_reasoning,
         clear_thinking: reasoning.clear_thinking,
-    };
-
-    let text = send_with_retry(&client, &api_key, &request).await?;
+    })
+}
 
-    let parsed: ChatResponse = serde_json::from_str(&text).map_err(|e| {
+/// Parse a chat completion returned for a `structured_request_body` request.
+pub(crate) fn parse_structured_completion<T>(
+    text: &str,
+    model: Model,
+    schema_name: &str,
+) -> anyhow::Result<StructuredResponse<T>>
+where
+    T: serde::de::DeserializeOwned,
+{
+    let parsed: ChatResponse = serde_json::from_str(text).map_err(|e| {
         anyhow::anyhow!(
             "Failed to parse {} response: {}\n{}",
             backend_label(),
             e,
-            sanitize_api_response(&text)
+            sanitize_api_response(text)
         )
     })?;
 
@@ -834,11 +1045,11 @@ where
         ));
     }
 
-    let data: T = parse_structured_content(&content)?;
+    let data: T = parse_named_response(schema_name, &content)?;
 
     Ok(StructuredResponse {
         data,
-        usage: parsed.usage,
+        usage: tag_usage(parsed.usage, model),
         speed_failover: None,
     })
 }
@@ -918,11 +1129,12 @@ where
             },
             Message {
                 role: "user".to_string(),
-                content: user.to_string(),
+                content: redaction::redact(user),
             },
         ],
         user: None,
-        max_completion_tokens: max_tokens,
+        max_completion_tokens: routing::routed_max_tokens(max_tokens),
+        temperature: routing::routed_temperature(None),
         stream,
         response_format,
         disable_reasoning: reasoning.disable_reasoning,
@@ -964,11 +1176,11 @@ where
         ));
     }
 
-    let data: T = parse_structured_content(&content)?;
+    let data: T = parse_named_response(schema_name, &content)?;
 
     Ok(StructuredResponse {
         data,
-        usage: parsed.usage,
+        usage: tag_usage(parsed.usage, model),
         speed_failover: None,
     })
 }
@@ -1058,6 +1270,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_response_upgrades_older_schema_versions() {
+        #[derive(Debug, serde::Deserialize)]
+        struct PreviewProbe {
+            verification_state: String,
+        }
+        let fenced = "```json\n{\"verified\":true,\"description\":\"x\"}\n```";
+        let parsed: PreviewProbe = parse_response(ResponseKind::Preview, fenced).unwrap();
+        assert_eq!(parsed.verification_state, "verified");
+        let named: PreviewProbe =
+            parse_named_response(&ResponseKind::Preview.schema_name(), fenced).unwrap();
+        assert_eq!(named.verification_state, "verified");
+        // Unregistered schemas parse as-is.
+        assert!(parse_named_response::<PreviewProbe>("commit_message", fenced).is_err());
+    }
+
     #[test]
     fn test_parse_structured_content_handles_markdown_fences() {
         let fenced = "```json\n{\"description\":\"hello\"}\n```";
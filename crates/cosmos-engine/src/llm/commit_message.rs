@@ -0,0 +1,180 @@
+//! Conventional-commit messages written from the staged diff.
+//!
+//! The Ship step pre-fills a message from the pending change descriptions alone; this asks the
+//! model to read what is actually staged and normalizes its answer into `type(scope): subject`.
+
+use super::client::{call_llm_structured, StructuredResponse};
+use super::models::{Model, Usage};
+use super::prompts::COMMIT_MESSAGE_SYSTEM;
+use super::routing;
+use cosmos_adapters::config::ModelRole;
+use cosmos_adapters::util::truncate;
+use serde::Deserialize;
+
+/// Staged diffs longer than this are cut before they are sent.
+const MAX_DIFF_CHARS: usize = 24_000;
+/// Conventional commit headers should fit in a one-line log.
+const MAX_HEADER_CHARS: usize = 72;
+/// Types accepted as-is; anything else is treated as a chore.
+const COMMIT_TYPES: [&str; 10] = [
+    "feat", "fix", "refactor", "perf", "docs", "test", "build", "ci", "style", "chore",
+];
+
+#[derive(Debug, Deserialize)]
+struct CommitMessageResponse {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    scope: String,
+    subject: String,
+    #[serde(default)]
+    body: String,
+}
+
+fn commit_message_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": "object",
+        "additionalProperties": false,
+        "properties": {
+            "type": { "type": "string" },
+            "scope": { "type": "string" },
+            "subject": { "type": "string" },
+            "body": { "type": "string" }
+        },
+        "required": ["type", "scope", "subject", "body"]
+    })
+}
+
+/// Write a conventional commit message for the staged diff.
+///
+/// `change_summaries` are the pending change descriptions, given as intent so the message
+/// explains why as well as what.
+pub async fn generate_commit_message(
+    staged_diff: &str,
+    change_summaries: &[String],
+) -> anyhow::Result<(String, Option<Usage>)> {
+    routing::with_role(
+        ModelRole::Summarize,
+        generate_commit_message_impl(staged_diff, change_summaries),
+    )
+    .await
+}
+
+async fn generate_commit_message_impl(
+    staged_diff: &str,
+    change_summaries: &[String],
+) -> anyhow::Result<(String, Option<Usage>)> {
+    if staged_diff.trim().is_empty() {
+        anyhow::bail!("Nothing is staged to describe");
+    }
+
+    let mut user = String::from("PENDING CHANGES:\n");
+    if change_summaries.is_empty() {
+        user.push_str("- (none recorded)\n");
+    }
+    for summary in change_summaries {
+        user.push_str(&format!("- {}\n", summary.trim()));
+    }
+    user.push_str(&format!(
+        "\nSTAGED DIFF:\n{}",
+        truncate(staged_diff, MAX_DIFF_CHARS)
+    ));
+
+    let StructuredResponse {
+        data: parsed,
+        usage,
+        ..
+    } = call_llm_structured::<CommitMessageResponse>(
+        COMMIT_MESSAGE_SYSTEM,
+        &user,
+        Model::Speed,
+        "commit_message",
+        commit_message_schema(),
+    )
+    .await?;
+
+    let message = format_commit_message(&parsed.kind, &parsed.scope, &parsed.subject, &parsed.body)
+        .ok_or_else(|| anyhow::anyhow!("Model returned an empty commit subject"))?;
+    Ok((message, usage))
+}
+
+/// Assemble `type(scope): subject` plus an optional body, tidying what the model returned.
+///
+/// Returns None when there is no subject to use.
+pub fn format_commit_message(kind: &str, scope: &str, subject: &str, body: &str) -> Option<String> {
+    let kind = kind.trim().to_lowercase();
+    let kind = if COMMIT_TYPES.contains(&kind.as_str()) {
+        kind
+    } else {
+        "chore".to_string()
+    };
+    let scope: String = scope
+        .trim()
+        .chars()
+        .filter(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '/' | '.'))
+        .collect();
+
+    // Models like to repeat the prefix in the subject or end it with a period.
+    let mut subject = subject.lines().next().unwrap_or("").trim();
+    if let Some((prefix, rest)) = subject.split_once(':') {
+        if prefix.len() < 20 && !prefix.contains(' ') {
+            subject = rest.trim();
+        }
+    }
+    let subject = subject.trim_end_matches('.').trim();
+    if subject.is_empty() {
+        return None;
+    }
+    let mut chars = subject.chars();
+    let subject = match chars.next() {
+        Some(first) if !chars.clone().next().is_some_and(char::is_uppercase) => {
+            first.to_lowercase().chain(chars).collect()
+        }
+        _ => subject.to_string(),
+    };
+
+    let prefix = if scope.is_empty() {
+        format!("{}: ", kind)
+    } else {
+        format!("{}({}): ", kind, scope)
+    };
+    let subject = truncate(
+        &subject,
+        MAX_HEADER_CHARS.saturating_sub(prefix.chars().count()),
+    );
+    let mut message = format!("{}{}", prefix, subject);
+    let body = body.trim();
+    if !body.is_empty() {
+        message.push_str("\n\n");
+        message.push_str(body);
+    }
+    Some(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_and_tidies_conventional_commits() {
+        assert_eq!(
+            format_commit_message(
+                "Fix",
+                "parser",
+                "fix: Guard the empty input case.",
+                "  Empty files used to panic.  "
+            )
+            .as_deref(),
+            Some("fix(parser): guard the empty input case\n\nEmpty files used to panic.")
+        );
+        // Unknown types fall back to chore; acronyms keep their case.
+        assert_eq!(
+            format_commit_message("improvement", "", "API retries back off", "").as_deref(),
+            Some("chore: API retries back off")
+        );
+        assert_eq!(format_commit_message("feat", "ui", " . ", "body"), None);
+
+        let long = format_commit_message("refactor", "", &"word ".repeat(40), "").unwrap();
+        assert!(long.chars().count() <= MAX_HEADER_CHARS);
+    }
+}
@@ -0,0 +1,310 @@
+//! Conflict detection between queued fixes.
+//!
+//! Suggestions are generated against one snapshot of the repo. Once an earlier fix lands, a
+//! later suggestion for the same file may point at lines that moved or were rewritten, and
+//! applying it from the stale anchors can clobber the earlier change. This compares affected
+//! files and anchor regions across pending suggestions before apply, and rebases later
+//! suggestions onto the post-apply content so their fixes are regenerated from what is on disk.
+
+use cosmos_core::suggest::Suggestion;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+/// Lines on either side of an anchor that a fix is assumed to touch.
+pub const ANCHOR_RADIUS_LINES: usize = 10;
+
+/// Risk flag added to suggestions whose anchor code was rewritten by an earlier fix.
+pub const REWRITTEN_ANCHOR_FLAG: &str = "anchor_rewritten_by_earlier_fix";
+
+/// Lines around a suggestion anchor (1-based, inclusive).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnchorRegion {
+    pub file: PathBuf,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl AnchorRegion {
+    fn around(file: &Path, line: usize) -> Self {
+        Self {
+            file: file.to_path_buf(),
+            start: line.saturating_sub(ANCHOR_RADIUS_LINES).max(1),
+            end: line + ANCHOR_RADIUS_LINES,
+        }
+    }
+
+    fn overlap(&self, other: &AnchorRegion) -> Option<(usize, usize)> {
+        if self.file != other.file {
+            return None;
+        }
+        let start = self.start.max(other.start);
+        let end = self.end.min(other.end);
+        (start <= end).then_some((start, end))
+    }
+}
+
+/// Anchor regions for a suggestion: its primary line plus every evidence reference.
+pub fn anchor_regions(suggestion: &Suggestion) -> Vec<AnchorRegion> {
+    let mut regions = Vec::new();
+    let anchors = suggestion
+        .line
+        .map(|line| (suggestion.file.as_path(), line))
+        .into_iter()
+        .chain(
+            suggestion
+                .evidence_refs
+                .iter()
+                .map(|evidence| (evidence.file.as_path(), evidence.line)),
+        );
+    for (file, line) in anchors {
+        let region = AnchorRegion::around(file, line);
+        if !regions.contains(&region) {
+            regions.push(region);
+        }
+    }
+    regions
+}
+
+/// Two queued fixes that touch the same file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixConflict {
+    /// The fix that applies first
+    pub earlier: Uuid,
+    /// The fix that must be rebased once `earlier` lands
+    pub later: Uuid,
+    pub file: PathBuf,
+    /// Overlapping anchor lines, when both fixes target the same code
+    pub overlap: Option<(usize, usize)>,
+}
+
+impl FixConflict {
+    pub fn is_overlapping(&self) -> bool {
+        self.overlap.is_some()
+    }
+}
+
+/// Conflicts between suggestions queued in apply order.
+pub fn detect_fix_conflicts(queue: &[&Suggestion]) -> Vec<FixConflict> {
+    let mut conflicts = Vec::new();
+    for (i, earlier) in queue.iter().enumerate() {
+        let earlier_regions = anchor_regions(earlier);
+        for later in &queue[i + 1..] {
+            let later_regions = anchor_regions(later);
+            let later_files = later.affected_files();
+            let mut seen: Vec<&PathBuf> = Vec::new();
+            for file in earlier.affected_files() {
+                if seen.contains(&file) || !later_files.contains(&file) {
+                    continue;
+                }
+                seen.push(file);
+                let overlap = earlier_regions
+                    .iter()
+                    .filter(|region| &region.file == file)
+                    .flat_map(|region| later_regions.iter().filter_map(|o| region.overlap(o)))
+                    .max_by_key(|(start, end)| end - start);
+                conflicts.push(FixConflict {
+                    earlier: earlier.id,
+                    later: later.id,
+                    file: file.clone(),
+                    overlap,
+                });
+            }
+        }
+    }
+    conflicts
+}
+
+/// How an applied edit moved the lines of a file: everything before `start` stays put,
+/// old lines `start..old_end` were rewritten, and everything after shifts by `delta`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct LineShift {
+    start: usize,
+    old_end: usize,
+    delta: isize,
+}
+
+impl LineShift {
+    fn between(before: &str, after: &str) -> Option<Self> {
+        if before == after {
+            return None;
+        }
+        let old: Vec<&str> = before.lines().collect();
+        let new: Vec<&str> = after.lines().collect();
+        let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+        let suffix = old[prefix..]
+            .iter()
+            .rev()
+            .zip(new[prefix..].iter().rev())
+            .take_while(|(a, b)| a == b)
+            .count();
+        Some(Self {
+            start: prefix + 1,
+            old_end: old.len() - suffix + 1,
+            delta: new.len() as isize - old.len() as isize,
+        })
+    }
+
+    /// Post-apply line for a pre-apply line, or None if the line was rewritten.
+    fn map(&self, line: usize) -> Option<usize> {
+        if line < self.start {
+            Some(line)
+        } else if line >= self.old_end {
+            Some((line as isize + self.delta).max(1) as usize)
+        } else {
+            None
+        }
+    }
+}
+
+/// A queued suggestion after an earlier fix was applied.
+#[derive(Debug, Clone)]
+pub enum RebasedSuggestion {
+    /// The applied fix touched none of its files
+    Unaffected,
+    /// Its anchors moved; the suggestion now points at post-apply lines
+    Shifted(Suggestion),
+    /// An anchor sat in rewritten code; those anchors are dropped and the fix is regenerated
+    /// from the post-apply content
+    Regenerate(Suggestion),
+}
+
+/// Rebase a queued suggestion onto files changed by an earlier fix
+/// (`(path, content_before, content_after)`).
+pub fn rebase_after_apply(
+    suggestion: &Suggestion,
+    applied: &[(PathBuf, String, String)],
+) -> RebasedSuggestion {
+    let shifts: Vec<(&PathBuf, LineShift)> = applied
+        .iter()
+        .filter_map(|(path, before, after)| {
+            LineShift::between(before, after).map(|shift| (path, shift))
+        })
+        .collect();
+    let shift_for = |file: &Path| {
+        shifts
+            .iter()
+            .find(|(path, _)| path.as_path() == file)
+            .map(|(_, shift)| *shift)
+    };
+    if !suggestion
+        .affected_files()
+        .iter()
+        .any(|file| shift_for(file).is_some())
+    {
+        return RebasedSuggestion::Unaffected;
+    }
+
+    let mut rebased = suggestion.clone();
+    let mut rewritten = false;
+    if let (Some(line), Some(shift)) = (rebased.line, shift_for(&rebased.file)) {
+        rebased.line = shift.map(line);
+        rewritten |= rebased.line.is_none();
+    }
+    rebased.evidence_refs.retain_mut(|evidence| {
+        let Some(shift) = shift_for(&evidence.file) else {
+            return true;
+        };
+        match shift.map(evidence.line) {
+            Some(line) => {
+                evidence.line = line;
+                true
+            }
+            None => {
+                rewritten = true;
+                false
+            }
+        }
+    });
+
+    if rewritten {
+        if !rebased
+            .implementation_risk_flags
+            .iter()
+            .any(|flag| flag == REWRITTEN_ANCHOR_FLAG)
+        {
+            rebased
+                .implementation_risk_flags
+                .push(REWRITTEN_ANCHOR_FLAG.to_string());
+        }
+        RebasedSuggestion::Regenerate(rebased)
+    } else {
+        RebasedSuggestion::Shifted(rebased)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmos_core::suggest::{Priority, SuggestionEvidenceRef, SuggestionKind, SuggestionSource};
+
+    fn suggestion(file: &str, line: usize) -> Suggestion {
+        Suggestion::new(
+            SuggestionKind::BugFix,
+            Priority::High,
+            PathBuf::from(file),
+            "Fix it".to_string(),
+            SuggestionSource::LlmDeep,
+        )
+        .with_line(line)
+    }
+
+    fn numbered(count: usize) -> String {
+        (1..=count).map(|i| format!("line {}\n", i)).collect()
+    }
+
+    #[test]
+    fn detects_same_file_and_overlapping_anchor_conflicts() {
+        let first = suggestion("src/a.rs", 40);
+        let near = suggestion("src/a.rs", 45);
+        let far = suggestion("src/a.rs", 200);
+        let other = suggestion("src/b.rs", 40);
+
+        let conflicts = detect_fix_conflicts(&[&first, &near, &far, &other]);
+        assert_eq!(conflicts.len(), 3);
+        assert_eq!(conflicts[0].earlier, first.id);
+        assert_eq!(conflicts[0].later, near.id);
+        assert_eq!(conflicts[0].overlap, Some((35, 50)));
+        assert!(!conflicts[1].is_overlapping());
+        assert!(conflicts.iter().all(|c| c.later != other.id));
+    }
+
+    #[test]
+    fn rebase_shifts_anchors_below_an_insertion() {
+        let before = numbered(100);
+        let after = before.replacen("line 10\n", "line 10\nadded a\nadded b\n", 1);
+        let later = suggestion("src/a.rs", 50).with_evidence_refs(vec![SuggestionEvidenceRef {
+            snippet_id: 1,
+            file: PathBuf::from("src/a.rs"),
+            line: 5,
+        }]);
+
+        let applied = vec![(PathBuf::from("src/a.rs"), before, after)];
+        let RebasedSuggestion::Shifted(rebased) = rebase_after_apply(&later, &applied) else {
+            panic!("expected shifted suggestion");
+        };
+        assert_eq!(rebased.line, Some(52));
+        assert_eq!(rebased.evidence_refs[0].line, 5);
+        assert!(matches!(
+            rebase_after_apply(&suggestion("src/b.rs", 50), &applied),
+            RebasedSuggestion::Unaffected
+        ));
+    }
+
+    #[test]
+    fn rebase_regenerates_when_anchor_was_rewritten() {
+        let before = numbered(100);
+        let after = before.replacen("line 50\n", "rewritten 50\n", 1);
+        let applied = vec![(PathBuf::from("src/a.rs"), before, after)];
+
+        let RebasedSuggestion::Regenerate(rebased) =
+            rebase_after_apply(&suggestion("src/a.rs", 50), &applied)
+        else {
+            panic!("expected regeneration");
+        };
+        assert_eq!(rebased.line, None);
+        assert!(rebased
+            .implementation_risk_flags
+            .iter()
+            .any(|flag| flag == REWRITTEN_ANCHOR_FLAG));
+    }
+}
@@ -0,0 +1,359 @@
+//! Extra agent tools backed by subprocess commands
+//!
+//! Teams ground Cosmos in internal systems ("look up the owner of this service") by
+//! declaring tools in `.cosmos/config.toml` or by registering them from an embedding
+//! program. Each tool is a manifest: a name, a description, a JSON schema for its
+//! arguments, and a shell command. The agent sees the tool next to the built-in ones;
+//! calling it runs the command from the repo root with the arguments in
+//! `COSMOS_TOOL_ARGUMENTS` and hands stdout back to the model.
+//!
+//! Repo-declared tools only run in trusted workspaces. Tools registered with [`register`]
+//! come from the host program and are always active.
+
+use super::implementation::{load_repo_harness_config, REPO_CONFIG_PATH};
+use super::tools::{FunctionDefinition, ToolDefinition, BUILTIN_TOOL_NAMES};
+use crate::plugins::PASSTHROUGH_ENV;
+use cosmos_adapters::util::{run_command_with_timeout, truncate};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::path::Path;
+use std::process::Command;
+use std::sync::RwLock;
+use std::time::Duration;
+
+const DEFAULT_TOOL_TIMEOUT_MS: u64 = 20_000;
+const MAX_TOOL_TIMEOUT_MS: u64 = 120_000;
+const MAX_CUSTOM_TOOLS: usize = 16;
+const MAX_NAME_LEN: usize = 64;
+
+/// One tool the agent can call, as declared under `[[tools]]`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CustomToolManifest {
+    /// Letters, digits, `_` and `-`; must not shadow a built-in tool
+    pub name: String,
+    /// Tells the model when to call the tool
+    pub description: String,
+    /// JSON schema for the arguments; an object schema. Defaults to no arguments.
+    #[serde(default = "default_parameters")]
+    pub parameters: Value,
+    /// Shell command, run with `sh -c` from the repo root.
+    pub command: String,
+    /// Defaults to 20s; capped at 2 minutes.
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+}
+
+fn default_parameters() -> Value {
+    serde_json::json!({ "type": "object", "properties": {} })
+}
+
+impl CustomToolManifest {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        let name = self.name.as_str();
+        if name.is_empty()
+            || name.len() > MAX_NAME_LEN
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        {
+            return Err(anyhow::anyhow!(
+                "Tool name '{}' must be 1-{} letters, digits, '_' or '-'",
+                name,
+                MAX_NAME_LEN
+            ));
+        }
+        if BUILTIN_TOOL_NAMES.contains(&name) {
+            return Err(anyhow::anyhow!(
+                "Tool '{}' would shadow a built-in tool",
+                name
+            ));
+        }
+        if self.description.trim().is_empty() {
+            return Err(anyhow::anyhow!("Tool '{}' needs a description", name));
+        }
+        if self.command.trim().is_empty() {
+            return Err(anyhow::anyhow!("Tool '{}' needs a command", name));
+        }
+        if self.parameters.get("type").and_then(Value::as_str) != Some("object") {
+            return Err(anyhow::anyhow!(
+                "Tool '{}' parameters must be a JSON schema with \"type\": \"object\"",
+                name
+            ));
+        }
+        Ok(())
+    }
+
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            tool_type: "function",
+            function: FunctionDefinition {
+                name: Cow::Owned(self.name.clone()),
+                strict: None,
+                description: Cow::Owned(self.description.clone()),
+                parameters: self.parameters.clone(),
+            },
+        }
+    }
+
+    fn timeout(&self) -> Duration {
+        Duration::from_millis(
+            self.timeout_ms
+                .unwrap_or(DEFAULT_TOOL_TIMEOUT_MS)
+                .clamp(1, MAX_TOOL_TIMEOUT_MS),
+        )
+    }
+}
+
+/// Load and validate the repo's `[[tools]]`; a missing config means none.
+pub fn load_repo_tools(repo_root: &Path) -> anyhow::Result<Vec<CustomToolManifest>> {
+    let tools = load_repo_harness_config(repo_root)?.tools;
+    validate_set(&tools).map_err(|e| anyhow::anyhow!("Invalid {}: {}", REPO_CONFIG_PATH, e))?;
+    Ok(tools)
+}
+
+fn validate_set(tools: &[CustomToolManifest]) -> anyhow::Result<()> {
+    if tools.len() > MAX_CUSTOM_TOOLS {
+        return Err(anyhow::anyhow!(
+            "at most {} custom tools are allowed",
+            MAX_CUSTOM_TOOLS
+        ));
+    }
+    let mut names = HashSet::new();
+    for tool in tools {
+        tool.validate()?;
+        if !names.insert(tool.name.as_str()) {
+            return Err(anyhow::anyhow!("tool '{}' is declared twice", tool.name));
+        }
+    }
+    Ok(())
+}
+
+static HOST_TOOLS: RwLock<Vec<CustomToolManifest>> = RwLock::new(Vec::new());
+static REPO_TOOLS: RwLock<Vec<CustomToolManifest>> = RwLock::new(Vec::new());
+
+/// Names of the repo tools from the last [`install`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InstalledTools {
+    pub active: Vec<String>,
+    /// Declared but not activated because the workspace is not trusted
+    pub untrusted: Vec<String>,
+}
+
+/// Load the repo's tools and make them available to the agent for this process.
+pub fn install(repo_root: &Path, trusted: bool) -> anyhow::Result<InstalledTools> {
+    let tools = load_repo_tools(repo_root)?;
+    let names: Vec<String> = tools.iter().map(|tool| tool.name.clone()).collect();
+    if let Ok(mut active) = REPO_TOOLS.write() {
+        *active = if trusted { tools } else { Vec::new() };
+    }
+    Ok(if trusted {
+        InstalledTools {
+            active: names,
+            untrusted: Vec::new(),
+        }
+    } else {
+        InstalledTools {
+            active: Vec::new(),
+            untrusted: names,
+        }
+    })
+}
+
+/// Register a tool from the host program. It replaces an earlier registration, or a repo
+/// tool, with the same name.
+pub fn register(manifest: CustomToolManifest) -> anyhow::Result<()> {
+    manifest.validate()?;
+    let mut host = HOST_TOOLS
+        .write()
+        .map_err(|_| anyhow::anyhow!("Custom tool registry is unavailable"))?;
+    host.retain(|tool| tool.name != manifest.name);
+    if host.len() >= MAX_CUSTOM_TOOLS {
+        return Err(anyhow::anyhow!(
+            "At most {} custom tools can be registered",
+            MAX_CUSTOM_TOOLS
+        ));
+    }
+    host.push(manifest);
+    Ok(())
+}
+
+/// Remove a host-registered tool; returns whether it was registered.
+pub fn unregister(name: &str) -> bool {
+    HOST_TOOLS
+        .write()
+        .map(|mut host| {
+            let before = host.len();
+            host.retain(|tool| tool.name != name);
+            host.len() != before
+        })
+        .unwrap_or(false)
+}
+
+/// Host tools first, then repo tools whose names the host didn't take.
+fn active_tools() -> Vec<CustomToolManifest> {
+    let mut tools = HOST_TOOLS
+        .read()
+        .map(|host| host.clone())
+        .unwrap_or_default();
+    if let Ok(repo) = REPO_TOOLS.read() {
+        for tool in repo.iter() {
+            if !tools.iter().any(|existing| existing.name == tool.name) {
+                tools.push(tool.clone());
+            }
+        }
+    }
+    tools
+}
+
+/// Names of the active custom tools, for diagnostics.
+pub fn active_tool_names() -> Vec<String> {
+    active_tools().into_iter().map(|tool| tool.name).collect()
+}
+
+pub(crate) fn definitions() -> Vec<ToolDefinition> {
+    active_tools()
+        .iter()
+        .map(CustomToolManifest::definition)
+        .collect()
+}
+
+/// Run the custom tool `name`, or None if no such tool is active.
+pub(crate) fn execute(root: &Path, name: &str, args_json: &str) -> Option<String> {
+    let tool = active_tools().into_iter().find(|tool| tool.name == name)?;
+    Some(run_tool(&tool, root, args_json).unwrap_or_else(|err| format!("Error: {}", err)))
+}
+
+fn run_tool(tool: &CustomToolManifest, root: &Path, args_json: &str) -> Result<String, String> {
+    let args_json = if args_json.trim().is_empty() {
+        "{}"
+    } else {
+        args_json
+    };
+    match serde_json::from_str::<Value>(args_json) {
+        Ok(Value::Object(_)) => {}
+        _ => return Err("tool arguments must be a JSON object".to_string()),
+    }
+
+    let mut command = Command::new("sh");
+    command
+        .current_dir(root)
+        .arg("-c")
+        .arg(&tool.command)
+        .env_clear()
+        .envs(
+            PASSTHROUGH_ENV
+                .iter()
+                .filter_map(|key| std::env::var_os(key).map(|value| (key.to_string(), value))),
+        )
+        .env("COSMOS_TOOL_NAME", &tool.name)
+        .env("COSMOS_TOOL_ARGUMENTS", args_json)
+        .env("COSMOS_REPO_ROOT", root);
+    let result = run_command_with_timeout(&mut command, tool.timeout())?;
+    if result.timed_out {
+        return Err(format!(
+            "{} timed out after {}ms",
+            tool.name,
+            tool.timeout().as_millis()
+        ));
+    }
+    if !result.status.is_some_and(|status| status.success()) {
+        let stderr = result.stderr.trim();
+        return Err(if stderr.is_empty() {
+            format!("{} exited with an error", tool.name)
+        } else {
+            format!(
+                "{} exited with an error: {}",
+                tool.name,
+                truncate(stderr, 500)
+            )
+        });
+    }
+    let stdout = result.stdout.trim();
+    Ok(if stdout.is_empty() {
+        "(no output)".to_string()
+    } else {
+        stdout.to_string()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest(name: &str, command: &str) -> CustomToolManifest {
+        CustomToolManifest {
+            name: name.to_string(),
+            description: "Look up a service".to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": { "service": { "type": "string" } },
+                "required": ["service"]
+            }),
+            command: command.to_string(),
+            timeout_ms: Some(5_000),
+        }
+    }
+
+    #[test]
+    fn test_manifest_validation_rejects_bad_tools() {
+        assert!(manifest("service_catalog", "echo").validate().is_ok());
+        assert!(manifest("read_lines", "echo").validate().is_err());
+        assert!(manifest("has space", "echo").validate().is_err());
+        assert!(manifest("catalog", " ").validate().is_err());
+        let mut no_schema = manifest("catalog", "echo");
+        no_schema.parameters = serde_json::json!({ "type": "string" });
+        assert!(no_schema.validate().is_err());
+    }
+
+    #[test]
+    fn test_registered_tool_is_offered_and_runs_with_arguments() {
+        let dir = tempfile::tempdir().unwrap();
+        register(manifest(
+            "catalog_lookup_test",
+            r#"test -z "$CEREBRAS_API_KEY" && echo "owner of $COSMOS_TOOL_ARGUMENTS""#,
+        ))
+        .unwrap();
+
+        assert!(definitions()
+            .iter()
+            .any(|tool| tool.function.name == "catalog_lookup_test"));
+
+        let output = execute(
+            dir.path(),
+            "catalog_lookup_test",
+            r#"{"service":"billing"}"#,
+        );
+        assert_eq!(output.as_deref(), Some(r#"owner of {"service":"billing"}"#));
+        assert!(execute(dir.path(), "catalog_lookup_test", "[1]")
+            .unwrap()
+            .contains("JSON object"));
+        assert!(unregister("catalog_lookup_test"));
+        assert!(execute(dir.path(), "catalog_lookup_test", "{}").is_none());
+    }
+
+    #[test]
+    fn test_repo_tools_need_trust_and_report_failures() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join(".cosmos")).unwrap();
+        std::fs::write(
+            dir.path().join(".cosmos/config.toml"),
+            "[[tools]]\nname = \"flaky_repo_tool\"\ndescription = \"Always fails\"\ncommand = \"echo down >&2; exit 1\"\n",
+        )
+        .unwrap();
+
+        let installed = install(dir.path(), false).unwrap();
+        assert_eq!(installed.untrusted, vec!["flaky_repo_tool".to_string()]);
+        assert!(execute(dir.path(), "flaky_repo_tool", "{}").is_none());
+
+        let installed = install(dir.path(), true).unwrap();
+        assert_eq!(installed.active, vec!["flaky_repo_tool".to_string()]);
+        let output = execute(dir.path(), "flaky_repo_tool", "").unwrap();
+        assert!(output.contains("exited with an error: down"), "{}", output);
+
+        std::fs::write(dir.path().join(".cosmos/config.toml"), "").unwrap();
+        install(dir.path(), true).unwrap();
+    }
+}
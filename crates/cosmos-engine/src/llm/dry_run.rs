@@ -0,0 +1,191 @@
+//! Dry-run planning
+//!
+//! Walks the suggestion and apply pipelines without calling the model so users can see
+//! which requests a run would make, roughly how many tokens each would send, and which
+//! files an apply would write. Costs are projected from rates observed in earlier runs
+//! (`.cosmos/v2/pipeline_metrics.jsonl`); we never hardcode provider pricing.
+
+use cosmos_adapters::cache::PipelineMetricRecord;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// Rough characters-per-token ratio for prompt text.
+const CHARS_PER_TOKEN: usize = 4;
+
+/// Assumed completion size for one request when nothing better is known.
+pub const COMPLETION_TOKENS_PER_REQUEST_ESTIMATE: u32 = 1_200;
+
+/// Token estimate for `chars` characters of prompt text.
+pub fn estimate_tokens(chars: usize) -> u32 {
+    chars.div_ceil(CHARS_PER_TOKEN).min(u32::MAX as usize) as u32
+}
+
+/// One model call (or bounded agentic loop) a run would make.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedLlmCall {
+    /// Pipeline stage: `suggest` or `apply`.
+    pub stage: String,
+    /// What the call does, e.g. `attempt 1 bug_hunter` or `review`.
+    pub label: String,
+    pub model: String,
+    /// Upper bound on requests (agentic iterations or repair loops).
+    pub max_requests: usize,
+    /// Packed prompt size per request; tool output and history are not included.
+    pub prompt_tokens_per_request: u32,
+    pub completion_tokens_per_request: u32,
+    /// Only made when an earlier step fails or the gate asks for another attempt.
+    pub conditional: bool,
+}
+
+impl PlannedLlmCall {
+    /// Upper-bound tokens across every request of this call.
+    pub fn max_tokens(&self) -> u64 {
+        (self.prompt_tokens_per_request as u64 + self.completion_tokens_per_request as u64)
+            * self.max_requests as u64
+    }
+}
+
+/// Token total for `calls`; the first value covers unconditional calls only.
+pub fn planned_token_range(calls: &[PlannedLlmCall]) -> (u64, u64) {
+    let expected = calls
+        .iter()
+        .filter(|call| !call.conditional)
+        .map(PlannedLlmCall::max_tokens)
+        .sum();
+    let worst = calls.iter().map(PlannedLlmCall::max_tokens).sum();
+    (expected, worst)
+}
+
+/// How a harness gate is expected to behave for a planned apply.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DryRunGateOutcome {
+    Pass,
+    Fail,
+    /// Depends on the generated change; only known once the apply runs.
+    AtRunTime,
+}
+
+impl DryRunGateOutcome {
+    pub fn label(self) -> &'static str {
+        match self {
+            DryRunGateOutcome::Pass => "pass",
+            DryRunGateOutcome::Fail => "fail",
+            DryRunGateOutcome::AtRunTime => "at run time",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DryRunGate {
+    pub gate: String,
+    pub outcome: DryRunGateOutcome,
+    pub detail: String,
+}
+
+/// What applying one suggestion would do.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApplyDryRun {
+    pub summary: String,
+    /// Files the harness is allowed to write for this suggestion.
+    pub files_written: Vec<PathBuf>,
+    pub calls: Vec<PlannedLlmCall>,
+    pub gates: Vec<DryRunGate>,
+    /// The harness stops spending once this is reached.
+    pub max_total_cost_usd: f64,
+    pub max_total_ms: u64,
+}
+
+/// Cost rates observed in earlier runs of this repo.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ObservedCostRates {
+    /// Number of priced runs the rates were derived from.
+    pub samples: usize,
+    pub usd_per_token: Option<f64>,
+    /// Average cost per run, keyed by pipeline stage, with the sample count.
+    pub avg_cost_by_stage: BTreeMap<String, (usize, f64)>,
+}
+
+impl ObservedCostRates {
+    pub fn from_pipeline_metrics(records: &[PipelineMetricRecord]) -> Self {
+        let priced = records
+            .iter()
+            .filter(|record| record.tokens > 0 && record.cost > 0.0)
+            .collect::<Vec<_>>();
+        let tokens = priced
+            .iter()
+            .map(|record| record.tokens as u64)
+            .sum::<u64>();
+        let cost = priced.iter().map(|record| record.cost).sum::<f64>();
+        let mut totals: BTreeMap<String, (usize, f64)> = BTreeMap::new();
+        for record in &priced {
+            let entry = totals.entry(record.stage.clone()).or_default();
+            entry.0 += 1;
+            entry.1 += record.cost;
+        }
+        Self {
+            samples: priced.len(),
+            usd_per_token: (tokens > 0).then(|| cost / tokens as f64),
+            avg_cost_by_stage: totals
+                .into_iter()
+                .map(|(stage, (count, total))| (stage, (count, total / count as f64)))
+                .collect(),
+        }
+    }
+
+    /// Projected cost for `tokens`, or `None` without priced history.
+    pub fn project(&self, tokens: u64) -> Option<f64> {
+        self.usd_per_token.map(|rate| rate * tokens as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn metric(stage: &str, tokens: u32, cost: f64) -> PipelineMetricRecord {
+        PipelineMetricRecord {
+            timestamp: Utc::now(),
+            stage: stage.to_string(),
+            suggest_ms: None,
+            verify_ms: None,
+            apply_ms: None,
+            review_ms: None,
+            tokens,
+            cost,
+            gate: "ok".to_string(),
+            passed: true,
+        }
+    }
+
+    #[test]
+    fn observed_rates_ignore_unpriced_runs() {
+        let rates = ObservedCostRates::from_pipeline_metrics(&[
+            metric("suggest", 10_000, 0.02),
+            metric("suggest", 30_000, 0.06),
+            metric("apply", 5_000, 0.0),
+        ]);
+        assert_eq!(rates.samples, 2);
+        assert_eq!(rates.avg_cost_by_stage.get("suggest"), Some(&(2, 0.04)));
+        assert!(!rates.avg_cost_by_stage.contains_key("apply"));
+        let projected = rates.project(1_000).unwrap();
+        assert!((projected - 0.002).abs() < 1e-9);
+
+        assert_eq!(ObservedCostRates::default().project(1_000), None);
+    }
+
+    #[test]
+    fn token_range_separates_conditional_calls() {
+        let call = |conditional| PlannedLlmCall {
+            stage: "apply".to_string(),
+            label: "generation".to_string(),
+            model: "m".to_string(),
+            max_requests: 2,
+            prompt_tokens_per_request: 100,
+            completion_tokens_per_request: 50,
+            conditional,
+        };
+        assert_eq!(planned_token_range(&[call(false), call(true)]), (300, 600));
+        assert_eq!(estimate_tokens(9), 3);
+    }
+}
@@ -0,0 +1,333 @@
+//! Model failover chains with health tracking.
+//!
+//! A role's route can list `fallbacks`: model ids tried in order when the model in front of
+//! them fails with a provider-side error (outage, server error, timeout, or rate limit after
+//! the client's own retries). Errors the next model would hit too, like a bad API key or a
+//! rejected request, end the chain. Cosmos talks to one backend, so a chain is a list of
+//! models on it.
+//!
+//! Every failover-worthy failure counts against its model. A model that fails
+//! [`FLAP_THRESHOLD`] times within [`FLAP_WINDOW`] is skipped for [`COOLDOWN`], so a
+//! flapping model stops costing every request a timeout; a success clears its record. When
+//! every model in a chain is cooling down the full chain is tried anyway.
+//!
+//! Models the provider preflight found unlisted are left out of every chain, so a role whose
+//! primary is retired starts on its first listed fallback. A chain with no listed model at
+//! all is tried in full.
+
+use super::client::{
+    backend_slug, classify_provider_error, provider_outcome_kind, ProviderFailureKind,
+};
+use super::routing;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Failures within [`FLAP_WINDOW`] that mark a model as flapping
+pub const FLAP_THRESHOLD: usize = 3;
+/// How far back failures count toward [`FLAP_THRESHOLD`]
+pub const FLAP_WINDOW: Duration = Duration::from_secs(120);
+/// How long a flapping model is skipped
+pub const COOLDOWN: Duration = Duration::from_secs(60);
+
+tokio::task_local! {
+    static RUN_ATTEMPTS: Arc<Mutex<Vec<FailoverAttempt>>>;
+}
+
+static HEALTH: Mutex<BTreeMap<String, ModelHealth>> = Mutex::new(BTreeMap::new());
+
+static UNAVAILABLE: Mutex<BTreeSet<String>> = Mutex::new(BTreeSet::new());
+
+#[derive(Debug, Default)]
+struct ModelHealth {
+    failures: VecDeque<Instant>,
+    skip_until: Option<Instant>,
+}
+
+/// One model tried for a request
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct FailoverAttempt {
+    pub model: String,
+    pub elapsed_ms: u64,
+    /// `success`, or the failure kind (`timeout`, `rate_limited`, `server_error`, ...)
+    pub outcome_kind: String,
+    pub error: Option<String>,
+}
+
+/// Send `request` to each model in the current role's chain until one succeeds.
+///
+/// `send` gets the request as JSON with `model` set to the model being tried.
+pub(crate) async fn send<T, R, F, Fut>(request: &T, mut send: F) -> anyhow::Result<R>
+where
+    T: Serialize,
+    F: FnMut(serde_json::Value) -> Fut,
+    Fut: Future<Output = anyhow::Result<R>>,
+{
+    let body = serde_json::to_value(request)?;
+    let Some(primary) = body.get("model").and_then(|m| m.as_str()).map(String::from) else {
+        return send(body).await;
+    };
+    let fallbacks = routing::routed_fallbacks();
+    let chain = chain(&primary, &fallbacks, Instant::now());
+    for model in std::iter::once(&primary).chain(&fallbacks) {
+        if !chain.contains(model) && is_unavailable(model) {
+            note_attempt(model, 0, "unavailable", Some("not listed by the provider"));
+        }
+    }
+    send_through(body, &chain, send).await
+}
+
+async fn send_through<R, F, Fut>(
+    mut body: serde_json::Value,
+    chain: &[String],
+    mut send: F,
+) -> anyhow::Result<R>
+where
+    F: FnMut(serde_json::Value) -> Fut,
+    Fut: Future<Output = anyhow::Result<R>>,
+{
+    let mut tried: Vec<String> = Vec::new();
+    for (i, model) in chain.iter().enumerate() {
+        body["model"] = serde_json::Value::String(model.clone());
+        let started = Instant::now();
+        let result = send(body.clone()).await;
+        let elapsed_ms = started.elapsed().as_millis() as u64;
+        let err = match result {
+            Ok(response) => {
+                record_success(model);
+                note_attempt(model, elapsed_ms, "success", None);
+                return Ok(response);
+            }
+            Err(err) => err,
+        };
+
+        let message = err.to_string();
+        let kind = classify_provider_error(&message);
+        note_attempt(
+            model,
+            elapsed_ms,
+            provider_outcome_kind(kind),
+            Some(&message),
+        );
+        if kind == ProviderFailureKind::Other {
+            return Err(with_tried(err, &tried));
+        }
+        record_failure(model, Instant::now());
+        if i + 1 == chain.len() {
+            return Err(with_tried(err, &tried));
+        }
+        tried.push(model.clone());
+    }
+    unreachable!("a failover chain always has at least one model")
+}
+
+fn with_tried(err: anyhow::Error, tried: &[String]) -> anyhow::Error {
+    if tried.is_empty() {
+        err
+    } else {
+        // Keep the last model's error first: callers match on its text
+        anyhow::anyhow!(
+            "{} (after failing over from {} on {})",
+            err,
+            tried.join(", "),
+            backend_slug()
+        )
+    }
+}
+
+/// Replace the set of models the provider does not list (from the preflight).
+pub(crate) fn mark_unavailable(models: Vec<String>) {
+    if let Ok(mut unavailable) = UNAVAILABLE.lock() {
+        *unavailable = models.into_iter().collect();
+    }
+}
+
+fn is_unavailable(model: &str) -> bool {
+    UNAVAILABLE
+        .lock()
+        .is_ok_and(|unavailable| unavailable.contains(model))
+}
+
+/// `primary` then `fallbacks`, without unlisted models or models cooling down (unless either
+/// leaves none).
+fn chain(primary: &str, fallbacks: &[String], now: Instant) -> Vec<String> {
+    let mut models = vec![primary.to_string()];
+    for fallback in fallbacks {
+        if !models.contains(fallback) {
+            models.push(fallback.clone());
+        }
+    }
+    let listed: Vec<String> = models
+        .iter()
+        .filter(|model| !is_unavailable(model))
+        .cloned()
+        .collect();
+    if !listed.is_empty() {
+        models = listed;
+    }
+    let Ok(health) = HEALTH.lock() else {
+        return models;
+    };
+    let healthy: Vec<String> = models
+        .iter()
+        .filter(|model| {
+            health
+                .get(*model)
+                .and_then(|h| h.skip_until)
+                .is_none_or(|until| until <= now)
+        })
+        .cloned()
+        .collect();
+    if healthy.is_empty() {
+        models
+    } else {
+        healthy
+    }
+}
+
+fn record_failure(model: &str, now: Instant) {
+    let Ok(mut health) = HEALTH.lock() else {
+        return;
+    };
+    let entry = health.entry(model.to_string()).or_default();
+    while entry
+        .failures
+        .front()
+        .is_some_and(|at| now.duration_since(*at) > FLAP_WINDOW)
+    {
+        entry.failures.pop_front();
+    }
+    entry.failures.push_back(now);
+    if entry.failures.len() >= FLAP_THRESHOLD {
+        entry.skip_until = Some(now + COOLDOWN);
+        entry.failures.clear();
+    }
+}
+
+fn record_success(model: &str) {
+    if let Ok(mut health) = HEALTH.lock() {
+        health.remove(model);
+    }
+}
+
+fn note_attempt(model: &str, elapsed_ms: u64, outcome_kind: &str, error: Option<&str>) {
+    let _ = RUN_ATTEMPTS.try_with(|attempts| {
+        if let Ok(mut attempts) = attempts.lock() {
+            attempts.push(FailoverAttempt {
+                model: model.to_string(),
+                elapsed_ms,
+                outcome_kind: outcome_kind.to_string(),
+                error: error.map(String::from),
+            });
+        }
+    });
+}
+
+/// Run `fut`, returning its output and every model tried while it ran.
+pub(crate) async fn record_attempts<F: Future>(fut: F) -> (F::Output, Vec<FailoverAttempt>) {
+    let attempts = Arc::new(Mutex::new(Vec::new()));
+    let output = RUN_ATTEMPTS.scope(Arc::clone(&attempts), fut).await;
+    let attempts = attempts.lock().map(|a| a.clone()).unwrap_or_default();
+    (output, attempts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flapping_models_are_skipped_until_their_cooldown_ends() {
+        let now = Instant::now();
+        let fallbacks = vec!["flap-b".to_string(), "flap-a".to_string()];
+        assert_eq!(chain("flap-a", &fallbacks, now), vec!["flap-a", "flap-b"]);
+
+        // Failures spread beyond the window never add up to a flap
+        record_failure("flap-a", now);
+        record_failure("flap-a", now + FLAP_WINDOW + Duration::from_secs(1));
+        assert_eq!(chain("flap-a", &fallbacks, now).len(), 2);
+
+        let later = now + FLAP_WINDOW + Duration::from_secs(2);
+        record_failure("flap-a", later);
+        record_failure("flap-a", later);
+        assert_eq!(chain("flap-a", &fallbacks, later), vec!["flap-b"]);
+        assert_eq!(
+            chain("flap-a", &fallbacks, later + COOLDOWN),
+            vec!["flap-a", "flap-b"]
+        );
+
+        // With the whole chain cooling down, everything is tried anyway
+        for _ in 0..FLAP_THRESHOLD {
+            record_failure("flap-b", later);
+        }
+        assert_eq!(chain("flap-a", &fallbacks, later), vec!["flap-a", "flap-b"]);
+
+        record_success("flap-a");
+        assert_eq!(chain("flap-a", &fallbacks, later), vec!["flap-a"]);
+        record_success("flap-b");
+    }
+
+    #[test]
+    fn unlisted_models_are_left_out_unless_none_are_listed() {
+        let now = Instant::now();
+        mark_unavailable(vec!["retired-a".to_string(), "retired-c".to_string()]);
+        let fallbacks = vec!["served-b".to_string()];
+        assert_eq!(chain("retired-a", &fallbacks, now), vec!["served-b"]);
+        assert_eq!(chain("served-b", &[], now), vec!["served-b"]);
+        assert_eq!(chain("retired-c", &[], now), vec!["retired-c"]);
+        mark_unavailable(Vec::new());
+        assert_eq!(
+            chain("retired-a", &fallbacks, now),
+            vec!["retired-a", "served-b"]
+        );
+    }
+
+    #[tokio::test]
+    async fn outages_fail_over_and_other_errors_stop_the_chain() {
+        let chain = vec!["outage-a".to_string(), "outage-b".to_string()];
+        let (result, attempts) = record_attempts(send_through(
+            serde_json::json!({ "model": "outage-a" }),
+            &chain,
+            |body| async move {
+                match body["model"].as_str() {
+                    Some("outage-b") => Ok("answer"),
+                    _ => Err(anyhow::anyhow!("Cerebras server error (503)")),
+                }
+            },
+        ))
+        .await;
+        assert_eq!(result.unwrap(), "answer");
+        let outcomes: Vec<(&str, &str)> = attempts
+            .iter()
+            .map(|a| (a.model.as_str(), a.outcome_kind.as_str()))
+            .collect();
+        assert_eq!(
+            outcomes,
+            vec![("outage-a", "server_error"), ("outage-b", "success")]
+        );
+
+        let err = send_through(
+            serde_json::json!({ "model": "outage-a" }),
+            &chain,
+            |_| async { Err::<(), _>(anyhow::anyhow!("Cerebras request timed out.")) },
+        )
+        .await
+        .unwrap_err();
+        assert!(err.to_string().starts_with("Cerebras request timed out."));
+        assert!(err.to_string().contains("failing over from outage-a"));
+        record_success("outage-a");
+        record_success("outage-b");
+
+        // A rejected request would be rejected by every model
+        let mut calls = Vec::new();
+        let result = send_through(serde_json::json!({ "model": "reject-a" }), &chain, |body| {
+            calls.push(body["model"].as_str().unwrap_or_default().to_string());
+            async { Err::<(), _>(anyhow::anyhow!("API error 400 Bad Request: nope")) }
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(calls, vec!["outage-a"]);
+        assert!(!HEALTH.lock().unwrap().contains_key("outage-a"));
+    }
+}
@@ -1,12 +1,16 @@
 use super::agentic::{call_llm_agentic, schema_to_response_format};
 use super::client::{
-    call_llm_structured_cached, call_llm_structured_limited_speed_with_failover,
+    call_llm_structured_cached, call_llm_structured_limited_speed_with_failover, parse_response,
     SpeedFailoverDiagnostics, StructuredResponse,
 };
 use super::models::{merge_usage, Model, Usage};
 use super::parse::{truncate_content, truncate_content_around_line};
 use super::prompt_utils::format_repo_memory_section;
-use super::prompts::{fix_content_system, multi_file_fix_system, FIX_PREVIEW_AGENTIC_SYSTEM};
+use super::prompts::{fix_content_system, fix_preview_system, multi_file_fix_system};
+use super::redaction::REDACTION_MARKER;
+use super::routing;
+use cosmos_adapters::config::ModelRole;
+use cosmos_core::protocol::schemas::ResponseKind;
 use cosmos_core::suggest::{Suggestion, SuggestionKind};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
@@ -365,7 +369,8 @@ fn is_retryable_edit_apply_error(message: &str) -> bool {
             || msg.contains("must be unique")
             || msg.contains("empty for non-empty")
             || msg.contains("placeholder")
-            || msg.contains("ellipsis"))
+            || msg.contains("ellipsis")
+            || msg.contains("redacted secret"))
 }
 
 fn searched_for_fragment(message: &str) -> Option<String> {
@@ -436,45 +441,6 @@ pub(crate) struct FixResponse {
     pub(crate) edits: Vec<EditOp>,
 }
 
-/// JSON Schema for FixResponse - used for structured output
-pub(crate) fn fix_response_schema() -> serde_json::Value {
-    json!({
-        "type": "object",
-        "properties": {
-            "description": {
-                "type": "string",
-                "description": "Brief description of what was changed"
-            },
-            "modified_areas": {
-                "type": "array",
-                "items": { "type": "string" },
-                "description": "List of functions/areas that were modified"
-            },
-            "edits": {
-                "type": "array",
-                "items": {
-                    "type": "object",
-                    "properties": {
-                        "old_string": {
-                            "type": "string",
-                            "description": "Exact text to find (must match exactly once)"
-                        },
-                        "new_string": {
-                            "type": "string",
-                            "description": "Replacement text"
-                        }
-                    },
-                    "required": ["old_string", "new_string"],
-                    "additionalProperties": false
-                },
-                "description": "Search/replace edit operations"
-            }
-        },
-        "required": ["edits"],
-        "additionalProperties": false
-    })
-}
-
 /// Call LLM with structured output, caching, and fallback for context limits
 ///
 /// Uses Cerebras's automatic prompt caching on supported models and can improve
@@ -566,6 +532,33 @@ pub async fn generate_fix_content_with_model(
     is_new_file: bool,
     model: Model,
     timeout_ms: u64,
+) -> anyhow::Result<AppliedFix> {
+    routing::with_role(
+        ModelRole::Implement,
+        generate_fix_content_with_model_impl(
+            path,
+            content,
+            suggestion,
+            plan,
+            repo_memory,
+            is_new_file,
+            model,
+            timeout_ms,
+        ),
+    )
+    .await
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn generate_fix_content_with_model_impl(
+    path: &Path,
+    content: &str,
+    suggestion: &Suggestion,
+    plan: &FixPreview,
+    repo_memory: Option<String>,
+    is_new_file: bool,
+    model: Model,
+    timeout_ms: u64,
 ) -> anyhow::Result<AppliedFix> {
     let plan_text = build_plan_text(
         plan,
@@ -635,8 +628,8 @@ pub async fn generate_fix_content_with_model(
             &user_full_attempt,
             &user_excerpt_attempt,
             model,
-            "fix_response",
-            fix_response_schema(),
+            &ResponseKind::Fix.schema_name(),
+            ResponseKind::Fix.schema(),
             prefer_full_prompt,
             max_tokens_for_fix_response(model),
             attempt_timeout_ms,
@@ -864,6 +857,15 @@ fn validate_edits_for_apply(
                 target_hint
             ));
         }
+        if edit.old_string.contains(REDACTION_MARKER) || edit.new_string.contains(REDACTION_MARKER)
+        {
+            return Err(anyhow::anyhow!(
+                "Edit {}: old_string/new_string includes a redacted secret ({}) in {}. Secrets are masked before you see them; anchor on nearby lines and leave the redacted line unchanged.",
+                i + 1,
+                REDACTION_MARKER,
+                context_label
+            ));
+        }
     }
     Ok(())
 }
@@ -1395,6 +1397,28 @@ pub async fn generate_multi_file_fix_with_model(
     repo_memory: Option<String>,
     model: Model,
     timeout_ms: u64,
+) -> anyhow::Result<MultiFileAppliedFix> {
+    routing::with_role(
+        ModelRole::Implement,
+        generate_multi_file_fix_with_model_impl(
+            files,
+            suggestion,
+            plan,
+            repo_memory,
+            model,
+            timeout_ms,
+        ),
+    )
+    .await
+}
+
+async fn generate_multi_file_fix_with_model_impl(
+    files: &[FileInput],
+    suggestion: &Suggestion,
+    plan: &FixPreview,
+    repo_memory: Option<String>,
+    model: Model,
+    timeout_ms: u64,
 ) -> anyhow::Result<MultiFileAppliedFix> {
     if files.is_empty() {
         return Err(anyhow::anyhow!("No files provided for multi-file fix"));
@@ -2028,65 +2052,6 @@ pub fn build_fix_preview_from_validated_suggestion(suggestion: &Suggestion) -> F
     }
 }
 
-/// JSON Schema for FixPreview - used for structured output on final agentic response
-/// This ensures the LLM returns valid, parseable JSON matching our expected format
-pub(crate) fn fix_preview_schema() -> serde_json::Value {
-    json!({
-        "type": "object",
-        "properties": {
-            "verified": {
-                "type": "boolean",
-                "description": "Whether the issue was verified to exist in the code"
-            },
-            "verification_state": {
-                "type": "string",
-                "enum": ["verified", "contradicted", "insufficient_evidence"],
-                "description": "Explicit verification contract result"
-            },
-            "friendly_title": {
-                "type": "string",
-                "description": "Friendly topic name for non-technical users"
-            },
-            "problem_summary": {
-                "type": "string",
-                "description": "Behavior-focused problem description"
-            },
-            "outcome": {
-                "type": "string",
-                "description": "What happens after the fix"
-            },
-            "verification_note": {
-                "type": "string",
-                "description": "Explanation of verification result"
-            },
-            "evidence_snippet": {
-                "type": ["string", "null"],
-                "description": "Code snippet that proves the claim"
-            },
-            "evidence_line": {
-                "type": ["integer", "null"],
-                "description": "Starting line number of the evidence snippet"
-            },
-            "description": {
-                "type": "string",
-                "description": "Human-readable description of what will change"
-            },
-            "affected_areas": {
-                "type": "array",
-                "items": { "type": "string" },
-                "description": "Which functions/areas are affected"
-            },
-            "scope": {
-                "type": "string",
-                "enum": ["small", "medium", "large"],
-                "description": "Estimated scope of the fix"
-            }
-        },
-        "required": ["verification_state", "friendly_title", "problem_summary", "outcome", "verification_note", "description", "affected_areas", "scope"],
-        "additionalProperties": false
-    })
-}
-
 /// Response structure for fix preview (for structured output parsing)
 #[derive(Debug, Clone, Deserialize)]
 pub(crate) struct FixPreviewJson {
@@ -2217,6 +2182,19 @@ pub async fn generate_fix_preview_agentic(
     suggestion: &Suggestion,
     modifier: Option<&str>,
     repo_memory: Option<String>,
+) -> anyhow::Result<(FixPreview, Option<Usage>)> {
+    routing::with_role(
+        ModelRole::Preview,
+        generate_fix_preview_agentic_impl(repo_root, suggestion, modifier, repo_memory),
+    )
+    .await
+}
+
+async fn generate_fix_preview_agentic_impl(
+    repo_root: &Path,
+    suggestion: &Suggestion,
+    modifier: Option<&str>,
+    repo_memory: Option<String>,
 ) -> anyhow::Result<(FixPreview, Option<Usage>)> {
     let modifier_text = modifier
         .map(|m| format!("\n\nUser modification request: {}", m))
@@ -2307,12 +2285,16 @@ VERIFY:
     let user = build_user_prompt(start, end, &code_section, false);
 
     // Use structured output to guarantee valid JSON response
-    let response_format = schema_to_response_format("fix_preview", fix_preview_schema());
+    let response_format = schema_to_response_format(
+        &ResponseKind::Preview.schema_name(),
+        ResponseKind::Preview.schema(),
+    );
 
     // Use Smart model for fix preview planning
     // 3 iterations - code already provided, minimal exploration needed
+    let system = fix_preview_system();
     let response = call_llm_agentic(
-        FIX_PREVIEW_AGENTIC_SYSTEM,
+        &system,
         &user,
         Model::Smart,
         repo_root,
@@ -2322,14 +2304,14 @@ VERIFY:
     )
     .await?;
 
-    // Response is guaranteed to be valid JSON matching the schema
-    let parsed: FixPreviewJson = serde_json::from_str(&response.content).map_err(|e| {
-        anyhow::anyhow!(
-            "Failed to parse fix preview response: {}. Content: {}",
-            e,
-            &response.content.chars().take(200).collect::<String>()
-        )
-    })?;
+    let parsed: FixPreviewJson =
+        parse_response(ResponseKind::Preview, &response.content).map_err(|e| {
+            anyhow::anyhow!(
+                "Failed to parse fix preview response: {}. Content: {}",
+                e,
+                &response.content.chars().take(200).collect::<String>()
+            )
+        })?;
 
     let mut preview = fix_preview_from_json(parsed, modifier);
     let mut usage = response.usage;
@@ -2339,7 +2321,7 @@ VERIFY:
         let (fallback_start, fallback_end, fallback_code) = render_excerpt(target_line, 120, 160);
         let fallback_user = build_user_prompt(fallback_start, fallback_end, &fallback_code, true);
         if let Ok(fallback_response) = call_llm_agentic(
-            FIX_PREVIEW_AGENTIC_SYSTEM,
+            &system,
             &fallback_user,
             Model::Smart,
             repo_root,
@@ -2351,7 +2333,7 @@ VERIFY:
         {
             usage = merge_usage(usage, fallback_response.usage);
             if let Ok(parsed_fallback) =
-                serde_json::from_str::<FixPreviewJson>(&fallback_response.content)
+                parse_response::<FixPreviewJson>(ResponseKind::Preview, &fallback_response.content)
             {
                 preview = fix_preview_from_json(parsed_fallback, modifier);
             }
@@ -2966,6 +2948,17 @@ fn b() {\n\
         validate_edits_for_apply(&edits, "file", None).unwrap();
     }
 
+    #[test]
+    fn test_validate_edits_rejects_redacted_secret_marker() {
+        let edits = vec![EditOp {
+            old_string: "API_TOKEN=[REDACTED_SECRET]\nDEBUG=true".to_string(),
+            new_string: "API_TOKEN=[REDACTED_SECRET]\nDEBUG=false".to_string(),
+        }];
+        let err = validate_edits_for_apply(&edits, "file", None).unwrap_err();
+        assert!(err.to_string().contains("redacted secret"));
+        assert!(is_retryable_edit_apply_error(&err.to_string()));
+    }
+
     #[test]
     fn test_validate_edits_rejects_delimiter_only_anchor() {
         let edits = vec![EditOp {
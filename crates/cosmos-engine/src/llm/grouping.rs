@@ -1,7 +1,9 @@
 use super::client::{call_llm_structured, StructuredResponse};
 use super::models::{Model, Usage};
 use super::prompts::GROUPING_CLASSIFY_SYSTEM;
+use super::routing;
 use cosmos_adapters::cache::normalize_cache_path;
+use cosmos_adapters::config::ModelRole;
 use cosmos_core::grouping::Layer;
 use cosmos_core::index::{CodebaseIndex, Language};
 use serde::{Deserialize, Serialize};
@@ -68,6 +70,17 @@ fn grouping_ai_response_schema() -> serde_json::Value {
 pub async fn classify_grouping_candidates(
     index: &CodebaseIndex,
     candidates: &[PathBuf],
+) -> anyhow::Result<(Vec<GroupingAiSuggestion>, Option<Usage>)> {
+    routing::with_role(
+        ModelRole::Summarize,
+        classify_grouping_candidates_impl(index, candidates),
+    )
+    .await
+}
+
+async fn classify_grouping_candidates_impl(
+    index: &CodebaseIndex,
+    candidates: &[PathBuf],
 ) -> anyhow::Result<(Vec<GroupingAiSuggestion>, Option<Usage>)> {
     if candidates.is_empty() {
         return Ok((Vec::new(), None));
@@ -163,6 +176,10 @@ fn language_label(language: Language) -> &'static str {
         Language::TypeScript => "typescript",
         Language::Python => "python",
         Language::Go => "go",
+        Language::Java => "java",
+        Language::Kotlin => "kotlin",
+        Language::C => "c",
+        Language::Cpp => "cpp",
         Language::Unknown => "unknown",
     }
 }
@@ -21,3 +21,11 @@ pub(super) fn default_enable_quick_check_baseline() -> bool {
 pub(super) fn default_require_independent_review_on_pass() -> bool {
     true
 }
+
+pub(super) fn default_quick_check_required() -> bool {
+    true
+}
+
+pub(super) fn default_max_auto_test_fix_loops() -> usize {
+    1
+}
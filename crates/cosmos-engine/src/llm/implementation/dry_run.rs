@@ -0,0 +1,270 @@
+use super::quick_checks::{command_to_string, planned_quick_check_command};
+use super::test_gate::render_test_command;
+use super::{
+    is_binary_extension, ImplementationHarnessConfig, ImplementationQuickChecksMode,
+    IMPLEMENTATION_MODEL,
+};
+use crate::llm::dry_run::{
+    estimate_tokens, ApplyDryRun, DryRunGate, DryRunGateOutcome, PlannedLlmCall,
+    COMPLETION_TOKENS_PER_REQUEST_ESTIMATE,
+};
+use crate::llm::prompts::{
+    fix_content_system, multi_file_fix_system, review_fix_system_prompt, review_system_prompt,
+};
+use crate::llm::routing;
+use cosmos_adapters::config::ModelRole;
+use cosmos_adapters::util::resolve_repo_path_allow_new;
+use cosmos_core::suggest::Suggestion;
+use std::path::{Path, PathBuf};
+
+fn gate(name: &str, outcome: DryRunGateOutcome, detail: String) -> DryRunGate {
+    DryRunGate {
+        gate: name.to_string(),
+        outcome,
+        detail,
+    }
+}
+
+fn apply_call(
+    label: &str,
+    model: &str,
+    max_requests: usize,
+    prompt_chars: usize,
+) -> PlannedLlmCall {
+    PlannedLlmCall {
+        stage: "apply".to_string(),
+        label: label.to_string(),
+        model: model.to_string(),
+        max_requests,
+        prompt_tokens_per_request: estimate_tokens(prompt_chars),
+        completion_tokens_per_request: COMPLETION_TOKENS_PER_REQUEST_ESTIMATE,
+        conditional: true,
+    }
+}
+
+/// Plan what `implement_validated_suggestion_with_harness` would do for `suggestion`,
+/// without creating a sandbox or calling the model.
+///
+/// Gates that depend on the generated change are reported as decided at run time.
+pub fn plan_harness_apply(
+    repo_root: &Path,
+    suggestion: &Suggestion,
+    config: &ImplementationHarnessConfig,
+) -> ApplyDryRun {
+    let files_written = suggestion
+        .affected_files()
+        .into_iter()
+        .cloned()
+        .collect::<Vec<PathBuf>>();
+    let mut gates = Vec::new();
+
+    let unsafe_files = files_written
+        .iter()
+        .filter(|file| resolve_repo_path_allow_new(repo_root, file).is_err())
+        .map(|file| file.display().to_string())
+        .collect::<Vec<_>>();
+    gates.push(if unsafe_files.is_empty() {
+        gate(
+            "path_safety",
+            DryRunGateOutcome::Pass,
+            "all files are inside the repo".to_string(),
+        )
+    } else {
+        gate(
+            "path_safety",
+            DryRunGateOutcome::Fail,
+            format!("outside the repo: {}", unsafe_files.join(", ")),
+        )
+    });
+
+    let scope_outcome = if files_written.len() <= config.max_changed_files {
+        DryRunGateOutcome::Pass
+    } else {
+        DryRunGateOutcome::Fail
+    };
+    gates.push(gate(
+        "scope",
+        scope_outcome,
+        format!(
+            "{}/{} files; line limits ({} total, {} per file) checked at run time",
+            files_written.len(),
+            config.max_changed_files,
+            config.max_total_changed_lines,
+            config.max_changed_lines_per_file
+        ),
+    ));
+
+    let binary_files = files_written
+        .iter()
+        .filter(|file| is_binary_extension(file))
+        .map(|file| file.display().to_string())
+        .collect::<Vec<_>>();
+    gates.push(if binary_files.is_empty() {
+        gate(
+            "binary_write",
+            DryRunGateOutcome::Pass,
+            "no binary file types".to_string(),
+        )
+    } else {
+        gate(
+            "binary_write",
+            DryRunGateOutcome::Fail,
+            format!("binary file types: {}", binary_files.join(", ")),
+        )
+    });
+
+    gates.push(match &config.license_gate {
+        Some(license) => gate(
+            "license",
+            DryRunGateOutcome::AtRunTime,
+            if license.insert_missing {
+                "missing headers will be inserted; denylist checked on added lines".to_string()
+            } else {
+                "changed files must carry the configured header".to_string()
+            },
+        ),
+        None => gate(
+            "license",
+            DryRunGateOutcome::Pass,
+            "no license policy configured".to_string(),
+        ),
+    });
+
+    let quick_checks_enabled = config.quick_checks_mode != ImplementationQuickChecksMode::Disabled;
+    let quick_check = quick_checks_enabled
+        .then(|| planned_quick_check_command(repo_root, &config.quick_checks))
+        .flatten();
+    gates.push(match (&quick_check, quick_checks_enabled) {
+        (_, false) => gate(
+            "quick_check",
+            DryRunGateOutcome::Pass,
+            "quick checks disabled".to_string(),
+        ),
+        (Some(command), true) => gate(
+            "quick_check",
+            DryRunGateOutcome::AtRunTime,
+            format!("runs `{}`", command_to_string(command)),
+        ),
+        (None, true) if config.require_quick_check_detectable => gate(
+            "quick_check",
+            DryRunGateOutcome::Fail,
+            "no quick-check command detected and this profile requires one".to_string(),
+        ),
+        (None, true) => gate(
+            "quick_check",
+            DryRunGateOutcome::Pass,
+            "no quick-check command detected (reduced confidence)".to_string(),
+        ),
+    });
+
+    if let Some(tests) = &config.test_gate {
+        gates.push(gate(
+            "tests",
+            DryRunGateOutcome::AtRunTime,
+            format!("runs `{}`", render_test_command(tests, &files_written)),
+        ));
+    }
+
+    gates.push(gate(
+        "review",
+        DryRunGateOutcome::AtRunTime,
+        format!(
+            "blocks on {} findings",
+            config.review_blocking_severities.join("/")
+        ),
+    ));
+
+    let file_chars = files_written
+        .iter()
+        .filter_map(|file| resolve_repo_path_allow_new(repo_root, file).ok())
+        .filter_map(|resolved| std::fs::metadata(resolved.absolute).ok())
+        .map(|meta| meta.len() as usize)
+        .sum::<usize>();
+    let suggestion_chars = suggestion.summary.len()
+        + suggestion.detail.as_deref().map(str::len).unwrap_or(0)
+        + suggestion.evidence.as_deref().map(str::len).unwrap_or(0);
+    let fix_system = if suggestion.is_multi_file() {
+        multi_file_fix_system()
+    } else {
+        fix_content_system()
+    };
+    let generation_chars = fix_system.len() + file_chars + suggestion_chars;
+    // The reviewer sees both the original and the updated content.
+    let review_chars = review_system_prompt(1, &[], None).len() + file_chars * 2;
+    let review_fix_chars = review_fix_system_prompt(2, &[]).len() + file_chars * 2;
+
+    let model = &routing::model_id_for_role(ModelRole::Implement, IMPLEMENTATION_MODEL);
+    let review_model = &routing::model_id_for_role(
+        ModelRole::Review,
+        config.adversarial_review_model.as_model(),
+    );
+    let mut attempt_calls = vec![
+        PlannedLlmCall {
+            conditional: false,
+            ..apply_call("generation", model, 1, generation_chars)
+        },
+        apply_call(
+            "smart escalation",
+            model,
+            config.max_smart_escalations_per_attempt,
+            generation_chars,
+        ),
+        apply_call(
+            "syntax repair",
+            model,
+            config.max_auto_syntax_fix_loops,
+            generation_chars,
+        ),
+    ];
+    if quick_check.is_some() {
+        attempt_calls.push(apply_call(
+            "quick-check repair",
+            model,
+            config.max_auto_quick_check_fix_loops,
+            generation_chars,
+        ));
+    }
+    if let Some(tests) = &config.test_gate {
+        attempt_calls.push(apply_call(
+            "test repair",
+            model,
+            tests.max_fix_loops,
+            generation_chars,
+        ));
+    }
+    attempt_calls.push(PlannedLlmCall {
+        conditional: false,
+        ..apply_call("review", review_model, 1, review_chars)
+    });
+    // Each review-fix loop is one fix followed by one re-review.
+    attempt_calls.push(apply_call(
+        "review fix loop",
+        review_model,
+        config.max_auto_review_fix_loops * 2,
+        review_fix_chars,
+    ));
+    attempt_calls.retain(|call| call.max_requests > 0);
+
+    let requests_per_attempt = attempt_calls
+        .iter()
+        .map(|call| call.max_requests)
+        .sum::<usize>();
+    let mut calls = attempt_calls;
+    if config.max_attempts > 1 {
+        calls.push(apply_call(
+            &format!("attempts 2-{}", config.max_attempts),
+            model,
+            (config.max_attempts - 1) * requests_per_attempt,
+            generation_chars,
+        ));
+    }
+
+    ApplyDryRun {
+        summary: suggestion.summary.clone(),
+        files_written,
+        calls,
+        gates,
+        max_total_cost_usd: config.max_total_cost_usd,
+        max_total_ms: config.max_total_ms,
+    }
+}
@@ -0,0 +1,265 @@
+use chrono::Datelike;
+use cosmos_adapters::util::{resolve_repo_path_allow_new, truncate};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// Markers that usually mean a snippet was copied from copyleft or otherwise
+/// incompatibly licensed source. Matched case-insensitively against added lines.
+const DEFAULT_LICENSE_DENYLIST: &[&str] = &[
+    "gnu general public license",
+    "gnu lesser general public license",
+    "gnu affero general public license",
+    "spdx-license-identifier: gpl",
+    "spdx-license-identifier: lgpl",
+    "spdx-license-identifier: agpl",
+    "this program is free software; you can redistribute it",
+    "creative commons attribution-sharealike",
+];
+
+/// Lines at the top of a file searched for the required header.
+const HEADER_SEARCH_EXTRA_LINES: usize = 10;
+
+/// Optional license/header policy, read from the `[license]` table of `.cosmos/config.toml`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct LicenseGateConfig {
+    /// Required header text (without comment markers). `{year}` matches any year and is
+    /// rendered as the current year on insert. Empty disables the header check.
+    pub header: String,
+    /// Extensions the header applies to; empty means every extension with a known comment style.
+    pub extensions: Vec<String>,
+    /// Insert the header into changed files that lack it instead of failing.
+    pub insert_missing: bool,
+    /// Extra denylist markers (case-insensitive) checked in addition to the built-in list.
+    pub denylist: Vec<String>,
+    /// Skip the built-in denylist markers.
+    pub disable_default_denylist: bool,
+}
+
+impl LicenseGateConfig {
+    fn header_lines(&self) -> Vec<&str> {
+        self.header
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect()
+    }
+
+    fn applies_to(&self, file: &Path) -> Option<&'static str> {
+        let ext = file
+            .extension()
+            .and_then(|e| e.to_str())?
+            .to_ascii_lowercase();
+        if !self.extensions.is_empty()
+            && !self
+                .extensions
+                .iter()
+                .any(|allowed| allowed.trim_start_matches('.').eq_ignore_ascii_case(&ext))
+        {
+            return None;
+        }
+        line_comment_prefix(&ext)
+    }
+
+    fn denylist_markers(&self) -> Vec<String> {
+        let builtin = if self.disable_default_denylist {
+            &[][..]
+        } else {
+            DEFAULT_LICENSE_DENYLIST
+        };
+        builtin
+            .iter()
+            .map(|marker| marker.to_string())
+            .chain(
+                self.denylist
+                    .iter()
+                    .map(|marker| marker.trim().to_lowercase()),
+            )
+            .filter(|marker| !marker.is_empty())
+            .collect()
+    }
+}
+
+fn line_comment_prefix(ext: &str) -> Option<&'static str> {
+    match ext {
+        "rs" | "ts" | "tsx" | "js" | "jsx" | "mjs" | "cjs" | "go" | "java" | "kt" | "kts"
+        | "swift" | "c" | "h" | "cc" | "cpp" | "cxx" | "hpp" | "hh" | "cs" | "scala" | "dart"
+        | "php" => Some("//"),
+        "py" | "rb" | "sh" | "bash" | "zsh" | "toml" | "yaml" | "yml" | "pl" | "r" => Some("#"),
+        "sql" | "lua" | "hs" => Some("--"),
+        _ => None,
+    }
+}
+
+fn strip_comment_markers(line: &str) -> &str {
+    let mut rest = line.trim();
+    for marker in ["///", "//!", "//", "/*", "*/", "#", "--", "*"] {
+        if let Some(stripped) = rest.strip_prefix(marker) {
+            rest = stripped;
+            break;
+        }
+    }
+    rest.trim_end_matches("*/").trim()
+}
+
+fn header_line_pattern(line: &str) -> Option<Regex> {
+    let escaped = regex::escape(line).replace(r"\{year\}", r"\d{4}");
+    Regex::new(&format!("^{}$", escaped)).ok()
+}
+
+/// True when the configured header appears (in order) near the top of `content`.
+pub(super) fn has_license_header(content: &str, config: &LicenseGateConfig) -> bool {
+    let header = config.header_lines();
+    if header.is_empty() {
+        return true;
+    }
+    let patterns = header
+        .iter()
+        .map(|line| header_line_pattern(line))
+        .collect::<Option<Vec<_>>>();
+    let Some(patterns) = patterns else {
+        return false;
+    };
+    let mut next = 0usize;
+    for line in content
+        .lines()
+        .take(header.len() + HEADER_SEARCH_EXTRA_LINES)
+    {
+        if patterns[next].is_match(strip_comment_markers(line)) {
+            next += 1;
+            if next == patterns.len() {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+/// Prepend the configured header, keeping a shebang line first.
+pub(super) fn insert_license_header(
+    content: &str,
+    config: &LicenseGateConfig,
+    prefix: &str,
+) -> String {
+    let year = chrono::Utc::now().year().to_string();
+    let mut header = String::new();
+    for line in config.header.trim().lines() {
+        let line = line.trim().replace("{year}", &year);
+        if line.is_empty() {
+            header.push_str(prefix);
+        } else {
+            header.push_str(&format!("{} {}", prefix, line));
+        }
+        header.push('\n');
+    }
+    header.push('\n');
+
+    match content.strip_prefix("#!") {
+        Some(_) => {
+            let (shebang, rest) = content.split_once('\n').unwrap_or((content, ""));
+            format!("{}\n{}{}", shebang, header, rest)
+        }
+        None => format!("{}{}", header, content),
+    }
+}
+
+/// Denylist markers found in lines that are new relative to `original`.
+pub(super) fn denylisted_markers_in_added_lines(
+    original: Option<&str>,
+    updated: &str,
+    config: &LicenseGateConfig,
+) -> Vec<String> {
+    let markers = config.denylist_markers();
+    if markers.is_empty() {
+        return Vec::new();
+    }
+    let existing = original
+        .map(|content| content.lines().map(str::trim).collect::<HashSet<_>>())
+        .unwrap_or_default();
+    // The org's own header may legitimately mention a license.
+    let header_patterns = config
+        .header_lines()
+        .into_iter()
+        .filter_map(header_line_pattern)
+        .collect::<Vec<_>>();
+    let mut found = Vec::new();
+    for line in updated.lines().map(str::trim) {
+        if existing.contains(line)
+            || header_patterns
+                .iter()
+                .any(|pattern| pattern.is_match(strip_comment_markers(line)))
+        {
+            continue;
+        }
+        let lowered = line.to_lowercase();
+        for marker in &markers {
+            if lowered.contains(marker.as_str()) && !found.contains(marker) {
+                found.push(marker.clone());
+            }
+        }
+    }
+    found
+}
+
+/// Check changed files against the license policy, inserting headers when configured.
+///
+/// `original_root` is the untouched repo used to tell generated lines from existing ones.
+/// Returns the files that received an inserted header.
+pub(super) fn license_gate(
+    sandbox_root: &Path,
+    original_root: &Path,
+    changed_files: &[PathBuf],
+    config: &LicenseGateConfig,
+) -> Result<Vec<PathBuf>, String> {
+    let mut inserted = Vec::new();
+    let mut violations = Vec::new();
+    for file in changed_files {
+        let resolved = resolve_repo_path_allow_new(sandbox_root, file)
+            .map_err(|e| format!("Unsafe changed file {}: {}", file.display(), e))?;
+        let content = match std::fs::read_to_string(&resolved.absolute) {
+            Ok(content) => content,
+            // Deleted files can't carry headers or copied code.
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(format!("Failed reading {}: {}", file.display(), e)),
+        };
+        let original = resolve_repo_path_allow_new(original_root, file)
+            .ok()
+            .and_then(|original| std::fs::read_to_string(original.absolute).ok());
+
+        let markers = denylisted_markers_in_added_lines(original.as_deref(), &content, config);
+        if !markers.is_empty() {
+            violations.push(format!(
+                "{} contains licensed-source markers ({})",
+                file.display(),
+                truncate(&markers.join(", "), 120)
+            ));
+            continue;
+        }
+
+        let Some(prefix) = config.applies_to(file) else {
+            continue;
+        };
+        if has_license_header(&content, config) {
+            continue;
+        }
+        if config.insert_missing {
+            let updated = insert_license_header(&content, config, prefix);
+            std::fs::write(&resolved.absolute, updated)
+                .map_err(|e| format!("Failed inserting header into {}: {}", file.display(), e))?;
+            inserted.push(file.clone());
+        } else {
+            violations.push(format!(
+                "{} is missing the required license header",
+                file.display()
+            ));
+        }
+    }
+
+    if violations.is_empty() {
+        Ok(inserted)
+    } else {
+        Err(violations.join("; "))
+    }
+}
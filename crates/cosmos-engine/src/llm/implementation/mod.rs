@@ -4,12 +4,19 @@ use super::fix::{
     FixGenerationErrorWithUsage, FixPreview,
 };
 use super::models::{merge_usage, Model, Usage};
+use super::redaction;
+use super::response_cache::{self, ResponseCacheStats};
 use super::review::{
     fix_review_findings_with_model, verify_changes_bounded_with_model, FixContext, ReviewFinding,
 };
+use super::routing;
+use super::spend::{self, ModelDowngrade};
+use super::tokens::{self, ContextBudget};
 use crate::lab::sandbox::SandboxSession;
 use chrono::Utc;
+use cosmos_adapters::analytics;
 use cosmos_adapters::cache::{Cache, ImplementationHarnessRecord};
+use cosmos_adapters::config::ModelRole;
 use cosmos_adapters::git_ops;
 use cosmos_adapters::util::{resolve_repo_path_allow_new, run_command_with_timeout, truncate};
 use cosmos_core::index::parser::{parse_file, parse_file_has_errors};
@@ -24,25 +31,39 @@ use std::time::Duration;
 use uuid::Uuid;
 
 mod defaults;
+mod dry_run;
+mod license_gate;
 mod quick_checks;
+mod repo_config;
 mod review_helpers;
+mod test_gate;
 
 use defaults::{
     default_enable_quick_check_baseline, default_max_auto_syntax_fix_loops,
     default_max_smart_escalations_per_attempt, default_require_independent_review_on_pass,
     default_reserve_independent_review_cost_usd, default_reserve_independent_review_ms,
 };
+pub use dry_run::plan_harness_apply;
+use license_gate::license_gate;
+pub use license_gate::LicenseGateConfig;
 #[cfg(test)]
-use quick_checks::quick_check_requires_real_node_modules;
+use license_gate::{denylisted_markers_in_added_lines, has_license_header};
 use quick_checks::{
-    command_to_string, detect_quick_check_command, is_eslint_fixable_failure,
-    is_prettier_formatting_failure, program_available_on_path, run_eslint_fix, run_prettier_write,
+    command_to_string, is_eslint_fixable_failure, is_prettier_formatting_failure,
+    planned_quick_check_command, program_available_on_path, run_eslint_fix, run_prettier_write,
     run_quick_checks, QuickCheckCommand,
 };
+#[cfg(test)]
+use quick_checks::{detect_quick_check_command, quick_check_requires_real_node_modules};
+pub use quick_checks::{with_check_output, QuickCheckConfig};
+pub(crate) use repo_config::load_repo_harness_config;
+pub(crate) use repo_config::REPO_CONFIG_PATH;
 use review_helpers::{
     blocking_findings, build_files_with_content, group_findings_by_file,
     is_probable_compile_error_false_positive,
 };
+pub use test_gate::TestGateConfig;
+use test_gate::{format_test_repair_modifier, run_test_gate};
 
 const APPLY_HARNESS_REPORT_DIR: &str = ".cosmos/apply_harness";
 const IMPLEMENTATION_MODEL: Model = Model::Smart;
@@ -50,8 +71,10 @@ const REASON_SCOPE_VIOLATION: &str = "scope_violation";
 const REASON_DIFF_BUDGET_VIOLATION: &str = "diff_budget_violation";
 const REASON_SYNTAX_VIOLATION: &str = "syntax_violation";
 const REASON_BINARY_WRITE_VIOLATION: &str = "binary_write_violation";
+const REASON_LICENSE_VIOLATION: &str = "license_violation";
 const REASON_QUICK_CHECK_UNAVAILABLE: &str = "quick_check_unavailable";
 const REASON_QUICK_CHECK_FAILED: &str = "quick_check_failed";
+const REASON_TESTS_FAILED: &str = "tests_failed";
 const REASON_BLOCKING_REVIEW_RESIDUAL: &str = "blocking_review_residual";
 const REASON_PLAIN_LANGUAGE_FAILURE: &str = "plain_language_failure";
 const REASON_NON_EMPTY_DIFF: &str = "non_empty_diff_violation";
@@ -112,6 +135,15 @@ pub struct ImplementationHarnessConfig {
     pub require_independent_review_on_pass: bool,
     #[serde(default)]
     pub adversarial_review_model: ImplementationReviewModel,
+    /// Optional license/header policy (`[license]` in `.cosmos/config.toml`).
+    #[serde(default)]
+    pub license_gate: Option<LicenseGateConfig>,
+    /// Declared quick checks (`[[quick_checks]]` in `.cosmos/config.toml`); replace auto-detection.
+    #[serde(default)]
+    pub quick_checks: Vec<QuickCheckConfig>,
+    /// Optional scoped test run (`[tests]` in `.cosmos/config.toml`), gated after quick checks.
+    #[serde(default)]
+    pub test_gate: Option<TestGateConfig>,
 }
 
 impl Default for ImplementationHarnessConfig {
@@ -179,9 +211,94 @@ impl ImplementationHarnessConfig {
             quick_check_fix_requires_in_scope_error: true,
             require_independent_review_on_pass: true,
             adversarial_review_model: ImplementationReviewModel::Smart,
+            license_gate: None,
+            quick_checks: Vec::new(),
+            test_gate: None,
         }
     }
 
+    /// Apply repo-level overrides from `.cosmos/config.toml`, if present.
+    pub fn with_repo_config(mut self, repo_root: &Path) -> anyhow::Result<Self> {
+        let repo_config = load_repo_harness_config(repo_root)?;
+        if repo_config.license.is_some() {
+            self.license_gate = repo_config.license;
+        }
+        for check in &repo_config.quick_checks {
+            if check.command.trim().is_empty() {
+                return Err(anyhow::anyhow!(
+                    "Invalid {}: quick check command cannot be empty",
+                    repo_config::REPO_CONFIG_PATH
+                ));
+            }
+            if let Some(dir) = &check.dir {
+                resolve_repo_path_allow_new(repo_root, dir).map_err(|e| {
+                    anyhow::anyhow!(
+                        "Invalid {}: quick check dir {}: {}",
+                        repo_config::REPO_CONFIG_PATH,
+                        dir.display(),
+                        e
+                    )
+                })?;
+            }
+        }
+        if !repo_config.quick_checks.is_empty() {
+            self.quick_checks = repo_config.quick_checks;
+        }
+        if let Some(tests) = repo_config.tests {
+            if tests.command.trim().is_empty() {
+                return Err(anyhow::anyhow!(
+                    "Invalid {}: tests command cannot be empty",
+                    repo_config::REPO_CONFIG_PATH
+                ));
+            }
+            if let Some(dir) = &tests.dir {
+                resolve_repo_path_allow_new(repo_root, dir).map_err(|e| {
+                    anyhow::anyhow!(
+                        "Invalid {}: tests dir {}: {}",
+                        repo_config::REPO_CONFIG_PATH,
+                        dir.display(),
+                        e
+                    )
+                })?;
+            }
+            self.test_gate = Some(tests);
+        }
+        if let Some(budget) = repo_config.budget {
+            if let Some(max_cost_usd) = budget.max_cost_usd {
+                if !max_cost_usd.is_finite() || max_cost_usd <= 0.0 {
+                    return Err(anyhow::anyhow!(
+                        "Invalid {}: budget.max_cost_usd must be greater than 0",
+                        repo_config::REPO_CONFIG_PATH
+                    ));
+                }
+                self.max_total_cost_usd = max_cost_usd;
+            }
+            if let Some(max_seconds) = budget.max_seconds {
+                if max_seconds == 0 {
+                    return Err(anyhow::anyhow!(
+                        "Invalid {}: budget.max_seconds must be greater than 0",
+                        repo_config::REPO_CONFIG_PATH
+                    ));
+                }
+                self.max_total_ms = max_seconds.saturating_mul(1_000);
+            }
+        }
+        Ok(self)
+    }
+
+    /// Never execute repo-controlled commands: no quick checks and no test gate.
+    ///
+    /// Used until the user trusts the workspace, so a freshly cloned repo can't run its
+    /// own `package.json` scripts or declared checks through the harness.
+    pub fn for_untrusted_workspace(mut self) -> Self {
+        self.quick_checks_mode = ImplementationQuickChecksMode::Disabled;
+        self.enable_quick_check_baseline = false;
+        self.require_quick_check_detectable = false;
+        self.quick_checks.clear();
+        self.test_gate = None;
+        self
+    }
+
     pub fn lab_strict() -> Self {
         let mut config = Self::interactive_strict();
         // Lab/CI uses a stricter policy surface (quick checks required), but we allow a small
@@ -300,6 +417,17 @@ impl ImplementationBudget {
         if let Some(reason) = self.exhausted(usage) {
             return Some(reason);
         }
+        // The operation-wide spend cap covers every attempt, so stop cleanly here rather
+        // than letting the client refuse the request mid-attempt.
+        if let Err(capped) = spend::check() {
+            return Some(ImplementationFailReason {
+                code: REASON_BUDGET_EXCEEDED.to_string(),
+                gate: "budget".to_string(),
+                message: capped.to_string(),
+                action: default_action_for_fail_reason("budget", REASON_BUDGET_EXCEEDED)
+                    .to_string(),
+            });
+        }
 
         let remaining_ms = self.remaining_ms();
         let min_ms_buffer = self.min_remaining_ms_buffer().clamp(
@@ -465,6 +593,10 @@ fn default_action_for_fail_reason(gate: &str, code: &str) -> &'static str {
             "Rewrite the user-facing summary in plain language and rerun apply."
         }
         REASON_NON_EMPTY_DIFF => "Generate at least one in-scope file change and rerun apply.",
+        REASON_LICENSE_VIOLATION => {
+            "Add the required license header or remove copied licensed code, then rerun apply."
+        }
+        REASON_TESTS_FAILED => "Fix the failing tests in the scoped files and rerun apply.",
         _ if gate == "quick_check" => "Resolve the quick-check issue in scope and rerun apply.",
         _ => "Review the failure details and rerun apply.",
     }
@@ -495,6 +627,10 @@ fn normalize_fail_reason_message(gate: &str, code: &str, message: &str) -> Strin
         REASON_PLAIN_LANGUAGE_FAILURE => {
             "Cosmos stopped because the user-facing description was not plain language"
         }
+        REASON_LICENSE_VIOLATION => {
+            "Cosmos stopped because the change did not meet the repo's license policy"
+        }
+        REASON_TESTS_FAILED => "Cosmos stopped because the project tests did not pass",
         _ if gate == "review" => "Cosmos stopped because review checks did not pass",
         _ if gate == "quick_check" => "Cosmos stopped because project quick checks did not pass",
         _ => "Cosmos stopped before applying changes",
@@ -600,6 +736,13 @@ pub struct ImplementationAttemptDiagnostics {
     pub quick_check_fix_loops: usize,
     #[serde(default)]
     pub quick_check_failure_summary: Option<String>,
+    /// Last run of the optional test gate, if it ran.
+    #[serde(default)]
+    pub test_outcome: Option<ImplementationCommandOutcome>,
+    #[serde(default)]
+    pub failing_tests: Vec<String>,
+    #[serde(default)]
+    pub test_fix_loops: usize,
     pub review_iterations: usize,
     pub review_blocking_remaining: usize,
     #[serde(default)]
@@ -635,6 +778,37 @@ pub struct ImplementationRunDiagnostics {
     pub report_path: Option<PathBuf>,
     #[serde(default)]
     pub finalization: ImplementationFinalizationDiagnostics,
+    /// Prompt templates replaced by `.cosmos/prompts/` overrides during this run.
+    #[serde(default)]
+    pub prompt_overrides: Vec<String>,
+    /// Secrets masked in prompts and tool results before they were sent.
+    #[serde(default)]
+    pub redacted_secret_count: usize,
+    /// Estimated context use of every model request the run sent, in order.
+    #[serde(default)]
+    pub context_budgets: Vec<ContextBudget>,
+    /// Switches to a cheaper model made because the run neared its spend cap.
+    #[serde(default)]
+    pub model_downgrades: Vec<ModelDowngrade>,
+    /// Requests answered from, or missed in, the response cache.
+    #[serde(default)]
+    pub response_cache: ResponseCacheStats,
+    /// Hunks of the passing attempt the user left out when the fix was finalized.
+    #[serde(default)]
+    pub rejected_hunks: Vec<ImplementationRejectedHunk>,
+    /// Unified diff of the passing attempt against the working tree; empty when no attempt
+    /// passed.
+    #[serde(default)]
+    pub proposed_diff: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ImplementationRejectedHunk {
+    pub path: PathBuf,
+    /// `@@ -a,b +c,d @@` header of the hunk in the passing attempt
+    pub header: String,
+    pub added_lines: usize,
+    pub removed_lines: usize,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -713,7 +887,25 @@ fn parse_path_line_col(raw: &str) -> Option<(String, u32, u32)> {
         .map(|ext| {
             matches!(
                 ext.to_ascii_lowercase().as_str(),
-                "ts" | "tsx" | "js" | "jsx" | "mjs" | "cjs" | "rs" | "go" | "py"
+                "ts" | "tsx"
+                    | "js"
+                    | "jsx"
+                    | "mjs"
+                    | "cjs"
+                    | "rs"
+                    | "go"
+                    | "py"
+                    | "java"
+                    | "kt"
+                    | "kts"
+                    | "c"
+                    | "h"
+                    | "cc"
+                    | "cpp"
+                    | "cxx"
+                    | "hh"
+                    | "hpp"
+                    | "hxx"
             )
         })
         .unwrap_or(false);
@@ -743,7 +935,25 @@ fn parse_tsc_error_line(raw: &str) -> Option<(String, u32, u32, String)> {
         .map(|ext| {
             matches!(
                 ext.to_ascii_lowercase().as_str(),
-                "ts" | "tsx" | "js" | "jsx" | "mjs" | "cjs" | "rs" | "go" | "py"
+                "ts" | "tsx"
+                    | "js"
+                    | "jsx"
+                    | "mjs"
+                    | "cjs"
+                    | "rs"
+                    | "go"
+                    | "py"
+                    | "java"
+                    | "kt"
+                    | "kts"
+                    | "c"
+                    | "h"
+                    | "cc"
+                    | "cpp"
+                    | "cxx"
+                    | "hh"
+                    | "hpp"
+                    | "hxx"
             )
         })
         .unwrap_or(false);
@@ -776,7 +986,25 @@ fn parse_colon_error_line_with_message(raw: &str) -> Option<(String, u32, u32, S
         .map(|ext| {
             matches!(
                 ext.to_ascii_lowercase().as_str(),
-                "ts" | "tsx" | "js" | "jsx" | "mjs" | "cjs" | "rs" | "go" | "py"
+                "ts" | "tsx"
+                    | "js"
+                    | "jsx"
+                    | "mjs"
+                    | "cjs"
+                    | "rs"
+                    | "go"
+                    | "py"
+                    | "java"
+                    | "kt"
+                    | "kts"
+                    | "c"
+                    | "h"
+                    | "cc"
+                    | "cpp"
+                    | "cxx"
+                    | "hh"
+                    | "hpp"
+                    | "hxx"
             )
         })
         .unwrap_or(false);
@@ -1766,6 +1994,18 @@ fn feedback_reasons_for_next_attempt(diag: &ImplementationAttemptDiagnostics) ->
 
     if !diag.fail_reason_records.is_empty() {
         for record in &diag.fail_reason_records {
+            if record.code == REASON_TESTS_FAILED && !diag.failing_tests.is_empty() {
+                out.push(format!(
+                    "These tests failed after the previous attempt: {}",
+                    diag.failing_tests
+                        .iter()
+                        .take(8)
+                        .cloned()
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ));
+                continue;
+            }
             if record.code == REASON_QUICK_CHECK_FAILED {
                 if let Some(outcome) = &diag.quick_check_outcome {
                     if let Some(summary) = summarize_quick_check_failure(outcome) {
@@ -1955,6 +2195,43 @@ pub async fn implement_validated_suggestion_with_harness(
 }
 
 pub async fn implement_validated_suggestion_with_harness_with_progress<F>(
+    repo_root: &Path,
+    suggestion: &Suggestion,
+    preview: &FixPreview,
+    repo_memory: Option<String>,
+    config: ImplementationHarnessConfig,
+    on_progress: F,
+) -> anyhow::Result<ImplementationRunResult>
+where
+    F: FnMut(usize, usize, &ImplementationAttemptDiagnostics),
+{
+    // Boxed: the harness future is large enough to overflow a worker thread's stack once
+    // wrapped in the redaction and budget scopes.
+    let run = Box::pin(
+        implement_validated_suggestion_with_harness_with_progress_impl(
+            repo_root,
+            suggestion,
+            preview,
+            repo_memory,
+            config,
+            on_progress,
+        ),
+    );
+    let ((((result, redacted), budgets), downgrades), cache_stats) =
+        response_cache::record_stats(spend::record_downgrades(tokens::record_budgets(
+            redaction::count_redactions(routing::with_role(ModelRole::Implement, run)),
+        )))
+        .await;
+    result.map(|mut run| {
+        run.diagnostics.redacted_secret_count = redacted;
+        run.diagnostics.context_budgets = budgets;
+        run.diagnostics.model_downgrades = downgrades;
+        run.diagnostics.response_cache = cache_stats;
+        run
+    })
+}
+
+async fn implement_validated_suggestion_with_harness_with_progress_impl<F>(
     repo_root: &Path,
     suggestion: &Suggestion,
     preview: &FixPreview,
@@ -2106,6 +2383,16 @@ where
         attempts,
         report_path: None,
         finalization: ImplementationFinalizationDiagnostics::default(),
+        prompt_overrides: super::prompt_templates::active_overrides(),
+        redacted_secret_count: 0,
+        context_budgets: Vec::new(),
+        model_downgrades: Vec::new(),
+        response_cache: ResponseCacheStats::default(),
+        rejected_hunks: Vec::new(),
+        proposed_diff: pass_payload
+            .as_ref()
+            .map(|payload| proposed_diff(&repo_root, &payload.file_changes))
+            .unwrap_or_default(),
     };
 
     if !diagnostics.passed {
@@ -2182,7 +2469,7 @@ async fn run_attempt(
     let mut llm_calls: Vec<ImplementationLlmCallRecord> = Vec::new();
     // Detect the repo's quick-check command up-front so diagnostics can still surface it even if
     // the attempt fails before reaching the quick-check gate (e.g. budget exhaustion during generation).
-    let detected_quick_check = detect_quick_check_command(repo_root);
+    let detected_quick_check = planned_quick_check_command(repo_root, &config.quick_checks);
     let detected_quick_check_command = detected_quick_check.as_ref().map(command_to_string);
 
     if let Some(reason) = global_budget.guard_before_llm_call(usage_so_far) {
@@ -2216,6 +2503,9 @@ async fn run_attempt(
             quick_check_outcomes: Vec::new(),
             quick_check_fix_loops: 0,
             quick_check_failure_summary: None,
+            test_outcome: None,
+            failing_tests: Vec::new(),
+            test_fix_loops: 0,
             review_iterations: 0,
             review_blocking_remaining: 0,
             remaining_blocking_titles: Vec::new(),
@@ -2299,6 +2589,9 @@ async fn run_attempt(
                 quick_check_outcomes: Vec::new(),
                 quick_check_fix_loops: 0,
                 quick_check_failure_summary: None,
+                test_outcome: None,
+                failing_tests: Vec::new(),
+                test_fix_loops: 0,
                 review_iterations: 0,
                 review_blocking_remaining: 0,
                 remaining_blocking_titles: Vec::new(),
@@ -2350,6 +2643,9 @@ async fn run_attempt(
                 quick_check_outcomes: Vec::new(),
                 quick_check_fix_loops: 0,
                 quick_check_failure_summary: None,
+                test_outcome: None,
+                failing_tests: Vec::new(),
+                test_fix_loops: 0,
                 review_iterations: 0,
                 review_blocking_remaining: 0,
                 remaining_blocking_titles: Vec::new(),
@@ -2381,6 +2677,7 @@ async fn run_attempt(
             &mut notes,
             config.quick_checks_mode,
             baseline_timeout_ms,
+            &config.quick_checks,
         )?;
         if baseline_status == ImplementationQuickCheckStatus::Failed {
             let summary = baseline_outcome
@@ -2432,6 +2729,9 @@ async fn run_attempt(
                     quick_check_outcomes,
                     quick_check_fix_loops: 0,
                     quick_check_failure_summary: summary,
+                    test_outcome: None,
+                    failing_tests: Vec::new(),
+                    test_fix_loops: 0,
                     review_iterations: 0,
                     review_blocking_remaining: 0,
                     remaining_blocking_titles: Vec::new(),
@@ -2493,6 +2793,9 @@ async fn run_attempt(
             quick_check_outcomes: Vec::new(),
             quick_check_fix_loops: 0,
             quick_check_failure_summary: None,
+            test_outcome: None,
+            failing_tests: Vec::new(),
+            test_fix_loops: 0,
             review_iterations: 0,
             review_blocking_remaining: 0,
             remaining_blocking_titles: Vec::new(),
@@ -2567,6 +2870,9 @@ async fn run_attempt(
                 quick_check_outcomes: Vec::new(),
                 quick_check_fix_loops: 0,
                 quick_check_failure_summary: None,
+                test_outcome: None,
+                failing_tests: Vec::new(),
+                test_fix_loops: 0,
                 review_iterations: 0,
                 review_blocking_remaining: 0,
                 remaining_blocking_titles: Vec::new(),
@@ -2627,6 +2933,9 @@ async fn run_attempt(
                         quick_check_outcomes: Vec::new(),
                         quick_check_fix_loops: 0,
                         quick_check_failure_summary: None,
+                        test_outcome: None,
+                        failing_tests: Vec::new(),
+                        test_fix_loops: 0,
                         review_iterations: 0,
                         review_blocking_remaining: 0,
                         remaining_blocking_titles: Vec::new(),
@@ -2701,6 +3010,9 @@ async fn run_attempt(
                             quick_check_outcomes: Vec::new(),
                             quick_check_fix_loops: 0,
                             quick_check_failure_summary: None,
+                            test_outcome: None,
+                            failing_tests: Vec::new(),
+                            test_fix_loops: 0,
                             review_iterations: 0,
                             review_blocking_remaining: 0,
                             remaining_blocking_titles: Vec::new(),
@@ -2759,6 +3071,9 @@ async fn run_attempt(
                             quick_check_outcomes: Vec::new(),
                             quick_check_fix_loops: 0,
                             quick_check_failure_summary: None,
+                            test_outcome: None,
+                            failing_tests: Vec::new(),
+                            test_fix_loops: 0,
                             review_iterations: 0,
                             review_blocking_remaining: 0,
                             remaining_blocking_titles: Vec::new(),
@@ -2808,6 +3123,9 @@ async fn run_attempt(
                     quick_check_outcomes: Vec::new(),
                     quick_check_fix_loops: 0,
                     quick_check_failure_summary: None,
+                    test_outcome: None,
+                    failing_tests: Vec::new(),
+                    test_fix_loops: 0,
                     review_iterations: 0,
                     review_blocking_remaining: 0,
                     remaining_blocking_titles: Vec::new(),
@@ -2860,6 +3178,9 @@ async fn run_attempt(
             quick_check_outcomes: Vec::new(),
             quick_check_fix_loops: 0,
             quick_check_failure_summary: None,
+            test_outcome: None,
+            failing_tests: Vec::new(),
+            test_fix_loops: 0,
             review_iterations: 0,
             review_blocking_remaining: 0,
             remaining_blocking_titles: Vec::new(),
@@ -3332,6 +3653,9 @@ async fn run_attempt(
             quick_check_outcomes: Vec::new(),
             quick_check_fix_loops: 0,
             quick_check_failure_summary: None,
+            test_outcome: None,
+            failing_tests: Vec::new(),
+            test_fix_loops: 0,
             review_iterations: 0,
             review_blocking_remaining: 0,
             remaining_blocking_titles: Vec::new(),
@@ -3371,6 +3695,7 @@ async fn run_attempt(
         &mut notes,
         config.quick_checks_mode,
         pre_review_quick_check_timeout_ms,
+        &config.quick_checks,
     )?;
 
     if let Some(outcome) = quick_outcome.clone() {
@@ -3538,6 +3863,7 @@ async fn run_attempt(
                             .saturating_sub(BUDGET_TIMEOUT_SLACK_MS)
                             .max(1),
                     ),
+                    &config.quick_checks,
                 )?;
                 quick_status = status;
                 quick_command = command;
@@ -3820,6 +4146,7 @@ async fn run_attempt(
                         .saturating_sub(BUDGET_TIMEOUT_SLACK_MS)
                         .max(1),
                 ),
+                &config.quick_checks,
             )?;
             quick_status = status;
             quick_command = command;
@@ -3918,6 +4245,9 @@ async fn run_attempt(
             quick_check_outcomes,
             quick_check_fix_loops,
             quick_check_failure_summary,
+            test_outcome: None,
+            failing_tests: Vec::new(),
+            test_fix_loops: 0,
             review_iterations: 0,
             review_blocking_remaining: 0,
             remaining_blocking_titles: Vec::new(),
@@ -4086,6 +4416,9 @@ async fn run_attempt(
             quick_check_outcomes,
             quick_check_fix_loops,
             quick_check_failure_summary,
+            test_outcome: None,
+            failing_tests: Vec::new(),
+            test_fix_loops: 0,
             review_iterations,
             review_blocking_remaining: blocking_remaining,
             remaining_blocking_titles,
@@ -4130,6 +4463,53 @@ async fn run_attempt(
             Some(REASON_BINARY_WRITE_VIOLATION)
         },
     );
+    if let Some(license_config) = config.license_gate.as_ref() {
+        let license_ok = match license_gate(
+            sandbox.path(),
+            repo_root,
+            &final_changed_files,
+            license_config,
+        ) {
+            Ok(inserted) => {
+                if !inserted.is_empty() {
+                    notes.push(format!(
+                        "license_headers_inserted: {}",
+                        inserted
+                            .iter()
+                            .map(|p| p.display().to_string())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ));
+                }
+                true
+            }
+            Err(err) => {
+                push_fail_reason(
+                    &mut fail_reasons,
+                    &mut fail_reason_records,
+                    "license",
+                    REASON_LICENSE_VIOLATION,
+                    err,
+                );
+                false
+            }
+        };
+        push_gate(
+            &mut gates,
+            "license",
+            license_ok,
+            if license_ok {
+                "License headers and denylist checks passed".to_string()
+            } else {
+                "License policy violation in changed files".to_string()
+            },
+            if license_ok {
+                None
+            } else {
+                Some(REASON_LICENSE_VIOLATION)
+            },
+        );
+    }
     let post_review_syntax = syntax_gate(sandbox.path(), &final_changed_files);
     if let Err(err) = post_review_syntax {
         push_fail_reason(
@@ -4191,6 +4571,9 @@ async fn run_attempt(
             quick_check_outcomes: quick_check_outcomes.clone(),
             quick_check_fix_loops,
             quick_check_failure_summary: quick_check_failure_summary.clone(),
+            test_outcome: None,
+            failing_tests: Vec::new(),
+            test_fix_loops: 0,
             review_iterations,
             review_blocking_remaining: blocking_remaining,
             remaining_blocking_titles,
@@ -4255,6 +4638,9 @@ async fn run_attempt(
             quick_check_outcomes: quick_check_outcomes.clone(),
             quick_check_fix_loops,
             quick_check_failure_summary: quick_check_failure_summary.clone(),
+            test_outcome: None,
+            failing_tests: Vec::new(),
+            test_fix_loops: 0,
             review_iterations,
             review_blocking_remaining: blocking_remaining,
             remaining_blocking_titles,
@@ -4283,6 +4669,7 @@ async fn run_attempt(
         &mut notes,
         config.quick_checks_mode,
         quick_check_timeout_ms,
+        &config.quick_checks,
     )?;
     quick_status = status;
     quick_command = command;
@@ -4703,6 +5090,7 @@ async fn run_attempt(
                         .saturating_sub(BUDGET_TIMEOUT_SLACK_MS)
                         .max(1),
                 ),
+                &config.quick_checks,
             )?;
             quick_status = status;
             quick_command = command;
@@ -4836,6 +5224,7 @@ async fn run_attempt(
                         .saturating_sub(BUDGET_TIMEOUT_SLACK_MS)
                         .max(1),
                 ),
+                &config.quick_checks,
             )?;
             quick_status = status;
             quick_command = command;
@@ -4900,6 +5289,365 @@ async fn run_attempt(
         quick_reason_code,
     );
 
+    let mut test_outcome: Option<ImplementationCommandOutcome> = None;
+    let mut failing_tests: Vec<String> = Vec::new();
+    let mut test_fix_loops = 0usize;
+    if let Some(test_config) = config
+        .test_gate
+        .as_ref()
+        .filter(|_| fail_reasons.is_empty())
+    {
+        let mut run = run_test_gate(
+            sandbox.path(),
+            Some(repo_root),
+            &mut notes,
+            test_config,
+            &final_changed_files,
+            attempt_budget
+                .remaining_ms()
+                .saturating_sub(BUDGET_TIMEOUT_SLACK_MS)
+                .max(1),
+        )?;
+        // Feed the failing test names back to the model, then rerun quick checks and tests.
+        while run.status == ImplementationQuickCheckStatus::Failed
+            && test_fix_loops < test_config.max_fix_loops
+            && fail_reasons.is_empty()
+        {
+            let Some(outcome) = run.outcome.clone() else {
+                break;
+            };
+            let target = extract_quick_check_error_paths(&outcome, sandbox.path())
+                .into_iter()
+                .find(|path| allowed_files.contains(path))
+                .or_else(|| {
+                    (files_changed_set.len() == 1)
+                        .then(|| files_changed_set.iter().next().cloned())
+                        .flatten()
+                        .filter(|only| allowed_files.contains(only))
+                });
+            let Some(target) = target else {
+                notes.push("test_repair_skipped_no_in_scope_path".to_string());
+                break;
+            };
+            if let Some(reason) = attempt_budget.guard_before_llm_call(&usage) {
+                notes.push("budget_exceeded".to_string());
+                push_fail_reason(
+                    &mut fail_reasons,
+                    &mut fail_reason_records,
+                    &reason.gate,
+                    &reason.code,
+                    reason.message.clone(),
+                );
+                push_gate(
+                    &mut gates,
+                    "budget",
+                    false,
+                    reason.message,
+                    Some(REASON_BUDGET_EXCEEDED),
+                );
+                break;
+            }
+
+            test_fix_loops += 1;
+            notes.push(format!("test_fix_loop_{}", test_fix_loops));
+            let resolved = resolve_repo_path_allow_new(sandbox.path(), &target).map_err(|e| {
+                anyhow::anyhow!("Unsafe test repair path {}: {}", target.display(), e)
+            })?;
+            let current_content = match std::fs::read_to_string(&resolved.absolute) {
+                Ok(content) => content,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+                Err(e) => {
+                    push_fail_reason(
+                        &mut fail_reasons,
+                        &mut fail_reason_records,
+                        "tests",
+                        REASON_TESTS_FAILED,
+                        format!(
+                            "Test auto-repair failed reading {}: {}",
+                            target.display(),
+                            truncate(&e.to_string(), 180)
+                        ),
+                    );
+                    break;
+                }
+            };
+            let is_new_file = current_content.trim().is_empty();
+            let mut repair_preview = feedback_preview.clone();
+            repair_preview.modifier = Some(format_test_repair_modifier(
+                feedback_preview.modifier.as_deref(),
+                &run.failing_tests,
+                &outcome,
+                &target,
+            ));
+
+            ensure_implementation_model(IMPLEMENTATION_MODEL)?;
+            let repair_timeout_ms = attempt_budget
+                .timeout_ms_for_next_llm_call()
+                .min(MAX_FIX_TIMEOUT_MS);
+            let fix = tokio::time::timeout(
+                Duration::from_millis(repair_timeout_ms),
+                generate_fix_content_with_model(
+                    &target,
+                    &current_content,
+                    suggestion,
+                    &repair_preview,
+                    repo_memory.clone(),
+                    is_new_file,
+                    IMPLEMENTATION_MODEL,
+                    repair_timeout_ms,
+                ),
+            )
+            .await;
+            let mut call = ImplementationLlmCallRecord {
+                kind: "test_repair".to_string(),
+                independence_role: Some("implementation".to_string()),
+                escalation_reason: None,
+                model: IMPLEMENTATION_MODEL.id().to_string(),
+                timeout_ms: repair_timeout_ms,
+                schema_fallback_used: false,
+                speed_failover: None,
+                error: None,
+            };
+            let fix = match fix {
+                Ok(Ok(value)) => {
+                    call.speed_failover = value.speed_failover.clone();
+                    llm_calls.push(call);
+                    value
+                }
+                Ok(Err(err)) => {
+                    if let Some(u) = err
+                        .downcast_ref::<FixGenerationErrorWithUsage>()
+                        .and_then(|e| e.usage.clone())
+                    {
+                        usage = merge_usage(usage, Some(u));
+                    }
+                    call.error = Some(truncate(&err.to_string(), 240));
+                    llm_calls.push(call);
+                    push_fail_reason(
+                        &mut fail_reasons,
+                        &mut fail_reason_records,
+                        "tests",
+                        REASON_TESTS_FAILED,
+                        format!(
+                            "Test auto-repair failed: {}",
+                            truncate(&err.to_string(), 180)
+                        ),
+                    );
+                    break;
+                }
+                Err(_) => {
+                    call.error = Some(format!("Timed out after {}ms", repair_timeout_ms));
+                    llm_calls.push(call);
+                    notes.push("budget_exceeded".to_string());
+                    let message = format!(
+                        "Stopped to respect the configured time budget (test repair timed out after {}ms; limit {}ms)",
+                        repair_timeout_ms, attempt_budget.max_total_ms
+                    );
+                    push_fail_reason(
+                        &mut fail_reasons,
+                        &mut fail_reason_records,
+                        "budget",
+                        REASON_BUDGET_EXCEEDED,
+                        message.clone(),
+                    );
+                    push_gate(
+                        &mut gates,
+                        "budget",
+                        false,
+                        message,
+                        Some(REASON_BUDGET_EXCEEDED),
+                    );
+                    break;
+                }
+            };
+            usage = merge_usage(usage, fix.usage.clone());
+
+            if let Some(parent) = resolved.absolute.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&resolved.absolute, &fix.new_content).map_err(|e| {
+                anyhow::anyhow!("Failed writing test repair {}: {}", target.display(), e)
+            })?;
+            files_changed_set.insert(target.clone());
+            generated
+                .modified_areas_by_file
+                .entry(target.clone())
+                .or_default()
+                .extend(fix.modified_areas.clone());
+            final_changed_files = files_changed_set.iter().cloned().collect::<Vec<_>>();
+            final_changed_files.sort();
+
+            if let Err(err) = syntax_gate(sandbox.path(), &final_changed_files) {
+                push_fail_reason(
+                    &mut fail_reasons,
+                    &mut fail_reason_records,
+                    "post_review_syntax",
+                    REASON_SYNTAX_VIOLATION,
+                    err,
+                );
+                break;
+            }
+            // The repair must not trade a test failure for a build failure.
+            let (status, command, outcome) = run_quick_checks(
+                sandbox.path(),
+                Some(repo_root),
+                &mut notes,
+                config.quick_checks_mode,
+                config.quick_check_timeout_ms.min(
+                    attempt_budget
+                        .remaining_ms()
+                        .saturating_sub(BUDGET_TIMEOUT_SLACK_MS)
+                        .max(1),
+                ),
+                &config.quick_checks,
+            )?;
+            quick_status = status;
+            quick_command = command;
+            if let Some(outcome) = outcome {
+                quick_check_outcomes.push(outcome.clone());
+                quick_outcome = Some(outcome);
+            }
+            if quick_status == ImplementationQuickCheckStatus::Failed {
+                push_fail_reason(
+                    &mut fail_reasons,
+                    &mut fail_reason_records,
+                    "quick_check",
+                    REASON_QUICK_CHECK_FAILED,
+                    "Quick project checks failed after test repair",
+                );
+                upsert_gate(
+                    &mut gates,
+                    "quick_check",
+                    false,
+                    "Quick checks failed after test repair".to_string(),
+                    Some(REASON_QUICK_CHECK_FAILED),
+                );
+                break;
+            }
+            run = run_test_gate(
+                sandbox.path(),
+                Some(repo_root),
+                &mut notes,
+                test_config,
+                &final_changed_files,
+                attempt_budget
+                    .remaining_ms()
+                    .saturating_sub(BUDGET_TIMEOUT_SLACK_MS)
+                    .max(1),
+            )?;
+        }
+
+        // Repaired code has not been reviewed yet; review it once before accepting it.
+        if run.status == ImplementationQuickCheckStatus::Passed
+            && test_fix_loops > 0
+            && fail_reasons.is_empty()
+        {
+            let mut rerun_review_iterations = 0usize;
+            let mut rerun_blocking_remaining = 0usize;
+            let mut rerun_remaining_titles = Vec::new();
+            let mut rerun_remaining_categories = Vec::new();
+            let review_rerun = run_review_gate(
+                sandbox.path(),
+                suggestion,
+                &generated.description,
+                &generated.old_contents,
+                &final_changed_files,
+                &mut llm_calls,
+                repo_memory.clone(),
+                quick_status,
+                quick_command.as_deref(),
+                blocking_severities,
+                config.adversarial_review_model.as_model(),
+                config.require_independent_review_on_pass,
+                config.max_auto_review_fix_loops,
+                &attempt_budget,
+                &mut usage,
+                &mut rerun_review_iterations,
+                &mut rerun_blocking_remaining,
+                &mut rerun_remaining_titles,
+                &mut rerun_remaining_categories,
+                &mut fixed_titles,
+                &mut files_changed_set,
+            )
+            .await;
+            review_iterations += rerun_review_iterations;
+            blocking_remaining = rerun_blocking_remaining;
+            remaining_blocking_titles = rerun_remaining_titles;
+            remaining_blocking_categories = rerun_remaining_categories;
+            match review_rerun {
+                Ok(()) if blocking_remaining > 0 => push_fail_reason(
+                    &mut fail_reasons,
+                    &mut fail_reason_records,
+                    "review",
+                    REASON_BLOCKING_REVIEW_RESIDUAL,
+                    "Blocking review findings appeared after test repair",
+                ),
+                Ok(()) => {}
+                Err(ReviewGateError::BudgetExceeded(reason)) => {
+                    notes.push("budget_exceeded".to_string());
+                    push_fail_reason(
+                        &mut fail_reasons,
+                        &mut fail_reason_records,
+                        &reason.gate,
+                        &reason.code,
+                        reason.message.clone(),
+                    );
+                    push_gate(
+                        &mut gates,
+                        "budget",
+                        false,
+                        reason.message,
+                        Some(REASON_BUDGET_EXCEEDED),
+                    );
+                }
+                Err(ReviewGateError::Failed(err)) => push_fail_reason(
+                    &mut fail_reasons,
+                    &mut fail_reason_records,
+                    "review",
+                    REASON_BLOCKING_REVIEW_RESIDUAL,
+                    err,
+                ),
+            }
+            final_changed_files = files_changed_set.iter().cloned().collect::<Vec<_>>();
+            final_changed_files.sort();
+        }
+
+        let tests_ok = run.status == ImplementationQuickCheckStatus::Passed;
+        let detail = match run.status {
+            ImplementationQuickCheckStatus::Passed => "Project tests passed".to_string(),
+            ImplementationQuickCheckStatus::Failed if run.failing_tests.is_empty() => {
+                "Project tests failed".to_string()
+            }
+            ImplementationQuickCheckStatus::Failed => format!(
+                "Failing tests: {}",
+                truncate(&run.failing_tests.join(", "), 240)
+            ),
+            ImplementationQuickCheckStatus::Unavailable => "Test command could not run".to_string(),
+        };
+        if !tests_ok {
+            notes.push(format!(
+                "tests_failed_command: {}",
+                run.command.as_deref().unwrap_or(&test_config.command)
+            ));
+            push_fail_reason(
+                &mut fail_reasons,
+                &mut fail_reason_records,
+                "tests",
+                REASON_TESTS_FAILED,
+                detail.clone(),
+            );
+        }
+        push_gate(
+            &mut gates,
+            "tests",
+            tests_ok,
+            detail,
+            (!tests_ok).then_some(REASON_TESTS_FAILED),
+        );
+        test_outcome = run.outcome;
+        failing_tests = run.failing_tests;
+    }
+
     // Re-evaluate deterministic scope + diff-size budgets on the *final* sandbox state (after any
     // in-attempt repairs). This guarantees we never accept a passing payload that drifted out of
     // scope or exceeded budgets during review/repair loops.
@@ -5085,6 +5833,9 @@ async fn run_attempt(
         quick_check_outcomes,
         quick_check_fix_loops,
         quick_check_failure_summary,
+        test_outcome,
+        failing_tests,
+        test_fix_loops,
         review_iterations,
         review_blocking_remaining: blocking_remaining,
         remaining_blocking_titles,
@@ -6280,6 +7031,89 @@ fn collect_sandbox_results(
     Ok(out)
 }
 
+/// Unified diff of `changes` against the files currently in `repo_root`.
+fn proposed_diff(repo_root: &Path, changes: &[ImplementationAppliedFile]) -> String {
+    let mut out = String::new();
+    for change in changes {
+        let current = std::fs::read_to_string(repo_root.join(&change.path)).ok();
+        let path = change.path.display().to_string();
+        let old_header = match current {
+            Some(_) => format!("a/{}", path),
+            None => "/dev/null".to_string(),
+        };
+        let diff = similar::TextDiff::from_lines(current.as_deref().unwrap_or(""), &change.content);
+        out.push_str(
+            &diff
+                .unified_diff()
+                .context_radius(3)
+                .header(&old_header, &format!("b/{}", path))
+                .to_string(),
+        );
+        if !out.is_empty() && !out.ends_with('\n') {
+            out.push('\n');
+        }
+    }
+    out
+}
+
+/// Run the quick checks `config` allows in `checkout`, a worktree of `repo_root`.
+///
+/// Returns the status, the commands that ran, and a summary of the failure when they failed.
+pub fn run_harness_quick_checks(
+    config: &ImplementationHarnessConfig,
+    checkout: &Path,
+    repo_root: &Path,
+) -> anyhow::Result<(
+    ImplementationQuickCheckStatus,
+    Option<String>,
+    Option<String>,
+)> {
+    let mut notes = Vec::new();
+    let (status, command, outcome) = run_quick_checks(
+        checkout,
+        Some(repo_root),
+        &mut notes,
+        config.quick_checks_mode,
+        config.quick_check_timeout_ms,
+        &config.quick_checks,
+    )?;
+    let failure = (status == ImplementationQuickCheckStatus::Failed)
+        .then(|| {
+            outcome.as_ref().map(|outcome| {
+                summarize_quick_check_failure(outcome).unwrap_or_else(|| {
+                    let output = if outcome.stderr_tail.trim().is_empty() {
+                        &outcome.stdout_tail
+                    } else {
+                        &outcome.stderr_tail
+                    };
+                    strip_ansi_sequences(output).trim().to_string()
+                })
+            })
+        })
+        .flatten();
+    Ok((status, command, failure))
+}
+
+/// Every harness report written under `.cosmos/apply_harness`, oldest first. Unreadable
+/// reports are skipped.
+pub fn load_harness_reports(repo_root: &Path) -> Vec<ImplementationRunDiagnostics> {
+    let Ok(entries) = std::fs::read_dir(repo_root.join(APPLY_HARNESS_REPORT_DIR)) else {
+        return Vec::new();
+    };
+    let mut reports: Vec<(std::time::SystemTime, ImplementationRunDiagnostics)> = entries
+        .flatten()
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|entry| {
+            let modified = entry.metadata().and_then(|m| m.modified()).ok()?;
+            let content = std::fs::read_to_string(entry.path()).ok()?;
+            let report = serde_json::from_str(&content).ok()?;
+            Some((modified, report))
+        })
+        .collect();
+    reports.sort_by_key(|(modified, _)| *modified);
+    reports.into_iter().map(|(_, report)| report).collect()
+}
+
 fn write_harness_report(
     repo_root: &Path,
     diagnostics: &ImplementationRunDiagnostics,
@@ -6391,6 +7225,27 @@ fn append_harness_telemetry(
     cache
         .append_implementation_harness(&record)
         .map_err(|e| anyhow::anyhow!("Failed to append implementation harness telemetry: {}", e))?;
+    if run_context == ImplementationHarnessRunContext::Interactive {
+        let mut failed_gates = Vec::new();
+        for gate in diagnostics
+            .attempts
+            .last()
+            .map(|attempt| attempt.gates.as_slice())
+            .unwrap_or_default()
+            .iter()
+            .filter(|gate| !gate.passed)
+        {
+            if !failed_gates.contains(&gate.gate) {
+                failed_gates.push(gate.gate.clone());
+            }
+        }
+        analytics::record(
+            repo_root,
+            "apply_harness",
+            Some(diagnostics.passed),
+            &failed_gates,
+        );
+    }
     Ok(())
 }
 
@@ -1,15 +1,87 @@
+use super::defaults::default_quick_check_required;
 use super::{
     strip_ansi_sequences, ImplementationCommandOutcome, ImplementationQuickCheckStatus,
     ImplementationQuickChecksMode,
 };
 use crate::lab::sandbox::SandboxSession;
-use cosmos_adapters::util::{run_command_with_timeout, truncate};
-use std::path::Path;
+use cosmos_adapters::util::{
+    run_command_with_timeout, run_command_with_timeout_streaming, truncate, CommandRunResult,
+    OutputLineSink,
+};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::Arc;
 use std::time::Duration;
 
 const MAX_COMMAND_OUTPUT_TAIL_CHARS: usize = 4_000;
 
+tokio::task_local! {
+    static CHECK_OUTPUT: OutputLineSink;
+}
+
+/// Run `fut` with quick-check and test-gate output sent to `sink` line by line as it is
+/// written, ANSI stripped. Each command starts with a `$ <command>` line.
+pub async fn with_check_output<F: Future>(sink: OutputLineSink, fut: F) -> F::Output {
+    CHECK_OUTPUT.scope(sink, fut).await
+}
+
+/// What a terminal would be left showing for one output line.
+///
+/// Progress bars redraw with `\r`; only the last redraw is kept.
+fn display_line(raw: &str) -> String {
+    let visible = raw
+        .rsplit('\r')
+        .find(|part| !part.trim().is_empty())
+        .unwrap_or("");
+    strip_ansi_sequences(visible)
+}
+
+/// Run a check command, streaming its output to the active `with_check_output` sink.
+fn run_check_command(
+    cmd: &mut Command,
+    command_str: &str,
+    timeout: Duration,
+) -> Result<CommandRunResult, String> {
+    let Ok(sink) = CHECK_OUTPUT.try_with(Arc::clone) else {
+        return run_command_with_timeout(cmd, timeout);
+    };
+    sink(&format!("$ {}", command_str));
+    let on_line: OutputLineSink = Arc::new(move |line: &str| sink(&display_line(line)));
+    run_command_with_timeout_streaming(cmd, timeout, Some(on_line))
+}
+
+/// A repo-declared quick check, read from `[[quick_checks]]` in `.cosmos/config.toml`.
+///
+/// Declared checks replace auto-detection; `COSMOS_FIX_HARNESS_CHECK_CMD` still wins.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuickCheckConfig {
+    /// Label used in notes; defaults to the command.
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Shell command, run with `sh -lc`.
+    pub command: String,
+    /// Working directory relative to the repo root.
+    #[serde(default)]
+    pub dir: Option<PathBuf>,
+    /// Per-command timeout, capped by the remaining quick-check budget.
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+    /// Optional checks never fail the apply; they only leave a note.
+    #[serde(default = "default_quick_check_required")]
+    pub required: bool,
+}
+
+impl QuickCheckConfig {
+    fn label(&self) -> &str {
+        self.name
+            .as_deref()
+            .filter(|name| !name.trim().is_empty())
+            .unwrap_or(&self.command)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub(super) enum QuickCheckCommand {
     Shell(String),
@@ -46,11 +118,39 @@ pub(super) fn program_available_on_path(program: &str) -> bool {
     false
 }
 
+fn env_quick_check_command() -> Option<QuickCheckCommand> {
+    std::env::var("COSMOS_FIX_HARNESS_CHECK_CMD")
+        .ok()
+        .filter(|shell_cmd| !shell_cmd.trim().is_empty())
+        .map(QuickCheckCommand::Shell)
+}
+
+/// The check the harness will run: the env override, then the repo's declared checks,
+/// then auto-detection.
+pub(super) fn planned_quick_check_command(
+    repo_root: &Path,
+    configured: &[QuickCheckConfig],
+) -> Option<QuickCheckCommand> {
+    if let Some(command) = env_quick_check_command() {
+        return Some(command);
+    }
+    if !configured.is_empty() {
+        let joined = configured
+            .iter()
+            .map(|check| match &check.dir {
+                Some(dir) => format!("(cd {} && {})", dir.display(), check.command),
+                None => check.command.clone(),
+            })
+            .collect::<Vec<_>>()
+            .join(" && ");
+        return Some(QuickCheckCommand::Shell(joined));
+    }
+    detect_quick_check_command(repo_root)
+}
+
 pub(super) fn detect_quick_check_command(repo_root: &Path) -> Option<QuickCheckCommand> {
-    if let Ok(shell_cmd) = std::env::var("COSMOS_FIX_HARNESS_CHECK_CMD") {
-        if !shell_cmd.trim().is_empty() {
-            return Some(QuickCheckCommand::Shell(shell_cmd));
-        }
+    if let Some(command) = env_quick_check_command() {
+        return Some(command);
     }
 
     if repo_root.join("Cargo.toml").exists() {
@@ -473,25 +573,127 @@ pub(super) fn tail_chars(s: &str, max_chars: usize) -> String {
         .collect::<String>()
 }
 
+type QuickCheckRun = (
+    ImplementationQuickCheckStatus,
+    Option<String>,
+    Option<ImplementationCommandOutcome>,
+);
+
 pub(super) fn run_quick_checks(
     repo_root: &Path,
     source_repo_root: Option<&Path>,
     notes: &mut Vec<String>,
     mode: ImplementationQuickChecksMode,
     timeout_ms: u64,
-) -> anyhow::Result<(
-    ImplementationQuickCheckStatus,
-    Option<String>,
-    Option<ImplementationCommandOutcome>,
-)> {
+    configured: &[QuickCheckConfig],
+) -> anyhow::Result<QuickCheckRun> {
     if mode == ImplementationQuickChecksMode::Disabled {
         return Ok((ImplementationQuickCheckStatus::Unavailable, None, None));
     }
 
+    if env_quick_check_command().is_none() && !configured.is_empty() {
+        return run_configured_quick_checks(
+            repo_root,
+            source_repo_root,
+            notes,
+            timeout_ms,
+            configured,
+        );
+    }
+
     let Some(command) = detect_quick_check_command(repo_root) else {
         return Ok((ImplementationQuickCheckStatus::Unavailable, None, None));
     };
+    run_quick_check_command(repo_root, source_repo_root, notes, command, timeout_ms)
+}
+
+/// Run the repo's declared checks in order.
+///
+/// Required checks decide the status; optional ones only leave a note when they fail.
+fn run_configured_quick_checks(
+    repo_root: &Path,
+    source_repo_root: Option<&Path>,
+    notes: &mut Vec<String>,
+    timeout_ms: u64,
+    configured: &[QuickCheckConfig],
+) -> anyhow::Result<QuickCheckRun> {
+    let start = std::time::Instant::now();
+    let mut commands = Vec::new();
+    let mut first_failure: Option<ImplementationCommandOutcome> = None;
+    let mut last_outcome = None;
+    let mut required_unavailable = false;
+
+    for check in configured {
+        let remaining_ms = timeout_ms.saturating_sub(start.elapsed().as_millis() as u64);
+        if remaining_ms == 0 {
+            notes.push(format!("quick_check_budget_exhausted: {}", check.label()));
+            if check.required {
+                required_unavailable = true;
+            }
+            continue;
+        }
+        let check_timeout_ms = check.timeout_ms.unwrap_or(timeout_ms).min(remaining_ms);
+        let (cwd, source_cwd) = match &check.dir {
+            Some(dir) => (
+                repo_root.join(dir),
+                source_repo_root.map(|root| root.join(dir)),
+            ),
+            None => (
+                repo_root.to_path_buf(),
+                source_repo_root.map(Path::to_path_buf),
+            ),
+        };
+        let (status, command, outcome) = run_quick_check_command(
+            &cwd,
+            source_cwd.as_deref(),
+            notes,
+            QuickCheckCommand::Shell(check.command.clone()),
+            check_timeout_ms,
+        )?;
+        commands.extend(command);
+        match status {
+            ImplementationQuickCheckStatus::Passed => {}
+            ImplementationQuickCheckStatus::Failed if check.required => {
+                if first_failure.is_none() {
+                    first_failure = outcome.clone();
+                }
+            }
+            ImplementationQuickCheckStatus::Unavailable if check.required => {
+                required_unavailable = true;
+            }
+            ImplementationQuickCheckStatus::Failed
+            | ImplementationQuickCheckStatus::Unavailable => {
+                notes.push(format!("quick_check_optional_failed: {}", check.label()));
+            }
+        }
+        if outcome.is_some() {
+            last_outcome = outcome;
+        }
+    }
+
+    let command = (!commands.is_empty()).then(|| commands.join(" && "));
+    if let Some(failure) = first_failure {
+        return Ok((
+            ImplementationQuickCheckStatus::Failed,
+            command,
+            Some(failure),
+        ));
+    }
+    let status = if required_unavailable {
+        ImplementationQuickCheckStatus::Unavailable
+    } else {
+        ImplementationQuickCheckStatus::Passed
+    };
+    Ok((status, command, last_outcome))
+}
 
+pub(super) fn run_quick_check_command(
+    repo_root: &Path,
+    source_repo_root: Option<&Path>,
+    notes: &mut Vec<String>,
+    command: QuickCheckCommand,
+    timeout_ms: u64,
+) -> anyhow::Result<QuickCheckRun> {
     if let Err(err) = ensure_quick_check_prereqs(repo_root, source_repo_root, &command, notes) {
         notes.push(format!(
             "quick_check_prereq_failed: {}",
@@ -547,7 +749,7 @@ pub(super) fn run_quick_checks(
 
     let start = std::time::Instant::now();
     let (output, start) =
-        match run_command_with_timeout(&mut cmd, Duration::from_millis(timeout_ms)) {
+        match run_check_command(&mut cmd, &command_str, Duration::from_millis(timeout_ms)) {
             Ok(output) => (output, start),
             Err(err) => {
                 if let Some(args) = python3_fallback_args {
@@ -568,8 +770,9 @@ pub(super) fn run_quick_checks(
                         fallback_cmd.env(k, v);
                     }
                     let fallback_start = std::time::Instant::now();
-                    match run_command_with_timeout(
+                    match run_check_command(
                         &mut fallback_cmd,
+                        &command_str,
                         Duration::from_millis(timeout_ms),
                     ) {
                         Ok(output) => (output, fallback_start),
@@ -0,0 +1,53 @@
+use super::license_gate::LicenseGateConfig;
+use super::quick_checks::QuickCheckConfig;
+use super::test_gate::TestGateConfig;
+use crate::llm::custom_tools::CustomToolManifest;
+use crate::plugins::PluginConfig;
+use cosmos_adapters::config::ModelRoute;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Repo-level harness settings, relative to the repo root.
+pub(crate) const REPO_CONFIG_PATH: &str = ".cosmos/config.toml";
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub(crate) struct RepoHarnessConfig {
+    pub license: Option<LicenseGateConfig>,
+    pub quick_checks: Vec<QuickCheckConfig>,
+    pub tests: Option<TestGateConfig>,
+    /// Default per-role routes; the user's own `model_routing` entries take precedence.
+    pub model_routing: BTreeMap<String, ModelRoute>,
+    pub budget: Option<RepoBudget>,
+    pub plugins: Vec<PluginConfig>,
+    /// Extra agent tools; see `custom_tools`
+    pub tools: Vec<CustomToolManifest>,
+}
+
+/// Per-fix spending limits that replace the harness defaults.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct RepoBudget {
+    pub max_cost_usd: Option<f64>,
+    pub max_seconds: Option<u64>,
+}
+
+/// Load `.cosmos/config.toml`; a missing file means no overrides.
+pub(crate) fn load_repo_harness_config(repo_root: &Path) -> anyhow::Result<RepoHarnessConfig> {
+    let path = repo_root.join(REPO_CONFIG_PATH);
+    let content = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(RepoHarnessConfig::default())
+        }
+        Err(e) => {
+            return Err(anyhow::anyhow!(
+                "Failed to read {}: {}",
+                REPO_CONFIG_PATH,
+                e
+            ))
+        }
+    };
+    toml::from_str(&content).map_err(|e| anyhow::anyhow!("Invalid {}: {}", REPO_CONFIG_PATH, e))
+}
@@ -0,0 +1,178 @@
+use super::defaults::default_max_auto_test_fix_loops;
+use super::quick_checks::{run_quick_check_command, QuickCheckCommand};
+use super::{strip_ansi_sequences, ImplementationCommandOutcome, ImplementationQuickCheckStatus};
+use cosmos_adapters::util::truncate;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Placeholder in the test command replaced by the shell-quoted changed files.
+const FILES_PLACEHOLDER: &str = "{files}";
+
+/// Most failing test names kept per run (and fed back to the repair prompt).
+const MAX_FAILING_TESTS: usize = 20;
+
+/// Optional scoped test run, read from the `[tests]` table of `.cosmos/config.toml`.
+///
+/// Runs after quick checks pass, e.g. `cargo test -p core` or `pnpm vitest related {files}`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TestGateConfig {
+    /// Shell command, run with `sh -lc`. `{files}` expands to the changed files.
+    pub command: String,
+    /// Working directory relative to the repo root.
+    #[serde(default)]
+    pub dir: Option<PathBuf>,
+    /// Timeout for one test run, capped by the remaining attempt budget.
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+    /// Repair loops fed with the failing test names before the attempt fails.
+    #[serde(default = "default_max_auto_test_fix_loops")]
+    pub max_fix_loops: usize,
+}
+
+/// Result of one run of the test gate.
+#[derive(Debug, Clone)]
+pub(super) struct TestGateRun {
+    pub status: ImplementationQuickCheckStatus,
+    pub command: Option<String>,
+    pub outcome: Option<ImplementationCommandOutcome>,
+    pub failing_tests: Vec<String>,
+}
+
+fn shell_quote(raw: &str) -> String {
+    if !raw.is_empty()
+        && raw
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "/._-+".contains(c))
+    {
+        raw.to_string()
+    } else {
+        format!("'{}'", raw.replace('\'', "'\\''"))
+    }
+}
+
+/// Expand `{files}` with the changed files, relative to the test working directory.
+pub(super) fn render_test_command(config: &TestGateConfig, changed_files: &[PathBuf]) -> String {
+    if !config.command.contains(FILES_PLACEHOLDER) {
+        return config.command.clone();
+    }
+    let files = changed_files
+        .iter()
+        .map(|file| match &config.dir {
+            Some(dir) => file.strip_prefix(dir).unwrap_or(file),
+            None => file.as_path(),
+        })
+        .map(|file| shell_quote(&file.to_string_lossy()))
+        .collect::<Vec<_>>()
+        .join(" ");
+    config.command.replace(FILES_PLACEHOLDER, &files)
+}
+
+pub(super) fn run_test_gate(
+    repo_root: &Path,
+    source_repo_root: Option<&Path>,
+    notes: &mut Vec<String>,
+    config: &TestGateConfig,
+    changed_files: &[PathBuf],
+    timeout_ms: u64,
+) -> anyhow::Result<TestGateRun> {
+    let (cwd, source_cwd) = match &config.dir {
+        Some(dir) => (
+            repo_root.join(dir),
+            source_repo_root.map(|root| root.join(dir)),
+        ),
+        None => (
+            repo_root.to_path_buf(),
+            source_repo_root.map(Path::to_path_buf),
+        ),
+    };
+    let timeout_ms = config.timeout_ms.unwrap_or(timeout_ms).min(timeout_ms);
+    let (status, command, outcome) = run_quick_check_command(
+        &cwd,
+        source_cwd.as_deref(),
+        notes,
+        QuickCheckCommand::Shell(render_test_command(config, changed_files)),
+        timeout_ms,
+    )?;
+    let failing_tests = match (&outcome, status) {
+        (Some(outcome), ImplementationQuickCheckStatus::Failed) => parse_failing_tests(outcome),
+        _ => Vec::new(),
+    };
+    Ok(TestGateRun {
+        status,
+        command,
+        outcome,
+        failing_tests,
+    })
+}
+
+/// Names of failing tests reported by common runners, in output order.
+pub(super) fn parse_failing_tests(outcome: &ImplementationCommandOutcome) -> Vec<String> {
+    let output = format!(
+        "{}\n{}",
+        strip_ansi_sequences(&outcome.stdout_tail),
+        strip_ansi_sequences(&outcome.stderr_tail)
+    );
+    let patterns = [
+        // cargo test
+        r"^test (\S+) \.\.\. FAILED$",
+        // pytest
+        r"^FAILED (\S+)",
+        // go test
+        r"^\s*--- FAIL: (\S+)",
+        // vitest / jest
+        r"^\s*(?:FAIL|×|✕|✗)\s+(.+?\S)(?:\s+\d+ms)?$",
+        r"^\s*● (.+›.+)$",
+    ]
+    .iter()
+    .filter_map(|pattern| Regex::new(pattern).ok())
+    .collect::<Vec<_>>();
+    let mut names: Vec<String> = Vec::new();
+    for line in output.lines() {
+        let Some(name) = patterns
+            .iter()
+            .find_map(|pattern| pattern.captures(line.trim_end()))
+            .and_then(|captures| captures.get(1))
+            .map(|m| m.as_str().trim().to_string())
+        else {
+            continue;
+        };
+        if !names.contains(&name) {
+            names.push(name);
+        }
+        if names.len() >= MAX_FAILING_TESTS {
+            break;
+        }
+    }
+    names
+}
+
+pub(super) fn format_test_repair_modifier(
+    existing: Option<&str>,
+    failing_tests: &[String],
+    outcome: &ImplementationCommandOutcome,
+    target: &Path,
+) -> String {
+    let mut parts = Vec::new();
+    if let Some(existing) = existing.map(str::trim).filter(|s| !s.is_empty()) {
+        parts.push(existing.to_string());
+    }
+    let failing = if failing_tests.is_empty() {
+        "- (runner output did not name the failing tests)".to_string()
+    } else {
+        failing_tests
+            .iter()
+            .map(|name| format!("- {}", name))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    let output = strip_ansi_sequences(&format!("{}\n{}", outcome.stdout_tail, outcome.stderr_tail));
+    parts.push(format!(
+        "Test repair request:\n- Test command: {}\n- File to repair: {}\nFailing tests:\n{}\n- Test output (truncated):\n{}\nRules:\n- Modify only this file; do not edit or skip the tests.\n- Make the failing tests pass without changing unrelated behavior.\n- Keep the diff minimal.",
+        outcome.command,
+        target.display(),
+        failing,
+        truncate(output.trim(), 900),
+    ));
+    parts.join("\n\n")
+}
@@ -16,6 +16,29 @@ fn plain_language_gate_accepts_short_user_facing_text() {
     ));
 }
 
+#[test]
+fn proposed_diff_covers_edited_and_new_files() {
+    let root = tempdir().unwrap();
+    std::fs::write(root.path().join("a.rs"), "fn a() {}\nfn b() {}\n").unwrap();
+    let changes = vec![
+        ImplementationAppliedFile {
+            path: PathBuf::from("a.rs"),
+            summary: String::new(),
+            content: "fn a() {}\nfn c() {}\n".to_string(),
+        },
+        ImplementationAppliedFile {
+            path: PathBuf::from("new.rs"),
+            summary: String::new(),
+            content: "fn n() {}\n".to_string(),
+        },
+    ];
+    let diff = proposed_diff(root.path(), &changes);
+    assert!(diff.contains("--- a/a.rs\n+++ b/a.rs\n"));
+    assert!(diff.contains("-fn b() {}\n+fn c() {}\n"));
+    assert!(diff.contains("--- /dev/null\n+++ b/new.rs\n"));
+    assert!(load_harness_reports(root.path()).is_empty());
+}
+
 #[test]
 fn diff_line_parser_ignores_headers() {
     let sample = "\
@@ -129,6 +152,133 @@ fn binary_write_gate_rejects_binary_extension() {
     assert!(result.is_err());
 }
 
+fn license_config(header: &str, insert_missing: bool) -> LicenseGateConfig {
+    LicenseGateConfig {
+        header: header.to_string(),
+        insert_missing,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn license_gate_rejects_missing_header_without_insert() {
+    let sandbox = tempdir().unwrap();
+    let original = tempdir().unwrap();
+    std::fs::write(sandbox.path().join("lib.rs"), "fn main() {}\n").unwrap();
+    let config = license_config("Copyright {year} Acme Corp.", false);
+
+    let err = license_gate(
+        sandbox.path(),
+        original.path(),
+        &[PathBuf::from("lib.rs")],
+        &config,
+    )
+    .unwrap_err();
+    assert!(err.contains("missing the required license header"));
+}
+
+#[test]
+fn license_gate_inserts_header_after_shebang_and_accepts_any_year() {
+    let sandbox = tempdir().unwrap();
+    let original = tempdir().unwrap();
+    std::fs::write(
+        sandbox.path().join("run.py"),
+        "#!/usr/bin/env python3\nprint('hi')\n",
+    )
+    .unwrap();
+    std::fs::write(
+        sandbox.path().join("old.rs"),
+        "// Copyright 2019 Acme Corp.\n\nfn old() {}\n",
+    )
+    .unwrap();
+    let config = license_config("Copyright {year} Acme Corp.", true);
+
+    let inserted = license_gate(
+        sandbox.path(),
+        original.path(),
+        &[PathBuf::from("run.py"), PathBuf::from("old.rs")],
+        &config,
+    )
+    .unwrap();
+
+    assert_eq!(inserted, vec![PathBuf::from("run.py")]);
+    let updated = std::fs::read_to_string(sandbox.path().join("run.py")).unwrap();
+    assert!(updated.starts_with("#!/usr/bin/env python3\n# Copyright "));
+    assert!(updated.ends_with("Acme Corp.\n\nprint('hi')\n"));
+}
+
+#[test]
+fn license_gate_flags_denylisted_markers_only_in_added_lines() {
+    let sandbox = tempdir().unwrap();
+    let original = tempdir().unwrap();
+    let existing = "// SPDX-License-Identifier: GPL-2.0\nfn vendored() {}\n";
+    std::fs::write(original.path().join("vendored.rs"), existing).unwrap();
+    std::fs::write(
+        sandbox.path().join("vendored.rs"),
+        format!("{}fn tweak() {{}}\n", existing),
+    )
+    .unwrap();
+    std::fs::write(
+        sandbox.path().join("copied.rs"),
+        "// This program is free software; you can redistribute it\nfn copied() {}\n",
+    )
+    .unwrap();
+    let config = LicenseGateConfig {
+        denylist: vec!["Proprietary Vendor SDK".to_string()],
+        ..Default::default()
+    };
+
+    assert!(license_gate(
+        sandbox.path(),
+        original.path(),
+        &[PathBuf::from("vendored.rs")],
+        &config,
+    )
+    .is_ok());
+    let err = license_gate(
+        sandbox.path(),
+        original.path(),
+        &[PathBuf::from("copied.rs")],
+        &config,
+    )
+    .unwrap_err();
+    assert!(err.contains("copied.rs contains licensed-source markers"));
+
+    let custom =
+        denylisted_markers_in_added_lines(None, "// from Proprietary Vendor SDK samples", &config);
+    assert_eq!(custom, vec!["proprietary vendor sdk".to_string()]);
+}
+
+#[test]
+fn repo_config_loads_license_table() {
+    let root = tempdir().unwrap();
+    let config = ImplementationHarnessConfig::interactive_strict()
+        .with_repo_config(root.path())
+        .unwrap();
+    assert!(config.license_gate.is_none());
+
+    std::fs::create_dir_all(root.path().join(".cosmos")).unwrap();
+    std::fs::write(
+        root.path().join(".cosmos/config.toml"),
+        "[license]\nheader = \"Copyright {year} Acme Corp.\"\ninsert_missing = true\n",
+    )
+    .unwrap();
+    let config = ImplementationHarnessConfig::interactive_strict()
+        .with_repo_config(root.path())
+        .unwrap();
+    let license = config.license_gate.expect("license table should load");
+    assert!(license.insert_missing);
+    assert!(has_license_header(
+        "// Copyright 2024 Acme Corp.\n",
+        &license
+    ));
+
+    std::fs::write(root.path().join(".cosmos/config.toml"), "[license\n").unwrap();
+    assert!(ImplementationHarnessConfig::interactive_strict()
+        .with_repo_config(root.path())
+        .is_err());
+}
+
 #[test]
 fn quick_checks_disabled_returns_unavailable() {
     let root = tempdir().unwrap();
@@ -138,6 +288,7 @@ fn quick_checks_disabled_returns_unavailable() {
         &mut Vec::new(),
         ImplementationQuickChecksMode::Disabled,
         100,
+        &[],
     )
     .unwrap();
     assert_eq!(status, ImplementationQuickCheckStatus::Unavailable);
@@ -145,6 +296,281 @@ fn quick_checks_disabled_returns_unavailable() {
     assert!(outcome.is_none());
 }
 
+#[test]
+fn repo_config_loads_quick_checks() {
+    let root = tempdir().unwrap();
+    std::fs::create_dir_all(root.path().join(".cosmos")).unwrap();
+    std::fs::write(
+        root.path().join(".cosmos/config.toml"),
+        "[[quick_checks]]\nname = \"lint\"\ncommand = \"npm run lint\"\ndir = \"web\"\ntimeout_ms = 5000\n\n[[quick_checks]]\ncommand = \"cargo test -p core\"\nrequired = false\n",
+    )
+    .unwrap();
+    let config = ImplementationHarnessConfig::interactive_strict()
+        .with_repo_config(root.path())
+        .unwrap();
+    assert_eq!(config.quick_checks.len(), 2);
+    assert_eq!(config.quick_checks[0].dir, Some(PathBuf::from("web")));
+    assert_eq!(config.quick_checks[0].timeout_ms, Some(5000));
+    assert!(config.quick_checks[0].required);
+    assert!(!config.quick_checks[1].required);
+    let planned = planned_quick_check_command(root.path(), &config.quick_checks)
+        .map(|command| command_to_string(&command));
+    assert_eq!(
+        planned.as_deref(),
+        Some("sh -lc '(cd web && npm run lint) && cargo test -p core'")
+    );
+
+    for invalid in [
+        "[[quick_checks]]\ncommand = \"  \"\n",
+        "[[quick_checks]]\ncommand = \"make\"\ndir = \"../outside\"\n",
+    ] {
+        std::fs::write(root.path().join(".cosmos/config.toml"), invalid).unwrap();
+        assert!(ImplementationHarnessConfig::interactive_strict()
+            .with_repo_config(root.path())
+            .is_err());
+    }
+}
+
+#[test]
+fn repo_config_loads_test_gate() {
+    let root = tempdir().unwrap();
+    std::fs::create_dir_all(root.path().join(".cosmos")).unwrap();
+    std::fs::write(
+        root.path().join(".cosmos/config.toml"),
+        "[tests]\ncommand = \"pnpm vitest related {files}\"\ndir = \"web\"\n",
+    )
+    .unwrap();
+    let config = ImplementationHarnessConfig::interactive_strict()
+        .with_repo_config(root.path())
+        .unwrap();
+    let tests = config.test_gate.expect("test gate");
+    assert_eq!(tests.max_fix_loops, 1);
+    assert_eq!(
+        test_gate::render_test_command(
+            &tests,
+            &[
+                PathBuf::from("web/src/a.ts"),
+                PathBuf::from("web/src/it's.ts")
+            ]
+        ),
+        "pnpm vitest related src/a.ts 'src/it'\\''s.ts'"
+    );
+
+    std::fs::write(
+        root.path().join(".cosmos/config.toml"),
+        "[tests]\ncommand = \"\"\n",
+    )
+    .unwrap();
+    assert!(ImplementationHarnessConfig::interactive_strict()
+        .with_repo_config(root.path())
+        .is_err());
+}
+
+#[test]
+fn repo_config_budget_replaces_harness_limits() {
+    let root = tempdir().unwrap();
+    std::fs::create_dir_all(root.path().join(".cosmos")).unwrap();
+    std::fs::write(
+        root.path().join(".cosmos/config.toml"),
+        "[budget]\nmax_cost_usd = 0.05\nmax_seconds = 90\n\n[model_routing.review]\ntemperature = 0.1\n",
+    )
+    .unwrap();
+    let config = ImplementationHarnessConfig::interactive_strict()
+        .with_repo_config(root.path())
+        .unwrap();
+    assert_eq!(config.max_total_cost_usd, 0.05);
+    assert_eq!(config.max_total_ms, 90_000);
+    let routes = crate::llm::routing::load_repo_model_routing(root.path()).unwrap();
+    assert_eq!(routes["review"].temperature, Some(0.1));
+
+    std::fs::write(
+        root.path().join(".cosmos/config.toml"),
+        "[budget]\nmax_cost_usd = 0\n\n[model_routing.reveiw]\n",
+    )
+    .unwrap();
+    assert!(ImplementationHarnessConfig::interactive_strict()
+        .with_repo_config(root.path())
+        .is_err());
+    assert!(crate::llm::routing::load_repo_model_routing(root.path()).is_err());
+}
+
+#[test]
+fn untrusted_workspace_config_runs_no_repo_commands() {
+    let root = tempdir().unwrap();
+    std::fs::create_dir_all(root.path().join(".cosmos")).unwrap();
+    std::fs::write(
+        root.path().join(".cosmos/config.toml"),
+        "[[quick_checks]]\ncommand = \"npm run lint\"\n\n[tests]\ncommand = \"npm test\"\n",
+    )
+    .unwrap();
+    let config = ImplementationHarnessConfig::interactive_strict()
+        .with_repo_config(root.path())
+        .unwrap()
+        .for_untrusted_workspace();
+    assert_eq!(
+        config.quick_checks_mode,
+        ImplementationQuickChecksMode::Disabled
+    );
+    assert!(config.quick_checks.is_empty());
+    assert!(config.test_gate.is_none());
+    assert!(!config.enable_quick_check_baseline);
+    assert!(!config.require_quick_check_detectable);
+}
+
+#[test]
+fn failing_test_names_are_parsed_from_common_runners() {
+    let outcome = |stdout: &str| ImplementationCommandOutcome {
+        command: "tests".to_string(),
+        duration_ms: 1,
+        success: false,
+        timed_out: false,
+        exit_code: Some(1),
+        stdout_tail: stdout.to_string(),
+        stderr_tail: String::new(),
+    };
+    let cargo = "running 2 tests\ntest parse::empty ... FAILED\ntest parse::ok ... ok\n\nfailures:\n    parse::empty\n";
+    assert_eq!(
+        test_gate::parse_failing_tests(&outcome(cargo)),
+        vec!["parse::empty"]
+    );
+    let pytest = "FAILED tests/test_api.py::test_login - AssertionError\nFAILED tests/test_api.py::test_login - again\n";
+    assert_eq!(
+        test_gate::parse_failing_tests(&outcome(pytest)),
+        vec!["tests/test_api.py::test_login"]
+    );
+    let go = "--- FAIL: TestParse (0.00s)\n    --- FAIL: TestParse/empty (0.00s)\nFAIL\n";
+    assert_eq!(
+        test_gate::parse_failing_tests(&outcome(go)),
+        vec!["TestParse", "TestParse/empty"]
+    );
+    let vitest = " \u{1b}[31m×\u{1b}[39m src/a.test.ts > parse > rejects empty 4ms\n ✓ src/a.test.ts > parse > accepts 1ms\n";
+    assert_eq!(
+        test_gate::parse_failing_tests(&outcome(vitest)),
+        vec!["src/a.test.ts > parse > rejects empty"]
+    );
+}
+
+#[test]
+fn test_gate_reports_failing_tests_from_the_sandbox() {
+    let root = tempdir().unwrap();
+    let config = TestGateConfig {
+        command: "echo 'test core::adds ... FAILED'; exit 101".to_string(),
+        dir: None,
+        timeout_ms: Some(5_000),
+        max_fix_loops: 1,
+    };
+    let run =
+        test_gate::run_test_gate(root.path(), None, &mut Vec::new(), &config, &[], 10_000).unwrap();
+    assert_eq!(run.status, ImplementationQuickCheckStatus::Failed);
+    assert_eq!(run.failing_tests, vec!["core::adds"]);
+    let modifier = test_gate::format_test_repair_modifier(
+        None,
+        &run.failing_tests,
+        run.outcome.as_ref().unwrap(),
+        Path::new("src/core.rs"),
+    );
+    assert!(modifier.contains("- core::adds"), "{}", modifier);
+    assert!(
+        modifier.contains("File to repair: src/core.rs"),
+        "{}",
+        modifier
+    );
+
+    let passing = TestGateConfig {
+        command: "true".to_string(),
+        ..config
+    };
+    let run = test_gate::run_test_gate(root.path(), None, &mut Vec::new(), &passing, &[], 10_000)
+        .unwrap();
+    assert_eq!(run.status, ImplementationQuickCheckStatus::Passed);
+    assert!(run.failing_tests.is_empty());
+}
+
+#[test]
+fn configured_quick_checks_respect_required_flag_and_dir() {
+    let root = tempdir().unwrap();
+    std::fs::create_dir_all(root.path().join("web")).unwrap();
+    std::fs::write(root.path().join("web/marker"), "").unwrap();
+    let check = |command: &str, dir: Option<&str>, required: bool| QuickCheckConfig {
+        name: None,
+        command: command.to_string(),
+        dir: dir.map(PathBuf::from),
+        timeout_ms: Some(5_000),
+        required,
+    };
+
+    let mut notes = Vec::new();
+    let (status, command, _) = run_quick_checks(
+        root.path(),
+        None,
+        &mut notes,
+        ImplementationQuickChecksMode::StrictAuto,
+        10_000,
+        &[
+            check("test -f marker", Some("web"), true),
+            check("exit 3", None, false),
+        ],
+    )
+    .unwrap();
+    assert_eq!(status, ImplementationQuickCheckStatus::Passed);
+    assert_eq!(
+        command.as_deref(),
+        Some("sh -lc 'test -f marker' && sh -lc 'exit 3'")
+    );
+    assert!(notes
+        .iter()
+        .any(|note| note == "quick_check_optional_failed: exit 3"));
+
+    let (status, _, outcome) = run_quick_checks(
+        root.path(),
+        None,
+        &mut Vec::new(),
+        ImplementationQuickChecksMode::StrictAuto,
+        10_000,
+        &[check("exit 4", None, true), check("true", None, true)],
+    )
+    .unwrap();
+    assert_eq!(status, ImplementationQuickCheckStatus::Failed);
+    let outcome = outcome.expect("failed check should report its outcome");
+    assert_eq!(outcome.command, "sh -lc 'exit 4'");
+    assert_eq!(outcome.exit_code, Some(4));
+}
+
+#[tokio::test]
+async fn quick_check_output_streams_clean_lines_to_the_sink() {
+    let root = tempdir().unwrap();
+    let lines = std::sync::Arc::new(std::sync::Mutex::new(Vec::<String>::new()));
+    let seen = std::sync::Arc::clone(&lines);
+    let check = QuickCheckConfig {
+        name: None,
+        command: r"printf '\033[32mok\033[0m\n10%%\r100%%\n'".to_string(),
+        dir: None,
+        timeout_ms: Some(5_000),
+        required: true,
+    };
+
+    let (status, _, _) = with_check_output(
+        std::sync::Arc::new(move |line: &str| seen.lock().unwrap().push(line.to_string())),
+        async {
+            run_quick_checks(
+                root.path(),
+                None,
+                &mut Vec::new(),
+                ImplementationQuickChecksMode::StrictAuto,
+                10_000,
+                &[check],
+            )
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(status, ImplementationQuickCheckStatus::Passed);
+    // The command header, then output with colors stripped and progress redraws collapsed.
+    let lines = lines.lock().unwrap();
+    assert!(lines[0].starts_with("$ sh -lc 'printf"));
+    assert_eq!(lines[1..], ["ok".to_string(), "100%".to_string()]);
+}
+
 #[test]
 fn quick_check_policy_matrix_matches_profiles() {
     let interactive = ImplementationHarnessConfig::interactive_strict();
@@ -812,3 +1238,54 @@ fn run_git(repo_root: &Path, args: &[&str]) {
         status
     );
 }
+
+#[test]
+fn dry_run_apply_plan_lists_files_gates_and_calls() {
+    use crate::llm::dry_run::DryRunGateOutcome;
+    use cosmos_core::suggest::{Priority, SuggestionKind, SuggestionSource};
+
+    let root = tempdir().unwrap();
+    std::fs::create_dir_all(root.path().join("src")).unwrap();
+    std::fs::write(root.path().join("src/lib.rs"), "pub fn parse() {}\n").unwrap();
+    std::fs::write(root.path().join("Cargo.toml"), "[package]\nname = \"x\"\n").unwrap();
+    let mut suggestion = Suggestion::new(
+        SuggestionKind::BugFix,
+        Priority::High,
+        PathBuf::from("src/lib.rs"),
+        "Parsing panics on empty input".to_string(),
+        SuggestionSource::LlmDeep,
+    );
+    suggestion.additional_files = vec![PathBuf::from("assets/logo.png")];
+    let config = ImplementationHarnessConfig::interactive_strict();
+
+    let plan = plan_harness_apply(root.path(), &suggestion, &config);
+    assert_eq!(
+        plan.files_written,
+        vec![
+            PathBuf::from("src/lib.rs"),
+            PathBuf::from("assets/logo.png")
+        ]
+    );
+    let outcome = |name: &str| {
+        plan.gates
+            .iter()
+            .find(|gate| gate.gate == name)
+            .map(|gate| gate.outcome)
+    };
+    assert_eq!(outcome("scope"), Some(DryRunGateOutcome::Pass));
+    assert_eq!(outcome("binary_write"), Some(DryRunGateOutcome::Fail));
+    assert_eq!(outcome("quick_check"), Some(DryRunGateOutcome::AtRunTime));
+    assert_eq!(outcome("tests"), None);
+    let unconditional = plan
+        .calls
+        .iter()
+        .filter(|call| !call.conditional)
+        .map(|call| call.label.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(unconditional, vec!["generation", "review"]);
+    assert!(plan
+        .calls
+        .iter()
+        .any(|call| call.label == format!("attempts 2-{}", config.max_attempts)));
+    assert_eq!(plan.max_total_cost_usd, config.max_total_cost_usd);
+}
@@ -0,0 +1,596 @@
+//! Merge conflict resolution.
+//!
+//! Parses the conflict blocks git leaves in a file and asks the model to resolve each one. The
+//! rebase assistant resolves every block it meets. `cosmos resolve` proposes resolutions for
+//! conflicts already in the working tree, gates them like an applied fix (syntax, then quick
+//! checks in a sandbox worktree), and leaves accepting or rejecting each block to the user.
+
+use super::client::{call_llm_structured, StructuredResponse};
+use super::implementation::{
+    run_harness_quick_checks, ImplementationHarnessConfig, ImplementationQuickCheckStatus,
+};
+use super::models::{merge_usage, Model, Usage};
+use super::prompts::MERGE_CONFLICT_SYSTEM;
+use super::routing;
+use crate::lab::sandbox::SandboxSession;
+use cosmos_adapters::config::ModelRole;
+use cosmos_adapters::git_ops;
+use cosmos_adapters::util::truncate;
+use cosmos_core::index::parser::{parse_file, parse_file_has_errors};
+use cosmos_core::index::Language;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// Conflicted files longer than this are sent as their hunks only.
+const MAX_FILE_CHARS: usize = 40_000;
+
+/// One `<<<<<<<` ... `>>>>>>>` block in a conflicted file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConflictHunk {
+    /// The checked-out side (the updated base while rebasing)
+    pub ours: String,
+    /// The incoming side (the commit being replayed while rebasing)
+    pub theirs: String,
+    /// What both sides started from, when git wrote diff3 markers
+    pub ancestor: Option<String>,
+    /// The block as it appears in the file, markers included
+    pub raw: String,
+    /// 1-based lines of the opening and closing markers
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+/// What each side of a conflict holds, so the model knows which change to build on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictSides {
+    /// Replaying a branch onto its base
+    Rebase,
+    /// Anything else that leaves conflicts: merge, cherry-pick, stash pop
+    Merge,
+}
+
+impl ConflictSides {
+    fn describe(self) -> &'static str {
+        match self {
+            ConflictSides::Rebase => {
+                "OURS is the updated base; THEIRS is the fix commit being replayed onto it."
+            }
+            ConflictSides::Merge => {
+                "OURS is the checked-out branch (HEAD); THEIRS is the change being brought in."
+            }
+        }
+    }
+}
+
+enum Segment {
+    Text(String),
+    Conflict(ConflictHunk),
+}
+
+fn is_marker(line: &str, marker: &str) -> bool {
+    line.strip_prefix(marker)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with([' ', '\n', '\r']))
+}
+
+/// Split a conflicted file into plain text and conflict blocks; None if a block never ends.
+fn parse_segments(content: &str) -> Option<Vec<Segment>> {
+    enum Side {
+        Ours,
+        Ancestor,
+        Theirs,
+    }
+
+    let mut segments = Vec::new();
+    let mut text = String::new();
+    let mut open: Option<(Side, ConflictHunk)> = None;
+    for (index, line) in content.split_inclusive('\n').enumerate() {
+        let line_number = index + 1;
+        match open.as_mut() {
+            None if is_marker(line, "<<<<<<<") => {
+                if !text.is_empty() {
+                    segments.push(Segment::Text(std::mem::take(&mut text)));
+                }
+                open = Some((
+                    Side::Ours,
+                    ConflictHunk {
+                        ours: String::new(),
+                        theirs: String::new(),
+                        ancestor: None,
+                        raw: line.to_string(),
+                        start_line: line_number,
+                        end_line: line_number,
+                    },
+                ));
+            }
+            None => text.push_str(line),
+            Some((side, hunk)) => {
+                hunk.raw.push_str(line);
+                if is_marker(line, "|||||||") {
+                    *side = Side::Ancestor;
+                    hunk.ancestor = Some(String::new());
+                } else if is_marker(line, "=======") {
+                    *side = Side::Theirs;
+                } else if is_marker(line, ">>>>>>>") {
+                    let (_, mut hunk) = open.take()?;
+                    hunk.end_line = line_number;
+                    segments.push(Segment::Conflict(hunk));
+                } else {
+                    match side {
+                        Side::Ours => hunk.ours.push_str(line),
+                        Side::Ancestor => {
+                            hunk.ancestor.get_or_insert_with(String::new).push_str(line)
+                        }
+                        Side::Theirs => hunk.theirs.push_str(line),
+                    }
+                }
+            }
+        }
+    }
+    if open.is_some() {
+        return None;
+    }
+    if !text.is_empty() {
+        segments.push(Segment::Text(text));
+    }
+    Some(segments)
+}
+
+/// Conflict blocks in a file git left conflicted, in order.
+pub fn conflict_hunks(content: &str) -> Vec<ConflictHunk> {
+    parse_segments(content)
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|segment| match segment {
+            Segment::Conflict(hunk) => Some(hunk),
+            Segment::Text(_) => None,
+        })
+        .collect()
+}
+
+/// Replace conflict blocks in order; a `None` resolution leaves that block as it is.
+pub fn resolve_hunks(content: &str, resolutions: &[Option<&str>]) -> anyhow::Result<String> {
+    let segments = parse_segments(content)
+        .ok_or_else(|| anyhow::anyhow!("A conflict block is missing its closing marker"))?;
+    let mut resolutions = resolutions.iter();
+    let mut out = String::with_capacity(content.len());
+    for segment in segments {
+        match segment {
+            Segment::Text(text) => out.push_str(&text),
+            Segment::Conflict(hunk) => {
+                let resolution = resolutions
+                    .next()
+                    .ok_or_else(|| anyhow::anyhow!("Missing a resolution for a conflict"))?;
+                match resolution {
+                    Some(resolution) => {
+                        out.push_str(resolution);
+                        if !resolution.is_empty() && !resolution.ends_with('\n') {
+                            out.push('\n');
+                        }
+                    }
+                    None => out.push_str(&hunk.raw),
+                }
+            }
+        }
+    }
+    if resolutions.next().is_some() {
+        anyhow::bail!("More resolutions than conflicts");
+    }
+    Ok(out)
+}
+
+/// Why `content` doesn't parse as the language of `path`, if it doesn't.
+pub fn syntax_error(path: &Path, content: &str) -> Option<String> {
+    let language = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(Language::from_extension)
+        .unwrap_or(Language::Unknown);
+    if language == Language::Unknown {
+        return None;
+    }
+    if let Err(err) = parse_file(path, content, language) {
+        return Some(truncate(&err.to_string(), 180));
+    }
+    match parse_file_has_errors(path, content, language) {
+        Ok(false) => None,
+        Ok(true) => Some("syntax errors detected".to_string()),
+        Err(err) => Some(truncate(&err.to_string(), 180)),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ConflictResolutionResponse {
+    resolutions: Vec<HunkResolution>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HunkResolution {
+    hunk: usize,
+    content: String,
+}
+
+fn conflict_resolution_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": "object",
+        "additionalProperties": false,
+        "properties": {
+            "resolutions": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "additionalProperties": false,
+                    "properties": {
+                        "hunk": { "type": "integer" },
+                        "content": { "type": "string" }
+                    },
+                    "required": ["hunk", "content"]
+                }
+            }
+        },
+        "required": ["resolutions"]
+    })
+}
+
+/// Ask the model for one resolution per conflict block in `content`.
+///
+/// `feedback` explains what was wrong with a previous attempt.
+pub(crate) async fn propose_resolutions(
+    path: &Path,
+    content: &str,
+    hunks: &[ConflictHunk],
+    sides: ConflictSides,
+    feedback: Option<&str>,
+) -> anyhow::Result<(Vec<String>, Option<Usage>)> {
+    let mut user = format!("FILE: {}\n{}\n\n", path.display(), sides.describe());
+    if content.len() <= MAX_FILE_CHARS {
+        user.push_str(&format!("CONFLICTED FILE:\n{}\n\n", content));
+    }
+    for (i, hunk) in hunks.iter().enumerate() {
+        user.push_str(&format!("HUNK {}\nOURS:\n{}", i + 1, hunk.ours));
+        if let Some(ancestor) = &hunk.ancestor {
+            user.push_str(&format!("ANCESTOR:\n{}", ancestor));
+        }
+        user.push_str(&format!("THEIRS:\n{}\n", hunk.theirs));
+    }
+    if let Some(feedback) = feedback {
+        user.push_str(&format!("\nPREVIOUS ATTEMPT FAILED:\n{}\n", feedback));
+    }
+
+    let StructuredResponse {
+        data: parsed,
+        usage,
+        ..
+    } = call_llm_structured::<ConflictResolutionResponse>(
+        MERGE_CONFLICT_SYSTEM,
+        &truncate(&user, MAX_FILE_CHARS * 2),
+        Model::Smart,
+        "merge_conflicts",
+        conflict_resolution_schema(),
+    )
+    .await?;
+
+    let mut resolutions = vec![None; hunks.len()];
+    for resolution in parsed.resolutions {
+        if let Some(slot) = resolution
+            .hunk
+            .checked_sub(1)
+            .and_then(|i| resolutions.get_mut(i))
+        {
+            *slot = Some(resolution.content);
+        }
+    }
+    let resolutions: Vec<String> = resolutions
+        .into_iter()
+        .collect::<Option<_>>()
+        .ok_or_else(|| anyhow::anyhow!("Model left a conflict in {} unresolved", path.display()))?;
+    if resolutions
+        .iter()
+        .any(|resolution| contains_marker(resolution))
+    {
+        anyhow::bail!("Model left conflict markers in {}", path.display());
+    }
+    Ok((resolutions, usage))
+}
+
+fn contains_marker(text: &str) -> bool {
+    text.lines().any(|line| {
+        ["<<<<<<<", "|||||||", "=======", ">>>>>>>"]
+            .iter()
+            .any(|marker| is_marker(line, marker))
+    })
+}
+
+/// Files in the working tree holding conflict blocks: git's unmerged paths plus changed files
+/// that still have markers in them.
+pub fn find_conflicted_files(repo_path: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    let status = git_ops::current_status(repo_path)?;
+    let mut candidates = git_ops::conflicted_files(repo_path)?;
+    for path in status
+        .staged
+        .iter()
+        .chain(&status.modified)
+        .chain(&status.untracked)
+    {
+        let path = PathBuf::from(path);
+        if !candidates.contains(&path) {
+            candidates.push(path);
+        }
+    }
+    let mut conflicted: Vec<PathBuf> = candidates
+        .into_iter()
+        .filter(|path| {
+            std::fs::read_to_string(repo_path.join(path))
+                .map(|content| !conflict_hunks(&content).is_empty())
+                .unwrap_or(false)
+        })
+        .collect();
+    conflicted.sort();
+    Ok(conflicted)
+}
+
+/// Proposed resolutions for one conflicted file.
+#[derive(Debug, Clone)]
+pub struct FileResolution {
+    pub path: PathBuf,
+    /// The file as it is on disk, conflict blocks included
+    pub content: String,
+    pub hunks: Vec<ConflictHunk>,
+    /// One proposed replacement per hunk
+    pub proposals: Vec<String>,
+    /// Why the file doesn't parse with every proposal applied, if it doesn't
+    pub syntax_error: Option<String>,
+}
+
+impl FileResolution {
+    /// The file with accepted proposals applied and rejected blocks left in place.
+    pub fn content_with(&self, accepted: &[bool]) -> anyhow::Result<String> {
+        let resolutions: Vec<Option<&str>> = self
+            .proposals
+            .iter()
+            .enumerate()
+            .map(|(i, proposal)| {
+                accepted
+                    .get(i)
+                    .copied()
+                    .unwrap_or(false)
+                    .then_some(proposal.as_str())
+            })
+            .collect();
+        resolve_hunks(&self.content, &resolutions)
+    }
+
+    /// The file with every proposal applied.
+    pub fn resolved_content(&self) -> anyhow::Result<String> {
+        self.content_with(&vec![true; self.proposals.len()])
+    }
+}
+
+/// Proposals for every conflicted file, and how the gates judged them.
+#[derive(Debug, Clone)]
+pub struct ConflictResolutionPlan {
+    pub files: Vec<FileResolution>,
+    /// Conflicted files the model couldn't propose resolutions for, with why
+    pub failures: Vec<(PathBuf, String)>,
+    /// Quick checks run with every proposal applied
+    pub quick_check_status: ImplementationQuickCheckStatus,
+    pub quick_check_command: Option<String>,
+    pub quick_check_failure: Option<String>,
+    pub usage: Option<Usage>,
+}
+
+/// Propose resolutions for the conflicts in the working tree without touching it.
+///
+/// A proposal that doesn't parse is retried once with the error. The quick checks `config`
+/// allows then run in a sandbox worktree holding the working tree's changes with every
+/// proposal applied. `on_progress` gets a short line per stage.
+pub async fn propose_conflict_resolutions<F: FnMut(&str)>(
+    repo_path: &Path,
+    config: &ImplementationHarnessConfig,
+    on_progress: F,
+) -> anyhow::Result<ConflictResolutionPlan> {
+    routing::with_role(
+        ModelRole::Implement,
+        propose_conflict_resolutions_impl(repo_path, config, on_progress),
+    )
+    .await
+}
+
+async fn propose_conflict_resolutions_impl<F: FnMut(&str)>(
+    repo_path: &Path,
+    config: &ImplementationHarnessConfig,
+    mut on_progress: F,
+) -> anyhow::Result<ConflictResolutionPlan> {
+    let mut plan = ConflictResolutionPlan {
+        files: Vec::new(),
+        failures: Vec::new(),
+        quick_check_status: ImplementationQuickCheckStatus::Unavailable,
+        quick_check_command: None,
+        quick_check_failure: None,
+        usage: None,
+    };
+    for path in find_conflicted_files(repo_path)? {
+        on_progress(&format!("Resolving {}", path.display()));
+        let content = std::fs::read_to_string(repo_path.join(&path))?;
+        let hunks = conflict_hunks(&content);
+        match propose_file_resolution(&path, content, hunks, &mut plan.usage).await {
+            Ok(file) => plan.files.push(file),
+            Err(e) => plan.failures.push((path, e.to_string())),
+        }
+    }
+    if plan.files.is_empty() {
+        return Ok(plan);
+    }
+
+    on_progress("Running quick checks");
+    let run_id = format!("resolve-{}", uuid::Uuid::new_v4());
+    let sandbox = SandboxSession::create(repo_path, &run_id, "resolve", false)?;
+    copy_working_tree_changes(repo_path, sandbox.path())?;
+    for file in &plan.files {
+        std::fs::write(sandbox.path().join(&file.path), file.resolved_content()?)?;
+    }
+    let (status, command, failure) = run_harness_quick_checks(config, sandbox.path(), repo_path)?;
+    plan.quick_check_status = status;
+    plan.quick_check_command = command;
+    plan.quick_check_failure = failure;
+    Ok(plan)
+}
+
+async fn propose_file_resolution(
+    path: &Path,
+    content: String,
+    hunks: Vec<ConflictHunk>,
+    usage: &mut Option<Usage>,
+) -> anyhow::Result<FileResolution> {
+    let (proposals, first_usage) =
+        propose_resolutions(path, &content, &hunks, ConflictSides::Merge, None).await?;
+    *usage = merge_usage(usage.take(), first_usage);
+    let mut file = FileResolution {
+        path: path.to_path_buf(),
+        content,
+        hunks,
+        proposals,
+        syntax_error: None,
+    };
+    file.syntax_error = syntax_error(path, &file.resolved_content()?);
+    if let Some(error) = file.syntax_error.clone() {
+        let feedback = format!("The resolved file doesn't parse: {}", error);
+        if let Ok((proposals, retry_usage)) = propose_resolutions(
+            path,
+            &file.content,
+            &file.hunks,
+            ConflictSides::Merge,
+            Some(&feedback),
+        )
+        .await
+        {
+            *usage = merge_usage(usage.take(), retry_usage);
+            file.proposals = proposals;
+            file.syntax_error = syntax_error(path, &file.resolved_content()?);
+        }
+    }
+    Ok(file)
+}
+
+/// Bring the working tree's uncommitted changes into a sandbox checked out at `HEAD`.
+fn copy_working_tree_changes(repo_path: &Path, sandbox: &Path) -> anyhow::Result<()> {
+    let status = git_ops::current_status(repo_path)?;
+    for path in status
+        .staged
+        .iter()
+        .chain(&status.modified)
+        .chain(&status.untracked)
+    {
+        let source = repo_path.join(path);
+        let target = sandbox.join(path);
+        if source.is_file() {
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::copy(&source, &target)?;
+        } else if !source.exists() && target.is_file() {
+            std::fs::remove_file(&target)?;
+        }
+    }
+    Ok(())
+}
+
+/// Write `file` with the accepted proposals; once no conflict blocks are left, stage it so git
+/// sees it as resolved. Returns whether it was fully resolved.
+pub fn apply_file_resolution(
+    repo_path: &Path,
+    file: &FileResolution,
+    accepted: &[bool],
+) -> anyhow::Result<bool> {
+    let content = file.content_with(accepted)?;
+    let on_disk = std::fs::read_to_string(repo_path.join(&file.path))?;
+    if on_disk != file.content {
+        anyhow::bail!(
+            "{} changed since the resolutions were proposed",
+            file.path.display()
+        );
+    }
+    std::fs::write(repo_path.join(&file.path), &content)?;
+    let resolved = conflict_hunks(&content).is_empty();
+    if resolved {
+        git_ops::stage_file(repo_path, &file.path.to_string_lossy())?;
+    }
+    Ok(resolved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFLICTED: &str = "fn main() {\n<<<<<<< HEAD\n    run(2);\n||||||| parent\n    run(1);\n=======\n    run_checked(1);\n>>>>>>> abc123 (Check the run)\n}\n";
+
+    #[test]
+    fn conflict_hunks_read_each_side_of_a_diff3_block() {
+        let hunks = conflict_hunks(CONFLICTED);
+        assert_eq!(hunks.len(), 1);
+        let hunk = &hunks[0];
+        assert_eq!(hunk.ours, "    run(2);\n");
+        assert_eq!(hunk.theirs, "    run_checked(1);\n");
+        assert_eq!(hunk.ancestor.as_deref(), Some("    run(1);\n"));
+        assert_eq!((hunk.start_line, hunk.end_line), (2, 8));
+        assert!(CONFLICTED.contains(&hunk.raw));
+        assert!(conflict_hunks("no conflicts here\n").is_empty());
+        assert!(conflict_hunks("<<<<<<< HEAD\nnever closed\n").is_empty());
+    }
+
+    #[test]
+    fn resolve_hunks_replaces_blocks_and_keeps_the_rest() {
+        let resolved = resolve_hunks(CONFLICTED, &[Some("    run_checked(2);")]).unwrap();
+        assert_eq!(resolved, "fn main() {\n    run_checked(2);\n}\n");
+        assert_eq!(resolve_hunks(CONFLICTED, &[None]).unwrap(), CONFLICTED);
+        assert!(resolve_hunks(CONFLICTED, &[]).is_err());
+        assert!(resolve_hunks(CONFLICTED, &[Some("a"), Some("b")]).is_err());
+    }
+
+    #[test]
+    fn applying_a_partial_resolution_keeps_rejected_blocks_unstaged() {
+        let dir = tempfile::tempdir().unwrap();
+        for args in [
+            &["init", "-q"][..],
+            &["config", "user.name", "Test User"],
+            &["config", "user.email", "test@example.com"],
+            &["commit", "-q", "--allow-empty", "-m", "Initial"],
+        ] {
+            let status = std::process::Command::new("git")
+                .current_dir(dir.path())
+                .args(args)
+                .status()
+                .unwrap();
+            assert!(status.success());
+        }
+        let content = format!("{}{}", CONFLICTED, CONFLICTED.replace("run", "walk"));
+        std::fs::write(dir.path().join("main.rs"), &content).unwrap();
+        assert_eq!(
+            find_conflicted_files(dir.path()).unwrap(),
+            vec![PathBuf::from("main.rs")]
+        );
+
+        let file = FileResolution {
+            path: PathBuf::from("main.rs"),
+            hunks: conflict_hunks(&content),
+            content,
+            proposals: vec![
+                "    run_checked(2);".to_string(),
+                "    walk_checked(2);".to_string(),
+            ],
+            syntax_error: None,
+        };
+        assert!(!apply_file_resolution(dir.path(), &file, &[true, false]).unwrap());
+        let written = std::fs::read_to_string(dir.path().join("main.rs")).unwrap();
+        assert!(written.starts_with("fn main() {\n    run_checked(2);\n}\n"));
+        assert_eq!(conflict_hunks(&written).len(), 1);
+        // The file moved on, so the old proposals no longer apply.
+        assert!(apply_file_resolution(dir.path(), &file, &[true, true]).is_err());
+    }
+
+    #[test]
+    fn syntax_error_flags_unparseable_rust_only() {
+        assert!(syntax_error(Path::new("lib.rs"), "fn main() {\n").is_some());
+        assert!(syntax_error(Path::new("lib.rs"), "fn main() {}\n").is_none());
+        assert!(syntax_error(Path::new("notes.txt"), "fn main() {\n").is_none());
+    }
+}
@@ -1,25 +1,50 @@
 pub mod agentic;
 pub mod analysis;
+pub mod batch;
+pub mod chat;
 pub mod client;
+pub mod commit_message;
+pub mod conflicts;
+pub mod custom_tools;
+pub mod dry_run;
+pub mod failover;
 pub mod fix;
 pub mod grouping;
 pub mod implementation;
+pub mod merge_conflicts;
 pub mod models;
 pub mod parse;
+pub mod pr_description;
+pub mod preflight;
+pub mod prompt_templates;
 pub mod prompt_utils;
 pub mod prompts;
+pub mod rebase;
+pub mod redaction;
+pub mod response_cache;
+pub mod retrieval;
 pub mod review;
+pub mod routing;
+pub mod spend;
+pub mod structured;
+pub mod summaries;
+pub mod tokens;
 pub mod tools;
 
 pub use agentic::AgenticStreamKind;
 pub use analysis::{
     analyze_codebase_fast_grounded, analyze_codebase_single_agent_reviewed, ask_question,
+    estimate_ask_context_tokens, plan_gated_suggestion_run, record_suggestion_run,
     run_fast_grounded_with_gate, run_fast_grounded_with_gate_with_progress,
-    run_fast_grounded_with_gate_with_progress_and_stream, GatedSuggestionRunResult,
-    SuggestionDiagnostics, SuggestionGateSnapshot, SuggestionQualityGateConfig,
-    SuggestionReviewFocus, SuggestionStreamSink,
+    run_fast_grounded_with_gate_with_progress_and_stream, AskContextReport,
+    GatedSuggestionRunResult, ShardDiagnostics, SuggestionDiagnostics, SuggestionGateSnapshot,
+    SuggestionPolicy, SuggestionQualityGateConfig, SuggestionReviewFocus, SuggestionStreamSink,
 };
+pub use batch::{BatchStatus, BatchUnsupported};
+pub use chat::{ChatSession, ChatTurn};
 pub use client::is_available;
+pub use commit_message::{format_commit_message, generate_commit_message};
+pub use conflicts::{detect_fix_conflicts, rebase_after_apply, FixConflict, RebasedSuggestion};
 pub use fix::{
     build_fix_preview_from_validated_suggestion, generate_fix_content,
     generate_fix_content_with_model, generate_fix_preview_agentic, generate_multi_file_fix,
@@ -27,15 +52,36 @@ pub use fix::{
 };
 pub use implementation::{
     implement_validated_suggestion_with_harness,
-    implement_validated_suggestion_with_harness_with_progress, record_harness_finalization_outcome,
-    ImplementationAppliedFile, ImplementationAttemptDiagnostics,
+    implement_validated_suggestion_with_harness_with_progress, load_harness_reports,
+    plan_harness_apply, record_harness_finalization_outcome, run_harness_quick_checks,
+    with_check_output, ImplementationAppliedFile, ImplementationAttemptDiagnostics,
     ImplementationFinalizationDiagnostics, ImplementationFinalizationStatus,
     ImplementationGateSnapshot, ImplementationHarnessConfig, ImplementationHarnessRunContext,
-    ImplementationQuickCheckStatus, ImplementationReviewModel, ImplementationRunDiagnostics,
-    ImplementationRunResult,
+    ImplementationQuickCheckStatus, ImplementationRejectedHunk, ImplementationReviewModel,
+    ImplementationRunDiagnostics, ImplementationRunResult, LicenseGateConfig, QuickCheckConfig,
+    TestGateConfig,
+};
+pub use merge_conflicts::{
+    apply_file_resolution, find_conflicted_files, propose_conflict_resolutions,
+    ConflictResolutionPlan, FileResolution,
 };
 pub use models::Usage;
+pub use pr_description::{synthesize_pr_description, HarnessEvidence, PrChangeSummary};
+pub use preflight::preflight_provider;
+pub use rebase::{rebase_branch_onto_base, RebaseOutcome};
+pub use response_cache::ResponseCacheStats;
+pub use retrieval::{retrieve, EmbeddingConfig, Retrieval, RetrievalStats, RetrievedSnippet};
 pub use review::{
     fix_review_findings, fix_review_findings_with_model, verify_changes,
     verify_changes_bounded_with_model, FixContext, ReviewFinding,
 };
+pub use spend::{ModelDowngrade, SpendCapExceeded};
+pub use structured::{
+    check_parse_fixture, load_parse_fixtures, parse_strategy_output, parse_structured_json,
+    ParseFixture, ParseStrategy,
+};
+pub use summaries::{
+    collect_summary_batch, regenerate_directory_summary, score_directory_summary,
+    submit_summary_batch, summary_batch_status, SummaryScore, SUMMARY_AUDIT_PASS_SCORE,
+};
+pub use tokens::{count_tokens, ContextBudget};
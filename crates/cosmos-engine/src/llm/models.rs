@@ -12,6 +12,9 @@ pub enum Model {
 /// Maximum tokens for all model tiers
 const MODEL_MAX_TOKENS: u32 = 40_000;
 
+/// Context window (prompt plus completion) for all model tiers
+pub const MODEL_CONTEXT_WINDOW: u32 = 131_072;
+
 /// Models we allow to use JSON formatting / structured outputs.
 const JSON_FORMAT_MODELS: [&str; 1] = ["zai-glm-4.7"];
 
@@ -31,6 +34,10 @@ impl Model {
         MODEL_MAX_TOKENS
     }
 
+    pub fn context_window(&self) -> u32 {
+        MODEL_CONTEXT_WINDOW
+    }
+
     /// Whether this model supports JSON response formatting.
     pub fn supports_json_mode(&self) -> bool {
         supports_json_format(self.id())
@@ -55,6 +62,9 @@ pub struct Usage {
     /// The provider returns this as `total_cost` in the usage object.
     #[serde(default, alias = "total_cost")]
     pub cost: Option<f64>,
+    /// Model that served the request (set by the client; providers omit it from usage).
+    #[serde(default)]
+    pub model: Option<String>,
 }
 
 impl Usage {
@@ -66,6 +76,18 @@ impl Usage {
     }
 }
 
+/// Tag usage parsed from a response with the model the request was sent to, and count its
+/// cost toward the current operation's spend cap.
+pub(crate) fn tag_usage(usage: Option<Usage>, model: Model) -> Option<Usage> {
+    if let Some(usage) = &usage {
+        super::spend::record(usage.cost());
+    }
+    usage.map(|usage| Usage {
+        model: Some(super::client::model_id_for_backend(model)),
+        ..usage
+    })
+}
+
 /// Merge two optional `Usage` values, summing their token counts and costs.
 pub(crate) fn merge_usage(primary: Option<Usage>, secondary: Option<Usage>) -> Option<Usage> {
     match (primary, secondary) {
@@ -79,6 +101,7 @@ pub(crate) fn merge_usage(primary: Option<Usage>, secondary: Option<Usage>) -> O
                 (None, Some(sc)) => Some(sc),
                 (None, None) => None,
             },
+            model: p.model.or(s.model),
         }),
         (Some(p), None) => Some(p),
         (None, Some(s)) => Some(s),
@@ -121,6 +144,7 @@ mod tests {
             completion_tokens: 1000,
             total_tokens: 2000,
             cost: Some(0.05),
+            model: None,
         };
         assert_eq!(usage.cost(), 0.05);
     }
@@ -132,6 +156,7 @@ mod tests {
             completion_tokens: 1000,
             total_tokens: 2000,
             cost: None,
+            model: None,
         };
         // Returns 0.0 when no cost is available (we don't estimate)
         assert_eq!(usage.cost(), 0.0);
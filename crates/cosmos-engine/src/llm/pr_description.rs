@@ -0,0 +1,321 @@
+//! Structured pull request descriptions built from applied fixes.
+//!
+//! Ship used to send a fixed summary. This assembles the problem, approach, files touched,
+//! risk notes, and the harness evidence (quick checks, tests, review) for every pending
+//! change, so reviewers see what was verified without opening the harness report.
+
+use super::implementation::{ImplementationQuickCheckStatus, ImplementationRunDiagnostics};
+use cosmos_adapters::util::truncate;
+use std::path::PathBuf;
+
+/// Changes larger than this are called out in the risk notes.
+const LARGE_CHANGE_LINES: usize = 150;
+/// Changes touching more files than this are called out in the risk notes.
+const WIDE_CHANGE_FILES: usize = 3;
+
+/// What the harness verified for one applied fix (taken from its passing attempt).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HarnessEvidence {
+    pub passing_attempt: usize,
+    pub attempt_count: usize,
+    pub reduced_confidence: bool,
+    pub quick_check_status: ImplementationQuickCheckStatus,
+    pub quick_check_command: Option<String>,
+    /// Test gate command, when it ran and passed.
+    pub test_command: Option<String>,
+    pub review_iterations: usize,
+    pub changed_lines_total: usize,
+}
+
+impl HarnessEvidence {
+    /// Evidence from the passing attempt, or None if the run never passed.
+    pub fn from_diagnostics(diagnostics: &ImplementationRunDiagnostics) -> Option<Self> {
+        let attempt = diagnostics.attempts.iter().rev().find(|a| a.passed)?;
+        Some(Self {
+            passing_attempt: attempt.attempt_index,
+            attempt_count: diagnostics.attempt_count,
+            reduced_confidence: diagnostics.reduced_confidence,
+            quick_check_status: attempt.quick_check_status,
+            quick_check_command: attempt.quick_check_command.clone(),
+            test_command: attempt
+                .test_outcome
+                .as_ref()
+                .filter(|outcome| outcome.success)
+                .map(|outcome| outcome.command.clone()),
+            review_iterations: attempt.review_iterations,
+            changed_lines_total: attempt.changed_lines_total,
+        })
+    }
+}
+
+/// One pending change as it should be described in the pull request.
+#[derive(Debug, Clone, Default)]
+pub struct PrChangeSummary {
+    pub title: Option<String>,
+    pub problem: Option<String>,
+    pub outcome: Option<String>,
+    /// Technical description from the harness
+    pub description: String,
+    pub files: Vec<PathBuf>,
+    pub evidence: Option<HarnessEvidence>,
+}
+
+impl PrChangeSummary {
+    fn label(&self) -> &str {
+        self.title.as_deref().unwrap_or("Improvement")
+    }
+
+    fn risk_notes(&self) -> Vec<String> {
+        let mut notes = Vec::new();
+        let Some(evidence) = &self.evidence else {
+            notes.push("No harness report recorded; verify manually.".to_string());
+            return notes;
+        };
+        if evidence.reduced_confidence {
+            notes.push("Harness finished with reduced confidence; review closely.".to_string());
+        }
+        if evidence.quick_check_status != ImplementationQuickCheckStatus::Passed {
+            notes.push(
+                "No quick check ran (none detected, disabled, or workspace not trusted)."
+                    .to_string(),
+            );
+        }
+        if evidence.passing_attempt > 1 {
+            notes.push(format!(
+                "Passed on harness attempt {} of {}.",
+                evidence.passing_attempt, evidence.attempt_count
+            ));
+        }
+        if evidence.changed_lines_total > LARGE_CHANGE_LINES {
+            notes.push(format!(
+                "Large change: {} lines.",
+                evidence.changed_lines_total
+            ));
+        }
+        if self.files.len() > WIDE_CHANGE_FILES {
+            notes.push(format!("Touches {} files.", self.files.len()));
+        }
+        notes
+    }
+
+    fn test_evidence(&self) -> String {
+        let Some(evidence) = &self.evidence else {
+            return "no harness report".to_string();
+        };
+        let mut parts = Vec::new();
+        match (
+            evidence.quick_check_status,
+            evidence.quick_check_command.as_deref(),
+        ) {
+            (ImplementationQuickCheckStatus::Passed, Some(command)) => {
+                parts.push(format!("quick check `{}` passed", command))
+            }
+            (ImplementationQuickCheckStatus::Passed, None) => {
+                parts.push("quick check passed".to_string())
+            }
+            _ => parts.push("no quick check".to_string()),
+        }
+        if let Some(command) = &evidence.test_command {
+            parts.push(format!("tests `{}` passed", command));
+        }
+        parts.push(format!(
+            "adversarial review passed ({} round{})",
+            evidence.review_iterations.max(1),
+            if evidence.review_iterations > 1 {
+                "s"
+            } else {
+                ""
+            }
+        ));
+        parts.join("; ")
+    }
+}
+
+fn pr_title(changes: &[PrChangeSummary]) -> String {
+    if let [change] = changes {
+        return change
+            .title
+            .clone()
+            .unwrap_or_else(|| truncate(&change.description, 50));
+    }
+
+    let titles: Vec<&str> = changes.iter().filter_map(|c| c.title.as_deref()).collect();
+    match titles.as_slice() {
+        [] => format!("{} improvements", changes.len()),
+        [only] => only.to_string(),
+        [first, second] => format!("{} and {}", first, second),
+        [first, second, rest @ ..] => {
+            format!("{}, {}, and {} more", first, second, rest.len())
+        }
+    }
+}
+
+/// Build the pull request title and Markdown body for the pending changes.
+pub fn synthesize_pr_description(changes: &[PrChangeSummary]) -> (String, String) {
+    if changes.is_empty() {
+        return (
+            "Improvements".to_string(),
+            "## Summary\n\nNo changes to describe.\n\n---\n*Applied with Cosmos*".to_string(),
+        );
+    }
+
+    let title = pr_title(changes);
+    let single = changes.len() == 1;
+    let mut body = String::new();
+
+    body.push_str("## Problem\n\n");
+    for change in changes {
+        let problem = change.problem.as_deref().unwrap_or(&change.description);
+        if single {
+            body.push_str(&format!("{}\n\n", problem));
+        } else {
+            body.push_str(&format!("- **{}**: {}\n", change.label(), problem));
+        }
+    }
+    if !single {
+        body.push('\n');
+    }
+
+    body.push_str("## Approach\n\n");
+    for change in changes {
+        let approach = match &change.outcome {
+            Some(outcome) if !change.description.is_empty() => {
+                format!("{} {}", outcome, change.description)
+            }
+            Some(outcome) => outcome.clone(),
+            None => change.description.clone(),
+        };
+        if single {
+            body.push_str(&format!("{}\n\n", approach));
+        } else {
+            body.push_str(&format!("- **{}**: {}\n", change.label(), approach));
+        }
+    }
+    if !single {
+        body.push('\n');
+    }
+
+    body.push_str("## Files touched\n\n");
+    let mut seen = Vec::new();
+    for path in changes.iter().flat_map(|change| &change.files) {
+        if !seen.contains(&path) {
+            seen.push(path);
+            body.push_str(&format!("- `{}`\n", path.display()));
+        }
+    }
+
+    body.push_str("\n## Risk notes\n\n");
+    let mut any_risk = false;
+    for change in changes {
+        for note in change.risk_notes() {
+            any_risk = true;
+            if single {
+                body.push_str(&format!("- {}\n", note));
+            } else {
+                body.push_str(&format!("- **{}**: {}\n", change.label(), note));
+            }
+        }
+    }
+    if !any_risk {
+        body.push_str("- None flagged by the harness.\n");
+    }
+
+    body.push_str("\n## Test evidence\n\n");
+    for change in changes {
+        if single {
+            body.push_str(&format!("- {}\n", change.test_evidence()));
+        } else {
+            body.push_str(&format!(
+                "- **{}**: {}\n",
+                change.label(),
+                change.test_evidence()
+            ));
+        }
+    }
+
+    body.push_str("\n---\n*Applied with Cosmos*");
+    (title, body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change(title: &str, files: &[&str], evidence: Option<HarnessEvidence>) -> PrChangeSummary {
+        PrChangeSummary {
+            title: Some(title.to_string()),
+            problem: Some(format!("{} was broken.", title)),
+            outcome: Some("Now it works.".to_string()),
+            description: "Guard the empty case.".to_string(),
+            files: files.iter().map(PathBuf::from).collect(),
+            evidence,
+        }
+    }
+
+    fn passing_evidence() -> HarnessEvidence {
+        HarnessEvidence {
+            passing_attempt: 1,
+            attempt_count: 1,
+            quick_check_status: ImplementationQuickCheckStatus::Passed,
+            quick_check_command: Some("cargo check".to_string()),
+            test_command: Some("cargo test -p core".to_string()),
+            review_iterations: 2,
+            changed_lines_total: 12,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn single_change_lists_every_section_with_harness_evidence() {
+        let (title, body) = synthesize_pr_description(&[change(
+            "Retry Handling",
+            &["src/retry.rs"],
+            Some(passing_evidence()),
+        )]);
+        assert_eq!(title, "Retry Handling");
+        for section in [
+            "## Problem\n\nRetry Handling was broken.",
+            "## Approach\n\nNow it works. Guard the empty case.",
+            "## Files touched\n\n- `src/retry.rs`",
+            "## Risk notes\n\n- None flagged by the harness.",
+            "quick check `cargo check` passed; tests `cargo test -p core` passed; adversarial review passed (2 rounds)",
+        ] {
+            assert!(body.contains(section), "missing {:?} in\n{}", section, body);
+        }
+    }
+
+    #[test]
+    fn multiple_changes_flag_risks_per_change_and_dedupe_files() {
+        let risky = HarnessEvidence {
+            passing_attempt: 2,
+            attempt_count: 2,
+            reduced_confidence: true,
+            quick_check_status: ImplementationQuickCheckStatus::Unavailable,
+            changed_lines_total: 400,
+            ..Default::default()
+        };
+        let (title, body) = synthesize_pr_description(&[
+            change(
+                "Retry Handling",
+                &["src/retry.rs"],
+                Some(passing_evidence()),
+            ),
+            change("Cache Keys", &["src/retry.rs", "src/cache.rs"], Some(risky)),
+            change("Logging", &["src/log.rs"], None),
+        ]);
+        assert_eq!(title, "Retry Handling, Cache Keys, and 1 more");
+        assert_eq!(body.matches("- `src/retry.rs`").count(), 1);
+        assert!(body.contains("- **Cache Keys**: Harness finished with reduced confidence"));
+        assert!(body.contains("- **Cache Keys**: Passed on harness attempt 2 of 2."));
+        assert!(body.contains("- **Cache Keys**: Large change: 400 lines."));
+        assert!(body.contains("- **Logging**: No harness report recorded"));
+        assert!(!body.contains("- **Retry Handling**: No quick check"));
+    }
+
+    #[test]
+    fn empty_changes_keep_the_placeholder_description() {
+        let (title, body) = synthesize_pr_description(&[]);
+        assert_eq!(title, "Improvements");
+        assert!(body.contains("No changes to describe."));
+    }
+}
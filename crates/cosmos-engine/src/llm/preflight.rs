@@ -0,0 +1,284 @@
+//! Fast provider/model availability check run before the first AI run of a session.
+//!
+//! A deprecated model or a provider outage otherwise only surfaces after the
+//! evidence-pack phase. The preflight lists the provider's models with a short
+//! timeout and fails with an actionable message instead. Successful checks are
+//! cached in `.cosmos/provider_preflight.json`, so most runs skip the request.
+//!
+//! Routes with `fallbacks` are checked as whole failover chains: a chain fails the
+//! preflight only when the provider lists none of its models. Unlisted models in a
+//! usable chain are handed to `failover`, which skips them.
+
+use super::client::{
+    api_key, apply_backend_headers, backend_label, backend_slug, create_http_client,
+    model_id_for_backend, models_url,
+};
+use super::failover;
+use super::models::Model;
+use super::routing;
+use chrono::{Duration, Utc};
+use cosmos_adapters::cache::{Cache, ProviderPreflightRecord};
+use serde::Deserialize;
+use std::path::Path;
+
+/// Upper bound on the model-list request; the preflight must stay cheap.
+const PREFLIGHT_TIMEOUT_SECS: u64 = 5;
+/// How long a successful preflight is trusted before the provider is asked again.
+const PREFLIGHT_CACHE_TTL_HOURS: i64 = 6;
+
+#[derive(Deserialize)]
+struct ModelList {
+    #[serde(default)]
+    data: Vec<ModelEntry>,
+}
+
+#[derive(Deserialize)]
+struct ModelEntry {
+    id: String,
+}
+
+fn preflight_disabled() -> bool {
+    std::env::var("COSMOS_SKIP_PREFLIGHT")
+        .ok()
+        .map(|value| {
+            matches!(
+                value.trim().to_ascii_lowercase().as_str(),
+                "1" | "true" | "yes" | "on"
+            )
+        })
+        .unwrap_or(false)
+}
+
+/// Failover chains the configured tiers and model routes send to the provider.
+pub(crate) fn required_model_chains() -> Vec<Vec<String>> {
+    let mut chains: Vec<Vec<String>> = Vec::new();
+    let tiers = [Model::Speed, Model::Smart].map(|model| vec![model_id_for_backend(model)]);
+    let routed = routing::routed_model_chains(&model_id_for_backend(Model::Smart));
+    for chain in tiers.into_iter().chain(routed) {
+        if !chains.contains(&chain) {
+            chains.push(chain);
+        }
+    }
+    chains
+}
+
+/// Models of the chains the provider lists no model for.
+pub(crate) fn missing_models(chains: &[Vec<String>], available: &[String]) -> Vec<String> {
+    let mut missing: Vec<String> = Vec::new();
+    for chain in chains {
+        if chain.iter().any(|id| available.contains(id)) {
+            continue;
+        }
+        for id in chain {
+            if !missing.contains(id) {
+                missing.push(id.clone());
+            }
+        }
+    }
+    missing
+}
+
+/// Chain entries the provider did not list, which failover skips.
+pub(crate) fn unlisted_models(chains: &[Vec<String>], available: &[String]) -> Vec<String> {
+    let mut unlisted: Vec<String> = Vec::new();
+    for id in chains.iter().flatten() {
+        if !available.contains(id) && !unlisted.contains(id) {
+            unlisted.push(id.clone());
+        }
+    }
+    unlisted
+}
+
+fn cached_preflight_is_fresh(record: &ProviderPreflightRecord, chains: &[Vec<String>]) -> bool {
+    record.backend == backend_slug()
+        && Utc::now().signed_duration_since(record.checked_at)
+            < Duration::hours(PREFLIGHT_CACHE_TTL_HOURS)
+        && missing_models(chains, &record.available_models).is_empty()
+}
+
+fn missing_models_message(missing: &[String]) -> String {
+    format!(
+        "{} no longer offers {} ({}). Update Cosmos or the model_routing entries in config.json to pick a supported model, or set COSMOS_SKIP_PREFLIGHT=1 to try anyway.",
+        backend_label(),
+        if missing.len() == 1 {
+            "the configured model"
+        } else {
+            "the configured models"
+        },
+        missing.join(", ")
+    )
+}
+
+/// Check that the provider is reachable and still serves the configured models.
+///
+/// Returns an error only when the provider is down, rejects the API key, or no longer
+/// lists any model of a configured chain. Inconclusive answers (unexpected status or payload) pass,
+/// so the preflight never blocks a run the provider would have served.
+pub async fn preflight_provider(repo_root: &Path) -> anyhow::Result<()> {
+    if preflight_disabled() {
+        return Ok(());
+    }
+    // A missing key is reported by the run itself with the setup prompt.
+    let Some(api_key) = api_key() else {
+        return Ok(());
+    };
+    let chains = required_model_chains();
+    let cache = Cache::new(repo_root);
+    if let Some(record) = cache
+        .load_provider_preflight()
+        .filter(|record| cached_preflight_is_fresh(record, &chains))
+    {
+        failover::mark_unavailable(unlisted_models(&chains, &record.available_models));
+        return Ok(());
+    }
+
+    let client = create_http_client(PREFLIGHT_TIMEOUT_SECS)?;
+    let response = apply_backend_headers(client.get(models_url()), &api_key)
+        .send()
+        .await
+        .map_err(|err| {
+            if err.is_timeout() {
+                anyhow::anyhow!(
+                    "{} did not respond within {}s. The service may be down; try again shortly.",
+                    backend_label(),
+                    PREFLIGHT_TIMEOUT_SECS
+                )
+            } else if err.is_connect() {
+                anyhow::anyhow!(
+                    "Could not connect to {}. Check your network and try again.",
+                    backend_label()
+                )
+            } else {
+                anyhow::anyhow!("{} preflight failed: {}", backend_label(), err)
+            }
+        })?;
+
+    let status = response.status();
+    match status.as_u16() {
+        401 => {
+            return Err(anyhow::anyhow!(
+            "Invalid Cerebras API key. Run 'cosmos --setup' or set CEREBRAS_API_KEY and try again."
+        ))
+        }
+        500..=599 => {
+            return Err(anyhow::anyhow!(
+                "{} server error ({}). The service may be temporarily unavailable.",
+                backend_label(),
+                status
+            ))
+        }
+        _ if !status.is_success() => return Ok(()),
+        _ => {}
+    }
+
+    let Ok(list) = response.json::<ModelList>().await else {
+        return Ok(());
+    };
+    let available = list
+        .data
+        .into_iter()
+        .map(|entry| entry.id)
+        .collect::<Vec<_>>();
+    if available.is_empty() {
+        return Ok(());
+    }
+    let missing = missing_models(&chains, &available);
+    if !missing.is_empty() {
+        return Err(anyhow::anyhow!("{}", missing_models_message(&missing)));
+    }
+    failover::mark_unavailable(unlisted_models(&chains, &available));
+
+    let _ = cache.save_provider_preflight(&ProviderPreflightRecord {
+        checked_at: Utc::now(),
+        backend: backend_slug().to_string(),
+        available_models: available,
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(hours_ago: i64, models: &[&str]) -> ProviderPreflightRecord {
+        ProviderPreflightRecord {
+            checked_at: Utc::now() - Duration::hours(hours_ago),
+            backend: backend_slug().to_string(),
+            available_models: models.iter().map(|id| id.to_string()).collect(),
+        }
+    }
+
+    fn chains(chains: &[&[&str]]) -> Vec<Vec<String>> {
+        chains
+            .iter()
+            .map(|chain| chain.iter().map(|id| id.to_string()).collect())
+            .collect()
+    }
+
+    fn ids(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    #[test]
+    fn missing_models_lists_only_unlisted_ids() {
+        let required = required_model_chains();
+        assert_eq!(required, chains(&[&["zai-glm-4.7"]]));
+        assert!(missing_models(&required, &ids(&["zai-glm-4.7"])).is_empty());
+        assert_eq!(
+            missing_models(&required, &ids(&["llama-4"])),
+            ids(&["zai-glm-4.7"])
+        );
+    }
+
+    #[test]
+    fn listed_primary_with_unlisted_fallback_passes() {
+        let required = chains(&[&["zai-glm-4.7", "retired-fallback"]]);
+        let available = ids(&["zai-glm-4.7"]);
+        assert!(missing_models(&required, &available).is_empty());
+        assert_eq!(
+            unlisted_models(&required, &available),
+            ids(&["retired-fallback"])
+        );
+    }
+
+    #[test]
+    fn unlisted_primary_with_listed_fallback_passes() {
+        let required = chains(&[&["retired-primary", "gpt-oss-120b"], &["zai-glm-4.7"]]);
+        let available = ids(&["zai-glm-4.7", "gpt-oss-120b"]);
+        assert!(missing_models(&required, &available).is_empty());
+        assert_eq!(
+            unlisted_models(&required, &available),
+            ids(&["retired-primary"])
+        );
+
+        // Without any listed model the chain still fails, naming every entry
+        assert_eq!(
+            missing_models(&required, &ids(&["zai-glm-4.7"])),
+            ids(&["retired-primary", "gpt-oss-120b"])
+        );
+    }
+
+    #[test]
+    fn cached_preflight_expires_and_rechecks_dropped_models() {
+        let required = required_model_chains();
+        assert!(cached_preflight_is_fresh(
+            &record(1, &["zai-glm-4.7"]),
+            &required
+        ));
+        assert!(!cached_preflight_is_fresh(
+            &record(PREFLIGHT_CACHE_TTL_HOURS + 1, &["zai-glm-4.7"]),
+            &required
+        ));
+        assert!(!cached_preflight_is_fresh(
+            &record(1, &["llama-4"]),
+            &required
+        ));
+    }
+
+    #[test]
+    fn missing_model_message_names_the_model_and_the_escape_hatch() {
+        let message = missing_models_message(&["zai-glm-4.7".to_string()]);
+        assert!(message.contains("zai-glm-4.7"));
+        assert!(message.contains("COSMOS_SKIP_PREFLIGHT"));
+    }
+}
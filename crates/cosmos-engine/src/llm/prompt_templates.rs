@@ -0,0 +1,302 @@
+//! Per-repo prompt template overrides
+//!
+//! The built-in prompts are exposed as named templates (`suggestion`, `summary`,
+//! `preview`, `fix`, `review`). A repo can replace any of them with
+//! `.cosmos/prompts/<name>.md`. Templates reference declared variables as
+//! `{{variable}}`; `{{default}}` expands to the built-in prompt so overrides can
+//! extend it instead of copying it. Templates are validated when loaded, so a typo
+//! fails at startup rather than silently producing a broken prompt.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::RwLock;
+
+/// Directory (relative to the repo root) holding template overrides.
+pub const PROMPTS_DIR: &str = ".cosmos/prompts";
+
+/// Named built-in prompt that can be overridden.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum PromptTemplate {
+    /// Suggestion generation (agentic bug/security review)
+    Suggestion,
+    /// Ask Cosmos answers
+    Summary,
+    /// Fix preview verification
+    Preview,
+    /// Fix generation (single-file, multi-file, and review-finding fixes)
+    Fix,
+    /// Adversarial review of applied changes
+    Review,
+}
+
+impl PromptTemplate {
+    pub const ALL: [PromptTemplate; 5] = [
+        PromptTemplate::Suggestion,
+        PromptTemplate::Summary,
+        PromptTemplate::Preview,
+        PromptTemplate::Fix,
+        PromptTemplate::Review,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            PromptTemplate::Suggestion => "suggestion",
+            PromptTemplate::Summary => "summary",
+            PromptTemplate::Preview => "preview",
+            PromptTemplate::Fix => "fix",
+            PromptTemplate::Review => "review",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|t| t.name() == name)
+    }
+
+    /// Variables the template may reference.
+    pub fn variables(self) -> &'static [&'static str] {
+        match self {
+            PromptTemplate::Suggestion | PromptTemplate::Preview => &["default"],
+            PromptTemplate::Summary => &["default", "project_ethos"],
+            PromptTemplate::Fix => &["default", "mode", "edit_rules", "quality_rules"],
+            PromptTemplate::Review => &["default", "iteration", "fixed_titles", "fix_context"],
+        }
+    }
+}
+
+/// Validated overrides for one repository.
+#[derive(Debug, Clone, Default)]
+pub struct PromptTemplateSet {
+    overrides: BTreeMap<PromptTemplate, String>,
+}
+
+impl PromptTemplateSet {
+    /// Load and validate `.cosmos/prompts/*.md`. A missing directory means no overrides.
+    pub fn load(repo_root: &Path) -> anyhow::Result<Self> {
+        let dir = repo_root.join(PROMPTS_DIR);
+        let entries = match std::fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(anyhow::anyhow!("Failed to read {}: {}", PROMPTS_DIR, e)),
+        };
+
+        let mut overrides = BTreeMap::new();
+        let mut errors = Vec::new();
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("md") {
+                continue;
+            }
+            let stem = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or_default()
+                .to_string();
+            let Some(template) = PromptTemplate::from_name(&stem) else {
+                errors.push(format!(
+                    "{}.md is not a known template (expected one of: {})",
+                    stem,
+                    PromptTemplate::ALL.map(PromptTemplate::name).join(", ")
+                ));
+                continue;
+            };
+            let text = match std::fs::read_to_string(&path) {
+                Ok(text) => text,
+                Err(e) => {
+                    errors.push(format!("{}.md could not be read: {}", stem, e));
+                    continue;
+                }
+            };
+            match validate_template(template, &text) {
+                Ok(()) => {
+                    overrides.insert(template, text.trim().to_string());
+                }
+                Err(e) => errors.push(format!("{}.md {}", stem, e)),
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(Self { overrides })
+        } else {
+            errors.sort();
+            Err(anyhow::anyhow!(
+                "Invalid prompt templates in {}: {}",
+                PROMPTS_DIR,
+                errors.join("; ")
+            ))
+        }
+    }
+
+    /// Names of templates replaced by this repo, in a stable order.
+    pub fn overridden(&self) -> Vec<&'static str> {
+        self.overrides.keys().map(|t| t.name()).collect()
+    }
+
+    /// Render `template`, falling back to `default` when it isn't overridden.
+    pub fn render(&self, template: PromptTemplate, default: &str, vars: &[(&str, &str)]) -> String {
+        match self.overrides.get(&template) {
+            Some(text) => interpolate(text, default, vars),
+            None => default.to_string(),
+        }
+    }
+}
+
+/// Variable names referenced by `{{...}}` placeholders, or an error for unterminated ones.
+fn placeholders(text: &str) -> Result<Vec<&str>, String> {
+    let mut names = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            return Err("has an unterminated `{{` placeholder".to_string());
+        };
+        names.push(after[..end].trim());
+        rest = &after[end + 2..];
+    }
+    Ok(names)
+}
+
+fn validate_template(template: PromptTemplate, text: &str) -> Result<(), String> {
+    if text.trim().is_empty() {
+        return Err("is empty".to_string());
+    }
+    let declared = template.variables();
+    let unknown = placeholders(text)?
+        .into_iter()
+        .filter(|name| !declared.contains(name))
+        .map(|name| format!("{{{{{}}}}}", name))
+        .collect::<Vec<_>>();
+    if unknown.is_empty() {
+        Ok(())
+    } else {
+        Err(format!(
+            "uses undeclared variables {} (available: {})",
+            unknown.join(", "),
+            declared.join(", ")
+        ))
+    }
+}
+
+fn interpolate(text: &str, default: &str, vars: &[(&str, &str)]) -> String {
+    let mut out = String::with_capacity(text.len() + default.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            break;
+        };
+        out.push_str(&rest[..start]);
+        let name = after[..end].trim();
+        let value = if name == "default" {
+            Some(default)
+        } else {
+            vars.iter().find(|(key, _)| *key == name).map(|(_, v)| *v)
+        };
+        out.push_str(value.unwrap_or_default());
+        rest = &after[end + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+static ACTIVE_TEMPLATES: RwLock<Option<PromptTemplateSet>> = RwLock::new(None);
+
+/// Load the repo's overrides and make them active for this process.
+///
+/// Returns the names of the overridden templates.
+pub fn install(repo_root: &Path) -> anyhow::Result<Vec<&'static str>> {
+    let set = PromptTemplateSet::load(repo_root)?;
+    let overridden = set.overridden();
+    if let Ok(mut active) = ACTIVE_TEMPLATES.write() {
+        *active = Some(set);
+    }
+    Ok(overridden)
+}
+
+/// Names of the active non-default templates, for diagnostics.
+pub fn active_overrides() -> Vec<String> {
+    ACTIVE_TEMPLATES
+        .read()
+        .ok()
+        .and_then(|active| {
+            active
+                .as_ref()
+                .map(|set| set.overridden().into_iter().map(String::from).collect())
+        })
+        .unwrap_or_default()
+}
+
+/// Render a prompt through the active overrides.
+pub(crate) fn render(template: PromptTemplate, default: &str, vars: &[(&str, &str)]) -> String {
+    match ACTIVE_TEMPLATES.read() {
+        Ok(active) => match active.as_ref() {
+            Some(set) => set.render(template, default, vars),
+            None => default.to_string(),
+        },
+        Err(_) => default.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repo_with_prompts(files: &[(&str, &str)]) -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let prompts = dir.path().join(PROMPTS_DIR);
+        std::fs::create_dir_all(&prompts).unwrap();
+        for (name, content) in files {
+            std::fs::write(prompts.join(name), content).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn missing_prompts_dir_uses_defaults() {
+        let dir = tempfile::tempdir().unwrap();
+        let set = PromptTemplateSet::load(dir.path()).unwrap();
+        assert!(set.overridden().is_empty());
+        assert_eq!(set.render(PromptTemplate::Fix, "built-in", &[]), "built-in");
+    }
+
+    #[test]
+    fn override_interpolates_declared_variables() {
+        let dir = repo_with_prompts(&[(
+            "review.md",
+            "{{ default }}\n\nIteration {{iteration}}. Also check logging.",
+        )]);
+        let set = PromptTemplateSet::load(dir.path()).unwrap();
+        assert_eq!(set.overridden(), vec!["review"]);
+        let rendered = set.render(
+            PromptTemplate::Review,
+            "Review this change.",
+            &[("iteration", "2")],
+        );
+        assert_eq!(
+            rendered,
+            "Review this change.\n\nIteration 2. Also check logging."
+        );
+        // Templates that aren't overridden keep the built-in prompt.
+        assert_eq!(set.render(PromptTemplate::Fix, "fix", &[]), "fix");
+    }
+
+    #[test]
+    fn load_rejects_undeclared_variables_and_unknown_templates() {
+        let dir = repo_with_prompts(&[
+            ("fix.md", "{{default}} {{ticket_id}}"),
+            ("fixes.md", "typo"),
+            ("notes.txt", "ignored"),
+        ]);
+        let err = PromptTemplateSet::load(dir.path()).unwrap_err().to_string();
+        assert!(err.contains("{{ticket_id}}"), "{}", err);
+        assert!(err.contains("fixes.md is not a known template"), "{}", err);
+        assert!(!err.contains("notes"), "{}", err);
+    }
+
+    #[test]
+    fn load_rejects_unterminated_and_empty_templates() {
+        let dir = repo_with_prompts(&[("preview.md", "{{default"), ("summary.md", "  \n")]);
+        let err = PromptTemplateSet::load(dir.path()).unwrap_err().to_string();
+        assert!(err.contains("preview.md has an unterminated"), "{}", err);
+        assert!(err.contains("summary.md is empty"), "{}", err);
+    }
+}
@@ -1,3 +1,5 @@
+use super::prompt_templates::{render, PromptTemplate};
+
 // ═══════════════════════════════════════════════════════════════════════════════
 // SHARED BUILDING BLOCKS (used by multiple prompts to reduce duplication)
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -49,7 +51,11 @@ Follow this project-specific ethos when deciding tone, priorities, and recommend
 If there is tension between generic style and this ethos, prioritize the ethos."#,
         );
     }
-    prompt
+    render(
+        PromptTemplate::Summary,
+        &prompt,
+        &[("project_ethos", project_ethos.unwrap_or_default().trim())],
+    )
 }
 
 /// Fast grounded suggestions prompt - no tools, rely only on provided evidence pack.
@@ -85,7 +91,7 @@ RULES:
 
 /// Single-file fix generation - uses EDIT_RULES and CODE_QUALITY_RULES
 pub fn fix_content_system() -> String {
-    format!(
+    let prompt = format!(
         r#"Implement the requested code fix using the plan provided by the user prompt.
 
 OUTPUT (JSON):
@@ -100,12 +106,13 @@ OUTPUT (JSON):
 {quality_rules}"#,
         edit_rules = EDIT_RULES,
         quality_rules = CODE_QUALITY_RULES
-    )
+    );
+    render_fix_template(&prompt, "single_file")
 }
 
 /// Multi-file fix generation - uses EDIT_RULES and CODE_QUALITY_RULES
 pub fn multi_file_fix_system() -> String {
-    format!(
+    let prompt = format!(
         r#"Implement the requested multi-file fix and keep changes consistent across files.
 
 OUTPUT (JSON):
@@ -125,6 +132,19 @@ MULTI-FILE:
 {quality_rules}"#,
         edit_rules = EDIT_RULES,
         quality_rules = CODE_QUALITY_RULES
+    );
+    render_fix_template(&prompt, "multi_file")
+}
+
+fn render_fix_template(prompt: &str, mode: &str) -> String {
+    render(
+        PromptTemplate::Fix,
+        prompt,
+        &[
+            ("mode", mode),
+            ("edit_rules", EDIT_RULES),
+            ("quality_rules", CODE_QUALITY_RULES),
+        ],
     )
 }
 
@@ -155,6 +175,11 @@ FIELD RULES:
 - `evidence_snippet`: copy code exactly from provided context.
 - Return JSON only."#;
 
+/// Fix preview prompt after repo template overrides
+pub fn fix_preview_system() -> String {
+    render(PromptTemplate::Preview, FIX_PREVIEW_AGENTIC_SYSTEM, &[])
+}
+
 pub const GROUPING_CLASSIFY_SYSTEM: &str = r#"Classify files into architectural layers.
 
 OUTPUT (JSON):
@@ -165,6 +190,55 @@ RULES:
 - Unsure = "unknown" with low confidence
 - No explanations or extra fields"#;
 
+pub const COMMIT_MESSAGE_SYSTEM: &str = r#"Write a git commit message for the staged diff.
+
+OUTPUT (JSON):
+{"type": "feat|fix|refactor|perf|docs|test|build|ci|style|chore", "scope": "short area or empty", "subject": "imperative summary", "body": "why and what, or empty"}
+
+RULES:
+- Describe what the diff actually changes; pending changes only explain intent
+- Subject: imperative mood, under 60 characters, no trailing period
+- Body: 1-3 short sentences or "- " bullets, wrapped at 72 columns
+- No explanations or extra fields"#;
+
+pub const MERGE_CONFLICT_SYSTEM: &str = r#"Resolve the conflict blocks git left in a file.
+
+Each hunk shows OURS, THEIRS, and sometimes ANCESTOR (what both started from). The request says which change each side holds.
+
+OUTPUT (JSON):
+{"resolutions": [{"hunk": 1, "content": "the lines that replace hunk 1"}]}
+
+RULES:
+- Keep both sides' changes where they fit together; where they clash, keep OURS and reapply THEIRS's intent on top of it
+- One resolution per hunk, numbered as given
+- Content is the exact replacement text: no conflict markers, no code fences
+- Match the surrounding indentation and style
+- No explanations or extra fields"#;
+
+pub const SUMMARY_AUDIT_SYSTEM: &str = r#"Check whether a cached summary of a code directory still describes it.
+
+OUTPUT (JSON):
+{"score": 0-10, "issue": "what the summary gets wrong or leaves out, or empty"}
+
+SCORING:
+- 9-10: accurate and says what the directory is for
+- 6-8: accurate but vague (lists files instead of saying what they do)
+- 0-5: wrong, misleading, or about something the code no longer does
+
+RULES:
+- Judge only against the files shown; don't guess about code you can't see
+- Issue: one short sentence, empty when the score is 9 or 10"#;
+
+pub const DIRECTORY_SUMMARY_SYSTEM: &str = r#"Summarize what a code directory does for a developer new to the codebase.
+
+OUTPUT (JSON):
+{"purpose": "1-2 plain sentences"}
+
+RULES:
+- Say what the module is responsible for and how the rest of the code uses it
+- Name the main types or entry points only when they help
+- Under 240 characters, no markdown, no file-by-file lists"#;
+
 use super::review::FixContext;
 
 /// Shared review output format
@@ -206,15 +280,26 @@ RULES:
 - Prefer a few high-signal findings over many weak ones
 - Empty findings if code is solid"#;
 
+fn format_fixed_titles(fixed_titles: &[String]) -> String {
+    if fixed_titles.is_empty() {
+        "(none)".to_string()
+    } else {
+        fixed_titles
+            .iter()
+            .map(|t| format!("- {}", t))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
 pub fn review_system_prompt(
     iteration: u32,
     fixed_titles: &[String],
     fix_context: Option<&FixContext>,
 ) -> String {
-    if iteration <= 1 {
-        // For initial review, use context-aware prompt if we have fix context
-        let base = if let Some(ctx) = fix_context {
-            let context_text = format!(
+    let context_text = fix_context
+        .map(|ctx| {
+            format!(
                 "Problem: {}\nOutcome: {}\nChanged: {}{}",
                 ctx.problem_summary,
                 ctx.outcome,
@@ -224,7 +309,14 @@ pub fn review_system_prompt(
                 } else {
                     format!("\nAreas: {}", ctx.modified_areas.join(", "))
                 }
-            );
+            )
+        })
+        .unwrap_or_default();
+    let fixed_list = format_fixed_titles(fixed_titles);
+
+    let prompt = if iteration <= 1 {
+        // For initial review, use context-aware prompt if we have fix context
+        let base = if fix_context.is_some() {
             REVIEW_SYSTEM_WITH_CONTEXT.replace("{fix_context}", &context_text)
         } else {
             REVIEW_SYSTEM_GENERIC.to_string()
@@ -247,22 +339,24 @@ Do not lower quality standards because this is a later iteration.
 
 {review_output}"#,
             iteration = iteration,
-            fixed_list = if fixed_titles.is_empty() {
-                "(none)".to_string()
-            } else {
-                fixed_titles
-                    .iter()
-                    .map(|t| format!("- {}", t))
-                    .collect::<Vec<_>>()
-                    .join("\n")
-            },
+            fixed_list = fixed_list,
             review_output = REVIEW_OUTPUT
         )
-    }
+    };
+
+    render(
+        PromptTemplate::Review,
+        &prompt,
+        &[
+            ("iteration", &iteration.to_string()),
+            ("fixed_titles", &fixed_list),
+            ("fix_context", &context_text),
+        ],
+    )
 }
 
 pub fn review_fix_system_prompt(iteration: u32, fixed_titles: &[String]) -> String {
-    if iteration <= 1 {
+    let prompt = if iteration <= 1 {
         format!(
             r#"Fix the selected review findings with search/replace edits.
 
@@ -299,19 +393,12 @@ Prioritize durable root-cause fixes.
 
 {quality_rules}"#,
             iteration = iteration,
-            fixed_list = if fixed_titles.is_empty() {
-                "(none)".to_string()
-            } else {
-                fixed_titles
-                    .iter()
-                    .map(|t| format!("- {}", t))
-                    .collect::<Vec<_>>()
-                    .join("\n")
-            },
+            fixed_list = format_fixed_titles(fixed_titles),
             edit_rules = EDIT_RULES,
             quality_rules = CODE_QUALITY_RULES
         )
-    }
+    };
+    render_fix_template(&prompt, "review_findings")
 }
 
 #[cfg(test)]
@@ -0,0 +1,141 @@
+//! Rebase assistant for a Cosmos branch whose base moved on.
+//!
+//! Once `main` gains commits, pushing the fix branch is rejected or its pull request conflicts.
+//! This fetches the base, replays the branch onto it in a throwaway worktree, asks the model to
+//! resolve each conflicted hunk, and runs the repo's quick checks on the result. The checked-out
+//! branch only moves once every commit replayed and the checks didn't fail.
+
+use super::implementation::{
+    run_harness_quick_checks, ImplementationHarnessConfig, ImplementationQuickCheckStatus,
+};
+use super::merge_conflicts::{conflict_hunks, propose_resolutions, resolve_hunks, ConflictSides};
+use super::models::{merge_usage, Usage};
+use super::routing;
+use crate::lab::sandbox::SandboxSession;
+use cosmos_adapters::config::ModelRole;
+use cosmos_adapters::git_ops::{self, BranchDrift, RebaseStep};
+use std::path::{Path, PathBuf};
+
+/// How the rebase went.
+#[derive(Debug, Clone)]
+pub struct RebaseOutcome {
+    /// The branch that was rebased
+    pub branch: String,
+    /// Drift before the rebase; nothing was replayed when `behind` is 0
+    pub drift: BranchDrift,
+    /// Files whose conflicts the model resolved
+    pub resolved_files: Vec<PathBuf>,
+    pub quick_check_status: ImplementationQuickCheckStatus,
+    pub quick_check_command: Option<String>,
+    /// What failed, when the quick checks did
+    pub quick_check_failure: Option<String>,
+    /// Whether the checked-out branch now points at the rebased commits
+    pub finalized: bool,
+    pub usage: Option<Usage>,
+}
+
+/// Rebase the checked-out branch onto the latest `base`.
+///
+/// The rebase runs in a sandbox worktree, so the checkout is untouched until the replayed
+/// commits pass the quick checks `config` allows. `on_progress` gets a short line per stage.
+pub async fn rebase_branch_onto_base<F: FnMut(&str)>(
+    repo_path: &Path,
+    base: &str,
+    config: &ImplementationHarnessConfig,
+    on_progress: F,
+) -> anyhow::Result<RebaseOutcome> {
+    routing::with_role(
+        ModelRole::Implement,
+        rebase_branch_onto_base_impl(repo_path, base, config, on_progress),
+    )
+    .await
+}
+
+async fn rebase_branch_onto_base_impl<F: FnMut(&str)>(
+    repo_path: &Path,
+    base: &str,
+    config: &ImplementationHarnessConfig,
+    mut on_progress: F,
+) -> anyhow::Result<RebaseOutcome> {
+    let branch = git_ops::current_status(repo_path)?.branch;
+    if branch == base {
+        anyhow::bail!(
+            "'{}' is the base branch; check out the branch to rebase",
+            base
+        );
+    }
+    on_progress(&format!("Fetching {}", base));
+    let drift = git_ops::fetch_base_drift(repo_path, base)?;
+    let mut outcome = RebaseOutcome {
+        branch,
+        drift,
+        resolved_files: Vec::new(),
+        quick_check_status: ImplementationQuickCheckStatus::Unavailable,
+        quick_check_command: None,
+        quick_check_failure: None,
+        finalized: false,
+        usage: None,
+    };
+    if outcome.drift.behind == 0 {
+        return Ok(outcome);
+    }
+
+    let run_id = format!("rebase-{}", uuid::Uuid::new_v4());
+    let sandbox = SandboxSession::create(repo_path, &run_id, "rebase", false)?;
+    on_progress(&format!(
+        "Replaying {} commit(s) onto {}",
+        outcome.drift.ahead, outcome.drift.base_ref
+    ));
+    let mut step = git_ops::rebase_onto(sandbox.path(), &outcome.drift.base_ref)?;
+    // Each round replays at least one commit, so this bounds a rebase that never settles.
+    for _ in 0..=outcome.drift.ahead {
+        let RebaseStep::Conflicted(files) = &step else {
+            break;
+        };
+        for file in files {
+            on_progress(&format!("Resolving conflicts in {}", file.display()));
+            let path = sandbox.path().join(file);
+            let content = std::fs::read_to_string(&path).map_err(|e| {
+                anyhow::anyhow!("Couldn't read conflicted {}: {}", file.display(), e)
+            })?;
+            let hunks = conflict_hunks(&content);
+            if hunks.is_empty() {
+                anyhow::bail!(
+                    "{} conflicts in a way Cosmos can't resolve (deleted, renamed, or binary); rebase it by hand",
+                    file.display()
+                );
+            }
+            let (proposals, usage) =
+                propose_resolutions(file, &content, &hunks, ConflictSides::Rebase, None).await?;
+            outcome.usage = merge_usage(outcome.usage.take(), usage);
+            let resolutions: Vec<Option<&str>> = proposals
+                .iter()
+                .map(|proposal| Some(proposal.as_str()))
+                .collect();
+            std::fs::write(&path, resolve_hunks(&content, &resolutions)?)?;
+            if !outcome.resolved_files.contains(file) {
+                outcome.resolved_files.push(file.clone());
+            }
+        }
+        step = git_ops::continue_rebase(sandbox.path(), files)?;
+    }
+    if step != RebaseStep::Done {
+        let _ = git_ops::abort_rebase(sandbox.path());
+        anyhow::bail!("The rebase kept stopping on conflicts; rebase it by hand");
+    }
+
+    on_progress("Running quick checks");
+    let (status, command, failure) = run_harness_quick_checks(config, sandbox.path(), repo_path)?;
+    outcome.quick_check_status = status;
+    outcome.quick_check_command = command;
+    outcome.quick_check_failure = failure;
+    if status == ImplementationQuickCheckStatus::Failed {
+        return Ok(outcome);
+    }
+
+    let rebased = git_ops::head_commit(sandbox.path())
+        .ok_or_else(|| anyhow::anyhow!("The rebased worktree has no HEAD commit"))?;
+    git_ops::move_checked_out_branch(repo_path, &outcome.branch, &rebased)?;
+    outcome.finalized = true;
+    Ok(outcome)
+}
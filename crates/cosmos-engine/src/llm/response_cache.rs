@@ -0,0 +1,202 @@
+//! Reuse of responses to identical requests.
+//!
+//! Off unless `response_cache.enabled` is set in the config. Only requests that ask for
+//! deterministic output (`temperature` 0) are cached: at any other temperature a second run
+//! is meant to be free to answer differently. Streamed requests are not cached either. The
+//! key is the SHA-256 of the request exactly as sent (model, messages, tools, limits), so any
+//! change to a prompt, the repo content in it, or the routing is a miss.
+//!
+//! Responses are stored per repo under `.cosmos/`. A hit is returned without its `usage`,
+//! because nothing was spent on it; the cost the original response reported is counted as
+//! saved in the run's [`ResponseCacheStats`].
+
+use cosmos_adapters::cache::Cache;
+use cosmos_adapters::config::Config;
+use cosmos_adapters::preset::sha256_hex;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+
+tokio::task_local! {
+    static RUN_STATS: Arc<Mutex<ResponseCacheStats>>;
+}
+
+static ACTIVE: RwLock<Option<Settings>> = RwLock::new(None);
+
+#[derive(Debug, Clone)]
+struct Settings {
+    repo_root: PathBuf,
+    ttl: Duration,
+    max_bytes: u64,
+}
+
+/// How a run used the response cache.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ResponseCacheStats {
+    /// Cacheable requests answered from the cache
+    pub hits: usize,
+    /// Cacheable requests that had to be sent
+    pub misses: usize,
+    /// Cost the cached responses reported when they were first paid for
+    pub saved_usd: f64,
+}
+
+impl ResponseCacheStats {
+    pub fn is_empty(&self) -> bool {
+        self.hits == 0 && self.misses == 0
+    }
+}
+
+/// Validate the config's `response_cache` and turn the cache on or off for `repo_root`.
+pub fn install(config: &Config, repo_root: &Path) -> anyhow::Result<()> {
+    if let Err(problems) = config.validate_response_cache() {
+        return Err(anyhow::anyhow!(
+            "Invalid response_cache in {}:\n  {}",
+            Config::config_location(),
+            problems.join("\n  ")
+        ));
+    }
+    let settings = config.response_cache().map(|cache| Settings {
+        repo_root: repo_root.to_path_buf(),
+        ttl: cache.ttl(),
+        max_bytes: cache.max_bytes(),
+    });
+    if let Ok(mut active) = ACTIVE.write() {
+        *active = settings;
+    }
+    Ok(())
+}
+
+fn settings() -> Option<Settings> {
+    ACTIVE.read().ok()?.clone()
+}
+
+/// Cache key for `request`, or None when the cache is off or the request isn't deterministic.
+pub(crate) fn key<T: Serialize>(request: &T) -> Option<String> {
+    settings()?;
+    request_key(&serde_json::to_value(request).ok()?)
+}
+
+fn request_key(request: &serde_json::Value) -> Option<String> {
+    let deterministic = request
+        .get("temperature")
+        .and_then(|t| t.as_f64())
+        .is_some_and(|t| t == 0.0);
+    let streamed = request
+        .get("stream")
+        .and_then(|s| s.as_bool())
+        .unwrap_or(false);
+    if !deterministic || streamed {
+        return None;
+    }
+    Some(sha256_hex(request.to_string().as_bytes()))
+}
+
+/// The stored response for `key`, without usage; counts a hit or a miss for the run.
+pub(crate) fn lookup(key: &str) -> Option<String> {
+    let settings = settings()?;
+    let cached = Cache::new(&settings.repo_root)
+        .load_cached_response(key, settings.ttl)
+        .and_then(|text| serde_json::from_str::<serde_json::Value>(&text).ok());
+    let Some(mut response) = cached else {
+        note(|stats| stats.misses += 1);
+        return None;
+    };
+    let saved_usd = response
+        .get("usage")
+        .and_then(|usage| usage.get("cost").or_else(|| usage.get("total_cost")))
+        .and_then(|cost| cost.as_f64())
+        .unwrap_or(0.0);
+    if let Some(fields) = response.as_object_mut() {
+        fields.remove("usage");
+    }
+    note(|stats| {
+        stats.hits += 1;
+        stats.saved_usd += saved_usd;
+    });
+    Some(response.to_string())
+}
+
+/// Keep a successful response for later identical requests.
+pub(crate) fn store(key: &str, response: &str) {
+    let Some(settings) = settings() else {
+        return;
+    };
+    // Only complete completions: provider error envelopes and odd payloads aren't replayed
+    let complete = serde_json::from_str::<serde_json::Value>(response)
+        .ok()
+        .is_some_and(|value| value.get("choices").is_some());
+    if complete {
+        let _ =
+            Cache::new(&settings.repo_root).save_cached_response(key, response, settings.max_bytes);
+    }
+}
+
+fn note(update: impl FnOnce(&mut ResponseCacheStats)) {
+    let _ = RUN_STATS.try_with(|stats| {
+        if let Ok(mut stats) = stats.lock() {
+            update(&mut stats);
+        }
+    });
+}
+
+/// Run `fut`, returning its output and how it used the response cache.
+pub(crate) async fn record_stats<F: Future>(fut: F) -> (F::Output, ResponseCacheStats) {
+    let stats = Arc::new(Mutex::new(ResponseCacheStats::default()));
+    let output = RUN_STATS.scope(Arc::clone(&stats), fut).await;
+    let stats = stats.lock().map(|s| s.clone()).unwrap_or_default();
+    (output, stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_deterministic_unstreamed_requests_get_a_key() {
+        let request = serde_json::json!({ "model": "m", "messages": [], "temperature": 0.0 });
+        let key = request_key(&request).unwrap();
+        assert_eq!(key.len(), 64);
+
+        let mut other_prompt = request.clone();
+        other_prompt["messages"] = serde_json::json!([{ "role": "user", "content": "hi" }]);
+        assert_ne!(request_key(&other_prompt), Some(key));
+
+        let warm = serde_json::json!({ "model": "m", "messages": [], "temperature": 0.2 });
+        assert_eq!(request_key(&warm), None);
+        let unset = serde_json::json!({ "model": "m", "messages": [] });
+        assert_eq!(request_key(&unset), None);
+        let streamed = serde_json::json!({ "model": "m", "temperature": 0, "stream": true });
+        assert_eq!(request_key(&streamed), None);
+    }
+
+    #[tokio::test]
+    async fn hits_come_back_without_usage_and_count_what_they_saved() {
+        let root = tempfile::tempdir().unwrap();
+        let config: Config =
+            serde_json::from_str(r#"{"response_cache":{"enabled":true}}"#).unwrap();
+        install(&config, root.path()).unwrap();
+
+        let request = serde_json::json!({ "model": "m", "messages": [], "temperature": 0.0 });
+        let response = r#"{"choices":[{"message":{"content":"ok"}}],"usage":{"total_tokens":9,"total_cost":0.002}}"#;
+        let ((), stats) = record_stats(async {
+            let key = key(&request).unwrap();
+            assert_eq!(lookup(&key), None);
+            store(&key, response);
+            store("error-envelope", r#"{"error":{"message":"busy"}}"#);
+            let hit: serde_json::Value = serde_json::from_str(&lookup(&key).unwrap()).unwrap();
+            assert_eq!(hit["choices"][0]["message"]["content"], "ok");
+            assert!(hit.get("usage").is_none());
+            assert_eq!(lookup("error-envelope"), None);
+        })
+        .await;
+        assert_eq!(stats.hits, 1);
+        assert_eq!(stats.misses, 2);
+        assert!((stats.saved_usd - 0.002).abs() < 1e-9);
+
+        install(&Config::default(), root.path()).unwrap();
+        assert_eq!(key(&request), None);
+    }
+}
@@ -0,0 +1,640 @@
+//! Semantic retrieval over code chunks.
+//!
+//! Grounding used to come from heuristics alone (churn, path and symbol matches). This embeds
+//! fixed-size chunks of every indexed file, caches the vectors by file content hash, and ranks
+//! chunks by cosine similarity to a query so suggestion generation and Ask Cosmos start from
+//! the code most related to what they are looking for.
+//!
+//! The provider comes from the `[embeddings]` table of `.cosmos/config.toml`:
+//!
+//! ```toml
+//! [embeddings]
+//! provider = "openai"                  # "local" (default), "openai", or "off"
+//! model = "text-embedding-3-small"
+//! url = "https://api.openai.com/v1/embeddings"  # any OpenAI-compatible endpoint
+//! api_key_env = "OPENAI_API_KEY"
+//! ```
+//!
+//! The local provider hashes identifier tokens into a fixed-size vector; it needs no network
+//! and no key, and is what runs when nothing is configured.
+
+use super::client::{create_http_client, truncate_str};
+use super::implementation::REPO_CONFIG_PATH;
+use super::models::MODEL_CONTEXT_WINDOW;
+use super::redaction;
+use cosmos_adapters::cache::{Cache, ChunkEmbedding, EmbeddingCache};
+use cosmos_core::index::CodebaseIndex;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Lines per embedded chunk.
+const CHUNK_LINES: usize = 40;
+/// Files larger than this are not embedded.
+const MAX_FILE_BYTES: u64 = 256 * 1024;
+/// Most files embedded per repo (in path order).
+const MAX_EMBEDDED_FILES: usize = 2_000;
+/// Characters of a chunk sent to a remote provider.
+const REMOTE_CHUNK_MAX_CHARS: usize = 4_000;
+/// Chunks per remote embedding request.
+const REMOTE_BATCH_SIZE: usize = 64;
+const REMOTE_TIMEOUT_SECS: u64 = 60;
+/// Hits below this similarity are noise.
+const MIN_SCORE: f32 = 0.08;
+/// Hits kept per file, so one file cannot crowd out the rest.
+const MAX_HITS_PER_FILE: usize = 2;
+/// Lines of each hit shown in a prompt, at the default context window.
+const SNIPPET_MAX_LINES: usize = 24;
+/// Upper bound on a rendered retrieval section at the default context window.
+pub const RETRIEVED_SECTION_MAX_CHARS: usize = 6_000;
+/// How far a model's window can scale the evidence budget down or up.
+const MIN_WINDOW_SCALE: f64 = 0.25;
+const MAX_WINDOW_SCALE: f64 = 4.0;
+
+fn default_local_dims() -> usize {
+    256
+}
+
+fn default_openai_model() -> String {
+    "text-embedding-3-small".to_string()
+}
+
+fn default_openai_url() -> String {
+    "https://api.openai.com/v1/embeddings".to_string()
+}
+
+fn default_openai_key_env() -> String {
+    "OPENAI_API_KEY".to_string()
+}
+
+/// Embedding provider, read from the `[embeddings]` table of `.cosmos/config.toml`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "provider", rename_all = "snake_case")]
+pub enum EmbeddingConfig {
+    /// Hashed identifier tokens; offline and free
+    Local {
+        #[serde(default = "default_local_dims")]
+        dims: usize,
+    },
+    /// Any OpenAI-compatible `/embeddings` endpoint
+    Openai {
+        #[serde(default = "default_openai_model")]
+        model: String,
+        #[serde(default = "default_openai_url")]
+        url: String,
+        /// Environment variable holding the API key
+        #[serde(default = "default_openai_key_env")]
+        api_key_env: String,
+    },
+    /// Retrieval disabled
+    Off,
+}
+
+impl Default for EmbeddingConfig {
+    fn default() -> Self {
+        Self::Local {
+            dims: default_local_dims(),
+        }
+    }
+}
+
+impl EmbeddingConfig {
+    /// Load `[embeddings]` from the repo config; missing means the local provider.
+    pub fn load(repo_root: &Path) -> anyhow::Result<Self> {
+        #[derive(Deserialize, Default)]
+        struct RepoEmbeddingConfig {
+            #[serde(default)]
+            embeddings: Option<EmbeddingConfig>,
+        }
+
+        let content = match std::fs::read_to_string(repo_root.join(REPO_CONFIG_PATH)) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => {
+                return Err(anyhow::anyhow!(
+                    "Failed to read {}: {}",
+                    REPO_CONFIG_PATH,
+                    e
+                ))
+            }
+        };
+        let parsed: RepoEmbeddingConfig = toml::from_str(&content)
+            .map_err(|e| anyhow::anyhow!("Invalid {}: {}", REPO_CONFIG_PATH, e))?;
+        Ok(parsed.embeddings.unwrap_or_default())
+    }
+
+    /// Identifies the vector space; cached vectors from another model are discarded.
+    pub fn model_id(&self) -> String {
+        match self {
+            Self::Local { dims } => format!("local-hash-{}", dims),
+            Self::Openai { model, url, .. } => format!("openai:{}@{}", model, url),
+            Self::Off => "off".to_string(),
+        }
+    }
+
+    async fn embed(&self, texts: &[String]) -> anyhow::Result<Vec<Vec<f32>>> {
+        match self {
+            Self::Local { dims } => Ok(texts
+                .iter()
+                .map(|text| local_embedding(text, *dims))
+                .collect()),
+            Self::Openai {
+                model,
+                url,
+                api_key_env,
+            } => embed_remote(model, url, api_key_env, texts).await,
+            Self::Off => Ok(Vec::new()),
+        }
+    }
+}
+
+/// FNV-1a, stable across toolchains so cached local vectors stay valid.
+fn stable_token_hash(token: &str) -> u64 {
+    token.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Identifier tokens, with camelCase and snake_case split into words.
+fn embedding_tokens(text: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    for word in text.split(|c: char| !c.is_ascii_alphanumeric()) {
+        let mut current = String::new();
+        let mut prev_lower = false;
+        for c in word.chars() {
+            if c.is_ascii_uppercase() && prev_lower && !current.is_empty() {
+                tokens.push(std::mem::take(&mut current));
+            }
+            prev_lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+            current.push(c.to_ascii_lowercase());
+        }
+        if !current.is_empty() {
+            tokens.push(current);
+        }
+    }
+    tokens.retain(|token| token.len() > 1 && !token.chars().all(|c| c.is_ascii_digit()));
+    tokens
+}
+
+fn local_embedding(text: &str, dims: usize) -> Vec<f32> {
+    let mut vector = vec![0.0f32; dims.max(1)];
+    for token in embedding_tokens(text) {
+        let hash = stable_token_hash(&token);
+        let slot = (hash % vector.len() as u64) as usize;
+        let sign = if hash >> 63 == 0 { 1.0 } else { -1.0 };
+        vector[slot] += sign;
+    }
+    normalize(&mut vector);
+    vector
+}
+
+fn normalize(vector: &mut [f32]) {
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|v| *v /= norm);
+    }
+}
+
+fn cosine(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+#[derive(Serialize)]
+struct EmbeddingRequest<'a> {
+    model: &'a str,
+    input: &'a [String],
+}
+
+#[derive(Deserialize)]
+struct EmbeddingResponse {
+    data: Vec<EmbeddingData>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingData {
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+async fn embed_remote(
+    model: &str,
+    url: &str,
+    api_key_env: &str,
+    texts: &[String],
+) -> anyhow::Result<Vec<Vec<f32>>> {
+    let api_key = std::env::var(api_key_env)
+        .ok()
+        .filter(|key| !key.trim().is_empty())
+        .ok_or_else(|| {
+            anyhow::anyhow!("Embedding provider needs an API key in ${}", api_key_env)
+        })?;
+    let client = create_http_client(REMOTE_TIMEOUT_SECS)?;
+    let mut vectors = Vec::with_capacity(texts.len());
+    for batch in texts.chunks(REMOTE_BATCH_SIZE) {
+        let batch: Vec<String> = batch.iter().map(|text| redaction::redact(text)).collect();
+        let resp = client
+            .post(url)
+            .bearer_auth(&api_key)
+            .json(&EmbeddingRequest {
+                model,
+                input: &batch,
+            })
+            .send()
+            .await
+            .map_err(|e| anyhow::anyhow!("Embedding request failed: {}", e))?;
+        let status = resp.status();
+        if !status.is_success() {
+            return Err(anyhow::anyhow!("Embedding provider returned {}", status));
+        }
+        let mut parsed: EmbeddingResponse = resp
+            .json()
+            .await
+            .map_err(|e| anyhow::anyhow!("Invalid embedding response: {}", e))?;
+        if parsed.data.len() != batch.len() {
+            return Err(anyhow::anyhow!(
+                "Embedding provider returned {} vectors for {} inputs",
+                parsed.data.len(),
+                batch.len()
+            ));
+        }
+        parsed.data.sort_by_key(|item| item.index);
+        vectors.extend(parsed.data.into_iter().map(|item| {
+            let mut vector = item.embedding;
+            normalize(&mut vector);
+            vector
+        }));
+    }
+    Ok(vectors)
+}
+
+/// A line chunk: start and end line (1-based, inclusive) and its text.
+type LineChunk = (usize, usize, String);
+
+/// Split a file into line chunks, skipping blank ones.
+fn chunk_lines(content: &str) -> Vec<LineChunk> {
+    let lines: Vec<&str> = content.lines().collect();
+    lines
+        .chunks(CHUNK_LINES)
+        .enumerate()
+        .filter(|(_, chunk)| chunk.iter().any(|line| !line.trim().is_empty()))
+        .map(|(i, chunk)| {
+            let start = i * CHUNK_LINES + 1;
+            (start, start + chunk.len() - 1, chunk.join("\n"))
+        })
+        .collect()
+}
+
+/// A chunk of code ranked against a query.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetrievedSnippet {
+    pub file: PathBuf,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub score: f32,
+    pub text: String,
+}
+
+/// What one retrieval did, for diagnostics.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RetrievalStats {
+    pub model: String,
+    /// Files whose vectors were reused from the cache
+    pub cached_files: usize,
+    /// Files embedded during this retrieval
+    pub embedded_files: usize,
+    /// Snippets returned for the query
+    pub hits: usize,
+}
+
+/// How much retrieved code a prompt carries, sized to the model's context window.
+///
+/// The defaults were tuned for the built-in 131k window. Smaller windows get fewer, shorter
+/// snippets; larger ones get more, with snippet length growing more slowly than the count so
+/// extra room buys breadth first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EvidenceBudget {
+    /// Snippets to retrieve
+    pub snippets: usize,
+    /// Lines shown per snippet
+    pub snippet_max_lines: usize,
+    /// Upper bound on the rendered section
+    pub section_max_chars: usize,
+}
+
+impl EvidenceBudget {
+    /// Budget for `context_window`, scaled from `base_snippets` at the default window.
+    pub fn for_context_window(context_window: u32, base_snippets: usize) -> Self {
+        let scale = (context_window as f64 / MODEL_CONTEXT_WINDOW as f64)
+            .clamp(MIN_WINDOW_SCALE, MAX_WINDOW_SCALE);
+        Self {
+            snippets: ((base_snippets as f64 * scale).round() as usize).max(1),
+            snippet_max_lines: ((SNIPPET_MAX_LINES as f64 * scale.sqrt()).round() as usize)
+                .max(CHUNK_LINES / 4),
+            section_max_chars: (RETRIEVED_SECTION_MAX_CHARS as f64 * scale).round() as usize,
+        }
+    }
+
+    /// Share of the section budget `section` uses.
+    pub fn utilization(&self, section: &str) -> f64 {
+        if self.section_max_chars == 0 {
+            return 0.0;
+        }
+        section.len() as f64 / self.section_max_chars as f64
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Retrieval {
+    pub snippets: Vec<RetrievedSnippet>,
+    pub stats: RetrievalStats,
+}
+
+impl Retrieval {
+    /// Prompt section listing the hits with their code within `budget`, or empty when there
+    /// are none.
+    pub fn to_prompt_section(&self, heading: &str, budget: &EvidenceBudget) -> String {
+        if self.snippets.is_empty() {
+            return String::new();
+        }
+        let mut section = format!("\n\n{}:\n", heading);
+        for snippet in &self.snippets {
+            let text: String = snippet
+                .text
+                .lines()
+                .take(budget.snippet_max_lines)
+                .collect::<Vec<_>>()
+                .join("\n");
+            let entry = format!(
+                "\n{}:{}-{} (similarity {:.2})\n```\n{}\n```\n",
+                snippet.file.display(),
+                snippet.start_line,
+                snippet.end_line,
+                snippet.score,
+                text
+            );
+            if section.len() + entry.len() > budget.section_max_chars {
+                break;
+            }
+            section.push_str(&entry);
+        }
+        section
+    }
+
+    pub fn files(&self) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = Vec::new();
+        for snippet in &self.snippets {
+            if !files.contains(&snippet.file) {
+                files.push(snippet.file.clone());
+            }
+        }
+        files
+    }
+}
+
+/// Embed any files whose vectors are missing or stale, then rank chunks against `query`.
+///
+/// Vectors are cached in `.cosmos/v2/embeddings.json`, keyed by file content hash.
+pub async fn retrieve(
+    repo_root: &Path,
+    index: &CodebaseIndex,
+    query: &str,
+    limit: usize,
+) -> anyhow::Result<Retrieval> {
+    let config = EmbeddingConfig::load(repo_root)?;
+    if config == EmbeddingConfig::Off || limit == 0 || query.trim().is_empty() {
+        return Ok(Retrieval::default());
+    }
+    let model = config.model_id();
+    let cache = Cache::new(repo_root);
+    let mut embeddings = cache
+        .load_embedding_cache(&model)
+        .unwrap_or_else(|| EmbeddingCache::new(&model));
+    embeddings.retain_files(|path| index.files.contains_key(path));
+
+    let mut paths: Vec<&PathBuf> = index.files.keys().collect();
+    paths.sort();
+    paths.truncate(MAX_EMBEDDED_FILES);
+
+    let mut stats = RetrievalStats {
+        model: model.clone(),
+        ..Default::default()
+    };
+    let mut pending: Vec<(PathBuf, String, Vec<LineChunk>)> = Vec::new();
+    for path in paths {
+        let file = &index.files[path];
+        if embeddings.get(path, &file.content_hash).is_some() {
+            stats.cached_files += 1;
+            continue;
+        }
+        let absolute = repo_root.join(path);
+        let small_enough = std::fs::metadata(&absolute)
+            .map(|meta| meta.len() <= MAX_FILE_BYTES)
+            .unwrap_or(false);
+        let Some(content) = small_enough
+            .then(|| std::fs::read_to_string(&absolute).ok())
+            .flatten()
+        else {
+            continue;
+        };
+        pending.push((
+            path.clone(),
+            file.content_hash.clone(),
+            chunk_lines(&content),
+        ));
+    }
+
+    if !pending.is_empty() {
+        let texts: Vec<String> = pending
+            .iter()
+            .flat_map(|(_, _, chunks)| chunks.iter())
+            .map(|(_, _, text)| truncate_str(text, REMOTE_CHUNK_MAX_CHARS).to_string())
+            .collect();
+        let mut vectors = config.embed(&texts).await?.into_iter();
+        for (path, content_hash, chunks) in pending {
+            let chunks = chunks
+                .into_iter()
+                .filter_map(|(start_line, end_line, _)| {
+                    vectors.next().map(|vector| ChunkEmbedding {
+                        start_line,
+                        end_line,
+                        vector,
+                    })
+                })
+                .collect();
+            embeddings.insert(path, content_hash, chunks);
+            stats.embedded_files += 1;
+        }
+        let _ = cache.save_embedding_cache(&embeddings);
+    }
+
+    let query_vector = config
+        .embed(&[query.to_string()])
+        .await?
+        .pop()
+        .unwrap_or_default();
+    let mut ranked: Vec<(&PathBuf, &ChunkEmbedding, f32)> = embeddings
+        .files
+        .iter()
+        .flat_map(|(path, entry)| entry.chunks.iter().map(move |chunk| (path, chunk)))
+        .map(|(path, chunk)| (path, chunk, cosine(&query_vector, &chunk.vector)))
+        .filter(|(_, _, score)| *score >= MIN_SCORE)
+        .collect();
+    ranked.sort_by(|a, b| {
+        b.2.total_cmp(&a.2)
+            .then_with(|| a.0.cmp(b.0))
+            .then_with(|| a.1.start_line.cmp(&b.1.start_line))
+    });
+
+    let mut snippets: Vec<RetrievedSnippet> = Vec::new();
+    for (path, chunk, score) in ranked {
+        if snippets.len() == limit {
+            break;
+        }
+        if snippets.iter().filter(|s| &s.file == path).count() >= MAX_HITS_PER_FILE {
+            continue;
+        }
+        let Ok(content) = std::fs::read_to_string(repo_root.join(path)) else {
+            continue;
+        };
+        let text = content
+            .lines()
+            .skip(chunk.start_line - 1)
+            .take(chunk.end_line + 1 - chunk.start_line)
+            .collect::<Vec<_>>()
+            .join("\n");
+        snippets.push(RetrievedSnippet {
+            file: path.clone(),
+            start_line: chunk.start_line,
+            end_line: chunk.end_line,
+            score,
+            text,
+        });
+    }
+    stats.hits = snippets.len();
+    Ok(Retrieval { snippets, stats })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokens_split_identifiers_and_local_vectors_rank_related_text() {
+        assert_eq!(
+            embedding_tokens("fn retryRequest(max_attempts: u32) -> 42"),
+            vec!["fn", "retry", "request", "max", "attempts", "u32"]
+        );
+
+        let query = local_embedding("retry request backoff", 256);
+        let related = local_embedding("fn retry_request() { backoff.sleep(); }", 256);
+        let unrelated = local_embedding("struct Theme { color: Rgb }", 256);
+        assert!(cosine(&query, &related) > cosine(&query, &unrelated));
+        assert!((cosine(&related, &related) - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn chunks_cover_the_file_and_skip_blank_ranges() {
+        let mut content: String = (1..=45).map(|i| format!("line {}\n", i)).collect();
+        content.push_str(&"\n".repeat(40));
+        let chunks = chunk_lines(&content);
+        assert_eq!(chunks.len(), 2);
+        assert_eq!((chunks[0].0, chunks[0].1), (1, 40));
+        assert_eq!((chunks[1].0, chunks[1].1), (41, 80));
+    }
+
+    #[test]
+    fn config_defaults_to_local_and_parses_providers() {
+        let root = tempfile::tempdir().unwrap();
+        assert_eq!(
+            EmbeddingConfig::load(root.path()).unwrap(),
+            EmbeddingConfig::default()
+        );
+
+        std::fs::create_dir_all(root.path().join(".cosmos")).unwrap();
+        std::fs::write(
+            root.path().join(REPO_CONFIG_PATH),
+            "[embeddings]\nprovider = \"openai\"\nmodel = \"nomic-embed-text\"\nurl = \"http://localhost:11434/v1/embeddings\"\n",
+        )
+        .unwrap();
+        let config = EmbeddingConfig::load(root.path()).unwrap();
+        assert_eq!(
+            config.model_id(),
+            "openai:nomic-embed-text@http://localhost:11434/v1/embeddings"
+        );
+        assert!(matches!(
+            config,
+            EmbeddingConfig::Openai { ref api_key_env, .. } if api_key_env == "OPENAI_API_KEY"
+        ));
+
+        std::fs::write(
+            root.path().join(REPO_CONFIG_PATH),
+            "[embeddings]\nprovider = \"off\"\n",
+        )
+        .unwrap();
+        assert_eq!(
+            EmbeddingConfig::load(root.path()).unwrap(),
+            EmbeddingConfig::Off
+        );
+    }
+
+    #[test]
+    fn evidence_budget_scales_with_the_context_window() {
+        let default = EvidenceBudget::for_context_window(MODEL_CONTEXT_WINDOW, 8);
+        assert_eq!(
+            default,
+            EvidenceBudget {
+                snippets: 8,
+                snippet_max_lines: SNIPPET_MAX_LINES,
+                section_max_chars: RETRIEVED_SECTION_MAX_CHARS,
+            }
+        );
+
+        let small = EvidenceBudget::for_context_window(32_768, 8);
+        assert_eq!(small.snippets, 2);
+        assert_eq!(small.snippet_max_lines, 12);
+        assert_eq!(small.section_max_chars, RETRIEVED_SECTION_MAX_CHARS / 4);
+
+        // Beyond 4x the default window the budget stops growing.
+        let huge = EvidenceBudget::for_context_window(2_000_000, 8);
+        assert_eq!(huge.snippets, 32);
+        assert_eq!(huge.snippet_max_lines, 48);
+        assert_eq!(huge.section_max_chars, RETRIEVED_SECTION_MAX_CHARS * 4);
+        assert!((huge.utilization(&"x".repeat(12_000)) - 0.5).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn retrieve_ranks_related_chunks_and_reuses_cached_vectors() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(root.path().join("src")).unwrap();
+        std::fs::write(
+            root.path().join("src/retry.rs"),
+            "pub fn retry_request(attempts: u32) {\n    backoff_sleep(attempts);\n}\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.path().join("src/theme.rs"),
+            "pub struct Theme {\n    pub color: Rgb,\n}\n",
+        )
+        .unwrap();
+        let index = CodebaseIndex::new(root.path()).unwrap();
+
+        let first = retrieve(root.path(), &index, "where do we retry a request", 5)
+            .await
+            .unwrap();
+        assert_eq!(first.snippets[0].file, PathBuf::from("src/retry.rs"));
+        assert_eq!(first.stats.hits, first.snippets.len());
+        assert_eq!(first.stats.embedded_files, index.files.len());
+        let budget = EvidenceBudget::for_context_window(MODEL_CONTEXT_WINDOW, 5);
+        assert!(first
+            .to_prompt_section("RELEVANT CODE", &budget)
+            .contains("src/retry.rs:1-3"));
+
+        let second = retrieve(root.path(), &index, "retry backoff", 5)
+            .await
+            .unwrap();
+        assert_eq!(second.stats.embedded_files, 0);
+        assert_eq!(second.stats.cached_files, index.files.len());
+    }
+}
@@ -1,16 +1,19 @@
 use super::agentic::{call_llm_agentic, schema_to_response_format};
 use super::client::{
     call_llm_structured_limited_no_reasoning, call_llm_structured_limited_speed_with_failover,
-    call_llm_with_usage, parse_structured_content, SpeedFailoverDiagnostics, StructuredResponse,
+    call_llm_with_usage, parse_response, SpeedFailoverDiagnostics, StructuredResponse,
 };
 use super::fix::{
-    apply_edits_with_context, fix_response_schema, format_edit_apply_repair_guidance,
-    normalize_generated_content, AppliedFix, FixResponse,
+    apply_edits_with_context, format_edit_apply_repair_guidance, normalize_generated_content,
+    AppliedFix, FixResponse,
 };
 use super::models::{merge_usage, Model, Usage};
 use super::parse::{truncate_content, truncate_content_around_line};
 use super::prompt_utils::format_repo_memory_section;
 use super::prompts::{review_fix_system_prompt, review_system_prompt};
+use super::routing;
+use cosmos_adapters::config::ModelRole;
+use cosmos_core::protocol::schemas::ResponseKind;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::time::Duration;
@@ -111,62 +114,6 @@ fn ensure_non_summary_model(model: Model, operation: &str) -> anyhow::Result<()>
     Ok(())
 }
 
-/// JSON Schema for ReviewResponse - used for structured output
-/// This ensures the LLM returns valid, parseable JSON matching our expected format
-pub(crate) fn review_response_schema() -> serde_json::Value {
-    serde_json::json!({
-        "type": "object",
-        "properties": {
-            "summary": {
-                "type": "string",
-                "description": "Brief overall assessment of the code changes"
-            },
-            "findings": {
-                "type": "array",
-                "items": {
-                    "type": "object",
-                    "properties": {
-                        "file": {
-                            "type": "string",
-                            "description": "Path to the file containing the issue"
-                        },
-                        "line": {
-                            "type": ["integer", "null"],
-                            "description": "Line number where the issue occurs"
-                        },
-                        "severity": {
-                            "type": "string",
-                            "enum": ["critical", "warning", "suggestion", "nitpick"],
-                            "description": "Severity level of the finding"
-                        },
-                        "category": {
-                            "type": "string",
-                            "description": "Category like bug, security, performance, logic, error-handling, style"
-                        },
-                        "title": {
-                            "type": "string",
-                            "description": "Short title for the finding"
-                        },
-                        "description": {
-                            "type": "string",
-                            "description": "Detailed explanation in plain language"
-                        },
-                        "recommended": {
-                            "type": "boolean",
-                            "description": "Whether the reviewer recommends fixing this"
-                        }
-                    },
-                    "required": ["severity", "title", "description", "recommended"],
-                    "additionalProperties": false
-                },
-                "description": "List of issues found in the code"
-            }
-        },
-        "required": ["summary", "findings"],
-        "additionalProperties": false
-    })
-}
-
 fn is_response_format_schema_error_text(message: &str) -> bool {
     let lower = message.to_ascii_lowercase();
     lower.contains("invalid schema for response_format")
@@ -217,6 +164,19 @@ pub async fn verify_changes(
     iteration: u32,
     fixed_titles: &[String],
     fix_context: Option<&FixContext>,
+) -> anyhow::Result<VerificationReview> {
+    routing::with_role(
+        ModelRole::Review,
+        verify_changes_impl(files_with_content, iteration, fixed_titles, fix_context),
+    )
+    .await
+}
+
+async fn verify_changes_impl(
+    files_with_content: &[(PathBuf, String, String)], // (path, old_content, new_content)
+    iteration: u32,
+    fixed_titles: &[String],
+    fix_context: Option<&FixContext>,
 ) -> anyhow::Result<VerificationReview> {
     // Get repo root from first file path
     let repo_root = files_with_content
@@ -245,7 +205,10 @@ pub async fn verify_changes(
     let user = build_lean_review_prompt(files_with_content, fix_context);
 
     // Use structured output to guarantee valid JSON response
-    let response_format = schema_to_response_format("review_response", review_response_schema());
+    let response_format = schema_to_response_format(
+        &ResponseKind::Review.schema_name(),
+        ResponseKind::Review.schema(),
+    );
 
     // Use Smart model for default adversarial review quality.
     // 4 iterations - diff already provided, occasional context needed
@@ -260,14 +223,14 @@ pub async fn verify_changes(
     )
     .await?;
 
-    // Response is guaranteed to be valid JSON matching the schema
-    let parsed: ReviewResponseJson = parse_structured_content(&response.content).map_err(|e| {
-        anyhow::anyhow!(
-            "Failed to parse review response: {}. Content: {}",
-            e,
-            &response.content.chars().take(200).collect::<String>()
-        )
-    })?;
+    let parsed: ReviewResponseJson = parse_response(ResponseKind::Review, &response.content)
+        .map_err(|e| {
+            anyhow::anyhow!(
+                "Failed to parse review response: {}. Content: {}",
+                e,
+                &response.content.chars().take(200).collect::<String>()
+            )
+        })?;
 
     Ok(VerificationReview {
         findings: parsed.findings.into_iter().map(Into::into).collect(),
@@ -310,6 +273,28 @@ pub async fn verify_changes_bounded_with_model(
     fix_context: Option<&FixContext>,
     model: Model,
     timeout_ms: u64,
+) -> anyhow::Result<VerificationReview> {
+    routing::with_role(
+        ModelRole::Review,
+        verify_changes_bounded_with_model_impl(
+            files_with_content,
+            iteration,
+            fixed_titles,
+            fix_context,
+            model,
+            timeout_ms,
+        ),
+    )
+    .await
+}
+
+async fn verify_changes_bounded_with_model_impl(
+    files_with_content: &[(PathBuf, String, String)], // (path, old_content, new_content)
+    iteration: u32,
+    fixed_titles: &[String],
+    fix_context: Option<&FixContext>,
+    model: Model,
+    timeout_ms: u64,
 ) -> anyhow::Result<VerificationReview> {
     ensure_non_summary_model(model, "Review")?;
     let system = review_system_prompt(iteration, fixed_titles, fix_context);
@@ -322,8 +307,8 @@ pub async fn verify_changes_bounded_with_model(
             call_llm_structured_limited_speed_with_failover(
                 &system,
                 &user,
-                "review_response",
-                review_response_schema(),
+                &ResponseKind::Review.schema_name(),
+                ResponseKind::Review.schema(),
                 MAX_TOKENS,
                 timeout_ms,
             )
@@ -333,8 +318,8 @@ pub async fn verify_changes_bounded_with_model(
                 &system,
                 &user,
                 model,
-                "review_response",
-                review_response_schema(),
+                &ResponseKind::Review.schema_name(),
+                ResponseKind::Review.schema(),
                 MAX_TOKENS,
                 timeout_ms,
             )
@@ -378,10 +363,12 @@ pub async fn verify_changes_bounded_with_model(
                     ));
                 }
             };
-            let parsed: ReviewResponseJson = parse_structured_content(&fallback_response.content)
-                .map_err(|parse_err| {
-                anyhow::anyhow!("Review schema fallback parse failed: {}", parse_err)
-            })?;
+            let parsed: ReviewResponseJson =
+                parse_response(ResponseKind::Review, &fallback_response.content).map_err(
+                    |parse_err| {
+                        anyhow::anyhow!("Review schema fallback parse failed: {}", parse_err)
+                    },
+                )?;
             validate_review_response_semantics(&parsed)?;
             Ok(VerificationReview {
                 findings: parsed.findings.into_iter().map(Into::into).collect(),
@@ -667,6 +654,35 @@ pub async fn fix_review_findings_with_model(
     fixed_titles: &[String],
     model: Model,
     timeout_ms: u64,
+) -> anyhow::Result<AppliedFix> {
+    routing::with_role(
+        ModelRole::Implement,
+        fix_review_findings_with_model_impl(
+            path,
+            content,
+            original_content,
+            findings,
+            repo_memory,
+            iteration,
+            fixed_titles,
+            model,
+            timeout_ms,
+        ),
+    )
+    .await
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn fix_review_findings_with_model_impl(
+    path: &std::path::Path,
+    content: &str,
+    original_content: Option<&str>,
+    findings: &[ReviewFinding],
+    repo_memory: Option<String>,
+    iteration: u32,
+    fixed_titles: &[String],
+    model: Model,
+    timeout_ms: u64,
 ) -> anyhow::Result<AppliedFix> {
     ensure_non_summary_model(model, "Review-fix generation")?;
     if findings.is_empty() {
@@ -779,8 +795,8 @@ pub async fn fix_review_findings_with_model(
             call_llm_structured_limited_speed_with_failover(
                 &system,
                 &user_attempt,
-                "fix_response",
-                fix_response_schema(),
+                &ResponseKind::Fix.schema_name(),
+                ResponseKind::Fix.schema(),
                 MAX_REVIEW_FIX_RESPONSE_TOKENS_SPEED,
                 attempt_timeout_ms,
             )
@@ -791,8 +807,8 @@ pub async fn fix_review_findings_with_model(
                 &system,
                 &user_attempt,
                 model,
-                "fix_response",
-                fix_response_schema(),
+                &ResponseKind::Fix.schema_name(),
+                ResponseKind::Fix.schema(),
                 MAX_REVIEW_FIX_RESPONSE_TOKENS_SPEED,
                 attempt_timeout_ms,
             )
@@ -832,10 +848,13 @@ pub async fn fix_review_findings_with_model(
                         ));
                     }
                 };
-                let parsed: FixResponse = parse_structured_content(&fallback_response.content)
-                    .map_err(|parse_err| {
-                        anyhow::anyhow!("Review-fix schema fallback parse failed: {}", parse_err)
-                    })?;
+                let parsed: FixResponse = parse_response(
+                    ResponseKind::Fix,
+                    &fallback_response.content,
+                )
+                .map_err(|parse_err| {
+                    anyhow::anyhow!("Review-fix schema fallback parse failed: {}", parse_err)
+                })?;
                 (parsed, fallback_response.usage, None, true)
             }
         };
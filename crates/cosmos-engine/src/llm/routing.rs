@@ -0,0 +1,197 @@
+//! Per-role model routing.
+//!
+//! Engine entry points run inside `with_role`, and the request builders in `client` and
+//! `agentic` consult the current role's `ModelRoute` for the model id, completion-token cap,
+//! and temperature. Roles without a route keep the built-in tier settings, as do operations
+//! close to their spend cap, which also drop to the Speed tier's model (see `spend`).
+
+use super::implementation::{load_repo_harness_config, REPO_CONFIG_PATH};
+use super::models::Model;
+use super::spend;
+use cosmos_adapters::config::{Config, ModelRole, ModelRoute};
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::path::Path;
+use std::sync::RwLock;
+
+tokio::task_local! {
+    static CURRENT_ROLE: ModelRole;
+}
+
+static ACTIVE_ROUTES: RwLock<BTreeMap<ModelRole, ModelRoute>> = RwLock::new(BTreeMap::new());
+
+/// Validate the config's `model_routing` and make it active for this process.
+///
+/// Returns the names of the routed roles.
+pub fn install(config: &Config) -> anyhow::Result<Vec<&'static str>> {
+    if let Err(problems) = config.validate_model_routing() {
+        return Err(anyhow::anyhow!(
+            "Invalid model_routing in {}:\n  {}",
+            Config::config_location(),
+            problems.join("\n  ")
+        ));
+    }
+    let routes: BTreeMap<ModelRole, ModelRoute> = ModelRole::ALL
+        .into_iter()
+        .filter_map(|role| config.model_route(role).map(|route| (role, route.clone())))
+        .collect();
+    let routed = routes.keys().map(|role| role.name()).collect();
+    if let Ok(mut active) = ACTIVE_ROUTES.write() {
+        *active = routes;
+    }
+    Ok(routed)
+}
+
+/// Like `install`, with the repo's `[model_routing]` from `.cosmos/config.toml` as defaults
+/// underneath the user's routes.
+pub fn install_for_repo(config: &Config, repo_root: &Path) -> anyhow::Result<Vec<&'static str>> {
+    let repo_routes = load_repo_model_routing(repo_root)?;
+    install(&config.clone().with_repo_model_routing(repo_routes))
+}
+
+/// Load and validate the repo's default routes without installing them.
+pub fn load_repo_model_routing(repo_root: &Path) -> anyhow::Result<BTreeMap<String, ModelRoute>> {
+    let routes = load_repo_harness_config(repo_root)?.model_routing;
+    let check = Config::default().with_repo_model_routing(routes.clone());
+    if let Err(problems) = check.validate_model_routing() {
+        return Err(anyhow::anyhow!(
+            "Invalid model_routing in {}:\n  {}",
+            REPO_CONFIG_PATH,
+            problems.join("\n  ")
+        ));
+    }
+    Ok(routes)
+}
+
+/// Run `fut` with `role` as the current routing role. Nested calls use the innermost role.
+///
+/// The outermost call also opens the spend-cap operation (see `spend`) that every request
+/// inside counts toward.
+pub(crate) async fn with_role<F: Future>(role: ModelRole, fut: F) -> F::Output {
+    CURRENT_ROLE.scope(role, spend::scoped(role, fut)).await
+}
+
+/// The role the current task runs under, if any.
+pub(crate) fn current_role() -> Option<ModelRole> {
+    CURRENT_ROLE.try_with(|role| *role).ok()
+}
+
+fn current_route() -> Option<ModelRoute> {
+    if spend::downgrade_due() {
+        return None;
+    }
+    let role = current_role()?;
+    ACTIVE_ROUTES.read().ok()?.get(&role).cloned()
+}
+
+/// Model id for the current role, falling back to the tier's built-in id.
+///
+/// Near its spend cap an operation gets the Speed tier's built-in model instead.
+pub(crate) fn routed_model_id(model: Model) -> String {
+    match CURRENT_ROLE.try_with(|role| *role) {
+        Ok(role) if spend::downgrade_due() => {
+            let routed = model_id_for_role(role, model);
+            let cheaper = Model::Speed.id();
+            if routed != cheaper {
+                spend::note_downgrade(&routed, cheaper);
+            }
+            cheaper.to_string()
+        }
+        Ok(role) => model_id_for_role(role, model),
+        Err(_) => model.id().to_string(),
+    }
+}
+
+/// Model id `role` sends for `model`, for planning outside a role scope (dry runs).
+pub(crate) fn model_id_for_role(role: ModelRole, model: Model) -> String {
+    ACTIVE_ROUTES
+        .read()
+        .ok()
+        .and_then(|routes| routes.get(&role).and_then(|route| route.model.clone()))
+        .unwrap_or_else(|| model.id().to_string())
+}
+
+/// Completion-token budget after applying the current role's cap.
+pub(crate) fn routed_max_tokens(default: u32) -> u32 {
+    apply_max_tokens(current_route().as_ref(), default)
+}
+
+/// Context window for the current role's model, or `default` when the role sets none.
+pub(crate) fn routed_context_window(default: u32) -> u32 {
+    current_route()
+        .and_then(|route| route.context_window)
+        .unwrap_or(default)
+}
+
+/// Context window `role` works with, for planning outside a role scope (dry runs).
+pub(crate) fn context_window_for_role(role: ModelRole, model: Model) -> u32 {
+    ACTIVE_ROUTES
+        .read()
+        .ok()
+        .and_then(|routes| routes.get(&role).and_then(|route| route.context_window))
+        .unwrap_or_else(|| model.context_window())
+}
+
+/// Temperature for the current role, or `default` when the role sets none.
+pub(crate) fn routed_temperature(default: Option<f32>) -> Option<f32> {
+    current_route()
+        .and_then(|route| route.temperature)
+        .or(default)
+}
+
+/// Models to fail over to, in order, when the current role's model is down.
+pub(crate) fn routed_fallbacks() -> Vec<String> {
+    current_route()
+        .map(|route| route.fallbacks)
+        .unwrap_or_default()
+}
+
+/// Each route's failover chain (its model, then its fallbacks), for the provider preflight.
+///
+/// Routes without their own model start from `default_model`.
+pub(crate) fn routed_model_chains(default_model: &str) -> Vec<Vec<String>> {
+    ACTIVE_ROUTES
+        .read()
+        .map(|routes| {
+            routes
+                .values()
+                .map(|route| {
+                    let primary = route.model.as_deref().unwrap_or(default_model);
+                    std::iter::once(primary.to_string())
+                        .chain(route.fallbacks.iter().cloned())
+                        .collect()
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn apply_max_tokens(route: Option<&ModelRoute>, default: u32) -> u32 {
+    match route.and_then(|route| route.max_tokens) {
+        Some(cap) => default.min(cap),
+        None => default,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn route_caps_but_never_raises_max_tokens() {
+        let route = ModelRoute {
+            max_tokens: Some(4_000),
+            ..ModelRoute::default()
+        };
+        assert_eq!(apply_max_tokens(Some(&route), 40_000), 4_000);
+        assert_eq!(apply_max_tokens(Some(&route), 1_000), 1_000);
+        assert_eq!(apply_max_tokens(None, 40_000), 40_000);
+    }
+
+    #[tokio::test]
+    async fn outside_a_role_scope_the_tier_defaults_apply() {
+        assert_eq!(routed_model_id(Model::Smart), Model::Smart.id());
+        assert_eq!(routed_temperature(Some(0.3)), Some(0.3));
+        assert_eq!(routed_max_tokens(1_200), 1_200);
+    }
+}
@@ -0,0 +1,298 @@
+//! Per-operation spend caps.
+//!
+//! Each engine entry point runs as one operation: the outermost `routing::with_role` scope
+//! opens it, and requests made under nested roles count toward it. The client adds every
+//! response's reported cost to the operation's total and refuses to send another request
+//! once the total reaches the cap configured for the operation's role (`spend_caps` in the
+//! config), failing with [`SpendCapExceeded`]. A request in flight can't be cut off, so an
+//! operation can overshoot its cap by at most the cost of its last call.
+//!
+//! Before that, once an operation has spent [`DOWNGRADE_AT`] of its cap, `routing` drops the
+//! role's route and sends the remaining requests to the built-in Speed model. Each downgrade
+//! is reported once, to the run's diagnostics (`record_downgrades`) and to the UI
+//! (`take_downgrades`).
+
+use cosmos_adapters::config::{Config, ModelRole};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+
+/// Share of its cap an operation can spend before it switches to the cheaper model
+pub const DOWNGRADE_AT: f64 = 0.8;
+
+tokio::task_local! {
+    static OPERATION: Arc<Operation>;
+    static RUN_DOWNGRADES: Arc<Mutex<Vec<ModelDowngrade>>>;
+}
+
+static ACTIVE_CAPS: RwLock<BTreeMap<ModelRole, f64>> = RwLock::new(BTreeMap::new());
+static NEXT_OPERATION: AtomicU64 = AtomicU64::new(1);
+/// Downgrades the UI hasn't shown yet
+static PENDING_DOWNGRADES: Mutex<Vec<ModelDowngrade>> = Mutex::new(Vec::new());
+
+/// One engine entry point's running total
+#[derive(Debug)]
+struct Operation {
+    id: String,
+    role: ModelRole,
+    spent_usd: Mutex<f64>,
+    downgraded: AtomicBool,
+}
+
+impl Operation {
+    /// Spent so far and the cap, when the operation's role has one
+    fn spend(&self) -> Option<(f64, f64)> {
+        let cap_usd = ACTIVE_CAPS
+            .read()
+            .ok()
+            .and_then(|caps| caps.get(&self.role).copied())?;
+        let spent_usd = self.spent_usd.lock().map(|spent| *spent).unwrap_or(0.0);
+        Some((spent_usd, cap_usd))
+    }
+}
+
+/// An operation close to its spend cap switched to a cheaper model for its remaining requests.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelDowngrade {
+    pub operation_id: String,
+    pub role: String,
+    /// Model the role would have used
+    pub from_model: String,
+    /// Model the remaining requests went to
+    pub to_model: String,
+    pub spent_usd: f64,
+    pub cap_usd: f64,
+}
+
+impl ModelDowngrade {
+    /// One-line explanation for a toast
+    pub fn message(&self) -> String {
+        format!(
+            "Near its ${:.2} {} spend cap, switched from {} to {}",
+            self.cap_usd, self.role, self.from_model, self.to_model
+        )
+    }
+}
+
+/// An operation hit its spend cap; no further requests were sent for it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpendCapExceeded {
+    /// Unique id of the stopped operation (`implement-12`)
+    pub operation_id: String,
+    /// Role name the cap is configured under
+    pub role: &'static str,
+    pub spent_usd: f64,
+    pub cap_usd: f64,
+}
+
+impl SpendCapExceeded {
+    /// Start of every message, for callers that only see the error as text
+    pub const MESSAGE_PREFIX: &'static str = "Spend cap reached";
+}
+
+impl std::fmt::Display for SpendCapExceeded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: this {} operation spent ${:.4} of its ${:.2} cap, so Cosmos stopped before \
+             another request (spend_caps.{} in {})",
+            Self::MESSAGE_PREFIX,
+            self.role,
+            self.spent_usd,
+            self.cap_usd,
+            self.role,
+            Config::config_location()
+        )
+    }
+}
+
+impl std::error::Error for SpendCapExceeded {}
+
+/// Validate the config's `spend_caps` and make them active for this process.
+pub fn install(config: &Config) -> anyhow::Result<()> {
+    if let Err(problems) = config.validate_spend_caps() {
+        return Err(anyhow::anyhow!(
+            "Invalid spend_caps in {}:\n  {}",
+            Config::config_location(),
+            problems.join("\n  ")
+        ));
+    }
+    let caps = ModelRole::ALL
+        .into_iter()
+        .filter_map(|role| config.spend_cap(role).map(|cap| (role, cap)))
+        .collect();
+    if let Ok(mut active) = ACTIVE_CAPS.write() {
+        *active = caps;
+    }
+    Ok(())
+}
+
+/// Run `fut` as a new operation of `role`, unless it already runs inside one.
+pub(crate) async fn scoped<F: Future>(role: ModelRole, fut: F) -> F::Output {
+    if OPERATION.try_with(|_| ()).is_ok() {
+        return fut.await;
+    }
+    let operation = Arc::new(Operation {
+        id: format!(
+            "{}-{}",
+            role.name(),
+            NEXT_OPERATION.fetch_add(1, Ordering::Relaxed)
+        ),
+        role,
+        spent_usd: Mutex::new(0.0),
+        downgraded: AtomicBool::new(false),
+    });
+    OPERATION.scope(operation, fut).await
+}
+
+/// Fail if the current operation has already reached its cap; called before each request.
+pub(crate) fn check() -> Result<(), SpendCapExceeded> {
+    let Ok(operation) = OPERATION.try_with(Arc::clone) else {
+        return Ok(());
+    };
+    let Some((spent_usd, cap_usd)) = operation.spend() else {
+        return Ok(());
+    };
+    if spent_usd >= cap_usd {
+        return Err(SpendCapExceeded {
+            operation_id: operation.id.clone(),
+            role: operation.role.name(),
+            spent_usd,
+            cap_usd,
+        });
+    }
+    Ok(())
+}
+
+/// Whether the current operation has spent enough of its cap to switch to the cheaper model.
+pub(crate) fn downgrade_due() -> bool {
+    OPERATION
+        .try_with(|operation| operation.spend())
+        .ok()
+        .flatten()
+        .is_some_and(|(spent_usd, cap_usd)| spent_usd >= cap_usd * DOWNGRADE_AT)
+}
+
+/// Report that the current operation now sends `to_model` instead of `from_model`.
+///
+/// Only the first downgrade of an operation is reported.
+pub(crate) fn note_downgrade(from_model: &str, to_model: &str) {
+    let Ok(operation) = OPERATION.try_with(Arc::clone) else {
+        return;
+    };
+    let Some((spent_usd, cap_usd)) = operation.spend() else {
+        return;
+    };
+    if operation.downgraded.swap(true, Ordering::Relaxed) {
+        return;
+    }
+    let downgrade = ModelDowngrade {
+        operation_id: operation.id.clone(),
+        role: operation.role.name().to_string(),
+        from_model: from_model.to_string(),
+        to_model: to_model.to_string(),
+        spent_usd,
+        cap_usd,
+    };
+    let _ = RUN_DOWNGRADES.try_with(|run| {
+        if let Ok(mut run) = run.lock() {
+            run.push(downgrade.clone());
+        }
+    });
+    if let Ok(mut pending) = PENDING_DOWNGRADES.lock() {
+        pending.push(downgrade);
+    }
+}
+
+/// Downgrades since the last call, oldest first, for the UI to announce.
+pub fn take_downgrades() -> Vec<ModelDowngrade> {
+    PENDING_DOWNGRADES
+        .lock()
+        .map(|mut pending| std::mem::take(&mut *pending))
+        .unwrap_or_default()
+}
+
+/// Run `fut`, returning its output and every downgrade made while it ran.
+pub(crate) async fn record_downgrades<F: Future>(fut: F) -> (F::Output, Vec<ModelDowngrade>) {
+    let downgrades = Arc::new(Mutex::new(Vec::new()));
+    let output = RUN_DOWNGRADES.scope(Arc::clone(&downgrades), fut).await;
+    let downgrades = downgrades.lock().map(|d| d.clone()).unwrap_or_default();
+    (output, downgrades)
+}
+
+/// Add a response's reported cost to the current operation.
+pub(crate) fn record(cost_usd: f64) {
+    if cost_usd <= 0.0 {
+        return;
+    }
+    let _ = OPERATION.try_with(|operation| {
+        if let Ok(mut spent) = operation.spent_usd.lock() {
+            *spent += cost_usd;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn operations_downgrade_near_and_stop_at_their_cap_with_nested_roles_sharing_the_total() {
+        let config: Config = serde_json::from_str(r#"{"spend_caps":{"implement":0.15}}"#).unwrap();
+        install(&config).unwrap();
+
+        // Outside any operation nothing is tracked or refused
+        record(1.0);
+        assert!(check().is_ok());
+
+        let stopped = scoped(ModelRole::Implement, async {
+            record(0.10);
+            assert!(check().is_ok());
+            // A nested role counts toward the same fix
+            scoped(ModelRole::Review, async { record(0.06) }).await;
+            check()
+        })
+        .await
+        .unwrap_err();
+        assert_eq!(stopped.role, "implement");
+        assert!(stopped.operation_id.starts_with("implement-"));
+        assert!((stopped.spent_usd - 0.16).abs() < 1e-9);
+        assert!(stopped
+            .to_string()
+            .starts_with(SpendCapExceeded::MESSAGE_PREFIX));
+
+        // Past DOWNGRADE_AT of the cap the operation switches model, reported once
+        let (downgraded, recorded) = record_downgrades(scoped(ModelRole::Implement, async {
+            record(0.10);
+            assert!(!downgrade_due());
+            record(0.03);
+            assert!(downgrade_due());
+            note_downgrade("big-model", "small-model");
+            note_downgrade("big-model", "small-model");
+            check().is_ok()
+        }))
+        .await;
+        assert!(downgraded);
+        assert_eq!(recorded.len(), 1);
+        assert_eq!(recorded[0].from_model, "big-model");
+        assert!(recorded[0]
+            .message()
+            .contains("switched from big-model to small-model"));
+        assert!(take_downgrades()
+            .iter()
+            .any(|d| d.operation_id == recorded[0].operation_id));
+
+        // Each operation starts from zero, and uncapped roles are never refused
+        scoped(ModelRole::Implement, async { assert!(check().is_ok()) }).await;
+        scoped(ModelRole::Ask, async {
+            record(5.0);
+            assert!(check().is_ok());
+            assert!(!downgrade_due());
+        })
+        .await;
+
+        install(&Config::default()).unwrap();
+    }
+}
@@ -0,0 +1,204 @@
+//! Public entry points for the structured-output parsers used by suggestion runs.
+//!
+//! Each [`ParseStrategy`] names the `parse_strategy` a run records in its diagnostics and
+//! parses model output exactly as that run does, including salvage of fenced, prose-wrapped,
+//! and trailing-comma JSON. The fixture helpers let downstream tools keep a golden corpus of
+//! real malformed outputs and assert each strategy still handles it.
+
+use super::analysis::parse_agentic_suggestions;
+use super::client::structured_candidates;
+use super::tools::parse_report_back_content;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+/// How a suggestion run turns model output into structured data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ParseStrategy {
+    /// Fast path: each churn subagent replies with an `agentic_suggestions` payload.
+    AgenticChurnSubagents,
+    /// Reviewed path: one agent finishes with `report_back` arguments.
+    SingleAgentDirectReport,
+}
+
+impl ParseStrategy {
+    pub const ALL: [ParseStrategy; 2] = [
+        ParseStrategy::AgenticChurnSubagents,
+        ParseStrategy::SingleAgentDirectReport,
+    ];
+
+    /// The `parse_strategy` value recorded in suggestion diagnostics.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ParseStrategy::AgenticChurnSubagents => "agentic_churn_subagents",
+            ParseStrategy::SingleAgentDirectReport => "single_agent_direct_report",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|strategy| strategy.as_str() == name.trim())
+    }
+}
+
+/// Parse a model reply into the first JSON value that survives salvage, without applying any
+/// response schema.
+pub fn parse_structured_json(content: &str) -> anyhow::Result<Value> {
+    structured_candidates(content)
+        .iter()
+        .find_map(|candidate| serde_json::from_str::<Value>(candidate).ok())
+        .ok_or_else(|| anyhow::anyhow!("No JSON value found in model output"))
+}
+
+/// Parse model output the way `strategy` does during a run, returning the normalized payload.
+pub fn parse_strategy_output(strategy: ParseStrategy, content: &str) -> anyhow::Result<Value> {
+    match strategy {
+        ParseStrategy::AgenticChurnSubagents => parse_agentic_suggestions(content),
+        ParseStrategy::SingleAgentDirectReport => {
+            let payload = parse_report_back_content(content).map_err(|err| anyhow::anyhow!(err))?;
+            Ok(serde_json::to_value(payload)?)
+        }
+    }
+}
+
+/// One golden case: raw model output and the subset of the parsed payload it must produce.
+#[derive(Debug, Clone)]
+pub struct ParseFixture {
+    pub name: String,
+    pub strategy: ParseStrategy,
+    pub input: String,
+    pub expected: Value,
+}
+
+/// Load fixtures laid out as `<dir>/<parse_strategy>/<case>.txt` with a sibling
+/// `<case>.expected.json`. Unknown strategy directories are an error.
+pub fn load_parse_fixtures(dir: &Path) -> anyhow::Result<Vec<ParseFixture>> {
+    let mut fixtures = Vec::new();
+    for strategy_dir in sorted_entries(dir)? {
+        if !strategy_dir.is_dir() {
+            continue;
+        }
+        let dir_name = strategy_dir
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or_default();
+        let strategy = ParseStrategy::from_name(dir_name)
+            .ok_or_else(|| anyhow::anyhow!("Unknown parse strategy directory '{}'", dir_name))?;
+        for input_path in sorted_entries(&strategy_dir)? {
+            if input_path.extension().and_then(|ext| ext.to_str()) != Some("txt") {
+                continue;
+            }
+            let name = input_path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .unwrap_or_default()
+                .to_string();
+            let expected_path = strategy_dir.join(format!("{}.expected.json", name));
+            let expected = std::fs::read_to_string(&expected_path)
+                .map_err(|err| anyhow::anyhow!("Missing {}: {}", expected_path.display(), err))?;
+            fixtures.push(ParseFixture {
+                name,
+                strategy,
+                input: std::fs::read_to_string(&input_path)?,
+                expected: serde_json::from_str(&expected).map_err(|err| {
+                    anyhow::anyhow!("Invalid {}: {}", expected_path.display(), err)
+                })?,
+            });
+        }
+    }
+    Ok(fixtures)
+}
+
+/// Check one fixture. Every key in `expected` must match the parsed payload; arrays must have
+/// the same length and match element by element.
+pub fn check_parse_fixture(fixture: &ParseFixture) -> Result<(), String> {
+    let label = format!("{}/{}", fixture.strategy.as_str(), fixture.name);
+    let parsed = parse_strategy_output(fixture.strategy, &fixture.input)
+        .map_err(|err| format!("{}: failed to parse: {}", label, err))?;
+    match_subset(&fixture.expected, &parsed, "$").map_err(|err| format!("{}: {}", label, err))
+}
+
+fn match_subset(expected: &Value, actual: &Value, path: &str) -> Result<(), String> {
+    match (expected, actual) {
+        (Value::Object(expected), Value::Object(actual)) => {
+            for (key, expected_value) in expected {
+                let child = format!("{}.{}", path, key);
+                let actual_value = actual
+                    .get(key)
+                    .ok_or_else(|| format!("{} is missing", child))?;
+                match_subset(expected_value, actual_value, &child)?;
+            }
+            Ok(())
+        }
+        (Value::Array(expected), Value::Array(actual)) => {
+            if expected.len() != actual.len() {
+                return Err(format!(
+                    "{} has {} items, expected {}",
+                    path,
+                    actual.len(),
+                    expected.len()
+                ));
+            }
+            for (idx, (expected_item, actual_item)) in expected.iter().zip(actual).enumerate() {
+                match_subset(expected_item, actual_item, &format!("{}[{}]", path, idx))?;
+            }
+            Ok(())
+        }
+        _ if expected == actual => Ok(()),
+        _ => Err(format!("{} is {}, expected {}", path, actual, expected)),
+    }
+}
+
+fn sorted_entries(dir: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    let mut entries = std::fs::read_dir(dir)
+        .map_err(|err| anyhow::anyhow!("Cannot read {}: {}", dir.display(), err))?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    entries.sort();
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture_dir() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/parse")
+    }
+
+    #[test]
+    fn test_golden_fixtures_cover_every_parse_strategy() {
+        let fixtures = load_parse_fixtures(&fixture_dir()).unwrap();
+        for strategy in ParseStrategy::ALL {
+            assert!(
+                fixtures.iter().any(|fixture| fixture.strategy == strategy),
+                "no fixtures for {}",
+                strategy.as_str()
+            );
+        }
+
+        let failures = fixtures
+            .iter()
+            .filter_map(|fixture| check_parse_fixture(fixture).err())
+            .collect::<Vec<_>>();
+        assert!(failures.is_empty(), "{}", failures.join("\n"));
+    }
+
+    #[test]
+    fn test_check_parse_fixture_reports_mismatches() {
+        let fixture = ParseFixture {
+            name: "wrong_line".to_string(),
+            strategy: ParseStrategy::AgenticChurnSubagents,
+            input: r#"{"suggestions":[{"file":"src/a.rs","line":3,"summary":"x"}]}"#.to_string(),
+            expected: serde_json::json!({"suggestions": [{"line": 4}]}),
+        };
+        let err = check_parse_fixture(&fixture).unwrap_err();
+        assert!(err.contains("$.suggestions[0].line"), "{}", err);
+    }
+
+    #[test]
+    fn test_parse_structured_json_strips_trailing_commas_outside_strings() {
+        let parsed = parse_structured_json("{\"a\": [1, 2,], \"b\": \"x,]\",}").unwrap();
+        assert_eq!(parsed, serde_json::json!({"a": [1, 2], "b": "x,]"}));
+    }
+}
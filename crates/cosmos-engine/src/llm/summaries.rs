@@ -0,0 +1,266 @@
+//! Auditing and regenerating cached directory summaries.
+//!
+//! Rollups are synthesized from the index, so they can read as a file list rather than a
+//! description, and a cached one can drift from the code. The audit asks the Speed model to
+//! score a summary against the directory's entry files; regeneration has it rewrite the purpose,
+//! one directory at a time or for many at once as a batch job (see `batch`) when waiting for
+//! the cheaper batch rate is fine.
+
+use super::batch::{self, BatchStatus};
+use super::client::{
+    call_llm_structured, parse_structured_completion, structured_request_body, StructuredResponse,
+};
+use super::models::{merge_usage, Model, Usage};
+use super::prompts::{DIRECTORY_SUMMARY_SYSTEM, SUMMARY_AUDIT_SYSTEM};
+use super::redaction::redact_secrets;
+use super::routing;
+use cosmos_adapters::config::ModelRole;
+use cosmos_adapters::util::truncate;
+use cosmos_core::index::directory::DirectorySummary;
+use cosmos_core::index::CodebaseIndex;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// Scores below this flag a summary for regeneration.
+pub const SUMMARY_AUDIT_PASS_SCORE: u8 = 6;
+/// Lines read from the top of each entry file.
+const EXCERPT_LINES: usize = 60;
+/// Total excerpt budget per directory.
+const MAX_EXCERPT_CHARS: usize = 8_000;
+const MAX_PURPOSE_CHARS: usize = 240;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SummaryScore {
+    /// 0-10, higher is better
+    pub score: u8,
+    /// What the summary gets wrong or leaves out (empty when it's fine)
+    pub issue: String,
+}
+
+impl SummaryScore {
+    pub fn passed(&self) -> bool {
+        self.score >= SUMMARY_AUDIT_PASS_SCORE
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SummaryScoreResponse {
+    score: f64,
+    #[serde(default)]
+    issue: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct DirectoryPurposeResponse {
+    purpose: String,
+}
+
+fn summary_score_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": "object",
+        "additionalProperties": false,
+        "properties": {
+            "score": { "type": "number" },
+            "issue": { "type": "string" }
+        },
+        "required": ["score", "issue"]
+    })
+}
+
+fn directory_purpose_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": "object",
+        "additionalProperties": false,
+        "properties": {
+            "purpose": { "type": "string" }
+        },
+        "required": ["purpose"]
+    })
+}
+
+/// Score a cached summary against the current contents of the directory's entry files.
+pub async fn score_directory_summary(
+    index: &CodebaseIndex,
+    summary: &DirectorySummary,
+) -> anyhow::Result<(SummaryScore, Option<Usage>)> {
+    routing::with_role(ModelRole::Summarize, async {
+        let user = format!(
+            "SUMMARY:\n{}\n\nFILES:\n{}",
+            summary.to_prompt_line(),
+            directory_excerpt(index, summary)
+        );
+        let StructuredResponse {
+            data: parsed,
+            usage,
+            ..
+        } = call_llm_structured::<SummaryScoreResponse>(
+            SUMMARY_AUDIT_SYSTEM,
+            &user,
+            Model::Speed,
+            "summary_audit",
+            summary_score_schema(),
+        )
+        .await?;
+        let score = SummaryScore {
+            score: parsed.score.round().clamp(0.0, 10.0) as u8,
+            issue: parsed.issue.trim().to_string(),
+        };
+        Ok((score, usage))
+    })
+    .await
+}
+
+/// Re-synthesize the rollup for `dir` and have the model write its purpose.
+pub async fn regenerate_directory_summary(
+    index: &CodebaseIndex,
+    dir: &Path,
+) -> anyhow::Result<(DirectorySummary, Option<Usage>)> {
+    routing::with_role(ModelRole::Summarize, async {
+        let (summary, user) = directory_summary_prompt(index, dir)?;
+        let StructuredResponse {
+            data: parsed,
+            usage,
+            ..
+        } = call_llm_structured::<DirectoryPurposeResponse>(
+            DIRECTORY_SUMMARY_SYSTEM,
+            &user,
+            Model::Speed,
+            "directory_summary",
+            directory_purpose_schema(),
+        )
+        .await?;
+        Ok((with_purpose(summary, parsed)?, usage))
+    })
+    .await
+}
+
+/// Submit purpose rewrites for every directory in `dirs` as one batch job.
+///
+/// Returns the job id for [`summary_batch_status`]; fails with `batch::BatchUnsupported` when
+/// the backend has no batch API.
+pub async fn submit_summary_batch(
+    index: &CodebaseIndex,
+    dirs: &[PathBuf],
+) -> anyhow::Result<String> {
+    routing::with_role(ModelRole::Summarize, async {
+        let mut requests = Vec::new();
+        for dir in dirs {
+            let Ok((_, user)) = directory_summary_prompt(index, dir) else {
+                continue;
+            };
+            let body = structured_request_body(
+                DIRECTORY_SUMMARY_SYSTEM,
+                &user,
+                Model::Speed,
+                "directory_summary",
+                directory_purpose_schema(),
+            )?;
+            requests.push((dir.to_string_lossy().into_owned(), body));
+        }
+        batch::submit(&requests).await
+    })
+    .await
+}
+
+/// Where the summary batch job `batch_id` stands.
+pub async fn summary_batch_status(batch_id: &str) -> anyhow::Result<BatchStatus> {
+    batch::status(batch_id).await
+}
+
+/// Directory summaries from a completed batch job, with their combined usage.
+///
+/// Each summary is re-synthesized from the current index with the model's purpose, so its
+/// fingerprint is today's; callers compare it with the one recorded at submission to drop
+/// directories that changed while the job ran.
+pub async fn collect_summary_batch(
+    index: &CodebaseIndex,
+    output_file_id: &str,
+) -> anyhow::Result<(
+    Vec<(PathBuf, anyhow::Result<DirectorySummary>)>,
+    Option<Usage>,
+)> {
+    routing::with_role(ModelRole::Summarize, async {
+        let mut collected = Vec::new();
+        let mut total_usage = None;
+        for (custom_id, result) in batch::results(output_file_id).await? {
+            let dir = PathBuf::from(custom_id);
+            let summary = match result {
+                Ok(text) => parse_structured_completion::<DirectoryPurposeResponse>(
+                    &text,
+                    Model::Speed,
+                    "directory_summary",
+                )
+                .and_then(|StructuredResponse { data, usage, .. }| {
+                    total_usage = merge_usage(total_usage.take(), usage);
+                    let (summary, _) = directory_summary_prompt(index, &dir)?;
+                    with_purpose(summary, data)
+                }),
+                Err(error) => Err(anyhow::anyhow!(error)),
+            };
+            collected.push((dir, summary));
+        }
+        Ok((collected, total_usage))
+    })
+    .await
+}
+
+/// The synthesized rollup for `dir` and the prompt asking the model for its purpose.
+fn directory_summary_prompt(
+    index: &CodebaseIndex,
+    dir: &Path,
+) -> anyhow::Result<(DirectorySummary, String)> {
+    let summary = DirectorySummary::synthesize(index, dir)
+        .ok_or_else(|| anyhow::anyhow!("{} has no indexed files", dir.display()))?;
+    let user = format!(
+        "DIRECTORY: {}\nOUTLINE: {}\n\nFILES:\n{}",
+        dir.display(),
+        summary.to_prompt_line(),
+        directory_excerpt(index, &summary)
+    );
+    Ok((summary, user))
+}
+
+fn with_purpose(
+    mut summary: DirectorySummary,
+    parsed: DirectoryPurposeResponse,
+) -> anyhow::Result<DirectorySummary> {
+    let purpose = parsed
+        .purpose
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    if purpose.is_empty() {
+        anyhow::bail!("Model returned an empty summary");
+    }
+    summary.purpose = truncate(&purpose, MAX_PURPOSE_CHARS);
+    summary.ai_written = true;
+    Ok(summary)
+}
+
+/// The top of each entry file (or the first files, when there are no entry files), with
+/// secrets masked.
+fn directory_excerpt(index: &CodebaseIndex, summary: &DirectorySummary) -> String {
+    let mut paths = summary.entry_points.clone();
+    if paths.is_empty() {
+        let mut children: Vec<_> = index
+            .files
+            .keys()
+            .filter(|path| path.starts_with(&summary.path))
+            .cloned()
+            .collect();
+        children.sort();
+        paths = children.into_iter().take(3).collect();
+    }
+    let mut out = String::new();
+    for path in paths {
+        let Ok(content) = std::fs::read_to_string(index.root.join(&path)) else {
+            continue;
+        };
+        let head: Vec<&str> = content.lines().take(EXCERPT_LINES).collect();
+        out.push_str(&format!("--- {}\n{}\n", path.display(), head.join("\n")));
+        if out.len() >= MAX_EXCERPT_CHARS {
+            break;
+        }
+    }
+    redact_secrets(&truncate(&out, MAX_EXCERPT_CHARS)).0
+}
@@ -0,0 +1,189 @@
+//! Token counting and per-call context budgets.
+//!
+//! `count_tokens` splits text the way cl100k-style BPE tokenizers pre-tokenize it (contractions,
+//! letter runs with one leading symbol, digit groups of up to three, punctuation runs, and
+//! whitespace) and estimates how many merged tokens each piece becomes. It runs offline and is
+//! an estimate, not an exact count, but close enough to tell whether a prompt fits. Every request sent inside `record_budgets` is logged as a
+//! `ContextBudget`, so diagnostics show how much of the window each call used.
+
+use super::models::MODEL_CONTEXT_WINDOW;
+use super::routing;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// Letters BPE merges into one token, on average, in identifiers and words.
+const LETTERS_PER_TOKEN: usize = 6;
+/// Punctuation characters per token (operators like `->`, `::`, `();` are merged).
+const SYMBOLS_PER_TOKEN: usize = 2;
+/// Whitespace characters per token (indentation runs are merged).
+const WHITESPACE_PER_TOKEN: usize = 8;
+/// Role and separator tokens the chat format adds around each message.
+const MESSAGE_OVERHEAD_TOKENS: u32 = 4;
+
+tokio::task_local! {
+    static RUN_BUDGETS: Arc<Mutex<Vec<ContextBudget>>>;
+}
+
+/// How much of the context window one request used.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContextBudget {
+    /// Routing role the request ran under (`suggest`, `implement`, ...), or `unrouted`
+    pub label: String,
+    pub model: String,
+    /// Estimated tokens across messages and tool definitions
+    pub prompt_tokens: u32,
+    /// Completion tokens the request reserved (`max_completion_tokens`)
+    pub completion_tokens: u32,
+    pub context_window: u32,
+}
+
+impl ContextBudget {
+    /// Prompt plus reserved completion tokens.
+    pub fn total_tokens(&self) -> u32 {
+        self.prompt_tokens.saturating_add(self.completion_tokens)
+    }
+
+    /// Share of the window the prompt and completion reserve take (may exceed 1.0).
+    pub fn fraction(&self) -> f64 {
+        if self.context_window == 0 {
+            return 0.0;
+        }
+        self.total_tokens() as f64 / self.context_window as f64
+    }
+
+    /// True when the provider would have to reject or truncate the request.
+    pub fn overflows(&self) -> bool {
+        self.total_tokens() > self.context_window
+    }
+}
+
+fn pretokenizer() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        // cl100k's split pattern without the whitespace lookahead the regex crate lacks.
+        Regex::new(
+            r"(?i:'s|'t|'re|'ve|'m|'ll|'d)|[^\r\n\p{L}\p{N}]?\p{L}+|\p{N}{1,3}| ?[^\s\p{L}\p{N}]+[\r\n]*|\s*[\r\n]+|\s+",
+        )
+        .expect("valid pretokenizer pattern")
+    })
+}
+
+/// Estimated BPE tokens in `text`.
+pub fn count_tokens(text: &str) -> u32 {
+    let tokens: usize = pretokenizer()
+        .find_iter(text)
+        .map(|piece| piece_tokens(piece.as_str()))
+        .sum();
+    tokens.min(u32::MAX as usize) as u32
+}
+
+fn piece_tokens(piece: &str) -> usize {
+    let letters = piece.chars().filter(|c| c.is_alphabetic()).count();
+    if letters > 0 {
+        return letters.div_ceil(LETTERS_PER_TOKEN);
+    }
+    if piece.chars().any(|c| c.is_numeric()) {
+        return 1;
+    }
+    let symbols = piece.chars().filter(|c| !c.is_whitespace()).count();
+    if symbols > 0 {
+        return symbols.div_ceil(SYMBOLS_PER_TOKEN);
+    }
+    piece.chars().count().div_ceil(WHITESPACE_PER_TOKEN)
+}
+
+/// Budget for a serialized chat request: its messages (content and tool calls), tool
+/// definitions, and reserved completion tokens.
+pub(crate) fn request_budget(request: &serde_json::Value) -> ContextBudget {
+    let mut prompt_tokens = 0u32;
+    for message in request["messages"].as_array().into_iter().flatten() {
+        prompt_tokens = prompt_tokens.saturating_add(MESSAGE_OVERHEAD_TOKENS);
+        if let Some(content) = message["content"].as_str() {
+            prompt_tokens = prompt_tokens.saturating_add(count_tokens(content));
+        }
+        if !message["tool_calls"].is_null() {
+            prompt_tokens =
+                prompt_tokens.saturating_add(count_tokens(&message["tool_calls"].to_string()));
+        }
+    }
+    if !request["tools"].is_null() {
+        prompt_tokens = prompt_tokens.saturating_add(count_tokens(&request["tools"].to_string()));
+    }
+    ContextBudget {
+        label: routing::current_role()
+            .map(|role| role.name().to_string())
+            .unwrap_or_else(|| "unrouted".to_string()),
+        model: request["model"].as_str().unwrap_or("unknown").to_string(),
+        prompt_tokens,
+        completion_tokens: request["max_completion_tokens"]
+            .as_u64()
+            .map(|n| n.min(u32::MAX as u64) as u32)
+            .unwrap_or(0),
+        context_window: routing::routed_context_window(MODEL_CONTEXT_WINDOW),
+    }
+}
+
+/// Log the budget of a request about to be sent to the current run (if any).
+pub(crate) fn note_request<T: Serialize>(request: &T) {
+    let _ = RUN_BUDGETS.try_with(|budgets| {
+        let Ok(value) = serde_json::to_value(request) else {
+            return;
+        };
+        if let Ok(mut budgets) = budgets.lock() {
+            budgets.push(request_budget(&value));
+        }
+    });
+}
+
+/// Run `fut`, returning its output and the budget of every request sent while it ran.
+/// Nested calls record into the innermost run only.
+pub(crate) async fn record_budgets<F: Future>(fut: F) -> (F::Output, Vec<ContextBudget>) {
+    let budgets = Arc::new(Mutex::new(Vec::new()));
+    let output = RUN_BUDGETS.scope(Arc::clone(&budgets), fut).await;
+    let budgets = budgets.lock().map(|b| b.clone()).unwrap_or_default();
+    (output, budgets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_tokens_like_a_bpe_pretokenizer() {
+        assert_eq!(count_tokens(""), 0);
+        assert_eq!(count_tokens("hello world"), 2);
+        assert_eq!(count_tokens("fn main() {}"), 4);
+        assert_eq!(count_tokens("It's 2026"), 5);
+        // Long identifiers split into several merges.
+        assert_eq!(count_tokens("CodebaseIndex"), 3);
+
+        let budget = request_budget(&serde_json::json!({
+            "model": "zai-glm-4.7",
+            "max_completion_tokens": 1000,
+            "messages": [
+                { "role": "system", "content": "hello world" },
+                { "role": "user", "content": "fn main() {}" }
+            ]
+        }));
+        assert_eq!(budget.label, "unrouted");
+        assert_eq!(budget.prompt_tokens, 2 * MESSAGE_OVERHEAD_TOKENS + 6);
+        assert_eq!(budget.total_tokens(), budget.prompt_tokens + 1000);
+        assert!(!budget.overflows());
+        assert!(budget.fraction() < 0.01);
+    }
+
+    #[tokio::test]
+    async fn records_budgets_for_requests_in_scope() {
+        let request = serde_json::json!({ "model": "m", "messages": [] });
+        note_request(&request);
+        let ((), budgets) = record_budgets(async {
+            note_request(&request);
+            note_request(&request);
+        })
+        .await;
+        assert_eq!(budgets.len(), 2);
+        assert_eq!(budgets[0].model, "m");
+    }
+}
@@ -3,14 +3,17 @@
 //! Philosophy: Support top-down exploration that's naturally token-efficient.
 //! Specialized tools enforce efficient patterns; shell is fallback for edge cases.
 
+use super::client::structured_candidates;
 use cosmos_adapters::util::{
     resolve_repo_path_allow_new, run_command_with_timeout, CommandRunResult,
 };
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
 use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::Duration;
 
 const RELACE_PATH_GUIDANCE: &str =
@@ -59,10 +62,10 @@ pub struct ToolDefinition {
 
 #[derive(Debug, Clone, Serialize)]
 pub struct FunctionDefinition {
-    pub name: &'static str,
+    pub name: Cow<'static, str>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub strict: Option<bool>,
-    pub description: &'static str,
+    pub description: Cow<'static, str>,
     pub parameters: serde_json::Value,
 }
 
@@ -143,6 +146,22 @@ struct ReportBackFileEntryWire {
     ranges: Vec<(i64, i64)>,
 }
 
+/// `parse_report_back_payload`, retrying salvaged candidates (fences, surrounding prose,
+/// trailing commas) when the arguments are not valid JSON. Validation errors are final.
+pub fn parse_report_back_content(content: &str) -> Result<ReportBackPayload, String> {
+    let strict_err = match parse_report_back_payload(content) {
+        Ok(payload) => return Ok(payload),
+        Err(err) => err,
+    };
+    if !strict_err.starts_with("Invalid report_back JSON") {
+        return Err(strict_err);
+    }
+    structured_candidates(content)
+        .iter()
+        .find_map(|candidate| parse_report_back_payload(candidate).ok())
+        .ok_or(strict_err)
+}
+
 pub fn parse_report_back_payload(args_json: &str) -> Result<ReportBackPayload, String> {
     let parsed: ReportBackPayloadWire = serde_json::from_str(args_json)
         .map_err(|err| format!("Invalid report_back JSON: {err}"))?;
@@ -161,10 +180,7 @@ pub fn parse_report_back_payload(args_json: &str) -> Result<ReportBackPayload, S
         ReportBackFilesWire::List(entries) => {
             let mut files: HashMap<String, Vec<(i64, i64)>> = HashMap::new();
             for entry in entries {
-                files
-                    .entry(entry.path)
-                    .or_default()
-                    .extend(entry.ranges.into_iter());
+                files.entry(entry.path).or_default().extend(entry.ranges);
             }
             files
         }
@@ -197,6 +213,23 @@ pub fn parse_report_back_payload(args_json: &str) -> Result<ReportBackPayload, S
     Ok(parsed)
 }
 
+/// Names `execute_tool` handles itself; custom tools may not reuse them.
+pub(crate) const BUILTIN_TOOL_NAMES: &[&str] = &[
+    "tree",
+    "head",
+    "search",
+    "read_lines",
+    "read_range",
+    "shell",
+    "view_file",
+    "open_file",
+    "view_directory",
+    "print_tree",
+    "grep_search",
+    "bash",
+    "report_back",
+];
+
 /// Get all available tool definitions for top-down exploration
 pub fn get_tool_definitions() -> Vec<ToolDefinition> {
     vec![
@@ -204,9 +237,9 @@ pub fn get_tool_definitions() -> Vec<ToolDefinition> {
         ToolDefinition {
             tool_type: "function",
             function: FunctionDefinition {
-                name: "tree",
+                name: Cow::Borrowed("tree"),
                 strict: None,
-                description: "List directory structure. Start here to understand the codebase layout.",
+                description: Cow::Borrowed("List directory structure. Start here to understand the codebase layout."),
                 parameters: serde_json::json!({
                     "type": "object",
                     "properties": {
@@ -226,9 +259,9 @@ pub fn get_tool_definitions() -> Vec<ToolDefinition> {
         ToolDefinition {
             tool_type: "function",
             function: FunctionDefinition {
-                name: "head",
+                name: Cow::Borrowed("head"),
                 strict: None,
-                description: "Read first N lines of a file. Use to see imports, exports, and structure before diving deeper.",
+                description: Cow::Borrowed("Read first N lines of a file. Use to see imports, exports, and structure before diving deeper."),
                 parameters: serde_json::json!({
                     "type": "object",
                     "properties": {
@@ -249,13 +282,13 @@ pub fn get_tool_definitions() -> Vec<ToolDefinition> {
         ToolDefinition {
             tool_type: "function",
             function: FunctionDefinition {
-                name: "search",
+                name: Cow::Borrowed("search"),
                 strict: None,
-                description: "Search for pattern in files. Returns matches with line numbers. Use to find where to look before reading.",
+                description: Cow::Borrowed("Regex search across the repo. Returns matching lines with line numbers and context (capped per call, and searches are limited per run). Use to find where to look before reading."),
                 parameters: serde_json::json!({
                     "type": "object",
                     "properties": {
-                        "pattern": {
+                        "regex": {
                             "type": "string",
                             "description": "Regex pattern to search for"
                         },
@@ -268,21 +301,21 @@ pub fn get_tool_definitions() -> Vec<ToolDefinition> {
                             "description": "Lines of context around matches (default: 2)"
                         }
                     },
-                    "required": ["pattern"]
+                    "required": ["regex"]
                 }),
             },
         },
-        // 4. READ_RANGE - Drill into specific sections
+        // 4. READ_LINES - Drill into specific sections
         ToolDefinition {
             tool_type: "function",
             function: FunctionDefinition {
-                name: "read_range",
+                name: Cow::Borrowed("read_lines"),
                 strict: None,
-                description: "Read specific line range from a file. Use after search to examine specific sections.",
+                description: Cow::Borrowed("Read a line range from a file, at most 200 lines per call (lines read are limited per run). Use after search to examine specific sections instead of reading whole files."),
                 parameters: serde_json::json!({
                     "type": "object",
                     "properties": {
-                        "path": {
+                        "file": {
                             "type": "string",
                             "description": "File path to read"
                         },
@@ -295,7 +328,7 @@ pub fn get_tool_definitions() -> Vec<ToolDefinition> {
                             "description": "Ending line number (inclusive)"
                         }
                     },
-                    "required": ["path", "start", "end"]
+                    "required": ["file", "start", "end"]
                 }),
             },
         },
@@ -303,9 +336,9 @@ pub fn get_tool_definitions() -> Vec<ToolDefinition> {
         ToolDefinition {
             tool_type: "function",
             function: FunctionDefinition {
-                name: "shell",
+                name: Cow::Borrowed("shell"),
                 strict: None,
-                description: "Execute shell command. Use only when specialized tools don't fit. Output truncated at 4KB.",
+                description: Cow::Borrowed("Execute shell command. Use only when specialized tools don't fit. Output truncated at 4KB."),
                 parameters: serde_json::json!({
                     "type": "object",
                     "properties": {
@@ -327,9 +360,9 @@ pub fn get_relace_search_tool_definitions() -> Vec<ToolDefinition> {
         ToolDefinition {
             tool_type: "function",
             function: FunctionDefinition {
-                name: "view_file",
+                name: Cow::Borrowed("view_file"),
                 strict: None,
-                description: "Tool for viewing/exploring the contents of existing files\n\nLine numbers are included in the output, indexing at 1. If the output does not include the end of the file, it will be noted after the final output line.\n\nExample (viewing the first 2 lines of a file):\n1   def my_function():\n2       print(\"Hello, World!\")\n... rest of file truncated ...",
+                description: Cow::Borrowed("Tool for viewing/exploring the contents of existing files\n\nLine numbers are included in the output, indexing at 1. If the output does not include the end of the file, it will be noted after the final output line.\n\nExample (viewing the first 2 lines of a file):\n1   def my_function():\n2       print(\"Hello, World!\")\n... rest of file truncated ..."),
                 parameters: serde_json::json!({
                     "type": "object",
                     "required": [],
@@ -367,9 +400,9 @@ pub fn get_relace_search_tool_definitions() -> Vec<ToolDefinition> {
         ToolDefinition {
             tool_type: "function",
             function: FunctionDefinition {
-                name: "view_directory",
+                name: Cow::Borrowed("view_directory"),
                 strict: None,
-                description: "Tool for viewing the contents of a directory.\n\n* Lists contents recursively, relative to the input directory\n* Directories are suffixed with a trailing slash '/'\n* Depth might be limited by the tool implementation\n* Output is limited to the first 250 items\n\nExample output:\nfile1.txt\nfile2.txt\nsubdir1/\nsubdir1/file3.txt",
+                description: Cow::Borrowed("Tool for viewing the contents of a directory.\n\n* Lists contents recursively, relative to the input directory\n* Directories are suffixed with a trailing slash '/'\n* Depth might be limited by the tool implementation\n* Output is limited to the first 250 items\n\nExample output:\nfile1.txt\nfile2.txt\nsubdir1/\nsubdir1/file3.txt"),
                 parameters: serde_json::json!({
                     "type": "object",
                     "required": [],
@@ -403,9 +436,9 @@ pub fn get_relace_search_tool_definitions() -> Vec<ToolDefinition> {
         ToolDefinition {
             tool_type: "function",
             function: FunctionDefinition {
-                name: "grep_search",
+                name: Cow::Borrowed("grep_search"),
                 strict: None,
-                description: "Fast text-based regex search that finds exact pattern matches within files or directories, utilizing the ripgrep command for efficient searching. Results will be formatted in the style of ripgrep and can be configured to include line numbers and content. To avoid overwhelming output, the results are capped at 50 matches. Use the include or exclude patterns to filter the search scope by file type or specific paths. This is best for finding exact text matches or regex patterns.",
+                description: Cow::Borrowed("Fast text-based regex search that finds exact pattern matches within files or directories, utilizing the ripgrep command for efficient searching. Results will be formatted in the style of ripgrep and can be configured to include line numbers and content. To avoid overwhelming output, the results are capped at 50 matches. Use the include or exclude patterns to filter the search scope by file type or specific paths. This is best for finding exact text matches or regex patterns."),
                 parameters: serde_json::json!({
                     "type": "object",
                     "required": ["query"],
@@ -439,9 +472,9 @@ pub fn get_relace_search_tool_definitions() -> Vec<ToolDefinition> {
         ToolDefinition {
             tool_type: "function",
             function: FunctionDefinition {
-                name: "search",
+                name: Cow::Borrowed("search"),
                 strict: None,
-                description: "Compatibility alias for grep_search. Supports either `query` or `pattern` and performs fast regex search within the repository.",
+                description: Cow::Borrowed("Compatibility alias for grep_search. Supports either `query` or `pattern` and performs fast regex search within the repository."),
                 parameters: serde_json::json!({
                     "type": "object",
                     "properties": {
@@ -478,10 +511,10 @@ pub fn get_relace_search_tool_definitions() -> Vec<ToolDefinition> {
         ToolDefinition {
             tool_type: "function",
             function: FunctionDefinition {
-                name: "repo_browser.search",
+                name: Cow::Borrowed("repo_browser.search"),
                 strict: None,
                 description:
-                    "Compatibility alias for search/grep_search used by some providers.",
+                    Cow::Borrowed("Compatibility alias for search/grep_search used by some providers."),
                 parameters: serde_json::json!({
                     "type": "object",
                     "properties": {
@@ -518,9 +551,9 @@ pub fn get_relace_search_tool_definitions() -> Vec<ToolDefinition> {
         ToolDefinition {
             tool_type: "function",
             function: FunctionDefinition {
-                name: "open_file",
+                name: Cow::Borrowed("open_file"),
                 strict: None,
-                description: "Compatibility alias for view_file. Opens a file at an optional line range.",
+                description: Cow::Borrowed("Compatibility alias for view_file. Opens a file at an optional line range."),
                 parameters: serde_json::json!({
                     "type": "object",
                     "properties": {
@@ -549,9 +582,9 @@ pub fn get_relace_search_tool_definitions() -> Vec<ToolDefinition> {
         ToolDefinition {
             tool_type: "function",
             function: FunctionDefinition {
-                name: "repo_browser.open_file",
+                name: Cow::Borrowed("repo_browser.open_file"),
                 strict: None,
-                description: "Compatibility alias for view_file used by some providers.",
+                description: Cow::Borrowed("Compatibility alias for view_file used by some providers."),
                 parameters: serde_json::json!({
                     "type": "object",
                     "properties": {
@@ -580,9 +613,9 @@ pub fn get_relace_search_tool_definitions() -> Vec<ToolDefinition> {
         ToolDefinition {
             tool_type: "function",
             function: FunctionDefinition {
-                name: "repo_browser.view_file",
+                name: Cow::Borrowed("repo_browser.view_file"),
                 strict: None,
-                description: "Compatibility alias for view_file used by some providers.",
+                description: Cow::Borrowed("Compatibility alias for view_file used by some providers."),
                 parameters: serde_json::json!({
                     "type": "object",
                     "properties": {
@@ -611,9 +644,9 @@ pub fn get_relace_search_tool_definitions() -> Vec<ToolDefinition> {
         ToolDefinition {
             tool_type: "function",
             function: FunctionDefinition {
-                name: "print_tree",
+                name: Cow::Borrowed("print_tree"),
                 strict: None,
-                description: "Compatibility alias for view_directory.",
+                description: Cow::Borrowed("Compatibility alias for view_directory."),
                 parameters: serde_json::json!({
                     "type": "object",
                     "properties": {
@@ -633,9 +666,9 @@ pub fn get_relace_search_tool_definitions() -> Vec<ToolDefinition> {
         ToolDefinition {
             tool_type: "function",
             function: FunctionDefinition {
-                name: "repo_browser.view_directory",
+                name: Cow::Borrowed("repo_browser.view_directory"),
                 strict: None,
-                description: "Compatibility alias for view_directory used by some providers.",
+                description: Cow::Borrowed("Compatibility alias for view_directory used by some providers."),
                 parameters: serde_json::json!({
                     "type": "object",
                     "properties": {
@@ -655,9 +688,9 @@ pub fn get_relace_search_tool_definitions() -> Vec<ToolDefinition> {
         ToolDefinition {
             tool_type: "function",
             function: FunctionDefinition {
-                name: "repo_browser.print_tree",
+                name: Cow::Borrowed("repo_browser.print_tree"),
                 strict: None,
-                description: "Compatibility alias for view_directory/print_tree used by some providers.",
+                description: Cow::Borrowed("Compatibility alias for view_directory/print_tree used by some providers."),
                 parameters: serde_json::json!({
                     "type": "object",
                     "properties": {
@@ -677,9 +710,9 @@ pub fn get_relace_search_tool_definitions() -> Vec<ToolDefinition> {
         ToolDefinition {
             tool_type: "function",
             function: FunctionDefinition {
-                name: "bash",
+                name: Cow::Borrowed("bash"),
                 strict: None,
-                description: "Tool for executing bash commands.\n\n* Avoid long running commands\n* Avoid dangerous/destructive commands\n* Prefer using other more specialized tools where possible",
+                description: Cow::Borrowed("Tool for executing bash commands.\n\n* Avoid long running commands\n* Avoid dangerous/destructive commands\n* Prefer using other more specialized tools where possible"),
                 parameters: serde_json::json!({
                     "type": "object",
                     "required": ["command"],
@@ -696,10 +729,10 @@ pub fn get_relace_search_tool_definitions() -> Vec<ToolDefinition> {
         ToolDefinition {
             tool_type: "function",
             function: FunctionDefinition {
-                name: "repo_browser.exec",
+                name: Cow::Borrowed("repo_browser.exec"),
                 strict: None,
                 description:
-                    "Compatibility alias for bash/shell execution used by some providers.",
+                    Cow::Borrowed("Compatibility alias for bash/shell execution used by some providers."),
                 parameters: serde_json::json!({
                     "type": "object",
                     "required": [],
@@ -720,9 +753,9 @@ pub fn get_relace_search_tool_definitions() -> Vec<ToolDefinition> {
         ToolDefinition {
             tool_type: "function",
             function: FunctionDefinition {
-                name: "report_back",
+                name: Cow::Borrowed("report_back"),
                 strict: None,
-                description: "This is a tool to use when you feel like you have finished exploring the codebase and understanding the problem, and now would like to report back to the user.",
+                description: Cow::Borrowed("This is a tool to use when you feel like you have finished exploring the codebase and understanding the problem, and now would like to report back to the user."),
                 parameters: serde_json::json!({
                     "type": "object",
                     "required": ["explanation", "files"],
@@ -834,9 +867,9 @@ pub fn get_relace_search_tool_definitions() -> Vec<ToolDefinition> {
         ToolDefinition {
             tool_type: "function",
             function: FunctionDefinition {
-                name: "repo_browser.report_back",
+                name: Cow::Borrowed("repo_browser.report_back"),
                 strict: None,
-                description: "Compatibility alias for report_back used by some providers.",
+                description: Cow::Borrowed("Compatibility alias for report_back used by some providers."),
                 parameters: serde_json::json!({
                     "type": "object",
                     "required": ["explanation", "files"],
@@ -992,9 +1025,11 @@ fn report_back_tool_definition() -> ToolDefinition {
     ToolDefinition {
         tool_type: "function",
         function: FunctionDefinition {
-            name: "report_back",
+            name: Cow::Borrowed("report_back"),
             strict: None,
-            description: "Report final findings after inspection. Call exactly once when done.",
+            description: Cow::Borrowed(
+                "Report final findings after inspection. Call exactly once when done.",
+            ),
             parameters: serde_json::json!({
                 "type": "object",
                 "required": ["explanation", "files"],
@@ -1076,6 +1111,15 @@ const DANGEROUS_SHELL_CHARS: &[char] = &[
 /// Maximum output size for tool results (4KB ≈ 1k tokens)
 const MAX_OUTPUT_SIZE: usize = 4000;
 
+/// Most lines one `read_lines` call returns
+const MAX_READ_LINES_PER_CALL: usize = 200;
+
+/// Default per-run cap on lines returned by the line-reading tools
+const DEFAULT_MAX_LINES_READ_PER_RUN: usize = 3_000;
+
+/// Default per-run cap on search calls
+const DEFAULT_MAX_SEARCHES_PER_RUN: usize = 40;
+
 /// Maximum length for search patterns to prevent ReDoS
 const MAX_PATTERN_LENGTH: usize = 500;
 
@@ -1121,11 +1165,7 @@ fn is_safe_regex_pattern(pattern: &str) -> Result<(), String> {
     for c in pattern.chars() {
         match c {
             '(' => in_group += 1,
-            ')' => {
-                if in_group > 0 {
-                    in_group -= 1;
-                }
-            }
+            ')' if in_group > 0 => in_group -= 1,
             '+' | '*' if prev_char == ')' && in_group == 0 => {
                 nested_quantifier_count += 1;
             }
@@ -1145,6 +1185,122 @@ fn is_safe_regex_pattern(pattern: &str) -> Result<(), String> {
     Ok(())
 }
 
+/// Per-run caps on what the read and search tools hand back, shared by every tool call in one
+/// agent loop. Once a cap is reached the tool answers with a note instead of output, so the
+/// model reports with the evidence it has rather than paging through whole files.
+#[derive(Debug)]
+pub struct ToolBudget {
+    max_lines_read: usize,
+    max_searches: usize,
+    lines_read: AtomicUsize,
+    searches: AtomicUsize,
+}
+
+impl ToolBudget {
+    pub fn new(max_lines_read: usize, max_searches: usize) -> Self {
+        Self {
+            max_lines_read,
+            max_searches,
+            lines_read: AtomicUsize::new(0),
+            searches: AtomicUsize::new(0),
+        }
+    }
+
+    /// Defaults, overridable with `COSMOS_TOOL_MAX_LINES_READ` and `COSMOS_TOOL_MAX_SEARCHES`.
+    pub fn from_env() -> Self {
+        let env_usize = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .and_then(|value| value.trim().parse::<usize>().ok())
+        };
+        Self::new(
+            env_usize("COSMOS_TOOL_MAX_LINES_READ").unwrap_or(DEFAULT_MAX_LINES_READ_PER_RUN),
+            env_usize("COSMOS_TOOL_MAX_SEARCHES").unwrap_or(DEFAULT_MAX_SEARCHES_PER_RUN),
+        )
+    }
+
+    pub fn lines_read(&self) -> usize {
+        self.lines_read.load(Ordering::Relaxed)
+    }
+
+    pub fn searches(&self) -> usize {
+        self.searches.load(Ordering::Relaxed)
+    }
+
+    fn remaining_lines(&self) -> usize {
+        self.max_lines_read.saturating_sub(self.lines_read())
+    }
+}
+
+/// Execute a tool call, enforcing `budget` on the line-reading and search tools.
+pub fn execute_tool_with_budget(
+    root: &Path,
+    tool_call: &ToolCall,
+    budget: &ToolBudget,
+) -> ToolResult {
+    let name = tool_call.function.name.as_str();
+    let args_json = tool_call.function.arguments.as_str();
+    let content = match name {
+        "read_lines" | "read_range" | "head" => {
+            let remaining = budget.remaining_lines();
+            if remaining == 0 {
+                format!(
+                    "Line budget for this run is used up ({} lines read). Report back with the evidence you already have.",
+                    budget.max_lines_read
+                )
+            } else {
+                let output = match name {
+                    "head" => execute_head(root, &clamp_head_args(args_json, remaining)),
+                    _ => execute_read_lines(root, args_json, remaining),
+                };
+                budget
+                    .lines_read
+                    .fetch_add(count_numbered_lines(&output), Ordering::Relaxed);
+                output
+            }
+        }
+        "search" | "repo_browser.search" | "grep_search" => {
+            if budget.searches() >= budget.max_searches {
+                format!(
+                    "Search budget for this run is used up ({} searches). Use read_lines on what you found, or report back.",
+                    budget.max_searches
+                )
+            } else {
+                budget.searches.fetch_add(1, Ordering::Relaxed);
+                return execute_tool(root, tool_call);
+            }
+        }
+        _ => return execute_tool(root, tool_call),
+    };
+
+    ToolResult {
+        tool_call_id: tool_call.id.clone(),
+        content,
+    }
+}
+
+/// Lines of file content in a reading tool's output (`  12│ code`).
+fn count_numbered_lines(output: &str) -> usize {
+    output
+        .lines()
+        .filter(|line| {
+            line.split_once('│').is_some_and(|(number, _)| {
+                let number = number.trim();
+                !number.is_empty() && number.chars().all(|c| c.is_ascii_digit())
+            })
+        })
+        .count()
+}
+
+fn clamp_head_args(args_json: &str, max_lines: usize) -> String {
+    let Ok(mut args) = serde_json::from_str::<serde_json::Value>(args_json) else {
+        return args_json.to_string();
+    };
+    let requested = args["lines"].as_u64().map(|n| n as usize).unwrap_or(50);
+    args["lines"] = serde_json::json!(requested.min(max_lines));
+    args.to_string()
+}
+
 /// Execute a tool call and return the result
 pub fn execute_tool(root: &Path, tool_call: &ToolCall) -> ToolResult {
     let content = match tool_call.function.name.as_str() {
@@ -1153,6 +1309,9 @@ pub fn execute_tool(root: &Path, tool_call: &ToolCall) -> ToolResult {
         "search" | "repo_browser.search" => {
             execute_search_alias(root, &tool_call.function.arguments)
         }
+        "read_lines" => {
+            execute_read_lines(root, &tool_call.function.arguments, MAX_READ_LINES_PER_CALL)
+        }
         "read_range" => execute_read_range(root, &tool_call.function.arguments),
         "shell" => execute_shell(root, &tool_call.function.arguments),
         "view_file" | "open_file" | "repo_browser.open_file" | "repo_browser.view_file" => {
@@ -1169,7 +1328,9 @@ pub fn execute_tool(root: &Path, tool_call: &ToolCall) -> ToolResult {
         "report_back" | "repo_browser.report_back" => {
             execute_report_back(&tool_call.function.arguments)
         }
-        _ => format!("Unknown tool: {}", tool_call.function.name),
+        name => super::custom_tools::execute(root, name, &tool_call.function.arguments)
+            .map(truncate_output)
+            .unwrap_or_else(|| format!("Unknown tool: {}", name)),
     };
 
     ToolResult {
@@ -1613,6 +1774,8 @@ fn execute_search_alias(root: &Path, args_json: &str) -> String {
     struct SearchAliasArgs {
         query: Option<String>,
         pattern: Option<String>,
+        regex: Option<String>,
+        context: Option<usize>,
         path: Option<String>,
         case_sensitive: Option<bool>,
         exclude_pattern: Option<String>,
@@ -1656,6 +1819,15 @@ fn execute_search_alias(root: &Path, args_json: &str) -> String {
         return execute_grep_search(root, &normalized.to_string());
     }
 
+    if let Some(regex) = args.regex.filter(|_| args.pattern.is_none()) {
+        let normalized = serde_json::json!({
+            "pattern": regex,
+            "path": args.path,
+            "context": args.context,
+        });
+        return execute_search(root, &normalized.to_string());
+    }
+
     execute_search(root, args_json)
 }
 
@@ -1911,6 +2083,39 @@ fn execute_read_range(root: &Path, args_json: &str) -> String {
     }
 }
 
+/// `read_range` with at most `max_lines` lines (and `MAX_READ_LINES_PER_CALL`) returned.
+fn execute_read_lines(root: &Path, args_json: &str, max_lines: usize) -> String {
+    #[derive(Deserialize)]
+    struct ReadLinesArgs {
+        file: Option<String>,
+        path: Option<String>,
+        start: usize,
+        end: usize,
+    }
+
+    let args: ReadLinesArgs = match serde_json::from_str(args_json) {
+        Ok(a) => a,
+        Err(e) => return format!("Invalid arguments: {}", e),
+    };
+    let Some(file) = args.file.or(args.path) else {
+        return "Invalid arguments: expected `file`".to_string();
+    };
+    let max_lines = max_lines.clamp(1, MAX_READ_LINES_PER_CALL);
+    let end = args
+        .end
+        .min(args.start.saturating_add(max_lines).saturating_sub(1));
+    let range = serde_json::json!({ "path": file, "start": args.start, "end": end });
+    let mut output = execute_read_range(root, &range.to_string());
+    if end < args.end && !output.starts_with("Invalid") {
+        output.push_str(&format!(
+            "\n... (stopped at line {}; call read_lines again from line {} for more)\n",
+            end,
+            end + 1
+        ));
+    }
+    truncate_output(output)
+}
+
 // ═══════════════════════════════════════════════════════════════════════════
 //  SHELL - Fallback for edge cases
 // ═══════════════════════════════════════════════════════════════════════════
@@ -1918,11 +2123,14 @@ fn execute_read_range(root: &Path, args_json: &str) -> String {
 /// Truncate output at line boundary
 fn truncate_output(result: String) -> String {
     if result.len() > MAX_OUTPUT_SIZE {
-        let truncate_at = result[..MAX_OUTPUT_SIZE]
-            .rfind('\n')
-            .unwrap_or(MAX_OUTPUT_SIZE);
+        // Back off to a char boundary so multi-byte output can't cause a panic
+        let mut limit = MAX_OUTPUT_SIZE;
+        while !result.is_char_boundary(limit) {
+            limit -= 1;
+        }
+        let truncate_at = result[..limit].rfind('\n').unwrap_or(limit);
         format!(
-            "{}\n\n... (truncated - use read_range for specific sections)",
+            "{}\n\n... (truncated - use read_lines for specific sections)",
             &result[..truncate_at]
         )
     } else {
@@ -2052,12 +2260,21 @@ mod tests {
     use std::fs;
     use tempfile::tempdir;
 
+    #[test]
+    fn test_truncate_output_handles_multibyte_boundary() {
+        // "é" is two bytes, so MAX_OUTPUT_SIZE lands mid-character for odd offsets
+        let output = format!("x{}", "é".repeat(MAX_OUTPUT_SIZE));
+        let truncated = truncate_output(output);
+        assert!(truncated.contains("(truncated"));
+        assert!(truncated.len() < MAX_OUTPUT_SIZE + 100);
+    }
+
     #[test]
     fn test_relace_tool_definitions_match_expected_names() {
         let tools = get_relace_search_tool_definitions();
         let names = tools
             .iter()
-            .map(|tool| tool.function.name)
+            .map(|tool| tool.function.name.as_ref())
             .collect::<Vec<_>>();
         assert_eq!(
             names,
@@ -2081,7 +2298,7 @@ mod tests {
         );
         let strict_by_name = tools
             .iter()
-            .map(|tool| (tool.function.name, tool.function.strict))
+            .map(|tool| (tool.function.name.as_ref(), tool.function.strict))
             .collect::<std::collections::HashMap<_, _>>();
         assert_eq!(strict_by_name.get("report_back"), Some(&None));
         assert_eq!(strict_by_name.get("repo_browser.report_back"), Some(&None));
@@ -2095,7 +2312,7 @@ mod tests {
         let tools = get_relace_search_tool_definitions_cerebras();
         let names = tools
             .iter()
-            .map(|tool| tool.function.name)
+            .map(|tool| tool.function.name.as_ref())
             .collect::<Vec<_>>();
         assert_eq!(
             names,
@@ -2103,7 +2320,7 @@ mod tests {
                 "tree",
                 "head",
                 "search",
-                "read_range",
+                "read_lines",
                 "shell",
                 "report_back"
             ]
@@ -2157,7 +2374,7 @@ mod tests {
                         .collect::<Vec<_>>()
                 })
                 .unwrap_or_default();
-            required_by_tool.insert(tool.function.name, required);
+            required_by_tool.insert(tool.function.name.to_string(), required);
         }
 
         assert_eq!(
@@ -2784,8 +3001,8 @@ mod tests {
         assert_eq!(tools.len(), 5);
 
         // Verify tools are in the right order for top-down exploration
-        let names: Vec<_> = tools.iter().map(|t| t.function.name).collect();
-        assert_eq!(names, vec!["tree", "head", "search", "read_range", "shell"]);
+        let names: Vec<_> = tools.iter().map(|t| t.function.name.as_ref()).collect();
+        assert_eq!(names, vec!["tree", "head", "search", "read_lines", "shell"]);
 
         // All tools should be functions
         for tool in &tools {
@@ -2968,6 +3185,58 @@ mod tests {
     //  READ_RANGE TOOL TESTS
     // ═══════════════════════════════════════════════════════════════════════
 
+    #[test]
+    fn test_read_lines_caps_lines_per_call_and_per_run() {
+        let dir = tempdir().unwrap();
+        let content = (1..=500)
+            .map(|i| format!("line {}", i))
+            .collect::<Vec<_>>()
+            .join("\n");
+        fs::write(dir.path().join("big.txt"), &content).unwrap();
+        let read = |start: usize, end: usize| ToolCall {
+            id: "1".to_string(),
+            function: FunctionCall {
+                name: "read_lines".to_string(),
+                arguments: format!(r#"{{"file": "big.txt", "start": {start}, "end": {end}}}"#),
+            },
+        };
+
+        let result = execute_tool(dir.path(), &read(1, 500));
+        assert!(result.content.contains("line 1\n"));
+        assert!(!result.content.contains("│ line 201"));
+
+        let budget = ToolBudget::new(30, 1);
+        let result = execute_tool_with_budget(dir.path(), &read(100, 120), &budget);
+        assert!(result.content.contains("line 120"));
+        assert_eq!(budget.lines_read(), 21);
+        let result = execute_tool_with_budget(dir.path(), &read(300, 400), &budget);
+        assert!(result.content.contains("line 308"));
+        assert!(!result.content.contains("│ line 309"));
+        assert!(result
+            .content
+            .contains("call read_lines again from line 309"));
+        assert_eq!(budget.lines_read(), 30);
+        let result = execute_tool_with_budget(dir.path(), &read(1, 5), &budget);
+        assert!(result
+            .content
+            .contains("Line budget for this run is used up"));
+
+        let search = ToolCall {
+            id: "2".to_string(),
+            function: FunctionCall {
+                name: "search".to_string(),
+                arguments: r#"{"regex": "line 42$"}"#.to_string(),
+            },
+        };
+        let first = execute_tool_with_budget(dir.path(), &search, &budget);
+        assert!(!first.content.contains("budget"));
+        let second = execute_tool_with_budget(dir.path(), &search, &budget);
+        assert!(second
+            .content
+            .contains("Search budget for this run is used up"));
+        assert_eq!(budget.searches(), 1);
+    }
+
     #[test]
     fn test_read_range_basic() {
         let dir = tempdir().unwrap();
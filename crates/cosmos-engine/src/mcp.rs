@@ -0,0 +1,544 @@
+//! Model Context Protocol server over the codebase index.
+//!
+//! Lets other AI agents use Cosmos's grounding data through four tools: `search_symbols`,
+//! `read_snippet`, `list_suggestions`, and `get_summary`. Messages are JSON-RPC 2.0; the
+//! transport (newline-delimited stdio for `cosmos mcp`) belongs to the caller.
+
+use crate::llm;
+use cosmos_adapters::util::resolve_repo_path_allow_new;
+use cosmos_core::context::WorkContext;
+use cosmos_core::index::directory::DirectorySummary;
+use cosmos_core::index::{CodebaseIndex, SymbolKind};
+use cosmos_core::suggest::SuggestionEngine;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+/// MCP revision this server implements
+pub const PROTOCOL_VERSION: &str = "2024-11-05";
+
+const DEFAULT_SYMBOL_LIMIT: usize = 50;
+const MAX_SNIPPET_LINES: usize = 400;
+const DEFAULT_SNIPPET_LINES: usize = 120;
+
+pub struct McpServer {
+    root: PathBuf,
+    index: CodebaseIndex,
+    context: WorkContext,
+    suggestions: SuggestionEngine,
+}
+
+impl McpServer {
+    pub fn new(
+        root: PathBuf,
+        index: CodebaseIndex,
+        context: WorkContext,
+        suggestions: SuggestionEngine,
+    ) -> Self {
+        Self {
+            root,
+            index,
+            context,
+            suggestions,
+        }
+    }
+
+    /// Handle one JSON-RPC message, returning the response (None for notifications).
+    pub async fn handle_message(&mut self, message: &str) -> Option<Value> {
+        let request: Value = match serde_json::from_str(message) {
+            Ok(request) => request,
+            Err(e) => return Some(error_response(Value::Null, -32700, &e.to_string())),
+        };
+        let id = request.get("id").cloned();
+        let method = request
+            .get("method")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        let params = request.get("params").cloned().unwrap_or(Value::Null);
+
+        let result = match method {
+            "initialize" => Ok(json!({
+                "protocolVersion": PROTOCOL_VERSION,
+                "capabilities": { "tools": {} },
+                "serverInfo": { "name": "cosmos", "version": env!("CARGO_PKG_VERSION") },
+            })),
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(json!({ "tools": tool_definitions() })),
+            "tools/call" => Ok(self.call_tool(&params).await),
+            _ if method.starts_with("notifications/") => return None,
+            other => Err(format!("unknown method '{}'", other)),
+        };
+        let id = id?;
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(message) => error_response(id, -32601, &message),
+        })
+    }
+
+    /// Run a tool; failures are reported in-band with `isError` as MCP expects.
+    async fn call_tool(&mut self, params: &Value) -> Value {
+        let name = params
+            .get("name")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        let args = params.get("arguments").cloned().unwrap_or(json!({}));
+        let output = match name {
+            "search_symbols" => parse_args(args).map(|args| self.search_symbols(args)),
+            "read_snippet" => match parse_args(args) {
+                Ok(args) => self.read_snippet(args),
+                Err(e) => Err(e),
+            },
+            "list_suggestions" => match parse_args(args) {
+                Ok(args) => self.list_suggestions(args).await,
+                Err(e) => Err(e),
+            },
+            "get_summary" => match parse_args(args) {
+                Ok(args) => self.get_summary(args),
+                Err(e) => Err(e),
+            },
+            other => Err(format!("unknown tool '{}'", other)),
+        };
+        let (text, is_error) = match output {
+            Ok(text) => (text, false),
+            Err(text) => (text, true),
+        };
+        json!({ "content": [{ "type": "text", "text": text }], "isError": is_error })
+    }
+
+    fn search_symbols(&self, args: SearchSymbolsArgs) -> String {
+        let query = args.query.to_lowercase();
+        let kind = args.kind.as_deref().map(str::to_lowercase);
+        // Symbol paths are absolute; report the index's repo-relative file path instead.
+        let mut matches: Vec<_> = self
+            .index
+            .files
+            .values()
+            .flat_map(|file| file.symbols.iter().map(move |symbol| (&file.path, symbol)))
+            .filter(|(_, symbol)| symbol.name.to_lowercase().contains(&query))
+            .filter(|(_, symbol)| {
+                kind.as_deref()
+                    .is_none_or(|kind| symbol_kind_name(symbol.kind) == kind)
+            })
+            .collect();
+        // Exact names first, then shorter (closer) names, then by location.
+        matches.sort_by(|(path_a, a), (path_b, b)| {
+            let exact_a = a.name.to_lowercase() != query;
+            let exact_b = b.name.to_lowercase() != query;
+            (exact_a, a.name.len(), path_a, a.line).cmp(&(exact_b, b.name.len(), path_b, b.line))
+        });
+        if matches.is_empty() {
+            return format!("No symbols matching '{}'", args.query);
+        }
+        let limit = args.limit.unwrap_or(DEFAULT_SYMBOL_LIMIT).max(1);
+        let mut out = String::new();
+        for (path, symbol) in matches.iter().take(limit) {
+            let _ = writeln!(
+                out,
+                "{}:{}-{} {} {}",
+                path.display(),
+                symbol.line,
+                symbol.end_line,
+                symbol_kind_name(symbol.kind),
+                symbol.name
+            );
+        }
+        if matches.len() > limit {
+            let _ = writeln!(out, "... {} more", matches.len() - limit);
+        }
+        out
+    }
+
+    fn read_snippet(&self, args: ReadSnippetArgs) -> Result<String, String> {
+        let resolved = resolve_repo_path_allow_new(&self.root, Path::new(&args.path))?;
+        let content = std::fs::read_to_string(&resolved.absolute)
+            .map_err(|e| format!("Failed to read {}: {}", args.path, e))?;
+        let lines: Vec<&str> = content.lines().collect();
+        let start = args.start_line.unwrap_or(1).max(1);
+        if start > lines.len().max(1) {
+            return Err(format!(
+                "start_line {} is past the end of {} ({} lines)",
+                start,
+                args.path,
+                lines.len()
+            ));
+        }
+        let end = args
+            .end_line
+            .unwrap_or(start + DEFAULT_SNIPPET_LINES - 1)
+            .clamp(start, start + MAX_SNIPPET_LINES - 1)
+            .min(lines.len());
+        let mut out = String::new();
+        for (offset, line) in lines[start - 1..end].iter().enumerate() {
+            let _ = writeln!(out, "{:>5}│ {}", start + offset, line);
+        }
+        if end < lines.len() {
+            let _ = writeln!(out, "... ({} more lines)", lines.len() - end);
+        }
+        // Grounding data leaves the machine; keep secrets out of it like every AI prompt.
+        Ok(llm::redaction::redact_secrets(&out).0)
+    }
+
+    async fn list_suggestions(&mut self, args: ListSuggestionsArgs) -> Result<String, String> {
+        if args.refresh {
+            if !llm::is_available() {
+                return Err("AI is unavailable; configure an API key to refresh".to_string());
+            }
+            let result = llm::run_fast_grounded_with_gate(
+                &self.root,
+                &self.index,
+                &self.context,
+                None,
+                llm::SuggestionQualityGateConfig::default(),
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+            self.suggestions.replace_llm_suggestions(result.suggestions);
+        }
+        let suggestions = self.suggestions.active_suggestions();
+        if suggestions.is_empty() {
+            return Ok("No suggestions".to_string());
+        }
+        let mut out = String::new();
+        for suggestion in suggestions {
+            let line = suggestion
+                .line
+                .map(|line| format!(":{}", line))
+                .unwrap_or_default();
+            let _ = writeln!(
+                out,
+                "- [{}] {} ({}{})",
+                suggestion.kind.label(),
+                suggestion.summary,
+                suggestion.file.display(),
+                line
+            );
+            if let Some(detail) = suggestion.detail.as_deref().filter(|d| !d.is_empty()) {
+                let _ = writeln!(out, "  {}", detail.trim());
+            }
+        }
+        Ok(out)
+    }
+
+    fn get_summary(&self, args: GetSummaryArgs) -> Result<String, String> {
+        let Some(path) = args.path else {
+            return Ok(self.project_summary());
+        };
+        let Some(file) = self.index.files.get(Path::new(&path)) else {
+            let dir = Path::new(path.trim_end_matches('/'));
+            return DirectorySummary::synthesize(&self.index, dir)
+                .map(|summary| format!("{}\n", summary.to_prompt_line()))
+                .ok_or_else(|| format!("{} is not in the index", path));
+        };
+        let mut out = String::new();
+        let _ = writeln!(out, "{} ({:?}, {} lines)", path, file.language, file.loc);
+        if !file.summary.purpose.is_empty() {
+            let _ = writeln!(out, "Purpose: {}", file.summary.purpose);
+        }
+        if let Some(layer) = &file.layer {
+            let _ = writeln!(out, "Layer: {:?}", layer);
+        }
+        let _ = writeln!(out, "Symbols:");
+        for symbol in &file.symbols {
+            let _ = writeln!(
+                out,
+                "  {}-{} {} {}",
+                symbol.line,
+                symbol.end_line,
+                symbol_kind_name(symbol.kind),
+                symbol.name
+            );
+        }
+        write_paths(&mut out, "Depends on", &file.summary.depends_on);
+        write_paths(&mut out, "Used by", &file.summary.used_by);
+        Ok(out)
+    }
+
+    fn project_summary(&self) -> String {
+        let stats = self.index.stats();
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "{} on {}: {} files, {} lines, {} symbols",
+            self.root.display(),
+            self.context.branch,
+            stats.file_count,
+            stats.total_loc,
+            stats.symbol_count
+        );
+        let mut languages: std::collections::BTreeMap<String, (usize, usize)> = Default::default();
+        for file in self.index.files.values() {
+            let entry = languages.entry(format!("{:?}", file.language)).or_default();
+            entry.0 += 1;
+            entry.1 += file.loc;
+        }
+        for (language, (files, loc)) in languages {
+            let _ = writeln!(out, "  {}: {} files, {} lines", language, files, loc);
+        }
+        if !self.index.packages.is_empty() {
+            let _ = writeln!(out, "Packages:");
+            for package in &self.index.packages {
+                let _ = writeln!(out, "  {} ({})", package.name, package.root.display());
+            }
+        }
+        let mut central: Vec<_> = self.index.files.values().collect();
+        central.sort_by(|a, b| {
+            b.summary
+                .used_by
+                .len()
+                .cmp(&a.summary.used_by.len())
+                .then_with(|| a.path.cmp(&b.path))
+        });
+        let central: Vec<_> = central
+            .into_iter()
+            .filter(|file| !file.summary.used_by.is_empty())
+            .take(10)
+            .collect();
+        if !central.is_empty() {
+            let _ = writeln!(out, "Most depended-on files:");
+            for file in central {
+                let _ = writeln!(
+                    out,
+                    "  {} (used by {})",
+                    file.path.display(),
+                    file.summary.used_by.len()
+                );
+            }
+        }
+        if !self.context.all_changed_files().is_empty() {
+            let _ = writeln!(out, "Uncommitted changes:");
+            for path in self.context.all_changed_files() {
+                let _ = writeln!(out, "  {}", path.display());
+            }
+        }
+        out
+    }
+}
+
+fn write_paths(out: &mut String, label: &str, paths: &[PathBuf]) {
+    if paths.is_empty() {
+        return;
+    }
+    let _ = writeln!(out, "{}:", label);
+    for path in paths {
+        let _ = writeln!(out, "  {}", path.display());
+    }
+}
+
+fn symbol_kind_name(kind: SymbolKind) -> &'static str {
+    match kind {
+        SymbolKind::Function => "function",
+        SymbolKind::Method => "method",
+        SymbolKind::Struct => "struct",
+        SymbolKind::Class => "class",
+        SymbolKind::Enum => "enum",
+        SymbolKind::Interface => "interface",
+        SymbolKind::Trait => "trait",
+        SymbolKind::Module => "module",
+        SymbolKind::Constant => "constant",
+        SymbolKind::Variable => "variable",
+    }
+}
+
+#[derive(Deserialize)]
+struct SearchSymbolsArgs {
+    query: String,
+    #[serde(default)]
+    kind: Option<String>,
+    #[serde(default)]
+    limit: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct ReadSnippetArgs {
+    path: String,
+    #[serde(default)]
+    start_line: Option<usize>,
+    #[serde(default)]
+    end_line: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct ListSuggestionsArgs {
+    #[serde(default)]
+    refresh: bool,
+}
+
+#[derive(Deserialize)]
+struct GetSummaryArgs {
+    #[serde(default)]
+    path: Option<String>,
+}
+
+fn parse_args<T: serde::de::DeserializeOwned>(args: Value) -> Result<T, String> {
+    serde_json::from_value(args).map_err(|e| format!("Invalid arguments: {}", e))
+}
+
+fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+/// Tool list returned by `tools/list`
+pub fn tool_definitions() -> Value {
+    json!([
+        {
+            "name": "search_symbols",
+            "description": "Find functions, types, and other symbols in the indexed codebase by name (case-insensitive substring). Returns path:start-end kind name per match.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string", "description": "Part of the symbol name" },
+                    "kind": {
+                        "type": "string",
+                        "enum": ["function", "method", "struct", "class", "enum", "interface", "trait", "module", "constant", "variable"],
+                    },
+                    "limit": { "type": "integer", "minimum": 1, "description": "Maximum matches (default 50)" },
+                },
+                "required": ["query"],
+            },
+        },
+        {
+            "name": "read_snippet",
+            "description": "Read numbered lines from a repository file (paths are repo-relative; secrets are redacted). Defaults to 120 lines, at most 400.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "path": { "type": "string" },
+                    "start_line": { "type": "integer", "minimum": 1 },
+                    "end_line": { "type": "integer", "minimum": 1 },
+                },
+                "required": ["path"],
+            },
+        },
+        {
+            "name": "list_suggestions",
+            "description": "List Cosmos's current improvement suggestions with file locations. Set refresh to run a new AI scan first.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "refresh": { "type": "boolean" } },
+            },
+        },
+        {
+            "name": "get_summary",
+            "description": "Summarize the project (languages, packages, central files, uncommitted changes) or, with path, one file's symbols and dependencies or one directory's rollup.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "path": { "type": "string", "description": "Repo-relative file or directory path" } },
+            },
+        },
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmos_core::index::{FileIndex, FileSummary, Language, Symbol, Visibility};
+    use std::collections::HashMap;
+
+    fn server(root: &Path) -> McpServer {
+        let path = PathBuf::from("src/lib.rs");
+        let symbol = |name: &str, line| Symbol {
+            name: name.to_string(),
+            kind: SymbolKind::Function,
+            file: path.clone(),
+            line,
+            end_line: line,
+            complexity: 1.0,
+            visibility: Visibility::Public,
+        };
+        let file = FileIndex {
+            path: path.clone(),
+            language: Language::Rust,
+            loc: 3,
+            content_hash: String::new(),
+            symbols: vec![symbol("parse_config", 1), symbol("parse", 3)],
+            dependencies: Vec::new(),
+            patterns: Vec::new(),
+            complexity: 1.0,
+            last_modified: chrono::Utc::now(),
+            summary: FileSummary::default(),
+            layer: None,
+            feature: None,
+        };
+        let index = CodebaseIndex {
+            root: root.to_path_buf(),
+            files: HashMap::from([(path, file)]),
+            index_errors: Vec::new(),
+            git_head: None,
+            packages: Vec::new(),
+            excluded_files: 0,
+            filter_fingerprint: None,
+        };
+        let context = WorkContext {
+            branch: "main".to_string(),
+            uncommitted_files: Vec::new(),
+            staged_files: Vec::new(),
+            untracked_files: Vec::new(),
+            inferred_focus: None,
+            modified_count: 0,
+            repo_root: root.to_path_buf(),
+        };
+        let suggestions = SuggestionEngine::new(index.clone());
+        McpServer::new(root.to_path_buf(), index, context, suggestions)
+    }
+
+    async fn call(server: &mut McpServer, name: &str, arguments: Value) -> Value {
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "tools/call",
+            "params": { "name": name, "arguments": arguments },
+        });
+        server.handle_message(&request.to_string()).await.unwrap()["result"].clone()
+    }
+
+    #[tokio::test]
+    async fn tools_search_read_and_report_errors_in_band() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("src")).unwrap();
+        std::fs::write(
+            dir.path().join("src/lib.rs"),
+            "fn parse_config() {}\n\nfn parse() {}\n",
+        )
+        .unwrap();
+        let mut server = server(dir.path());
+
+        let listed = server
+            .handle_message(r#"{"jsonrpc":"2.0","id":0,"method":"tools/list"}"#)
+            .await
+            .unwrap();
+        assert_eq!(listed["result"]["tools"].as_array().unwrap().len(), 4);
+        assert!(server
+            .handle_message(r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#)
+            .await
+            .is_none());
+
+        let found = call(&mut server, "search_symbols", json!({ "query": "PARSE" })).await;
+        let text = found["content"][0]["text"].as_str().unwrap();
+        assert!(text.starts_with("src/lib.rs:3-3 function parse\n"));
+        assert!(text.contains("parse_config"));
+
+        let snippet = call(
+            &mut server,
+            "read_snippet",
+            json!({ "path": "src/lib.rs", "start_line": 3 }),
+        )
+        .await;
+        assert_eq!(snippet["content"][0]["text"], "    3│ fn parse() {}\n");
+
+        let escaped = call(
+            &mut server,
+            "read_snippet",
+            json!({ "path": "../etc/passwd" }),
+        )
+        .await;
+        assert_eq!(escaped["isError"], true);
+        let dir = call(&mut server, "get_summary", json!({ "path": "src/" })).await;
+        assert!(dir["content"][0]["text"]
+            .as_str()
+            .unwrap()
+            .starts_with("src/ (1 files"));
+        let missing = call(&mut server, "get_summary", json!({ "path": "src/none.rs" })).await;
+        assert_eq!(missing["isError"], true);
+    }
+}
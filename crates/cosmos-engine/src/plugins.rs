@@ -0,0 +1,430 @@
+//! Third-party analyzers that report suggestions over a subprocess protocol.
+//!
+//! A plugin is a command declared under `[[plugins]]` in `.cosmos/config.toml`. Cosmos writes
+//! the serialized index to a temp file and runs the command with `sh -c` from the repo root.
+//! The environment is cleared except for `PATH`, `HOME`, locale, and temp-dir variables, plus:
+//!
+//! - `COSMOS_PLUGIN_PROTOCOL` - protocol version (`1`)
+//! - `COSMOS_PLUGIN_INDEX` - path of the index JSON (the `index.json` cache shape)
+//! - `COSMOS_REPO_ROOT` - absolute repo root
+//!
+//! The plugin prints `{"suggestions": [{"file", "line"?, "kind"?, "priority"?, "category"?,
+//! "summary", "detail"?, "evidence"?}]}` to stdout and exits 0. Findings must point at indexed
+//! files; anything else is dropped. Plugins only run in trusted workspaces, under a timeout
+//! and an output cap.
+
+use crate::llm::implementation::{load_repo_harness_config, REPO_CONFIG_PATH};
+use cosmos_adapters::config::Config;
+use cosmos_adapters::util::{run_command_with_timeout, truncate};
+use cosmos_core::index::CodebaseIndex;
+use cosmos_core::suggest::{
+    Confidence, Priority, Suggestion, SuggestionCategory, SuggestionKind, SuggestionSource,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Component, Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+
+/// Version of the stdin-free env/stdout protocol described above
+pub const PLUGIN_PROTOCOL_VERSION: u32 = 1;
+
+const DEFAULT_PLUGIN_TIMEOUT_MS: u64 = 30_000;
+const MAX_PLUGIN_TIMEOUT_MS: u64 = 300_000;
+const MAX_SUGGESTIONS_PER_PLUGIN: usize = 200;
+const MAX_PLUGIN_OUTPUT_BYTES: usize = 4 * 1024 * 1024;
+const MAX_SUMMARY_CHARS: usize = 200;
+const MAX_DETAIL_CHARS: usize = 4_000;
+/// Variables a plugin inherits; API keys and tokens never reach it.
+pub(crate) const PASSTHROUGH_ENV: &[&str] =
+    &["PATH", "HOME", "LANG", "LC_ALL", "TMPDIR", "SYSTEMROOT"];
+
+/// One `[[plugins]]` entry in `.cosmos/config.toml`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PluginConfig {
+    /// Shown in notes and on each finding
+    pub name: String,
+    /// Shell command, run with `sh -c` from the repo root.
+    pub command: String,
+    /// Set to false to keep the entry without running it.
+    #[serde(default = "default_plugin_enabled")]
+    pub enabled: bool,
+    /// Defaults to 30s; capped at 5 minutes.
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+}
+
+fn default_plugin_enabled() -> bool {
+    true
+}
+
+impl PluginConfig {
+    fn timeout(&self) -> Duration {
+        Duration::from_millis(
+            self.timeout_ms
+                .unwrap_or(DEFAULT_PLUGIN_TIMEOUT_MS)
+                .clamp(1, MAX_PLUGIN_TIMEOUT_MS),
+        )
+    }
+}
+
+/// What one plugin contributed to a run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PluginReport {
+    pub name: String,
+    pub suggestions: usize,
+    /// Findings dropped for pointing outside the index or missing a summary
+    pub dropped: usize,
+    /// Why the plugin produced nothing (not trusted, timed out, bad output, ...)
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PluginRun {
+    pub suggestions: Vec<Suggestion>,
+    pub reports: Vec<PluginReport>,
+}
+
+#[derive(Deserialize)]
+struct PluginOutput {
+    #[serde(default)]
+    suggestions: Vec<PluginFinding>,
+}
+
+#[derive(Deserialize)]
+struct PluginFinding {
+    file: PathBuf,
+    #[serde(default)]
+    line: Option<usize>,
+    #[serde(default)]
+    kind: Option<String>,
+    #[serde(default)]
+    priority: Option<String>,
+    #[serde(default)]
+    category: Option<String>,
+    #[serde(default)]
+    summary: String,
+    #[serde(default)]
+    detail: Option<String>,
+    #[serde(default)]
+    evidence: Option<String>,
+}
+
+/// Load and validate the repo's `[[plugins]]`; a missing config means none.
+pub fn load_plugins(repo_root: &Path) -> anyhow::Result<Vec<PluginConfig>> {
+    let plugins = load_repo_harness_config(repo_root)?.plugins;
+    let mut names = HashSet::new();
+    for plugin in &plugins {
+        if plugin.name.trim().is_empty() || plugin.command.trim().is_empty() {
+            return Err(anyhow::anyhow!(
+                "Invalid {}: every [[plugins]] entry needs a name and a command",
+                REPO_CONFIG_PATH
+            ));
+        }
+        if !names.insert(plugin.name.trim()) {
+            return Err(anyhow::anyhow!(
+                "Invalid {}: plugin '{}' is declared twice",
+                REPO_CONFIG_PATH,
+                plugin.name
+            ));
+        }
+    }
+    Ok(plugins)
+}
+
+/// Run the repo's enabled plugins against `index`, if the workspace is trusted.
+pub fn run_configured_plugins(
+    repo_root: &Path,
+    index: &CodebaseIndex,
+) -> anyhow::Result<PluginRun> {
+    let plugins = load_plugins(repo_root)?;
+    let trusted = Config::load().is_workspace_trusted(repo_root);
+    Ok(run_plugins(repo_root, index, &plugins, trusted))
+}
+
+/// Run each enabled plugin in turn. Untrusted workspaces report every plugin as skipped.
+pub fn run_plugins(
+    repo_root: &Path,
+    index: &CodebaseIndex,
+    plugins: &[PluginConfig],
+    trusted: bool,
+) -> PluginRun {
+    let enabled: Vec<&PluginConfig> = plugins.iter().filter(|plugin| plugin.enabled).collect();
+    let mut run = PluginRun::default();
+    if enabled.is_empty() {
+        return run;
+    }
+    let failed = |plugin: &PluginConfig, error: String| PluginReport {
+        name: plugin.name.clone(),
+        suggestions: 0,
+        dropped: 0,
+        error: Some(error),
+    };
+    if !trusted {
+        run.reports = enabled
+            .iter()
+            .map(|plugin| failed(plugin, "skipped: workspace is not trusted".to_string()))
+            .collect();
+        return run;
+    }
+
+    let index_path =
+        std::env::temp_dir().join(format!("cosmos-plugin-index-{}.json", uuid::Uuid::new_v4()));
+    let written = serde_json::to_vec(index)
+        .map_err(|e| e.to_string())
+        .and_then(|json| std::fs::write(&index_path, json).map_err(|e| e.to_string()));
+    if let Err(e) = written {
+        run.reports = enabled
+            .iter()
+            .map(|plugin| failed(plugin, format!("could not write the index: {}", e)))
+            .collect();
+        return run;
+    }
+
+    for plugin in enabled {
+        match run_plugin(plugin, repo_root, &index_path) {
+            Ok(findings) => {
+                let total = findings.len();
+                let suggestions: Vec<Suggestion> = findings
+                    .into_iter()
+                    .filter_map(|finding| to_suggestion(&plugin.name, finding, index))
+                    .collect();
+                run.reports.push(PluginReport {
+                    name: plugin.name.clone(),
+                    suggestions: suggestions.len(),
+                    dropped: total - suggestions.len(),
+                    error: None,
+                });
+                run.suggestions.extend(suggestions);
+            }
+            Err(error) => run.reports.push(failed(plugin, error)),
+        }
+    }
+    let _ = std::fs::remove_file(&index_path);
+    run
+}
+
+fn run_plugin(
+    plugin: &PluginConfig,
+    repo_root: &Path,
+    index_path: &Path,
+) -> Result<Vec<PluginFinding>, String> {
+    let mut command = Command::new("sh");
+    command
+        .current_dir(repo_root)
+        .arg("-c")
+        .arg(&plugin.command)
+        .env_clear()
+        .envs(
+            PASSTHROUGH_ENV
+                .iter()
+                .filter_map(|key| std::env::var_os(key).map(|value| (key.to_string(), value))),
+        )
+        .env(
+            "COSMOS_PLUGIN_PROTOCOL",
+            PLUGIN_PROTOCOL_VERSION.to_string(),
+        )
+        .env("COSMOS_PLUGIN_INDEX", index_path)
+        .env("COSMOS_REPO_ROOT", repo_root);
+    let result = run_command_with_timeout(&mut command, plugin.timeout())?;
+    if result.timed_out {
+        return Err(format!(
+            "timed out after {}ms",
+            plugin.timeout().as_millis()
+        ));
+    }
+    if !result.status.is_some_and(|status| status.success()) {
+        let stderr = result.stderr.trim();
+        return Err(if stderr.is_empty() {
+            "exited with an error".to_string()
+        } else {
+            format!("exited with an error: {}", truncate(stderr, 300))
+        });
+    }
+    if result.stdout.len() > MAX_PLUGIN_OUTPUT_BYTES {
+        return Err(format!("output exceeds {} bytes", MAX_PLUGIN_OUTPUT_BYTES));
+    }
+    let output: PluginOutput = serde_json::from_str(result.stdout.trim())
+        .map_err(|e| format!("stdout is not a suggestions payload: {}", e))?;
+    Ok(output
+        .suggestions
+        .into_iter()
+        .take(MAX_SUGGESTIONS_PER_PLUGIN)
+        .collect())
+}
+
+/// Turn a plugin finding into a suggestion, or None if it doesn't point at an indexed file.
+fn to_suggestion(
+    plugin_name: &str,
+    finding: PluginFinding,
+    index: &CodebaseIndex,
+) -> Option<Suggestion> {
+    let file = normalize_relative(&finding.file)?;
+    let file_index = index.files.get(&file)?;
+    let summary = finding.summary.trim();
+    if summary.is_empty() {
+        return None;
+    }
+    let line = finding
+        .line
+        .filter(|line| *line >= 1 && *line <= file_index.loc.max(1));
+
+    let priority = match finding.priority.as_deref().map(str::to_ascii_lowercase) {
+        Some(p) if p == "high" => Priority::High,
+        Some(p) if p == "low" => Priority::Low,
+        _ => Priority::Medium,
+    };
+    let mut suggestion = Suggestion::new(
+        parse_kind(finding.kind.as_deref()),
+        priority,
+        file,
+        truncate(summary, MAX_SUMMARY_CHARS),
+        SuggestionSource::Plugin,
+    );
+    suggestion.line = line;
+    suggestion.confidence = Confidence::Medium;
+    if finding
+        .category
+        .as_deref()
+        .is_some_and(|category| category.eq_ignore_ascii_case("security"))
+    {
+        suggestion.category = SuggestionCategory::Security;
+    }
+    let credit = format!("Reported by the `{}` analyzer plugin.", plugin_name);
+    suggestion.detail = Some(match finding.detail.as_deref().map(str::trim) {
+        Some(detail) if !detail.is_empty() => {
+            format!("{}\n\n{}", truncate(detail, MAX_DETAIL_CHARS), credit)
+        }
+        _ => credit,
+    });
+    suggestion.evidence = finding
+        .evidence
+        .filter(|evidence| !evidence.trim().is_empty())
+        .map(|evidence| truncate(&evidence, MAX_DETAIL_CHARS));
+    Some(suggestion)
+}
+
+fn parse_kind(kind: Option<&str>) -> SuggestionKind {
+    match kind.map(str::to_ascii_lowercase).as_deref() {
+        Some("bug" | "bugfix" | "bug_fix" | "fix") => SuggestionKind::BugFix,
+        Some("feature") => SuggestionKind::Feature,
+        Some("optimization" | "performance" | "perf") => SuggestionKind::Optimization,
+        Some("quality") => SuggestionKind::Quality,
+        Some("documentation" | "docs") => SuggestionKind::Documentation,
+        Some("testing" | "test" | "tests") => SuggestionKind::Testing,
+        Some("refactoring" | "refactor") => SuggestionKind::Refactoring,
+        _ => SuggestionKind::Improvement,
+    }
+}
+
+/// Repo-relative path without `.` / `..` segments, or None if it escapes the repo.
+fn normalize_relative(path: &Path) -> Option<PathBuf> {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => out.push(part),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    (!out.as_os_str().is_empty()).then_some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repo() -> (tempfile::TempDir, CodebaseIndex) {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("src")).unwrap();
+        std::fs::write(
+            dir.path().join("src/lib.rs"),
+            "pub fn one() -> u32 {\n    1\n}\n",
+        )
+        .unwrap();
+        let index = CodebaseIndex::new(dir.path()).unwrap();
+        (dir, index)
+    }
+
+    fn plugin(name: &str, command: &str) -> PluginConfig {
+        PluginConfig {
+            name: name.to_string(),
+            command: command.to_string(),
+            enabled: true,
+            timeout_ms: Some(10_000),
+        }
+    }
+
+    #[test]
+    fn test_plugin_findings_become_suggestions() {
+        let (dir, index) = repo();
+        let script = r#"test -s "$COSMOS_PLUGIN_INDEX" && test -z "$CEREBRAS_API_KEY" && echo '{"suggestions":[{"file":"./src/lib.rs","line":2,"kind":"bug","priority":"high","summary":"returns 1"},{"file":"../outside.rs","summary":"escapes"},{"file":"src/lib.rs","summary":"  "}]}'"#;
+        std::env::set_var("CEREBRAS_API_KEY", "secret");
+        let run = run_plugins(dir.path(), &index, &[plugin("lint", script)], true);
+        std::env::remove_var("CEREBRAS_API_KEY");
+
+        assert_eq!(
+            run.reports,
+            vec![PluginReport {
+                name: "lint".to_string(),
+                suggestions: 1,
+                dropped: 2,
+                error: None,
+            }]
+        );
+        let suggestion = &run.suggestions[0];
+        assert_eq!(suggestion.file, PathBuf::from("src/lib.rs"));
+        assert_eq!(suggestion.line, Some(2));
+        assert_eq!(suggestion.kind, SuggestionKind::BugFix);
+        assert_eq!(suggestion.priority, Priority::High);
+        assert_eq!(suggestion.source, SuggestionSource::Plugin);
+        assert!(suggestion.detail.as_deref().unwrap().contains("`lint`"));
+    }
+
+    #[test]
+    fn test_plugins_report_failures_and_respect_trust_and_enabled() {
+        let (dir, index) = repo();
+        let mut disabled = plugin("off", "exit 1");
+        disabled.enabled = false;
+        let mut slow = plugin("slow", "sleep 5");
+        slow.timeout_ms = Some(100);
+        let plugins = vec![
+            disabled,
+            slow,
+            plugin("broken", "echo not json"),
+            plugin("failing", "echo boom >&2; exit 3"),
+        ];
+
+        let run = run_plugins(dir.path(), &index, &plugins, true);
+        let errors: Vec<_> = run
+            .reports
+            .iter()
+            .map(|report| (report.name.as_str(), report.error.clone().unwrap()))
+            .collect();
+        assert_eq!(errors.len(), 3);
+        assert!(errors[0].1.contains("timed out"));
+        assert!(errors[1].1.contains("not a suggestions payload"));
+        assert!(errors[2].1.contains("boom"));
+
+        let untrusted = run_plugins(dir.path(), &index, &plugins, false);
+        assert_eq!(untrusted.reports.len(), 3);
+        assert!(untrusted
+            .reports
+            .iter()
+            .all(|report| report.error.as_deref() == Some("skipped: workspace is not trusted")));
+    }
+
+    #[test]
+    fn test_load_plugins_rejects_duplicate_names() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join(".cosmos")).unwrap();
+        std::fs::write(
+            dir.path().join(".cosmos/config.toml"),
+            "[[plugins]]\nname = \"lint\"\ncommand = \"a\"\n\n[[plugins]]\nname = \"lint\"\ncommand = \"b\"\nenabled = false\n",
+        )
+        .unwrap();
+        let err = load_plugins(dir.path()).unwrap_err();
+        assert!(err.to_string().contains("declared twice"));
+    }
+}
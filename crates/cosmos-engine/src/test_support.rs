@@ -0,0 +1,26 @@
+//! Fixtures shared by the engine's tests and the crates that embed it.
+
+use std::path::Path;
+
+/// Run git in `root`, panicking if it fails.
+pub fn run_git(root: &Path, args: &[&str]) {
+    let status = std::process::Command::new("git")
+        .current_dir(root)
+        .args(args)
+        .status()
+        .unwrap();
+    assert!(status.success(), "git {:?} failed", args);
+}
+
+/// A git repo with one committed file, `src/lib.rs`.
+pub fn repo_with_file() -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir_all(dir.path().join("src")).unwrap();
+    std::fs::write(dir.path().join("src/lib.rs"), "pub fn one() -> u32 { 1 }\n").unwrap();
+    run_git(dir.path(), &["init", "-q"]);
+    run_git(dir.path(), &["config", "user.email", "cosmos@example.com"]);
+    run_git(dir.path(), &["config", "user.name", "Cosmos"]);
+    run_git(dir.path(), &["add", "."]);
+    run_git(dir.path(), &["commit", "-q", "-m", "init"]);
+    dir
+}
@@ -0,0 +1,240 @@
+//! Python bindings for the Cosmos engine, built as the `cosmos` module with maturin.
+//!
+//! ```python
+//! import cosmos
+//!
+//! index = cosmos.build_index("/path/to/repo")          # dict, same shape as index.json
+//! engine = cosmos.Cosmos("/path/to/repo", api_key="...")
+//! for suggestion in engine.scan_and_suggest():
+//!     result = engine.apply_with_harness(suggestion["id"])
+//!     print(suggestion["summary"], result["passed"])
+//! ```
+//!
+//! Calls block until the engine finishes and release the GIL while they run. Results cross
+//! into Python as plain dicts and lists (the engine's serde JSON shape). Failures raise
+//! `cosmos.CosmosError`.
+
+use cosmos_core::index::CodebaseIndex;
+use cosmos_core::suggest::Suggestion;
+use cosmos_engine::{Cosmos, Provider};
+use pyo3::create_exception;
+use pyo3::exceptions::PyException;
+use pyo3::prelude::*;
+use serde_json::json;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+create_exception!(cosmos, CosmosError, PyException);
+
+fn engine_error(error: anyhow::Error) -> PyErr {
+    CosmosError::new_err(format!("{:#}", error))
+}
+
+/// Convert anything serializable into the equivalent Python object via `json.loads`.
+fn to_python(py: Python<'_>, value: &impl serde::Serialize) -> PyResult<PyObject> {
+    let text = serde_json::to_string(value).map_err(|e| engine_error(e.into()))?;
+    Ok(py.import("json")?.call_method1("loads", (text,))?.unbind())
+}
+
+/// Index the repository at `path` and return the index as a dict.
+#[pyfunction]
+fn build_index(py: Python<'_>, path: PathBuf) -> PyResult<PyObject> {
+    let index = py
+        .allow_threads(|| CodebaseIndex::new(&path))
+        .map_err(engine_error)?;
+    to_python(py, &index)
+}
+
+/// A repository opened for suggestion runs. Keeps the suggestions from the last
+/// `scan_and_suggest` so they can be previewed and applied by id.
+#[pyclass(name = "Cosmos", module = "cosmos")]
+struct PyCosmos {
+    inner: Cosmos,
+    runtime: tokio::runtime::Runtime,
+    suggestions: Vec<Suggestion>,
+}
+
+impl PyCosmos {
+    fn suggestion(&self, id: &str) -> PyResult<&Suggestion> {
+        let id = Uuid::parse_str(id)
+            .map_err(|e| CosmosError::new_err(format!("Invalid suggestion id '{}': {}", id, e)))?;
+        self.suggestions
+            .iter()
+            .find(|suggestion| suggestion.id == id)
+            .ok_or_else(|| {
+                CosmosError::new_err(format!(
+                    "No suggestion {} in the last scan; call scan_and_suggest first",
+                    id
+                ))
+            })
+    }
+}
+
+#[pymethods]
+impl PyCosmos {
+    /// Open `repo`. Without `api_key`, uses the key the `cosmos` CLI would use. Without
+    /// `trust_workspace`, uses the trust recorded by the CLI.
+    #[new]
+    #[pyo3(signature = (repo, api_key=None, trust_workspace=None))]
+    fn new(
+        py: Python<'_>,
+        repo: PathBuf,
+        api_key: Option<String>,
+        trust_workspace: Option<bool>,
+    ) -> PyResult<Self> {
+        let provider = match api_key {
+            Some(key) => Provider::cerebras(key),
+            None => Provider::from_environment(),
+        };
+        let mut builder = Cosmos::builder().repo(repo).provider(provider);
+        if let Some(trusted) = trust_workspace {
+            builder = builder.trust_workspace(trusted);
+        }
+        let inner = py.allow_threads(|| builder.build()).map_err(engine_error)?;
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| engine_error(e.into()))?;
+        Ok(Self {
+            inner,
+            runtime,
+            suggestions: Vec::new(),
+        })
+    }
+
+    #[getter]
+    fn root(&self) -> PathBuf {
+        self.inner.root().to_path_buf()
+    }
+
+    /// The current index as a dict.
+    fn index(&self, py: Python<'_>) -> PyResult<PyObject> {
+        to_python(py, self.inner.index())
+    }
+
+    /// Re-index after the working tree changed.
+    fn refresh(&mut self, py: Python<'_>) -> PyResult<()> {
+        py.allow_threads(|| self.inner.refresh())
+            .map_err(engine_error)
+    }
+
+    /// Run a grounded suggestion scan; returns the suggestions as dicts.
+    fn scan_and_suggest(&mut self, py: Python<'_>) -> PyResult<PyObject> {
+        let suggestions = py
+            .allow_threads(|| self.runtime.block_on(self.inner.suggest()))
+            .map_err(engine_error)?;
+        self.suggestions = suggestions;
+        to_python(py, &self.suggestions)
+    }
+
+    /// Preview the fix for a suggestion from the last scan.
+    fn preview(&self, py: Python<'_>, suggestion_id: &str) -> PyResult<PyObject> {
+        let suggestion = self.suggestion(suggestion_id)?;
+        let preview = py
+            .allow_threads(|| self.runtime.block_on(self.inner.preview(suggestion)))
+            .map_err(engine_error)?;
+        to_python(
+            py,
+            &json!({
+                "summary": preview.summary,
+                "outcome": preview.outcome,
+                "files": preview.files,
+                "preview_hash": preview.preview_hash,
+            }),
+        )
+    }
+
+    /// Implement a suggestion under the strict harness, writing the files when it passes.
+    /// Pass the `preview_hash` from `preview` to refuse the run if the files changed since.
+    #[pyo3(signature = (suggestion_id, preview_hash=None))]
+    fn apply_with_harness(
+        &self,
+        py: Python<'_>,
+        suggestion_id: &str,
+        preview_hash: Option<String>,
+    ) -> PyResult<PyObject> {
+        let suggestion = self.suggestion(suggestion_id)?;
+        let outcome = py
+            .allow_threads(|| {
+                self.runtime.block_on(async {
+                    let mut preview = self.inner.preview(suggestion).await?;
+                    if let Some(hash) = preview_hash {
+                        preview.preview_hash = hash;
+                    }
+                    self.inner.apply(suggestion, &preview).await
+                })
+            })
+            .map_err(engine_error)?;
+        let files: Vec<_> = outcome
+            .files
+            .iter()
+            .map(|file| json!({ "path": file.path, "content": file.content }))
+            .collect();
+        to_python(
+            py,
+            &json!({
+                "passed": outcome.passed,
+                "description": outcome.description,
+                "files": files,
+                "fail_reasons": outcome.fail_reasons,
+                "report_path": outcome.report_path,
+            }),
+        )
+    }
+}
+
+#[pymodule]
+fn cosmos(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyCosmos>()?;
+    m.add_function(wrap_pyfunction!(build_index, m)?)?;
+    m.add("CosmosError", m.py().get_type::<CosmosError>())?;
+    m.add("API_VERSION", cosmos_engine::API_VERSION)?;
+    m.add("__version__", env!("CARGO_PKG_VERSION"))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmos_engine::test_support::repo_with_file;
+    use pyo3::types::{PyDict, PyList};
+
+    #[test]
+    fn test_build_index_returns_a_dict_keyed_by_file() {
+        let dir = repo_with_file();
+        Python::with_gil(|py| {
+            let index = build_index(py, dir.path().to_path_buf()).unwrap();
+            let index = index.bind(py).downcast::<PyDict>().unwrap();
+            let files = index.get_item("files").unwrap().unwrap();
+            assert!(files.contains("src/lib.rs").unwrap());
+        });
+    }
+
+    #[test]
+    fn test_unknown_suggestion_raises_cosmos_error() {
+        let dir = repo_with_file();
+        Python::with_gil(|py| {
+            let engine = PyCosmos::new(
+                py,
+                dir.path().to_path_buf(),
+                Some("unused".to_string()),
+                Some(false),
+            )
+            .unwrap();
+            let index = engine.index(py).unwrap();
+            assert!(index.bind(py).downcast::<PyDict>().is_ok());
+
+            let err = engine
+                .apply_with_harness(py, &Uuid::new_v4().to_string(), None)
+                .unwrap_err();
+            assert!(err.is_instance_of::<CosmosError>(py));
+            assert!(err.to_string().contains("call scan_and_suggest first"));
+            assert!(engine.preview(py, "not-a-uuid").is_err());
+            assert!(to_python(py, &Vec::<Suggestion>::new())
+                .unwrap()
+                .bind(py)
+                .downcast::<PyList>()
+                .is_ok());
+        });
+    }
+}
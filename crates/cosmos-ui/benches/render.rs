@@ -0,0 +1,151 @@
+//! Frame cost of the main panels. Run with `cargo bench -p cosmos-ui`.
+//!
+//! Each case draws a loaded panel into a test terminal over and over, then reports the time
+//! and heap allocations per frame. A case fails when its steady-state allocations go over
+//! budget, so render changes that bring per-frame churn back show up here.
+
+use cosmos_core::context::WorkContext;
+use cosmos_core::index::CodebaseIndex;
+use cosmos_core::suggest::{
+    Priority, Suggestion, SuggestionEngine, SuggestionKind, SuggestionSource,
+};
+use cosmos_engine::llm::ReviewFinding;
+use cosmos_ui::ui::{self, App, WorkflowStep};
+use ratatui::backend::TestBackend;
+use ratatui::Terminal;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+const WARMUP_FRAMES: usize = 5;
+const FRAMES: usize = 200;
+
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+fn main() {
+    let mut failures = Vec::new();
+    for (name, app, budget) in [
+        ("suggestions (2000)", suggestions_app(), 280),
+        ("review (40 findings)", review_app(), 250),
+    ] {
+        let (micros, allocations) = measure(&app);
+        println!(
+            "{:<22} {:>8.1} µs/frame {:>8} allocations/frame (budget {})",
+            name, micros, allocations, budget
+        );
+        if allocations > budget {
+            failures.push(name);
+        }
+    }
+    if !failures.is_empty() {
+        eprintln!("over the allocation budget: {}", failures.join(", "));
+        std::process::exit(1);
+    }
+}
+
+/// Average time and allocations per frame, after a few frames to fill the render caches
+fn measure(app: &App) -> (f64, usize) {
+    let mut terminal = Terminal::new(TestBackend::new(160, 48)).expect("test terminal");
+    for _ in 0..WARMUP_FRAMES {
+        terminal.draw(|f| ui::render(f, app)).expect("draw");
+    }
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    let started = Instant::now();
+    for _ in 0..FRAMES {
+        terminal.draw(|f| ui::render(f, app)).expect("draw");
+    }
+    let micros = started.elapsed().as_secs_f64() * 1e6 / FRAMES as f64;
+    let allocations = (ALLOCATIONS.load(Ordering::Relaxed) - before) / FRAMES;
+    (micros, allocations)
+}
+
+fn base_app() -> App {
+    let root = std::env::temp_dir().join(format!("cosmos_render_bench_{}", std::process::id()));
+    std::fs::create_dir_all(&root).expect("bench dir");
+    let index = CodebaseIndex {
+        root: root.clone(),
+        files: HashMap::new(),
+        index_errors: Vec::new(),
+        git_head: Some("deadbeef".to_string()),
+        packages: Vec::new(),
+        excluded_files: 0,
+        filter_fingerprint: None,
+    };
+    let suggestions = SuggestionEngine::new(index.clone());
+    let context = WorkContext {
+        branch: "main".to_string(),
+        uncommitted_files: Vec::new(),
+        staged_files: Vec::new(),
+        untracked_files: Vec::new(),
+        inferred_focus: None,
+        modified_count: 0,
+        repo_root: root,
+    };
+    App::new(index, suggestions, context)
+}
+
+fn suggestions_app() -> App {
+    let mut app = base_app();
+    for i in 0..2000 {
+        app.suggestions.add_llm_suggestion(Suggestion::new(
+            SuggestionKind::BugFix,
+            Priority::Medium,
+            PathBuf::from(format!("src/module_{}.rs", i)),
+            format!(
+                "Finding {}: the retry loop never backs off, so a flaky upstream gets hammered \
+                 with requests until the deadline passes",
+                i
+            ),
+            SuggestionSource::LlmDeep,
+        ));
+    }
+    app.suggestion_selected = 1000;
+    app.suggestion_scroll = 998;
+    app
+}
+
+fn review_app() -> App {
+    let mut app = base_app();
+    app.workflow_step = WorkflowStep::Review;
+    app.review_state.summary = "Two issues worth fixing before shipping.".to_string();
+    app.review_state.findings = (0..40)
+        .map(|i| ReviewFinding {
+            file: format!("src/module_{}.rs", i),
+            line: Some(10 + i),
+            severity: "warning".to_string(),
+            category: "bug".to_string(),
+            title: format!("Finding {}: retry loop never backs off", i),
+            description: "A flaky upstream gets hammered with requests until the deadline \
+                          passes.\n\nAdd exponential backoff with jitter, and stop retrying \
+                          once the caller's deadline is within one backoff step."
+                .to_string(),
+            recommended: true,
+        })
+        .collect();
+    app.review_state.cursor = 3;
+    app.review_state.selected.insert(1);
+    app
+}
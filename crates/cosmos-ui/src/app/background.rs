@@ -13,12 +13,16 @@
 //!
 
 use crate::app::messages::BackgroundMessage;
+use crate::app::tasks::{TaskHandle, TaskKind};
 use crate::app::RuntimeContext;
 use crate::ui;
 use crate::ui::{App, LoadingState, WorkflowStep};
 use chrono::Utc;
+use cosmos_adapters::analytics;
 use cosmos_adapters::cache;
+use cosmos_adapters::usage;
 use cosmos_adapters::util::truncate;
+use cosmos_engine::llm::SpendCapExceeded;
 use futures::FutureExt;
 use std::future::Future;
 use std::panic::AssertUnwindSafe;
@@ -47,6 +51,27 @@ fn maybe_prompt_api_key_overlay(app: &mut App, message: &str) -> bool {
     true
 }
 
+/// Errors the user has to act on outside Cosmos get their own explanation
+/// instead of a generic failure alert.
+fn maybe_explain_blocking_error(app: &mut App, message: &str) -> bool {
+    maybe_prompt_api_key_overlay(app, message) || maybe_explain_spend_cap(app, message)
+}
+
+fn maybe_explain_spend_cap(app: &mut App, message: &str) -> bool {
+    let Some(start) = message.find(SpendCapExceeded::MESSAGE_PREFIX) else {
+        return false;
+    };
+    app.open_alert(
+        SpendCapExceeded::MESSAGE_PREFIX,
+        format!(
+            "{}\n\nRaise or remove the cap under \"spend_caps\" to let this kind of \
+             operation spend more.",
+            truncate(&message[start..], 240)
+        ),
+    );
+    true
+}
+
 fn suggestions_budget_ms() -> u64 {
     // Keep a bounded default so stalled suggestion runs eventually unwind.
     // Set COSMOS_SUGGEST_MAX_MS=0 to opt into unbounded runs.
@@ -57,45 +82,73 @@ fn suggestions_budget_ms() -> u64 {
         .unwrap_or(DEFAULT_MS)
 }
 
+/// Most recent suggestion ratings loaded for display and for tuning suggestion runs.
+pub const SUGGESTION_FEEDBACK_WINDOW: usize = 200;
+
+#[allow(clippy::too_many_arguments)]
 fn spawn_suggestions_generation(
     tx: mpsc::Sender<BackgroundMessage>,
+    handle: TaskHandle,
     repo_root: PathBuf,
     index: cosmos_core::index::CodebaseIndex,
     context: cosmos_core::context::WorkContext,
     repo_memory_context: String,
     review_focus: cosmos_engine::llm::SuggestionReviewFocus,
+    feedback: Vec<cache::SuggestionFeedbackRecord>,
+    background: bool,
 ) {
     let tx_suggestions = tx.clone();
-    spawn_background(tx.clone(), "suggestions_generation", async move {
+    spawn_cancellable(tx.clone(), "suggestions_generation", handle, async move {
         let stage_start = std::time::Instant::now();
         let mem = if repo_memory_context.trim().is_empty() {
             None
         } else {
             Some(repo_memory_context)
         };
-        let stream_sink = Arc::new(
-            move |worker: String, kind: cosmos_engine::llm::AgenticStreamKind, line: String| {
-                let _ = tx.send(BackgroundMessage::SuggestionsStream { worker, kind, line });
-            },
-        );
-        let mut gate_config = cosmos_engine::llm::SuggestionQualityGateConfig::default();
-        gate_config.min_final_count = 1;
-        // Make one bounded retry when a pass returns zero verified findings.
-        gate_config.max_attempts = 2;
-        gate_config.max_suggest_ms = suggestions_budget_ms();
-        gate_config.review_focus = review_focus;
+        // Background scans run silently; only a visible run streams its progress.
+        let stream_sink: Option<cosmos_engine::llm::SuggestionStreamSink> =
+            (!background).then(|| {
+                Arc::new(
+                    move |worker: String,
+                          kind: cosmos_engine::llm::AgenticStreamKind,
+                          line: String| {
+                        let _ =
+                            tx.send(BackgroundMessage::SuggestionsStream { worker, kind, line });
+                    },
+                ) as cosmos_engine::llm::SuggestionStreamSink
+            });
+        let gate_config = cosmos_engine::llm::SuggestionQualityGateConfig {
+            min_final_count: 1,
+            // Make one bounded retry when a pass returns zero verified findings.
+            max_attempts: 2,
+            max_suggest_ms: suggestions_budget_ms(),
+            review_focus,
+            feedback,
+            ..Default::default()
+        };
         let run = cosmos_engine::llm::run_fast_grounded_with_gate_with_progress_and_stream(
             &repo_root,
             &index,
             &context,
             mem,
             gate_config,
-            Some(stream_sink),
+            stream_sink,
             |_, _, _, _| {},
         )
         .await;
 
         match run {
+            Ok(result) if background => {
+                let _ = tx_suggestions.send(BackgroundMessage::BackgroundScanReady {
+                    model: result.diagnostics.model.clone(),
+                    suggestions: result.suggestions,
+                    usage: result.usage,
+                    diagnostics: result.diagnostics,
+                });
+            }
+            Err(e) if background => {
+                let _ = tx_suggestions.send(BackgroundMessage::BackgroundScanError(e.to_string()));
+            }
             Ok(result) => {
                 let suggestions = result.suggestions;
                 let usage = result.usage;
@@ -151,16 +204,70 @@ pub fn request_suggestions_refresh(
     app.clear_suggestion_stream();
     app.clear_apply_confirm();
 
-    let index = app.index.clone();
-    let context = app.context.clone();
+    // In a monorepo, keep the run focused on the package the user scoped to.
+    let (index, context) = match app.scoped_package() {
+        Some(package) => (
+            app.index.scoped_to_package(package),
+            app.context.scoped_to_package(package),
+        ),
+        None => (app.index.clone(), app.context.clone()),
+    };
+    let repo_memory_context = app.repo_memory.to_prompt_context(12, 900);
+    let feedback = cache::Cache::new(&app.repo_path)
+        .load_recent_suggestion_feedback(SUGGESTION_FEEDBACK_WINDOW)
+        .unwrap_or_default();
+    spawn_suggestions_generation(
+        tx,
+        app.tasks.start(TaskKind::Suggestions),
+        repo_root,
+        index,
+        context,
+        repo_memory_context,
+        app.suggestion_review_focus,
+        feedback,
+        false,
+    );
+    true
+}
+
+/// Start a scheduled re-scan without touching the loading state; its results wait in
+/// `App::pending_rescan` until the user is idle. Returns false if one can't start now.
+pub fn request_background_scan(
+    app: &mut App,
+    tx: mpsc::Sender<BackgroundMessage>,
+    repo_root: PathBuf,
+) -> bool {
+    if !cosmos_engine::llm::is_available() || !app.is_idle_for_background_scan() {
+        return false;
+    }
+    // Re-index so the scan sees edits made since the last run.
+    let Ok(fresh_index) = cosmos_core::index::CodebaseIndex::new(&repo_root) else {
+        return false;
+    };
+    app.replace_index(fresh_index);
+    let (index, context) = match app.scoped_package() {
+        Some(package) => (
+            app.index.scoped_to_package(package),
+            app.context.scoped_to_package(package),
+        ),
+        None => (app.index.clone(), app.context.clone()),
+    };
     let repo_memory_context = app.repo_memory.to_prompt_context(12, 900);
+    let feedback = cache::Cache::new(&app.repo_path)
+        .load_recent_suggestion_feedback(SUGGESTION_FEEDBACK_WINDOW)
+        .unwrap_or_default();
+    // Count the interval from the start so a slow or failing scan isn't retried right away.
+    app.last_suggestion_scan_at = Some(std::time::Instant::now());
     spawn_suggestions_generation(
         tx,
+        app.tasks.start(TaskKind::Suggestions),
         repo_root,
         index,
         context,
         repo_memory_context,
         app.suggestion_review_focus,
+        feedback,
+        true,
     );
     true
 }
@@ -179,36 +286,15 @@ fn handle_suggestions_ready_message(
     ctx: &RuntimeContext,
 ) {
     let run_id = diagnostics.run_id.clone();
-    let validated_count = suggestions
-        .iter()
-        .filter(|s| {
-            s.validation_state == cosmos_core::suggest::SuggestionValidationState::Validated
-        })
-        .count();
-    let cache = cache::Cache::new(&app.repo_path);
-    let run_audit = cache::SuggestionRunAuditRecord {
-        timestamp: Utc::now(),
-        run_id: run_id.clone(),
-        suggestion_count: suggestions.len(),
-        validated_count,
-        rejected_count: diagnostics.rejected_count,
-        model: Some(model.clone()),
-        parse_strategy: Some(diagnostics.parse_strategy.clone()),
-        attempt_index: Some(diagnostics.attempt_index),
-        attempt_count: Some(diagnostics.attempt_count),
-        gate_passed: Some(diagnostics.gate_passed),
-        gate_fail_reasons: diagnostics.gate_fail_reasons.clone(),
-        llm_ms: Some(diagnostics.llm_ms),
-        tool_calls: Some(diagnostics.tool_calls),
-        notes: diagnostics.notes.clone(),
-        response_preview: if diagnostics.response_preview.trim().is_empty() {
-            None
-        } else {
-            Some(diagnostics.response_preview.clone())
-        },
-        suggestions: suggestions.clone(),
-    };
-    let _ = cache.append_suggestion_run_audit(&run_audit);
+    if let Some(health) = cosmos_engine::llm::record_suggestion_run(
+        &app.repo_path,
+        &app.index,
+        &suggestions,
+        &model,
+        &diagnostics,
+    ) {
+        app.record_health(health.score);
+    }
     if !diagnostics.gate_passed {
         restore_loading_after_suggestion_stage(app);
         app.clear_apply_confirm();
@@ -217,6 +303,7 @@ fn handle_suggestions_ready_message(
         } else {
             diagnostics.gate_fail_reasons.join("; ")
         };
+        app.notify_finished("Suggestions withheld by the quality gate");
         app.open_alert(
             "Suggestions withheld",
             format!(
@@ -226,14 +313,18 @@ fn handle_suggestions_ready_message(
         );
         return;
     }
-    let contradiction_counts = cache
+    app.notify_finished(match suggestions.len() {
+        1 => "1 suggestion ready".to_string(),
+        n => format!("{} suggestions ready", n),
+    });
+    let contradiction_counts = cache::Cache::new(&app.repo_path)
         .recent_contradicted_evidence_counts(300)
         .unwrap_or_default();
     app.suggestions.replace_llm_suggestions(suggestions);
     app.suggestions
         .sort_with_context(&app.context, Some(&contradiction_counts));
 
-    let (tokens, cost) = track_usage(app, usage.as_ref(), ctx);
+    let (tokens, cost) = track_usage(app, usage.as_ref(), usage::OPERATION_SUGGESTIONS, ctx);
     record_pipeline_metric(
         app,
         "suggest",
@@ -248,6 +339,38 @@ fn handle_suggestions_ready_message(
     app.active_model = Some(model);
     app.clear_apply_confirm();
     app.current_suggestion_run_id = Some(run_id);
+    // A manual run supersedes any background results and their "new" badges.
+    app.last_suggestion_scan_at = Some(std::time::Instant::now());
+    app.pending_rescan = None;
+    app.new_suggestion_ids.clear();
+}
+
+fn handle_background_scan_ready_message(
+    app: &mut App,
+    suggestions: Vec<cosmos_core::suggest::Suggestion>,
+    usage: Option<cosmos_engine::llm::Usage>,
+    model: String,
+    diagnostics: cosmos_engine::llm::SuggestionDiagnostics,
+    ctx: &RuntimeContext,
+) {
+    if let Some(health) = cosmos_engine::llm::record_suggestion_run(
+        &app.repo_path,
+        &app.index,
+        &suggestions,
+        &model,
+        &diagnostics,
+    ) {
+        app.record_health(health.score);
+    }
+    let _ = track_usage(app, usage.as_ref(), usage::OPERATION_SUGGESTIONS, ctx);
+    // A scan that fails the quality gate leaves the current list alone.
+    if !diagnostics.gate_passed {
+        return;
+    }
+    app.active_model = Some(model);
+    app.current_suggestion_run_id = Some(diagnostics.run_id);
+    app.pending_rescan = Some(suggestions);
+    app.apply_pending_rescan();
 }
 
 fn build_files_with_content_for_review(
@@ -269,6 +392,7 @@ fn build_files_with_content_for_review(
 
 fn spawn_verification_after_direct_fix(
     tx: mpsc::Sender<BackgroundMessage>,
+    handle: TaskHandle,
     files_with_content: Vec<(PathBuf, String, String)>,
     problem_summary: String,
     outcome: String,
@@ -281,7 +405,7 @@ fn spawn_verification_after_direct_fix(
         modified_areas: Vec::new(),
     };
 
-    spawn_background(tx.clone(), "verification", async move {
+    spawn_cancellable(tx.clone(), "verification", handle, async move {
         let review_start = std::time::Instant::now();
         match cosmos_engine::llm::verify_changes(&files_with_content, 1, &[], Some(&fix_context))
             .await
@@ -313,17 +437,21 @@ fn handle_direct_fix_applied_message(
     usage: Option<cosmos_engine::llm::Usage>,
     branch_name: String,
     source_branch: String,
+    undo_run_id: Option<String>,
     friendly_title: String,
     problem_summary: String,
     outcome: String,
+    harness_evidence: Option<cosmos_engine::llm::HarnessEvidence>,
     duration_ms: u64,
     ctx: &RuntimeContext,
 ) {
-    let (tokens, cost) = track_usage(app, usage.as_ref(), ctx);
+    let (tokens, cost) = track_usage(app, usage.as_ref(), usage::OPERATION_FIXES, ctx);
     record_pipeline_metric(app, "apply", duration_ms, tokens, cost, "apply_fix", true);
 
     app.loading = LoadingState::None;
+    app.notify_finished(format!("Fix applied: {}", friendly_title));
     app.suggestions.mark_applied(suggestion_id);
+    app.record_cosmos_branch(&branch_name, Some(&source_branch));
     app.cosmos_branch = Some(branch_name);
     app.cosmos_base_branch = Some(source_branch);
 
@@ -331,17 +459,21 @@ fn handle_direct_fix_applied_message(
         .iter()
         .map(|(path, diff)| ui::FileChange::new(path.clone(), diff.clone()))
         .collect();
-    app.pending_changes
-        .push(ui::PendingChange::with_preview_context_multi(
+    app.pending_changes.push(
+        ui::PendingChange::with_preview_context_multi(
             suggestion_id,
             ui_file_changes,
             description.clone(),
             friendly_title,
             problem_summary.clone(),
             outcome.clone(),
-        ));
+        )
+        .with_undo_run_id(undo_run_id)
+        .with_harness_evidence(harness_evidence),
+    );
 
     let files_with_content = build_files_with_content_for_review(&app.repo_path, &file_changes);
+    app.rebase_suggestions_after_apply(&files_with_content);
     let review_files = files_with_content
         .iter()
         .map(|(path, original, new_content)| ui::ReviewFileContent {
@@ -355,6 +487,7 @@ fn handle_direct_fix_applied_message(
 
     spawn_verification_after_direct_fix(
         ctx.tx.clone(),
+        app.tasks.start(TaskKind::Review),
         files_with_content,
         problem_summary,
         outcome,
@@ -390,11 +523,12 @@ fn apply_review_fix_file_changes(
 
 fn spawn_reverification(
     tx: mpsc::Sender<BackgroundMessage>,
+    handle: TaskHandle,
     files_with_content: Vec<(PathBuf, String, String)>,
     iteration: u32,
     fixed_titles: Vec<String>,
 ) {
-    spawn_background(tx.clone(), "re_verification", async move {
+    spawn_cancellable(tx.clone(), "re_verification", handle, async move {
         let review_start = std::time::Instant::now();
         match cosmos_engine::llm::verify_changes(
             &files_with_content,
@@ -429,7 +563,7 @@ fn handle_verification_fix_complete_message(
     duration_ms: u64,
     ctx: &RuntimeContext,
 ) {
-    let (tokens, cost) = track_usage(app, usage.as_ref(), ctx);
+    let (tokens, cost) = track_usage(app, usage.as_ref(), usage::OPERATION_FIXES, ctx);
     record_pipeline_metric(
         app,
         "review",
@@ -467,7 +601,26 @@ fn handle_verification_fix_complete_message(
 
     app.review_state.reviewing = true;
     app.loading = LoadingState::ReviewingChanges;
-    spawn_reverification(ctx.tx.clone(), files_with_content, iteration, fixed_titles);
+    spawn_reverification(
+        ctx.tx.clone(),
+        app.tasks.start(TaskKind::Review),
+        files_with_content,
+        iteration,
+        fixed_titles,
+    );
+}
+
+fn handle_provider_unavailable_message(app: &mut App, error: String) {
+    if maybe_explain_blocking_error(app, &error) {
+        return;
+    }
+    app.open_alert(
+        "AI provider unavailable",
+        format!(
+            "AI actions will fail until this is resolved: {}",
+            truncate(&error, 200)
+        ),
+    );
 }
 
 fn handle_background_error_message(app: &mut App, error: String) {
@@ -481,7 +634,7 @@ fn handle_background_error_message(app: &mut App, error: String) {
         app.review_state.fixing = false;
     }
 
-    if maybe_prompt_api_key_overlay(app, &error) {
+    if maybe_explain_blocking_error(app, &error) {
         return;
     }
     if error.contains("verification failed") || error.contains("Re-verification failed") {
@@ -504,7 +657,8 @@ fn handle_background_error_message(app: &mut App, error: String) {
 
 fn handle_suggestions_error_message(app: &mut App, error: String) {
     restore_loading_after_suggestion_stage(app);
-    if !maybe_prompt_api_key_overlay(app, &error) {
+    app.notify_finished("Suggestion run failed");
+    if !maybe_explain_blocking_error(app, &error) {
         app.open_alert(
             "Suggestions failed",
             format!("Couldn't generate suggestions: {}", error),
@@ -536,7 +690,7 @@ fn handle_grouping_enhanced_message(
     if updated_files > 0 {
         app.apply_grouping_update(grouping);
     }
-    let _ = track_usage(app, usage.as_ref(), ctx);
+    let _ = track_usage(app, usage.as_ref(), usage::OPERATION_GROUPING, ctx);
     if updated_files > 0 {
         app.active_model = Some(model);
     }
@@ -551,7 +705,7 @@ fn handle_preview_ready_message(
     ctx: &RuntimeContext,
 ) {
     app.loading = LoadingState::None;
-    let (tokens, cost) = track_usage(app, usage.as_ref(), ctx);
+    let (tokens, cost) = track_usage(app, usage.as_ref(), usage::OPERATION_FIXES, ctx);
     let gate = match preview.verification_state {
         cosmos_core::suggest::VerificationState::Verified => "verified",
         cosmos_core::suggest::VerificationState::Contradicted => "contradicted",
@@ -610,7 +764,7 @@ fn handle_preview_error_message(app: &mut App, error: String) {
     app.loading = LoadingState::None;
     app.workflow_step = WorkflowStep::Suggestions;
     app.verify_state = ui::VerifyState::default();
-    if !maybe_prompt_api_key_overlay(app, &error) {
+    if !maybe_explain_blocking_error(app, &error) {
         app.open_alert(
             "Preview failed",
             format!("Couldn't prepare a safe preview: {}", truncate(&error, 120)),
@@ -629,6 +783,7 @@ fn handle_apply_harness_failed_message(
     report_path: Option<PathBuf>,
 ) {
     app.loading = LoadingState::None;
+    app.notify_finished("Fix couldn't be applied");
     app.workflow_step = WorkflowStep::Suggestions;
     app.verify_state = ui::VerifyState::default();
     app.clear_apply_confirm();
@@ -647,6 +802,13 @@ fn handle_apply_harness_failed_message(
     if let Some(path) = report_path {
         detail = format!("{}. See report at {}", detail, path.display());
     }
+    if let Some(capped) = fail_reasons
+        .iter()
+        .find(|reason| reason.contains(SpendCapExceeded::MESSAGE_PREFIX))
+    {
+        maybe_explain_spend_cap(app, capped);
+        return;
+    }
     app.open_alert(
         "Apply failed",
         format!(
@@ -658,10 +820,11 @@ fn handle_apply_harness_failed_message(
 
 fn handle_direct_fix_error_message(app: &mut App, error: String) {
     app.loading = LoadingState::None;
+    app.notify_finished("Fix couldn't be applied");
     app.workflow_step = WorkflowStep::Suggestions;
     app.verify_state = ui::VerifyState::default();
     app.clear_apply_confirm();
-    if !maybe_prompt_api_key_overlay(app, &error) {
+    if !maybe_explain_blocking_error(app, &error) {
         app.open_alert(
             "Apply failed",
             format!(
@@ -681,6 +844,7 @@ fn handle_ship_progress_message(app: &mut App, step: ui::ShipStep) {
 }
 
 fn handle_ship_complete_message(app: &mut App, url: String) {
+    analytics::record(&app.repo_path, "ship", Some(true), &[]);
     if app.workflow_step == WorkflowStep::Ship {
         app.set_ship_pr_url(url);
     } else {
@@ -690,7 +854,32 @@ fn handle_ship_complete_message(app: &mut App, url: String) {
     }
 }
 
+fn handle_ship_hook_result_message(app: &mut App, run: cosmos_adapters::git_ops::PreCommitHookRun) {
+    if !run.passed {
+        analytics::record(
+            &app.repo_path,
+            "ship",
+            Some(false),
+            &["pre_commit_hook".to_string()],
+        );
+    }
+    if app.workflow_step == WorkflowStep::Ship {
+        app.set_ship_hook_run(run);
+    } else if !run.passed {
+        app.ship_step = None;
+        app.open_alert(
+            "Pre-commit hook failed",
+            format!(
+                "The {} hook blocked the commit: {}",
+                run.kind.label(),
+                truncate(&run.output, 240)
+            ),
+        );
+    }
+}
+
 fn handle_ship_error_message(app: &mut App, error: String) {
+    analytics::record(&app.repo_path, "ship", Some(false), &[]);
     app.ship_step = None;
     app.close_overlay();
     app.open_alert(
@@ -703,11 +892,12 @@ fn handle_ship_error_message(app: &mut App, error: String) {
 }
 
 fn handle_question_error_message(app: &mut App, request_id: u64, error: String) {
+    analytics::record(&app.repo_path, "ask", Some(false), &[]);
     let is_active = app.complete_ask_request(request_id);
     if !is_active {
         return;
     }
-    if !maybe_prompt_api_key_overlay(app, &error) {
+    if !maybe_explain_blocking_error(app, &error) {
         app.show_inquiry(format!(
             "Couldn't answer that right now.\n\n{}",
             truncate(&error, 180)
@@ -715,6 +905,7 @@ fn handle_question_error_message(app: &mut App, request_id: u64, error: String)
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn handle_question_response_with_cache_message(
     app: &mut App,
     request_id: u64,
@@ -722,23 +913,26 @@ fn handle_question_response_with_cache_message(
     answer: String,
     usage: Option<cosmos_engine::llm::Usage>,
     context_hash: String,
+    context_report: cosmos_engine::llm::AskContextReport,
     ctx: &RuntimeContext,
 ) {
     let _ = track_usage_for_ask(app, usage.as_ref(), ctx);
     app.question_cache
-        .set(question, answer.clone(), context_hash);
+        .set(question.clone(), answer.clone(), context_hash);
     let cache = cache::Cache::new(&app.repo_path);
     let _ = cache.save_question_cache(&app.question_cache);
 
     if !app.complete_ask_request(request_id) {
         return;
     }
-    app.show_inquiry(answer);
+    app.show_chat_answer(question, answer, Some(context_report));
+    let _ = app.chat_session.save(&cache);
 }
 
 fn handle_question_response_message(
     app: &mut App,
     request_id: u64,
+    question: String,
     answer: String,
     usage: Option<cosmos_engine::llm::Usage>,
     ctx: &RuntimeContext,
@@ -747,7 +941,8 @@ fn handle_question_response_message(
     if !app.complete_ask_request(request_id) {
         return;
     }
-    app.show_inquiry(answer);
+    app.show_chat_answer(question, answer, None);
+    let _ = app.chat_session.save(&cache::Cache::new(&app.repo_path));
 }
 
 fn handle_verification_complete_message(
@@ -758,7 +953,7 @@ fn handle_verification_complete_message(
     duration_ms: u64,
     ctx: &RuntimeContext,
 ) {
-    let (tokens, cost) = track_usage(app, usage.as_ref(), ctx);
+    let (tokens, cost) = track_usage(app, usage.as_ref(), usage::OPERATION_REVIEWS, ctx);
     record_pipeline_metric(
         app,
         "review",
@@ -771,6 +966,146 @@ fn handle_verification_complete_message(
     app.set_review_findings(findings, summary);
 }
 
+/// Fetch an open pull request and run the adversarial review over its diff.
+pub fn spawn_pr_review(
+    tx: mpsc::Sender<BackgroundMessage>,
+    handle: TaskHandle,
+    repo_path: PathBuf,
+    reference: cosmos_adapters::github::PrReference,
+) {
+    use cosmos_core::protocol::{AppliedFile, ChangeSet, Engine, FixContext};
+
+    spawn_cancellable(tx.clone(), "pr_review", handle, async move {
+        let result = async {
+            let (owner, repo) = match reference.repo {
+                Some(repo) => repo,
+                None => cosmos_adapters::github::get_remote_info(&repo_path)?,
+            };
+            let pull_request =
+                cosmos_adapters::github::fetch_pull_request(&owner, &repo, reference.number)
+                    .await?;
+            let applied = |path: &str, content: &str| AppliedFile {
+                path: PathBuf::from(path),
+                content: content.to_string(),
+            };
+            let change_set = ChangeSet {
+                files: pull_request
+                    .files
+                    .iter()
+                    .map(|f| applied(&f.path, &f.content))
+                    .collect(),
+                originals: pull_request
+                    .files
+                    .iter()
+                    .map(|f| applied(&f.path, &f.original))
+                    .collect(),
+            };
+            let context = FixContext {
+                problem_summary: pull_request.title.clone(),
+                outcome: String::new(),
+                description: pull_request.body.clone(),
+            };
+            let report = cosmos_engine::CosmosEngine
+                .adversarial_review(&change_set, &context)
+                .await?;
+            anyhow::Ok((pull_request, report))
+        }
+        .await;
+
+        let message = match result {
+            Ok((pull_request, report)) => BackgroundMessage::PrReviewReady {
+                pull_request,
+                findings: report
+                    .findings
+                    .into_iter()
+                    .map(|f| cosmos_engine::llm::ReviewFinding {
+                        recommended: matches!(f.severity.as_str(), "critical" | "warning"),
+                        file: f.file,
+                        line: f.line.map(|line| line as u32),
+                        severity: f.severity,
+                        category: String::new(),
+                        title: f.title,
+                        description: f.detail,
+                    })
+                    .collect(),
+                summary: report.summary,
+            },
+            Err(e) => BackgroundMessage::PrReviewError(e.to_string()),
+        };
+        let _ = tx.send(message);
+    });
+}
+
+fn handle_pr_review_error_message(app: &mut App, error: String) {
+    analytics::record(&app.repo_path, "pr_review", Some(false), &[]);
+    app.loading = LoadingState::None;
+    app.review_state.reviewing = false;
+    app.review_state.pr_number = None;
+    app.workflow_step = WorkflowStep::Suggestions;
+    if !maybe_explain_blocking_error(app, &error) {
+        app.open_alert(
+            "PR review failed",
+            format!(
+                "Couldn't review the pull request: {}",
+                truncate(&error, 160)
+            ),
+        );
+    }
+}
+
+fn handle_pr_review_posted_message(app: &mut App, url: String) {
+    app.review_state.posting = false;
+    app.open_alert("Review posted", format!("Posted to {}", url));
+}
+
+fn handle_pr_review_post_error_message(app: &mut App, error: String) {
+    app.review_state.posting = false;
+    app.open_alert(
+        "Couldn't post review",
+        format!("GitHub rejected the review: {}", truncate(&error, 160)),
+    );
+}
+
+fn handle_task_cancelled_message(app: &mut App, kind: TaskKind) {
+    if !app.tasks.take_cancelled(kind) {
+        return;
+    }
+    match kind {
+        TaskKind::Suggestions => {
+            restore_loading_after_suggestion_stage(app);
+            app.clear_suggestion_stream();
+        }
+        TaskKind::Fix if app.review_state.fixing => {
+            app.review_state.fixing = false;
+            app.loading = LoadingState::None;
+        }
+        TaskKind::Fix => {
+            app.loading = LoadingState::None;
+            app.workflow_step = WorkflowStep::Suggestions;
+            app.verify_state = ui::VerifyState::default();
+            app.clear_apply_confirm();
+        }
+        TaskKind::Review if app.review_state.pr_number.is_some() => {
+            app.loading = LoadingState::None;
+            app.review_state.reviewing = false;
+            app.review_state.pr_number = None;
+            app.workflow_step = WorkflowStep::Suggestions;
+        }
+        TaskKind::Review => {
+            app.loading = LoadingState::None;
+            app.review_state.reviewing = false;
+            app.review_state.verification_failed = true;
+            app.review_state.verification_error = Some("Review cancelled".to_string());
+            app.review_state.confirm_ship = false;
+            if app.review_state.summary.is_empty() {
+                app.review_state.summary =
+                    "Review cancelled before completion. Review manually before shipping."
+                        .to_string();
+            }
+        }
+    }
+}
+
 fn handle_update_progress_message(app: &mut App, percent: u8) {
     app.update_progress = Some(percent);
     app.set_update_progress(percent);
@@ -813,6 +1148,16 @@ fn handle_generation_messages(
             handle_suggestions_stream_message(app, worker, kind, line);
             None
         }
+        BackgroundMessage::BackgroundScanReady {
+            suggestions,
+            usage,
+            model,
+            diagnostics,
+        } => {
+            handle_background_scan_ready_message(app, suggestions, usage, model, diagnostics, ctx);
+            None
+        }
+        BackgroundMessage::BackgroundScanError(_error) => None,
         BackgroundMessage::GroupingEnhanced {
             grouping,
             updated_files,
@@ -823,6 +1168,32 @@ fn handle_generation_messages(
             None
         }
         BackgroundMessage::GroupingEnhanceError(_error) => None,
+        BackgroundMessage::CommitMessageReady { message, usage } => {
+            let _ = track_usage(app, usage.as_ref(), usage::OPERATION_SUMMARIES, ctx);
+            app.set_generated_commit_message(message);
+            None
+        }
+        BackgroundMessage::CommitMessageError(error) => {
+            app.commit_message_generation_failed(&error);
+            None
+        }
+        BackgroundMessage::DirectorySummaryReady { summary, usage } => {
+            let _ = track_usage(app, usage.as_ref(), usage::OPERATION_SUMMARIES, ctx);
+            app.show_toast(format!("Summary rewritten for {}/", summary.path.display()));
+            app.directory_summaries
+                .entries
+                .insert(summary.path.clone(), summary);
+            let _ = cache::Cache::new(&app.repo_path)
+                .save_directory_summaries(&app.directory_summaries);
+            None
+        }
+        BackgroundMessage::DirectorySummaryError(error) => {
+            app.show_toast(format!(
+                "Couldn't rewrite the summary: {}",
+                truncate(&error, 80)
+            ));
+            None
+        }
         other => Some(other),
     }
 }
@@ -864,6 +1235,10 @@ fn handle_apply_messages(
             handle_apply_harness_progress_message(app);
             None
         }
+        BackgroundMessage::CheckOutput(line) => {
+            app.push_command_log_line(line);
+            None
+        }
         BackgroundMessage::ApplyHarnessFailed {
             summary,
             fail_reasons,
@@ -876,6 +1251,15 @@ fn handle_apply_messages(
             detail: _,
             report_path: _,
         } => None,
+        BackgroundMessage::ApplyHunkSelection { pending, diffs } => {
+            app.loading = LoadingState::None;
+            app.notify_finished("Fix ready: pick the hunks to keep");
+            app.pending_fix_apply = Some(pending);
+            app.overlay = ui::Overlay::Diff {
+                view: Box::new(ui::diff::DiffView::for_fix_selection(diffs)),
+            };
+            None
+        }
         BackgroundMessage::DirectFixApplied {
             suggestion_id,
             file_changes,
@@ -883,9 +1267,11 @@ fn handle_apply_messages(
             usage,
             branch_name,
             source_branch,
+            undo_run_id,
             friendly_title,
             problem_summary,
             outcome,
+            harness_evidence,
             duration_ms,
         } => {
             handle_direct_fix_applied_message(
@@ -896,9 +1282,11 @@ fn handle_apply_messages(
                 usage,
                 branch_name,
                 source_branch,
+                undo_run_id,
                 friendly_title,
                 problem_summary,
                 outcome,
+                harness_evidence,
                 duration_ms,
                 ctx,
             );
@@ -922,14 +1310,36 @@ fn handle_ship_repo_messages(app: &mut App, msg: BackgroundMessage) -> Option<Ba
             handle_ship_complete_message(app, url);
             None
         }
+        BackgroundMessage::ShipHookResult(run) => {
+            handle_ship_hook_result_message(app, run);
+            None
+        }
         BackgroundMessage::ShipError(error) => {
             handle_ship_error_message(app, error);
             None
         }
+        BackgroundMessage::ShipStacked => {
+            analytics::record(&app.repo_path, "ship", Some(true), &[]);
+            app.push_stack_entry();
+            None
+        }
+        BackgroundMessage::StackPrCreated { index, url } => {
+            app.finish_stack_pr(index, Some(url));
+            None
+        }
+        BackgroundMessage::StackPrError { index, error } => {
+            app.finish_stack_pr(index, None);
+            app.open_alert(
+                "Couldn't open pull request",
+                format!("Stack entry {}: {}", index + 1, truncate(&error, 160)),
+            );
+            None
+        }
         BackgroundMessage::ResetComplete { options } => {
             app.loading = LoadingState::None;
             if options.contains(&cosmos_adapters::cache::ResetOption::QuestionCache) {
                 app.question_cache = cosmos_adapters::cache::QuestionCache::default();
+                app.chat_session = cosmos_engine::llm::ChatSession::default();
             }
             None
         }
@@ -963,6 +1373,7 @@ fn handle_misc_messages(app: &mut App, msg: BackgroundMessage, ctx: &RuntimeCont
             answer,
             usage,
             context_hash,
+            context_report,
         } => {
             handle_question_response_with_cache_message(
                 app,
@@ -971,15 +1382,17 @@ fn handle_misc_messages(app: &mut App, msg: BackgroundMessage, ctx: &RuntimeCont
                 answer,
                 usage,
                 context_hash,
+                context_report,
                 ctx,
             );
         }
         BackgroundMessage::QuestionResponse {
             request_id,
+            question,
             answer,
             usage,
         } => {
-            handle_question_response_message(app, request_id, answer, usage, ctx);
+            handle_question_response_message(app, request_id, question, answer, usage, ctx);
         }
         BackgroundMessage::VerificationComplete {
             findings,
@@ -997,9 +1410,51 @@ fn handle_misc_messages(app: &mut App, msg: BackgroundMessage, ctx: &RuntimeCont
         } => {
             handle_verification_fix_complete_message(app, file_changes, usage, duration_ms, ctx);
         }
+        BackgroundMessage::PrReviewReady {
+            pull_request,
+            findings,
+            summary,
+        } => {
+            analytics::record(&app.repo_path, "pr_review", Some(true), &[]);
+            app.set_pr_review(pull_request, findings, summary);
+        }
+        BackgroundMessage::PrReviewError(error) => {
+            handle_pr_review_error_message(app, error);
+        }
+        BackgroundMessage::PrReviewPosted(url) => {
+            handle_pr_review_posted_message(app, url);
+        }
+        BackgroundMessage::PrReviewPostError(error) => {
+            handle_pr_review_post_error_message(app, error);
+        }
         BackgroundMessage::UpdateAvailable { latest_version } => {
             app.update_available = Some(latest_version);
         }
+        BackgroundMessage::FollowUpPrFound(pr) => {
+            app.follow_up_pr = Some(pr);
+        }
+        BackgroundMessage::RebaseProgress(detail) => {
+            app.show_toast(detail);
+        }
+        BackgroundMessage::RebaseComplete(outcome) => {
+            let _ = track_usage(app, outcome.usage.as_ref(), usage::OPERATION_FIXES, ctx);
+            app.finish_rebase(Ok(outcome));
+        }
+        BackgroundMessage::RebaseError(error) => {
+            app.finish_rebase(Err(error));
+        }
+        BackgroundMessage::BranchStatesReady(states) => {
+            app.set_branch_states(states);
+        }
+        BackgroundMessage::BranchDeleted { branch, result } => {
+            app.finish_branch_delete(&branch, result);
+        }
+        BackgroundMessage::TaskCancelled(kind) => {
+            handle_task_cancelled_message(app, kind);
+        }
+        BackgroundMessage::ProviderUnavailable(error) => {
+            handle_provider_unavailable_message(app, error);
+        }
         BackgroundMessage::UpdateProgress { percent } => {
             handle_update_progress_message(app, percent);
         }
@@ -1009,18 +1464,30 @@ fn handle_misc_messages(app: &mut App, msg: BackgroundMessage, ctx: &RuntimeCont
         BackgroundMessage::SuggestionsReady { .. }
         | BackgroundMessage::SuggestionsError(_)
         | BackgroundMessage::SuggestionsStream { .. }
+        | BackgroundMessage::BackgroundScanReady { .. }
+        | BackgroundMessage::BackgroundScanError(_)
         | BackgroundMessage::GroupingEnhanced { .. }
         | BackgroundMessage::GroupingEnhanceError(_)
+        | BackgroundMessage::CommitMessageReady { .. }
+        | BackgroundMessage::CommitMessageError(_)
+        | BackgroundMessage::DirectorySummaryReady { .. }
+        | BackgroundMessage::DirectorySummaryError(_)
         | BackgroundMessage::PreviewReady { .. }
         | BackgroundMessage::PreviewError(_)
         | BackgroundMessage::ApplyHarnessProgress { .. }
+        | BackgroundMessage::CheckOutput(_)
         | BackgroundMessage::ApplyHarnessFailed { .. }
         | BackgroundMessage::ApplyHarnessReducedConfidence { .. }
+        | BackgroundMessage::ApplyHunkSelection { .. }
         | BackgroundMessage::DirectFixApplied { .. }
         | BackgroundMessage::DirectFixError(_)
         | BackgroundMessage::ShipProgress(_)
         | BackgroundMessage::ShipComplete(_)
+        | BackgroundMessage::ShipHookResult(_)
         | BackgroundMessage::ShipError(_)
+        | BackgroundMessage::ShipStacked
+        | BackgroundMessage::StackPrCreated { .. }
+        | BackgroundMessage::StackPrError { .. }
         | BackgroundMessage::ResetComplete { .. }
         | BackgroundMessage::StashComplete { .. }
         | BackgroundMessage::DiscardComplete
@@ -1063,9 +1530,10 @@ pub fn drain_messages(
 fn track_usage(
     app: &mut App,
     usage: Option<&cosmos_engine::llm::Usage>,
+    operation: &str,
     ctx: &RuntimeContext,
 ) -> (u32, f64) {
-    track_usage_internal(app, usage, ctx, true)
+    track_usage_internal(app, usage, operation, ctx, true)
 }
 
 fn track_usage_for_ask(
@@ -1073,12 +1541,14 @@ fn track_usage_for_ask(
     usage: Option<&cosmos_engine::llm::Usage>,
     ctx: &RuntimeContext,
 ) -> (u32, f64) {
-    track_usage_internal(app, usage, ctx, false)
+    analytics::record(&app.repo_path, "ask", Some(true), &[]);
+    track_usage_internal(app, usage, usage::OPERATION_SUMMARIES, ctx, false)
 }
 
 fn track_usage_internal(
     app: &mut App,
     usage: Option<&cosmos_engine::llm::Usage>,
+    operation: &str,
     _ctx: &RuntimeContext,
     show_budget_guardrails: bool,
 ) -> (u32, f64) {
@@ -1086,6 +1556,16 @@ fn track_usage_internal(
         return (0, 0.0);
     };
 
+    usage::record(
+        &app.repo_path,
+        operation,
+        usage.model.as_deref().unwrap_or("unknown"),
+        usage.prompt_tokens,
+        usage.completion_tokens,
+        usage.total_tokens,
+        usage.cost,
+    );
+
     let cost = usage.cost();
     app.session_cost += cost;
     app.session_tokens += usage.total_tokens;
@@ -1137,6 +1617,12 @@ fn record_pipeline_metric(
     }
 
     let _ = cache.append_pipeline_metric(&metric);
+    let failed_gates = if passed {
+        Vec::new()
+    } else {
+        vec![gate.to_string()]
+    };
+    analytics::record(&app.repo_path, stage, Some(passed), &failed_gates);
 }
 
 pub fn spawn_background<F>(tx: mpsc::Sender<BackgroundMessage>, task_name: &'static str, fut: F)
@@ -1160,6 +1646,28 @@ where
     });
 }
 
+/// Spawn a job the user can cancel with `x`. A cancelled job is dropped at its next await
+/// point and reports `TaskCancelled` instead of its result.
+pub fn spawn_cancellable<F>(
+    tx: mpsc::Sender<BackgroundMessage>,
+    task_name: &'static str,
+    handle: TaskHandle,
+    fut: F,
+) where
+    F: Future<Output = ()> + Send + 'static,
+{
+    let tx_cancelled = tx.clone();
+    spawn_background(tx, task_name, async move {
+        tokio::select! {
+            _ = handle.token.cancelled() => {
+                let _ = tx_cancelled.send(BackgroundMessage::TaskCancelled(handle.kind));
+            }
+            _ = fut => {}
+        }
+        handle.mark_finished();
+    });
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1183,6 +1691,9 @@ mod tests {
             files: HashMap::new(),
             index_errors: Vec::new(),
             git_head: Some("deadbeef".to_string()),
+            packages: Vec::new(),
+            excluded_files: 0,
+            filter_fingerprint: None,
         };
         let suggestions = SuggestionEngine::new(index.clone());
         let context = WorkContext {
@@ -1206,12 +1717,14 @@ mod tests {
         let (tx, rx) = mpsc::channel();
         tx.send(BackgroundMessage::QuestionResponse {
             request_id: stale_id,
+            question: "q".to_string(),
             answer: "stale".to_string(),
             usage: None,
         })
         .unwrap();
         tx.send(BackgroundMessage::QuestionResponse {
             request_id: active_id,
+            question: "q".to_string(),
             answer: "active".to_string(),
             usage: None,
         })
@@ -1257,4 +1770,50 @@ mod tests {
         assert_eq!(app.active_ask_request_id, Some(request_id));
         assert!(app.ask_cosmos_state.is_none());
     }
+
+    #[test]
+    fn cancelled_fix_reclaims_loading_state() {
+        let mut app = make_test_app();
+        app.loading = LoadingState::GeneratingFix;
+        let handle = app.tasks.start(TaskKind::Fix);
+        assert_eq!(app.tasks.cancel_active(), Some(TaskKind::Fix));
+        assert!(handle.token.is_cancelled());
+
+        let (tx, rx) = mpsc::channel();
+        tx.send(BackgroundMessage::TaskCancelled(TaskKind::Fix))
+            .unwrap();
+
+        let index = app.index.clone();
+        let repo_path = app.repo_path.clone();
+        let ctx = RuntimeContext {
+            index: &index,
+            repo_path: &repo_path,
+            tx: &tx,
+        };
+        drain_messages(&mut app, &rx, &ctx);
+
+        assert_eq!(app.loading, LoadingState::None);
+        assert_eq!(app.workflow_step, WorkflowStep::Suggestions);
+        assert_eq!(app.tasks.active(), None);
+        assert_eq!(app.overlay, ui::Overlay::None);
+    }
+
+    #[test]
+    fn spend_cap_errors_explain_the_cap_instead_of_a_generic_failure() {
+        let mut app = make_test_app();
+        let capped = SpendCapExceeded {
+            operation_id: "implement-3".to_string(),
+            role: "implement",
+            spent_usd: 0.16,
+            cap_usd: 0.15,
+        };
+        handle_direct_fix_error_message(&mut app, format!("Fix generation failed: {}", capped));
+
+        let ui::Overlay::Alert { title, message, .. } = &app.overlay else {
+            panic!("expected an alert, got {:?}", app.overlay);
+        };
+        assert_eq!(title, SpendCapExceeded::MESSAGE_PREFIX);
+        assert!(message.starts_with(SpendCapExceeded::MESSAGE_PREFIX));
+        assert!(message.contains("spend_caps"));
+    }
 }
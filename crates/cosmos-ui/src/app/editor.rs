@@ -0,0 +1,126 @@
+//! Open files in the user's editor from the TUI.
+//!
+//! The terminal is handed over to `$VISUAL` / `$EDITOR` while it runs and restored
+//! afterwards; the index and git context are refreshed so edits show up immediately.
+
+use crate::ui::{App, EditorRequest};
+use anyhow::{Context, Result};
+use cosmos_core::index::CodebaseIndex;
+use crossterm::{
+    event::{DisableFocusChange, DisableMouseCapture, EnableFocusChange, EnableMouseCapture},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::prelude::*;
+use std::io;
+use std::path::Path;
+use std::process::Command;
+
+/// Editor used when neither `$VISUAL` nor `$EDITOR` is set
+const FALLBACK_EDITOR: &str = "vi";
+
+/// Build the editor command line for `path`, jumping to `line` when given.
+///
+/// Most terminal editors take `+N`; GUI editors that use `path:N` are special-cased.
+pub fn editor_command(editor: &str, path: &Path, line: Option<usize>) -> Vec<String> {
+    let mut parts: Vec<String> = editor.split_whitespace().map(str::to_string).collect();
+    if parts.is_empty() {
+        parts.push(FALLBACK_EDITOR.to_string());
+    }
+    let program = Path::new(&parts[0])
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or_default()
+        .to_string();
+    let path = path.to_string_lossy().to_string();
+    match (line, program.as_str()) {
+        (Some(line), "code" | "code-insiders" | "codium" | "cursor") => {
+            parts.push("--wait".to_string());
+            parts.push("--goto".to_string());
+            parts.push(format!("{}:{}", path, line));
+        }
+        (Some(line), "subl" | "zed") => {
+            parts.push("--wait".to_string());
+            parts.push(format!("{}:{}", path, line));
+        }
+        (Some(line), _) => {
+            parts.push(format!("+{}", line));
+            parts.push(path);
+        }
+        (None, _) => parts.push(path),
+    }
+    parts
+}
+
+fn configured_editor() -> String {
+    ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.trim().is_empty())
+        .unwrap_or_else(|| FALLBACK_EDITOR.to_string())
+}
+
+/// Suspend the TUI, run the editor, then restore the terminal and refresh repo state.
+pub fn open_in_editor<B: Backend>(
+    terminal: &mut Terminal<B>,
+    app: &mut App,
+    request: &EditorRequest,
+    repo_path: &Path,
+) -> Result<()> {
+    let command = editor_command(&configured_editor(), &request.path, request.line);
+
+    disable_raw_mode()?;
+    execute!(
+        io::stdout(),
+        LeaveAlternateScreen,
+        DisableMouseCapture,
+        DisableFocusChange
+    )?;
+    let status = Command::new(&command[0])
+        .args(&command[1..])
+        .current_dir(repo_path)
+        .status()
+        .with_context(|| format!("Failed to start editor '{}'", command[0]));
+    enable_raw_mode()?;
+    execute!(
+        io::stdout(),
+        EnterAlternateScreen,
+        EnableMouseCapture,
+        EnableFocusChange
+    )?;
+    terminal.clear()?;
+
+    let _ = app.context.refresh();
+    if let Ok(fresh) = CodebaseIndex::new(repo_path) {
+        app.replace_index(fresh);
+    }
+
+    match status {
+        Ok(status) if status.success() => {}
+        Ok(status) => app.open_alert(
+            "Editor exited with an error",
+            format!("'{}' exited with {}", command[0], status),
+        ),
+        Err(e) => app.open_alert("Couldn't open editor", format!("{:#}", e)),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn editor_command_uses_plus_line_or_goto_style() {
+        let path = Path::new("src/lib.rs");
+        assert_eq!(
+            editor_command("nvim", path, Some(42)),
+            vec!["nvim", "+42", "src/lib.rs"]
+        );
+        assert_eq!(
+            editor_command("/usr/bin/code -n", path, Some(7)),
+            vec!["/usr/bin/code", "-n", "--wait", "--goto", "src/lib.rs:7"]
+        );
+        assert_eq!(editor_command("  ", path, None), vec!["vi", "src/lib.rs"]);
+    }
+}
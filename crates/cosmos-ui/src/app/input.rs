@@ -7,19 +7,24 @@
 //! shutdown, which is expected and safe to ignore.
 
 use crate::app::RuntimeContext;
+use crate::ui::keymap::Action;
 use crate::ui::{App, InputMode, Overlay};
 use anyhow::Result;
-use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::event::KeyEvent;
 
 mod normal;
 mod overlay;
 mod question;
+mod review;
 mod search;
+mod ship;
 
 use normal::handle_normal_mode;
 use overlay::handle_overlay_input;
 use question::handle_question_input;
+use review::handle_finding_field_input;
 use search::handle_search_input;
+use ship::handle_ship_field_input;
 
 // ═══════════════════════════════════════════════════════════════════════════
 //  MAIN INPUT DISPATCHER
@@ -28,9 +33,13 @@ use search::handle_search_input;
 /// Main key event handler - dispatches to mode-specific handlers
 pub fn handle_key_event(app: &mut App, key: KeyEvent, ctx: &RuntimeContext) -> Result<()> {
     // Global panel toggle shortcut.
-    if app.overlay == Overlay::None && key.code == KeyCode::Tab {
+    if app.overlay == Overlay::None && app.keymap.is_bound(Action::TogglePanel, &key) {
         if app.input_mode == InputMode::Question {
             app.exit_question();
+        } else if app.input_mode == InputMode::ShipField {
+            app.finish_ship_field_edit();
+        } else if app.input_mode == InputMode::FindingField {
+            app.finish_finding_edit();
         }
         app.toggle_panel();
         return Ok(());
@@ -40,6 +49,8 @@ pub fn handle_key_event(app: &mut App, key: KeyEvent, ctx: &RuntimeContext) -> R
     match app.input_mode {
         InputMode::Search => return handle_search_input(app, key),
         InputMode::Question => return handle_question_input(app, key, ctx),
+        InputMode::ShipField => return handle_ship_field_input(app, key),
+        InputMode::FindingField => return handle_finding_field_input(app, key),
         InputMode::Normal => {}
     }
 
@@ -1,16 +1,24 @@
 use crate::app::background;
-use crate::app::messages::BackgroundMessage;
+use crate::app::messages::{BackgroundMessage, PendingFixApply};
+use crate::app::tasks::TaskKind;
 use crate::app::RuntimeContext;
-use crate::ui::{ActivePanel, App, LoadingState, Overlay, ShipStep, WorkflowStep};
+use crate::ui::diff::FileDiff;
+use crate::ui::keymap::Action;
+use crate::ui::{
+    ActivePanel, App, FindingField, LoadingState, Overlay, RepeatableAction, ShipField, ShipStep,
+    WorkflowStep,
+};
 use anyhow::Result;
-use cosmos_adapters::git_ops;
+use cosmos_adapters::config::GitPolicy;
 use cosmos_adapters::util::{hash_bytes, resolve_repo_path_allow_new};
-use cosmos_core::suggest::Suggestion;
+use cosmos_adapters::{git_ops, github};
+use cosmos_core::suggest::{Suggestion, SuggestionFeedback};
 use cosmos_engine::llm::FixPreview;
 use cosmos_engine::llm::{
     ImplementationAppliedFile, ImplementationFinalizationStatus, ImplementationHarnessRunContext,
+    ImplementationRejectedHunk,
 };
-use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::event::KeyEvent;
 use std::collections::HashMap;
 use std::path::PathBuf;
 
@@ -37,8 +45,10 @@ enum ApplyError {
     SuggestionWeakGrounding,
     /// Failed to check git status
     GitStatusFailed(String),
-    /// Working tree has uncommitted changes
+    /// Staged changes would be swept into the fix commit
     DirtyWorkingTree,
+    /// Files the fix writes have uncommitted edits that applying would overwrite
+    DirtyTargetFiles(Vec<PathBuf>),
     /// Files have changed since the preview was generated
     FilesChanged(Vec<PathBuf>),
     /// Path resolution failed (security check)
@@ -67,26 +77,18 @@ impl ApplyError {
             }
             Self::GitStatusFailed(e) => format!("Git error: {}. Check repo state.", e),
             Self::DirtyWorkingTree => {
-                "Apply failed: working tree has changes. Commit or stash first.".into()
-            }
-            Self::FilesChanged(paths) => {
-                let names: Vec<String> = paths
-                    .iter()
-                    .take(3)
-                    .map(|p| p.display().to_string())
-                    .collect();
-                let more = paths.len().saturating_sub(3);
-                let suffix = if more > 0 {
-                    format!(" (+{} more)", more)
-                } else {
-                    String::new()
-                };
-                format!(
-                    "Apply failed: files changed ({}{}). Refresh suggestions and try again.",
-                    names.join(", "),
-                    suffix
-                )
+                "Apply failed: working tree has staged changes. Commit or stash first.".into()
             }
+            Self::DirtyTargetFiles(paths) => format!(
+                "Apply failed: {} {} uncommitted edits the fix would overwrite. Commit or stash {} first.",
+                summarize_paths(paths),
+                if paths.len() == 1 { "has" } else { "have" },
+                if paths.len() == 1 { "it" } else { "them" }
+            ),
+            Self::FilesChanged(paths) => format!(
+                "Apply failed: files changed ({}). Refresh suggestions and try again.",
+                summarize_paths(paths)
+            ),
             Self::UnsafePath(path, e) => {
                 format!("Apply failed: unsafe path {}: {}", path.display(), e)
             }
@@ -97,6 +99,40 @@ impl ApplyError {
     }
 }
 
+/// Up to three paths, then a count of the rest
+fn summarize_paths(paths: &[PathBuf]) -> String {
+    let names: Vec<String> = paths
+        .iter()
+        .take(3)
+        .map(|p| p.display().to_string())
+        .collect();
+    let more = paths.len().saturating_sub(3);
+    if more > 0 {
+        format!("{} (+{} more)", names.join(", "), more)
+    } else {
+        names.join(", ")
+    }
+}
+
+/// Paths among `targets` with uncommitted edits (modified, staged, or untracked)
+fn dirty_paths<'a>(
+    status: &git_ops::GitStatus,
+    targets: impl IntoIterator<Item = &'a PathBuf>,
+) -> Vec<PathBuf> {
+    let dirty: std::collections::HashSet<&std::path::Path> = status
+        .staged
+        .iter()
+        .chain(&status.modified)
+        .chain(&status.untracked)
+        .map(std::path::Path::new)
+        .collect();
+    targets
+        .into_iter()
+        .filter(|path| dirty.contains(path.as_path()))
+        .cloned()
+        .collect()
+}
+
 /// Context needed to apply a fix, validated and ready to use
 struct ApplyContext {
     preview: FixPreview,
@@ -155,12 +191,17 @@ fn validate_apply_fix(app: &App) -> std::result::Result<ApplyContext, ApplyError
         return Err(ApplyError::FilesChanged(changed_files));
     }
 
+    // The harness works in its own worktree from HEAD, so unrelated uncommitted edits are
+    // safe. Edits to the files it writes are not, and staged work would land in its commit.
     let status = git_ops::current_status(&app.repo_path)
         .map_err(|e| ApplyError::GitStatusFailed(e.to_string()))?;
-    let changed_count = status.staged.len() + status.modified.len() + status.untracked.len();
-    if changed_count > 0 {
+    if !status.staged.is_empty() {
         return Err(ApplyError::DirtyWorkingTree);
     }
+    let dirty_targets = dirty_paths(&status, current_hashes.keys());
+    if !dirty_targets.is_empty() {
+        return Err(ApplyError::DirtyTargetFiles(dirty_targets));
+    }
 
     let preview = cosmos_engine::llm::build_fix_preview_from_validated_suggestion(&suggestion);
     Ok(ApplyContext {
@@ -302,7 +343,11 @@ fn resolve_review_file_path(
 }
 
 fn start_review_fix_for_selected_findings(app: &mut App, ctx: &RuntimeContext) {
-    if app.review_state.selected.is_empty() || app.review_state.reviewing || app.review_state.fixing
+    // Findings on someone else's pull request are posted, never fixed locally.
+    if app.review_state.selected.is_empty()
+        || app.review_state.reviewing
+        || app.review_state.fixing
+        || app.is_pr_review()
     {
         return;
     }
@@ -330,8 +375,9 @@ fn start_review_fix_for_selected_findings(app: &mut App, ctx: &RuntimeContext) {
     let tx_fix = ctx.tx.clone();
 
     app.set_review_fixing(true);
+    let handle = app.tasks.start(TaskKind::Fix);
 
-    background::spawn_background(ctx.tx.clone(), "verification_fix", async move {
+    background::spawn_cancellable(ctx.tx.clone(), "verification_fix", handle, async move {
         let stage_start = std::time::Instant::now();
         let mut findings_by_file: HashMap<PathBuf, Vec<cosmos_engine::llm::ReviewFinding>> =
             HashMap::new();
@@ -386,6 +432,9 @@ fn start_review_fix_for_selected_findings(app: &mut App, ctx: &RuntimeContext) {
                         total_usage.completion_tokens += u.completion_tokens;
                         total_usage.total_tokens += u.total_tokens;
                         total_usage.cost = Some(total_usage.cost.unwrap_or(0.0) + u.cost());
+                        if total_usage.model.is_none() {
+                            total_usage.model = u.model;
+                        }
                         saw_usage = true;
                     }
                 }
@@ -419,9 +468,13 @@ struct ApplyFinalizationFailure {
     mutation_on_failure: bool,
 }
 
-fn has_repo_mutations(repo_path: &std::path::Path) -> bool {
+/// Branch name, applied file changes, and the undo run id of a finalized fix.
+type FinalizedHarnessResult = (String, Vec<(PathBuf, String)>, String);
+
+/// Whether any of `paths` still differs from HEAD (other uncommitted files are the user's)
+fn has_repo_mutations(repo_path: &std::path::Path, paths: &[PathBuf]) -> bool {
     git_ops::current_status(repo_path)
-        .map(|status| !(status.staged.is_empty() && status.modified.is_empty()))
+        .map(|status| !status.staged.is_empty() || !dirty_paths(&status, paths).is_empty())
         .unwrap_or(true)
 }
 
@@ -455,13 +508,14 @@ fn rollback_finalization_failure(
     apply_finalization_failure(
         format!("{message} ({rollback_detail})"),
         ImplementationFinalizationStatus::RolledBack,
-        has_repo_mutations(repo_path),
+        has_repo_mutations(repo_path, touched_files),
     )
 }
 
 fn validate_finalization_repo_state(
     repo_path: &std::path::Path,
     source_branch: &str,
+    files: &[ImplementationAppliedFile],
 ) -> std::result::Result<(), ApplyFinalizationFailure> {
     let status = git_ops::current_status(repo_path).map_err(|error| {
         apply_finalization_failure(
@@ -484,7 +538,7 @@ fn validate_finalization_repo_state(
             false,
         ));
     }
-    if !(status.staged.is_empty() && status.modified.is_empty()) {
+    if !status.staged.is_empty() {
         return Err(apply_finalization_failure(
             "Finalization stopped because repository state changed while preparing apply."
                 .to_string(),
@@ -492,6 +546,19 @@ fn validate_finalization_repo_state(
             true,
         ));
     }
+    // Only port the fix back onto files that are still clean; never clobber the user's edits.
+    let targets: Vec<PathBuf> = files.iter().map(|file| file.path.clone()).collect();
+    let dirty = dirty_paths(&status, &targets);
+    if !dirty.is_empty() {
+        return Err(apply_finalization_failure(
+            format!(
+                "Finalization stopped because {} changed while apply was running; your edits were left untouched.",
+                summarize_paths(&dirty)
+            ),
+            ImplementationFinalizationStatus::FailedBeforeFinalize,
+            true,
+        ));
+    }
     Ok(())
 }
 
@@ -569,28 +636,57 @@ fn apply_finalized_file_on_branch(
     Ok((file.path.clone(), file.summary.clone()))
 }
 
+/// Finalize a passing harness result on a fix branch.
+///
+/// Returns the branch name, the applied file changes, and the undo run id holding the
+/// pre-apply snapshots. Finalization fails when the snapshots can't be recorded.
 fn finalize_harness_result_on_branch(
     repo_path: &std::path::Path,
     source_branch: &str,
     suggestion: &Suggestion,
     files: &[ImplementationAppliedFile],
-) -> std::result::Result<(String, Vec<(PathBuf, String)>), ApplyFinalizationFailure> {
-    validate_finalization_repo_state(repo_path, source_branch)?;
-
-    let branch_name =
-        git_ops::generate_fix_branch_name(&suggestion.id.to_string(), &suggestion.summary);
+    git_policy: &GitPolicy,
+    follow_up_branch: Option<&str>,
+) -> std::result::Result<FinalizedHarnessResult, ApplyFinalizationFailure> {
+    validate_finalization_repo_state(repo_path, source_branch, files)?;
+
+    let snapshot_paths = files.iter().map(|f| f.path.clone()).collect::<Vec<_>>();
+    // On a branch with an open pull request, keep working on it so Ship can follow up.
+    let branch_name = match follow_up_branch.filter(|branch| *branch == source_branch) {
+        Some(branch) => branch.to_string(),
+        None => git_ops::policy_branch_name(
+            git_policy,
+            suggestion.kind.conventional_type(),
+            &suggestion.id.to_string(),
+            &suggestion.summary,
+        ),
+    };
     let branch_outcome =
-        git_ops::create_fix_branch_from_current_with_outcome(repo_path, &branch_name).map_err(
-            |error| {
-                apply_finalization_failure(
-                    format!("Could not create fix branch: {}", error),
-                    ImplementationFinalizationStatus::FailedBeforeFinalize,
-                    false,
-                )
-            },
-        )?;
+        git_ops::create_fix_branch_from_current_with_outcome(repo_path, &branch_name, git_policy)
+            .map_err(|error| {
+            apply_finalization_failure(
+                format!("Could not create fix branch: {}", error),
+                ImplementationFinalizationStatus::FailedBeforeFinalize,
+                false,
+            )
+        })?;
 
     let mut touched_files = Vec::new();
+    // Without snapshots undo could only restore HEAD and would wipe earlier pending changes.
+    let snapshots =
+        cosmos_adapters::undo::snapshot_files(repo_path, &snapshot_paths).map_err(|error| {
+            rollback_finalization_failure(
+                repo_path,
+                source_branch,
+                &branch_outcome.branch_name,
+                branch_outcome.created_new,
+                &touched_files,
+                format!(
+                    "Finalization stopped because undo snapshots could not be taken: {}",
+                    error
+                ),
+            )
+        })?;
     let mut final_file_changes = Vec::new();
     for file in files {
         final_file_changes.push(apply_finalized_file_on_branch(
@@ -601,7 +697,26 @@ fn finalize_harness_result_on_branch(
             file,
         )?);
     }
-    Ok((branch_outcome.branch_name, final_file_changes))
+    let undo_run_id = cosmos_adapters::undo::record_applied_fix(
+        repo_path,
+        suggestion.id,
+        suggestion.summary.clone(),
+        snapshots,
+    )
+    .map_err(|error| {
+        rollback_finalization_failure(
+            repo_path,
+            source_branch,
+            &branch_outcome.branch_name,
+            branch_outcome.created_new,
+            &touched_files,
+            format!(
+                "Finalization failed because the undo entry could not be recorded: {}",
+                error
+            ),
+        )
+    })?;
+    Ok((branch_outcome.branch_name, final_file_changes, undo_run_id))
 }
 
 fn rollback_finalization(
@@ -725,6 +840,7 @@ fn handle_non_passing_harness_result(
     );
 }
 
+#[allow(clippy::too_many_arguments)]
 fn handle_passing_harness_result(
     tx_apply: &std::sync::mpsc::Sender<BackgroundMessage>,
     repo_path: &std::path::Path,
@@ -733,19 +849,30 @@ fn handle_passing_harness_result(
     preview: &FixPreview,
     stage_start: std::time::Instant,
     result: &mut cosmos_engine::llm::ImplementationRunResult,
+    git_policy: &GitPolicy,
+    follow_up_branch: Option<&str>,
 ) {
     match finalize_harness_result_on_branch(
         repo_path,
         source_branch,
         suggestion,
         &result.file_changes,
+        git_policy,
+        follow_up_branch,
     ) {
-        Ok((created_branch, file_changes)) => {
+        Ok((created_branch, file_changes, undo_run_id)) => {
+            let detail = match result.diagnostics.rejected_hunks.len() {
+                0 => "Applied passing harness result on fix branch".to_string(),
+                rejected => format!(
+                    "Applied passing harness result on fix branch with {} hunk(s) rejected",
+                    rejected
+                ),
+            };
             record_interactive_finalization_outcome(
                 repo_path,
                 &mut result.diagnostics,
                 ImplementationFinalizationStatus::Applied,
-                Some("Applied passing harness result on fix branch".to_string()),
+                Some(detail),
                 false,
             );
             if result.diagnostics.reduced_confidence {
@@ -761,9 +888,13 @@ fn handle_passing_harness_result(
                 usage: result.usage.clone(),
                 branch_name: created_branch,
                 source_branch: source_branch.to_string(),
+                undo_run_id: Some(undo_run_id),
                 friendly_title: preview.friendly_title.clone(),
                 problem_summary: preview.problem_summary.clone(),
                 outcome: preview.outcome.clone(),
+                harness_evidence: cosmos_engine::llm::HarnessEvidence::from_diagnostics(
+                    &result.diagnostics,
+                ),
                 duration_ms: stage_start.elapsed().as_millis() as u64,
             });
         }
@@ -785,6 +916,108 @@ fn handle_passing_harness_result(
     }
 }
 
+/// Diff each file of a passing fix against the working tree.
+fn fix_hunk_diffs(
+    repo_path: &std::path::Path,
+    files: &[ImplementationAppliedFile],
+) -> Vec<FileDiff> {
+    files
+        .iter()
+        .map(|file| {
+            let original = std::fs::read_to_string(repo_path.join(&file.path)).unwrap_or_default();
+            FileDiff::new(file.path.clone(), &original, &file.content)
+        })
+        .collect()
+}
+
+/// Rebuild a fix from the user's hunk decisions.
+///
+/// Files with rejected hunks get their resolved content; files where every hunk was
+/// rejected are left out entirely. Returns the files to finalize and the rejected hunks.
+fn apply_hunk_decisions(
+    files: &[ImplementationAppliedFile],
+    diffs: &[FileDiff],
+) -> (
+    Vec<ImplementationAppliedFile>,
+    Vec<ImplementationRejectedHunk>,
+) {
+    let mut kept = Vec::new();
+    let mut rejected = Vec::new();
+    for file in files {
+        let Some(diff) = diffs.iter().find(|diff| diff.path == file.path) else {
+            kept.push(file.clone());
+            continue;
+        };
+        rejected.extend(diff.hunks.iter().filter(|hunk| !hunk.accepted).map(|hunk| {
+            ImplementationRejectedHunk {
+                path: file.path.clone(),
+                header: hunk.header.clone(),
+                added_lines: hunk.added_count(),
+                removed_lines: hunk.removed_count(),
+            }
+        }));
+        if !diff.has_rejections() {
+            kept.push(file.clone());
+        } else if diff.hunks.iter().any(|hunk| hunk.accepted) {
+            kept.push(ImplementationAppliedFile {
+                content: diff.resolved_content(),
+                ..file.clone()
+            });
+        }
+    }
+    (kept, rejected)
+}
+
+/// Finalize the pending fix with only the hunks kept in the diff viewer.
+pub(super) fn finalize_pending_fix_apply(app: &mut App, ctx: &RuntimeContext) {
+    let diffs = match &app.overlay {
+        Overlay::Diff { view } => view.files.clone(),
+        _ => Vec::new(),
+    };
+    let Some(mut pending) = app.pending_fix_apply.take() else {
+        app.close_overlay();
+        return;
+    };
+    let (files, rejected) = apply_hunk_decisions(&pending.result.file_changes, &diffs);
+    if files.is_empty() {
+        app.pending_fix_apply = Some(pending);
+        cancel_pending_fix_apply(app, ctx, "Every hunk was rejected during hunk selection");
+        return;
+    }
+    app.close_overlay();
+    app.loading = LoadingState::GeneratingFix;
+    pending.result.file_changes = files;
+    pending.result.diagnostics.rejected_hunks = rejected;
+    handle_passing_harness_result(
+        ctx.tx,
+        ctx.repo_path,
+        &pending.source_branch,
+        &pending.suggestion,
+        &pending.preview,
+        pending.stage_start,
+        &mut pending.result,
+        &app.git_policy,
+        app.follow_up_branch(),
+    );
+}
+
+/// Drop the pending fix without writing anything, recording why in the harness report.
+pub(super) fn cancel_pending_fix_apply(app: &mut App, ctx: &RuntimeContext, reason: &str) {
+    app.close_overlay();
+    app.loading = LoadingState::None;
+    app.clear_apply_confirm();
+    let Some(mut pending) = app.pending_fix_apply.take() else {
+        return;
+    };
+    record_interactive_finalization_outcome(
+        ctx.repo_path,
+        &mut pending.result.diagnostics,
+        ImplementationFinalizationStatus::FailedBeforeFinalize,
+        Some(reason.to_string()),
+        false,
+    );
+}
+
 fn start_apply_for_context(app: &mut App, ctx: &RuntimeContext, apply_ctx: ApplyContext) {
     app.loading = LoadingState::GeneratingFix;
     app.clear_apply_confirm();
@@ -794,35 +1027,54 @@ fn start_apply_for_context(app: &mut App, ctx: &RuntimeContext, apply_ctx: Apply
     let preview = apply_ctx.preview;
     let suggestion = apply_ctx.suggestion;
     let repo_memory_context = apply_ctx.repo_memory_context;
+    let workspace_trusted = app.workspace_trusted;
+    let git_policy = app.git_policy.clone();
+    let follow_up_branch = app.follow_up_branch().map(str::to_string);
+    let handle = app.tasks.start(TaskKind::Fix);
 
-    background::spawn_background(ctx.tx.clone(), "apply_fix", async move {
+    background::spawn_cancellable(ctx.tx.clone(), "apply_fix", handle, async move {
         let stage_start = std::time::Instant::now();
         let source_branch = git_ops::current_status(&repo_path)
             .map(|s| s.branch)
             .unwrap_or_else(|_| "unknown".to_string());
         let mem = optional_repo_memory_context(repo_memory_context);
 
-        let config = cosmos_engine::llm::ImplementationHarnessConfig::interactive_strict();
+        let config = match cosmos_engine::llm::ImplementationHarnessConfig::interactive_strict()
+            .with_repo_config(&repo_path)
+        {
+            Ok(config) if workspace_trusted => config,
+            Ok(config) => config.for_untrusted_workspace(),
+            Err(e) => {
+                let _ = tx_apply.send(BackgroundMessage::DirectFixError(e.to_string()));
+                return;
+            }
+        };
         let _ = tx_apply.send(BackgroundMessage::ApplyHarnessProgress {
             attempt_index: 1,
             attempt_count: config.max_attempts,
             detail: "starting strict implementation harness".to_string(),
         });
         let tx_progress = tx_apply.clone();
-
-        match cosmos_engine::llm::implement_validated_suggestion_with_harness_with_progress(
-            &repo_path,
-            &suggestion,
-            &preview,
-            mem,
-            config,
-            |attempt_index, attempt_count, diagnostics| {
-                let _ = tx_progress.send(BackgroundMessage::ApplyHarnessProgress {
-                    attempt_index,
-                    attempt_count,
-                    detail: apply_harness_progress_detail(diagnostics),
-                });
-            },
+        let tx_output = tx_apply.clone();
+
+        match cosmos_engine::llm::with_check_output(
+            std::sync::Arc::new(move |line: &str| {
+                let _ = tx_output.send(BackgroundMessage::CheckOutput(line.to_string()));
+            }),
+            cosmos_engine::llm::implement_validated_suggestion_with_harness_with_progress(
+                &repo_path,
+                &suggestion,
+                &preview,
+                mem,
+                config,
+                |attempt_index, attempt_count, diagnostics| {
+                    let _ = tx_progress.send(BackgroundMessage::ApplyHarnessProgress {
+                        attempt_index,
+                        attempt_count,
+                        detail: apply_harness_progress_detail(diagnostics),
+                    });
+                },
+            ),
         )
         .await
         {
@@ -831,6 +1083,20 @@ fn start_apply_for_context(app: &mut App, ctx: &RuntimeContext, apply_ctx: Apply
                     handle_non_passing_harness_result(&tx_apply, &repo_path, &mut result);
                     return;
                 }
+                let diffs = fix_hunk_diffs(&repo_path, &result.file_changes);
+                if diffs.iter().map(|diff| diff.hunks.len()).sum::<usize>() > 1 {
+                    let _ = tx_apply.send(BackgroundMessage::ApplyHunkSelection {
+                        pending: Box::new(PendingFixApply {
+                            source_branch,
+                            suggestion,
+                            preview,
+                            stage_start,
+                            result,
+                        }),
+                        diffs,
+                    });
+                    return;
+                }
                 handle_passing_harness_result(
                     &tx_apply,
                     &repo_path,
@@ -839,6 +1105,8 @@ fn start_apply_for_context(app: &mut App, ctx: &RuntimeContext, apply_ctx: Apply
                     &preview,
                     stage_start,
                     &mut result,
+                    &git_policy,
+                    follow_up_branch.as_deref(),
                 );
             }
             Err(e) => {
@@ -879,6 +1147,7 @@ fn review_interaction_ready(app: &App) -> bool {
     app.workflow_step == WorkflowStep::Review
         && !app.review_state.reviewing
         && !app.review_state.fixing
+        && !app.review_state.posting
 }
 
 fn handle_down_key(app: &mut App) {
@@ -930,6 +1199,10 @@ fn handle_enter_in_ask_panel(app: &mut App) -> bool {
 }
 
 fn handle_enter_suggestions(app: &mut App) {
+    app.toggle_selected_suggestion_expanded();
+}
+
+fn open_selected_apply_plan(app: &mut App) {
     let suggestion = app.selected_suggestion().cloned();
     if let Some(suggestion) = suggestion {
         if !llm_available_for_apply() {
@@ -943,39 +1216,266 @@ fn handle_enter_suggestions(app: &mut App) {
     }
 }
 
+/// Do `action` to the selected suggestion and remember it for `.`.
+fn run_repeatable(app: &mut App, action: RepeatableAction) {
+    let Some(id) = app.selected_suggestion().map(|s| s.id) else {
+        return;
+    };
+    match action {
+        RepeatableAction::Rate(feedback) => app.rate_selected_suggestion(feedback),
+        RepeatableAction::Snooze(choice) => app.snooze_suggestion(id, choice),
+        RepeatableAction::OpenApplyPlan => open_selected_apply_plan(app),
+    }
+    app.last_repeatable = Some((action, id));
+}
+
+/// Repeat the last suggestion action on the next suggestion, like vim's `.`.
+///
+/// Snoozing takes a suggestion off the list, leaving the next one selected already; after
+/// anything else the selection first steps past the suggestion last acted on.
+fn repeat_last_action(app: &mut App) {
+    let Some((action, acted_on)) = app.last_repeatable else {
+        app.show_toast("Nothing to repeat yet");
+        return;
+    };
+    if app.selected_suggestion().map(|s| s.id) == Some(acted_on) {
+        app.navigate_down();
+        if app.selected_suggestion().map(|s| s.id) == Some(acted_on) {
+            app.show_toast("No more suggestions");
+            return;
+        }
+    }
+    run_repeatable(app, action);
+}
+
 fn handle_enter_review(app: &mut App, ctx: &RuntimeContext) {
     if !review_interaction_ready(app) {
         return;
     }
+    if app.is_pr_review() {
+        start_pr_review_post(app, ctx);
+        return;
+    }
     if !app.review_state.selected.is_empty() {
         start_review_fix_for_selected_findings(app, ctx);
         return;
     }
     if app.review_passed() {
-        app.start_ship();
+        start_ship_with_commit_message(app, ctx);
         return;
     }
     if app.review_state.confirm_ship {
         app.review_state.confirm_ship = false;
-        app.start_ship();
+        start_ship_with_commit_message(app, ctx);
         return;
     }
     app.review_state.confirm_ship = true;
 }
 
+fn start_pr_review_post(app: &mut App, ctx: &RuntimeContext) {
+    let Some(pull_request) = app.review_state.pull_request.clone() else {
+        return;
+    };
+    if app.review_state.posting {
+        return;
+    }
+    let summary = app.review_state.summary.clone();
+    let comments = app.pr_review_comments();
+    let tx_post = ctx.tx.clone();
+    app.review_state.posting = true;
+
+    background::spawn_background(ctx.tx.clone(), "pr_review_post", async move {
+        let message = match cosmos_adapters::github::post_pull_request_review(
+            &pull_request,
+            &summary,
+            &comments,
+        )
+        .await
+        {
+            Ok(url) => BackgroundMessage::PrReviewPosted(url),
+            Err(e) => BackgroundMessage::PrReviewPostError(e.to_string()),
+        };
+        let _ = tx_post.send(message);
+    });
+}
+
+/// Enter the Ship step and, when AI is available, replace the pre-filled commit message with
+/// one written from the staged diff.
+fn start_ship_with_commit_message(app: &mut App, ctx: &RuntimeContext) {
+    app.start_ship();
+    if cosmos_engine::llm::is_available() {
+        request_commit_message(app, ctx);
+    }
+}
+
+fn request_commit_message(app: &mut App, ctx: &RuntimeContext) {
+    if !llm_available_for_apply() {
+        prompt_api_key_setup(
+            app,
+            "No API key configured yet. Add your Cerebras key to write commit messages.",
+        );
+        return;
+    }
+    if !app.begin_commit_message_generation() {
+        return;
+    }
+    let repo_path = app.repo_path.clone();
+    let summaries: Vec<String> = app
+        .pending_changes
+        .iter()
+        .map(|change| change.description.clone())
+        .collect();
+    let tx = ctx.tx.clone();
+
+    background::spawn_background(ctx.tx.clone(), "commit_message", async move {
+        let result = match git_ops::staged_diff(&repo_path) {
+            Ok(diff) => cosmos_engine::llm::generate_commit_message(&diff, &summaries).await,
+            Err(e) => Err(e),
+        };
+        let message = match result {
+            Ok((message, usage)) => BackgroundMessage::CommitMessageReady { message, usage },
+            Err(e) => BackgroundMessage::CommitMessageError(e.to_string()),
+        };
+        let _ = tx.send(message);
+    });
+}
+
+fn start_rebase(app: &mut App, ctx: &RuntimeContext) {
+    let Some(base) = app.begin_rebase() else {
+        return;
+    };
+    let repo_path = app.repo_path.clone();
+    let workspace_trusted = app.workspace_trusted;
+    let tx = ctx.tx.clone();
+
+    background::spawn_background(ctx.tx.clone(), "rebase", async move {
+        let config = match cosmos_engine::llm::ImplementationHarnessConfig::interactive_strict()
+            .with_repo_config(&repo_path)
+        {
+            Ok(config) if workspace_trusted => config,
+            Ok(config) => config.for_untrusted_workspace(),
+            Err(e) => {
+                let _ = tx.send(BackgroundMessage::RebaseError(e.to_string()));
+                return;
+            }
+        };
+        let tx_progress = tx.clone();
+        let result =
+            cosmos_engine::llm::rebase_branch_onto_base(&repo_path, &base, &config, |detail| {
+                let _ = tx_progress.send(BackgroundMessage::RebaseProgress(detail.to_string()));
+            })
+            .await;
+        let _ = tx.send(match result {
+            Ok(outcome) => BackgroundMessage::RebaseComplete(outcome),
+            Err(e) => BackgroundMessage::RebaseError(e.to_string()),
+        });
+    });
+}
+
 fn start_ship_confirm(app: &mut App, ctx: &RuntimeContext) {
+    let problems = app.ship_policy_problems();
+    if !problems.is_empty() {
+        app.open_alert(
+            "Blocked by git policy",
+            format!(
+                "Fix these before shipping (c edits the commit message):\n{}",
+                problems
+                    .iter()
+                    .map(|problem| format!("• {}", problem))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            ),
+        );
+        return;
+    }
     let repo_path = app.repo_path.clone();
     let branch_name = app.ship_state.branch_name.clone();
-    let commit_message = app.ship_state.commit_message.clone();
-    let (pr_title, pr_body) = app.generate_pr_content();
+    // One commit per kept change when splitting, otherwise one commit for everything staged.
+    let commits: Vec<(String, Option<Vec<PathBuf>>)> = if app.ship_state.split_commits {
+        app.ship_commit_plan()
+            .into_iter()
+            .map(|(message, files)| (message, Some(files)))
+            .collect()
+    } else {
+        vec![(app.ship_state.commit_message.clone(), None)]
+    };
+    let git_policy = app.git_policy.clone();
+    let shipped_files = app.ship_state.files.clone();
+    let (pr_title, pr_body) = if app.ship_state.pr_title.trim().is_empty() {
+        app.generate_pr_content()
+    } else {
+        (
+            app.ship_state.pr_title.trim().to_string(),
+            app.ship_state.pr_body.clone(),
+        )
+    };
+    let pr_options = app.ship_state.pr_options();
+    let follow_up = app.ship_state.follow_up.clone();
+    let workspace_trusted = app.workspace_trusted;
+    let stacked = app.stacked_changes;
     let tx_ship = ctx.tx.clone();
 
+    app.ship_state.hook_run = None;
     app.set_ship_step(ShipStep::Committing);
 
     background::spawn_background(ctx.tx.clone(), "ship_confirm", async move {
+        // libgit2 commits skip hooks, so run the repo's pre-commit hook first
+        // (only for trusted workspaces; the hook is the repo's own code).
+        let hook = if workspace_trusted {
+            git_ops::detect_pre_commit_hook(&repo_path)
+        } else {
+            Ok(None)
+        };
+        match hook {
+            Ok(Some(hook)) => {
+                let _ = tx_ship.send(BackgroundMessage::ShipProgress(ShipStep::RunningHooks));
+                let run = match git_ops::run_pre_commit_hook(&repo_path, &hook) {
+                    Ok(run) => run,
+                    Err(e) => {
+                        let _ = tx_ship.send(BackgroundMessage::ShipError(e.to_string()));
+                        return;
+                    }
+                };
+                let passed = run.passed;
+                if passed {
+                    // A passing hook may still reformat files; commit what it produced.
+                    for file in run.modified_files.iter().filter(|file| {
+                        shipped_files
+                            .iter()
+                            .any(|shipped| shipped == std::path::Path::new(file.as_str()))
+                    }) {
+                        if let Err(e) = git_ops::stage_file(&repo_path, file) {
+                            let _ = tx_ship.send(BackgroundMessage::ShipError(e.to_string()));
+                            return;
+                        }
+                    }
+                }
+                let _ = tx_ship.send(BackgroundMessage::ShipHookResult(run));
+                if !passed {
+                    return;
+                }
+            }
+            Ok(None) => {}
+            Err(e) => {
+                let _ = tx_ship.send(BackgroundMessage::ShipError(e.to_string()));
+                return;
+            }
+        }
+
         let _ = tx_ship.send(BackgroundMessage::ShipProgress(ShipStep::Committing));
-        if let Err(e) = git_ops::commit(&repo_path, &commit_message) {
-            let _ = tx_ship.send(BackgroundMessage::ShipError(e.to_string()));
+        for (message, files) in &commits {
+            let committed = match files {
+                Some(files) => git_ops::commit_paths(&repo_path, message, &git_policy, files),
+                None => git_ops::commit(&repo_path, message, &git_policy),
+            };
+            if let Err(e) = committed {
+                let _ = tx_ship.send(BackgroundMessage::ShipError(e.to_string()));
+                return;
+            }
+        }
+        if stacked {
+            // The stack list pushes and opens a pull request per entry.
+            let _ = tx_ship.send(BackgroundMessage::ShipStacked);
             return;
         }
 
@@ -986,7 +1486,19 @@ fn start_ship_confirm(app: &mut App, ctx: &RuntimeContext) {
         }
 
         let _ = tx_ship.send(BackgroundMessage::ShipProgress(ShipStep::CreatingPR));
-        match git_ops::create_pr(&repo_path, &pr_title, &pr_body).await {
+        if let Some(pr) = follow_up {
+            // The commits are already on the pull request; the comment tells reviewers why.
+            let message = match github::post_pull_request_comment(&pr, &pr_body).await {
+                Ok(_) => BackgroundMessage::ShipComplete(pr.html_url),
+                Err(e) => BackgroundMessage::ShipError(format!(
+                    "Pushed to PR #{} but couldn't comment: {}",
+                    pr.number, e
+                )),
+            };
+            let _ = tx_ship.send(message);
+            return;
+        }
+        match git_ops::create_pr(&repo_path, &pr_title, &pr_body, &pr_options).await {
             Ok(url) => {
                 let _ = tx_ship.send(BackgroundMessage::ShipComplete(url));
             }
@@ -997,19 +1509,100 @@ fn start_ship_confirm(app: &mut App, ctx: &RuntimeContext) {
     });
 }
 
+/// Stage whatever the failed hook fixed (e.g. formatting) and run the ship again.
+fn retry_ship_after_hook_failure(app: &mut App, ctx: &RuntimeContext) {
+    for file in app.ship_hook_fixes_to_stage() {
+        if let Err(e) = git_ops::stage_file(&app.repo_path, &file) {
+            app.open_alert("Couldn't stage hook fixes", e.to_string());
+            return;
+        }
+    }
+    start_ship_confirm(app, ctx);
+}
+
+/// Pull request options can be edited on the Ship Confirm screen
+fn ship_confirm_ready(app: &App) -> bool {
+    app.active_panel == ActivePanel::Suggestions
+        && app.workflow_step == WorkflowStep::Ship
+        && app.ship_state.step == ShipStep::Confirm
+}
+
+/// Options for a new pull request can be edited; a follow-up push keeps the existing one's
+fn pr_options_ready(app: &App) -> bool {
+    ship_confirm_ready(app) && app.ship_state.follow_up.is_none()
+}
+
+/// The per-change commit list is open
+fn ship_commits_ready(app: &App) -> bool {
+    app.active_panel == ActivePanel::Suggestions
+        && app.workflow_step == WorkflowStep::Ship
+        && app.ship_state.step == ShipStep::Commits
+}
+
 fn handle_enter_ship(app: &mut App, ctx: &RuntimeContext) {
     match app.ship_state.step {
         ShipStep::Confirm => start_ship_confirm(app, ctx),
+        ShipStep::HooksFailed => retry_ship_after_hook_failure(app, ctx),
         ShipStep::Done => {
             if let Some(url) = &app.ship_state.pr_url {
                 let _ = git_ops::open_url(url);
             }
             app.workflow_complete();
         }
+        ShipStep::Stack => open_stack_entry_pr(app, ctx),
+        ShipStep::Commits => app.finish_commit_split(true),
         _ => {}
     }
 }
 
+/// Push the selected stack entry (and the branches under it) and open its pull request,
+/// or open the existing one in the browser.
+fn open_stack_entry_pr(app: &mut App, ctx: &RuntimeContext) {
+    let index = app.ship_state.stack_selected;
+    if let Some(url) = app.stack.get(index).and_then(|entry| entry.pr_url.clone()) {
+        let _ = git_ops::open_url(&url);
+        return;
+    }
+    let Some(entry) = app.begin_stack_pr(index) else {
+        return;
+    };
+    // The pull request's base is the previous entry's branch, so it must be on the remote.
+    let branches: Vec<String> = app.stack[..=index]
+        .iter()
+        .map(|entry| entry.branch.clone())
+        .collect();
+    let repo_path = app.repo_path.clone();
+    let tx = ctx.tx.clone();
+
+    background::spawn_background(ctx.tx.clone(), "stack_pr", async move {
+        for branch in &branches {
+            if let Err(e) = git_ops::push_branch(&repo_path, branch) {
+                let _ = tx.send(BackgroundMessage::StackPrError {
+                    index,
+                    error: e.to_string(),
+                });
+                return;
+            }
+        }
+        let message = match git_ops::create_pr_from_branch(
+            &repo_path,
+            &entry.branch,
+            &entry.title,
+            &entry.body,
+            &entry.options,
+        )
+        .await
+        {
+            Ok(url) => BackgroundMessage::StackPrCreated { index, url },
+            Err(e) => BackgroundMessage::StackPrError {
+                index,
+                error: e.to_string(),
+            },
+        };
+        let _ = tx.send(message);
+    });
+}
+
 fn handle_enter_key(app: &mut App, ctx: &RuntimeContext) {
     if handle_enter_in_ask_panel(app) {
         return;
@@ -1030,8 +1623,18 @@ fn handle_escape_key(app: &mut App) {
         app.exit_ask_cosmos();
     } else if app.workflow_step == WorkflowStep::Suggestions && app.armed_suggestion_id.is_some() {
         app.clear_apply_confirm();
+    } else if app.workflow_step == WorkflowStep::Ship
+        && app.ship_state.step == ShipStep::HooksFailed
+    {
+        app.dismiss_ship_hook_failure();
+    } else if app.workflow_step == WorkflowStep::Ship && app.ship_state.step == ShipStep::Stack {
+        app.close_stack();
+    } else if app.workflow_step == WorkflowStep::Ship && app.ship_state.step == ShipStep::Commits {
+        app.finish_commit_split(false);
     } else if app.workflow_step != WorkflowStep::Suggestions {
         app.workflow_back();
+    } else if !app.suggestion_filter.is_empty() {
+        app.set_suggestion_filter("");
     } else if !app.search_query.is_empty() {
         app.exit_search();
     } else if app.overlay != Overlay::None {
@@ -1041,51 +1644,127 @@ fn handle_escape_key(app: &mut App) {
 
 /// Handle key events in normal mode (no special input active)
 pub(super) fn handle_normal_mode(app: &mut App, key: KeyEvent, ctx: &RuntimeContext) -> Result<()> {
-    match key.code {
-        KeyCode::Char('q') => app.should_quit = true,
-        KeyCode::Down => handle_down_key(app),
-        KeyCode::Up => handle_up_key(app),
-        KeyCode::Char(' ') => {
-            if review_interaction_ready(app) {
-                app.review_toggle_finding();
-            }
+    for action in app.keymap.actions_for(&key) {
+        if dispatch_normal_action(app, action, ctx) {
+            break;
         }
-        KeyCode::Char('f') => {
-            if review_interaction_ready(app) && !app.review_state.selected.is_empty() {
-                start_review_fix_for_selected_findings(app, ctx);
-            }
+    }
+    Ok(())
+}
+
+/// Run an action if it applies in the current state. Returns false so the next
+/// action bound to the same key can be tried.
+fn dispatch_normal_action(app: &mut App, action: Action, ctx: &RuntimeContext) -> bool {
+    let suggestions_ready = app.active_panel == ActivePanel::Suggestions
+        && app.workflow_step == WorkflowStep::Suggestions;
+    match action {
+        Action::Quit => app.should_quit = true,
+        Action::NavigateDown => handle_down_key(app),
+        Action::NavigateUp => handle_up_key(app),
+        Action::ToggleFinding if review_interaction_ready(app) => app.review_toggle_finding(),
+        Action::FixFindings
+            if review_interaction_ready(app) && !app.review_state.selected.is_empty() =>
+        {
+            start_review_fix_for_selected_findings(app, ctx);
         }
-        KeyCode::Enter => handle_enter_key(app, ctx),
-        KeyCode::Esc => handle_escape_key(app),
-        KeyCode::Char('?') => app.toggle_help(),
-        KeyCode::Char('a') => {
-            if app.active_panel == ActivePanel::Suggestions && review_interaction_ready(app) {
-                app.review_select_all();
-            }
+        Action::Confirm => handle_enter_key(app, ctx),
+        Action::Back => handle_escape_key(app),
+        Action::ToggleHelp => app.toggle_help(),
+        Action::SelectAllFindings
+            if app.active_panel == ActivePanel::Suggestions && review_interaction_ready(app) =>
+        {
+            app.review_select_all();
         }
-        KeyCode::Char('k') => app.open_api_key_overlay(None),
-        KeyCode::Char('u') => {
+        Action::OpenApplyPlan if suggestions_ready => {
+            run_repeatable(app, RepeatableAction::OpenApplyPlan)
+        }
+        Action::OpenInEditor if suggestions_ready => app.open_selected_suggestion_in_editor(),
+        Action::Copy => match app.copy_target() {
+            Some((what, text)) => app.copy_to_clipboard(what, &text),
+            None => return false,
+        },
+        Action::ApiKeySetup => app.open_api_key_overlay(None),
+        Action::CancelTask if app.tasks.cancel_active().is_some() => app.needs_redraw = true,
+        Action::Undo if app.undo_last_bulk_dismissal() => {}
+        Action::Undo => {
             if let Err(e) = app.undo_last_pending_change() {
                 app.open_alert("Couldn't undo", e);
             }
         }
-        KeyCode::Char('r') => {
-            if app.active_panel == ActivePanel::Suggestions
-                && app.workflow_step == WorkflowStep::Suggestions
-            {
-                refresh_suggestions_now(app, ctx, "Manual refresh");
-            }
+        Action::RefreshSuggestions if suggestions_ready => {
+            refresh_suggestions_now(app, ctx, "Manual refresh");
         }
-        KeyCode::Char('m') => {
-            if app.active_panel == ActivePanel::Suggestions
-                && app.workflow_step == WorkflowStep::Suggestions
-                && app.loading != LoadingState::GeneratingSuggestions
-            {
-                app.open_suggestion_focus_overlay();
-            }
+        Action::ChooseFocus
+            if suggestions_ready && app.loading != LoadingState::GeneratingSuggestions =>
+        {
+            app.open_suggestion_focus_overlay();
+        }
+        Action::RateGood if suggestions_ready => {
+            run_repeatable(app, RepeatableAction::Rate(SuggestionFeedback::Good))
+        }
+        Action::RateBad if suggestions_ready => {
+            run_repeatable(app, RepeatableAction::Rate(SuggestionFeedback::Bad))
         }
-        KeyCode::Char('R') => app.open_reset_overlay(),
-        KeyCode::Char('U') => {
+        Action::RateIrrelevant if suggestions_ready => {
+            run_repeatable(app, RepeatableAction::Rate(SuggestionFeedback::Irrelevant))
+        }
+        Action::Snooze if suggestions_ready => app.open_snooze_overlay(),
+        Action::ShowSnoozed if suggestions_ready => app.open_snoozed_overlay(),
+        Action::BulkDismiss if suggestions_ready => app.open_bulk_dismiss_overlay(),
+        Action::RepeatLast if suggestions_ready => repeat_last_action(app),
+        Action::CyclePackage if suggestions_ready && !app.index.packages.is_empty() => {
+            app.cycle_package_scope();
+        }
+        Action::Search if suggestions_ready => app.start_search(),
+        Action::ToggleSort if suggestions_ready => app.toggle_suggestion_sort(),
+        Action::ToggleDraft if pr_options_ready(app) => app.ship_toggle_draft(),
+        Action::EditFindingTitle if review_interaction_ready(app) => {
+            app.start_finding_edit(FindingField::Title)
+        }
+        Action::EditFindingDescription if review_interaction_ready(app) => {
+            app.start_finding_edit(FindingField::Description)
+        }
+        Action::DowngradeFinding if review_interaction_ready(app) => app.review_downgrade_finding(),
+        Action::ShowDiff if review_interaction_ready(app) && !app.review_state.files.is_empty() => {
+            app.open_diff_view()
+        }
+        Action::EditBase if pr_options_ready(app) => app.start_ship_field_edit(ShipField::Base),
+        Action::EditReviewers if pr_options_ready(app) => {
+            app.start_ship_field_edit(ShipField::Reviewers)
+        }
+        Action::EditLabels if pr_options_ready(app) => app.start_ship_field_edit(ShipField::Labels),
+        Action::EditTitle if pr_options_ready(app) => app.start_ship_field_edit(ShipField::Title),
+        Action::EditDescription if ship_confirm_ready(app) => {
+            app.start_ship_field_edit(ShipField::Description)
+        }
+        Action::EditCommitMessage
+            if ship_confirm_ready(app)
+                && !app.ship_state.generating_commit_message
+                && !app.ship_state.split_commits =>
+        {
+            app.start_ship_field_edit(ShipField::CommitMessage)
+        }
+        Action::RegenerateCommitMessage
+            if ship_confirm_ready(app) && !app.ship_state.split_commits =>
+        {
+            request_commit_message(app, ctx)
+        }
+        Action::SplitCommits if ship_confirm_ready(app) => app.open_commit_split(),
+        Action::MoveCommitUp if ship_commits_ready(app) => app.move_split_commit(true),
+        Action::MoveCommitDown if ship_commits_ready(app) => app.move_split_commit(false),
+        Action::ToggleCommit if ship_commits_ready(app) => app.toggle_split_commit(),
+        Action::OpenStack
+            if app.workflow_step == WorkflowStep::Suggestions && !app.stack.is_empty() =>
+        {
+            app.open_stack()
+        }
+        Action::CommandLog => app.toggle_command_log(),
+        Action::Branches => super::overlay::open_branches_overlay(app, ctx),
+        Action::Rebase => start_rebase(app, ctx),
+        Action::Reset => app.open_reset_overlay(),
+        Action::Settings => app.open_settings_overlay(),
+        Action::WorkspaceTrust => app.open_workspace_trust_overlay(),
+        Action::Update => {
             if let Some(target_version) = app.update_available.clone() {
                 app.show_update_overlay(
                     cosmos_adapters::update::CURRENT_VERSION.to_string(),
@@ -1093,9 +1772,9 @@ pub(super) fn handle_normal_mode(app: &mut App, key: KeyEvent, ctx: &RuntimeCont
                 );
             }
         }
-        _ => {}
+        _ => return false,
     }
-    Ok(())
+    true
 }
 
 // =============================================================================
@@ -148,6 +148,9 @@ fn k_opens_api_key_overlay() {
         files: HashMap::new(),
         index_errors: Vec::new(),
         git_head: Some("deadbeef".to_string()),
+        packages: Vec::new(),
+        excluded_files: 0,
+        filter_fingerprint: None,
     };
     let suggestions = SuggestionEngine::new(index.clone());
     let context = WorkContext {
@@ -181,6 +184,72 @@ fn k_opens_api_key_overlay() {
     let _ = std::fs::remove_dir_all(root);
 }
 
+#[test]
+fn remapped_keys_dispatch_through_keymap() {
+    let mut root = std::env::temp_dir();
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    root.push(format!("cosmos_keymap_test_{}", nanos));
+    std::fs::create_dir_all(&root).unwrap();
+
+    let index = CodebaseIndex {
+        root: root.clone(),
+        files: HashMap::new(),
+        index_errors: Vec::new(),
+        git_head: Some("deadbeef".to_string()),
+        packages: Vec::new(),
+        excluded_files: 0,
+        filter_fingerprint: None,
+    };
+    let suggestions = SuggestionEngine::new(index.clone());
+    let context = WorkContext {
+        branch: "main".to_string(),
+        uncommitted_files: Vec::new(),
+        staged_files: Vec::new(),
+        untracked_files: Vec::new(),
+        inferred_focus: None,
+        modified_count: 0,
+        repo_root: root.clone(),
+    };
+    let mut app = App::new(index.clone(), suggestions, context);
+    app.workflow_step = WorkflowStep::Suggestions;
+    let overrides = std::collections::BTreeMap::from([
+        ("api_key_setup".to_string(), vec!["ctrl+k".to_string()]),
+        ("quit".to_string(), vec!["ctrl+q".to_string()]),
+    ]);
+    app.keymap = crate::ui::keymap::Keymap::from_config(&overrides).0;
+
+    let (tx, _rx) = mpsc::channel();
+    let ctx = crate::app::RuntimeContext {
+        index: &index,
+        repo_path: &root,
+        tx: &tx,
+    };
+
+    for code in ['k', 'q'] {
+        handle_normal_mode(
+            &mut app,
+            KeyEvent::new(KeyCode::Char(code), KeyModifiers::NONE),
+            &ctx,
+        )
+        .unwrap();
+    }
+    assert_eq!(app.overlay, Overlay::None);
+    assert!(!app.should_quit);
+
+    handle_normal_mode(
+        &mut app,
+        KeyEvent::new(KeyCode::Char('k'), KeyModifiers::CONTROL),
+        &ctx,
+    )
+    .unwrap();
+    assert!(matches!(app.overlay, Overlay::ApiKeySetup { .. }));
+
+    let _ = std::fs::remove_dir_all(root);
+}
+
 #[test]
 fn m_opens_suggestion_focus_overlay() {
     let mut root = std::env::temp_dir();
@@ -196,6 +265,9 @@ fn m_opens_suggestion_focus_overlay() {
         files: HashMap::new(),
         index_errors: Vec::new(),
         git_head: Some("deadbeef".to_string()),
+        packages: Vec::new(),
+        excluded_files: 0,
+        filter_fingerprint: None,
     };
     let suggestions = SuggestionEngine::new(index.clone());
     let context = WorkContext {
@@ -234,6 +306,66 @@ fn m_opens_suggestion_focus_overlay() {
     let _ = std::fs::remove_dir_all(root);
 }
 
+#[test]
+fn enter_expands_suggestion_in_place_and_collapses_on_second_press() {
+    let root = tempdir().unwrap();
+    let root = root.path().to_path_buf();
+    let index = CodebaseIndex {
+        root: root.clone(),
+        files: HashMap::new(),
+        index_errors: Vec::new(),
+        git_head: Some("deadbeef".to_string()),
+        packages: Vec::new(),
+        excluded_files: 0,
+        filter_fingerprint: None,
+    };
+    let mut suggestions = SuggestionEngine::new(index.clone());
+    for idx in 0..5 {
+        suggestions
+            .suggestions
+            .push(cosmos_core::suggest::Suggestion::new(
+                cosmos_core::suggest::SuggestionKind::BugFix,
+                cosmos_core::suggest::Priority::High,
+                PathBuf::from(format!("src/file_{}.rs", idx)),
+                format!("Issue {}", idx),
+                cosmos_core::suggest::SuggestionSource::LlmDeep,
+            ));
+    }
+    let context = WorkContext {
+        branch: "main".to_string(),
+        uncommitted_files: Vec::new(),
+        staged_files: Vec::new(),
+        untracked_files: Vec::new(),
+        inferred_focus: None,
+        modified_count: 0,
+        repo_root: root.clone(),
+    };
+    let mut app = App::new(index.clone(), suggestions, context);
+    app.workflow_step = WorkflowStep::Suggestions;
+    for _ in 0..4 {
+        app.navigate_down();
+    }
+    let scroll = app.suggestion_scroll;
+    let selected_id = app.selected_suggestion().unwrap().id;
+
+    let (tx, _rx) = mpsc::channel();
+    let ctx = crate::app::RuntimeContext {
+        index: &index,
+        repo_path: &root,
+        tx: &tx,
+    };
+    let enter = KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE);
+
+    handle_normal_mode(&mut app, enter, &ctx).unwrap();
+    assert_eq!(app.expanded_suggestion_id, Some(selected_id));
+    assert_eq!(app.overlay, Overlay::None);
+    assert_eq!(app.suggestion_scroll, scroll);
+
+    handle_normal_mode(&mut app, enter, &ctx).unwrap();
+    assert!(app.expanded_suggestion_id.is_none());
+    assert_eq!(app.suggestion_scroll, scroll);
+}
+
 #[test]
 fn r_requires_mode_selection_before_running() {
     let mut root = std::env::temp_dir();
@@ -249,6 +381,9 @@ fn r_requires_mode_selection_before_running() {
         files: HashMap::new(),
         index_errors: Vec::new(),
         git_head: Some("deadbeef".to_string()),
+        packages: Vec::new(),
+        excluded_files: 0,
+        filter_fingerprint: None,
     };
     let suggestions = SuggestionEngine::new(index.clone());
     let context = WorkContext {
@@ -285,7 +420,7 @@ fn r_requires_mode_selection_before_running() {
 }
 
 #[test]
-fn enter_opens_apply_plan_without_mutation() {
+fn o_opens_apply_plan_without_mutation() {
     std::env::set_var("CEREBRAS_API_KEY", "test-key");
     let mut root = std::env::temp_dir();
     let nanos = SystemTime::now()
@@ -301,6 +436,9 @@ fn enter_opens_apply_plan_without_mutation() {
         files: HashMap::new(),
         index_errors: Vec::new(),
         git_head: Some("deadbeef".to_string()),
+        packages: Vec::new(),
+        excluded_files: 0,
+        filter_fingerprint: None,
     };
     let mut suggestions = SuggestionEngine::new(index.clone());
     let suggestion = cosmos_core::suggest::Suggestion::new(
@@ -334,7 +472,7 @@ fn enter_opens_apply_plan_without_mutation() {
         tx: &tx,
     };
 
-    let key = KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE);
+    let key = KeyEvent::new(KeyCode::Char('o'), KeyModifiers::NONE);
     handle_normal_mode(&mut app, key, &ctx).unwrap();
 
     assert_eq!(app.armed_suggestion_id, Some(suggestion_id));
@@ -348,7 +486,7 @@ fn enter_opens_apply_plan_without_mutation() {
 }
 
 #[test]
-fn enter_rejects_weakly_grounded_suggestion() {
+fn o_rejects_weakly_grounded_suggestion() {
     std::env::set_var("CEREBRAS_API_KEY", "test-key");
     let mut root = std::env::temp_dir();
     let nanos = SystemTime::now()
@@ -364,6 +502,9 @@ fn enter_rejects_weakly_grounded_suggestion() {
         files: HashMap::new(),
         index_errors: Vec::new(),
         git_head: Some("deadbeef".to_string()),
+        packages: Vec::new(),
+        excluded_files: 0,
+        filter_fingerprint: None,
     };
     let mut suggestions = SuggestionEngine::new(index.clone());
     let suggestion = cosmos_core::suggest::Suggestion::new(
@@ -397,7 +538,7 @@ fn enter_rejects_weakly_grounded_suggestion() {
         tx: &tx,
     };
 
-    let key = KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE);
+    let key = KeyEvent::new(KeyCode::Char('o'), KeyModifiers::NONE);
     handle_normal_mode(&mut app, key, &ctx).unwrap();
 
     match &app.overlay {
@@ -430,6 +571,9 @@ fn apply_arm_resets_on_selection_change() {
         files: HashMap::new(),
         index_errors: Vec::new(),
         git_head: Some("deadbeef".to_string()),
+        packages: Vec::new(),
+        excluded_files: 0,
+        filter_fingerprint: None,
     };
     let mut suggestions = SuggestionEngine::new(index.clone());
     suggestions.suggestions.push(
@@ -476,7 +620,7 @@ fn apply_arm_resets_on_selection_change() {
 
     handle_normal_mode(
         &mut app,
-        KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE),
+        KeyEvent::new(KeyCode::Char('o'), KeyModifiers::NONE),
         &ctx,
     )
     .unwrap();
@@ -507,6 +651,9 @@ fn apply_plan_cancel_clears_apply_confirmation() {
         files: HashMap::new(),
         index_errors: Vec::new(),
         git_head: Some("deadbeef".to_string()),
+        packages: Vec::new(),
+        excluded_files: 0,
+        filter_fingerprint: None,
     };
     let mut suggestions = SuggestionEngine::new(index.clone());
     suggestions.suggestions.push(
@@ -541,7 +688,7 @@ fn apply_plan_cancel_clears_apply_confirmation() {
 
     handle_normal_mode(
         &mut app,
-        KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE),
+        KeyEvent::new(KeyCode::Char('o'), KeyModifiers::NONE),
         &ctx,
     )
     .unwrap();
@@ -579,6 +726,9 @@ fn apply_plan_confirm_reports_files_changed_since_preview() {
         files: HashMap::new(),
         index_errors: Vec::new(),
         git_head: Some("deadbeef".to_string()),
+        packages: Vec::new(),
+        excluded_files: 0,
+        filter_fingerprint: None,
     };
     let mut suggestions = SuggestionEngine::new(index.clone());
     suggestions.suggestions.push(
@@ -613,7 +763,7 @@ fn apply_plan_confirm_reports_files_changed_since_preview() {
 
     handle_normal_mode(
         &mut app,
-        KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE),
+        KeyEvent::new(KeyCode::Char('o'), KeyModifiers::NONE),
         &ctx,
     )
     .unwrap();
@@ -646,6 +796,9 @@ async fn apply_plan_confirm_starts_apply_flow() {
         files: HashMap::new(),
         index_errors: Vec::new(),
         git_head: Some("deadbeef".to_string()),
+        packages: Vec::new(),
+        excluded_files: 0,
+        filter_fingerprint: None,
     };
     let mut suggestions = SuggestionEngine::new(index.clone());
     suggestions.suggestions.push(
@@ -680,7 +833,7 @@ async fn apply_plan_confirm_starts_apply_flow() {
 
     crate::app::input::handle_key_event(
         &mut app,
-        KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE),
+        KeyEvent::new(KeyCode::Char('o'), KeyModifiers::NONE),
         &ctx,
     )
     .unwrap();
@@ -752,6 +905,8 @@ fn finalization_refuses_if_branch_changed_during_apply() {
             summary: "Modified".to_string(),
             content: "fn demo() { println!(\"x\"); }\n".to_string(),
         }],
+        &GitPolicy::default(),
+        None,
     );
     assert!(result.is_err());
     let err = result.unwrap_err();
@@ -794,6 +949,8 @@ fn finalization_rolls_back_on_unsafe_path_and_deletes_branch() {
             summary: "Nope".to_string(),
             content: "bad".to_string(),
         }],
+        &GitPolicy::default(),
+        None,
     );
     assert!(result.is_err());
     let err = result.unwrap_err();
@@ -813,6 +970,67 @@ fn finalization_rolls_back_on_unsafe_path_and_deletes_branch() {
         .is_err());
 }
 
+#[test]
+fn finalization_leaves_unrelated_edits_alone_and_refuses_dirty_targets() {
+    let (_dir, repo_path) = init_temp_git_repo_with_file();
+    let source_branch = git_ops::current_status(&repo_path).unwrap().branch;
+    std::fs::write(repo_path.join("notes.txt"), "work in progress\n").unwrap();
+
+    let suggestion = cosmos_core::suggest::Suggestion::new(
+        cosmos_core::suggest::SuggestionKind::Improvement,
+        cosmos_core::suggest::Priority::High,
+        PathBuf::from("src/lib.rs"),
+        "Improve demo".to_string(),
+        cosmos_core::suggest::SuggestionSource::LlmDeep,
+    );
+    let payload = [ImplementationAppliedFile {
+        path: PathBuf::from("src/lib.rs"),
+        summary: "Modified: demo".to_string(),
+        content: "fn demo() { println!(\"x\"); }\n".to_string(),
+    }];
+
+    // A hand edit to a file the fix writes is never overwritten.
+    std::fs::write(repo_path.join("src/lib.rs"), "fn demo() { todo!() }\n").unwrap();
+    let err = finalize_harness_result_on_branch(
+        &repo_path,
+        &source_branch,
+        &suggestion,
+        &payload,
+        &GitPolicy::default(),
+        None,
+    )
+    .unwrap_err();
+    assert_eq!(
+        err.status,
+        ImplementationFinalizationStatus::FailedBeforeFinalize
+    );
+    assert!(err.message.contains("src/lib.rs"));
+    assert_eq!(
+        std::fs::read_to_string(repo_path.join("src/lib.rs")).unwrap(),
+        "fn demo() { todo!() }\n"
+    );
+
+    // Unrelated uncommitted work rides along untouched and unstaged.
+    git_ops::restore_file(&repo_path, std::path::Path::new("src/lib.rs")).unwrap();
+    let (branch, _, _) = finalize_harness_result_on_branch(
+        &repo_path,
+        &source_branch,
+        &suggestion,
+        &payload,
+        &GitPolicy::default(),
+        None,
+    )
+    .unwrap();
+    let status = git_ops::current_status(&repo_path).unwrap();
+    assert_eq!(status.branch, branch);
+    assert_eq!(status.staged, vec!["src/lib.rs".to_string()]);
+    assert_eq!(status.untracked, vec!["notes.txt".to_string()]);
+    assert_eq!(
+        std::fs::read_to_string(repo_path.join("notes.txt")).unwrap(),
+        "work in progress\n"
+    );
+}
+
 #[test]
 fn finalization_success_stages_only_payload_files() {
     let (_dir, repo_path) = init_temp_git_repo_with_file();
@@ -826,7 +1044,7 @@ fn finalization_success_stages_only_payload_files() {
         cosmos_core::suggest::SuggestionSource::LlmDeep,
     );
 
-    let (branch, changes) = finalize_harness_result_on_branch(
+    let (branch, changes, undo_run_id) = finalize_harness_result_on_branch(
         &repo_path,
         &source_branch,
         &suggestion,
@@ -835,6 +1053,8 @@ fn finalization_success_stages_only_payload_files() {
             summary: "Modified: demo".to_string(),
             content: "fn demo() { println!(\"x\"); }\n".to_string(),
         }],
+        &GitPolicy::default(),
+        None,
     )
     .unwrap();
 
@@ -848,4 +1068,272 @@ fn finalization_success_stages_only_payload_files() {
 
     let content = std::fs::read_to_string(repo_path.join("src/lib.rs")).unwrap();
     assert!(content.contains("println!"));
+
+    // The pre-apply snapshot should be restorable through the undo stack.
+    cosmos_adapters::undo::undo(&repo_path, &undo_run_id).unwrap();
+    let restored = std::fs::read_to_string(repo_path.join("src/lib.rs")).unwrap();
+    assert!(!restored.contains("println!"));
+}
+
+#[test]
+fn finalization_on_a_follow_up_branch_stays_on_it() {
+    let (_dir, repo_path) = init_temp_git_repo_with_file();
+    let source_branch =
+        git_ops::create_fix_branch_from_current(&repo_path, "fix/aaaa-first").unwrap();
+
+    let suggestion = cosmos_core::suggest::Suggestion::new(
+        cosmos_core::suggest::SuggestionKind::Improvement,
+        cosmos_core::suggest::Priority::High,
+        PathBuf::from("src/lib.rs"),
+        "Improve demo".to_string(),
+        cosmos_core::suggest::SuggestionSource::LlmDeep,
+    );
+    let (branch, _, _) = finalize_harness_result_on_branch(
+        &repo_path,
+        &source_branch,
+        &suggestion,
+        &[ImplementationAppliedFile {
+            path: PathBuf::from("src/lib.rs"),
+            summary: "Modified: demo".to_string(),
+            content: "fn demo() { println!(\"x\"); }\n".to_string(),
+        }],
+        &GitPolicy::default(),
+        Some("fix/aaaa-first"),
+    )
+    .unwrap();
+    assert_eq!(branch, "fix/aaaa-first");
+    let status = git_ops::current_status(&repo_path).unwrap();
+    assert_eq!(status.branch, "fix/aaaa-first");
+    assert_eq!(status.staged, vec!["src/lib.rs".to_string()]);
+}
+
+#[test]
+fn rating_keys_persist_latest_feedback_for_selected_suggestion() {
+    let root = tempdir().unwrap();
+    let index = CodebaseIndex {
+        root: root.path().to_path_buf(),
+        files: HashMap::new(),
+        index_errors: Vec::new(),
+        git_head: Some("deadbeef".to_string()),
+        packages: Vec::new(),
+        excluded_files: 0,
+        filter_fingerprint: None,
+    };
+    let mut suggestions = SuggestionEngine::new(index.clone());
+    let suggestion = cosmos_core::suggest::Suggestion::new(
+        cosmos_core::suggest::SuggestionKind::BugFix,
+        cosmos_core::suggest::Priority::High,
+        PathBuf::from("src/lib.rs"),
+        "Unchecked index in pager".to_string(),
+        cosmos_core::suggest::SuggestionSource::LlmDeep,
+    );
+    let suggestion_id = suggestion.id;
+    suggestions.suggestions.push(suggestion);
+    let context = WorkContext {
+        branch: "main".to_string(),
+        uncommitted_files: Vec::new(),
+        staged_files: Vec::new(),
+        untracked_files: Vec::new(),
+        inferred_focus: None,
+        modified_count: 0,
+        repo_root: root.path().to_path_buf(),
+    };
+    let mut app = App::new(index.clone(), suggestions, context);
+    app.workflow_step = WorkflowStep::Suggestions;
+
+    let (tx, _rx) = mpsc::channel();
+    let ctx = crate::app::RuntimeContext {
+        index: &index,
+        repo_path: &index.root,
+        tx: &tx,
+    };
+    for key in ['-', '-', 'i'] {
+        let key = KeyEvent::new(KeyCode::Char(key), KeyModifiers::NONE);
+        handle_normal_mode(&mut app, key, &ctx).unwrap();
+    }
+
+    assert_eq!(
+        app.suggestion_feedback.get(&suggestion_id),
+        Some(&SuggestionFeedback::Irrelevant)
+    );
+    // Repeating a rating does not write a duplicate row.
+    let records = cosmos_adapters::cache::Cache::new(root.path())
+        .load_recent_suggestion_feedback(10)
+        .unwrap();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].feedback, SuggestionFeedback::Bad);
+    assert_eq!(records[1].summary, "Unchecked index in pager");
+}
+
+#[test]
+fn dot_repeats_the_last_suggestion_action_on_the_next_suggestion() {
+    let root = tempdir().unwrap();
+    let index = CodebaseIndex {
+        root: root.path().to_path_buf(),
+        files: HashMap::new(),
+        index_errors: Vec::new(),
+        git_head: Some("deadbeef".to_string()),
+        packages: Vec::new(),
+        excluded_files: 0,
+        filter_fingerprint: None,
+    };
+    let mut suggestions = SuggestionEngine::new(index.clone());
+    for file in ["src/a.rs", "src/b.rs", "src/c.rs"] {
+        suggestions
+            .suggestions
+            .push(cosmos_core::suggest::Suggestion::new(
+                cosmos_core::suggest::SuggestionKind::BugFix,
+                cosmos_core::suggest::Priority::High,
+                PathBuf::from(file),
+                format!("Unchecked index in {}", file),
+                cosmos_core::suggest::SuggestionSource::LlmDeep,
+            ));
+    }
+    let ids: Vec<uuid::Uuid> = suggestions.suggestions.iter().map(|s| s.id).collect();
+    let context = WorkContext {
+        branch: "main".to_string(),
+        uncommitted_files: Vec::new(),
+        staged_files: Vec::new(),
+        untracked_files: Vec::new(),
+        inferred_focus: None,
+        modified_count: 0,
+        repo_root: root.path().to_path_buf(),
+    };
+    let mut app = App::new(index.clone(), suggestions, context);
+    app.workflow_step = WorkflowStep::Suggestions;
+
+    let (tx, _rx) = mpsc::channel();
+    let ctx = crate::app::RuntimeContext {
+        index: &index,
+        repo_path: &index.root,
+        tx: &tx,
+    };
+    let press = |app: &mut App, key: char| {
+        let key = KeyEvent::new(KeyCode::Char(key), KeyModifiers::NONE);
+        handle_normal_mode(app, key, &ctx).unwrap();
+    };
+
+    press(&mut app, '.');
+    assert!(app.suggestion_feedback.is_empty());
+    press(&mut app, '-');
+    press(&mut app, '.');
+    press(&mut app, '.');
+    for id in &ids {
+        assert_eq!(
+            app.suggestion_feedback.get(id),
+            Some(&SuggestionFeedback::Bad)
+        );
+    }
+    // At the end of the list there is no next suggestion to repeat on
+    press(&mut app, '.');
+    assert_eq!(app.suggestion_selected, 2);
+
+    // Snoozing takes the suggestion off the list, so the next one is done in place
+    app.suggestion_selected = 0;
+    app.snooze_suggestion(ids[0], 0);
+    app.last_repeatable = Some((RepeatableAction::Snooze(0), ids[0]));
+    press(&mut app, '.');
+    let shown: Vec<uuid::Uuid> = app
+        .active_suggestions_for_display()
+        .iter()
+        .map(|s| s.id)
+        .collect();
+    assert_eq!(shown, vec![ids[2]]);
+}
+
+#[test]
+fn diff_view_reverts_rejected_hunks_on_enter() {
+    let root = tempdir().unwrap();
+    let original = "fn a() {\n    one();\n}\n\n\n\n\n\n\n\nfn b() {\n    two();\n}\n";
+    let changed = "fn a() {\n    uno();\n}\n\n\n\n\n\n\n\nfn b() {\n    dos();\n}\n";
+    std::fs::create_dir_all(root.path().join("src")).unwrap();
+    std::fs::write(root.path().join("src/lib.rs"), changed).unwrap();
+    let index = CodebaseIndex {
+        root: root.path().to_path_buf(),
+        files: HashMap::new(),
+        index_errors: Vec::new(),
+        git_head: None,
+        packages: Vec::new(),
+        excluded_files: 0,
+        filter_fingerprint: None,
+    };
+    let context = WorkContext {
+        branch: "main".to_string(),
+        uncommitted_files: Vec::new(),
+        staged_files: Vec::new(),
+        untracked_files: Vec::new(),
+        inferred_focus: None,
+        modified_count: 0,
+        repo_root: root.path().to_path_buf(),
+    };
+    let mut app = App::new(index.clone(), SuggestionEngine::new(index.clone()), context);
+    app.workflow_step = WorkflowStep::Review;
+    app.review_state.files.push(crate::ui::ReviewFileContent {
+        path: PathBuf::from("src/lib.rs"),
+        original_content: original.to_string(),
+        new_content: changed.to_string(),
+    });
+
+    let (tx, _rx) = mpsc::channel();
+    let ctx = crate::app::RuntimeContext {
+        index: &index,
+        repo_path: &index.root,
+        tx: &tx,
+    };
+    handle_normal_mode(
+        &mut app,
+        KeyEvent::new(KeyCode::Char('d'), KeyModifiers::NONE),
+        &ctx,
+    )
+    .unwrap();
+    assert!(matches!(&app.overlay, Overlay::Diff { view } if view.hunk_count() == 2));
+
+    for key in [KeyCode::Char('n'), KeyCode::Char('r'), KeyCode::Enter] {
+        crate::app::input::overlay::handle_overlay_input(
+            &mut app,
+            KeyEvent::new(key, KeyModifiers::NONE),
+            &ctx,
+        )
+        .unwrap();
+    }
+
+    assert_eq!(app.overlay, Overlay::None);
+    let on_disk = std::fs::read_to_string(root.path().join("src/lib.rs")).unwrap();
+    assert!(on_disk.contains("uno();") && on_disk.contains("two();"));
+    assert_eq!(app.review_state.files[0].new_content, on_disk);
+}
+
+#[test]
+fn apply_hunk_decisions_keeps_accepted_hunks_and_records_rejections() {
+    let original = "fn a() {\n    one();\n}\n\n\n\n\n\n\n\nfn b() {\n    two();\n}\n";
+    let changed = "fn a() {\n    uno();\n}\n\n\n\n\n\n\n\nfn b() {\n    dos();\n}\n";
+    let files = vec![
+        ImplementationAppliedFile {
+            path: PathBuf::from("src/lib.rs"),
+            summary: "rename calls".to_string(),
+            content: changed.to_string(),
+        },
+        ImplementationAppliedFile {
+            path: PathBuf::from("src/new.rs"),
+            summary: "add helper".to_string(),
+            content: "pub fn helper() {}\n".to_string(),
+        },
+    ];
+    let mut diffs = vec![
+        FileDiff::new(PathBuf::from("src/lib.rs"), original, changed),
+        FileDiff::new(PathBuf::from("src/new.rs"), "", "pub fn helper() {}\n"),
+    ];
+    diffs[0].hunks[1].accepted = false;
+    diffs[1].hunks[0].accepted = false;
+
+    let (kept, rejected) = apply_hunk_decisions(&files, &diffs);
+
+    assert_eq!(kept.len(), 1);
+    assert_eq!(kept[0].path, PathBuf::from("src/lib.rs"));
+    assert!(kept[0].content.contains("uno();") && kept[0].content.contains("two();"));
+    assert_eq!(kept[0].summary, "rename calls");
+    assert_eq!(rejected.len(), 2);
+    assert_eq!(rejected[0].path, PathBuf::from("src/lib.rs"));
+    assert_eq!((rejected[0].added_lines, rejected[0].removed_lines), (1, 1));
+    assert_eq!(rejected[1].path, PathBuf::from("src/new.rs"));
 }
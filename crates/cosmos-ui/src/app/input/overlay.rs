@@ -1,8 +1,12 @@
-use super::normal::confirm_apply_from_overlay;
+use super::normal::{
+    cancel_pending_fix_apply, confirm_apply_from_overlay, finalize_pending_fix_apply,
+};
 use crate::app::background;
 use crate::app::messages::BackgroundMessage;
 use crate::app::RuntimeContext;
-use crate::ui::{App, LoadingState, Overlay, StartupAction, StartupMode};
+use crate::ui::{
+    App, EditorRequest, LoadingState, Overlay, RepeatableAction, StartupAction, StartupMode,
+};
 use anyhow::Result;
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 
@@ -173,6 +177,11 @@ fn handle_api_key_overlay_input(app: &mut App, key: &KeyEvent, _ctx: &RuntimeCon
             let mut cfg = cosmos_adapters::config::Config::load();
             match cfg.set_api_key(&candidate) {
                 Ok(()) => {
+                    // Offline heuristics give way to AI suggestions once a key is set.
+                    app.suggestions
+                        .refresh_static_suggestions(!cosmos_engine::llm::is_available());
+                    app.suggestion_selected = 0;
+                    app.suggestion_scroll = 0;
                     app.close_overlay();
                     app.open_alert(
                         "API key saved",
@@ -248,6 +257,191 @@ fn handle_apply_plan_overlay_input(app: &mut App, key: &KeyEvent, ctx: &RuntimeC
     }
 }
 
+fn handle_ask_context_picker_input(app: &mut App, key: &KeyEvent, ctx: &RuntimeContext) {
+    match key.code {
+        KeyCode::Esc => app.close_ask_context_picker(false),
+        KeyCode::Enter => app.close_ask_context_picker(true),
+        KeyCode::Down => app.ask_context_navigate(1),
+        KeyCode::Up => app.ask_context_navigate(-1),
+        KeyCode::PageDown => app.ask_context_navigate(10),
+        KeyCode::PageUp => app.ask_context_navigate(-10),
+        KeyCode::Char(' ') => app.ask_context_toggle_selected(),
+        KeyCode::Char('o') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.ask_context_open_focused_in_editor()
+        }
+        KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            regenerate_focused_directory_summary(app, ctx)
+        }
+        KeyCode::Backspace => app.ask_context_edit_query(None),
+        KeyCode::Char(c) => app.ask_context_edit_query(Some(c)),
+        _ => {}
+    }
+}
+
+/// Have the AI rewrite the focused folder's cached summary in the background.
+fn regenerate_focused_directory_summary(app: &mut App, ctx: &RuntimeContext) {
+    let Some(dir) = app.ask_context_focused_directory() else {
+        app.show_toast("Only folders have summaries");
+        return;
+    };
+    if !cosmos_engine::llm::is_available() {
+        app.show_toast("Rewriting summaries needs an API key (cosmos --setup)");
+        return;
+    }
+    app.show_toast(format!("Rewriting the summary for {}/...", dir.display()));
+    let index = app.index.clone();
+    let tx = ctx.tx.clone();
+    background::spawn_background(ctx.tx.clone(), "directory_summary", async move {
+        let message = match cosmos_engine::llm::regenerate_directory_summary(&index, &dir).await {
+            Ok((summary, usage)) => BackgroundMessage::DirectorySummaryReady { summary, usage },
+            Err(e) => BackgroundMessage::DirectorySummaryError(e.to_string()),
+        };
+        let _ = tx.send(message);
+    });
+}
+
+fn handle_settings_overlay_input(app: &mut App, key: &KeyEvent) {
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') => {
+            let _ = app.close_settings_overlay(false);
+        }
+        KeyCode::Down => app.settings_navigate(1),
+        KeyCode::Up => app.settings_navigate(-1),
+        KeyCode::Enter => {
+            if let Err(e) = app.close_settings_overlay(true) {
+                app.open_alert("Couldn't save theme", e);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Open the branch list and poll the forge for its pull requests' states
+pub(super) fn open_branches_overlay(app: &mut App, ctx: &RuntimeContext) {
+    let polls = app.open_branches_overlay();
+    spawn_branch_status_poll(ctx, &app.repo_path, polls);
+}
+
+fn spawn_branch_status_poll(
+    ctx: &RuntimeContext,
+    repo_path: &std::path::Path,
+    polls: Vec<(String, String)>,
+) {
+    if polls.is_empty() {
+        return;
+    }
+    let tx = ctx.tx.clone();
+    let repo_path = repo_path.to_path_buf();
+    background::spawn_background(ctx.tx.clone(), "branch_status", async move {
+        let mut states = Vec::with_capacity(polls.len());
+        for (branch, url) in polls {
+            let state = cosmos_adapters::forge::pull_request_state(&repo_path, &url)
+                .await
+                .map_err(|e| e.to_string());
+            states.push((branch, state));
+        }
+        let _ = tx.send(BackgroundMessage::BranchStatesReady(states));
+    });
+}
+
+fn handle_snooze_overlay_input(app: &mut App, key: &KeyEvent) {
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') => app.close_overlay(),
+        KeyCode::Down => app.snooze_navigate(1),
+        KeyCode::Up => app.snooze_navigate(-1),
+        KeyCode::Enter => {
+            if let Overlay::Snooze {
+                suggestion_id,
+                selected,
+            } = app.overlay
+            {
+                app.last_repeatable = Some((RepeatableAction::Snooze(selected), suggestion_id));
+            }
+            app.confirm_snooze();
+        }
+        _ => {}
+    }
+}
+
+fn handle_snoozed_overlay_input(app: &mut App, key: &KeyEvent) {
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') => app.close_overlay(),
+        KeyCode::Down => app.snoozed_navigate(1),
+        KeyCode::Up => app.snoozed_navigate(-1),
+        KeyCode::Enter | KeyCode::Char('u') => app.unsnooze_selected(),
+        _ => {}
+    }
+}
+
+fn handle_bulk_dismiss_overlay_input(app: &mut App, key: &KeyEvent) {
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') => app.close_overlay(),
+        KeyCode::Down => app.bulk_dismiss_navigate(1),
+        KeyCode::Up => app.bulk_dismiss_navigate(-1),
+        KeyCode::Enter => app.confirm_bulk_dismiss(),
+        _ => {}
+    }
+}
+
+fn handle_branches_overlay_input(app: &mut App, key: &KeyEvent, ctx: &RuntimeContext) {
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') => app.close_overlay(),
+        KeyCode::Down => app.branches_navigate(1),
+        KeyCode::Up => app.branches_navigate(-1),
+        KeyCode::Enter => {
+            if let Some(url) = app
+                .selected_cosmos_branch()
+                .and_then(|record| record.pr_url.clone())
+            {
+                let _ = cosmos_adapters::git_ops::open_url(&url);
+            }
+        }
+        KeyCode::Char('y') => {
+            if let Some(url) = app
+                .selected_cosmos_branch()
+                .and_then(|record| record.pr_url.clone())
+            {
+                app.copy_to_clipboard("PR URL", &url);
+            }
+        }
+        KeyCode::Char('s') | KeyCode::Char('b') => {
+            let Some(target) = app.branch_switch_target(key.code == KeyCode::Char('b')) else {
+                return;
+            };
+            match app.switch_branch_context(&target) {
+                Ok(summary) => {
+                    app.close_overlay();
+                    app.show_toast(summary);
+                }
+                Err(error) => app.set_branches_status(error),
+            }
+        }
+        KeyCode::Char('r') => {
+            let polls = app.branch_status_polls();
+            spawn_branch_status_poll(ctx, &app.repo_path, polls);
+        }
+        KeyCode::Char('d') => {
+            let Some(record) = app.begin_branch_delete() else {
+                return;
+            };
+            let tx = ctx.tx.clone();
+            let repo_path = app.repo_path.clone();
+            background::spawn_background(ctx.tx.clone(), "delete_branch", async move {
+                let result = cosmos_adapters::git_ops::delete_merged_branch(
+                    &repo_path,
+                    &record.branch,
+                    record.base.as_deref(),
+                    record.pr_head.as_deref(),
+                )
+                .map_err(|e| e.to_string());
+                let branch = record.branch;
+                let _ = tx.send(BackgroundMessage::BranchDeleted { branch, result });
+            });
+        }
+        _ => {}
+    }
+}
+
 fn handle_reset_overlay_input(app: &mut App, key: &KeyEvent, ctx: &RuntimeContext) {
     match key.code {
         KeyCode::Esc | KeyCode::Char('q') => {
@@ -366,10 +560,8 @@ fn handle_update_overlay_input(
 ) {
     let is_downloading = progress.is_some() && !has_error;
     match key.code {
-        KeyCode::Char('n') | KeyCode::Esc | KeyCode::Char('q') => {
-            if !is_downloading {
-                app.close_overlay();
-            }
+        KeyCode::Char('n') | KeyCode::Esc | KeyCode::Char('q') if !is_downloading => {
+            app.close_overlay();
         }
         KeyCode::Char('y') | KeyCode::Enter => {
             if is_downloading {
@@ -421,6 +613,65 @@ fn handle_welcome_overlay_input(app: &mut App, key: &KeyEvent) {
     }
 }
 
+fn handle_workspace_trust_overlay_input(app: &mut App, key: &KeyEvent) {
+    let trusted = match key.code {
+        KeyCode::Char('t') => true,
+        KeyCode::Char('n') | KeyCode::Esc => false,
+        _ => return,
+    };
+    let mut config = cosmos_adapters::config::Config::load();
+    config.set_workspace_trust(&app.repo_path, trusted);
+    let saved = config.save();
+    app.resolve_workspace_trust(trusted);
+    if let Err(e) = saved {
+        app.open_alert(
+            "Couldn't save workspace trust",
+            format!("{}. The choice applies to this session only.", e),
+        );
+    }
+}
+
+fn handle_diff_overlay_input(app: &mut App, key: &KeyEvent, ctx: &RuntimeContext) {
+    let fix_selection = matches!(&app.overlay, Overlay::Diff { view } if view.fix_selection);
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') if fix_selection => {
+            cancel_pending_fix_apply(app, ctx, "Apply cancelled during hunk selection")
+        }
+        KeyCode::Esc | KeyCode::Char('q') => app.close_overlay(),
+        KeyCode::Enter if fix_selection => finalize_pending_fix_apply(app, ctx),
+        KeyCode::Enter => {
+            if let Err(e) = app.apply_diff_decisions() {
+                app.open_alert("Couldn't revert hunks", e);
+            }
+        }
+        KeyCode::Char('y') => {
+            if let Overlay::Diff { view } = &app.overlay {
+                let text = view.unified_text();
+                app.copy_to_clipboard("diff", &text);
+            }
+        }
+        code => {
+            let Overlay::Diff { view } = &mut app.overlay else {
+                return;
+            };
+            match code {
+                KeyCode::Char('n') | KeyCode::Right | KeyCode::Tab => view.select_next(),
+                KeyCode::Char('p') | KeyCode::Left | KeyCode::BackTab => view.select_prev(),
+                KeyCode::Down => view.scroll += 1,
+                KeyCode::Up => view.scroll = view.scroll.saturating_sub(1),
+                KeyCode::Char('a') => view.set_selected_accepted(true),
+                KeyCode::Char('r') => view.set_selected_accepted(false),
+                KeyCode::Char(' ') => view.toggle_selected(),
+                KeyCode::Char('s') => {
+                    let width = crossterm::terminal::size().map(|(w, _)| w).unwrap_or(0);
+                    view.toggle_layout(width);
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
 fn handle_generic_overlay_input(app: &mut App, key: &KeyEvent) {
     match key.code {
         KeyCode::Esc | KeyCode::Char('q') => app.close_overlay(),
@@ -442,6 +693,16 @@ pub(super) fn handle_overlay_input(
         Overlay::ApiKeySetup { .. } => handle_api_key_overlay_input(app, &key, ctx),
         Overlay::SuggestionFocus { .. } => handle_suggestion_focus_overlay_input(app, &key, ctx),
         Overlay::ApplyPlan { .. } => handle_apply_plan_overlay_input(app, &key, ctx),
+        Overlay::Diff { .. } => handle_diff_overlay_input(app, &key, ctx),
+        Overlay::AskContextPicker { .. } => handle_ask_context_picker_input(app, &key, ctx),
+        Overlay::FileDetail { path, .. } if key.code == KeyCode::Char('e') => {
+            app.editor_request = Some(EditorRequest { path, line: None });
+        }
+        Overlay::Settings { .. } => handle_settings_overlay_input(app, &key),
+        Overlay::Snooze { .. } => handle_snooze_overlay_input(app, &key),
+        Overlay::Snoozed { .. } => handle_snoozed_overlay_input(app, &key),
+        Overlay::BulkDismiss { .. } => handle_bulk_dismiss_overlay_input(app, &key),
+        Overlay::Branches { .. } => handle_branches_overlay_input(app, &key, ctx),
         Overlay::Reset { .. } => handle_reset_overlay_input(app, &key, ctx),
         Overlay::StartupCheck { .. } => handle_startup_check_overlay_input(app, &key, ctx),
         Overlay::Update {
@@ -451,6 +712,9 @@ pub(super) fn handle_overlay_input(
             ..
         } => handle_update_overlay_input(app, &key, ctx, target_version, progress, error.is_some()),
         Overlay::Welcome => handle_welcome_overlay_input(app, &key),
+        Overlay::WorkspaceTrust => handle_workspace_trust_overlay_input(app, &key),
+        // Any key sends the mascot away; the key itself isn't acted on
+        Overlay::Mascot { .. } => app.close_overlay(),
         _ => handle_generic_overlay_input(app, &key),
     }
     Ok(())
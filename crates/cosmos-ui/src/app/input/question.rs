@@ -1,6 +1,7 @@
 use crate::app::background;
 use crate::app::messages::BackgroundMessage;
 use crate::app::RuntimeContext;
+use crate::ui::keymap::Action;
 use crate::ui::{App, InputMode, ASK_STARTER_QUESTIONS};
 use anyhow::Result;
 use cosmos_adapters::util::{hash_bytes, hash_str, resolve_repo_path_allow_new};
@@ -14,6 +15,15 @@ pub(super) fn handle_question_input(
     ctx: &RuntimeContext,
 ) -> Result<()> {
     match key.code {
+        _ if app.keymap.is_bound(Action::AskPickFiles, &key) => {
+            app.open_ask_context_picker();
+        }
+        _ if app.keymap.is_bound(Action::AskNewConversation, &key) => {
+            app.new_chat_session();
+            let _ = app
+                .chat_session
+                .save(&cosmos_adapters::cache::Cache::new(&app.repo_path));
+        }
         KeyCode::Esc => {
             app.question_input.clear();
             app.question_suggestion_selected = 0;
@@ -90,6 +100,17 @@ fn compute_context_hash(app: &App) -> String {
     // Include prompt memory context digest since it influences generated answers.
     hash_str(&app.repo_memory.to_prompt_context(12, 900)).hash(&mut hasher);
 
+    // Picked grounding files change what the answer is based on.
+    let mut picked = app.ask_context_paths.clone();
+    picked.sort();
+    picked.hash(&mut hasher);
+
+    // Follow-ups are answered in light of the conversation so far.
+    for turn in &app.chat_session.turns {
+        turn.question.hash(&mut hasher);
+        turn.answer.hash(&mut hasher);
+    }
+
     // Include project ethos digest so ask cache invalidates when ETHOS.md changes.
     let ethos_digest = match std::fs::read_to_string(app.repo_path.join("ETHOS.md")) {
         Ok(content) if !content.trim().is_empty() => hash_str(content.trim()),
@@ -121,6 +142,7 @@ fn submit_question(app: &mut App, ctx: &RuntimeContext) -> Result<()> {
         // Cache hit! Use cached answer directly
         let _ = ctx.tx.send(BackgroundMessage::QuestionResponse {
             request_id,
+            question,
             answer: cached_answer.to_string(),
             usage: None, // No usage for cached response
         });
@@ -134,6 +156,8 @@ fn submit_question(app: &mut App, ctx: &RuntimeContext) -> Result<()> {
     let repo_memory_context = app.repo_memory.to_prompt_context(12, 900);
     let question_for_cache = question.clone();
     let context_hash_for_cache = context_hash;
+    let selected_paths = app.ask_context_paths.clone();
+    let conversation = app.chat_session.clone();
 
     background::spawn_background(ctx.tx.clone(), "ask_question", async move {
         let mem = if repo_memory_context.trim().is_empty() {
@@ -141,8 +165,17 @@ fn submit_question(app: &mut App, ctx: &RuntimeContext) -> Result<()> {
         } else {
             Some(repo_memory_context)
         };
-        match cosmos_engine::llm::ask_question(&index_clone, &context_clone, &question, mem).await {
-            Ok((answer, usage)) => {
+        match cosmos_engine::llm::ask_question(
+            &index_clone,
+            &context_clone,
+            &question,
+            mem,
+            &selected_paths,
+            &conversation,
+        )
+        .await
+        {
+            Ok((answer, usage, context_report)) => {
                 // Send response with cache metadata for storage
                 let _ = tx_question.send(BackgroundMessage::QuestionResponseWithCache {
                     request_id,
@@ -150,6 +183,7 @@ fn submit_question(app: &mut App, ctx: &RuntimeContext) -> Result<()> {
                     answer,
                     usage,
                     context_hash: context_hash_for_cache,
+                    context_report,
                 });
             }
             Err(e) => {
@@ -181,6 +215,9 @@ mod tests {
             files: HashMap::new(),
             index_errors: Vec::new(),
             git_head: None,
+            packages: Vec::new(),
+            excluded_files: 0,
+            filter_fingerprint: None,
         };
         let suggestions = SuggestionEngine::new(index.clone());
         let context = WorkContext {
@@ -240,6 +277,80 @@ mod tests {
         let _ = std::fs::remove_dir_all(&root);
     }
 
+    #[test]
+    fn ctrl_f_opens_context_picker_and_picks_change_context_hash() {
+        let mut root = std::env::temp_dir();
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        root.push(format!("cosmos_question_context_picker_test_{}", nanos));
+        std::fs::create_dir_all(&root).unwrap();
+
+        let mut app = make_test_app(&root);
+        app.start_question();
+        let before = compute_context_hash(&app);
+
+        let (tx, _rx) = mpsc::channel();
+        let index = app.index.clone();
+        let ctx = RuntimeContext {
+            index: &index,
+            repo_path: &root,
+            tx: &tx,
+        };
+        handle_question_input(
+            &mut app,
+            KeyEvent::new(KeyCode::Char('f'), KeyModifiers::CONTROL),
+            &ctx,
+        )
+        .unwrap();
+        assert!(matches!(
+            app.overlay,
+            crate::ui::Overlay::AskContextPicker { .. }
+        ));
+
+        app.ask_context_paths = vec![PathBuf::from("src")];
+        assert_ne!(before, compute_context_hash(&app));
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn conversation_changes_context_hash_and_ctrl_n_starts_over() {
+        let mut root = std::env::temp_dir();
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        root.push(format!("cosmos_question_chat_session_test_{}", nanos));
+        std::fs::create_dir_all(&root).unwrap();
+
+        let mut app = make_test_app(&root);
+        app.start_question();
+        let fresh = compute_context_hash(&app);
+        app.show_chat_answer("Where is retry?".to_string(), "retry.rs".to_string(), None);
+        app.start_question();
+        assert_ne!(fresh, compute_context_hash(&app));
+
+        let (tx, _rx) = mpsc::channel();
+        let index = app.index.clone();
+        let ctx = RuntimeContext {
+            index: &index,
+            repo_path: &root,
+            tx: &tx,
+        };
+        handle_question_input(
+            &mut app,
+            KeyEvent::new(KeyCode::Char('n'), KeyModifiers::CONTROL),
+            &ctx,
+        )
+        .unwrap();
+        assert!(app.chat_session.is_empty());
+        assert_eq!(fresh, compute_context_hash(&app));
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
     #[test]
     fn enter_with_empty_input_uses_selected_starter_question() {
         let mut root = std::env::temp_dir();
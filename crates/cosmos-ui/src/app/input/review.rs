@@ -0,0 +1,17 @@
+use crate::ui::{App, FindingField};
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+
+/// Handle key events while editing a review finding
+pub(super) fn handle_finding_field_input(app: &mut App, key: KeyEvent) -> Result<()> {
+    match key.code {
+        KeyCode::Enter if app.review_state.editing == Some(FindingField::Description) => {
+            app.finding_field_push('\n')
+        }
+        KeyCode::Esc | KeyCode::Enter => app.finish_finding_edit(),
+        KeyCode::Backspace => app.finding_field_pop(),
+        KeyCode::Char(c) => app.finding_field_push(c),
+        _ => {}
+    }
+    Ok(())
+}
@@ -0,0 +1,17 @@
+use crate::ui::App;
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+
+/// Handle key events while editing a pull request option on the Ship Confirm screen
+pub(super) fn handle_ship_field_input(app: &mut App, key: KeyEvent) -> Result<()> {
+    match key.code {
+        KeyCode::Enter if app.ship_state.editing.is_some_and(|f| f.is_multiline()) => {
+            app.ship_field_push('\n')
+        }
+        KeyCode::Esc | KeyCode::Enter => app.finish_ship_field_edit(),
+        KeyCode::Backspace => app.ship_field_pop(),
+        KeyCode::Char(c) => app.ship_field_push(c),
+        _ => {}
+    }
+    Ok(())
+}
@@ -5,6 +5,7 @@ use std::path::PathBuf;
 use uuid::Uuid;
 
 /// Messages from background tasks to the main UI thread
+#[allow(clippy::large_enum_variant)]
 pub enum BackgroundMessage {
     /// Suggestion generation completed (actionable list).
     SuggestionsReady {
@@ -20,6 +21,15 @@ pub enum BackgroundMessage {
         line: String,
     },
     SuggestionsError(String),
+    /// Scheduled background re-scan completed; applied once the user is idle.
+    BackgroundScanReady {
+        suggestions: Vec<suggest::Suggestion>,
+        usage: Option<cosmos_engine::llm::Usage>,
+        model: String,
+        diagnostics: cosmos_engine::llm::SuggestionDiagnostics,
+    },
+    /// Scheduled background re-scan failed (not surfaced; the next interval retries)
+    BackgroundScanError(String),
     /// AI-assisted grouping update ready
     GroupingEnhanced {
         grouping: cosmos_core::grouping::CodebaseGrouping,
@@ -42,6 +52,8 @@ pub enum BackgroundMessage {
         attempt_count: usize,
         detail: String,
     },
+    /// One line of quick-check output, streamed while the harness runs it.
+    CheckOutput(String),
     /// Detailed apply-harness failure payload.
     ApplyHarnessFailed {
         summary: String,
@@ -54,6 +66,13 @@ pub enum BackgroundMessage {
         detail: String,
         report_path: Option<PathBuf>,
     },
+    /// The harness passed with a fix spanning several hunks; the user picks which to keep
+    /// before anything is written.
+    ApplyHunkSelection {
+        pending: Box<PendingFixApply>,
+        /// Hunks of each changed file, diffed against the working tree
+        diffs: Vec<ui::diff::FileDiff>,
+    },
     /// Direct fix applied (Smart preset generated + applied the change)
     /// Supports both single-file and multi-file changes
     DirectFixApplied {
@@ -65,22 +84,68 @@ pub enum BackgroundMessage {
         branch_name: String,
         /// Branch that was checked out before Cosmos created its fix branch.
         source_branch: String,
+        /// Undo stack entry holding pre-apply file snapshots (if recorded).
+        undo_run_id: Option<String>,
         /// Human-friendly title for PR (e.g., "Batch Processing")
         friendly_title: String,
         /// Behavior-focused problem description for non-technical readers
         problem_summary: String,
         /// What will be different after the fix
         outcome: String,
+        /// What the harness verified on the passing attempt
+        harness_evidence: Option<cosmos_engine::llm::HarnessEvidence>,
         /// Time spent generating + applying this fix
         duration_ms: u64,
     },
     DirectFixError(String),
     /// Ship workflow progress update
     ShipProgress(ui::ShipStep),
+    /// Pre-commit hook finished during shipping
+    ShipHookResult(cosmos_adapters::git_ops::PreCommitHookRun),
     /// Ship workflow completed successfully with PR URL
     ShipComplete(String),
     /// Ship workflow error
     ShipError(String),
+    /// Stacked mode: the change is committed on its branch; no push or PR yet
+    ShipStacked,
+    /// Open pull request found for the Cosmos branch checked out at startup
+    FollowUpPrFound(cosmos_adapters::github::BranchPullRequest),
+    /// Stage of a running rebase
+    RebaseProgress(String),
+    /// Rebase finished (possibly without moving the branch)
+    RebaseComplete(cosmos_engine::llm::RebaseOutcome),
+    RebaseError(String),
+    /// Polled pull request states for tracked branches
+    BranchStatesReady(Vec<(String, Result<cosmos_adapters::forge::PrState, String>)>),
+    /// A merged branch was deleted (or couldn't be)
+    BranchDeleted {
+        branch: String,
+        result: Result<(), String>,
+    },
+    /// Pull request opened for a stack entry
+    StackPrCreated {
+        index: usize,
+        url: String,
+    },
+    /// Pushing or opening the pull request for a stack entry failed
+    StackPrError {
+        index: usize,
+        error: String,
+    },
+    /// Commit message written from the staged diff
+    CommitMessageReady {
+        message: String,
+        usage: Option<cosmos_engine::llm::Usage>,
+    },
+    /// Commit message generation failed; the current message stays
+    CommitMessageError(String),
+    /// A directory summary was rewritten on request from the context picker
+    DirectorySummaryReady {
+        summary: cosmos_core::index::directory::DirectorySummary,
+        usage: Option<cosmos_engine::llm::Usage>,
+    },
+    /// Regenerating a directory summary failed; the cached one stays
+    DirectorySummaryError(String),
     /// Cache reset completed
     ResetComplete {
         options: Vec<cosmos_adapters::cache::ResetOption>,
@@ -100,6 +165,7 @@ pub enum BackgroundMessage {
     /// Response to a user question
     QuestionResponse {
         request_id: u64,
+        question: String,
         answer: String,
         usage: Option<cosmos_engine::llm::Usage>,
     },
@@ -110,6 +176,7 @@ pub enum BackgroundMessage {
         answer: String,
         usage: Option<cosmos_engine::llm::Usage>,
         context_hash: String,
+        context_report: cosmos_engine::llm::AskContextReport,
     },
     /// Error while answering a user question
     QuestionError {
@@ -130,6 +197,22 @@ pub enum BackgroundMessage {
         usage: Option<cosmos_engine::llm::Usage>,
         duration_ms: u64,
     },
+    /// An existing pull request was fetched and reviewed (`--review-pr`)
+    PrReviewReady {
+        pull_request: cosmos_adapters::github::OpenPullRequest,
+        findings: Vec<cosmos_engine::llm::ReviewFinding>,
+        summary: String,
+    },
+    /// Fetching or reviewing the pull request failed
+    PrReviewError(String),
+    /// Review comments were posted to the pull request (review URL)
+    PrReviewPosted(String),
+    /// Posting review comments failed
+    PrReviewPostError(String),
+    /// A cancellable job stopped because the user pressed `x`
+    TaskCancelled(crate::app::tasks::TaskKind),
+    /// Startup preflight found the AI provider or configured model unavailable
+    ProviderUnavailable(String),
     /// New version available - show update panel
     UpdateAvailable {
         latest_version: String,
@@ -141,3 +224,13 @@ pub enum BackgroundMessage {
     /// Update failed
     UpdateError(String),
 }
+
+/// A passing harness result held back until the user finishes hunk selection
+#[derive(Debug)]
+pub struct PendingFixApply {
+    pub source_branch: String,
+    pub suggestion: suggest::Suggestion,
+    pub preview: cosmos_engine::llm::FixPreview,
+    pub stage_start: std::time::Instant,
+    pub result: cosmos_engine::llm::ImplementationRunResult,
+}
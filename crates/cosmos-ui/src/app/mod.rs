@@ -4,9 +4,11 @@
 //! input handling, and message passing infrastructure.
 
 pub mod background;
+pub mod editor;
 pub mod input;
 pub mod messages;
 pub mod runtime;
+pub mod tasks;
 
 pub use runtime::run_tui;
 
@@ -8,8 +8,10 @@
 //! - Cache saves are best-effort - failure means regeneration next time
 
 use crate::app::messages::BackgroundMessage;
-use crate::app::{background, input, RuntimeContext};
+use crate::app::tasks::TaskKind;
+use crate::app::{background, editor, input, RuntimeContext};
 use crate::ui;
+use crate::ui::keymap::Action;
 use crate::ui::{App, LoadingState};
 use anyhow::Result;
 use cosmos_adapters::cache;
@@ -20,7 +22,10 @@ use cosmos_core::index::CodebaseIndex;
 use cosmos_core::suggest::SuggestionEngine;
 use cosmos_engine::llm::grouping as grouping_llm;
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyEventKind},
+    event::{
+        self, DisableFocusChange, DisableMouseCapture, EnableFocusChange, EnableMouseCapture,
+        Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers,
+    },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
@@ -31,6 +36,13 @@ use std::path::PathBuf;
 use std::sync::mpsc;
 use std::time::Duration;
 
+/// Spinner frame interval while one is on screen.
+const SPINNER_INTERVAL: Duration = Duration::from_millis(100);
+/// Longest wait for input while a background job is running, so its results land promptly.
+const BUSY_POLL: Duration = Duration::from_millis(250);
+/// Longest wait for input when nothing is animating or running.
+const IDLE_POLL: Duration = Duration::from_secs(1);
+
 /// Run the TUI application with background LLM tasks
 pub async fn run_tui(
     index: CodebaseIndex,
@@ -38,24 +50,59 @@ pub async fn run_tui(
     context: WorkContext,
     cache_manager: cache::Cache,
     repo_path: PathBuf,
+    review_pr: Option<cosmos_adapters::github::PrReference>,
 ) -> Result<()> {
     // Set up terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    execute!(
+        stdout,
+        EnterAlternateScreen,
+        EnableMouseCapture,
+        EnableFocusChange
+    )?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
     // Create app with loading state
     let mut app = App::new(index.clone(), suggestions, context.clone());
+    app.graphics = cosmos_adapters::terminal::graphics_protocol();
     // Load repo-local “memory” (decisions/conventions) from .cosmos/
     app.repo_memory = cache_manager.load_repo_memory();
     // Load cached domain glossary (auto-extracted terminology)
     app.glossary = cache_manager.load_glossary().unwrap_or_default();
+    // Load directory rollups, refreshing any whose files changed since they were cached
+    app.directory_summaries = cache_manager.directory_summaries(&app.index);
     // Load cached question answers
     app.question_cache = cache_manager.load_question_cache().unwrap_or_default();
+    // Resume the Ask Cosmos conversation
+    app.chat_session = cosmos_engine::llm::ChatSession::load(&cache_manager);
     // Load rolling verify precision from per-suggestion quality telemetry.
     app.rolling_verify_precision = cache_manager.rolling_verify_precision(50);
+    // Seed the header's health trend from scored runs.
+    for run in cache_manager
+        .load_recent_suggestion_run_audit(crate::ui::HEALTH_HISTORY_LEN * 4)
+        .unwrap_or_default()
+    {
+        if let Some(health) = run.health {
+            app.record_health(health.score);
+        }
+    }
+    // Restore snoozes, dropping the ones that ended while Cosmos was closed.
+    app.snoozes = cache_manager.load_snoozes();
+    app.prune_snoozes();
+    // Keep findings from earlier bulk dismissals out of the list.
+    app.dismissed = cache_manager.load_dismissals();
+    // Restore suggestion ratings so cached suggestions keep their markers.
+    app.suggestion_feedback = cache_manager
+        .load_recent_suggestion_feedback(background::SUGGESTION_FEEDBACK_WINDOW)
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|record| {
+            let id = uuid::Uuid::parse_str(&record.suggestion_id).ok()?;
+            Some((id, record.feedback))
+        })
+        .collect();
 
     // Check for unsaved work and show startup overlay if needed
     if let Ok(status) = git_ops::current_status(&repo_path) {
@@ -70,6 +117,21 @@ pub async fn run_tui(
         }
     }
 
+    // Untrusted repos never run their own quick checks, tests, or hooks. Ask once per repo,
+    // ahead of the welcome overlay, which then shows on the next launch.
+    let trust_config = cosmos_adapters::config::Config::load();
+    app.workspace_trusted = trust_config.is_workspace_trusted(&repo_path);
+    let trust_undecided = trust_config.workspace_trust(&repo_path).is_none();
+    let theme_problems = app.load_theme(trust_config.theme());
+    app.keymap = ui::keymap::Keymap::from_config(trust_config.keybindings()).0;
+    app.background_scan_interval = trust_config.background_scan_interval();
+    app.git_policy = trust_config.git_policy().clone();
+    app.stacked_changes = trust_config.stacked_changes();
+    app.notify_mode = trust_config.notify_on_finish();
+    if trust_undecided && app.overlay == ui::Overlay::None {
+        app.open_workspace_trust_overlay();
+    }
+
     // Show welcome overlay on first run (only if no other overlay is showing)
     if !cache_manager.has_seen_welcome() && app.overlay == ui::Overlay::None {
         app.overlay = ui::Overlay::Welcome;
@@ -77,6 +139,16 @@ pub async fn run_tui(
         let _ = cache_manager.mark_welcome_seen();
     }
 
+    if !theme_problems.is_empty() && app.overlay == ui::Overlay::None {
+        app.open_alert(
+            "Theme config partly ignored",
+            format!(
+                "~/.config/cosmos/config.json:\n{}",
+                theme_problems.join("\n")
+            ),
+        );
+    }
+
     // Check if we have API access
     let ai_enabled = cosmos_engine::llm::is_available();
     if !ai_enabled {
@@ -92,6 +164,9 @@ pub async fn run_tui(
     // Gate startup generation until startup check is resolved.
     if matches!(app.overlay, ui::Overlay::StartupCheck { .. }) {
         wait_for_startup_decision(&mut terminal, &mut app, &rx, &tx, &repo_path, &index)?;
+        if trust_undecided && app.overlay == ui::Overlay::None {
+            app.open_workspace_trust_overlay();
+        }
     }
 
     // Refresh context/index after startup choice so background generation reflects
@@ -138,6 +213,44 @@ pub async fn run_tui(
         });
     }
 
+    // A checked-out Cosmos branch with an open pull request: new fixes follow up on it.
+    if let Ok(status) = git_ops::current_status(&repo_path) {
+        if git_ops::is_cosmos_branch(&app.git_policy, &status.branch) {
+            let tx_follow_up = tx.clone();
+            let follow_up_path = repo_path.clone();
+            background::spawn_background(tx.clone(), "follow_up_pr", async move {
+                let Ok((owner, repo)) = cosmos_adapters::github::get_remote_info(&follow_up_path)
+                else {
+                    return;
+                };
+                if let Ok(Some(pr)) =
+                    cosmos_adapters::github::find_open_pull_request(&owner, &repo, &status.branch)
+                        .await
+                {
+                    let _ = tx_follow_up.send(BackgroundMessage::FollowUpPrFound(pr));
+                }
+            });
+        }
+    }
+
+    // Provider preflight: surface a down provider or retired model before the first run.
+    if ai_enabled {
+        let tx_preflight = tx.clone();
+        let preflight_path = repo_path.clone();
+        background::spawn_background(tx.clone(), "provider_preflight", async move {
+            if let Err(err) = cosmos_engine::llm::preflight_provider(&preflight_path).await {
+                let _ = tx_preflight.send(BackgroundMessage::ProviderUnavailable(err.to_string()));
+            }
+        });
+    }
+
+    // `--review-pr`: review an existing pull request and open its findings in the Review panel.
+    if let Some(reference) = review_pr.filter(|_| ai_enabled) {
+        app.start_pr_review(reference.number);
+        let handle = app.tasks.start(TaskKind::Review);
+        background::spawn_pr_review(tx.clone(), handle, repo_path.clone(), reference);
+    }
+
     // AI grouping enhancement: low-confidence files only, capped for safety
     if grouping_ai_enabled && ai_enabled {
         let max_files =
@@ -189,6 +302,12 @@ pub async fn run_tui(
                                 total_usage.prompt_tokens += u.prompt_tokens;
                                 total_usage.completion_tokens += u.completion_tokens;
                                 total_usage.total_tokens += u.total_tokens;
+                                if let Some(cost) = u.cost {
+                                    total_usage.cost = Some(total_usage.cost.unwrap_or(0.0) + cost);
+                                }
+                                if total_usage.model.is_none() {
+                                    total_usage.model = u.model;
+                                }
                                 saw_usage = true;
                             }
                         }
@@ -231,6 +350,11 @@ pub async fn run_tui(
         }
     }
 
+    // Brief splash, unless a prompt is already waiting for the user
+    if app.overlay == ui::Overlay::None {
+        app.show_mascot(true);
+    }
+
     // Main loop with async event handling
     let result = run_loop(&mut terminal, &mut app, rx, tx, repo_path, startup_index);
 
@@ -239,14 +363,111 @@ pub async fn run_tui(
     execute!(
         terminal.backend_mut(),
         LeaveAlternateScreen,
-        DisableMouseCapture
+        DisableMouseCapture,
+        DisableFocusChange
     )?;
     terminal.show_cursor()?;
 
     result
 }
 
-fn wait_for_startup_decision<B: Backend>(
+/// Draw a frame, then make the link text on screen clickable
+fn draw<B: Backend + io::Write>(terminal: &mut Terminal<B>, app: &App) -> Result<()> {
+    let frame = terminal.draw(|f| ui::render(f, app))?;
+    let mut links = Vec::new();
+    ui::links::write_hyperlinks(&mut links, frame.buffer)?;
+    if !links.is_empty() {
+        let backend = terminal.backend_mut();
+        backend.write_all(&links)?;
+        io::Write::flush(backend)?;
+    }
+    Ok(())
+}
+
+/// Draw the mascot image over its blank art cells while the mascot overlay is open, and
+/// remove it once the overlay closes or moves. `placed` is where it was last drawn.
+fn sync_mascot_image<B: Backend + io::Write>(
+    terminal: &mut Terminal<B>,
+    app: &App,
+    placed: &mut Option<Rect>,
+) -> Result<()> {
+    use cosmos_adapters::terminal::{self as term, GraphicsProtocol};
+
+    let size = terminal.size()?;
+    let screen = Rect::new(0, 0, size.width, size.height);
+    let wanted = match app.overlay {
+        ui::Overlay::Mascot { .. }
+            if app.graphics != GraphicsProtocol::Text
+                && !ui::terminal_too_small(size.width, size.height) =>
+        {
+            Some(ui::mascot::art_area(screen)).filter(|area| !area.is_empty())
+        }
+        _ => None,
+    };
+    if *placed == wanted {
+        return Ok(());
+    }
+
+    if placed.take().is_some() {
+        if app.graphics == GraphicsProtocol::Kitty {
+            terminal
+                .backend_mut()
+                .write_all(term::kitty_delete().as_bytes())?;
+        }
+        // Image pixels sit outside ratatui's buffer; a full repaint clears what's left
+        terminal.clear()?;
+        draw(terminal, app)?;
+    }
+
+    if let Some(area) = wanted {
+        // Pixel size of a cell, guessed when the terminal doesn't report it
+        let cell_px = crossterm::terminal::window_size()
+            .ok()
+            .filter(|w| w.width > 0 && w.columns > 0 && w.rows > 0)
+            .map(|w| (w.width / w.columns, w.height / w.rows))
+            .unwrap_or((10, 20));
+        let color = match app.palette.resolve(ui::theme::Theme::GREY_300) {
+            Color::Rgb(r, g, b) => (r, g, b),
+            _ => (190, 190, 190),
+        };
+        if let Some(image) = ui::mascot::image_sequence(app.graphics, area, cell_px, color) {
+            let column = ui::mascot::image_column(area, cell_px);
+            let backend = terminal.backend_mut();
+            execute!(
+                backend,
+                crossterm::cursor::SavePosition,
+                crossterm::cursor::MoveTo(column, area.y)
+            )?;
+            backend.write_all(image.as_bytes())?;
+            execute!(backend, crossterm::cursor::RestorePosition)?;
+        }
+        *placed = Some(area);
+    }
+    Ok(())
+}
+
+/// Route a key press; while the terminal is too small for the UI only quit is honoured,
+/// since every other action would change state the user can't see
+fn handle_key<B: Backend>(
+    terminal: &Terminal<B>,
+    app: &mut App,
+    key: KeyEvent,
+    ctx: &RuntimeContext,
+) -> Result<()> {
+    app.last_input_at = std::time::Instant::now();
+    let size = terminal.size()?;
+    if ui::terminal_too_small(size.width, size.height) {
+        let ctrl_c =
+            key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL);
+        if ctrl_c || app.keymap.is_bound(Action::Quit, &key) {
+            app.should_quit = true;
+        }
+        return Ok(());
+    }
+    input::handle_key_event(app, key, ctx)
+}
+
+fn wait_for_startup_decision<B: Backend + io::Write>(
     terminal: &mut Terminal<B>,
     app: &mut App,
     rx: &mpsc::Receiver<BackgroundMessage>,
@@ -254,7 +475,6 @@ fn wait_for_startup_decision<B: Backend>(
     repo_path: &PathBuf,
     index: &CodebaseIndex,
 ) -> Result<()> {
-    let spinner_interval = Duration::from_millis(100);
     let idle_poll = Duration::from_millis(120);
     let mut last_spinner_tick = std::time::Instant::now();
     let mut needs_redraw = true;
@@ -274,7 +494,7 @@ fn wait_for_startup_decision<B: Backend>(
             break;
         }
 
-        if app.loading.is_loading() && last_spinner_tick.elapsed() >= spinner_interval {
+        if app.spinner_visible() && last_spinner_tick.elapsed() >= SPINNER_INTERVAL {
             app.tick_loading();
             last_spinner_tick = std::time::Instant::now();
             needs_redraw = true;
@@ -288,7 +508,7 @@ fn wait_for_startup_decision<B: Backend>(
         }
 
         if needs_redraw {
-            terminal.draw(|f| ui::render(f, app))?;
+            draw(terminal, app)?;
             needs_redraw = false;
             app.needs_redraw = false;
         }
@@ -298,13 +518,19 @@ fn wait_for_startup_decision<B: Backend>(
         }
 
         if event::poll(idle_poll)? {
-            if let Event::Key(key) = event::read()? {
-                if key.kind != KeyEventKind::Press {
-                    continue;
+            match event::read()? {
+                Event::Key(key) if key.kind == KeyEventKind::Press => {
+                    handle_key(terminal, app, key, &ctx)?;
+                    needs_redraw = true;
+                    app.needs_redraw = true;
+                }
+                Event::Resize(..) => {
+                    ui::reset_render_caches();
+                    needs_redraw = true;
                 }
-                input::handle_key_event(app, key, &ctx)?;
-                needs_redraw = true;
-                app.needs_redraw = true;
+                Event::FocusGained => app.terminal_focused = Some(true),
+                Event::FocusLost => app.terminal_focused = Some(false),
+                _ => {}
             }
         }
     }
@@ -313,7 +539,7 @@ fn wait_for_startup_decision<B: Backend>(
 }
 
 /// Main event loop with background message handling
-fn run_loop<B: Backend>(
+fn run_loop<B: Backend + io::Write>(
     terminal: &mut Terminal<B>,
     app: &mut App,
     rx: mpsc::Receiver<BackgroundMessage>,
@@ -324,8 +550,6 @@ fn run_loop<B: Backend>(
     // Track scheduled maintenance ticks
     let mut last_git_refresh = std::time::Instant::now();
     let mut last_spinner_tick = std::time::Instant::now();
-    let spinner_interval = Duration::from_millis(100);
-    let idle_poll_cap = Duration::from_millis(500);
 
     let git_refresh_interval = if index.stats().file_count > 20000 {
         std::time::Duration::from_secs(10)
@@ -341,10 +565,11 @@ fn run_loop<B: Backend>(
         tx: &tx,
     };
     let mut needs_redraw = app.needs_redraw;
+    let mut mascot_image: Option<Rect> = None;
 
     loop {
-        // Advance spinner only while loading to avoid idle frame churn.
-        if app.loading.is_loading() && last_spinner_tick.elapsed() >= spinner_interval {
+        // Advance the spinner only while one is on screen to avoid idle frame churn.
+        if app.spinner_visible() && last_spinner_tick.elapsed() >= SPINNER_INTERVAL {
             app.tick_loading();
             last_spinner_tick = std::time::Instant::now();
             needs_redraw = true;
@@ -356,6 +581,7 @@ fn run_loop<B: Backend>(
                 Ok(_) => {
                     app.git_refresh_error = None;
                     app.git_refresh_error_at = None;
+                    app.observe_git_state();
                     needs_redraw = true;
                 }
                 Err(e) => {
@@ -378,42 +604,91 @@ fn run_loop<B: Backend>(
         if background::drain_messages(app, &rx, &ctx) {
             needs_redraw = true;
         }
+        // Operations close to their spend cap switch to a cheaper model; say so as it happens
+        for downgrade in cosmos_engine::llm::spend::take_downgrades() {
+            app.show_toast(downgrade.message());
+            needs_redraw = true;
+        }
+        if let Some(message) = app.pending_notification.take() {
+            let sequence = cosmos_adapters::terminal::notification_sequence(
+                app.notify_mode,
+                "Cosmos",
+                &message,
+            );
+            let backend = terminal.backend_mut();
+            backend.write_all(sequence.as_bytes())?;
+            io::Write::flush(backend)?;
+        }
+
+        // Scheduled re-scan: start one when due, and land finished results once idle.
+        if app.background_scan_due() {
+            background::request_background_scan(app, tx.clone(), repo_path.clone());
+        }
+        if app.apply_pending_rescan() || app.expire_toast() || app.expire_splash() {
+            needs_redraw = true;
+        }
+        if app.mascot_idle_due() {
+            app.show_mascot(false);
+        }
         if app.needs_redraw {
             needs_redraw = true;
         }
 
         if needs_redraw {
-            terminal.draw(|f| ui::render(f, app))?;
+            draw(terminal, app)?;
+            sync_mascot_image(terminal, app, &mut mascot_image)?;
             needs_redraw = false;
             app.needs_redraw = false;
         }
 
         // Poll until the next scheduled tick, or sooner if an input event arrives.
         let to_next_git = git_refresh_interval.saturating_sub(last_git_refresh.elapsed());
-        let to_next_spinner = if app.loading.is_loading() {
-            spinner_interval.saturating_sub(last_spinner_tick.elapsed())
-        } else {
-            idle_poll_cap
-        };
-        let poll_timeout = to_next_git.min(to_next_spinner).min(idle_poll_cap);
+        let poll_timeout = poll_timeout(app, last_spinner_tick.elapsed()).min(to_next_git);
 
         if event::poll(poll_timeout)? {
-            if let Event::Key(key) = event::read()? {
-                if key.kind != KeyEventKind::Press {
-                    continue;
+            match event::read()? {
+                Event::Key(key) if key.kind == KeyEventKind::Press => {
+                    handle_key(terminal, app, key, &ctx)?;
+                    needs_redraw = true;
+                    app.needs_redraw = true;
                 }
-                input::handle_key_event(app, key, &ctx)?;
-                needs_redraw = true;
-                app.needs_redraw = true;
+                Event::Resize(..) => {
+                    ui::reset_render_caches();
+                    needs_redraw = true;
+                }
+                Event::FocusGained => app.terminal_focused = Some(true),
+                Event::FocusLost => app.terminal_focused = Some(false),
+                _ => {}
             }
         }
 
+        if let Some(request) = app.editor_request.take() {
+            editor::open_in_editor(terminal, app, &request, &repo_path)?;
+            needs_redraw = true;
+        }
+
         if app.should_quit {
             return Ok(());
         }
     }
 }
 
+/// How long to wait for input before the loop has work of its own: the next spinner frame,
+/// a check for background results, or the toast expiring.
+fn poll_timeout(app: &App, since_spinner_tick: Duration) -> Duration {
+    let timeout = if app.spinner_visible() {
+        SPINNER_INTERVAL.saturating_sub(since_spinner_tick)
+    } else if app.tasks.active().is_some() {
+        BUSY_POLL
+    } else {
+        IDLE_POLL
+    };
+    [app.toast_remaining(), app.splash_remaining()]
+        .into_iter()
+        .flatten()
+        .fold(timeout, Duration::min)
+}
+
 fn cached_grouping_overrides(
     grouping: &cosmos_core::grouping::CodebaseGrouping,
     cache: &cache::GroupingAiCache,
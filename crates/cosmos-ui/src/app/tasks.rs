@@ -0,0 +1,138 @@
+//! Cancellable background jobs.
+//!
+//! Long AI jobs (suggestion generation, fixes, reviews) each get a `TaskHandle`. Pressing
+//! `x` cancels the active one; its task stops at the next await point and reports
+//! `BackgroundMessage::TaskCancelled` so the UI can reclaim the loading state.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
+
+/// Kind of background job the user can cancel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TaskKind {
+    Suggestions,
+    Fix,
+    Review,
+}
+
+impl TaskKind {
+    /// Most visible first: a review or fix blocks the workflow, suggestions run behind it.
+    const PRIORITY: [TaskKind; 3] = [TaskKind::Review, TaskKind::Fix, TaskKind::Suggestions];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            TaskKind::Suggestions => "Suggestion generation",
+            TaskKind::Fix => "Fix",
+            TaskKind::Review => "Review",
+        }
+    }
+}
+
+/// Shared between the UI and one spawned job.
+#[derive(Debug, Clone)]
+pub struct TaskHandle {
+    pub kind: TaskKind,
+    pub token: CancellationToken,
+    finished: Arc<AtomicBool>,
+}
+
+impl TaskHandle {
+    /// Called by the job when it stops, whether it completed or was cancelled.
+    pub fn mark_finished(&self) {
+        self.finished.store(true, Ordering::Relaxed);
+    }
+
+    fn is_running(&self) -> bool {
+        !self.finished.load(Ordering::Relaxed) && !self.token.is_cancelled()
+    }
+}
+
+/// Handles for the running jobs, at most one per kind.
+#[derive(Debug, Default)]
+pub struct TaskManager {
+    active: HashMap<TaskKind, TaskHandle>,
+}
+
+impl TaskManager {
+    /// Register a new job of `kind`, cancelling any job of the same kind it replaces.
+    pub fn start(&mut self, kind: TaskKind) -> TaskHandle {
+        let handle = TaskHandle {
+            kind,
+            token: CancellationToken::new(),
+            finished: Arc::new(AtomicBool::new(false)),
+        };
+        if let Some(previous) = self.active.insert(kind, handle.clone()) {
+            previous.token.cancel();
+        }
+        handle
+    }
+
+    /// The job `x` would cancel, if any.
+    pub fn active(&self) -> Option<TaskKind> {
+        TaskKind::PRIORITY.into_iter().find(|kind| {
+            self.active
+                .get(kind)
+                .is_some_and(|handle| handle.is_running())
+        })
+    }
+
+    /// Cancel the most visible running job, returning its kind.
+    pub fn cancel_active(&mut self) -> Option<TaskKind> {
+        let kind = self.active()?;
+        if let Some(handle) = self.active.get(&kind) {
+            handle.token.cancel();
+        }
+        Some(kind)
+    }
+
+    /// Whether a `TaskCancelled` report for `kind` belongs to the current job (rather than
+    /// one a newer job replaced). Forgets the job when it does.
+    pub fn take_cancelled(&mut self, kind: TaskKind) -> bool {
+        let current = self
+            .active
+            .get(&kind)
+            .is_some_and(|handle| handle.token.is_cancelled());
+        if current {
+            self.active.remove(&kind);
+        }
+        current
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cancel_prefers_the_blocking_job_and_skips_finished_ones() {
+        let mut tasks = TaskManager::default();
+        assert_eq!(tasks.cancel_active(), None);
+
+        let suggestions = tasks.start(TaskKind::Suggestions);
+        let review = tasks.start(TaskKind::Review);
+        assert_eq!(tasks.active(), Some(TaskKind::Review));
+
+        assert_eq!(tasks.cancel_active(), Some(TaskKind::Review));
+        assert!(review.token.is_cancelled());
+        assert!(!suggestions.token.is_cancelled());
+        assert!(tasks.take_cancelled(TaskKind::Review));
+        assert_eq!(tasks.active(), Some(TaskKind::Suggestions));
+
+        suggestions.mark_finished();
+        assert_eq!(tasks.cancel_active(), None);
+    }
+
+    #[test]
+    fn a_replaced_job_reporting_cancellation_is_ignored() {
+        let mut tasks = TaskManager::default();
+        let first = tasks.start(TaskKind::Fix);
+        let second = tasks.start(TaskKind::Fix);
+        assert!(first.token.is_cancelled());
+        assert!(!second.token.is_cancelled());
+
+        assert!(!tasks.take_cancelled(TaskKind::Fix));
+        assert_eq!(tasks.active(), Some(TaskKind::Fix));
+    }
+}
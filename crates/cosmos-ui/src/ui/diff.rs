@@ -0,0 +1,374 @@
+//! Diff model for the change viewer.
+//!
+//! Splits an applied change into hunks with intra-line highlights, tracks which hunks the
+//! user keeps, and rebuilds file contents with rejected hunks put back the way they were.
+
+use similar::{ChangeTag, DiffOp, DiffTag, TextDiff};
+use std::path::PathBuf;
+
+/// Lines of unchanged context around each hunk
+const CONTEXT_LINES: usize = 3;
+
+/// Terminal width at which the viewer switches to side-by-side by default
+pub const SIDE_BY_SIDE_MIN_WIDTH: u16 = 140;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffLineKind {
+    Context,
+    Removed,
+    Added,
+}
+
+/// A run of text within a line; `emphasized` marks the words that changed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffSegment {
+    pub text: String,
+    pub emphasized: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffLine {
+    pub kind: DiffLineKind,
+    /// 1-based line number in the original file (None for added lines)
+    pub old_line: Option<usize>,
+    /// 1-based line number in the changed file (None for removed lines)
+    pub new_line: Option<usize>,
+    pub segments: Vec<DiffSegment>,
+}
+
+impl DiffLine {
+    pub fn text(&self) -> String {
+        self.segments.iter().map(|s| s.text.as_str()).collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffHunk {
+    /// `@@ -a,b +c,d @@` header
+    pub header: String,
+    pub lines: Vec<DiffLine>,
+    /// Whether the change is kept when decisions are applied
+    pub accepted: bool,
+    ops: Vec<DiffOp>,
+}
+
+impl DiffHunk {
+    pub fn added_count(&self) -> usize {
+        self.count(DiffLineKind::Added)
+    }
+
+    pub fn removed_count(&self) -> usize {
+        self.count(DiffLineKind::Removed)
+    }
+
+    fn count(&self, kind: DiffLineKind) -> usize {
+        self.lines.iter().filter(|line| line.kind == kind).count()
+    }
+}
+
+/// One changed file, split into hunks
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileDiff {
+    pub path: PathBuf,
+    pub hunks: Vec<DiffHunk>,
+    original: String,
+    changed: String,
+}
+
+impl FileDiff {
+    pub fn new(path: PathBuf, original: &str, changed: &str) -> Self {
+        let diff = TextDiff::from_lines(original, changed);
+        let hunks = diff
+            .grouped_ops(CONTEXT_LINES)
+            .into_iter()
+            .filter(|group| !group.is_empty())
+            .map(|group| build_hunk(&diff, group))
+            .collect();
+        Self {
+            path,
+            hunks,
+            original: original.to_string(),
+            changed: changed.to_string(),
+        }
+    }
+
+    pub fn has_rejections(&self) -> bool {
+        self.hunks.iter().any(|hunk| !hunk.accepted)
+    }
+
+    /// File content with accepted hunks applied and rejected hunks reverted.
+    pub fn resolved_content(&self) -> String {
+        if !self.has_rejections() {
+            return self.changed.clone();
+        }
+        let diff = TextDiff::from_lines(&self.original, &self.changed);
+        let old_lines = diff.old_slices();
+        let new_lines = diff.new_slices();
+        let mut out = String::with_capacity(self.changed.len());
+        for op in diff.ops() {
+            let keep_change = op.tag() == DiffTag::Equal
+                || self
+                    .hunks
+                    .iter()
+                    .find(|hunk| hunk.ops.contains(op))
+                    .is_none_or(|hunk| hunk.accepted);
+            let lines = if keep_change {
+                &new_lines[op.new_range()]
+            } else {
+                &old_lines[op.old_range()]
+            };
+            for line in lines {
+                out.push_str(line);
+            }
+        }
+        out
+    }
+}
+
+fn build_hunk<'a>(diff: &'a TextDiff<'a, 'a, 'a, str>, group: Vec<DiffOp>) -> DiffHunk {
+    let first = group[0];
+    let last = group[group.len() - 1];
+    let old_start = first.old_range().start;
+    let new_start = first.new_range().start;
+    let old_len = last.old_range().end - old_start;
+    let new_len = last.new_range().end - new_start;
+    let header = format!(
+        "@@ -{},{} +{},{} @@",
+        old_start + 1,
+        old_len,
+        new_start + 1,
+        new_len
+    );
+
+    let mut lines = Vec::new();
+    for op in &group {
+        for change in diff.iter_inline_changes(op) {
+            let kind = match change.tag() {
+                ChangeTag::Equal => DiffLineKind::Context,
+                ChangeTag::Delete => DiffLineKind::Removed,
+                ChangeTag::Insert => DiffLineKind::Added,
+            };
+            let segments = change
+                .iter_strings_lossy()
+                .map(|(emphasized, text)| DiffSegment {
+                    text: text.trim_end_matches(['\n', '\r']).to_string(),
+                    emphasized,
+                })
+                .filter(|segment| !segment.text.is_empty())
+                .collect();
+            lines.push(DiffLine {
+                kind,
+                old_line: change.old_index().map(|i| i + 1),
+                new_line: change.new_index().map(|i| i + 1),
+                segments,
+            });
+        }
+    }
+
+    DiffHunk {
+        header,
+        lines,
+        accepted: true,
+        ops: group
+            .into_iter()
+            .filter(|op| op.tag() != DiffTag::Equal)
+            .collect(),
+    }
+}
+
+/// Diff viewer state: every changed file, the focused hunk, and the layout
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffView {
+    pub files: Vec<FileDiff>,
+    /// Focused hunk, counted across all files
+    pub selected: usize,
+    /// Lines scrolled within the focused hunk
+    pub scroll: usize,
+    /// Layout override; None follows the terminal width
+    pub side_by_side: Option<bool>,
+    /// Whether hunks can be rejected (false for pull request reviews)
+    pub editable: bool,
+    /// Choosing hunks of a passing fix before it is written, rather than reviewing applied changes
+    pub fix_selection: bool,
+}
+
+impl DiffView {
+    pub fn new(files: Vec<FileDiff>, editable: bool) -> Self {
+        Self {
+            files,
+            selected: 0,
+            scroll: 0,
+            side_by_side: None,
+            editable,
+            fix_selection: false,
+        }
+    }
+
+    pub fn for_fix_selection(files: Vec<FileDiff>) -> Self {
+        Self {
+            fix_selection: true,
+            ..Self::new(files, true)
+        }
+    }
+
+    pub fn hunk_count(&self) -> usize {
+        self.files.iter().map(|file| file.hunks.len()).sum()
+    }
+
+    /// (file index, hunk index) of the focused hunk
+    pub fn selected_position(&self) -> Option<(usize, usize)> {
+        let mut remaining = self.selected;
+        for (file_index, file) in self.files.iter().enumerate() {
+            if remaining < file.hunks.len() {
+                return Some((file_index, remaining));
+            }
+            remaining -= file.hunks.len();
+        }
+        None
+    }
+
+    pub fn select_next(&mut self) {
+        if self.selected + 1 < self.hunk_count() {
+            self.selected += 1;
+            self.scroll = 0;
+        }
+    }
+
+    pub fn select_prev(&mut self) {
+        if self.selected > 0 {
+            self.selected -= 1;
+            self.scroll = 0;
+        }
+    }
+
+    /// Keep or reject the focused hunk
+    pub fn set_selected_accepted(&mut self, accepted: bool) {
+        if !self.editable {
+            return;
+        }
+        if let Some((file_index, hunk_index)) = self.selected_position() {
+            self.files[file_index].hunks[hunk_index].accepted = accepted;
+        }
+    }
+
+    pub fn toggle_selected(&mut self) {
+        if let Some((file_index, hunk_index)) = self.selected_position() {
+            let accepted = self.files[file_index].hunks[hunk_index].accepted;
+            self.set_selected_accepted(!accepted);
+        }
+    }
+
+    pub fn rejected_count(&self) -> usize {
+        self.files
+            .iter()
+            .flat_map(|file| &file.hunks)
+            .filter(|hunk| !hunk.accepted)
+            .count()
+    }
+
+    /// Every file as a unified diff, for copying out of the viewer
+    pub fn unified_text(&self) -> String {
+        let mut out = String::new();
+        for file in &self.files {
+            let path = file.path.display();
+            out.push_str(&format!("--- a/{}\n+++ b/{}\n", path, path));
+            for hunk in &file.hunks {
+                out.push_str(&hunk.header);
+                out.push('\n');
+                for line in &hunk.lines {
+                    out.push(match line.kind {
+                        DiffLineKind::Context => ' ',
+                        DiffLineKind::Removed => '-',
+                        DiffLineKind::Added => '+',
+                    });
+                    out.push_str(&line.text());
+                    out.push('\n');
+                }
+            }
+        }
+        out
+    }
+
+    pub fn use_side_by_side(&self, terminal_width: u16) -> bool {
+        self.side_by_side
+            .unwrap_or(terminal_width >= SIDE_BY_SIDE_MIN_WIDTH)
+    }
+
+    pub fn toggle_layout(&mut self, terminal_width: u16) {
+        self.side_by_side = Some(!self.use_side_by_side(terminal_width));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ORIGINAL: &str = "fn a() {\n    let x = 1;\n}\n\n\n\n\n\n\n\nfn b() {\n    old();\n}\n";
+    const CHANGED: &str = "fn a() {\n    let x = 2;\n}\n\n\n\n\n\n\n\nfn b() {\n    new();\n}\n";
+
+    #[test]
+    fn splits_hunks_with_intra_line_emphasis() {
+        let file = FileDiff::new(PathBuf::from("src/lib.rs"), ORIGINAL, CHANGED);
+        assert_eq!(file.hunks.len(), 2);
+
+        let first = &file.hunks[0];
+        assert!(first.header.starts_with("@@ -1,"));
+        assert_eq!((first.removed_count(), first.added_count()), (1, 1));
+        let added = first
+            .lines
+            .iter()
+            .find(|line| line.kind == DiffLineKind::Added)
+            .unwrap();
+        assert_eq!(added.text(), "    let x = 2;");
+        assert_eq!(added.new_line, Some(2));
+        let emphasized: Vec<&str> = added
+            .segments
+            .iter()
+            .filter(|segment| segment.emphasized)
+            .map(|segment| segment.text.as_str())
+            .collect();
+        assert_eq!(emphasized, vec!["2;"]);
+    }
+
+    #[test]
+    fn unified_text_lists_every_hunk_with_line_markers() {
+        let view = DiffView::new(
+            vec![FileDiff::new(
+                PathBuf::from("src/lib.rs"),
+                ORIGINAL,
+                CHANGED,
+            )],
+            true,
+        );
+        let text = view.unified_text();
+        assert!(text.starts_with("--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -1,"));
+        assert!(text.contains("\n-    let x = 1;\n+    let x = 2;\n"));
+        assert!(text.contains("\n-    old();\n+    new();\n"));
+        assert_eq!(text.matches("@@ -").count(), 2);
+    }
+
+    #[test]
+    fn rejected_hunks_are_reverted_in_resolved_content() {
+        let mut view = DiffView::new(
+            vec![FileDiff::new(
+                PathBuf::from("src/lib.rs"),
+                ORIGINAL,
+                CHANGED,
+            )],
+            true,
+        );
+        assert_eq!(view.files[0].resolved_content(), CHANGED);
+
+        view.select_next();
+        view.set_selected_accepted(false);
+        assert_eq!(view.rejected_count(), 1);
+        let resolved = view.files[0].resolved_content();
+        assert!(resolved.contains("let x = 2;"));
+        assert!(resolved.contains("old();"));
+        assert!(!resolved.contains("new();"));
+
+        let mut read_only = DiffView::new(view.files.clone(), false);
+        read_only.toggle_selected();
+        assert_eq!(read_only.rejected_count(), 1);
+    }
+}
@@ -1,6 +1,13 @@
 //! UI helper functions and utilities
 
+use cosmos_core::util::split_at_width;
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::Style;
+use ratatui::text::{Line, Span};
+use unicode_segmentation::UnicodeSegmentation;
+
+// Width-aware text handling lives in core so CLI output measures text the same way.
+pub use cosmos_core::util::{display_width, truncate_to_width};
 
 /// Create a centered rect using up certain percentage of the available rect
 pub fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
@@ -34,6 +41,9 @@ pub fn wrap_text(text: &str, width: usize) -> Vec<String> {
 
 /// Wrap text with different widths for first line vs continuation lines
 /// This is useful when the first line has a prefix (like "Fix: ") that takes up space
+///
+/// Widths are measured in terminal columns and words are only ever broken on
+/// grapheme boundaries, so wide characters and emoji stay intact.
 pub fn wrap_text_variable_width(
     text: &str,
     first_line_width: usize,
@@ -45,6 +55,7 @@ pub fn wrap_text_variable_width(
 
     let mut lines = Vec::new();
     let mut current_line = String::new();
+    let mut current_line_width = 0;
 
     for word in text.split_whitespace() {
         // Use first_line_width for the first line, continuation_width for others
@@ -53,36 +64,21 @@ pub fn wrap_text_variable_width(
         } else {
             continuation_width
         };
+        let word_width = display_width(word);
 
         if current_line.is_empty() {
-            if word.len() > current_width {
-                // Word is longer than width, force break it
-                let mut remaining = word;
-                while remaining.len() > current_width {
-                    lines.push(remaining[..current_width].to_string());
-                    remaining = &remaining[current_width..];
-                }
-                current_line = remaining.to_string();
-            } else {
-                current_line = word.to_string();
-            }
-        } else if current_line.len() + 1 + word.len() <= current_width {
+            current_line = break_long_word(word, current_width, continuation_width, &mut lines);
+            current_line_width = display_width(&current_line);
+        } else if current_line_width + 1 + word_width <= current_width {
             current_line.push(' ');
             current_line.push_str(word);
+            current_line_width += 1 + word_width;
         } else {
-            lines.push(current_line);
+            lines.push(std::mem::take(&mut current_line));
             // After pushing, we're now on a continuation line
-            let next_width = continuation_width;
-            if word.len() > next_width {
-                let mut remaining = word;
-                while remaining.len() > next_width {
-                    lines.push(remaining[..next_width].to_string());
-                    remaining = &remaining[next_width..];
-                }
-                current_line = remaining.to_string();
-            } else {
-                current_line = word.to_string();
-            }
+            current_line =
+                break_long_word(word, continuation_width, continuation_width, &mut lines);
+            current_line_width = display_width(&current_line);
         }
     }
 
@@ -97,6 +93,146 @@ pub fn wrap_text_variable_width(
     }
 }
 
+/// Force-break a word that is wider than the line, returning the unfinished tail
+fn break_long_word(
+    word: &str,
+    first_width: usize,
+    continuation_width: usize,
+    lines: &mut Vec<String>,
+) -> String {
+    let mut remaining = word;
+    let mut width = first_width;
+    while display_width(remaining) > width {
+        let (mut head, mut tail) = split_at_width(remaining, width);
+        if head.is_empty() {
+            // A single grapheme wider than the line still has to go somewhere
+            let first_len = remaining.graphemes(true).next().map_or(0, str::len);
+            (head, tail) = remaining.split_at(first_len);
+        }
+        lines.push(head.to_string());
+        remaining = tail;
+        width = continuation_width;
+    }
+    remaining.to_string()
+}
+
+/// Wrap styled spans, keeping each piece's style, with different widths for the first line
+/// and continuation lines
+///
+/// Lines only break at whitespace, so pieces that touch (`**bold**,` or a link followed by
+/// a full stop) stay together, and a non-breaking space (U+00A0) keeps its neighbours on one
+/// line. A `\n` forces a break. Words wider than a whole line are split on grapheme
+/// boundaries.
+pub fn wrap_spans(
+    spans: &[Span<'_>],
+    first_line_width: usize,
+    continuation_width: usize,
+) -> Vec<Line<'static>> {
+    enum Token {
+        Word(Vec<(String, Style)>),
+        Space(Style),
+        Break,
+    }
+
+    let mut tokens = Vec::new();
+    let mut word: Vec<(String, Style)> = Vec::new();
+    for span in spans {
+        for ch in span.content.chars() {
+            if ch == '\n' || (ch.is_whitespace() && ch != '\u{a0}') {
+                if !word.is_empty() {
+                    tokens.push(Token::Word(std::mem::take(&mut word)));
+                }
+                if ch == '\n' {
+                    tokens.push(Token::Break);
+                } else if !matches!(tokens.last(), Some(Token::Space(_))) {
+                    tokens.push(Token::Space(span.style));
+                }
+                continue;
+            }
+            match word.last_mut() {
+                Some((text, style)) if *style == span.style => text.push(ch),
+                _ => word.push((ch.to_string(), span.style)),
+            }
+        }
+    }
+    if !word.is_empty() {
+        tokens.push(Token::Word(word));
+    }
+
+    if first_line_width == 0 || continuation_width == 0 {
+        let line: Vec<Span<'static>> = spans
+            .iter()
+            .map(|span| Span::styled(span.content.to_string(), span.style))
+            .collect();
+        return vec![Line::from(line)];
+    }
+
+    let mut lines: Vec<Line<'static>> = Vec::new();
+    let mut current: Vec<Span<'static>> = Vec::new();
+    let mut used = 0;
+    let mut pending_space = None;
+    let limit = |lines: &Vec<Line<'static>>| {
+        if lines.is_empty() {
+            first_line_width
+        } else {
+            continuation_width
+        }
+    };
+
+    for token in tokens {
+        match token {
+            Token::Break => {
+                lines.push(Line::from(std::mem::take(&mut current)));
+                used = 0;
+                pending_space = None;
+            }
+            Token::Space(style) => {
+                if used > 0 {
+                    pending_space = Some(style);
+                }
+            }
+            Token::Word(pieces) => {
+                let word_width: usize = pieces.iter().map(|(text, _)| display_width(text)).sum();
+                if let Some(style) = pending_space.take() {
+                    if used + 1 + word_width <= limit(&lines) {
+                        current.push(Span::styled(" ", style));
+                        used += 1;
+                    } else {
+                        lines.push(Line::from(std::mem::take(&mut current)));
+                        used = 0;
+                    }
+                }
+                for (text, style) in pieces {
+                    let mut remaining = text.as_str();
+                    while !remaining.is_empty() {
+                        let room = limit(&lines).saturating_sub(used);
+                        let (mut head, mut tail) = split_at_width(remaining, room);
+                        if head.is_empty() && used == 0 {
+                            // A single grapheme wider than the line still has to go somewhere
+                            let first_len = remaining.graphemes(true).next().map_or(0, str::len);
+                            (head, tail) = remaining.split_at(first_len);
+                        }
+                        if !head.is_empty() {
+                            current.push(Span::styled(head.to_string(), style));
+                            used += display_width(head);
+                        }
+                        if !tail.is_empty() {
+                            lines.push(Line::from(std::mem::take(&mut current)));
+                            used = 0;
+                        }
+                        remaining = tail;
+                    }
+                }
+            }
+        }
+    }
+
+    if !current.is_empty() || lines.is_empty() {
+        lines.push(Line::from(current));
+    }
+    lines
+}
+
 /// Convert the first character of a string to lowercase
 pub fn lowercase_first(s: &str) -> String {
     let mut chars = s.chars();
@@ -106,6 +242,33 @@ pub fn lowercase_first(s: &str) -> String {
     }
 }
 
+/// Compact token count: `950`, `12.3k`, `131k`
+pub fn format_token_count(tokens: u32) -> String {
+    match tokens {
+        0..=999 => tokens.to_string(),
+        1_000..=99_999 => format!("{:.1}k", tokens as f64 / 1000.0),
+        _ => format!("{}k", tokens / 1000),
+    }
+}
+
+/// Context usage bar: `▰▰▱▱▱▱▱▱▱▱ 12.3k of 131k tokens (9%)`
+pub fn context_usage_bar(used: u32, window: u32, width: usize) -> String {
+    let fraction = if window == 0 {
+        0.0
+    } else {
+        used as f64 / window as f64
+    };
+    let filled = ((fraction * width as f64).ceil() as usize).min(width);
+    format!(
+        "{}{} {} of {} tokens ({:.0}%)",
+        "▰".repeat(filled),
+        "▱".repeat(width - filled),
+        format_token_count(used),
+        format_token_count(window),
+        fraction * 100.0
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -125,6 +288,78 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_wrap_text_measures_cjk_by_display_width() {
+        let result = wrap_text("日本語のテキスト 折り返し", 8);
+        assert_eq!(result, vec!["日本語の", "テキスト", "折り返し"]);
+        for line in &result {
+            assert!(display_width(line) <= 8);
+        }
+    }
+
+    #[test]
+    fn test_wrap_text_breaks_long_words_on_grapheme_boundaries() {
+        let word = "e\u{301}".repeat(6);
+        let result = wrap_text(&word, 4);
+        assert_eq!(result, vec!["e\u{301}".repeat(4), "e\u{301}".repeat(2)]);
+
+        let emoji = "👍🏽👍🏽👍🏽";
+        let result = wrap_text(emoji, 4);
+        assert_eq!(result, vec!["👍🏽👍🏽", "👍🏽"]);
+    }
+
+    #[test]
+    fn test_wrap_text_places_oversized_grapheme_on_its_own_line() {
+        let result = wrap_text("漢字", 1);
+        assert_eq!(result, vec!["漢", "字"]);
+    }
+
+    #[test]
+    fn test_wrap_spans_keeps_styles_and_glued_punctuation() {
+        use ratatui::style::{Modifier, Style};
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        let code = Style::default().add_modifier(Modifier::ITALIC);
+        let spans = [
+            Span::raw("Call "),
+            Span::styled("retry", bold),
+            Span::raw(", then "),
+            Span::styled("`cargo\u{a0}test`", code),
+            Span::raw(" again."),
+        ];
+        let lines = wrap_spans(&spans, 20, 20);
+        let text: Vec<String> = lines.iter().map(|line| line.to_string()).collect();
+        assert_eq!(text, vec!["Call retry, then", "`cargo\u{a0}test` again."]);
+        assert!(lines[0]
+            .spans
+            .iter()
+            .any(|s| s.content == "retry" && s.style == bold));
+        assert!(lines[1]
+            .spans
+            .iter()
+            .any(|s| s.content == "`cargo\u{a0}test`" && s.style == code));
+
+        // Over-long words still break, and explicit newlines are kept
+        let lines = wrap_spans(&[Span::raw("abcdefgh\nij")], 3, 3);
+        let text: Vec<String> = lines.iter().map(|line| line.to_string()).collect();
+        assert_eq!(text, vec!["abc", "def", "gh", "ij"]);
+    }
+
+    #[test]
+    fn test_truncate_to_width_ascii() {
+        assert_eq!(truncate_to_width("hello world", 8), "hello w…");
+        assert_eq!(truncate_to_width("hello", 8), "hello");
+        assert_eq!(truncate_to_width("hello", 0), "");
+    }
+
+    #[test]
+    fn test_truncate_to_width_wide_and_combining() {
+        // Each CJK char takes two columns; never exceed the budget
+        assert_eq!(truncate_to_width("こんにちは", 6), "こん…");
+        assert_eq!(truncate_to_width("こんにちは", 5), "こん…");
+        assert_eq!(truncate_to_width("cafe\u{301} au lait", 6), "cafe\u{301} …");
+        assert!(display_width(&truncate_to_width("🚀🚀🚀🚀", 5)) <= 5);
+    }
+
     #[test]
     fn test_wrap_text_empty() {
         let result = wrap_text("", 10);
@@ -147,6 +382,19 @@ mod tests {
         assert_eq!(lowercase_first("already"), "already");
     }
 
+    #[test]
+    fn test_context_usage_bar() {
+        assert_eq!(format_token_count(950), "950");
+        assert_eq!(format_token_count(12_345), "12.3k");
+        assert_eq!(format_token_count(131_072), "131k");
+        assert_eq!(
+            context_usage_bar(12_345, 131_072, 10),
+            "▰▱▱▱▱▱▱▱▱▱ 12.3k of 131k tokens (9%)"
+        );
+        assert!(context_usage_bar(200_000, 131_072, 4).starts_with("▰▰▰▰ "));
+        assert!(context_usage_bar(0, 0, 4).starts_with("▱▱▱▱ 0 of 0"));
+    }
+
     #[test]
     fn test_centered_rect() {
         use ratatui::layout::Rect;
@@ -0,0 +1,616 @@
+//! Key bindings - translates key presses into semantic actions
+//!
+//! The defaults are the built-in bindings; `keybindings` in `~/.config/cosmos/config.json`
+//! replaces the keys of individual actions. The help overlay is generated from the active map.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::BTreeMap;
+
+/// Help overlay section an action is listed under
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HelpSection {
+    Navigation,
+    Actions,
+    Review,
+    Ship,
+    Privacy,
+}
+
+impl HelpSection {
+    pub const ALL: [HelpSection; 5] = [
+        HelpSection::Navigation,
+        HelpSection::Actions,
+        HelpSection::Review,
+        HelpSection::Ship,
+        HelpSection::Privacy,
+    ];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            HelpSection::Navigation => "Navigation",
+            HelpSection::Actions => "Actions",
+            HelpSection::Review => "Review",
+            HelpSection::Ship => "Ship",
+            HelpSection::Privacy => "Privacy",
+        }
+    }
+}
+
+/// Something a key press can do outside of text input and overlays
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    TogglePanel,
+    NavigateUp,
+    NavigateDown,
+    Confirm,
+    Back,
+    OpenApplyPlan,
+    OpenInEditor,
+    Copy,
+    RefreshSuggestions,
+    RateGood,
+    RateBad,
+    RateIrrelevant,
+    Snooze,
+    ShowSnoozed,
+    BulkDismiss,
+    RepeatLast,
+    ChooseFocus,
+    CyclePackage,
+    Search,
+    ToggleSort,
+    AskPickFiles,
+    AskNewConversation,
+    CancelTask,
+    Undo,
+    ApiKeySetup,
+    ToggleHelp,
+    Quit,
+    ToggleFinding,
+    SelectAllFindings,
+    FixFindings,
+    EditFindingTitle,
+    EditFindingDescription,
+    DowngradeFinding,
+    ShowDiff,
+    ToggleDraft,
+    EditBase,
+    EditReviewers,
+    EditLabels,
+    EditTitle,
+    EditDescription,
+    EditCommitMessage,
+    RegenerateCommitMessage,
+    SplitCommits,
+    MoveCommitUp,
+    MoveCommitDown,
+    ToggleCommit,
+    OpenStack,
+    Branches,
+    Rebase,
+    CommandLog,
+    Reset,
+    Settings,
+    WorkspaceTrust,
+    Update,
+}
+
+impl Action {
+    pub const ALL: [Action; 54] = [
+        Action::TogglePanel,
+        Action::NavigateUp,
+        Action::NavigateDown,
+        Action::Confirm,
+        Action::Back,
+        Action::OpenApplyPlan,
+        Action::OpenInEditor,
+        Action::Copy,
+        Action::RefreshSuggestions,
+        Action::RateGood,
+        Action::RateBad,
+        Action::RateIrrelevant,
+        Action::Snooze,
+        Action::ShowSnoozed,
+        Action::BulkDismiss,
+        Action::RepeatLast,
+        Action::ChooseFocus,
+        Action::CyclePackage,
+        Action::Search,
+        Action::ToggleSort,
+        Action::AskPickFiles,
+        Action::AskNewConversation,
+        Action::CancelTask,
+        Action::Undo,
+        Action::ApiKeySetup,
+        Action::ToggleHelp,
+        Action::Quit,
+        Action::ToggleFinding,
+        Action::SelectAllFindings,
+        Action::FixFindings,
+        Action::EditFindingTitle,
+        Action::EditFindingDescription,
+        Action::DowngradeFinding,
+        Action::ShowDiff,
+        Action::ToggleDraft,
+        Action::EditBase,
+        Action::EditReviewers,
+        Action::EditLabels,
+        Action::EditTitle,
+        Action::EditDescription,
+        Action::EditCommitMessage,
+        Action::RegenerateCommitMessage,
+        Action::SplitCommits,
+        Action::MoveCommitUp,
+        Action::MoveCommitDown,
+        Action::ToggleCommit,
+        Action::OpenStack,
+        Action::Branches,
+        Action::Rebase,
+        Action::CommandLog,
+        Action::Reset,
+        Action::Settings,
+        Action::WorkspaceTrust,
+        Action::Update,
+    ];
+
+    /// Key used for this action in `keybindings`
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::TogglePanel => "toggle_panel",
+            Action::NavigateUp => "navigate_up",
+            Action::NavigateDown => "navigate_down",
+            Action::Confirm => "confirm",
+            Action::Back => "back",
+            Action::OpenApplyPlan => "open_apply_plan",
+            Action::OpenInEditor => "open_in_editor",
+            Action::Copy => "copy",
+            Action::RefreshSuggestions => "refresh_suggestions",
+            Action::RateGood => "rate_good",
+            Action::RateBad => "rate_bad",
+            Action::RateIrrelevant => "rate_irrelevant",
+            Action::Snooze => "snooze",
+            Action::ShowSnoozed => "show_snoozed",
+            Action::BulkDismiss => "bulk_dismiss",
+            Action::RepeatLast => "repeat_last",
+            Action::ChooseFocus => "choose_focus",
+            Action::CyclePackage => "cycle_package",
+            Action::Search => "search",
+            Action::ToggleSort => "toggle_sort",
+            Action::AskPickFiles => "ask_pick_files",
+            Action::AskNewConversation => "ask_new_conversation",
+            Action::CancelTask => "cancel_task",
+            Action::Undo => "undo",
+            Action::ApiKeySetup => "api_key_setup",
+            Action::ToggleHelp => "toggle_help",
+            Action::Quit => "quit",
+            Action::ToggleFinding => "toggle_finding",
+            Action::SelectAllFindings => "select_all_findings",
+            Action::FixFindings => "fix_findings",
+            Action::EditFindingTitle => "edit_finding_title",
+            Action::EditFindingDescription => "edit_finding_description",
+            Action::DowngradeFinding => "downgrade_finding",
+            Action::ShowDiff => "show_diff",
+            Action::ToggleDraft => "toggle_draft",
+            Action::EditBase => "edit_base",
+            Action::EditReviewers => "edit_reviewers",
+            Action::EditLabels => "edit_labels",
+            Action::EditTitle => "edit_title",
+            Action::EditDescription => "edit_description",
+            Action::EditCommitMessage => "edit_commit_message",
+            Action::RegenerateCommitMessage => "regenerate_commit_message",
+            Action::SplitCommits => "split_commits",
+            Action::MoveCommitUp => "move_commit_up",
+            Action::MoveCommitDown => "move_commit_down",
+            Action::ToggleCommit => "toggle_commit",
+            Action::OpenStack => "open_stack",
+            Action::Branches => "branches",
+            Action::Rebase => "rebase",
+            Action::CommandLog => "command_log",
+            Action::Reset => "reset",
+            Action::Settings => "settings",
+            Action::WorkspaceTrust => "workspace_trust",
+            Action::Update => "update",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|action| action.name() == name)
+    }
+
+    /// Help overlay text
+    pub fn description(self) -> &'static str {
+        match self {
+            Action::TogglePanel => "Switch suggestions/ask",
+            Action::NavigateUp => "Move up",
+            Action::NavigateDown => "Move down",
+            Action::Confirm => "Expand suggestion / confirm",
+            Action::Back => "Go back / cancel",
+            Action::OpenApplyPlan => "Open apply plan",
+            Action::OpenInEditor => "Open file in $EDITOR",
+            Action::Copy => "Copy suggestion, finding, answer, or PR URL",
+            Action::RefreshSuggestions => "Refresh suggestions",
+            Action::RateGood => "Rate suggestion good",
+            Action::RateBad => "Rate suggestion bad",
+            Action::RateIrrelevant => "Rate suggestion irrelevant",
+            Action::Snooze => "Snooze suggestion for days or until its file changes",
+            Action::ShowSnoozed => "Review / unsnooze snoozed suggestions",
+            Action::BulkDismiss => "Dismiss all suggestions of a kind or in a directory",
+            Action::RepeatLast => "Repeat the last rate, snooze, or preview on the next suggestion",
+            Action::ChooseFocus => "Choose bug/security mode",
+            Action::CyclePackage => "Scope to a workspace package",
+            Action::Search => "Filter suggestions (kind: priority: confidence: file: text)",
+            Action::ToggleSort => "Sort suggestions by impact / relevance",
+            Action::AskPickFiles => "Pick files to ground Ask",
+            Action::AskNewConversation => "Start a new Ask conversation",
+            Action::CancelTask => "Cancel running AI task",
+            Action::Undo => "Undo last applied fix",
+            Action::ApiKeySetup => "Open Cerebras setup guide",
+            Action::ToggleHelp => "Show help",
+            Action::Quit => "Quit",
+            Action::ToggleFinding => "Select finding",
+            Action::SelectAllFindings => "Select all findings",
+            Action::FixFindings => "Fix selected findings",
+            Action::EditFindingTitle => "Edit finding title",
+            Action::EditFindingDescription => "Edit finding description",
+            Action::DowngradeFinding => "Lower finding severity",
+            Action::ShowDiff => "Diff changes under review",
+            Action::ToggleDraft => "Toggle draft PR",
+            Action::EditBase => "Edit base branch",
+            Action::EditReviewers => "Edit reviewers",
+            Action::EditLabels => "Edit labels",
+            Action::EditTitle => "Edit PR title",
+            Action::EditDescription => "Edit PR description",
+            Action::EditCommitMessage => "Edit commit message",
+            Action::RegenerateCommitMessage => "Write commit message from staged diff",
+            Action::SplitCommits => "One commit per applied change",
+            Action::MoveCommitUp => "Move commit earlier",
+            Action::MoveCommitDown => "Move commit later",
+            Action::ToggleCommit => "Keep / leave out change",
+            Action::OpenStack => "Show stacked branches",
+            Action::Branches => "Cosmos branches: switch, PR status, and cleanup",
+            Action::Rebase => "Rebase the fix branch onto its updated base",
+            Action::CommandLog => "Show quick-check output",
+            Action::Reset => "Reset Cosmos",
+            Action::Settings => "Settings (color theme)",
+            Action::WorkspaceTrust => "Trust / restrict this repo",
+            Action::Update => "Check for updates",
+        }
+    }
+
+    pub fn section(self) -> HelpSection {
+        match self {
+            Action::TogglePanel
+            | Action::NavigateUp
+            | Action::NavigateDown
+            | Action::Confirm
+            | Action::Back => HelpSection::Navigation,
+            Action::ToggleFinding
+            | Action::SelectAllFindings
+            | Action::FixFindings
+            | Action::EditFindingTitle
+            | Action::EditFindingDescription
+            | Action::DowngradeFinding
+            | Action::ShowDiff => HelpSection::Review,
+            Action::ToggleDraft
+            | Action::EditBase
+            | Action::EditReviewers
+            | Action::EditLabels
+            | Action::EditTitle
+            | Action::EditDescription
+            | Action::EditCommitMessage
+            | Action::RegenerateCommitMessage
+            | Action::SplitCommits
+            | Action::MoveCommitUp
+            | Action::MoveCommitDown
+            | Action::ToggleCommit
+            | Action::OpenStack
+            | Action::Branches
+            | Action::Rebase => HelpSection::Ship,
+            Action::Reset | Action::Settings | Action::WorkspaceTrust | Action::Update => {
+                HelpSection::Privacy
+            }
+            _ => HelpSection::Actions,
+        }
+    }
+
+    fn default_keys(self) -> &'static [&'static str] {
+        match self {
+            Action::TogglePanel => &["tab"],
+            Action::NavigateUp => &["up"],
+            Action::NavigateDown => &["down"],
+            Action::Confirm => &["enter"],
+            Action::Back => &["esc"],
+            Action::OpenApplyPlan => &["o"],
+            Action::OpenInEditor => &["e"],
+            Action::Copy => &["y"],
+            Action::RefreshSuggestions => &["r"],
+            Action::RateGood => &["+"],
+            Action::RateBad => &["-"],
+            Action::RateIrrelevant => &["i"],
+            Action::Snooze => &["z"],
+            Action::ShowSnoozed => &["Z"],
+            Action::BulkDismiss => &["D"],
+            Action::RepeatLast => &["."],
+            Action::ChooseFocus => &["m"],
+            Action::CyclePackage => &["p"],
+            Action::Search => &["/"],
+            Action::ToggleSort => &["I"],
+            Action::AskPickFiles => &["ctrl+f"],
+            Action::AskNewConversation => &["ctrl+n"],
+            Action::CancelTask => &["x"],
+            Action::Undo => &["u"],
+            Action::ApiKeySetup => &["k"],
+            Action::ToggleHelp => &["?"],
+            Action::Quit => &["q"],
+            Action::ToggleFinding => &["space"],
+            Action::SelectAllFindings => &["a"],
+            Action::FixFindings => &["f"],
+            Action::EditFindingTitle => &["t"],
+            Action::EditFindingDescription => &["e"],
+            Action::DowngradeFinding => &["-"],
+            Action::ShowDiff => &["d"],
+            Action::ToggleDraft => &["d"],
+            Action::EditBase => &["b"],
+            Action::EditReviewers => &["r"],
+            Action::EditLabels => &["l"],
+            Action::EditTitle => &["t"],
+            Action::EditDescription => &["e"],
+            Action::EditCommitMessage => &["c"],
+            Action::RegenerateCommitMessage => &["g"],
+            Action::SplitCommits => &["m"],
+            Action::MoveCommitUp => &["K"],
+            Action::MoveCommitDown => &["J"],
+            Action::ToggleCommit => &["space"],
+            Action::OpenStack => &["s"],
+            Action::Branches => &["B"],
+            Action::Rebase => &["M"],
+            Action::CommandLog => &["L"],
+            Action::Reset => &["R"],
+            Action::Settings => &["S"],
+            Action::WorkspaceTrust => &["T"],
+            Action::Update => &["U"],
+        }
+    }
+}
+
+/// One key, with the Ctrl/Alt modifiers it requires (Shift is implied by the character)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyBinding {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyBinding {
+    /// Parse `ctrl+f`, `alt+enter`, `j`, `R`, `space`, `pagedown`, `f5`, ...
+    pub fn parse(spec: &str) -> Option<Self> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = spec.trim();
+        loop {
+            let lower = rest.to_ascii_lowercase();
+            if lower.len() > 1 && lower.starts_with("ctrl+") {
+                modifiers |= KeyModifiers::CONTROL;
+                rest = &rest[5..];
+            } else if lower.len() > 1 && lower.starts_with("alt+") {
+                modifiers |= KeyModifiers::ALT;
+                rest = &rest[4..];
+            } else {
+                break;
+            }
+        }
+        let code = match rest.to_ascii_lowercase().as_str() {
+            "enter" | "return" => KeyCode::Enter,
+            "esc" | "escape" => KeyCode::Esc,
+            "tab" => KeyCode::Tab,
+            "backtab" => KeyCode::BackTab,
+            "space" => KeyCode::Char(' '),
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "backspace" => KeyCode::Backspace,
+            "delete" => KeyCode::Delete,
+            lower => {
+                let mut chars = rest.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => KeyCode::Char(c),
+                    _ => {
+                        let n = lower.strip_prefix('f')?.parse::<u8>().ok()?;
+                        if !(1..=12).contains(&n) {
+                            return None;
+                        }
+                        KeyCode::F(n)
+                    }
+                }
+            }
+        };
+        if matches!(code, KeyCode::Char(c) if c.is_whitespace() && c != ' ') {
+            return None;
+        }
+        Some(Self { code, modifiers })
+    }
+
+    pub fn matches(&self, key: &KeyEvent) -> bool {
+        let required = KeyModifiers::CONTROL | KeyModifiers::ALT;
+        key.code == self.code && key.modifiers & required == self.modifiers & required
+    }
+
+    /// Short label for help and hints (`^F`, `↵`, `Space`)
+    pub fn label(&self) -> String {
+        let key = match self.code {
+            KeyCode::Enter => "↵".to_string(),
+            KeyCode::Esc => "Esc".to_string(),
+            KeyCode::Tab => "Tab".to_string(),
+            KeyCode::BackTab => "⇧Tab".to_string(),
+            KeyCode::Char(' ') => "Space".to_string(),
+            KeyCode::Up => "↑".to_string(),
+            KeyCode::Down => "↓".to_string(),
+            KeyCode::Left => "←".to_string(),
+            KeyCode::Right => "→".to_string(),
+            KeyCode::PageUp => "PgUp".to_string(),
+            KeyCode::PageDown => "PgDn".to_string(),
+            KeyCode::Home => "Home".to_string(),
+            KeyCode::End => "End".to_string(),
+            KeyCode::Backspace => "⌫".to_string(),
+            KeyCode::Delete => "Del".to_string(),
+            KeyCode::F(n) => format!("F{}", n),
+            KeyCode::Char(c) if self.modifiers.contains(KeyModifiers::CONTROL) => {
+                c.to_ascii_uppercase().to_string()
+            }
+            KeyCode::Char(c) => c.to_string(),
+            _ => "?".to_string(),
+        };
+        let mut label = String::new();
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            label.push('^');
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            label.push_str("M-");
+        }
+        label.push_str(&key);
+        label
+    }
+}
+
+/// The active key map
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Keymap {
+    bindings: Vec<(Action, Vec<KeyBinding>)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let bindings = Action::ALL
+            .into_iter()
+            .map(|action| {
+                let keys = action
+                    .default_keys()
+                    .iter()
+                    .filter_map(|spec| KeyBinding::parse(spec))
+                    .collect();
+                (action, keys)
+            })
+            .collect();
+        Self { bindings }
+    }
+}
+
+impl Keymap {
+    /// Defaults with `keybindings` overrides applied (action name to key list).
+    ///
+    /// An override replaces every default key of that action; an empty list unbinds it.
+    /// Unknown actions and unparseable keys are skipped and returned as problems.
+    pub fn from_config(overrides: &BTreeMap<String, Vec<String>>) -> (Self, Vec<String>) {
+        let mut keymap = Self::default();
+        let mut problems = Vec::new();
+        for (name, specs) in overrides {
+            let Some(action) = Action::from_name(name) else {
+                problems.push(format!("keybindings.{}: unknown action", name));
+                continue;
+            };
+            let mut keys = Vec::new();
+            for spec in specs {
+                match KeyBinding::parse(spec) {
+                    Some(key) => keys.push(key),
+                    None => problems.push(format!("keybindings.{}: '{}' is not a key", name, spec)),
+                }
+            }
+            if let Some((_, bound)) = keymap.bindings.iter_mut().find(|(a, _)| *a == action) {
+                *bound = keys;
+            }
+        }
+        (keymap, problems)
+    }
+
+    /// Every action bound to this key press, in declaration order
+    pub fn actions_for(&self, key: &KeyEvent) -> Vec<Action> {
+        self.bindings
+            .iter()
+            .filter(|(_, keys)| keys.iter().any(|binding| binding.matches(key)))
+            .map(|(action, _)| *action)
+            .collect()
+    }
+
+    pub fn is_bound(&self, action: Action, key: &KeyEvent) -> bool {
+        self.keys(action).iter().any(|binding| binding.matches(key))
+    }
+
+    pub fn keys(&self, action: Action) -> &[KeyBinding] {
+        self.bindings
+            .iter()
+            .find(|(a, _)| *a == action)
+            .map(|(_, keys)| keys.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Label for an action's keys (`↑/k`), or `-` when unbound
+    pub fn label(&self, action: Action) -> String {
+        let keys = self.keys(action);
+        if keys.is_empty() {
+            return "-".to_string();
+        }
+        keys.iter()
+            .map(KeyBinding::label)
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_key_specs_and_matches_events() {
+        let ctrl_f = KeyBinding::parse("ctrl+f").unwrap();
+        assert!(ctrl_f.matches(&KeyEvent::new(KeyCode::Char('f'), KeyModifiers::CONTROL)));
+        assert!(!ctrl_f.matches(&KeyEvent::new(KeyCode::Char('f'), KeyModifiers::NONE)));
+        assert_eq!(ctrl_f.label(), "^F");
+
+        let upper = KeyBinding::parse("R").unwrap();
+        assert!(upper.matches(&KeyEvent::new(KeyCode::Char('R'), KeyModifiers::SHIFT)));
+        assert_eq!(KeyBinding::parse("space").unwrap().code, KeyCode::Char(' '));
+        assert_eq!(KeyBinding::parse("F5").unwrap().code, KeyCode::F(5));
+        assert!(KeyBinding::parse("ctrl+").is_none());
+        assert!(KeyBinding::parse("f13").is_none());
+        assert!(KeyBinding::parse("jk").is_none());
+    }
+
+    #[test]
+    fn overrides_replace_default_keys_and_report_problems() {
+        let overrides = BTreeMap::from([
+            (
+                "navigate_down".to_string(),
+                vec!["down".to_string(), "j".to_string()],
+            ),
+            ("quit".to_string(), vec!["ctrl+q".to_string()]),
+            ("launch".to_string(), vec!["l".to_string()]),
+            ("undo".to_string(), vec!["hyper+z".to_string()]),
+        ]);
+        let (keymap, problems) = Keymap::from_config(&overrides);
+
+        let j = KeyEvent::new(KeyCode::Char('j'), KeyModifiers::NONE);
+        assert_eq!(keymap.actions_for(&j), vec![Action::NavigateDown]);
+        let q = KeyEvent::new(KeyCode::Char('q'), KeyModifiers::NONE);
+        assert!(keymap.actions_for(&q).is_empty());
+        assert_eq!(keymap.label(Action::NavigateDown), "↓/j");
+        assert_eq!(keymap.label(Action::Undo), "-");
+        assert_eq!(
+            problems,
+            vec![
+                "keybindings.launch: unknown action".to_string(),
+                "keybindings.undo: 'hyper+z' is not a key".to_string(),
+            ]
+        );
+
+        // Context-dependent keys resolve to every candidate action.
+        let r = KeyEvent::new(KeyCode::Char('r'), KeyModifiers::NONE);
+        assert_eq!(
+            Keymap::default().actions_for(&r),
+            vec![Action::RefreshSuggestions, Action::EditReviewers]
+        );
+    }
+}
@@ -0,0 +1,262 @@
+//! Clickable OSC-8 hyperlinks for PR URLs, file paths and markdown links.
+//!
+//! Escape sequences can't live inside buffer cells: ratatui measures each cell symbol with
+//! `unicode-width`, which counts every escape byte as a column and throws the frame diff
+//! off. Instead, render code styles link text with [`link_span`], which underlines it and
+//! remembers its target, and [`write_hyperlinks`] repaints those underlined cells wrapped
+//! in OSC-8 sequences right after each draw. Terminals without OSC-8 support ignore the
+//! sequences; `COSMOS_HYPERLINKS=0` turns them off entirely.
+
+use super::helpers::display_width;
+use ratatui::buffer::Buffer;
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Span;
+use std::cell::RefCell;
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Links remembered across frames, so cached renders keep their targets
+const MAX_LINKS: usize = 256;
+
+thread_local! {
+    static LINKS: RefCell<Vec<(String, String)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Whether hyperlinks are written at all (`COSMOS_HYPERLINKS=0` or `TERM=dumb` disable them)
+pub fn hyperlinks_enabled() -> bool {
+    static ENABLED: OnceLock<bool> = OnceLock::new();
+    *ENABLED.get_or_init(|| {
+        let opted_out = std::env::var("COSMOS_HYPERLINKS")
+            .map(|v| matches!(v.to_lowercase().as_str(), "0" | "false" | "no" | "off"))
+            .unwrap_or(false);
+        !opted_out && std::env::var("TERM").map(|t| t != "dumb").unwrap_or(true)
+    })
+}
+
+/// Styled, underlined span for `text` that opens `url` when clicked
+pub fn link_span(text: impl Into<String>, url: &str, style: Style) -> Span<'static> {
+    let text = text.into();
+    register(&text, url);
+    Span::styled(text, style.add_modifier(Modifier::UNDERLINED))
+}
+
+/// Remember that underlined `text` on screen points at `url`
+///
+/// Only `http`, `https` and `file` targets made of printable ASCII are kept, so model
+/// output can't smuggle control sequences or odd schemes into the terminal.
+pub fn register(text: &str, url: &str) {
+    let text = text.trim();
+    if text.is_empty() || !is_safe_url(url) {
+        return;
+    }
+    LINKS.with(|links| {
+        let mut links = links.borrow_mut();
+        if links.iter().any(|(t, u)| t == text && u == url) {
+            return;
+        }
+        links.retain(|(t, _)| t != text);
+        if links.len() >= MAX_LINKS {
+            links.remove(0);
+        }
+        links.push((text.to_string(), url.to_string()));
+    });
+}
+
+fn is_safe_url(url: &str) -> bool {
+    let scheme_ok = ["https://", "http://", "file://"]
+        .iter()
+        .any(|scheme| url.starts_with(scheme));
+    scheme_ok && url.bytes().all(|b| b.is_ascii_graphic())
+}
+
+/// `file://` URL for a path inside the repository
+pub fn file_url(repo_root: &Path, path: &Path) -> String {
+    let absolute = repo_root.join(path);
+    let mut url = String::from("file://");
+    for byte in absolute.to_string_lossy().bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'/' | b'-' | b'_' | b'.' | b'~' => {
+                url.push(byte as char)
+            }
+            _ => url.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    url
+}
+
+/// Repaint registered link text in `buffer` wrapped in OSC-8 hyperlinks
+///
+/// Matches runs of underlined cells against the registered link text; a run that holds
+/// part of a link (the text wrapped onto another line) still links to the whole target.
+pub fn write_hyperlinks<W: Write>(out: &mut W, buffer: &Buffer) -> io::Result<()> {
+    if !hyperlinks_enabled() {
+        return Ok(());
+    }
+    LINKS.with(|links| {
+        let links = links.borrow();
+        if links.is_empty() {
+            return Ok(());
+        }
+
+        let mut wrote = false;
+        let area = buffer.area;
+        for y in area.top()..area.bottom() {
+            let mut run: Vec<u16> = Vec::new();
+            let mut x = area.left();
+            while x < area.right() {
+                let cell = &buffer[(x, y)];
+                // Wide characters blank the cells they cover, so step over those
+                let width = display_width(cell.symbol()).max(1) as u16;
+                if cell.modifier.contains(Modifier::UNDERLINED) {
+                    run.push(x);
+                } else if !run.is_empty() {
+                    wrote |= write_run(out, buffer, y, &run, &links)?;
+                    run.clear();
+                }
+                x = x.saturating_add(width);
+            }
+            if !run.is_empty() {
+                wrote |= write_run(out, buffer, y, &run, &links)?;
+            }
+        }
+        if wrote {
+            out.flush()?;
+        }
+        Ok(())
+    })
+}
+
+fn write_run<W: Write>(
+    out: &mut W,
+    buffer: &Buffer,
+    y: u16,
+    cells: &[u16],
+    links: &[(String, String)],
+) -> io::Result<bool> {
+    // Leave padding outside the link so only the text itself is clickable
+    let is_blank = |x: &u16| buffer[(*x, y)].symbol().trim().is_empty();
+    let start = cells.iter().position(|x| !is_blank(x));
+    let end = cells.iter().rposition(|x| !is_blank(x));
+    let (Some(start), Some(end)) = (start, end) else {
+        return Ok(false);
+    };
+    let cells = &cells[start..=end];
+    let text: String = cells.iter().map(|x| buffer[(*x, y)].symbol()).collect();
+
+    // Newest registration wins: exact text first, then a fragment of a wrapped link
+    let url = links
+        .iter()
+        .rev()
+        .find(|(t, _)| *t == text)
+        .or_else(|| links.iter().rev().find(|(t, _)| t.contains(text.as_str())))
+        .map(|(_, url)| url);
+    let Some(url) = url else {
+        return Ok(false);
+    };
+
+    use crossterm::{cursor, queue, style};
+    queue!(
+        out,
+        cursor::SavePosition,
+        cursor::MoveTo(cells[0], y),
+        style::Print(format!("\x1b]8;;{}\x1b\\", url))
+    )?;
+    for x in cells {
+        let cell = &buffer[(*x, y)];
+        queue!(
+            out,
+            style::SetAttribute(style::Attribute::Reset),
+            style::SetForegroundColor(cell.fg.into()),
+            style::SetBackgroundColor(cell.bg.into())
+        )?;
+        for (modifier, attribute) in [
+            (Modifier::BOLD, style::Attribute::Bold),
+            (Modifier::DIM, style::Attribute::Dim),
+            (Modifier::ITALIC, style::Attribute::Italic),
+            (Modifier::UNDERLINED, style::Attribute::Underlined),
+            (Modifier::REVERSED, style::Attribute::Reverse),
+            (Modifier::CROSSED_OUT, style::Attribute::CrossedOut),
+        ] {
+            if cell.modifier.contains(modifier) {
+                queue!(out, style::SetAttribute(attribute))?;
+            }
+        }
+        queue!(out, style::Print(cell.symbol()))?;
+    }
+    queue!(
+        out,
+        style::Print("\x1b]8;;\x1b\\"),
+        style::SetAttribute(style::Attribute::Reset),
+        style::ResetColor,
+        cursor::RestorePosition
+    )?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::layout::Rect;
+    use ratatui::style::Style;
+    use ratatui::text::Line;
+    use ratatui::widgets::{Paragraph, Widget};
+
+    fn render(lines: Vec<Line<'static>>, width: u16) -> Buffer {
+        let area = Rect::new(0, 0, width, lines.len() as u16);
+        let mut buffer = Buffer::empty(area);
+        Paragraph::new(lines).render(area, &mut buffer);
+        buffer
+    }
+
+    #[test]
+    fn underlined_link_text_is_wrapped_in_osc8() {
+        let url = "https://github.com/acme/widgets/pull/42";
+        let buffer = render(
+            vec![Line::from(vec![
+                Span::raw("PR: "),
+                link_span("pull/42", url, Style::default()),
+                Span::raw(" done"),
+            ])],
+            30,
+        );
+
+        let mut out = Vec::new();
+        write_hyperlinks(&mut out, &buffer).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let open = format!("\x1b]8;;{}\x1b\\", url);
+        let start = out.find(&open).expect("link opened") + open.len();
+        let end = out.find("\x1b]8;;\x1b\\").expect("link closed");
+        let linked: String = out[start..end]
+            .split('\x1b')
+            .filter_map(|chunk| chunk.rsplit_once('m').map(|(_, text)| text))
+            .collect();
+        assert_eq!(linked, "pull/42");
+    }
+
+    #[test]
+    fn wrapped_link_fragments_and_unsafe_targets() {
+        register("read the release notes", "https://example.com/notes");
+        register("evil", "https://example.com/\x1b]0;pwned\x07");
+        register("script", "javascript:alert(1)");
+        let underlined = Style::default().add_modifier(Modifier::UNDERLINED);
+        let buffer = render(
+            vec![
+                Line::from(Span::styled("read the", underlined)),
+                Line::from(Span::styled("evil script", underlined)),
+            ],
+            12,
+        );
+
+        let mut out = Vec::new();
+        write_hyperlinks(&mut out, &buffer).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert_eq!(out.matches("https://example.com/notes").count(), 1);
+        assert!(!out.contains("pwned") && !out.contains("javascript"));
+
+        assert_eq!(
+            file_url(Path::new("/repo"), Path::new("src/my file.rs")),
+            "file:///repo/src/my%20file.rs"
+        );
+    }
+}
@@ -2,13 +2,23 @@
 //!
 //! Uses a markdown event parser so Ask responses render consistent rich text.
 
+use super::helpers::wrap_spans;
+use super::links;
 use super::theme::Theme;
-use pulldown_cmark::{CodeBlockKind, Event, HeadingLevel, Options, Parser, Tag, TagEnd};
+use cosmos_core::index::parser::{highlight, HighlightKind};
+use cosmos_core::index::Language;
+use cosmos_core::util::split_at_width;
+use pulldown_cmark::{Alignment, CodeBlockKind, Event, HeadingLevel, Options, Parser, Tag, TagEnd};
 use ratatui::{
     style::{Modifier, Style},
     text::{Line, Span},
 };
-use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+use std::cell::RefCell;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+use unicode_width::UnicodeWidthStr;
 
 #[derive(Clone, Copy, Default)]
 struct InlineState {
@@ -114,58 +124,120 @@ fn quote_prefix(depth: usize) -> String {
     "│ ".repeat(depth)
 }
 
+/// Text hash and width
+type ParsedKey = (u64, usize);
+
+thread_local! {
+    static PARSED_CACHE: RefCell<HashMap<ParsedKey, Rc<[Line<'static>]>>> =
+        RefCell::new(HashMap::new());
+}
+
+/// Parsed markdown entries kept between frames before the cache is reset
+const PARSED_CACHE_LIMIT: usize = 64;
+
+/// [`parse_markdown`], memoized by text and width so panels that redraw every frame don't
+/// re-parse (and re-highlight) unchanged text
+pub fn parse_markdown_cached(text: &str, max_width: usize) -> Rc<[Line<'static>]> {
+    let mut hasher = DefaultHasher::new();
+    text.hash(&mut hasher);
+    let key = (hasher.finish(), max_width);
+    PARSED_CACHE.with(|cache| {
+        if let Some(lines) = cache.borrow().get(&key) {
+            return Rc::clone(lines);
+        }
+        let lines: Rc<[Line<'static>]> = parse_markdown(text, max_width).into();
+        let mut cache = cache.borrow_mut();
+        if cache.len() >= PARSED_CACHE_LIMIT {
+            cache.clear();
+        }
+        cache.insert(key, Rc::clone(&lines));
+        lines
+    })
+}
+
+/// Forget every memoized parse (the terminal was resized, so old widths are dead weight)
+pub fn clear_parsed_cache() {
+    PARSED_CACHE.with(|cache| cache.borrow_mut().clear());
+}
+
+/// Rows of a table being parsed; each cell holds its styled segments
+struct TableState {
+    alignments: Vec<Alignment>,
+    rows: Vec<Vec<Vec<StyledSegment>>>,
+    header_rows: usize,
+}
+
 /// Parse markdown text and convert to styled lines constrained by `max_width`.
+///
+/// Handles headings, nested and loose lists, block quotes, tables, and fenced code blocks,
+/// which are syntax highlighted when the fence names a language Cosmos indexes.
 pub fn parse_markdown(text: &str, max_width: usize) -> Vec<Line<'static>> {
     let options = Options::ENABLE_STRIKETHROUGH
         | Options::ENABLE_TASKLISTS
-        | Options::ENABLE_SMART_PUNCTUATION;
+        | Options::ENABLE_SMART_PUNCTUATION
+        | Options::ENABLE_TABLES;
     let parser = Parser::new_ext(text, options);
 
     let mut lines = Vec::new();
     let mut inline_state = InlineState::default();
     let mut blockquote_depth = 0usize;
     let mut list_stack: Vec<ListState> = Vec::new();
+    // Open list items; after an item's first line its block drops the marker so later
+    // paragraphs and nested content line up under the item text
+    let mut item_stack: Vec<BlockFormat> = Vec::new();
 
     let mut block = BlockFormat::paragraph(blockquote_depth);
     let mut current_segments: Vec<StyledSegment> = Vec::new();
-    let mut in_code_block = false;
+    let mut code_block: Option<(Language, String)> = None;
+    let mut table: Option<TableState> = None;
+    let mut link: Option<(usize, String)> = None;
 
     let flush_block = |lines: &mut Vec<Line<'static>>,
                        block: &BlockFormat,
-                       current_segments: &mut Vec<StyledSegment>| {
+                       current_segments: &mut Vec<StyledSegment>,
+                       item_stack: &mut Vec<BlockFormat>| {
         if current_segments.is_empty() {
             return;
         }
-        let wrapped = wrap_segments(
-            current_segments,
-            block,
-            max_width.max(1),
-            Style::default().fg(Theme::GREY_100),
-        );
+        let wrapped = wrap_segments(current_segments, block, max_width.max(1));
         lines.extend(wrapped);
         current_segments.clear();
+        if let Some(item) = item_stack.last_mut() {
+            item.first_prefix = item.cont_prefix.clone();
+        }
+    };
+    let resume_block = |item_stack: &[BlockFormat], blockquote_depth: usize| {
+        item_stack
+            .last()
+            .cloned()
+            .unwrap_or_else(|| BlockFormat::paragraph(blockquote_depth))
     };
 
     for event in parser {
         match event {
             Event::Start(tag) => match tag {
                 Tag::Paragraph => {
-                    flush_block(&mut lines, &block, &mut current_segments);
-                    block = BlockFormat::paragraph(blockquote_depth);
+                    flush_block(&mut lines, &block, &mut current_segments, &mut item_stack);
+                    block = resume_block(&item_stack, blockquote_depth);
                 }
                 Tag::Heading { level, .. } => {
-                    flush_block(&mut lines, &block, &mut current_segments);
+                    flush_block(&mut lines, &block, &mut current_segments, &mut item_stack);
                     block = BlockFormat::heading(level, blockquote_depth);
                 }
                 Tag::BlockQuote(_) => {
-                    flush_block(&mut lines, &block, &mut current_segments);
+                    flush_block(&mut lines, &block, &mut current_segments, &mut item_stack);
                     blockquote_depth += 1;
                     block = BlockFormat::paragraph(blockquote_depth);
                 }
-                Tag::List(Some(start)) => list_stack.push(ListState::Ordered(start)),
-                Tag::List(None) => list_stack.push(ListState::Bullet),
+                Tag::List(start) => {
+                    flush_block(&mut lines, &block, &mut current_segments, &mut item_stack);
+                    list_stack.push(match start {
+                        Some(start) => ListState::Ordered(start),
+                        None => ListState::Bullet,
+                    });
+                }
                 Tag::Item => {
-                    flush_block(&mut lines, &block, &mut current_segments);
+                    flush_block(&mut lines, &block, &mut current_segments, &mut item_stack);
                     let list_depth = list_stack.len().saturating_sub(1);
                     let marker = match list_stack.last_mut() {
                         Some(ListState::Ordered(next)) => {
@@ -176,66 +248,131 @@ pub fn parse_markdown(text: &str, max_width: usize) -> Vec<Line<'static>> {
                         _ => "• ".to_string(),
                     };
                     block = BlockFormat::list_item(marker, blockquote_depth, list_depth);
+                    item_stack.push(block.clone());
                 }
                 Tag::Emphasis => inline_state.italic += 1,
                 Tag::Strong => inline_state.bold += 1,
-                Tag::Link { .. } => inline_state.link += 1,
+                Tag::Link { dest_url, .. } => {
+                    inline_state.link += 1;
+                    link = Some((current_segments.len(), dest_url.into_string()));
+                }
                 Tag::CodeBlock(kind) => {
-                    flush_block(&mut lines, &block, &mut current_segments);
-                    in_code_block = true;
+                    flush_block(&mut lines, &block, &mut current_segments, &mut item_stack);
                     block = BlockFormat::code_block(blockquote_depth);
+                    let mut language = Language::Unknown;
                     if let CodeBlockKind::Fenced(lang) = kind {
                         let lang = lang.trim();
                         if !lang.is_empty() {
+                            // Info strings may carry more than the language (`rust,ignore`)
+                            let tag = lang.split([',', ' ']).next().unwrap_or(lang);
+                            language = Language::from_fence_tag(tag);
                             current_segments.push(StyledSegment {
                                 text: format!("[{}]", lang),
                                 style: Style::default().fg(Theme::GREY_500),
                             });
-                            flush_block(&mut lines, &block, &mut current_segments);
+                            flush_block(&mut lines, &block, &mut current_segments, &mut item_stack);
                         }
                     }
+                    code_block = Some((language, String::new()));
                 }
+                Tag::Table(alignments) => {
+                    flush_block(&mut lines, &block, &mut current_segments, &mut item_stack);
+                    table = Some(TableState {
+                        alignments,
+                        rows: Vec::new(),
+                        header_rows: 0,
+                    });
+                }
+                Tag::TableHead | Tag::TableRow => {
+                    if let Some(table) = table.as_mut() {
+                        table.rows.push(Vec::new());
+                    }
+                }
+                Tag::TableCell => current_segments.clear(),
                 _ => {}
             },
             Event::End(tag) => match tag {
-                TagEnd::Paragraph | TagEnd::Heading(_) | TagEnd::Item => {
-                    flush_block(&mut lines, &block, &mut current_segments);
-                    block = BlockFormat::paragraph(blockquote_depth);
+                TagEnd::Paragraph | TagEnd::Heading(_) => {
+                    flush_block(&mut lines, &block, &mut current_segments, &mut item_stack);
+                    block = resume_block(&item_stack, blockquote_depth);
+                }
+                TagEnd::Item => {
+                    flush_block(&mut lines, &block, &mut current_segments, &mut item_stack);
+                    item_stack.pop();
+                    block = resume_block(&item_stack, blockquote_depth);
                 }
                 TagEnd::BlockQuote(_) => {
-                    flush_block(&mut lines, &block, &mut current_segments);
+                    flush_block(&mut lines, &block, &mut current_segments, &mut item_stack);
                     blockquote_depth = blockquote_depth.saturating_sub(1);
-                    block = BlockFormat::paragraph(blockquote_depth);
+                    block = resume_block(&item_stack, blockquote_depth);
                 }
                 TagEnd::List(_) => {
-                    flush_block(&mut lines, &block, &mut current_segments);
+                    flush_block(&mut lines, &block, &mut current_segments, &mut item_stack);
                     list_stack.pop();
-                    block = BlockFormat::paragraph(blockquote_depth);
+                    block = resume_block(&item_stack, blockquote_depth);
                 }
                 TagEnd::Emphasis => inline_state.italic = inline_state.italic.saturating_sub(1),
                 TagEnd::Strong => inline_state.bold = inline_state.bold.saturating_sub(1),
-                TagEnd::Link => inline_state.link = inline_state.link.saturating_sub(1),
+                TagEnd::Link => {
+                    inline_state.link = inline_state.link.saturating_sub(1);
+                    if let Some((start, url)) = link.take() {
+                        let text: String = current_segments[start.min(current_segments.len())..]
+                            .iter()
+                            .map(|seg| seg.text.as_str())
+                            .collect();
+                        links::register(
+                            &text.split_whitespace().collect::<Vec<_>>().join(" "),
+                            &url,
+                        );
+                    }
+                }
                 TagEnd::CodeBlock => {
-                    flush_block(&mut lines, &block, &mut current_segments);
-                    in_code_block = false;
-                    block = BlockFormat::paragraph(blockquote_depth);
+                    if let Some((language, code)) = code_block.take() {
+                        current_segments.extend(highlight_code(&code, language, block.base_style));
+                    }
+                    flush_block(&mut lines, &block, &mut current_segments, &mut item_stack);
+                    block = resume_block(&item_stack, blockquote_depth);
+                }
+                TagEnd::TableCell => {
+                    if let Some(row) = table.as_mut().and_then(|table| table.rows.last_mut()) {
+                        row.push(std::mem::take(&mut current_segments));
+                    }
+                }
+                TagEnd::TableHead => {
+                    if let Some(table) = table.as_mut() {
+                        table.header_rows = table.rows.len();
+                    }
+                }
+                TagEnd::Table => {
+                    if let Some(table) = table.take() {
+                        let width = max_width.saturating_sub(block.first_prefix.width());
+                        for mut line in render_table(&table, width) {
+                            if !block.first_prefix.is_empty() {
+                                line.spans.insert(
+                                    0,
+                                    Span::styled(block.cont_prefix.clone(), block.prefix_style),
+                                );
+                            }
+                            lines.push(line);
+                        }
+                    }
                 }
                 _ => {}
             },
             Event::Text(content) => {
-                let style = if in_code_block {
-                    block.base_style
-                } else {
-                    inline_state.style(block.base_style)
-                };
+                if let Some((_, code)) = code_block.as_mut() {
+                    code.push_str(&content);
+                    continue;
+                }
                 current_segments.push(StyledSegment {
                     text: content.into_string(),
-                    style,
+                    style: inline_state.style(block.base_style),
                 });
             }
             Event::Code(content) => {
+                // Keep the backticks and hold the span together when wrapping
                 current_segments.push(StyledSegment {
-                    text: content.into_string(),
+                    text: format!("`{}`", content.replace(' ', "\u{a0}")),
                     style: Style::default()
                         .fg(Theme::GREY_200)
                         .add_modifier(Modifier::BOLD),
@@ -254,7 +391,7 @@ pub fn parse_markdown(text: &str, max_width: usize) -> Vec<Line<'static>> {
                 });
             }
             Event::Rule => {
-                flush_block(&mut lines, &block, &mut current_segments);
+                flush_block(&mut lines, &block, &mut current_segments, &mut item_stack);
                 let rule_len = max_width.clamp(8, 64);
                 lines.push(Line::from(vec![Span::styled(
                     "─".repeat(rule_len),
@@ -284,7 +421,7 @@ pub fn parse_markdown(text: &str, max_width: usize) -> Vec<Line<'static>> {
         }
     }
 
-    flush_block(&mut lines, &block, &mut current_segments);
+    flush_block(&mut lines, &block, &mut current_segments, &mut item_stack);
     if lines.is_empty() {
         lines.push(Line::from(""));
     }
@@ -295,156 +432,176 @@ fn wrap_segments(
     segments: &[StyledSegment],
     format: &BlockFormat,
     max_width: usize,
-    space_style: Style,
 ) -> Vec<Line<'static>> {
-    #[derive(Clone)]
-    enum Token {
-        Word(String, Style),
-        Break,
-    }
-
-    let mut tokens = Vec::new();
-    for seg in segments {
-        let mut first_part = true;
-        for part in seg.text.split('\n') {
-            if !first_part {
-                tokens.push(Token::Break);
-            }
-            first_part = false;
-            for word in part.split_whitespace() {
-                tokens.push(Token::Word(word.to_string(), seg.style));
+    let spans: Vec<Span<'_>> = segments
+        .iter()
+        .map(|seg| Span::styled(seg.text.as_str(), seg.style))
+        .collect();
+    let first_width = max_width.saturating_sub(format.first_prefix.width()).max(1);
+    let cont_width = max_width.saturating_sub(format.cont_prefix.width()).max(1);
+
+    wrap_spans(&spans, first_width, cont_width)
+        .into_iter()
+        .enumerate()
+        .map(|(i, line)| {
+            let prefix = if i == 0 {
+                &format.first_prefix
+            } else {
+                &format.cont_prefix
+            };
+            let mut spans = Vec::with_capacity(line.spans.len() + 1);
+            if !prefix.is_empty() {
+                spans.push(Span::styled(prefix.clone(), format.prefix_style));
             }
+            // Non-breaking spaces only steer wrapping; copied text should have plain spaces
+            spans.extend(line.spans.into_iter().map(|span| {
+                if span.content.contains('\u{a0}') {
+                    Span::styled(span.content.replace('\u{a0}', " "), span.style)
+                } else {
+                    span
+                }
+            }));
+            Line::from(spans)
+        })
+        .collect()
+}
+
+/// Split a code block into syntax-colored segments.
+///
+/// Spaces become non-breaking so indentation and alignment survive wrapping; a line wider
+/// than the panel is cut at the edge instead of at its spaces.
+fn highlight_code(code: &str, language: Language, base_style: Style) -> Vec<StyledSegment> {
+    let code = code.strip_suffix('\n').unwrap_or(code);
+    let segment = |text: &str, style: Style| StyledSegment {
+        text: text.replace('\t', "    ").replace(' ', "\u{a0}"),
+        style,
+    };
+    let mut segments = Vec::new();
+    let mut pos = 0;
+    for highlight in highlight(code, language) {
+        if highlight.range.start > pos {
+            segments.push(segment(&code[pos..highlight.range.start], base_style));
         }
+        let style = base_style.fg(match highlight.kind {
+            HighlightKind::Keyword => Theme::SYNTAX_KEYWORD,
+            HighlightKind::String => Theme::SYNTAX_STRING,
+            HighlightKind::Comment => Theme::SYNTAX_COMMENT,
+            HighlightKind::Number => Theme::SYNTAX_NUMBER,
+            HighlightKind::Function => Theme::SYNTAX_FUNCTION,
+            HighlightKind::Type => Theme::SYNTAX_TYPE,
+        });
+        segments.push(segment(&code[highlight.range.clone()], style));
+        pos = highlight.range.end;
     }
+    if pos < code.len() {
+        segments.push(segment(&code[pos..], base_style));
+    }
+    segments
+}
 
-    if tokens.is_empty() {
-        return vec![Line::from("")];
+/// Lay out a table with columns sized to their content, shrinking the widest columns (and
+/// truncating their cells) when the table is wider than `max_width`.
+fn render_table(table: &TableState, max_width: usize) -> Vec<Line<'static>> {
+    const SEPARATOR: &str = " │ ";
+    let columns = table.rows.iter().map(Vec::len).max().unwrap_or(0);
+    if columns == 0 {
+        return Vec::new();
+    }
+    let cell_width =
+        |cell: &[StyledSegment]| -> usize { cell.iter().map(|seg| seg.text.width()).sum() };
+    let mut widths = vec![1usize; columns];
+    for row in &table.rows {
+        for (col, cell) in row.iter().enumerate() {
+            widths[col] = widths[col].max(cell_width(cell));
+        }
+    }
+    let separators = SEPARATOR.width() * (columns - 1);
+    while widths.iter().sum::<usize>() + separators > max_width {
+        let (widest, width) = widths
+            .iter()
+            .copied()
+            .enumerate()
+            .max_by_key(|(_, width)| *width)
+            .unwrap_or((0, 0));
+        if width <= 3 {
+            break;
+        }
+        widths[widest] -= 1;
     }
 
+    let border = Style::default().fg(Theme::GREY_500);
     let mut lines = Vec::new();
-    let mut line_spans: Vec<Span<'static>> = Vec::new();
-    let mut line_width = 0usize;
-    let mut line_has_word = false;
-    let mut first_line = true;
-
-    let start_line = |line_spans: &mut Vec<Span<'static>>, first: bool| {
-        let prefix = if first {
-            &format.first_prefix
-        } else {
-            &format.cont_prefix
-        };
-        if !prefix.is_empty() {
-            line_spans.push(Span::styled(prefix.clone(), format.prefix_style));
+    for (index, row) in table.rows.iter().enumerate() {
+        let header = index < table.header_rows;
+        let mut spans = Vec::new();
+        for (col, width) in widths.iter().enumerate() {
+            if col > 0 {
+                spans.push(Span::styled(SEPARATOR, border));
+            }
+            let cell = row.get(col).map(Vec::as_slice).unwrap_or_default();
+            let align = table
+                .alignments
+                .get(col)
+                .copied()
+                .unwrap_or(Alignment::None);
+            spans.extend(fit_cell(cell, *width, align, header));
         }
-    };
+        lines.push(Line::from(spans));
+        if header && index + 1 == table.header_rows {
+            let rule: Vec<String> = widths.iter().map(|width| "─".repeat(*width)).collect();
+            lines.push(Line::from(Span::styled(rule.join("─┼─"), border)));
+        }
+    }
+    lines
+}
 
-    let line_limit = |first: bool| -> usize {
-        let prefix = if first {
-            &format.first_prefix
+/// A table cell truncated or padded to exactly `width` columns
+fn fit_cell(
+    cell: &[StyledSegment],
+    width: usize,
+    align: Alignment,
+    header: bool,
+) -> Vec<Span<'static>> {
+    let total: usize = cell.iter().map(|seg| seg.text.width()).sum();
+    let budget = if total > width {
+        width.saturating_sub(1)
+    } else {
+        width
+    };
+    let mut spans = Vec::new();
+    let mut used = 0;
+    for seg in cell {
+        let style = if header {
+            seg.style.add_modifier(Modifier::BOLD)
         } else {
-            &format.cont_prefix
+            seg.style
         };
-        max_width.saturating_sub(prefix.width()).max(1)
-    };
-
-    start_line(&mut line_spans, first_line);
-
-    let push_line = |lines: &mut Vec<Line<'static>>,
-                     line_spans: &mut Vec<Span<'static>>,
-                     first_line: &mut bool,
-                     line_width: &mut usize,
-                     line_has_word: &mut bool| {
-        lines.push(Line::from(std::mem::take(line_spans)));
-        *first_line = false;
-        *line_width = 0;
-        *line_has_word = false;
-        start_line(line_spans, false);
-    };
-
-    for token in tokens {
-        match token {
-            Token::Break => {
-                push_line(
-                    &mut lines,
-                    &mut line_spans,
-                    &mut first_line,
-                    &mut line_width,
-                    &mut line_has_word,
-                );
-            }
-            Token::Word(word, style) => {
-                let limit = line_limit(first_line);
-                let word_width = word.width();
-                let needed = if line_has_word {
-                    line_width.saturating_add(1 + word_width)
-                } else {
-                    word_width
-                };
-
-                if line_has_word && needed > limit {
-                    push_line(
-                        &mut lines,
-                        &mut line_spans,
-                        &mut first_line,
-                        &mut line_width,
-                        &mut line_has_word,
-                    );
-                }
-
-                if line_has_word {
-                    line_spans.push(Span::styled(" ".to_string(), space_style));
-                    line_width = line_width.saturating_add(1);
-                }
-
-                if word_width <= line_limit(first_line) {
-                    line_spans.push(Span::styled(word, style));
-                    line_width = line_width.saturating_add(word_width);
-                    line_has_word = true;
-                    continue;
-                }
-
-                let mut chunk = String::new();
-                let mut chunk_width = 0usize;
-                for ch in word.chars() {
-                    let ch_width = ch.width().unwrap_or(1);
-                    let chunk_limit = line_limit(first_line);
-                    if line_has_word && chunk.is_empty() && line_width + ch_width > chunk_limit {
-                        push_line(
-                            &mut lines,
-                            &mut line_spans,
-                            &mut first_line,
-                            &mut line_width,
-                            &mut line_has_word,
-                        );
-                    }
-                    if chunk_width + ch_width > chunk_limit && !chunk.is_empty() {
-                        line_spans.push(Span::styled(chunk.clone(), style));
-                        line_width = line_width.saturating_add(chunk_width);
-                        line_has_word = true;
-                        push_line(
-                            &mut lines,
-                            &mut line_spans,
-                            &mut first_line,
-                            &mut line_width,
-                            &mut line_has_word,
-                        );
-                        chunk.clear();
-                        chunk_width = 0;
-                    }
-                    chunk.push(ch);
-                    chunk_width += ch_width;
-                }
-                if !chunk.is_empty() {
-                    line_spans.push(Span::styled(chunk, style));
-                    line_width = line_width.saturating_add(chunk_width);
-                    line_has_word = true;
-                }
-            }
+        let (head, _) = split_at_width(&seg.text, budget - used);
+        if !head.is_empty() {
+            used += head.width();
+            spans.push(Span::styled(head.to_string(), style));
+        }
+        if head.len() < seg.text.len() {
+            break;
         }
     }
-
-    lines.push(Line::from(line_spans));
-    lines
+    if total > width {
+        spans.push(Span::styled("…", Style::default().fg(Theme::GREY_500)));
+        used += 1;
+    }
+    let slack = width.saturating_sub(used);
+    let (left, right) = match align {
+        Alignment::Right => (slack, 0),
+        Alignment::Center => (slack / 2, slack - slack / 2),
+        Alignment::None | Alignment::Left => (0, slack),
+    };
+    if left > 0 {
+        spans.insert(0, Span::raw(" ".repeat(left)));
+    }
+    if right > 0 {
+        spans.push(Span::raw(" ".repeat(right)));
+    }
+    spans
 }
 
 #[cfg(test)]
@@ -489,4 +646,63 @@ mod tests {
         assert!(rendered.contains("let value = 42;"));
         assert!(rendered.contains("println!"));
     }
+
+    #[test]
+    fn parse_markdown_keeps_inline_formatting_through_wraps() {
+        let input = "Run `cargo test --workspace` after **this**, then see [the docs](https://example.com/docs).";
+        let lines = parse_markdown(input, 24);
+        let rendered: Vec<String> = lines.iter().map(|line| line.to_string()).collect();
+        let joined = rendered.join("\n");
+
+        assert!(joined.contains("`cargo test --workspace`"));
+        assert!(rendered.iter().any(|line| line.contains("this,")));
+        assert!(lines
+            .iter()
+            .flat_map(|line| line.spans.iter())
+            .any(|span| span.content == "docs"
+                && span.style.add_modifier.contains(Modifier::UNDERLINED)));
+        assert!(lines.iter().all(|line| line.width() <= 24));
+    }
+
+    fn rendered_lines(lines: &[Line<'static>]) -> Vec<String> {
+        lines.iter().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn parse_markdown_renders_tables_and_nested_loose_lists() {
+        let input = "| File | Lines |\n|:-----|------:|\n| src/main.rs | 42 |\n| a_really_long_file_name.rs | 7 |\n\n- first\n\n  more about first\n\n  - nested\n- second";
+        let rendered = rendered_lines(&parse_markdown(input, 24));
+
+        assert_eq!(rendered[0], "File             │ Lines");
+        assert_eq!(rendered[1], "─────────────────┼──────");
+        assert_eq!(rendered[2], "src/main.rs      │    42");
+        assert_eq!(rendered[3], "a_really_long_f… │     7");
+        assert!(rendered.iter().all(|line| line.chars().count() <= 24));
+
+        let list: Vec<&String> = rendered.iter().skip(4).filter(|l| !l.is_empty()).collect();
+        assert_eq!(list[0], "• first");
+        assert_eq!(list[1], "  more about first");
+        assert_eq!(list[2], "  • nested");
+        assert_eq!(list[3], "• second");
+    }
+
+    #[test]
+    fn parse_markdown_highlights_fenced_code() {
+        let input = "```rust\nfn main() {\n    let s = \"hi\"; // greet\n}\n```";
+        let lines = parse_markdown(input, 60);
+        let rendered = rendered_lines(&lines);
+        assert_eq!(rendered[0], "│ [rust]");
+        assert_eq!(rendered[2], "│     let s = \"hi\"; // greet");
+
+        let color_of = |text: &str| {
+            lines
+                .iter()
+                .flat_map(|line| line.spans.iter())
+                .find(|span| span.content == text)
+                .and_then(|span| span.style.fg)
+        };
+        assert_eq!(color_of("fn"), Some(Theme::SYNTAX_KEYWORD));
+        assert_eq!(color_of("\"hi\""), Some(Theme::SYNTAX_STRING));
+        assert_eq!(color_of("// greet"), Some(Theme::SYNTAX_COMMENT));
+    }
 }
@@ -0,0 +1,160 @@
+//! The galaxy from the Cosmos logo, shown on the startup splash and after a long idle.
+//!
+//! Terminals with kitty or sixel graphics get a real image drawn over the blank art area
+//! after each draw (see `app::runtime`); everywhere else the ASCII art fills those cells.
+
+use cosmos_adapters::terminal::{self, Bitmap, GraphicsProtocol};
+use ratatui::layout::Rect;
+use std::f32::consts::PI;
+
+/// Text fallback, also the size of the area the image is drawn into
+pub const ART: [&str; 11] = [
+    r"          .-~~~~~~~~~~-.          ",
+    r"       .-'   .-~~~~-.   '-.       ",
+    r"     .'    .'  .--.  '.    '.     ",
+    r"    /    .'   /    \   \     \    ",
+    r"   |    /    |  ()  |   |     |   ",
+    r"   |   |      \    /   .'     |   ",
+    r"   |    \      '--'  .'      /    ",
+    r"    \    '.       .-'      .'     ",
+    r"     '.    '-...-'       .'       ",
+    r"       '-.            .-'         ",
+    r"          '-~~~~~~~~-'            ",
+];
+
+/// Columns the art takes up
+pub fn art_width() -> u16 {
+    ART.iter()
+        .map(|line| line.chars().count())
+        .max()
+        .unwrap_or(0) as u16
+}
+
+/// Caption rows under the art: blank, title, blank, hint
+const CAPTION_ROWS: u16 = 4;
+
+/// Overlay box for the mascot, centred in `area` and clipped to it
+pub fn box_area(area: Rect) -> Rect {
+    let width = art_width() + 8;
+    let height = ART.len() as u16 + CAPTION_ROWS + 3;
+    Rect::new(
+        area.x + area.width.saturating_sub(width) / 2,
+        area.y + area.height.saturating_sub(height) / 2,
+        width,
+        height,
+    )
+    .intersection(area)
+}
+
+/// Cells the art (or image) occupies inside [`box_area`]; empty when it doesn't fit whole
+pub fn art_area(area: Rect) -> Rect {
+    let outer = box_area(area);
+    let art = Rect::new(outer.x + 4, outer.y + 2, art_width(), ART.len() as u16);
+    if art.intersection(outer) == art {
+        art
+    } else {
+        Rect::default()
+    }
+}
+
+/// The galaxy as a one-colour bitmap: an outer ring, two spiral arms, and a bright core
+pub fn galaxy(width: usize, height: usize) -> Bitmap {
+    let (cx, cy) = (width as f32 / 2.0, height as f32 / 2.0);
+    let radius = cx.min(cy).max(1.0);
+    let pixels = (0..height)
+        .flat_map(|y| (0..width).map(move |x| (x, y)))
+        .map(|(x, y)| {
+            let dx = (x as f32 + 0.5 - cx) / radius;
+            let dy = (y as f32 + 0.5 - cy) / radius;
+            let r = (dx * dx + dy * dy).sqrt();
+            let ring = (0.82..=0.98).contains(&r);
+            let core = r <= 0.16;
+            // Two arms: the angle advances with the radius, repeating every half turn
+            let arm = (0.3..=0.68).contains(&r) && (dy.atan2(dx) - 5.0 * r).rem_euclid(PI) < 0.6;
+            ring || core || arm
+        })
+        .collect();
+    Bitmap {
+        width,
+        height,
+        pixels,
+    }
+}
+
+/// Escape sequence that draws the galaxy over `area`, for a cell of `cell_px` pixels
+///
+/// Write it with the cursor at the top-left of `area`. None for text-only terminals.
+pub fn image_sequence(
+    protocol: GraphicsProtocol,
+    area: Rect,
+    cell_px: (u16, u16),
+    color: (u8, u8, u8),
+) -> Option<String> {
+    if area.is_empty() {
+        return None;
+    }
+    // Square image, as tall as the area and centred across it
+    let (cell_w, cell_h) = (cell_px.0.max(1) as usize, cell_px.1.max(1) as usize);
+    let side = area.height as usize * cell_h;
+    let cols = side.div_ceil(cell_w).min(area.width as usize) as u16;
+    match protocol {
+        GraphicsProtocol::Text => None,
+        GraphicsProtocol::Kitty => {
+            // Kitty scales to the cell box, so a modest bitmap is enough
+            let image = galaxy(160, 160);
+            Some(terminal::kitty_image(&image, color, cols, area.height))
+        }
+        GraphicsProtocol::Sixel => {
+            let image = galaxy(cols as usize * cell_w, side);
+            Some(terminal::sixel_image(&image, color))
+        }
+    }
+}
+
+/// Column offset that centres an image of `cols` cells across `area`
+pub fn image_column(area: Rect, cell_px: (u16, u16)) -> u16 {
+    let side = area.height as usize * cell_px.1.max(1) as usize;
+    let cols = side
+        .div_ceil(cell_px.0.max(1) as usize)
+        .min(area.width as usize) as u16;
+    area.x + (area.width - cols) / 2
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn art_fits_its_box_and_galaxy_has_ring_arms_and_core() {
+        assert!(ART
+            .iter()
+            .all(|line| line.chars().count() == art_width() as usize));
+        let screen = Rect::new(0, 0, 80, 24);
+        let art = art_area(screen);
+        assert_eq!(art.intersection(box_area(screen)), art);
+        assert_eq!(art_area(Rect::new(0, 0, 20, 10)), Rect::default());
+
+        let image = galaxy(64, 64);
+        let lit = |x: usize, y: usize| image.pixels[y * 64 + x];
+        assert!(lit(32, 32), "core");
+        assert!(lit(32, 1), "outer ring");
+        assert!(!lit(32, 8), "gap inside the ring");
+        assert!(!lit(0, 0), "corner outside the galaxy");
+        let arm_pixels = (0..64 * 64)
+            .filter(|i| {
+                let (dx, dy) = ((i % 64) as f32 - 31.5, (i / 64) as f32 - 31.5);
+                let r = (dx * dx + dy * dy).sqrt() / 32.0;
+                (0.35..0.65).contains(&r) && image.pixels[*i]
+            })
+            .count();
+        assert!(arm_pixels > 100, "spiral arms");
+
+        assert_eq!(
+            image_sequence(GraphicsProtocol::Text, art, (10, 20), (255, 255, 255)),
+            None
+        );
+        let sixel =
+            image_sequence(GraphicsProtocol::Sixel, art, (10, 20), (255, 255, 255)).unwrap();
+        assert!(sixel.contains("\"1;1;220;220"));
+    }
+}
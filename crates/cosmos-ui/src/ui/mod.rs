@@ -3,29 +3,38 @@
 //! Renders a dual-panel terminal interface with header, main content, and footer.
 //! See `render/mod.rs` for the layout implementation.
 
+pub mod diff;
 pub mod helpers;
+pub mod keymap;
+pub mod links;
 pub mod markdown;
+pub mod mascot;
 pub mod theme;
 pub mod types;
 
 mod render;
 mod tree;
 
-pub use render::render;
+pub use render::{
+    render, reset_caches as reset_render_caches, terminal_too_small, MIN_TERMINAL_HEIGHT,
+    MIN_TERMINAL_WIDTH,
+};
 
 // Re-export all types for backward compatibility
 pub use types::{
-    ActivePanel, AskCosmosState, FileChange, InputMode, LoadingState, Overlay, PendingChange,
-    ReviewFileContent, ReviewState, ShipState, ShipStep, StartupAction, StartupMode, VerifyState,
-    ViewMode, WorkflowStep, SPINNER_FRAMES,
+    ActivePanel, AskCosmosState, BranchContext, BulkDismissal, CommandLog, EditorRequest,
+    FileChange, FindingField, InputMode, LoadingState, Overlay, PendingChange, RepeatableAction,
+    ReviewFileContent, ReviewState, ShipField, ShipState, ShipStep, SplitCommit, StackEntry,
+    StartupAction, StartupMode, SuggestionSort, VerifyState, ViewMode, WorkflowStep,
+    SPINNER_FRAMES,
 };
 
 use cosmos_core::context::WorkContext;
 use cosmos_core::index::{CodebaseIndex, FlatTreeEntry};
 use cosmos_core::suggest::{Suggestion, SuggestionEngine};
-use helpers::lowercase_first;
+use helpers::{lowercase_first, truncate_to_width};
 use std::collections::{HashMap, HashSet};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
 use tree::{build_file_tree, build_grouped_tree};
 
@@ -103,6 +112,8 @@ pub struct App {
     pub project_selected: usize,
     pub suggestion_scroll: usize,
     pub suggestion_selected: usize,
+    /// Suggestion expanded in place in the list (Enter toggles)
+    pub expanded_suggestion_id: Option<uuid::Uuid>,
     pub overlay: Overlay,
     pub should_quit: bool,
 
@@ -110,10 +121,18 @@ pub struct App {
     pub input_mode: InputMode,
     pub search_query: String,
     pub view_mode: ViewMode,
+    /// Workspace package the project panel and suggestions are scoped to (monorepos)
+    pub package_scope: Option<String>,
+    /// Filter on the Suggestions panel, typed into the search input
+    pub suggestion_filter: cosmos_core::suggest::filter::SuggestionFilter,
+    /// Order of the Suggestions panel
+    pub suggestion_sort: SuggestionSort,
 
     // Question input (ask cosmos)
     pub question_input: String,
     pub question_suggestion_selected: usize,
+    /// Files/directories picked to ground Ask Cosmos answers (empty = automatic)
+    pub ask_context_paths: Vec<PathBuf>,
     pub ask_in_flight: bool,
     pub active_ask_request_id: Option<u64>,
     next_ask_request_id: u64,
@@ -121,6 +140,8 @@ pub struct App {
     // Loading state for background tasks
     pub loading: LoadingState,
     pub loading_frame: usize,
+    /// Cancellation handles for running AI jobs (`x` cancels the active one)
+    pub tasks: crate::app::tasks::TaskManager,
 
     // Personal repo memory (local)
     pub repo_memory: cosmos_adapters::cache::RepoMemory,
@@ -130,6 +151,8 @@ pub struct App {
 
     // Question answer cache
     pub question_cache: cosmos_adapters::cache::QuestionCache,
+    /// Ask Cosmos conversation so far (persisted per repo)
+    pub chat_session: cosmos_engine::llm::ChatSession,
 
     // Cost tracking
     pub session_cost: f64,            // Total USD spent this session
@@ -155,9 +178,23 @@ pub struct App {
 
     // Pending changes for batch commit workflow
     pub pending_changes: Vec<PendingChange>,
+    /// File to open in the user's editor on the next main-loop pass
+    pub editor_request: Option<EditorRequest>,
+    /// Passing fix waiting on hunk selection before it is finalized
+    pub pending_fix_apply: Option<Box<crate::app::messages::PendingFixApply>>,
     pub cosmos_branch: Option<String>,
     /// Branch user was on before Cosmos created a working fix branch.
     pub cosmos_base_branch: Option<String>,
+    /// What was set aside on each branch Cosmos switched away from, keyed by branch name
+    pub branch_contexts: HashMap<String, BranchContext>,
+    /// Open pull request for a shipped Cosmos branch; later fixes are pushed onto it
+    pub follow_up_pr: Option<cosmos_adapters::github::BranchPullRequest>,
+    /// Ship commits each change onto its own branch instead of opening a PR right away
+    pub stacked_changes: bool,
+    /// Changes committed in stacked mode, oldest first
+    pub stack: Vec<StackEntry>,
+    /// Streamed quick-check output from fixes
+    pub command_log: CommandLog,
 
     // PR URL for "press Enter to open" flow
     pub pr_url: Option<String>,
@@ -180,6 +217,20 @@ pub struct App {
     pub current_suggestion_run_id: Option<String>,
     /// Rolling precision from recent verify outcomes.
     pub rolling_verify_precision: Option<f64>,
+    /// Rollups shown when a directory is focused in the context picker.
+    pub directory_summaries: cosmos_adapters::cache::DirectorySummaryCache,
+    /// Health scores of recent suggestion runs (oldest first), drawn in the header.
+    pub health_history: Vec<u8>,
+    /// Latest good/bad/irrelevant rating per suggestion.
+    pub suggestion_feedback: HashMap<uuid::Uuid, cosmos_core::suggest::SuggestionFeedback>,
+    /// Suggestions hidden from the list until a date or until their file changes.
+    pub snoozes: cosmos_adapters::cache::Snoozes,
+    /// Bulk dismissals this session, oldest first, for undo.
+    pub dismissals: Vec<BulkDismissal>,
+    /// Recorded bulk dismissals, whose findings stay out of the list across sessions.
+    pub dismissed: cosmos_adapters::cache::Dismissals,
+    /// Last suggestion action and the suggestion it was done to, for `.` to repeat.
+    pub last_repeatable: Option<(RepeatableAction, uuid::Uuid)>,
     /// Armed suggestion id for two-step apply confirmation.
     pub armed_suggestion_id: Option<uuid::Uuid>,
     /// File hash snapshot captured when apply confirmation was armed.
@@ -196,6 +247,71 @@ pub struct App {
     pub budget_warned_hard: bool,
     /// Runtime redraw hint for dirty-frame rendering.
     pub needs_redraw: bool,
+    /// Whether the user trusts this repo to run its own quick checks, tests, and hooks.
+    pub workspace_trusted: bool,
+    /// Active color theme, applied to every rendered frame
+    pub palette: theme::Palette,
+    /// `theme` config section, kept so switching themes preserves custom colors
+    pub theme_config: Option<cosmos_adapters::config::ThemeConfig>,
+    /// Active key bindings (defaults plus `keybindings` overrides)
+    pub keymap: keymap::Keymap,
+    /// Branch naming and commit message rules checked before git runs
+    pub git_policy: cosmos_adapters::config::GitPolicy,
+
+    // Background scan state
+    /// How often suggestions re-run in the background (None = off)
+    pub background_scan_interval: Option<Duration>,
+    /// When the last suggestion run (manual or background) finished
+    pub last_suggestion_scan_at: Option<Instant>,
+    /// Background scan results waiting for the user to be idle before they replace the list
+    pub pending_rescan: Option<Vec<Suggestion>>,
+    /// Suggestions the last background scan added, badged "NEW" in the Suggestions panel
+    pub new_suggestion_ids: HashSet<uuid::Uuid>,
+    /// Short-lived notification drawn over the top-right corner
+    pub toast: Option<Toast>,
+    /// Git state at the last status refresh, for noticing commits, checkouts, and edits
+    pub git_snapshot: Option<cosmos_core::context::GitStateSnapshot>,
+
+    // Mascot
+    /// Image protocol the mascot is drawn with (text-only terminals get ASCII art)
+    pub graphics: cosmos_adapters::terminal::GraphicsProtocol,
+    /// Last key press, for showing the mascot after a long idle
+    pub last_input_at: Instant,
+    /// When the mascot overlay opened
+    mascot_shown_at: Option<Instant>,
+
+    // Notifications
+    /// Bell and/or desktop notification when a long task finishes (`notify_on_finish`)
+    pub notify_mode: cosmos_adapters::config::NotifyMode,
+    /// Terminal focus from focus-change events; None until the terminal reports any
+    pub terminal_focused: Option<bool>,
+    /// Notification waiting for the runtime to write it to the terminal
+    pub pending_notification: Option<String>,
+}
+
+/// How long the startup splash stays up unless a key dismisses it sooner
+const SPLASH_DURATION: Duration = Duration::from_millis(1500);
+
+/// Quiet time before the mascot drifts in
+const MASCOT_IDLE_AFTER: Duration = Duration::from_secs(10 * 60);
+
+/// How long a toast stays on screen
+const TOAST_DURATION: Duration = Duration::from_secs(6);
+/// Suggestion runs shown in the header's health trend.
+pub const HEALTH_HISTORY_LEN: usize = 20;
+/// Snooze lengths offered for a suggestion; None snoozes until its file changes.
+pub const SNOOZE_CHOICES: [(&str, Option<i64>); 4] = [
+    ("For 1 day", Some(1)),
+    ("For 7 days", Some(7)),
+    ("For 30 days", Some(30)),
+    ("Until the file changes", None),
+];
+
+/// A short-lived notification
+#[derive(Debug, Clone)]
+pub struct Toast {
+    pub message: String,
+    pub shown_at: Instant,
 }
 
 impl App {
@@ -222,21 +338,28 @@ impl App {
             project_selected: 0,
             suggestion_scroll: 0,
             suggestion_selected: 0,
+            expanded_suggestion_id: None,
             overlay: Overlay::None,
             should_quit: false,
             input_mode: InputMode::Normal,
             search_query: String::new(),
             view_mode: ViewMode::Grouped, // Default to grouped view
+            package_scope: None,
+            suggestion_filter: Default::default(),
+            suggestion_sort: SuggestionSort::default(),
             question_input: String::new(),
             question_suggestion_selected: 0,
+            ask_context_paths: Vec::new(),
             ask_in_flight: false,
             active_ask_request_id: None,
             next_ask_request_id: 1,
             loading: LoadingState::None,
             loading_frame: 0,
+            tasks: crate::app::tasks::TaskManager::default(),
             repo_memory: cosmos_adapters::cache::RepoMemory::default(),
             glossary: cosmos_adapters::cache::DomainGlossary::default(),
             question_cache: cosmos_adapters::cache::QuestionCache::default(),
+            chat_session: cosmos_engine::llm::ChatSession::default(),
             session_cost: 0.0,
             session_tokens: 0,
             active_model: None,
@@ -254,8 +377,14 @@ impl App {
             grouped_search_entries,
             grouping_search_files,
             pending_changes: Vec::new(),
+            editor_request: None,
+            pending_fix_apply: None,
             cosmos_branch: None,
             cosmos_base_branch: None,
+            follow_up_pr: None,
+            stacked_changes: false,
+            stack: Vec::new(),
+            command_log: CommandLog::default(),
             pr_url: None,
             ship_step: None,
             workflow_step: WorkflowStep::default(),
@@ -267,6 +396,13 @@ impl App {
             git_refresh_error_at: None,
             current_suggestion_run_id: None,
             rolling_verify_precision: None,
+            health_history: Vec::new(),
+            directory_summaries: Default::default(),
+            suggestion_feedback: HashMap::new(),
+            snoozes: Default::default(),
+            dismissals: Vec::new(),
+            dismissed: Default::default(),
+            last_repeatable: None,
             armed_suggestion_id: None,
             armed_file_hashes: HashMap::new(),
             update_available: None,
@@ -274,11 +410,307 @@ impl App {
             budget_warned_soft: false,
             budget_warned_hard: false,
             needs_redraw: true,
+            workspace_trusted: false,
+            palette: theme::Palette::default(),
+            theme_config: None,
+            keymap: keymap::Keymap::default(),
+            git_policy: cosmos_adapters::config::GitPolicy::default(),
+            background_scan_interval: None,
+            last_suggestion_scan_at: None,
+            pending_rescan: None,
+            new_suggestion_ids: HashSet::new(),
+            toast: None,
+            git_snapshot: None,
+            branch_contexts: HashMap::new(),
+            graphics: cosmos_adapters::terminal::GraphicsProtocol::Text,
+            last_input_at: Instant::now(),
+            mascot_shown_at: None,
+            notify_mode: cosmos_adapters::config::NotifyMode::Off,
+            terminal_focused: None,
+            pending_notification: None,
+        }
+    }
+
+    /// Compare the refreshed git state with the previous refresh. A commit or checkout
+    /// re-indexes; edits to files a suggestion points at drop that suggestion (its evidence
+    /// may be stale), and the rest are re-ranked for the new set of changed files.
+    /// Returns true if anything changed.
+    pub fn observe_git_state(&mut self) -> bool {
+        let current = self.context.git_snapshot();
+        let Some(previous) = self.git_snapshot.replace(current.clone()) else {
+            return false;
+        };
+        let change = self.context.changes_between(&previous, &current);
+        if change.is_empty() {
+            return false;
+        }
+
+        if change.head_changed || change.branch_changed {
+            if let Ok(index) = CodebaseIndex::new(&self.repo_path) {
+                self.replace_index(index);
+            }
+        }
+        let removed = self
+            .suggestions
+            .invalidate_files(&change.files, self.armed_suggestion_id);
+        if let Some(pending) = &mut self.pending_rescan {
+            pending.retain(|s| !s.affected_files().iter().any(|f| change.files.contains(*f)));
+        }
+        self.suggestions.sort_with_context(&self.context, None);
+
+        let shown = self.active_suggestions_for_display().len();
+        self.suggestion_selected = self.suggestion_selected.min(shown.saturating_sub(1));
+        self.suggestion_scroll = self.suggestion_scroll.min(self.suggestion_selected);
+        if self.expanded_suggestion_id.is_some_and(|id| {
+            !self
+                .active_suggestions_for_display()
+                .iter()
+                .any(|s| s.id == id)
+        }) {
+            self.expanded_suggestion_id = None;
+        }
+        match removed {
+            0 => {}
+            1 => self.show_toast("1 suggestion cleared: its file changed"),
+            n => self.show_toast(format!("{} suggestions cleared: their files changed", n)),
+        }
+        true
+    }
+
+    /// Add a run's health score to the header trend, keeping the last `HEALTH_HISTORY_LEN`.
+    pub fn record_health(&mut self, score: u8) {
+        self.health_history.push(score);
+        if self.health_history.len() > HEALTH_HISTORY_LEN {
+            let excess = self.health_history.len() - HEALTH_HISTORY_LEN;
+            self.health_history.drain(..excess);
+        }
+    }
+
+    /// Show a notification that disappears on its own.
+    pub fn show_toast(&mut self, message: impl Into<String>) {
+        self.toast = Some(Toast {
+            message: message.into(),
+            shown_at: Instant::now(),
+        });
+        self.needs_redraw = true;
+    }
+
+    /// Time until the toast expires, if one is showing
+    pub fn toast_remaining(&self) -> Option<Duration> {
+        self.toast
+            .as_ref()
+            .map(|toast| TOAST_DURATION.saturating_sub(toast.shown_at.elapsed()))
+    }
+
+    /// Drop the toast once it has been shown long enough. Returns true if it was removed.
+    pub fn expire_toast(&mut self) -> bool {
+        if self
+            .toast
+            .as_ref()
+            .is_some_and(|toast| toast.shown_at.elapsed() >= TOAST_DURATION)
+        {
+            self.toast = None;
+            return true;
+        }
+        false
+    }
+
+    /// Queue a bell/desktop notification that a long task finished, unless notifications are
+    /// off or the terminal has focus (the user is already looking). Terminals that never
+    /// report focus are notified every time.
+    pub fn notify_finished(&mut self, message: impl Into<String>) {
+        if self.notify_mode == cosmos_adapters::config::NotifyMode::Off
+            || self.terminal_focused == Some(true)
+        {
+            return;
+        }
+        self.pending_notification = Some(message.into());
+    }
+
+    /// Open the mascot overlay, as the startup splash or the idle easter egg
+    pub fn show_mascot(&mut self, splash: bool) {
+        self.overlay = Overlay::Mascot { splash };
+        self.mascot_shown_at = Some(Instant::now());
+        self.needs_redraw = true;
+    }
+
+    /// Time until the startup splash closes itself, if it is showing
+    pub fn splash_remaining(&self) -> Option<Duration> {
+        match (&self.overlay, self.mascot_shown_at) {
+            (Overlay::Mascot { splash: true }, Some(shown_at)) => {
+                Some(SPLASH_DURATION.saturating_sub(shown_at.elapsed()))
+            }
+            _ => None,
+        }
+    }
+
+    /// Close the startup splash once it has been up long enough. Returns true if it closed.
+    pub fn expire_splash(&mut self) -> bool {
+        if self.splash_remaining() == Some(Duration::ZERO) {
+            self.close_overlay();
+            self.mascot_shown_at = None;
+            return true;
+        }
+        false
+    }
+
+    /// Whether nothing has happened for long enough to bring the mascot out
+    pub fn mascot_idle_due(&self) -> bool {
+        self.overlay == Overlay::None
+            && self.input_mode == InputMode::Normal
+            && self.loading == LoadingState::None
+            && self.tasks.active().is_none()
+            && self.last_input_at.elapsed() >= MASCOT_IDLE_AFTER
+    }
+
+    /// Whether a background scan may start or land without disturbing the user: nothing
+    /// running, no overlay or text input open, and no fix in progress.
+    pub fn is_idle_for_background_scan(&self) -> bool {
+        self.loading == LoadingState::None
+            && self.overlay == Overlay::None
+            && self.input_mode == InputMode::Normal
+            && self.workflow_step == WorkflowStep::Suggestions
+            && self.armed_suggestion_id.is_none()
+            && self.tasks.active().is_none()
+    }
+
+    /// Whether the configured background scan interval has passed since the last run.
+    /// Scans only repeat a run the user started, so nothing happens before the first one.
+    pub fn background_scan_due(&self) -> bool {
+        match (self.background_scan_interval, self.last_suggestion_scan_at) {
+            (Some(interval), Some(last)) => last.elapsed() >= interval,
+            _ => false,
+        }
+    }
+
+    /// Swap in waiting background scan results once the user is idle, badging the new ones.
+    /// Returns true if the list changed.
+    pub fn apply_pending_rescan(&mut self) -> bool {
+        if self.pending_rescan.is_none() || !self.is_idle_for_background_scan() {
+            return false;
+        }
+        let Some(suggestions) = self.pending_rescan.take() else {
+            return false;
+        };
+        let selected_id = self
+            .active_suggestions_for_display()
+            .get(self.suggestion_selected)
+            .map(|s| s.id);
+        let new_ids = self.suggestions.merge_rescan(suggestions);
+        self.suggestions.sort_with_context(&self.context, None);
+        self.new_suggestion_ids = new_ids.iter().copied().collect();
+
+        // Keep the cursor on the same suggestion when it survived the rescan.
+        let display_ids: Vec<uuid::Uuid> = self
+            .active_suggestions_for_display()
+            .iter()
+            .map(|s| s.id)
+            .collect();
+        self.suggestion_selected = selected_id
+            .and_then(|id| display_ids.iter().position(|shown| *shown == id))
+            .unwrap_or(0);
+        if self
+            .expanded_suggestion_id
+            .is_some_and(|id| !display_ids.contains(&id))
+        {
+            self.expanded_suggestion_id = None;
+        }
+        self.suggestion_scroll = self.suggestion_scroll.min(self.suggestion_selected);
+
+        match new_ids.len() {
+            0 => {}
+            1 => self.show_toast("1 new suggestion since last scan"),
+            n => self.show_toast(format!("{} new suggestions since last scan", n)),
+        }
+        self.needs_redraw = true;
+        true
+    }
+
+    /// Suggestions shown in the list: in the package scope, passing the filter, in sort order
+    pub fn active_suggestions_for_display(&self) -> Vec<&Suggestion> {
+        let mut suggestions = self.scoped_suggestions();
+        suggestions.retain(|s| self.suggestion_filter.matches(s));
+        if self.suggestion_sort == SuggestionSort::Impact {
+            self.suggestions.sort_by_impact(&mut suggestions);
+        }
+        suggestions
+    }
+
+    /// Switch between relevance and impact order, keeping the focused suggestion in view
+    pub fn toggle_suggestion_sort(&mut self) {
+        let focused = self
+            .active_suggestions_for_display()
+            .get(self.suggestion_selected)
+            .map(|s| s.id);
+        self.suggestion_sort = self.suggestion_sort.toggle();
+        self.suggestion_selected = focused
+            .and_then(|id| {
+                self.active_suggestions_for_display()
+                    .iter()
+                    .position(|s| s.id == id)
+            })
+            .unwrap_or(0);
+        self.suggestion_scroll = self.suggestion_scroll.min(self.suggestion_selected);
+        self.needs_redraw = true;
+    }
+
+    /// Suggestions in the package scope, before the filter
+    pub fn scoped_suggestions(&self) -> Vec<&Suggestion> {
+        let mut suggestions = self.suggestions.active_suggestions();
+        suggestions.retain(|s| !self.is_snoozed(s) && !self.dismissed.covers(s));
+        match self.scoped_package() {
+            Some(package) => suggestions
+                .into_iter()
+                .filter(|s| s.affected_files().iter().any(|f| package.contains(f)))
+                .collect(),
+            None => suggestions,
         }
     }
 
-    fn active_suggestions_for_display(&self) -> Vec<&Suggestion> {
-        self.suggestions.active_suggestions()
+    /// Replace the Suggestions panel filter and go back to the top of the list
+    pub fn set_suggestion_filter(&mut self, query: &str) {
+        self.suggestion_filter = cosmos_core::suggest::filter::SuggestionFilter::parse(query);
+        self.suggestion_selected = 0;
+        self.suggestion_scroll = 0;
+        self.expanded_suggestion_id = None;
+        self.clear_apply_confirm();
+        self.needs_redraw = true;
+    }
+
+    /// Whether the search input edits the suggestion filter rather than the file search
+    fn search_targets_suggestions(&self) -> bool {
+        self.active_panel == ActivePanel::Suggestions
+            && self.workflow_step == WorkflowStep::Suggestions
+    }
+
+    /// The workspace package currently in scope, if any
+    pub fn scoped_package(&self) -> Option<&cosmos_core::index::workspace::WorkspacePackage> {
+        self.package_scope
+            .as_deref()
+            .and_then(|name| self.index.package_named(name))
+    }
+
+    /// Cycle the package scope: whole repo, then each workspace package in order.
+    pub fn cycle_package_scope(&mut self) {
+        if self.index.packages.is_empty() {
+            self.package_scope = None;
+            return;
+        }
+        let next = match self.package_scope.as_deref() {
+            None => Some(0),
+            Some(name) => self
+                .index
+                .packages
+                .iter()
+                .position(|p| p.name == name)
+                .map(|idx| idx + 1)
+                .filter(|idx| *idx < self.index.packages.len()),
+        };
+        self.package_scope = next.map(|idx| self.index.packages[idx].name.clone());
+        self.suggestion_selected = 0;
+        self.suggestion_scroll = 0;
+        self.clear_apply_confirm();
+        self.apply_filter();
     }
 
     /// Apply a new grouping and rebuild grouped trees.
@@ -300,10 +732,17 @@ impl App {
     /// Replace index-backed UI data after a refresh.
     pub fn replace_index(&mut self, index: CodebaseIndex) {
         self.index = index;
+        if self.scoped_package().is_none() {
+            self.package_scope = None;
+        }
         self.suggestions.index = self.index.clone();
+        self.suggestions
+            .refresh_static_suggestions(!cosmos_engine::llm::is_available());
         self.file_tree = build_file_tree(&self.index);
         self.flat_search_entries = build_flat_search_entries(&self.file_tree);
         self.filtered_tree_indices = (0..self.file_tree.len()).collect();
+        self.directory_summaries.refresh(&self.index);
+        self.prune_snoozes();
         let grouping = self.index.generate_grouping();
         self.apply_grouping_update(grouping);
     }
@@ -313,10 +752,13 @@ impl App {
         self.pending_changes.clear();
         self.cosmos_branch = None;
         self.cosmos_base_branch = None;
+        let _ = cosmos_adapters::undo::clear(&self.repo_path);
     }
 
-    /// Undo the most recent applied change by restoring files from git.
-    /// Supports multi-file changes - restores all files atomically.
+    /// Undo the most recent applied change.
+    /// Restores the pre-apply snapshots from the undo stack when available
+    /// (so earlier pending changes to the same file survive), falling back to git HEAD.
+    /// Supports multi-file changes - restores all files together.
     /// Removes it from the pending queue.
     /// If this was the last pending change, returns to main branch and suggestions step.
     pub fn undo_last_pending_change(&mut self) -> Result<(), String> {
@@ -325,15 +767,23 @@ impl App {
             .pop()
             .ok_or_else(|| "No pending changes to undo".to_string())?;
 
-        // Collect paths to restore (to avoid borrow issues)
-        let files_to_restore: Vec<_> = change.files.iter().map(|f| f.path.clone()).collect();
-
-        // Restore all files from git HEAD
-        for path in &files_to_restore {
-            if let Err(e) = cosmos_adapters::git_ops::restore_file(&self.repo_path, path) {
+        if let Some(run_id) = change.undo_run_id.as_deref() {
+            if let Err(e) = cosmos_adapters::undo::undo(&self.repo_path, run_id) {
                 // Put the change back since we couldn't fully undo
                 self.pending_changes.push(change);
-                return Err(format!("Failed to restore {}: {}", path.display(), e));
+                return Err(e.to_string());
+            }
+        } else {
+            // Collect paths to restore (to avoid borrow issues)
+            let files_to_restore: Vec<_> = change.files.iter().map(|f| f.path.clone()).collect();
+
+            // Restore all files from git HEAD
+            for path in &files_to_restore {
+                if let Err(e) = cosmos_adapters::git_ops::restore_file(&self.repo_path, path) {
+                    // Put the change back since we couldn't fully undo
+                    self.pending_changes.push(change);
+                    return Err(format!("Failed to restore {}: {}", path.display(), e));
+                }
             }
         }
 
@@ -365,9 +815,18 @@ impl App {
         Ok(())
     }
 
+    /// Whether a spinner is on screen, so frames keep coming while nothing else changes
+    pub fn spinner_visible(&self) -> bool {
+        self.loading.is_loading()
+            || self.ask_in_flight
+            || self.review_state.reviewing
+            || self.review_state.fixing
+            || self.review_state.posting
+    }
+
     /// Tick the loading animation
     pub fn tick_loading(&mut self) {
-        if self.loading.is_loading() {
+        if self.spinner_visible() {
             self.loading_frame = self.loading_frame.wrapping_add(1);
         }
     }
@@ -585,10 +1044,8 @@ impl App {
             let state = &mut workers[idx];
 
             match kind {
-                "reasoning" => {
-                    if !text.is_empty() {
-                        state.reasoning = Some(text.to_string());
-                    }
+                "reasoning" if !text.is_empty() => {
+                    state.reasoning = Some(text.to_string());
                 }
                 "tool" => {
                     for tool in parse_tool_names(text) {
@@ -608,10 +1065,8 @@ impl App {
                         }
                     }
                 }
-                "notice" => {
-                    if !text.is_empty() {
-                        state.notice = Some(text.to_string());
-                    }
+                "notice" if !text.is_empty() => {
+                    state.notice = Some(text.to_string());
                 }
                 _ => {}
             }
@@ -636,12 +1091,12 @@ impl App {
             ));
 
             let thought = latest_worker_thought(worker.reasoning.as_deref());
-            lines.push(format!("  now: {}", truncate_display_text(&thought, 92)));
+            lines.push(format!("  now: {}", truncate_to_width(&thought, 92)));
 
             if let Some(recent_tools) = summarize_recent_tool_activity(&worker.tool_history) {
                 lines.push(format!(
                     "  recent: {}",
-                    truncate_display_text(&recent_tools, 92)
+                    truncate_to_width(&recent_tools, 92)
                 ));
             }
 
@@ -651,22 +1106,28 @@ impl App {
             if let Some(notice) = worker.notice.as_deref() {
                 lines.push(format!(
                     "  note: {}",
-                    truncate_display_text(&humanize_reasoning_segment(notice), 92)
+                    truncate_to_width(&humanize_reasoning_segment(notice), 92)
                 ));
             }
         }
         lines
     }
 
-    /// Enter search mode
+    /// Enter search mode; on the Suggestions panel the current filter is kept to refine
     pub fn start_search(&mut self) {
         self.input_mode = InputMode::Search;
-        self.search_query.clear();
+        if !self.search_targets_suggestions() {
+            self.search_query.clear();
+        }
     }
 
-    /// Exit search mode
+    /// Exit search mode, clearing the query
     pub fn exit_search(&mut self) {
         self.input_mode = InputMode::Normal;
+        if self.search_targets_suggestions() {
+            self.set_suggestion_filter("");
+            return;
+        }
         self.search_query.clear();
         self.apply_filter();
     }
@@ -744,12 +1205,23 @@ impl App {
 
     /// Add character to search query
     pub fn search_push(&mut self, c: char) {
+        if self.search_targets_suggestions() {
+            let query = format!("{}{}", self.suggestion_filter.query(), c);
+            self.set_suggestion_filter(&query);
+            return;
+        }
         self.search_query.push(c);
         self.apply_filter();
     }
 
     /// Remove last character from search query
     pub fn search_pop(&mut self) {
+        if self.search_targets_suggestions() {
+            let mut query = self.suggestion_filter.query().to_string();
+            query.pop();
+            self.set_suggestion_filter(&query);
+            return;
+        }
         self.search_query.pop();
         self.apply_filter();
     }
@@ -772,7 +1244,8 @@ impl App {
     }
 
     fn apply_flat_filter(&mut self) {
-        if self.search_query.is_empty() {
+        let scope_root = self.scoped_package().map(|p| p.root.clone());
+        if self.search_query.is_empty() && scope_root.is_none() {
             self.filtered_tree_indices = (0..self.file_tree.len()).collect();
         } else {
             let query = self.search_query.to_lowercase();
@@ -780,8 +1253,12 @@ impl App {
                 .flat_search_entries
                 .iter()
                 .enumerate()
-                .filter(|(_, entry)| {
-                    entry.name_lower.contains(&query) || entry.path_lower.contains(&query)
+                .filter(|(idx, entry)| {
+                    let in_scope = scope_root
+                        .as_ref()
+                        .is_none_or(|root| self.file_tree[*idx].path.starts_with(root));
+                    in_scope
+                        && (entry.name_lower.contains(&query) || entry.path_lower.contains(&query))
                 })
                 .map(|(idx, _)| idx)
                 .collect();
@@ -793,7 +1270,8 @@ impl App {
     }
 
     fn apply_grouped_filter(&mut self) {
-        if self.search_query.is_empty() {
+        let scope_root = self.scoped_package().map(|p| p.root.clone());
+        if self.search_query.is_empty() && scope_root.is_none() {
             self.filtered_grouped_indices = (0..self.grouped_tree.len()).collect();
             if self.project_selected >= self.filtered_grouped_indices.len() {
                 self.project_selected = self.filtered_grouped_indices.len().saturating_sub(1);
@@ -802,10 +1280,17 @@ impl App {
         }
 
         let query = self.search_query.to_lowercase();
+        let scope_root_lower = scope_root
+            .as_ref()
+            .map(|root| PathBuf::from(root.to_string_lossy().to_lowercase()));
         let mut matching_layers: HashSet<cosmos_core::grouping::Layer> = HashSet::new();
 
         for entry in &self.grouping_search_files {
-            if entry.name_lower.contains(&query) || entry.path_lower.contains(&query) {
+            let in_scope = scope_root_lower
+                .as_ref()
+                .is_none_or(|root| Path::new(&entry.path_lower).starts_with(root));
+            if in_scope && (entry.name_lower.contains(&query) || entry.path_lower.contains(&query))
+            {
                 matching_layers.insert(entry.layer);
             }
         }
@@ -817,7 +1302,8 @@ impl App {
         }
 
         self.rebuild_grouped_tree_cache();
-        self.filtered_grouped_indices = self.filter_grouped_indices(&query, &matching_layers);
+        self.filtered_grouped_indices =
+            self.filter_grouped_indices(&query, &matching_layers, scope_root.as_deref());
 
         if self.project_selected >= self.filtered_grouped_indices.len() {
             self.project_selected = self.filtered_grouped_indices.len().saturating_sub(1);
@@ -825,10 +1311,12 @@ impl App {
     }
 
     /// Filter out grouped entries in a single pass.
+    /// Files outside `scope_root` (when set) are always dropped.
     fn filter_grouped_indices(
         &self,
         query: &str,
         matching_layers: &HashSet<cosmos_core::grouping::Layer>,
+        scope_root: Option<&Path>,
     ) -> Vec<usize> {
         use cosmos_core::grouping::GroupedEntryKind;
 
@@ -866,14 +1354,20 @@ impl App {
                         continue;
                     }
                     current_feature_idx = Some(idx);
-                    current_feature_name_match =
-                        self.grouped_search_entries[idx].name_lower.contains(query);
+                    current_feature_name_match = !query.is_empty()
+                        && self.grouped_search_entries[idx].name_lower.contains(query);
                     current_feature_emitted = false;
                 }
                 GroupedEntryKind::File => {
                     if !current_layer_matches {
                         continue;
                     }
+                    let in_scope = scope_root.is_none_or(|root| {
+                        entry.path.as_ref().is_some_and(|p| p.starts_with(root))
+                    });
+                    if !in_scope {
+                        continue;
+                    }
 
                     let search = &self.grouped_search_entries[idx];
                     let name_matches = search.name_lower.contains(query);
@@ -1107,188 +1601,1350 @@ impl App {
         suggestions.get(self.suggestion_selected).copied()
     }
 
-    /// Arm two-step apply confirmation for the currently selected suggestion.
-    pub fn arm_apply_confirm(
-        &mut self,
-        suggestion_id: uuid::Uuid,
-        file_hashes: HashMap<PathBuf, String>,
-    ) {
-        self.armed_suggestion_id = Some(suggestion_id);
-        self.armed_file_hashes = file_hashes;
+    /// Queue the selected suggestion's file for `$EDITOR`, at the suggestion's line.
+    pub fn open_selected_suggestion_in_editor(&mut self) {
+        let Some(suggestion) = self.selected_suggestion() else {
+            return;
+        };
+        let line = suggestion.line.or_else(|| {
+            suggestion
+                .evidence_refs
+                .iter()
+                .find(|evidence| evidence.file == suggestion.file)
+                .map(|evidence| evidence.line)
+        });
+        self.editor_request = Some(EditorRequest {
+            path: suggestion.file.clone(),
+            line,
+        });
     }
 
-    /// Clear two-step apply confirmation state.
-    pub fn clear_apply_confirm(&mut self) {
-        self.armed_suggestion_id = None;
-        self.armed_file_hashes.clear();
+    /// What the copy key grabs here, with a label for the toast: the Ask answer when the
+    /// Ask panel is focused, the PR URL once one exists, otherwise the focused finding or
+    /// suggestion
+    pub fn copy_target(&self) -> Option<(&'static str, String)> {
+        if self.active_panel == ActivePanel::Ask {
+            return self
+                .ask_cosmos_state
+                .as_ref()
+                .filter(|state| !state.response.trim().is_empty())
+                .map(|state| ("answer", state.response.clone()));
+        }
+        match self.workflow_step {
+            WorkflowStep::Ship => {
+                let url = if self.ship_state.step == ShipStep::Stack {
+                    self.stack
+                        .get(self.ship_state.stack_selected)
+                        .and_then(|entry| entry.pr_url.clone())
+                } else {
+                    self.ship_state.pr_url.clone()
+                };
+                url.or_else(|| self.pr_url.clone())
+                    .map(|url| ("PR URL", url))
+            }
+            WorkflowStep::Review => self
+                .review_state
+                .findings
+                .get(self.review_state.cursor)
+                .map(|finding| {
+                    let location = match finding.line {
+                        Some(line) => format!("{}:{}", finding.file, line),
+                        None => finding.file.clone(),
+                    };
+                    (
+                        "finding",
+                        format!("{}\n{}\n\n{}", finding.title, location, finding.description),
+                    )
+                }),
+            WorkflowStep::Suggestions => self.selected_suggestion().map(|suggestion| {
+                let location = match suggestion.line {
+                    Some(line) => format!("{}:{}", suggestion.file.display(), line),
+                    None => suggestion.file.display().to_string(),
+                };
+                let mut text = format!("{}\n{}", suggestion.summary, location);
+                if let Some(detail) = suggestion
+                    .detail
+                    .as_deref()
+                    .filter(|d| !d.trim().is_empty())
+                {
+                    text.push_str("\n\n");
+                    text.push_str(detail.trim());
+                }
+                ("suggestion", text)
+            }),
+        }
     }
 
-    /// Toggle help overlay
-    pub fn toggle_help(&mut self) {
-        self.overlay = match self.overlay {
-            Overlay::Help { .. } => Overlay::None,
-            _ => Overlay::Help { scroll: 0 },
-        };
+    /// Put `text` on the clipboard and confirm with a toast, or explain why it failed
+    pub fn copy_to_clipboard(&mut self, what: &str, text: &str) {
+        match cosmos_adapters::clipboard::copy(text) {
+            Ok(cosmos_adapters::clipboard::ClipboardMethod::Native(_)) => {
+                self.show_toast(format!("Copied {}", what))
+            }
+            Ok(cosmos_adapters::clipboard::ClipboardMethod::Osc52) => {
+                self.show_toast(format!("Copied {} via the terminal", what))
+            }
+            Err(e) => self.open_alert("Couldn't copy", e.to_string()),
+        }
     }
 
-    /// Close overlay
-    pub fn close_overlay(&mut self) {
-        self.overlay = Overlay::None;
+    /// Rate the selected suggestion. Ratings are persisted and shape later suggestion runs.
+    pub fn rate_selected_suggestion(&mut self, feedback: cosmos_core::suggest::SuggestionFeedback) {
+        let Some(suggestion) = self.selected_suggestion() else {
+            return;
+        };
+        if self.suggestion_feedback.get(&suggestion.id) == Some(&feedback) {
+            return;
+        }
+        let record = cosmos_adapters::cache::SuggestionFeedbackRecord {
+            timestamp: chrono::Utc::now(),
+            suggestion_id: suggestion.id.to_string(),
+            feedback,
+            category: suggestion.category,
+            file: suggestion.file.clone(),
+            summary: suggestion.summary.clone(),
+        };
+        let id = suggestion.id;
+        if let Err(e) =
+            cosmos_adapters::cache::Cache::new(&self.repo_path).append_suggestion_feedback(&record)
+        {
+            self.open_alert("Couldn't save feedback", e.to_string());
+            return;
+        }
+        self.suggestion_feedback.insert(id, feedback);
     }
 
-    /// Open in-TUI API key setup overlay.
-    pub fn open_api_key_overlay(&mut self, error: Option<String>) {
-        self.overlay = Overlay::ApiKeySetup {
-            input: String::new(),
-            error,
-            save_armed: false,
-        };
+    /// Whether `suggestion` is under a snooze that hasn't ended
+    pub fn is_snoozed(&self, suggestion: &Suggestion) -> bool {
+        self.snoozes.get(suggestion).is_some_and(|record| {
+            record.is_active(chrono::Utc::now(), self.file_content_hash(&record.file))
+        })
     }
 
-    pub fn open_suggestion_focus_overlay(&mut self) {
-        self.overlay = Overlay::SuggestionFocus {
-            selected: self.suggestion_review_focus,
-        };
+    fn file_content_hash(&self, path: &Path) -> Option<&str> {
+        self.index
+            .files
+            .get(path)
+            .map(|file| file.content_hash.as_str())
     }
 
-    pub fn suggestion_focus_set_selected(
-        &mut self,
-        selected: cosmos_engine::llm::SuggestionReviewFocus,
-    ) {
-        if let Overlay::SuggestionFocus {
-            selected: overlay_selected,
-        } = &mut self.overlay
-        {
-            *overlay_selected = selected;
+    /// Forget snoozes that have ended, e.g. after the index picked up edited files.
+    pub fn prune_snoozes(&mut self) {
+        let now = chrono::Utc::now();
+        let index = &self.index;
+        let before = self.snoozes.snoozes.len();
+        self.snoozes.retain_active(now, |path| {
+            index.files.get(path).map(|file| file.content_hash.as_str())
+        });
+        if self.snoozes.snoozes.len() != before {
+            let active = self.snoozes.clone();
+            let _ = cosmos_adapters::cache::Cache::new(&self.repo_path)
+                .update_snoozes(|snoozes| *snoozes = active);
         }
     }
 
-    pub fn suggestion_focus_navigate(&mut self, delta: isize) {
-        if let Overlay::SuggestionFocus {
-            selected: overlay_selected,
-        } = &mut self.overlay
-        {
-            if delta != 0 {
-                *overlay_selected = overlay_selected.toggle();
-            }
-        }
+    /// Ask how long to snooze the selected suggestion.
+    pub fn open_snooze_overlay(&mut self) {
+        let Some(suggestion_id) = self.selected_suggestion().map(|s| s.id) else {
+            return;
+        };
+        self.overlay = Overlay::Snooze {
+            suggestion_id,
+            selected: 0,
+        };
     }
 
-    pub fn suggestion_focus_selected(&self) -> Option<cosmos_engine::llm::SuggestionReviewFocus> {
-        match self.overlay {
-            Overlay::SuggestionFocus { selected } => Some(selected),
-            _ => None,
+    pub fn snooze_navigate(&mut self, delta: isize) {
+        if let Overlay::Snooze { selected, .. } = &mut self.overlay {
+            let len = SNOOZE_CHOICES.len();
+            *selected = (*selected as isize + delta).rem_euclid(len as isize) as usize;
         }
     }
 
-    /// Open pre-apply plan overlay with explicit scope and intent.
-    pub fn open_apply_plan_overlay(
-        &mut self,
-        suggestion_id: uuid::Uuid,
-        preview: cosmos_engine::llm::FixPreview,
-        affected_files: Vec<PathBuf>,
-        show_data_notice: bool,
-    ) {
-        self.overlay = Overlay::ApplyPlan {
+    /// Snooze the suggestion the overlay was opened for with the focused choice.
+    pub fn confirm_snooze(&mut self) {
+        let Overlay::Snooze {
             suggestion_id,
-            preview,
-            affected_files,
-            confirm_apply: false,
-            show_technical_details: false,
-            show_data_notice,
-            scroll: 0,
+            selected,
+        } = self.overlay
+        else {
+            return;
         };
+        self.close_overlay();
+        self.snooze_suggestion(suggestion_id, selected);
     }
 
-    pub fn apply_plan_scroll_down(&mut self) {
-        if let Overlay::ApplyPlan { scroll, .. } = &mut self.overlay {
-            *scroll += 1;
+    /// Snooze `suggestion_id` with row `choice` of `SNOOZE_CHOICES`.
+    pub fn snooze_suggestion(&mut self, suggestion_id: uuid::Uuid, choice: usize) {
+        let Some(suggestion) = self
+            .suggestions
+            .suggestions
+            .iter()
+            .find(|s| s.id == suggestion_id)
+        else {
+            return;
+        };
+        let (label, days) = SNOOZE_CHOICES[choice.min(SNOOZE_CHOICES.len() - 1)];
+        let record = match days {
+            Some(days) => cosmos_adapters::cache::SnoozeRecord::for_days(suggestion, days),
+            None => match self.file_content_hash(&suggestion.file) {
+                Some(hash) => {
+                    cosmos_adapters::cache::SnoozeRecord::until_file_changes(suggestion, hash)
+                }
+                None => {
+                    self.show_toast("That file isn't indexed; snooze it for a few days instead");
+                    return;
+                }
+            },
+        };
+        match cosmos_adapters::cache::Cache::new(&self.repo_path)
+            .update_snoozes(|snoozes| snoozes.snooze(record))
+        {
+            Ok(snoozes) => self.snoozes = snoozes,
+            Err(e) => {
+                self.open_alert("Couldn't snooze", e.to_string());
+                return;
+            }
         }
+        self.expanded_suggestion_id = None;
+        self.clear_apply_confirm();
+        let shown = self.active_suggestions_for_display().len();
+        self.suggestion_selected = self.suggestion_selected.min(shown.saturating_sub(1));
+        self.suggestion_scroll = self.suggestion_scroll.min(self.suggestion_selected);
+        self.show_toast(format!("Snoozed {}", label.to_lowercase()));
     }
 
-    pub fn apply_plan_scroll_up(&mut self) {
-        if let Overlay::ApplyPlan { scroll, .. } = &mut self.overlay {
-            *scroll = scroll.saturating_sub(1);
+    /// List snoozed suggestions.
+    pub fn open_snoozed_overlay(&mut self) {
+        self.prune_snoozes();
+        self.overlay = Overlay::Snoozed { selected: 0 };
+    }
+
+    pub fn snoozed_navigate(&mut self, delta: isize) {
+        let len = self.snoozes.snoozes.len();
+        if let Overlay::Snoozed { selected } = &mut self.overlay {
+            if len > 0 {
+                *selected = (*selected as isize + delta).rem_euclid(len as isize) as usize;
+            }
         }
     }
 
-    pub fn apply_plan_toggle_technical_details(&mut self) {
-        if let Overlay::ApplyPlan {
-            show_technical_details,
-            ..
-        } = &mut self.overlay
+    /// Bring the focused snoozed suggestion back to the list.
+    pub fn unsnooze_selected(&mut self) {
+        let Overlay::Snoozed { selected } = self.overlay else {
+            return;
+        };
+        let Some(finding_id) = self
+            .snoozes
+            .snoozes
+            .get(selected)
+            .map(|record| record.finding_id)
+        else {
+            return;
+        };
+        match cosmos_adapters::cache::Cache::new(&self.repo_path)
+            .update_snoozes(|snoozes| snoozes.unsnooze(finding_id))
         {
-            *show_technical_details = !*show_technical_details;
+            Ok(snoozes) => self.snoozes = snoozes,
+            Err(e) => {
+                self.open_alert("Couldn't unsnooze", e.to_string());
+                return;
+            }
+        }
+        let remaining = self.snoozes.snoozes.len();
+        if let Overlay::Snoozed { selected } = &mut self.overlay {
+            *selected = (*selected).min(remaining.saturating_sub(1));
         }
+        self.needs_redraw = true;
     }
 
-    pub fn apply_plan_set_confirm(&mut self, confirm: bool) {
+    /// Offer scopes around the selected suggestion to dismiss in one go.
+    pub fn open_bulk_dismiss_overlay(&mut self) {
+        let Some(focused) = self.selected_suggestion() else {
+            return;
+        };
+        let listed = self.scoped_suggestions();
+        let scopes = cosmos_core::suggest::BulkDismissScope::around(focused)
+            .into_iter()
+            .map(|scope| {
+                let count = listed.iter().filter(|s| scope.matches(s)).count();
+                (scope, count)
+            })
+            .collect();
+        self.overlay = Overlay::BulkDismiss {
+            scopes,
+            selected: 0,
+        };
+    }
+
+    pub fn bulk_dismiss_navigate(&mut self, delta: isize) {
+        if let Overlay::BulkDismiss { scopes, selected } = &mut self.overlay {
+            if !scopes.is_empty() {
+                let len = scopes.len() as isize;
+                *selected = (*selected as isize + delta).rem_euclid(len) as usize;
+            }
+        }
+    }
+
+    /// Dismiss every listed suggestion in the focused scope.
+    pub fn confirm_bulk_dismiss(&mut self) {
+        let Overlay::BulkDismiss { scopes, selected } = &self.overlay else {
+            return;
+        };
+        let Some((scope, _)) = scopes.get(*selected).cloned() else {
+            return;
+        };
+        self.close_overlay();
+        let matched: Vec<&Suggestion> = self
+            .scoped_suggestions()
+            .into_iter()
+            .filter(|s| !s.applied && scope.matches(s))
+            .collect();
+        if matched.is_empty() {
+            self.show_toast(format!("No {} to dismiss", scope.label()));
+            return;
+        }
+        let ids: HashSet<uuid::Uuid> = matched.iter().map(|s| s.id).collect();
+        let record = cosmos_adapters::cache::DismissalRecord::new(&scope.label(), &matched);
+        let record_id = record.id;
+        match cosmos_adapters::cache::Cache::new(&self.repo_path)
+            .update_dismissals(|dismissals| dismissals.record(record))
+        {
+            Ok(dismissed) => self.dismissed = dismissed,
+            Err(e) => {
+                self.open_alert("Couldn't dismiss", e.to_string());
+                return;
+            }
+        }
+        let dismissed = self.suggestions.dismiss(&ids);
+        self.show_toast(format!(
+            "Dismissed {} {} · u to undo",
+            dismissed.len(),
+            scope.label()
+        ));
+        self.dismissals.push(BulkDismissal {
+            label: scope.label(),
+            record_id,
+            suggestions: dismissed,
+            pending_changes: self.pending_changes.len(),
+        });
+        self.expanded_suggestion_id = None;
+        self.clear_apply_confirm();
+        let shown = self.active_suggestions_for_display().len();
+        self.suggestion_selected = self.suggestion_selected.min(shown.saturating_sub(1));
+        self.suggestion_scroll = self.suggestion_scroll.min(self.suggestion_selected);
+    }
+
+    /// Undo the latest bulk dismiss, unless a fix was applied after it (that is undone first).
+    ///
+    /// Returns whether there was one to undo.
+    pub fn undo_last_bulk_dismissal(&mut self) -> bool {
+        let latest = self
+            .dismissals
+            .last()
+            .is_some_and(|dismissal| dismissal.pending_changes >= self.pending_changes.len());
+        let Some(dismissal) = latest.then(|| self.dismissals.pop()).flatten() else {
+            return false;
+        };
+        match cosmos_adapters::cache::Cache::new(&self.repo_path)
+            .update_dismissals(|dismissals| dismissals.remove(dismissal.record_id))
+        {
+            Ok(dismissed) => self.dismissed = dismissed,
+            Err(e) => {
+                self.dismissals.push(dismissal);
+                self.open_alert("Couldn't undo the dismissal", e.to_string());
+                return true;
+            }
+        }
+        let restored = self.suggestions.restore(dismissal.suggestions);
+        self.show_toast(format!("Restored {} {}", restored, dismissal.label));
+        true
+    }
+
+    /// Expand the selected suggestion in place, or collapse it if already expanded.
+    ///
+    /// Scroll position is left alone so the list doesn't jump while scanning.
+    pub fn toggle_selected_suggestion_expanded(&mut self) {
+        let Some(id) = self.selected_suggestion().map(|s| s.id) else {
+            return;
+        };
+        self.expanded_suggestion_id = if self.expanded_suggestion_id == Some(id) {
+            None
+        } else {
+            Some(id)
+        };
+    }
+
+    /// Symbols in the suggestion's primary file that enclose its line, innermost first.
+    pub fn suggestion_affected_symbols(&self, suggestion: &Suggestion) -> Vec<String> {
+        let Some(line) = suggestion.line else {
+            return Vec::new();
+        };
+        let Some(file) = self.index.files.get(&suggestion.file) else {
+            return Vec::new();
+        };
+        let mut enclosing = file
+            .symbols
+            .iter()
+            .filter(|symbol| symbol.line <= line && line <= symbol.end_line)
+            .collect::<Vec<_>>();
+        enclosing.sort_by_key(|symbol| symbol.line_count());
+        enclosing
+            .into_iter()
+            .map(|symbol| symbol.name.clone())
+            .collect()
+    }
+
+    /// Arm two-step apply confirmation for the currently selected suggestion.
+    pub fn arm_apply_confirm(
+        &mut self,
+        suggestion_id: uuid::Uuid,
+        file_hashes: HashMap<PathBuf, String>,
+    ) {
+        self.armed_suggestion_id = Some(suggestion_id);
+        self.armed_file_hashes = file_hashes;
+    }
+
+    /// Clear two-step apply confirmation state.
+    pub fn clear_apply_confirm(&mut self) {
+        self.armed_suggestion_id = None;
+        self.armed_file_hashes.clear();
+    }
+
+    /// Toggle help overlay
+    pub fn toggle_help(&mut self) {
+        self.overlay = match self.overlay {
+            Overlay::Help { .. } => Overlay::None,
+            _ => Overlay::Help { scroll: 0 },
+        };
+    }
+
+    /// Close overlay
+    pub fn close_overlay(&mut self) {
+        self.overlay = Overlay::None;
+    }
+
+    /// Ask whether this repo may run its own commands.
+    pub fn open_workspace_trust_overlay(&mut self) {
+        self.overlay = Overlay::WorkspaceTrust;
+    }
+
+    /// Apply the user's trust decision and close the prompt.
+    pub fn resolve_workspace_trust(&mut self, trusted: bool) {
+        self.workspace_trusted = trusted;
+        self.close_overlay();
+    }
+
+    /// Open in-TUI API key setup overlay.
+    pub fn open_api_key_overlay(&mut self, error: Option<String>) {
+        self.overlay = Overlay::ApiKeySetup {
+            input: String::new(),
+            error,
+            save_armed: false,
+        };
+    }
+
+    pub fn open_suggestion_focus_overlay(&mut self) {
+        self.overlay = Overlay::SuggestionFocus {
+            selected: self.suggestion_review_focus,
+        };
+    }
+
+    pub fn suggestion_focus_set_selected(
+        &mut self,
+        selected: cosmos_engine::llm::SuggestionReviewFocus,
+    ) {
+        if let Overlay::SuggestionFocus {
+            selected: overlay_selected,
+        } = &mut self.overlay
+        {
+            *overlay_selected = selected;
+        }
+    }
+
+    pub fn suggestion_focus_navigate(&mut self, delta: isize) {
+        if let Overlay::SuggestionFocus {
+            selected: overlay_selected,
+        } = &mut self.overlay
+        {
+            if delta != 0 {
+                *overlay_selected = overlay_selected.toggle();
+            }
+        }
+    }
+
+    pub fn suggestion_focus_selected(&self) -> Option<cosmos_engine::llm::SuggestionReviewFocus> {
+        match self.overlay {
+            Overlay::SuggestionFocus { selected } => Some(selected),
+            _ => None,
+        }
+    }
+
+    /// Open pre-apply plan overlay with explicit scope and intent.
+    pub fn open_apply_plan_overlay(
+        &mut self,
+        suggestion_id: uuid::Uuid,
+        preview: cosmos_engine::llm::FixPreview,
+        affected_files: Vec<PathBuf>,
+        show_data_notice: bool,
+    ) {
+        let conflicts = self.apply_plan_conflicts(suggestion_id);
+        self.overlay = Overlay::ApplyPlan {
+            suggestion_id,
+            preview: Box::new(preview),
+            affected_files,
+            confirm_apply: false,
+            show_technical_details: false,
+            show_data_notice,
+            conflicts,
+            scroll: 0,
+        };
+    }
+
+    /// Describe the other unapplied suggestions that share files with the one about to apply.
+    fn apply_plan_conflicts(&self, suggestion_id: uuid::Uuid) -> Vec<String> {
+        let suggestions = &self.suggestions.suggestions;
+        let Some(target) = suggestions.iter().find(|s| s.id == suggestion_id) else {
+            return Vec::new();
+        };
+        let queue: Vec<&Suggestion> = std::iter::once(target)
+            .chain(
+                suggestions
+                    .iter()
+                    .filter(|s| s.id != suggestion_id && !s.applied),
+            )
+            .collect();
+        cosmos_engine::llm::detect_fix_conflicts(&queue)
+            .into_iter()
+            .filter(|conflict| conflict.earlier == suggestion_id)
+            .filter_map(|conflict| {
+                let later = suggestions.iter().find(|s| s.id == conflict.later)?;
+                Some(match conflict.overlap {
+                    Some((start, end)) => format!(
+                        "Overlaps \"{}\" in {} (lines {}-{}); it will be regenerated after this applies.",
+                        later.summary,
+                        conflict.file.display(),
+                        start,
+                        end
+                    ),
+                    None => format!(
+                        "\"{}\" also edits {}; its line anchors will be rebased after this applies.",
+                        later.summary,
+                        conflict.file.display()
+                    ),
+                })
+            })
+            .collect()
+    }
+
+    /// Rebase unapplied suggestions onto files changed by the fix that just applied, so later
+    /// fixes are generated from the post-apply content instead of stale anchors.
+    pub fn rebase_suggestions_after_apply(&mut self, applied: &[(PathBuf, String, String)]) {
+        for suggestion in self
+            .suggestions
+            .suggestions
+            .iter_mut()
+            .filter(|s| !s.applied)
+        {
+            match cosmos_engine::llm::rebase_after_apply(suggestion, applied) {
+                cosmos_engine::llm::RebasedSuggestion::Unaffected => {}
+                cosmos_engine::llm::RebasedSuggestion::Shifted(rebased)
+                | cosmos_engine::llm::RebasedSuggestion::Regenerate(rebased) => {
+                    *suggestion = rebased;
+                }
+            }
+        }
+    }
+
+    pub fn apply_plan_scroll_down(&mut self) {
+        if let Overlay::ApplyPlan { scroll, .. } = &mut self.overlay {
+            *scroll += 1;
+        }
+    }
+
+    pub fn apply_plan_scroll_up(&mut self) {
+        if let Overlay::ApplyPlan { scroll, .. } = &mut self.overlay {
+            *scroll = scroll.saturating_sub(1);
+        }
+    }
+
+    pub fn apply_plan_toggle_technical_details(&mut self) {
+        if let Overlay::ApplyPlan {
+            show_technical_details,
+            ..
+        } = &mut self.overlay
+        {
+            *show_technical_details = !*show_technical_details;
+        }
+    }
+
+    pub fn apply_plan_set_confirm(&mut self, confirm: bool) {
         if let Overlay::ApplyPlan { confirm_apply, .. } = &mut self.overlay {
             *confirm_apply = confirm;
         }
     }
 
-    pub fn apply_plan_confirmed(&self) -> bool {
-        matches!(
-            self.overlay,
-            Overlay::ApplyPlan {
-                confirm_apply: true,
-                ..
+    pub fn apply_plan_confirmed(&self) -> bool {
+        matches!(
+            self.overlay,
+            Overlay::ApplyPlan {
+                confirm_apply: true,
+                ..
+            }
+        )
+    }
+
+    pub fn apply_plan_suggestion_id(&self) -> Option<uuid::Uuid> {
+        match &self.overlay {
+            Overlay::ApplyPlan { suggestion_id, .. } => Some(*suggestion_id),
+            _ => None,
+        }
+    }
+
+    /// Show inquiry response in the right panel (Ask Cosmos mode)
+    pub fn show_inquiry(&mut self, response: String) {
+        self.input_mode = InputMode::Normal;
+        self.ask_in_flight = false;
+        self.active_ask_request_id = None;
+        self.ask_cosmos_state = Some(AskCosmosState {
+            response,
+            scroll: 0,
+            question: None,
+            transcript: String::new(),
+            transcript_scroll: 0,
+            context: None,
+        });
+    }
+
+    /// Record an answered question in the conversation and show it below the earlier turns.
+    ///
+    /// `context` is what the answer was grounded on (None for cached answers).
+    pub fn show_chat_answer(
+        &mut self,
+        question: String,
+        answer: String,
+        context: Option<cosmos_engine::llm::AskContextReport>,
+    ) {
+        self.chat_session
+            .record(question.clone(), answer.clone(), context.as_ref());
+        self.show_inquiry(answer);
+        if let Some(state) = self.ask_cosmos_state.as_mut() {
+            state.question = Some(question);
+            state.transcript = self.chat_session.earlier_transcript();
+            state.context = context;
+        }
+    }
+
+    /// Forget the conversation so the next question starts fresh
+    pub fn new_chat_session(&mut self) {
+        self.chat_session.clear();
+        self.ask_cosmos_state = None;
+    }
+
+    /// Exit ask cosmos mode and return to suggestions
+    pub fn exit_ask_cosmos(&mut self) {
+        self.ask_cosmos_state = None;
+        self.workflow_step = WorkflowStep::Suggestions;
+    }
+
+    /// Check if in ask cosmos mode (showing response)
+    pub fn is_ask_cosmos_mode(&self) -> bool {
+        self.ask_cosmos_state.is_some()
+    }
+
+    /// Scroll ask cosmos response down (back out of the transcript first)
+    pub fn ask_cosmos_scroll_down(&mut self) {
+        if let Some(state) = &mut self.ask_cosmos_state {
+            if state.transcript_scroll > 0 {
+                state.transcript_scroll -= 1;
+            } else {
+                state.scroll = state.scroll.saturating_add(1);
+            }
+        }
+    }
+
+    /// Scroll ask cosmos response up, continuing into the earlier conversation
+    pub fn ask_cosmos_scroll_up(&mut self) {
+        if let Some(state) = &mut self.ask_cosmos_state {
+            if state.scroll > 0 {
+                state.scroll -= 1;
+            } else if !state.transcript.is_empty() {
+                state.transcript_scroll = state.transcript_scroll.saturating_add(1);
+            }
+        }
+    }
+
+    /// Show a blocking message overlay for important failures.
+    /// Open the diff viewer over the files in the current review.
+    pub fn open_diff_view(&mut self) {
+        let files: Vec<diff::FileDiff> = self
+            .review_state
+            .files
+            .iter()
+            .map(|file| {
+                diff::FileDiff::new(file.path.clone(), &file.original_content, &file.new_content)
+            })
+            .filter(|file| !file.hunks.is_empty())
+            .collect();
+        if files.is_empty() {
+            self.open_alert(
+                "No changes to show",
+                "The reviewed files match their originals.",
+            );
+            return;
+        }
+        let editable = !self.is_pr_review();
+        self.overlay = Overlay::Diff {
+            view: Box::new(diff::DiffView::new(files, editable)),
+        };
+    }
+
+    /// Apply the diff viewer's decisions: rejected hunks are reverted in the working tree.
+    /// Returns how many hunks were reverted.
+    pub fn apply_diff_decisions(&mut self) -> Result<usize, String> {
+        let Overlay::Diff { view } = &self.overlay else {
+            return Ok(0);
+        };
+        if !view.editable {
+            self.close_overlay();
+            return Ok(0);
+        }
+        let reverted = view.rejected_count();
+        let resolved: Vec<(PathBuf, String)> = view
+            .files
+            .iter()
+            .filter(|file| file.has_rejections())
+            .map(|file| (file.path.clone(), file.resolved_content()))
+            .collect();
+        for (path, content) in resolved {
+            std::fs::write(self.repo_path.join(&path), &content)
+                .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+            if let Some(file) = self.review_state.files.iter_mut().find(|f| f.path == path) {
+                file.new_content = content;
+            }
+        }
+        self.close_overlay();
+        Ok(reverted)
+    }
+
+    pub fn open_alert<T: Into<String>, U: Into<String>>(&mut self, title: T, message: U) {
+        self.overlay = Overlay::Alert {
+            title: title.into(),
+            message: message.into(),
+            scroll: 0,
+        };
+        self.needs_redraw = true;
+    }
+
+    // ═══════════════════════════════════════════════════════════════════════════
+    //  ASK CONTEXT PICKER OVERLAY
+    // ═══════════════════════════════════════════════════════════════════════════
+
+    /// Open the Ask Cosmos context picker, seeded with the current selection.
+    ///
+    /// Question mode is suspended while the picker is open so keys reach the overlay.
+    pub fn open_ask_context_picker(&mut self) {
+        self.input_mode = InputMode::Normal;
+        self.overlay = Overlay::AskContextPicker {
+            query: String::new(),
+            selected: 0,
+            picked: self.ask_context_paths.clone(),
+            picked_tokens: self.estimate_ask_context_tokens(&self.ask_context_paths),
+        };
+    }
+
+    /// Project tree entries matching the picker filter
+    pub fn ask_context_candidates(&self) -> Vec<&FlatTreeEntry> {
+        let Overlay::AskContextPicker { query, .. } = &self.overlay else {
+            return Vec::new();
+        };
+        let needle = query.to_lowercase();
+        self.file_tree
+            .iter()
+            .filter(|entry| {
+                needle.is_empty()
+                    || entry
+                        .path
+                        .to_string_lossy()
+                        .to_lowercase()
+                        .contains(&needle)
+            })
+            .collect()
+    }
+
+    /// Navigate in the context picker
+    pub fn ask_context_navigate(&mut self, delta: isize) {
+        let len = self.ask_context_candidates().len();
+        if let Overlay::AskContextPicker { selected, .. } = &mut self.overlay {
+            if len == 0 {
+                *selected = 0;
+                return;
+            }
+            *selected = if delta > 0 {
+                (*selected + delta as usize).min(len - 1)
+            } else {
+                selected.saturating_sub((-delta) as usize)
+            };
+        }
+    }
+
+    /// Edit the picker filter; `None` removes the last character
+    pub fn ask_context_edit_query(&mut self, c: Option<char>) {
+        if let Overlay::AskContextPicker {
+            query, selected, ..
+        } = &mut self.overlay
+        {
+            match c {
+                Some(c) => query.push(c),
+                None => {
+                    query.pop();
+                }
+            }
+            *selected = 0;
+        }
+    }
+
+    /// Toggle the focused entry in or out of the picked set
+    pub fn ask_context_toggle_selected(&mut self) {
+        let focused = match &self.overlay {
+            Overlay::AskContextPicker { selected, .. } => self
+                .ask_context_candidates()
+                .get(*selected)
+                .map(|entry| entry.path.clone()),
+            _ => None,
+        };
+        let Some(path) = focused else {
+            return;
+        };
+        let mut picked = match &self.overlay {
+            Overlay::AskContextPicker { picked, .. } => picked.clone(),
+            _ => return,
+        };
+        if let Some(pos) = picked.iter().position(|p| *p == path) {
+            picked.remove(pos);
+        } else {
+            picked.push(path);
+        }
+        let tokens = self.estimate_ask_context_tokens(&picked);
+        if let Overlay::AskContextPicker {
+            picked: current,
+            picked_tokens,
+            ..
+        } = &mut self.overlay
+        {
+            *current = picked;
+            *picked_tokens = tokens;
+        }
+    }
+
+    /// Estimated prompt tokens `paths` would add to an Ask question
+    fn estimate_ask_context_tokens(&self, paths: &[PathBuf]) -> u32 {
+        cosmos_engine::llm::estimate_ask_context_tokens(&self.repo_path, &self.index, paths)
+    }
+
+    /// Queue the focused picker entry for `$EDITOR` (files only)
+    pub fn ask_context_open_focused_in_editor(&mut self) {
+        let focused = match &self.overlay {
+            Overlay::AskContextPicker { selected, .. } => self
+                .ask_context_candidates()
+                .get(*selected)
+                .filter(|entry| !entry.is_dir)
+                .map(|entry| entry.path.clone()),
+            _ => None,
+        };
+        if let Some(path) = focused {
+            self.editor_request = Some(EditorRequest { path, line: None });
+        }
+    }
+
+    /// The folder focused in the context picker, if a folder is focused
+    pub fn ask_context_focused_directory(&self) -> Option<PathBuf> {
+        match &self.overlay {
+            Overlay::AskContextPicker { selected, .. } => self
+                .ask_context_candidates()
+                .get(*selected)
+                .filter(|entry| entry.is_dir)
+                .map(|entry| entry.path.clone()),
+            _ => None,
+        }
+    }
+
+    /// Close the picker, keeping the picked set when `confirm` is true
+    pub fn close_ask_context_picker(&mut self, confirm: bool) {
+        if let Overlay::AskContextPicker { picked, .. } = &self.overlay {
+            if confirm {
+                self.ask_context_paths = picked.clone();
+            }
+            self.overlay = Overlay::None;
+            self.input_mode = InputMode::Question;
+        }
+    }
+
+    // ═══════════════════════════════════════════════════════════════════════════
+    //  SETTINGS OVERLAY
+    // ═══════════════════════════════════════════════════════════════════════════
+
+    /// Load the theme from the `theme` config section, returning any config problems.
+    pub fn load_theme(
+        &mut self,
+        config: Option<&cosmos_adapters::config::ThemeConfig>,
+    ) -> Vec<String> {
+        let (palette, problems) = theme::Palette::from_config(config);
+        self.palette = palette;
+        self.theme_config = config.cloned();
+        problems
+    }
+
+    pub fn open_settings_overlay(&mut self) {
+        let original = self.palette.name;
+        let selected = theme::ThemeName::ALL
+            .iter()
+            .position(|name| *name == original)
+            .unwrap_or(0);
+        self.overlay = Overlay::Settings { selected, original };
+    }
+
+    /// Move the theme selection and preview it
+    pub fn settings_navigate(&mut self, delta: isize) {
+        let Overlay::Settings { selected, .. } = &mut self.overlay else {
+            return;
+        };
+        let len = theme::ThemeName::ALL.len();
+        *selected = if delta > 0 {
+            (*selected + delta as usize) % len
+        } else {
+            (*selected + len - ((-delta) as usize % len)) % len
+        };
+        let name = theme::ThemeName::ALL[*selected];
+        self.preview_theme(name);
+    }
+
+    fn preview_theme(&mut self, name: theme::ThemeName) {
+        let mut config = self.theme_config.clone().unwrap_or_default();
+        config.name = Some(name.name().to_string());
+        self.palette = theme::Palette::from_config(Some(&config)).0;
+    }
+
+    /// Close settings; `save` keeps the previewed theme and persists it to the user config.
+    pub fn close_settings_overlay(&mut self, save: bool) -> Result<(), String> {
+        let Overlay::Settings { selected, original } = self.overlay else {
+            return Ok(());
+        };
+        self.overlay = Overlay::None;
+        if !save {
+            self.preview_theme(original);
+            return Ok(());
+        }
+        let name = theme::ThemeName::ALL[selected];
+        self.preview_theme(name);
+        let config = self.theme_config.get_or_insert_with(Default::default);
+        config.name = Some(name.name().to_string());
+        let mut user_config = cosmos_adapters::config::Config::load();
+        user_config.set_theme_name(name.name());
+        user_config.save()
+    }
+
+    // ═══════════════════════════════════════════════════════════════════════════
+    //  COSMOS BRANCHES OVERLAY
+    // ═══════════════════════════════════════════════════════════════════════════
+
+    /// Track a branch Cosmos applied fixes on
+    pub fn record_cosmos_branch(&self, branch: &str, base: Option<&str>) {
+        let _ = cosmos_adapters::cache::Cache::new(&self.repo_path).update_cosmos_branches(
+            |branches| {
+                branches.record_branch(branch, base);
+            },
+        );
+    }
+
+    /// Remember the pull request shipped from a tracked branch, and the commit it shipped
+    pub fn record_cosmos_pull_request(&self, branch: &str, url: &str) {
+        let head = cosmos_adapters::git_ops::branch_tip(&self.repo_path, branch);
+        let _ = cosmos_adapters::cache::Cache::new(&self.repo_path).update_cosmos_branches(
+            |branches| branches.record_pull_request(branch, url, head.as_deref()),
+        );
+    }
+
+    /// Open the branch list; branches deleted outside Cosmos are forgotten.
+    ///
+    /// Returns the `(branch, pull request URL)` pairs whose state should be polled.
+    pub fn open_branches_overlay(&mut self) -> Vec<(String, String)> {
+        let cache = cosmos_adapters::cache::Cache::new(&self.repo_path);
+        let mut branches = cache.load_cosmos_branches();
+        let gone: Vec<String> = branches
+            .branches
+            .iter()
+            .filter(|record| {
+                !cosmos_adapters::git_ops::local_branch_exists(&self.repo_path, &record.branch)
+            })
+            .map(|record| record.branch.clone())
+            .collect();
+        if !gone.is_empty() {
+            if let Ok(updated) = cache.update_cosmos_branches(|branches| {
+                for branch in &gone {
+                    branches.remove(branch);
+                }
+            }) {
+                branches = updated;
             }
-        )
+        }
+        let branches = branches.branches;
+        let polls = branch_polls(&branches);
+        self.overlay = Overlay::Branches {
+            branches,
+            selected: 0,
+            status: (!polls.is_empty()).then(|| "Checking pull requests...".to_string()),
+        };
+        polls
     }
 
-    pub fn apply_plan_suggestion_id(&self) -> Option<uuid::Uuid> {
+    /// Pull requests in the open branch list, to poll again
+    pub fn branch_status_polls(&mut self) -> Vec<(String, String)> {
+        let Overlay::Branches {
+            branches, status, ..
+        } = &mut self.overlay
+        else {
+            return Vec::new();
+        };
+        let polls = branch_polls(branches);
+        if !polls.is_empty() {
+            *status = Some("Checking pull requests...".to_string());
+        }
+        polls
+    }
+
+    pub fn branches_navigate(&mut self, delta: isize) {
+        if let Overlay::Branches {
+            branches, selected, ..
+        } = &mut self.overlay
+        {
+            let len = branches.len();
+            if len == 0 {
+                return;
+            }
+            *selected = if delta > 0 {
+                (*selected + delta as usize) % len
+            } else {
+                (*selected + len - ((-delta) as usize % len)) % len
+            };
+        }
+    }
+
+    /// Focused branch in the branch list
+    pub fn selected_cosmos_branch(&self) -> Option<&cosmos_adapters::cache::CosmosBranchRecord> {
         match &self.overlay {
-            Overlay::ApplyPlan { suggestion_id, .. } => Some(*suggestion_id),
+            Overlay::Branches {
+                branches, selected, ..
+            } => branches.get(*selected),
             _ => None,
         }
     }
 
-    /// Show inquiry response in the right panel (Ask Cosmos mode)
-    pub fn show_inquiry(&mut self, response: String) {
-        self.input_mode = InputMode::Normal;
-        self.ask_in_flight = false;
-        self.active_ask_request_id = None;
-        self.ask_cosmos_state = Some(AskCosmosState {
-            response,
-            scroll: 0,
-        });
+    /// Store polled pull request states and show them in the branch list
+    pub fn set_branch_states(
+        &mut self,
+        states: Vec<(String, Result<cosmos_adapters::forge::PrState, String>)>,
+    ) {
+        let updated = cosmos_adapters::cache::Cache::new(&self.repo_path).update_cosmos_branches(
+            |branches| {
+                for (branch, state) in &states {
+                    if let Ok(state) = state {
+                        branches.set_pr_state(branch, *state);
+                    }
+                }
+            },
+        );
+        let failed: Vec<String> = states
+            .iter()
+            .filter_map(|(branch, state)| {
+                state
+                    .as_ref()
+                    .err()
+                    .map(|error| format!("{}: {}", branch, error))
+            })
+            .collect();
+        if let Overlay::Branches {
+            branches, status, ..
+        } = &mut self.overlay
+        {
+            match updated {
+                Ok(updated) => {
+                    // Keep the on-screen order; only the polled fields change.
+                    for record in branches.iter_mut() {
+                        if let Some(fresh) = updated.get(&record.branch) {
+                            *record = fresh.clone();
+                        }
+                    }
+                }
+                Err(_) => {
+                    for (branch, state) in &states {
+                        if let (Some(record), Ok(state)) = (
+                            branches.iter_mut().find(|record| &record.branch == branch),
+                            state,
+                        ) {
+                            record.pr_state = Some(*state);
+                        }
+                    }
+                }
+            }
+            *status = failed.first().map(|first| {
+                format!(
+                    "Couldn't check {} pull request(s) ({})",
+                    failed.len(),
+                    first
+                )
+            });
+        }
     }
 
-    /// Exit ask cosmos mode and return to suggestions
-    pub fn exit_ask_cosmos(&mut self) {
-        self.ask_cosmos_state = None;
-        self.workflow_step = WorkflowStep::Suggestions;
+    /// Start deleting the focused branch; only branches whose pull request merged qualify.
+    pub fn begin_branch_delete(&mut self) -> Option<cosmos_adapters::cache::CosmosBranchRecord> {
+        let Overlay::Branches {
+            branches,
+            selected,
+            status,
+        } = &mut self.overlay
+        else {
+            return None;
+        };
+        let record = branches.get(*selected)?;
+        if record.pr_state != Some(cosmos_adapters::forge::PrState::Merged) {
+            *status = Some(format!(
+                "{} hasn't merged; only merged branches are deleted",
+                record.branch
+            ));
+            return None;
+        }
+        if record.branch == self.context.branch {
+            *status = Some(format!(
+                "{} is checked out; switch branches first",
+                record.branch
+            ));
+            return None;
+        }
+        *status = Some(format!("Deleting {}...", record.branch));
+        Some(record.clone())
     }
 
-    /// Check if in ask cosmos mode (showing response)
-    pub fn is_ask_cosmos_mode(&self) -> bool {
-        self.ask_cosmos_state.is_some()
+    /// Finish a branch delete: forget the branch, or show why it failed
+    pub fn finish_branch_delete(&mut self, branch: &str, result: Result<(), String>) {
+        if result.is_ok() {
+            let _ = cosmos_adapters::cache::Cache::new(&self.repo_path)
+                .update_cosmos_branches(|branches| branches.remove(branch));
+        }
+        if let Overlay::Branches {
+            branches,
+            selected,
+            status,
+        } = &mut self.overlay
+        {
+            match result {
+                Ok(()) => {
+                    branches.retain(|record| record.branch != branch);
+                    *selected = (*selected).min(branches.len().saturating_sub(1));
+                    *status = Some(format!("Deleted {} locally and on the remote", branch));
+                }
+                Err(error) => *status = Some(error),
+            }
+        }
     }
 
-    /// Scroll ask cosmos response down
-    pub fn ask_cosmos_scroll_down(&mut self) {
-        if let Some(state) = &mut self.ask_cosmos_state {
-            state.scroll = state.scroll.saturating_add(1);
+    /// Branch to switch to from the branch list: the focused branch, or its base.
+    pub fn branch_switch_target(&self, base: bool) -> Option<String> {
+        let record = self.selected_cosmos_branch()?;
+        if !base {
+            return Some(record.branch.clone());
         }
+        record
+            .base
+            .clone()
+            .or_else(|| cosmos_adapters::git_ops::get_main_branch_name(&self.repo_path).ok())
     }
 
-    /// Scroll ask cosmos response up
-    pub fn ask_cosmos_scroll_up(&mut self) {
-        if let Some(state) = &mut self.ask_cosmos_state {
-            state.scroll = state.scroll.saturating_sub(1);
+    /// Show a problem or progress line under the branch list
+    pub fn set_branches_status(&mut self, message: String) {
+        if let Overlay::Branches { status, .. } = &mut self.overlay {
+            *status = Some(message);
         }
     }
 
-    /// Show a blocking message overlay for important failures.
-    pub fn open_alert<T: Into<String>, U: Into<String>>(&mut self, title: T, message: U) {
-        self.overlay = Overlay::Alert {
-            title: title.into(),
-            message: message.into(),
-            scroll: 0,
+    /// Check out `target`, setting aside this branch's focused suggestion, pending changes, and
+    /// uncommitted work (stashed), and bringing back whatever was set aside the last time
+    /// Cosmos left `target`. Returns a summary of what moved.
+    pub fn switch_branch_context(&mut self, target: &str) -> Result<String, String> {
+        let from = self.context.branch.clone();
+        if target == from {
+            return Err(format!("{} is already checked out", target));
+        }
+        if self.loading.is_loading()
+            || self.pending_fix_apply.is_some()
+            || self.workflow_step != WorkflowStep::Suggestions
+        {
+            return Err("Finish the fix in progress before switching branches".to_string());
+        }
+        let switch =
+            cosmos_adapters::git_ops::switch_branch_stashing(&self.repo_path, &from, target)
+                .map_err(|e| format!("{:#}", e))?;
+
+        let selected_suggestion = self
+            .active_suggestions_for_display()
+            .get(self.suggestion_selected)
+            .map(|s| s.id);
+        self.branch_contexts.insert(
+            from.clone(),
+            BranchContext {
+                selected_suggestion,
+                pending_changes: std::mem::take(&mut self.pending_changes),
+                cosmos_branch: self.cosmos_branch.take(),
+                cosmos_base_branch: self.cosmos_base_branch.take(),
+            },
+        );
+        self.armed_suggestion_id = None;
+        self.armed_file_hashes.clear();
+        self.expanded_suggestion_id = None;
+
+        let restored = self.branch_contexts.remove(target).unwrap_or_default();
+        let restored_changes = restored.pending_changes.len();
+        self.pending_changes = restored.pending_changes;
+        self.cosmos_branch = restored.cosmos_branch;
+        self.cosmos_base_branch = restored.cosmos_base_branch;
+        let _ = self.context.refresh();
+        self.observe_git_state();
+        if let Some(position) = restored.selected_suggestion.and_then(|id| {
+            self.active_suggestions_for_display()
+                .iter()
+                .position(|s| s.id == id)
+        }) {
+            self.suggestion_selected = position;
+            self.suggestion_scroll = self.suggestion_scroll.min(position);
+        }
+
+        let mut summary = format!("Switched to {}", target);
+        if switch.stashed {
+            summary.push_str(&format!("; stashed the work on {}", from));
+        }
+        if switch.restored {
+            summary.push_str("; restored its stashed work");
+        }
+        if restored_changes > 0 {
+            summary.push_str(&format!("; {} pending change(s) back", restored_changes));
+        }
+        Ok(summary)
+    }
+
+    // ═══════════════════════════════════════════════════════════════════════════
+    //  REBASE ASSISTANT
+    // ═══════════════════════════════════════════════════════════════════════════
+
+    /// Base to rebase the checked-out Cosmos branch onto, or why it can't be rebased.
+    ///
+    /// Prefers the branch it was cut from, then falls back to the main branch.
+    pub fn rebase_base(&self) -> Result<String, String> {
+        let branch = self.context.branch.as_str();
+        if !cosmos_adapters::git_ops::is_cosmos_branch(&self.git_policy, branch) {
+            return Err(format!(
+                "{} isn't a Cosmos branch; check out the fix branch to rebase it",
+                branch
+            ));
+        }
+        if self.cosmos_branch.as_deref() == Some(branch) {
+            if let Some(base) = self.cosmos_base_branch.clone() {
+                return Ok(base);
+            }
+        }
+        let recorded = cosmos_adapters::cache::Cache::new(&self.repo_path)
+            .load_cosmos_branches()
+            .get(branch)
+            .and_then(|record| record.base.clone());
+        match recorded {
+            Some(base) => Ok(base),
+            None => cosmos_adapters::git_ops::get_main_branch_name(&self.repo_path)
+                .map_err(|e| e.to_string()),
+        }
+    }
+
+    /// Start rebasing the checked-out branch; returns the base, or None (with an alert) if it
+    /// can't be rebased right now.
+    pub fn begin_rebase(&mut self) -> Option<String> {
+        if self.loading.is_loading() {
+            return None;
+        }
+        match self.rebase_base() {
+            Ok(base) => {
+                self.loading = LoadingState::Rebasing;
+                self.show_toast(format!("Rebasing onto {}...", base));
+                Some(base)
+            }
+            Err(message) => {
+                self.open_alert("Can't rebase", message);
+                None
+            }
+        }
+    }
+
+    /// Report how a rebase went
+    pub fn finish_rebase(&mut self, result: Result<cosmos_engine::llm::RebaseOutcome, String>) {
+        use cosmos_engine::llm::ImplementationQuickCheckStatus;
+
+        self.loading = LoadingState::None;
+        let outcome = match result {
+            Ok(outcome) => outcome,
+            Err(error) => {
+                self.open_alert("Rebase failed", error);
+                return;
+            }
         };
-        self.needs_redraw = true;
+        if outcome.drift.behind == 0 {
+            self.show_toast(format!(
+                "{} is up to date with {}",
+                outcome.branch, outcome.drift.base_ref
+            ));
+            return;
+        }
+
+        let mut message = format!(
+            "Replayed {} commit(s) from {} onto {} ({} new there).",
+            outcome.drift.ahead, outcome.branch, outcome.drift.base_ref, outcome.drift.behind
+        );
+        if !outcome.resolved_files.is_empty() {
+            message.push_str(&format!(
+                "\n\nResolved conflicts in:\n{}",
+                outcome
+                    .resolved_files
+                    .iter()
+                    .map(|path| format!("• {}", path.display()))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            ));
+        }
+        let checks = outcome
+            .quick_check_command
+            .as_deref()
+            .unwrap_or("quick checks");
+        match outcome.quick_check_status {
+            ImplementationQuickCheckStatus::Failed => {
+                message.push_str(&format!(
+                    "\n\n{} failed on the rebased code, so {} was left as it was.",
+                    checks, outcome.branch
+                ));
+                if let Some(failure) = &outcome.quick_check_failure {
+                    message.push_str(&format!("\n{}", failure));
+                }
+                self.open_alert("Rebase not applied", message);
+                return;
+            }
+            ImplementationQuickCheckStatus::Passed => {
+                message.push_str(&format!("\n\n{} passed.", checks));
+            }
+            ImplementationQuickCheckStatus::Unavailable => {
+                message.push_str("\n\nNo quick checks ran; check the result before pushing.");
+            }
+        }
+        message.push_str(
+            "\n\nIf the branch was already pushed, the next push replaces it \
+             (git push --force-with-lease).",
+        );
+        self.open_alert("Rebased", message);
     }
 
     // ═══════════════════════════════════════════════════════════════════════════
@@ -1537,9 +3193,30 @@ impl App {
         }
     }
 
+    /// Open or close the quick-check output log
+    pub fn toggle_command_log(&mut self) {
+        self.overlay = match self.overlay {
+            Overlay::CommandLog { .. } => Overlay::None,
+            _ => Overlay::CommandLog { scroll_back: 0 },
+        };
+    }
+
+    /// Append a streamed quick-check line, keeping a scrolled-back view where it is
+    pub fn push_command_log_line(&mut self, line: String) {
+        self.command_log.push(line);
+        if let Overlay::CommandLog { scroll_back } = &mut self.overlay {
+            if *scroll_back > 0 {
+                *scroll_back = (*scroll_back + 1).min(self.command_log.len().saturating_sub(1));
+            }
+        }
+    }
+
     /// Scroll overlay down
     pub fn overlay_scroll_down(&mut self) {
         match &mut self.overlay {
+            Overlay::CommandLog { scroll_back } => {
+                *scroll_back = scroll_back.saturating_sub(1);
+            }
             Overlay::Alert { scroll, .. }
             | Overlay::Help { scroll }
             | Overlay::FileDetail { scroll, .. } => {
@@ -1552,6 +3229,9 @@ impl App {
     /// Scroll overlay up
     pub fn overlay_scroll_up(&mut self) {
         match &mut self.overlay {
+            Overlay::CommandLog { scroll_back } => {
+                *scroll_back = (*scroll_back + 1).min(self.command_log.len().saturating_sub(1));
+            }
             Overlay::Alert { scroll, .. }
             | Overlay::Help { scroll }
             | Overlay::FileDetail { scroll, .. } => {
@@ -1597,139 +3277,24 @@ impl App {
         }
     }
 
-    /// Generate human-friendly PR title and body from pending changes
+    /// Generate the PR title and body from pending changes
     ///
-    /// Returns (title, body) tuple with content written for non-technical readers
-    /// while still including technical details for developers who want them.
+    /// The body covers problem, approach, files touched, risk notes, and the test
+    /// evidence each change's harness run recorded.
     pub fn generate_pr_content(&self) -> (String, String) {
-        if self.pending_changes.is_empty() {
-            return (
-                "Improvements".to_string(),
-                "## Summary\n\nNo changes to describe.\n\n---\n*Applied with Cosmos*".to_string(),
-            );
-        }
-
-        if self.pending_changes.len() == 1 {
-            // Single change: use the friendly context directly
-            let change = &self.pending_changes[0];
-
-            let title = change.friendly_title.clone().unwrap_or_else(|| {
-                // Fallback: extract a friendly title from description
-                let desc = &change.description;
-                if desc.len() > 50 {
-                    format!("{}...", &desc[..47])
-                } else {
-                    desc.clone()
-                }
-            });
-
-            let mut body = String::from("## Summary\n\n");
-
-            // Add the problem summary if available
-            if let Some(problem) = &change.problem_summary {
-                body.push_str(problem);
-                body.push_str("\n\n");
-            }
-
-            // Add the outcome if available
-            if let Some(outcome) = &change.outcome {
-                body.push_str("**The fix:** ");
-                body.push_str(outcome);
-                body.push_str("\n\n");
-            }
-
-            // Add technical details section
-            body.push_str("## Details\n\n");
-            if change.is_multi_file() {
-                body.push_str(&format!(
-                    "- **Files:** {} files modified\n",
-                    change.files.len()
-                ));
-                for file_change in &change.files {
-                    body.push_str(&format!("  - `{}`\n", file_change.path.display()));
-                }
-            } else {
-                body.push_str(&format!("- **File:** `{}`\n", change.file_path().display()));
-                let diff = change.diff();
-                if !diff.is_empty() && !diff.starts_with("Modified areas:") {
-                    body.push_str(&format!("- **Changes:** {}\n", diff));
-                } else if diff.starts_with("Modified areas:") {
-                    body.push_str(&format!("- {}\n", diff));
-                }
-            }
-
-            body.push_str("\n---\n*Applied with Cosmos*");
-
-            (title, body)
-        } else {
-            // Multiple changes: summarize themes
-            let title = self.generate_pr_title_for_multiple_changes();
-
-            let mut body = String::from("## Summary\n\n");
-            body.push_str(&format!(
-                "This PR addresses {} issues:\n\n",
-                self.pending_changes.len()
-            ));
-
-            for change in &self.pending_changes {
-                let change_title = change.friendly_title.as_deref().unwrap_or("Improvement");
-
-                body.push_str(&format!("- **{}**: ", change_title));
-
-                if let Some(problem) = &change.problem_summary {
-                    body.push_str(problem);
-                    if let Some(outcome) = &change.outcome {
-                        body.push_str(&format!(" {}", outcome));
-                    }
-                } else {
-                    body.push_str(&change.description);
-                }
-                body.push_str("\n\n");
-            }
-
-            // Add files changed section
-            body.push_str("## Files Changed\n\n");
-            for change in &self.pending_changes {
-                for file_change in &change.files {
-                    body.push_str(&format!("- `{}`\n", file_change.path.display()));
-                }
-            }
-
-            body.push_str("\n---\n*Applied with Cosmos*");
-
-            (title, body)
-        }
-    }
-
-    /// Generate a title for PRs with multiple changes by finding common themes
-    fn generate_pr_title_for_multiple_changes(&self) -> String {
-        // Collect all friendly titles
-        let titles: Vec<&str> = self
+        let changes: Vec<cosmos_engine::llm::PrChangeSummary> = self
             .pending_changes
             .iter()
-            .filter_map(|c| c.friendly_title.as_deref())
+            .map(|change| cosmos_engine::llm::PrChangeSummary {
+                title: change.friendly_title.clone(),
+                problem: change.problem_summary.clone(),
+                outcome: change.outcome.clone(),
+                description: change.description.clone(),
+                files: change.files.iter().map(|f| f.path.clone()).collect(),
+                evidence: change.harness_evidence.clone(),
+            })
             .collect();
-
-        if titles.is_empty() {
-            // Fallback: generic title with count
-            return format!("{} improvements", self.pending_changes.len());
-        }
-
-        if titles.len() == 1 {
-            return titles[0].to_string();
-        }
-
-        if titles.len() == 2 {
-            return format!("{} and {}", titles[0], titles[1]);
-        }
-
-        // For 3+ changes, list the first two and add "and more"
-        format!(
-            "{}, {}, and {} more",
-            titles[0],
-            titles[1],
-            titles.len() - 2
-        )
+        cosmos_engine::llm::synthesize_pr_description(&changes)
     }
 
     // ═══════════════════════════════════════════════════════════════════════════
@@ -1869,11 +3434,31 @@ impl App {
 
     /// Scroll ship panel down
     pub fn ship_scroll_down(&mut self) {
+        if self.ship_state.step == ShipStep::Stack {
+            if self.ship_state.stack_selected + 1 < self.stack.len() {
+                self.ship_state.stack_selected += 1;
+            }
+            return;
+        }
+        if self.ship_state.step == ShipStep::Commits {
+            if self.ship_state.commit_selected + 1 < self.ship_state.commits.len() {
+                self.ship_state.commit_selected += 1;
+            }
+            return;
+        }
         self.ship_state.scroll += 1;
     }
 
     /// Scroll ship panel up
     pub fn ship_scroll_up(&mut self) {
+        if self.ship_state.step == ShipStep::Stack {
+            self.ship_state.stack_selected = self.ship_state.stack_selected.saturating_sub(1);
+            return;
+        }
+        if self.ship_state.step == ShipStep::Commits {
+            self.ship_state.commit_selected = self.ship_state.commit_selected.saturating_sub(1);
+            return;
+        }
         self.ship_state.scroll = self.ship_state.scroll.saturating_sub(1);
     }
 
@@ -1894,24 +3479,83 @@ impl App {
             confirm_extra_review_budget: false,
             verification_failed: false,
             verification_error: None,
+            pr_number: None,
+            pull_request: None,
+            posting: false,
+            editing: None,
+            edited: HashMap::new(),
         };
         self.workflow_step = WorkflowStep::Review;
         self.loading = LoadingState::ReviewingChanges;
     }
 
+    /// Open the Review panel for an existing pull request while it is fetched and reviewed
+    pub fn start_pr_review(&mut self, number: u64) {
+        self.start_review(Vec::new());
+        self.review_state.pr_number = Some(number);
+    }
+
+    /// Show the findings for a fetched pull request
+    pub fn set_pr_review(
+        &mut self,
+        pull_request: cosmos_adapters::github::OpenPullRequest,
+        findings: Vec<cosmos_engine::llm::ReviewFinding>,
+        summary: String,
+    ) {
+        self.review_state.files = pull_request
+            .files
+            .iter()
+            .map(|file| ReviewFileContent {
+                path: PathBuf::from(&file.path),
+                original_content: file.original.clone(),
+                new_content: file.content.clone(),
+            })
+            .collect();
+        self.review_state.pr_number = Some(pull_request.number);
+        self.review_state.pull_request = Some(pull_request);
+        self.set_review_findings(findings, summary);
+    }
+
+    /// Whether the Review panel is reviewing an existing pull request
+    pub fn is_pr_review(&self) -> bool {
+        self.review_state.pr_number.is_some()
+    }
+
+    /// Review comments to post: the selected findings, or every finding if none are selected
+    pub fn pr_review_comments(&self) -> Vec<cosmos_adapters::github::PrReviewComment> {
+        let state = &self.review_state;
+        state
+            .findings
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| state.selected.is_empty() || state.selected.contains(i))
+            .map(|(_, finding)| cosmos_adapters::github::PrReviewComment {
+                path: finding.file.clone(),
+                line: finding.line,
+                body: format!(
+                    "**{}** ({})\n\n{}",
+                    finding.title, finding.severity, finding.description
+                ),
+            })
+            .collect()
+    }
+
     /// Set review findings from the adversarial reviewer
     pub fn set_review_findings(
         &mut self,
         findings: Vec<cosmos_engine::llm::ReviewFinding>,
         summary: String,
     ) {
-        self.review_state.findings = findings.clone();
+        self.review_state.findings = findings;
         self.review_state.summary = summary;
         self.review_state.reviewing = false;
         self.review_state.confirm_ship = false;
         self.review_state.confirm_extra_review_budget = false;
+        self.review_state.editing = None;
+        self.review_state.edited.clear();
+        self.apply_saved_finding_edits();
         // Pre-select recommended findings
-        for (i, finding) in findings.iter().enumerate() {
+        for (i, finding) in self.review_state.findings.iter().enumerate() {
             if finding.recommended {
                 self.review_state.selected.insert(i);
             }
@@ -1919,6 +3563,131 @@ impl App {
         self.loading = LoadingState::None;
     }
 
+    /// Re-apply corrections the user made when the reviewer raised the same finding before
+    fn apply_saved_finding_edits(&mut self) {
+        let edits = cosmos_adapters::cache::Cache::new(&self.repo_path)
+            .load_recent_review_finding_edits(500)
+            .unwrap_or_default();
+        for (i, finding) in self.review_state.findings.iter_mut().enumerate() {
+            let Some(edit) = edits
+                .iter()
+                .rev()
+                .find(|edit| edit.file == finding.file && edit.original_title == finding.title)
+            else {
+                continue;
+            };
+            self.review_state
+                .edited
+                .insert(i, std::mem::replace(&mut finding.title, edit.title.clone()));
+            finding.description = edit.description.clone();
+            finding.severity = edit.severity.clone();
+        }
+    }
+
+    /// Start editing the title or description of the finding at the cursor
+    pub fn start_finding_edit(&mut self, field: FindingField) {
+        let cursor = self.review_state.cursor;
+        let Some(finding) = self.review_state.findings.get(cursor) else {
+            return;
+        };
+        self.review_state
+            .edited
+            .entry(cursor)
+            .or_insert_with(|| finding.title.clone());
+        self.review_state.editing = Some(field);
+        self.review_state.confirm_ship = false;
+        self.input_mode = InputMode::FindingField;
+    }
+
+    /// Stop editing the finding, keeping and saving what was typed
+    pub fn finish_finding_edit(&mut self) {
+        if self.review_state.editing.take().is_some() {
+            let cursor = self.review_state.cursor;
+            if let Some(finding) = self.review_state.findings.get_mut(cursor) {
+                if finding.title.trim().is_empty() {
+                    finding.title = self.review_state.edited[&cursor].clone();
+                }
+            }
+            self.save_finding_edit(cursor);
+        }
+        self.input_mode = InputMode::Normal;
+    }
+
+    fn finding_field_value_mut(&mut self) -> Option<&mut String> {
+        let field = self.review_state.editing?;
+        let finding = self
+            .review_state
+            .findings
+            .get_mut(self.review_state.cursor)?;
+        Some(match field {
+            FindingField::Title => &mut finding.title,
+            FindingField::Description => &mut finding.description,
+        })
+    }
+
+    /// Add character to the finding field being edited
+    pub fn finding_field_push(&mut self, c: char) {
+        if let Some(value) = self.finding_field_value_mut() {
+            value.push(c);
+        }
+    }
+
+    /// Remove last character from the finding field being edited
+    pub fn finding_field_pop(&mut self) {
+        if let Some(value) = self.finding_field_value_mut() {
+            value.pop();
+        }
+    }
+
+    /// Lower the severity of the finding at the cursor by one step
+    /// (critical → warning → suggestion → nitpick)
+    pub fn review_downgrade_finding(&mut self) {
+        const LADDER: [&str; 4] = ["critical", "warning", "suggestion", "nitpick"];
+        let cursor = self.review_state.cursor;
+        let Some(finding) = self.review_state.findings.get_mut(cursor) else {
+            return;
+        };
+        let Some(step) = LADDER.iter().position(|s| *s == finding.severity) else {
+            return;
+        };
+        let Some(lower) = LADDER.get(step + 1) else {
+            return;
+        };
+        self.review_state
+            .edited
+            .entry(cursor)
+            .or_insert_with(|| finding.title.clone());
+        finding.severity = lower.to_string();
+        self.review_state.confirm_ship = false;
+        self.save_finding_edit(cursor);
+    }
+
+    /// Persist the correction to finding `idx` so later review rounds keep it
+    fn save_finding_edit(&mut self, idx: usize) {
+        let (Some(finding), Some(original_title)) = (
+            self.review_state.findings.get(idx),
+            self.review_state.edited.get(&idx),
+        ) else {
+            return;
+        };
+        let record = cosmos_adapters::cache::ReviewFindingEditRecord {
+            timestamp: chrono::Utc::now(),
+            file: finding.file.clone(),
+            original_title: original_title.clone(),
+            title: finding.title.clone(),
+            description: finding.description.clone(),
+            severity: finding.severity.clone(),
+        };
+        if let Err(e) =
+            cosmos_adapters::cache::Cache::new(&self.repo_path).append_review_finding_edit(&record)
+        {
+            self.show_toast(format!(
+                "Couldn't save finding edit: {}",
+                truncate_to_width(&e.to_string(), 80)
+            ));
+        }
+    }
+
     /// Toggle selection of finding at cursor in review
     pub fn review_toggle_finding(&mut self) {
         let cursor = self.review_state.cursor;
@@ -2011,6 +3780,7 @@ impl App {
         }
         self.review_state.findings.clear();
         self.review_state.selected.clear();
+        self.review_state.edited.clear();
         self.review_state.summary.clear();
         self.review_state.reviewing = false;
         self.review_state.fixing = false;
@@ -2031,12 +3801,38 @@ impl App {
 
         // Generate commit message using the shared method
         let commit_message = self.generate_commit_message();
+        let (pr_title, pr_body) = self.generate_pr_content();
 
         // Use existing cosmos branch or create name for new one
         let branch_name = self.cosmos_branch.clone().unwrap_or_else(|| {
-            format!("cosmos-fix-{}", chrono::Utc::now().format("%Y%m%d-%H%M%S"))
+            let stamp = chrono::Utc::now().format("%Y%m%d-%H%M%S").to_string();
+            if self.git_policy.branch_pattern.is_some() {
+                let summary = self
+                    .pending_changes
+                    .first()
+                    .map(|c| c.description.as_str())
+                    .unwrap_or("apply changes");
+                cosmos_adapters::git_ops::policy_branch_name(
+                    &self.git_policy,
+                    "chore",
+                    &stamp,
+                    summary,
+                )
+            } else {
+                format!("cosmos-fix-{}", stamp)
+            }
         });
 
+        // Fixes applied on a branch that already has a pull request go onto that request.
+        let follow_up = self
+            .follow_up_pr
+            .clone()
+            .filter(|_| self.follow_up_branch() == Some(branch_name.as_str()));
+        let (pr_title, pr_body) = match &follow_up {
+            Some(pr) => (pr.title.clone(), self.follow_up_comment()),
+            None => (pr_title, pr_body),
+        };
+
         self.ship_state = ShipState {
             branch_name,
             commit_message,
@@ -2044,30 +3840,426 @@ impl App {
             step: ShipStep::Confirm,
             scroll: 0,
             pr_url: None,
+            hook_run: None,
+            pr_title,
+            pr_body,
+            follow_up,
+            ..ShipState::default()
         };
         self.workflow_step = WorkflowStep::Ship;
     }
 
+    /// Branch whose open pull request new fixes follow up on; stacked mode always branches
+    pub fn follow_up_branch(&self) -> Option<&str> {
+        self.follow_up_pr
+            .as_ref()
+            .filter(|_| !self.stacked_changes)
+            .map(|pr| pr.branch.as_str())
+    }
+
+    /// Comment summarizing the pending changes for a pull request they are pushed onto
+    pub fn follow_up_comment(&self) -> String {
+        let mut body = format!("Pushed {} more change(s):\n", self.pending_changes.len());
+        for change in &self.pending_changes {
+            let title = change
+                .friendly_title
+                .as_deref()
+                .filter(|title| !title.trim().is_empty())
+                .unwrap_or(&change.description);
+            body.push_str(&format!("\n- **{}**", title.trim()));
+            if let Some(outcome) = change
+                .outcome
+                .as_deref()
+                .filter(|outcome| !outcome.trim().is_empty())
+            {
+                body.push_str(&format!(": {}", outcome.trim()));
+            }
+            let files: Vec<String> = change
+                .files
+                .iter()
+                .map(|file| format!("`{}`", file.path.display()))
+                .collect();
+            if !files.is_empty() {
+                body.push_str(&format!(" ({})", files.join(", ")));
+            }
+        }
+        body.push_str("\n\n---\n*Pushed with Cosmos*");
+        body
+    }
+
+    /// Problems the git policy finds with the branch and commit message about to ship
+    pub fn ship_policy_problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        if self.ship_state.split_commits {
+            for (message, _) in self.ship_commit_plan() {
+                if let Err(found) =
+                    cosmos_adapters::git_ops::check_commit_message(&self.git_policy, &message)
+                {
+                    let subject = message.lines().next().unwrap_or_default();
+                    problems.extend(
+                        found
+                            .into_iter()
+                            .map(|problem| format!("{}: {}", subject, problem)),
+                    );
+                }
+            }
+            problems.extend(self.dropped_change_conflicts());
+        } else if let Err(found) = cosmos_adapters::git_ops::check_commit_message(
+            &self.git_policy,
+            &self.ship_state.commit_message,
+        ) {
+            problems = found;
+        }
+        if let Err(problem) = cosmos_adapters::git_ops::check_branch_name(
+            &self.git_policy,
+            &self.ship_state.branch_name,
+        ) {
+            problems.push(problem);
+        }
+        problems
+    }
+
+    /// Open the per-change commit list from Ship Confirm
+    pub fn open_commit_split(&mut self) {
+        if self.ship_state.step != ShipStep::Confirm {
+            return;
+        }
+        if self.pending_changes.len() < 2 {
+            self.show_toast("Only one change to commit");
+            return;
+        }
+        if self.ship_state.commits.len() != self.pending_changes.len() {
+            self.ship_state.commits = (0..self.pending_changes.len())
+                .map(|change| SplitCommit {
+                    change,
+                    included: true,
+                })
+                .collect();
+        }
+        self.ship_state.commit_selected = 0;
+        self.ship_state.step = ShipStep::Commits;
+    }
+
+    /// Return to Ship Confirm, committing per change if `split` and any change is kept
+    pub fn finish_commit_split(&mut self, split: bool) {
+        self.ship_state.split_commits =
+            split && self.ship_state.commits.iter().any(|commit| commit.included);
+        self.ship_state.files = if self.ship_state.split_commits {
+            self.ship_commit_plan()
+                .into_iter()
+                .flat_map(|(_, files)| files)
+                .collect()
+        } else {
+            self.pending_changes
+                .iter()
+                .flat_map(|change| change.files.iter().map(|file| file.path.clone()))
+                .collect()
+        };
+        self.ship_state.step = ShipStep::Confirm;
+    }
+
+    /// Move the highlighted commit one place earlier (`up`) or later
+    pub fn move_split_commit(&mut self, up: bool) {
+        let selected = self.ship_state.commit_selected;
+        let target = if up {
+            selected.checked_sub(1)
+        } else {
+            Some(selected + 1).filter(|next| *next < self.ship_state.commits.len())
+        };
+        if let Some(target) = target {
+            self.ship_state.commits.swap(selected, target);
+            self.ship_state.commit_selected = target;
+        }
+    }
+
+    /// Keep or leave out the highlighted change
+    pub fn toggle_split_commit(&mut self) {
+        if let Some(commit) = self
+            .ship_state
+            .commits
+            .get_mut(self.ship_state.commit_selected)
+        {
+            commit.included = !commit.included;
+        }
+    }
+
+    /// Commits to make when splitting, in order: each kept change's description and files.
+    /// Changes that touch a file an earlier kept change also touches join that commit, since
+    /// a staged file can't be divided between commits.
+    pub fn ship_commit_plan(&self) -> Vec<(String, Vec<PathBuf>)> {
+        let mut plan: Vec<(Vec<String>, Vec<PathBuf>)> = Vec::new();
+        for commit in self.ship_state.commits.iter().filter(|c| c.included) {
+            let Some(change) = self.pending_changes.get(commit.change) else {
+                continue;
+            };
+            let files: Vec<PathBuf> = change.files.iter().map(|f| f.path.clone()).collect();
+            let overlapping: Vec<usize> = plan
+                .iter()
+                .enumerate()
+                .filter(|(_, (_, planned))| files.iter().any(|file| planned.contains(file)))
+                .map(|(i, _)| i)
+                .collect();
+            let mut entry = (vec![change.description.trim().to_string()], files);
+            let Some(&first) = overlapping.first() else {
+                plan.push(entry);
+                continue;
+            };
+            // Later groups fold into the earliest one, keeping their commit order.
+            for &i in overlapping[1..].iter().rev() {
+                let (messages, files) = plan.remove(i);
+                entry.0.splice(0..0, messages);
+                entry.1.splice(0..0, files);
+            }
+            let (messages, planned) = &mut plan[first];
+            messages.extend(entry.0);
+            for file in entry.1 {
+                if !planned.contains(&file) {
+                    planned.push(file);
+                }
+            }
+        }
+        plan.into_iter()
+            .map(|(messages, files)| (messages.join("\n\n"), files))
+            .collect()
+    }
+
+    /// Left-out changes that share a file with a kept one; their edits would be committed anyway
+    fn dropped_change_conflicts(&self) -> Vec<String> {
+        let kept: HashSet<&PathBuf> = self
+            .ship_state
+            .commits
+            .iter()
+            .filter(|commit| commit.included)
+            .filter_map(|commit| self.pending_changes.get(commit.change))
+            .flat_map(|change| change.files.iter().map(|file| &file.path))
+            .collect();
+        self.ship_state
+            .commits
+            .iter()
+            .filter(|commit| !commit.included)
+            .filter_map(|commit| self.pending_changes.get(commit.change))
+            .filter_map(|change| {
+                let shared = change.files.iter().find(|file| kept.contains(&file.path))?;
+                Some(format!(
+                    "'{}' is left out but shares {} with a kept change; keep both or neither",
+                    truncate_to_width(change.description.trim(), 60),
+                    shared.path.display()
+                ))
+            })
+            .collect()
+    }
+
     /// Update ship step progress
     pub fn set_ship_step(&mut self, step: ShipStep) {
         self.ship_state.step = step;
     }
 
+    /// Record a pre-commit hook run; a failure pauses shipping until the user retries
+    pub fn set_ship_hook_run(&mut self, run: cosmos_adapters::git_ops::PreCommitHookRun) {
+        if !run.passed {
+            self.ship_state.step = ShipStep::HooksFailed;
+            self.ship_state.scroll = 0;
+        }
+        self.ship_state.hook_run = Some(run);
+    }
+
+    /// Files the failed hook rewrote that are part of this ship, ready to be restaged
+    pub fn ship_hook_fixes_to_stage(&self) -> Vec<String> {
+        let Some(run) = &self.ship_state.hook_run else {
+            return Vec::new();
+        };
+        run.modified_files
+            .iter()
+            .filter(|file| {
+                self.ship_state
+                    .files
+                    .iter()
+                    .any(|shipped| shipped == Path::new(file.as_str()))
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Toggle whether the pull request opens as a draft
+    pub fn ship_toggle_draft(&mut self) {
+        self.ship_state.draft = !self.ship_state.draft;
+    }
+
+    /// Start editing a pull request option on the Confirm screen
+    pub fn start_ship_field_edit(&mut self, field: ShipField) {
+        if self.ship_state.step != ShipStep::Confirm {
+            return;
+        }
+        self.ship_state.editing = Some(field);
+        self.input_mode = InputMode::ShipField;
+    }
+
+    /// Stop editing the pull request option, keeping what was typed
+    pub fn finish_ship_field_edit(&mut self) {
+        self.ship_state.editing = None;
+        self.input_mode = InputMode::Normal;
+    }
+
+    fn ship_field_value_mut(&mut self) -> Option<&mut String> {
+        match self.ship_state.editing? {
+            ShipField::Base => Some(&mut self.ship_state.base),
+            ShipField::Reviewers => Some(&mut self.ship_state.reviewers),
+            ShipField::Labels => Some(&mut self.ship_state.labels),
+            ShipField::Title => Some(&mut self.ship_state.pr_title),
+            ShipField::Description => Some(&mut self.ship_state.pr_body),
+            ShipField::CommitMessage => Some(&mut self.ship_state.commit_message),
+        }
+    }
+
+    /// Add character to the pull request option being edited
+    pub fn ship_field_push(&mut self, c: char) {
+        if let Some(value) = self.ship_field_value_mut() {
+            value.push(c);
+        }
+    }
+
+    /// Remove last character from the pull request option being edited
+    pub fn ship_field_pop(&mut self) {
+        if let Some(value) = self.ship_field_value_mut() {
+            value.pop();
+        }
+    }
+
+    /// Mark a commit message as being written; false if one is already on the way
+    /// or shipping has started
+    pub fn begin_commit_message_generation(&mut self) -> bool {
+        if self.ship_state.step != ShipStep::Confirm || self.ship_state.generating_commit_message {
+            return false;
+        }
+        self.ship_state.generating_commit_message = true;
+        true
+    }
+
+    /// Use a generated commit message, unless shipping moved on or the message is being edited
+    pub fn set_generated_commit_message(&mut self, message: String) {
+        self.ship_state.generating_commit_message = false;
+        if self.ship_state.step == ShipStep::Confirm
+            && self.ship_state.editing != Some(ShipField::CommitMessage)
+        {
+            self.ship_state.commit_message = message;
+        }
+    }
+
+    /// Keep the current commit message after generation failed
+    pub fn commit_message_generation_failed(&mut self, error: &str) {
+        self.ship_state.generating_commit_message = false;
+        if self.workflow_step == WorkflowStep::Ship {
+            self.show_toast(format!(
+                "Couldn't write a commit message: {}",
+                truncate_to_width(error, 80)
+            ));
+        }
+    }
+
+    /// Leave the hook failure view and return to the ship summary
+    pub fn dismiss_ship_hook_failure(&mut self) {
+        self.ship_state.step = ShipStep::Confirm;
+        self.ship_state.hook_run = None;
+        self.ship_state.scroll = 0;
+    }
+
     /// Set ship PR URL on completion
     pub fn set_ship_pr_url(&mut self, url: String) {
+        self.record_cosmos_pull_request(&self.ship_state.branch_name, &url);
+        // Remember a newly opened GitHub pull request so later fixes on its branch follow up on it.
+        if self.ship_state.follow_up.is_none() {
+            if let Ok(cosmos_adapters::github::PrReference {
+                repo: Some((owner, repo)),
+                number,
+            }) = url.parse()
+            {
+                self.follow_up_pr = Some(cosmos_adapters::github::BranchPullRequest {
+                    owner,
+                    repo,
+                    number,
+                    title: self.ship_state.pr_title.clone(),
+                    html_url: url.clone(),
+                    branch: self.ship_state.branch_name.clone(),
+                });
+            }
+        }
         self.ship_state.pr_url = Some(url);
         self.ship_state.step = ShipStep::Done;
     }
 
+    /// Record the change just committed in stacked mode and show the stack.
+    ///
+    /// The checkout stays on the new branch, so the next applied fix branches from it.
+    pub fn push_stack_entry(&mut self) {
+        let mut options = self.ship_state.pr_options();
+        if options.base.is_none() {
+            options.base = self.cosmos_base_branch.clone();
+        }
+        self.stack.push(StackEntry {
+            branch: self.ship_state.branch_name.clone(),
+            title: self.ship_state.pr_title.trim().to_string(),
+            body: self.ship_state.pr_body.clone(),
+            options,
+            pr_url: None,
+            opening: false,
+        });
+        self.verify_state = VerifyState::default();
+        self.review_state = ReviewState::default();
+        self.pending_changes.clear();
+        self.cosmos_branch = None;
+        self.cosmos_base_branch = None;
+        // The commit is the undo point now.
+        let _ = cosmos_adapters::undo::clear(&self.repo_path);
+        self.open_stack();
+    }
+
+    /// Show the stacked branches in the Ship panel
+    pub fn open_stack(&mut self) {
+        if self.stack.is_empty() {
+            return;
+        }
+        self.ship_state = ShipState {
+            step: ShipStep::Stack,
+            stack_selected: self.stack.len() - 1,
+            ..ShipState::default()
+        };
+        self.workflow_step = WorkflowStep::Ship;
+    }
+
+    /// Leave the stack list for the suggestions
+    pub fn close_stack(&mut self) {
+        self.ship_state = ShipState::default();
+        self.workflow_step = WorkflowStep::Suggestions;
+    }
+
+    /// Mark a stack entry's pull request as being opened; None if it is open or on its way
+    pub fn begin_stack_pr(&mut self, index: usize) -> Option<StackEntry> {
+        let entry = self.stack.get_mut(index)?;
+        if entry.opening || entry.pr_url.is_some() {
+            return None;
+        }
+        entry.opening = true;
+        Some(entry.clone())
+    }
+
+    /// Record the outcome of opening a stack entry's pull request
+    pub fn finish_stack_pr(&mut self, index: usize, url: Option<String>) {
+        if let (Some(entry), Some(url)) = (self.stack.get(index), url.as_deref()) {
+            self.record_cosmos_pull_request(&entry.branch, url);
+        }
+        if let Some(entry) = self.stack.get_mut(index) {
+            entry.opening = false;
+            entry.pr_url = url;
+        }
+    }
+
     /// Reset workflow to suggestions after shipping
     pub fn workflow_complete(&mut self) {
         self.workflow_step = WorkflowStep::Suggestions;
         self.verify_state = VerifyState::default();
         self.review_state = ReviewState::default();
         self.ship_state = ShipState::default();
-        self.pending_changes.clear();
-        self.cosmos_branch = None;
-        self.cosmos_base_branch = None;
+        self.clear_pending_changes();
     }
 
     /// Check if currently on main/master branch
@@ -2076,6 +4268,19 @@ impl App {
     }
 }
 
+/// `(branch, pull request URL)` for each tracked branch that has a pull request
+fn branch_polls(branches: &[cosmos_adapters::cache::CosmosBranchRecord]) -> Vec<(String, String)> {
+    branches
+        .iter()
+        .filter_map(|record| {
+            record
+                .pr_url
+                .as_ref()
+                .map(|url| (record.branch.clone(), url.clone()))
+        })
+        .collect()
+}
+
 fn parse_reasoning_stream_line(line: &str) -> Option<(&str, &str)> {
     let (worker, kind, text) = parse_stream_tagged_line(line)?;
     if kind != "reasoning" {
@@ -2227,20 +4432,6 @@ fn latest_worker_thought(reasoning: Option<&str>) -> String {
     }
 }
 
-fn truncate_display_text(text: &str, max_chars: usize) -> String {
-    if max_chars == 0 {
-        return String::new();
-    }
-    let count = text.chars().count();
-    if count <= max_chars {
-        return text.to_string();
-    }
-
-    let mut truncated: String = text.chars().take(max_chars.saturating_sub(1)).collect();
-    truncated.push('…');
-    truncated
-}
-
 fn chunk_has_reasoning_boundary(chunk: &str) -> bool {
     chunk
         .chars()
@@ -2382,7 +4573,7 @@ fn format_reasoning_stream_block(worker: &str, text: &str) -> Vec<String> {
         if !humanized.is_empty() {
             lines.push(format!(
                 "  • {}",
-                truncate_display_text(&humanized, STREAM_REASONING_SEGMENT_MAX_CHARS)
+                truncate_to_width(&humanized, STREAM_REASONING_SEGMENT_MAX_CHARS)
             ));
         }
     }
@@ -2435,7 +4626,7 @@ mod tests {
     use super::*;
     use cosmos_core::context::WorkContext;
     use cosmos_core::index::CodebaseIndex;
-    use cosmos_core::suggest::SuggestionEngine;
+    use cosmos_core::suggest::{Priority, SuggestionEngine, SuggestionKind, SuggestionSource};
     use std::collections::HashMap;
     use std::time::{SystemTime, UNIX_EPOCH};
 
@@ -2453,6 +4644,9 @@ mod tests {
             files: HashMap::new(),
             index_errors: Vec::new(),
             git_head: Some("deadbeef".to_string()),
+            packages: Vec::new(),
+            excluded_files: 0,
+            filter_fingerprint: None,
         };
         let suggestions = SuggestionEngine::new(index.clone());
         let context = WorkContext {
@@ -2468,6 +4662,21 @@ mod tests {
         App::new(index, suggestions, context)
     }
 
+    fn test_suggestion(
+        kind: SuggestionKind,
+        priority: Priority,
+        file: &str,
+        summary: &str,
+    ) -> Suggestion {
+        Suggestion::new(
+            kind,
+            priority,
+            PathBuf::from(file),
+            summary.to_string(),
+            SuggestionSource::LlmDeep,
+        )
+    }
+
     #[test]
     fn suggestion_stream_reasoning_chunks_coalesce_for_same_worker() {
         let mut app = make_test_app();
@@ -2719,6 +4928,199 @@ mod tests {
             .any(|line| line.contains("status: investigating")));
     }
 
+    #[test]
+    fn background_rescan_waits_for_idle_and_badges_new_findings() {
+        let finding = |summary: &str, file: &str| {
+            test_suggestion(SuggestionKind::BugFix, Priority::High, file, summary)
+        };
+        let mut app = make_test_app();
+        app.suggestions.replace_llm_suggestions(vec![
+            finding("Retry loop never backs off", "src/a.rs"),
+            finding("Timeout is ignored", "src/b.rs"),
+        ]);
+        app.suggestion_selected = 1;
+        let selected_id = app.active_suggestions_for_display()[1].id;
+
+        let fresh = finding("Cache key collides", "src/c.rs");
+        let fresh_id = fresh.id;
+        app.pending_rescan = Some(vec![
+            fresh,
+            finding("Retry loop never backs off", "src/a.rs"),
+            finding("Timeout is ignored", "src/b.rs"),
+        ]);
+        app.overlay = Overlay::Help { scroll: 0 };
+        assert!(!app.apply_pending_rescan());
+
+        app.overlay = Overlay::None;
+        assert!(app.apply_pending_rescan());
+        assert!(app.pending_rescan.is_none());
+        assert_eq!(app.new_suggestion_ids, HashSet::from([fresh_id]));
+        let display = app.active_suggestions_for_display();
+        assert_eq!(display.len(), 3);
+        assert_eq!(display[app.suggestion_selected].id, selected_id);
+        assert_eq!(
+            app.toast.as_ref().map(|toast| toast.message.as_str()),
+            Some("1 new suggestion since last scan")
+        );
+    }
+
+    #[test]
+    fn snoozed_suggestions_leave_the_list_until_unsnoozed() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut app = make_test_app();
+        app.repo_path = dir.path().to_path_buf();
+        let finding = |file: &str| {
+            test_suggestion(
+                SuggestionKind::BugFix,
+                Priority::High,
+                file,
+                &format!("Issue in {}", file),
+            )
+        };
+        app.suggestions
+            .replace_llm_suggestions(vec![finding("a.rs"), finding("b.rs")]);
+        let first = app.active_suggestions_for_display()[0].file.clone();
+
+        app.open_snooze_overlay();
+        app.snooze_navigate(-1);
+        app.confirm_snooze();
+        // Nothing is indexed here, so "until the file changes" has no hash to wait on
+        assert!(app.snoozes.snoozes.is_empty());
+        assert_eq!(app.active_suggestions_for_display().len(), 2);
+
+        app.open_snooze_overlay();
+        app.snooze_navigate(1);
+        app.confirm_snooze();
+        let shown: Vec<_> = app
+            .active_suggestions_for_display()
+            .iter()
+            .map(|s| s.file.clone())
+            .collect();
+        assert_eq!(shown.len(), 1);
+        assert!(!shown.contains(&first));
+        assert_eq!(
+            cosmos_adapters::cache::Cache::new(dir.path())
+                .load_snoozes()
+                .snoozes
+                .len(),
+            1
+        );
+
+        app.open_snoozed_overlay();
+        app.unsnooze_selected();
+        assert!(app.snoozes.snoozes.is_empty());
+        assert_eq!(app.active_suggestions_for_display().len(), 2);
+    }
+
+    #[test]
+    fn bulk_dismiss_removes_a_directory_and_undo_brings_it_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut app = make_test_app();
+        app.repo_path = dir.path().to_path_buf();
+        let finding = |file: &str| {
+            test_suggestion(
+                SuggestionKind::Documentation,
+                Priority::Low,
+                file,
+                &format!("Document {}", file),
+            )
+        };
+        app.suggestions.replace_llm_suggestions(vec![
+            finding("legacy/a.rs"),
+            finding("legacy/b.rs"),
+            finding("src/c.rs"),
+        ]);
+        app.suggestion_selected = app
+            .active_suggestions_for_display()
+            .iter()
+            .position(|s| s.file.starts_with("legacy"))
+            .unwrap();
+
+        app.open_bulk_dismiss_overlay();
+        let Overlay::BulkDismiss { scopes, .. } = &app.overlay else {
+            panic!("bulk dismiss overlay should be open");
+        };
+        // Low-priority docs, all docs, then legacy/
+        assert_eq!(
+            scopes.iter().map(|(_, count)| *count).collect::<Vec<_>>(),
+            vec![3, 3, 2]
+        );
+        app.bulk_dismiss_navigate(-1);
+        app.confirm_bulk_dismiss();
+        let shown: Vec<_> = app
+            .active_suggestions_for_display()
+            .iter()
+            .map(|s| s.file.clone())
+            .collect();
+        assert_eq!(shown, vec![PathBuf::from("src/c.rs")]);
+        assert_eq!(app.dismissals.len(), 1);
+        let recorded = cosmos_adapters::cache::Cache::new(dir.path()).load_dismissals();
+        assert_eq!(recorded.dismissals.len(), 1);
+        assert_eq!(recorded.dismissals[0].finding_ids.len(), 2);
+
+        // A new session keeps the dismissed findings out of the list
+        let mut reopened = make_test_app();
+        reopened.repo_path = dir.path().to_path_buf();
+        reopened.suggestions.suggestions = app.suggestions.suggestions.clone();
+        reopened
+            .suggestions
+            .restore(app.dismissals[0].suggestions.clone());
+        reopened.dismissed = recorded;
+        assert_eq!(reopened.active_suggestions_for_display().len(), 1);
+
+        assert!(app.undo_last_bulk_dismissal());
+        assert_eq!(app.active_suggestions_for_display().len(), 3);
+        assert!(cosmos_adapters::cache::Cache::new(dir.path())
+            .load_dismissals()
+            .dismissals
+            .is_empty());
+        assert!(!app.undo_last_bulk_dismissal());
+    }
+
+    #[test]
+    fn git_edits_drop_suggestions_for_changed_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_path_buf();
+        let repo = git2::Repository::init(&root).unwrap();
+        std::fs::write(root.join("a.rs"), "fn a() {}\n").unwrap();
+        std::fs::write(root.join("b.rs"), "fn b() {}\n").unwrap();
+        let mut git_index = repo.index().unwrap();
+        git_index
+            .add_all(["*"], git2::IndexAddOption::DEFAULT, None)
+            .unwrap();
+        let tree = repo.find_tree(git_index.write_tree().unwrap()).unwrap();
+        let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+        repo.commit(Some("HEAD"), &signature, &signature, "init", &tree, &[])
+            .unwrap();
+
+        let mut app = make_test_app();
+        app.repo_path = root.clone();
+        app.context = WorkContext::load(&root).unwrap();
+        let finding = |file: &str| {
+            test_suggestion(
+                SuggestionKind::BugFix,
+                Priority::High,
+                file,
+                &format!("Issue in {}", file),
+            )
+        };
+        app.suggestions
+            .replace_llm_suggestions(vec![finding("a.rs"), finding("b.rs")]);
+        assert!(!app.observe_git_state());
+
+        std::fs::write(root.join("a.rs"), "fn a() { todo!() }\n").unwrap();
+        app.context.refresh().unwrap();
+        assert!(app.observe_git_state());
+        let remaining: Vec<_> = app
+            .active_suggestions_for_display()
+            .iter()
+            .map(|s| s.file.clone())
+            .collect();
+        assert_eq!(remaining, vec![PathBuf::from("b.rs")]);
+        assert!(app.toast.is_some());
+        assert!(!app.observe_git_state());
+    }
+
     #[test]
     fn review_passed_is_false_when_verification_failed() {
         let mut app = make_test_app();
@@ -2839,6 +5241,40 @@ mod tests {
         assert!(app.active_ask_request_id.is_none());
     }
 
+    #[test]
+    fn follow_up_answers_keep_a_scrollable_transcript() {
+        let mut app = make_test_app();
+        app.show_chat_answer(
+            "Where is retry handled?".to_string(),
+            "In `src/retry.rs`.".to_string(),
+            None,
+        );
+        app.show_chat_answer(
+            "What about the other call site?".to_string(),
+            "`src/client.rs` calls it too.".to_string(),
+            None,
+        );
+
+        let state = app.ask_cosmos_state.as_ref().unwrap();
+        assert_eq!(app.chat_session.len(), 2);
+        assert_eq!(state.response, "`src/client.rs` calls it too.");
+        assert_eq!(
+            state.question.as_deref(),
+            Some("What about the other call site?")
+        );
+        assert!(state.transcript.contains("Where is retry handled?"));
+
+        app.ask_cosmos_scroll_down();
+        app.ask_cosmos_scroll_up();
+        app.ask_cosmos_scroll_up();
+        let state = app.ask_cosmos_state.as_ref().unwrap();
+        assert_eq!((state.scroll, state.transcript_scroll), (0, 1));
+
+        app.new_chat_session();
+        assert!(app.chat_session.is_empty());
+        assert!(app.ask_cosmos_state.is_none());
+    }
+
     #[test]
     fn startup_actions_for_changed_context() {
         let actions = App::startup_actions_for_context(2, "feature/work", "main");
@@ -2940,4 +5376,932 @@ mod tests {
             panic!("expected alert overlay");
         }
     }
+
+    #[test]
+    fn ask_context_picker_filters_toggles_and_confirms() {
+        let mut app = make_test_app();
+        app.file_tree = ["src", "src/ui.rs", "src/lib.rs", "docs/ui.md"]
+            .into_iter()
+            .map(|path| FlatTreeEntry {
+                name: path.rsplit('/').next().unwrap().to_string(),
+                path: PathBuf::from(path),
+                is_dir: !path.contains('.'),
+                depth: path.matches('/').count(),
+                priority: ' ',
+            })
+            .collect();
+        app.start_question();
+
+        app.open_ask_context_picker();
+        assert_eq!(app.input_mode, InputMode::Normal);
+        for c in "ui".chars() {
+            app.ask_context_edit_query(Some(c));
+        }
+        assert_eq!(app.ask_context_candidates().len(), 2);
+        app.ask_context_navigate(1);
+        app.ask_context_toggle_selected();
+        app.close_ask_context_picker(true);
+
+        assert_eq!(app.input_mode, InputMode::Question);
+        assert_eq!(app.ask_context_paths, vec![PathBuf::from("docs/ui.md")]);
+
+        // Cancelling keeps the previous selection.
+        app.open_ask_context_picker();
+        app.ask_context_toggle_selected();
+        app.close_ask_context_picker(false);
+        assert_eq!(app.ask_context_paths, vec![PathBuf::from("docs/ui.md")]);
+        assert!(matches!(app.overlay, Overlay::None));
+    }
+
+    #[test]
+    fn failed_pre_commit_hook_pauses_ship_and_offers_shipped_fixes() {
+        let mut app = make_test_app();
+        app.workflow_step = WorkflowStep::Ship;
+        app.ship_state.files = vec![PathBuf::from("src/lib.rs")];
+        app.set_ship_step(ShipStep::RunningHooks);
+
+        app.set_ship_hook_run(cosmos_adapters::git_ops::PreCommitHookRun {
+            kind: cosmos_adapters::git_ops::PreCommitHookKind::PreCommit,
+            passed: false,
+            timed_out: false,
+            output: "rustfmt....Failed\n- files were modified by this hook".to_string(),
+            modified_files: vec!["src/lib.rs".to_string(), "src/other.rs".to_string()],
+        });
+        assert_eq!(app.ship_state.step, ShipStep::HooksFailed);
+        // Only files in this ship are restaged; unrelated rewrites stay unstaged.
+        assert_eq!(
+            app.ship_hook_fixes_to_stage(),
+            vec!["src/lib.rs".to_string()]
+        );
+
+        app.dismiss_ship_hook_failure();
+        assert_eq!(app.ship_state.step, ShipStep::Confirm);
+        assert!(app.ship_state.hook_run.is_none());
+        assert!(app.ship_hook_fixes_to_stage().is_empty());
+    }
+
+    #[test]
+    fn workspace_starts_restricted_until_trusted() {
+        let mut app = make_test_app();
+        assert!(!app.workspace_trusted);
+
+        app.open_workspace_trust_overlay();
+        assert_eq!(app.overlay, Overlay::WorkspaceTrust);
+        app.resolve_workspace_trust(true);
+        assert!(app.workspace_trusted);
+        assert_eq!(app.overlay, Overlay::None);
+    }
+
+    #[test]
+    fn pr_review_posts_selected_findings_or_all() {
+        let mut app = make_test_app();
+        app.start_pr_review(7);
+        assert!(app.is_pr_review());
+        assert!(app.review_state.reviewing);
+        assert_eq!(app.workflow_step, WorkflowStep::Review);
+
+        let finding =
+            |title: &str, severity: &str, recommended: bool| cosmos_engine::llm::ReviewFinding {
+                file: "src/retry.rs".to_string(),
+                line: Some(12),
+                severity: severity.to_string(),
+                category: String::new(),
+                title: title.to_string(),
+                description: "Details.".to_string(),
+                recommended,
+            };
+        app.set_pr_review(
+            cosmos_adapters::github::OpenPullRequest {
+                owner: "octo".to_string(),
+                repo: "cosmos".to_string(),
+                number: 7,
+                title: "Retry".to_string(),
+                body: String::new(),
+                html_url: String::new(),
+                head_sha: "abc123".to_string(),
+                files: vec![cosmos_adapters::github::PullRequestFile {
+                    path: "src/retry.rs".to_string(),
+                    original: "old".to_string(),
+                    content: "new".to_string(),
+                    diff_lines: Default::default(),
+                }],
+            },
+            vec![
+                finding("Off by one", "warning", true),
+                finding("Naming", "nitpick", false),
+            ],
+            "Two issues.".to_string(),
+        );
+        assert!(!app.review_state.reviewing);
+        assert_eq!(app.review_state.files[0].original_content, "old");
+
+        let comments = app.pr_review_comments();
+        assert_eq!(comments.len(), 1);
+        assert_eq!(comments[0].line, Some(12));
+        assert!(comments[0].body.starts_with("**Off by one** (warning)"));
+
+        app.review_state.selected.clear();
+        assert_eq!(app.pr_review_comments().len(), 2);
+    }
+
+    #[test]
+    fn review_finding_edits_feed_the_fix_and_survive_the_next_round() {
+        let mut app = make_test_app();
+        app.start_review(Vec::new());
+        let finding = |title: &str| cosmos_engine::llm::ReviewFinding {
+            file: "src/retry.rs".to_string(),
+            line: Some(12),
+            severity: "critical".to_string(),
+            category: "bug".to_string(),
+            title: title.to_string(),
+            description: "Loop never ends.".to_string(),
+            recommended: true,
+        };
+        app.set_review_findings(vec![finding("Infinite retry")], "One issue.".to_string());
+
+        app.start_finding_edit(FindingField::Title);
+        assert_eq!(app.input_mode, InputMode::FindingField);
+        for _ in 0.."retry".len() {
+            app.finding_field_pop();
+        }
+        "loop without backoff"
+            .chars()
+            .for_each(|c| app.finding_field_push(c));
+        app.finish_finding_edit();
+        assert_eq!(app.input_mode, InputMode::Normal);
+
+        app.start_finding_edit(FindingField::Description);
+        app.finding_field_push('\n');
+        "Only the delay is wrong."
+            .chars()
+            .for_each(|c| app.finding_field_push(c));
+        app.finish_finding_edit();
+        app.review_downgrade_finding();
+
+        let selected = app.get_selected_review_findings();
+        assert_eq!(selected[0].title, "Infinite loop without backoff");
+        assert_eq!(
+            selected[0].description,
+            "Loop never ends.\nOnly the delay is wrong."
+        );
+        assert_eq!(selected[0].severity, "warning");
+
+        // The reviewer raises the same finding again; the correction is reapplied.
+        app.set_review_findings(
+            vec![finding("Infinite retry"), finding("Unbounded queue")],
+            "Two issues.".to_string(),
+        );
+        let findings = &app.review_state.findings;
+        assert_eq!(findings[0].title, "Infinite loop without backoff");
+        assert_eq!(findings[0].severity, "warning");
+        assert_eq!(findings[1].severity, "critical");
+        assert_eq!(
+            app.review_state.edited.get(&0).map(String::as_str),
+            Some("Infinite retry")
+        );
+        assert!(!app.review_state.edited.contains_key(&1));
+    }
+
+    #[test]
+    fn apply_plan_lists_queued_conflicts_and_apply_rebases_them() {
+        use cosmos_core::suggest::{Priority, Suggestion, SuggestionKind, SuggestionSource};
+
+        let mut app = make_test_app();
+        let make = |line: usize| {
+            Suggestion::new(
+                SuggestionKind::BugFix,
+                Priority::High,
+                PathBuf::from("src/lib.rs"),
+                format!("Fix line {}", line),
+                SuggestionSource::LlmDeep,
+            )
+            .with_line(line)
+        };
+        let (first, near, far) = (make(40), make(45), make(90));
+        let (first_id, near_id, far_id) = (first.id, near.id, far.id);
+        let preview = cosmos_engine::llm::build_fix_preview_from_validated_suggestion(&first);
+        app.suggestions.suggestions = vec![first, near, far];
+
+        app.open_apply_plan_overlay(first_id, preview, vec![PathBuf::from("src/lib.rs")], false);
+        let Overlay::ApplyPlan { conflicts, .. } = &app.overlay else {
+            panic!("expected apply plan");
+        };
+        assert_eq!(conflicts.len(), 2);
+        assert!(conflicts[0].starts_with("Overlaps \"Fix line 45\""));
+        assert!(conflicts[1].contains("line anchors will be rebased"));
+
+        // The first fix rewrites lines 40-45 and adds two lines.
+        let before: String = (1..=100).map(|i| format!("line {}\n", i)).collect();
+        let after: String = (1..=100)
+            .flat_map(|i| match i {
+                40..=45 => vec![format!("fixed {}\n", i)],
+                46 => vec![
+                    "added\n".to_string(),
+                    "added\n".to_string(),
+                    "line 46\n".to_string(),
+                ],
+                _ => vec![format!("line {}\n", i)],
+            })
+            .collect();
+        app.suggestions.mark_applied(first_id);
+        app.rebase_suggestions_after_apply(&[(PathBuf::from("src/lib.rs"), before, after)]);
+
+        let find = |id| {
+            app.suggestions
+                .suggestions
+                .iter()
+                .find(|s| s.id == id)
+                .unwrap()
+        };
+        assert_eq!(find(near_id).line, None);
+        assert!(find(near_id)
+            .implementation_risk_flags
+            .iter()
+            .any(|flag| flag == "anchor_rewritten_by_earlier_fix"));
+        assert_eq!(find(far_id).line, Some(92));
+        assert_eq!(find(first_id).line, Some(40));
+    }
+
+    #[test]
+    fn command_log_is_bounded_and_holds_a_scrolled_back_view() {
+        let mut app = make_test_app();
+        for n in 0..CommandLog::CAPACITY + 5 {
+            app.push_command_log_line(format!("line {}", n));
+        }
+        assert_eq!(app.command_log.len(), CommandLog::CAPACITY);
+        assert_eq!(app.command_log.lines().next().unwrap(), "line 5");
+
+        // Following the tail stays at the tail as lines arrive.
+        app.toggle_command_log();
+        app.push_command_log_line("next".to_string());
+        assert_eq!(app.overlay, Overlay::CommandLog { scroll_back: 0 });
+
+        // Scrolled back, the view stays on the same lines.
+        app.overlay_scroll_up();
+        app.overlay_scroll_up();
+        app.push_command_log_line("after".to_string());
+        assert_eq!(app.overlay, Overlay::CommandLog { scroll_back: 3 });
+        app.overlay_scroll_down();
+        assert_eq!(app.overlay, Overlay::CommandLog { scroll_back: 2 });
+
+        app.toggle_command_log();
+        assert_eq!(app.overlay, Overlay::None);
+    }
+
+    #[test]
+    fn ship_splits_commits_per_change_in_the_chosen_order() {
+        let mut app = make_test_app();
+        for (description, files) in [
+            ("Guard the empty case", vec!["src/parse.rs"]),
+            ("Retry with backoff", vec!["src/net.rs"]),
+            ("Log parse failures", vec!["src/log.rs", "src/parse.rs"]),
+            ("Rename config loader", vec!["src/config.rs"]),
+        ] {
+            app.pending_changes
+                .push(PendingChange::with_preview_context_multi(
+                    uuid::Uuid::new_v4(),
+                    files
+                        .into_iter()
+                        .map(|file| FileChange::new(PathBuf::from(file), String::new()))
+                        .collect(),
+                    description.to_string(),
+                    String::new(),
+                    String::new(),
+                    String::new(),
+                ));
+        }
+        app.start_ship();
+        app.open_commit_split();
+        assert_eq!(app.ship_state.step, ShipStep::Commits);
+
+        // Commit the config rename first and leave the retry change out.
+        app.ship_state.commit_selected = 3;
+        for _ in 0..4 {
+            app.move_split_commit(true);
+        }
+        assert_eq!(app.ship_state.commit_selected, 0);
+        app.ship_state.commit_selected = 2;
+        app.toggle_split_commit();
+        app.finish_commit_split(true);
+        assert!(app.ship_state.split_commits);
+        assert_eq!(app.ship_state.step, ShipStep::Confirm);
+
+        let plan = app.ship_commit_plan();
+        assert_eq!(
+            plan,
+            vec![
+                (
+                    "Rename config loader".to_string(),
+                    vec![PathBuf::from("src/config.rs")]
+                ),
+                (
+                    "Guard the empty case\n\nLog parse failures".to_string(),
+                    vec![PathBuf::from("src/parse.rs"), PathBuf::from("src/log.rs")]
+                ),
+            ]
+        );
+        assert!(!app.ship_state.files.contains(&PathBuf::from("src/net.rs")));
+        assert!(app.ship_policy_problems().is_empty());
+
+        // Leaving out a change that shares a file with a kept one is refused.
+        app.open_commit_split();
+        app.ship_state.commit_selected = 1;
+        app.toggle_split_commit();
+        app.finish_commit_split(true);
+        assert_eq!(app.ship_policy_problems().len(), 1);
+
+        // Esc goes back to a single commit.
+        app.open_commit_split();
+        app.finish_commit_split(false);
+        assert!(!app.ship_state.split_commits);
+        assert_eq!(app.ship_state.files.len(), 5);
+    }
+
+    #[test]
+    fn ship_generates_editable_pr_description_with_harness_evidence() {
+        let mut app = make_test_app();
+        app.pending_changes.push(
+            PendingChange::with_preview_context_multi(
+                uuid::Uuid::new_v4(),
+                vec![FileChange::new(PathBuf::from("src/lib.rs"), String::new())],
+                "Guard the empty case.".to_string(),
+                "Empty Input".to_string(),
+                "Empty input crashes the parser.".to_string(),
+                "Empty input is ignored.".to_string(),
+            )
+            .with_harness_evidence(Some(cosmos_engine::llm::HarnessEvidence {
+                passing_attempt: 1,
+                attempt_count: 1,
+                quick_check_status: cosmos_engine::llm::ImplementationQuickCheckStatus::Passed,
+                quick_check_command: Some("cargo check".to_string()),
+                review_iterations: 1,
+                ..Default::default()
+            })),
+        );
+        app.start_ship();
+        assert_eq!(app.ship_state.pr_title, "Empty Input");
+        assert!(app
+            .ship_state
+            .pr_body
+            .contains("## Test evidence\n\n- quick check `cargo check` passed"));
+
+        app.start_ship_field_edit(ShipField::Title);
+        for _ in 0.."Input".len() {
+            app.ship_field_pop();
+        }
+        for c in "Parser Input".chars() {
+            app.ship_field_push(c);
+        }
+        app.finish_ship_field_edit();
+        assert_eq!(app.ship_state.pr_title, "Empty Parser Input");
+    }
+
+    #[test]
+    fn ship_confirm_edits_pull_request_options() {
+        let mut app = make_test_app();
+        app.start_ship();
+        app.ship_toggle_draft();
+
+        app.start_ship_field_edit(ShipField::Reviewers);
+        assert_eq!(app.input_mode, InputMode::ShipField);
+        for c in "@alice, my-org/platformx".chars() {
+            app.ship_field_push(c);
+        }
+        app.ship_field_pop();
+        app.finish_ship_field_edit();
+        app.start_ship_field_edit(ShipField::Base);
+        for c in "release".chars() {
+            app.ship_field_push(c);
+        }
+        app.finish_ship_field_edit();
+        assert_eq!(app.input_mode, InputMode::Normal);
+
+        let options = app.ship_state.pr_options();
+        assert!(options.draft);
+        assert_eq!(options.base.as_deref(), Some("release"));
+        assert_eq!(options.reviewers, vec!["alice", "my-org/platform"]);
+        assert!(options.labels.is_empty());
+
+        // Options can't change once shipping has started.
+        app.set_ship_step(ShipStep::Pushing);
+        app.start_ship_field_edit(ShipField::Labels);
+        assert_eq!(app.input_mode, InputMode::Normal);
+    }
+
+    #[test]
+    fn generated_commit_message_replaces_prefill_and_stays_editable() {
+        let mut app = make_test_app();
+        app.start_ship();
+        assert_eq!(app.ship_state.commit_message, "chore: apply changes");
+
+        assert!(app.begin_commit_message_generation());
+        assert!(!app.begin_commit_message_generation());
+        app.set_generated_commit_message("fix(parser): guard empty input".to_string());
+        assert!(!app.ship_state.generating_commit_message);
+        assert_eq!(
+            app.ship_state.commit_message,
+            "fix(parser): guard empty input"
+        );
+
+        app.start_ship_field_edit(ShipField::CommitMessage);
+        assert!(ShipField::CommitMessage.is_multiline());
+        for c in "\n\nEmpty files panicked.".chars() {
+            app.ship_field_push(c);
+        }
+        // A rewrite that lands mid-edit doesn't clobber what is being typed.
+        assert!(app.begin_commit_message_generation());
+        app.set_generated_commit_message("chore: something else".to_string());
+        app.finish_ship_field_edit();
+        assert_eq!(
+            app.ship_state.commit_message,
+            "fix(parser): guard empty input\n\nEmpty files panicked."
+        );
+
+        app.set_ship_step(ShipStep::Committing);
+        assert!(!app.begin_commit_message_generation());
+    }
+
+    #[test]
+    fn ship_lists_git_policy_problems_before_running_git() {
+        let mut app = make_test_app();
+        app.git_policy = cosmos_adapters::config::GitPolicy {
+            branch_pattern: Some("cosmos/<kind>/<slug>".to_string()),
+            conventional_commits: true,
+            max_subject_length: Some(50),
+        };
+        app.start_ship();
+        // The generated branch and default message already follow the policy.
+        assert!(app.ship_state.branch_name.starts_with("cosmos/chore/"));
+        assert!(app.ship_policy_problems().is_empty());
+
+        app.ship_state.commit_message = "Apply the fixes".to_string();
+        app.ship_state.branch_name = "cosmos-fix-20260101".to_string();
+        let problems = app.ship_policy_problems();
+        assert_eq!(problems.len(), 2, "{:?}", problems);
+        assert!(problems[0].contains("conventional commits"));
+        assert!(problems[1].contains("naming policy"));
+    }
+
+    #[test]
+    fn copy_target_follows_the_focused_panel_and_step() {
+        let mut app = make_test_app();
+        app.start_ship();
+        app.set_ship_pr_url("https://github.com/acme/widgets/pull/42".to_string());
+        assert_eq!(
+            app.copy_target(),
+            Some((
+                "PR URL",
+                "https://github.com/acme/widgets/pull/42".to_string()
+            ))
+        );
+
+        app.active_panel = ActivePanel::Ask;
+        assert_eq!(app.copy_target(), None);
+        app.ask_cosmos_state = Some(AskCosmosState {
+            response: "Use `retry_with_backoff`.".to_string(),
+            scroll: 0,
+            question: None,
+            transcript: String::new(),
+            transcript_scroll: 0,
+            context: None,
+        });
+        assert_eq!(
+            app.copy_target(),
+            Some(("answer", "Use `retry_with_backoff`.".to_string()))
+        );
+    }
+
+    #[test]
+    fn shipped_pull_request_is_followed_up_by_later_fixes_on_its_branch() {
+        let mut app = make_test_app();
+        app.cosmos_branch = Some("fix/aaaa-first".to_string());
+        app.start_ship();
+        app.set_ship_pr_url("https://github.com/acme/widgets/pull/42".to_string());
+        app.workflow_complete();
+        assert_eq!(app.follow_up_branch(), Some("fix/aaaa-first"));
+
+        app.cosmos_branch = Some("fix/aaaa-first".to_string());
+        app.pending_changes
+            .push(PendingChange::with_preview_context_multi(
+                uuid::Uuid::new_v4(),
+                vec![FileChange::new(PathBuf::from("src/lib.rs"), String::new())],
+                "Guard the empty case.".to_string(),
+                "Empty Input".to_string(),
+                "Empty input crashes the parser.".to_string(),
+                "Empty input is ignored.".to_string(),
+            ));
+        app.start_ship();
+        let pr = app.ship_state.follow_up.as_ref().expect("follow-up PR");
+        assert_eq!((pr.owner.as_str(), pr.number), ("acme", 42));
+        assert!(app
+            .ship_state
+            .pr_body
+            .contains("- **Empty Input**: Empty input is ignored. (`src/lib.rs`)"));
+
+        // A different branch, or stacked mode, opens a new pull request instead.
+        app.cosmos_branch = Some("fix/bbbb-second".to_string());
+        app.start_ship();
+        assert!(app.ship_state.follow_up.is_none());
+        app.cosmos_branch = Some("fix/aaaa-first".to_string());
+        app.stacked_changes = true;
+        app.start_ship();
+        assert!(app.ship_state.follow_up.is_none());
+    }
+
+    #[test]
+    fn only_merged_cosmos_branches_can_be_deleted_from_the_branch_list() {
+        use cosmos_adapters::forge::PrState;
+
+        let mut app = make_test_app();
+        app.record_cosmos_branch("fix/aaaa-open", Some("main"));
+        app.record_cosmos_branch("fix/bbbb-merged", Some("main"));
+        app.record_cosmos_pull_request("fix/aaaa-open", "https://github.com/acme/widgets/pull/1");
+        app.record_cosmos_pull_request("fix/bbbb-merged", "https://github.com/acme/widgets/pull/2");
+        let cache = cosmos_adapters::cache::Cache::new(&app.repo_path);
+        app.overlay = Overlay::Branches {
+            branches: cache.load_cosmos_branches().branches,
+            selected: 0,
+            status: None,
+        };
+        app.set_branch_states(vec![
+            ("fix/aaaa-open".to_string(), Ok(PrState::Open)),
+            ("fix/bbbb-merged".to_string(), Ok(PrState::Merged)),
+        ]);
+
+        assert_eq!(app.begin_branch_delete(), None);
+        app.branches_navigate(1);
+        assert_eq!(
+            app.begin_branch_delete().map(|record| record.branch),
+            Some("fix/bbbb-merged".to_string())
+        );
+        app.finish_branch_delete("fix/bbbb-merged", Ok(()));
+
+        let Overlay::Branches {
+            branches, selected, ..
+        } = &app.overlay
+        else {
+            panic!("expected the branch list to stay open");
+        };
+        assert_eq!(branches.len(), 1);
+        assert_eq!(*selected, 0);
+        let stored = cache.load_cosmos_branches();
+        assert!(stored.get("fix/bbbb-merged").is_none());
+        assert_eq!(
+            stored
+                .get("fix/aaaa-open")
+                .and_then(|record| record.pr_state),
+            Some(PrState::Open)
+        );
+    }
+
+    #[test]
+    fn switching_branches_sets_pending_changes_aside_and_brings_them_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_path_buf();
+        let repo = git2::Repository::init(&root).unwrap();
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "Test").unwrap();
+        config.set_str("user.email", "test@example.com").unwrap();
+        std::fs::write(root.join("a.rs"), "fn a() {}\n").unwrap();
+        let mut git_index = repo.index().unwrap();
+        git_index
+            .add_all(["*"], git2::IndexAddOption::DEFAULT, None)
+            .unwrap();
+        git_index.write().unwrap();
+        let tree = repo.find_tree(git_index.write_tree().unwrap()).unwrap();
+        let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+        let head = repo
+            .commit(Some("HEAD"), &signature, &signature, "init", &tree, &[])
+            .unwrap();
+        repo.branch("fix/other", &repo.find_commit(head).unwrap(), false)
+            .unwrap();
+
+        let mut app = make_test_app();
+        app.repo_path = root.clone();
+        app.context = WorkContext::load(&root).unwrap();
+        let base = app.context.branch.clone();
+        let finding = |summary: &str| {
+            test_suggestion(SuggestionKind::BugFix, Priority::High, "b.rs", summary)
+        };
+        let (first, second) = (finding("First"), finding("Second"));
+        let second_id = second.id;
+        app.suggestions.replace_llm_suggestions(vec![first, second]);
+        app.observe_git_state();
+        app.suggestion_selected = app
+            .active_suggestions_for_display()
+            .iter()
+            .position(|s| s.id == second_id)
+            .unwrap();
+
+        std::fs::write(root.join("a.rs"), "fn a() { todo!() }\n").unwrap();
+        app.pending_changes
+            .push(PendingChange::with_preview_context_multi(
+                uuid::Uuid::new_v4(),
+                vec![FileChange::new(PathBuf::from("a.rs"), String::new())],
+                "Fix a".to_string(),
+                "Fix a".to_string(),
+                String::new(),
+                String::new(),
+            ));
+        app.cosmos_base_branch = Some(base.clone());
+
+        let summary = app.switch_branch_context("fix/other").unwrap();
+        assert!(summary.contains(&format!("stashed the work on {}", base)));
+        assert_eq!(app.context.branch, "fix/other");
+        assert!(app.pending_changes.is_empty());
+        assert!(app.cosmos_base_branch.is_none());
+        assert_eq!(
+            std::fs::read_to_string(root.join("a.rs")).unwrap(),
+            "fn a() {}\n"
+        );
+        app.suggestion_selected = 0;
+
+        let summary = app.switch_branch_context(&base).unwrap();
+        assert!(summary.contains("restored its stashed work"));
+        assert!(summary.contains("1 pending change(s) back"));
+        assert_eq!(app.pending_changes.len(), 1);
+        assert_eq!(app.cosmos_base_branch.as_deref(), Some(base.as_str()));
+        assert_eq!(
+            app.active_suggestions_for_display()[app.suggestion_selected].id,
+            second_id
+        );
+        assert_eq!(
+            std::fs::read_to_string(root.join("a.rs")).unwrap(),
+            "fn a() { todo!() }\n"
+        );
+        assert!(app.switch_branch_context(&base).is_err());
+    }
+
+    #[test]
+    fn rebase_targets_the_branch_base_and_keeps_it_when_checks_fail() {
+        use cosmos_engine::llm::{ImplementationQuickCheckStatus, RebaseOutcome};
+
+        let mut app = make_test_app();
+        app.context.branch = "main".to_string();
+        assert!(app
+            .rebase_base()
+            .unwrap_err()
+            .contains("isn't a Cosmos branch"));
+
+        app.context.branch = "fix/aaaa-first".to_string();
+        app.record_cosmos_branch("fix/aaaa-first", Some("develop"));
+        assert_eq!(app.rebase_base().as_deref(), Ok("develop"));
+        app.cosmos_branch = Some("fix/aaaa-first".to_string());
+        app.cosmos_base_branch = Some("release".to_string());
+        assert_eq!(app.begin_rebase().as_deref(), Some("release"));
+        assert_eq!(app.loading, LoadingState::Rebasing);
+        assert_eq!(app.begin_rebase(), None);
+
+        app.finish_rebase(Ok(RebaseOutcome {
+            branch: "fix/aaaa-first".to_string(),
+            drift: cosmos_adapters::git_ops::BranchDrift {
+                base_ref: "origin/release".to_string(),
+                behind: 3,
+                ahead: 1,
+            },
+            resolved_files: vec![PathBuf::from("src/lib.rs")],
+            quick_check_status: ImplementationQuickCheckStatus::Failed,
+            quick_check_command: Some("cargo check".to_string()),
+            quick_check_failure: Some("error[E0425]: cannot find value `x`".to_string()),
+            finalized: false,
+            usage: None,
+        }));
+        assert_eq!(app.loading, LoadingState::None);
+        let Overlay::Alert { title, message, .. } = &app.overlay else {
+            panic!("expected an alert");
+        };
+        assert_eq!(title, "Rebase not applied");
+        assert!(message.contains("• src/lib.rs"));
+        assert!(message.contains("cargo check failed"));
+        assert!(message.contains("E0425"));
+    }
+
+    #[test]
+    fn stacked_ship_records_entries_based_on_the_previous_branch() {
+        let mut app = make_test_app();
+        app.stacked_changes = true;
+        for (branch, base) in [
+            ("fix/aaaa-first", "main"),
+            ("fix/bbbb-second", "fix/aaaa-first"),
+        ] {
+            app.cosmos_branch = Some(branch.to_string());
+            app.cosmos_base_branch = Some(base.to_string());
+            app.start_ship();
+            app.push_stack_entry();
+        }
+
+        assert_eq!(app.workflow_step, WorkflowStep::Ship);
+        assert_eq!(app.ship_state.step, ShipStep::Stack);
+        assert_eq!(app.ship_state.stack_selected, 1);
+        assert!(app.cosmos_branch.is_none());
+        let bases: Vec<Option<&str>> = app
+            .stack
+            .iter()
+            .map(|entry| entry.options.base.as_deref())
+            .collect();
+        assert_eq!(bases, vec![Some("main"), Some("fix/aaaa-first")]);
+
+        app.ship_scroll_up();
+        app.ship_scroll_up();
+        assert_eq!(app.ship_state.stack_selected, 0);
+        assert_eq!(app.begin_stack_pr(0).unwrap().branch, "fix/aaaa-first");
+        assert!(app.begin_stack_pr(0).is_none());
+        app.finish_stack_pr(0, Some("https://example.com/pr/1".to_string()));
+        assert!(app.begin_stack_pr(0).is_none());
+
+        app.close_stack();
+        assert_eq!(app.workflow_step, WorkflowStep::Suggestions);
+        app.open_stack();
+        assert_eq!(app.ship_state.stack_selected, 1);
+    }
+
+    #[test]
+    fn package_scope_cycles_and_filters_suggestions() {
+        use cosmos_core::index::workspace::{WorkspaceKind, WorkspacePackage};
+        use cosmos_core::suggest::{Priority, Suggestion, SuggestionKind, SuggestionSource};
+
+        let mut app = make_test_app();
+        app.index.packages = ["api", "web"]
+            .iter()
+            .map(|name| WorkspacePackage {
+                name: name.to_string(),
+                root: PathBuf::from(format!("packages/{}", name)),
+                kind: WorkspaceKind::Pnpm,
+            })
+            .collect();
+        for file in ["packages/api/src/db.ts", "packages/web/src/app.tsx"] {
+            app.suggestions.add_llm_suggestion(Suggestion::new(
+                SuggestionKind::BugFix,
+                Priority::High,
+                PathBuf::from(file),
+                format!("Fix {}", file),
+                SuggestionSource::LlmDeep,
+            ));
+        }
+        assert_eq!(app.active_suggestions_for_display().len(), 2);
+
+        app.cycle_package_scope();
+        assert_eq!(app.package_scope.as_deref(), Some("api"));
+        let scoped = app.active_suggestions_for_display();
+        assert_eq!(scoped.len(), 1);
+        assert_eq!(scoped[0].file, PathBuf::from("packages/api/src/db.ts"));
+
+        app.cycle_package_scope();
+        assert_eq!(app.package_scope.as_deref(), Some("web"));
+        app.cycle_package_scope();
+        assert_eq!(app.package_scope, None);
+        assert_eq!(app.active_suggestions_for_display().len(), 2);
+    }
+
+    #[test]
+    fn impact_sort_orders_by_estimate_and_keeps_focus() {
+        use cosmos_core::suggest::{Priority, Suggestion, SuggestionKind, SuggestionSource};
+
+        let mut app = make_test_app();
+        for (priority, summary) in [
+            (Priority::Low, "Minor"),
+            (Priority::High, "Major"),
+            (Priority::Medium, "Middling"),
+        ] {
+            app.suggestions.add_llm_suggestion(Suggestion::new(
+                SuggestionKind::BugFix,
+                priority,
+                PathBuf::from("src/lib.rs"),
+                summary.to_string(),
+                SuggestionSource::LlmDeep,
+            ));
+        }
+        app.suggestion_selected = app
+            .active_suggestions_for_display()
+            .iter()
+            .position(|s| s.summary == "Middling")
+            .unwrap();
+
+        app.toggle_suggestion_sort();
+        assert_eq!(app.suggestion_sort, SuggestionSort::Impact);
+        let order: Vec<_> = app
+            .active_suggestions_for_display()
+            .iter()
+            .map(|s| s.summary.clone())
+            .collect();
+        assert_eq!(order, vec!["Major", "Middling", "Minor"]);
+        assert_eq!(app.suggestion_selected, 1);
+
+        app.toggle_suggestion_sort();
+        assert_eq!(app.suggestion_sort, SuggestionSort::Relevance);
+        assert_eq!(
+            app.active_suggestions_for_display()[app.suggestion_selected].summary,
+            "Middling"
+        );
+    }
+
+    #[test]
+    fn search_input_filters_suggestions_before_selection() {
+        use cosmos_core::suggest::{Priority, Suggestion, SuggestionKind, SuggestionSource};
+
+        let mut app = make_test_app();
+        for (kind, file) in [
+            (SuggestionKind::BugFix, "src/db.rs"),
+            (SuggestionKind::Refactoring, "src/db.rs"),
+            (SuggestionKind::BugFix, "web/app.ts"),
+        ] {
+            app.suggestions.add_llm_suggestion(Suggestion::new(
+                kind,
+                Priority::High,
+                PathBuf::from(file),
+                format!("Issue in {}", file),
+                SuggestionSource::LlmDeep,
+            ));
+        }
+        app.suggestion_selected = 2;
+
+        app.start_search();
+        for c in "kind:fix f:*.rs".chars() {
+            app.search_push(c);
+        }
+        let shown = app.active_suggestions_for_display();
+        assert_eq!(shown.len(), 1);
+        assert_eq!(shown[0].file, PathBuf::from("src/db.rs"));
+        assert_eq!(shown[0].kind, SuggestionKind::BugFix);
+        assert_eq!(app.suggestion_selected, 0);
+        assert!(app.search_query.is_empty());
+
+        // Enter keeps the filter; starting the search again refines it.
+        app.input_mode = InputMode::Normal;
+        app.start_search();
+        for _ in 0.." f:*.rs".len() {
+            app.search_pop();
+        }
+        assert_eq!(app.suggestion_filter.query(), "kind:fix");
+        assert_eq!(app.active_suggestions_for_display().len(), 2);
+
+        app.exit_search();
+        assert!(app.suggestion_filter.is_empty());
+        assert_eq!(app.active_suggestions_for_display().len(), 3);
+    }
+
+    #[test]
+    fn spinner_frames_advance_only_while_a_spinner_is_on_screen() {
+        let mut app = make_test_app();
+        app.tick_loading();
+        assert!(!app.spinner_visible());
+        assert_eq!(app.loading_frame, 0);
+
+        app.begin_ask_request();
+        assert!(app.spinner_visible());
+        app.tick_loading();
+        assert_eq!(app.loading_frame, 1);
+
+        assert!(app.toast_remaining().is_none());
+        app.show_toast("Saved");
+        assert!(app
+            .toast_remaining()
+            .is_some_and(|left| left <= TOAST_DURATION));
+    }
+
+    #[test]
+    fn splash_closes_itself_and_mascot_returns_after_idle() {
+        let mut app = make_test_app();
+        app.show_mascot(true);
+        assert!(app.splash_remaining().is_some());
+        assert!(!app.expire_splash());
+
+        app.mascot_shown_at = Instant::now().checked_sub(SPLASH_DURATION);
+        assert!(app.expire_splash());
+        assert_eq!(app.overlay, Overlay::None);
+
+        assert!(!app.mascot_idle_due());
+        app.last_input_at = Instant::now() - MASCOT_IDLE_AFTER;
+        assert!(app.mascot_idle_due());
+        app.show_mascot(false);
+        // The idle mascot waits for a key instead of timing out
+        assert_eq!(app.splash_remaining(), None);
+        assert!(!app.expire_splash());
+        assert!(!app.mascot_idle_due());
+    }
+
+    #[test]
+    fn finished_tasks_notify_only_when_unwatched() {
+        use cosmos_adapters::config::NotifyMode;
+        let mut app = make_test_app();
+        app.notify_finished("Suggestions ready");
+        assert_eq!(app.pending_notification, None, "off by default in the app");
+
+        app.notify_mode = NotifyMode::Both;
+        app.terminal_focused = Some(true);
+        app.notify_finished("Suggestions ready");
+        assert_eq!(app.pending_notification, None, "focused: user is watching");
+
+        app.terminal_focused = Some(false);
+        app.notify_finished("Fix applied");
+        assert_eq!(app.pending_notification.as_deref(), Some("Fix applied"));
+
+        app.pending_notification = None;
+        app.terminal_focused = None;
+        app.notify_finished("Fix applied");
+        assert!(
+            app.pending_notification.is_some(),
+            "no focus reports: always notify"
+        );
+    }
 }
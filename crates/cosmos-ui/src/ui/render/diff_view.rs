@@ -0,0 +1,311 @@
+use crate::ui::diff::{DiffHunk, DiffLine, DiffLineKind, DiffView};
+use crate::ui::helpers::{display_width, truncate_to_width};
+use crate::ui::theme::Theme;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Margin},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+/// Width of a line-number gutter column
+const GUTTER_WIDTH: usize = 5;
+
+pub(super) fn render_diff_view(frame: &mut Frame, view: &DiffView) {
+    let viewport = frame.area();
+    let area = viewport.inner(Margin {
+        vertical: 1,
+        horizontal: 2,
+    });
+    frame.render_widget(Clear, area);
+
+    let title = if view.fix_selection {
+        " Choose hunks to apply "
+    } else {
+        " Changes "
+    };
+    let block = Block::default()
+        .title(title)
+        .title_style(Style::default().fg(Theme::GREY_100))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Theme::GREY_400))
+        .style(Style::default().bg(Theme::GREY_900));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2), // File and hunk header
+            Constraint::Min(3),    // Hunk body
+            Constraint::Length(2), // Controls
+        ])
+        .split(inner);
+
+    let Some((file_index, hunk_index)) = view.selected_position() else {
+        return;
+    };
+    let file = &view.files[file_index];
+    let hunk = &file.hunks[hunk_index];
+    let width = chunks[1].width as usize;
+
+    let status = if hunk.accepted {
+        Span::styled(
+            " kept ",
+            Style::default().fg(Theme::GREY_900).bg(Theme::GREEN),
+        )
+    } else {
+        Span::styled(
+            " rejected ",
+            Style::default().fg(Theme::GREY_900).bg(Theme::RED),
+        )
+    };
+    let header = Line::from(vec![
+        Span::styled(
+            format!(
+                "  {}",
+                truncate_to_width(&file.path.display().to_string(), width.saturating_sub(48))
+            ),
+            Style::default()
+                .fg(Theme::WHITE)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(
+            format!(
+                "  hunk {}/{}  ",
+                view.selected + 1,
+                view.hunk_count().max(1)
+            ),
+            Style::default().fg(Theme::GREY_400),
+        ),
+        Span::styled(
+            format!("+{} ", hunk.added_count()),
+            Style::default().fg(Theme::GREEN),
+        ),
+        Span::styled(
+            format!("-{}  ", hunk.removed_count()),
+            Style::default().fg(Theme::RED),
+        ),
+        status,
+    ]);
+    let hunk_header = Line::from(Span::styled(
+        format!("  {}", hunk.header),
+        Style::default().fg(Theme::ACCENT),
+    ));
+    frame.render_widget(Paragraph::new(vec![header, hunk_header]), chunks[0]);
+
+    let body = if view.use_side_by_side(viewport.width) {
+        side_by_side_lines(hunk, width)
+    } else {
+        unified_lines(hunk, width)
+    };
+    let max_scroll = body.len().saturating_sub(chunks[1].height as usize);
+    let scroll = view.scroll.min(max_scroll);
+    frame.render_widget(Paragraph::new(body).scroll((scroll as u16, 0)), chunks[1]);
+
+    frame.render_widget(Paragraph::new(controls_lines(view)), chunks[2]);
+}
+
+fn unified_lines(hunk: &DiffHunk, width: usize) -> Vec<Line<'static>> {
+    let text_width = width.saturating_sub(GUTTER_WIDTH * 2 + 4);
+    hunk.lines
+        .iter()
+        .map(|line| {
+            let mut spans = vec![
+                gutter(line.old_line),
+                gutter(line.new_line),
+                marker(line.kind, hunk.accepted),
+            ];
+            spans.extend(segment_spans(line, text_width, hunk.accepted));
+            Line::from(spans)
+        })
+        .collect()
+}
+
+fn side_by_side_lines(hunk: &DiffHunk, width: usize) -> Vec<Line<'static>> {
+    let half = width.saturating_sub(1) / 2;
+    let text_width = half.saturating_sub(GUTTER_WIDTH + 3);
+    let mut rows = Vec::new();
+    let mut i = 0;
+    while i < hunk.lines.len() {
+        let line = &hunk.lines[i];
+        if line.kind == DiffLineKind::Context {
+            rows.push((Some(line), Some(line)));
+            i += 1;
+            continue;
+        }
+        // Pair a run of removals with the additions that follow it.
+        let removed: Vec<&DiffLine> = hunk.lines[i..]
+            .iter()
+            .take_while(|l| l.kind == DiffLineKind::Removed)
+            .collect();
+        i += removed.len();
+        let added: Vec<&DiffLine> = hunk.lines[i..]
+            .iter()
+            .take_while(|l| l.kind == DiffLineKind::Added)
+            .collect();
+        i += added.len();
+        for row in 0..removed.len().max(added.len()) {
+            rows.push((removed.get(row).copied(), added.get(row).copied()));
+        }
+    }
+
+    rows.into_iter()
+        .map(|(left, right)| {
+            let mut spans = half_spans(left, true, half, text_width, hunk.accepted);
+            spans.push(Span::styled("│", Style::default().fg(Theme::GREY_600)));
+            spans.extend(half_spans(right, false, half, text_width, hunk.accepted));
+            Line::from(spans)
+        })
+        .collect()
+}
+
+fn half_spans(
+    line: Option<&DiffLine>,
+    left: bool,
+    half: usize,
+    text_width: usize,
+    accepted: bool,
+) -> Vec<Span<'static>> {
+    let Some(line) = line else {
+        return vec![Span::raw(" ".repeat(half))];
+    };
+    let number = if left { line.old_line } else { line.new_line };
+    let mut spans = vec![gutter(number), marker(line.kind, accepted)];
+    let text = segment_spans(line, text_width, accepted);
+    let used = GUTTER_WIDTH
+        + 2
+        + text
+            .iter()
+            .map(|s| display_width(&s.content))
+            .sum::<usize>();
+    spans.extend(text);
+    spans.push(Span::raw(" ".repeat(half.saturating_sub(used))));
+    spans
+}
+
+fn gutter(number: Option<usize>) -> Span<'static> {
+    let text = number.map(|n| n.to_string()).unwrap_or_default();
+    Span::styled(
+        format!("{:>width$} ", text, width = GUTTER_WIDTH - 1),
+        Style::default().fg(Theme::GREY_600),
+    )
+}
+
+fn marker(kind: DiffLineKind, accepted: bool) -> Span<'static> {
+    let (symbol, style) = match kind {
+        DiffLineKind::Context => (" ", Style::default()),
+        DiffLineKind::Removed => ("-", Style::default().fg(Theme::RED)),
+        DiffLineKind::Added => ("+", Style::default().fg(Theme::GREEN)),
+    };
+    let style = if accepted || kind == DiffLineKind::Context {
+        style
+    } else {
+        Style::default().fg(Theme::GREY_600)
+    };
+    Span::styled(format!("{} ", symbol), style)
+}
+
+/// Styled spans for a line's text, truncated to `max_width`, with changed words highlighted.
+fn segment_spans(line: &DiffLine, max_width: usize, accepted: bool) -> Vec<Span<'static>> {
+    let (base, emphasis) = match line.kind {
+        DiffLineKind::Context => (Style::default().fg(Theme::GREY_300), None),
+        DiffLineKind::Removed => (
+            Style::default().fg(Theme::RED),
+            Some(Theme::DIFF_REMOVED_EMPHASIS),
+        ),
+        DiffLineKind::Added => (
+            Style::default().fg(Theme::GREEN),
+            Some(Theme::DIFF_ADDED_EMPHASIS),
+        ),
+    };
+    let rejected = !accepted && line.kind != DiffLineKind::Context;
+    let base = if rejected {
+        Style::default().fg(Theme::GREY_600)
+    } else {
+        base
+    };
+    let base = if rejected && line.kind == DiffLineKind::Added {
+        base.add_modifier(Modifier::CROSSED_OUT)
+    } else {
+        base
+    };
+
+    let mut spans = Vec::new();
+    let mut remaining = max_width;
+    for segment in &line.segments {
+        if remaining == 0 {
+            break;
+        }
+        let text = segment.text.replace('\t', "    ");
+        let fitted = if display_width(&text) > remaining {
+            truncate_to_width(&text, remaining)
+        } else {
+            text
+        };
+        remaining = remaining.saturating_sub(display_width(&fitted));
+        let style = match emphasis {
+            Some(bg) if segment.emphasized && !rejected => base.bg(bg),
+            _ => base,
+        };
+        spans.push(Span::styled(fitted, style));
+    }
+    spans
+}
+
+fn controls_lines(view: &DiffView) -> Vec<Line<'static>> {
+    let key_style = Style::default().fg(Theme::GREY_900).bg(Theme::GREY_300);
+    let label_style = Style::default().fg(Theme::GREY_400);
+    let mut spans = vec![
+        Span::raw("  "),
+        Span::styled(" n/p ", key_style),
+        Span::styled(" hunk  ", label_style),
+        Span::styled(" ↑↓ ", key_style),
+        Span::styled(" scroll  ", label_style),
+        Span::styled(" s ", key_style),
+        Span::styled(" layout  ", label_style),
+        Span::styled(" y ", key_style),
+        Span::styled(" copy  ", label_style),
+    ];
+    if view.editable {
+        spans.extend([
+            Span::styled(" a ", key_style),
+            Span::styled(" keep  ", label_style),
+            Span::styled(" r ", key_style),
+            Span::styled(" reject  ", label_style),
+            Span::styled(" ↵ ", key_style),
+            Span::styled(" apply  ", label_style),
+        ]);
+    }
+    spans.extend([
+        Span::styled(" esc ", key_style),
+        Span::styled(
+            if view.fix_selection {
+                " cancel apply"
+            } else {
+                " close"
+            },
+            label_style,
+        ),
+    ]);
+
+    let summary = if view.editable && view.rejected_count() > 0 {
+        let outcome = if view.fix_selection {
+            "left out of the fix"
+        } else {
+            "reverted"
+        };
+        Span::styled(
+            format!(
+                "  {} hunk(s) will be {} when you press ↵",
+                view.rejected_count(),
+                outcome
+            ),
+            Style::default().fg(Theme::YELLOW),
+        )
+    } else {
+        Span::raw("")
+    };
+    vec![Line::from(summary), Line::from(spans)]
+}
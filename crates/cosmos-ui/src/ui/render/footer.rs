@@ -1,5 +1,6 @@
+use crate::ui::helpers::{display_width, truncate_to_width};
 use crate::ui::theme::Theme;
-use crate::ui::{ActivePanel, App, LoadingState, ShipStep, WorkflowStep};
+use crate::ui::{ActivePanel, App, FindingField, LoadingState, ShipStep, WorkflowStep};
 use ratatui::{
     layout::Rect,
     style::Style,
@@ -27,6 +28,7 @@ struct FooterCacheKey {
     ship_step: ShipStep,
     has_pending_changes: bool,
     has_update_available: bool,
+    cancellable: bool,
     ai_available: bool,
 }
 
@@ -62,7 +64,7 @@ impl FooterButton {
 
     fn width(&self) -> usize {
         // " key " + " label " + "  " (spacing between buttons)
-        self.key.chars().count() + 2 + self.label.chars().count() + 3
+        display_width(self.key) + 2 + display_width(self.label) + 3
     }
 
     fn to_spans(&self) -> Vec<Span<'static>> {
@@ -125,11 +127,7 @@ pub(super) fn render_footer(frame: &mut Frame, area: Rect, app: &App) {
     };
 
     // Calculate status width
-    let branch_display = if app.context.branch.len() > 20 {
-        format!("{}…", &app.context.branch[..19])
-    } else {
-        app.context.branch.clone()
-    };
+    let branch_display = truncate_to_width(&app.context.branch, 20);
 
     let stale_text = if app.git_refresh_error.is_some() {
         "  status stale"
@@ -142,10 +140,10 @@ pub(super) fn render_footer(frame: &mut Frame, area: Rect, app: &App) {
     } else {
         String::new()
     };
-    let session_cost_len = session_cost_text.chars().count();
+    let session_cost_len = display_width(&session_cost_text);
 
     // Base status: "  project ⎇ branch"
-    let base_status_width = 2 + project_name.chars().count() + 3 + branch_display.chars().count();
+    let base_status_width = 2 + display_width(project_name) + 3 + display_width(&branch_display);
 
     let cache_key = FooterCacheKey {
         available_width,
@@ -164,6 +162,7 @@ pub(super) fn render_footer(frame: &mut Frame, area: Rect, app: &App) {
         ship_step: app.ship_state.step,
         has_pending_changes: !app.pending_changes.is_empty(),
         has_update_available: app.update_available.is_some(),
+        cancellable: app.tasks.active().is_some(),
         ai_available: cosmos_engine::llm::is_available(),
     };
 
@@ -263,22 +262,13 @@ pub(super) fn render_footer(frame: &mut Frame, area: Rect, app: &App) {
 
         // Truncate branch name to fit
         let remaining_for_branch =
-            space_for_status.saturating_sub(2 + project_name.chars().count() + 3);
-        let truncated_branch = if branch_display.chars().count() > remaining_for_branch {
-            if remaining_for_branch > 1 {
-                format!(
-                    "{}…",
-                    branch_display
-                        .chars()
-                        .take(remaining_for_branch.saturating_sub(1))
-                        .collect::<String>()
-                )
-            } else {
+            space_for_status.saturating_sub(2 + display_width(project_name) + 3);
+        let truncated_branch =
+            if display_width(&branch_display) > remaining_for_branch && remaining_for_branch <= 1 {
                 String::new()
-            }
-        } else {
-            branch_display.clone()
-        };
+            } else {
+                truncate_to_width(&branch_display, remaining_for_branch)
+            };
 
         if !truncated_branch.is_empty() {
             spans.push(Span::styled(
@@ -288,9 +278,9 @@ pub(super) fn render_footer(frame: &mut Frame, area: Rect, app: &App) {
         }
 
         // Add stale indicator if it fits
-        let current_status_len: usize = spans.iter().map(|s| s.content.chars().count()).sum();
+        let current_status_len: usize = spans.iter().map(|s| display_width(&s.content)).sum();
         if !stale_text.is_empty()
-            && current_status_len + stale_text.chars().count() <= space_for_status
+            && current_status_len + display_width(stale_text) <= space_for_status
         {
             spans.push(Span::styled(
                 stale_text.to_string(),
@@ -299,7 +289,7 @@ pub(super) fn render_footer(frame: &mut Frame, area: Rect, app: &App) {
         }
 
         // Add session cost if it fits
-        let current_status_len: usize = spans.iter().map(|s| s.content.chars().count()).sum();
+        let current_status_len: usize = spans.iter().map(|s| display_width(&s.content)).sum();
         if !session_cost_text.is_empty()
             && current_status_len + session_cost_len <= space_for_status
         {
@@ -311,7 +301,7 @@ pub(super) fn render_footer(frame: &mut Frame, area: Rect, app: &App) {
     }
 
     // Add spacer
-    let current_len: usize = spans.iter().map(|s| s.content.chars().count()).sum();
+    let current_len: usize = spans.iter().map(|s| display_width(&s.content)).sum();
     let spacer_len = available_width.saturating_sub(current_len + used_width);
     if spacer_len > 0 {
         spans.push(Span::styled(" ".repeat(spacer_len), Style::default()));
@@ -355,11 +345,23 @@ fn get_primary_buttons(app: &App) -> Vec<FooterButton> {
             if app.loading == LoadingState::GeneratingFix {
                 vec![]
             } else {
-                vec![primary_button("↵", "preview")]
+                let expanded = app
+                    .selected_suggestion()
+                    .is_some_and(|s| Some(s.id) == app.expanded_suggestion_id);
+                vec![primary_button(
+                    "↵",
+                    if expanded { "collapse" } else { "expand" },
+                )]
             }
         }
         WorkflowStep::Review => {
-            if app.review_passed() {
+            if app.review_state.editing == Some(FindingField::Description) {
+                vec![primary_button("Esc", "done")]
+            } else if app.review_state.editing.is_some() {
+                vec![primary_button("↵", "done")]
+            } else if app.is_pr_review() {
+                vec![primary_button("↵", "post review")]
+            } else if app.review_passed() {
                 vec![primary_button("↵", "ship")]
             } else if app.review_state.verification_failed {
                 vec![primary_button("↵", "override")]
@@ -368,7 +370,29 @@ fn get_primary_buttons(app: &App) -> Vec<FooterButton> {
             }
         }
         WorkflowStep::Ship => match app.ship_state.step {
+            ShipStep::Confirm if app.ship_state.editing.is_some_and(|f| f.is_multiline()) => {
+                vec![primary_button("Esc", "done")]
+            }
+            ShipStep::Confirm if app.ship_state.editing.is_some() => {
+                vec![primary_button("↵", "done")]
+            }
+            ShipStep::Commits => vec![primary_button("↵", "use these commits")],
+            ShipStep::Confirm if app.stacked_changes => {
+                vec![primary_button("↵", "commit to stack")]
+            }
+            ShipStep::Confirm if app.ship_state.follow_up.is_some() => {
+                vec![primary_button("↵", "push to PR")]
+            }
             ShipStep::Confirm => vec![primary_button("↵", "ship")],
+            ShipStep::Stack => match app.stack.get(app.ship_state.stack_selected) {
+                Some(entry) if entry.pr_url.is_some() => vec![primary_button("↵", "open PR")],
+                Some(entry) if !entry.opening => vec![primary_button("↵", "push & open PR")],
+                _ => vec![],
+            },
+            ShipStep::HooksFailed if !app.ship_hook_fixes_to_stage().is_empty() => {
+                vec![primary_button("↵", "stage fixes & retry")]
+            }
+            ShipStep::HooksFailed => vec![primary_button("↵", "retry")],
             ShipStep::Done => vec![primary_button("↵", "open PR")],
             _ => vec![],
         },
@@ -382,17 +406,64 @@ fn get_secondary_buttons(app: &App) -> Vec<FooterButton> {
     }
 
     match app.workflow_step {
-        WorkflowStep::Suggestions => vec![],
+        WorkflowStep::Suggestions => {
+            if app.selected_suggestion().is_some() && app.loading != LoadingState::GeneratingFix {
+                vec![secondary_button("o", "plan")]
+            } else {
+                vec![]
+            }
+        }
         WorkflowStep::Review => {
-            if app.review_passed() || app.review_state.verification_failed {
+            if app.review_state.editing.is_some() {
+                return vec![];
+            }
+            let mut buttons = if app.review_passed() || app.review_state.verification_failed {
                 vec![secondary_button("Esc", "back")]
             } else {
-                vec![hint_button("␣", "select"), secondary_button("Esc", "back")]
+                vec![
+                    hint_button("␣", "select"),
+                    hint_button("t/e/-", "edit/downgrade"),
+                    secondary_button("Esc", "back"),
+                ]
+            };
+            if !app.review_state.files.is_empty() && !app.review_state.reviewing {
+                buttons.insert(0, hint_button("d", "diff"));
             }
+            buttons
         }
         WorkflowStep::Ship => match app.ship_state.step {
-            ShipStep::Confirm => vec![secondary_button("Esc", "back")],
-            ShipStep::Done => vec![secondary_button("Esc", "done")],
+            ShipStep::Confirm if app.ship_state.editing.is_some() => vec![],
+            ShipStep::Confirm if app.ship_state.follow_up.is_some() => vec![
+                hint_button("e", "edit comment"),
+                if app.ship_state.split_commits {
+                    hint_button("m", "edit commits")
+                } else {
+                    hint_button("c/g", "edit/write commit")
+                },
+                secondary_button("Esc", "back"),
+            ],
+            ShipStep::Confirm if app.ship_state.split_commits => vec![
+                hint_button("d", "draft"),
+                hint_button("b/r/l", "base/reviewers/labels"),
+                hint_button("t/e", "edit PR"),
+                hint_button("m", "edit commits"),
+                secondary_button("Esc", "back"),
+            ],
+            ShipStep::Confirm => vec![
+                hint_button("d", "draft"),
+                hint_button("b/r/l", "base/reviewers/labels"),
+                hint_button("t/e", "edit PR"),
+                hint_button("c/g", "edit/write commit"),
+                hint_button("m", "split commits"),
+                secondary_button("Esc", "back"),
+            ],
+            ShipStep::Commits => vec![
+                hint_button("␣", "keep/leave out"),
+                hint_button("J/K", "move"),
+                secondary_button("Esc", "one commit"),
+            ],
+            ShipStep::HooksFailed => vec![secondary_button("Esc", "back")],
+            ShipStep::Done | ShipStep::Stack => vec![secondary_button("Esc", "done")],
             _ => vec![],
         },
     }
@@ -407,12 +478,19 @@ fn get_hint_buttons(app: &App) -> Vec<FooterButton> {
     {
         hints.push(hint_button("r", "refresh"));
         hints.push(hint_button("m", "mode"));
+        if !app.stack.is_empty() {
+            hints.push(hint_button("s", "stack"));
+        }
     }
 
     if !cosmos_engine::llm::is_available() {
         hints.push(hint_button("k", "API key"));
     }
 
+    if !app.workspace_trusted {
+        hints.push(hint_button("T", "trust repo"));
+    }
+
     hints.push(hint_button("Tab", "panel"));
     hints
 }
@@ -421,6 +499,26 @@ fn get_hint_buttons(app: &App) -> Vec<FooterButton> {
 fn get_optional_buttons(app: &App) -> Vec<FooterButton> {
     let mut buttons = Vec::new();
 
+    if app.tasks.active().is_some() {
+        buttons.push(FooterButton::new(
+            "x",
+            "cancel",
+            Theme::GREY_900,
+            Theme::RED,
+            Theme::GREY_400,
+        ));
+    }
+
+    if app.loading == LoadingState::GeneratingFix {
+        buttons.push(FooterButton::new(
+            "L",
+            "log",
+            Theme::GREY_900,
+            Theme::GREY_300,
+            Theme::GREY_400,
+        ));
+    }
+
     if !app.pending_changes.is_empty() {
         buttons.push(FooterButton::new(
             "u",
@@ -1,14 +1,14 @@
 use crate::ui::theme::Theme;
 use crate::ui::App;
 use ratatui::{
-    layout::Rect,
+    layout::{Alignment, Rect},
     style::{Modifier, Style},
     text::{Line, Span},
     widgets::Paragraph,
     Frame,
 };
 
-pub(super) fn render_header(frame: &mut Frame, area: Rect, _app: &App) {
+pub(super) fn render_header(frame: &mut Frame, area: Rect, app: &App) {
     // Build spans for the logo
     let spans = vec![Span::styled(
         format!("   {}", Theme::COSMOS_LOGO),
@@ -21,4 +21,41 @@ pub(super) fn render_header(frame: &mut Frame, area: Rect, _app: &App) {
 
     let header = Paragraph::new(lines).style(Style::default().bg(Theme::BG));
     frame.render_widget(header, area);
+
+    if let Some(trend) = health_trend(&app.health_history) {
+        let trend = Paragraph::new(vec![Line::from(""), trend]).alignment(Alignment::Right);
+        frame.render_widget(trend, area);
+    }
+}
+
+/// "health 78 ▃▅▆█ +4": the latest score, its recent trend, and the change since the last run.
+fn health_trend(history: &[u8]) -> Option<Line<'static>> {
+    let &latest = history.last()?;
+    let color = match latest {
+        80..=100 => Theme::GREEN,
+        60..=79 => Theme::YELLOW,
+        _ => Theme::RED,
+    };
+    let mut spans = vec![
+        Span::styled("health ", Style::default().fg(Theme::GREY_500)),
+        Span::styled(
+            latest.to_string(),
+            Style::default().fg(color).add_modifier(Modifier::BOLD),
+        ),
+    ];
+    if history.len() > 1 {
+        spans.push(Span::styled(
+            format!(" {}", cosmos_core::health::sparkline(history)),
+            Style::default().fg(Theme::GREY_400),
+        ));
+        let delta = latest as i16 - history[history.len() - 2] as i16;
+        if delta != 0 {
+            spans.push(Span::styled(
+                format!(" {:+}", delta),
+                Style::default().fg(if delta > 0 { Theme::GREEN } else { Theme::RED }),
+            ));
+        }
+    }
+    spans.push(Span::raw("   "));
+    Some(Line::from(spans))
 }
@@ -1,9 +1,12 @@
-use crate::ui::helpers::{wrap_text, wrap_text_variable_width};
+use crate::ui::helpers::{
+    display_width, format_token_count, truncate_to_width, wrap_text, wrap_text_variable_width,
+};
+use crate::ui::links;
 use crate::ui::markdown;
 use crate::ui::theme::Theme;
 use crate::ui::{
-    ActivePanel, App, AskCosmosState, LoadingState, ShipStep, WorkflowStep, ASK_STARTER_QUESTIONS,
-    SPINNER_FRAMES,
+    ActivePanel, App, AskCosmosState, FindingField, InputMode, LoadingState, ShipField, ShipStep,
+    WorkflowStep, ASK_STARTER_QUESTIONS, SPINNER_FRAMES,
 };
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
@@ -14,7 +17,9 @@ use ratatui::{
 };
 use std::cell::RefCell;
 use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
 use std::hash::{Hash, Hasher};
+use std::rc::Rc;
 
 /// Cached main layout to avoid recomputing on every frame
 struct CachedMainLayout {
@@ -27,11 +32,38 @@ struct CachedAskMarkdown {
     response_hash: u64,
     width: usize,
     padded_lines: Vec<Line<'static>>,
+    /// Index of the latest question line (earlier turns come before it)
+    latest_start: usize,
+}
+
+/// Wrapped title and description of the review finding at the cursor
+struct CachedFindingDetail {
+    key: u64,
+    title: Rc<[String]>,
+    description: Rc<[String]>,
 }
 
+/// Wrapped suggestion summaries by suggestion id, summary hash, and line widths
+type SummaryWrapKey = (uuid::Uuid, u64, usize, usize);
+
+/// Entries kept before the wrap cache starts over; resizes leave stale widths behind.
+const SUMMARY_WRAP_CACHE_LIMIT: usize = 4096;
+
 thread_local! {
     static MAIN_LAYOUT_CACHE: RefCell<Option<CachedMainLayout>> = const { RefCell::new(None) };
     static ASK_MARKDOWN_CACHE: RefCell<Option<CachedAskMarkdown>> = const { RefCell::new(None) };
+    static SUMMARY_WRAP_CACHE: RefCell<HashMap<SummaryWrapKey, Rc<[String]>>> =
+        RefCell::new(HashMap::new());
+    static FINDING_DETAIL_CACHE: RefCell<Option<CachedFindingDetail>> =
+        const { RefCell::new(None) };
+}
+
+/// Clear the layout and wrapped-text caches; see [`super::reset_caches`]
+pub(super) fn reset_caches() {
+    MAIN_LAYOUT_CACHE.with(|cache| cache.borrow_mut().take());
+    ASK_MARKDOWN_CACHE.with(|cache| cache.borrow_mut().take());
+    SUMMARY_WRAP_CACHE.with(|cache| cache.borrow_mut().clear());
+    FINDING_DETAIL_CACHE.with(|cache| cache.borrow_mut().take());
 }
 
 const ASK_TARGET_PERCENT: u16 = 30;
@@ -116,7 +148,8 @@ fn render_suggestions_panel(frame: &mut Frame, area: Rect, app: &App) {
     let content_height = area.height.saturating_sub(2) as usize;
     let inner_width = area.width.saturating_sub(4) as usize;
 
-    let mut lines = vec![];
+    // Sized for a full panel so pushing lines doesn't regrow the buffer mid-frame.
+    let mut lines = Vec::with_capacity(content_height + 8);
 
     // Render content based on workflow step
     match app.workflow_step {
@@ -131,8 +164,11 @@ fn render_suggestions_panel(frame: &mut Frame, area: Rect, app: &App) {
         }
     }
 
-    // Build title with workflow breadcrumbs in the border.
-    let title = render_workflow_title(app.workflow_step);
+    // Build title with workflow breadcrumbs in the border, then the filter if one is set.
+    let mut title = render_workflow_title(app.workflow_step);
+    if let Some(filter) = suggestion_filter_title(app) {
+        title.push_str(&format!("· {} ", filter));
+    }
 
     let block = Block::default()
         .title(title)
@@ -145,6 +181,26 @@ fn render_suggestions_panel(frame: &mut Frame, area: Rect, app: &App) {
     frame.render_widget(paragraph, area);
 }
 
+/// Filter state for the Suggestions title: the query while it's typed, then what it matches.
+fn suggestion_filter_title(app: &App) -> Option<String> {
+    if app.workflow_step != WorkflowStep::Suggestions {
+        return None;
+    }
+    let filter = &app.suggestion_filter;
+    if app.input_mode == InputMode::Search && app.active_panel == ActivePanel::Suggestions {
+        return Some(format!("/{}▏", filter.query()));
+    }
+    if filter.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "filter: {} ({} of {})",
+        filter.describe(),
+        app.active_suggestions_for_display().len(),
+        app.scoped_suggestions().len()
+    ))
+}
+
 /// Build the workflow title for the border.
 fn render_workflow_title(current: WorkflowStep) -> String {
     let steps = [
@@ -178,7 +234,7 @@ fn render_suggestions_content<'a>(
     visible_height: usize,
     inner_width: usize,
 ) {
-    let suggestions = app.suggestions.active_suggestions();
+    let suggestions = app.active_suggestions_for_display();
 
     // Top padding for breathing room
     lines.push(Line::from(""));
@@ -198,6 +254,57 @@ fn render_suggestions_content<'a>(
             Style::default().fg(Theme::GREY_500),
         ),
     ]));
+    lines.push(Line::from(vec![
+        Span::styled("    Sort: ", Style::default().fg(Theme::GREY_500)),
+        Span::styled(
+            app.suggestion_sort.label(),
+            Style::default().fg(Theme::GREY_300),
+        ),
+        Span::styled(
+            format!("  (press I for {})", app.suggestion_sort.toggle().label()),
+            Style::default().fg(Theme::GREY_500),
+        ),
+    ]));
+    if !app.index.packages.is_empty() {
+        let scope_label = app
+            .scoped_package()
+            .map(|p| format!("{} ({})", p.name, p.kind.label()))
+            .unwrap_or_else(|| format!("all {} packages", app.index.packages.len()));
+        lines.push(Line::from(vec![
+            Span::styled("    Package: ", Style::default().fg(Theme::GREY_500)),
+            Span::styled(scope_label, Style::default().fg(Theme::GREY_300)),
+            Span::styled(
+                "  (press p to switch)",
+                Style::default().fg(Theme::GREY_500),
+            ),
+        ]));
+    }
+    let new_count = suggestions
+        .iter()
+        .filter(|s| app.new_suggestion_ids.contains(&s.id))
+        .count();
+    if new_count > 0 {
+        lines.push(Line::from(vec![
+            Span::styled("    ", Style::default()),
+            Span::styled(
+                format!(" {} new since last scan ", new_count),
+                Style::default().fg(Theme::GREY_900).bg(Theme::ACCENT),
+            ),
+        ]));
+    }
+    if !app.snoozes.snoozes.is_empty() {
+        lines.push(Line::from(vec![
+            Span::styled("    Snoozed: ", Style::default().fg(Theme::GREY_500)),
+            Span::styled(
+                app.snoozes.snoozes.len().to_string(),
+                Style::default().fg(Theme::GREY_300),
+            ),
+            Span::styled(
+                "  (press Z to review)",
+                Style::default().fg(Theme::GREY_500),
+            ),
+        ]));
+    }
     lines.push(Line::from(""));
 
     // Check for loading states relevant to suggestions panel
@@ -205,6 +312,7 @@ fn render_suggestions_content<'a>(
         LoadingState::GeneratingSuggestions => Some("Generating suggestions...".to_string()),
         LoadingState::Resetting => Some("Resetting cache...".to_string()),
         LoadingState::SwitchingBranch => Some("Switching to main branch...".to_string()),
+        LoadingState::Rebasing => Some("Rebasing onto the base branch...".to_string()),
         LoadingState::None => None,
         _ => None,
     };
@@ -224,10 +332,9 @@ fn render_suggestions_content<'a>(
             for raw in display_lines {
                 let style = if raw.starts_with("• Live Agent Activity")
                     || raw.starts_with("• Updated Plan")
+                    || raw.ends_with("thinking:")
                 {
                     Style::default().fg(Theme::GREY_200)
-                } else if raw.ends_with("thinking:") {
-                    Style::default().fg(Theme::GREY_200)
                 } else if raw.contains("☑") {
                     Style::default().fg(Theme::GREY_300)
                 } else if raw.contains("☐") {
@@ -269,6 +376,14 @@ fn render_suggestions_content<'a>(
         return;
     }
 
+    if suggestions.is_empty() && !app.suggestion_filter.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "    No suggestions match the filter. Press / to change it or Esc to clear it.",
+            Style::default().fg(Theme::GREY_400),
+        )));
+        return;
+    }
+
     if suggestions.is_empty() {
         let has_ai = cosmos_engine::llm::is_available();
 
@@ -278,8 +393,8 @@ fn render_suggestions_content<'a>(
         let row_width = card_width;
 
         let center_row = |text: &str| -> String {
-            let clipped: String = text.chars().take(row_width).collect();
-            let len = clipped.chars().count();
+            let clipped = truncate_to_width(text, row_width);
+            let len = display_width(&clipped);
             if len >= row_width {
                 return clipped;
             }
@@ -397,130 +512,38 @@ fn render_suggestions_content<'a>(
         return;
     }
 
-    let mut line_count = 0;
     // Use nearly full width - just leave small margin
     let text_width = inner_width.saturating_sub(4);
-
-    for (i, suggestion) in suggestions.iter().enumerate().skip(app.suggestion_scroll) {
-        if line_count >= visible_height.saturating_sub(4) {
+    // Rows left under the header, keeping the last two for the scroll indicator.
+    let budget = visible_height.saturating_sub(lines.len() + 2);
+
+    // Only the cards in view are built. The window starts at the scroll offset, but moves down
+    // when taller cards (long summaries, an expanded one) would push the selection out of view.
+    let selected = app.suggestion_selected.min(suggestions.len() - 1);
+    let mut window = VecDeque::new();
+    let mut window_height = 0;
+    for (i, suggestion) in suggestions
+        .iter()
+        .enumerate()
+        .take(selected + 1)
+        .skip(app.suggestion_scroll.min(selected))
+    {
+        let card = suggestion_card_lines(app, suggestion, i == selected && is_active, text_width);
+        window_height += card.len();
+        window.push_back(card);
+    }
+    while window_height > budget && window.len() > 1 {
+        window_height -= window.pop_front().map_or(0, |card| card.len());
+    }
+    for suggestion in suggestions.iter().skip(selected + 1) {
+        if window_height >= budget {
             break;
         }
-
-        let is_selected = i == app.suggestion_selected && is_active;
-
-        // Kind label with subtle styling - brighter when selected
-        let kind_label = suggestion.kind.label();
-        let kind_style = if is_selected {
-            Style::default().fg(Theme::GREY_100)
-        } else {
-            Style::default().fg(Theme::GREY_500)
-        };
-
-        let criticality_label = match suggestion.criticality {
-            cosmos_core::suggest::Criticality::Critical => "CRIT",
-            cosmos_core::suggest::Criticality::High => "HIGH",
-            cosmos_core::suggest::Criticality::Medium => "MED",
-            cosmos_core::suggest::Criticality::Low => "LOW",
-        };
-        let criticality_style = match suggestion.criticality {
-            cosmos_core::suggest::Criticality::Critical => {
-                Style::default().fg(Theme::GREY_900).bg(Theme::RED)
-            }
-            cosmos_core::suggest::Criticality::High => {
-                Style::default().fg(Theme::GREY_900).bg(Theme::YELLOW)
-            }
-            cosmos_core::suggest::Criticality::Medium => {
-                Style::default().fg(Theme::GREY_900).bg(Theme::GREY_300)
-            }
-            cosmos_core::suggest::Criticality::Low => {
-                Style::default().fg(Theme::GREY_900).bg(Theme::GREY_400)
-            }
-        };
-
-        let category_label = suggestion.category.label().to_ascii_uppercase();
-        let category_style = match suggestion.category {
-            cosmos_core::suggest::SuggestionCategory::Bug => {
-                Style::default().fg(Theme::GREY_900).bg(Theme::GREY_300)
-            }
-            cosmos_core::suggest::SuggestionCategory::Security => {
-                Style::default().fg(Theme::GREY_900).bg(Theme::ACCENT)
-            }
-        };
-
-        // Multi-file indicator
-        let multi_file_indicator = if suggestion.is_multi_file() {
-            format!(" [{}]", suggestion.file_count())
-        } else {
-            String::new()
-        };
-        let multi_file_style = Style::default().fg(Theme::ACCENT);
-
-        // Summary text style - selection via styling only (bold + bright)
-        let summary_style = if is_selected {
-            Style::default()
-                .fg(Theme::WHITE)
-                .add_modifier(Modifier::BOLD)
-        } else {
-            Style::default().fg(Theme::GREY_300)
-        };
-
-        // First line has: padding + badges + kind + multi-file + ": "
-        let first_prefix_len = 2
-            + 2
-            + criticality_label.len()
-            + 2
-            + 2
-            + category_label.len()
-            + 2
-            + kind_label.len()
-            + multi_file_indicator.len()
-            + 2;
-        let first_line_width = text_width.saturating_sub(first_prefix_len);
-        // Continuation lines just have small indent (5 chars)
-        let cont_indent = "     ";
-        let cont_line_width = text_width.saturating_sub(5);
-
-        // Use variable width wrapping: first line is shorter due to prefix
-        let wrapped =
-            wrap_text_variable_width(&suggestion.summary, first_line_width, cont_line_width);
-
-        // Render first line with kind and multi-file indicator
-        if let Some(first_line) = wrapped.first() {
-            let mut spans = vec![
-                Span::styled("  ", Style::default()),
-                Span::styled(format!(" {} ", criticality_label), criticality_style),
-                Span::styled(" ", Style::default()),
-                Span::styled(format!(" {} ", category_label), category_style),
-                Span::styled(" ", Style::default()),
-                Span::styled(kind_label, kind_style),
-            ];
-            if suggestion.is_multi_file() {
-                spans.push(Span::styled(multi_file_indicator, multi_file_style));
-            }
-            spans.push(Span::styled(": ", kind_style));
-            spans.push(Span::styled(first_line.clone(), summary_style));
-            lines.push(Line::from(spans));
-            line_count += 1;
-        }
-
-        // Render ALL continuation lines (no artificial limit)
-        for wrapped_line in wrapped.iter().skip(1) {
-            if line_count >= visible_height.saturating_sub(4) {
-                break;
-            }
-            lines.push(Line::from(vec![
-                Span::styled(cont_indent, Style::default()),
-                Span::styled(wrapped_line.clone(), summary_style),
-            ]));
-            line_count += 1;
-        }
-
-        // Add empty line for spacing between suggestions
-        if line_count < visible_height.saturating_sub(4) {
-            lines.push(Line::from(""));
-            line_count += 1;
-        }
+        let card = suggestion_card_lines(app, suggestion, false, text_width);
+        window_height += card.len();
+        window.push_back(card);
     }
+    lines.extend(window.into_iter().flatten().take(budget));
 
     // Bottom hints
     let content_lines = lines.len();
@@ -540,6 +563,291 @@ fn render_suggestions_content<'a>(
     }
 }
 
+/// Summary wrapped to the list's widths, reused across frames until the text or width changes.
+fn wrapped_suggestion_summary(
+    suggestion: &cosmos_core::suggest::Suggestion,
+    first_width: usize,
+    cont_width: usize,
+) -> Rc<[String]> {
+    let mut hasher = DefaultHasher::new();
+    suggestion.summary.hash(&mut hasher);
+    let key = (suggestion.id, hasher.finish(), first_width, cont_width);
+    SUMMARY_WRAP_CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        if let Some(wrapped) = cache.get(&key) {
+            return Rc::clone(wrapped);
+        }
+        if cache.len() >= SUMMARY_WRAP_CACHE_LIMIT {
+            cache.clear();
+        }
+        let wrapped: Rc<[String]> =
+            wrap_text_variable_width(&suggestion.summary, first_width, cont_width).into();
+        cache.insert(key, Rc::clone(&wrapped));
+        wrapped
+    })
+}
+
+/// Lines for one suggestion in the list: badges and summary, its details when expanded, and
+/// a spacer.
+fn suggestion_card_lines(
+    app: &App,
+    suggestion: &cosmos_core::suggest::Suggestion,
+    is_selected: bool,
+    text_width: usize,
+) -> Vec<Line<'static>> {
+    // Kind label with subtle styling - brighter when selected
+    let kind_label = suggestion.kind.label();
+    let kind_style = if is_selected {
+        Style::default().fg(Theme::GREY_100)
+    } else {
+        Style::default().fg(Theme::GREY_500)
+    };
+
+    // Badges are padded here rather than formatted, so a card costs no allocations for them.
+    let criticality_badge = match suggestion.criticality {
+        cosmos_core::suggest::Criticality::Critical => " CRIT ",
+        cosmos_core::suggest::Criticality::High => " HIGH ",
+        cosmos_core::suggest::Criticality::Medium => " MED ",
+        cosmos_core::suggest::Criticality::Low => " LOW ",
+    };
+    let criticality_style = match suggestion.criticality {
+        cosmos_core::suggest::Criticality::Critical => {
+            Style::default().fg(Theme::GREY_900).bg(Theme::RED)
+        }
+        cosmos_core::suggest::Criticality::High => {
+            Style::default().fg(Theme::GREY_900).bg(Theme::YELLOW)
+        }
+        cosmos_core::suggest::Criticality::Medium => {
+            Style::default().fg(Theme::GREY_900).bg(Theme::GREY_300)
+        }
+        cosmos_core::suggest::Criticality::Low => {
+            Style::default().fg(Theme::GREY_900).bg(Theme::GREY_400)
+        }
+    };
+
+    let (category_badge, category_style) = match suggestion.category {
+        cosmos_core::suggest::SuggestionCategory::Bug => (
+            " BUG ",
+            Style::default().fg(Theme::GREY_900).bg(Theme::GREY_300),
+        ),
+        cosmos_core::suggest::SuggestionCategory::Security => (
+            " SECURITY ",
+            Style::default().fg(Theme::GREY_900).bg(Theme::ACCENT),
+        ),
+    };
+
+    // Multi-file indicator
+    let multi_file_indicator = if suggestion.is_multi_file() {
+        format!(" [{}]", suggestion.file_count())
+    } else {
+        String::new()
+    };
+    let multi_file_style = Style::default().fg(Theme::ACCENT);
+
+    // Rating marker from +/-/i feedback
+    let (feedback_marker, feedback_style) = match app.suggestion_feedback.get(&suggestion.id) {
+        Some(cosmos_core::suggest::SuggestionFeedback::Good) => {
+            (" ✓", Style::default().fg(Theme::GREEN))
+        }
+        Some(cosmos_core::suggest::SuggestionFeedback::Bad) => {
+            (" ✗", Style::default().fg(Theme::RED))
+        }
+        Some(cosmos_core::suggest::SuggestionFeedback::Irrelevant) => {
+            (" ∅", Style::default().fg(Theme::GREY_500))
+        }
+        None => ("", Style::default()),
+    };
+
+    // Badge for findings the last background scan added
+    let new_marker = if app.new_suggestion_ids.contains(&suggestion.id) {
+        " NEW"
+    } else {
+        ""
+    };
+    let new_style = Style::default()
+        .fg(Theme::ACCENT)
+        .add_modifier(Modifier::BOLD);
+
+    // Count of runs that reported the finding, when earlier runs already had it
+    let redetected_marker = suggestion
+        .redetection
+        .as_ref()
+        .map(|seen| format!(" ↻{}", seen.runs))
+        .unwrap_or_default();
+
+    // Summary text style - selection via styling only (bold + bright)
+    let summary_style = if is_selected {
+        Style::default()
+            .fg(Theme::WHITE)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(Theme::GREY_300)
+    };
+
+    // First line has: padding + badges + kind + multi-file + ": "
+    let first_prefix_len = 2
+        + criticality_badge.len()
+        + 2
+        + category_badge.len()
+        + 2
+        + kind_label.len()
+        + multi_file_indicator.len()
+        + feedback_marker.chars().count()
+        + new_marker.len()
+        + redetected_marker.chars().count()
+        + 2;
+    let first_line_width = text_width.saturating_sub(first_prefix_len);
+    // Continuation lines just have small indent (5 chars)
+    let cont_indent = "     ";
+    let cont_line_width = text_width.saturating_sub(5);
+
+    // Use variable width wrapping: first line is shorter due to prefix
+    let wrapped = wrapped_suggestion_summary(suggestion, first_line_width, cont_line_width);
+    let mut card = Vec::with_capacity(wrapped.len() + 1);
+
+    // Render first line with kind and multi-file indicator
+    if let Some(first_line) = wrapped.first() {
+        let mut spans = vec![
+            Span::styled("  ", Style::default()),
+            Span::styled(criticality_badge, criticality_style),
+            Span::styled(" ", Style::default()),
+            Span::styled(category_badge, category_style),
+            Span::styled(" ", Style::default()),
+            Span::styled(kind_label, kind_style),
+        ];
+        if suggestion.is_multi_file() {
+            spans.push(Span::styled(multi_file_indicator, multi_file_style));
+        }
+        if !feedback_marker.is_empty() {
+            spans.push(Span::styled(feedback_marker, feedback_style));
+        }
+        if !new_marker.is_empty() {
+            spans.push(Span::styled(new_marker, new_style));
+        }
+        if !redetected_marker.is_empty() {
+            spans.push(Span::styled(
+                redetected_marker,
+                Style::default().fg(Theme::GREY_500),
+            ));
+        }
+        spans.push(Span::styled(": ", kind_style));
+        spans.push(Span::styled(first_line.clone(), summary_style));
+        card.push(Line::from(spans));
+    }
+
+    // Render ALL continuation lines (no artificial limit)
+    for wrapped_line in wrapped.iter().skip(1) {
+        card.push(Line::from(vec![
+            Span::styled(cont_indent, Style::default()),
+            Span::styled(wrapped_line.clone(), summary_style),
+        ]));
+    }
+
+    if app.expanded_suggestion_id == Some(suggestion.id) {
+        card.extend(expanded_suggestion_lines(app, suggestion, cont_line_width));
+    }
+
+    // Add empty line for spacing between suggestions
+    card.push(Line::from(""));
+    card
+}
+
+/// Detail lines shown under a suggestion expanded in place.
+fn expanded_suggestion_lines(
+    app: &App,
+    suggestion: &cosmos_core::suggest::Suggestion,
+    width: usize,
+) -> Vec<Line<'static>> {
+    const MAX_DETAIL_LINES: usize = 6;
+    let indent = "     ";
+    let label_style = Style::default().fg(Theme::GREY_500);
+    let value_style = Style::default().fg(Theme::GREY_300);
+    let mut lines = vec![Line::from("")];
+
+    if let Some(detail) = suggestion
+        .detail
+        .as_deref()
+        .filter(|d| !d.trim().is_empty())
+    {
+        let parsed = markdown::parse_markdown_cached(detail, width);
+        let truncated = parsed.len() > MAX_DETAIL_LINES;
+        for (idx, line) in parsed.iter().take(MAX_DETAIL_LINES).enumerate() {
+            let mut spans = Vec::with_capacity(line.spans.len() + 2);
+            spans.push(Span::styled(indent, Style::default()));
+            spans.extend(line.spans.iter().cloned());
+            if truncated && idx + 1 == MAX_DETAIL_LINES {
+                spans.push(Span::styled("…", Style::default().fg(Theme::GREY_200)));
+            }
+            lines.push(Line::from(spans));
+        }
+        lines.push(Line::from(""));
+    }
+
+    let location = match suggestion.line {
+        Some(line) => format!("{}:{}", suggestion.file.display(), line),
+        None => suggestion.file.display().to_string(),
+    };
+    lines.push(Line::from(vec![
+        Span::styled(format!("{}File     ", indent), label_style),
+        links::link_span(
+            truncate_to_width(&location, width.saturating_sub(9)),
+            &links::file_url(&app.repo_path, &suggestion.file),
+            value_style,
+        ),
+    ]));
+    for extra in &suggestion.additional_files {
+        lines.push(Line::from(vec![
+            Span::styled(format!("{}         ", indent), label_style),
+            Span::styled(
+                truncate_to_width(&extra.display().to_string(), width.saturating_sub(9)),
+                value_style,
+            ),
+        ]));
+    }
+
+    let symbols = app.suggestion_affected_symbols(suggestion);
+    if !symbols.is_empty() {
+        lines.push(Line::from(vec![
+            Span::styled(format!("{}Symbols  ", indent), label_style),
+            Span::styled(
+                truncate_to_width(&symbols.join(", "), width.saturating_sub(9)),
+                value_style,
+            ),
+        ]));
+    }
+
+    let impact = app.suggestions.impact_estimate(suggestion);
+    let priority = match suggestion.priority {
+        cosmos_core::suggest::Priority::High => "high",
+        cosmos_core::suggest::Priority::Medium => "medium",
+        cosmos_core::suggest::Priority::Low => "low",
+    };
+    lines.push(Line::from(vec![
+        Span::styled(format!("{}Impact   ", indent), label_style),
+        Span::styled(format!("{:.1}", impact.score), value_style),
+        Span::styled(
+            truncate_to_width(
+                &format!(
+                    "  ({} priority × {:.1} hotspot × {} lines)",
+                    priority, impact.hotspot, impact.affected_loc
+                ),
+                width.saturating_sub(14),
+            ),
+            label_style,
+        ),
+    ]));
+
+    let key_style = Style::default().fg(Theme::GREY_900).bg(Theme::GREY_300);
+    lines.push(Line::from(vec![
+        Span::styled(format!("{}Actions  ", indent), label_style),
+        Span::styled(" o ", key_style),
+        Span::styled(" apply plan  ", label_style),
+        Span::styled(" ↵ ", key_style),
+        Span::styled(" collapse", label_style),
+    ]));
+    lines
+}
+
 /// Render the Review step content  
 fn render_review_content<'a>(
     lines: &mut Vec<Line<'a>>,
@@ -556,31 +864,48 @@ fn render_review_content<'a>(
             Span::styled("    ", Style::default()),
             Span::styled(format!("{} ", spinner), Style::default().fg(Theme::WHITE)),
             Span::styled(
-                "Reviewing your changes...",
+                match state.pr_number {
+                    Some(number) => format!("Reviewing PR #{}...", number),
+                    None => "Reviewing your changes...".to_string(),
+                },
                 Style::default().fg(Theme::GREY_300),
             ),
         ]));
         return;
     }
 
-    if state.fixing {
+    if state.fixing || state.posting {
         let spinner = SPINNER_FRAMES[app.loading_frame % SPINNER_FRAMES.len()];
         lines.push(Line::from(""));
         lines.push(Line::from(vec![
             Span::styled("    ", Style::default()),
             Span::styled(format!("{} ", spinner), Style::default().fg(Theme::WHITE)),
-            Span::styled("Applying fixes...", Style::default().fg(Theme::GREY_300)),
+            Span::styled(
+                if state.posting {
+                    "Posting review..."
+                } else {
+                    "Applying fixes..."
+                },
+                Style::default().fg(Theme::GREY_300),
+            ),
         ]));
         return;
     }
 
-    let file_name = state
-        .files
-        .first()
-        .map(|f| f.path.as_path())
-        .and_then(|p| p.file_name())
-        .and_then(|n| n.to_str())
-        .unwrap_or("file");
+    let pr_review = state.pr_number.is_some();
+    let verb = if pr_review { "post" } else { "fix" };
+
+    let file_name = match &state.pull_request {
+        Some(pr) => format!("PR #{} {}", pr.number, pr.title),
+        None => state
+            .files
+            .first()
+            .map(|f| f.path.as_path())
+            .and_then(|p| p.file_name())
+            .and_then(|n| n.to_str())
+            .unwrap_or("file")
+            .to_string(),
+    };
 
     // Header: file name with optional round indicator (no "Review" label - shown in workflow breadcrumb)
     lines.push(Line::from(vec![
@@ -685,6 +1010,8 @@ fn render_review_content<'a>(
                     } else {
                         " Arm Ship Override"
                     }
+                } else if pr_review {
+                    " Post Review"
                 } else {
                     " Continue to Ship"
                 },
@@ -712,7 +1039,7 @@ fn render_review_content<'a>(
             ),
             if selected_count > 0 {
                 Span::styled(
-                    format!(" · {} to fix", selected_count),
+                    format!(" · {} to {}", selected_count, verb),
                     Style::default().fg(Theme::GREEN),
                 )
             } else {
@@ -741,36 +1068,56 @@ fn render_review_content<'a>(
                 _ => "Note",
             };
 
+            let editing = state.editing;
             lines.push(Line::from(vec![
                 Span::styled("  ", Style::default()),
                 Span::styled(
                     format!(" {} ", severity_label),
                     Style::default().fg(Theme::GREY_900).bg(severity_color),
                 ),
+                match editing {
+                    Some(FindingField::Title) => Span::styled(
+                        "  editing title (↵ when done)",
+                        Style::default().fg(Theme::GREY_400),
+                    ),
+                    Some(FindingField::Description) => Span::styled(
+                        "  editing description (Esc when done)",
+                        Style::default().fg(Theme::GREY_400),
+                    ),
+                    None if state.edited.contains_key(&state.cursor) => {
+                        Span::styled("  edited", Style::default().fg(Theme::GREY_400))
+                    }
+                    None => Span::styled("", Style::default()),
+                },
             ]));
             lines.push(Line::from(""));
 
+            let (title_lines, desc_lines) =
+                wrapped_finding_detail(current_finding, editing, text_width);
+
             // Title - prominent and bold
-            for title_line in wrap_text(&current_finding.title, text_width) {
-                lines.push(Line::from(vec![Span::styled(
-                    format!("  {}", title_line),
-                    Style::default()
-                        .fg(Theme::WHITE)
-                        .add_modifier(Modifier::BOLD),
-                )]));
+            for title_line in title_lines.iter() {
+                lines.push(Line::from(vec![
+                    Span::raw("  "),
+                    Span::styled(
+                        title_line.clone(),
+                        Style::default()
+                            .fg(Theme::WHITE)
+                            .add_modifier(Modifier::BOLD),
+                    ),
+                ]));
             }
             lines.push(Line::from(""));
 
             // Description - the full explanation, clearly laid out
-            if !current_finding.description.is_empty() {
-                let desc_lines = wrap_text(&current_finding.description, text_width);
+            if !desc_lines.is_empty() {
                 // Show as many description lines as we have budget for
                 let max_desc_lines = detail_budget.saturating_sub(4); // Reserve space for severity + title
                 for desc_line in desc_lines.iter().take(max_desc_lines.max(6)) {
-                    lines.push(Line::from(vec![Span::styled(
-                        format!("  {}", desc_line),
-                        Style::default().fg(Theme::GREY_200),
-                    )]));
+                    lines.push(Line::from(vec![
+                        Span::raw("  "),
+                        Span::styled(desc_line.clone(), Style::default().fg(Theme::GREY_200)),
+                    ]));
                 }
                 // If truncated, show indicator
                 if desc_lines.len() > max_desc_lines.max(6) {
@@ -788,7 +1135,14 @@ fn render_review_content<'a>(
                 lines.push(Line::from(vec![
                     Span::styled("  ", Style::default()),
                     Span::styled("[×]", Style::default().fg(Theme::GREEN)),
-                    Span::styled(" Selected for fixing", Style::default().fg(Theme::GREEN)),
+                    Span::styled(
+                        if pr_review {
+                            " Selected to post"
+                        } else {
+                            " Selected for fixing"
+                        },
+                        Style::default().fg(Theme::GREEN),
+                    ),
                 ]));
             } else {
                 lines.push(Line::from(vec![
@@ -819,16 +1173,12 @@ fn render_review_content<'a>(
                 let is_cursor = i == state.cursor;
                 let is_selected = state.selected.contains(&i);
 
-                let indicator = if is_cursor { "›" } else { " " };
-                let checkbox = if is_selected { "×" } else { " " };
+                let indicator = if is_cursor { "  ›" } else { "   " };
+                let checkbox = if is_selected { "[×] " } else { "[ ] " };
 
                 // Truncate title to fit on one line
                 let max_title_len = text_width.saturating_sub(8);
-                let title = if finding.title.len() > max_title_len {
-                    format!("{}...", &finding.title[..max_title_len.saturating_sub(3)])
-                } else {
-                    finding.title.clone()
-                };
+                let title = truncate_to_width(&finding.title, max_title_len);
 
                 let title_style = if is_cursor {
                     Style::default().fg(Theme::WHITE)
@@ -838,7 +1188,7 @@ fn render_review_content<'a>(
 
                 lines.push(Line::from(vec![
                     Span::styled(
-                        format!("  {}", indicator),
+                        indicator,
                         Style::default().fg(if is_cursor {
                             Theme::WHITE
                         } else {
@@ -846,7 +1196,7 @@ fn render_review_content<'a>(
                         }),
                     ),
                     Span::styled(
-                        format!("[{}] ", checkbox),
+                        checkbox,
                         Style::default().fg(if is_selected {
                             Theme::GREEN
                         } else {
@@ -871,6 +1221,69 @@ fn render_review_content<'a>(
     }
 }
 
+/// Title and description of a review finding wrapped to the panel, with the edit cursor on the
+/// field being edited. Reused across frames while the finding, field, and width stay the same.
+fn wrapped_finding_detail(
+    finding: &cosmos_engine::llm::ReviewFinding,
+    editing: Option<FindingField>,
+    width: usize,
+) -> (Rc<[String]>, Rc<[String]>) {
+    let mut hasher = DefaultHasher::new();
+    finding.title.hash(&mut hasher);
+    finding.description.hash(&mut hasher);
+    editing.map(|field| field as u8).hash(&mut hasher);
+    width.hash(&mut hasher);
+    let key = hasher.finish();
+
+    FINDING_DETAIL_CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        if let Some(cached) = cache.as_ref().filter(|cached| cached.key == key) {
+            return (Rc::clone(&cached.title), Rc::clone(&cached.description));
+        }
+        let mut title = finding.title.clone();
+        if editing == Some(FindingField::Title) {
+            title.push('█');
+        }
+        let mut description = finding.description.clone();
+        if editing == Some(FindingField::Description) {
+            description.push('█');
+        }
+        let title: Rc<[String]> = wrap_text(&title, width).into();
+        let description: Rc<[String]> = if description.is_empty() {
+            Rc::from([])
+        } else {
+            description
+                .split('\n')
+                .flat_map(|line| {
+                    if line.is_empty() {
+                        vec![String::new()]
+                    } else {
+                        wrap_text(line, width)
+                    }
+                })
+                .collect()
+        };
+        *cache = Some(CachedFindingDetail {
+            key,
+            title: Rc::clone(&title),
+            description: Rc::clone(&description),
+        });
+        (title, description)
+    })
+}
+
+fn push_ship_hook_passed_line(lines: &mut Vec<Line<'_>>, state: &crate::ui::ShipState) {
+    if let Some(run) = state.hook_run.as_ref().filter(|run| run.passed) {
+        lines.push(Line::from(vec![
+            Span::styled("  + ", Style::default().fg(Theme::GREEN)),
+            Span::styled(
+                format!("{} pre-commit hook passed", run.kind.label()),
+                Style::default().fg(Theme::GREY_400),
+            ),
+        ]));
+    }
+}
+
 /// Render the Ship step content
 fn render_ship_content<'a>(
     lines: &mut Vec<Line<'a>>,
@@ -882,6 +1295,8 @@ fn render_ship_content<'a>(
     let text_width = inner_width.saturating_sub(6);
 
     match state.step {
+        ShipStep::Stack => render_stack_content(lines, app, visible_height, text_width),
+        ShipStep::Commits => render_commit_split_content(lines, app, visible_height, text_width),
         ShipStep::Done => {
             // Build scrollable content
             let mut content: Vec<Line<'a>> = Vec::new();
@@ -889,7 +1304,11 @@ fn render_ship_content<'a>(
             content.push(Line::from(vec![
                 Span::styled("  + ", Style::default().fg(Theme::GREEN)),
                 Span::styled(
-                    "Pull request created!",
+                    if state.follow_up.is_some() {
+                        "Pull request updated!"
+                    } else {
+                        "Pull request created!"
+                    },
                     Style::default()
                         .fg(Theme::GREEN)
                         .add_modifier(Modifier::BOLD),
@@ -900,7 +1319,7 @@ fn render_ship_content<'a>(
             if let Some(url) = &state.pr_url {
                 content.push(Line::from(vec![
                     Span::styled("  ", Style::default()),
-                    Span::styled(url.clone(), Style::default().fg(Theme::GREY_300)),
+                    links::link_span(url.clone(), url, Style::default().fg(Theme::GREY_300)),
                 ]));
                 content.push(Line::from(""));
                 content.push(Line::from(vec![
@@ -919,8 +1338,69 @@ fn render_ship_content<'a>(
                 lines.push(line);
             }
         }
+        ShipStep::RunningHooks => {
+            lines.push(Line::from(""));
+            lines.push(Line::from(vec![
+                Span::styled("  ⠋ ", Style::default().fg(Theme::WHITE)),
+                Span::styled(
+                    "Running pre-commit hook...",
+                    Style::default().fg(Theme::GREY_300),
+                ),
+            ]));
+        }
+        ShipStep::HooksFailed => {
+            let mut content: Vec<Line<'a>> = Vec::new();
+            if let Some(run) = &state.hook_run {
+                content.push(Line::from(vec![
+                    Span::styled("  ✗ ", Style::default().fg(Theme::RED)),
+                    Span::styled(
+                        if run.timed_out {
+                            format!("The {} pre-commit hook timed out", run.kind.label())
+                        } else {
+                            format!("The {} pre-commit hook failed", run.kind.label())
+                        },
+                        Style::default()
+                            .fg(Theme::WHITE)
+                            .add_modifier(Modifier::BOLD),
+                    ),
+                ]));
+                content.push(Line::from(""));
+                let fixes = app.ship_hook_fixes_to_stage();
+                if !fixes.is_empty() {
+                    content.push(Line::from(vec![Span::styled(
+                        format!(
+                            "  It changed {} file(s); ↵ stages the fixes and retries:",
+                            fixes.len()
+                        ),
+                        Style::default().fg(Theme::GREY_400),
+                    )]));
+                    for file in fixes {
+                        content.push(Line::from(vec![Span::styled(
+                            format!("    • {}", file),
+                            Style::default().fg(Theme::GREY_300),
+                        )]));
+                    }
+                    content.push(Line::from(""));
+                }
+                for line in run.output.lines() {
+                    for wrapped in wrap_text(line, text_width) {
+                        content.push(Line::from(vec![Span::styled(
+                            format!("  {}", wrapped),
+                            Style::default().fg(Theme::GREY_400),
+                        )]));
+                    }
+                }
+            }
+
+            let total_content = content.len();
+            let scroll = state.scroll.min(total_content.saturating_sub(1));
+            for line in content.into_iter().skip(scroll).take(visible_height) {
+                lines.push(line);
+            }
+        }
         ShipStep::Committing => {
             lines.push(Line::from(""));
+            push_ship_hook_passed_line(lines, state);
             lines.push(Line::from(vec![
                 Span::styled("  ⠋ ", Style::default().fg(Theme::WHITE)),
                 Span::styled(
@@ -931,6 +1411,7 @@ fn render_ship_content<'a>(
         }
         ShipStep::Pushing => {
             lines.push(Line::from(""));
+            push_ship_hook_passed_line(lines, state);
             lines.push(Line::from(vec![
                 Span::styled("  + ", Style::default().fg(Theme::GREEN)),
                 Span::styled("Committed", Style::default().fg(Theme::GREY_400)),
@@ -942,6 +1423,7 @@ fn render_ship_content<'a>(
         }
         ShipStep::CreatingPR => {
             lines.push(Line::from(""));
+            push_ship_hook_passed_line(lines, state);
             lines.push(Line::from(vec![
                 Span::styled("  + ", Style::default().fg(Theme::GREEN)),
                 Span::styled("Committed", Style::default().fg(Theme::GREY_400)),
@@ -953,7 +1435,11 @@ fn render_ship_content<'a>(
             lines.push(Line::from(vec![
                 Span::styled("  ⠋ ", Style::default().fg(Theme::WHITE)),
                 Span::styled(
-                    "Creating pull request...",
+                    if state.follow_up.is_some() {
+                        "Commenting on pull request..."
+                    } else {
+                        "Creating pull request..."
+                    },
                     Style::default().fg(Theme::GREY_300),
                 ),
             ]));
@@ -967,6 +1453,111 @@ fn render_ship_content<'a>(
                 Span::styled("  Branch: ", Style::default().fg(Theme::GREY_500)),
                 Span::styled(state.branch_name.clone(), Style::default().fg(Theme::WHITE)),
             ]));
+            if app.stacked_changes {
+                content.push(Line::from(vec![Span::styled(
+                    format!(
+                        "  Stacked mode: ↵ commits as stack entry {}; open its PR from the stack",
+                        app.stack.len() + 1
+                    ),
+                    Style::default().fg(Theme::GREY_500),
+                )]));
+            }
+            content.push(Line::from(""));
+
+            if let Some(pr) = &state.follow_up {
+                // Follow-up push: the existing pull request keeps its options and title
+                content.push(Line::from(vec![
+                    Span::styled("  Pull request: ", Style::default().fg(Theme::GREY_500)),
+                    Span::styled(
+                        format!("#{} {}", pr.number, pr.title),
+                        Style::default().fg(Theme::WHITE),
+                    ),
+                ]));
+                content.push(Line::from(vec![Span::styled(
+                    "  ↵ pushes onto it and comments with a summary of these changes",
+                    Style::default().fg(Theme::GREY_500),
+                )]));
+            } else {
+                // Pull request options, editable before shipping
+                content.push(ship_option_line(
+                    "d",
+                    "Draft",
+                    if state.draft { "yes" } else { "no" },
+                    "",
+                    false,
+                ));
+                for field in [ShipField::Base, ShipField::Reviewers, ShipField::Labels] {
+                    let (key, value, placeholder) = match field {
+                        ShipField::Base => ("b", &state.base, "default branch"),
+                        ShipField::Reviewers => ("r", &state.reviewers, "none"),
+                        ShipField::Labels => ("l", &state.labels, "none"),
+                        ShipField::Title | ShipField::Description | ShipField::CommitMessage => {
+                            continue
+                        }
+                    };
+                    content.push(ship_option_line(
+                        key,
+                        field.label(),
+                        value,
+                        placeholder,
+                        state.editing == Some(field),
+                    ));
+                }
+                content.push(Line::from(""));
+
+                // Pull request title and description, generated from the pending changes
+                content.push(ship_option_line(
+                    "t",
+                    ShipField::Title.label(),
+                    &state.pr_title,
+                    "generated",
+                    state.editing == Some(ShipField::Title),
+                ));
+            }
+            let editing_body = state.editing == Some(ShipField::Description);
+            let body_label = match (state.follow_up.is_some(), editing_body) {
+                (true, true) => "Comment (Esc when done):",
+                (true, false) => "Comment:",
+                (false, true) => "Description (Esc when done):",
+                (false, false) => "Description:",
+            };
+            content.push(Line::from(vec![
+                Span::styled("  e ", Style::default().fg(Theme::GREY_500)),
+                Span::styled(body_label, Style::default().fg(Theme::GREY_400)),
+            ]));
+            if editing_body {
+                // Edit the raw text so the cursor sits where typing lands
+                let mut body_lines: Vec<String> = state
+                    .pr_body
+                    .split('\n')
+                    .flat_map(|line| {
+                        if line.is_empty() {
+                            vec![String::new()]
+                        } else {
+                            wrap_text(line, text_width.saturating_sub(2))
+                        }
+                    })
+                    .collect();
+                match body_lines.last_mut() {
+                    Some(last) => last.push('█'),
+                    None => body_lines.push("█".to_string()),
+                }
+                for line in body_lines {
+                    content.push(Line::from(vec![Span::styled(
+                        format!("    {}", line),
+                        Style::default().fg(Theme::WHITE),
+                    )]));
+                }
+            } else {
+                let parsed =
+                    markdown::parse_markdown_cached(&state.pr_body, text_width.saturating_sub(2));
+                for line in parsed.iter() {
+                    let mut spans = Vec::with_capacity(line.spans.len() + 1);
+                    spans.push(Span::styled("    ", Style::default()));
+                    spans.extend(line.spans.iter().cloned());
+                    content.push(Line::from(spans));
+                }
+            }
             content.push(Line::from(""));
 
             // Files - show all files for scrolling
@@ -983,16 +1574,74 @@ fn render_ship_content<'a>(
             }
             content.push(Line::from(""));
 
-            // Commit message - show full message for scrolling
-            content.push(Line::from(vec![Span::styled(
-                "  Commit message:",
-                Style::default().fg(Theme::GREY_400),
-            )]));
-            for line in wrap_text(&state.commit_message, text_width) {
-                content.push(Line::from(vec![Span::styled(
-                    format!("  {}", line),
-                    Style::default().fg(Theme::WHITE),
-                )]));
+            // Per-change commits replace the single commit message
+            if state.split_commits {
+                let plan = app.ship_commit_plan();
+                content.push(Line::from(vec![
+                    Span::styled("  m ", Style::default().fg(Theme::GREY_500)),
+                    Span::styled(
+                        format!("{} commits:", plan.len()),
+                        Style::default().fg(Theme::GREY_400),
+                    ),
+                ]));
+                for (index, (message, _)) in plan.iter().enumerate() {
+                    let subject = message.lines().next().unwrap_or_default();
+                    content.push(Line::from(vec![Span::styled(
+                        format!(
+                            "    {}. {}",
+                            index + 1,
+                            truncate_to_width(subject, text_width.saturating_sub(6))
+                        ),
+                        Style::default().fg(Theme::WHITE),
+                    )]));
+                }
+            } else {
+                // Commit message - show full message for scrolling
+                let editing_message = state.editing == Some(ShipField::CommitMessage);
+                content.push(Line::from(vec![
+                    Span::styled("  c ", Style::default().fg(Theme::GREY_500)),
+                    Span::styled(
+                        if editing_message {
+                            "Commit message (Esc when done):"
+                        } else {
+                            "Commit message:"
+                        },
+                        Style::default().fg(Theme::GREY_400),
+                    ),
+                    Span::styled(
+                        if state.generating_commit_message {
+                            "  writing from staged diff..."
+                        } else if editing_message {
+                            ""
+                        } else {
+                            "  g rewrite"
+                        },
+                        Style::default().fg(Theme::GREY_500),
+                    ),
+                ]));
+                let mut message_lines: Vec<String> = state
+                    .commit_message
+                    .split('\n')
+                    .flat_map(|line| {
+                        if line.is_empty() {
+                            vec![String::new()]
+                        } else {
+                            wrap_text(line, text_width.saturating_sub(2))
+                        }
+                    })
+                    .collect();
+                if editing_message {
+                    match message_lines.last_mut() {
+                        Some(last) => last.push('█'),
+                        None => message_lines.push("█".to_string()),
+                    }
+                }
+                for line in message_lines {
+                    content.push(Line::from(vec![Span::styled(
+                        format!("    {}", line),
+                        Style::default().fg(Theme::WHITE),
+                    )]));
+                }
             }
 
             // Use full visible height for scrollable content
@@ -1028,6 +1677,177 @@ fn render_ship_content<'a>(
     }
 }
 
+/// One pull request option row on the Ship Confirm screen, with its edit key
+/// Stacked branches, oldest first, each with its pull request status
+fn render_stack_content<'a>(
+    lines: &mut Vec<Line<'a>>,
+    app: &'a App,
+    visible_height: usize,
+    text_width: usize,
+) {
+    let state = &app.ship_state;
+    lines.push(Line::from(vec![Span::styled(
+        format!("  {} stacked change(s), oldest first:", app.stack.len()),
+        Style::default().fg(Theme::GREY_400),
+    )]));
+    lines.push(Line::from(""));
+
+    let mut content: Vec<Line<'a>> = Vec::new();
+    let mut selected_line = 0;
+    for (index, entry) in app.stack.iter().enumerate() {
+        let selected = index == state.stack_selected;
+        if selected {
+            selected_line = content.len();
+        }
+        let status = match (&entry.pr_url, entry.opening) {
+            (Some(_), _) => Span::styled("PR open", Style::default().fg(Theme::GREEN)),
+            (None, true) => Span::styled("opening PR...", Style::default().fg(Theme::WHITE)),
+            (None, false) => Span::styled("no PR yet", Style::default().fg(Theme::GREY_500)),
+        };
+        content.push(Line::from(vec![
+            Span::styled(
+                if selected { "  › " } else { "    " },
+                Style::default().fg(Theme::ACCENT),
+            ),
+            Span::styled(
+                format!("{}. ", index + 1),
+                Style::default().fg(Theme::GREY_500),
+            ),
+            Span::styled(
+                truncate_to_width(&entry.title, text_width.saturating_sub(20)),
+                Style::default().fg(Theme::WHITE).add_modifier(if selected {
+                    Modifier::BOLD
+                } else {
+                    Modifier::empty()
+                }),
+            ),
+            Span::styled("  ", Style::default()),
+            status,
+        ]));
+        content.push(Line::from(vec![Span::styled(
+            format!(
+                "       {} → {}",
+                entry.branch,
+                entry.options.base.as_deref().unwrap_or("default branch")
+            ),
+            Style::default().fg(Theme::GREY_500),
+        )]));
+        if let Some(url) = &entry.pr_url {
+            content.push(Line::from(vec![
+                Span::styled("       ", Style::default()),
+                links::link_span(url.clone(), url, Style::default().fg(Theme::GREY_300)),
+            ]));
+        }
+    }
+
+    let visible = visible_height.saturating_sub(2);
+    let skip = (selected_line + 3).saturating_sub(visible);
+    lines.extend(content.into_iter().skip(skip).take(visible));
+}
+
+/// Per-change commit list: order and pick the changes to commit
+fn render_commit_split_content<'a>(
+    lines: &mut Vec<Line<'a>>,
+    app: &'a App,
+    visible_height: usize,
+    text_width: usize,
+) {
+    let state = &app.ship_state;
+    lines.push(Line::from(vec![Span::styled(
+        "  One commit per change, first to last (J/K move, Space keeps or leaves out):",
+        Style::default().fg(Theme::GREY_400),
+    )]));
+    lines.push(Line::from(""));
+
+    let mut content: Vec<Line<'a>> = Vec::new();
+    let mut selected_line = 0;
+    for (index, commit) in state.commits.iter().enumerate() {
+        let Some(change) = app.pending_changes.get(commit.change) else {
+            continue;
+        };
+        let selected = index == state.commit_selected;
+        if selected {
+            selected_line = content.len();
+        }
+        content.push(Line::from(vec![
+            Span::styled(
+                if selected { "  › " } else { "    " },
+                Style::default().fg(Theme::ACCENT),
+            ),
+            Span::styled(
+                if commit.included { "[×] " } else { "[ ] " },
+                Style::default().fg(if commit.included {
+                    Theme::GREEN
+                } else {
+                    Theme::GREY_600
+                }),
+            ),
+            Span::styled(
+                truncate_to_width(change.description.trim(), text_width.saturating_sub(8)),
+                Style::default()
+                    .fg(if commit.included {
+                        Theme::WHITE
+                    } else {
+                        Theme::GREY_500
+                    })
+                    .add_modifier(if selected {
+                        Modifier::BOLD
+                    } else {
+                        Modifier::empty()
+                    }),
+            ),
+        ]));
+        let files = change
+            .files
+            .iter()
+            .map(|file| file.path.display().to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        content.push(Line::from(vec![Span::styled(
+            format!(
+                "        {}",
+                truncate_to_width(&files, text_width.saturating_sub(8))
+            ),
+            Style::default().fg(Theme::GREY_500),
+        )]));
+    }
+
+    let visible = visible_height.saturating_sub(2);
+    let skip = (selected_line + 3).saturating_sub(visible);
+    lines.extend(content.into_iter().skip(skip).take(visible));
+}
+
+fn ship_option_line<'a>(
+    key: &'static str,
+    label: &'static str,
+    value: &str,
+    placeholder: &str,
+    editing: bool,
+) -> Line<'a> {
+    let mut spans = vec![
+        Span::styled(format!("  {} ", key), Style::default().fg(Theme::GREY_500)),
+        Span::styled(
+            format!("{:<10}", format!("{}:", label)),
+            Style::default().fg(Theme::GREY_400),
+        ),
+    ];
+    if value.is_empty() && !editing {
+        spans.push(Span::styled(
+            placeholder.to_string(),
+            Style::default().fg(Theme::GREY_500),
+        ));
+    } else {
+        spans.push(Span::styled(
+            value.to_string(),
+            Style::default().fg(Theme::WHITE),
+        ));
+    }
+    if editing {
+        spans.push(Span::styled("█", Style::default().fg(Theme::WHITE)));
+    }
+    Line::from(spans)
+}
+
 fn render_ask_panel(frame: &mut Frame, area: Rect, app: &App) {
     let is_active = app.active_panel == ActivePanel::Ask;
 
@@ -1079,13 +1899,13 @@ fn render_question_mode_content<'a>(
             Span::styled(
                 format!(
                     " {}",
-                    truncate_with_ellipsis("Type your question...", text_width)
+                    truncate_to_width("Type your question...", text_width)
                 ),
                 Style::default().fg(Theme::GREY_500),
             ),
         ])
     } else {
-        let shown = truncate_with_ellipsis(&app.question_input, text_width.saturating_sub(1));
+        let shown = truncate_to_width(&app.question_input, text_width.saturating_sub(1));
         Line::from(vec![
             Span::styled("  ", Style::default()),
             Span::styled(shown, Style::default().fg(Theme::WHITE)),
@@ -1093,6 +1913,36 @@ fn render_question_mode_content<'a>(
         ])
     };
     lines.push(input_line);
+    if !app.ask_context_paths.is_empty() {
+        let names: Vec<String> = app
+            .ask_context_paths
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect();
+        lines.push(Line::from(vec![Span::styled(
+            format!(
+                "  Grounded in: {}",
+                truncate_to_width(&names.join(", "), text_width.saturating_sub(12))
+            ),
+            Style::default().fg(Theme::GREY_400),
+        )]));
+    }
+    if !app.chat_session.is_empty() {
+        let earlier = match app.chat_session.len() {
+            1 => "1 earlier question".to_string(),
+            n => format!("{} earlier questions", n),
+        };
+        lines.push(Line::from(vec![Span::styled(
+            format!(
+                "  {}",
+                truncate_to_width(
+                    &format!("Follow-up to {} · ^N new conversation", earlier),
+                    text_width
+                )
+            ),
+            Style::default().fg(Theme::GREY_500),
+        )]));
+    }
     lines.push(Line::from(""));
 
     if app.ask_in_flight {
@@ -1195,6 +2045,12 @@ fn render_question_mode_content<'a>(
                 ),
                 Span::styled(" ask ", Style::default().fg(Theme::GREY_400)),
                 Span::styled(" ", Style::default()),
+                Span::styled(
+                    " ^F ",
+                    Style::default().fg(Theme::GREY_900).bg(Theme::GREY_400),
+                ),
+                Span::styled(" files ", Style::default().fg(Theme::GREY_400)),
+                Span::styled(" ", Style::default()),
                 Span::styled(
                     " Esc ",
                     Style::default().fg(Theme::GREY_900).bg(Theme::GREY_400),
@@ -1210,6 +2066,12 @@ fn render_question_mode_content<'a>(
                 ),
                 Span::styled(" ask ", Style::default().fg(Theme::GREY_400)),
                 Span::styled(" ", Style::default()),
+                Span::styled(
+                    " ^F ",
+                    Style::default().fg(Theme::GREY_900).bg(Theme::GREY_400),
+                ),
+                Span::styled(" files ", Style::default().fg(Theme::GREY_400)),
+                Span::styled(" ", Style::default()),
                 Span::styled(
                     " Esc ",
                     Style::default().fg(Theme::GREY_900).bg(Theme::GREY_400),
@@ -1221,21 +2083,6 @@ fn render_question_mode_content<'a>(
     }
 }
 
-fn truncate_with_ellipsis(text: &str, max_chars: usize) -> String {
-    if max_chars == 0 {
-        return String::new();
-    }
-    let len = text.chars().count();
-    if len <= max_chars {
-        return text.to_string();
-    }
-    if max_chars == 1 {
-        return "…".to_string();
-    }
-    let head: String = text.chars().take(max_chars.saturating_sub(1)).collect();
-    format!("{head}…")
-}
-
 fn suggestion_window(total: usize, selected: usize, list_height: usize) -> (usize, usize) {
     if total == 0 {
         return (0, 0);
@@ -1258,9 +2105,14 @@ fn render_ask_cosmos_content<'a>(
     lines.push(Line::from(""));
 
     let text_width = inner_width.saturating_sub(6);
-    let response_hash = stable_hash(&ask_state.response);
-
-    let padded_lines = ASK_MARKDOWN_CACHE.with(|cache| {
+    let response_hash = stable_hash(&format!(
+        "{}\0{}\0{}",
+        ask_state.transcript,
+        ask_state.question.as_deref().unwrap_or_default(),
+        ask_state.response
+    ));
+
+    let (padded_lines, latest_start) = ASK_MARKDOWN_CACHE.with(|cache| {
         let mut cache = cache.borrow_mut();
         let needs_reparse = cache
             .as_ref()
@@ -1268,23 +2120,47 @@ fn render_ask_cosmos_content<'a>(
             .unwrap_or(true);
 
         if needs_reparse {
-            let parsed_lines = markdown::parse_markdown(&ask_state.response, text_width);
-            let mut with_padding = Vec::with_capacity(parsed_lines.len());
-            for line in parsed_lines {
-                let mut spans = vec![Span::styled("  ", Style::default())];
-                spans.extend(line.spans);
-                with_padding.push(Line::from(spans));
+            let pad = |parsed: Vec<Line<'static>>| -> Vec<Line<'static>> {
+                parsed
+                    .into_iter()
+                    .map(|line| {
+                        let mut spans = vec![Span::styled("  ", Style::default())];
+                        spans.extend(line.spans);
+                        Line::from(spans)
+                    })
+                    .collect()
+            };
+            let mut with_padding = Vec::new();
+            if !ask_state.transcript.is_empty() {
+                with_padding.extend(pad(markdown::parse_markdown(
+                    &ask_state.transcript,
+                    text_width,
+                )));
+                with_padding.push(Line::from(""));
+            }
+            let latest_start = with_padding.len();
+            if let Some(question) = ask_state.question.as_deref() {
+                with_padding.extend(pad(markdown::parse_markdown(
+                    &format!("**You:** {}", question.trim()),
+                    text_width,
+                )));
+                with_padding.push(Line::from(""));
             }
+            with_padding.extend(pad(markdown::parse_markdown(
+                &ask_state.response,
+                text_width,
+            )));
             *cache = Some(CachedAskMarkdown {
                 response_hash,
                 width: text_width,
                 padded_lines: with_padding,
+                latest_start,
             });
         }
 
         cache
             .as_ref()
-            .map(|cached| cached.padded_lines.clone())
+            .map(|cached| (cached.padded_lines.clone(), cached.latest_start))
             .unwrap_or_default()
     });
 
@@ -1292,7 +2168,9 @@ fn render_ask_cosmos_content<'a>(
     // Account for: 1 empty top + 1 scroll indicator + 1 empty + 1 hint = 4 lines overhead
     let content_height = visible_height.saturating_sub(4);
     let total_lines = padded_lines.len();
-    let scroll = ask_state.scroll.min(total_lines.saturating_sub(1));
+    // The latest answer opens at its first line; scrolling up walks back through the transcript.
+    let scroll = (latest_start.saturating_sub(ask_state.transcript_scroll) + ask_state.scroll)
+        .min(total_lines.saturating_sub(1));
 
     // Render visible content
     for line in padded_lines.iter().skip(scroll).take(content_height) {
@@ -1313,7 +2191,17 @@ fn render_ask_cosmos_content<'a>(
         lines.push(Line::from(""));
     }
 
-    lines.push(Line::from(""));
+    // What the answer was grounded on, for transparency
+    match ask_state.context.as_ref() {
+        Some(report) => lines.push(Line::from(vec![Span::styled(
+            format!(
+                "  {}",
+                truncate_to_width(&format_ask_context_report(report), text_width)
+            ),
+            Style::default().fg(Theme::GREY_500),
+        )])),
+        None => lines.push(Line::from("")),
+    }
 
     // Action hints at bottom
     lines.push(Line::from(vec![
@@ -1332,6 +2220,48 @@ fn render_ask_cosmos_content<'a>(
     ]));
 }
 
+fn format_ask_context_report(report: &cosmos_engine::llm::AskContextReport) -> String {
+    let files = match report.files.len() {
+        1 => "1 file".to_string(),
+        n => format!("{} files", n),
+    };
+    let mut summary = if report.user_selected {
+        format!(
+            "Context: {} picked by you · {} sent",
+            files,
+            format_bytes(report.content_bytes)
+        )
+    } else {
+        format!(
+            "Context: {} auto-selected (paths only) · {} prompt",
+            files,
+            format_bytes(report.prompt_bytes)
+        )
+    };
+    if report.context_window > 0 {
+        summary.push_str(&format!(
+            " · {} tokens ({:.0}% of window)",
+            format_token_count(report.prompt_tokens),
+            report.prompt_tokens as f64 / report.context_window as f64 * 100.0
+        ));
+    }
+    if report.retrieval_hits > 0 {
+        summary.push_str(&format!(" · {} semantic hits", report.retrieval_hits));
+    }
+    if !report.skipped.is_empty() {
+        summary.push_str(&format!(" · {} skipped", report.skipped.len()));
+    }
+    summary
+}
+
+fn format_bytes(bytes: usize) -> String {
+    if bytes < 1024 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    }
+}
+
 fn stable_hash(input: &str) -> u64 {
     let mut hasher = DefaultHasher::new();
     input.hash(&mut hasher);
@@ -1364,6 +2294,9 @@ mod tests {
             files: HashMap::new(),
             index_errors: Vec::new(),
             git_head: Some("deadbeef".to_string()),
+            packages: Vec::new(),
+            excluded_files: 0,
+            filter_fingerprint: None,
         };
         let suggestions = SuggestionEngine::new(index.clone());
         let context = WorkContext {
@@ -1401,6 +2334,36 @@ mod tests {
         assert_eq!(suggestions_width, SUGGESTIONS_MIN_COLS);
     }
 
+    #[test]
+    fn ask_context_report_summarizes_picked_files() {
+        let report = cosmos_engine::llm::AskContextReport {
+            files: vec![
+                std::path::PathBuf::from("src/a.rs"),
+                std::path::PathBuf::from("src/b.rs"),
+            ],
+            skipped: vec![std::path::PathBuf::from("src/big.rs")],
+            content_bytes: 2048,
+            prompt_bytes: 4096,
+            prompt_tokens: 1_300,
+            context_window: 131_072,
+            user_selected: true,
+            retrieval_hits: 0,
+        };
+
+        assert_eq!(
+            format_ask_context_report(&report),
+            "Context: 2 files picked by you · 2.0 KB sent · 1.3k tokens (1% of window) · 1 skipped"
+        );
+
+        let retrieved = cosmos_engine::llm::AskContextReport {
+            skipped: Vec::new(),
+            user_selected: false,
+            retrieval_hits: 4,
+            ..report
+        };
+        assert!(format_ask_context_report(&retrieved).ends_with(" · 4 semantic hits"));
+    }
+
     #[test]
     fn ask_hints_hidden_when_panel_is_not_active() {
         let app = make_test_app();
@@ -1460,4 +2423,53 @@ mod tests {
         assert!(rendered.contains("CRIT"));
         assert!(rendered.contains("SECURITY"));
     }
+
+    #[test]
+    fn huge_suggestion_lists_only_build_the_cards_in_view() {
+        let mut app = make_test_app();
+        for i in 0..2000 {
+            app.suggestions.add_llm_suggestion(Suggestion::new(
+                SuggestionKind::Improvement,
+                Priority::Medium,
+                std::path::PathBuf::from(format!("src/module_{}.rs", i)),
+                format!(
+                    "Finding {:04}: this summary is long enough that it wraps onto a second \
+                     line at the width the panel is rendered with in this test",
+                    i
+                ),
+                SuggestionSource::LlmDeep,
+            ));
+        }
+        let display: Vec<String> = app
+            .active_suggestions_for_display()
+            .iter()
+            .map(|s| s.summary[..12].to_string())
+            .collect();
+        // A stale scroll offset far above the selection, as after a burst of taller cards.
+        app.suggestion_scroll = 1880;
+        app.suggestion_selected = 1900;
+        app.expanded_suggestion_id = Some(app.active_suggestions_for_display()[1900].id);
+
+        let visible_height = 30;
+        let mut lines = Vec::new();
+        render_suggestions_content(&mut lines, &app, true, visible_height, 90);
+        let rendered: Vec<String> = lines
+            .iter()
+            .map(|line| {
+                line.spans
+                    .iter()
+                    .map(|span| span.content.as_ref())
+                    .collect::<String>()
+            })
+            .collect();
+        let shown: Vec<&String> = display
+            .iter()
+            .filter(|finding| rendered.iter().any(|line| line.contains(finding.as_str())))
+            .collect();
+
+        assert!(shown.contains(&&display[1900]));
+        assert!(!shown.contains(&&display[1880]));
+        assert!(shown.len() < 10);
+        assert!(rendered.len() <= visible_height);
+    }
 }
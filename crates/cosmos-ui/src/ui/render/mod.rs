@@ -1,3 +1,4 @@
+mod diff_view;
 mod footer;
 mod header;
 mod main;
@@ -6,24 +7,95 @@ mod overlays;
 use crate::ui::theme::Theme;
 use crate::ui::{App, Overlay};
 use ratatui::{
-    layout::{Constraint, Direction, Layout},
-    style::Style,
-    widgets::Block,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Paragraph},
     Frame,
 };
 
+use diff_view::render_diff_view;
 use footer::render_footer;
 use header::render_header;
 use main::render_main;
 use overlays::{
-    render_alert, render_api_key_overlay, render_apply_plan, render_file_detail, render_help,
-    render_reset_overlay, render_startup_check, render_suggestion_focus_overlay,
-    render_update_overlay, render_welcome,
+    render_alert, render_api_key_overlay, render_apply_plan, render_ask_context_picker,
+    render_branches_overlay, render_bulk_dismiss_overlay, render_command_log, render_file_detail,
+    render_help, render_mascot, render_reset_overlay, render_settings_overlay,
+    render_snooze_overlay, render_snoozed_overlay, render_startup_check,
+    render_suggestion_focus_overlay, render_toast, render_update_overlay, render_welcome,
+    render_workspace_trust,
 };
 
+/// Smallest terminal the full layout is drawn in; below it a resize prompt is shown instead
+pub const MIN_TERMINAL_WIDTH: u16 = 80;
+pub const MIN_TERMINAL_HEIGHT: u16 = 24;
+
+/// Whether a `width` x `height` terminal is below the supported minimum
+pub fn terminal_too_small(width: u16, height: u16) -> bool {
+    width < MIN_TERMINAL_WIDTH || height < MIN_TERMINAL_HEIGHT
+}
+
+/// Drop width-dependent render caches so the next frame re-wraps from scratch
+///
+/// Called on resize events; rapid resizes otherwise leave stale layouts and wrapped text
+/// keyed by sizes that no longer exist.
+pub fn reset_caches() {
+    main::reset_caches();
+    crate::ui::markdown::clear_parsed_cache();
+}
+
 /// Main render function
 pub fn render(frame: &mut Frame, app: &App) {
     let area = frame.area();
+    if terminal_too_small(area.width, area.height) {
+        render_too_small(frame, area);
+    } else {
+        render_screen(frame, app);
+    }
+    app.palette.apply(frame.buffer_mut());
+}
+
+/// Resize prompt shown instead of the layout when the terminal is too small for it
+fn render_too_small(frame: &mut Frame, area: Rect) {
+    frame.render_widget(Block::default().style(Style::default().bg(Theme::BG)), area);
+    if area.width == 0 || area.height == 0 {
+        return;
+    }
+
+    let lines = vec![
+        Line::from(Span::styled(
+            "Terminal too small",
+            Style::default()
+                .fg(Theme::WHITE)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(Span::styled(
+            format!(
+                "{}×{}, needs at least {}×{}",
+                area.width, area.height, MIN_TERMINAL_WIDTH, MIN_TERMINAL_HEIGHT
+            ),
+            Style::default().fg(Theme::GREY_300),
+        )),
+        Line::from(Span::styled(
+            "Resize the window, or press q to quit",
+            Style::default().fg(Theme::GREY_500),
+        )),
+    ];
+    let height = (lines.len() as u16).min(area.height);
+    let top = area.y + (area.height - height) / 2;
+    let text_area = Rect::new(area.x, top, area.width, height);
+    frame.render_widget(
+        Paragraph::new(lines)
+            .alignment(Alignment::Center)
+            .wrap(ratatui::widgets::Wrap { trim: true }),
+        text_area,
+    );
+}
+
+/// Full layout: header, main panels, footer, toast, and the open overlay
+fn render_screen(frame: &mut Frame, app: &App) {
+    let area = frame.area();
 
     // Clear with dark background
     frame.render_widget(Block::default().style(Style::default().bg(Theme::BG)), area);
@@ -44,6 +116,10 @@ pub fn render(frame: &mut Frame, app: &App) {
 
     // Loading is shown inline in the footer status bar (non-blocking)
 
+    if let Some(toast) = &app.toast {
+        render_toast(frame, layout[1], &toast.message);
+    }
+
     // Overlays
     match &app.overlay {
         Overlay::Alert {
@@ -51,7 +127,10 @@ pub fn render(frame: &mut Frame, app: &App) {
             message,
             scroll,
         } => render_alert(frame, title, message, *scroll),
-        Overlay::Help { scroll } => render_help(frame, *scroll),
+        Overlay::Help { scroll } => render_help(frame, &app.keymap, *scroll),
+        Overlay::CommandLog { scroll_back } => {
+            render_command_log(frame, &app.command_log, *scroll_back)
+        }
         Overlay::FileDetail { path, scroll } => {
             if let Some(file_index) = app.index.files.get(path) {
                 render_file_detail(frame, path, file_index, *scroll);
@@ -73,6 +152,7 @@ pub fn render(frame: &mut Frame, app: &App) {
             confirm_apply,
             show_technical_details,
             show_data_notice,
+            conflicts,
             scroll,
             ..
         } => {
@@ -83,9 +163,48 @@ pub fn render(frame: &mut Frame, app: &App) {
                 *confirm_apply,
                 *show_technical_details,
                 *show_data_notice,
+                conflicts,
                 *scroll,
             );
         }
+        Overlay::Diff { view } => render_diff_view(frame, view),
+        Overlay::AskContextPicker {
+            query,
+            selected,
+            picked,
+            picked_tokens,
+        } => {
+            render_ask_context_picker(frame, app, query, *selected, picked, *picked_tokens);
+        }
+        Overlay::Settings { selected, .. } => {
+            render_settings_overlay(frame, &app.palette, *selected);
+        }
+        Overlay::Snooze {
+            suggestion_id,
+            selected,
+        } => {
+            let summary = app
+                .suggestions
+                .suggestions
+                .iter()
+                .find(|s| s.id == *suggestion_id)
+                .map(|s| s.summary.as_str())
+                .unwrap_or_default();
+            render_snooze_overlay(frame, summary, *selected);
+        }
+        Overlay::Snoozed { selected } => {
+            render_snoozed_overlay(frame, &app.snoozes.snoozes, *selected);
+        }
+        Overlay::BulkDismiss { scopes, selected } => {
+            render_bulk_dismiss_overlay(frame, scopes, *selected);
+        }
+        Overlay::Branches {
+            branches,
+            selected,
+            status,
+        } => {
+            render_branches_overlay(frame, branches, *selected, status.as_deref());
+        }
         Overlay::Reset {
             options,
             selected,
@@ -126,6 +245,236 @@ pub fn render(frame: &mut Frame, app: &App) {
         Overlay::Welcome => {
             render_welcome(frame);
         }
+        Overlay::WorkspaceTrust => {
+            render_workspace_trust(frame, app);
+        }
+        Overlay::Mascot { splash } => {
+            render_mascot(frame, app, *splash);
+        }
         Overlay::None => {}
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::types::{ShipStep, WorkflowStep};
+    use cosmos_core::context::WorkContext;
+    use cosmos_core::index::CodebaseIndex;
+    use cosmos_core::suggest::{
+        Priority, Suggestion, SuggestionEngine, SuggestionKind, SuggestionSource,
+    };
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    const WIDTHS: [u16; 11] = [0, 1, 2, 5, 10, 20, 40, 79, 80, 81, 120];
+    const HEIGHTS: [u16; 9] = [0, 1, 2, 5, 10, 23, 24, 25, 40];
+
+    fn make_test_app() -> App {
+        let root =
+            std::env::temp_dir().join(format!("cosmos_render_test_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&root).unwrap();
+        let index = CodebaseIndex {
+            root: root.clone(),
+            files: HashMap::new(),
+            index_errors: Vec::new(),
+            git_head: Some("deadbeef".to_string()),
+            packages: Vec::new(),
+            excluded_files: 0,
+            filter_fingerprint: None,
+        };
+        let suggestions = SuggestionEngine::new(index.clone());
+        let context = WorkContext {
+            branch: "main".to_string(),
+            uncommitted_files: Vec::new(),
+            staged_files: Vec::new(),
+            untracked_files: Vec::new(),
+            inferred_focus: None,
+            modified_count: 0,
+            repo_root: root,
+        };
+        App::new(index, suggestions, context)
+    }
+
+    /// Apps covering each panel, workflow step and overlay, with text long enough to wrap
+    fn sample_apps() -> Vec<(&'static str, App)> {
+        let long = "Rapid resizes shouldn't panic: this sentence is long enough to wrap a few \
+                    times, with `inline code`, a [link](https://example.com) and 日本語 text.";
+        let mut apps = Vec::new();
+
+        let mut app = make_test_app();
+        for i in 0..3 {
+            app.suggestions.add_llm_suggestion(
+                Suggestion::new(
+                    SuggestionKind::BugFix,
+                    Priority::High,
+                    PathBuf::from(format!("src/module_{}.rs", i)),
+                    long.to_string(),
+                    SuggestionSource::LlmDeep,
+                )
+                .with_detail(format!(
+                    "## Why\n\n{}\n\n- one\n- two\n\n```rust\nfn main() {{}}\n```",
+                    long
+                )),
+            );
+        }
+        app.expanded_suggestion_id = app.suggestions.suggestions.first().map(|s| s.id);
+        app.show_toast(long);
+        apps.push(("suggestions", app));
+
+        let mut app = make_test_app();
+        app.show_inquiry(format!(
+            "# Answer\n\n{}\n\n| a | b |\n|---|---|\n| 1 | 2 |",
+            long
+        ));
+        apps.push(("ask", app));
+
+        let mut app = make_test_app();
+        app.workflow_step = WorkflowStep::Review;
+        app.set_review_findings(
+            vec![cosmos_engine::llm::ReviewFinding {
+                file: "src/lib.rs".to_string(),
+                line: Some(12),
+                severity: "critical".to_string(),
+                category: "bug".to_string(),
+                title: long.to_string(),
+                description: long.to_string(),
+                recommended: true,
+            }],
+            long.to_string(),
+        );
+        apps.push(("review", app));
+
+        for step in [ShipStep::Confirm, ShipStep::Done] {
+            let mut app = make_test_app();
+            app.workflow_step = WorkflowStep::Ship;
+            app.ship_state.step = step;
+            app.ship_state.branch_name = "fix/a-very-long-branch-name-for-resizing".to_string();
+            app.ship_state.commit_message = long.to_string();
+            app.ship_state.pr_body = long.to_string();
+            app.ship_state.pr_url = Some("https://github.com/acme/widgets/pull/42".to_string());
+            apps.push(("ship", app));
+        }
+
+        let overlays = [
+            Overlay::Alert {
+                title: "Alert".to_string(),
+                message: long.to_string(),
+                scroll: 0,
+            },
+            Overlay::Help { scroll: 0 },
+            Overlay::CommandLog { scroll_back: 0 },
+            Overlay::ApiKeySetup {
+                input: "sk-or-v1-abc".to_string(),
+                error: Some(long.to_string()),
+                save_armed: false,
+            },
+            Overlay::SuggestionFocus {
+                selected: cosmos_engine::llm::SuggestionReviewFocus::BugHunt,
+            },
+            Overlay::Diff {
+                view: Box::new(crate::ui::diff::DiffView::new(
+                    vec![crate::ui::diff::FileDiff::new(
+                        PathBuf::from("src/lib.rs"),
+                        "fn a() {}\nfn b() {}\n",
+                        "fn a() { todo!() }\nfn b() {}\nfn c() {}\n",
+                    )],
+                    true,
+                )),
+            },
+            Overlay::Snooze {
+                suggestion_id: uuid::Uuid::new_v4(),
+                selected: 3,
+            },
+            Overlay::Snoozed { selected: 0 },
+            Overlay::BulkDismiss {
+                scopes: vec![(
+                    cosmos_core::suggest::BulkDismissScope::Directory(PathBuf::from(long)),
+                    3,
+                )],
+                selected: 0,
+            },
+            Overlay::Branches {
+                branches: Vec::new(),
+                selected: 0,
+                status: Some(long.to_string()),
+            },
+            Overlay::Update {
+                current_version: "0.1.0".to_string(),
+                target_version: "0.2.0".to_string(),
+                progress: Some(42),
+                error: None,
+            },
+            Overlay::Welcome,
+            Overlay::WorkspaceTrust,
+            Overlay::Mascot { splash: true },
+            Overlay::Mascot { splash: false },
+        ];
+        for overlay in overlays {
+            let mut app = make_test_app();
+            app.overlay = overlay;
+            apps.push(("overlay", app));
+        }
+
+        let mut app = make_test_app();
+        app.open_settings_overlay();
+        apps.push(("settings", app));
+        let mut app = make_test_app();
+        app.open_reset_overlay();
+        apps.push(("reset", app));
+        let mut app = make_test_app();
+        app.show_startup_check(12, "feature/resize".to_string(), "main".to_string());
+        apps.push(("startup check", app));
+
+        apps
+    }
+
+    fn draw(app: &App, width: u16, height: u16, draw: fn(&mut Frame, &App)) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+        let frame = terminal.draw(|f| draw(f, app)).unwrap();
+        frame
+            .buffer
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect()
+    }
+
+    #[test]
+    fn every_screen_survives_tiny_and_odd_sizes() {
+        let mut failures = Vec::new();
+        for (name, app) in sample_apps() {
+            for width in WIDTHS {
+                for height in HEIGHTS {
+                    reset_caches();
+                    // Draw the full layout too, not just the too-small prompt, so the
+                    // panel and overlay math is exercised below the minimum size
+                    for draw_fn in [render as fn(&mut Frame, &App), render_screen] {
+                        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                            draw(&app, width, height, draw_fn)
+                        }));
+                        if result.is_err() {
+                            failures.push(format!("{} at {}x{}", name, width, height));
+                        }
+                    }
+                }
+            }
+        }
+        assert!(failures.is_empty(), "render panicked: {:?}", failures);
+    }
+
+    #[test]
+    fn small_terminals_get_a_resize_prompt() {
+        let app = make_test_app();
+        let small = draw(&app, 60, 20, render);
+        assert!(small.contains("Terminal too small"));
+        assert!(small.contains("60×20, needs at least 80×24"));
+
+        let fits = draw(&app, MIN_TERMINAL_WIDTH, MIN_TERMINAL_HEIGHT, render);
+        assert!(!fits.contains("Terminal too small"));
+        assert!(terminal_too_small(MIN_TERMINAL_WIDTH - 1, 40));
+        assert!(!terminal_too_small(MIN_TERMINAL_WIDTH, MIN_TERMINAL_HEIGHT));
+    }
+}
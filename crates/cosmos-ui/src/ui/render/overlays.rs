@@ -1,6 +1,13 @@
-use crate::ui::helpers::{centered_rect, wrap_text};
-use crate::ui::theme::Theme;
-use crate::ui::{StartupAction, StartupMode};
+use crate::ui::helpers::{
+    centered_rect, context_usage_bar, display_width, truncate_to_width, wrap_text,
+};
+use crate::ui::keymap::{Action, HelpSection, Keymap};
+use crate::ui::links;
+use crate::ui::mascot;
+use crate::ui::theme::{Palette, Theme, ThemeName};
+use crate::ui::{App, CommandLog, StartupAction, StartupMode, SNOOZE_CHOICES};
+use cosmos_adapters::terminal::GraphicsProtocol;
+use cosmos_engine::llm::models::MODEL_CONTEXT_WINDOW;
 use cosmos_engine::llm::SuggestionReviewFocus;
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
@@ -11,10 +18,35 @@ use ratatui::{
 };
 use std::path::{Path, PathBuf};
 
+/// Small notification box in the top-right corner of `area`; doesn't take input.
+pub(super) fn render_toast(frame: &mut Frame, area: Rect, message: &str) {
+    let text = truncate_to_width(message, area.width.saturating_sub(8) as usize);
+    let width = (display_width(&text) as u16 + 6).min(area.width);
+    let toast_area = Rect::new(
+        area.x + area.width.saturating_sub(width + 1),
+        area.y + 1,
+        width,
+        3,
+    )
+    .intersection(area);
+    frame.render_widget(Clear, toast_area);
+    let toast = Paragraph::new(Line::from(Span::styled(
+        format!("  {}", text),
+        Style::default().fg(Theme::WHITE),
+    )))
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Theme::ACCENT))
+            .style(Style::default().bg(Theme::GREY_800)),
+    );
+    frame.render_widget(toast, toast_area);
+}
+
 pub(super) fn render_alert(frame: &mut Frame, title: &str, message: &str, scroll: usize) {
     let viewport = frame.area();
     let max_width = viewport.width.saturating_sub(2).max(24);
-    let preferred_width = viewport.width.saturating_sub(6).min(132).max(56);
+    let preferred_width = viewport.width.saturating_sub(6).clamp(56, 132);
     let width = preferred_width.min(max_width);
     let text_width = width.saturating_sub(8).max(16) as usize;
     let wrapped_message = wrap_text(message, text_width);
@@ -32,7 +64,9 @@ pub(super) fn render_alert(frame: &mut Frame, title: &str, message: &str, scroll
         viewport.y + viewport.height.saturating_sub(desired_height) / 2,
         width,
         desired_height,
-    );
+    )
+    // The minimum size above can exceed a tiny terminal; keep the box on screen
+    .intersection(viewport);
     frame.render_widget(Clear, area);
 
     let mut lines: Vec<Line> = vec![
@@ -123,7 +157,78 @@ pub(super) fn render_alert(frame: &mut Frame, title: &str, message: &str, scroll
     );
 }
 
-pub(super) fn render_help(frame: &mut Frame, scroll: usize) {
+/// Streamed quick-check output; follows the newest line unless scrolled back.
+pub(super) fn render_command_log(frame: &mut Frame, log: &CommandLog, scroll_back: usize) {
+    let area = centered_rect(80, 80, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Check output ")
+        .title_style(Style::default().fg(Theme::GREY_100))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Theme::GREY_400))
+        .style(Style::default().bg(Theme::GREY_800));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(1)])
+        .split(inner);
+    let content_area = chunks[0];
+    let height = content_area.height.max(1) as usize;
+    let width = content_area.width.saturating_sub(2) as usize;
+
+    let scroll_back = scroll_back.min(log.len().saturating_sub(height));
+    let end = log.len() - scroll_back;
+    let lines: Vec<Line> = if log.is_empty() {
+        vec![Line::from(Span::styled(
+            " No check output yet. It streams here while a fix runs its quick checks.",
+            Style::default().fg(Theme::GREY_500),
+        ))]
+    } else {
+        log.lines()
+            .take(end)
+            .skip(end.saturating_sub(height))
+            .map(|line| {
+                let style = if line.starts_with("$ ") {
+                    Style::default()
+                        .fg(Theme::WHITE)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(Theme::GREY_200)
+                };
+                Line::from(Span::styled(
+                    format!(" {}", truncate_to_width(line, width)),
+                    style,
+                ))
+            })
+            .collect()
+    };
+    frame.render_widget(Paragraph::new(lines), content_area);
+
+    let position = if scroll_back == 0 {
+        "following".to_string()
+    } else {
+        format!("{} lines back", scroll_back)
+    };
+    frame.render_widget(
+        Paragraph::new(Line::from(vec![
+            Span::styled(
+                format!("  ↑/↓ scroll ({})  ", position),
+                Style::default().fg(Theme::GREY_500),
+            ),
+            Span::styled(
+                "Esc",
+                Style::default().fg(Theme::GREY_900).bg(Theme::GREY_300),
+            ),
+            Span::styled(" to close", Style::default().fg(Theme::GREY_500)),
+        ])),
+        chunks[1],
+    );
+}
+
+pub(super) fn render_help(frame: &mut Frame, keymap: &Keymap, scroll: usize) {
     let area = centered_rect(55, 80, frame.area());
     frame.render_widget(Clear, area);
 
@@ -174,35 +279,19 @@ pub(super) fn render_help(frame: &mut Frame, scroll: usize) {
 
     let mut help_text: Vec<Line<'static>> = vec![Line::from("")];
 
-    // Navigation section
-    help_text.extend(section_start("Navigation"));
-    help_text.push(section_spacer());
-    help_text.push(key_row("Tab", "Switch suggestions/ask"));
-    help_text.push(key_row("↑↓", "Move up/down"));
-    help_text.push(key_row("↵", "Preview / confirm action"));
-    help_text.push(key_row("Esc", "Go back / cancel"));
-    help_text.push(section_spacer());
-    help_text.push(section_end());
-
-    // Actions section
-    help_text.extend(section_start("Actions"));
-    help_text.push(section_spacer());
-    help_text.push(key_row("↵", "Open apply plan / confirm"));
-    help_text.push(key_row("r", "Refresh suggestions"));
-    help_text.push(key_row("m", "Choose bug/security mode"));
-    help_text.push(key_row("k", "Open Cerebras setup guide"));
-    help_text.push(key_row("?", "Show help"));
-    help_text.push(key_row("q", "Quit"));
-    help_text.push(section_spacer());
-    help_text.push(section_end());
-
-    // Privacy and reset section
-    help_text.extend(section_start("Privacy"));
-    help_text.push(section_spacer());
-    help_text.push(key_row("R", "Reset Cosmos"));
-    help_text.push(key_row("U", "Check for updates"));
-    help_text.push(section_spacer());
-    help_text.push(section_end());
+    // One section per group, listing each action under its active keys.
+    for section in HelpSection::ALL {
+        help_text.extend(section_start(section.title()));
+        help_text.push(section_spacer());
+        for action in Action::ALL
+            .into_iter()
+            .filter(|action| action.section() == section)
+        {
+            help_text.push(key_row(&keymap.label(action), action.description()));
+        }
+        help_text.push(section_spacer());
+        help_text.push(section_end());
+    }
 
     let max_lines = (area.height as usize).saturating_sub(4);
     let visible = &help_text[scroll..help_text.len().min(scroll + max_lines)];
@@ -641,6 +730,7 @@ pub(super) fn render_suggestion_focus_overlay(frame: &mut Frame, selected: Sugge
     frame.render_widget(footer, footer_area);
 }
 
+#[allow(clippy::too_many_arguments)]
 pub(super) fn render_apply_plan(
     frame: &mut Frame,
     preview: &cosmos_engine::llm::FixPreview,
@@ -648,6 +738,7 @@ pub(super) fn render_apply_plan(
     confirm_apply: bool,
     show_technical_details: bool,
     show_data_notice: bool,
+    conflicts: &[String],
     scroll: usize,
 ) {
     let area = centered_rect(72, 78, frame.area());
@@ -781,6 +872,27 @@ pub(super) fn render_apply_plan(
         ]));
     }
 
+    if !conflicts.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(vec![
+            Span::styled("  ", Style::default()),
+            Span::styled(
+                "Conflicts with queued fixes",
+                Style::default()
+                    .fg(Theme::YELLOW)
+                    .add_modifier(Modifier::BOLD),
+            ),
+        ]));
+        for conflict in conflicts {
+            for line in wrap_text(conflict, text_width) {
+                lines.push(Line::from(vec![
+                    Span::styled("    ", Style::default()),
+                    Span::styled(line, Style::default().fg(Theme::GREY_300)),
+                ]));
+            }
+        }
+    }
+
     if show_data_notice {
         lines.push(Line::from(""));
         lines.push(Line::from(vec![
@@ -898,6 +1010,177 @@ pub(super) fn render_apply_plan(
     frame.render_widget(footer, footer_area);
 }
 
+pub(super) fn render_ask_context_picker(
+    frame: &mut Frame,
+    app: &App,
+    query: &str,
+    selected: usize,
+    picked: &[PathBuf],
+    picked_tokens: u32,
+) {
+    let area = centered_rect(60, 70, frame.area());
+    frame.render_widget(Clear, area);
+
+    let text_width = area.width.saturating_sub(12) as usize;
+    let candidates = app.ask_context_candidates();
+    let mut lines: Vec<Line> = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            "  Pick files or folders to ground the answer:",
+            Style::default().fg(Theme::GREY_300),
+        )),
+        Line::from(vec![
+            Span::styled("  Filter: ", Style::default().fg(Theme::GREY_500)),
+            Span::styled(query.to_string(), Style::default().fg(Theme::WHITE)),
+            Span::styled("█", Style::default().fg(Theme::WHITE)),
+        ]),
+        Line::from(""),
+    ];
+
+    // blank + intro + filter + blank, then blank + two directory-summary lines + summary +
+    // separator + hints + blank + borders
+    let list_height = (area.height as usize).saturating_sub(13).max(1);
+    let start = selected.saturating_sub(list_height.saturating_sub(1));
+    if candidates.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "  No matching files",
+            Style::default().fg(Theme::GREY_500),
+        )));
+    }
+    for (i, entry) in candidates.iter().enumerate().skip(start).take(list_height) {
+        let is_focused = i == selected;
+        let is_picked = picked.contains(&entry.path);
+        let checkbox = if is_picked { "[x]" } else { "[ ]" };
+        let indicator = if is_focused { "▸ " } else { "  " };
+        let mut label = entry.path.display().to_string();
+        if entry.is_dir {
+            label.push('/');
+        }
+        let label_color = if entry.is_dir {
+            Theme::GREY_300
+        } else {
+            Theme::GREY_100
+        };
+        let line_style = if is_focused {
+            Style::default().bg(Theme::GREY_700)
+        } else {
+            Style::default()
+        };
+        lines.push(
+            Line::from(vec![
+                Span::styled(
+                    format!("  {}", indicator),
+                    Style::default().fg(Theme::ACCENT),
+                ),
+                Span::styled(
+                    format!("{} ", checkbox),
+                    Style::default().fg(if is_picked {
+                        Theme::GREEN
+                    } else {
+                        Theme::GREY_500
+                    }),
+                ),
+                Span::styled(
+                    truncate_to_width(&label, text_width),
+                    Style::default().fg(label_color),
+                ),
+            ])
+            .style(line_style),
+        );
+    }
+
+    lines.push(Line::from(""));
+    // A focused directory shows what it holds, so picking a whole module is an informed choice.
+    let directory = candidates
+        .get(selected)
+        .filter(|entry| entry.is_dir)
+        .and_then(|entry| app.directory_summaries.get(&entry.path));
+    let (purpose, outline) = match directory {
+        Some(directory) => {
+            let mut outline = Vec::new();
+            if !directory.entry_points.is_empty() {
+                let entries: Vec<String> = directory
+                    .entry_points
+                    .iter()
+                    .filter_map(|path| path.file_name())
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .collect();
+                outline.push(format!("entry: {}", entries.join(", ")));
+            }
+            if !directory.key_exports.is_empty() {
+                outline.push(format!("exports: {}", directory.key_exports.join(", ")));
+            }
+            outline.push("^R rewrites".to_string());
+            (
+                format!(
+                    "{} ({} files, {} lines)",
+                    directory.purpose, directory.file_count, directory.loc
+                ),
+                outline.join("  ·  "),
+            )
+        }
+        None => (String::new(), String::new()),
+    };
+    lines.push(Line::from(Span::styled(
+        format!("  {}", truncate_to_width(&purpose, text_width + 8)),
+        Style::default().fg(Theme::GREY_200),
+    )));
+    lines.push(Line::from(Span::styled(
+        format!("  {}", truncate_to_width(&outline, text_width + 8)),
+        Style::default().fg(Theme::GREY_500),
+    )));
+    // Picked files fill the prompt; show how much of the window they take before asking.
+    let summary = if picked.is_empty() {
+        "  Nothing picked - Cosmos chooses context automatically".to_string()
+    } else {
+        format!(
+            "  {} picked  {}",
+            picked.len(),
+            context_usage_bar(picked_tokens, MODEL_CONTEXT_WINDOW, 12)
+        )
+    };
+    lines.push(Line::from(Span::styled(
+        truncate_to_width(&summary, text_width + 10),
+        Style::default().fg(Theme::GREY_400),
+    )));
+    lines.push(Line::from(Span::styled(
+        "  ─────────────────────────────────────────────────",
+        Style::default().fg(Theme::GREY_600),
+    )));
+    lines.push(Line::from(vec![
+        Span::styled("   ", Style::default()),
+        Span::styled(
+            " Space ",
+            Style::default().fg(Theme::GREY_900).bg(Theme::GREY_400),
+        ),
+        Span::styled(" toggle  ", Style::default().fg(Theme::GREY_400)),
+        Span::styled(
+            " ↵ ",
+            Style::default().fg(Theme::GREY_900).bg(Theme::GREY_400),
+        ),
+        Span::styled(" done  ", Style::default().fg(Theme::GREY_400)),
+        Span::styled(
+            " ^O ",
+            Style::default().fg(Theme::GREY_900).bg(Theme::GREY_400),
+        ),
+        Span::styled(" edit  ", Style::default().fg(Theme::GREY_400)),
+        Span::styled(
+            " Esc ",
+            Style::default().fg(Theme::GREY_900).bg(Theme::GREY_400),
+        ),
+        Span::styled(" cancel", Style::default().fg(Theme::GREY_400)),
+    ]));
+
+    let block = Block::default()
+        .title(" Ask Context ")
+        .title_style(Style::default().fg(Theme::GREY_100))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Theme::ACCENT))
+        .style(Style::default().bg(Theme::GREY_800));
+
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
 pub(super) fn render_reset_overlay(
     frame: &mut Frame,
     options: &[(cosmos_adapters::cache::ResetOption, bool)],
@@ -1011,81 +1294,515 @@ pub(super) fn render_reset_overlay(
     frame.render_widget(paragraph, area);
 }
 
-pub(super) fn render_startup_check(
+pub(super) fn render_branches_overlay(
     frame: &mut Frame,
-    changed_count: usize,
-    current_branch: &str,
-    main_branch: &str,
-    mode: StartupMode,
-    selected_action: StartupAction,
+    branches: &[cosmos_adapters::cache::CosmosBranchRecord],
+    selected: usize,
+    status: Option<&str>,
 ) {
-    let viewport = frame.area();
-    let is_branch_only = changed_count == 0 && current_branch != main_branch;
-    let desired_width = if mode == StartupMode::ConfirmDiscard {
-        74
-    } else {
-        98
-    };
-    let desired_height = if mode == StartupMode::ConfirmDiscard {
-        14
-    } else if is_branch_only {
-        18
-    } else {
-        20
-    };
-    let max_width = viewport.width.saturating_sub(2);
-    let max_height = viewport.height.saturating_sub(2);
-    let width = desired_width.min(max_width).max(40).min(viewport.width);
-    let height = desired_height.min(max_height).max(10).min(viewport.height);
-    let area = Rect::new(
-        viewport.x + viewport.width.saturating_sub(width) / 2,
-        viewport.y + viewport.height.saturating_sub(height) / 2,
-        width,
-        height,
-    );
-    frame.render_widget(Clear, area);
-
-    let title = if mode == StartupMode::ConfirmDiscard {
-        " Confirm "
-    } else {
-        " Startup Check "
-    };
+    use cosmos_adapters::forge::PrState;
 
-    // Outer block with border
-    let outer_block = Block::default()
-        .title(title)
-        .title_style(Style::default().fg(Theme::GREY_100))
-        .borders(Borders::ALL)
-        .border_style(Style::default().fg(Theme::ACCENT))
-        .style(Style::default().bg(Theme::GREY_800));
+    let area = centered_rect(70, 60, frame.area());
+    frame.render_widget(Clear, area);
 
-    let inner_area = outer_block.inner(area);
-    frame.render_widget(outer_block, area);
+    let mut lines: Vec<Line> = Vec::new();
+    lines.push(Line::from(""));
 
-    // Split inner area: scrollable body + fixed footer
-    let layout = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([Constraint::Min(1), Constraint::Length(1)])
-        .split(inner_area);
+    if branches.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "  No Cosmos branches are being tracked.",
+            Style::default().fg(Theme::GREY_400),
+        )));
+    }
 
-    let body_area = layout[0];
-    let footer_area = layout[1];
+    for (i, record) in branches.iter().enumerate() {
+        let is_focused = i == selected;
+        let indicator = if is_focused { "▸ " } else { "  " };
+        let (state_label, state_color) = match record.pr_state {
+            Some(PrState::Open) => ("open", Theme::GREEN),
+            Some(PrState::Merged) => ("merged", Theme::ACCENT),
+            Some(PrState::Closed) => ("closed", Theme::GREY_500),
+            None if record.pr_url.is_some() => ("unknown", Theme::YELLOW),
+            None => ("no PR", Theme::GREY_500),
+        };
+        let line_style = if is_focused {
+            Style::default().bg(Theme::GREY_700)
+        } else {
+            Style::default()
+        };
 
-    // Build body content
-    let mut lines: Vec<Line> = Vec::new();
-    let compact = mode == StartupMode::Choose && body_area.height <= 10;
-    let show_selected_desc = !compact && body_area.height >= 12;
+        lines.push(
+            Line::from(vec![
+                Span::styled(
+                    format!("  {}", indicator),
+                    Style::default().fg(Theme::ACCENT),
+                ),
+                Span::styled(
+                    format!("{:<8}", state_label),
+                    Style::default().fg(state_color),
+                ),
+                Span::styled(record.branch.clone(), Style::default().fg(Theme::GREY_100)),
+            ])
+            .style(line_style),
+        );
+        if is_focused {
+            if let Some(url) = record.pr_url.as_deref() {
+                lines.push(Line::from(vec![
+                    Span::raw("            "),
+                    links::link_span(url, url, Style::default().fg(Theme::GREY_500)),
+                ]));
+            }
+        }
+    }
 
-    if mode == StartupMode::ConfirmDiscard {
-        // Confirmation dialog for discard
+    if let Some(message) = status {
         lines.push(Line::from(""));
+        lines.push(Line::from(vec![
+            Span::styled("  · ", Style::default().fg(Theme::GREY_500)),
+            Span::styled(message.to_string(), Style::default().fg(Theme::GREY_200)),
+        ]));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "  ─────────────────────────────────────────────────",
+        Style::default().fg(Theme::GREY_600),
+    )));
+    lines.push(Line::from(vec![
+        Span::styled("   ", Style::default()),
+        Span::styled(
+            " ↵ ",
+            Style::default().fg(Theme::GREY_900).bg(Theme::GREY_400),
+        ),
+        Span::styled(" open PR  ", Style::default().fg(Theme::GREY_400)),
+        Span::styled(
+            " s ",
+            Style::default().fg(Theme::GREY_900).bg(Theme::GREY_400),
+        ),
+        Span::styled(" switch  ", Style::default().fg(Theme::GREY_400)),
+        Span::styled(
+            " b ",
+            Style::default().fg(Theme::GREY_900).bg(Theme::GREY_400),
+        ),
+        Span::styled(" to base  ", Style::default().fg(Theme::GREY_400)),
+        Span::styled(
+            " d ",
+            Style::default().fg(Theme::GREY_900).bg(Theme::GREY_400),
+        ),
+        Span::styled(" delete merged  ", Style::default().fg(Theme::GREY_400)),
+        Span::styled(
+            " r ",
+            Style::default().fg(Theme::GREY_900).bg(Theme::GREY_400),
+        ),
+        Span::styled(" refresh  ", Style::default().fg(Theme::GREY_400)),
+        Span::styled(
+            " y ",
+            Style::default().fg(Theme::GREY_900).bg(Theme::GREY_400),
+        ),
+        Span::styled(" copy URL  ", Style::default().fg(Theme::GREY_400)),
+        Span::styled(
+            " Esc ",
+            Style::default().fg(Theme::GREY_900).bg(Theme::GREY_400),
+        ),
+        Span::styled(" close", Style::default().fg(Theme::GREY_400)),
+    ]));
+    lines.push(Line::from(""));
+
+    let block = Block::default()
+        .title(" Cosmos Branches ")
+        .title_style(Style::default().fg(Theme::GREY_100))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Theme::ACCENT))
+        .style(Style::default().bg(Theme::GREY_800));
+
+    let paragraph = Paragraph::new(lines)
+        .block(block)
+        .wrap(Wrap { trim: false });
+
+    frame.render_widget(paragraph, area);
+}
+
+pub(super) fn render_snooze_overlay(frame: &mut Frame, summary: &str, selected: usize) {
+    let area = centered_rect(50, 40, frame.area());
+    frame.render_widget(Clear, area);
+
+    let summary_width = area.width.saturating_sub(6) as usize;
+    let mut lines: Vec<Line> = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            format!("  {}", truncate_to_width(summary, summary_width)),
+            Style::default().fg(Theme::GREY_300),
+        )),
+        Line::from(""),
+    ];
+
+    for (i, (label, _)) in SNOOZE_CHOICES.iter().enumerate() {
+        let is_focused = i == selected;
+        let indicator = if is_focused { "▸ " } else { "  " };
+        let line_style = if is_focused {
+            Style::default().bg(Theme::GREY_700)
+        } else {
+            Style::default()
+        };
+        lines.push(
+            Line::from(vec![
+                Span::styled(
+                    format!("  {}", indicator),
+                    Style::default().fg(Theme::ACCENT),
+                ),
+                Span::styled(label.to_string(), Style::default().fg(Theme::GREY_100)),
+            ])
+            .style(line_style),
+        );
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "  ─────────────────────────────────────────────────",
+        Style::default().fg(Theme::GREY_600),
+    )));
+    lines.push(Line::from(vec![
+        Span::styled("   ", Style::default()),
+        Span::styled(
+            " ↵ ",
+            Style::default().fg(Theme::GREY_900).bg(Theme::GREY_400),
+        ),
+        Span::styled(" snooze  ", Style::default().fg(Theme::GREY_400)),
+        Span::styled(
+            " Esc ",
+            Style::default().fg(Theme::GREY_900).bg(Theme::GREY_400),
+        ),
+        Span::styled(" cancel", Style::default().fg(Theme::GREY_400)),
+    ]));
+
+    let block = Block::default()
+        .title(" Snooze Suggestion ")
+        .title_style(Style::default().fg(Theme::GREY_100))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Theme::ACCENT))
+        .style(Style::default().bg(Theme::GREY_800));
+
+    let paragraph = Paragraph::new(lines)
+        .block(block)
+        .wrap(Wrap { trim: false });
+
+    frame.render_widget(paragraph, area);
+}
+
+pub(super) fn render_bulk_dismiss_overlay(
+    frame: &mut Frame,
+    scopes: &[(cosmos_core::suggest::BulkDismissScope, usize)],
+    selected: usize,
+) {
+    let area = centered_rect(50, 50, frame.area());
+    frame.render_widget(Clear, area);
+
+    let mut lines: Vec<Line> = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            "  Dismiss every listed suggestion in:",
+            Style::default().fg(Theme::GREY_300),
+        )),
+        Line::from(""),
+    ];
+
+    let label_width = area.width.saturating_sub(14) as usize;
+    for (i, (scope, count)) in scopes.iter().enumerate() {
+        let is_focused = i == selected;
+        let indicator = if is_focused { "▸ " } else { "  " };
+        let line_style = if is_focused {
+            Style::default().bg(Theme::GREY_700)
+        } else {
+            Style::default()
+        };
+        lines.push(
+            Line::from(vec![
+                Span::styled(
+                    format!("  {}", indicator),
+                    Style::default().fg(Theme::ACCENT),
+                ),
+                Span::styled(
+                    truncate_to_width(&scope.label(), label_width),
+                    Style::default().fg(Theme::GREY_100),
+                ),
+                Span::styled(format!("  {}", count), Style::default().fg(Theme::GREY_500)),
+            ])
+            .style(line_style),
+        );
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "  ─────────────────────────────────────────────────",
+        Style::default().fg(Theme::GREY_600),
+    )));
+    lines.push(Line::from(vec![
+        Span::styled("   ", Style::default()),
+        Span::styled(
+            " ↵ ",
+            Style::default().fg(Theme::GREY_900).bg(Theme::GREY_400),
+        ),
+        Span::styled(" dismiss  ", Style::default().fg(Theme::GREY_400)),
+        Span::styled(
+            " Esc ",
+            Style::default().fg(Theme::GREY_900).bg(Theme::GREY_400),
+        ),
+        Span::styled(" cancel  ", Style::default().fg(Theme::GREY_400)),
+        Span::styled("u undoes afterwards", Style::default().fg(Theme::GREY_500)),
+    ]));
+
+    let block = Block::default()
+        .title(" Dismiss Suggestions ")
+        .title_style(Style::default().fg(Theme::GREY_100))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Theme::ACCENT))
+        .style(Style::default().bg(Theme::GREY_800));
+
+    let paragraph = Paragraph::new(lines)
+        .block(block)
+        .wrap(Wrap { trim: false });
+
+    frame.render_widget(paragraph, area);
+}
+
+pub(super) fn render_snoozed_overlay(
+    frame: &mut Frame,
+    snoozes: &[cosmos_adapters::cache::SnoozeRecord],
+    selected: usize,
+) {
+    let area = centered_rect(70, 60, frame.area());
+    frame.render_widget(Clear, area);
+
+    let mut lines: Vec<Line> = Vec::new();
+    lines.push(Line::from(""));
+
+    if snoozes.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "  No suggestions are snoozed.",
+            Style::default().fg(Theme::GREY_400),
+        )));
+    }
+
+    let summary_width = area.width.saturating_sub(8) as usize;
+    for (i, record) in snoozes.iter().enumerate() {
+        let is_focused = i == selected;
+        let indicator = if is_focused { "▸ " } else { "  " };
+        let line_style = if is_focused {
+            Style::default().bg(Theme::GREY_700)
+        } else {
+            Style::default()
+        };
+        lines.push(
+            Line::from(vec![
+                Span::styled(
+                    format!("  {}", indicator),
+                    Style::default().fg(Theme::ACCENT),
+                ),
+                Span::styled(
+                    truncate_to_width(&record.summary, summary_width),
+                    Style::default().fg(Theme::GREY_100),
+                ),
+            ])
+            .style(line_style),
+        );
+        lines.push(Line::from(Span::styled(
+            format!("      {} · {}", record.file.display(), record.until_label()),
+            Style::default().fg(Theme::GREY_500),
+        )));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "  ─────────────────────────────────────────────────",
+        Style::default().fg(Theme::GREY_600),
+    )));
+    lines.push(Line::from(vec![
+        Span::styled("   ", Style::default()),
+        Span::styled(
+            " ↵ ",
+            Style::default().fg(Theme::GREY_900).bg(Theme::GREY_400),
+        ),
+        Span::styled(" unsnooze  ", Style::default().fg(Theme::GREY_400)),
+        Span::styled(
+            " Esc ",
+            Style::default().fg(Theme::GREY_900).bg(Theme::GREY_400),
+        ),
+        Span::styled(" close", Style::default().fg(Theme::GREY_400)),
+    ]));
+    lines.push(Line::from(""));
+
+    let block = Block::default()
+        .title(format!(" Snoozed Suggestions ({}) ", snoozes.len()))
+        .title_style(Style::default().fg(Theme::GREY_100))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Theme::ACCENT))
+        .style(Style::default().bg(Theme::GREY_800));
+
+    let paragraph = Paragraph::new(lines)
+        .block(block)
+        .wrap(Wrap { trim: false });
+
+    frame.render_widget(paragraph, area);
+}
+
+pub(super) fn render_settings_overlay(frame: &mut Frame, palette: &Palette, selected: usize) {
+    let area = centered_rect(50, 40, frame.area());
+    frame.render_widget(Clear, area);
+
+    let mut lines: Vec<Line> = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            "  Color theme:",
+            Style::default().fg(Theme::GREY_300),
+        )),
+        Line::from(""),
+    ];
+
+    for (i, name) in ThemeName::ALL.iter().enumerate() {
+        let is_focused = i == selected;
+        let indicator = if is_focused { "▸ " } else { "  " };
+        let line_style = if is_focused {
+            Style::default().bg(Theme::GREY_700)
+        } else {
+            Style::default()
+        };
+        let mut spans = vec![
+            Span::styled(
+                format!("  {}", indicator),
+                Style::default().fg(Theme::ACCENT),
+            ),
+            Span::styled(
+                format!("{:<16}", name.label()),
+                Style::default().fg(Theme::GREY_100),
+            ),
+        ];
+        if is_focused && palette.customized {
+            spans.push(Span::styled(
+                "(+ custom colors)",
+                Style::default().fg(Theme::GREY_500),
+            ));
+        }
+        lines.push(Line::from(spans).style(line_style));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "  Custom colors: \"theme\" in ~/.config/cosmos/config.json",
+        Style::default().fg(Theme::GREY_500),
+    )));
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "  ─────────────────────────────────────────────────",
+        Style::default().fg(Theme::GREY_600),
+    )));
+    lines.push(Line::from(vec![
+        Span::styled("   ", Style::default()),
+        Span::styled(
+            " ↑↓ ",
+            Style::default().fg(Theme::GREY_900).bg(Theme::GREY_400),
+        ),
+        Span::styled(" preview  ", Style::default().fg(Theme::GREY_400)),
+        Span::styled(
+            " ↵ ",
+            Style::default().fg(Theme::GREY_900).bg(Theme::GREY_400),
+        ),
+        Span::styled(" save  ", Style::default().fg(Theme::GREY_400)),
+        Span::styled(
+            " Esc ",
+            Style::default().fg(Theme::GREY_900).bg(Theme::GREY_400),
+        ),
+        Span::styled(" cancel", Style::default().fg(Theme::GREY_400)),
+    ]));
+
+    let block = Block::default()
+        .title(" Settings ")
+        .title_style(Style::default().fg(Theme::GREY_100))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Theme::ACCENT))
+        .style(Style::default().bg(Theme::GREY_800));
+
+    let paragraph = Paragraph::new(lines)
+        .block(block)
+        .wrap(Wrap { trim: false });
+
+    frame.render_widget(paragraph, area);
+}
+
+pub(super) fn render_startup_check(
+    frame: &mut Frame,
+    changed_count: usize,
+    current_branch: &str,
+    main_branch: &str,
+    mode: StartupMode,
+    selected_action: StartupAction,
+) {
+    let viewport = frame.area();
+    let is_branch_only = changed_count == 0 && current_branch != main_branch;
+    let desired_width = if mode == StartupMode::ConfirmDiscard {
+        74
+    } else {
+        98
+    };
+    let desired_height = if mode == StartupMode::ConfirmDiscard {
+        14
+    } else if is_branch_only {
+        18
+    } else {
+        20
+    };
+    let max_width = viewport.width.saturating_sub(2);
+    let max_height = viewport.height.saturating_sub(2);
+    let width = desired_width.min(max_width).max(40).min(viewport.width);
+    let height = desired_height.min(max_height).max(10).min(viewport.height);
+    let area = Rect::new(
+        viewport.x + viewport.width.saturating_sub(width) / 2,
+        viewport.y + viewport.height.saturating_sub(height) / 2,
+        width,
+        height,
+    );
+    frame.render_widget(Clear, area);
+
+    let title = if mode == StartupMode::ConfirmDiscard {
+        " Confirm "
+    } else {
+        " Startup Check "
+    };
+
+    // Outer block with border
+    let outer_block = Block::default()
+        .title(title)
+        .title_style(Style::default().fg(Theme::GREY_100))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Theme::ACCENT))
+        .style(Style::default().bg(Theme::GREY_800));
+
+    let inner_area = outer_block.inner(area);
+    frame.render_widget(outer_block, area);
+
+    // Split inner area: scrollable body + fixed footer
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(1)])
+        .split(inner_area);
+
+    let body_area = layout[0];
+    let footer_area = layout[1];
+
+    // Build body content
+    let mut lines: Vec<Line> = Vec::new();
+    let compact = mode == StartupMode::Choose && body_area.height <= 10;
+    let show_selected_desc = !compact && body_area.height >= 12;
+
+    if mode == StartupMode::ConfirmDiscard {
+        // Confirmation dialog for discard
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            "  Discard uncommitted changes?",
+            Style::default()
+                .fg(Theme::WHITE)
+                .add_modifier(Modifier::BOLD),
+        )));
         lines.push(Line::from(Span::styled(
-            "  Discard uncommitted changes?",
-            Style::default()
-                .fg(Theme::WHITE)
-                .add_modifier(Modifier::BOLD),
-        )));
-        lines.push(Line::from(Span::styled(
             "  This permanently removes local changes on this branch.",
             Style::default().fg(Theme::GREY_300),
         )));
@@ -1497,6 +2214,140 @@ pub(super) fn render_welcome(frame: &mut Frame) {
     frame.render_widget(block, area);
 }
 
+/// Galaxy mascot: the startup splash, or the easter egg after a long idle
+///
+/// With kitty or sixel graphics the art cells stay blank; the runtime draws the image
+/// over them after the frame.
+pub(super) fn render_mascot(frame: &mut Frame, app: &App, splash: bool) {
+    let area = mascot::box_area(frame.area());
+    frame.render_widget(Clear, area);
+
+    let draw_art =
+        app.graphics == GraphicsProtocol::Text || mascot::art_area(frame.area()).is_empty();
+    let mut lines: Vec<Line> = vec![Line::from("")];
+    for row in mascot::ART {
+        let row = if draw_art { row } else { "" };
+        lines.push(Line::from(Span::styled(
+            format!("   {}", row),
+            Style::default().fg(Theme::GREY_300),
+        )));
+    }
+    lines.push(Line::from(""));
+    let (title, hint) = if splash {
+        (
+            Theme::COSMOS_LOGO,
+            "a contemplative companion for your codebase",
+        )
+    } else {
+        ("still here", "press any key to get back to it")
+    };
+    lines.push(
+        Line::from(Span::styled(
+            title,
+            Style::default()
+                .fg(Theme::WHITE)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .centered(),
+    );
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(hint, Style::default().fg(Theme::GREY_500))).centered());
+
+    let block = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Theme::GREY_600))
+            .style(Style::default().bg(Theme::GREY_900)),
+    );
+    frame.render_widget(block, area);
+}
+
+pub(super) fn render_workspace_trust(frame: &mut Frame, app: &App) {
+    let area = centered_rect(60, 50, frame.area());
+    frame.render_widget(Clear, area);
+
+    let repo_name = app
+        .repo_path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("this repository")
+        .to_string();
+
+    let lines: Vec<Line> = vec![
+        Line::from(""),
+        Line::from(vec![
+            Span::styled(
+                "  Do you trust ",
+                Style::default()
+                    .fg(Theme::WHITE)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                repo_name,
+                Style::default()
+                    .fg(Theme::GREEN)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                "?",
+                Style::default()
+                    .fg(Theme::WHITE)
+                    .add_modifier(Modifier::BOLD),
+            ),
+        ]),
+        Line::from(""),
+        Line::from(vec![Span::styled(
+            "  Trusted repos can run their own commands while Cosmos applies and ships fixes:",
+            Style::default().fg(Theme::GREY_300),
+        )]),
+        Line::from(vec![Span::styled(
+            "    • quick checks (package.json scripts, .cosmos/config.toml checks)",
+            Style::default().fg(Theme::GREY_400),
+        )]),
+        Line::from(vec![Span::styled(
+            "    • the [tests] gate",
+            Style::default().fg(Theme::GREY_400),
+        )]),
+        Line::from(vec![Span::styled(
+            "    • git pre-commit hooks before Ship commits",
+            Style::default().fg(Theme::GREY_400),
+        )]),
+        Line::from(""),
+        Line::from(vec![Span::styled(
+            "  Only trust code you know. In restricted mode fixes still apply and ship,",
+            Style::default().fg(Theme::GREY_500),
+        )]),
+        Line::from(vec![Span::styled(
+            "  but none of the repo's commands run. Press T later to change your mind.",
+            Style::default().fg(Theme::GREY_500),
+        )]),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("  ", Style::default()),
+            Span::styled(" t ", Style::default().fg(Theme::GREY_900).bg(Theme::GREEN)),
+            Span::styled(" trust  ", Style::default().fg(Theme::GREY_400)),
+            Span::styled(
+                " n ",
+                Style::default().fg(Theme::GREY_900).bg(Theme::GREY_300),
+            ),
+            Span::styled(" restricted mode", Style::default().fg(Theme::GREY_400)),
+        ]),
+    ];
+
+    let block = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .title(" Workspace Trust ")
+                .title_style(Style::default().fg(Theme::GREY_100))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Theme::GREY_400))
+                .style(Style::default().bg(Theme::GREY_900)),
+        )
+        .wrap(Wrap { trim: false });
+
+    frame.render_widget(block, area);
+}
+
 pub(super) fn render_update_overlay(
     frame: &mut Frame,
     current_version: &str,
@@ -2,7 +2,12 @@
 //!
 //! A contemplative, high-contrast palette with celestial motifs.
 //! "Where code meets the cosmos"
+//!
+//! Render code draws with the `Theme` colors below, which double as palette slots:
+//! after each frame, [`Palette::apply`] swaps every slot for the active theme's color.
 
+use cosmos_adapters::config::ThemeConfig;
+use ratatui::buffer::Buffer;
 use ratatui::style::Color;
 
 /// The Cosmos theme - monochromatic with meaning
@@ -59,12 +64,29 @@ impl Theme {
     /// Red for removals - brighter for contrast  
     pub const RED: Color = Color::Rgb(230, 120, 120);
 
+    /// Background for changed words within an added line
+    pub const DIFF_ADDED_EMPHASIS: Color = Color::Rgb(30, 80, 40);
+
+    /// Background for changed words within a removed line
+    pub const DIFF_REMOVED_EMPHASIS: Color = Color::Rgb(95, 35, 35);
+
     /// Yellow/orange for warnings - visible but not alarming
     pub const YELLOW: Color = Color::Rgb(255, 200, 100);
 
     /// Accent color for highlighting selections
     pub const ACCENT: Color = Color::Rgb(140, 180, 255);
 
+    // ─────────────────────────────────────────────────────────────────────
+    // Syntax colors for code blocks
+    // ─────────────────────────────────────────────────────────────────────
+
+    pub const SYNTAX_KEYWORD: Color = Color::Rgb(198, 160, 246);
+    pub const SYNTAX_STRING: Color = Color::Rgb(166, 209, 137);
+    pub const SYNTAX_COMMENT: Color = Color::Rgb(128, 135, 150);
+    pub const SYNTAX_NUMBER: Color = Color::Rgb(239, 159, 118);
+    pub const SYNTAX_FUNCTION: Color = Color::Rgb(130, 200, 230);
+    pub const SYNTAX_TYPE: Color = Color::Rgb(229, 200, 144);
+
     // ─────────────────────────────────────────────────────────────────────
     // Badge colors for categorization (monochromatic)
     // ─────────────────────────────────────────────────────────────────────
@@ -113,3 +135,258 @@ impl Theme {
     pub const VIEW_FLAT: &'static str = "𝘧𝘭𝘢𝘵";
     pub const VIEW_GROUPED: &'static str = "𝘨𝘳𝘰𝘶𝘱𝘦𝘥";
 }
+
+// ═══════════════════════════════════════════════════════════════════════════
+//  PALETTES
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// Built-in color themes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ThemeName {
+    #[default]
+    Dark,
+    Light,
+    HighContrast,
+}
+
+impl ThemeName {
+    pub const ALL: [ThemeName; 3] = [ThemeName::Dark, ThemeName::Light, ThemeName::HighContrast];
+
+    /// Key used for this theme in `theme.name`
+    pub fn name(self) -> &'static str {
+        match self {
+            ThemeName::Dark => "dark",
+            ThemeName::Light => "light",
+            ThemeName::HighContrast => "high-contrast",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ThemeName::Dark => "Dark",
+            ThemeName::Light => "Light",
+            ThemeName::HighContrast => "High contrast",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|theme| theme.name() == name)
+    }
+
+    fn colors(self) -> [Color; SLOT_COUNT] {
+        match self {
+            ThemeName::Dark => SLOTS.map(|(_, color)| color),
+            ThemeName::Light => [
+                Color::Rgb(20, 20, 20),
+                Color::Rgb(28, 28, 28),
+                Color::Rgb(40, 40, 40),
+                Color::Rgb(60, 60, 60),
+                Color::Rgb(85, 85, 85),
+                Color::Rgb(110, 110, 110),
+                Color::Rgb(140, 140, 140),
+                Color::Rgb(190, 190, 190),
+                Color::Rgb(220, 220, 220),
+                Color::Rgb(236, 236, 236),
+                Color::Rgb(250, 250, 250),
+                Color::Rgb(20, 130, 50),
+                Color::Rgb(190, 40, 40),
+                Color::Rgb(170, 110, 0),
+                Color::Rgb(30, 90, 200),
+                Color::Rgb(200, 240, 205),
+                Color::Rgb(250, 210, 210),
+                Color::Rgb(136, 57, 239),
+                Color::Rgb(64, 130, 40),
+                Color::Rgb(120, 125, 140),
+                Color::Rgb(200, 90, 20),
+                Color::Rgb(30, 110, 180),
+                Color::Rgb(160, 110, 0),
+            ],
+            ThemeName::HighContrast => [
+                Color::Rgb(255, 255, 255),
+                Color::Rgb(255, 255, 255),
+                Color::Rgb(255, 255, 255),
+                Color::Rgb(255, 255, 255),
+                Color::Rgb(240, 240, 240),
+                Color::Rgb(220, 220, 220),
+                Color::Rgb(190, 190, 190),
+                Color::Rgb(150, 150, 150),
+                Color::Rgb(40, 40, 40),
+                Color::Rgb(0, 0, 0),
+                Color::Rgb(0, 0, 0),
+                Color::Rgb(80, 255, 80),
+                Color::Rgb(255, 90, 90),
+                Color::Rgb(255, 230, 0),
+                Color::Rgb(110, 190, 255),
+                Color::Rgb(0, 110, 0),
+                Color::Rgb(140, 0, 0),
+                Color::Rgb(230, 170, 255),
+                Color::Rgb(120, 255, 140),
+                Color::Rgb(200, 200, 200),
+                Color::Rgb(255, 180, 120),
+                Color::Rgb(120, 220, 255),
+                Color::Rgb(255, 220, 140),
+            ],
+        }
+    }
+}
+
+const SLOT_COUNT: usize = 23;
+
+/// Palette slots, keyed as in `theme.colors`, with the color render code draws for each
+const SLOTS: [(&str, Color); SLOT_COUNT] = [
+    ("white", Theme::WHITE),
+    ("grey_50", Theme::GREY_50),
+    ("grey_100", Theme::GREY_100),
+    ("grey_200", Theme::GREY_200),
+    ("grey_300", Theme::GREY_300),
+    ("grey_400", Theme::GREY_400),
+    ("grey_500", Theme::GREY_500),
+    ("grey_600", Theme::GREY_600),
+    ("grey_700", Theme::GREY_700),
+    ("grey_800", Theme::GREY_800),
+    ("grey_900", Theme::GREY_900),
+    ("green", Theme::GREEN),
+    ("red", Theme::RED),
+    ("yellow", Theme::YELLOW),
+    ("accent", Theme::ACCENT),
+    ("diff_added_emphasis", Theme::DIFF_ADDED_EMPHASIS),
+    ("diff_removed_emphasis", Theme::DIFF_REMOVED_EMPHASIS),
+    ("syntax_keyword", Theme::SYNTAX_KEYWORD),
+    ("syntax_string", Theme::SYNTAX_STRING),
+    ("syntax_comment", Theme::SYNTAX_COMMENT),
+    ("syntax_number", Theme::SYNTAX_NUMBER),
+    ("syntax_function", Theme::SYNTAX_FUNCTION),
+    ("syntax_type", Theme::SYNTAX_TYPE),
+];
+
+/// The active theme: a built-in palette with any custom colors layered on top
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Palette {
+    pub name: ThemeName,
+    /// Whether `theme.colors` overrides were applied
+    pub customized: bool,
+    colors: [Color; SLOT_COUNT],
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self::builtin(ThemeName::Dark)
+    }
+}
+
+impl Palette {
+    pub fn builtin(name: ThemeName) -> Self {
+        Self {
+            name,
+            customized: false,
+            colors: name.colors(),
+        }
+    }
+
+    /// Build the palette from the `theme` config section.
+    ///
+    /// Unknown theme names, slots, or malformed colors are skipped and returned as problems.
+    pub fn from_config(config: Option<&ThemeConfig>) -> (Self, Vec<String>) {
+        let Some(config) = config else {
+            return (Self::default(), Vec::new());
+        };
+        let mut problems = Vec::new();
+        let name = match config.name.as_deref() {
+            None => ThemeName::Dark,
+            Some(name) => ThemeName::from_name(name).unwrap_or_else(|| {
+                let known: Vec<&str> = ThemeName::ALL.iter().map(|theme| theme.name()).collect();
+                problems.push(format!(
+                    "theme.name: unknown theme '{}' (expected one of {})",
+                    name,
+                    known.join(", ")
+                ));
+                ThemeName::Dark
+            }),
+        };
+        let mut palette = Self::builtin(name);
+        for (slot, value) in &config.colors {
+            let Some(index) = SLOTS.iter().position(|(key, _)| key == slot) else {
+                problems.push(format!("theme.colors.{}: unknown color slot", slot));
+                continue;
+            };
+            match parse_hex_color(value) {
+                Some(color) => {
+                    palette.colors[index] = color;
+                    palette.customized = true;
+                }
+                None => problems.push(format!(
+                    "theme.colors.{}: '{}' is not a #rrggbb color",
+                    slot, value
+                )),
+            }
+        }
+        (palette, problems)
+    }
+
+    /// The active color for a slot color; colors outside the palette pass through.
+    pub fn resolve(&self, color: Color) -> Color {
+        SLOTS
+            .iter()
+            .position(|(_, slot)| *slot == color)
+            .map_or(color, |index| self.colors[index])
+    }
+
+    /// Recolor a rendered frame from the slot colors to this palette.
+    pub fn apply(&self, buffer: &mut Buffer) {
+        if self.colors == ThemeName::Dark.colors() {
+            return;
+        }
+        for cell in buffer.content.iter_mut() {
+            cell.fg = self.resolve(cell.fg);
+            cell.bg = self.resolve(cell.bg);
+        }
+    }
+}
+
+fn parse_hex_color(value: &str) -> Option<Color> {
+    let hex = value.trim().strip_prefix('#')?;
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some(Color::Rgb(channel(0)?, channel(2)?, channel(4)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn palette_from_config_layers_custom_colors_and_reports_problems() {
+        let config = ThemeConfig {
+            name: Some("light".to_string()),
+            colors: BTreeMap::from([
+                ("accent".to_string(), "#FF8800".to_string()),
+                ("green".to_string(), "green".to_string()),
+                ("sparkle".to_string(), "#000000".to_string()),
+            ]),
+        };
+        let (palette, problems) = Palette::from_config(Some(&config));
+
+        assert_eq!(palette.name, ThemeName::Light);
+        assert!(palette.customized);
+        assert_eq!(palette.resolve(Theme::ACCENT), Color::Rgb(255, 136, 0));
+        assert_eq!(palette.resolve(Theme::GREY_900), Color::Rgb(250, 250, 250));
+        assert_eq!(palette.resolve(Theme::GREEN), Color::Rgb(20, 130, 50));
+        assert_eq!(palette.resolve(Color::Reset), Color::Reset);
+        assert_eq!(problems.len(), 2);
+        assert!(problems[0].starts_with("theme.colors.green"));
+        assert!(problems[1].starts_with("theme.colors.sparkle"));
+    }
+
+    #[test]
+    fn apply_recolors_rendered_cells() {
+        let mut buffer = Buffer::empty(ratatui::layout::Rect::new(0, 0, 2, 1));
+        buffer.content[0].set_fg(Theme::WHITE).set_bg(Theme::BG);
+        Palette::builtin(ThemeName::Light).apply(&mut buffer);
+        assert_eq!(buffer.content[0].fg, Color::Rgb(20, 20, 20));
+        assert_eq!(buffer.content[0].bg, Color::Rgb(250, 250, 250));
+        assert_eq!(buffer.content[1].fg, Color::Reset);
+    }
+}
@@ -44,13 +44,41 @@ impl ViewMode {
     }
 }
 
+/// Order of the Suggestions list
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SuggestionSort {
+    /// Criticality first, nudged toward files you're changing
+    #[default]
+    Relevance,
+    /// Highest estimated impact first (priority × file hotspot × affected lines)
+    Impact,
+}
+
+impl SuggestionSort {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SuggestionSort::Relevance => "relevance",
+            SuggestionSort::Impact => "impact",
+        }
+    }
+
+    pub fn toggle(&self) -> Self {
+        match self {
+            SuggestionSort::Relevance => SuggestionSort::Impact,
+            SuggestionSort::Impact => SuggestionSort::Relevance,
+        }
+    }
+}
+
 /// Input mode
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum InputMode {
     #[default]
     Normal,
     Search,
-    Question, // Asking cosmos a question
+    Question,     // Asking cosmos a question
+    ShipField,    // Editing a pull request option on the Ship Confirm screen
+    FindingField, // Editing a review finding before it is fixed or posted
 }
 
 // ═══════════════════════════════════════════════════════════════════════════
@@ -71,6 +99,7 @@ pub enum LoadingState {
     Stashing,            // Saving work via git stash
     Discarding,          // Discarding uncommitted changes
     SwitchingBranch,     // Switching to main branch from startup check
+    Rebasing,            // Rebasing the fix branch onto its updated base
 }
 
 impl LoadingState {
@@ -104,6 +133,11 @@ pub enum Overlay {
         path: PathBuf,
         scroll: usize,
     },
+    /// Quick-check output as it streams in
+    CommandLog {
+        /// Lines scrolled back from the newest; 0 follows the tail
+        scroll_back: usize,
+    },
     /// API key entry overlay (in-TUI BYOK setup)
     ApiKeySetup {
         input: String,
@@ -117,13 +151,61 @@ pub enum Overlay {
     /// Apply plan preview - explicit scope/intent gate before mutation
     ApplyPlan {
         suggestion_id: uuid::Uuid,
-        preview: FixPreview,
+        preview: Box<FixPreview>,
         affected_files: Vec<PathBuf>,
         confirm_apply: bool,
         show_technical_details: bool,
         show_data_notice: bool,
+        /// Queued suggestions that touch the same files, rebased once this fix applies
+        conflicts: Vec<String>,
         scroll: usize,
     },
+    /// Hunk-by-hunk view of the changes under review, with keep/reject per hunk
+    Diff {
+        view: Box<super::diff::DiffView>,
+    },
+    /// Ask Cosmos context picker - choose files/directories to ground an answer
+    AskContextPicker {
+        /// Filter typed to narrow the project tree
+        query: String,
+        /// Focused row among the filtered entries
+        selected: usize,
+        /// Picked repo-relative files and directories
+        picked: Vec<PathBuf>,
+        /// Estimated prompt tokens the picked files add (refreshed on toggle)
+        picked_tokens: u32,
+    },
+    /// Settings - pick the color theme (previewed live while navigating)
+    Settings {
+        /// Focused theme row
+        selected: usize,
+        /// Theme active when the overlay opened, restored on cancel
+        original: super::theme::ThemeName,
+    },
+    /// How long to snooze a suggestion
+    Snooze {
+        suggestion_id: uuid::Uuid,
+        /// Focused row of `SNOOZE_CHOICES`
+        selected: usize,
+    },
+    /// Snoozed suggestions, to review or bring back
+    Snoozed {
+        selected: usize,
+    },
+    /// Confirm dismissing every suggestion in one scope
+    BulkDismiss {
+        /// Scopes around the focused suggestion, with how many listed suggestions each covers
+        scopes: Vec<(cosmos_core::suggest::BulkDismissScope, usize)>,
+        selected: usize,
+    },
+    /// Branches Cosmos created, with the state of their pull requests
+    Branches {
+        branches: Vec<cosmos_adapters::cache::CosmosBranchRecord>,
+        /// Currently focused branch index
+        selected: usize,
+        /// Progress or the last problem, shown under the list
+        status: Option<String>,
+    },
     /// Reset cosmos - selective cache/data reset
     Reset {
         /// List of (option, is_selected) pairs
@@ -159,6 +241,13 @@ pub enum Overlay {
     },
     /// Welcome overlay - shown on first run to explain the basics
     Welcome,
+    /// Workspace trust prompt - shown the first time a repo is opened
+    WorkspaceTrust,
+    /// The galaxy mascot: a brief startup splash, or an easter egg after a long idle
+    Mascot {
+        /// Startup splash (dismisses itself) rather than the idle easter egg
+        splash: bool,
+    },
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -184,10 +273,14 @@ pub enum StartupMode {
 pub enum ShipStep {
     #[default]
     Confirm, // Show what will happen
-    Committing, // Committing changes
-    Pushing,    // Pushing to remote
-    CreatingPR, // Creating pull request
-    Done,       // PR created successfully
+    RunningHooks, // Running the repo's pre-commit hook
+    HooksFailed,  // Pre-commit hook failed; output shown
+    Committing,   // Committing changes
+    Pushing,      // Pushing to remote
+    CreatingPR,   // Creating pull request
+    Done,         // PR created successfully
+    Stack,        // Stacked branches listed; open a PR per entry
+    Commits,      // Ordering and picking the changes to commit one by one
 }
 
 /// Main workflow steps for the right panel: Suggestions → Review → Ship
@@ -246,6 +339,15 @@ impl VerifyState {
     }
 }
 
+/// A file to open in `$EDITOR` once the main loop can hand over the terminal
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EditorRequest {
+    /// Repo-relative path
+    pub path: PathBuf,
+    /// 1-based line to jump to
+    pub line: Option<usize>,
+}
+
 /// State for the Review step
 #[derive(Debug, Clone, Default)]
 pub struct ReviewFileContent {
@@ -275,6 +377,16 @@ pub struct ReviewState {
     pub verification_failed: bool,
     /// Error message from failed verification (for display)
     pub verification_error: Option<String>,
+    /// PR number under review (`--review-pr`); findings are posted to the PR, not fixed
+    pub pr_number: Option<u64>,
+    /// The fetched pull request, once its review is ready
+    pub pull_request: Option<cosmos_adapters::github::OpenPullRequest>,
+    /// Review comments are being posted to the pull request
+    pub posting: bool,
+    /// Field of the finding at the cursor being edited
+    pub editing: Option<FindingField>,
+    /// Reviewer's original title of each finding the user corrected, by index
+    pub edited: HashMap<usize, String>,
 }
 
 /// State for the Ship step
@@ -286,6 +398,138 @@ pub struct ShipState {
     pub step: ShipStep,
     pub scroll: usize,
     pub pr_url: Option<String>,
+    /// Latest pre-commit hook run, shown in the Ship panel
+    pub hook_run: Option<cosmos_adapters::git_ops::PreCommitHookRun>,
+    /// Open the pull request as a draft
+    pub draft: bool,
+    /// Target branch; empty means the repository's default branch
+    pub base: String,
+    /// Comma-separated reviewers (`org/team` for GitHub teams)
+    pub reviewers: String,
+    /// Comma-separated labels
+    pub labels: String,
+    /// Pull request title, generated from pending changes and editable before shipping
+    pub pr_title: String,
+    /// Pull request description, generated from pending changes and editable before shipping
+    pub pr_body: String,
+    /// Pull request option currently being edited
+    pub editing: Option<ShipField>,
+    /// A commit message is being written from the staged diff
+    pub generating_commit_message: bool,
+    /// Highlighted entry in the stack list
+    pub stack_selected: usize,
+    /// Make one commit per pending change instead of a single commit
+    pub split_commits: bool,
+    /// Pending changes in the order they will be committed when splitting
+    pub commits: Vec<SplitCommit>,
+    /// Highlighted entry in the commit list
+    pub commit_selected: usize,
+    /// Existing pull request for the branch: push onto it and comment instead of opening one
+    pub follow_up: Option<cosmos_adapters::github::BranchPullRequest>,
+}
+
+/// A pending change in the per-change commit list
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SplitCommit {
+    /// Index into `App::pending_changes`
+    pub change: usize,
+    /// False when the change is left out of this ship (it stays staged)
+    pub included: bool,
+}
+
+impl ShipState {
+    /// Pull request options as entered on the Confirm screen
+    pub fn pr_options(&self) -> cosmos_adapters::forge::PrOptions {
+        let base = self.base.trim();
+        cosmos_adapters::forge::PrOptions {
+            draft: self.draft,
+            base: (!base.is_empty()).then(|| base.to_string()),
+            reviewers: cosmos_adapters::forge::split_list(&self.reviewers),
+            labels: cosmos_adapters::forge::split_list(&self.labels),
+        }
+    }
+}
+
+/// One committed change in stacked mode: its own branch, based on the entry before it
+#[derive(Debug, Clone)]
+pub struct StackEntry {
+    pub branch: String,
+    pub title: String,
+    pub body: String,
+    /// Pull request options; `base` is the previous entry's branch, or the original base
+    pub options: cosmos_adapters::forge::PrOptions,
+    pub pr_url: Option<String>,
+    /// Pushing and opening the pull request is in progress
+    pub opening: bool,
+}
+
+/// Editable pull request options on the Ship Confirm screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShipField {
+    Base,
+    Reviewers,
+    Labels,
+    Title,
+    /// Multi-line: Enter inserts a newline, Esc finishes
+    Description,
+    /// Multi-line, like Description
+    CommitMessage,
+}
+
+impl ShipField {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ShipField::Base => "Base",
+            ShipField::Reviewers => "Reviewers",
+            ShipField::Labels => "Labels",
+            ShipField::Title => "Title",
+            ShipField::Description => "Description",
+            ShipField::CommitMessage => "Commit message",
+        }
+    }
+
+    /// Enter inserts a newline instead of finishing the edit
+    pub fn is_multiline(&self) -> bool {
+        matches!(self, ShipField::Description | ShipField::CommitMessage)
+    }
+}
+
+/// Editable parts of a review finding
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FindingField {
+    Title,
+    /// Multi-line: Enter inserts a newline, Esc finishes
+    Description,
+}
+
+/// Output of the harness's quick checks and test commands, newest last
+#[derive(Debug, Clone, Default)]
+pub struct CommandLog {
+    lines: std::collections::VecDeque<String>,
+}
+
+impl CommandLog {
+    /// Oldest lines are dropped past this many
+    pub const CAPACITY: usize = 2_000;
+
+    pub fn push(&mut self, line: String) {
+        if self.lines.len() == Self::CAPACITY {
+            self.lines.pop_front();
+        }
+        self.lines.push_back(line);
+    }
+
+    pub fn lines(&self) -> impl DoubleEndedIterator<Item = &String> + ExactSizeIterator {
+        self.lines.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
 }
 
 /// State for the Ask Cosmos panel mode
@@ -293,6 +537,14 @@ pub struct ShipState {
 pub struct AskCosmosState {
     pub response: String,
     pub scroll: usize,
+    /// The question being answered, shown above the response (None for errors)
+    pub question: Option<String>,
+    /// Earlier turns of the conversation, shown above the question
+    pub transcript: String,
+    /// Lines scrolled up into the transcript from the start of the response
+    pub transcript_scroll: usize,
+    /// What the answer was grounded on (None for cached answers and errors)
+    pub context: Option<cosmos_engine::llm::AskContextReport>,
 }
 
 // ═══════════════════════════════════════════════════════════════════════════
@@ -312,6 +564,27 @@ impl FileChange {
     }
 }
 
+/// A suggestion action `.` can repeat on the next suggestion
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RepeatableAction {
+    Rate(cosmos_core::suggest::SuggestionFeedback),
+    /// Snooze with this row of `SNOOZE_CHOICES`
+    Snooze(usize),
+    OpenApplyPlan,
+}
+
+/// Suggestions removed by one bulk dismiss, kept so `u` can bring them back
+#[derive(Debug, Clone)]
+pub struct BulkDismissal {
+    /// `low-priority docs suggestions`
+    pub label: String,
+    /// Its entry in the recorded dismissals, removed on undo
+    pub record_id: uuid::Uuid,
+    pub suggestions: Vec<cosmos_core::suggest::Suggestion>,
+    /// Pending changes when it happened; undo takes back later applied fixes first
+    pub pending_changes: usize,
+}
+
 /// A pending change that has been applied but not yet committed
 #[derive(Debug, Clone)]
 pub struct PendingChange {
@@ -325,6 +598,10 @@ pub struct PendingChange {
     pub problem_summary: Option<String>,
     /// What will be different after the fix (outcome-focused)
     pub outcome: Option<String>,
+    /// Undo stack entry with pre-apply snapshots (None = restore from HEAD)
+    pub undo_run_id: Option<String>,
+    /// What the harness verified, carried into the PR description
+    pub harness_evidence: Option<cosmos_engine::llm::HarnessEvidence>,
 }
 
 impl PendingChange {
@@ -344,9 +621,26 @@ impl PendingChange {
             friendly_title: Some(friendly_title),
             problem_summary: Some(problem_summary),
             outcome: Some(outcome),
+            undo_run_id: None,
+            harness_evidence: None,
         }
     }
 
+    /// Attach the undo stack entry recorded when this change was applied
+    pub fn with_undo_run_id(mut self, undo_run_id: Option<String>) -> Self {
+        self.undo_run_id = undo_run_id;
+        self
+    }
+
+    /// Attach the harness evidence from the passing attempt
+    pub fn with_harness_evidence(
+        mut self,
+        harness_evidence: Option<cosmos_engine::llm::HarnessEvidence>,
+    ) -> Self {
+        self.harness_evidence = harness_evidence;
+        self
+    }
+
     /// Get the primary file path (first file, for backward compatibility)
     pub fn file_path(&self) -> &PathBuf {
         &self.files[0].path
@@ -362,3 +656,13 @@ impl PendingChange {
         self.files.len() > 1
     }
 }
+
+/// Cosmos state set aside when switching away from a branch, brought back on return
+#[derive(Debug, Clone, Default)]
+pub struct BranchContext {
+    /// Suggestion that was focused in the list
+    pub selected_suggestion: Option<uuid::Uuid>,
+    pub pending_changes: Vec<PendingChange>,
+    pub cosmos_branch: Option<String>,
+    pub cosmos_base_branch: Option<String>,
+}